@@ -0,0 +1,148 @@
+//! Where captured prompts, raw LLM responses and search responses go.
+//!
+//! Capture is off by default. When enabled, the pipeline scrubs each artifact
+//! of credentials and personal data before handing it to the configured sink,
+//! and the admin route reads them back.
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use gorkd_core::{
+    ArtifactSink, JobId, LlmArtifact, SearchArtifact, Store, StoreArtifactSink, StoreError,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Directory used by `ARTIFACT_CAPTURE=dir` when `ARTIFACT_DIR` is unset.
+pub const DEFAULT_ARTIFACT_DIR: &str = "artifacts";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ArtifactCapture {
+    #[default]
+    Off,
+    /// Keep artifacts in the job store.
+    Store,
+    /// Write one JSON file per artifact under `<dir>/<job_id>/`, and per
+    /// search response under `<dir>/<job_id>/search/`.
+    Directory(PathBuf),
+}
+
+impl ArtifactCapture {
+    /// Reads `ARTIFACT_CAPTURE` (`off`, `store` or `dir`) and `ARTIFACT_DIR`.
+    pub fn from_env() -> Self {
+        match env::var("ARTIFACT_CAPTURE")
+            .unwrap_or_default()
+            .to_lowercase()
+            .as_str()
+        {
+            "store" => Self::Store,
+            "dir" | "directory" => Self::Directory(
+                env::var("ARTIFACT_DIR")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|_| PathBuf::from(DEFAULT_ARTIFACT_DIR)),
+            ),
+            _ => Self::Off,
+        }
+    }
+
+    pub fn sink(&self, store: Arc<dyn Store>) -> Option<Arc<dyn ArtifactSink>> {
+        match self {
+            Self::Off => None,
+            Self::Store => Some(Arc::new(StoreArtifactSink::new(store))),
+            Self::Directory(dir) => Some(Arc::new(DirectoryArtifactSink::new(dir.clone()))),
+        }
+    }
+}
+
+/// Writes artifacts to a local directory so they survive restarts of the
+/// in-memory store and can be inspected with ordinary tools.
+#[derive(Debug)]
+pub struct DirectoryArtifactSink {
+    root: PathBuf,
+}
+
+impl DirectoryArtifactSink {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn job_dir(&self, job_id: &JobId) -> PathBuf {
+        self.root.join(job_id.as_str())
+    }
+}
+
+fn io_error(e: std::io::Error) -> StoreError {
+    StoreError::Query(e.to_string())
+}
+
+/// Writes `artifact` as the next numbered file of `dir`, named after `name`.
+async fn write_next<T: Serialize>(dir: &Path, name: &str, artifact: &T) -> Result<(), StoreError> {
+    tokio::fs::create_dir_all(dir).await.map_err(io_error)?;
+    let count = json_files(dir).await?.len();
+
+    let body = serde_json::to_vec_pretty(artifact)
+        .map_err(|e| StoreError::Serialization(e.to_string()))?;
+    let path = dir.join(format!("{:04}-{}.json", count, name));
+    tokio::fs::write(path, body).await.map_err(io_error)
+}
+
+/// Reads every file of `dir` in name order.
+async fn read_all<T: DeserializeOwned>(dir: &Path) -> Result<Vec<T>, StoreError> {
+    let paths = json_files(dir).await?;
+    let mut artifacts = Vec::with_capacity(paths.len());
+    for path in paths {
+        let body = tokio::fs::read(&path).await.map_err(io_error)?;
+        let artifact =
+            serde_json::from_slice(&body).map_err(|e| StoreError::Serialization(e.to_string()))?;
+        artifacts.push(artifact);
+    }
+    Ok(artifacts)
+}
+
+/// The files of `dir`, sorted by name, leaving out subdirectories; none
+/// when it does not exist.
+async fn json_files(dir: &Path) -> Result<Vec<PathBuf>, StoreError> {
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(io_error(e)),
+    };
+    let mut paths = Vec::new();
+    while let Some(entry) = entries.next_entry().await.map_err(io_error)? {
+        if entry.file_type().await.map_err(io_error)?.is_file() {
+            paths.push(entry.path());
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+#[async_trait]
+impl ArtifactSink for DirectoryArtifactSink {
+    async fn capture(&self, job_id: &JobId, artifact: LlmArtifact) -> Result<(), StoreError> {
+        write_next(&self.job_dir(job_id), &artifact.stage, &artifact).await
+    }
+
+    async fn artifacts(&self, job_id: &JobId) -> Result<Vec<LlmArtifact>, StoreError> {
+        read_all(&self.job_dir(job_id)).await
+    }
+
+    async fn capture_search(
+        &self,
+        job_id: &JobId,
+        artifact: SearchArtifact,
+    ) -> Result<(), StoreError> {
+        let dir = self.job_dir(job_id).join("search");
+        write_next(&dir, &artifact.provider, &artifact).await
+    }
+
+    async fn search_artifacts(&self, job_id: &JobId) -> Result<Vec<SearchArtifact>, StoreError> {
+        read_all(&self.job_dir(job_id).join("search")).await
+    }
+
+    fn sink_name(&self) -> &str {
+        "directory"
+    }
+}
@@ -0,0 +1,52 @@
+//! Runs research pipelines for jobs queued by `gorkd-api` in
+//! `JOB_EXECUTION=queue` mode. Start as many workers as search and LLM
+//! throughput require; each job is claimed by exactly one of them. Without
+//! a store shared with the API the worker could never see a job, so it
+//! refuses to start.
+
+use std::sync::Arc;
+
+use gorkd_api::bootstrap;
+use gorkd_api::config_check::{self, ConfigSummary};
+use gorkd_api::execution::{self, worker_config_from_env};
+use gorkd_core::Worker;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer())
+        .with(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
+
+    config_check::check_env().enforce();
+
+    let Some(store) = execution::shared_store() else {
+        tracing::error!("no store shared with gorkd-api is available; the worker cannot run");
+        std::process::exit(1);
+    };
+
+    let state = bootstrap::state_from_env(store).await;
+    let mut report = config_check::check_state(&state);
+    if config_check::live_checks_enabled() {
+        report.extend(config_check::check_live(&state).await);
+    }
+    report.enforce();
+
+    let config = worker_config_from_env();
+    tracing::info!(
+        "configuration:\n{}",
+        ConfigSummary::of(&state).with("worker concurrency", config.concurrency.to_string())
+    );
+    tracing::info!(
+        concurrency = config.concurrency,
+        poll_interval_ms = config.poll_interval.as_millis() as u64,
+        "worker started"
+    );
+
+    Worker::new(Arc::clone(&state.store), Arc::new(state.pipeline()), config)
+        .run(bootstrap::shutdown_signal())
+        .await;
+
+    tracing::info!("shutdown complete");
+}
@@ -0,0 +1,458 @@
+//! Provider and pipeline setup shared by the `gorkd-api` and `gorkd-worker`
+//! binaries, so both run jobs with the same configuration.
+
+use std::sync::Arc;
+use std::time::Duration;
+use std::{env, fs};
+
+use gorkd_core::{
+    CitationMarkers, Confidence, ConsistencyPolicy, ContentFetcher, ContentLimits, DeadLinkPolicy,
+    DomainPolicy, EscalationPolicy, FeedReader, FreshnessPolicy, HttpOptions, LinkChecker,
+    MockLlmProvider, MockSearchProvider, ResearchProfiles, Store, EXHAUSTIVE_MAX_SOURCES,
+};
+use gorkd_llm::{
+    build_http_client_with_options, embedder_from_config, moderator_from_config, LlmConfig,
+    LlmRegistry, DEFAULT_TIMEOUT_SECS,
+};
+use gorkd_search::{
+    conditional, links, FetchSchedule, HttpClient, HttpContentFetcher, HttpCrawler, HttpFeedReader,
+    HttpLinkChecker, ProviderRegistry, ScheduledFetcher, SearchConfig, WaybackMachine,
+    YouTubeTranscriptFetcher,
+};
+use tokio::signal;
+
+use crate::artifacts::ArtifactCapture;
+use crate::feeds::FeedSettings;
+use crate::objects::{self, ObjectStorage};
+use crate::plugins::PluginSettings;
+use crate::publish::EventPublishing;
+use crate::state::AppState;
+use crate::store_metrics::{slow_threshold_from_env, InstrumentedStore};
+
+/// Timeout of each page download of a corpus crawl.
+const CRAWL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Timeout of each download of a corpus feed.
+const FEED_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Builds the application state from the environment, falling back to mock
+/// providers when none are configured. Calls to `store` are timed, and large
+/// bodies kept in object storage when it is configured; use the state's
+/// store so both apply.
+///
+/// The binaries run [`crate::config_check`] first, which refuses the mock
+/// fallback unless `ALLOW_MOCK_PROVIDERS` is set.
+pub async fn state_from_env(store: Arc<dyn Store>) -> AppState {
+    let http_options = http_options_from_env();
+
+    let object_storage = ObjectStorage::from_env();
+    if !object_storage.is_off() {
+        tracing::info!(storage = ?object_storage, "keeping large bodies in object storage");
+    }
+    let (store, content_metrics) =
+        object_storage.wrap(store, objects::min_bytes_from_env(), &http_options);
+    let store = InstrumentedStore::new(store, slow_threshold_from_env());
+    let store_metrics = store.metrics();
+    let store: Arc<dyn Store> = Arc::new(store);
+
+    // One client per kind of provider, cloned wherever it is needed, so
+    // connections are pooled across providers and jobs.
+    let llm_http =
+        build_http_client_with_options(Duration::from_secs(DEFAULT_TIMEOUT_SECS), &http_options)
+            .expect("failed to create HTTP client");
+
+    let llm_config = LlmConfig::from_env();
+    let llm_registry = if llm_config.has_provider() {
+        let registry = LlmRegistry::from_config(llm_http.clone(), &llm_config);
+        tracing::info!(
+            models = ?registry.available_models(),
+            default = ?registry.default_model_id(),
+            fallbacks = ?registry.fallback_model_ids(),
+            "initialized LLM providers from environment"
+        );
+        if llm_config.max_images > 0 {
+            tracing::info!(
+                max_images = llm_config.max_images,
+                vision_models = ?registry.vision_models(),
+                "showing source images to vision-capable models"
+            );
+        }
+        if let Some(ref policy) = llm_config.routing {
+            match registry.fast() {
+                Some(fast) => tracing::info!(
+                    fast_model = fast.model_id(),
+                    simple_types = ?policy.simple_types,
+                    "routing simple questions to the fast model"
+                ),
+                None => tracing::warn!(
+                    fast_model = ?registry.fast_model_id(),
+                    "LLM_ROUTING is on but the fast model is not available"
+                ),
+            }
+        }
+        if let Some(ref shadow) = llm_config.shadow_model {
+            match registry.shadow() {
+                Some(_) => tracing::info!(
+                    shadow_model = %shadow,
+                    "evaluating model in shadow of the default model"
+                ),
+                None => tracing::warn!(
+                    shadow_model = %shadow,
+                    "LLM_SHADOW_MODEL must name an available model other than the default"
+                ),
+            }
+        }
+        registry
+    } else {
+        tracing::warn!("no LLM providers configured, using mock provider");
+        let mock = Arc::new(MockLlmProvider::new("mock-gpt-4"));
+        LlmRegistry::builder()
+            .register("mock-gpt-4", mock)
+            .default_model("mock-gpt-4")
+            .build()
+    };
+
+    let mut content_limits = ContentLimits::default();
+    let mut max_sources_limit = EXHAUSTIVE_MAX_SOURCES;
+    let mut max_per_domain = None;
+    let mut domain_policy = DomainPolicy::default();
+    let mut content_fetcher: Option<Arc<dyn ContentFetcher>> = None;
+    let fetch_cache_bytes = conditional::cache_bytes_from_env();
+    let (search_registry, source_expansion) = match SearchConfig::from_env() {
+        Ok(config) => {
+            let config = config.with_http_options(http_options.clone());
+            let http = config.http_client().expect("failed to create HTTP client");
+            let registry = ProviderRegistry::from_config(&config, &http);
+            tracing::info!(
+                providers = ?registry.list(),
+                "initialized search providers from environment"
+            );
+            let source_expansion = if config.expand_similar {
+                let provider = registry.similar_pages_provider();
+                match provider {
+                    Some(ref provider) => tracing::info!(
+                        provider = provider.provider_id(),
+                        "expanding sources with similar pages"
+                    ),
+                    None => tracing::warn!(
+                        "SEARCH_EXPAND_SIMILAR is set but no configured provider can find similar pages"
+                    ),
+                }
+                provider
+            } else {
+                None
+            };
+            content_limits = config.content_limits;
+            max_sources_limit = config.max_sources_limit;
+            max_per_domain = config.max_per_domain;
+            if !config.domain_policy.is_empty() {
+                tracing::info!(
+                    allow = ?config.domain_policy.allow,
+                    deny = ?config.domain_policy.deny,
+                    "enforcing domain policy"
+                );
+            }
+            domain_policy = config.domain_policy;
+            if config.fetch_content {
+                let mut fetcher =
+                    HttpContentFetcher::new(http.clone()).with_validator_cache(fetch_cache_bytes);
+                if config.archive_fallback {
+                    fetcher = fetcher.with_archive_fallback(WaybackMachine::new(http.clone()));
+                    tracing::info!("reading dead and paywalled pages from the Wayback Machine");
+                }
+                content_fetcher = Some(Arc::new(fetcher));
+                tracing::info!("fetching content for sources returned without it");
+            }
+            if config.youtube_transcripts {
+                let mut youtube = YouTubeTranscriptFetcher::new(http.clone())
+                    .with_languages(config.youtube_transcript_languages.clone());
+                if let Some(fetcher) = content_fetcher.take() {
+                    youtube = youtube.with_fallback(fetcher);
+                }
+                content_fetcher = Some(Arc::new(youtube));
+                tracing::info!(
+                    languages = ?config.youtube_transcript_languages,
+                    "using transcripts for YouTube sources"
+                );
+            }
+            if let Some(fetcher) = content_fetcher.take() {
+                let schedule = FetchSchedule::from_env();
+                tracing::info!(?schedule, "scheduling page fetches per host");
+                content_fetcher = Some(Arc::new(
+                    ScheduledFetcher::new(fetcher, http, schedule)
+                        .with_user_agent(http_options.user_agent()),
+                ));
+            }
+            (registry, source_expansion)
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "no usable search providers, using mock provider");
+            let mut registry = ProviderRegistry::new();
+            registry.register(
+                "mock-tavily",
+                Arc::new(MockSearchProvider::new("mock-tavily")),
+            );
+            (registry, None)
+        }
+    };
+
+    let crawler = HttpClient::with_options(CRAWL_TIMEOUT, &http_options)
+        .expect("failed to create HTTP client");
+    let crawler = HttpCrawler::new(crawler)
+        .with_user_agent(http_options.user_agent())
+        .with_validator_cache(fetch_cache_bytes);
+
+    let feeds = FeedSettings::from_env();
+    let feed_reader: Option<Arc<dyn FeedReader>> = if feeds.subscriptions.is_empty() {
+        None
+    } else {
+        let client = HttpClient::with_options(FEED_TIMEOUT, &http_options)
+            .expect("failed to create HTTP client");
+        Some(Arc::new(
+            HttpFeedReader::new(client).with_validator_cache(fetch_cache_bytes),
+        ))
+    };
+
+    let dead_links = link_check_from_env();
+    let link_checker: Option<Arc<dyn LinkChecker>> = dead_links.map(|policy| {
+        let client = HttpClient::with_options(links::timeout_from_env(), &http_options)
+            .expect("failed to create HTTP client");
+        tracing::info!(?policy, "checking the links of answers");
+        Arc::new(HttpLinkChecker::new(client).with_cache_ttl(links::cache_ttl_from_env()))
+            as Arc<dyn LinkChecker>
+    });
+
+    let moderator = moderator_from_config(llm_http.clone(), &llm_config);
+    let embedder = embedder_from_config(llm_http, &llm_config);
+
+    let artifact_capture = ArtifactCapture::from_env();
+    if artifact_capture != ArtifactCapture::Off {
+        tracing::info!(capture = ?artifact_capture, "capturing LLM prompt/response artifacts");
+    }
+    let artifact_sink = artifact_capture.sink(Arc::clone(&store));
+
+    let event_publishing = EventPublishing::from_env();
+    let event_publisher = event_publishing.publisher().await;
+    if let Some(ref publisher) = event_publisher {
+        tracing::info!(
+            publisher = publisher.publisher_name(),
+            "publishing job lifecycle events"
+        );
+    }
+
+    let plugin_settings = PluginSettings::from_env();
+    let plugins = plugin_settings
+        .load()
+        .unwrap_or_else(|e| panic!("failed to load WASM_PLUGINS: {}", e));
+    if !plugins.is_empty() {
+        tracing::info!(
+            plugins = ?plugins.iter().map(|p| p.plugin_name()).collect::<Vec<_>>(),
+            fuel = plugin_settings.fuel,
+            "running WASM plugins in every job"
+        );
+    }
+
+    AppState::with_registries(store, search_registry, llm_registry)
+        .with_moderation(moderator, llm_config.moderation)
+        .with_embedder(embedder)
+        .with_length_policies(llm_config.length_policies)
+        .with_outline_reports(llm_config.outline_reports)
+        .with_knowledge_graph(llm_config.knowledge_graph)
+        .with_routing(llm_config.routing)
+        .with_source_expansion(source_expansion)
+        .with_content_limits(content_limits)
+        .with_source_caps(max_sources_limit, max_per_domain)
+        .with_domain_policy(domain_policy)
+        .with_profiles(profiles_from_env())
+        .with_content_fetcher(content_fetcher)
+        .with_link_checker(link_checker, dead_links.unwrap_or_default())
+        .with_citation_markers(citation_markers_from_env())
+        .with_freshness(freshness_from_env())
+        .with_escalation(escalation_from_env())
+        .with_consistency(consistency_from_env())
+        .with_crawler(Some(Arc::new(crawler)))
+        .with_feeds(feed_reader, feeds.subscriptions, feeds.poll_interval)
+        .with_artifact_sink(artifact_sink)
+        .with_event_publisher(event_publisher)
+        .with_plugins(plugins)
+        .with_store_metrics(store_metrics)
+        .with_content_metrics(content_metrics)
+}
+
+/// Reads the settings of every outbound HTTP client: `HTTP_USER_AGENT`,
+/// `HTTP_PROXY_URL` (http, https or socks5), `HTTP_NO_PROXY` (falling back
+/// to `NO_PROXY`), `HTTP_CA_CERT_FILE`, `HTTP_ACCEPT_INVALID_CERTS` and the
+/// connection settings `HTTP_CONNECT_TIMEOUT_SECS`,
+/// `HTTP_POOL_MAX_IDLE_PER_HOST`, `HTTP_POOL_IDLE_TIMEOUT_SECS` and
+/// `HTTP_TCP_KEEPALIVE_SECS`. Unparsable numbers are ignored.
+///
+/// # Panics
+///
+/// If `HTTP_CA_CERT_FILE` is set but cannot be read or holds no PEM
+/// certificate: requests through a TLS-intercepting proxy would all fail.
+pub fn http_options_from_env() -> HttpOptions {
+    let var = |name: &str| env::var(name).ok().filter(|s| !s.trim().is_empty());
+    let mut options = HttpOptions::new();
+
+    if let Some(user_agent) = var("HTTP_USER_AGENT") {
+        options = options.with_user_agent(user_agent);
+    }
+    if let Some(proxy) = var("HTTP_PROXY_URL") {
+        tracing::info!(proxy = %redact_userinfo(&proxy), "sending outbound requests through proxy");
+        options = options.with_proxy(proxy);
+    }
+    if let Some(no_proxy) = var("HTTP_NO_PROXY").or_else(|| var("NO_PROXY")) {
+        options = options.with_no_proxy(no_proxy);
+    }
+    if let Some(path) = var("HTTP_CA_CERT_FILE") {
+        let pem = fs::read(&path)
+            .unwrap_or_else(|e| panic!("failed to read HTTP_CA_CERT_FILE {}: {}", path, e));
+        if !String::from_utf8_lossy(&pem).contains("-----BEGIN CERTIFICATE-----") {
+            panic!("HTTP_CA_CERT_FILE {} holds no PEM certificate", path);
+        }
+        tracing::info!(path, "trusting extra certificate authorities");
+        options = options.with_root_certificates(pem);
+    }
+    let accept_invalid_certs = var("HTTP_ACCEPT_INVALID_CERTS")
+        .is_some_and(|s| matches!(s.to_lowercase().as_str(), "on" | "true" | "1" | "yes"));
+    if accept_invalid_certs {
+        tracing::warn!("HTTP_ACCEPT_INVALID_CERTS is set: server certificates are not verified");
+        options = options.accept_invalid_certs(true);
+    }
+
+    let secs = |name: &str| {
+        var(name)
+            .and_then(|s| s.trim().parse().ok())
+            .map(Duration::from_secs)
+    };
+    options.connect_timeout = secs("HTTP_CONNECT_TIMEOUT_SECS");
+    options.pool_max_idle_per_host =
+        var("HTTP_POOL_MAX_IDLE_PER_HOST").and_then(|s| s.trim().parse().ok());
+    options.pool_idle_timeout = secs("HTTP_POOL_IDLE_TIMEOUT_SECS");
+    options.tcp_keepalive = secs("HTTP_TCP_KEEPALIVE_SECS");
+
+    options
+}
+
+/// `url` without the credentials of a `user:password@` prefix, for logging.
+fn redact_userinfo(url: &str) -> String {
+    match (url.find("://"), url.rfind('@')) {
+        (Some(scheme), Some(at)) if at > scheme => {
+            format!("{}://***@{}", &url[..scheme], &url[at + 1..])
+        }
+        _ => url.to_string(),
+    }
+}
+
+/// Reads research profiles from the JSON file at `RESEARCH_PROFILES_FILE`.
+///
+/// # Panics
+///
+/// If the file is set but cannot be read or parsed: running without the
+/// source policies it holds would silently widen what jobs may cite.
+pub fn profiles_from_env() -> ResearchProfiles {
+    let Some(path) = env::var("RESEARCH_PROFILES_FILE")
+        .ok()
+        .filter(|s| !s.is_empty())
+    else {
+        return ResearchProfiles::default();
+    };
+
+    let json = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("failed to read RESEARCH_PROFILES_FILE {}: {}", path, e));
+    let profiles = ResearchProfiles::from_json(&json)
+        .unwrap_or_else(|e| panic!("invalid research profiles in {}: {}", path, e));
+    tracing::info!(profiles = ?profiles.names(), "loaded research profiles");
+    profiles
+}
+
+/// Reads `LINK_CHECK`: `mark`, `rerank` or `annotate` checks the links of
+/// every answer and handles dead ones that way; unset or `off` checks none.
+pub fn link_check_from_env() -> Option<DeadLinkPolicy> {
+    env::var("LINK_CHECK")
+        .ok()
+        .and_then(|value| DeadLinkPolicy::parse(&value))
+}
+
+/// Reads `CITATION_MARKERS`: `numeric` (the default) cites sources inline
+/// as `[1]` with a table of references, `source_id` as `[src_abc123]`.
+pub fn citation_markers_from_env() -> CitationMarkers {
+    env::var("CITATION_MARKERS")
+        .ok()
+        .and_then(|value| CitationMarkers::parse(&value))
+        .unwrap_or_default()
+}
+
+/// Reads `ESCALATE_BELOW_CONFIDENCE`: `high`, `medium` or `low` searches
+/// again, more broadly, for answers less confident than that; unset or
+/// `off` escalates none.
+pub fn escalation_from_env() -> Option<EscalationPolicy> {
+    env::var("ESCALATE_BELOW_CONFIDENCE")
+        .ok()
+        .and_then(|value| Confidence::parse(&value))
+        .map(EscalationPolicy::new)
+}
+
+/// Reads how high-stakes jobs sample their answer from
+/// `CONSISTENCY_SAMPLES` (3 unless set, at most 10) and
+/// `CONSISTENCY_TEMPERATURE` (0.7 unless set). Unparsable values keep their
+/// default.
+pub fn consistency_from_env() -> ConsistencyPolicy {
+    let defaults = ConsistencyPolicy::default();
+    let samples = env::var("CONSISTENCY_SAMPLES")
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(defaults.samples);
+    let temperature = env::var("CONSISTENCY_TEMPERATURE")
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(defaults.temperature);
+    ConsistencyPolicy::new(samples, temperature)
+}
+
+/// Reads how long answers stay current, in hours, from
+/// `ANSWER_TTL_BREAKING_HOURS`, `ANSWER_TTL_VOLATILE_HOURS`,
+/// `ANSWER_TTL_STABLE_HOURS` and `ANSWER_TTL_EVERGREEN_HOURS`. Unset or
+/// unparsable ones keep their default.
+pub fn freshness_from_env() -> FreshnessPolicy {
+    let hours = |name: &str, default: Duration| {
+        env::var(name)
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok())
+            .map_or(default, |hours| Duration::from_secs(hours * 60 * 60))
+    };
+    let defaults = FreshnessPolicy::default();
+    FreshnessPolicy {
+        breaking: hours("ANSWER_TTL_BREAKING_HOURS", defaults.breaking),
+        volatile: hours("ANSWER_TTL_VOLATILE_HOURS", defaults.volatile),
+        stable: hours("ANSWER_TTL_STABLE_HOURS", defaults.stable),
+        evergreen: hours("ANSWER_TTL_EVERGREEN_HOURS", defaults.evergreen),
+    }
+}
+
+/// Resolves on Ctrl+C or, on Unix, SIGTERM.
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("failed to install signal handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    tracing::info!("shutdown signal received");
+}
@@ -0,0 +1,832 @@
+//! Configuration checks run before the binaries start serving.
+//!
+//! A mistyped number is ignored, a key pasted into the wrong variable fails
+//! the first jobs, and a missing provider quietly leaves the mock in its
+//! place. Both binaries therefore check what they were given before doing
+//! any work: [`check_env`] the format of every variable that is set,
+//! [`check_state`] that the models and providers it names were built and,
+//! with `CONFIG_CHECK_LIVE` on, [`check_live`] that each provider accepts
+//! its credentials. All problems are reported at once, each with the
+//! setting to change, and any error stops startup. [`ConfigSummary`] lists
+//! what the process runs with once it passes.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use std::{env, fs};
+
+use gorkd_core::{
+    ChatRequest, CitationMarkers, DeadLinkPolicy, LlmProvider, Message, ModerationPolicy,
+    ResearchProfiles, SearchProvider, SearchQuery, MAX_CONSISTENCY_SAMPLES,
+};
+use gorkd_llm::WebhookSchema;
+use gorkd_search::{ConfigError, SearchConfig, WebhookConfig};
+
+use crate::execution::{self, JobExecution};
+use crate::feeds;
+use crate::state::AppState;
+
+/// Variables holding whole numbers, such as timeouts, sizes and limits.
+const NUMBERS: &[&str] = &[
+    "SEARCH_TIMEOUT_SECS",
+    "SEARCH_MAX_RESULTS",
+    "SEARCH_MAX_SOURCES_LIMIT",
+    "SEARCH_MAX_PER_DOMAIN",
+    "SOURCE_MAX_BYTES",
+    "SOURCES_MAX_TOTAL_BYTES",
+    "LLM_TIMEOUT_SECS",
+    "LLM_MAX_RETRIES",
+    "LLM_MAX_IMAGES",
+    "LLM_MAX_CONCURRENT",
+    "LLM_MAX_CONCURRENT_PER_PROVIDER",
+    "LLM_MAX_CONCURRENT_ANTHROPIC",
+    "LLM_MAX_CONCURRENT_OPENAI",
+    "LLM_MAX_CONCURRENT_BEDROCK",
+    "LLM_MAX_CONCURRENT_WEBHOOK",
+    "LLM_WEBHOOK_CONTEXT_TOKENS",
+    "LLM_ROUTING_MAX_WORDS",
+    "LLM_ROUTING_MAX_ENTITIES",
+    "LLM_ROUTING_MAX_HOPS",
+    "STORE_SLOW_QUERY_MS",
+    "HTTP_CONNECT_TIMEOUT_SECS",
+    "HTTP_POOL_MAX_IDLE_PER_HOST",
+    "HTTP_POOL_IDLE_TIMEOUT_SECS",
+    "HTTP_TCP_KEEPALIVE_SECS",
+    "JOB_QUEUE_MAX_DEPTH",
+    "JOB_QUEUE_RETRY_AFTER_SECS",
+    "JOB_RETRY_MAX_ATTEMPTS",
+    "JOB_RETRY_BACKOFF_MS",
+    "WORKER_CONCURRENCY",
+    "WORKER_POLL_INTERVAL_MS",
+    "WORKER_LEASE_SECS",
+    "CORPUS_FEED_POLL_SECS",
+    "OBJECT_STORAGE_MIN_BYTES",
+    "FETCH_CACHE_MB",
+    "FETCH_MAX_PER_HOST",
+    "FETCH_MAX_CONCURRENT",
+    "LINK_CHECK_TIMEOUT_SECS",
+    "LINK_CHECK_CACHE_SECS",
+    "ANSWER_TTL_BREAKING_HOURS",
+    "ANSWER_TTL_VOLATILE_HOURS",
+    "ANSWER_TTL_STABLE_HOURS",
+    "ANSWER_TTL_EVERGREEN_HOURS",
+];
+
+/// Variables holding the base URL of an HTTP API.
+const URLS: &[&str] = &[
+    "ANTHROPIC_BASE_URL",
+    "OPENAI_BASE_URL",
+    "BEDROCK_ENDPOINT",
+    "LLM_WEBHOOK_URL",
+    "S3_ENDPOINT",
+];
+
+/// API keys, the prefix the provider's own keys start with, and the base
+/// URL that, when set, may point at a gateway with keys of its own.
+const KEYS: &[(&str, &str, Option<&str>)] = &[
+    ("ANTHROPIC_API_KEY", "sk-ant-", Some("ANTHROPIC_BASE_URL")),
+    ("OPENAI_API_KEY", "sk-", Some("OPENAI_BASE_URL")),
+    ("TAVILY_API_KEY", "tvly-", None),
+    ("EXA_API_KEY", "", None),
+    ("AWS_ACCESS_KEY_ID", "", None),
+    ("AWS_SECRET_ACCESS_KEY", "", None),
+];
+
+/// How long a live check waits for a provider to answer.
+const LIVE_CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Worth a look, but the process can run as configured.
+    Warning,
+    /// The process would not run as configured.
+    Error,
+}
+
+/// One problem with a setting, and what to do about it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigIssue {
+    pub severity: Severity,
+    /// The environment variable to change.
+    pub setting: String,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    pub fn error(setting: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            setting: setting.into(),
+            message: message.into(),
+        }
+    }
+
+    pub fn warning(setting: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            setting: setting.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.setting, self.message)
+    }
+}
+
+/// The issues found by one or more checks.
+#[derive(Clone, Debug, Default)]
+pub struct ConfigReport {
+    pub issues: Vec<ConfigIssue>,
+}
+
+impl ConfigReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, issue: ConfigIssue) {
+        self.issues.push(issue);
+    }
+
+    pub fn extend(&mut self, other: ConfigReport) {
+        self.issues.extend(other.issues);
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ConfigIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    /// Logs every issue and, if any is an error, exits the process.
+    pub fn enforce(&self) {
+        for issue in &self.issues {
+            match issue.severity {
+                Severity::Warning => tracing::warn!(setting = %issue.setting, "{}", issue.message),
+                Severity::Error => tracing::error!(setting = %issue.setting, "{}", issue.message),
+            }
+        }
+        if self.has_errors() {
+            tracing::error!(
+                errors = self.errors().count(),
+                "invalid configuration, not starting"
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Checks the process environment with [`check_vars`], and the search
+/// settings as [`SearchConfig`] reads them.
+pub fn check_env() -> ConfigReport {
+    let mut report = check_vars(|name| env::var(name).ok());
+    match SearchConfig::from_env() {
+        // Reported by `check_vars`, which knows about ALLOW_MOCK_PROVIDERS.
+        Ok(_) | Err(ConfigError::NoProvidersConfigured) => {}
+        Err(ConfigError::InvalidSearxngUrl(reason)) => {
+            report.push(ConfigIssue::error("SEARXNG_URL", reason))
+        }
+        Err(ConfigError::InvalidValue { name, reason }) => {
+            report.push(ConfigIssue::error(name, reason))
+        }
+        Err(e) => report.push(ConfigIssue::error("search configuration", e.to_string())),
+    }
+    report
+}
+
+/// Checks the settings `var` returns, without connecting to anything.
+/// Unset and empty variables are left to their defaults.
+pub fn check_vars(var: impl Fn(&str) -> Option<String>) -> ConfigReport {
+    let var = |name: &str| var(name).filter(|s| !s.trim().is_empty());
+    let mut report = ConfigReport::new();
+
+    if let Some(port) = var("PORT") {
+        if port.trim().parse::<u16>().is_err() {
+            report.push(ConfigIssue::error(
+                "PORT",
+                format!("expected a port number, got {:?}", port),
+            ));
+        }
+    }
+    for &name in NUMBERS {
+        if let Some(value) = var(name) {
+            if value.trim().parse::<u64>().is_err() {
+                report.push(ConfigIssue::error(
+                    name,
+                    format!("expected a whole number, got {:?}", value),
+                ));
+            }
+        }
+    }
+
+    for &name in URLS {
+        if let Some(url) = var(name) {
+            if let Err(reason) = check_url(&url, &["http", "https"]) {
+                report.push(ConfigIssue::error(name, reason));
+            }
+        }
+    }
+    if let Some(proxy) = var("HTTP_PROXY_URL") {
+        if let Err(reason) = check_url(&proxy, &["http", "https", "socks5", "socks5h"]) {
+            report.push(ConfigIssue::error("HTTP_PROXY_URL", reason));
+        }
+    }
+
+    for &(name, prefix, gateway) in KEYS {
+        let Some(key) = var(name) else {
+            continue;
+        };
+        if key.trim() != key || key.contains(char::is_whitespace) {
+            report.push(ConfigIssue::error(
+                name,
+                "contains whitespace; check for a stray space or newline pasted with the key",
+            ));
+        } else if !prefix.is_empty()
+            && !key.starts_with(prefix)
+            && gateway.map_or(true, |gateway| var(gateway).is_none())
+        {
+            report.push(ConfigIssue::warning(
+                name,
+                format!(
+                    "does not look like a key for this provider, which start with {:?}",
+                    prefix
+                ),
+            ));
+        }
+    }
+    if var("AWS_ACCESS_KEY_ID").is_some() != var("AWS_SECRET_ACCESS_KEY").is_some() {
+        report.push(ConfigIssue::error(
+            "AWS_SECRET_ACCESS_KEY",
+            "AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY must be set together",
+        ));
+    }
+
+    if let Some(mode) = var("JOB_EXECUTION") {
+        match mode.to_lowercase().as_str() {
+            "inline" => {}
+            "queue" if execution::shared_store().is_some() => {}
+            "queue" => report.push(ConfigIssue::error(
+                "JOB_EXECUTION",
+                "queue mode needs a store shared with gorkd-worker, and this build only has \
+                 the in-memory store; use inline",
+            )),
+            _ => report.push(ConfigIssue::error(
+                "JOB_EXECUTION",
+                format!("expected inline or queue, got {:?}", mode),
+            )),
+        }
+    }
+    if let Some(policy) = var("LLM_MODERATION_POLICY") {
+        if let Err(reason) = ModerationPolicy::from_str(&policy) {
+            report.push(ConfigIssue::error(
+                "LLM_MODERATION_POLICY",
+                format!("{}; expected off, flag or block", reason),
+            ));
+        }
+    }
+    if let Some(schema) = var("LLM_WEBHOOK_SCHEMA") {
+        if let Err(reason) = WebhookSchema::from_str(&schema) {
+            report.push(ConfigIssue::error("LLM_WEBHOOK_SCHEMA", reason));
+        }
+    }
+    if let Some(capture) = var("ARTIFACT_CAPTURE") {
+        if !["off", "store", "dir", "directory"].contains(&capture.to_lowercase().as_str()) {
+            report.push(ConfigIssue::error(
+                "ARTIFACT_CAPTURE",
+                format!("expected off, store or dir, got {:?}", capture),
+            ));
+        }
+    }
+    if let Some(policy) = var("LINK_CHECK") {
+        if policy.to_lowercase() != "off" && DeadLinkPolicy::parse(&policy).is_none() {
+            report.push(ConfigIssue::error(
+                "LINK_CHECK",
+                format!("expected off, mark, rerank or annotate, got {:?}", policy),
+            ));
+        }
+    }
+    if let Some(markers) = var("CITATION_MARKERS") {
+        if CitationMarkers::parse(&markers).is_none() {
+            report.push(ConfigIssue::error(
+                "CITATION_MARKERS",
+                format!("expected numeric or source_id, got {:?}", markers),
+            ));
+        }
+    }
+    if let Some(threshold) = var("ESCALATE_BELOW_CONFIDENCE") {
+        let threshold = threshold.to_lowercase();
+        if !matches!(threshold.trim(), "off" | "high" | "medium" | "low") {
+            report.push(ConfigIssue::error(
+                "ESCALATE_BELOW_CONFIDENCE",
+                format!("expected off, high, medium or low, got {:?}", threshold),
+            ));
+        }
+    }
+    if let Some(samples) = var("CONSISTENCY_SAMPLES") {
+        let valid = samples
+            .trim()
+            .parse::<usize>()
+            .is_ok_and(|n| (1..=MAX_CONSISTENCY_SAMPLES).contains(&n));
+        if !valid {
+            report.push(ConfigIssue::error(
+                "CONSISTENCY_SAMPLES",
+                format!(
+                    "expected a number from 1 to {}, got {:?}",
+                    MAX_CONSISTENCY_SAMPLES, samples
+                ),
+            ));
+        }
+    }
+    if let Some(temperature) = var("CONSISTENCY_TEMPERATURE") {
+        let valid = temperature
+            .trim()
+            .parse::<f32>()
+            .is_ok_and(|t| (0.0..=2.0).contains(&t));
+        if !valid {
+            report.push(ConfigIssue::error(
+                "CONSISTENCY_TEMPERATURE",
+                format!("expected a number from 0 to 2, got {:?}", temperature),
+            ));
+        }
+    }
+    if let Some(storage) = var("OBJECT_STORAGE") {
+        match storage.to_lowercase().as_str() {
+            "off" | "dir" | "directory" => {}
+            "s3" => {
+                let either = |name: &str| {
+                    var(&format!("S3_{}", name)).or_else(|| var(&format!("AWS_{}", name)))
+                };
+                if var("S3_BUCKET").is_none() {
+                    report.push(ConfigIssue::error(
+                        "S3_BUCKET",
+                        "OBJECT_STORAGE is s3 but no bucket is set",
+                    ));
+                }
+                if either("ACCESS_KEY_ID").is_none() || either("SECRET_ACCESS_KEY").is_none() {
+                    report.push(ConfigIssue::error(
+                        "S3_ACCESS_KEY_ID",
+                        "OBJECT_STORAGE is s3 but no keys are set; set S3_ACCESS_KEY_ID and \
+                         S3_SECRET_ACCESS_KEY, or their AWS_ counterparts",
+                    ));
+                }
+            }
+            _ => report.push(ConfigIssue::error(
+                "OBJECT_STORAGE",
+                format!("expected off, dir or s3, got {:?}", storage),
+            )),
+        }
+    }
+    if let Some(publisher) = var("EVENT_PUBLISHER") {
+        match publisher.to_lowercase().as_str() {
+            "off" => {}
+            "nats" if cfg!(feature = "nats") => {}
+            "kafka" if cfg!(feature = "kafka") => {}
+            kind @ ("nats" | "kafka") => report.push(ConfigIssue::error(
+                "EVENT_PUBLISHER",
+                format!(
+                    "this build has no {} support; build with --features {}",
+                    kind, kind
+                ),
+            )),
+            _ => report.push(ConfigIssue::error(
+                "EVENT_PUBLISHER",
+                format!("expected off, nats or kafka, got {:?}", publisher),
+            )),
+        }
+    }
+
+    if let Some(paths) = var("WASM_PLUGINS") {
+        if !cfg!(feature = "wasm-plugins") {
+            report.push(ConfigIssue::error(
+                "WASM_PLUGINS",
+                "this build has no WASM plugin support; build with --features wasm-plugins",
+            ));
+        }
+        for path in paths.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            if let Err(e) = fs::metadata(path) {
+                report.push(ConfigIssue::error(
+                    "WASM_PLUGINS",
+                    format!("cannot read {}: {}", path, e),
+                ));
+            }
+        }
+    }
+
+    if let Some(spec) = var("CORPUS_FEEDS") {
+        if let Err(reason) = feeds::parse_subscriptions(&spec) {
+            report.push(ConfigIssue::error("CORPUS_FEEDS", reason));
+        }
+    }
+
+    if let Some(path) = var("HTTP_CA_CERT_FILE") {
+        match fs::read(&path) {
+            Err(e) => report.push(ConfigIssue::error(
+                "HTTP_CA_CERT_FILE",
+                format!("cannot read {}: {}", path, e),
+            )),
+            Ok(pem) if !String::from_utf8_lossy(&pem).contains("-----BEGIN CERTIFICATE-----") => {
+                report.push(ConfigIssue::error(
+                    "HTTP_CA_CERT_FILE",
+                    format!("{} holds no PEM certificate", path),
+                ))
+            }
+            Ok(_) => {}
+        }
+    }
+    if let Some(path) = var("RESEARCH_PROFILES_FILE") {
+        let profiles = fs::read_to_string(&path)
+            .map_err(|e| format!("cannot read {}: {}", path, e))
+            .and_then(|json| {
+                ResearchProfiles::from_json(&json)
+                    .map_err(|e| format!("invalid profiles in {}: {}", path, e))
+            });
+        if let Err(reason) = profiles {
+            report.push(ConfigIssue::error("RESEARCH_PROFILES_FILE", reason));
+        }
+    }
+
+    let allow_mock = var("ALLOW_MOCK_PROVIDERS")
+        .is_some_and(|s| matches!(s.to_lowercase().as_str(), "true" | "1" | "yes" | "on"));
+    let missing = |setting: &str, message: &str| {
+        if allow_mock {
+            ConfigIssue::warning(setting, format!("{}; using the mock provider", message))
+        } else {
+            ConfigIssue::error(
+                setting,
+                format!(
+                    "{}; set ALLOW_MOCK_PROVIDERS=true to run with the mock provider",
+                    message
+                ),
+            )
+        }
+    };
+    let has_llm = var("ANTHROPIC_API_KEY").is_some()
+        || var("OPENAI_API_KEY").is_some()
+        || var("BEDROCK_REGION").is_some()
+        || var("LLM_WEBHOOK_URL").is_some();
+    if !has_llm {
+        report.push(missing(
+            "ANTHROPIC_API_KEY",
+            "no LLM provider configured: set ANTHROPIC_API_KEY, OPENAI_API_KEY, BEDROCK_REGION \
+             or LLM_WEBHOOK_URL",
+        ));
+    }
+    let mut search_providers: Vec<String> = [
+        ("tavily", "TAVILY_API_KEY"),
+        ("exa", "EXA_API_KEY"),
+        ("searxng", "SEARXNG_URL"),
+        ("webhook", "SEARCH_WEBHOOK_URL"),
+    ]
+    .into_iter()
+    .filter(|(_, setting)| var(setting).is_some())
+    .map(|(provider, _)| provider.to_string())
+    .collect();
+    // A webhooks file that cannot be read is reported by `check_env`.
+    if let Some(webhooks) = var("SEARCH_WEBHOOKS_FILE")
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|json| WebhookConfig::parse_list(&json).ok())
+    {
+        search_providers.extend(webhooks.into_iter().map(|webhook| webhook.id));
+    }
+    if search_providers.is_empty() {
+        report.push(missing(
+            "TAVILY_API_KEY",
+            "no search provider configured: set TAVILY_API_KEY, EXA_API_KEY, SEARXNG_URL, \
+             SEARCH_WEBHOOK_URL or SEARCH_WEBHOOKS_FILE",
+        ));
+    }
+    if let Some(shadow) = var("SEARCH_SHADOW_PROVIDER") {
+        let shadow = shadow.trim().to_lowercase();
+        if !search_providers.contains(&shadow) {
+            report.push(ConfigIssue::error(
+                "SEARCH_SHADOW_PROVIDER",
+                format!(
+                    "{} is not configured; configured providers: {}",
+                    shadow,
+                    list(&search_providers)
+                ),
+            ));
+        } else if search_providers.len() == 1 {
+            report.push(ConfigIssue::error(
+                "SEARCH_SHADOW_PROVIDER",
+                format!("{} is the only provider and cannot shadow itself", shadow),
+            ));
+        }
+    }
+
+    report
+}
+
+/// Whether `CONFIG_CHECK_LIVE` asks for [`check_live`] at startup.
+pub fn live_checks_enabled() -> bool {
+    env::var("CONFIG_CHECK_LIVE")
+        .is_ok_and(|s| matches!(s.to_lowercase().as_str(), "true" | "1" | "yes" | "on"))
+}
+
+/// Checks that every model the LLM settings name was registered.
+pub fn check_state(state: &AppState) -> ConfigReport {
+    let registry = &state.llm_registry;
+    let mut report = ConfigReport::new();
+    let available = registry.available_models();
+    let unavailable = |setting: &str, model: &str| {
+        ConfigIssue::error(
+            setting,
+            format!(
+                "model {} is not available; available models: {}",
+                model,
+                list(&available)
+            ),
+        )
+    };
+
+    match registry.default_model_id() {
+        Some(model) if registry.default().is_none() => {
+            report.push(unavailable("LLM_DEFAULT_MODEL", model))
+        }
+        Some(_) => {}
+        None => report.push(ConfigIssue::error(
+            "LLM_DEFAULT_MODEL",
+            "no default model is set",
+        )),
+    }
+    for model in registry.fallback_model_ids() {
+        if registry.get(model).is_none() {
+            report.push(unavailable("LLM_FALLBACK_MODEL", model));
+        }
+    }
+    if state.routing_policy.is_some() {
+        match registry.fast_model_id() {
+            Some(model) if registry.fast().is_none() => {
+                report.push(unavailable("LLM_FAST_MODEL", model))
+            }
+            Some(_) => {}
+            None => report.push(ConfigIssue::error(
+                "LLM_FAST_MODEL",
+                "LLM_ROUTING is on but no fast model is set",
+            )),
+        }
+    }
+    if let Some(model) = registry.shadow_model_id() {
+        if registry.shadow().is_none() {
+            report.push(ConfigIssue::error(
+                "LLM_SHADOW_MODEL",
+                format!(
+                    "{} must name an available model other than the default; available models: {}",
+                    model,
+                    list(&available)
+                ),
+            ));
+        }
+    }
+
+    report
+}
+
+/// Sends one small request to each LLM provider and one search to each
+/// search provider, reporting those that fail. Costs a few tokens and
+/// search credits per provider.
+pub async fn check_live(state: &AppState) -> ConfigReport {
+    let mut report = ConfigReport::new();
+
+    for (name, provider) in llm_providers(state) {
+        match ping_llm(provider.as_ref()).await {
+            Ok(()) => {
+                tracing::info!(provider = %name, model = provider.model_id(), "LLM provider reachable")
+            }
+            Err(reason) => report.push(ConfigIssue::error(
+                llm_setting(&name),
+                format!(
+                    "{} ({}) rejected a test request: {}",
+                    name,
+                    provider.model_id(),
+                    reason
+                ),
+            )),
+        }
+    }
+
+    for provider in search_providers(state) {
+        let id = provider.provider_id();
+        match ping_search(provider.as_ref()).await {
+            Ok(_) => tracing::info!(provider = %id, "search provider reachable"),
+            Err(reason) => report.push(ConfigIssue::error(
+                search_setting(id),
+                format!("{} rejected a test search: {}", id, reason),
+            )),
+        }
+    }
+
+    report
+}
+
+/// One model of each LLM provider, the default model's first: one is enough
+/// to prove a provider's credentials.
+pub(crate) fn llm_providers(state: &AppState) -> BTreeMap<String, Arc<dyn LlmProvider>> {
+    let registry = &state.llm_registry;
+    let mut providers: BTreeMap<String, Arc<dyn LlmProvider>> = BTreeMap::new();
+    for model in registry.default().into_iter().chain(
+        registry
+            .available_models()
+            .iter()
+            .filter_map(|model| registry.get(model)),
+    ) {
+        providers
+            .entry(model.provider_name().to_string())
+            .or_insert(model);
+    }
+    providers
+}
+
+/// Every registered search provider, the shadow candidate included.
+pub(crate) fn search_providers(state: &AppState) -> Vec<Arc<dyn SearchProvider>> {
+    let search = &state.search_registry;
+    search
+        .providers_in_order()
+        .into_iter()
+        .chain(search.shadow())
+        .collect()
+}
+
+/// Asks `provider` for a few tokens.
+pub(crate) async fn ping_llm(provider: &dyn LlmProvider) -> Result<(), String> {
+    let request = ChatRequest::new(vec![Message::user("Reply with OK.")]).with_max_tokens(5);
+    match tokio::time::timeout(LIVE_CHECK_TIMEOUT, provider.chat(request)).await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(no_answer()),
+    }
+}
+
+/// Runs a small search, returning how many results it found.
+pub(crate) async fn ping_search(provider: &dyn SearchProvider) -> Result<usize, String> {
+    let query = SearchQuery::new("gorkd configuration check");
+    match tokio::time::timeout(LIVE_CHECK_TIMEOUT, provider.search(&query)).await {
+        Ok(Ok(results)) => Ok(results.len()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(no_answer()),
+    }
+}
+
+fn no_answer() -> String {
+    format!("no answer within {}s", LIVE_CHECK_TIMEOUT.as_secs())
+}
+
+fn llm_setting(provider: &str) -> String {
+    match provider {
+        "anthropic" => "ANTHROPIC_API_KEY".to_string(),
+        "openai" => "OPENAI_API_KEY".to_string(),
+        "bedrock" => "AWS_ACCESS_KEY_ID".to_string(),
+        "webhook" => "LLM_WEBHOOK_URL".to_string(),
+        other => format!("{} credentials", other),
+    }
+}
+
+fn search_setting(provider: &str) -> String {
+    match provider {
+        "tavily" => "TAVILY_API_KEY".to_string(),
+        "exa" => "EXA_API_KEY".to_string(),
+        "searxng" => "SEARXNG_URL".to_string(),
+        "webhook" => "SEARCH_WEBHOOK_URL".to_string(),
+        other => format!("{} credentials", other),
+    }
+}
+
+/// Checks that `url` is absolute, with one of `schemes` and a host.
+fn check_url(url: &str, schemes: &[&str]) -> Result<(), String> {
+    let Some((scheme, rest)) = url.trim().split_once("://") else {
+        return Err(format!(
+            "{:?} is not an absolute URL, such as https://host",
+            url
+        ));
+    };
+    if !schemes.contains(&scheme.to_lowercase().as_str()) {
+        return Err(format!(
+            "unsupported scheme {:?}; expected {}",
+            scheme,
+            schemes.join(" or ")
+        ));
+    }
+    let host = rest.rsplit_once('@').map_or(rest, |(_, host)| host);
+    if host.split(['/', ':']).next().unwrap_or("").is_empty() {
+        return Err(format!("{:?} has no host", url));
+    }
+    Ok(())
+}
+
+fn list(items: &[impl AsRef<str>]) -> String {
+    match items {
+        [] => "none".to_string(),
+        items => items
+            .iter()
+            .map(AsRef::as_ref)
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+/// What a process runs with, as a two-column table for the startup log.
+#[derive(Clone, Debug, Default)]
+pub struct ConfigSummary {
+    rows: Vec<(String, String)>,
+}
+
+impl ConfigSummary {
+    /// The providers, models and limits of `state`.
+    pub fn of(state: &AppState) -> Self {
+        let llm = &state.llm_registry;
+        let search = &state.search_registry;
+        let model = |id: Option<&str>| id.unwrap_or("none").to_string();
+        let limits = &state.content_limits;
+
+        let mut search_providers = list(&search.list());
+        if let Some(shadow) = search.shadow_id() {
+            search_providers.push_str(&format!(" (shadow: {})", shadow));
+        }
+        let execution = match state.job_execution {
+            JobExecution::Inline => "inline".to_string(),
+            JobExecution::Queue => "queue".to_string(),
+        };
+
+        Self::default()
+            .with("search providers", search_providers)
+            .with("llm models", list(&llm.available_models()))
+            .with("default model", model(llm.default_model_id()))
+            .with("fallback models", list(llm.fallback_model_ids()))
+            .with(
+                "fast model",
+                match state.routing_policy {
+                    Some(_) => model(llm.fast_model_id()),
+                    None => "off".to_string(),
+                },
+            )
+            .with("shadow model", model(llm.shadow_model_id()))
+            .with(
+                "moderation",
+                format!("{:?}", state.moderation_policy).to_lowercase(),
+            )
+            .with("job execution", execution)
+            .with(
+                "queue depth",
+                state
+                    .job_queue
+                    .max_depth()
+                    .map_or("unlimited".to_string(), |depth| depth.to_string()),
+            )
+            .with("max sources", state.max_sources_limit.to_string())
+            .with(
+                "max per domain",
+                state
+                    .max_per_domain
+                    .map_or("unlimited".to_string(), |max| max.to_string()),
+            )
+            .with(
+                "source content",
+                format!(
+                    "{} bytes each, {} in total",
+                    limits.max_source_bytes, limits.max_total_bytes
+                ),
+            )
+            .with("profiles", list(&state.profiles.names()))
+            .with(
+                "corpus feeds",
+                match state.feed_subscriptions.len() {
+                    0 => "none".to_string(),
+                    feeds => format!("{} every {}s", feeds, state.feed_poll_interval.as_secs()),
+                },
+            )
+    }
+
+    pub fn with(mut self, setting: impl Into<String>, value: impl Into<String>) -> Self {
+        self.rows.push((setting.into(), value.into()));
+        self
+    }
+
+    pub fn rows(&self) -> &[(String, String)] {
+        &self.rows
+    }
+}
+
+impl fmt::Display for ConfigSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.rows.iter().map(|(setting, _)| setting.len()).max();
+        for (setting, value) in &self.rows {
+            writeln!(
+                f,
+                "  {:width$}  {}",
+                setting,
+                value,
+                width = width.unwrap_or(0)
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::error::ErrorCode;
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateResearchRequest {
@@ -10,6 +12,214 @@ pub struct CreateResearchRequest {
         max_length = 2000
     )]
     pub query: String,
+    /// Asks for a `structured` answer matching this schema, alongside the
+    /// prose answer.
+    #[serde(default)]
+    #[schema(nullable)]
+    pub answer_schema: Option<AnswerSchemaRequest>,
+    /// Synthesizes the answer with each of these models over the same
+    /// sources, for comparison. The first model that answers provides the
+    /// job's answer. Empty uses the default model.
+    #[serde(default)]
+    #[schema(example = json!(["gpt-4o", "claude-sonnet-4-20250514"]))]
+    pub models: Vec<String>,
+    /// How much to research and how long an answer to write. Defaults to
+    /// the profile's depth, or `standard`.
+    #[serde(default)]
+    #[schema(nullable)]
+    pub depth: Option<AnswerDepth>,
+    /// The persona and tone the answer is written in. Defaults to the
+    /// profile's style, or `neutral`.
+    #[serde(default)]
+    #[schema(nullable)]
+    pub style: Option<AnswerStyle>,
+    /// Adds the key people, organizations and technologies of the cited
+    /// sources to the answer, as `key_entities`. Costs one more model call.
+    #[serde(default)]
+    pub extract_entities: bool,
+    /// Writes the answer several times and keeps only the claims most of
+    /// them make, listing what they disagreed on among the `limitations`.
+    /// Costs one model call per sample (3 unless configured). Jobs with a
+    /// `max_cost` answer once.
+    #[serde(default)]
+    pub high_stakes: bool,
+    /// Searches the documents ingested through `POST /v1/corpus/documents`
+    /// alongside the web, so answers can cite both.
+    #[serde(default)]
+    pub include_corpus: bool,
+    /// Searches only the corpus, leaving out the web, so the answer draws
+    /// only on the workspace's documents. Implies `include_corpus`.
+    #[serde(default)]
+    pub corpus_only: bool,
+    /// Collects, ranks and stores sources, then completes without an answer
+    /// and without any LLM call. Read the sources from
+    /// `GET /v1/jobs/{job_id}/sources`. Cannot be combined with
+    /// `answer_schema`, `models`, `extract_entities`, `high_stakes` or
+    /// `max_cost`.
+    #[serde(default)]
+    pub sources_only: bool,
+    /// Researches with a configured profile: its trusted domains, content
+    /// type, depth and style.
+    #[serde(default)]
+    #[schema(nullable, example = "medical")]
+    pub profile: Option<String>,
+    /// ISO 639-1 code of the language sources should be in. Overrides the
+    /// profile's.
+    #[serde(default)]
+    #[schema(nullable, example = "de")]
+    pub language: Option<String>,
+    /// ISO 3166-1 alpha-2 code of the country to favor sources from.
+    /// Overrides the profile's.
+    #[serde(default)]
+    #[schema(nullable, example = "DE")]
+    pub country: Option<String>,
+    /// Only sources published at or after this time, for research into a
+    /// past period.
+    #[serde(default)]
+    #[schema(nullable, example = "2023-03-01T00:00:00Z")]
+    pub published_after: Option<DateTime<Utc>>,
+    /// Only sources published before this time.
+    #[serde(default)]
+    #[schema(nullable, example = "2023-07-01T00:00:00Z")]
+    pub published_before: Option<DateTime<Utc>>,
+    /// Sources to collect, in place of the depth's number. At most the
+    /// server's limit (25 unless configured).
+    #[serde(default)]
+    #[schema(nullable, minimum = 1, example = 5)]
+    pub max_sources: Option<usize>,
+    /// Drops search results scoring below this, from 0 to 1.
+    #[serde(default)]
+    #[schema(nullable, minimum = 0.0, maximum = 1.0, example = 0.5)]
+    pub min_score: Option<f32>,
+    /// Sources kept from any one domain. Cannot exceed the server's own
+    /// per-domain cap, when it has one.
+    #[serde(default)]
+    #[schema(nullable, minimum = 1, example = 2)]
+    pub max_per_domain: Option<usize>,
+    /// The most synthesis may cost, in US dollars or tokens. Over budget,
+    /// the model reads fewer sources, then a cheaper model is used, and the
+    /// job fails with `budget_exceeded` when neither fits. Overrides the
+    /// profile's; cannot be combined with `models`.
+    #[serde(default)]
+    #[schema(nullable)]
+    pub max_cost: Option<CostBudget>,
+    /// Labels for finding the job again with `GET /v1/jobs?tag=`. Up to 16,
+    /// each 1-64 letters, digits, `-`, `_`, `.` or `:`.
+    #[serde(default)]
+    #[schema(example = json!(["competitor-analysis", "acme"]))]
+    pub tags: Vec<String>,
+    /// Any JSON object, up to 4 KiB, stored with the job and returned as
+    /// given, such as the IDs of your own records.
+    #[serde(default)]
+    #[schema(nullable, example = json!({"account_id": "acct_42"}))]
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// A spending cap: `{"usd": 0.05}` or `{"tokens": 20000}`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CostBudget {
+    #[schema(example = 0.05)]
+    Usd(f64),
+    #[schema(example = 20000)]
+    Tokens(usize),
+}
+
+impl From<CostBudget> for gorkd_core::CostBudget {
+    fn from(budget: CostBudget) -> Self {
+        match budget {
+            CostBudget::Usd(usd) => Self::Usd(usd),
+            CostBudget::Tokens(tokens) => Self::Tokens(tokens),
+        }
+    }
+}
+
+impl From<gorkd_core::CostBudget> for CostBudget {
+    fn from(budget: gorkd_core::CostBudget) -> Self {
+        match budget {
+            gorkd_core::CostBudget::Usd(usd) => Self::Usd(usd),
+            gorkd_core::CostBudget::Tokens(tokens) => Self::Tokens(tokens),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AnswerDepth {
+    /// A two-sentence answer from 3 sources.
+    Tldr,
+    /// An answer sized to the question type, from up to 10 sources.
+    #[default]
+    Standard,
+    /// A structured long-form report from up to 25 sources.
+    Exhaustive,
+}
+
+impl From<AnswerDepth> for gorkd_core::AnswerDepth {
+    fn from(depth: AnswerDepth) -> Self {
+        match depth {
+            AnswerDepth::Tldr => Self::Tldr,
+            AnswerDepth::Standard => Self::Standard,
+            AnswerDepth::Exhaustive => Self::Exhaustive,
+        }
+    }
+}
+
+impl From<gorkd_core::AnswerDepth> for AnswerDepth {
+    fn from(depth: gorkd_core::AnswerDepth) -> Self {
+        match depth {
+            gorkd_core::AnswerDepth::Tldr => Self::Tldr,
+            gorkd_core::AnswerDepth::Exhaustive => Self::Exhaustive,
+            _ => Self::Standard,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AnswerStyle {
+    /// Plain, impartial prose.
+    #[default]
+    Neutral,
+    /// Bottom line first, then implications, without jargon.
+    Executive,
+    /// Formal and precise, with claims qualified by their evidence.
+    Academic,
+    /// Everyday words and analogies, for a reader new to the topic.
+    Eli5,
+}
+
+impl From<AnswerStyle> for gorkd_core::AnswerStyle {
+    fn from(style: AnswerStyle) -> Self {
+        match style {
+            AnswerStyle::Neutral => Self::Neutral,
+            AnswerStyle::Executive => Self::Executive,
+            AnswerStyle::Academic => Self::Academic,
+            AnswerStyle::Eli5 => Self::Eli5,
+        }
+    }
+}
+
+impl From<gorkd_core::AnswerStyle> for AnswerStyle {
+    fn from(style: gorkd_core::AnswerStyle) -> Self {
+        match style {
+            gorkd_core::AnswerStyle::Executive => Self::Executive,
+            gorkd_core::AnswerStyle::Academic => Self::Academic,
+            gorkd_core::AnswerStyle::Eli5 => Self::Eli5,
+            _ => Self::Neutral,
+        }
+    }
+}
+
+/// A caller-supplied JSON Schema for the structured answer. Supports `type`,
+/// `properties`, `required`, `additionalProperties`, `items`, `enum` and the
+/// length and range bounds; the root must be an object.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AnswerSchemaRequest {
+    #[schema(example = "product_comparison", max_length = 64)]
+    pub name: String,
+    #[schema(value_type = Object)]
+    pub schema: serde_json::Value,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -19,9 +229,40 @@ pub struct CreateResearchResponse {
     pub status: JobStatus,
     #[schema(example = "/v1/jobs/job_abc123xyz456/stream")]
     pub stream_url: String,
+    /// The failed job this one retries. Set by `POST /v1/jobs/{id}/retry`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "job_def456uvw789")]
+    pub retried_from: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, ToSchema)]
+/// What went wrong with a failed job.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FailureDetail {
+    /// The status the job was in when it failed.
+    pub stage: JobStatus,
+    pub kind: ErrorCode,
+    /// The search provider or LLM model that failed, if one did.
+    #[schema(nullable, example = "tavily")]
+    pub provider: Option<String>,
+    /// Whether submitting the query again may succeed.
+    pub retryable: bool,
+    #[schema(example = "search failed: rate limited by provider: tavily")]
+    pub message: String,
+}
+
+impl From<gorkd_core::JobFailure> for FailureDetail {
+    fn from(failure: gorkd_core::JobFailure) -> Self {
+        Self {
+            stage: failure.stage.into(),
+            kind: failure.kind.into(),
+            provider: failure.provider,
+            retryable: failure.retryable,
+            message: failure.message,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum JobStatus {
     Pending,
@@ -48,6 +289,20 @@ impl From<gorkd_core::JobStatus> for JobStatus {
     }
 }
 
+impl From<JobStatus> for gorkd_core::JobStatus {
+    fn from(status: JobStatus) -> Self {
+        match status {
+            JobStatus::Pending => Self::Pending,
+            JobStatus::Planning => Self::Planning,
+            JobStatus::Searching => Self::Searching,
+            JobStatus::Fetching => Self::Fetching,
+            JobStatus::Synthesizing => Self::Synthesizing,
+            JobStatus::Completed => Self::Completed,
+            JobStatus::Failed => Self::Failed,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct JobResponse {
     #[schema(example = "job_abc123xyz456")]
@@ -55,10 +310,112 @@ pub struct JobResponse {
     pub status: JobStatus,
     #[schema(example = "What caused the 2024 CrowdStrike outage?")]
     pub query: String,
+    pub depth: AnswerDepth,
+    pub style: AnswerStyle,
+    /// Whether the answer lists its key entities.
+    pub extract_entities: bool,
+    /// Whether the answer was sampled several times for consistency.
+    pub high_stakes: bool,
+    /// Whether the document corpus was searched alongside the web.
+    pub include_corpus: bool,
+    /// Whether only the document corpus was searched.
+    pub corpus_only: bool,
+    /// Whether the job only collected sources, so it has no answer.
+    pub sources_only: bool,
+    /// Workspace whose corpus the job searches, when not the default one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(nullable, example = "research-team")]
+    pub workspace: Option<String>,
+    /// The research profile the job was created with.
+    #[schema(nullable, example = "medical")]
+    pub profile: Option<String>,
+    /// Language the job's sources were searched in.
+    #[schema(nullable, example = "de")]
+    pub language: Option<String>,
+    /// Country the job's search favored.
+    #[schema(nullable, example = "DE")]
+    pub country: Option<String>,
+    #[schema(nullable)]
+    pub published_after: Option<DateTime<Utc>>,
+    #[schema(nullable)]
+    pub published_before: Option<DateTime<Utc>>,
+    /// The most the job's synthesis may cost.
+    #[schema(nullable)]
+    pub max_cost: Option<CostBudget>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     #[schema(nullable)]
     pub error_message: Option<String>,
+    /// Why the job failed; see the error code catalog. Same as
+    /// `failure.kind`.
+    #[schema(nullable)]
+    pub error_code: Option<ErrorCode>,
+    #[schema(nullable)]
+    pub failure: Option<FailureDetail>,
+    /// The failed job this one retries.
+    #[schema(nullable, example = "job_def456uvw789")]
+    pub retried_from: Option<String>,
+    /// Runs of the query so far, counting this one.
+    #[schema(example = 1)]
+    pub attempt: u32,
+    #[schema(example = json!(["competitor-analysis"]))]
+    pub tags: Vec<String>,
+    /// The metadata the job was created with.
+    #[schema(nullable)]
+    pub metadata: Option<serde_json::Value>,
+    /// Estimated progress, from the job's event log. Only when a single job
+    /// is requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress: Option<ProgressDetail>,
+    #[schema(nullable)]
+    pub answer: Option<AnswerDetail>,
+}
+
+impl JobResponse {
+    pub fn with_answer(mut self, answer: Option<gorkd_core::ResearchAnswer>) -> Self {
+        self.answer = answer.map(Into::into);
+        self
+    }
+
+    pub fn with_progress(mut self, events: &[gorkd_core::JobEvent]) -> Self {
+        self.progress = Some(gorkd_core::JobProgress::estimate(events).into());
+        self
+    }
+}
+
+/// How far along a job is, estimated from the steps it has recorded.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ProgressDetail {
+    /// Estimated share of the job done, from 0 to 100. Only completed jobs
+    /// reach 100; failed ones keep what they had reached.
+    #[schema(example = 40)]
+    pub percent: u8,
+    pub stage: JobStatus,
+    /// Queries searched so far out of those planned, once searching has
+    /// begun.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queries: Option<QueryProgressDetail>,
+}
+
+impl From<gorkd_core::JobProgress> for ProgressDetail {
+    fn from(progress: gorkd_core::JobProgress) -> Self {
+        Self {
+            percent: progress.percent,
+            stage: progress.stage.into(),
+            queries: progress.queries.map(|q| QueryProgressDetail {
+                executed: q.executed,
+                planned: q.planned,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct QueryProgressDetail {
+    #[schema(example = 2)]
+    pub executed: usize,
+    #[schema(example = 3)]
+    pub planned: usize,
 }
 
 impl From<gorkd_core::ResearchJob> for JobResponse {
@@ -67,42 +424,2325 @@ impl From<gorkd_core::ResearchJob> for JobResponse {
             job_id: job.id.to_string(),
             status: job.status.into(),
             query: job.query,
+            depth: job.depth.into(),
+            style: job.style.into(),
+            extract_entities: job.extract_entities,
+            high_stakes: job.high_stakes,
+            include_corpus: job.include_corpus,
+            corpus_only: job.corpus_only,
+            sources_only: job.sources_only,
+            workspace: job.workspace,
+            profile: job.profile,
+            language: job.filters.language,
+            country: job.filters.country,
+            published_after: job.filters.published_after,
+            published_before: job.filters.published_before,
+            max_cost: job.max_cost.map(Into::into),
             created_at: job.created_at,
             updated_at: job.updated_at,
             error_message: job.error_message,
+            error_code: job.failure.as_ref().map(|f| f.kind.into()),
+            failure: job.failure.map(Into::into),
+            retried_from: job.retried_from.map(|id| id.to_string()),
+            attempt: job.attempt,
+            tags: job.tags,
+            metadata: job.metadata,
+            progress: None,
+            answer: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Confidence {
+    High,
+    Medium,
+    Low,
+    Insufficient,
+}
+
+impl From<gorkd_core::Confidence> for Confidence {
+    fn from(confidence: gorkd_core::Confidence) -> Self {
+        match confidence {
+            gorkd_core::Confidence::High => Self::High,
+            gorkd_core::Confidence::Medium => Self::Medium,
+            gorkd_core::Confidence::Low => Self::Low,
+            gorkd_core::Confidence::Insufficient => Self::Insufficient,
+            _ => Self::Insufficient,
         }
     }
 }
 
 #[derive(Debug, Serialize, ToSchema)]
-pub struct SourceDetail {
+pub struct CitationDetail {
+    #[schema(example = "The outage was caused by a faulty configuration update")]
+    pub claim: String,
     #[schema(example = "src_abc123xyz456")]
-    pub id: String,
-    #[schema(example = "https://blogs.microsoft.com/...")]
-    pub url: String,
-    #[schema(example = "Helping our customers through the CrowdStrike outage")]
-    pub title: String,
-    #[schema(example = "microsoft.com")]
-    pub domain: String,
+    pub source_id: String,
     #[schema(nullable)]
-    pub published_at: Option<DateTime<Utc>>,
-    pub relevance_score: f32,
+    pub quote: Option<String>,
+    /// Whether `quote` was found in the source; `false` flags a quote the
+    /// source may not support. Null for citations without a quote.
+    #[schema(nullable)]
+    pub quote_found: Option<bool>,
+    /// Where `quote` is in the source content.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote_span: Option<TextSpan>,
+    /// The source URL with a `#:~:text=` fragment that scrolls to and
+    /// highlights the quote. Set for quotes found in web pages.
+    #[schema(nullable)]
+    pub anchored_url: Option<String>,
+    /// Whether the cited page could be reached when the answer was
+    /// delivered. Omitted when links are not checked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_status: Option<LinkStatus>,
+    /// The number the answer's text cites the source by, as in `[2]`.
+    /// Omitted when the text cites sources by ID.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = 2)]
+    pub reference: Option<usize>,
 }
 
-impl From<gorkd_core::Source> for SourceDetail {
-    fn from(source: gorkd_core::Source) -> Self {
+impl From<gorkd_core::Citation> for CitationDetail {
+    fn from(citation: gorkd_core::Citation) -> Self {
+        let quote_span = citation.quote_location.as_ref().and_then(TextSpan::of);
         Self {
-            id: source.id.to_string(),
-            url: source.url,
-            title: source.title,
-            domain: source.metadata.domain,
-            published_at: source.metadata.published_at,
-            relevance_score: source.relevance_score,
+            claim: citation.claim,
+            source_id: citation.source_id.to_string(),
+            quote: citation.quote,
+            quote_found: citation.quote_location.map(|l| l.is_found()),
+            quote_span,
+            anchored_url: citation.anchored_url,
+            link_status: citation.link_status.map(Into::into),
+            reference: citation.reference,
         }
     }
 }
 
+/// The source a numeric marker such as `[1]` in an answer refers to.
 #[derive(Debug, Serialize, ToSchema)]
-pub struct JobSourceResponse {
-    pub sources: Vec<SourceDetail>,
+pub struct ReferenceDetail {
+    #[schema(example = 1)]
+    pub number: usize,
+    #[schema(example = "src_abc123xyz456")]
+    pub source_id: String,
+}
+
+impl From<gorkd_core::Reference> for ReferenceDetail {
+    fn from(reference: gorkd_core::Reference) -> Self {
+        Self {
+            number: reference.number,
+            source_id: reference.source_id.to_string(),
+        }
+    }
+}
+
+/// One sentence of an answer's `detail` and the citations supporting it.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SentenceEvidenceDetail {
+    /// Character offset of the sentence in `detail`.
+    #[schema(example = 0)]
+    pub start: usize,
+    /// Character offset just past the sentence.
+    #[schema(example = 58)]
+    pub end: usize,
+    /// Positions in the answer's `citations`; empty for sentences nothing
+    /// supports.
+    #[schema(example = json!([0]))]
+    pub citations: Vec<usize>,
+    /// The sources the sentence cites, each once.
+    #[schema(example = json!(["src_abc123xyz456"]))]
+    pub source_ids: Vec<String>,
+}
+
+impl From<gorkd_core::SentenceEvidence> for SentenceEvidenceDetail {
+    fn from(evidence: gorkd_core::SentenceEvidence) -> Self {
+        Self {
+            start: evidence.start,
+            end: evidence.end,
+            citations: evidence.citations,
+            source_ids: evidence
+                .source_ids
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkStatus {
+    Live,
+    /// The page is gone or its host does not answer.
+    Dead,
+    /// The check timed out or the site turned it away.
+    Unknown,
+}
+
+impl From<gorkd_core::LinkStatus> for LinkStatus {
+    fn from(status: gorkd_core::LinkStatus) -> Self {
+        match status {
+            gorkd_core::LinkStatus::Live => Self::Live,
+            gorkd_core::LinkStatus::Dead => Self::Dead,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// A range of a source's content, in characters (Unicode scalar values).
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TextSpan {
+    #[schema(example = 120)]
+    pub start: usize,
+    /// Exclusive.
+    #[schema(example = 184)]
+    pub end: usize,
+    /// Whether the quote appears verbatim, rather than with different case,
+    /// whitespace or punctuation, or with elided words.
+    pub exact: bool,
+}
+
+impl TextSpan {
+    fn of(location: &gorkd_core::QuoteLocation) -> Option<Self> {
+        match *location {
+            gorkd_core::QuoteLocation::Found { start, end, exact } => {
+                Some(Self { start, end, exact })
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ModerationDetail {
+    pub flagged: bool,
+    #[schema(example = json!(["violence"]))]
+    pub categories: Vec<String>,
+    #[schema(example = "openai")]
+    pub moderator: String,
+}
+
+impl From<gorkd_core::ModerationVerdict> for ModerationDetail {
+    fn from(verdict: gorkd_core::ModerationVerdict) -> Self {
+        Self {
+            flagged: verdict.flagged,
+            categories: verdict.categories,
+            moderator: verdict.moderator,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AnswerDetail {
+    #[schema(example = "A faulty content update to CrowdStrike Falcon crashed Windows hosts.")]
+    pub summary: String,
+    /// Cites sources inline as `[1]`, numbered in `references`, or as
+    /// `[src_abc123]` where the server keeps source ID markers.
+    pub detail: String,
+    pub citations: Vec<CitationDetail>,
+    /// The source each numeric marker of `summary` and `detail` refers to,
+    /// in order. Omitted when the text cites sources by ID.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<ReferenceDetail>,
+    /// Each sentence of `detail`, by character offsets, with the citations
+    /// supporting it, for showing the evidence of a sentence on hover.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub evidence: Vec<SentenceEvidenceDetail>,
+    pub confidence: Confidence,
+    pub limitations: Vec<String>,
+    /// Up to three questions to research next, for one-click deeper dives.
+    /// Each can be sent as the `query` of a new job.
+    #[schema(example = json!(["How did CrowdStrike change its update process afterwards?"]))]
+    pub suggested_followups: Vec<String>,
+    #[schema(example = "claude-sonnet-4-20250514")]
+    pub model: String,
+    #[schema(nullable)]
+    pub moderation: Option<ModerationDetail>,
+    pub token_usage: TokenUsageDetail,
+    /// How the job's `max_cost` shaped synthesis; absent without one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget: Option<BudgetDetail>,
+    /// Why routing chose `model`; absent when routing is off.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub routing: Option<RoutingDetail>,
+    /// How the job searched again after a first answer below the
+    /// server's confidence threshold; absent when it did not.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub escalation: Option<EscalationDetail>,
+    /// How the samples of a `high_stakes` job agreed; absent for other
+    /// jobs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consistency: Option<ConsistencyDetail>,
+    /// Present when the job was created with an `answer_schema`; matches it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Object, nullable)]
+    pub structured: Option<serde_json::Value>,
+    /// The sections of an exhaustive answer written from an outline, in the
+    /// order `detail` presents them; absent for answers written in one pass.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sections: Vec<AnswerSectionDetail>,
+    /// The key entities of the cited sources, most important first; present
+    /// when the job was created with `extract_entities` and extraction
+    /// succeeded.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub key_entities: Vec<KeyEntityDetail>,
+    /// When the answer goes out of date; absent for answers written before
+    /// freshness was tracked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub freshness: Option<FreshnessDetail>,
+    /// Why the sources could not answer the question, and what to try
+    /// instead; present only when `confidence` is `insufficient`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub why_insufficient: Option<WhyInsufficientDetail>,
+}
+
+/// What was searched for an unanswerable question and what might answer it.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WhyInsufficientDetail {
+    #[schema(example = json!(["rust 2024 edition release date"]))]
+    pub searched: Vec<String>,
+    /// Kinds of sources that would answer the question but were not found.
+    #[schema(example = json!(["The Rust project's official release announcements"]))]
+    pub missing_sources: Vec<String>,
+    /// Queries more likely to find an answer, each usable as the `query` of
+    /// a new job.
+    #[schema(example = json!(["When was the Rust 2024 edition stabilized?"]))]
+    pub reformulations: Vec<String>,
+}
+
+impl From<gorkd_core::WhyInsufficient> for WhyInsufficientDetail {
+    fn from(why: gorkd_core::WhyInsufficient) -> Self {
+        Self {
+            searched: why.searched,
+            missing_sources: why.missing_sources,
+            reformulations: why.reformulations,
+        }
+    }
+}
+
+/// How quickly an answer's topic changes and whether the answer is stale.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FreshnessDetail {
+    pub volatility: Volatility,
+    /// When the answer stops being current.
+    pub expires_at: DateTime<Utc>,
+    /// Whether `expires_at` has passed.
+    pub stale: bool,
+    /// What to do about a stale answer; absent while it is current.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(
+        example = "This answer may be out of date. Run the query again with POST /v1/research for a current one."
+    )]
+    pub suggestion: Option<String>,
+}
+
+impl From<gorkd_core::AnswerFreshness> for FreshnessDetail {
+    fn from(freshness: gorkd_core::AnswerFreshness) -> Self {
+        let stale = freshness.is_stale(Utc::now());
+        Self {
+            volatility: freshness.volatility.into(),
+            expires_at: freshness.expires_at,
+            stale,
+            suggestion: stale.then(|| {
+                "This answer may be out of date. Run the query again with POST /v1/research \
+                 for a current one."
+                    .to_string()
+            }),
+        }
+    }
+}
+
+/// How quickly what an answer says goes out of date.
+#[derive(Debug, Clone, Copy, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Volatility {
+    /// What is happening right now; current for hours.
+    Breaking,
+    /// The last week or month; current for about a day.
+    Volatile,
+    /// Facts and comparisons; current for about a week.
+    Stable,
+    /// Explanations, how-tos and history; current for about a month.
+    Evergreen,
+}
+
+impl From<gorkd_core::Volatility> for Volatility {
+    fn from(volatility: gorkd_core::Volatility) -> Self {
+        match volatility {
+            gorkd_core::Volatility::Breaking => Self::Breaking,
+            gorkd_core::Volatility::Volatile => Self::Volatile,
+            gorkd_core::Volatility::Evergreen => Self::Evergreen,
+            _ => Self::Stable,
+        }
+    }
+}
+
+/// One section of an answer written section by section.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AnswerSectionDetail {
+    #[schema(example = "Root cause")]
+    pub title: String,
+    /// The sources the section was written from.
+    pub source_ids: Vec<String>,
+    /// The section's citations; each is also in the answer's `citations`.
+    pub citations: Vec<CitationDetail>,
+}
+
+impl From<gorkd_core::AnswerSection> for AnswerSectionDetail {
+    fn from(section: gorkd_core::AnswerSection) -> Self {
+        Self {
+            title: section.title,
+            source_ids: section.source_ids.iter().map(ToString::to_string).collect(),
+            citations: section.citations.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityKind {
+    Person,
+    Organization,
+    Technology,
+    Place,
+    Other,
+}
+
+impl From<gorkd_core::EntityKind> for EntityKind {
+    fn from(kind: gorkd_core::EntityKind) -> Self {
+        match kind {
+            gorkd_core::EntityKind::Person => Self::Person,
+            gorkd_core::EntityKind::Organization => Self::Organization,
+            gorkd_core::EntityKind::Technology => Self::Technology,
+            gorkd_core::EntityKind::Place => Self::Place,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// A person, organization, technology or other entity the answer's sources
+/// discuss.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct KeyEntityDetail {
+    #[schema(example = "CrowdStrike Falcon")]
+    pub name: String,
+    pub kind: EntityKind,
+    #[schema(example = "CrowdStrike's endpoint security agent, which runs in the Windows kernel.")]
+    pub description: String,
+    /// The sources the description was drawn from.
+    pub source_ids: Vec<String>,
+}
+
+impl From<gorkd_core::KeyEntity> for KeyEntityDetail {
+    fn from(entity: gorkd_core::KeyEntity) -> Self {
+        Self {
+            name: entity.name,
+            kind: entity.kind.into(),
+            description: entity.description,
+            source_ids: entity.source_ids.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
+
+impl From<gorkd_core::ResearchAnswer> for AnswerDetail {
+    fn from(answer: gorkd_core::ResearchAnswer) -> Self {
+        let token_usage = TokenUsageDetail::from(&answer.synthesis_metadata);
+        let budget = answer.synthesis_metadata.budget.map(Into::into);
+        let routing = answer.synthesis_metadata.routing.map(Into::into);
+        let escalation = answer.synthesis_metadata.escalation.map(Into::into);
+        let consistency = answer.synthesis_metadata.consistency.map(Into::into);
+        Self {
+            summary: answer.summary,
+            detail: answer.detail,
+            citations: answer.citations.into_iter().map(Into::into).collect(),
+            references: answer.references.into_iter().map(Into::into).collect(),
+            evidence: answer.evidence.into_iter().map(Into::into).collect(),
+            confidence: answer.confidence.into(),
+            limitations: answer.limitations,
+            suggested_followups: answer.suggested_followups,
+            model: answer.synthesis_metadata.model,
+            moderation: answer.synthesis_metadata.moderation.map(Into::into),
+            token_usage,
+            budget,
+            routing,
+            escalation,
+            consistency,
+            structured: answer.structured,
+            sections: answer.sections.into_iter().map(Into::into).collect(),
+            key_entities: answer.key_entities.into_iter().map(Into::into).collect(),
+            freshness: answer.freshness.map(Into::into),
+            why_insufficient: answer.why_insufficient.map(Into::into),
+        }
+    }
+}
+
+/// Tokens spent on the job, split by the pipeline stage that spent them.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TokenUsageDetail {
+    #[schema(example = 4200)]
+    pub total_tokens: usize,
+    /// Tokens the prompts of all stages took.
+    #[schema(example = 3600)]
+    pub prompt_tokens: usize,
+    /// Tokens the models generated across all stages.
+    #[schema(example = 600)]
+    pub completion_tokens: usize,
+    /// Empty for answers recorded before per-stage tracking.
+    pub stages: Vec<StageTokenUsageDetail>,
+    /// What the answer's model charged for its stages, when its price is
+    /// known.
+    #[schema(nullable, example = 0.0198)]
+    pub cost_usd: Option<f64>,
+    /// How much of the model's context window the synthesis prompt filled,
+    /// and how many sources it left out. Absent for answers recorded
+    /// before it was tracked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<ContextUsageDetail>,
+}
+
+impl From<&gorkd_core::SynthesisMetadata> for TokenUsageDetail {
+    fn from(metadata: &gorkd_core::SynthesisMetadata) -> Self {
+        Self {
+            total_tokens: metadata.total_tokens(),
+            prompt_tokens: metadata.prompt_tokens(),
+            completion_tokens: metadata.completion_tokens(),
+            stages: metadata.stage_usage.iter().map(Into::into).collect(),
+            cost_usd: metadata.cost_usd,
+            context: metadata.context.as_ref().map(Into::into),
+        }
+    }
+}
+
+/// How full the synthesis prompt left the model's context window.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ContextUsageDetail {
+    /// Tokens the model accepts in one request.
+    #[schema(example = 128000)]
+    pub window_tokens: usize,
+    #[schema(example = 3600)]
+    pub prompt_tokens: usize,
+    /// Share of the window the prompt took, in percent.
+    #[schema(example = 2.8)]
+    pub utilization_percent: f32,
+    /// Sources the job had to answer from.
+    #[schema(example = 12)]
+    pub sources_available: usize,
+    /// Sources the prompt included; the rest were dropped by the context
+    /// source limit or the job's budget.
+    #[schema(example = 10)]
+    pub sources_used: usize,
+    /// Included sources whose content was shortened to fit the content
+    /// limits.
+    #[schema(example = 2)]
+    pub sources_truncated: usize,
+}
+
+impl From<&gorkd_core::ContextUsage> for ContextUsageDetail {
+    fn from(usage: &gorkd_core::ContextUsage) -> Self {
+        Self {
+            window_tokens: usage.window_tokens,
+            prompt_tokens: usage.prompt_tokens,
+            utilization_percent: usage.utilization(),
+            sources_available: usage.sources_available,
+            sources_used: usage.sources_used,
+            sources_truncated: usage.sources_truncated,
+        }
+    }
+}
+
+/// How a job's budget was applied before synthesis, and what it spent.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BudgetDetail {
+    pub max_cost: CostBudget,
+    /// Tokens synthesis was expected to use, counting the full answer
+    /// length allowed.
+    #[schema(example = 6100)]
+    pub estimated_tokens: usize,
+    #[schema(nullable, example = 0.0312)]
+    pub estimated_usd: Option<f64>,
+    /// Sources the model read; fewer than usual when the budget trimmed
+    /// them.
+    #[schema(example = 3)]
+    pub context_sources: usize,
+    /// The model the job would have used, when the budget switched to a
+    /// cheaper one.
+    #[schema(nullable, example = "claude-sonnet-4-20250514")]
+    pub downgraded_from: Option<String>,
+    #[schema(example = 4200)]
+    pub spent_tokens: usize,
+    #[schema(nullable, example = 0.0198)]
+    pub spent_usd: Option<f64>,
+}
+
+impl From<gorkd_core::BudgetReport> for BudgetDetail {
+    fn from(report: gorkd_core::BudgetReport) -> Self {
+        Self {
+            max_cost: report.max_cost.into(),
+            estimated_tokens: report.estimate.tokens(),
+            estimated_usd: report.estimate.usd,
+            context_sources: report.context_sources,
+            downgraded_from: report.downgraded_from,
+            spent_tokens: report.spent_tokens,
+            spent_usd: report.spent_usd,
+        }
+    }
+}
+
+/// The model a job was routed to by the complexity of its question.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RoutingDetail {
+    pub tier: ModelTier,
+    #[schema(example = "claude-3-5-haiku-20241022")]
+    pub model: String,
+    #[schema(example = "simple factual question")]
+    pub reason: String,
+    #[schema(example = "factual")]
+    pub question_type: String,
+    #[schema(example = 6)]
+    pub words: usize,
+    #[schema(example = 1)]
+    pub entities: usize,
+    /// Questions answered in turn: one, plus one for each dependent clause
+    /// or follow-up question.
+    #[schema(example = 1)]
+    pub hops: usize,
+}
+
+impl From<gorkd_core::RoutingDecision> for RoutingDetail {
+    fn from(decision: gorkd_core::RoutingDecision) -> Self {
+        let complexity = decision.complexity;
+        Self {
+            tier: decision.tier.into(),
+            model: decision.model,
+            reason: decision.reason,
+            question_type: complexity.question_type.to_string(),
+            words: complexity.words,
+            entities: complexity.entities,
+            hops: complexity.hops,
+        }
+    }
+}
+
+/// The broader search a job ran after its first answer came back unsure.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EscalationDetail {
+    /// Confidence of the first answer.
+    pub initial_confidence: Confidence,
+    #[schema(example = json!(["rust borrow checker", "rust borrow checker explained"]))]
+    pub queries: Vec<String>,
+    /// Filters of the job the broader search dropped.
+    #[schema(example = json!(["recency"]))]
+    pub relaxed_filters: Vec<String>,
+    #[schema(example = 12)]
+    pub max_sources: usize,
+    /// Sources the broader search found that the first had not.
+    #[schema(example = 4)]
+    pub sources_added: usize,
+    /// Whether this answer is the second one, which was at least as
+    /// confident as the first.
+    pub replaced: bool,
+    /// Why the broader search failed; the first answer was kept.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl From<gorkd_core::EscalationReport> for EscalationDetail {
+    fn from(report: gorkd_core::EscalationReport) -> Self {
+        Self {
+            initial_confidence: report.initial_confidence.into(),
+            queries: report.queries,
+            relaxed_filters: report.relaxed_filters,
+            max_sources: report.max_sources,
+            sources_added: report.sources_added,
+            replaced: report.replaced,
+            error: report.error,
+        }
+    }
+}
+
+/// How the answers sampled for a high-stakes job agreed.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConsistencyDetail {
+    /// Answers the job synthesized.
+    #[schema(example = 3)]
+    pub samples: usize,
+    /// Samples that answered; the others failed.
+    #[schema(example = 3)]
+    pub answered: usize,
+    #[schema(example = 0.7)]
+    pub temperature: f32,
+    /// Share of the kept answer's citations most samples made, from 0 to 1.
+    #[schema(example = 0.75)]
+    pub agreement: f32,
+    /// Claims too few samples made, left out of the citations.
+    pub disputed: Vec<DisputedClaimDetail>,
+    /// Whether the samples came back with different confidence; the answer
+    /// has the median one.
+    pub confidence_disagreed: bool,
+}
+
+impl From<gorkd_core::ConsistencyReport> for ConsistencyDetail {
+    fn from(report: gorkd_core::ConsistencyReport) -> Self {
+        Self {
+            samples: report.samples,
+            answered: report.answered,
+            temperature: report.temperature,
+            agreement: report.agreement,
+            disputed: report.disputed.into_iter().map(Into::into).collect(),
+            confidence_disagreed: report.confidence_disagreed,
+        }
+    }
+}
+
+/// A claim one sample made that most of the others did not.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DisputedClaimDetail {
+    #[schema(example = "The outage affected 8.5 million devices")]
+    pub claim: String,
+    #[schema(example = "src_abc123")]
+    pub source_id: String,
+    /// Samples that made the claim.
+    #[schema(example = 1)]
+    pub samples: usize,
+}
+
+impl From<gorkd_core::DisputedClaim> for DisputedClaimDetail {
+    fn from(claim: gorkd_core::DisputedClaim) -> Self {
+        Self {
+            claim: claim.claim,
+            source_id: claim.source_id.to_string(),
+            samples: claim.samples,
+        }
+    }
+}
+
+/// `fast` for the cheap model simple questions are routed to, `premium`
+/// for the default model.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelTier {
+    Fast,
+    Premium,
+}
+
+impl From<gorkd_core::ModelTier> for ModelTier {
+    fn from(tier: gorkd_core::ModelTier) -> Self {
+        match tier {
+            gorkd_core::ModelTier::Fast => Self::Fast,
+            gorkd_core::ModelTier::Premium => Self::Premium,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LlmStage {
+    Planning,
+    Outline,
+    Synthesis,
+    Extraction,
+    Verification,
+}
+
+impl From<gorkd_core::LlmStage> for LlmStage {
+    fn from(stage: gorkd_core::LlmStage) -> Self {
+        match stage {
+            gorkd_core::LlmStage::Planning => Self::Planning,
+            gorkd_core::LlmStage::Outline => Self::Outline,
+            gorkd_core::LlmStage::Extraction => Self::Extraction,
+            gorkd_core::LlmStage::Verification => Self::Verification,
+            _ => Self::Synthesis,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StageTokenUsageDetail {
+    pub stage: LlmStage,
+    #[schema(example = "claude-sonnet-4-20250514")]
+    pub model: String,
+    #[schema(example = 3600)]
+    pub prompt_tokens: usize,
+    #[schema(example = 600)]
+    pub completion_tokens: usize,
+    #[schema(example = 4200)]
+    pub total_tokens: usize,
+}
+
+impl From<&gorkd_core::StageTokenUsage> for StageTokenUsageDetail {
+    fn from(usage: &gorkd_core::StageTokenUsage) -> Self {
+        Self {
+            stage: usage.stage.into(),
+            model: usage.model.clone(),
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            total_tokens: usage.total(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SourceDetail {
+    #[schema(example = "src_abc123xyz456")]
+    pub id: String,
+    #[schema(example = "https://blogs.microsoft.com/...")]
+    pub url: String,
+    #[schema(example = "Helping our customers through the CrowdStrike outage")]
+    pub title: String,
+    #[schema(example = "microsoft.com")]
+    pub domain: String,
+    #[schema(nullable)]
+    pub published_at: Option<DateTime<Utc>>,
+    pub relevance_score: f32,
+    /// Search provider that returned this source.
+    #[schema(example = "tavily", nullable)]
+    pub provider: Option<String>,
+    /// Image URLs the search provider associated with this source.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<String>,
+    /// Whether the source's content was cut to fit the content size limits.
+    pub truncated: bool,
+    /// Format of the page the content was extracted from, when it was
+    /// fetched by gorkd rather than returned by the search provider.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<DocumentFormat>,
+    /// How readers' feedback has judged the source's domain, when it had any
+    /// at search time. `relevance_score` already includes the domain's trust.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trust: Option<TrustLabel>,
+    /// The Wayback Machine snapshot the content was read from, when the page
+    /// itself was gone or paywalled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archived: Option<ArchivedSnapshotDetail>,
+    /// The stretch of the content where the query's terms occur, so a UI
+    /// can show why the source was retrieved without loading its content.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<SnippetDetail>,
+    /// The text the answer quotes from this source, in content order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<SourceHighlight>,
+    /// The extracted text the highlights point into; only with
+    /// `include_content=true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+impl SourceDetail {
+    /// Adds the quotes of `answer` found in `source`, and its content if
+    /// asked for.
+    pub fn new(
+        source: gorkd_core::Source,
+        answer: Option<&gorkd_core::ResearchAnswer>,
+        include_content: bool,
+    ) -> Self {
+        let mut highlights: Vec<SourceHighlight> = answer
+            .into_iter()
+            .flat_map(|a| &a.citations)
+            .filter(|c| c.source_id == source.id)
+            .filter_map(|c| {
+                let span = TextSpan::of(c.quote_location.as_ref()?)?;
+                Some(SourceHighlight {
+                    text: gorkd_core::highlight::char_slice(&source.content, span.start, span.end)
+                        .to_string(),
+                    claim: c.claim.clone(),
+                    span,
+                })
+            })
+            .collect();
+        highlights.sort_by_key(|h| h.span.start);
+
+        let content = include_content.then(|| source.content.clone());
+        Self {
+            highlights,
+            content,
+            ..source.into()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ArchivedSnapshotDetail {
+    #[schema(example = "https://web.archive.org/web/20240719043012/https://example.com/story")]
+    pub url: String,
+    /// When the archive captured the page.
+    pub captured_at: DateTime<Utc>,
+}
+
+impl From<gorkd_core::ArchivedSnapshot> for ArchivedSnapshotDetail {
+    fn from(snapshot: gorkd_core::ArchivedSnapshot) -> Self {
+        Self {
+            url: snapshot.url,
+            captured_at: snapshot.captured_at,
+        }
+    }
+}
+
+/// A stretch of a source's content with the query's terms marked.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SnippetDetail {
+    /// The content shown, with line breaks turned into spaces.
+    #[schema(
+        example = "Microsoft estimates that the CrowdStrike update affected 8.5 million Windows devices."
+    )]
+    pub text: String,
+    /// Character offset of the snippet in the source's content.
+    #[schema(example = 0)]
+    pub start: usize,
+    /// Character offset just past the snippet in the content.
+    #[schema(example = 85)]
+    pub end: usize,
+    /// Occurrences of the query's terms, with offsets in characters of
+    /// `text`. Empty when none occur and the snippet opens the content.
+    pub matches: Vec<TermMatchDetail>,
+}
+
+impl From<gorkd_core::Snippet> for SnippetDetail {
+    fn from(snippet: gorkd_core::Snippet) -> Self {
+        Self {
+            text: snippet.text,
+            start: snippet.start,
+            end: snippet.end,
+            matches: snippet.matches.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// An occurrence of a query term in a snippet.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TermMatchDetail {
+    #[schema(example = 29)]
+    pub start: usize,
+    /// Exclusive.
+    #[schema(example = 40)]
+    pub end: usize,
+    /// The term matched, in lowercase: a keyword or entity of the query.
+    #[schema(example = "crowdstrike")]
+    pub term: String,
+}
+
+impl From<gorkd_core::TermMatch> for TermMatchDetail {
+    fn from(m: gorkd_core::TermMatch) -> Self {
+        Self {
+            start: m.start,
+            end: m.end,
+            term: m.term,
+        }
+    }
+}
+
+/// Text of a source quoted by the answer.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SourceHighlight {
+    #[serde(flatten)]
+    pub span: TextSpan,
+    /// The quoted text as it appears in the source.
+    pub text: String,
+    /// The claim the quote supports.
+    pub claim: String,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentFormat {
+    Html,
+    Pdf,
+    PlainText,
+    Markdown,
+    Transcript,
+}
+
+impl From<gorkd_core::DocumentFormat> for DocumentFormat {
+    fn from(format: gorkd_core::DocumentFormat) -> Self {
+        match format {
+            gorkd_core::DocumentFormat::Html => Self::Html,
+            gorkd_core::DocumentFormat::Pdf => Self::Pdf,
+            gorkd_core::DocumentFormat::Markdown => Self::Markdown,
+            gorkd_core::DocumentFormat::Transcript => Self::Transcript,
+            _ => Self::PlainText,
+        }
+    }
+}
+
+impl From<gorkd_core::Source> for SourceDetail {
+    fn from(source: gorkd_core::Source) -> Self {
+        Self {
+            id: source.id.to_string(),
+            url: source.url,
+            title: source.title,
+            domain: source.metadata.domain,
+            published_at: source.metadata.published_at,
+            relevance_score: source.relevance_score,
+            provider: source.metadata.provider.map(|p| p.0),
+            images: source.images,
+            truncated: source.truncated,
+            format: source.metadata.format.map(Into::into),
+            trust: source.metadata.trust.map(Into::into),
+            archived: source.metadata.archived.map(Into::into),
+            snippet: source.snippet.map(Into::into),
+            highlights: Vec::new(),
+            content: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JobSourceResponse {
+    pub sources: Vec<SourceDetail>,
+    #[schema(nullable)]
+    pub search: Option<SearchMetadataDetail>,
+    /// Present only when `group_by=domain` was requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domains: Option<Vec<DomainGroup>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DomainGroup {
+    #[schema(example = "microsoft.com")]
+    pub domain: String,
+    #[schema(example = 3)]
+    pub count: usize,
+    #[schema(example = 0.92)]
+    pub best_score: f32,
+    /// IDs of this domain's sources, in the same order as `sources`.
+    pub source_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceSort {
+    /// Highest relevance first.
+    #[default]
+    Score,
+    /// Most recently published first; undated sources last.
+    Date,
+    /// Alphabetical by domain, then by relevance.
+    Domain,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceGrouping {
+    Domain,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct JobListQuery {
+    /// Keep only jobs with this tag.
+    #[param(example = "competitor-analysis")]
+    pub tag: Option<String>,
+    /// Jobs per page, from 1 to 100; defaults to 20.
+    pub limit: Option<usize>,
+    /// Jobs to skip, newest first.
+    pub offset: Option<usize>,
+}
+
+/// A page of jobs, newest first. Jobs are listed without their answers.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JobListResponse {
+    pub jobs: Vec<JobResponse>,
+    #[schema(example = 20)]
+    pub limit: usize,
+    #[schema(example = 0)]
+    pub offset: usize,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct JobSummaryQuery {
+    /// Keep only jobs in this status.
+    pub status: Option<JobStatus>,
+    /// Keep only jobs with this tag.
+    #[param(example = "competitor-analysis")]
+    pub tag: Option<String>,
+    /// Keep only jobs whose query or answer summary contains this text,
+    /// ignoring case.
+    #[param(example = "rust")]
+    pub q: Option<String>,
+    /// Jobs per page, from 1 to 100; defaults to 20.
+    pub limit: Option<usize>,
+    /// Jobs to skip, newest first.
+    pub offset: Option<usize>,
+}
+
+/// One job as dashboards list it.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JobSummaryDetail {
+    #[schema(example = "01HQXYZ...")]
+    pub job_id: String,
+    pub status: JobStatus,
+    pub query: String,
+    /// The answer's summary, once the job has an answer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+    /// Time from creation until the job completed or failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<gorkd_core::JobSummary> for JobSummaryDetail {
+    fn from(summary: gorkd_core::JobSummary) -> Self {
+        Self {
+            job_id: summary.id.to_string(),
+            status: summary.status.into(),
+            query: summary.query,
+            summary: summary.summary,
+            cost_usd: summary.cost_usd,
+            duration_ms: summary.duration_ms,
+            tags: summary.tags,
+            workspace: summary.workspace,
+            created_at: summary.created_at,
+            updated_at: summary.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StatusCountDetail {
+    pub status: JobStatus,
+    #[schema(example = 12)]
+    pub count: usize,
+}
+
+impl From<gorkd_core::StatusCount> for StatusCountDetail {
+    fn from(count: gorkd_core::StatusCount) -> Self {
+        Self {
+            status: count.status.into(),
+            count: count.count,
+        }
+    }
+}
+
+/// A page of job summaries, newest first, with how many jobs matching the
+/// same filter are in each status.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JobSummaryListResponse {
+    pub jobs: Vec<JobSummaryDetail>,
+    pub counts: Vec<StatusCountDetail>,
+    #[schema(example = 20)]
+    pub limit: usize,
+    #[schema(example = 0)]
+    pub offset: usize,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StatsQuery {
+    /// Days back to sum jobs over, counting today, from 1 to 365; defaults
+    /// to 30.
+    #[param(example = 7)]
+    pub days: Option<u32>,
+}
+
+/// Usage summed over the jobs created in a window of days. No query, answer
+/// or job ID is included.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StatsResponse {
+    /// Start of the window, midnight UTC.
+    pub since: DateTime<Utc>,
+    #[schema(example = 30)]
+    pub days: u32,
+    #[schema(example = 120)]
+    pub jobs: usize,
+    #[schema(example = 110)]
+    pub completed: usize,
+    #[schema(example = 6)]
+    pub failed: usize,
+    /// Share of finished jobs that completed; absent before any finished.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = 0.948)]
+    pub success_rate: Option<f64>,
+    /// Days with at least one job, oldest first.
+    pub daily: Vec<DailyUsageDetail>,
+    /// Median time jobs spent in each stage they left, in pipeline order.
+    pub stage_latency: Vec<StageLatencyDetail>,
+    /// Domains cited by the most answers, each counted once per answer.
+    pub top_domains: Vec<DomainCitationsDetail>,
+    /// Models that wrote the most answers.
+    pub models: Vec<ModelUsageDetail>,
+    /// Source contents found already in object storage since the server
+    /// started, when object storage is configured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_cache: Option<ContentCacheDetail>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DailyUsageDetail {
+    pub date: NaiveDate,
+    pub jobs: usize,
+    pub completed: usize,
+    pub failed: usize,
+}
+
+impl From<gorkd_core::DailyUsage> for DailyUsageDetail {
+    fn from(day: gorkd_core::DailyUsage) -> Self {
+        Self {
+            date: day.date,
+            jobs: day.jobs,
+            completed: day.completed,
+            failed: day.failed,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StageLatencyDetail {
+    pub stage: JobStatus,
+    #[schema(example = 1850)]
+    pub median_ms: u64,
+    /// Jobs that left the stage.
+    pub samples: usize,
+}
+
+impl From<gorkd_core::StageLatency> for StageLatencyDetail {
+    fn from(latency: gorkd_core::StageLatency) -> Self {
+        Self {
+            stage: latency.stage.into(),
+            median_ms: latency.median_ms,
+            samples: latency.samples,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DomainCitationsDetail {
+    #[schema(example = "reuters.com")]
+    pub domain: String,
+    pub answers: usize,
+}
+
+impl From<gorkd_core::DomainCitations> for DomainCitationsDetail {
+    fn from(domain: gorkd_core::DomainCitations) -> Self {
+        Self {
+            domain: domain.domain,
+            answers: domain.answers,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ModelUsageDetail {
+    #[schema(example = "claude-sonnet-4-20250514")]
+    pub model: String,
+    pub answers: usize,
+}
+
+impl From<gorkd_core::ModelUsage> for ModelUsageDetail {
+    fn from(model: gorkd_core::ModelUsage) -> Self {
+        Self {
+            model: model.model,
+            answers: model.answers,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ContentCacheDetail {
+    /// Contents already stored when a job fetched them again.
+    #[schema(example = 45)]
+    pub hits: u64,
+    /// Contents stored, whether or not they were already.
+    #[schema(example = 165)]
+    pub lookups: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = 0.273)]
+    pub hit_rate: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SourcesQuery {
+    /// Aggregate sources by domain.
+    pub group_by: Option<SourceGrouping>,
+    /// Sort order, defaults to `score`.
+    pub sort: Option<SourceSort>,
+    /// Drop sources scoring below this value (0.0-1.0).
+    pub min_score: Option<f32>,
+    /// Keep only sources from this domain or its subdomains.
+    #[param(example = "microsoft.com")]
+    pub domain: Option<String>,
+    /// Include each source's extracted text, which highlight offsets point
+    /// into.
+    pub include_content: Option<bool>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SearchMetadataDetail {
+    #[schema(example = json!(["crowdstrike outage microsoft"]))]
+    pub queries_executed: Vec<String>,
+    /// Queries that failed and were left out of the results.
+    pub queries_skipped: Vec<String>,
+    /// Providers that returned results, in the order they were used.
+    #[schema(example = json!(["tavily"]))]
+    pub providers_used: Vec<String>,
+    #[schema(example = 10)]
+    pub total_results: usize,
+    #[schema(example = 850)]
+    pub duration_ms: u64,
+    /// The period search was narrowed to, as read from the question.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_constraint: Option<TimeConstraint>,
+}
+
+/// A period read from the question's wording, such as "since 2021".
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TimeConstraint {
+    /// "latest", "as of today": limited to the past week.
+    Recent,
+    /// "history of": not limited.
+    Historical,
+    SpecificDate {
+        date: DateTime<Utc>,
+    },
+    /// Either bound may be open; relative periods such as "the last two
+    /// weeks" end now.
+    DateRange {
+        #[schema(nullable)]
+        from: Option<DateTime<Utc>>,
+        #[schema(nullable)]
+        to: Option<DateTime<Utc>>,
+    },
+}
+
+impl From<gorkd_core::TimeConstraint> for TimeConstraint {
+    fn from(constraint: gorkd_core::TimeConstraint) -> Self {
+        match constraint {
+            gorkd_core::TimeConstraint::Recent => Self::Recent,
+            gorkd_core::TimeConstraint::Historical => Self::Historical,
+            gorkd_core::TimeConstraint::SpecificDate(date) => Self::SpecificDate { date },
+            gorkd_core::TimeConstraint::DateRange { from, to } => Self::DateRange { from, to },
+            _ => Self::Historical,
+        }
+    }
+}
+
+impl From<gorkd_core::SearchMetadata> for SearchMetadataDetail {
+    fn from(metadata: gorkd_core::SearchMetadata) -> Self {
+        Self {
+            queries_executed: metadata.queries_executed,
+            queries_skipped: metadata.queries_skipped,
+            providers_used: metadata.providers_used.into_iter().map(|p| p.0).collect(),
+            total_results: metadata.total_results,
+            duration_ms: metadata.fetch_duration.as_millis() as u64,
+            time_constraint: metadata.time_constraint.map(Into::into),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JobEventDetail {
+    #[schema(example = 3)]
+    pub sequence: u64,
+    pub timestamp: DateTime<Utc>,
+    /// Milliseconds since the job's first event.
+    #[schema(example = 412)]
+    pub elapsed_ms: i64,
+    #[serde(rename = "type")]
+    #[schema(example = "provider_attempt")]
+    pub event_type: String,
+    /// Event-specific fields; shape depends on `type`.
+    #[schema(value_type = Object)]
+    pub data: serde_json::Value,
+}
+
+impl JobEventDetail {
+    fn new(event: gorkd_core::JobEvent, started_at: DateTime<Utc>) -> Self {
+        let event_type = event.kind.name().to_string();
+        let mut data = serde_json::to_value(&event.kind).unwrap_or_default();
+        if let Some(fields) = data.as_object_mut() {
+            fields.remove("type");
+        }
+
+        Self {
+            sequence: event.sequence,
+            timestamp: event.timestamp,
+            elapsed_ms: (event.timestamp - started_at).num_milliseconds(),
+            event_type,
+            data,
+        }
+    }
+}
+
+/// Every requested model's answer to a job, with how far they agree.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ModelComparisonResponse {
+    #[schema(example = "job_abc123xyz456")]
+    pub job_id: String,
+    pub status: JobStatus,
+    /// In the order the models were requested; empty until synthesis ends.
+    pub answers: Vec<ModelAnswerDetail>,
+    /// Present once at least two models have answered.
+    #[schema(nullable)]
+    pub agreement: Option<AgreementDetail>,
+}
+
+impl ModelComparisonResponse {
+    pub fn new(job: &gorkd_core::ResearchJob, comparison: gorkd_core::ModelComparison) -> Self {
+        let agreement = comparison.agreement().map(Into::into);
+        Self {
+            job_id: job.id.to_string(),
+            status: job.status.clone().into(),
+            answers: comparison.answers.into_iter().map(Into::into).collect(),
+            agreement,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ModelAnswerDetail {
+    #[schema(example = "gpt-4o")]
+    pub model: String,
+    #[schema(nullable)]
+    pub answer: Option<AnswerDetail>,
+    /// Why the model has no answer.
+    #[schema(nullable)]
+    pub error: Option<String>,
+}
+
+impl From<gorkd_core::ModelAnswer> for ModelAnswerDetail {
+    fn from(answer: gorkd_core::ModelAnswer) -> Self {
+        Self {
+            model: answer.model,
+            answer: answer.answer.map(Into::into),
+            error: answer.error,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AgreementDetail {
+    /// Whether every model reported the same confidence.
+    pub confidence_agrees: bool,
+    /// Mean pairwise overlap of the sources the models cited, from 0 to 1.
+    #[schema(example = 0.67)]
+    pub citation_overlap: f32,
+    /// Sources cited by some models but not all.
+    pub disputed_sources: Vec<String>,
+}
+
+impl From<gorkd_core::ComparisonAgreement> for AgreementDetail {
+    fn from(agreement: gorkd_core::ComparisonAgreement) -> Self {
+        Self {
+            confidence_agrees: agreement.confidence_agrees,
+            citation_overlap: agreement.citation_overlap,
+            disputed_sources: agreement
+                .disputed_sources
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        }
+    }
+}
+
+/// What changed from one job's answer to another's.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AnswerDiffResponse {
+    #[schema(example = "job_abc123xyz456")]
+    pub from_job_id: String,
+    #[schema(example = "job_def789uvw012")]
+    pub to_job_id: String,
+    pub confidence: ConfidenceChange,
+    /// Claims that were added, removed or reworded, in answer order.
+    pub claims: Vec<ClaimChangeDetail>,
+    pub unchanged_claims: usize,
+    /// Citations of sources the earlier answer did not cite.
+    pub added_citations: Vec<CitationChangeDetail>,
+    /// Citations of sources the later answer no longer cites.
+    pub removed_citations: Vec<CitationChangeDetail>,
+}
+
+impl AnswerDiffResponse {
+    pub fn new(
+        from: &gorkd_core::JobId,
+        to: &gorkd_core::JobId,
+        diff: gorkd_core::AnswerDiff,
+    ) -> Self {
+        let delta = diff.confidence_delta();
+        Self {
+            from_job_id: from.to_string(),
+            to_job_id: to.to_string(),
+            confidence: ConfidenceChange {
+                from: diff.confidence_before.into(),
+                to: diff.confidence_after.into(),
+                delta,
+            },
+            claims: diff.claims.into_iter().map(Into::into).collect(),
+            unchanged_claims: diff.unchanged_claims,
+            added_citations: diff.added_citations.into_iter().map(Into::into).collect(),
+            removed_citations: diff.removed_citations.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConfidenceChange {
+    pub from: Confidence,
+    pub to: Confidence,
+    /// Steps between the two, positive when the later answer is more
+    /// confident.
+    #[schema(example = 1)]
+    pub delta: i8,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ClaimChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ClaimChangeDetail {
+    pub change: ClaimChangeKind,
+    /// The claim in the earlier answer; absent for added claims.
+    #[schema(nullable)]
+    pub before: Option<String>,
+    /// The claim in the later answer; absent for removed claims.
+    #[schema(nullable)]
+    pub after: Option<String>,
+    /// Word overlap of a reworded claim, from 0.5 to 1.
+    #[schema(nullable)]
+    pub similarity: Option<f32>,
+}
+
+impl From<gorkd_core::ClaimChange> for ClaimChangeDetail {
+    fn from(change: gorkd_core::ClaimChange) -> Self {
+        match change {
+            gorkd_core::ClaimChange::Added(claim) => Self {
+                change: ClaimChangeKind::Added,
+                before: None,
+                after: Some(claim),
+                similarity: None,
+            },
+            gorkd_core::ClaimChange::Removed(claim) => Self {
+                change: ClaimChangeKind::Removed,
+                before: Some(claim),
+                after: None,
+                similarity: None,
+            },
+            gorkd_core::ClaimChange::Changed {
+                before,
+                after,
+                similarity,
+            } => Self {
+                change: ClaimChangeKind::Changed,
+                before: Some(before),
+                after: Some(after),
+                similarity: Some(similarity),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CitationChangeDetail {
+    pub claim: String,
+    #[schema(example = "src_abc123xyz456")]
+    pub source_id: String,
+    #[schema(nullable)]
+    pub url: Option<String>,
+}
+
+impl From<gorkd_core::CitationChange> for CitationChangeDetail {
+    fn from(citation: gorkd_core::CitationChange) -> Self {
+        Self {
+            claim: citation.claim,
+            source_id: citation.source_id.to_string(),
+            url: citation.url,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JobEventsResponse {
+    pub events: Vec<JobEventDetail>,
+}
+
+impl From<Vec<gorkd_core::JobEvent>> for JobEventsResponse {
+    fn from(events: Vec<gorkd_core::JobEvent>) -> Self {
+        let started_at = events.first().map(|e| e.timestamp).unwrap_or_else(Utc::now);
+        Self {
+            events: events
+                .into_iter()
+                .map(|e| JobEventDetail::new(e, started_at))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ArtifactMessage {
+    #[schema(example = "user")]
+    pub role: String,
+    pub content: String,
+    /// Image URLs sent with the message to a vision-capable model.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<String>,
+}
+
+impl From<gorkd_core::Message> for ArtifactMessage {
+    fn from(message: gorkd_core::Message) -> Self {
+        let role = match message.role {
+            gorkd_core::Role::System => "system",
+            gorkd_core::Role::User => "user",
+            gorkd_core::Role::Assistant => "assistant",
+        };
+
+        Self {
+            role: role.to_string(),
+            content: message.content,
+            images: message.images,
+        }
+    }
+}
+
+/// One captured LLM exchange, with credentials and personal data scrubbed.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ArtifactDetail {
+    pub captured_at: DateTime<Utc>,
+    #[schema(example = "synthesis")]
+    pub stage: String,
+    #[schema(example = "anthropic")]
+    pub provider: String,
+    #[schema(example = "claude-sonnet-4-20250514")]
+    pub model: String,
+    /// The exact prompt sent to the model.
+    pub messages: Vec<ArtifactMessage>,
+    /// The model's output before parsing.
+    pub raw_response: Option<String>,
+    pub error: Option<String>,
+}
+
+impl From<gorkd_core::LlmArtifact> for ArtifactDetail {
+    fn from(artifact: gorkd_core::LlmArtifact) -> Self {
+        Self {
+            captured_at: artifact.captured_at,
+            stage: artifact.stage,
+            provider: artifact.provider,
+            model: artifact.model,
+            messages: artifact.messages.into_iter().map(Into::into).collect(),
+            raw_response: artifact.raw_response,
+            error: artifact.error,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JobArtifactsResponse {
+    #[schema(example = "job_abc123xyz456")]
+    pub job_id: String,
+    /// Where the artifacts were read from: `store` or `directory`.
+    #[schema(example = "store")]
+    pub sink: String,
+    pub artifacts: Vec<ArtifactDetail>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReprocessResponse {
+    #[schema(example = "job_abc123xyz456")]
+    pub job_id: String,
+    /// Number of captured search responses parsed and ranked again.
+    #[schema(example = 3)]
+    pub search_responses: usize,
+    /// Number of sources the job has after reprocessing.
+    #[schema(example = 8)]
+    pub sources: usize,
+    /// Whether the answer was parsed again from the model's captured output.
+    pub answer_reparsed: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JobImportResponse {
+    /// Number of jobs written, with their sources and answers.
+    #[schema(example = 120)]
+    pub imported: usize,
+    /// Number of jobs left out because a job with the same ID exists.
+    #[schema(example = 0)]
+    pub skipped: usize,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateProjectRequest {
+    #[schema(example = "EV market study", min_length = 1, max_length = 200)]
+    pub name: String,
+    /// What the project sets out to learn. Given to the model that writes
+    /// the project report.
+    #[serde(default)]
+    #[schema(nullable, example = "Size the 2024 EV market and its leaders")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProjectResponse {
+    #[schema(example = "prj_abc123xyz456")]
+    pub project_id: String,
+    #[schema(example = "EV market study")]
+    pub name: String,
+    #[schema(nullable)]
+    pub description: Option<String>,
+    /// Member jobs, in the order they were attached.
+    #[schema(example = json!(["job_abc123xyz456"]))]
+    pub job_ids: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<gorkd_core::Project> for ProjectResponse {
+    fn from(project: gorkd_core::Project) -> Self {
+        Self {
+            project_id: project.id.to_string(),
+            name: project.name,
+            description: project.description,
+            job_ids: project.job_ids.iter().map(ToString::to_string).collect(),
+            created_at: project.created_at,
+            updated_at: project.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AttachJobRequest {
+    #[schema(example = "job_abc123xyz456")]
+    pub job_id: String,
+}
+
+/// A project's jobs, in the order they were attached, without their answers.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProjectJobsResponse {
+    #[schema(example = "prj_abc123xyz456")]
+    pub project_id: String,
+    pub jobs: Vec<JobResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProjectFindingDetail {
+    #[schema(example = "job_abc123xyz456")]
+    pub job_id: String,
+    #[schema(example = "How many EVs were sold in 2024?")]
+    pub query: String,
+    #[schema(example = "About 17 million, a quarter more than in 2023.")]
+    pub summary: String,
+    pub confidence: Confidence,
+}
+
+impl From<gorkd_core::ProjectFinding> for ProjectFindingDetail {
+    fn from(finding: gorkd_core::ProjectFinding) -> Self {
+        Self {
+            job_id: finding.job_id.to_string(),
+            query: finding.query,
+            summary: finding.summary,
+            confidence: finding.confidence.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProjectReportResponse {
+    #[schema(example = "prj_abc123xyz456")]
+    pub project_id: String,
+    /// The report, in Markdown. Cites findings as `[Q1]`, `[Q2]`, ... in
+    /// the order of `findings`.
+    pub report: String,
+    pub findings: Vec<ProjectFindingDetail>,
+    /// Member jobs left out because they have no answer yet, or failed.
+    pub unanswered: Vec<String>,
+    #[schema(example = "claude-sonnet-4-20250514")]
+    pub model: String,
+    #[schema(example = 3100)]
+    pub total_tokens: usize,
+    pub generated_at: DateTime<Utc>,
+}
+
+impl From<gorkd_core::ProjectReport> for ProjectReportResponse {
+    fn from(report: gorkd_core::ProjectReport) -> Self {
+        Self {
+            project_id: report.project_id.to_string(),
+            report: report.report,
+            findings: report.findings.into_iter().map(Into::into).collect(),
+            unanswered: report.unanswered.iter().map(ToString::to_string).collect(),
+            model: report.model,
+            total_tokens: report.total_tokens,
+            generated_at: report.generated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SynthesizeRequest {
+    /// The question to answer over the jobs' results.
+    #[schema(
+        example = "How is the EV market likely to develop through 2026?",
+        min_length = 1,
+        max_length = 2000
+    )]
+    pub question: String,
+    /// Completed jobs to pool, 2 to 10. Repeats are ignored.
+    #[schema(example = json!(["job_abc123xyz456", "job_def456uvw789"]))]
+    pub job_ids: Vec<String>,
+    /// Collected sources given to the model, besides the jobs' answers; 1
+    /// to 50, defaulting to 20.
+    #[serde(default)]
+    #[schema(nullable, minimum = 1, maximum = 50, example = 20)]
+    pub max_sources: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PooledSourceKind {
+    /// A source a job collected.
+    Source,
+    /// A job's own answer, with a `gorkd://jobs/{id}` URL.
+    Answer,
+}
+
+impl From<gorkd_core::PooledSourceKind> for PooledSourceKind {
+    fn from(kind: gorkd_core::PooledSourceKind) -> Self {
+        match kind {
+            gorkd_core::PooledSourceKind::Source => Self::Source,
+            gorkd_core::PooledSourceKind::Answer => Self::Answer,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PooledSourceDetail {
+    #[schema(example = "src_abc123xyz456")]
+    pub source_id: String,
+    pub kind: PooledSourceKind,
+    #[schema(example = "https://www.iea.org/reports/global-ev-outlook-2024")]
+    pub url: String,
+    #[schema(example = "Global EV Outlook 2024")]
+    pub title: String,
+    /// Jobs that collected the source, or the job whose answer it is.
+    #[schema(example = json!(["job_abc123xyz456"]))]
+    pub job_ids: Vec<String>,
+}
+
+impl From<gorkd_core::PooledSource> for PooledSourceDetail {
+    fn from(pooled: gorkd_core::PooledSource) -> Self {
+        Self {
+            source_id: pooled.source.id.to_string(),
+            kind: pooled.kind.into(),
+            url: pooled.source.url,
+            title: pooled.source.title,
+            job_ids: pooled.job_ids.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SynthesisResponse {
+    #[schema(example = "How is the EV market likely to develop through 2026?")]
+    pub question: String,
+    pub job_ids: Vec<String>,
+    /// Citations refer to `sources` by `source_id`.
+    pub answer: AnswerDetail,
+    /// What the model was given: the jobs' answers, then their best
+    /// sources, cited ones first.
+    pub sources: Vec<PooledSourceDetail>,
+}
+
+/// A source the caller retrieved, for `POST /v1/synthesize/direct`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DirectSourceRequest {
+    #[schema(example = "https://wiki.corp.example/hr/vacation")]
+    pub url: String,
+    #[schema(example = "Vacation policy", min_length = 1, max_length = 500)]
+    pub title: String,
+    /// The source's text. Long sources are truncated to the same content
+    /// limits as collected ones.
+    #[schema(example = "Employees accrue 1.5 vacation days per month...")]
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DirectSynthesizeRequest {
+    /// The question to answer over the sources.
+    #[schema(
+        example = "How many vacation days do new employees get?",
+        min_length = 1,
+        max_length = 2000
+    )]
+    pub question: String,
+    /// Sources to answer from, 1 to 50. The answer cites only these.
+    pub sources: Vec<DirectSourceRequest>,
+    /// Persona and tone of the answer; neutral by default.
+    #[serde(default)]
+    #[schema(nullable)]
+    pub style: Option<AnswerStyle>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DirectSynthesisResponse {
+    #[schema(example = "How many vacation days do new employees get?")]
+    pub question: String,
+    /// Citations refer to `sources` by `id`.
+    pub answer: AnswerDetail,
+    /// What the model was given, in request order, with the IDs the
+    /// citations use. Sources past the total content limit are left out.
+    pub sources: Vec<SourceDetail>,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct KnowledgeQuery {
+    /// Keep facts whose subject contains this, ignoring case.
+    #[param(example = "crowdstrike")]
+    pub subject: Option<String>,
+    /// Keep facts whose relation contains this, ignoring case.
+    #[param(example = "released")]
+    pub relation: Option<String>,
+    /// Keep facts whose object contains this, ignoring case.
+    pub object: Option<String>,
+    /// Keep facts drawn from this job's sources.
+    #[param(example = "job_abc123xyz456")]
+    pub job_id: Option<String>,
+    /// Facts to return, from 1 to 200; defaults to 50.
+    pub limit: Option<usize>,
+}
+
+/// A source stating a fact, and the job that read it.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FactSourceDetail {
+    #[schema(example = "job_abc123xyz456")]
+    pub job_id: String,
+    #[schema(example = "src_abc123xyz456")]
+    pub source_id: String,
+    #[schema(
+        example = "https://www.crowdstrike.com/blog/falcon-update-for-windows-hosts-technical-details/"
+    )]
+    pub url: String,
+    #[schema(example = "Technical Details: Falcon Update for Windows Hosts")]
+    pub title: String,
+}
+
+impl From<gorkd_core::FactSource> for FactSourceDetail {
+    fn from(source: gorkd_core::FactSource) -> Self {
+        Self {
+            job_id: source.job_id.to_string(),
+            source_id: source.source_id.to_string(),
+            url: source.url,
+            title: source.title,
+        }
+    }
+}
+
+/// A (subject, relation, object) triple from the knowledge base.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FactDetail {
+    #[schema(example = "fct_abc123xyz456")]
+    pub fact_id: String,
+    #[schema(example = "CrowdStrike")]
+    pub subject: String,
+    #[schema(example = "released")]
+    pub relation: String,
+    #[schema(example = "Channel File 291")]
+    pub object: String,
+    /// Sources stating the fact, in the order they were recorded.
+    pub sources: Vec<FactSourceDetail>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<gorkd_core::Fact> for FactDetail {
+    fn from(fact: gorkd_core::Fact) -> Self {
+        Self {
+            fact_id: fact.id.to_string(),
+            subject: fact.subject,
+            relation: fact.relation,
+            object: fact.object,
+            sources: fact.sources.into_iter().map(Into::into).collect(),
+            created_at: fact.created_at,
+            updated_at: fact.updated_at,
+        }
+    }
+}
+
+/// Facts matching a knowledge query, those stated by the most sources first.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct KnowledgeResponse {
+    pub facts: Vec<FactDetail>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateDocumentRequest {
+    #[schema(example = "Vacation policy", min_length = 1, max_length = 500)]
+    pub title: String,
+    /// The document's text, up to 1 MB.
+    #[schema(example = "Employees accrue 1.5 vacation days per month...")]
+    pub content: String,
+    /// Where readers can find the document. Sources drawn from it cite this
+    /// URL, or `corpus://<document_id>` without one.
+    #[serde(default)]
+    #[schema(nullable, example = "https://wiki.corp.example/hr/vacation")]
+    pub url: Option<String>,
+    /// When the document was published. Jobs given `published_after` or
+    /// `published_before` leave out documents published outside that
+    /// period; undated ones are always searched.
+    #[serde(default)]
+    #[schema(nullable)]
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+/// Query parameters of `POST /v1/corpus/documents/upload`, whose body is
+/// the file itself.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct UploadDocumentQuery {
+    /// The document's title, 1-500 characters.
+    #[param(example = "Vacation policy")]
+    pub title: String,
+    /// Where readers can find the document.
+    #[param(example = "https://wiki.corp.example/hr/vacation.pdf")]
+    pub url: Option<String>,
+}
+
+/// A site to crawl into the corpus.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CrawlCorpusRequest {
+    /// A page to start from, or a `sitemap.xml` listing the pages to add.
+    #[schema(example = "https://docs.example.com/")]
+    pub url: String,
+    /// Most pages added, from 1 to 200; defaults to 50.
+    #[serde(default)]
+    #[schema(nullable, minimum = 1, maximum = 200, example = 50)]
+    pub max_pages: Option<usize>,
+    /// Links followed away from the starting pages, from 0 to 5; defaults
+    /// to 2.
+    #[serde(default)]
+    #[schema(nullable, minimum = 0, maximum = 5, example = 2)]
+    pub max_depth: Option<usize>,
+}
+
+/// The documents a crawl added or kept, in the order their pages were read.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CrawlCorpusResponse {
+    pub documents: Vec<DocumentResponse>,
+    /// Documents of pages crawled before that the new ones replaced.
+    #[schema(example = 0)]
+    pub replaced: usize,
+    /// Documents kept as they were because their pages had not changed
+    /// since the last crawl.
+    #[schema(example = 0)]
+    pub unchanged: usize,
+}
+
+/// A document in the corpus, without its content.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DocumentResponse {
+    #[schema(example = "doc_abc123xyz456")]
+    pub document_id: String,
+    #[schema(example = "default")]
+    pub workspace: String,
+    #[schema(example = "Vacation policy")]
+    pub title: String,
+    #[schema(nullable, example = "https://wiki.corp.example/hr/vacation")]
+    pub url: Option<String>,
+    /// The format the document was added in.
+    pub format: DocumentFormat,
+    /// When the document was published, if known.
+    #[schema(nullable)]
+    pub published_at: Option<DateTime<Utc>>,
+    /// Characters of text extracted from the document.
+    #[schema(example = 5210)]
+    pub characters: usize,
+    /// Passages the document was split into for search.
+    #[schema(example = 4)]
+    pub chunks: usize,
+    #[schema(example = "text-embedding-3-small")]
+    pub embedding_model: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<gorkd_core::CorpusDocument> for DocumentResponse {
+    fn from(document: gorkd_core::CorpusDocument) -> Self {
+        Self {
+            document_id: document.id.to_string(),
+            workspace: document.workspace,
+            title: document.title,
+            url: document.url,
+            format: document.format.into(),
+            published_at: document.published_at,
+            characters: document.content.chars().count(),
+            chunks: document.chunks.len(),
+            embedding_model: document.embedding_model,
+            created_at: document.created_at,
+        }
+    }
+}
+
+/// An RSS or Atom feed whose new entries are added to the corpus.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FeedResponse {
+    #[schema(example = "https://blog.rust-lang.org/feed.xml")]
+    pub url: String,
+    #[schema(example = "news")]
+    pub workspace: String,
+}
+
+/// The feeds of a workspace.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FeedListResponse {
+    pub feeds: Vec<FeedResponse>,
+    /// Seconds between reads of each feed.
+    #[schema(example = 900)]
+    pub poll_interval_secs: u64,
+}
+
+/// A feed that could not be read, or whose entries could not be added.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FeedFailureResponse {
+    #[schema(example = "https://blog.rust-lang.org/feed.xml")]
+    pub url: String,
+    #[schema(example = "Network error: connection failed")]
+    pub error: String,
+}
+
+/// What reading a workspace's feeds added to the corpus.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FeedPollResponse {
+    /// Feeds read without error.
+    #[schema(example = 2)]
+    pub feeds_read: usize,
+    /// Entries added as documents.
+    #[schema(example = 3)]
+    pub added: usize,
+    /// Entries already in the corpus.
+    #[schema(example = 17)]
+    pub skipped: usize,
+    pub failures: Vec<FeedFailureResponse>,
+}
+
+impl From<gorkd_core::FeedPollReport> for FeedPollResponse {
+    fn from(report: gorkd_core::FeedPollReport) -> Self {
+        Self {
+            feeds_read: report.feeds_read,
+            added: report.added,
+            skipped: report.skipped,
+            failures: report
+                .failures
+                .into_iter()
+                .map(|failure| FeedFailureResponse {
+                    url: failure.url,
+                    error: failure.error,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A document in the corpus with its extracted text.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DocumentContentResponse {
+    #[serde(flatten)]
+    pub document: DocumentResponse,
+    pub content: String,
+}
+
+impl From<gorkd_core::CorpusDocument> for DocumentContentResponse {
+    fn from(document: gorkd_core::CorpusDocument) -> Self {
+        let content = document.content.clone();
+        Self {
+            document: document.into(),
+            content,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DocumentListQuery {
+    /// Documents per page, from 1 to 100; defaults to 20.
+    pub limit: Option<usize>,
+    /// Documents to skip, newest first.
+    pub offset: Option<usize>,
+}
+
+/// A page of corpus documents, newest first.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DocumentListResponse {
+    pub documents: Vec<DocumentResponse>,
+    #[schema(example = 20)]
+    pub limit: usize,
+    #[schema(example = 0)]
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AnswerRating {
+    Helpful,
+    Unhelpful,
+}
+
+impl From<AnswerRating> for gorkd_core::AnswerRating {
+    fn from(rating: AnswerRating) -> Self {
+        match rating {
+            AnswerRating::Helpful => Self::Helpful,
+            AnswerRating::Unhelpful => Self::Unhelpful,
+        }
+    }
+}
+
+impl From<gorkd_core::AnswerRating> for AnswerRating {
+    fn from(rating: gorkd_core::AnswerRating) -> Self {
+        match rating {
+            gorkd_core::AnswerRating::Helpful => Self::Helpful,
+            _ => Self::Unhelpful,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TrustLabel {
+    /// Answers citing the domain were mostly rated helpful.
+    Trusted,
+    Neutral,
+    /// Readers flagged the domain's sources or rated answers citing it
+    /// unhelpful.
+    Distrusted,
+}
+
+impl From<gorkd_core::TrustLabel> for TrustLabel {
+    fn from(label: gorkd_core::TrustLabel) -> Self {
+        match label {
+            gorkd_core::TrustLabel::Trusted => Self::Trusted,
+            gorkd_core::TrustLabel::Distrusted => Self::Distrusted,
+            _ => Self::Neutral,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SourceFlagRequest {
+    #[schema(example = "src_abc123xyz456")]
+    pub source_id: String,
+    #[serde(default)]
+    #[schema(nullable, example = "Press release presented as reporting")]
+    pub reason: Option<String>,
+}
+
+/// A reader's verdict on a job: a rating of its answer, sources flagged as
+/// bad, a comment, or any of them together.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct FeedbackRequest {
+    /// Rates the answer; lends or takes trust from the domains it cites.
+    /// Requires a completed job.
+    #[serde(default)]
+    #[schema(nullable)]
+    pub rating: Option<AnswerRating>,
+    /// Sources of the job to flag as bad; counts against their domains.
+    #[serde(default)]
+    pub flagged_sources: Vec<SourceFlagRequest>,
+    #[serde(default)]
+    #[schema(nullable, max_length = 2000)]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SourceFlagDetail {
+    #[schema(example = "src_abc123xyz456")]
+    pub source_id: String,
+    #[schema(example = "example-news.com")]
+    pub domain: String,
+    #[schema(nullable)]
+    pub reason: Option<String>,
+}
+
+impl From<gorkd_core::SourceFlag> for SourceFlagDetail {
+    fn from(flag: gorkd_core::SourceFlag) -> Self {
+        Self {
+            source_id: flag.source_id.to_string(),
+            domain: flag.domain,
+            reason: flag.reason,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FeedbackResponse {
+    #[schema(example = "fbk_abc123xyz456")]
+    pub feedback_id: String,
+    #[schema(example = "job_abc123xyz456")]
+    pub job_id: String,
+    #[schema(nullable)]
+    pub rating: Option<AnswerRating>,
+    /// Domains of the sources the rated answer cites.
+    #[schema(example = json!(["microsoft.com"]))]
+    pub rated_domains: Vec<String>,
+    pub flagged_sources: Vec<SourceFlagDetail>,
+    #[schema(nullable)]
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<gorkd_core::Feedback> for FeedbackResponse {
+    fn from(feedback: gorkd_core::Feedback) -> Self {
+        Self {
+            feedback_id: feedback.id.to_string(),
+            job_id: feedback.job_id.to_string(),
+            rating: feedback.rating.map(Into::into),
+            rated_domains: feedback.rated_domains,
+            flagged_sources: feedback
+                .flagged_sources
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            comment: feedback.comment,
+            created_at: feedback.created_at,
+        }
+    }
+}
+
+/// A job's feedback, oldest first.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FeedbackListResponse {
+    pub job_id: String,
+    pub feedback: Vec<FeedbackResponse>,
+}
+
+/// The research capability as a callable tool, for agent frameworks that
+/// register tools from a JSON Schema.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ToolSchemaResponse {
+    #[schema(example = "gorkd_research")]
+    pub name: String,
+    pub description: String,
+    /// JSON Schema of the tool's arguments, a subset of the body of
+    /// `POST /v1/research`.
+    pub parameters: serde_json::Value,
+    /// JSON Schema of the tool's result, the job's `answer`.
+    pub returns: serde_json::Value,
+    pub invocation: ToolInvocationDetail,
+    /// The tool as an OpenAI function tool, ready for a `tools` array.
+    pub openai: serde_json::Value,
+}
+
+/// How a call to the tool maps onto the API.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ToolInvocationDetail {
+    /// Creates the job from the tool's arguments, sent as the JSON body.
+    #[schema(example = "POST /v1/research")]
+    pub create: String,
+    /// Polled with the returned `job_id` until its status is one of
+    /// `poll_until`.
+    #[schema(example = "GET /v1/jobs/{job_id}")]
+    pub poll: String,
+    pub poll_until: Vec<JobStatus>,
+    /// Lists the sources the answer's citations refer to.
+    #[schema(example = "GET /v1/jobs/{job_id}/sources")]
+    pub sources: String,
 }
@@ -1,10 +1,79 @@
-use axum::http::StatusCode;
+use std::time::Duration;
+
+use axum::http::{header, StatusCode};
 use axum::response::{IntoResponse, Response};
 use axum::Json;
 use serde::Serialize;
 use thiserror::Error;
 use utoipa::ToSchema;
 
+/// Machine-readable error code, in error responses and failed jobs. New codes
+/// may be added; treat unknown ones like `internal_error`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    ValidationError,
+    NotFound,
+    FeatureDisabled,
+    Conflict,
+    RateLimited,
+    Overloaded,
+    ProviderUnavailable,
+    ProviderError,
+    Timeout,
+    ContextTooLong,
+    ContentBlocked,
+    NoSources,
+    InvalidAnswer,
+    BudgetExceeded,
+    StoreUnavailable,
+    InternalError,
+}
+
+impl ErrorCode {
+    /// The HTTP status sent with this code.
+    pub fn status(self) -> StatusCode {
+        match self {
+            Self::ValidationError => StatusCode::BAD_REQUEST,
+            Self::NotFound | Self::FeatureDisabled => StatusCode::NOT_FOUND,
+            Self::Conflict => StatusCode::CONFLICT,
+            Self::NoSources | Self::ContentBlocked | Self::BudgetExceeded => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            Self::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            Self::ProviderError | Self::ContextTooLong | Self::InvalidAnswer => {
+                StatusCode::BAD_GATEWAY
+            }
+            Self::Timeout => StatusCode::GATEWAY_TIMEOUT,
+            Self::Overloaded | Self::ProviderUnavailable | Self::StoreUnavailable => {
+                StatusCode::SERVICE_UNAVAILABLE
+            }
+            Self::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<gorkd_core::ErrorCode> for ErrorCode {
+    fn from(code: gorkd_core::ErrorCode) -> Self {
+        match code {
+            gorkd_core::ErrorCode::ValidationError => Self::ValidationError,
+            gorkd_core::ErrorCode::NotFound => Self::NotFound,
+            gorkd_core::ErrorCode::Conflict => Self::Conflict,
+            gorkd_core::ErrorCode::RateLimited => Self::RateLimited,
+            gorkd_core::ErrorCode::ProviderUnavailable => Self::ProviderUnavailable,
+            gorkd_core::ErrorCode::ProviderError => Self::ProviderError,
+            gorkd_core::ErrorCode::Timeout => Self::Timeout,
+            gorkd_core::ErrorCode::ContextTooLong => Self::ContextTooLong,
+            gorkd_core::ErrorCode::ContentBlocked => Self::ContentBlocked,
+            gorkd_core::ErrorCode::NoSources => Self::NoSources,
+            gorkd_core::ErrorCode::InvalidAnswer => Self::InvalidAnswer,
+            gorkd_core::ErrorCode::BudgetExceeded => Self::BudgetExceeded,
+            gorkd_core::ErrorCode::StoreUnavailable => Self::StoreUnavailable,
+            _ => Self::InternalError,
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum AppError {
     #[error("validation error: {0}")]
@@ -15,6 +84,16 @@ pub enum AppError {
 
     #[error("internal error: {0}")]
     Internal(String),
+
+    #[error("feature disabled: {0}")]
+    Disabled(String),
+
+    #[error("server busy, retry in {}s", retry_after.as_secs())]
+    Overloaded { retry_after: Duration },
+
+    /// A core error, reported with the code it maps to.
+    #[error("{message}")]
+    Coded { code: ErrorCode, message: String },
 }
 
 impl AppError {
@@ -26,9 +105,43 @@ impl AppError {
         Self::NotFound(msg.into())
     }
 
+    /// The job is not in a state that allows the request.
+    pub fn conflict(msg: impl Into<String>) -> Self {
+        Self::Coded {
+            code: ErrorCode::Conflict,
+            message: msg.into(),
+        }
+    }
+
     pub fn internal(msg: impl Into<String>) -> Self {
         Self::Internal(msg.into())
     }
+
+    pub fn disabled(feature: impl Into<String>) -> Self {
+        Self::Disabled(feature.into())
+    }
+
+    pub fn overloaded(retry_after: Duration) -> Self {
+        Self::Overloaded { retry_after }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::Validation(_) => ErrorCode::ValidationError,
+            Self::NotFound(_) => ErrorCode::NotFound,
+            Self::Internal(_) => ErrorCode::InternalError,
+            Self::Disabled(_) => ErrorCode::FeatureDisabled,
+            Self::Overloaded { .. } => ErrorCode::Overloaded,
+            Self::Coded { code, .. } => *code,
+        }
+    }
+
+    fn coded(code: gorkd_core::ErrorCode, message: String) -> Self {
+        Self::Coded {
+            code: code.into(),
+            message,
+        }
+    }
 }
 
 impl From<gorkd_core::QueryError> for AppError {
@@ -37,11 +150,39 @@ impl From<gorkd_core::QueryError> for AppError {
     }
 }
 
+impl From<gorkd_core::SchemaError> for AppError {
+    fn from(err: gorkd_core::SchemaError) -> Self {
+        Self::Validation(err.to_string())
+    }
+}
+
 impl From<gorkd_core::StoreError> for AppError {
     fn from(err: gorkd_core::StoreError) -> Self {
         match err {
-            gorkd_core::StoreError::JobNotFound { id } => Self::NotFound(id),
-            _ => Self::Internal(err.to_string()),
+            gorkd_core::StoreError::JobNotFound { id }
+            | gorkd_core::StoreError::ProjectNotFound { id } => Self::NotFound(id),
+            _ => Self::coded(err.code(), err.to_string()),
+        }
+    }
+}
+
+impl From<gorkd_core::SearchError> for AppError {
+    fn from(err: gorkd_core::SearchError) -> Self {
+        Self::coded(err.code(), err.to_string())
+    }
+}
+
+impl From<gorkd_core::LlmError> for AppError {
+    fn from(err: gorkd_core::LlmError) -> Self {
+        Self::coded(err.code(), err.to_string())
+    }
+}
+
+impl From<gorkd_core::PipelineError> for AppError {
+    fn from(err: gorkd_core::PipelineError) -> Self {
+        match err {
+            gorkd_core::PipelineError::Store(e) => e.into(),
+            _ => Self::coded(err.code(), err.to_string()),
         }
     }
 }
@@ -53,17 +194,16 @@ pub struct ApiError {
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ApiErrorBody {
-    #[schema(example = "validation_error")]
-    pub code: String,
+    pub code: ErrorCode,
     #[schema(example = "Query cannot be empty")]
     pub message: String,
 }
 
 impl ApiError {
-    pub fn new(code: impl Into<String>, message: impl Into<String>) -> Self {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
         Self {
             error: ApiErrorBody {
-                code: code.into(),
+                code,
                 message: message.into(),
             },
         }
@@ -72,13 +212,17 @@ impl ApiError {
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        let (status, code) = match &self {
-            Self::Validation(_) => (StatusCode::BAD_REQUEST, "validation_error"),
-            Self::NotFound(_) => (StatusCode::NOT_FOUND, "not_found"),
-            Self::Internal(_) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error"),
-        };
-
+        let code = self.code();
+        let status = code.status();
         let body = ApiError::new(code, self.to_string());
-        (status, Json(body)).into_response()
+        match self {
+            Self::Overloaded { retry_after } => (
+                status,
+                [(header::RETRY_AFTER, retry_after.as_secs().to_string())],
+                Json(body),
+            )
+                .into_response(),
+            _ => (status, Json(body)).into_response(),
+        }
     }
 }
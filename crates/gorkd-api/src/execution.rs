@@ -0,0 +1,106 @@
+//! Where research pipelines run.
+//!
+//! By default the API runs each job's pipeline in-process. With
+//! `JOB_EXECUTION=queue` it only stores new jobs as pending and leaves them
+//! to `gorkd-worker` processes sharing the same store, so search and LLM
+//! throughput scale independently of HTTP serving. Queue mode needs a
+//! [`shared_store`]; until this build has one, the configuration check
+//! rejects it and `gorkd-worker` refuses to start.
+
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+
+use gorkd_core::{ErrorCode, RetryPolicy, Store, WorkerConfig};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum JobExecution {
+    /// The API process runs the pipeline as soon as a job is created.
+    #[default]
+    Inline,
+    /// Jobs wait in the store for a worker to claim them.
+    Queue,
+}
+
+impl JobExecution {
+    /// Reads `JOB_EXECUTION` (`inline` or `queue`), defaulting to inline.
+    pub fn from_env() -> Self {
+        match env::var("JOB_EXECUTION") {
+            Ok(value) if value.eq_ignore_ascii_case("queue") => Self::Queue,
+            _ => Self::Inline,
+        }
+    }
+}
+
+/// The store `gorkd-api` and `gorkd-worker` processes share in queue mode,
+/// or `None` when this build has none. Only the in-memory store exists so
+/// far, and it is private to the process that built it, so jobs queued in
+/// it could never reach a worker.
+pub fn shared_store() -> Option<Arc<dyn Store>> {
+    None
+}
+
+/// Reads `WORKER_CONCURRENCY`, `WORKER_POLL_INTERVAL_MS` and
+/// `WORKER_LEASE_SECS`, and the retry policy of [`retry_policy_from_env`].
+pub fn worker_config_from_env() -> WorkerConfig {
+    let defaults = WorkerConfig::default();
+    let concurrency = env::var("WORKER_CONCURRENCY")
+        .ok()
+        .and_then(|s| s.parse::<usize>().ok())
+        .filter(|&n| n > 0)
+        .unwrap_or(defaults.concurrency);
+    let poll_interval = env::var("WORKER_POLL_INTERVAL_MS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .map(Duration::from_millis)
+        .unwrap_or(defaults.poll_interval);
+    let lease_duration = env::var("WORKER_LEASE_SECS")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .filter(|&n| n > 0)
+        .map(Duration::from_secs)
+        .unwrap_or(defaults.lease_duration);
+
+    WorkerConfig {
+        concurrency,
+        poll_interval,
+        lease_duration,
+        retry: retry_policy_from_env(),
+        ..defaults
+    }
+}
+
+/// Reads `JOB_RETRY_MAX_ATTEMPTS` (unset or `1` disables automatic retries),
+/// `JOB_RETRY_BACKOFF_MS` and `JOB_RETRY_KINDS`, a comma-separated list of
+/// error codes such as `rate_limited,timeout`. Unknown codes are ignored.
+pub fn retry_policy_from_env() -> RetryPolicy {
+    let defaults = RetryPolicy::default();
+    let max_attempts = env::var("JOB_RETRY_MAX_ATTEMPTS")
+        .ok()
+        .and_then(|s| s.parse::<u32>().ok())
+        .filter(|&n| n > 0)
+        .unwrap_or(defaults.max_attempts);
+    let backoff = env::var("JOB_RETRY_BACKOFF_MS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .map(Duration::from_millis)
+        .unwrap_or(defaults.backoff);
+    let kinds = env::var("JOB_RETRY_KINDS")
+        .ok()
+        .map(|s| parse_error_codes(&s))
+        .unwrap_or(defaults.kinds);
+
+    RetryPolicy {
+        max_attempts,
+        backoff,
+        kinds,
+    }
+}
+
+fn parse_error_codes(list: &str) -> Vec<ErrorCode> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|code| !code.is_empty())
+        .filter_map(|code| serde_json::from_value(serde_json::Value::from(code)).ok())
+        .collect()
+}
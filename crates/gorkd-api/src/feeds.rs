@@ -0,0 +1,142 @@
+//! Feed subscriptions that keep the document corpus current.
+//!
+//! `CORPUS_FEEDS` lists RSS or Atom feeds, each read every
+//! `CORPUS_FEED_POLL_SECS` by the API process, with new entries added to
+//! the corpus of the feed's workspace. Monitoring jobs then research the
+//! workspace with `corpus_only` and a `published_after` date, so answers
+//! draw only on what the feeds published lately.
+
+use std::env;
+use std::future::Future;
+use std::time::Duration;
+
+use gorkd_core::{
+    is_valid_workspace, FeedPollReport, FeedPoller, FeedSubscription, DEFAULT_FEED_POLL_INTERVAL,
+    DEFAULT_WORKSPACE,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeedSettings {
+    pub subscriptions: Vec<FeedSubscription>,
+    pub poll_interval: Duration,
+}
+
+impl Default for FeedSettings {
+    fn default() -> Self {
+        Self {
+            subscriptions: Vec::new(),
+            poll_interval: DEFAULT_FEED_POLL_INTERVAL,
+        }
+    }
+}
+
+impl FeedSettings {
+    /// Reads `CORPUS_FEEDS` and `CORPUS_FEED_POLL_SECS`. Entries of
+    /// `CORPUS_FEEDS` that cannot be parsed are left out; the config check
+    /// reports them before startup.
+    pub fn from_env() -> Self {
+        let subscriptions = env::var("CORPUS_FEEDS")
+            .map(|spec| {
+                spec.split(',')
+                    .filter_map(|entry| parse_subscription(entry).ok().flatten())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let poll_interval = env::var("CORPUS_FEED_POLL_SECS")
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+            .filter(|&secs| secs > 0)
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_FEED_POLL_INTERVAL);
+
+        Self {
+            subscriptions,
+            poll_interval,
+        }
+    }
+}
+
+/// Parses a comma-separated list of feeds, each `<url>` for the default
+/// workspace or `<workspace>=<url>`.
+pub fn parse_subscriptions(spec: &str) -> Result<Vec<FeedSubscription>, String> {
+    let mut subscriptions = Vec::new();
+    for entry in spec.split(',') {
+        if let Some(subscription) = parse_subscription(entry)? {
+            subscriptions.push(subscription);
+        }
+    }
+    Ok(subscriptions)
+}
+
+/// One entry of `CORPUS_FEEDS`, or `None` when it is blank.
+fn parse_subscription(entry: &str) -> Result<Option<FeedSubscription>, String> {
+    let entry = entry.trim();
+    if entry.is_empty() {
+        return Ok(None);
+    }
+
+    // A URL's scheme is followed by `:`, so a `=` before any `:` separates
+    // the workspace.
+    let (workspace, url) = match entry.split_once('=') {
+        Some((workspace, url)) if !workspace.contains(':') => (workspace.trim(), url.trim()),
+        _ => (DEFAULT_WORKSPACE, entry),
+    };
+    if !is_valid_workspace(workspace) {
+        return Err(format!(
+            "{:?} is not a workspace name; use letters, digits, '-' or '_'",
+            workspace
+        ));
+    }
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        return Err(format!("{:?} is not an http or https URL", url));
+    }
+
+    Ok(Some(FeedSubscription::new(url, workspace)))
+}
+
+/// Polls the feeds until `shutdown` resolves, logging what each pass added.
+pub async fn poll_feeds(poller: FeedPoller, shutdown: impl Future<Output = ()>) {
+    tracing::info!(feeds = poller.subscriptions().len(), "polling corpus feeds");
+    poller.run(shutdown, |report| log_report(&report)).await;
+}
+
+pub(crate) fn log_report(report: &FeedPollReport) {
+    for failure in &report.failures {
+        tracing::warn!(feed = %failure.url, error = %failure.error, "failed to poll feed");
+    }
+    if report.added > 0 {
+        tracing::info!(
+            feeds = report.feeds_read,
+            added = report.added,
+            "added feed entries to corpus"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_feeds_with_and_without_workspaces() {
+        let feeds = parse_subscriptions(
+            " https://news.example.com/rss , news=https://blog.example.com/atom.xml?tag=a=b,",
+        )
+        .unwrap();
+
+        assert_eq!(
+            feeds,
+            vec![
+                FeedSubscription::new("https://news.example.com/rss", "default"),
+                FeedSubscription::new("https://blog.example.com/atom.xml?tag=a=b", "news"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_workspaces_and_urls() {
+        assert!(parse_subscriptions("my team=https://news.example.com/rss").is_err());
+        assert!(parse_subscriptions("news=ftp://news.example.com/rss").is_err());
+        assert!(parse_subscriptions("news.example.com/rss").is_err());
+    }
+}
@@ -5,11 +5,23 @@ use utoipa::OpenApi;
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_scalar::{Scalar, Servable};
 
+pub mod artifacts;
+pub mod bootstrap;
+pub mod config_check;
 mod dto;
 mod error;
+pub mod execution;
+pub mod feeds;
+pub mod objects;
 mod openapi;
+pub mod plugins;
+pub mod publish;
+pub mod queue;
 pub mod routes;
+pub mod self_test;
 mod state;
+pub mod store_metrics;
+pub mod stream;
 
 pub use state::AppState;
 
@@ -19,7 +31,13 @@ pub fn app(state: Arc<AppState>) -> Router {
     let (router, api) = OpenApiRouter::with_openapi(ApiDoc::openapi())
         .merge(routes::health::router())
         .merge(routes::research::router())
+        .merge(routes::tools::router())
         .merge(routes::jobs::router())
+        .merge(routes::projects::router())
+        .merge(routes::knowledge::router())
+        .merge(routes::stats::router())
+        .merge(routes::corpus::router())
+        .merge(routes::admin::router())
         .split_for_parts();
 
     router
@@ -1,11 +1,12 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
 
-use gorkd_api::{app, AppState};
-use gorkd_core::{MockLlmProvider, MockSearchProvider, MockStore};
-use gorkd_llm::{default_http_client, LlmConfig, LlmRegistry};
-use gorkd_search::{ProviderRegistry, SearchConfig};
-use tokio::signal;
+use gorkd_api::config_check::{self, ConfigSummary};
+use gorkd_api::execution::{retry_policy_from_env, JobExecution};
+use gorkd_api::feeds;
+use gorkd_api::queue::QueueConfig;
+use gorkd_api::{app, bootstrap, self_test};
+use gorkd_core::{MockStore, Store};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
@@ -15,58 +16,48 @@ async fn main() {
         .with(tracing_subscriber::EnvFilter::from_default_env())
         .init();
 
+    // Exit before building anything from settings that cannot work.
+    config_check::check_env().enforce();
+
     let port: u16 = std::env::var("PORT")
         .ok()
         .and_then(|p| p.parse().ok())
         .unwrap_or(4000);
 
-    let store = Arc::new(MockStore::new());
-
-    let llm_config = LlmConfig::from_env();
-    let llm_registry = if llm_config.has_provider() {
-        let http = default_http_client().expect("failed to create HTTP client");
-        let registry = LlmRegistry::from_config(http, &llm_config);
-        tracing::info!(
-            models = ?registry.available_models(),
-            default = ?registry.default_model_id(),
-            fallback = ?registry.fallback_model_id(),
-            "initialized LLM providers from environment"
-        );
-        registry
-    } else {
-        tracing::warn!("no LLM providers configured, using mock provider");
-        let mock = Arc::new(MockLlmProvider::new("mock-gpt-4"));
-        LlmRegistry::builder()
-            .register("mock-gpt-4", mock)
-            .default_model("mock-gpt-4")
-            .build()
-    };
+    let store: Arc<dyn Store> = Arc::new(MockStore::new());
 
-    let search_registry = match SearchConfig::from_env() {
-        Ok(config) => {
-            let registry = ProviderRegistry::from_config(&config);
-            tracing::info!(
-                providers = ?registry.list(),
-                "initialized search providers from environment"
-            );
-            registry
-        }
-        Err(_) => {
-            tracing::warn!("no search providers configured, using mock provider");
-            let mut registry = ProviderRegistry::new();
-            registry.register(
-                "mock-tavily",
-                Arc::new(MockSearchProvider::new("mock-tavily")),
-            );
-            registry
-        }
-    };
+    let state = Arc::new(
+        bootstrap::state_from_env(store)
+            .await
+            .with_job_execution(JobExecution::from_env())
+            .with_job_queue(QueueConfig::from_env())
+            .with_retry_policy(retry_policy_from_env()),
+    );
+
+    // `--self-test` checks the deployment end to end instead of serving.
+    if std::env::args().skip(1).any(|arg| arg == "--self-test") {
+        let report = self_test::run(&state).await;
+        println!("{}", report);
+        std::process::exit(if report.passed() { 0 } else { 1 });
+    }
 
-    let state = Arc::new(AppState::with_registries(
-        store,
-        search_registry,
-        llm_registry,
-    ));
+    let mut report = config_check::check_state(&state);
+    if config_check::live_checks_enabled() {
+        report.extend(config_check::check_live(&state).await);
+    }
+    report.enforce();
+    tracing::info!(
+        "configuration:\n{}",
+        ConfigSummary::of(&state)
+            .with("store", "in-memory")
+            .with("port", port.to_string())
+    );
+
+    // Polling stops with the runtime; an entry cut off mid-poll is not
+    // stored, so the next start adds it.
+    if let Some(poller) = state.feed_poller(None) {
+        tokio::spawn(feeds::poll_feeds(poller, std::future::pending()));
+    }
 
     let app = app(state);
 
@@ -76,35 +67,9 @@ async fn main() {
 
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
     axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
+        .with_graceful_shutdown(bootstrap::shutdown_signal())
         .await
         .unwrap();
 
     tracing::info!("shutdown complete");
 }
-
-async fn shutdown_signal() {
-    let ctrl_c = async {
-        signal::ctrl_c()
-            .await
-            .expect("failed to install Ctrl+C handler");
-    };
-
-    #[cfg(unix)]
-    let terminate = async {
-        signal::unix::signal(signal::unix::SignalKind::terminate())
-            .expect("failed to install signal handler")
-            .recv()
-            .await;
-    };
-
-    #[cfg(not(unix))]
-    let terminate = std::future::pending::<()>();
-
-    tokio::select! {
-        _ = ctrl_c => {},
-        _ = terminate => {},
-    }
-
-    tracing::info!("shutdown signal received");
-}
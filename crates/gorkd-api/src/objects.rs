@@ -0,0 +1,99 @@
+//! Where large source contents and raw search responses are kept.
+//!
+//! Object storage is off by default, leaving every body in the job store.
+//! When enabled, the store is wrapped in an [`OffloadingStore`] that moves
+//! bodies of at least `OBJECT_STORAGE_MIN_BYTES` into the configured backend
+//! and keeps only their keys in its rows. Source contents are shared by hash
+//! across jobs; `GET /metrics` reports how often that saved a write.
+
+use std::env;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use gorkd_core::{HttpOptions, Store};
+use gorkd_llm::build_http_client_with_options;
+use gorkd_store::{
+    ContentMetrics, DirectoryObjectStore, ObjectStore, OffloadingStore, S3Config, S3ObjectStore,
+    DEFAULT_MIN_OFFLOAD_BYTES,
+};
+
+/// Directory used by `OBJECT_STORAGE=dir` when `OBJECT_STORAGE_DIR` is unset.
+pub const DEFAULT_OBJECT_DIR: &str = "objects";
+
+/// Timeout of each object storage request.
+const OBJECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Debug, Default)]
+pub enum ObjectStorage {
+    #[default]
+    Off,
+    /// Keep objects as files under a local directory.
+    Directory(PathBuf),
+    /// Keep objects in an S3-compatible bucket, such as AWS S3 or MinIO.
+    S3(S3Config),
+}
+
+impl ObjectStorage {
+    /// Reads `OBJECT_STORAGE` (`off`, `dir` or `s3`), `OBJECT_STORAGE_DIR`
+    /// and, for `s3`, the bucket settings read by [`S3Config::from_env`]. An
+    /// S3 bucket without keys leaves object storage off.
+    pub fn from_env() -> Self {
+        match env::var("OBJECT_STORAGE")
+            .unwrap_or_default()
+            .to_lowercase()
+            .as_str()
+        {
+            "dir" | "directory" => Self::Directory(
+                env::var("OBJECT_STORAGE_DIR")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|_| PathBuf::from(DEFAULT_OBJECT_DIR)),
+            ),
+            "s3" => S3Config::from_env().map_or(Self::Off, Self::S3),
+            _ => Self::Off,
+        }
+    }
+
+    pub fn is_off(&self) -> bool {
+        matches!(self, Self::Off)
+    }
+
+    pub fn object_store(&self, http_options: &HttpOptions) -> Option<Arc<dyn ObjectStore>> {
+        match self {
+            Self::Off => None,
+            Self::Directory(dir) => Some(Arc::new(DirectoryObjectStore::new(dir.clone()))),
+            Self::S3(config) => {
+                let client = build_http_client_with_options(OBJECT_TIMEOUT, http_options)
+                    .expect("failed to create HTTP client");
+                Some(Arc::new(S3ObjectStore::new(client, config.clone())))
+            }
+        }
+    }
+
+    /// Wraps `store` so it offloads bodies of at least `min_bytes` bytes,
+    /// returning it with the metrics of the source contents it keeps, or
+    /// returns it as it is when object storage is off.
+    pub fn wrap(
+        &self,
+        store: Arc<dyn Store>,
+        min_bytes: usize,
+        http_options: &HttpOptions,
+    ) -> (Arc<dyn Store>, Option<Arc<ContentMetrics>>) {
+        match self.object_store(http_options) {
+            Some(objects) => {
+                let store = OffloadingStore::new(store, objects).with_min_bytes(min_bytes);
+                let metrics = store.metrics();
+                (Arc::new(store), Some(metrics))
+            }
+            None => (store, None),
+        }
+    }
+}
+
+/// Reads `OBJECT_STORAGE_MIN_BYTES`.
+pub fn min_bytes_from_env() -> usize {
+    env::var("OBJECT_STORAGE_MIN_BYTES")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_MIN_OFFLOAD_BYTES)
+}
@@ -1,11 +1,36 @@
 use utoipa::OpenApi;
 
 use crate::dto::{
-    CreateResearchRequest, CreateResearchResponse, JobResponse, JobSourceResponse, JobStatus,
-    SourceDetail,
+    AgreementDetail, AnswerDepth, AnswerDetail, AnswerDiffResponse, AnswerRating,
+    AnswerSchemaRequest, AnswerSectionDetail, AnswerStyle, ArchivedSnapshotDetail, ArtifactDetail,
+    ArtifactMessage, AttachJobRequest, BudgetDetail, CitationChangeDetail, CitationDetail,
+    ClaimChangeDetail, ClaimChangeKind, Confidence, ConfidenceChange, ConsistencyDetail,
+    ContentCacheDetail, ContextUsageDetail, CostBudget, CrawlCorpusRequest, CrawlCorpusResponse,
+    CreateDocumentRequest, CreateProjectRequest, CreateResearchRequest, CreateResearchResponse,
+    DailyUsageDetail, DirectSourceRequest, DirectSynthesisResponse, DirectSynthesizeRequest,
+    DisputedClaimDetail, DocumentContentResponse, DocumentFormat, DocumentListResponse,
+    DocumentResponse, DomainCitationsDetail, DomainGroup, EntityKind, EscalationDetail, FactDetail,
+    FactSourceDetail, FailureDetail, FeedFailureResponse, FeedListResponse, FeedPollResponse,
+    FeedResponse, FeedbackListResponse, FeedbackRequest, FeedbackResponse, FreshnessDetail,
+    JobArtifactsResponse, JobEventDetail, JobEventsResponse, JobImportResponse, JobListResponse,
+    JobResponse, JobSourceResponse, JobStatus, JobSummaryDetail, JobSummaryListResponse,
+    KeyEntityDetail, KnowledgeResponse, LinkStatus, LlmStage, ModelAnswerDetail,
+    ModelComparisonResponse, ModelTier, ModelUsageDetail, ModerationDetail, PooledSourceDetail,
+    PooledSourceKind, ProgressDetail, ProjectFindingDetail, ProjectJobsResponse,
+    ProjectReportResponse, ProjectResponse, QueryProgressDetail, ReferenceDetail,
+    ReprocessResponse, RoutingDetail, SearchMetadataDetail, SentenceEvidenceDetail, SnippetDetail,
+    SourceDetail, SourceFlagDetail, SourceFlagRequest, SourceGrouping, SourceHighlight, SourceSort,
+    StageLatencyDetail, StageTokenUsageDetail, StatsResponse, StatusCountDetail, SynthesisResponse,
+    SynthesizeRequest, TermMatchDetail, TextSpan, TimeConstraint, TokenUsageDetail,
+    ToolInvocationDetail, ToolSchemaResponse, TrustLabel, Volatility, WhyInsufficientDetail,
 };
-use crate::error::{ApiError, ApiErrorBody};
-use crate::routes::health::HealthResponse;
+use crate::error::{ApiError, ApiErrorBody, ErrorCode};
+use crate::routes::health::{
+    ContentMetricsDetail, HealthResponse, LlmConcurrencyDetail, LlmProviderConcurrency,
+    LlmSlotUsage, MetricsResponse, QueueHealth, ShadowEvaluationMetrics, StoreHealthDetail,
+    StoreMetricsDetail, StoreOperationMetrics,
+};
+use crate::stream::{StreamEvent, TracedStreamEvent};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -18,18 +43,137 @@ use crate::routes::health::HealthResponse;
     tags(
         (name = "research", description = "Research operations"),
         (name = "jobs", description = "Job management"),
-        (name = "health", description = "Health checks")
+        (name = "projects", description = "Jobs grouped into research projects"),
+        (name = "knowledge", description = "Facts accumulated across jobs"),
+        (name = "stats", description = "Aggregate usage analytics"),
+        (name = "corpus", description = "Documents searched alongside the web"),
+        (name = "health", description = "Health checks"),
+        (name = "admin", description = "Operator diagnostics")
     ),
     components(schemas(
         CreateResearchRequest,
+        AnswerSchemaRequest,
+        AnswerDepth,
+        AnswerStyle,
+        CostBudget,
         CreateResearchResponse,
+        SynthesizeRequest,
+        SynthesisResponse,
+        DirectSynthesizeRequest,
+        DirectSourceRequest,
+        DirectSynthesisResponse,
+        ToolSchemaResponse,
+        ToolInvocationDetail,
+        PooledSourceDetail,
+        PooledSourceKind,
+        JobListResponse,
+        JobSummaryListResponse,
+        JobSummaryDetail,
+        StatusCountDetail,
         JobResponse,
+        CreateProjectRequest,
+        ProjectResponse,
+        AttachJobRequest,
+        ProjectJobsResponse,
+        ProjectFindingDetail,
+        ProjectReportResponse,
+        KnowledgeResponse,
+        FactDetail,
+        FactSourceDetail,
+        CreateDocumentRequest,
+        DocumentResponse,
+        DocumentContentResponse,
+        DocumentListResponse,
+        CrawlCorpusRequest,
+        CrawlCorpusResponse,
+        FeedResponse,
+        FeedListResponse,
+        FeedFailureResponse,
+        FeedPollResponse,
         JobSourceResponse,
         SourceDetail,
+        SourceHighlight,
+        SnippetDetail,
+        TermMatchDetail,
+        ArchivedSnapshotDetail,
+        LinkStatus,
+        TextSpan,
+        DocumentFormat,
+        SearchMetadataDetail,
+        TimeConstraint,
+        DomainGroup,
+        SourceSort,
+        SourceGrouping,
+        JobEventsResponse,
+        JobEventDetail,
+        FeedbackRequest,
+        SourceFlagRequest,
+        AnswerRating,
+        FeedbackResponse,
+        SourceFlagDetail,
+        FeedbackListResponse,
+        TrustLabel,
+        ModelComparisonResponse,
+        ModelAnswerDetail,
+        AgreementDetail,
+        AnswerDiffResponse,
+        ConfidenceChange,
+        ClaimChangeDetail,
+        ClaimChangeKind,
+        CitationChangeDetail,
+        JobArtifactsResponse,
+        ReprocessResponse,
+        JobImportResponse,
+        StatsResponse,
+        DailyUsageDetail,
+        StageLatencyDetail,
+        DomainCitationsDetail,
+        ModelUsageDetail,
+        ContentCacheDetail,
+        ArtifactDetail,
+        ArtifactMessage,
         JobStatus,
+        ProgressDetail,
+        QueryProgressDetail,
+        FailureDetail,
+        AnswerDetail,
+        AnswerSectionDetail,
+        KeyEntityDetail,
+        EntityKind,
+        FreshnessDetail,
+        WhyInsufficientDetail,
+        EscalationDetail,
+        ConsistencyDetail,
+        DisputedClaimDetail,
+        Volatility,
+        CitationDetail,
+        ReferenceDetail,
+        SentenceEvidenceDetail,
+        Confidence,
+        ModerationDetail,
+        TokenUsageDetail,
+        ContextUsageDetail,
+        StageTokenUsageDetail,
+        BudgetDetail,
+        RoutingDetail,
+        ModelTier,
+        LlmStage,
         ApiError,
         ApiErrorBody,
+        ErrorCode,
         HealthResponse,
+        QueueHealth,
+        StoreHealthDetail,
+        MetricsResponse,
+        StoreMetricsDetail,
+        StoreOperationMetrics,
+        ContentMetricsDetail,
+        LlmConcurrencyDetail,
+        LlmProviderConcurrency,
+        LlmSlotUsage,
+        ShadowEvaluationMetrics,
+        StreamEvent,
+        TracedStreamEvent,
     ))
 )]
 pub struct ApiDoc;
@@ -0,0 +1,526 @@
+//! Sandboxed WASM plugins for custom pipeline steps (experimental).
+//!
+//! Plugins rewrite queries, filter sources or post-process answers with
+//! business logic that does not belong in gorkd. Each is a WebAssembly
+//! module speaking the guest API in `docs/interfaces/wasm-plugins.md`: it
+//! imports nothing, so it sees only the JSON each hook hands it, and every
+//! call runs in a fresh instance with bounded fuel and memory. Running them
+//! needs the `wasm-plugins` cargo feature, so builds without plugins skip
+//! the wasmtime dependency.
+
+use std::env;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use gorkd_core::{PipelinePlugin, PluginError};
+
+/// Version of the guest API plugins must report from `gorkd_api_version`.
+pub const PLUGIN_API_VERSION: i32 = 1;
+
+/// Instructions, roughly, one hook call may run when `WASM_PLUGIN_FUEL` is
+/// unset.
+pub const DEFAULT_PLUGIN_FUEL: u64 = 500_000_000;
+
+/// Linear memory one plugin instance may grow to when
+/// `WASM_PLUGIN_MAX_MEMORY_MB` is unset.
+pub const DEFAULT_PLUGIN_MEMORY_MB: usize = 64;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PluginSettings {
+    /// Modules to load, in the order their hooks run. Text (`.wat`)
+    /// modules are accepted as well as binary ones.
+    pub paths: Vec<PathBuf>,
+    pub fuel: u64,
+    pub max_memory_bytes: usize,
+}
+
+impl Default for PluginSettings {
+    fn default() -> Self {
+        Self {
+            paths: Vec::new(),
+            fuel: DEFAULT_PLUGIN_FUEL,
+            max_memory_bytes: DEFAULT_PLUGIN_MEMORY_MB * 1024 * 1024,
+        }
+    }
+}
+
+impl PluginSettings {
+    /// Reads `WASM_PLUGINS` (comma-separated module paths),
+    /// `WASM_PLUGIN_FUEL` and `WASM_PLUGIN_MAX_MEMORY_MB`. Unparsable
+    /// numbers are ignored.
+    pub fn from_env() -> Self {
+        let var = |name: &str| env::var(name).ok().filter(|s| !s.trim().is_empty());
+        let mut settings = Self::default();
+
+        if let Some(paths) = var("WASM_PLUGINS") {
+            settings.paths = paths
+                .split(',')
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .map(PathBuf::from)
+                .collect();
+        }
+        if let Some(fuel) = var("WASM_PLUGIN_FUEL").and_then(|s| s.trim().parse().ok()) {
+            settings.fuel = fuel;
+        }
+        if let Some(mb) =
+            var("WASM_PLUGIN_MAX_MEMORY_MB").and_then(|s| s.trim().parse::<usize>().ok())
+        {
+            settings.max_memory_bytes = mb * 1024 * 1024;
+        }
+        settings
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Compiles every configured module and checks it speaks the guest API.
+    pub fn load(&self) -> Result<Vec<Arc<dyn PipelinePlugin>>, PluginError> {
+        if self.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        #[cfg(feature = "wasm-plugins")]
+        {
+            let engine = wasm::engine()?;
+            self.paths
+                .iter()
+                .map(|path| {
+                    let plugin = wasm::WasmPlugin::from_file(&engine, path, self)?;
+                    Ok(Arc::new(plugin) as Arc<dyn PipelinePlugin>)
+                })
+                .collect()
+        }
+        #[cfg(not(feature = "wasm-plugins"))]
+        {
+            Err(PluginError::Failed(
+                "this build has no WASM plugin support; build with --features wasm-plugins"
+                    .to_string(),
+            ))
+        }
+    }
+}
+
+#[cfg(feature = "wasm-plugins")]
+pub use wasm::WasmPlugin;
+
+#[cfg(feature = "wasm-plugins")]
+mod wasm {
+    use std::path::Path;
+
+    use async_trait::async_trait;
+    use gorkd_core::{PipelinePlugin, PluginError, ResearchAnswer, Source};
+    use serde::de::DeserializeOwned;
+    use serde::{Deserialize, Serialize};
+    use wasmtime::{
+        Config, Engine, Instance, Module, Store, StoreLimits, StoreLimitsBuilder, Trap,
+    };
+
+    use super::{PluginSettings, PLUGIN_API_VERSION};
+
+    const ALLOC: &str = "gorkd_alloc";
+    const API_VERSION: &str = "gorkd_api_version";
+    const REWRITE_QUERY: &str = "gorkd_rewrite_query";
+    const FILTER_SOURCES: &str = "gorkd_filter_sources";
+    const POST_PROCESS: &str = "gorkd_post_process";
+
+    pub(super) fn engine() -> Result<Engine, PluginError> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        Engine::new(&config).map_err(|e| PluginError::Failed(e.to_string()))
+    }
+
+    /// A plugin compiled from a WASM module. Each hook call instantiates the
+    /// module afresh, so no state survives from one call to the next.
+    #[derive(Clone)]
+    pub struct WasmPlugin {
+        name: String,
+        engine: Engine,
+        module: Module,
+        fuel: u64,
+        max_memory_bytes: usize,
+    }
+
+    impl WasmPlugin {
+        /// Compiles the module at `path`, named after its file stem.
+        pub fn from_file(
+            engine: &Engine,
+            path: &Path,
+            settings: &PluginSettings,
+        ) -> Result<Self, PluginError> {
+            let name = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string());
+            let module = Module::from_file(engine, path)
+                .map_err(|e| PluginError::Failed(format!("{}: {}", path.display(), e)))?;
+            Self::new(name, engine, module, settings)
+        }
+
+        /// Compiles `bytes`, binary or text.
+        pub fn from_bytes(
+            name: impl Into<String>,
+            engine: &Engine,
+            bytes: &[u8],
+            settings: &PluginSettings,
+        ) -> Result<Self, PluginError> {
+            let module =
+                Module::new(engine, bytes).map_err(|e| PluginError::Failed(e.to_string()))?;
+            Self::new(name.into(), engine, module, settings)
+        }
+
+        fn new(
+            name: String,
+            engine: &Engine,
+            module: Module,
+            settings: &PluginSettings,
+        ) -> Result<Self, PluginError> {
+            if let Some(import) = module.imports().next() {
+                return Err(PluginError::Failed(format!(
+                    "{} imports {}::{}; plugins may import nothing",
+                    name,
+                    import.module(),
+                    import.name()
+                )));
+            }
+            for export in ["memory", ALLOC, API_VERSION] {
+                if module.get_export(export).is_none() {
+                    return Err(PluginError::Failed(format!(
+                        "{} does not export {}",
+                        name, export
+                    )));
+                }
+            }
+
+            let plugin = Self {
+                name,
+                engine: engine.clone(),
+                module,
+                fuel: settings.fuel,
+                max_memory_bytes: settings.max_memory_bytes,
+            };
+            let mut store = plugin.store()?;
+            let instance = plugin.instantiate(&mut store)?;
+            let version = instance
+                .get_typed_func::<(), i32>(&mut store, API_VERSION)
+                .and_then(|f| f.call(&mut store, ()))
+                .map_err(|e| trap_error(&e))?;
+            if version != PLUGIN_API_VERSION {
+                return Err(PluginError::Failed(format!(
+                    "{} speaks guest API version {}, expected {}",
+                    plugin.name, version, PLUGIN_API_VERSION
+                )));
+            }
+            Ok(plugin)
+        }
+
+        fn store(&self) -> Result<Store<StoreLimits>, PluginError> {
+            let limits = StoreLimitsBuilder::new()
+                .memory_size(self.max_memory_bytes)
+                .instances(1)
+                .build();
+            let mut store = Store::new(&self.engine, limits);
+            store.limiter(|limits| limits);
+            store
+                .set_fuel(self.fuel)
+                .map_err(|e| PluginError::Failed(e.to_string()))?;
+            Ok(store)
+        }
+
+        fn instantiate(&self, store: &mut Store<StoreLimits>) -> Result<Instance, PluginError> {
+            Instance::new(&mut *store, &self.module, &[]).map_err(|e| trap_error(&e))
+        }
+
+        /// Hands `input` to the hook `export` and parses what it returns;
+        /// `None` when the plugin has no such hook or leaves the input as
+        /// it was.
+        async fn call<I, O>(
+            &self,
+            export: &'static str,
+            input: &I,
+        ) -> Result<Option<O>, PluginError>
+        where
+            I: Serialize,
+            O: DeserializeOwned + Send + 'static,
+        {
+            if self.module.get_export(export).is_none() {
+                return Ok(None);
+            }
+            let input =
+                serde_json::to_vec(input).map_err(|e| PluginError::Failed(e.to_string()))?;
+            let plugin = self.clone();
+            tokio::task::spawn_blocking(move || plugin.call_blocking(export, &input))
+                .await
+                .map_err(|e| PluginError::Failed(e.to_string()))?
+        }
+
+        fn call_blocking<O: DeserializeOwned>(
+            &self,
+            export: &str,
+            input: &[u8],
+        ) -> Result<Option<O>, PluginError> {
+            let mut store = self.store()?;
+            let instance = self.instantiate(&mut store)?;
+            let memory = instance
+                .get_memory(&mut store, "memory")
+                .ok_or_else(|| PluginError::Failed("memory is not a memory".to_string()))?;
+            let alloc = instance
+                .get_typed_func::<i32, i32>(&mut store, ALLOC)
+                .map_err(|e| PluginError::Failed(e.to_string()))?;
+            let hook = instance
+                .get_typed_func::<(i32, i32), i64>(&mut store, export)
+                .map_err(|e| PluginError::Failed(e.to_string()))?;
+
+            let len = i32::try_from(input.len())
+                .map_err(|_| PluginError::Failed("input too large".to_string()))?;
+            let ptr = alloc.call(&mut store, len).map_err(|e| trap_error(&e))?;
+            memory
+                .write(&mut store, ptr as u32 as usize, input)
+                .map_err(|e| PluginError::InvalidOutput(format!("{}: {}", ALLOC, e)))?;
+
+            let packed = hook
+                .call(&mut store, (ptr, len))
+                .map_err(|e| trap_error(&e))? as u64;
+            let (out_ptr, out_len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+            if out_len == 0 {
+                return Ok(None);
+            }
+            let mut output = vec![0; out_len];
+            memory
+                .read(&store, out_ptr, &mut output)
+                .map_err(|e| PluginError::InvalidOutput(e.to_string()))?;
+            serde_json::from_slice(&output)
+                .map(Some)
+                .map_err(|e| PluginError::InvalidOutput(e.to_string()))
+        }
+    }
+
+    fn trap_error(error: &wasmtime::Error) -> PluginError {
+        match error.downcast_ref::<Trap>() {
+            Some(Trap::OutOfFuel) => PluginError::Exhausted("fuel"),
+            _ => PluginError::Failed(format!("{:#}", error)),
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct QueryIo {
+        query: String,
+    }
+
+    #[derive(Serialize)]
+    struct SourcesIn<'a> {
+        query: &'a str,
+        sources: Vec<GuestSource<'a>>,
+    }
+
+    #[derive(Serialize)]
+    struct GuestSource<'a> {
+        id: &'a str,
+        url: &'a str,
+        title: &'a str,
+        domain: &'a str,
+        content: &'a str,
+    }
+
+    #[derive(Deserialize)]
+    struct SourcesOut {
+        keep: Vec<String>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct GuestAnswer {
+        summary: String,
+        detail: String,
+        limitations: Vec<String>,
+    }
+
+    #[derive(Serialize)]
+    struct AnswerIn<'a> {
+        query: &'a str,
+        answer: GuestAnswer,
+    }
+
+    #[derive(Deserialize)]
+    struct AnswerOut {
+        answer: GuestAnswer,
+    }
+
+    #[async_trait]
+    impl PipelinePlugin for WasmPlugin {
+        async fn rewrite_query(&self, query: &mut String) -> Result<(), PluginError> {
+            let input = QueryIo {
+                query: query.clone(),
+            };
+            if let Some(QueryIo { query: rewritten }) = self.call(REWRITE_QUERY, &input).await? {
+                *query = rewritten;
+            }
+            Ok(())
+        }
+
+        async fn filter_sources(
+            &self,
+            query: &str,
+            sources: &mut Vec<Source>,
+        ) -> Result<(), PluginError> {
+            let input = SourcesIn {
+                query,
+                sources: sources
+                    .iter()
+                    .map(|s| GuestSource {
+                        id: s.id.as_str(),
+                        url: &s.url,
+                        title: &s.title,
+                        domain: &s.metadata.domain,
+                        content: &s.content,
+                    })
+                    .collect(),
+            };
+            if let Some(SourcesOut { keep }) = self.call(FILTER_SOURCES, &input).await? {
+                sources.retain(|s| keep.iter().any(|id| id == s.id.as_str()));
+            }
+            Ok(())
+        }
+
+        async fn post_process(
+            &self,
+            query: &str,
+            answer: &mut ResearchAnswer,
+        ) -> Result<(), PluginError> {
+            let input = AnswerIn {
+                query,
+                answer: GuestAnswer {
+                    summary: answer.summary.clone(),
+                    detail: answer.detail.clone(),
+                    limitations: answer.limitations.clone(),
+                },
+            };
+            if let Some(AnswerOut { answer: edited }) = self.call(POST_PROCESS, &input).await? {
+                answer.summary = edited.summary;
+                answer.detail = edited.detail;
+                answer.limitations = edited.limitations;
+            }
+            Ok(())
+        }
+
+        fn plugin_name(&self) -> &str {
+            &self.name
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        /// A guest whose `hook` export returns the JSON `output` from a data
+        /// segment, whatever it is given.
+        fn constant_guest(hook: &str, output: &str) -> String {
+            format!(
+                r#"(module
+                    (memory (export "memory") 1)
+                    (data (i32.const 1024) "{output}")
+                    (func (export "gorkd_api_version") (result i32) i32.const 1)
+                    (func (export "gorkd_alloc") (param i32) (result i32) i32.const 4096)
+                    (func (export "{hook}") (param i32 i32) (result i64)
+                        i64.const {packed}))"#,
+                output = output.replace('"', "\\22"),
+                hook = hook,
+                packed = (1024u64 << 32) | output.len() as u64,
+            )
+        }
+
+        fn plugin(wat: &str) -> Result<WasmPlugin, PluginError> {
+            WasmPlugin::from_bytes(
+                "test",
+                &engine().unwrap(),
+                wat.as_bytes(),
+                &PluginSettings::default(),
+            )
+        }
+
+        #[tokio::test]
+        async fn rewrites_query() {
+            let plugin = plugin(&constant_guest(
+                REWRITE_QUERY,
+                r#"{"query":"rust language"}"#,
+            ))
+            .unwrap();
+
+            let mut query = "rust".to_string();
+            plugin.rewrite_query(&mut query).await.unwrap();
+
+            assert_eq!(query, "rust language");
+        }
+
+        #[tokio::test]
+        async fn keeps_sources_the_guest_lists() {
+            let mut sources = vec![
+                Source::new("https://a.example", "A", "a"),
+                Source::new("https://b.example", "B", "b"),
+            ];
+            let keep = format!(r#"{{"keep":["{}"]}}"#, sources[1].id.as_str());
+            let plugin = plugin(&constant_guest(FILTER_SOURCES, &keep)).unwrap();
+
+            plugin.filter_sources("q", &mut sources).await.unwrap();
+
+            assert_eq!(sources.len(), 1);
+            assert_eq!(sources[0].url, "https://b.example");
+        }
+
+        #[tokio::test]
+        async fn leaves_input_without_the_hook() {
+            let plugin = plugin(&constant_guest(REWRITE_QUERY, r#"{"query":"other"}"#)).unwrap();
+            let mut sources = vec![Source::new("https://a.example", "A", "a")];
+
+            plugin.filter_sources("q", &mut sources).await.unwrap();
+
+            assert_eq!(sources.len(), 1);
+        }
+
+        #[tokio::test]
+        async fn stops_guest_out_of_fuel() {
+            let plugin = plugin(
+                r#"(module
+                    (memory (export "memory") 1)
+                    (func (export "gorkd_api_version") (result i32) i32.const 1)
+                    (func (export "gorkd_alloc") (param i32) (result i32) i32.const 0)
+                    (func (export "gorkd_rewrite_query") (param i32 i32) (result i64)
+                        (loop $spin (br $spin))
+                        i64.const 0))"#,
+            )
+            .unwrap();
+
+            let mut query = "rust".to_string();
+            let result = plugin.rewrite_query(&mut query).await;
+
+            assert!(matches!(result, Err(PluginError::Exhausted("fuel"))));
+            assert_eq!(query, "rust");
+        }
+
+        #[test]
+        fn rejects_guests_with_imports() {
+            let result = plugin(
+                r#"(module
+                    (import "wasi_snapshot_preview1" "fd_write"
+                        (func (param i32 i32 i32 i32) (result i32)))
+                    (memory (export "memory") 1)
+                    (func (export "gorkd_api_version") (result i32) i32.const 1)
+                    (func (export "gorkd_alloc") (param i32) (result i32) i32.const 0))"#,
+            );
+
+            assert!(matches!(result, Err(PluginError::Failed(ref m)) if m.contains("fd_write")));
+        }
+
+        #[test]
+        fn rejects_other_api_versions() {
+            let result = plugin(
+                r#"(module
+                    (memory (export "memory") 1)
+                    (func (export "gorkd_api_version") (result i32) i32.const 2)
+                    (func (export "gorkd_alloc") (param i32) (result i32) i32.const 0))"#,
+            );
+
+            assert!(matches!(result, Err(PluginError::Failed(ref m)) if m.contains("version 2")));
+        }
+    }
+}
@@ -0,0 +1,190 @@
+//! Publishing of job lifecycle events to a message broker.
+//!
+//! Publishing is off by default. Other systems subscribe to learn when jobs
+//! are created, change stage, complete or fail, instead of polling the API.
+//! Each broker client sits behind a cargo feature of the same name (`nats`,
+//! `kafka`), so builds that do not need one skip its dependencies.
+
+use std::env;
+use std::sync::Arc;
+
+use gorkd_core::EventPublisher;
+#[cfg(any(feature = "nats", feature = "kafka"))]
+use gorkd_core::{LifecycleEvent, PublishError, TRACE_ID_HEADER};
+
+/// Subject prefix (NATS) or topic (Kafka) used when `EVENT_TOPIC` is unset.
+pub const DEFAULT_EVENT_TOPIC: &str = "gorkd.jobs";
+pub const DEFAULT_NATS_URL: &str = "nats://localhost:4222";
+pub const DEFAULT_KAFKA_BROKERS: &str = "localhost:9092";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum EventPublishing {
+    #[default]
+    Off,
+    /// Publish each event to `<subject>.<event type>`, so subscribers can
+    /// pick event types with subject wildcards.
+    Nats { url: String, subject: String },
+    /// Publish every event to one topic, keyed by job ID so each job's events
+    /// land in one partition and stay in order.
+    Kafka { brokers: String, topic: String },
+}
+
+impl EventPublishing {
+    /// Reads `EVENT_PUBLISHER` (`off`, `nats` or `kafka`), `EVENT_TOPIC`,
+    /// `NATS_URL` and `KAFKA_BROKERS`.
+    pub fn from_env() -> Self {
+        let topic = || env::var("EVENT_TOPIC").unwrap_or_else(|_| DEFAULT_EVENT_TOPIC.to_string());
+
+        match env::var("EVENT_PUBLISHER")
+            .unwrap_or_default()
+            .to_lowercase()
+            .as_str()
+        {
+            "nats" => Self::Nats {
+                url: env::var("NATS_URL").unwrap_or_else(|_| DEFAULT_NATS_URL.to_string()),
+                subject: topic(),
+            },
+            "kafka" => Self::Kafka {
+                brokers: env::var("KAFKA_BROKERS")
+                    .unwrap_or_else(|_| DEFAULT_KAFKA_BROKERS.to_string()),
+                topic: topic(),
+            },
+            _ => Self::Off,
+        }
+    }
+
+    /// Connects to the configured broker. A broker that cannot be reached, or
+    /// was not compiled in, is logged and leaves publishing off rather than
+    /// keeping the process from starting.
+    pub async fn publisher(&self) -> Option<Arc<dyn EventPublisher>> {
+        match self {
+            Self::Off => None,
+            #[cfg(feature = "nats")]
+            Self::Nats { url, subject } => {
+                match NatsEventPublisher::connect(url, subject.clone()).await {
+                    Ok(publisher) => Some(Arc::new(publisher)),
+                    Err(e) => {
+                        tracing::error!(error = %e, url, "failed to connect to NATS, not publishing job events");
+                        None
+                    }
+                }
+            }
+            #[cfg(feature = "kafka")]
+            Self::Kafka { brokers, topic } => {
+                match KafkaEventPublisher::new(brokers, topic.clone()) {
+                    Ok(publisher) => Some(Arc::new(publisher)),
+                    Err(e) => {
+                        tracing::error!(error = %e, brokers, "failed to create Kafka producer, not publishing job events");
+                        None
+                    }
+                }
+            }
+            #[allow(unreachable_patterns)]
+            other => {
+                tracing::warn!(
+                    publisher = ?other,
+                    "EVENT_PUBLISHER needs gorkd-api built with the matching feature (nats or kafka)"
+                );
+                None
+            }
+        }
+    }
+}
+
+/// Publishes to core NATS. Delivery is at most once; subscribers that need
+/// every event should read the job's event log as well.
+#[cfg(feature = "nats")]
+pub struct NatsEventPublisher {
+    client: async_nats::Client,
+    subject: String,
+}
+
+#[cfg(feature = "nats")]
+impl NatsEventPublisher {
+    pub async fn connect(url: &str, subject: String) -> Result<Self, PublishError> {
+        let client = async_nats::connect(url)
+            .await
+            .map_err(|e| PublishError::Connection(e.to_string()))?;
+        Ok(Self { client, subject })
+    }
+}
+
+#[cfg(feature = "nats")]
+#[async_trait::async_trait]
+impl EventPublisher for NatsEventPublisher {
+    async fn publish(&self, event: &LifecycleEvent) -> Result<(), PublishError> {
+        let payload =
+            serde_json::to_vec(event).map_err(|e| PublishError::Serialization(e.to_string()))?;
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert(TRACE_ID_HEADER, event.trace_id.as_str());
+
+        self.client
+            .publish_with_headers(
+                format!("{}.{}", self.subject, event.kind.name()),
+                headers,
+                payload.into(),
+            )
+            .await
+            .map_err(|e| PublishError::Connection(e.to_string()))
+    }
+
+    fn publisher_name(&self) -> &str {
+        "nats"
+    }
+}
+
+#[cfg(feature = "kafka")]
+pub struct KafkaEventPublisher {
+    producer: rdkafka::producer::FutureProducer,
+    topic: String,
+}
+
+#[cfg(feature = "kafka")]
+impl KafkaEventPublisher {
+    /// How long an event may wait for a full producer queue.
+    const QUEUE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+    pub fn new(brokers: &str, topic: String) -> Result<Self, PublishError> {
+        let producer = rdkafka::ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("message.timeout.ms", "10000")
+            .create()
+            .map_err(|e| PublishError::Connection(e.to_string()))?;
+        Ok(Self { producer, topic })
+    }
+}
+
+#[cfg(feature = "kafka")]
+#[async_trait::async_trait]
+impl EventPublisher for KafkaEventPublisher {
+    async fn publish(&self, event: &LifecycleEvent) -> Result<(), PublishError> {
+        use rdkafka::message::{Header, OwnedHeaders};
+        use rdkafka::producer::FutureRecord;
+
+        let payload =
+            serde_json::to_vec(event).map_err(|e| PublishError::Serialization(e.to_string()))?;
+        let headers = OwnedHeaders::new()
+            .insert(Header {
+                key: "type",
+                value: Some(event.kind.name()),
+            })
+            .insert(Header {
+                key: TRACE_ID_HEADER,
+                value: Some(event.trace_id.as_str()),
+            });
+        let record = FutureRecord::to(&self.topic)
+            .key(event.job_id.as_str())
+            .payload(&payload)
+            .headers(headers);
+
+        self.producer
+            .send(record, Self::QUEUE_TIMEOUT)
+            .await
+            .map(|_| ())
+            .map_err(|(e, _)| PublishError::Rejected(e.to_string()))
+    }
+
+    fn publisher_name(&self) -> &str {
+        "kafka"
+    }
+}
@@ -0,0 +1,111 @@
+//! Admission control for research jobs.
+//!
+//! Every accepted job holds a [`JobPermit`] until its pipeline finishes. With
+//! a maximum depth configured, new jobs are turned away with `503 Service
+//! Unavailable` and a `Retry-After` hint once that many are in flight, rather
+//! than piling up behind saturated LLM and search providers.
+
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// `Retry-After` sent with rejected jobs when none is configured.
+pub const DEFAULT_RETRY_AFTER_SECS: u64 = 10;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueueConfig {
+    /// Jobs allowed in flight at once; `None` accepts everything.
+    pub max_depth: Option<usize>,
+    pub retry_after: Duration,
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: None,
+            retry_after: Duration::from_secs(DEFAULT_RETRY_AFTER_SECS),
+        }
+    }
+}
+
+impl QueueConfig {
+    /// Reads `JOB_QUEUE_MAX_DEPTH` (unset or `0` disables the limit) and
+    /// `JOB_QUEUE_RETRY_AFTER_SECS`.
+    pub fn from_env() -> Self {
+        let max_depth = env::var("JOB_QUEUE_MAX_DEPTH")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .filter(|&depth| depth > 0);
+        let retry_after_secs = env::var("JOB_QUEUE_RETRY_AFTER_SECS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_RETRY_AFTER_SECS);
+
+        Self {
+            max_depth,
+            retry_after: Duration::from_secs(retry_after_secs),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct JobQueue {
+    config: QueueConfig,
+    depth: AtomicUsize,
+}
+
+impl JobQueue {
+    pub fn new(config: QueueConfig) -> Self {
+        Self {
+            config,
+            depth: AtomicUsize::new(0),
+        }
+    }
+
+    /// Reserves a slot for a new job, or returns `None` when the queue is
+    /// full. The slot is released when the permit is dropped.
+    pub fn try_acquire(self: &Arc<Self>) -> Option<JobPermit> {
+        let max_depth = self.config.max_depth.unwrap_or(usize::MAX);
+        self.depth
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |depth| {
+                (depth < max_depth).then_some(depth + 1)
+            })
+            .ok()?;
+
+        Some(JobPermit {
+            queue: Arc::clone(self),
+        })
+    }
+
+    /// Jobs currently in flight.
+    pub fn depth(&self) -> usize {
+        self.depth.load(Ordering::SeqCst)
+    }
+
+    pub fn max_depth(&self) -> Option<usize> {
+        self.config.max_depth
+    }
+
+    pub fn retry_after(&self) -> Duration {
+        self.config.retry_after
+    }
+
+    pub fn is_saturated(&self) -> bool {
+        self.config
+            .max_depth
+            .is_some_and(|max_depth| self.depth() >= max_depth)
+    }
+}
+
+/// A reserved queue slot, held for the lifetime of one job.
+#[derive(Debug)]
+pub struct JobPermit {
+    queue: Arc<JobQueue>,
+}
+
+impl Drop for JobPermit {
+    fn drop(&mut self) {
+        self.queue.depth.fetch_sub(1, Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,257 @@
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::extract::{Path, State};
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::{BoxError, Json};
+use futures::{Stream, StreamExt};
+use gorkd_core::archive::{export_job, import_job};
+use gorkd_core::traits::Store;
+use gorkd_core::{ArchivedJob, JobCursor, JobId, JobStatus};
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+use crate::dto::{JobArtifactsResponse, JobImportResponse, ReprocessResponse};
+use crate::error::{ApiError, AppError};
+use crate::state::AppState;
+
+const JSONL_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Jobs read from the store at a time while exporting.
+const EXPORT_PAGE_SIZE: usize = 100;
+
+#[utoipa::path(
+    get,
+    path = "/v1/admin/jobs/export",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Every job with its sources and answer, newest first, one JSON object per line",
+            body = String, content_type = "application/x-ndjson"),
+    )
+)]
+pub async fn export_jobs(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, JSONL_CONTENT_TYPE)],
+        Body::from_stream(export_pages(state.store.clone(), EXPORT_PAGE_SIZE)),
+    )
+}
+
+/// The store's jobs as JSON lines, `page_size` jobs at a time, newest first.
+/// Pages follow a cursor, so jobs created during the export neither show up
+/// nor push jobs already written onto the next page.
+fn export_pages(
+    store: Arc<dyn Store>,
+    page_size: usize,
+) -> impl Stream<Item = Result<String, BoxError>> {
+    futures::stream::try_unfold(Some(None), move |cursor: Option<Option<JobCursor>>| {
+        let store = store.clone();
+        async move {
+            let Some(cursor) = cursor else {
+                return Ok(None);
+            };
+            let jobs = store.list_jobs_before(cursor.as_ref(), page_size).await?;
+            let Some(last) = jobs.last() else {
+                return Ok(None);
+            };
+
+            let next = (jobs.len() == page_size).then(|| Some(JobCursor::of(last)));
+            let mut lines = String::new();
+            for job in jobs {
+                let archived = export_job(store.as_ref(), job).await?;
+                lines.push_str(&serde_json::to_string(&archived)?);
+                lines.push('\n');
+            }
+            Ok(Some((lines, next)))
+        }
+    })
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/admin/jobs/import",
+    tag = "admin",
+    request_body(content = String, content_type = "application/x-ndjson",
+        description = "Jobs as written by `GET /v1/admin/jobs/export`, one JSON object per line"),
+    responses(
+        (status = 200, description = "Jobs written, and jobs left out because they exist", body = JobImportResponse),
+        (status = 400, description = "A line is not an exported job; the lines before it were imported", body = ApiError),
+    )
+)]
+pub async fn import_jobs(
+    State(state): State<Arc<AppState>>,
+    body: Body,
+) -> Result<Json<JobImportResponse>, AppError> {
+    let mut response = JobImportResponse {
+        imported: 0,
+        skipped: 0,
+    };
+    let mut chunks = body.into_data_stream();
+    let mut pending = Vec::new();
+    let mut line = 0;
+
+    while let Some(chunk) = chunks.next().await {
+        let chunk = chunk.map_err(|e| AppError::validation(format!("reading body: {}", e)))?;
+        pending.extend_from_slice(&chunk);
+        while let Some(end) = pending.iter().position(|&b| b == b'\n') {
+            let bytes: Vec<u8> = pending.drain(..=end).collect();
+            line += 1;
+            import_line(state.store.as_ref(), &bytes, line, &mut response).await?;
+        }
+    }
+    import_line(state.store.as_ref(), &pending, line + 1, &mut response).await?;
+
+    Ok(Json(response))
+}
+
+async fn import_line(
+    store: &dyn Store,
+    bytes: &[u8],
+    line: usize,
+    response: &mut JobImportResponse,
+) -> Result<(), AppError> {
+    if bytes.iter().all(u8::is_ascii_whitespace) {
+        return Ok(());
+    }
+
+    let archived: ArchivedJob = serde_json::from_slice(bytes)
+        .map_err(|e| AppError::validation(format!("line {}: {}", line, e)))?;
+    if import_job(store, &archived).await? {
+        response.imported += 1;
+    } else {
+        response.skipped += 1;
+    }
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/admin/jobs/{id}/artifacts",
+    tag = "admin",
+    params(
+        ("id" = String, Path, description = "Job ID")
+    ),
+    responses(
+        (status = 200, description = "Captured prompts and raw responses, oldest first", body = JobArtifactsResponse),
+        (status = 404, description = "Job not found or artifact capture disabled", body = ApiError),
+    )
+)]
+pub async fn get_artifacts(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<JobArtifactsResponse>, AppError> {
+    let sink = state
+        .artifact_sink
+        .as_ref()
+        .ok_or_else(|| AppError::disabled("artifact capture"))?;
+
+    let job_id: JobId = id
+        .parse()
+        .map_err(|_| AppError::validation("invalid job ID format"))?;
+
+    state
+        .store
+        .get_job(&job_id)
+        .await?
+        .ok_or_else(|| AppError::not_found(job_id.to_string()))?;
+
+    let artifacts = sink.artifacts(&job_id).await?;
+
+    Ok(Json(JobArtifactsResponse {
+        job_id: job_id.to_string(),
+        sink: sink.sink_name().to_string(),
+        artifacts: artifacts.into_iter().map(Into::into).collect(),
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/admin/jobs/{id}/reprocess",
+    tag = "admin",
+    params(
+        ("id" = String, Path, description = "Job ID")
+    ),
+    responses(
+        (status = 200, description = "Sources and answer rebuilt from the captured provider responses", body = ReprocessResponse),
+        (status = 404, description = "Job not found or artifact capture disabled", body = ApiError),
+        (status = 409, description = "Job not completed, or nothing was captured for it", body = ApiError),
+    )
+)]
+pub async fn reprocess(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<ReprocessResponse>, AppError> {
+    if state.artifact_sink.is_none() {
+        return Err(AppError::disabled("artifact capture"));
+    }
+
+    let job_id: JobId = id
+        .parse()
+        .map_err(|_| AppError::validation("invalid job ID format"))?;
+
+    let job = state
+        .store
+        .get_job(&job_id)
+        .await?
+        .ok_or_else(|| AppError::not_found(job_id.to_string()))?;
+    if job.status != JobStatus::Completed {
+        return Err(AppError::conflict(format!(
+            "job {} has not completed; only completed jobs can be reprocessed",
+            job.id
+        )));
+    }
+
+    let reprocessed = state.pipeline().reprocess(&job).await?;
+    if reprocessed.is_empty() {
+        return Err(AppError::conflict(format!(
+            "job {} has no captured responses to reprocess",
+            job.id
+        )));
+    }
+
+    Ok(Json(ReprocessResponse {
+        job_id: job.id.to_string(),
+        search_responses: reprocessed.search_responses,
+        sources: reprocessed.sources.len(),
+        answer_reparsed: reprocessed.answer.is_some(),
+    }))
+}
+
+pub fn router() -> OpenApiRouter<Arc<AppState>> {
+    OpenApiRouter::new()
+        .routes(routes!(export_jobs))
+        .routes(routes!(import_jobs))
+        .routes(routes!(get_artifacts))
+        .routes(routes!(reprocess))
+}
+
+#[cfg(test)]
+mod tests {
+    use gorkd_core::mock::MockStore;
+    use gorkd_core::ResearchJob;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn export_leaves_out_jobs_created_between_pages() {
+        let store = Arc::new(MockStore::new());
+        for i in 0..3 {
+            let job = ResearchJob::new(format!("query {}", i)).unwrap();
+            store.create_job(&job).await.unwrap();
+        }
+
+        let mut pages = Box::pin(export_pages(store.clone(), 2));
+        let mut lines = pages.next().await.unwrap().unwrap();
+        let late = ResearchJob::new("created during the export").unwrap();
+        store.create_job(&late).await.unwrap();
+        while let Some(page) = pages.next().await {
+            lines.push_str(&page.unwrap());
+        }
+
+        let queries: Vec<String> = lines
+            .lines()
+            .map(|line| serde_json::from_str::<ArchivedJob>(line).unwrap().job.query)
+            .collect();
+        assert_eq!(queries, ["query 2", "query 1", "query 0"]);
+    }
+}
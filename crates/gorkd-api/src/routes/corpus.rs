@@ -0,0 +1,522 @@
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use axum::extract::rejection::QueryRejection;
+use axum::extract::{DefaultBodyLimit, Path, Query, State};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::IntoResponse;
+use axum::Json;
+use gorkd_core::{
+    index_document, is_valid_workspace, CorpusDocument, CrawlRequest, CrawledPage, DocumentFormat,
+    DocumentId, DEFAULT_WORKSPACE, MAX_CORPUS_DOCUMENT_BYTES, MAX_WORKSPACE_LENGTH,
+    WORKSPACE_HEADER,
+};
+use gorkd_search::fetch::{detect_format, extract_text};
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+use crate::dto::{
+    CrawlCorpusRequest, CrawlCorpusResponse, CreateDocumentRequest, DocumentContentResponse,
+    DocumentListQuery, DocumentListResponse, DocumentResponse, FeedListResponse, FeedPollResponse,
+    FeedResponse, UploadDocumentQuery,
+};
+use crate::error::{ApiError, AppError};
+use crate::state::AppState;
+
+/// Longest document title accepted, in characters.
+const MAX_TITLE_LENGTH: usize = 500;
+
+/// Largest file accepted by `POST /v1/corpus/documents/upload`. PDFs carry
+/// far more bytes than the text extracted from them, which is held to
+/// [`MAX_CORPUS_DOCUMENT_BYTES`].
+const MAX_UPLOAD_BYTES: usize = 10 * 1024 * 1024;
+
+/// Pages added by `POST /v1/corpus/crawl` when the request does not say.
+const DEFAULT_CRAWL_PAGES: usize = 50;
+
+/// Most pages `POST /v1/corpus/crawl` adds.
+const MAX_CRAWL_PAGES: usize = 200;
+
+/// Links a crawl follows away from its starting pages when the request does
+/// not say.
+const DEFAULT_CRAWL_DEPTH: usize = 2;
+
+/// Most links a crawl follows away from its starting pages.
+const MAX_CRAWL_DEPTH: usize = 5;
+
+/// Documents per page of `GET /v1/corpus/documents` when the request does
+/// not say.
+const DEFAULT_LIST_LIMIT: usize = 20;
+
+/// Most documents per page of `GET /v1/corpus/documents`.
+const MAX_LIST_LIMIT: usize = 100;
+
+#[utoipa::path(
+    post,
+    path = "/v1/corpus/documents",
+    tag = "corpus",
+    params(
+        ("X-Gorkd-Workspace" = Option<String>, Header, description = "Workspace to add the document to; defaults to `default`")
+    ),
+    request_body = CreateDocumentRequest,
+    responses(
+        (status = 201, description = "Document embedded and added to the corpus", body = DocumentResponse),
+        (status = 400, description = "Invalid request", body = ApiError),
+        (status = 502, description = "Embedding the document failed", body = ApiError),
+    )
+)]
+pub async fn create_document(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<CreateDocumentRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let workspace = workspace(&headers)?;
+    let document = checked_document(&req.title, req.content, req.url)?.with_workspace(workspace);
+    let document = match req.published_at {
+        Some(published_at) => document.with_published_at(published_at),
+        None => document,
+    };
+
+    add_document(&state, document).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/corpus/documents/upload",
+    tag = "corpus",
+    params(
+        UploadDocumentQuery,
+        ("X-Gorkd-Workspace" = Option<String>, Header, description = "Workspace to add the document to; defaults to `default`")
+    ),
+    request_body(
+        content = Vec<u8>,
+        description = "The file, up to 10 MB, as `text/plain`, `text/markdown`, `text/html` or `application/pdf`",
+        content_type = "application/pdf"
+    ),
+    responses(
+        (status = 201, description = "Document extracted, embedded and added to the corpus", body = DocumentResponse),
+        (status = 400, description = "Invalid request, unsupported file type or unreadable file", body = ApiError),
+        (status = 413, description = "File larger than 10 MB"),
+        (status = 502, description = "Embedding the document failed", body = ApiError),
+    )
+)]
+pub async fn upload_document(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    query: Result<Query<UploadDocumentQuery>, QueryRejection>,
+    body: Bytes,
+) -> Result<impl IntoResponse, AppError> {
+    let Query(query) = query.map_err(|e| AppError::validation(e.body_text()))?;
+    let workspace = workspace(&headers)?;
+
+    let content_type = headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok());
+    let format = detect_format(content_type, query.url.as_deref().unwrap_or(""), &body)
+        .filter(|format| {
+            matches!(
+                format,
+                DocumentFormat::PlainText
+                    | DocumentFormat::Markdown
+                    | DocumentFormat::Html
+                    | DocumentFormat::Pdf
+            )
+        })
+        .ok_or_else(|| {
+            AppError::validation(
+                "unsupported file type; send text/plain, text/markdown, text/html or application/pdf",
+            )
+        })?;
+    // PDF parsing is CPU-bound, so it stays off the async workers.
+    let content = tokio::task::spawn_blocking(move || extract_text(format, &body))
+        .await
+        .map_err(|e| AppError::internal(format!("text extraction panicked: {}", e)))?
+        .map_err(|e| AppError::validation(format!("could not read the file: {}", e)))?;
+
+    let document = checked_document(&query.title, content, query.url)?
+        .with_workspace(workspace)
+        .with_format(format);
+
+    add_document(&state, document).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/corpus/crawl",
+    tag = "corpus",
+    params(
+        ("X-Gorkd-Workspace" = Option<String>, Header, description = "Workspace to add the pages to; defaults to `default`")
+    ),
+    request_body = CrawlCorpusRequest,
+    responses(
+        (status = 201, description = "Pages crawled, embedded and added to the corpus", body = CrawlCorpusResponse),
+        (status = 400, description = "Invalid request", body = ApiError),
+        (status = 404, description = "Site crawling disabled", body = ApiError),
+        (status = 502, description = "The starting page could not be read, or embedding failed", body = ApiError),
+    )
+)]
+pub async fn crawl_site(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<CrawlCorpusRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let workspace = workspace(&headers)?;
+    let crawler = state
+        .crawler
+        .as_ref()
+        .ok_or_else(|| AppError::disabled("site crawling"))?;
+
+    if !req.url.starts_with("https://") && !req.url.starts_with("http://") {
+        return Err(AppError::validation("url must be an http or https URL"));
+    }
+    let max_pages = req.max_pages.unwrap_or(DEFAULT_CRAWL_PAGES);
+    if max_pages == 0 || max_pages > MAX_CRAWL_PAGES {
+        return Err(AppError::validation(format!(
+            "max_pages must be between 1 and {}",
+            MAX_CRAWL_PAGES
+        )));
+    }
+    let max_depth = req.max_depth.unwrap_or(DEFAULT_CRAWL_DEPTH);
+    if max_depth > MAX_CRAWL_DEPTH {
+        return Err(AppError::validation(format!(
+            "max_depth must be at most {}",
+            MAX_CRAWL_DEPTH
+        )));
+    }
+
+    let pages = crawler
+        .crawl(&CrawlRequest::new(&req.url, max_pages, max_depth))
+        .await?;
+
+    let mut documents = Vec::with_capacity(pages.len());
+    let mut replaced = 0;
+    let mut unchanged = 0;
+    for page in pages {
+        let previous = state
+            .store
+            .find_document_by_url(&workspace, &page.url)
+            .await?;
+        // A page the site says has not changed keeps its document, so it is
+        // not embedded again.
+        let previous = match previous {
+            Some(previous) if page.unchanged => {
+                documents.push(DocumentResponse::from(previous));
+                unchanged += 1;
+                continue;
+            }
+            previous => previous,
+        };
+        let document =
+            store_document(&state, crawled_document(page).with_workspace(&workspace)).await?;
+        // The new document is stored first, so a failed crawl never loses a
+        // page that was already searchable.
+        if let Some(previous) = previous {
+            state
+                .store
+                .delete_document(&workspace, &previous.id)
+                .await?;
+            replaced += 1;
+        }
+        documents.push(DocumentResponse::from(document));
+    }
+
+    tracing::info!(
+        seed = %req.url,
+        workspace = %workspace,
+        crawler = crawler.crawler_name(),
+        documents = documents.len(),
+        replaced,
+        unchanged,
+        "crawled site into corpus"
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CrawlCorpusResponse {
+            documents,
+            replaced,
+            unchanged,
+        }),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/corpus/feeds",
+    tag = "corpus",
+    params(
+        ("X-Gorkd-Workspace" = Option<String>, Header, description = "Workspace whose feeds to list; defaults to `default`")
+    ),
+    responses(
+        (status = 200, description = "The feeds adding entries to the workspace's corpus", body = FeedListResponse),
+        (status = 400, description = "Invalid workspace", body = ApiError),
+    )
+)]
+pub async fn list_feeds(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<FeedListResponse>, AppError> {
+    let workspace = workspace(&headers)?;
+
+    let feeds = state
+        .feed_subscriptions
+        .iter()
+        .filter(|feed| feed.workspace == workspace)
+        .map(|feed| FeedResponse {
+            url: feed.url.clone(),
+            workspace: feed.workspace.clone(),
+        })
+        .collect();
+    Ok(Json(FeedListResponse {
+        feeds,
+        poll_interval_secs: state.feed_poll_interval.as_secs(),
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/corpus/feeds/poll",
+    tag = "corpus",
+    params(
+        ("X-Gorkd-Workspace" = Option<String>, Header, description = "Workspace whose feeds to read; defaults to `default`")
+    ),
+    responses(
+        (status = 200, description = "The workspace's feeds read now instead of at the next poll", body = FeedPollResponse),
+        (status = 400, description = "Invalid workspace", body = ApiError),
+        (status = 404, description = "No feeds configured", body = ApiError),
+    )
+)]
+pub async fn poll_feeds(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<FeedPollResponse>, AppError> {
+    let workspace = workspace(&headers)?;
+    if state.feed_reader.is_none() {
+        return Err(AppError::disabled("corpus feeds"));
+    }
+
+    let report = match state.feed_poller(Some(&workspace)) {
+        Some(poller) => poller.poll_once().await,
+        None => Default::default(),
+    };
+    crate::feeds::log_report(&report);
+    Ok(Json(report.into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/corpus/documents",
+    tag = "corpus",
+    params(
+        DocumentListQuery,
+        ("X-Gorkd-Workspace" = Option<String>, Header, description = "Workspace to list; defaults to `default`")
+    ),
+    responses(
+        (status = 200, description = "The workspace's documents, newest first", body = DocumentListResponse),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+    )
+)]
+pub async fn list_documents(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    query: Result<Query<DocumentListQuery>, QueryRejection>,
+) -> Result<Json<DocumentListResponse>, AppError> {
+    let Query(query) = query.map_err(|e| AppError::validation(e.body_text()))?;
+    let workspace = workspace(&headers)?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIST_LIMIT);
+    if limit == 0 || limit > MAX_LIST_LIMIT {
+        return Err(AppError::validation(format!(
+            "limit must be between 1 and {}",
+            MAX_LIST_LIMIT
+        )));
+    }
+    let offset = query.offset.unwrap_or(0);
+
+    let documents = state
+        .store
+        .list_documents(&workspace, limit, offset)
+        .await?;
+    Ok(Json(DocumentListResponse {
+        documents: documents.into_iter().map(Into::into).collect(),
+        limit,
+        offset,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/corpus/documents/{id}",
+    tag = "corpus",
+    params(
+        ("id" = String, Path, description = "Document ID"),
+        ("X-Gorkd-Workspace" = Option<String>, Header, description = "Workspace of the document; defaults to `default`")
+    ),
+    responses(
+        (status = 200, description = "The document with its extracted text", body = DocumentContentResponse),
+        (status = 400, description = "Invalid document ID", body = ApiError),
+        (status = 404, description = "Document not found in the workspace", body = ApiError),
+    )
+)]
+pub async fn get_document(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Json<DocumentContentResponse>, AppError> {
+    let workspace = workspace(&headers)?;
+    let document_id = parse_document_id(&id)?;
+
+    let document = state
+        .store
+        .get_document(&workspace, &document_id)
+        .await?
+        .ok_or_else(|| AppError::not_found(document_id.to_string()))?;
+    Ok(Json(document.into()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/corpus/documents/{id}",
+    tag = "corpus",
+    params(
+        ("id" = String, Path, description = "Document ID"),
+        ("X-Gorkd-Workspace" = Option<String>, Header, description = "Workspace of the document; defaults to `default`")
+    ),
+    responses(
+        (status = 204, description = "Document removed from the corpus"),
+        (status = 400, description = "Invalid document ID", body = ApiError),
+        (status = 404, description = "Document not found in the workspace", body = ApiError),
+    )
+)]
+pub async fn delete_document(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let workspace = workspace(&headers)?;
+    let document_id = parse_document_id(&id)?;
+
+    if !state
+        .store
+        .delete_document(&workspace, &document_id)
+        .await?
+    {
+        return Err(AppError::not_found(document_id.to_string()));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The workspace named by the request's `X-Gorkd-Workspace` header, or the
+/// default one.
+pub(crate) fn workspace(headers: &HeaderMap) -> Result<String, AppError> {
+    let Some(value) = headers.get(WORKSPACE_HEADER) else {
+        return Ok(DEFAULT_WORKSPACE.to_string());
+    };
+
+    match value.to_str() {
+        Ok(name) if is_valid_workspace(name) => Ok(name.to_string()),
+        _ => Err(AppError::validation(format!(
+            "{} must be 1-{} letters, digits, '-' or '_'",
+            WORKSPACE_HEADER, MAX_WORKSPACE_LENGTH
+        ))),
+    }
+}
+
+fn checked_document(
+    title: &str,
+    content: String,
+    url: Option<String>,
+) -> Result<CorpusDocument, AppError> {
+    let title = title.trim();
+    if title.is_empty() || title.chars().count() > MAX_TITLE_LENGTH {
+        return Err(AppError::validation(format!(
+            "title must be 1-{} characters",
+            MAX_TITLE_LENGTH
+        )));
+    }
+    if content.trim().is_empty() {
+        return Err(AppError::validation("content must not be empty"));
+    }
+    if content.len() > MAX_CORPUS_DOCUMENT_BYTES {
+        return Err(AppError::validation(format!(
+            "content must be at most {} bytes",
+            MAX_CORPUS_DOCUMENT_BYTES
+        )));
+    }
+
+    let mut document = CorpusDocument::new(title, content);
+    if let Some(url) = url.filter(|u| !u.trim().is_empty()) {
+        if !url.starts_with("https://") && !url.starts_with("http://") {
+            return Err(AppError::validation("url must be an http or https URL"));
+        }
+        document = document.with_url(url);
+    }
+    Ok(document)
+}
+
+/// A crawled page as a document, its title and text cut to what the corpus
+/// accepts.
+fn crawled_document(page: CrawledPage) -> CorpusDocument {
+    let title: String = page.title.trim().chars().take(MAX_TITLE_LENGTH).collect();
+    let mut content = page.text;
+    if content.len() > MAX_CORPUS_DOCUMENT_BYTES {
+        let mut end = MAX_CORPUS_DOCUMENT_BYTES;
+        while !content.is_char_boundary(end) {
+            end -= 1;
+        }
+        content.truncate(end);
+    }
+
+    CorpusDocument::new(title, content)
+        .with_url(page.url)
+        .with_format(page.format)
+}
+
+/// Chunks and embeds `document`, then stores it.
+async fn add_document(
+    state: &AppState,
+    document: CorpusDocument,
+) -> Result<impl IntoResponse, AppError> {
+    let document = store_document(state, document).await?;
+    Ok((StatusCode::CREATED, Json(DocumentResponse::from(document))))
+}
+
+/// Chunks and embeds `document`, then stores it and returns it indexed.
+async fn store_document(
+    state: &AppState,
+    document: CorpusDocument,
+) -> Result<CorpusDocument, AppError> {
+    let document = index_document(state.embedder.as_ref(), document).await?;
+    state.store.store_document(&document).await?;
+
+    tracing::info!(
+        document_id = %document.id,
+        workspace = %document.workspace,
+        format = ?document.format,
+        chunks = document.chunks.len(),
+        model = %document.embedding_model,
+        "added document to corpus"
+    );
+
+    Ok(document)
+}
+
+fn parse_document_id(id: &str) -> Result<DocumentId, AppError> {
+    id.parse()
+        .map_err(|_| AppError::validation("invalid document ID format"))
+}
+
+pub fn router() -> OpenApiRouter<Arc<AppState>> {
+    let uploads = OpenApiRouter::new()
+        .routes(routes!(upload_document))
+        .layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES));
+
+    OpenApiRouter::new()
+        .routes(routes!(create_document, list_documents))
+        .routes(routes!(crawl_site))
+        .routes(routes!(list_feeds))
+        .routes(routes!(poll_feeds))
+        .routes(routes!(get_document, delete_document))
+        .merge(uploads)
+}
@@ -1,21 +1,204 @@
 use std::sync::Arc;
 
 use axum::extract::State;
+use axum::http::StatusCode;
 use axum::Json;
+use gorkd_core::ShadowEvaluation;
+use gorkd_llm::SlotUsage;
+use gorkd_store::ContentStats;
 use serde::Serialize;
 use utoipa::ToSchema;
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
 
+use crate::error::{ApiError, AppError};
 use crate::state::AppState;
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct HealthResponse {
+    /// `healthy`, `degraded` when the store answers slowly, or `unhealthy`
+    /// when it cannot be reached.
     #[schema(example = "healthy")]
     pub status: String,
     #[schema(example = "0.1.0")]
     pub version: String,
     pub uptime_seconds: u64,
+    pub queue: QueueHealth,
+    pub store: StoreHealthDetail,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StoreHealthDetail {
+    pub reachable: bool,
+    /// How long the store took to answer the probe.
+    #[schema(example = 2)]
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MetricsResponse {
+    pub store: StoreMetricsDetail,
+    /// Source contents kept in object storage; absent when it is off.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<ContentMetricsDetail>,
+    /// LLM requests in flight and waiting; absent when they are not limited.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub llm: Option<LlmConcurrencyDetail>,
+    /// Candidate providers and models evaluated in shadow of the ones
+    /// serving requests; absent when none are.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub shadow: Vec<ShadowEvaluationMetrics>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StoreMetricsDetail {
+    /// Operations at least this slow are counted as slow and logged.
+    #[schema(example = 250)]
+    pub slow_threshold_ms: u64,
+    /// Operations that ran since startup, by name.
+    pub operations: Vec<StoreOperationMetrics>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StoreOperationMetrics {
+    #[schema(example = "get_job")]
+    pub operation: String,
+    pub calls: u64,
+    pub errors: u64,
+    pub slow: u64,
+    /// Rows read or written by successful calls.
+    pub rows: u64,
+    pub mean_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Source contents written to object storage since startup. Contents are
+/// kept once by hash, so a page fetched again by another job is a dedup hit
+/// instead of a write.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ContentMetricsDetail {
+    #[schema(example = 120)]
+    pub objects_written: u64,
+    #[schema(example = 45)]
+    pub dedup_hits: u64,
+    /// Contents deleted once no job referred to them.
+    #[schema(example = 3)]
+    pub objects_deleted: u64,
+    pub bytes_written: u64,
+    /// Bytes dedup hits did not write again.
+    pub bytes_deduplicated: u64,
+}
+
+impl From<ContentStats> for ContentMetricsDetail {
+    fn from(stats: ContentStats) -> Self {
+        Self {
+            objects_written: stats.objects_written,
+            dedup_hits: stats.dedup_hits,
+            objects_deleted: stats.objects_deleted,
+            bytes_written: stats.bytes_written,
+            bytes_deduplicated: stats.bytes_deduplicated,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LlmConcurrencyDetail {
+    pub overall: LlmSlotUsage,
+    /// Providers that have been called since startup.
+    pub providers: Vec<LlmProviderConcurrency>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LlmProviderConcurrency {
+    #[schema(example = "anthropic")]
+    pub provider: String,
+    #[serde(flatten)]
+    pub usage: LlmSlotUsage,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LlmSlotUsage {
+    /// Requests sent and not yet answered.
+    #[schema(example = 4)]
+    pub in_flight: usize,
+    /// Requests queued behind the limit.
+    #[schema(example = 0)]
+    pub waiting: usize,
+    /// Requests allowed in flight at once; absent when unlimited.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = 8)]
+    pub limit: Option<usize>,
+}
+
+impl From<SlotUsage> for LlmSlotUsage {
+    fn from(usage: SlotUsage) -> Self {
+        Self {
+            in_flight: usage.in_flight,
+            waiting: usage.waiting,
+            limit: usage.limit,
+        }
+    }
+}
+
+/// How a candidate compared with the primary it shadowed, since startup.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ShadowEvaluationMetrics {
+    /// `search` or `llm`.
+    #[schema(example = "search")]
+    pub kind: String,
+    /// The provider or model serving requests.
+    #[schema(example = "fallback")]
+    pub primary: String,
+    #[schema(example = "exa")]
+    pub candidate: String,
+    pub calls: u64,
+    pub primary_errors: u64,
+    pub candidate_errors: u64,
+    /// Calls the candidate answered sooner than the primary.
+    pub candidate_faster: u64,
+    pub primary_mean_ms: f64,
+    pub candidate_mean_ms: f64,
+    pub candidate_max_ms: f64,
+    /// Mean overlap with the primary's results, from 0 to 1: of returned
+    /// URLs for search, of cited sources for answers. Absent until both
+    /// have succeeded on a comparable call.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = 0.62)]
+    pub mean_overlap: Option<f64>,
+}
+
+impl From<ShadowEvaluation> for ShadowEvaluationMetrics {
+    fn from(evaluation: ShadowEvaluation) -> Self {
+        let stats = evaluation.stats;
+        Self {
+            kind: evaluation.kind.as_str().to_string(),
+            primary: evaluation.primary,
+            candidate: evaluation.candidate,
+            calls: stats.calls,
+            primary_errors: stats.primary_errors,
+            candidate_errors: stats.candidate_errors,
+            candidate_faster: stats.candidate_faster,
+            primary_mean_ms: stats.primary_mean().as_secs_f64() * 1000.0,
+            candidate_mean_ms: stats.candidate_mean().as_secs_f64() * 1000.0,
+            candidate_max_ms: stats.candidate_max.as_secs_f64() * 1000.0,
+            mean_overlap: stats.mean_overlap(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QueueHealth {
+    /// Research jobs currently in flight.
+    #[schema(example = 3)]
+    pub depth: usize,
+    /// Jobs allowed in flight before new ones get `503`; absent when
+    /// unlimited.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = 20)]
+    pub max_depth: Option<usize>,
+    pub saturated: bool,
 }
 
 #[utoipa::path(
@@ -23,17 +206,109 @@ pub struct HealthResponse {
     path = "/health",
     tag = "health",
     responses(
-        (status = 200, description = "Service is healthy", body = HealthResponse),
+        (status = 200, description = "Service is healthy or degraded", body = HealthResponse),
+        (status = 503, description = "The store cannot be reached", body = HealthResponse),
     )
 )]
-pub async fn health_check(State(state): State<Arc<AppState>>) -> Json<HealthResponse> {
-    Json(HealthResponse {
-        status: "healthy".to_string(),
+pub async fn health_check(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<HealthResponse>) {
+    let store = state.store.health().await;
+    let slow = state
+        .store_metrics
+        .as_ref()
+        .is_some_and(|metrics| store.latency >= metrics.slow_threshold());
+    let (code, status) = match (store.reachable, slow) {
+        (false, _) => (StatusCode::SERVICE_UNAVAILABLE, "unhealthy"),
+        (true, true) => (StatusCode::OK, "degraded"),
+        (true, false) => (StatusCode::OK, "healthy"),
+    };
+
+    let body = HealthResponse {
+        status: status.to_string(),
         version: env!("CARGO_PKG_VERSION").to_string(),
         uptime_seconds: state.uptime_seconds(),
-    })
+        queue: QueueHealth {
+            depth: state.job_queue.depth(),
+            max_depth: state.job_queue.max_depth(),
+            saturated: state.job_queue.is_saturated(),
+        },
+        store: StoreHealthDetail {
+            reachable: store.reachable,
+            latency_ms: store.latency.as_millis() as u64,
+            error: store.error,
+        },
+    };
+    (code, Json(body))
+}
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "health",
+    responses(
+        (status = 200, description = "Store operation timings since startup, source contents kept in object storage, LLM requests in flight and shadow evaluations", body = MetricsResponse),
+        (status = 404, description = "Store metrics disabled", body = ApiError),
+    )
+)]
+pub async fn get_metrics(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<MetricsResponse>, AppError> {
+    let metrics = state
+        .store_metrics
+        .as_ref()
+        .ok_or_else(|| AppError::disabled("store metrics"))?;
+
+    let operations = metrics
+        .snapshot()
+        .into_iter()
+        .map(|(operation, stats)| StoreOperationMetrics {
+            operation: operation.to_string(),
+            calls: stats.calls,
+            errors: stats.errors,
+            slow: stats.slow,
+            rows: stats.rows,
+            mean_ms: stats.mean().as_secs_f64() * 1000.0,
+            max_ms: stats.max.as_secs_f64() * 1000.0,
+        })
+        .collect();
+
+    let llm = state.llm_registry.limiter().map(|limiter| {
+        let snapshot = limiter.snapshot();
+        LlmConcurrencyDetail {
+            overall: snapshot.overall.into(),
+            providers: snapshot
+                .providers
+                .into_iter()
+                .map(|(provider, usage)| LlmProviderConcurrency {
+                    provider,
+                    usage: usage.into(),
+                })
+                .collect(),
+        }
+    });
+
+    Ok(Json(MetricsResponse {
+        store: StoreMetricsDetail {
+            slow_threshold_ms: metrics.slow_threshold().as_millis() as u64,
+            operations,
+        },
+        content: state
+            .content_metrics
+            .as_ref()
+            .map(|metrics| metrics.snapshot().into()),
+        llm,
+        shadow: state
+            .shadow_metrics
+            .snapshot()
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+    }))
 }
 
 pub fn router() -> OpenApiRouter<Arc<AppState>> {
-    OpenApiRouter::new().routes(routes!(health_check))
+    OpenApiRouter::new()
+        .routes(routes!(health_check))
+        .routes(routes!(get_metrics))
 }
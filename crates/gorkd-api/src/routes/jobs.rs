@@ -1,19 +1,131 @@
-use std::convert::Infallible;
 use std::sync::Arc;
-use std::time::Duration;
 
-use axum::extract::{Path, State};
+use axum::extract::rejection::QueryRejection;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query, State};
+use axum::http::{header, StatusCode};
 use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::response::IntoResponse;
 use axum::Json;
-use futures::stream::{self, Stream};
-use gorkd_core::JobId;
+use futures::StreamExt;
+use gorkd_core::export;
+use gorkd_core::retry::create_retry;
+use gorkd_core::{
+    AnswerDiff, Feedback, JobId, JobStatus, JobSummaryFilter, ResearchAnswer, ResearchJob, Source,
+    MAX_FEEDBACK_COMMENT_LENGTH,
+};
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
 
-use crate::dto::{JobResponse, JobSourceResponse, SourceDetail};
+use crate::dto::{
+    AnswerDiffResponse, CreateResearchResponse, DomainGroup, FeedbackListResponse, FeedbackRequest,
+    FeedbackResponse, JobEventsResponse, JobListQuery, JobListResponse, JobResponse,
+    JobSourceResponse, JobSummaryDetail, JobSummaryListResponse, JobSummaryQuery,
+    ModelComparisonResponse, SourceDetail, SourceGrouping, SourceSort, SourcesQuery,
+    StatusCountDetail,
+};
 use crate::error::{ApiError, AppError};
+use crate::routes::research::{admit, launch};
+use crate::routes::trace_header;
 use crate::state::AppState;
+use crate::stream::{self, TracedStreamEvent};
+
+const BIBTEX_CONTENT_TYPE: &str = "application/x-bibtex; charset=utf-8";
+const CSL_JSON_CONTENT_TYPE: &str = "application/vnd.citationstyles.csl+json";
+
+/// Jobs per page of `GET /v1/jobs` when the request does not say.
+const DEFAULT_LIST_LIMIT: usize = 20;
+
+/// Most jobs per page of `GET /v1/jobs`.
+const MAX_LIST_LIMIT: usize = 100;
+
+#[utoipa::path(
+    get,
+    path = "/v1/jobs",
+    tag = "jobs",
+    params(JobListQuery),
+    responses(
+        (status = 200, description = "Jobs, newest first", body = JobListResponse),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+    )
+)]
+pub async fn list_jobs(
+    State(state): State<Arc<AppState>>,
+    query: Result<Query<JobListQuery>, QueryRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let Query(query) = query.map_err(|e| AppError::validation(e.body_text()))?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIST_LIMIT);
+    if limit == 0 || limit > MAX_LIST_LIMIT {
+        return Err(AppError::validation(format!(
+            "limit must be between 1 and {}",
+            MAX_LIST_LIMIT
+        )));
+    }
+    let offset = query.offset.unwrap_or(0);
+
+    let jobs = match query.tag {
+        Some(ref tag) if tag.is_empty() => {
+            return Err(AppError::validation("tag must not be empty"));
+        }
+        Some(ref tag) => state.store.list_jobs_by_tag(tag, limit, offset).await?,
+        None => state.store.list_jobs(limit, offset).await?,
+    };
+
+    Ok(Json(JobListResponse {
+        jobs: jobs.into_iter().map(JobResponse::from).collect(),
+        limit,
+        offset,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/jobs/summaries",
+    tag = "jobs",
+    params(JobSummaryQuery),
+    responses(
+        (status = 200, description = "Job summaries, newest first, with counts by status",
+            body = JobSummaryListResponse),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+    )
+)]
+pub async fn list_job_summaries(
+    State(state): State<Arc<AppState>>,
+    query: Result<Query<JobSummaryQuery>, QueryRejection>,
+) -> Result<impl IntoResponse, AppError> {
+    let Query(query) = query.map_err(|e| AppError::validation(e.body_text()))?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIST_LIMIT);
+    if limit == 0 || limit > MAX_LIST_LIMIT {
+        return Err(AppError::validation(format!(
+            "limit must be between 1 and {}",
+            MAX_LIST_LIMIT
+        )));
+    }
+    let offset = query.offset.unwrap_or(0);
+    if query.tag.as_deref().is_some_and(str::is_empty) {
+        return Err(AppError::validation("tag must not be empty"));
+    }
+
+    let filter = JobSummaryFilter {
+        status: query.status.map(Into::into),
+        tag: query.tag,
+        text: query.q.filter(|q| !q.trim().is_empty()),
+    };
+    let jobs = state
+        .store
+        .list_job_summaries(&filter, limit, offset)
+        .await?;
+    let counts = state.store.count_job_summaries(&filter).await?;
+
+    Ok(Json(JobSummaryListResponse {
+        jobs: jobs.into_iter().map(JobSummaryDetail::from).collect(),
+        counts: counts.into_iter().map(StatusCountDetail::from).collect(),
+        limit,
+        offset,
+    }))
+}
 
 #[utoipa::path(
     get,
@@ -23,17 +135,61 @@ use crate::state::AppState;
         ("id" = String, Path, description = "Job ID")
     ),
     responses(
-        (status = 200, description = "Job found", body = JobResponse),
+        (status = 200, description = "Job found", body = JobResponse,
+            headers(("X-Gorkd-Trace-Id" = String, description = "Trace ID of the job"))),
         (status = 404, description = "Job not found", body = ApiError),
     )
 )]
 pub async fn get_job(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
-) -> Result<Json<JobResponse>, AppError> {
+) -> Result<impl IntoResponse, AppError> {
+    let job = find_job(&state, &id).await?;
+    let answer = state.store.get_answer(&job.id).await?;
+    let events = state.store.get_events(&job.id).await?;
+
+    Ok((
+        trace_header(&job),
+        Json(
+            JobResponse::from(job)
+                .with_progress(&events)
+                .with_answer(answer),
+        ),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/jobs/{id}/sources",
+    tag = "jobs",
+    params(
+        ("id" = String, Path, description = "Job ID"),
+        SourcesQuery,
+    ),
+    responses(
+        (status = 200, description = "Sources found", body = JobSourceResponse,
+            headers(("X-Gorkd-Trace-Id" = String, description = "Trace ID of the job"))),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+        (status = 404, description = "Job not found", body = ApiError),
+    )
+)]
+pub async fn get_sources(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    query: Result<Query<SourcesQuery>, QueryRejection>,
+) -> Result<impl IntoResponse, AppError> {
     let job_id: JobId = id
         .parse()
         .map_err(|_| AppError::validation("invalid job ID format"))?;
+    let Query(query) = query.map_err(|e| AppError::validation(e.body_text()))?;
+
+    if let Some(min_score) = query.min_score {
+        if !(0.0..=1.0).contains(&min_score) {
+            return Err(AppError::validation(
+                "min_score must be between 0.0 and 1.0",
+            ));
+        }
+    }
 
     let job = state
         .store
@@ -41,41 +197,253 @@ pub async fn get_job(
         .await?
         .ok_or_else(|| AppError::not_found(job_id.to_string()))?;
 
-    Ok(Json(job.into()))
+    let mut sources = state.store.get_sources(&job_id).await?;
+    filter_sources(&mut sources, &query);
+    sort_sources(&mut sources, query.sort.unwrap_or_default());
+
+    let domains = query.group_by.map(|grouping| match grouping {
+        SourceGrouping::Domain => group_by_domain(&sources),
+    });
+    let answer = state.store.get_answer(&job_id).await?;
+    let include_content = query.include_content.unwrap_or(false);
+    let source_details: Vec<SourceDetail> = sources
+        .into_iter()
+        .map(|source| SourceDetail::new(source, answer.as_ref(), include_content))
+        .collect();
+    let search = state.store.get_search_metadata(&job_id).await?;
+
+    Ok((
+        trace_header(&job),
+        Json(JobSourceResponse {
+            sources: source_details,
+            search: search.map(Into::into),
+            domains,
+        }),
+    ))
+}
+
+fn filter_sources(sources: &mut Vec<Source>, query: &SourcesQuery) {
+    if let Some(min_score) = query.min_score {
+        sources.retain(|s| s.relevance_score >= min_score);
+    }
+
+    if let Some(domain) = query.domain.as_deref() {
+        let domain = domain.trim().to_ascii_lowercase();
+        let suffix = format!(".{}", domain);
+        sources.retain(|s| {
+            let source_domain = s.metadata.domain.to_ascii_lowercase();
+            source_domain == domain || source_domain.ends_with(&suffix)
+        });
+    }
+}
+
+fn sort_sources(sources: &mut [Source], sort: SourceSort) {
+    let by_score = |a: &Source, b: &Source| b.relevance_score.total_cmp(&a.relevance_score);
+
+    match sort {
+        SourceSort::Score => sources.sort_by(by_score),
+        SourceSort::Date => sources.sort_by(|a, b| {
+            b.metadata
+                .published_at
+                .cmp(&a.metadata.published_at)
+                .then_with(|| by_score(a, b))
+        }),
+        SourceSort::Domain => sources.sort_by(|a, b| {
+            a.metadata
+                .domain
+                .cmp(&b.metadata.domain)
+                .then_with(|| by_score(a, b))
+        }),
+    }
+}
+
+/// Groups sources by domain, strongest domains first. `sources` is expected
+/// to be sorted already so each group's IDs keep the response order.
+fn group_by_domain(sources: &[Source]) -> Vec<DomainGroup> {
+    let mut groups: Vec<DomainGroup> = Vec::new();
+
+    for source in sources {
+        let domain = &source.metadata.domain;
+        match groups.iter_mut().find(|g| &g.domain == domain) {
+            Some(group) => {
+                group.count += 1;
+                group.best_score = group.best_score.max(source.relevance_score);
+                group.source_ids.push(source.id.to_string());
+            }
+            None => groups.push(DomainGroup {
+                domain: domain.clone(),
+                count: 1,
+                best_score: source.relevance_score,
+                source_ids: vec![source.id.to_string()],
+            }),
+        }
+    }
+
+    groups.sort_by(|a, b| {
+        b.best_score
+            .total_cmp(&a.best_score)
+            .then_with(|| b.count.cmp(&a.count))
+            .then_with(|| a.domain.cmp(&b.domain))
+    });
+    groups
 }
 
 #[utoipa::path(
     get,
-    path = "/v1/jobs/{id}/sources",
+    path = "/v1/jobs/{id}/sources.bib",
     tag = "jobs",
     params(
         ("id" = String, Path, description = "Job ID")
     ),
     responses(
-        (status = 200, description = "Sources found", body = JobSourceResponse),
+        (status = 200, description = "Sources as BibTeX", body = String, content_type = "application/x-bibtex",
+            headers(("X-Gorkd-Trace-Id" = String, description = "Trace ID of the job"))),
         (status = 404, description = "Job not found", body = ApiError),
     )
 )]
-pub async fn get_sources(
+pub async fn get_sources_bibtex(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
-) -> Result<Json<JobSourceResponse>, AppError> {
-    let job_id: JobId = id
-        .parse()
-        .map_err(|_| AppError::validation("invalid job ID format"))?;
+) -> Result<impl IntoResponse, AppError> {
+    let (job, sources) = load_sources(&state, &id).await?;
+    let body = export::to_bibtex(&sources, job.created_at);
 
-    state
+    Ok((
+        trace_header(&job),
+        [(header::CONTENT_TYPE, BIBTEX_CONTENT_TYPE)],
+        body,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/jobs/{id}/sources.csl.json",
+    tag = "jobs",
+    params(
+        ("id" = String, Path, description = "Job ID")
+    ),
+    responses(
+        (status = 200, description = "Sources as CSL-JSON", body = Object, content_type = "application/vnd.citationstyles.csl+json",
+            headers(("X-Gorkd-Trace-Id" = String, description = "Trace ID of the job"))),
+        (status = 404, description = "Job not found", body = ApiError),
+    )
+)]
+pub async fn get_sources_csl(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let (job, sources) = load_sources(&state, &id).await?;
+    let body = export::to_csl_json(&sources, job.created_at);
+
+    Ok((
+        trace_header(&job),
+        [(header::CONTENT_TYPE, CSL_JSON_CONTENT_TYPE)],
+        Json(body),
+    ))
+}
+
+async fn load_sources(state: &AppState, id: &str) -> Result<(ResearchJob, Vec<Source>), AppError> {
+    let job = find_job(state, id).await?;
+    let sources = state.store.get_sources(&job.id).await?;
+
+    Ok((job, sources))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/jobs/{id}/events",
+    tag = "jobs",
+    params(
+        ("id" = String, Path, description = "Job ID")
+    ),
+    responses(
+        (status = 200, description = "Job event history, oldest first", body = JobEventsResponse,
+            headers(("X-Gorkd-Trace-Id" = String, description = "Trace ID of the job"))),
+        (status = 404, description = "Job not found", body = ApiError),
+    )
+)]
+pub async fn get_events(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let job = find_job(&state, &id).await?;
+    let events = state.store.get_events(&job.id).await?;
+
+    Ok((trace_header(&job), Json(JobEventsResponse::from(events))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/jobs/{id}/comparison",
+    tag = "jobs",
+    params(
+        ("id" = String, Path, description = "Job ID")
+    ),
+    responses(
+        (status = 200, description = "Each model's answer and their agreement", body = ModelComparisonResponse,
+            headers(("X-Gorkd-Trace-Id" = String, description = "Trace ID of the job"))),
+        (status = 404, description = "Job not found or not created with `models`", body = ApiError),
+    )
+)]
+pub async fn get_comparison(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let job = find_job(&state, &id).await?;
+    if job.models.is_empty() {
+        return Err(AppError::not_found(format!(
+            "{} has no model comparison",
+            job.id
+        )));
+    }
+    let comparison = state
         .store
-        .get_job(&job_id)
+        .get_comparison(&job.id)
         .await?
-        .ok_or_else(|| AppError::not_found(job_id.to_string()))?;
+        .unwrap_or_default();
 
-    let sources = state.store.get_sources(&job_id).await?;
-    let source_details: Vec<SourceDetail> = sources.into_iter().map(Into::into).collect();
+    Ok((
+        trace_header(&job),
+        Json(ModelComparisonResponse::new(&job, comparison)),
+    ))
+}
 
-    Ok(Json(JobSourceResponse {
-        sources: source_details,
-    }))
+#[utoipa::path(
+    get,
+    path = "/v1/jobs/{id}/diff/{other_id}",
+    tag = "jobs",
+    params(
+        ("id" = String, Path, description = "Job whose answer is the earlier one"),
+        ("other_id" = String, Path, description = "Job whose answer is the later one"),
+    ),
+    responses(
+        (status = 200, description = "Changes from the first job's answer to the second's", body = AnswerDiffResponse),
+        (status = 404, description = "Job not found or without an answer", body = ApiError),
+    )
+)]
+pub async fn get_diff(
+    State(state): State<Arc<AppState>>,
+    Path((id, other_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    let (from, from_answer, from_sources) = load_answer(&state, &id).await?;
+    let (to, to_answer, to_sources) = load_answer(&state, &other_id).await?;
+
+    let diff = AnswerDiff::new(&from_answer, &from_sources, &to_answer, &to_sources);
+    Ok(Json(AnswerDiffResponse::new(&from.id, &to.id, diff)))
+}
+
+async fn load_answer(
+    state: &AppState,
+    id: &str,
+) -> Result<(ResearchJob, ResearchAnswer, Vec<Source>), AppError> {
+    let (job, sources) = load_sources(state, id).await?;
+    let answer = state
+        .store
+        .get_answer(&job.id)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("{} has no answer", job.id)))?;
+
+    Ok((job, answer, sources))
 }
 
 #[utoipa::path(
@@ -86,7 +454,8 @@ pub async fn get_sources(
         ("id" = String, Path, description = "Job ID")
     ),
     responses(
-        (status = 200, description = "SSE stream", content_type = "text/event-stream"),
+        (status = 200, description = "SSE stream of job updates; each `data` payload is a TracedStreamEvent", body = TracedStreamEvent, content_type = "text/event-stream",
+            headers(("X-Gorkd-Trace-Id" = String, description = "Trace ID of the job"))),
         (status = 404, description = "Job not found", body = ApiError),
     )
 )]
@@ -94,6 +463,202 @@ pub async fn get_stream(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
 ) -> Result<impl IntoResponse, AppError> {
+    let job = find_job(&state, &id).await?;
+    let headers = trace_header(&job);
+
+    let events = stream::job_stream(state, job.id).map(move |event| {
+        Event::default()
+            .event(event.name())
+            .json_data(TracedStreamEvent::new(&job.trace_id, event))
+    });
+
+    Ok((headers, Sse::new(events).keep_alive(KeepAlive::default())))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/jobs/{id}/ws",
+    tag = "jobs",
+    params(
+        ("id" = String, Path, description = "Job ID")
+    ),
+    responses(
+        (status = 101, description = "WebSocket upgrade; each text message is a TracedStreamEvent", body = TracedStreamEvent),
+        (status = 404, description = "Job not found", body = ApiError),
+    )
+)]
+pub async fn get_ws(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    ws: WebSocketUpgrade,
+) -> Result<impl IntoResponse, AppError> {
+    let job = find_job(&state, &id).await?;
+
+    Ok((
+        trace_header(&job),
+        ws.on_upgrade(move |socket| forward_events(socket, state, job)),
+    ))
+}
+
+async fn forward_events(mut socket: WebSocket, state: Arc<AppState>, job: ResearchJob) {
+    let mut events = std::pin::pin!(stream::job_stream(state, job.id));
+
+    while let Some(event) = events.next().await {
+        let Ok(text) = serde_json::to_string(&TracedStreamEvent::new(&job.trace_id, event)) else {
+            continue;
+        };
+        if socket.send(Message::Text(text.into())).await.is_err() {
+            return;
+        }
+    }
+
+    let _ = socket.send(Message::Close(None)).await;
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/jobs/{id}/retry",
+    tag = "jobs",
+    params(
+        ("id" = String, Path, description = "ID of the failed job")
+    ),
+    responses(
+        (status = 202, description = "Retry created", body = CreateResearchResponse,
+            headers(("X-Gorkd-Trace-Id" = String, description = "Trace ID of the retry"))),
+        (status = 404, description = "Job not found", body = ApiError),
+        (status = 409, description = "Job has not failed", body = ApiError),
+        (status = 503, description = "Job queue full; retry after the `Retry-After` delay", body = ApiError,
+            headers(("Retry-After" = u64, description = "Seconds to wait before retrying"))),
+    )
+)]
+pub async fn retry_job(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let job = find_job(&state, &id).await?;
+    if job.status != JobStatus::Failed {
+        return Err(AppError::conflict(format!(
+            "job {} has not failed; only failed jobs can be retried",
+            job.id
+        )));
+    }
+
+    let permit = admit(&state)?;
+    let retry = create_retry(state.store.as_ref(), &job).await?;
+    tracing::info!(
+        job_id = %retry.id,
+        trace_id = %retry.trace_id,
+        retried_from = %job.id,
+        attempt = retry.attempt,
+        "created retry of failed job"
+    );
+
+    let headers = trace_header(&retry);
+    let response = launch(&state, retry, permit).await;
+
+    Ok((StatusCode::ACCEPTED, headers, Json(response)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/jobs/{id}/feedback",
+    tag = "jobs",
+    params(
+        ("id" = String, Path, description = "Job ID")
+    ),
+    request_body = FeedbackRequest,
+    responses(
+        (status = 201, description = "Feedback recorded", body = FeedbackResponse),
+        (status = 400, description = "Empty feedback, a comment too long, or a source not of this job", body = ApiError),
+        (status = 404, description = "Job not found", body = ApiError),
+        (status = 409, description = "Rating a job that has no answer", body = ApiError),
+    )
+)]
+pub async fn create_feedback(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(req): Json<FeedbackRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let job = find_job(&state, &id).await?;
+    let comment = req.comment.filter(|c| !c.trim().is_empty());
+    if req.rating.is_none() && req.flagged_sources.is_empty() && comment.is_none() {
+        return Err(AppError::validation(
+            "feedback must rate the answer, flag a source or comment",
+        ));
+    }
+    if comment
+        .as_ref()
+        .is_some_and(|c| c.chars().count() > MAX_FEEDBACK_COMMENT_LENGTH)
+    {
+        return Err(AppError::validation(format!(
+            "comment must be at most {} characters",
+            MAX_FEEDBACK_COMMENT_LENGTH
+        )));
+    }
+
+    let sources = state.store.get_sources(&job.id).await?;
+    let mut feedback = Feedback::new(job.id.clone());
+    if let Some(rating) = req.rating {
+        let answer =
+            state.store.get_answer(&job.id).await?.ok_or_else(|| {
+                AppError::conflict(format!("job {} has no answer to rate", job.id))
+            })?;
+        feedback = feedback.with_rating(rating.into(), &answer, &sources);
+    }
+    for flag in req.flagged_sources {
+        let source = sources
+            .iter()
+            .find(|s| s.id.as_str() == flag.source_id)
+            .ok_or_else(|| {
+                AppError::validation(format!(
+                    "{} is not a source of job {}",
+                    flag.source_id, job.id
+                ))
+            })?;
+        feedback = feedback.with_flag(source, flag.reason.filter(|r| !r.trim().is_empty()));
+    }
+    if let Some(comment) = comment {
+        feedback = feedback.with_comment(comment);
+    }
+    state.store.store_feedback(&feedback).await?;
+
+    tracing::info!(
+        job_id = %job.id,
+        feedback_id = %feedback.id,
+        rating = ?feedback.rating,
+        flagged = feedback.flagged_sources.len(),
+        "recorded feedback"
+    );
+
+    Ok((StatusCode::CREATED, Json(FeedbackResponse::from(feedback))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/jobs/{id}/feedback",
+    tag = "jobs",
+    params(
+        ("id" = String, Path, description = "Job ID")
+    ),
+    responses(
+        (status = 200, description = "The job's feedback, oldest first", body = FeedbackListResponse),
+        (status = 404, description = "Job not found", body = ApiError),
+    )
+)]
+pub async fn get_feedback(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<FeedbackListResponse>, AppError> {
+    let job = find_job(&state, &id).await?;
+    let feedback = state.store.get_feedback(&job.id).await?;
+
+    Ok(Json(FeedbackListResponse {
+        job_id: job.id.to_string(),
+        feedback: feedback.into_iter().map(Into::into).collect(),
+    }))
+}
+
+async fn find_job(state: &AppState, id: &str) -> Result<ResearchJob, AppError> {
     let job_id: JobId = id
         .parse()
         .map_err(|_| AppError::validation("invalid job ID format"))?;
@@ -102,48 +667,22 @@ pub async fn get_stream(
         .store
         .get_job(&job_id)
         .await?
-        .ok_or_else(|| AppError::not_found(job_id.to_string()))?;
-
-    let stream = stubbed_event_stream(job_id.to_string());
-
-    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
-}
-
-fn stubbed_event_stream(
-    job_id: String,
-) -> impl Stream<Item = Result<Event, Infallible>> + Send + 'static {
-    stream::unfold(0u8, move |state| {
-        let job_id = job_id.clone();
-        async move {
-            match state {
-                0 => {
-                    let event = Event::default()
-                        .event("status")
-                        .data(r#"{"stage":"pending","message":"Job queued..."}"#);
-                    Some((Ok(event), 1))
-                }
-                1 => {
-                    tokio::time::sleep(Duration::from_millis(500)).await;
-                    let event = Event::default()
-                        .event("status")
-                        .data(r#"{"stage":"searching","message":"Searching sources..."}"#);
-                    Some((Ok(event), 2))
-                }
-                2 => {
-                    tokio::time::sleep(Duration::from_millis(500)).await;
-                    let data = format!(r#"{{"job_id":"{}","duration_ms":1000}}"#, job_id);
-                    let event = Event::default().event("complete").data(data);
-                    Some((Ok(event), 3))
-                }
-                _ => None,
-            }
-        }
-    })
+        .ok_or_else(|| AppError::not_found(job_id.to_string()))
 }
 
 pub fn router() -> OpenApiRouter<Arc<AppState>> {
     OpenApiRouter::new()
+        .routes(routes!(list_jobs))
+        .routes(routes!(list_job_summaries))
         .routes(routes!(get_job))
         .routes(routes!(get_sources))
+        .routes(routes!(get_sources_bibtex))
+        .routes(routes!(get_sources_csl))
+        .routes(routes!(get_events))
+        .routes(routes!(get_comparison))
+        .routes(routes!(get_diff))
         .routes(routes!(get_stream))
+        .routes(routes!(get_ws))
+        .routes(routes!(retry_job))
+        .routes(routes!(create_feedback, get_feedback))
 }
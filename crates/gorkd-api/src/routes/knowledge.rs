@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use axum::extract::rejection::QueryRejection;
+use axum::extract::{Query, State};
+use axum::Json;
+use gorkd_core::{FactQuery, JobId};
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+use crate::dto::{KnowledgeQuery, KnowledgeResponse};
+use crate::error::{ApiError, AppError};
+use crate::state::AppState;
+
+/// Facts per response of `GET /v1/knowledge` when the request does not say.
+const DEFAULT_FACT_LIMIT: usize = 50;
+
+/// Most facts per response of `GET /v1/knowledge`.
+const MAX_FACT_LIMIT: usize = 200;
+
+#[utoipa::path(
+    get,
+    path = "/v1/knowledge",
+    tag = "knowledge",
+    params(KnowledgeQuery),
+    responses(
+        (status = 200, description = "Matching facts, those with the most sources first", body = KnowledgeResponse),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+    )
+)]
+pub async fn find_facts(
+    State(state): State<Arc<AppState>>,
+    query: Result<Query<KnowledgeQuery>, QueryRejection>,
+) -> Result<Json<KnowledgeResponse>, AppError> {
+    let Query(query) = query.map_err(|e| AppError::validation(e.body_text()))?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_FACT_LIMIT);
+    if limit == 0 || limit > MAX_FACT_LIMIT {
+        return Err(AppError::validation(format!(
+            "limit must be between 1 and {}",
+            MAX_FACT_LIMIT
+        )));
+    }
+
+    let mut fact_query = FactQuery::new(limit);
+    fact_query.subject = checked_term("subject", query.subject)?;
+    fact_query.relation = checked_term("relation", query.relation)?;
+    fact_query.object = checked_term("object", query.object)?;
+    if let Some(ref job_id) = query.job_id {
+        let job_id: JobId = job_id
+            .parse()
+            .map_err(|_| AppError::validation("invalid job ID format"))?;
+        fact_query = fact_query.with_job(job_id);
+    }
+
+    let facts = state.store.find_facts(&fact_query).await?;
+    Ok(Json(KnowledgeResponse {
+        facts: facts.into_iter().map(Into::into).collect(),
+    }))
+}
+
+fn checked_term(name: &str, term: Option<String>) -> Result<Option<String>, AppError> {
+    match term {
+        Some(ref t) if t.trim().is_empty() => {
+            Err(AppError::validation(format!("{} must not be empty", name)))
+        }
+        term => Ok(term),
+    }
+}
+
+pub fn router() -> OpenApiRouter<Arc<AppState>> {
+    OpenApiRouter::new().routes(routes!(find_facts))
+}
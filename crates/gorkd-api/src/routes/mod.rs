@@ -1,3 +1,16 @@
+pub mod admin;
+pub mod corpus;
 pub mod health;
 pub mod jobs;
+pub mod knowledge;
+pub mod projects;
 pub mod research;
+pub mod stats;
+pub mod tools;
+
+use gorkd_core::{ResearchJob, TRACE_ID_HEADER};
+
+/// Tags a job-scoped response with the job's trace ID.
+pub(crate) fn trace_header(job: &ResearchJob) -> [(&'static str, String); 1] {
+    [(TRACE_ID_HEADER, job.trace_id.to_string())]
+}
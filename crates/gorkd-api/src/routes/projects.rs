@@ -0,0 +1,213 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use gorkd_core::{
+    JobId, Project, ProjectId, ProjectReport, MAX_PROJECT_JOBS, MAX_PROJECT_NAME_LENGTH,
+};
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+use crate::dto::{
+    AttachJobRequest, CreateProjectRequest, JobResponse, ProjectJobsResponse,
+    ProjectReportResponse, ProjectResponse,
+};
+use crate::error::{ApiError, AppError};
+use crate::state::AppState;
+
+#[utoipa::path(
+    post,
+    path = "/v1/projects",
+    tag = "projects",
+    request_body = CreateProjectRequest,
+    responses(
+        (status = 201, description = "Project created", body = ProjectResponse),
+        (status = 400, description = "Invalid request", body = ApiError),
+    )
+)]
+pub async fn create_project(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<CreateProjectRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let name = req.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_PROJECT_NAME_LENGTH {
+        return Err(AppError::validation(format!(
+            "name must be 1-{} characters",
+            MAX_PROJECT_NAME_LENGTH
+        )));
+    }
+
+    let mut project = Project::new(name);
+    if let Some(description) = req.description.filter(|d| !d.trim().is_empty()) {
+        project = project.with_description(description);
+    }
+    state.store.create_project(&project).await?;
+
+    tracing::info!(project_id = %project.id, name = %project.name, "created project");
+
+    Ok((StatusCode::CREATED, Json(ProjectResponse::from(project))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/projects/{id}",
+    tag = "projects",
+    params(
+        ("id" = String, Path, description = "Project ID")
+    ),
+    responses(
+        (status = 200, description = "Project found", body = ProjectResponse),
+        (status = 404, description = "Project not found", body = ApiError),
+    )
+)]
+pub async fn get_project(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<ProjectResponse>, AppError> {
+    let project = find_project(&state, &id).await?;
+    Ok(Json(project.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/projects/{id}/jobs",
+    tag = "projects",
+    params(
+        ("id" = String, Path, description = "Project ID")
+    ),
+    request_body = AttachJobRequest,
+    responses(
+        (status = 200, description = "Job attached, or already a member", body = ProjectResponse),
+        (status = 400, description = "Invalid job ID", body = ApiError),
+        (status = 404, description = "Project or job not found", body = ApiError),
+        (status = 409, description = "Project already holds the most jobs allowed", body = ApiError),
+    )
+)]
+pub async fn attach_job(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(req): Json<AttachJobRequest>,
+) -> Result<Json<ProjectResponse>, AppError> {
+    let project_id = parse_project_id(&id)?;
+    let job_id: JobId = req
+        .job_id
+        .parse()
+        .map_err(|_| AppError::validation("invalid job ID format"))?;
+
+    state
+        .store
+        .get_job(&job_id)
+        .await?
+        .ok_or_else(|| AppError::not_found(job_id.to_string()))?;
+
+    let project = state
+        .store
+        .attach_job(&project_id, &job_id, MAX_PROJECT_JOBS)
+        .await?;
+    Ok(Json(project.into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/projects/{id}/jobs",
+    tag = "projects",
+    params(
+        ("id" = String, Path, description = "Project ID")
+    ),
+    responses(
+        (status = 200, description = "Member jobs, in the order attached", body = ProjectJobsResponse),
+        (status = 404, description = "Project not found", body = ApiError),
+    )
+)]
+pub async fn get_project_jobs(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<ProjectJobsResponse>, AppError> {
+    let project = find_project(&state, &id).await?;
+
+    let mut jobs = Vec::with_capacity(project.job_ids.len());
+    for job_id in &project.job_ids {
+        if let Some(job) = state.store.get_job(job_id).await? {
+            jobs.push(JobResponse::from(job));
+        }
+    }
+
+    Ok(Json(ProjectJobsResponse {
+        project_id: project.id.to_string(),
+        jobs,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/projects/{id}/report",
+    tag = "projects",
+    params(
+        ("id" = String, Path, description = "Project ID")
+    ),
+    responses(
+        (status = 200, description = "Report synthesized from the answered jobs", body = ProjectReportResponse),
+        (status = 404, description = "Project not found", body = ApiError),
+        (status = 409, description = "No member job has an answer yet", body = ApiError),
+    )
+)]
+pub async fn create_report(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<ProjectReportResponse>, AppError> {
+    let project = find_project(&state, &id).await?;
+
+    let mut members = Vec::with_capacity(project.job_ids.len());
+    for job_id in &project.job_ids {
+        if let Some(job) = state.store.get_job(job_id).await? {
+            let answer = state.store.get_answer(job_id).await?;
+            members.push((job, answer));
+        }
+    }
+    if members.iter().all(|(_, answer)| answer.is_none()) {
+        return Err(AppError::conflict(format!(
+            "project {} has no answered jobs to report on",
+            project.id
+        )));
+    }
+
+    let llm = state
+        .default_llm_provider()
+        .ok_or_else(|| AppError::internal("no LLM provider configured"))?;
+    let report = ProjectReport::synthesize(&project, members, llm.as_ref()).await?;
+
+    tracing::info!(
+        project_id = %project.id,
+        findings = report.findings.len(),
+        unanswered = report.unanswered.len(),
+        model = %report.model,
+        "generated project report"
+    );
+
+    Ok(Json(report.into()))
+}
+
+fn parse_project_id(id: &str) -> Result<ProjectId, AppError> {
+    id.parse()
+        .map_err(|_| AppError::validation("invalid project ID format"))
+}
+
+async fn find_project(state: &AppState, id: &str) -> Result<Project, AppError> {
+    let project_id = parse_project_id(id)?;
+
+    state
+        .store
+        .get_project(&project_id)
+        .await?
+        .ok_or_else(|| AppError::not_found(project_id.to_string()))
+}
+
+pub fn router() -> OpenApiRouter<Arc<AppState>> {
+    OpenApiRouter::new()
+        .routes(routes!(create_project))
+        .routes(routes!(get_project))
+        .routes(routes!(attach_job, get_project_jobs))
+        .routes(routes!(create_report))
+}
@@ -1,62 +1,629 @@
 use std::sync::Arc;
 
 use axum::extract::State;
-use axum::http::StatusCode;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::IntoResponse;
 use axum::Json;
-use gorkd_core::ResearchJob;
+use gorkd_core::{
+    validate_query, AnswerSchema, CrossJobSynthesis, DirectSynthesis, JobId,
+    JobStatus as CoreJobStatus, LifecycleEvent, LifecycleEventKind, ResearchJob, ResearchProfile,
+    SearchFilters, Source, SourceLimits, SourcePool, DEFAULT_POOLED_SOURCES, MAX_COMPARISON_MODELS,
+    MAX_DIRECT_SOURCES, MAX_POOLED_SOURCES, MAX_SYNTHESIS_JOBS, WORKSPACE_HEADER,
+};
+use tracing::Instrument;
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
 
-use crate::dto::{CreateResearchRequest, CreateResearchResponse, JobStatus};
+use crate::dto::{
+    CostBudget, CreateResearchRequest, CreateResearchResponse, DirectSourceRequest,
+    DirectSynthesisResponse, DirectSynthesizeRequest, JobStatus, SourceDetail, SynthesisResponse,
+    SynthesizeRequest,
+};
 use crate::error::{ApiError, AppError};
+use crate::execution::JobExecution;
+use crate::queue::JobPermit;
+use crate::routes::corpus::workspace;
+use crate::routes::trace_header;
 use crate::state::AppState;
 
+/// Most tags on one job.
+const MAX_TAGS: usize = 16;
+
+/// Longest tag, in bytes.
+const MAX_TAG_LENGTH: usize = 64;
+
+/// Largest metadata object, serialized.
+const MAX_METADATA_BYTES: usize = 4096;
+
+/// Longest title of a caller-provided source.
+const MAX_SOURCE_TITLE_LENGTH: usize = 500;
+
 #[utoipa::path(
     post,
     path = "/v1/research",
     tag = "research",
+    params(
+        ("X-Gorkd-Workspace" = Option<String>, Header, description = "Workspace whose corpus `include_corpus` searches; defaults to `default`")
+    ),
     request_body = CreateResearchRequest,
     responses(
-        (status = 202, description = "Job created", body = CreateResearchResponse),
+        (status = 202, description = "Job created", body = CreateResearchResponse,
+            headers(("X-Gorkd-Trace-Id" = String, description = "Trace ID of the job"))),
         (status = 400, description = "Invalid request", body = ApiError),
+        (status = 503, description = "Job queue full; retry after the `Retry-After` delay", body = ApiError,
+            headers(("Retry-After" = u64, description = "Seconds to wait before retrying"))),
     )
 )]
 pub async fn create_research(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Json(req): Json<CreateResearchRequest>,
-) -> Result<(StatusCode, Json<CreateResearchResponse>), AppError> {
-    let job = ResearchJob::new(&req.query)?;
-    let job_id = job.id.to_string();
+) -> Result<impl IntoResponse, AppError> {
+    let mut job =
+        ResearchJob::new(&req.query)?.with_source_limits(checked_source_limits(&state, &req)?);
+    if let Some(ref name) = req.profile {
+        job = job.with_profile(name, checked_profile(&state, name)?);
+    }
+    let filters = checked_filters(&req, job.filters.clone())?;
+    job = job.with_filters(filters);
+    if let Some(depth) = req.depth {
+        job = job.with_depth(depth.into());
+    }
+    if let Some(style) = req.style {
+        job = job.with_style(style.into());
+    }
+    if req.extract_entities {
+        job = job.with_entity_extraction();
+    }
+    if req.high_stakes {
+        job = job.with_high_stakes();
+    }
+    if req.corpus_only {
+        job = job.with_corpus_only();
+    } else if req.include_corpus {
+        job = job.with_corpus();
+    }
+    if req.sources_only {
+        check_sources_only(&req)?;
+        job = job.with_sources_only();
+    }
+    if headers.contains_key(WORKSPACE_HEADER) {
+        job = job.with_workspace(workspace(&headers)?);
+    }
+    if let Some(max_cost) = req.max_cost {
+        job = job.with_max_cost(checked_max_cost(&req, max_cost)?);
+    }
+    if let Some(schema) = req.answer_schema {
+        job = job.with_answer_schema(AnswerSchema::new(schema.name, schema.schema)?);
+    }
+    if !req.models.is_empty() {
+        job = job.with_models(checked_models(&state, req.models)?);
+    }
+    if !req.tags.is_empty() {
+        job = job.with_tags(checked_tags(req.tags)?);
+    }
+    if let Some(metadata) = req.metadata {
+        job = job.with_metadata(checked_metadata(metadata)?);
+    }
 
+    let permit = admit(&state)?;
     state.store.create_job(&job).await?;
 
-    tracing::info!(job_id = %job_id, query = %req.query, "created research job");
+    tracing::info!(
+        job_id = %job.id,
+        trace_id = %job.trace_id,
+        query = %req.query,
+        "created research job"
+    );
 
-    let pipeline = state.pipeline();
-    tokio::spawn(async move {
-        match pipeline.run(job).await {
-            Ok(result) => {
-                tracing::info!(
-                    job_id = %result.job.id,
-                    sources = result.sources.len(),
-                    "pipeline completed"
+    let headers = trace_header(&job);
+    let response = launch(&state, job, permit).await;
+
+    Ok((StatusCode::ACCEPTED, headers, Json(response)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/synthesize",
+    tag = "research",
+    request_body = SynthesizeRequest,
+    responses(
+        (status = 200, description = "Answer over the pooled results of the jobs", body = SynthesisResponse),
+        (status = 400, description = "Invalid request", body = ApiError),
+        (status = 404, description = "Job not found", body = ApiError),
+        (status = 409, description = "A job has not completed", body = ApiError),
+    )
+)]
+pub async fn synthesize(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<SynthesizeRequest>,
+) -> Result<Json<SynthesisResponse>, AppError> {
+    validate_query(&req.question)?;
+    let job_ids = checked_job_ids(req.job_ids)?;
+    let max_sources = req.max_sources.unwrap_or(DEFAULT_POOLED_SOURCES);
+    if max_sources == 0 || max_sources > MAX_POOLED_SOURCES {
+        return Err(AppError::validation(format!(
+            "max_sources must be between 1 and {}",
+            MAX_POOLED_SOURCES
+        )));
+    }
+
+    let mut pool = SourcePool::new();
+    for job_id in &job_ids {
+        let job = state
+            .store
+            .get_job(job_id)
+            .await?
+            .ok_or_else(|| AppError::not_found(job_id.to_string()))?;
+        if job.status != CoreJobStatus::Completed {
+            return Err(AppError::conflict(format!(
+                "job {} has not completed",
+                job.id
+            )));
+        }
+        let answer = state.store.get_answer(job_id).await?;
+        let sources = state.store.get_sources(job_id).await?;
+        pool.add_job(&job, answer.as_ref(), sources);
+    }
+
+    let llm = state
+        .default_llm_provider()
+        .ok_or_else(|| AppError::internal("no LLM provider configured"))?;
+    let synthesis =
+        CrossJobSynthesis::synthesize(&req.question, pool, max_sources, llm.as_ref()).await?;
+
+    tracing::info!(
+        jobs = job_ids.len(),
+        sources = synthesis.sources.len(),
+        citations = synthesis.answer.citations.len(),
+        "synthesized across jobs"
+    );
+
+    Ok(Json(SynthesisResponse {
+        question: synthesis.question,
+        job_ids: job_ids.iter().map(ToString::to_string).collect(),
+        answer: synthesis.answer.into(),
+        sources: synthesis.sources.into_iter().map(Into::into).collect(),
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/synthesize/direct",
+    tag = "research",
+    request_body = DirectSynthesizeRequest,
+    responses(
+        (status = 200, description = "Answer over the given sources", body = DirectSynthesisResponse),
+        (status = 400, description = "Invalid request", body = ApiError),
+    )
+)]
+pub async fn synthesize_direct(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<DirectSynthesizeRequest>,
+) -> Result<Json<DirectSynthesisResponse>, AppError> {
+    validate_query(&req.question)?;
+    let sources = checked_direct_sources(req.sources)?;
+
+    let llm = state
+        .default_llm_provider()
+        .ok_or_else(|| AppError::internal("no LLM provider configured"))?;
+    let style = req.style.map(Into::into).unwrap_or_default();
+    let synthesis = DirectSynthesis::synthesize(
+        &req.question,
+        sources,
+        style,
+        state.citation_markers,
+        llm.as_ref(),
+    )
+    .await?;
+
+    tracing::info!(
+        sources = synthesis.sources.len(),
+        citations = synthesis.answer.citations.len(),
+        "synthesized over caller sources"
+    );
+
+    let sources = synthesis
+        .sources
+        .into_iter()
+        .map(|source| SourceDetail::new(source, Some(&synthesis.answer), false))
+        .collect();
+    Ok(Json(DirectSynthesisResponse {
+        question: synthesis.question,
+        answer: synthesis.answer.into(),
+        sources,
+    }))
+}
+
+/// Takes a queue slot for a new job when jobs run in this process, or
+/// rejects it when the queue is full. Queued jobs run in workers, which
+/// bound their own concurrency.
+pub(crate) fn admit(state: &AppState) -> Result<Option<JobPermit>, AppError> {
+    match state.job_execution {
+        JobExecution::Inline => {
+            let Some(permit) = state.job_queue.try_acquire() else {
+                tracing::warn!(
+                    depth = state.job_queue.depth(),
+                    "job queue full, rejecting research request"
                 );
-            }
-            Err(e) => {
-                tracing::error!(error = %e, "pipeline failed");
-            }
+                return Err(AppError::overloaded(state.job_queue.retry_after()));
+            };
+            Ok(Some(permit))
         }
-    });
+        JobExecution::Queue => Ok(None),
+    }
+}
+
+/// Announces a stored job and, with a permit from [`admit`], starts its
+/// pipeline in this process.
+pub(crate) async fn launch(
+    state: &Arc<AppState>,
+    job: ResearchJob,
+    permit: Option<JobPermit>,
+) -> CreateResearchResponse {
+    announce(state, &job).await;
 
     let response = CreateResearchResponse {
-        job_id: job_id.clone(),
+        job_id: job.id.to_string(),
         status: JobStatus::Pending,
-        stream_url: format!("/v1/jobs/{}/stream", job_id),
+        stream_url: format!("/v1/jobs/{}/stream", job.id),
+        retried_from: job.retried_from.as_ref().map(ToString::to_string),
     };
+    if let Some(permit) = permit {
+        spawn_pipeline(state, job, permit);
+    }
+    response
+}
+
+async fn announce(state: &AppState, job: &ResearchJob) {
+    if let Some(ref publisher) = state.event_publisher {
+        let event = LifecycleEvent::new(
+            job,
+            LifecycleEventKind::Created {
+                query: job.query.clone(),
+            },
+        );
+        // Best effort, like the pipeline's own lifecycle events.
+        let _ = publisher.publish(&event).await;
+    }
+}
+
+/// Checks that every model to compare is configured and that there are no
+/// more than [`MAX_COMPARISON_MODELS`] of them, ignoring repeats.
+fn checked_models(state: &AppState, models: Vec<String>) -> Result<Vec<String>, AppError> {
+    let available = state.available_llm_models();
+    let mut checked: Vec<String> = Vec::with_capacity(models.len());
+
+    for model in models {
+        if !available.contains(&model) {
+            return Err(AppError::validation(format!(
+                "unknown model {:?}; available: {}",
+                model,
+                available.join(", ")
+            )));
+        }
+        if !checked.contains(&model) {
+            checked.push(model);
+        }
+    }
 
-    Ok((StatusCode::ACCEPTED, Json(response)))
+    if checked.len() > MAX_COMPARISON_MODELS {
+        return Err(AppError::validation(format!(
+            "at most {} models can be compared",
+            MAX_COMPARISON_MODELS
+        )));
+    }
+    Ok(checked)
+}
+
+/// Parses the jobs to pool, ignoring repeats, and checks there are 2 to
+/// [`MAX_SYNTHESIS_JOBS`] of them.
+fn checked_job_ids(ids: Vec<String>) -> Result<Vec<JobId>, AppError> {
+    let mut job_ids: Vec<JobId> = Vec::with_capacity(ids.len());
+    for id in ids {
+        let job_id: JobId = id
+            .parse()
+            .map_err(|_| AppError::validation(format!("invalid job ID format: {:?}", id)))?;
+        if !job_ids.contains(&job_id) {
+            job_ids.push(job_id);
+        }
+    }
+    if job_ids.len() < 2 || job_ids.len() > MAX_SYNTHESIS_JOBS {
+        return Err(AppError::validation(format!(
+            "job_ids must name 2 to {} jobs",
+            MAX_SYNTHESIS_JOBS
+        )));
+    }
+    Ok(job_ids)
+}
+
+/// Turns the caller's sources into sources to answer from, checking there
+/// are 1 to [`MAX_DIRECT_SOURCES`] of them, each with an http(s) URL, a
+/// title and some content.
+fn checked_direct_sources(sources: Vec<DirectSourceRequest>) -> Result<Vec<Source>, AppError> {
+    if sources.is_empty() || sources.len() > MAX_DIRECT_SOURCES {
+        return Err(AppError::validation(format!(
+            "sources must hold 1 to {} sources",
+            MAX_DIRECT_SOURCES
+        )));
+    }
+    sources
+        .into_iter()
+        .enumerate()
+        .map(|(i, source)| {
+            if !source.url.starts_with("http://") && !source.url.starts_with("https://") {
+                return Err(AppError::validation(format!(
+                    "sources[{}].url must be an http or https URL",
+                    i
+                )));
+            }
+            let title = source.title.trim();
+            if title.is_empty() || title.chars().count() > MAX_SOURCE_TITLE_LENGTH {
+                return Err(AppError::validation(format!(
+                    "sources[{}].title must be 1-{} characters",
+                    i, MAX_SOURCE_TITLE_LENGTH
+                )));
+            }
+            if source.content.trim().is_empty() {
+                return Err(AppError::validation(format!(
+                    "sources[{}].content must not be empty",
+                    i
+                )));
+            }
+            Ok(Source::new(source.url, title, source.content))
+        })
+        .collect()
+}
+
+/// Checks that a budget is positive and not set on a model comparison,
+/// which it would not apply to.
+/// Rejects the options that shape an answer, which a sources-only job
+/// never writes.
+fn check_sources_only(req: &CreateResearchRequest) -> Result<(), AppError> {
+    let answer_options = [
+        ("answer_schema", req.answer_schema.is_some()),
+        ("models", !req.models.is_empty()),
+        ("extract_entities", req.extract_entities),
+        ("high_stakes", req.high_stakes),
+        ("max_cost", req.max_cost.is_some()),
+    ];
+    match answer_options.iter().find(|(_, set)| *set) {
+        Some((name, _)) => Err(AppError::validation(format!(
+            "sources_only cannot be combined with {}",
+            name
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn checked_max_cost(
+    req: &CreateResearchRequest,
+    max_cost: CostBudget,
+) -> Result<gorkd_core::CostBudget, AppError> {
+    if !req.models.is_empty() {
+        return Err(AppError::validation(
+            "max_cost cannot be combined with models",
+        ));
+    }
+    let positive = match max_cost {
+        CostBudget::Usd(usd) => usd.is_finite() && usd > 0.0,
+        CostBudget::Tokens(tokens) => tokens > 0,
+    };
+    if !positive {
+        return Err(AppError::validation("max_cost must be greater than zero"));
+    }
+    Ok(max_cost.into())
+}
+
+/// Checks that there are at most [`MAX_TAGS`] tags, each 1 to
+/// [`MAX_TAG_LENGTH`] letters, digits, `-`, `_`, `.` or `:`.
+fn checked_tags(tags: Vec<String>) -> Result<Vec<String>, AppError> {
+    if tags.len() > MAX_TAGS {
+        return Err(AppError::validation(format!(
+            "at most {} tags are allowed",
+            MAX_TAGS
+        )));
+    }
+    let allowed = |c: char| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':');
+    for tag in &tags {
+        if tag.is_empty() || tag.len() > MAX_TAG_LENGTH || !tag.chars().all(allowed) {
+            return Err(AppError::validation(format!(
+                "tags must be 1-{} letters, digits, '-', '_', '.' or ':', got {:?}",
+                MAX_TAG_LENGTH, tag
+            )));
+        }
+    }
+    Ok(tags)
+}
+
+/// Checks that metadata is a JSON object of at most [`MAX_METADATA_BYTES`].
+fn checked_metadata(metadata: serde_json::Value) -> Result<serde_json::Value, AppError> {
+    if !metadata.is_object() {
+        return Err(AppError::validation("metadata must be a JSON object"));
+    }
+    let size = serde_json::to_vec(&metadata)
+        .map_err(|e| AppError::internal(e.to_string()))?
+        .len();
+    if size > MAX_METADATA_BYTES {
+        return Err(AppError::validation(format!(
+            "metadata must be at most {} bytes, got {}",
+            MAX_METADATA_BYTES, size
+        )));
+    }
+    Ok(metadata)
+}
+
+/// Looks up the research profile `name`.
+fn checked_profile<'a>(state: &'a AppState, name: &str) -> Result<&'a ResearchProfile, AppError> {
+    state.profiles.get(name).ok_or_else(|| {
+        AppError::validation(format!(
+            "unknown profile {:?}; available: {}",
+            name,
+            state.profiles.names().join(", ")
+        ))
+    })
+}
+
+/// Sets the request's language, country and publication dates on
+/// `filters`, checking that the codes have two letters and the dates are in
+/// order.
+fn checked_filters(
+    req: &CreateResearchRequest,
+    mut filters: SearchFilters,
+) -> Result<SearchFilters, AppError> {
+    let two_letters = |code: &str| code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic());
+
+    if let Some(ref language) = req.language {
+        if !two_letters(language) {
+            return Err(AppError::validation(format!(
+                "language must be a two-letter ISO 639-1 code such as \"de\", got {:?}",
+                language
+            )));
+        }
+        filters = filters.with_language(language);
+    }
+    if let Some(ref country) = req.country {
+        if !two_letters(country) {
+            return Err(AppError::validation(format!(
+                "country must be a two-letter ISO 3166-1 code such as \"DE\", got {:?}",
+                country
+            )));
+        }
+        filters = filters.with_country(country);
+    }
+
+    if req.published_after.is_some() || req.published_before.is_some() {
+        if let (Some(after), Some(before)) = (req.published_after, req.published_before) {
+            if after >= before {
+                return Err(AppError::validation(
+                    "published_after must be before published_before",
+                ));
+            }
+        }
+        filters = filters.published_between(req.published_after, req.published_before);
+    }
+    Ok(filters)
+}
+
+/// Checks the request's source bounds against the server's caps.
+fn checked_source_limits(
+    state: &AppState,
+    req: &CreateResearchRequest,
+) -> Result<SourceLimits, AppError> {
+    if let Some(max_sources) = req.max_sources {
+        if max_sources == 0 || max_sources > state.max_sources_limit {
+            return Err(AppError::validation(format!(
+                "max_sources must be between 1 and {}",
+                state.max_sources_limit
+            )));
+        }
+    }
+    if let Some(min_score) = req.min_score {
+        if !(0.0..=1.0).contains(&min_score) {
+            return Err(AppError::validation("min_score must be between 0 and 1"));
+        }
+    }
+    if let Some(max_per_domain) = req.max_per_domain {
+        if max_per_domain == 0 {
+            return Err(AppError::validation("max_per_domain must be at least 1"));
+        }
+        if let Some(cap) = state.max_per_domain.filter(|&cap| max_per_domain > cap) {
+            return Err(AppError::validation(format!(
+                "max_per_domain must be at most {}",
+                cap
+            )));
+        }
+    }
+
+    Ok(SourceLimits {
+        max_sources: req.max_sources,
+        min_score: req.min_score,
+        max_per_domain: req.max_per_domain,
+    })
+}
+
+/// Runs the job's pipeline in this process, holding its queue slot until it
+/// finishes. Failed jobs are retried in the same slot, as the state's retry
+/// policy allows.
+fn spawn_pipeline(state: &Arc<AppState>, job: ResearchJob, permit: JobPermit) {
+    let state = Arc::clone(state);
+    tokio::spawn(async move {
+        let _permit = permit;
+        let mut job = job;
+        loop {
+            let job_id = job.id.clone();
+            // Provider spans nest under this one, so every log line of the
+            // job carries its trace ID.
+            let span =
+                tracing::info_span!("research_job", job_id = %job.id, trace_id = %job.trace_id);
+            let failed = run_pipeline(&state, job).instrument(span).await;
+            if !failed || !state.retry_policy.is_enabled() {
+                return;
+            }
+
+            match state
+                .retry_policy
+                .retry_failed(state.store.as_ref(), &job_id)
+                .await
+            {
+                Ok(Some(retry)) => {
+                    tracing::info!(
+                        job_id = %retry.id,
+                        retried_from = %job_id,
+                        attempt = retry.attempt,
+                        "retrying failed job"
+                    );
+                    announce(&state, &retry).await;
+                    job = retry;
+                }
+                Ok(None) => return,
+                Err(e) => {
+                    tracing::warn!(job_id = %job_id, error = %e, "could not retry failed job");
+                    return;
+                }
+            }
+        }
+    });
+}
+
+/// Runs the job's pipeline, returning whether it failed.
+async fn run_pipeline(state: &AppState, job: ResearchJob) -> bool {
+    match state.pipeline().run(job).await {
+        Ok(result) => {
+            tracing::info!(
+                job_id = %result.job.id,
+                sources = result.sources.len(),
+                "pipeline completed"
+            );
+            for compliance in &result.search_metadata.filter_compliance {
+                if compliance.violations > 0 {
+                    tracing::warn!(
+                        provider = %compliance.provider,
+                        results = compliance.results,
+                        violations = compliance.violations,
+                        compliance_rate = compliance.rate(),
+                        "provider returned results outside the search filters"
+                    );
+                } else {
+                    tracing::debug!(
+                        provider = %compliance.provider,
+                        results = compliance.results,
+                        "provider kept to the search filters"
+                    );
+                }
+            }
+            false
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "pipeline failed");
+            true
+        }
+    }
 }
 
 pub fn router() -> OpenApiRouter<Arc<AppState>> {
-    OpenApiRouter::new().routes(routes!(create_research))
+    OpenApiRouter::new()
+        .routes(routes!(create_research))
+        .routes(routes!(synthesize))
+        .routes(routes!(synthesize_direct))
 }
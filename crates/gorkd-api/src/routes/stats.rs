@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use axum::extract::rejection::QueryRejection;
+use axum::extract::{Query, State};
+use axum::Json;
+use chrono::{Duration, Utc};
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+use crate::dto::{ContentCacheDetail, StatsQuery, StatsResponse};
+use crate::error::{ApiError, AppError};
+use crate::state::AppState;
+
+/// Days `GET /v1/stats` sums over when the request does not say.
+const DEFAULT_STATS_DAYS: u32 = 30;
+
+/// Most days `GET /v1/stats` sums over.
+const MAX_STATS_DAYS: u32 = 365;
+
+#[utoipa::path(
+    get,
+    path = "/v1/stats",
+    tag = "stats",
+    params(StatsQuery),
+    responses(
+        (status = 200, description = "Usage summed over the window", body = StatsResponse),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+    )
+)]
+pub async fn get_stats(
+    State(state): State<Arc<AppState>>,
+    query: Result<Query<StatsQuery>, QueryRejection>,
+) -> Result<Json<StatsResponse>, AppError> {
+    let Query(query) = query.map_err(|e| AppError::validation(e.body_text()))?;
+
+    let days = query.days.unwrap_or(DEFAULT_STATS_DAYS);
+    if days == 0 || days > MAX_STATS_DAYS {
+        return Err(AppError::validation(format!(
+            "days must be between 1 and {}",
+            MAX_STATS_DAYS
+        )));
+    }
+    let today = Utc::now().date_naive();
+    let since = (today - Duration::days(i64::from(days) - 1))
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_utc();
+
+    let stats = state.store.usage_stats(since).await?;
+    let content_cache = state.content_metrics.as_ref().map(|metrics| {
+        let content = metrics.snapshot();
+        let lookups = content.objects_written + content.dedup_hits;
+        ContentCacheDetail {
+            hits: content.dedup_hits,
+            lookups,
+            hit_rate: (lookups > 0).then(|| content.dedup_hits as f64 / lookups as f64),
+        }
+    });
+
+    Ok(Json(StatsResponse {
+        since,
+        days,
+        success_rate: stats.success_rate(),
+        jobs: stats.jobs,
+        completed: stats.completed,
+        failed: stats.failed,
+        daily: stats.days.into_iter().map(Into::into).collect(),
+        stage_latency: stats.stage_latency.into_iter().map(Into::into).collect(),
+        top_domains: stats.top_domains.into_iter().map(Into::into).collect(),
+        models: stats.models.into_iter().map(Into::into).collect(),
+        content_cache,
+    }))
+}
+
+pub fn router() -> OpenApiRouter<Arc<AppState>> {
+    OpenApiRouter::new().routes(routes!(get_stats))
+}
@@ -0,0 +1,196 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use axum::Json;
+use serde_json::{json, Map, Value};
+use utoipa::OpenApi;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+use crate::dto::{JobStatus, ToolInvocationDetail, ToolSchemaResponse};
+use crate::openapi::ApiDoc;
+use crate::state::AppState;
+
+/// Name of the research tool, valid as an OpenAI function name.
+const TOOL_NAME: &str = "gorkd_research";
+
+const TOOL_DESCRIPTION: &str = "Research a question on the web and answer it with \
+    citations to the sources found. Use for questions that need current or verifiable \
+    facts. Research takes from several seconds to a few minutes.";
+
+/// Fields of `CreateResearchRequest` offered as tool arguments. The rest
+/// (models, budgets, tags, metadata) are for API clients, not agents.
+const TOOL_PARAMETERS: &[&str] = &[
+    "query",
+    "depth",
+    "style",
+    "include_corpus",
+    "corpus_only",
+    "profile",
+    "language",
+    "country",
+    "published_after",
+    "published_before",
+    "max_sources",
+];
+
+#[utoipa::path(
+    get,
+    path = "/v1/tool-schema",
+    tag = "research",
+    responses(
+        (status = 200, description = "The research capability as a tool definition", body = ToolSchemaResponse),
+    )
+)]
+pub async fn get_tool_schema() -> Json<ToolSchemaResponse> {
+    Json(tool_schema())
+}
+
+fn tool_schema() -> ToolSchemaResponse {
+    let schemas = component_schemas();
+    let parameters = parameters(&schemas);
+
+    ToolSchemaResponse {
+        name: TOOL_NAME.to_string(),
+        description: TOOL_DESCRIPTION.to_string(),
+        openai: json!({
+            "type": "function",
+            "function": {
+                "name": TOOL_NAME,
+                "description": TOOL_DESCRIPTION,
+                "parameters": parameters,
+            }
+        }),
+        parameters,
+        returns: inline(&schemas["AnswerDetail"], &schemas, false),
+        invocation: ToolInvocationDetail {
+            create: "POST /v1/research".to_string(),
+            poll: "GET /v1/jobs/{job_id}".to_string(),
+            poll_until: vec![JobStatus::Completed, JobStatus::Failed],
+            sources: "GET /v1/jobs/{job_id}/sources".to_string(),
+        },
+    }
+}
+
+/// The API's component schemas by name, as JSON.
+fn component_schemas() -> BTreeMap<String, Value> {
+    let components = ApiDoc::openapi().components.unwrap_or_default();
+    components
+        .schemas
+        .into_iter()
+        .filter_map(|(name, schema)| Some((name, serde_json::to_value(schema).ok()?)))
+        .collect()
+}
+
+/// The tool's arguments: the [`TOOL_PARAMETERS`] of the research request,
+/// with references inlined since tool schemas must stand alone. Arguments
+/// are optional by leaving them out, so `null` is not offered.
+fn parameters(schemas: &BTreeMap<String, Value>) -> Value {
+    let request = &schemas["CreateResearchRequest"];
+    let mut properties = Map::new();
+    for &name in TOOL_PARAMETERS {
+        if let Some(property) = request["properties"].get(name) {
+            properties.insert(name.to_string(), inline(property, schemas, true));
+        }
+    }
+    let required: Vec<&Value> = request["required"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|name| name.as_str().is_some_and(|n| TOOL_PARAMETERS.contains(&n)))
+        .collect();
+
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+    })
+}
+
+/// `schema` with each `$ref` replaced by the schema it names, and with
+/// `null` alternatives dropped when `drop_null` is set.
+fn inline(schema: &Value, schemas: &BTreeMap<String, Value>, drop_null: bool) -> Value {
+    match schema {
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| inline(item, schemas, drop_null))
+                .collect(),
+        ),
+        Value::Object(object) => {
+            let mut inlined = Map::new();
+            if let Some(name) = object
+                .get("$ref")
+                .and_then(Value::as_str)
+                .and_then(|r| r.strip_prefix("#/components/schemas/"))
+            {
+                if let Some(Value::Object(target)) =
+                    schemas.get(name).map(|s| inline(s, schemas, drop_null))
+                {
+                    inlined.extend(target);
+                }
+            }
+            for (key, value) in object {
+                if key != "$ref" {
+                    inlined.insert(key.clone(), inline(value, schemas, drop_null));
+                }
+            }
+            if drop_null {
+                drop_null_alternative(&mut inlined);
+            }
+            Value::Object(inlined)
+        }
+        value => value.clone(),
+    }
+}
+
+/// Turns `{"type": ["string", "null"]}` into `{"type": "string"}`, and a
+/// `oneOf` of `null` and one schema into that schema.
+fn drop_null_alternative(schema: &mut Map<String, Value>) {
+    if let Some(Value::Array(types)) = schema.get("type") {
+        let types: Vec<Value> = types.iter().filter(|t| *t != "null").cloned().collect();
+        if types.len() == 1 {
+            schema.insert("type".to_string(), types[0].clone());
+        }
+    }
+    for key in ["oneOf", "anyOf"] {
+        let Some(Value::Array(alternatives)) = schema.get(key) else {
+            continue;
+        };
+        let rest: Vec<&Value> = alternatives
+            .iter()
+            .filter(|alternative| alternative.get("type") != Some(&json!("null")))
+            .collect();
+        if let [Value::Object(only)] = rest.as_slice() {
+            let only = only.clone();
+            schema.remove(key);
+            for (key, value) in only {
+                schema.entry(key).or_insert(value);
+            }
+        }
+    }
+}
+
+pub fn router() -> OpenApiRouter<Arc<AppState>> {
+    OpenApiRouter::new().routes(routes!(get_tool_schema))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offers_every_tool_parameter_inlined() {
+        let schema = tool_schema();
+        let properties = schema.parameters["properties"].as_object().unwrap();
+
+        assert_eq!(properties.len(), TOOL_PARAMETERS.len());
+        assert_eq!(schema.parameters["required"], json!(["query"]));
+        assert_eq!(properties["query"]["type"], "string");
+        assert_eq!(properties["language"]["type"], "string");
+        assert!(properties["depth"]["enum"].is_array());
+        assert!(!schema.parameters.to_string().contains("$ref"));
+        assert!(!schema.returns.to_string().contains("$ref"));
+        assert_eq!(schema.openai["function"]["parameters"], schema.parameters);
+    }
+}
@@ -0,0 +1,174 @@
+//! End-to-end self-test of a deployment, run with `gorkd-api --self-test`.
+//!
+//! Passing the startup checks means the settings are well-formed, not that
+//! the deployment answers questions. The self-test exercises each part a
+//! job depends on with live calls: the store, every search provider, one
+//! model of every LLM provider, and finally a full pipeline run of
+//! [`SELF_TEST_QUERY`] at the shortest depth. Each part is reported with
+//! whether it passed and how long it took, so an operator can verify a
+//! deployment before pointing users at it.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use gorkd_core::{AnswerDepth, ResearchJob};
+
+use crate::config_check;
+use crate::state::AppState;
+
+/// The question the self-test researches.
+pub const SELF_TEST_QUERY: &str = "What is the capital of France?";
+
+/// How long the full pipeline run may take.
+const PIPELINE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How one component fared.
+#[derive(Clone, Debug)]
+pub struct ComponentResult {
+    pub component: String,
+    pub passed: bool,
+    pub latency: Duration,
+    /// What the component returned, or why it failed.
+    pub detail: String,
+}
+
+impl ComponentResult {
+    fn new(
+        component: impl Into<String>,
+        latency: Duration,
+        result: Result<String, String>,
+    ) -> Self {
+        let (passed, detail) = match result {
+            Ok(detail) => (true, detail),
+            Err(detail) => (false, detail),
+        };
+        Self {
+            component: component.into(),
+            passed,
+            latency,
+            detail,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SelfTestReport {
+    pub results: Vec<ComponentResult>,
+}
+
+impl SelfTestReport {
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(|result| result.passed)
+    }
+}
+
+impl fmt::Display for SelfTestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .results
+            .iter()
+            .map(|result| result.component.len())
+            .max()
+            .unwrap_or(0);
+        for result in &self.results {
+            writeln!(
+                f,
+                "{}  {:width$}  {:>7} ms  {}",
+                if result.passed { "PASS" } else { "FAIL" },
+                result.component,
+                result.latency.as_millis(),
+                result.detail,
+                width = width
+            )?;
+        }
+        let failed = self.results.iter().filter(|result| !result.passed).count();
+        match failed {
+            0 => write!(f, "all {} checks passed", self.results.len()),
+            failed => write!(f, "{} of {} checks failed", failed, self.results.len()),
+        }
+    }
+}
+
+/// Runs every check against the providers and store of `state`. The
+/// pipeline run stores a job tagged `self-test`.
+pub async fn run(state: &AppState) -> SelfTestReport {
+    let mut report = SelfTestReport::default();
+
+    let config = config_check::check_state(state);
+    report.results.push(ComponentResult::new(
+        "configuration",
+        Duration::ZERO,
+        match config.errors().map(ToString::to_string).collect::<Vec<_>>() {
+            errors if errors.is_empty() => Ok(format!("{} warnings", config.issues.len())),
+            errors => Err(errors.join("; ")),
+        },
+    ));
+
+    let job = ResearchJob::new(SELF_TEST_QUERY)
+        .expect("self-test query is valid")
+        .with_depth(AnswerDepth::Tldr)
+        .with_tags(["self-test"]);
+    let started = Instant::now();
+    let stored = match state.store.create_job(&job).await {
+        Ok(()) => match state.store.get_job(&job.id).await {
+            Ok(Some(_)) => Ok("job written and read back".to_string()),
+            Ok(None) => Err("job written but not found".to_string()),
+            Err(e) => Err(e.to_string()),
+        },
+        Err(e) => Err(e.to_string()),
+    };
+    let store_ok = stored.is_ok();
+    report
+        .results
+        .push(ComponentResult::new("store", started.elapsed(), stored));
+
+    for provider in config_check::search_providers(state) {
+        let started = Instant::now();
+        let result = config_check::ping_search(provider.as_ref())
+            .await
+            .map(|results| format!("{} results", results));
+        report.results.push(ComponentResult::new(
+            format!("search: {}", provider.provider_id()),
+            started.elapsed(),
+            result,
+        ));
+    }
+
+    for (name, provider) in config_check::llm_providers(state) {
+        let started = Instant::now();
+        let result = config_check::ping_llm(provider.as_ref())
+            .await
+            .map(|()| "answered".to_string());
+        report.results.push(ComponentResult::new(
+            format!("llm: {} ({})", name, provider.model_id()),
+            started.elapsed(),
+            result,
+        ));
+    }
+
+    // The pipeline updates the job it runs, so it needs the stored one.
+    let started = Instant::now();
+    let result = if !store_ok {
+        Err("skipped: the store failed".to_string())
+    } else {
+        match tokio::time::timeout(PIPELINE_TIMEOUT, state.pipeline().run(job)).await {
+            Ok(Ok(result)) => match result.answer {
+                Some(answer) => Ok(format!(
+                    "{} sources, {} citations, {:?} confidence",
+                    result.sources.len(),
+                    answer.citations.len(),
+                    answer.confidence
+                )
+                .to_lowercase()),
+                None => Err("completed without an answer".to_string()),
+            },
+            Ok(Err(e)) => Err(e.to_string()),
+            Err(_) => Err(format!("no answer within {}s", PIPELINE_TIMEOUT.as_secs())),
+        }
+    };
+    report
+        .results
+        .push(ComponentResult::new("pipeline", started.elapsed(), result));
+
+    report
+}
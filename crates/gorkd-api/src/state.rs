@@ -1,15 +1,89 @@
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use gorkd_core::{LlmProvider, Pipeline, SearchProvider, Store};
-use gorkd_llm::LlmRegistry;
-use gorkd_search::{FallbackSearchProvider, ProviderRegistry};
+use gorkd_core::{
+    ArtifactSink, CitationMarkers, ConsistencyPolicy, ContentFetcher, ContentLimits,
+    DeadLinkPolicy, DomainPolicy, Embedder, EscalationPolicy, EventPublisher, ExecutorConfig,
+    FactExtractorConfig, FeedPoller, FeedReader, FeedSubscription, FreshnessPolicy, LengthPolicies,
+    LinkCheckConfig, LinkChecker, LlmProvider, ModerationPolicy, Moderator, OutlinerConfig,
+    Pipeline, PipelineConfig, PipelinePlugin, ResearchProfiles, RetryPolicy, RoutingPolicy,
+    SearchProvider, ShadowMetrics, SiteCrawler, Store, DEFAULT_FEED_POLL_INTERVAL,
+    EXHAUSTIVE_MAX_SOURCES,
+};
+use gorkd_llm::{HashingEmbedder, LlmRegistry, ShadowLlmProvider};
+use gorkd_search::{FallbackSearchProvider, ProviderRegistry, ShadowSearchProvider};
+use gorkd_store::ContentMetrics;
+
+use crate::execution::JobExecution;
+use crate::queue::{JobQueue, QueueConfig};
+use crate::store_metrics::StoreMetrics;
 
 pub struct AppState {
     pub store: Arc<dyn Store>,
+    /// Timings of `store`'s operations, when it is instrumented.
+    pub store_metrics: Option<Arc<StoreMetrics>>,
+    /// Source contents kept in object storage, when it is configured.
+    pub content_metrics: Option<Arc<ContentMetrics>>,
     pub search_provider: Arc<dyn SearchProvider>,
+    /// Finds pages similar to the top-ranked sources, when enabled.
+    pub source_expansion: Option<Arc<dyn SearchProvider>>,
     pub llm_registry: LlmRegistry,
     pub search_registry: ProviderRegistry,
+    /// How the registries' shadow candidates compare with the providers and
+    /// model serving requests.
+    pub shadow_metrics: Arc<ShadowMetrics>,
+    pub moderator: Option<Arc<dyn Moderator>>,
+    /// Embeds corpus documents and the queries searching them.
+    pub embedder: Arc<dyn Embedder>,
+    pub moderation_policy: ModerationPolicy,
+    pub length_policies: LengthPolicies,
+    /// Whether exhaustive answers are outlined and written section by
+    /// section.
+    pub outline_reports: bool,
+    /// Whether completed jobs add facts to the knowledge base.
+    pub knowledge_graph: bool,
+    /// Which questions the registry's fast model answers; `None` answers
+    /// all of them with the default model.
+    pub routing_policy: Option<RoutingPolicy>,
+    pub content_limits: ContentLimits,
+    /// Most sources a request may ask a job to collect.
+    pub max_sources_limit: usize,
+    /// Sources kept from any one domain, unless a request lowers it.
+    pub max_per_domain: Option<usize>,
+    /// Domains every job is limited to or kept from.
+    pub domain_policy: DomainPolicy,
+    /// Research profiles requests can select by name.
+    pub profiles: ResearchProfiles,
+    /// Downloads pages that search returned without content.
+    pub content_fetcher: Option<Arc<dyn ContentFetcher>>,
+    /// Checks the links of answers before they are delivered.
+    pub link_checker: Option<Arc<dyn LinkChecker>>,
+    /// What happens to citations of dead links.
+    pub dead_links: DeadLinkPolicy,
+    /// Whether answers cite sources inline by number or by ID.
+    pub citation_markers: CitationMarkers,
+    /// How long answers stay current, by the volatility of their topic.
+    pub freshness: FreshnessPolicy,
+    /// Which answers jobs search again for; `None` escalates none.
+    pub escalation: Option<EscalationPolicy>,
+    /// How many answers high-stakes jobs sample, and at what temperature.
+    pub consistency: ConsistencyPolicy,
+    /// Reads sites into the corpus for `POST /v1/corpus/crawl`.
+    pub crawler: Option<Arc<dyn SiteCrawler>>,
+    /// Reads the feeds of `feed_subscriptions`.
+    pub feed_reader: Option<Arc<dyn FeedReader>>,
+    /// Feeds whose new entries are added to the corpus.
+    pub feed_subscriptions: Vec<FeedSubscription>,
+    pub feed_poll_interval: Duration,
+    pub artifact_sink: Option<Arc<dyn ArtifactSink>>,
+    pub event_publisher: Option<Arc<dyn EventPublisher>>,
+    /// Custom steps run in every job, in order.
+    pub plugins: Vec<Arc<dyn PipelinePlugin>>,
+    pub job_queue: Arc<JobQueue>,
+    pub job_execution: JobExecution,
+    /// Failed jobs the API retries on its own. Applies to jobs run inline;
+    /// workers have their own policy.
+    pub retry_policy: RetryPolicy,
     pub started_at: Instant,
 }
 
@@ -26,9 +100,42 @@ impl AppState {
 
         Self {
             store,
+            store_metrics: None,
+            content_metrics: None,
             search_provider,
+            source_expansion: None,
             llm_registry,
             search_registry: ProviderRegistry::new(),
+            shadow_metrics: Arc::default(),
+            moderator: None,
+            embedder: Arc::new(HashingEmbedder::new()),
+            moderation_policy: ModerationPolicy::Off,
+            length_policies: LengthPolicies::default(),
+            outline_reports: true,
+            knowledge_graph: false,
+            routing_policy: None,
+            content_limits: ContentLimits::default(),
+            max_sources_limit: EXHAUSTIVE_MAX_SOURCES,
+            max_per_domain: None,
+            domain_policy: DomainPolicy::default(),
+            profiles: ResearchProfiles::default(),
+            content_fetcher: None,
+            link_checker: None,
+            dead_links: DeadLinkPolicy::default(),
+            citation_markers: CitationMarkers::default(),
+            freshness: FreshnessPolicy::default(),
+            escalation: None,
+            consistency: ConsistencyPolicy::default(),
+            crawler: None,
+            feed_reader: None,
+            feed_subscriptions: Vec::new(),
+            feed_poll_interval: DEFAULT_FEED_POLL_INTERVAL,
+            artifact_sink: None,
+            event_publisher: None,
+            plugins: Vec::new(),
+            job_queue: Arc::default(),
+            job_execution: JobExecution::default(),
+            retry_policy: RetryPolicy::default(),
             started_at: Instant::now(),
         }
     }
@@ -38,17 +145,274 @@ impl AppState {
         search_registry: ProviderRegistry,
         llm_registry: LlmRegistry,
     ) -> Self {
-        let fallback = FallbackSearchProvider::from_registry(&search_registry);
+        let shadow_metrics: Arc<ShadowMetrics> = Arc::default();
+        let fallback: Arc<dyn SearchProvider> =
+            Arc::new(FallbackSearchProvider::from_registry(&search_registry));
+        let search_provider: Arc<dyn SearchProvider> = match search_registry.shadow() {
+            Some(candidate) => Arc::new(ShadowSearchProvider::new(
+                fallback,
+                candidate,
+                Arc::clone(&shadow_metrics),
+            )),
+            None => fallback,
+        };
 
         Self {
             store,
-            search_provider: Arc::new(fallback),
+            store_metrics: None,
+            content_metrics: None,
+            search_provider,
+            source_expansion: None,
             llm_registry,
             search_registry,
+            shadow_metrics,
+            moderator: None,
+            embedder: Arc::new(HashingEmbedder::new()),
+            moderation_policy: ModerationPolicy::Off,
+            length_policies: LengthPolicies::default(),
+            outline_reports: true,
+            knowledge_graph: false,
+            routing_policy: None,
+            content_limits: ContentLimits::default(),
+            max_sources_limit: EXHAUSTIVE_MAX_SOURCES,
+            max_per_domain: None,
+            domain_policy: DomainPolicy::default(),
+            profiles: ResearchProfiles::default(),
+            content_fetcher: None,
+            link_checker: None,
+            dead_links: DeadLinkPolicy::default(),
+            citation_markers: CitationMarkers::default(),
+            freshness: FreshnessPolicy::default(),
+            escalation: None,
+            consistency: ConsistencyPolicy::default(),
+            crawler: None,
+            feed_reader: None,
+            feed_subscriptions: Vec::new(),
+            feed_poll_interval: DEFAULT_FEED_POLL_INTERVAL,
+            artifact_sink: None,
+            event_publisher: None,
+            plugins: Vec::new(),
+            job_queue: Arc::default(),
+            job_execution: JobExecution::default(),
+            retry_policy: RetryPolicy::default(),
             started_at: Instant::now(),
         }
     }
 
+    pub fn with_moderation(
+        mut self,
+        moderator: Option<Arc<dyn Moderator>>,
+        policy: ModerationPolicy,
+    ) -> Self {
+        self.moderator = moderator;
+        self.moderation_policy = policy;
+        self
+    }
+
+    /// Sets the embedder of the document corpus.
+    pub fn with_embedder(mut self, embedder: Arc<dyn Embedder>) -> Self {
+        self.embedder = embedder;
+        self
+    }
+
+    /// Sets the answer length applied to each question type.
+    pub fn with_length_policies(mut self, policies: LengthPolicies) -> Self {
+        self.length_policies = policies;
+        self
+    }
+
+    /// Writes exhaustive answers section by section from an outline, or in
+    /// one pass.
+    pub fn with_outline_reports(mut self, enabled: bool) -> Self {
+        self.outline_reports = enabled;
+        self
+    }
+
+    /// Adds the facts each job's cited sources state to the knowledge base.
+    pub fn with_knowledge_graph(mut self, enabled: bool) -> Self {
+        self.knowledge_graph = enabled;
+        self
+    }
+
+    /// Answers the questions `policy` finds simple with the registry's fast
+    /// model.
+    pub fn with_routing(mut self, policy: Option<RoutingPolicy>) -> Self {
+        self.routing_policy = policy;
+        self
+    }
+
+    /// Caps the content kept from each source and from all sources of a job.
+    pub fn with_content_limits(mut self, limits: ContentLimits) -> Self {
+        self.content_limits = limits;
+        self
+    }
+
+    /// Bounds the sources requests may ask for: at most `max_sources_limit`
+    /// per job and, when set, at most `max_per_domain` from any one domain.
+    pub fn with_source_caps(
+        mut self,
+        max_sources_limit: usize,
+        max_per_domain: Option<usize>,
+    ) -> Self {
+        self.max_sources_limit = max_sources_limit;
+        self.max_per_domain = max_per_domain;
+        self
+    }
+
+    /// Enforces `policy` on the sources of every job, whatever filters the
+    /// job asks for.
+    pub fn with_domain_policy(mut self, policy: DomainPolicy) -> Self {
+        self.domain_policy = policy;
+        self
+    }
+
+    /// Lets requests select one of `profiles` by name.
+    pub fn with_profiles(mut self, profiles: ResearchProfiles) -> Self {
+        self.profiles = profiles;
+        self
+    }
+
+    /// Fetches the text of sources that came back without content.
+    pub fn with_content_fetcher(mut self, fetcher: Option<Arc<dyn ContentFetcher>>) -> Self {
+        self.content_fetcher = fetcher;
+        self
+    }
+
+    /// Checks the links of every answer with `checker`, handling citations
+    /// of dead ones as `policy` says.
+    pub fn with_link_checker(
+        mut self,
+        checker: Option<Arc<dyn LinkChecker>>,
+        policy: DeadLinkPolicy,
+    ) -> Self {
+        self.link_checker = checker;
+        self.dead_links = policy;
+        self
+    }
+
+    pub fn with_citation_markers(mut self, markers: CitationMarkers) -> Self {
+        self.citation_markers = markers;
+        self
+    }
+
+    pub fn with_freshness(mut self, policy: FreshnessPolicy) -> Self {
+        self.freshness = policy;
+        self
+    }
+
+    /// Searches again, more broadly, for answers `policy` escalates.
+    pub fn with_escalation(mut self, policy: Option<EscalationPolicy>) -> Self {
+        self.escalation = policy;
+        self
+    }
+
+    pub fn with_consistency(mut self, policy: ConsistencyPolicy) -> Self {
+        self.consistency = policy;
+        self
+    }
+
+    /// Lets sites be crawled into the corpus with `crawler`.
+    pub fn with_crawler(mut self, crawler: Option<Arc<dyn SiteCrawler>>) -> Self {
+        self.crawler = crawler;
+        self
+    }
+
+    /// Adds new entries of `subscriptions` to the corpus, reading the feeds
+    /// with `reader` every `poll_interval`.
+    pub fn with_feeds(
+        mut self,
+        reader: Option<Arc<dyn FeedReader>>,
+        subscriptions: Vec<FeedSubscription>,
+        poll_interval: Duration,
+    ) -> Self {
+        self.feed_reader = reader;
+        self.feed_subscriptions = subscriptions;
+        self.feed_poll_interval = poll_interval;
+        self
+    }
+
+    /// Polls the feeds of `workspace`, or of every workspace. `None` when
+    /// feeds are not read or there are none to poll.
+    pub fn feed_poller(&self, workspace: Option<&str>) -> Option<FeedPoller> {
+        let reader = self.feed_reader.as_ref()?;
+        let subscriptions: Vec<FeedSubscription> = self
+            .feed_subscriptions
+            .iter()
+            .filter(|feed| workspace.map_or(true, |w| feed.workspace == w))
+            .cloned()
+            .collect();
+        if subscriptions.is_empty() {
+            return None;
+        }
+
+        Some(
+            FeedPoller::new(
+                Arc::clone(&self.store),
+                Arc::clone(&self.embedder),
+                Arc::clone(reader),
+                subscriptions,
+            )
+            .with_interval(self.feed_poll_interval),
+        )
+    }
+
+    /// Expands collected sources with similar pages found by `provider`.
+    pub fn with_source_expansion(mut self, provider: Option<Arc<dyn SearchProvider>>) -> Self {
+        self.source_expansion = provider;
+        self
+    }
+
+    /// Enables capture of redacted prompts and raw LLM responses.
+    pub fn with_artifact_sink(mut self, sink: Option<Arc<dyn ArtifactSink>>) -> Self {
+        self.artifact_sink = sink;
+        self
+    }
+
+    /// Publishes job lifecycle events to other systems.
+    pub fn with_event_publisher(mut self, publisher: Option<Arc<dyn EventPublisher>>) -> Self {
+        self.event_publisher = publisher;
+        self
+    }
+
+    /// Runs the hooks of `plugins` in every job.
+    pub fn with_plugins(mut self, plugins: Vec<Arc<dyn PipelinePlugin>>) -> Self {
+        self.plugins = plugins;
+        self
+    }
+
+    /// Serves the timings of an
+    /// [`InstrumentedStore`](crate::store_metrics::InstrumentedStore) at
+    /// `/metrics`.
+    pub fn with_store_metrics(mut self, metrics: Arc<StoreMetrics>) -> Self {
+        self.store_metrics = Some(metrics);
+        self
+    }
+
+    /// Serves how many source contents object storage wrote and shared at
+    /// `/metrics`.
+    pub fn with_content_metrics(mut self, metrics: Option<Arc<ContentMetrics>>) -> Self {
+        self.content_metrics = metrics;
+        self
+    }
+
+    /// Limits how many jobs may be in flight at once.
+    pub fn with_job_queue(mut self, config: QueueConfig) -> Self {
+        self.job_queue = Arc::new(JobQueue::new(config));
+        self
+    }
+
+    /// Chooses whether pipelines run in this process or in workers.
+    pub fn with_job_execution(mut self, execution: JobExecution) -> Self {
+        self.job_execution = execution;
+        self
+    }
+
+    /// Retries failed inline jobs automatically, as `policy` allows.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
     pub fn uptime_seconds(&self) -> u64 {
         self.started_at.elapsed().as_secs()
     }
@@ -70,11 +434,91 @@ impl AppState {
             .llm_registry
             .default()
             .expect("LlmRegistry must have a default provider");
+        let llm_provider: Arc<dyn LlmProvider> = match self.llm_registry.shadow() {
+            Some(candidate) => Arc::new(ShadowLlmProvider::new(
+                llm_provider,
+                candidate,
+                Arc::clone(&self.shadow_metrics),
+            )),
+            None => llm_provider,
+        };
 
-        Pipeline::new(
+        let config = PipelineConfig {
+            moderation: self.moderation_policy,
+            length: self.length_policies.clone(),
+            routing: self.routing_policy.clone(),
+            outline: OutlinerConfig {
+                enabled: self.outline_reports,
+                ..Default::default()
+            },
+            facts: FactExtractorConfig {
+                enabled: self.knowledge_graph,
+                ..Default::default()
+            },
+            links: LinkCheckConfig {
+                policy: self.dead_links,
+                ..Default::default()
+            },
+            citation_markers: self.citation_markers,
+            freshness: self.freshness.clone(),
+            escalation: self.escalation.clone(),
+            consistency: self.consistency.clone(),
+            executor: ExecutorConfig {
+                content_limits: self.content_limits.clone(),
+                max_per_domain: self.max_per_domain,
+                domain_policy: self.domain_policy.clone(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let comparison_models = self
+            .available_llm_models()
+            .iter()
+            .filter_map(|model| self.llm_registry.get(model))
+            .collect::<Vec<_>>();
+        let pipeline = Pipeline::new(
             Arc::clone(&self.store),
             Arc::clone(&self.search_provider),
             llm_provider,
         )
+        .with_config(config)
+        .with_comparison_models(comparison_models)
+        .with_corpus(Arc::clone(&self.embedder))
+        .with_plugins(self.plugins.iter().cloned());
+
+        let pipeline = match self.llm_registry.fast() {
+            Some(fast) => pipeline.with_fast_model(fast),
+            None => pipeline,
+        };
+
+        let pipeline = match self.source_expansion {
+            Some(ref provider) => pipeline.with_source_expansion(Arc::clone(provider)),
+            None => pipeline,
+        };
+
+        let pipeline = match self.content_fetcher {
+            Some(ref fetcher) => pipeline.with_content_fetcher(Arc::clone(fetcher)),
+            None => pipeline,
+        };
+
+        let pipeline = match self.link_checker {
+            Some(ref checker) => pipeline.with_link_checker(Arc::clone(checker)),
+            None => pipeline,
+        };
+
+        let pipeline = match self.moderator {
+            Some(ref moderator) => pipeline.with_moderator(Arc::clone(moderator)),
+            None => pipeline,
+        };
+
+        let pipeline = match self.artifact_sink {
+            Some(ref sink) => pipeline.with_artifact_sink(Arc::clone(sink)),
+            None => pipeline,
+        };
+
+        match self.event_publisher {
+            Some(ref publisher) => pipeline.with_event_publisher(Arc::clone(publisher)),
+            None => pipeline,
+        }
     }
 }
@@ -0,0 +1,562 @@
+//! Timing of store operations.
+//!
+//! [`InstrumentedStore`] wraps any [`Store`] and records, per operation, how
+//! often it ran, how long it took, how many rows it read or wrote and how
+//! often it failed. Operations slower than the configured threshold are logged
+//! as warnings, so a degrading database shows up before jobs start timing out.
+//! `GET /metrics` serves the totals.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use gorkd_core::{
+    CorpusDocument, CorpusMatch, DocumentId, DomainTrust, Fact, FactQuery, Feedback, JobCursor,
+    JobEvent, JobId, JobSummary, JobSummaryFilter, LlmArtifact, ModelComparison, Project,
+    ProjectId, ResearchAnswer, ResearchJob, SearchArtifact, SearchFilters, SearchMetadata, Source,
+    StatusCount, Store, StoreError, StoreHealth, UsageStats, WorkerId,
+};
+
+/// Operations taking longer than this are logged when `STORE_SLOW_QUERY_MS`
+/// is unset.
+pub const DEFAULT_SLOW_THRESHOLD: Duration = Duration::from_millis(250);
+
+/// Reads `STORE_SLOW_QUERY_MS`.
+pub fn slow_threshold_from_env() -> Duration {
+    env::var("STORE_SLOW_QUERY_MS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_SLOW_THRESHOLD)
+}
+
+/// Totals for one store operation since startup.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OperationStats {
+    pub calls: u64,
+    pub errors: u64,
+    /// Calls that took at least the slow threshold.
+    pub slow: u64,
+    /// Rows read or written by successful calls.
+    pub rows: u64,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+impl OperationStats {
+    pub fn mean(&self) -> Duration {
+        match self.calls {
+            0 => Duration::ZERO,
+            calls => self.total / calls as u32,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct StoreMetrics {
+    slow_threshold: Duration,
+    operations: Mutex<BTreeMap<&'static str, OperationStats>>,
+}
+
+impl StoreMetrics {
+    pub fn new(slow_threshold: Duration) -> Self {
+        Self {
+            slow_threshold,
+            operations: Mutex::default(),
+        }
+    }
+
+    pub fn slow_threshold(&self) -> Duration {
+        self.slow_threshold
+    }
+
+    /// Totals of every operation that ran, by operation name.
+    pub fn snapshot(&self) -> Vec<(&'static str, OperationStats)> {
+        let operations = self.operations.lock().unwrap();
+        operations
+            .iter()
+            .map(|(name, stats)| (*name, stats.clone()))
+            .collect()
+    }
+
+    /// Records a call, returning whether it was slow.
+    fn record(&self, operation: &'static str, elapsed: Duration, rows: Option<usize>) -> bool {
+        let slow = elapsed >= self.slow_threshold;
+        let mut operations = self.operations.lock().unwrap();
+        let stats = operations.entry(operation).or_default();
+
+        stats.calls += 1;
+        match rows {
+            Some(rows) => stats.rows += rows as u64,
+            None => stats.errors += 1,
+        }
+        if slow {
+            stats.slow += 1;
+        }
+        stats.total += elapsed;
+        stats.max = stats.max.max(elapsed);
+        slow
+    }
+}
+
+/// A [`Store`] that records [`StoreMetrics`] for every call to the store it
+/// wraps.
+pub struct InstrumentedStore {
+    inner: Arc<dyn Store>,
+    metrics: Arc<StoreMetrics>,
+}
+
+impl InstrumentedStore {
+    pub fn new(inner: Arc<dyn Store>, slow_threshold: Duration) -> Self {
+        Self {
+            inner,
+            metrics: Arc::new(StoreMetrics::new(slow_threshold)),
+        }
+    }
+
+    pub fn metrics(&self) -> Arc<StoreMetrics> {
+        Arc::clone(&self.metrics)
+    }
+
+    /// Times `call`, counting `rows(&value)` rows when it succeeds.
+    async fn observe<T>(
+        &self,
+        operation: &'static str,
+        call: impl Future<Output = Result<T, StoreError>>,
+        rows: impl FnOnce(&T) -> usize,
+    ) -> Result<T, StoreError> {
+        let started = Instant::now();
+        let result = call.await;
+        let elapsed = started.elapsed();
+        let rows = result.as_ref().ok().map(rows);
+
+        let elapsed_ms = elapsed.as_millis() as u64;
+        if self.metrics.record(operation, elapsed, rows) {
+            tracing::warn!(
+                operation,
+                elapsed_ms,
+                rows,
+                threshold_ms = self.metrics.slow_threshold.as_millis() as u64,
+                "slow store operation"
+            );
+        } else {
+            tracing::debug!(operation, elapsed_ms, rows, "store operation");
+        }
+        if let Err(ref e) = result {
+            tracing::warn!(operation, elapsed_ms, error = %e, "store operation failed");
+        }
+
+        result
+    }
+}
+
+fn count<T>(option: &Option<T>) -> usize {
+    usize::from(option.is_some())
+}
+
+#[async_trait]
+impl Store for InstrumentedStore {
+    async fn create_job(&self, job: &ResearchJob) -> Result<(), StoreError> {
+        self.observe("create_job", self.inner.create_job(job), |_| 1)
+            .await
+    }
+
+    async fn get_job(&self, id: &JobId) -> Result<Option<ResearchJob>, StoreError> {
+        self.observe("get_job", self.inner.get_job(id), count).await
+    }
+
+    async fn update_job(&self, job: &ResearchJob) -> Result<(), StoreError> {
+        self.observe("update_job", self.inner.update_job(job), |_| 1)
+            .await
+    }
+
+    async fn list_jobs(&self, limit: usize, offset: usize) -> Result<Vec<ResearchJob>, StoreError> {
+        self.observe("list_jobs", self.inner.list_jobs(limit, offset), Vec::len)
+            .await
+    }
+
+    async fn list_jobs_before(
+        &self,
+        cursor: Option<&JobCursor>,
+        limit: usize,
+    ) -> Result<Vec<ResearchJob>, StoreError> {
+        self.observe(
+            "list_jobs_before",
+            self.inner.list_jobs_before(cursor, limit),
+            Vec::len,
+        )
+        .await
+    }
+
+    async fn list_jobs_by_tag(
+        &self,
+        tag: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<ResearchJob>, StoreError> {
+        self.observe(
+            "list_jobs_by_tag",
+            self.inner.list_jobs_by_tag(tag, limit, offset),
+            Vec::len,
+        )
+        .await
+    }
+
+    async fn list_job_summaries(
+        &self,
+        filter: &JobSummaryFilter,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<JobSummary>, StoreError> {
+        self.observe(
+            "list_job_summaries",
+            self.inner.list_job_summaries(filter, limit, offset),
+            Vec::len,
+        )
+        .await
+    }
+
+    async fn count_job_summaries(
+        &self,
+        filter: &JobSummaryFilter,
+    ) -> Result<Vec<StatusCount>, StoreError> {
+        self.observe(
+            "count_job_summaries",
+            self.inner.count_job_summaries(filter),
+            Vec::len,
+        )
+        .await
+    }
+
+    async fn usage_stats(&self, since: DateTime<Utc>) -> Result<UsageStats, StoreError> {
+        self.observe("usage_stats", self.inner.usage_stats(since), |stats| {
+            stats.jobs
+        })
+        .await
+    }
+
+    async fn create_project(&self, project: &Project) -> Result<(), StoreError> {
+        self.observe("create_project", self.inner.create_project(project), |_| 1)
+            .await
+    }
+
+    async fn get_project(&self, id: &ProjectId) -> Result<Option<Project>, StoreError> {
+        self.observe("get_project", self.inner.get_project(id), count)
+            .await
+    }
+
+    async fn attach_job(
+        &self,
+        project_id: &ProjectId,
+        job_id: &JobId,
+        max_jobs: usize,
+    ) -> Result<Project, StoreError> {
+        self.observe(
+            "attach_job",
+            self.inner.attach_job(project_id, job_id, max_jobs),
+            |_| 1,
+        )
+        .await
+    }
+
+    async fn record_facts(&self, facts: &[Fact]) -> Result<(), StoreError> {
+        self.observe("record_facts", self.inner.record_facts(facts), |_| {
+            facts.len()
+        })
+        .await
+    }
+
+    async fn find_facts(&self, query: &FactQuery) -> Result<Vec<Fact>, StoreError> {
+        self.observe("find_facts", self.inner.find_facts(query), Vec::len)
+            .await
+    }
+
+    async fn store_document(&self, document: &CorpusDocument) -> Result<(), StoreError> {
+        self.observe(
+            "store_document",
+            self.inner.store_document(document),
+            |_| document.chunks.len(),
+        )
+        .await
+    }
+
+    async fn list_documents(
+        &self,
+        workspace: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<CorpusDocument>, StoreError> {
+        self.observe(
+            "list_documents",
+            self.inner.list_documents(workspace, limit, offset),
+            Vec::len,
+        )
+        .await
+    }
+
+    async fn get_document(
+        &self,
+        workspace: &str,
+        id: &DocumentId,
+    ) -> Result<Option<CorpusDocument>, StoreError> {
+        self.observe(
+            "get_document",
+            self.inner.get_document(workspace, id),
+            |document| usize::from(document.is_some()),
+        )
+        .await
+    }
+
+    async fn find_document_by_url(
+        &self,
+        workspace: &str,
+        url: &str,
+    ) -> Result<Option<CorpusDocument>, StoreError> {
+        self.observe(
+            "find_document_by_url",
+            self.inner.find_document_by_url(workspace, url),
+            |document| usize::from(document.is_some()),
+        )
+        .await
+    }
+
+    async fn delete_document(&self, workspace: &str, id: &DocumentId) -> Result<bool, StoreError> {
+        self.observe(
+            "delete_document",
+            self.inner.delete_document(workspace, id),
+            |deleted| usize::from(*deleted),
+        )
+        .await
+    }
+
+    async fn search_corpus(
+        &self,
+        workspace: &str,
+        model: &str,
+        embedding: &[f32],
+        filters: &SearchFilters,
+        limit: usize,
+    ) -> Result<Vec<CorpusMatch>, StoreError> {
+        self.observe(
+            "search_corpus",
+            self.inner
+                .search_corpus(workspace, model, embedding, filters, limit),
+            Vec::len,
+        )
+        .await
+    }
+
+    async fn store_feedback(&self, feedback: &Feedback) -> Result<(), StoreError> {
+        self.observe("store_feedback", self.inner.store_feedback(feedback), |_| 1)
+            .await
+    }
+
+    async fn get_feedback(&self, job_id: &JobId) -> Result<Vec<Feedback>, StoreError> {
+        self.observe("get_feedback", self.inner.get_feedback(job_id), Vec::len)
+            .await
+    }
+
+    async fn domain_trust(&self) -> Result<Vec<DomainTrust>, StoreError> {
+        self.observe("domain_trust", self.inner.domain_trust(), Vec::len)
+            .await
+    }
+
+    async fn claim_next_job(
+        &self,
+        worker: &WorkerId,
+        lease: Duration,
+    ) -> Result<Option<ResearchJob>, StoreError> {
+        self.observe(
+            "claim_next_job",
+            self.inner.claim_next_job(worker, lease),
+            count,
+        )
+        .await
+    }
+
+    async fn heartbeat(
+        &self,
+        job_id: &JobId,
+        worker: &WorkerId,
+        lease: Duration,
+    ) -> Result<(), StoreError> {
+        self.observe(
+            "heartbeat",
+            self.inner.heartbeat(job_id, worker, lease),
+            |_| 1,
+        )
+        .await
+    }
+
+    async fn release(&self, job_id: &JobId, worker: &WorkerId) -> Result<(), StoreError> {
+        self.observe("release", self.inner.release(job_id, worker), |_| 1)
+            .await
+    }
+
+    async fn store_sources(&self, job_id: &JobId, sources: &[Source]) -> Result<(), StoreError> {
+        self.observe(
+            "store_sources",
+            self.inner.store_sources(job_id, sources),
+            |_| sources.len(),
+        )
+        .await
+    }
+
+    async fn get_sources(&self, job_id: &JobId) -> Result<Vec<Source>, StoreError> {
+        self.observe("get_sources", self.inner.get_sources(job_id), Vec::len)
+            .await
+    }
+
+    async fn store_search_metadata(
+        &self,
+        job_id: &JobId,
+        metadata: &SearchMetadata,
+    ) -> Result<(), StoreError> {
+        self.observe(
+            "store_search_metadata",
+            self.inner.store_search_metadata(job_id, metadata),
+            |_| 1,
+        )
+        .await
+    }
+
+    async fn get_search_metadata(
+        &self,
+        job_id: &JobId,
+    ) -> Result<Option<SearchMetadata>, StoreError> {
+        self.observe(
+            "get_search_metadata",
+            self.inner.get_search_metadata(job_id),
+            count,
+        )
+        .await
+    }
+
+    async fn store_answer(
+        &self,
+        job_id: &JobId,
+        answer: &ResearchAnswer,
+    ) -> Result<(), StoreError> {
+        self.observe(
+            "store_answer",
+            self.inner.store_answer(job_id, answer),
+            |_| 1,
+        )
+        .await
+    }
+
+    async fn get_answer(&self, job_id: &JobId) -> Result<Option<ResearchAnswer>, StoreError> {
+        self.observe("get_answer", self.inner.get_answer(job_id), count)
+            .await
+    }
+
+    async fn store_comparison(
+        &self,
+        job_id: &JobId,
+        comparison: &ModelComparison,
+    ) -> Result<(), StoreError> {
+        self.observe(
+            "store_comparison",
+            self.inner.store_comparison(job_id, comparison),
+            |_| comparison.answers.len(),
+        )
+        .await
+    }
+
+    async fn get_comparison(&self, job_id: &JobId) -> Result<Option<ModelComparison>, StoreError> {
+        self.observe("get_comparison", self.inner.get_comparison(job_id), count)
+            .await
+    }
+
+    async fn append_event(&self, event: JobEvent) -> Result<JobEvent, StoreError> {
+        self.observe("append_event", self.inner.append_event(event), |_| 1)
+            .await
+    }
+
+    async fn get_events(&self, job_id: &JobId) -> Result<Vec<JobEvent>, StoreError> {
+        self.observe("get_events", self.inner.get_events(job_id), Vec::len)
+            .await
+    }
+
+    async fn store_artifact(
+        &self,
+        job_id: &JobId,
+        artifact: &LlmArtifact,
+    ) -> Result<(), StoreError> {
+        self.observe(
+            "store_artifact",
+            self.inner.store_artifact(job_id, artifact),
+            |_| 1,
+        )
+        .await
+    }
+
+    async fn get_artifacts(&self, job_id: &JobId) -> Result<Vec<LlmArtifact>, StoreError> {
+        self.observe("get_artifacts", self.inner.get_artifacts(job_id), Vec::len)
+            .await
+    }
+
+    async fn store_search_artifact(
+        &self,
+        job_id: &JobId,
+        artifact: &SearchArtifact,
+    ) -> Result<(), StoreError> {
+        self.observe(
+            "store_search_artifact",
+            self.inner.store_search_artifact(job_id, artifact),
+            |_| 1,
+        )
+        .await
+    }
+
+    async fn get_search_artifacts(
+        &self,
+        job_id: &JobId,
+    ) -> Result<Vec<SearchArtifact>, StoreError> {
+        self.observe(
+            "get_search_artifacts",
+            self.inner.get_search_artifacts(job_id),
+            Vec::len,
+        )
+        .await
+    }
+
+    async fn acquire_content(&self, hash: &str) -> Result<u64, StoreError> {
+        self.observe("acquire_content", self.inner.acquire_content(hash), |_| 1)
+            .await
+    }
+
+    async fn release_content(&self, hash: &str) -> Result<u64, StoreError> {
+        self.observe("release_content", self.inner.release_content(hash), |_| 1)
+            .await
+    }
+
+    async fn health(&self) -> StoreHealth {
+        let health = self.inner.health().await;
+        let rows = health.reachable.then_some(0);
+        if self.metrics.record("health", health.latency, rows) {
+            tracing::warn!(
+                latency_ms = health.latency.as_millis() as u64,
+                "slow store health probe"
+            );
+        }
+        health
+    }
+
+    async fn find_similar(
+        &self,
+        embedding: &[f32],
+        threshold: f32,
+    ) -> Result<Option<JobId>, StoreError> {
+        self.observe(
+            "find_similar",
+            self.inner.find_similar(embedding, threshold),
+            count,
+        )
+        .await
+    }
+}
@@ -0,0 +1,231 @@
+//! Live job updates shared by the SSE and WebSocket routes.
+//!
+//! Both transports replay the job's persisted event log and then poll it for
+//! new entries, translating each into a [`StreamEvent`] until the job reaches
+//! a terminal state.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::stream::{self, Stream};
+use gorkd_core::{JobEvent, JobEventKind, JobId, JobProgress, TraceId};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::dto::{JobStatus, ProgressDetail, SourceDetail};
+use crate::state::AppState;
+
+/// How often the event log is checked for new entries.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Streams are closed with an error event after this long.
+const STREAM_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A single update pushed to SSE and WebSocket clients.
+///
+/// Over SSE the `event` tag doubles as the SSE event name; over WebSocket each
+/// text message is one serialized event.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum StreamEvent {
+    /// The job moved to a new pipeline stage.
+    Status {
+        stage: JobStatus,
+        #[schema(example = "Searching sources...")]
+        message: String,
+    },
+    /// The job's estimated progress moved on.
+    Progress { progress: ProgressDetail },
+    /// A source was collected for the answer.
+    SourceFound { source: Box<SourceDetail> },
+    /// A chunk of answer text. Providers do not stream yet, so the full
+    /// summary currently arrives as a single token event.
+    Token { text: String },
+    /// The job finished successfully.
+    Complete {
+        #[schema(example = "job_abc123xyz456")]
+        job_id: String,
+        #[schema(example = 14230)]
+        duration_ms: i64,
+    },
+    /// The job failed or the stream could not continue.
+    Error { message: String },
+}
+
+impl StreamEvent {
+    /// Event name used for the SSE `event:` field.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Status { .. } => "status",
+            Self::Progress { .. } => "progress",
+            Self::SourceFound { .. } => "source_found",
+            Self::Token { .. } => "token",
+            Self::Complete { .. } => "complete",
+            Self::Error { .. } => "error",
+        }
+    }
+
+    fn status(status: gorkd_core::JobStatus) -> Self {
+        let message = match status {
+            gorkd_core::JobStatus::Pending => "Job queued...",
+            gorkd_core::JobStatus::Planning => "Analyzing query...",
+            gorkd_core::JobStatus::Searching => "Searching sources...",
+            gorkd_core::JobStatus::Fetching => "Fetching pages...",
+            gorkd_core::JobStatus::Synthesizing => "Generating answer...",
+            gorkd_core::JobStatus::Completed => "Research complete",
+            gorkd_core::JobStatus::Failed => "Research failed",
+            _ => "Working...",
+        };
+
+        Self::Status {
+            stage: status.into(),
+            message: message.to_string(),
+        }
+    }
+
+    fn is_terminal(&self) -> bool {
+        matches!(self, Self::Complete { .. } | Self::Error { .. })
+    }
+}
+
+/// A [`StreamEvent`] tagged with the job's trace ID, as sent to clients.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TracedStreamEvent {
+    #[schema(example = "trc_abc123xyz456")]
+    pub trace_id: String,
+    #[serde(flatten)]
+    pub event: StreamEvent,
+}
+
+impl TracedStreamEvent {
+    pub fn new(trace_id: &TraceId, event: StreamEvent) -> Self {
+        Self {
+            trace_id: trace_id.to_string(),
+            event,
+        }
+    }
+}
+
+struct Cursor {
+    state: Arc<AppState>,
+    job_id: JobId,
+    seen: usize,
+    progress: JobProgress,
+    pending: VecDeque<StreamEvent>,
+    started: Instant,
+    finished: bool,
+}
+
+/// Streams a job's updates from the beginning, ending after a `complete` or
+/// `error` event. Clients connecting late receive the full history first.
+pub fn job_stream(
+    state: Arc<AppState>,
+    job_id: JobId,
+) -> impl Stream<Item = StreamEvent> + Send + 'static {
+    let cursor = Cursor {
+        state,
+        job_id,
+        seen: 0,
+        progress: JobProgress::default(),
+        // Jobs start pending, which is not itself a recorded stage change.
+        pending: VecDeque::from([StreamEvent::status(gorkd_core::JobStatus::Pending)]),
+        started: Instant::now(),
+        finished: false,
+    };
+
+    stream::unfold(cursor, |mut cursor| async move {
+        loop {
+            if let Some(event) = cursor.pending.pop_front() {
+                if event.is_terminal() {
+                    cursor.pending.clear();
+                    cursor.finished = true;
+                }
+                return Some((event, cursor));
+            }
+
+            if cursor.finished {
+                return None;
+            }
+
+            if cursor.started.elapsed() >= STREAM_TIMEOUT {
+                cursor.pending.push_back(StreamEvent::Error {
+                    message: "stream timed out".to_string(),
+                });
+                continue;
+            }
+
+            if let Err(e) = cursor.poll().await {
+                cursor.pending.push_back(StreamEvent::Error {
+                    message: e.to_string(),
+                });
+                continue;
+            }
+
+            if cursor.pending.is_empty() {
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        }
+    })
+}
+
+impl Cursor {
+    async fn poll(&mut self) -> Result<(), gorkd_core::StoreError> {
+        let events = self.state.store.get_events(&self.job_id).await?;
+        let new_events: Vec<JobEvent> = events.into_iter().skip(self.seen).collect();
+        self.seen += new_events.len();
+
+        for event in new_events {
+            self.translate(event).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn translate(&mut self, event: JobEvent) -> Result<(), gorkd_core::StoreError> {
+        let percent = self.progress.percent;
+        self.progress.apply(&event.kind);
+        if self.progress.percent != percent {
+            self.pending.push_back(StreamEvent::Progress {
+                progress: self.progress.clone().into(),
+            });
+        }
+
+        match event.kind {
+            JobEventKind::StageChanged {
+                status: gorkd_core::JobStatus::Completed,
+            } => {
+                if let Some(answer) = self.state.store.get_answer(&self.job_id).await? {
+                    self.pending.push_back(StreamEvent::Token {
+                        text: answer.summary,
+                    });
+                }
+
+                let duration_ms = match self.state.store.get_job(&self.job_id).await? {
+                    Some(job) => (event.timestamp - job.created_at).num_milliseconds(),
+                    None => 0,
+                };
+                self.pending.push_back(StreamEvent::Complete {
+                    job_id: self.job_id.to_string(),
+                    duration_ms,
+                });
+            }
+            JobEventKind::StageChanged { status } => {
+                self.pending.push_back(StreamEvent::status(status));
+            }
+            JobEventKind::SourcesCollected { .. } => {
+                let sources = self.state.store.get_sources(&self.job_id).await?;
+                self.pending
+                    .extend(sources.into_iter().map(|source| StreamEvent::SourceFound {
+                        source: Box::new(source.into()),
+                    }));
+            }
+            JobEventKind::Failed { message } => {
+                self.pending.push_back(StreamEvent::Error { message });
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
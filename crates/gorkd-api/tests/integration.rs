@@ -1,9 +1,23 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
 use axum_test::TestServer;
-use gorkd_api::{app, AppState};
-use gorkd_core::{MockLlmProvider, MockSearchProvider, MockStore};
+use gorkd_api::artifacts::{ArtifactCapture, DirectoryArtifactSink};
+use gorkd_api::config_check::{self, ConfigIssue, ConfigReport, ConfigSummary, Severity};
+use gorkd_api::execution::JobExecution;
+use gorkd_api::queue::QueueConfig;
+use gorkd_api::store_metrics::InstrumentedStore;
+use gorkd_api::{app, self_test, AppState};
+use gorkd_core::{
+    ArtifactSink, Confidence, CrawledPage, DeadLinkPolicy, DocumentFormat, DomainPolicy, FeedItem,
+    FeedSubscription, FreshnessPolicy, JobId, LlmError, MockCrawler, MockEventPublisher,
+    MockFeedReader, MockLinkChecker, MockLlmProvider, MockLlmStep, MockModerator,
+    MockSearchProvider, MockStore, ModerationPolicy, ResearchJob, ResearchProfiles, RetryPolicy,
+    RoutingPolicy, Source, Store, Worker, WorkerConfig,
+};
+use gorkd_llm::LlmRegistry;
+use gorkd_search::ProviderRegistry;
 use serde_json::{json, Value};
 
 fn create_test_app() -> TestServer {
@@ -17,184 +31,3123 @@ fn create_test_app() -> TestServer {
     TestServer::new(app).unwrap()
 }
 
+/// Builds a server whose store records metrics, counting operations at least
+/// `slow_threshold` long as slow.
+fn create_instrumented_app(slow_threshold: Duration) -> TestServer {
+    let store = InstrumentedStore::new(Arc::new(MockStore::new()), slow_threshold);
+    let metrics = store.metrics();
+    let state = AppState::new(
+        Arc::new(store),
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(MockLlmProvider::new("mock-gpt-4")),
+    )
+    .with_store_metrics(metrics);
+
+    TestServer::new(app(Arc::new(state))).unwrap()
+}
+
+/// Builds a server whose store already holds a job with the given sources.
+async fn create_app_with_sources(sources: Vec<Source>) -> (TestServer, String) {
+    let store = Arc::new(MockStore::new());
+    let job = ResearchJob::new("What is Rust?").unwrap();
+    store.create_job(&job).await.unwrap();
+    store.store_sources(&job.id, &sources).await.unwrap();
+
+    let state = AppState::new(
+        store,
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(MockLlmProvider::new("mock-gpt-4")),
+    );
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    (server, job.id.to_string())
+}
+
+fn scored_source(url: &str, score: f32) -> Source {
+    Source::new(url, "Title", "Content").with_relevance_score(score)
+}
+
+async fn wait_for_terminal_job(server: &TestServer, job_id: &str) -> Value {
+    for _ in 0..40 {
+        tokio::time::sleep(Duration::from_millis(25)).await;
+
+        let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+        if job["status"] == "completed" || job["status"] == "failed" {
+            return job;
+        }
+    }
+
+    panic!("job {} did not reach a terminal state", job_id);
+}
+
 #[cfg(feature = "integration")]
 fn create_real_provider_app() -> Option<TestServer> {
     use gorkd_llm::{LlmConfig, LlmRegistry};
     use gorkd_search::{ProviderRegistry, SearchConfig};
 
     let search_config = SearchConfig::from_env().ok()?;
-    let search_registry = ProviderRegistry::from_config(&search_config);
+    let search_http = search_config.http_client().ok()?;
+    let search_registry = ProviderRegistry::from_config(&search_config, &search_http);
 
     if search_registry.is_empty() {
         return None;
     }
 
-    let llm_config = LlmConfig::from_env();
-    if !llm_config.has_provider() {
-        return None;
-    }
+    let llm_config = LlmConfig::from_env();
+    if !llm_config.has_provider() {
+        return None;
+    }
+
+    let http = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(60))
+        .build()
+        .expect("failed to create HTTP client");
+
+    let llm_registry = LlmRegistry::from_config(http, &llm_config);
+
+    let store = Arc::new(MockStore::new());
+    let state = Arc::new(AppState::with_registries(
+        store,
+        search_registry,
+        llm_registry,
+    ));
+
+    Some(TestServer::new(app(state)).unwrap())
+}
+
+#[tokio::test]
+async fn test_health_check() {
+    let server = create_test_app();
+
+    let response = server.get("/health").await;
+
+    response.assert_status_ok();
+    let body: Value = response.json();
+    assert_eq!(body["status"], "healthy");
+    assert!(body["version"].as_str().is_some());
+    assert_eq!(body["store"]["reachable"], true);
+    assert!(body["store"].get("error").is_none());
+}
+
+#[tokio::test]
+async fn test_store_metrics_count_operations() {
+    let server = create_instrumented_app(Duration::from_secs(10));
+    let job_id = run_to_completion(&server, json!({"query": "What is Rust?"})).await;
+    server.get(&format!("/v1/jobs/{}/sources", job_id)).await;
+
+    let body: Value = server.get("/metrics").await.json();
+    assert_eq!(body["store"]["slow_threshold_ms"], 10_000);
+    let operations = body["store"]["operations"].as_array().unwrap();
+    let operation = |name: &str| {
+        operations
+            .iter()
+            .find(|o| o["operation"] == name)
+            .unwrap_or_else(|| panic!("no metrics for {}", name))
+    };
+    assert_eq!(operation("create_job")["calls"], 1);
+    assert_eq!(operation("create_job")["rows"], 1);
+    assert!(operation("get_job")["calls"].as_u64().unwrap() >= 2);
+    assert_eq!(operation("get_sources")["errors"], 0);
+    assert_eq!(operation("get_sources")["slow"], 0);
+    assert_eq!(
+        operation("get_sources")["rows"],
+        operation("store_sources")["rows"]
+    );
+}
+
+#[tokio::test]
+async fn test_metrics_report_shadow_candidates() {
+    let store = InstrumentedStore::new(Arc::new(MockStore::new()), Duration::from_secs(10));
+    let metrics = store.metrics();
+    let mut search = ProviderRegistry::new();
+    search.register(
+        "mock-tavily",
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+    );
+    search.register(
+        "mock-exa",
+        Arc::new(MockSearchProvider::new("mock-exa").with_result_count(4)),
+    );
+    assert!(search.set_shadow("mock-exa"));
+    let llm = LlmRegistry::builder()
+        .register("mock-gpt-4", Arc::new(MockLlmProvider::new("mock-gpt-4")))
+        .register("mock-claude", Arc::new(MockLlmProvider::new("mock-claude")))
+        .default_model("mock-gpt-4")
+        .shadow_model("mock-claude")
+        .build();
+    let state = AppState::with_registries(Arc::new(store), search, llm).with_store_metrics(metrics);
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    let job_id = run_to_completion(&server, json!({"query": "What is Rust?"})).await;
+
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert_eq!(job["answer"]["model"], "mock-gpt-4");
+    let mut shadow = Vec::new();
+    for _ in 0..100 {
+        let body: Value = server.get("/metrics").await.json();
+        shadow = body["shadow"].as_array().cloned().unwrap_or_default();
+        if shadow.len() == 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(shadow.len(), 2);
+    assert_eq!(
+        (&shadow[0]["kind"], &shadow[0]["candidate"]),
+        (&json!("search"), &json!("mock-exa"))
+    );
+    assert_eq!(shadow[0]["candidate_errors"], 0);
+    assert_eq!(shadow[0]["mean_overlap"], 0.0);
+    assert_eq!(
+        (&shadow[1]["kind"], &shadow[1]["primary"]),
+        (&json!("llm"), &json!("mock-gpt-4"))
+    );
+    assert!(shadow[1]["calls"].as_u64().unwrap() >= 1);
+}
+
+#[tokio::test]
+async fn test_slow_store_degrades_health() {
+    let server = create_instrumented_app(Duration::ZERO);
+
+    let response = server.get("/health").await;
+
+    response.assert_status_ok();
+    let body: Value = response.json();
+    assert_eq!(body["status"], "degraded");
+
+    let metrics: Value = server.get("/metrics").await.json();
+    let health = &metrics["store"]["operations"][0];
+    assert_eq!(health["operation"], "health");
+    assert_eq!(health["slow"], 1);
+}
+
+#[tokio::test]
+async fn test_metrics_report_llm_concurrency() {
+    use gorkd_llm::{ConcurrencyLimits, LlmLimiter, LlmRegistry};
+    use gorkd_search::ProviderRegistry;
+
+    let store = InstrumentedStore::new(Arc::new(MockStore::new()), Duration::from_secs(10));
+    let metrics = store.metrics();
+    let mut search_registry = ProviderRegistry::new();
+    search_registry.register("mock", Arc::new(MockSearchProvider::new("mock-tavily")));
+    let limits = ConcurrencyLimits::default()
+        .with_overall(8)
+        .with_per_provider(2);
+    let llm_registry = LlmRegistry::builder()
+        .register("mock-gpt-4", Arc::new(MockLlmProvider::new("mock-gpt-4")))
+        .limiter(Arc::new(LlmLimiter::new(limits)))
+        .build();
+    let state = AppState::with_registries(Arc::new(store), search_registry, llm_registry)
+        .with_store_metrics(metrics);
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    run_to_completion(&server, json!({"query": "What is Rust?"})).await;
+
+    let body: Value = server.get("/metrics").await.json();
+    assert_eq!(body["llm"]["overall"]["limit"], 8);
+    assert_eq!(body["llm"]["overall"]["in_flight"], 0);
+    let provider = &body["llm"]["providers"][0];
+    assert_eq!(provider["provider"], "mock");
+    assert_eq!(provider["limit"], 2);
+    assert_eq!(provider["waiting"], 0);
+    assert!(create_instrumented_app(Duration::from_secs(10))
+        .get("/metrics")
+        .await
+        .json::<Value>()
+        .get("llm")
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_metrics_disabled_without_instrumented_store() {
+    let server = create_test_app();
+
+    server.get("/metrics").await.assert_status_not_found();
+}
+
+#[tokio::test]
+async fn test_research_happy_path() {
+    let server = create_test_app();
+
+    let response = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?"}))
+        .await;
+
+    response.assert_status(axum::http::StatusCode::ACCEPTED);
+
+    let body: Value = response.json();
+    let job_id = body["job_id"].as_str().unwrap();
+    assert!(job_id.starts_with("job_"));
+    assert_eq!(body["status"], "pending");
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let response = server.get(&format!("/v1/jobs/{}", job_id)).await;
+    response.assert_status_ok();
+
+    let job: Value = response.json();
+    assert_eq!(job["job_id"], job_id);
+    assert!([
+        "pending",
+        "planning",
+        "searching",
+        "synthesizing",
+        "completed"
+    ]
+    .contains(&job["status"].as_str().unwrap()));
+}
+
+#[tokio::test]
+async fn test_invalid_query_empty() {
+    let server = create_test_app();
+
+    let response = server
+        .post("/v1/research")
+        .json(&json!({"query": ""}))
+        .await;
+
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+
+    let body: Value = response.json();
+    assert!(body["error"]["code"].as_str().is_some());
+}
+
+#[tokio::test]
+async fn test_invalid_query_too_long() {
+    let server = create_test_app();
+
+    let long_query = "x".repeat(2001);
+    let response = server
+        .post("/v1/research")
+        .json(&json!({"query": long_query}))
+        .await;
+
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_job_not_found() {
+    let server = create_test_app();
+
+    let response = server.get("/v1/jobs/job_abcdef123456").await;
+
+    response.assert_status(axum::http::StatusCode::NOT_FOUND);
+
+    let body: Value = response.json();
+    assert_eq!(body["error"]["code"], "not_found");
+}
+
+#[tokio::test]
+async fn test_invalid_job_id_format() {
+    let server = create_test_app();
+
+    let response = server.get("/v1/jobs/invalid-format").await;
+
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_tool_schema_describes_research_calls() {
+    let server = create_test_app();
+
+    let schema: Value = server.get("/v1/tool-schema").await.json();
+
+    assert_eq!(schema["name"], "gorkd_research");
+    assert_eq!(schema["openai"]["type"], "function");
+    assert_eq!(
+        schema["openai"]["function"]["parameters"],
+        schema["parameters"]
+    );
+    assert_eq!(
+        schema["invocation"]["poll_until"],
+        json!(["completed", "failed"])
+    );
+    let properties = schema["parameters"]["properties"].as_object().unwrap();
+    assert!(properties.contains_key("query"));
+    assert!(!properties.contains_key("metadata"));
+    assert!(schema["returns"]["properties"]["summary"].is_object());
+
+    // Arguments an agent fills in from the schema are a valid request.
+    let arguments = json!({"query": "What is Rust?", "depth": "tldr", "language": "en"});
+    let response = server.post("/v1/research").json(&arguments).await;
+    response.assert_status(axum::http::StatusCode::ACCEPTED);
+}
+
+#[tokio::test]
+async fn test_get_sources() {
+    let server = create_test_app();
+
+    let response = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?"}))
+        .await;
+
+    let body: Value = response.json();
+    let job_id = body["job_id"].as_str().unwrap();
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let response = server.get(&format!("/v1/jobs/{}/sources", job_id)).await;
+    response.assert_status_ok();
+
+    let body: Value = response.json();
+    assert!(body["sources"].is_array());
+}
+
+#[tokio::test]
+async fn test_sources_carry_snippets_with_query_terms() {
+    let server = create_test_app();
+    let job_id = run_to_completion(&server, json!({"query": "Is Rust memory safe?"})).await;
+
+    let body: Value = server
+        .get(&format!("/v1/jobs/{}/sources", job_id))
+        .await
+        .json();
+
+    let source = &body["sources"][0];
+    let snippet = &source["snippet"];
+    let text: Vec<char> = snippet["text"].as_str().unwrap().chars().collect();
+    let matches = snippet["matches"].as_array().unwrap();
+    let terms: Vec<&str> = matches
+        .iter()
+        .map(|m| m["term"].as_str().unwrap())
+        .collect();
+    assert_eq!(terms, vec!["rust", "memory", "safe"]);
+    for m in matches {
+        let (start, end) = (m["start"].as_u64().unwrap(), m["end"].as_u64().unwrap());
+        let matched: String = text[start as usize..end as usize].iter().collect();
+        assert_eq!(matched.to_lowercase(), m["term"].as_str().unwrap());
+    }
+    assert!(source["content"].is_null());
+}
+
+#[tokio::test]
+async fn test_sources_only_jobs_complete_without_an_answer() {
+    let server = create_test_app();
+    let job_id = run_to_completion(
+        &server,
+        json!({"query": "What is Rust?", "sources_only": true}),
+    )
+    .await;
+
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert_eq!(job["sources_only"], true);
+    assert!(job["answer"].is_null());
+    let body: Value = server
+        .get(&format!("/v1/jobs/{}/events", job_id))
+        .await
+        .json();
+    let events = body["events"].as_array().unwrap();
+    assert!(!events
+        .iter()
+        .any(|e| e["data"]["status"] == "synthesizing" || e["type"] == "answer_synthesized"));
+
+    let sources: Value = server
+        .get(&format!("/v1/jobs/{}/sources", job_id))
+        .await
+        .json();
+    assert!(!sources["sources"].as_array().unwrap().is_empty());
+
+    let response = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?", "sources_only": true, "extract_entities": true}))
+        .await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+    let body: Value = response.json();
+    assert!(body["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("extract_entities"));
+}
+
+#[tokio::test]
+async fn test_sources_include_search_metadata() {
+    let server = create_test_app();
+
+    let body: Value = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?"}))
+        .await
+        .json();
+    let job_id = body["job_id"].as_str().unwrap();
+
+    wait_for_terminal_job(&server, job_id).await;
+
+    let body: Value = server
+        .get(&format!("/v1/jobs/{}/sources", job_id))
+        .await
+        .json();
+
+    assert_eq!(body["search"]["providers_used"], json!(["mock-tavily"]));
+    assert!(!body["search"]["queries_executed"]
+        .as_array()
+        .unwrap()
+        .is_empty());
+    assert!(body["sources"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|s| s["provider"] == "mock-tavily"));
+}
+
+#[tokio::test]
+async fn test_pipeline_completes() {
+    let server = create_test_app();
+
+    let response = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust programming language?"}))
+        .await;
+
+    let body: Value = response.json();
+    let job_id = body["job_id"].as_str().unwrap();
+
+    let mut completed = false;
+    for _ in 0..20 {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let response = server.get(&format!("/v1/jobs/{}", job_id)).await;
+        let job: Value = response.json();
+
+        if job["status"] == "completed" {
+            completed = true;
+            break;
+        }
+        if job["status"] == "failed" {
+            panic!("Pipeline failed: {:?}", job["error_message"]);
+        }
+    }
+
+    assert!(completed, "Pipeline did not complete within timeout");
+}
+
+#[tokio::test]
+async fn test_completed_job_includes_answer() {
+    let server = create_test_app();
+
+    let body: Value = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?"}))
+        .await
+        .json();
+    let job_id = body["job_id"].as_str().unwrap();
+
+    let job = wait_for_terminal_job(&server, job_id).await;
+
+    assert_eq!(job["status"], "completed");
+    assert!(job["answer"]["summary"].as_str().is_some());
+    assert_eq!(job["answer"]["confidence"], "high");
+    assert!(job["answer"]["moderation"].is_null());
+    assert!(job["answer"].get("structured").is_none());
+}
+
+#[tokio::test]
+async fn test_answer_suggests_followups() {
+    let raw = json!({
+        "summary": "Rust is a systems language.",
+        "detail": "Rust focuses on memory safety.",
+        "suggested_followups": ["Who maintains Rust?", "How fast is Rust compared to C++?"]
+    });
+    let state = AppState::new(
+        Arc::new(MockStore::new()),
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(
+            MockLlmProvider::new("mock-gpt-4").with_script([MockLlmStep::Raw(raw.to_string())]),
+        ),
+    );
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    let job_id = run_to_completion(&server, json!({"query": "What is Rust?"})).await;
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert_eq!(
+        job["answer"]["suggested_followups"],
+        json!(["Who maintains Rust?", "How fast is Rust compared to C++?"])
+    );
+
+    let job_id = run_to_completion(&server, json!({"query": "What is Rust?"})).await;
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert_eq!(job["answer"]["suggested_followups"], json!([]));
+}
+
+#[tokio::test]
+async fn test_answer_lists_key_entities() {
+    let entities = json!({"entities": [
+        {"name": "Mozilla", "kind": "organization", "description": "Rust's first sponsor.", "sources": [1]},
+        {"name": "Cargo", "kind": "technology", "description": "Rust's build tool.", "sources": [9]}
+    ]});
+    let state = AppState::new(
+        Arc::new(MockStore::new()),
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(
+            MockLlmProvider::new("mock-gpt-4")
+                .with_script([MockLlmStep::Succeed, MockLlmStep::Raw(entities.to_string())]),
+        ),
+    );
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    let job_id = run_to_completion(
+        &server,
+        json!({"query": "What is Rust?", "extract_entities": true}),
+    )
+    .await;
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert_eq!(job["extract_entities"], true);
+    let key_entities = job["answer"]["key_entities"].as_array().unwrap();
+    assert_eq!(key_entities.len(), 1);
+    assert_eq!(key_entities[0]["name"], "Mozilla");
+    assert_eq!(key_entities[0]["kind"], "organization");
+    assert_eq!(
+        key_entities[0]["source_ids"][0],
+        job["answer"]["citations"][0]["source_id"]
+    );
+    let stages = job["answer"]["token_usage"]["stages"].as_array().unwrap();
+    assert!(stages.iter().any(|s| s["stage"] == "extraction"));
+
+    let job_id = run_to_completion(&server, json!({"query": "What is Rust?"})).await;
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert_eq!(job["extract_entities"], false);
+    assert!(job["answer"].get("key_entities").is_none());
+}
+
+#[tokio::test]
+async fn test_knowledge_base_accumulates_facts_from_jobs() {
+    let facts = json!({"facts": [
+        {"subject": "Rust", "relation": "was started at", "object": "Mozilla", "sources": [1, 2]},
+        {"subject": "Rust", "relation": "is compiled by", "object": "rustc", "sources": [3]}
+    ]});
+    let state = AppState::new(
+        Arc::new(MockStore::new()),
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(
+            MockLlmProvider::new("mock-gpt-4")
+                .with_script([MockLlmStep::Succeed, MockLlmStep::Raw(facts.to_string())]),
+        ),
+    )
+    .with_knowledge_graph(true);
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    let job_id = run_to_completion(&server, json!({"query": "What is Rust?"})).await;
+
+    let body: Value = server.get("/v1/knowledge").await.json();
+    let facts = body["facts"].as_array().unwrap();
+    assert_eq!(facts.len(), 2);
+    assert_eq!(facts[0]["object"], "Mozilla");
+    assert_eq!(facts[0]["sources"].as_array().unwrap().len(), 2);
+    assert_eq!(facts[0]["sources"][0]["job_id"], job_id.as_str());
+
+    let body: Value = server
+        .get("/v1/knowledge")
+        .add_query_param("relation", "COMPILED")
+        .add_query_param("job_id", &job_id)
+        .await
+        .json();
+    assert_eq!(body["facts"].as_array().unwrap().len(), 1);
+    assert_eq!(body["facts"][0]["object"], "rustc");
+
+    server
+        .get("/v1/knowledge")
+        .add_query_param("subject", " ")
+        .await
+        .assert_status_bad_request();
+    server
+        .get("/v1/knowledge")
+        .add_query_param("limit", "0")
+        .await
+        .assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_corpus_documents_are_searched_alongside_the_web() {
+    let server = create_test_app();
+
+    let response = server
+        .post("/v1/corpus/documents")
+        .json(&json!({
+            "title": "Rust style guide",
+            "content": "Our Rust services use the 2021 edition and forbid unsafe code.",
+            "url": "https://wiki.corp.example/rust"
+        }))
+        .await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+    let document: Value = response.json();
+    let document_id = document["document_id"].as_str().unwrap().to_string();
+    assert!(document_id.starts_with("doc_"));
+    assert_eq!(document["chunks"], 1);
+    assert_eq!(document["embedding_model"], "hashing");
+
+    let body: Value = server.get("/v1/corpus/documents").await.json();
+    assert_eq!(body["documents"].as_array().unwrap().len(), 1);
+    assert_eq!(body["documents"][0]["title"], "Rust style guide");
+
+    let job_id = run_to_completion(
+        &server,
+        json!({"query": "What is Rust?", "include_corpus": true}),
+    )
+    .await;
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert_eq!(job["include_corpus"], true);
+    let body: Value = server
+        .get(&format!("/v1/jobs/{}/sources", job_id))
+        .await
+        .json();
+    let urls: Vec<&str> = body["sources"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["url"].as_str().unwrap())
+        .collect();
+    assert!(urls.contains(&"https://wiki.corp.example/rust"));
+    assert!(urls.contains(&"https://example.com/article-1"));
+
+    let job_id = run_to_completion(&server, json!({"query": "What is Rust?"})).await;
+    let body: Value = server
+        .get(&format!("/v1/jobs/{}/sources", job_id))
+        .await
+        .json();
+    assert!(body["sources"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|s| s["url"] != "https://wiki.corp.example/rust"));
+
+    server
+        .delete(&format!("/v1/corpus/documents/{}", document_id))
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+    server
+        .delete(&format!("/v1/corpus/documents/{}", document_id))
+        .await
+        .assert_status_not_found();
+    server
+        .post("/v1/corpus/documents")
+        .json(&json!({"title": "Empty", "content": "  "}))
+        .await
+        .assert_status_bad_request();
+    server
+        .post("/v1/corpus/documents")
+        .json(&json!({"title": "Bad URL", "content": "Text", "url": "ftp://files"}))
+        .await
+        .assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_corpus_upload_extracts_text_from_files() {
+    let server = create_test_app();
+
+    let response = server
+        .post("/v1/corpus/documents/upload")
+        .add_query_param("title", "Runbook")
+        .content_type("text/markdown")
+        .bytes("# Deploys\n\nRun **cargo build** before every deploy.".into())
+        .await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+    let document: Value = response.json();
+    assert_eq!(document["format"], "markdown");
+    assert_eq!(document["workspace"], "default");
+    let document_id = document["document_id"].as_str().unwrap().to_string();
+
+    let body: Value = server
+        .get(&format!("/v1/corpus/documents/{}", document_id))
+        .await
+        .json();
+    assert_eq!(body["title"], "Runbook");
+    assert!(body["content"].as_str().unwrap().contains("cargo build"));
+
+    let response = server
+        .post("/v1/corpus/documents/upload")
+        .add_query_param("title", "Notes")
+        .add_query_param("url", "https://wiki.corp.example/notes.txt")
+        .bytes("Plain notes.".into())
+        .await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+    assert_eq!(response.json::<Value>()["format"], "plain_text");
+
+    server
+        .post("/v1/corpus/documents/upload")
+        .add_query_param("title", "Archive")
+        .content_type("application/zip")
+        .bytes(vec![0x50, 0x4b, 0x03, 0x04].into())
+        .await
+        .assert_status_bad_request();
+    server
+        .post("/v1/corpus/documents/upload")
+        .content_type("text/plain")
+        .bytes("No title".into())
+        .await
+        .assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_corpus_workspaces_are_isolated() {
+    let server = create_test_app();
+
+    let response = server
+        .post("/v1/corpus/documents")
+        .add_header("X-Gorkd-Workspace", "team-a")
+        .json(&json!({
+            "title": "Team A handbook",
+            "content": "Rust services at team A forbid unsafe code.",
+            "url": "https://wiki.corp.example/team-a"
+        }))
+        .await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+    let document: Value = response.json();
+    assert_eq!(document["workspace"], "team-a");
+    let document_id = document["document_id"].as_str().unwrap().to_string();
+
+    let body: Value = server.get("/v1/corpus/documents").await.json();
+    assert!(body["documents"].as_array().unwrap().is_empty());
+    let body: Value = server
+        .get("/v1/corpus/documents")
+        .add_header("X-Gorkd-Workspace", "team-a")
+        .await
+        .json();
+    assert_eq!(body["documents"].as_array().unwrap().len(), 1);
+    server
+        .get(&format!("/v1/corpus/documents/{}", document_id))
+        .add_header("X-Gorkd-Workspace", "team-b")
+        .await
+        .assert_status_not_found();
+    server
+        .delete(&format!("/v1/corpus/documents/{}", document_id))
+        .await
+        .assert_status_not_found();
+
+    let sources = |body: Value| -> Vec<String> {
+        body["sources"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| s["url"].as_str().unwrap().to_string())
+            .collect()
+    };
+    let response = server
+        .post("/v1/research")
+        .add_header("X-Gorkd-Workspace", "team-a")
+        .json(&json!({"query": "What is Rust?", "include_corpus": true}))
+        .await;
+    response.assert_status(axum::http::StatusCode::ACCEPTED);
+    let job_id = response.json::<Value>()["job_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let job = wait_for_terminal_job(&server, &job_id).await;
+    assert_eq!(job["status"], "completed");
+    assert_eq!(job["workspace"], "team-a");
+    let body: Value = server
+        .get(&format!("/v1/jobs/{}/sources", job_id))
+        .await
+        .json();
+    assert!(sources(body).contains(&"https://wiki.corp.example/team-a".to_string()));
+
+    let job_id = run_to_completion(
+        &server,
+        json!({"query": "What is Rust?", "include_corpus": true}),
+    )
+    .await;
+    let body: Value = server
+        .get(&format!("/v1/jobs/{}/sources", job_id))
+        .await
+        .json();
+    assert!(!sources(body).contains(&"https://wiki.corp.example/team-a".to_string()));
+
+    server
+        .get("/v1/corpus/documents")
+        .add_header("X-Gorkd-Workspace", "team a")
+        .await
+        .assert_status_bad_request();
+    server
+        .delete(&format!("/v1/corpus/documents/{}", document_id))
+        .add_header("X-Gorkd-Workspace", "team-a")
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+}
+
+fn create_crawling_app(crawler: MockCrawler) -> (TestServer, Arc<MockCrawler>) {
+    let crawler = Arc::new(crawler);
+    let state = AppState::new(
+        Arc::new(MockStore::new()),
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(MockLlmProvider::new("mock-gpt-4")),
+    )
+    .with_crawler(Some(crawler.clone()));
+
+    (TestServer::new(app(Arc::new(state))).unwrap(), crawler)
+}
+
+#[tokio::test]
+async fn test_crawled_sites_answer_corpus_only_research() {
+    let crawler = MockCrawler::new()
+        .with_page(CrawledPage::new(
+            "https://docs.example.com/install",
+            "Installing the CLI",
+            DocumentFormat::Html,
+            "Install the CLI with cargo install, then run it from any project.",
+        ))
+        .with_page(CrawledPage::new(
+            "https://docs.example.com/guide.pdf",
+            "https://docs.example.com/guide.pdf",
+            DocumentFormat::Pdf,
+            "The guide covers configuration of the CLI.",
+        ));
+    let (server, crawler) = create_crawling_app(crawler);
+
+    let response = server
+        .post("/v1/corpus/crawl")
+        .add_header("X-Gorkd-Workspace", "docs")
+        .json(&json!({"url": "https://docs.example.com/", "max_pages": 10, "max_depth": 1}))
+        .await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+    let body: Value = response.json();
+    assert_eq!(body["replaced"], 0);
+    let documents = body["documents"].as_array().unwrap();
+    assert_eq!(documents.len(), 2);
+    assert_eq!(documents[0]["title"], "Installing the CLI");
+    assert_eq!(documents[0]["url"], "https://docs.example.com/install");
+    assert_eq!(documents[0]["workspace"], "docs");
+    assert_eq!(documents[1]["format"], "pdf");
+    let requests = crawler.requests();
+    assert_eq!(requests[0].seed, "https://docs.example.com/");
+    assert_eq!((requests[0].max_pages, requests[0].max_depth), (10, 1));
+
+    let response = server
+        .post("/v1/corpus/crawl")
+        .add_header("X-Gorkd-Workspace", "docs")
+        .json(&json!({"url": "https://docs.example.com/"}))
+        .await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+    assert_eq!(response.json::<Value>()["replaced"], 2);
+    assert_eq!(
+        (
+            crawler.requests()[1].max_pages,
+            crawler.requests()[1].max_depth
+        ),
+        (50, 2)
+    );
+    let body: Value = server
+        .get("/v1/corpus/documents")
+        .add_header("X-Gorkd-Workspace", "docs")
+        .await
+        .json();
+    assert_eq!(body["documents"].as_array().unwrap().len(), 2);
+
+    let response = server
+        .post("/v1/research")
+        .add_header("X-Gorkd-Workspace", "docs")
+        .json(&json!({"query": "How do I install the CLI?", "corpus_only": true}))
+        .await;
+    response.assert_status(axum::http::StatusCode::ACCEPTED);
+    let job_id = response.json::<Value>()["job_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let job = wait_for_terminal_job(&server, &job_id).await;
+    assert_eq!(job["status"], "completed");
+    assert_eq!(job["corpus_only"], true);
+    assert_eq!(job["include_corpus"], true);
+    let body: Value = server
+        .get(&format!("/v1/jobs/{}/sources", job_id))
+        .await
+        .json();
+    assert!(body["sources"].as_array().unwrap().iter().all(|s| s["url"]
+        .as_str()
+        .unwrap()
+        .starts_with("https://docs.example.com/")));
+
+    for invalid in [
+        json!({"url": "ftp://docs.example.com/"}),
+        json!({"url": "https://docs.example.com/", "max_pages": 0}),
+        json!({"url": "https://docs.example.com/", "max_pages": 201}),
+        json!({"url": "https://docs.example.com/", "max_depth": 6}),
+    ] {
+        server
+            .post("/v1/corpus/crawl")
+            .json(&invalid)
+            .await
+            .assert_status_bad_request();
+    }
+}
+
+#[tokio::test]
+async fn test_recrawl_keeps_documents_of_unchanged_pages() {
+    let crawler = MockCrawler::new().with_page(
+        CrawledPage::new(
+            "https://docs.example.com/install",
+            "Installing the CLI",
+            DocumentFormat::Html,
+            "Install the CLI with cargo install.",
+        )
+        .with_unchanged(true),
+    );
+    let (server, _) = create_crawling_app(crawler);
+    let crawl = || {
+        server
+            .post("/v1/corpus/crawl")
+            .json(&json!({"url": "https://docs.example.com/"}))
+    };
+
+    // Nothing to keep yet, so the page is added like any other.
+    let first: Value = crawl().await.json();
+    assert_eq!(first["unchanged"], 0);
+
+    let response = crawl().await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+    let second: Value = response.json();
+    assert_eq!(second["unchanged"], 1);
+    assert_eq!(second["replaced"], 0);
+    assert_eq!(
+        second["documents"][0]["document_id"],
+        first["documents"][0]["document_id"]
+    );
+}
+
+#[tokio::test]
+async fn test_crawl_reports_unreadable_seeds_and_disabled_crawling() {
+    let (server, _) = create_crawling_app(MockCrawler::failing());
+    let response = server
+        .post("/v1/corpus/crawl")
+        .json(&json!({"url": "https://docs.example.com/"}))
+        .await;
+    response.assert_status(axum::http::StatusCode::BAD_GATEWAY);
+
+    create_test_app()
+        .post("/v1/corpus/crawl")
+        .json(&json!({"url": "https://docs.example.com/"}))
+        .await
+        .assert_status_not_found();
+}
+
+#[tokio::test]
+async fn test_feed_entries_scope_corpus_research_to_recent_news() {
+    let now = chrono::Utc::now();
+    let reader = MockFeedReader::new()
+        .with_item(
+            "https://news.example.com/rss",
+            FeedItem::new(
+                "https://news.example.com/rust-1-90",
+                "Rust 1.90 released",
+                "Rust 1.90 was released this week with faster builds.",
+            )
+            .with_published_at(now - chrono::Duration::days(1)),
+        )
+        .with_item(
+            "https://news.example.com/rss",
+            FeedItem::new(
+                "https://news.example.com/rust-1-70",
+                "Rust 1.70 released",
+                "Rust 1.70 was released with faster builds.",
+            )
+            .with_published_at(now - chrono::Duration::days(400)),
+        );
+    let state = AppState::new(
+        Arc::new(MockStore::new()),
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(MockLlmProvider::new("mock-gpt-4")),
+    )
+    .with_feeds(
+        Some(Arc::new(reader)),
+        vec![FeedSubscription::new(
+            "https://news.example.com/rss",
+            "news",
+        )],
+        Duration::from_secs(600),
+    );
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    let body: Value = server
+        .get("/v1/corpus/feeds")
+        .add_header("X-Gorkd-Workspace", "news")
+        .await
+        .json();
+    assert_eq!(body["feeds"][0]["url"], "https://news.example.com/rss");
+    assert_eq!(body["poll_interval_secs"], 600);
+    let body: Value = server.get("/v1/corpus/feeds").await.json();
+    assert!(body["feeds"].as_array().unwrap().is_empty());
+
+    let poll = |server: &TestServer| {
+        server
+            .post("/v1/corpus/feeds/poll")
+            .add_header("X-Gorkd-Workspace", "news")
+    };
+    let body: Value = poll(&server).await.json();
+    assert_eq!(
+        (body["feeds_read"].as_u64(), body["added"].as_u64()),
+        (Some(1), Some(2))
+    );
+    let body: Value = poll(&server).await.json();
+    assert_eq!(
+        (body["added"].as_u64(), body["skipped"].as_u64()),
+        (Some(0), Some(2))
+    );
+
+    let body: Value = server
+        .get("/v1/corpus/documents")
+        .add_header("X-Gorkd-Workspace", "news")
+        .await
+        .json();
+    let documents = body["documents"].as_array().unwrap();
+    assert_eq!(documents.len(), 2);
+    assert!(documents.iter().all(|d| d["published_at"].is_string()));
+
+    let response = server
+        .post("/v1/research")
+        .add_header("X-Gorkd-Workspace", "news")
+        .json(&json!({
+            "query": "What changed in the latest Rust release?",
+            "corpus_only": true,
+            "published_after": (now - chrono::Duration::days(7)).to_rfc3339(),
+        }))
+        .await;
+    response.assert_status(axum::http::StatusCode::ACCEPTED);
+    let job_id = response.json::<Value>()["job_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let job = wait_for_terminal_job(&server, &job_id).await;
+    assert_eq!(job["status"], "completed");
+    let body: Value = server
+        .get(&format!("/v1/jobs/{}/sources", job_id))
+        .await
+        .json();
+    let urls: Vec<&str> = body["sources"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["url"].as_str().unwrap())
+        .collect();
+    assert_eq!(urls, ["https://news.example.com/rust-1-90"]);
+
+    create_test_app()
+        .post("/v1/corpus/feeds/poll")
+        .await
+        .assert_status_not_found();
+}
+
+#[tokio::test]
+async fn test_feedback_sets_domain_trust_for_later_jobs() {
+    let server = create_test_app();
+    let job_id = run_to_completion(&server, json!({"query": "What is Rust?"})).await;
+    let body: Value = server
+        .get(&format!("/v1/jobs/{}/sources", job_id))
+        .await
+        .json();
+    let flagged = body["sources"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["url"] == "https://example.com/article-1")
+        .unwrap()["id"]
+        .clone();
+    let path = format!("/v1/jobs/{}/feedback", job_id);
+
+    // Two readers flag the same domain.
+    for reason in ["Outdated", "Press release"] {
+        let response = server
+            .post(&path)
+            .json(&json!({
+                "flagged_sources": [{"source_id": flagged, "reason": reason}]
+            }))
+            .await;
+        response.assert_status(axum::http::StatusCode::CREATED);
+        let body: Value = response.json();
+        assert_eq!(body["flagged_sources"][0]["domain"], "example.com");
+    }
+    let body: Value = server
+        .post(&path)
+        .json(&json!({"rating": "helpful", "comment": "Clear."}))
+        .await
+        .json();
+    assert_eq!(body["rated_domains"], json!(["example.com", "example.org"]));
+
+    let body: Value = server.get(&path).await.json();
+    assert_eq!(body["feedback"].as_array().unwrap().len(), 3);
+    assert_eq!(
+        body["feedback"][0]["flagged_sources"][0]["reason"],
+        "Outdated"
+    );
+
+    let next = run_to_completion(&server, json!({"query": "What is Rust used for?"})).await;
+    let body: Value = server
+        .get(&format!("/v1/jobs/{}/sources", next))
+        .await
+        .json();
+    let sources = body["sources"].as_array().unwrap();
+    assert_eq!(sources[1]["url"], "https://example.org/resource");
+    assert_eq!(sources[1]["trust"], "neutral");
+    assert_eq!(sources[2]["trust"], "distrusted");
+    assert!(sources[2]["relevance_score"].as_f64().unwrap() < 0.85);
+
+    server
+        .post(&path)
+        .json(&json!({"comment": " "}))
+        .await
+        .assert_status_bad_request();
+    server
+        .post(&path)
+        .json(&json!({"comment": "x".repeat(2001)}))
+        .await
+        .assert_status_bad_request();
+    server
+        .post(&path)
+        .json(&json!({"flagged_sources": [{"source_id": "src_unknown"}]}))
+        .await
+        .assert_status_bad_request();
+    server
+        .post(&format!("/v1/jobs/{}/feedback", JobId::new()))
+        .json(&json!({"rating": "helpful"}))
+        .await
+        .assert_status_not_found();
+}
+
+#[tokio::test]
+async fn test_completed_job_breaks_down_token_usage_by_stage() {
+    let server = create_test_app();
+
+    let body: Value = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?"}))
+        .await
+        .json();
+    let job_id = body["job_id"].as_str().unwrap();
+
+    let job = wait_for_terminal_job(&server, job_id).await;
+
+    let usage = &job["answer"]["token_usage"];
+    assert_eq!(usage["total_tokens"], 500);
+    assert_eq!(usage["stages"][0]["stage"], "synthesis");
+    assert_eq!(usage["stages"][0]["model"], "mock-gpt-4");
+    assert_eq!(usage["stages"][0]["prompt_tokens"], 400);
+    assert_eq!(usage["stages"][0]["completion_tokens"], 100);
+}
+
+#[tokio::test]
+async fn test_completed_job_reports_prompt_and_context_usage() {
+    let server = create_test_app();
+
+    let body: Value = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?"}))
+        .await
+        .json();
+    let job_id = body["job_id"].as_str().unwrap();
+
+    let job = wait_for_terminal_job(&server, job_id).await;
+
+    let usage = &job["answer"]["token_usage"];
+    assert_eq!(usage["prompt_tokens"], 400);
+    assert_eq!(usage["completion_tokens"], 100);
+    let context = &usage["context"];
+    assert_eq!(context["window_tokens"], 128000);
+    assert_eq!(context["prompt_tokens"], 400);
+    assert!((context["utilization_percent"].as_f64().unwrap() - 0.3125).abs() < 1e-6);
+    let available = context["sources_available"].as_u64().unwrap();
+    let used = context["sources_used"].as_u64().unwrap();
+    assert!(used > 0 && used <= available);
+    let sources: Value = server
+        .get(&format!("/v1/jobs/{}/sources", job_id))
+        .await
+        .json();
+    assert_eq!(
+        available as usize,
+        sources["sources"].as_array().unwrap().len()
+    );
+}
+
+#[tokio::test]
+async fn test_depth_sets_source_budget() {
+    let state = AppState::new(
+        Arc::new(MockStore::new()),
+        Arc::new(MockSearchProvider::new("mock-tavily").with_result_count(12)),
+        Arc::new(MockLlmProvider::new("mock-gpt-4")),
+    );
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    for (depth, sources) in [("tldr", 3), ("standard", 10), ("exhaustive", 12)] {
+        let job_id =
+            run_to_completion(&server, json!({"query": "What is Rust?", "depth": depth})).await;
+
+        let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+        assert_eq!(job["depth"], depth);
+        let body: Value = server
+            .get(&format!("/v1/jobs/{}/sources", job_id))
+            .await
+            .json();
+        assert_eq!(
+            body["sources"].as_array().unwrap().len(),
+            sources,
+            "{}",
+            depth
+        );
+    }
+
+    let job_id = run_to_completion(&server, json!({"query": "What is Rust?"})).await;
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert_eq!(job["depth"], "standard");
+}
+
+#[tokio::test]
+async fn test_exhaustive_answer_is_written_by_section() {
+    let outline = r#"{"sections": [
+        {"title": "Origins", "focus": "Where Rust came from", "sources": [1, 2]},
+        {"title": "Adoption", "focus": "Who uses it", "sources": [3]}
+    ]}"#;
+    let state = AppState::new(
+        Arc::new(MockStore::new()),
+        Arc::new(MockSearchProvider::new("mock-tavily").with_result_count(12)),
+        Arc::new(
+            MockLlmProvider::new("mock-gpt-4").with_script([MockLlmStep::Raw(outline.into())]),
+        ),
+    );
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    let job_id = run_to_completion(
+        &server,
+        json!({"query": "What is Rust?", "depth": "exhaustive"}),
+    )
+    .await;
+
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    let answer = &job["answer"];
+    assert!(answer["detail"].as_str().unwrap().starts_with("## Origins"));
+    let sections = answer["sections"].as_array().unwrap();
+    assert_eq!(sections.len(), 2);
+    assert_eq!(sections[1]["title"], "Adoption");
+    assert_eq!(sections[0]["source_ids"].as_array().unwrap().len(), 2);
+    assert_eq!(sections[0]["citations"].as_array().unwrap().len(), 2);
+    assert_eq!(answer["token_usage"]["stages"][0]["stage"], "outline");
+
+    let job_id = run_to_completion(&server, json!({"query": "What is Rust?"})).await;
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert!(job["answer"].get("sections").is_none());
+}
+
+#[tokio::test]
+async fn test_request_bounds_sources() {
+    let state = AppState::new(
+        Arc::new(MockStore::new()),
+        Arc::new(MockSearchProvider::new("mock-tavily").with_result_count(12)),
+        Arc::new(MockLlmProvider::new("mock-gpt-4")),
+    );
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    for (bounds, sources) in [
+        (json!({"max_sources": 5}), 5),
+        (json!({"min_score": 0.5}), 7),
+        (json!({"max_per_domain": 2}), 2),
+    ] {
+        let mut request = json!({"query": "What is Rust?"});
+        request
+            .as_object_mut()
+            .unwrap()
+            .extend(bounds.as_object().unwrap().clone());
+        let job_id = run_to_completion(&server, request).await;
+
+        let body: Value = server
+            .get(&format!("/v1/jobs/{}/sources", job_id))
+            .await
+            .json();
+        assert_eq!(
+            body["sources"].as_array().unwrap().len(),
+            sources,
+            "{}",
+            bounds
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_request_source_bounds_are_capped() {
+    let state = AppState::new(
+        Arc::new(MockStore::new()),
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(MockLlmProvider::new("mock-gpt-4")),
+    )
+    .with_source_caps(20, Some(3));
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    for bounds in [
+        json!({"max_sources": 0}),
+        json!({"max_sources": 21}),
+        json!({"min_score": 1.5}),
+        json!({"max_per_domain": 0}),
+        json!({"max_per_domain": 4}),
+    ] {
+        let mut request = json!({"query": "What is Rust?"});
+        request
+            .as_object_mut()
+            .unwrap()
+            .extend(bounds.as_object().unwrap().clone());
+        let response = server.post("/v1/research").json(&request).await;
+
+        response.assert_status_bad_request();
+        let body: Value = response.json();
+        assert_eq!(body["error"]["code"], "validation_error", "{}", bounds);
+    }
+
+    let response = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?", "max_sources": 20, "max_per_domain": 3}))
+        .await;
+    response.assert_status(axum::http::StatusCode::ACCEPTED);
+}
+
+#[tokio::test]
+async fn test_budget_caps_synthesis() {
+    let server = create_test_app();
+
+    let job_id = run_to_completion(
+        &server,
+        json!({"query": "What is Rust?", "max_cost": {"tokens": 100000}}),
+    )
+    .await;
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert_eq!(job["max_cost"]["tokens"], 100000);
+    let budget = &job["answer"]["budget"];
+    assert_eq!(
+        budget["spent_tokens"],
+        job["answer"]["token_usage"]["total_tokens"]
+    );
+    assert!(budget["estimated_tokens"].as_u64().unwrap() <= 100000);
+    assert!(budget["downgraded_from"].is_null());
+
+    let body: Value = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?", "max_cost": {"tokens": 10}}))
+        .await
+        .json();
+    let job_id = body["job_id"].as_str().unwrap();
+    let job = wait_for_terminal_job(&server, job_id).await;
+    assert_eq!(job["status"], "failed");
+    assert_eq!(job["error_code"], "budget_exceeded");
+    assert_eq!(job["failure"]["provider"], "mock-gpt-4");
+
+    for request in [
+        json!({"query": "What is Rust?", "max_cost": {"usd": 0.0}}),
+        json!({"query": "What is Rust?", "max_cost": {"tokens": 0}}),
+        json!({"query": "What is Rust?", "max_cost": {"usd": 0.05}, "models": ["mock-gpt-4"]}),
+    ] {
+        server
+            .post("/v1/research")
+            .json(&request)
+            .await
+            .assert_status_bad_request();
+    }
+}
+
+#[tokio::test]
+async fn test_routes_simple_questions_to_fast_model() {
+    let mut state = AppState::new(
+        Arc::new(MockStore::new()),
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(MockLlmProvider::new("mock-gpt-4")),
+    )
+    .with_routing(Some(RoutingPolicy::default()));
+    state
+        .llm_registry
+        .register("mock-mini", Arc::new(MockLlmProvider::new("mock-mini")));
+    state.llm_registry.set_fast("mock-mini");
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    let job_id =
+        run_to_completion(&server, json!({"query": "What is the capital of France?"})).await;
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert_eq!(job["answer"]["model"], "mock-mini");
+    let routing = &job["answer"]["routing"];
+    assert_eq!(routing["tier"], "fast");
+    assert_eq!(routing["question_type"], "factual");
+    assert_eq!(routing["hops"], 1);
+
+    let job_id = run_to_completion(&server, json!({"query": "Why do ships float on water?"})).await;
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert_eq!(job["answer"]["model"], "mock-gpt-4");
+    assert_eq!(job["answer"]["routing"]["tier"], "premium");
+
+    let events: Value = server
+        .get(&format!("/v1/jobs/{}/events", job_id))
+        .await
+        .json();
+    assert!(events["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|e| e["type"] == "model_routed"));
+}
+
+#[tokio::test]
+async fn test_lists_jobs_by_tag_with_metadata() {
+    let server = create_test_app();
+
+    let tagged = run_to_completion(
+        &server,
+        json!({
+            "query": "Who makes the Pixel phone?",
+            "tags": ["competitor-analysis", "q3"],
+            "metadata": {"account_id": "acct_42"}
+        }),
+    )
+    .await;
+    run_to_completion(&server, json!({"query": "Who makes the iPhone?"})).await;
+
+    let job: Value = server.get(&format!("/v1/jobs/{}", tagged)).await.json();
+    assert_eq!(job["tags"], json!(["competitor-analysis", "q3"]));
+    assert_eq!(job["metadata"]["account_id"], "acct_42");
+
+    let list: Value = server
+        .get("/v1/jobs")
+        .add_query_param("tag", "competitor-analysis")
+        .await
+        .json();
+    let jobs = list["jobs"].as_array().unwrap();
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0]["job_id"], tagged);
+    assert_eq!(jobs[0]["metadata"]["account_id"], "acct_42");
+
+    let all: Value = server.get("/v1/jobs").await.json();
+    assert_eq!(all["jobs"].as_array().unwrap().len(), 2);
+    assert_eq!(all["limit"], 20);
+
+    let response = server
+        .post("/v1/research")
+        .json(&json!({"query": "test", "tags": ["has space"]}))
+        .await;
+    response.assert_status_bad_request();
+    let response = server
+        .post("/v1/research")
+        .json(&json!({"query": "test", "metadata": ["not", "an", "object"]}))
+        .await;
+    response.assert_status_bad_request();
+    server
+        .get("/v1/jobs")
+        .add_query_param("limit", 0)
+        .await
+        .assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_lists_job_summaries_with_status_counts() {
+    let server = create_test_app();
+
+    let tagged = run_to_completion(
+        &server,
+        json!({"query": "Who makes the Pixel phone?", "tags": ["competitor-analysis"]}),
+    )
+    .await;
+    run_to_completion(&server, json!({"query": "Who makes the iPhone?"})).await;
+
+    let list: Value = server.get("/v1/jobs/summaries").await.json();
+    let jobs = list["jobs"].as_array().unwrap();
+    assert_eq!(jobs.len(), 2);
+    assert!(jobs.iter().all(|j| j["status"] == "completed"));
+    assert!(jobs.iter().all(|j| j["summary"].is_string()));
+    assert!(jobs.iter().all(|j| j["duration_ms"].is_u64()));
+    assert_eq!(list["counts"], json!([{"status": "completed", "count": 2}]));
+
+    let list: Value = server
+        .get("/v1/jobs/summaries")
+        .add_query_param("tag", "competitor-analysis")
+        .add_query_param("q", "PIXEL")
+        .await
+        .json();
+    let jobs = list["jobs"].as_array().unwrap();
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0]["job_id"], tagged);
+
+    let list: Value = server
+        .get("/v1/jobs/summaries")
+        .add_query_param("status", "failed")
+        .await
+        .json();
+    assert!(list["jobs"].as_array().unwrap().is_empty());
+    assert_eq!(list["counts"], json!([]));
+
+    server
+        .get("/v1/jobs/summaries")
+        .add_query_param("status", "lost")
+        .await
+        .assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_stats_sum_usage_without_job_content() {
+    let server = create_test_app();
+    run_to_completion(&server, json!({"query": "Who makes the Pixel phone?"})).await;
+    run_to_completion(&server, json!({"query": "Who makes the iPhone?"})).await;
+
+    let response = server.get("/v1/stats").add_query_param("days", 7).await;
+    response.assert_status_ok();
+    let stats: Value = response.json();
+    assert_eq!(stats["days"], 7);
+    assert_eq!(stats["jobs"], 2);
+    assert_eq!(stats["completed"], 2);
+    assert_eq!(stats["success_rate"], 1.0);
+    assert_eq!(stats["daily"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        stats["models"],
+        json!([{"model": "mock-gpt-4", "answers": 2}])
+    );
+    let stages = stats["stage_latency"].as_array().unwrap();
+    assert!(stages.iter().any(|s| s["stage"] == "searching"));
+    assert!(!stats.to_string().contains("iPhone"));
+
+    server
+        .get("/v1/stats")
+        .add_query_param("days", 0)
+        .await
+        .assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_synthesizes_across_completed_jobs() {
+    let server = create_test_app();
+
+    let first = run_to_completion(&server, json!({"query": "How many EVs sold in 2024?"})).await;
+    let second = run_to_completion(&server, json!({"query": "Which EV maker sold most?"})).await;
+
+    let response = server
+        .post("/v1/synthesize")
+        .json(&json!({
+            "question": "How is the EV market developing?",
+            "job_ids": [first, second, first]
+        }))
+        .await;
+    response.assert_status_ok();
+    let synthesis: Value = response.json();
+    assert_eq!(synthesis["job_ids"], json!([first, second]));
+
+    let sources = synthesis["sources"].as_array().unwrap();
+    assert_eq!(sources[0]["kind"], "answer");
+    assert_eq!(sources[0]["job_ids"], json!([first]));
+    assert!(sources.iter().any(|s| s["kind"] == "source"));
+
+    let citations = synthesis["answer"]["citations"].as_array().unwrap();
+    assert!(!citations.is_empty());
+    for citation in citations {
+        assert!(sources
+            .iter()
+            .any(|s| s["source_id"] == citation["source_id"]));
+    }
+
+    server
+        .post("/v1/synthesize")
+        .json(&json!({"question": "Why?", "job_ids": [first]}))
+        .await
+        .assert_status_bad_request();
+    server
+        .post("/v1/synthesize")
+        .json(&json!({"question": "Why?", "job_ids": [first, "job_doesnotexist"]}))
+        .await
+        .assert_status_not_found();
+}
+
+#[tokio::test]
+async fn test_synthesizes_over_caller_sources() {
+    let server = create_test_app();
+
+    let response = server
+        .post("/v1/synthesize/direct")
+        .json(&json!({
+            "question": "How many vacation days do new employees get?",
+            "sources": [
+                {
+                    "url": "https://wiki.corp.example/hr/vacation",
+                    "title": "Vacation policy",
+                    "content": "Employees accrue 1.5 vacation days per month."
+                },
+                {
+                    "url": "https://wiki.corp.example/hr/onboarding",
+                    "title": "Onboarding",
+                    "content": "New employees start with 5 vacation days."
+                }
+            ],
+            "style": "executive"
+        }))
+        .await;
+    response.assert_status_ok();
+    let synthesis: Value = response.json();
+    let sources = synthesis["sources"].as_array().unwrap();
+    assert_eq!(sources.len(), 2);
+    assert_eq!(sources[0]["url"], "https://wiki.corp.example/hr/vacation");
+
+    let citations = synthesis["answer"]["citations"].as_array().unwrap();
+    assert!(!citations.is_empty());
+    for citation in citations {
+        assert!(sources.iter().any(|s| s["id"] == citation["source_id"]));
+    }
+
+    server
+        .post("/v1/synthesize/direct")
+        .json(&json!({"question": "Why?", "sources": []}))
+        .await
+        .assert_status_bad_request();
+    server
+        .post("/v1/synthesize/direct")
+        .json(&json!({
+            "question": "Why?",
+            "sources": [{"url": "ftp://example.com", "title": "A", "content": "B"}]
+        }))
+        .await
+        .assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_project_groups_jobs_into_report() {
+    let server = create_test_app();
+
+    let response = server
+        .post("/v1/projects")
+        .json(&json!({"name": "EV market", "description": "Size the EV market"}))
+        .await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+    let project: Value = response.json();
+    let project_id = project["project_id"].as_str().unwrap().to_string();
+    assert!(project_id.starts_with("prj_"));
+
+    let report_url = format!("/v1/projects/{}/report", project_id);
+    server
+        .post(&report_url)
+        .await
+        .assert_status(axum::http::StatusCode::CONFLICT);
+
+    let first = run_to_completion(&server, json!({"query": "How many EVs sold in 2024?"})).await;
+    let second = run_to_completion(&server, json!({"query": "Which EV maker sold most?"})).await;
+    let jobs_url = format!("/v1/projects/{}/jobs", project_id);
+    for job_id in [&first, &second, &first] {
+        server
+            .post(&jobs_url)
+            .json(&json!({"job_id": job_id}))
+            .await
+            .assert_status_ok();
+    }
+
+    let project: Value = server
+        .get(&format!("/v1/projects/{}", project_id))
+        .await
+        .json();
+    assert_eq!(project["job_ids"], json!([first, second]));
+
+    let jobs: Value = server.get(&jobs_url).await.json();
+    assert_eq!(jobs["jobs"][1]["query"], "Which EV maker sold most?");
+
+    let report: Value = server.post(&report_url).await.json();
+    assert_eq!(report["findings"].as_array().unwrap().len(), 2);
+    assert_eq!(report["findings"][0]["job_id"], first);
+    assert!(report["report"]
+        .as_str()
+        .unwrap()
+        .contains("[Q2] Which EV maker sold most?"));
+    assert_eq!(report["unanswered"], json!([]));
+
+    server
+        .post(&jobs_url)
+        .json(&json!({"job_id": "job_doesnotexist"}))
+        .await
+        .assert_status_not_found();
+    server
+        .get("/v1/projects/prj_doesnotexist")
+        .await
+        .assert_status_not_found();
+    server
+        .post("/v1/projects")
+        .json(&json!({"name": "  "}))
+        .await
+        .assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_style_sets_answer_voice() {
+    let profiles = ResearchProfiles::from_json(r#"{"board": {"style": "executive"}}"#).unwrap();
+    let store = Arc::new(MockStore::new());
+    let sink = ArtifactCapture::Store
+        .sink(Arc::clone(&store) as Arc<dyn Store>)
+        .unwrap();
+    let state = AppState::new(
+        store,
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(MockLlmProvider::new("mock-gpt-4")),
+    )
+    .with_profiles(profiles)
+    .with_artifact_sink(Some(sink));
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    let job_id =
+        run_to_completion(&server, json!({"query": "What is Rust?", "style": "eli5"})).await;
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert_eq!(job["style"], "eli5");
+    let body: Value = server
+        .get(&format!("/v1/admin/jobs/{}/artifacts", job_id))
+        .await
+        .json();
+    let messages = body["artifacts"][0]["messages"].as_array().unwrap();
+    assert!(messages
+        .iter()
+        .any(|m| m["content"] == "Answer style: eli5"));
+
+    let job_id = run_to_completion(
+        &server,
+        json!({"query": "What is Rust?", "profile": "board"}),
+    )
+    .await;
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert_eq!(job["style"], "executive");
+
+    let job_id = run_to_completion(&server, json!({"query": "What is Rust?"})).await;
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert_eq!(job["style"], "neutral");
+
+    let response = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?", "style": "pirate"}))
+        .await;
+    response.assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_profile_pins_domains_and_depth() {
+    let profiles = ResearchProfiles::from_json(
+        r#"{"org": {"include_domains": ["example.org"], "depth": "tldr"}}"#,
+    )
+    .unwrap();
+    let state = AppState::new(
+        Arc::new(MockStore::new()),
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(MockLlmProvider::new("mock-gpt-4")),
+    )
+    .with_profiles(profiles);
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    let job_id =
+        run_to_completion(&server, json!({"query": "What is Rust?", "profile": "org"})).await;
+
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert_eq!(job["profile"], "org");
+    assert_eq!(job["depth"], "tldr");
+    let body: Value = server
+        .get(&format!("/v1/jobs/{}/sources", job_id))
+        .await
+        .json();
+    let sources = body["sources"].as_array().unwrap();
+    assert_eq!(sources.len(), 1);
+    assert_eq!(sources[0]["url"], "https://example.org/resource");
+
+    let job_id = run_to_completion(
+        &server,
+        json!({"query": "What is Rust?", "profile": "org", "depth": "exhaustive"}),
+    )
+    .await;
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert_eq!(job["depth"], "exhaustive");
+
+    let response = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?", "profile": "legal"}))
+        .await;
+    response.assert_status_bad_request();
+    let body: Value = response.json();
+    assert_eq!(body["error"]["code"], "validation_error");
+}
+
+#[tokio::test]
+async fn test_domain_policy_overrides_profile() {
+    let profiles = ResearchProfiles::from_json(
+        r#"{"all": {"include_domains": ["example.com", "example.org"]}}"#,
+    )
+    .unwrap();
+    let state = AppState::new(
+        Arc::new(MockStore::new()),
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(MockLlmProvider::new("mock-gpt-4")),
+    )
+    .with_profiles(profiles)
+    .with_domain_policy(DomainPolicy {
+        deny: vec!["example.com".to_string()],
+        ..Default::default()
+    });
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    let job_id =
+        run_to_completion(&server, json!({"query": "What is Rust?", "profile": "all"})).await;
+
+    let body: Value = server
+        .get(&format!("/v1/jobs/{}/sources", job_id))
+        .await
+        .json();
+    let urls: Vec<&str> = body["sources"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["url"].as_str().unwrap())
+        .collect();
+    assert_eq!(urls, vec!["https://example.org/resource"]);
+}
+
+#[tokio::test]
+async fn test_locale_filters_search() {
+    let search = Arc::new(MockSearchProvider::new("mock-tavily"));
+    let state = AppState::new(
+        Arc::new(MockStore::new()),
+        search.clone(),
+        Arc::new(MockLlmProvider::new("mock-gpt-4")),
+    );
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    let job_id = run_to_completion(
+        &server,
+        json!({"query": "Kündigungsfrist Mietvertrag", "language": "DE", "country": "de"}),
+    )
+    .await;
+
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert_eq!(job["language"], "de");
+    assert_eq!(job["country"], "DE");
+    let filters = &search.queries()[0].filters;
+    assert_eq!(filters.language.as_deref(), Some("de"));
+    assert_eq!(filters.country.as_deref(), Some("DE"));
+
+    for locale in [json!({"language": "deu"}), json!({"country": "Germany"})] {
+        let mut request = json!({"query": "What is Rust?"});
+        request
+            .as_object_mut()
+            .unwrap()
+            .extend(locale.as_object().unwrap().clone());
+        server
+            .post("/v1/research")
+            .json(&request)
+            .await
+            .assert_status_bad_request();
+    }
+}
+
+#[tokio::test]
+async fn test_date_range_filters_search() {
+    let search = Arc::new(MockSearchProvider::new("mock-tavily"));
+    let state = AppState::new(
+        Arc::new(MockStore::new()),
+        search.clone(),
+        Arc::new(MockLlmProvider::new("mock-gpt-4")),
+    );
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    let job_id = run_to_completion(
+        &server,
+        json!({
+            "query": "Coverage of the SVB collapse",
+            "published_after": "2023-03-01T00:00:00Z",
+            "published_before": "2023-07-01T00:00:00Z"
+        }),
+    )
+    .await;
+
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert_eq!(job["published_after"], "2023-03-01T00:00:00Z");
+    let filters = &search.queries()[0].filters;
+    assert_eq!(
+        filters.published_before.unwrap().to_rfc3339(),
+        "2023-07-01T00:00:00+00:00"
+    );
+
+    server
+        .post("/v1/research")
+        .json(&json!({
+            "query": "What is Rust?",
+            "published_after": "2023-07-01T00:00:00Z",
+            "published_before": "2023-03-01T00:00:00Z"
+        }))
+        .await
+        .assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_question_period_narrows_search() {
+    let search = Arc::new(MockSearchProvider::new("mock-tavily"));
+    let state = AppState::new(
+        Arc::new(MockStore::new()),
+        search.clone(),
+        Arc::new(MockLlmProvider::new("mock-gpt-4")),
+    );
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    let job_id = run_to_completion(&server, json!({"query": "Rust releases since 2021"})).await;
+
+    let sources: Value = server
+        .get(&format!("/v1/jobs/{}/sources", job_id))
+        .await
+        .json();
+    assert_eq!(sources["search"]["time_constraint"]["kind"], "date_range");
+    assert_eq!(
+        sources["search"]["time_constraint"]["from"],
+        "2021-01-01T00:00:00Z"
+    );
+    let filters = &search.queries()[0].filters;
+    assert_eq!(
+        filters.published_after.unwrap().to_rfc3339(),
+        "2021-01-01T00:00:00+00:00"
+    );
+}
+
+#[tokio::test]
+async fn test_answer_schema_returns_structured_answer() {
+    let server = create_test_app();
+
+    let body: Value = server
+        .post("/v1/research")
+        .json(&json!({
+            "query": "Compare Rust web frameworks",
+            "answer_schema": {
+                "name": "framework_comparison",
+                "schema": {
+                    "type": "object",
+                    "required": ["verdict"],
+                    "properties": {
+                        "verdict": {"type": "string", "enum": ["axum", "actix"]}
+                    }
+                }
+            }
+        }))
+        .await
+        .json();
+    let job_id = body["job_id"].as_str().unwrap();
+
+    let job = wait_for_terminal_job(&server, job_id).await;
+
+    assert_eq!(job["status"], "completed");
+    assert_eq!(job["answer"]["structured"], json!({"verdict": "axum"}));
+}
+
+#[tokio::test]
+async fn test_answer_schema_is_validated() {
+    let server = create_test_app();
+
+    let response = server
+        .post("/v1/research")
+        .json(&json!({
+            "query": "What is Rust?",
+            "answer_schema": {"name": "list", "schema": {"type": "array"}}
+        }))
+        .await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+
+    let response = server
+        .post("/v1/research")
+        .json(&json!({
+            "query": "What is Rust?",
+            "answer_schema": {"name": "refs", "schema": {"type": "object", "$ref": "#/a"}}
+        }))
+        .await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+    let body: Value = response.json();
+    assert!(body["error"]["message"].as_str().unwrap().contains("$ref"));
+}
+
+fn create_comparison_app() -> TestServer {
+    let mut state = AppState::new(
+        Arc::new(MockStore::new()),
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(MockLlmProvider::new("mock-gpt-4")),
+    );
+    state.llm_registry.register(
+        "mock-claude",
+        Arc::new(MockLlmProvider::new("mock-claude").with_confidence(Confidence::Low)),
+    );
+    TestServer::new(app(Arc::new(state))).unwrap()
+}
+
+#[tokio::test]
+async fn test_compares_answers_across_models() {
+    let server = create_comparison_app();
+
+    let body: Value = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?", "models": ["mock-claude", "mock-gpt-4"]}))
+        .await
+        .json();
+    let job_id = body["job_id"].as_str().unwrap();
+
+    let job = wait_for_terminal_job(&server, job_id).await;
+    assert_eq!(job["status"], "completed");
+    assert_eq!(job["answer"]["model"], "mock-claude");
+
+    let response = server.get(&format!("/v1/jobs/{}/comparison", job_id)).await;
+    response.assert_status_ok();
+    let comparison: Value = response.json();
+    assert_eq!(comparison["answers"][0]["model"], "mock-claude");
+    assert_eq!(comparison["answers"][1]["model"], "mock-gpt-4");
+    assert_eq!(comparison["answers"][1]["answer"]["confidence"], "high");
+    assert_eq!(comparison["agreement"]["confidence_agrees"], false);
+    assert_eq!(comparison["agreement"]["citation_overlap"], 1.0);
+}
+
+#[tokio::test]
+async fn test_comparison_rejects_unknown_models() {
+    let server = create_comparison_app();
+
+    let response = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?", "models": ["mock-claude", "gpt-9"]}))
+        .await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+    let body: Value = response.json();
+    assert!(body["error"]["message"].as_str().unwrap().contains("gpt-9"));
+
+    let models = vec!["mock-claude"; 5];
+    let response = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?", "models": models}))
+        .await;
+    response.assert_status(axum::http::StatusCode::ACCEPTED);
+}
+
+#[tokio::test]
+async fn test_comparison_not_found_without_models() {
+    let server = create_test_app();
+
+    let body: Value = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?"}))
+        .await
+        .json();
+
+    let response = server
+        .get(&format!(
+            "/v1/jobs/{}/comparison",
+            body["job_id"].as_str().unwrap()
+        ))
+        .await;
+    response.assert_status_not_found();
+}
+
+async fn run_to_completion(server: &TestServer, request: Value) -> String {
+    let body: Value = server.post("/v1/research").json(&request).await.json();
+    let job_id = body["job_id"].as_str().unwrap().to_string();
+    let job = wait_for_terminal_job(server, &job_id).await;
+    assert_eq!(job["status"], "completed");
+    job_id
+}
+
+#[tokio::test]
+async fn test_diffs_answers_of_two_jobs() {
+    let server = create_comparison_app();
+    let first = run_to_completion(&server, json!({"query": "What is Rust?"})).await;
+    let rerun = run_to_completion(&server, json!({"query": "What is Rust?"})).await;
+    let other_model = run_to_completion(
+        &server,
+        json!({"query": "What is Rust?", "models": ["mock-claude"]}),
+    )
+    .await;
+
+    let response = server
+        .get(&format!("/v1/jobs/{}/diff/{}", first, rerun))
+        .await;
+    response.assert_status_ok();
+    let diff: Value = response.json();
+    assert_eq!(diff["from_job_id"], first.as_str());
+    assert_eq!(diff["claims"], json!([]));
+    // Reruns cite the same pages under new source IDs.
+    assert_eq!(diff["added_citations"], json!([]));
+    assert_eq!(diff["removed_citations"], json!([]));
+    assert_eq!(diff["confidence"]["delta"], 0);
+
+    let diff: Value = server
+        .get(&format!("/v1/jobs/{}/diff/{}", first, other_model))
+        .await
+        .json();
+    assert_eq!(diff["confidence"]["from"], "high");
+    assert_eq!(diff["confidence"]["to"], "low");
+    assert_eq!(diff["confidence"]["delta"], -2);
+}
+
+#[tokio::test]
+async fn test_diff_requires_both_answers() {
+    let server = create_test_app();
+    let job_id = run_to_completion(&server, json!({"query": "What is Rust?"})).await;
+    let unsaved = ResearchJob::new("What is Rust?").unwrap();
+
+    let response = server
+        .get(&format!("/v1/jobs/{}/diff/{}", job_id, unsaved.id))
+        .await;
+
+    response.assert_status_not_found();
+}
+
+#[tokio::test]
+async fn test_blocked_answer_fails_job() {
+    let state = AppState::new(
+        Arc::new(MockStore::new()),
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(MockLlmProvider::new("mock-gpt-4")),
+    )
+    .with_moderation(
+        Some(Arc::new(MockModerator::flagging(["violence"]))),
+        ModerationPolicy::Block,
+    );
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    let body: Value = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?"}))
+        .await
+        .json();
+    let job_id = body["job_id"].as_str().unwrap();
+
+    let job = wait_for_terminal_job(&server, job_id).await;
+
+    assert_eq!(job["status"], "failed");
+    assert_eq!(job["error_code"], "content_blocked");
+    assert_eq!(job["failure"]["kind"], "content_blocked");
+    assert_eq!(job["failure"]["stage"], "synthesizing");
+    assert_eq!(job["failure"]["retryable"], false);
+    assert_eq!(job["failure"]["message"], job["error_message"]);
+    assert!(job["error_message"]
+        .as_str()
+        .unwrap()
+        .contains("content policy"));
+    assert!(job["answer"].is_null());
+}
+
+#[tokio::test]
+async fn test_dead_links_are_marked_and_annotated() {
+    let state = AppState::new(
+        Arc::new(MockStore::new()),
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(MockLlmProvider::new("mock-gpt-4")),
+    )
+    .with_link_checker(
+        Some(Arc::new(
+            MockLinkChecker::new().with_dead("https://example.com/article-1"),
+        )),
+        DeadLinkPolicy::Annotate,
+    );
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    let body: Value = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?"}))
+        .await
+        .json();
+    let job_id = body["job_id"].as_str().unwrap();
+
+    let job = wait_for_terminal_job(&server, job_id).await;
+
+    assert_eq!(job["status"], "completed");
+    let citations = job["answer"]["citations"].as_array().unwrap();
+    assert_eq!(citations[0]["link_status"], "dead");
+    assert!(citations[1..].iter().all(|c| c["link_status"] == "live"));
+    assert!(job["answer"]["limitations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|l| l
+            .as_str()
+            .unwrap()
+            .contains("https://example.com/article-1")));
+}
+
+#[tokio::test]
+async fn test_answers_past_their_ttl_are_marked_stale() {
+    let state = AppState::new(
+        Arc::new(MockStore::new()),
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(MockLlmProvider::new("mock-gpt-4")),
+    )
+    .with_freshness(FreshnessPolicy {
+        breaking: Duration::ZERO,
+        ..Default::default()
+    });
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    let breaking: Value = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is the latest news on Rust?"}))
+        .await
+        .json();
+    let evergreen: Value = server
+        .post("/v1/research")
+        .json(&json!({"query": "How does the Rust borrow checker work?"}))
+        .await
+        .json();
+
+    let breaking = wait_for_terminal_job(&server, breaking["job_id"].as_str().unwrap()).await;
+    let evergreen = wait_for_terminal_job(&server, evergreen["job_id"].as_str().unwrap()).await;
+
+    let stale = &breaking["answer"]["freshness"];
+    assert_eq!(stale["volatility"], "breaking");
+    assert_eq!(stale["stale"], true);
+    assert!(stale["suggestion"]
+        .as_str()
+        .unwrap()
+        .contains("POST /v1/research"));
+    let current = &evergreen["answer"]["freshness"];
+    assert_eq!(current["volatility"], "evergreen");
+    assert_eq!(current["stale"], false);
+    assert!(current.get("suggestion").is_none());
+}
+
+#[tokio::test]
+async fn test_sources_filter_and_sort() {
+    let (server, job_id) = create_app_with_sources(vec![
+        scored_source("https://rust-lang.org/a", 0.5),
+        scored_source("https://blog.rust-lang.org/b", 0.9),
+        scored_source("https://example.com/c", 0.7),
+        scored_source("https://example.com/d", 0.2),
+    ])
+    .await;
+
+    let body: Value = server
+        .get(&format!(
+            "/v1/jobs/{}/sources?min_score=0.4&domain=rust-lang.org",
+            job_id
+        ))
+        .await
+        .json();
+    let domains: Vec<&str> = body["sources"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["domain"].as_str().unwrap())
+        .collect();
+    assert_eq!(domains, vec!["blog.rust-lang.org", "rust-lang.org"]);
+    assert!(body.get("domains").is_none());
+
+    let body: Value = server
+        .get(&format!("/v1/jobs/{}/sources?sort=domain", job_id))
+        .await
+        .json();
+    let first = &body["sources"][0];
+    assert_eq!(first["domain"], "blog.rust-lang.org");
+    assert_eq!(body["sources"][1]["url"], "https://example.com/c");
+}
+
+#[tokio::test]
+async fn test_sources_include_images_when_present() {
+    let (server, job_id) = create_app_with_sources(vec![
+        scored_source("https://example.com/a", 0.9).with_images(["https://example.com/a.png"]),
+        scored_source("https://example.com/b", 0.5),
+    ])
+    .await;
+
+    let body: Value = server
+        .get(&format!("/v1/jobs/{}/sources", job_id))
+        .await
+        .json();
+
+    assert_eq!(
+        body["sources"][0]["images"],
+        json!(["https://example.com/a.png"])
+    );
+    assert!(body["sources"][1].get("images").is_none());
+}
 
-    let http = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(60))
-        .build()
-        .expect("failed to create HTTP client");
+#[tokio::test]
+async fn test_answers_cite_sources_by_number() {
+    let server = create_test_app();
+    let job_id = run_to_completion(&server, json!({"query": "What is Rust?"})).await;
+
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    let answer = &job["answer"];
+    let detail = answer["detail"].as_str().unwrap();
+    assert!(detail.contains("[1]"));
+    assert!(!detail.contains("[src_"));
+    let references = answer["references"].as_array().unwrap();
+    assert_eq!(references[0]["number"], 1);
+    let citation = &answer["citations"][0];
+    let reference = citation["reference"].as_u64().unwrap() as usize;
+    assert_eq!(
+        references[reference - 1]["source_id"],
+        citation["source_id"]
+    );
+}
 
-    let llm_registry = LlmRegistry::from_config(http, &llm_config);
+#[tokio::test]
+async fn test_answer_sentences_map_to_their_evidence() {
+    let server = create_test_app();
+    let job_id = run_to_completion(&server, json!({"query": "What is Rust?"})).await;
+
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    let answer = &job["answer"];
+    let detail: Vec<char> = answer["detail"].as_str().unwrap().chars().collect();
+    let evidence = answer["evidence"].as_array().unwrap();
+    let cited = evidence
+        .iter()
+        .find(|e| !e["citations"].as_array().unwrap().is_empty())
+        .unwrap();
+    let (start, end) = (
+        cited["start"].as_u64().unwrap() as usize,
+        cited["end"].as_u64().unwrap() as usize,
+    );
+    let sentence: String = detail[start..end].iter().collect();
+    assert!(sentence.ends_with("[1][2][3]."));
+    assert_eq!(cited["citations"], json!([0, 1, 2]));
+    assert_eq!(cited["source_ids"][0], answer["citations"][0]["source_id"]);
+}
 
-    let store = Arc::new(MockStore::new());
-    let state = Arc::new(AppState::with_registries(
-        store,
-        search_registry,
-        llm_registry,
-    ));
+#[tokio::test]
+async fn test_high_stakes_answers_are_sampled_for_consistency() {
+    let server = create_test_app();
+    let job_id = run_to_completion(
+        &server,
+        json!({"query": "What is Rust?", "high_stakes": true}),
+    )
+    .await;
+
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert_eq!(job["high_stakes"], true);
+    let consistency = &job["answer"]["consistency"];
+    assert_eq!(consistency["samples"], 3);
+    assert_eq!(consistency["answered"], 3);
+    assert_eq!(consistency["agreement"], 1.0);
+    assert_eq!(consistency["disputed"], json!([]));
+    assert_eq!(job["answer"]["token_usage"]["total_tokens"], 1500);
+
+    let job_id = run_to_completion(&server, json!({"query": "What is Rust?"})).await;
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert_eq!(job["high_stakes"], false);
+    assert!(job["answer"].get("consistency").is_none());
 
-    Some(TestServer::new(app(state)).unwrap())
+    let response = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?", "sources_only": true, "high_stakes": true}))
+        .await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
 }
 
 #[tokio::test]
-async fn test_health_check() {
+async fn test_cited_quotes_are_highlighted() {
     let server = create_test_app();
+    let job_id = run_to_completion(&server, json!({"query": "What is Rust?"})).await;
+
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    let citation = &job["answer"]["citations"][0];
+    assert_eq!(citation["quote_found"], true);
+    assert_eq!(citation["quote_span"]["exact"], true);
+    let anchored_url = citation["anchored_url"].as_str().unwrap();
+    assert!(anchored_url.contains("#:~:text="));
+
+    let body: Value = server
+        .get(&format!("/v1/jobs/{}/sources?include_content=true", job_id))
+        .await
+        .json();
+    let source = body["sources"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["id"] == citation["source_id"])
+        .unwrap();
+    let highlight = &source["highlights"][0];
+    assert_eq!(highlight["text"], citation["quote"]);
+    assert_eq!(highlight["start"], citation["quote_span"]["start"]);
+    let content: Vec<char> = source["content"].as_str().unwrap().chars().collect();
+    let start = highlight["start"].as_u64().unwrap() as usize;
+    let end = highlight["end"].as_u64().unwrap() as usize;
+    assert_eq!(
+        content[start..end].iter().collect::<String>(),
+        highlight["text"].as_str().unwrap()
+    );
+
+    let body: Value = server
+        .get(&format!("/v1/jobs/{}/sources", job_id))
+        .await
+        .json();
+    assert!(body["sources"][0].get("content").is_none());
+}
 
-    let response = server.get("/health").await;
+#[tokio::test]
+async fn test_sources_group_by_domain() {
+    let (server, job_id) = create_app_with_sources(vec![
+        scored_source("https://example.com/a", 0.6),
+        scored_source("https://rust-lang.org/b", 0.9),
+        scored_source("https://example.com/c", 0.8),
+    ])
+    .await;
 
+    let response = server
+        .get(&format!("/v1/jobs/{}/sources?group_by=domain", job_id))
+        .await;
     response.assert_status_ok();
+
     let body: Value = response.json();
-    assert_eq!(body["status"], "healthy");
-    assert!(body["version"].as_str().is_some());
+    let groups = body["domains"].as_array().unwrap();
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0]["domain"], "rust-lang.org");
+    assert_eq!(groups[1]["domain"], "example.com");
+    assert_eq!(groups[1]["count"], 2);
+    assert!((groups[1]["best_score"].as_f64().unwrap() - 0.8).abs() < 1e-6);
+    assert_eq!(groups[1]["source_ids"].as_array().unwrap().len(), 2);
+    assert_eq!(body["sources"].as_array().unwrap().len(), 3);
 }
 
 #[tokio::test]
-async fn test_research_happy_path() {
-    let server = create_test_app();
+async fn test_sources_rejects_invalid_params() {
+    let (server, job_id) = create_app_with_sources(vec![]).await;
 
     let response = server
-        .post("/v1/research")
-        .json(&json!({"query": "What is Rust?"}))
+        .get(&format!("/v1/jobs/{}/sources?min_score=2", job_id))
         .await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
 
-    response.assert_status(axum::http::StatusCode::ACCEPTED);
-
+    let response = server
+        .get(&format!("/v1/jobs/{}/sources?sort=random", job_id))
+        .await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
     let body: Value = response.json();
-    let job_id = body["job_id"].as_str().unwrap();
-    assert!(job_id.starts_with("job_"));
-    assert_eq!(body["status"], "pending");
+    assert_eq!(body["error"]["code"], "validation_error");
+}
 
-    tokio::time::sleep(Duration::from_millis(100)).await;
+#[tokio::test]
+async fn test_sources_bibtex_export() {
+    let (server, job_id) = create_app_with_sources(vec![
+        scored_source("https://rust-lang.org/a", 0.5),
+        scored_source("https://example.com/b", 0.9),
+    ])
+    .await;
 
-    let response = server.get(&format!("/v1/jobs/{}", job_id)).await;
+    let response = server
+        .get(&format!("/v1/jobs/{}/sources.bib", job_id))
+        .await;
     response.assert_status_ok();
+    assert!(response
+        .header("content-type")
+        .to_str()
+        .unwrap()
+        .starts_with("application/x-bibtex"));
+
+    let bib = response.text();
+    assert_eq!(bib.matches("@misc{").count(), 2);
+    assert!(bib.contains("url = {https://rust-lang.org/a}"));
+}
 
-    let job: Value = response.json();
-    assert_eq!(job["job_id"], job_id);
-    assert!([
-        "pending",
-        "planning",
-        "searching",
-        "synthesizing",
-        "completed"
-    ]
-    .contains(&job["status"].as_str().unwrap()));
+#[tokio::test]
+async fn test_sources_csl_json_export() {
+    let (server, job_id) =
+        create_app_with_sources(vec![scored_source("https://rust-lang.org/a", 0.5)]).await;
+
+    let response = server
+        .get(&format!("/v1/jobs/{}/sources.csl.json", job_id))
+        .await;
+    response.assert_status_ok();
+
+    let items: Value = response.json();
+    assert_eq!(items[0]["type"], "webpage");
+    assert_eq!(items[0]["URL"], "https://rust-lang.org/a");
+    assert!(items[0]["accessed"]["date-parts"].is_array());
 }
 
 #[tokio::test]
-async fn test_invalid_query_empty() {
+async fn test_sources_export_not_found() {
     let server = create_test_app();
 
-    let response = server
+    let response = server.get("/v1/jobs/job_abcdef123456/sources.bib").await;
+    response.assert_status(axum::http::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_stream_emits_typed_events() {
+    let server = create_test_app();
+
+    let body: Value = server
         .post("/v1/research")
-        .json(&json!({"query": ""}))
-        .await;
+        .json(&json!({"query": "What is Rust?"}))
+        .await
+        .json();
+    let job_id = body["job_id"].as_str().unwrap();
 
-    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+    let response = server.get(&format!("/v1/jobs/{}/stream", job_id)).await;
+    response.assert_status_ok();
 
-    let body: Value = response.json();
-    assert!(body["error"]["code"].as_str().is_some());
+    let text = response.text();
+    let events: Vec<&str> = text
+        .lines()
+        .filter_map(|line| line.strip_prefix("event: "))
+        .collect();
+    assert_eq!(events.first(), Some(&"status"));
+    assert!(events.contains(&"source_found"));
+    assert!(events.contains(&"token"));
+    assert_eq!(events.last(), Some(&"complete"));
+
+    let complete: Value = text
+        .lines()
+        .filter_map(|line| line.strip_prefix("data: "))
+        .next_back()
+        .map(|data| serde_json::from_str(data).unwrap())
+        .unwrap();
+    assert_eq!(complete["event"], "complete");
+    assert_eq!(complete["job_id"], job_id);
 }
 
 #[tokio::test]
-async fn test_invalid_query_too_long() {
+async fn test_jobs_report_progress_percentage() {
+    let server = create_test_app();
+    let job_id = run_to_completion(&server, json!({"query": "What is Rust?"})).await;
+
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert_eq!(job["progress"]["percent"], 100);
+    assert_eq!(job["progress"]["stage"], "completed");
+    assert_eq!(job["progress"]["queries"]["executed"], 1);
+    assert_eq!(job["progress"]["queries"]["planned"], 1);
+
+    let text = server
+        .get(&format!("/v1/jobs/{}/stream", job_id))
+        .await
+        .text();
+    let percents: Vec<u64> = text
+        .lines()
+        .filter_map(|line| line.strip_prefix("data: "))
+        .map(|data| serde_json::from_str::<Value>(data).unwrap())
+        .filter(|event| event["event"] == "progress")
+        .map(|event| event["progress"]["percent"].as_u64().unwrap())
+        .collect();
+    assert!(percents.len() > 2);
+    assert!(percents.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(percents.last(), Some(&100));
+
+    let listed: Value = server.get("/v1/jobs").await.json();
+    assert!(listed["jobs"][0].get("progress").is_none());
+}
+
+#[tokio::test]
+async fn test_trace_id_propagated_to_responses_and_events() {
     let server = create_test_app();
 
-    let long_query = "x".repeat(2001);
     let response = server
         .post("/v1/research")
-        .json(&json!({"query": long_query}))
+        .json(&json!({"query": "What is Rust?"}))
         .await;
+    let trace_id = response.header("x-gorkd-trace-id");
+    let trace_id = trace_id.to_str().unwrap().to_string();
+    assert!(trace_id.starts_with("trc_"));
+    let body: Value = response.json();
+    let job_id = body["job_id"].as_str().unwrap();
 
-    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+    let response = server.get(&format!("/v1/jobs/{}/stream", job_id)).await;
+    assert_eq!(response.header("x-gorkd-trace-id"), trace_id.as_str());
+    let text = response.text();
+    assert!(text
+        .lines()
+        .filter_map(|line| line.strip_prefix("data: "))
+        .map(|data| serde_json::from_str::<Value>(data).unwrap())
+        .all(|event| event["trace_id"] == trace_id.as_str()));
+
+    for path in ["", "/sources", "/events"] {
+        let response = server.get(&format!("/v1/jobs/{}{}", job_id, path)).await;
+        response.assert_status_ok();
+        assert_eq!(response.header("x-gorkd-trace-id"), trace_id.as_str());
+    }
 }
 
 #[tokio::test]
-async fn test_job_not_found() {
+async fn test_websocket_emits_typed_events() {
+    let state = AppState::new(
+        Arc::new(MockStore::new()),
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(MockLlmProvider::new("mock-gpt-4")),
+    );
+    let server = TestServer::builder()
+        .http_transport()
+        .build(app(Arc::new(state)))
+        .unwrap();
+
+    let body: Value = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?"}))
+        .await
+        .json();
+    let job_id = body["job_id"].as_str().unwrap();
+
+    let mut ws = server
+        .get_websocket(&format!("/v1/jobs/{}/ws", job_id))
+        .await
+        .into_websocket()
+        .await;
+
+    let first: Value = ws.receive_json().await;
+    assert_eq!(first["event"], "status");
+    assert_eq!(first["stage"], "pending");
+
+    let mut last = first;
+    while last["event"] != "complete" && last["event"] != "error" {
+        last = ws.receive_json().await;
+    }
+    assert_eq!(last["event"], "complete");
+}
+
+#[tokio::test]
+async fn test_job_events_history() {
     let server = create_test_app();
 
-    let response = server.get("/v1/jobs/job_abcdef123456").await;
+    let body: Value = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?"}))
+        .await
+        .json();
+    let job_id = body["job_id"].as_str().unwrap();
 
-    response.assert_status(axum::http::StatusCode::NOT_FOUND);
+    wait_for_terminal_job(&server, job_id).await;
+
+    let response = server.get(&format!("/v1/jobs/{}/events", job_id)).await;
+    response.assert_status_ok();
 
     let body: Value = response.json();
-    assert_eq!(body["error"]["code"], "not_found");
+    let events = body["events"].as_array().unwrap();
+    assert_eq!(events[0]["type"], "stage_changed");
+    assert_eq!(events[0]["data"]["status"], "planning");
+    assert_eq!(events[0]["elapsed_ms"], 0);
+    assert!(events.iter().any(|e| e["type"] == "provider_attempt"
+        && e["data"]["provider"] == "mock-tavily"
+        && e["data"]["succeeded"] == true));
+    assert_eq!(events.last().unwrap()["data"]["status"], "completed");
+
+    let sequences: Vec<u64> = events
+        .iter()
+        .map(|e| e["sequence"].as_u64().unwrap())
+        .collect();
+    assert!(sequences.windows(2).all(|w| w[0] < w[1]));
 }
 
 #[tokio::test]
-async fn test_invalid_job_id_format() {
+async fn test_job_events_not_found() {
     let server = create_test_app();
 
-    let response = server.get("/v1/jobs/invalid-format").await;
+    let response = server.get("/v1/jobs/job_abcdef123456/events").await;
 
-    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+    response.assert_status(axum::http::StatusCode::NOT_FOUND);
+}
+
+async fn run_captured_job(sink: Arc<dyn ArtifactSink>, store: Arc<MockStore>) -> Value {
+    let llm = MockLlmProvider::new("mock-gpt-4").with_script([MockLlmStep::Raw(
+        "Sure! Contact ops@example.com for details.".into(),
+    )]);
+    let state = AppState::new(
+        store,
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(llm),
+    )
+    .with_artifact_sink(Some(sink));
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    let body: Value = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?"}))
+        .await
+        .json();
+    let job_id = body["job_id"].as_str().unwrap().to_string();
+    let job = wait_for_terminal_job(&server, &job_id).await;
+    assert_eq!(job["status"], "failed");
+    assert_eq!(job["error_code"], "provider_error");
+    assert_eq!(job["failure"]["provider"], "mock-gpt-4");
+
+    server
+        .get(&format!("/v1/admin/jobs/{}/artifacts", job_id))
+        .await
+        .json()
 }
 
 #[tokio::test]
-async fn test_get_sources() {
-    let server = create_test_app();
+async fn test_artifacts_captured_in_store() {
+    let store = Arc::new(MockStore::new());
+    let sink = ArtifactCapture::Store
+        .sink(Arc::clone(&store) as Arc<dyn Store>)
+        .unwrap();
+
+    let body = run_captured_job(sink, store).await;
+
+    assert_eq!(body["sink"], "store");
+    let artifact = &body["artifacts"][0];
+    assert_eq!(artifact["stage"], "synthesis");
+    assert_eq!(artifact["model"], "mock-gpt-4");
+    assert_eq!(artifact["messages"][0]["role"], "system");
+    assert_eq!(artifact["messages"][1]["role"], "user");
+    assert_eq!(
+        artifact["raw_response"],
+        "Sure! Contact [EMAIL] for details."
+    );
+    assert!(artifact["error"]
+        .as_str()
+        .unwrap()
+        .contains("failed to parse"));
+}
 
-    let response = server
+#[tokio::test]
+async fn test_artifacts_captured_in_directory() {
+    let dir = std::env::temp_dir().join(format!("gorkd-artifacts-{}", std::process::id()));
+    let sink = Arc::new(DirectoryArtifactSink::new(&dir));
+
+    let body = run_captured_job(sink, Arc::new(MockStore::new())).await;
+
+    assert_eq!(body["sink"], "directory");
+    assert_eq!(body["artifacts"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        body["artifacts"][0]["raw_response"],
+        "Sure! Contact [EMAIL] for details."
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_reprocesses_job_from_captured_responses() {
+    let store = Arc::new(MockStore::new());
+    let sink = ArtifactCapture::Store
+        .sink(Arc::clone(&store) as Arc<dyn Store>)
+        .unwrap();
+    let llm = MockLlmProvider::new("mock-gpt-4").with_script([MockLlmStep::Raw(
+        r#"{"summary": "Rust is fast.", "detail": "It compiles to native code."}"#.into(),
+    )]);
+    let state = AppState::new(
+        store,
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(llm),
+    )
+    .with_artifact_sink(Some(sink));
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    let body: Value = server
         .post("/v1/research")
         .json(&json!({"query": "What is Rust?"}))
+        .await
+        .json();
+    let job_id = body["job_id"].as_str().unwrap().to_string();
+    let job = wait_for_terminal_job(&server, &job_id).await;
+    assert_eq!(job["status"], "completed");
+
+    let response = server
+        .post(&format!("/v1/admin/jobs/{}/reprocess", job_id))
         .await;
 
+    response.assert_status_ok();
     let body: Value = response.json();
+    assert_eq!(body["job_id"], job_id.as_str());
+    assert!(body["search_responses"].as_u64().unwrap() > 0);
+    assert!(body["sources"].as_u64().unwrap() > 0);
+    assert_eq!(body["answer_reparsed"], true);
+}
+
+#[tokio::test]
+async fn test_artifacts_disabled_by_default() {
+    let server = create_test_app();
+
+    let body: Value = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?"}))
+        .await
+        .json();
     let job_id = body["job_id"].as_str().unwrap();
 
-    tokio::time::sleep(Duration::from_millis(200)).await;
+    let response = server
+        .get(&format!("/v1/admin/jobs/{}/artifacts", job_id))
+        .await;
 
-    let response = server.get(&format!("/v1/jobs/{}/sources", job_id)).await;
+    response.assert_status(axum::http::StatusCode::NOT_FOUND);
+    let error: Value = response.json();
+    assert_eq!(error["error"]["code"], "feature_disabled");
+}
+
+#[tokio::test]
+async fn test_exports_and_imports_jobs_as_jsonl() {
+    let server = create_test_app();
+    let first = run_to_completion(&server, json!({"query": "What is Rust?"})).await;
+    let second = run_to_completion(&server, json!({"query": "What is Go?"})).await;
+
+    let response = server.get("/v1/admin/jobs/export").await;
     response.assert_status_ok();
+    assert_eq!(response.header("content-type"), "application/x-ndjson");
+    let export = response.text();
+    let lines: Vec<Value> = export
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines.iter().all(|l| l["answer"]["summary"].is_string()));
+    assert!(lines
+        .iter()
+        .all(|l| !l["sources"].as_array().unwrap().is_empty()));
+
+    let other = create_test_app();
+    let imported: Value = other
+        .post("/v1/admin/jobs/import")
+        .bytes(export.clone().into())
+        .await
+        .json();
+    assert_eq!(imported, json!({"imported": 2, "skipped": 0}));
+
+    for job_id in [&first, &second] {
+        let job: Value = other.get(&format!("/v1/jobs/{}", job_id)).await.json();
+        assert_eq!(job["status"], "completed");
+        assert!(job["answer"]["summary"].is_string());
+        let sources: Value = other
+            .get(&format!("/v1/jobs/{}/sources", job_id))
+            .await
+            .json();
+        assert!(!sources["sources"].as_array().unwrap().is_empty());
+    }
 
-    let body: Value = response.json();
-    assert!(body["sources"].is_array());
+    let again: Value = other
+        .post("/v1/admin/jobs/import")
+        .bytes(export.into())
+        .await
+        .json();
+    assert_eq!(again, json!({"imported": 0, "skipped": 2}));
+
+    let response = other
+        .post("/v1/admin/jobs/import")
+        .bytes("\n{\"job\": 1}\n".into())
+        .await;
+    response.assert_status_bad_request();
+    assert!(response.text().contains("line 2"));
 }
 
 #[tokio::test]
-async fn test_pipeline_completes() {
-    let server = create_test_app();
+async fn test_worker_runs_jobs_the_api_queued() {
+    // The API and the worker share one store, as queue mode requires.
+    let store: Arc<dyn Store> = Arc::new(MockStore::new());
+    let state = AppState::new(
+        Arc::clone(&store),
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(MockLlmProvider::new("mock-gpt-4")),
+    )
+    .with_job_execution(JobExecution::Queue);
+    let worker = Worker::new(
+        Arc::clone(&store),
+        Arc::new(state.pipeline()),
+        WorkerConfig::default(),
+    );
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    let body: Value = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?"}))
+        .await
+        .json();
+    let job_id = body["job_id"].as_str().unwrap();
 
-    let response = server
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert_eq!(job["status"], "pending");
+
+    assert!(worker.run_once().await.unwrap());
+    let job: Value = server.get(&format!("/v1/jobs/{}", job_id)).await.json();
+    assert_eq!(job["status"], "completed");
+    assert!(!worker.run_once().await.unwrap());
+}
+
+#[tokio::test]
+async fn test_publishes_job_lifecycle_events() {
+    let publisher = Arc::new(MockEventPublisher::new());
+    let state = AppState::new(
+        Arc::new(MockStore::new()),
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(MockLlmProvider::new("mock-gpt-4")),
+    )
+    .with_event_publisher(Some(publisher.clone()));
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    let body: Value = server
         .post("/v1/research")
-        .json(&json!({"query": "What is Rust programming language?"}))
+        .json(&json!({"query": "What is Rust?"}))
+        .await
+        .json();
+    let job_id = body["job_id"].as_str().unwrap();
+    wait_for_terminal_job(&server, job_id).await;
+
+    let events: Vec<Value> = publisher
+        .events()
+        .iter()
+        .map(|e| serde_json::to_value(e).unwrap())
+        .collect();
+    let types: Vec<&str> = events.iter().map(|e| e["type"].as_str().unwrap()).collect();
+    assert_eq!(
+        types,
+        [
+            "created",
+            "stage_changed",
+            "stage_changed",
+            "stage_changed",
+            "completed"
+        ]
+    );
+    assert_eq!(events[0]["query"], "What is Rust?");
+    assert!(events
+        .iter()
+        .all(|e| e["job_id"] == job_id && e["schema_version"] == 1));
+}
+
+#[tokio::test]
+async fn test_rejects_jobs_when_queue_full() {
+    let state = AppState::new(
+        Arc::new(MockStore::new()),
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(MockLlmProvider::new("mock-gpt-4").with_latency(Duration::from_millis(200))),
+    )
+    .with_job_queue(QueueConfig {
+        max_depth: Some(1),
+        retry_after: Duration::from_secs(7),
+    });
+    let server = TestServer::new(app(Arc::new(state))).unwrap();
+
+    let first = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?"}))
         .await;
+    first.assert_status(axum::http::StatusCode::ACCEPTED);
+    let job_id = first.json::<Value>()["job_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let health: Value = server.get("/health").await.json();
+    assert_eq!(health["queue"]["depth"], 1);
+    assert_eq!(health["queue"]["max_depth"], 1);
+    assert_eq!(health["queue"]["saturated"], true);
+
+    let rejected = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Go?"}))
+        .await;
+    rejected.assert_status(axum::http::StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(rejected.header("retry-after"), "7");
+    assert_eq!(rejected.json::<Value>()["error"]["code"], "overloaded");
+
+    wait_for_terminal_job(&server, &job_id).await;
+    // The slot frees when the pipeline task exits, just after the job completes.
+    for _ in 0..40 {
+        let health: Value = server.get("/health").await.json();
+        if health["queue"]["depth"] == 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Go?"}))
+        .await
+        .assert_status(axum::http::StatusCode::ACCEPTED);
+}
+
+#[tokio::test]
+async fn test_health_reports_unbounded_queue() {
+    let server = create_test_app();
+
+    let health: Value = server.get("/health").await.json();
+
+    assert_eq!(health["queue"]["depth"], 0);
+    assert!(health["queue"].get("max_depth").is_none());
+    assert_eq!(health["queue"]["saturated"], false);
+}
+
+/// Builds a server whose model is rate limited on its first call.
+fn create_flaky_app(
+    search: Arc<MockSearchProvider>,
+    store: Arc<MockStore>,
+    retry: RetryPolicy,
+) -> TestServer {
+    let llm = MockLlmProvider::new("mock-gpt-4").with_script([MockLlmStep::Fail(
+        LlmError::RateLimited {
+            retry_after_secs: None,
+        },
+    )]);
+    let state = AppState::new(store, search, Arc::new(llm)).with_retry_policy(retry);
+
+    TestServer::new(app(Arc::new(state))).unwrap()
+}
 
+#[tokio::test]
+async fn test_retry_failed_job_reuses_sources() {
+    let search = Arc::new(MockSearchProvider::new("mock-tavily"));
+    let server = create_flaky_app(
+        Arc::clone(&search),
+        Arc::new(MockStore::new()),
+        RetryPolicy::default(),
+    );
+    let body: Value = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?"}))
+        .await
+        .json();
+    let failed_id = body["job_id"].as_str().unwrap();
+    let failed = wait_for_terminal_job(&server, failed_id).await;
+    assert_eq!(failed["failure"]["stage"], "synthesizing");
+    let searches = search.call_count();
+
+    let response = server.post(&format!("/v1/jobs/{}/retry", failed_id)).await;
+
+    response.assert_status(axum::http::StatusCode::ACCEPTED);
     let body: Value = response.json();
-    let job_id = body["job_id"].as_str().unwrap();
+    assert_eq!(body["retried_from"], failed_id);
+    let retry_id = body["job_id"].as_str().unwrap();
+    assert_ne!(retry_id, failed_id);
+    assert_eq!(
+        response.header("x-gorkd-trace-id"),
+        server
+            .get(&format!("/v1/jobs/{}", retry_id))
+            .await
+            .header("x-gorkd-trace-id")
+    );
+
+    let retry = wait_for_terminal_job(&server, retry_id).await;
+    assert_eq!(retry["status"], "completed");
+    assert_eq!(retry["retried_from"], failed_id);
+    assert_eq!(retry["attempt"], 2);
+    assert_eq!(search.call_count(), searches);
+
+    let sources: Value = server
+        .get(&format!("/v1/jobs/{}/sources", retry_id))
+        .await
+        .json();
+    assert!(!sources["sources"].as_array().unwrap().is_empty());
+}
 
-    let mut completed = false;
-    for _ in 0..20 {
-        tokio::time::sleep(Duration::from_millis(50)).await;
+#[tokio::test]
+async fn test_retry_requires_failed_job() {
+    let server = create_test_app();
+    let job_id = run_to_completion(&server, json!({"query": "What is Rust?"})).await;
 
-        let response = server.get(&format!("/v1/jobs/{}", job_id)).await;
-        let job: Value = response.json();
+    let response = server.post(&format!("/v1/jobs/{}/retry", job_id)).await;
+    response.assert_status(axum::http::StatusCode::CONFLICT);
+    let body: Value = response.json();
+    assert_eq!(body["error"]["code"], "conflict");
 
-        if job["status"] == "completed" {
-            completed = true;
+    server
+        .post("/v1/jobs/job_abc123xyz456/retry")
+        .await
+        .assert_status_not_found();
+}
+
+#[tokio::test]
+async fn test_retry_policy_retries_failed_jobs() {
+    let store = Arc::new(MockStore::new());
+    let retry = RetryPolicy {
+        max_attempts: 2,
+        backoff: Duration::from_millis(1),
+        ..Default::default()
+    };
+    let server = create_flaky_app(
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::clone(&store),
+        retry,
+    );
+    let body: Value = server
+        .post("/v1/research")
+        .json(&json!({"query": "What is Rust?"}))
+        .await
+        .json();
+    let failed_id = body["job_id"].as_str().unwrap();
+    let failed = wait_for_terminal_job(&server, failed_id).await;
+    assert_eq!(failed["failure"]["kind"], "rate_limited");
+
+    let mut retry = None;
+    for _ in 0..40 {
+        let jobs = store.list_jobs(10, 0).await.unwrap();
+        retry = jobs.into_iter().find(|job| job.retried_from.is_some());
+        if retry.is_some() {
             break;
         }
-        if job["status"] == "failed" {
-            panic!("Pipeline failed: {:?}", job["error_message"]);
-        }
+        tokio::time::sleep(Duration::from_millis(25)).await;
     }
+    let retry = retry.expect("failed job was not retried");
 
-    assert!(completed, "Pipeline did not complete within timeout");
+    let retry = wait_for_terminal_job(&server, retry.id.as_str()).await;
+    assert_eq!(retry["status"], "completed");
+    assert_eq!(retry["retried_from"], failed_id);
+    assert_eq!(retry["attempt"], 2);
 }
 
 #[cfg(feature = "integration")]
@@ -353,3 +3306,235 @@ mod real_provider_tests {
         assert!(body["job_id"].as_str().is_some());
     }
 }
+
+fn check(vars: &[(&str, &str)]) -> ConfigReport {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    config_check::check_vars(|name| vars.get(name).cloned())
+}
+
+fn issue<'a>(report: &'a ConfigReport, setting: &str) -> Option<&'a ConfigIssue> {
+    report.issues.iter().find(|issue| issue.setting == setting)
+}
+
+#[test]
+fn test_config_check_reports_bad_settings() {
+    let report = check(&[
+        ("ANTHROPIC_API_KEY", "sk-ant-test"),
+        ("TAVILY_API_KEY", "tvly-test"),
+        ("PORT", "80800"),
+        ("LLM_TIMEOUT_SECS", "30s"),
+        ("OPENAI_BASE_URL", "api.openai.com"),
+        ("HTTP_PROXY_URL", "ftp://proxy:21"),
+        ("EXA_API_KEY", "exa key"),
+        ("JOB_EXECUTION", "queued"),
+        ("SEARCH_SHADOW_PROVIDER", "exa"),
+        ("RESEARCH_PROFILES_FILE", "/nonexistent/profiles.json"),
+        (
+            "CORPUS_FEEDS",
+            "news=https://news.example.com/rss,blog.example.com/atom",
+        ),
+    ]);
+
+    for setting in [
+        "PORT",
+        "LLM_TIMEOUT_SECS",
+        "OPENAI_BASE_URL",
+        "HTTP_PROXY_URL",
+        "EXA_API_KEY",
+        "JOB_EXECUTION",
+        "RESEARCH_PROFILES_FILE",
+        "CORPUS_FEEDS",
+    ] {
+        let found = issue(&report, setting).unwrap_or_else(|| panic!("{} not reported", setting));
+        assert_eq!(found.severity, Severity::Error, "{}", found);
+    }
+    // Exa counts as configured despite its bad key, so it may shadow Tavily.
+    assert!(issue(&report, "SEARCH_SHADOW_PROVIDER").is_none());
+    assert!(issue(&report, "ANTHROPIC_API_KEY").is_none());
+
+    let clean = check(&[
+        ("ANTHROPIC_API_KEY", "sk-ant-test"),
+        ("TAVILY_API_KEY", "tvly-test"),
+        ("PORT", "4000"),
+        (
+            "ANTHROPIC_BASE_URL",
+            "https://gateway.internal:8443/anthropic",
+        ),
+    ]);
+    assert!(clean.issues.is_empty(), "{:?}", clean.issues);
+}
+
+#[test]
+fn test_config_check_rejects_queue_mode_without_shared_store() {
+    let report = check(&[("ALLOW_MOCK_PROVIDERS", "true"), ("JOB_EXECUTION", "queue")]);
+    let found = issue(&report, "JOB_EXECUTION").expect("queue mode not reported");
+    assert_eq!(found.severity, Severity::Error);
+    assert!(found.message.contains("shared"), "{}", found);
+
+    let report = check(&[
+        ("ALLOW_MOCK_PROVIDERS", "true"),
+        ("JOB_EXECUTION", "inline"),
+    ]);
+    assert!(!report.has_errors(), "{:?}", report.issues);
+}
+
+#[test]
+fn test_config_check_requires_bucket_for_s3_object_storage() {
+    let report = check(&[("ALLOW_MOCK_PROVIDERS", "true"), ("OBJECT_STORAGE", "s3")]);
+    assert!(issue(&report, "S3_BUCKET").is_some());
+    assert!(issue(&report, "S3_ACCESS_KEY_ID").is_some());
+
+    let report = check(&[
+        ("ALLOW_MOCK_PROVIDERS", "true"),
+        ("OBJECT_STORAGE", "s3"),
+        ("S3_BUCKET", "gorkd-objects"),
+        ("S3_ENDPOINT", "http://localhost:9000"),
+        ("AWS_ACCESS_KEY_ID", "minio"),
+        ("AWS_SECRET_ACCESS_KEY", "minio-secret"),
+    ]);
+    assert!(!report.has_errors(), "{:?}", report.issues);
+
+    let report = check(&[("ALLOW_MOCK_PROVIDERS", "true"), ("OBJECT_STORAGE", "gcs")]);
+    assert!(issue(&report, "OBJECT_STORAGE").is_some());
+}
+
+#[test]
+fn test_config_check_requires_providers_unless_mocks_allowed() {
+    let report = check(&[("OPENAI_API_KEY", "not-a-key")]);
+    assert_eq!(
+        issue(&report, "OPENAI_API_KEY").map(|i| i.severity),
+        Some(Severity::Warning)
+    );
+    let missing_search = issue(&report, "TAVILY_API_KEY").unwrap();
+    assert_eq!(missing_search.severity, Severity::Error);
+    assert!(missing_search.message.contains("ALLOW_MOCK_PROVIDERS"));
+    assert!(issue(&report, "ANTHROPIC_API_KEY").is_none());
+
+    let report = check(&[("ALLOW_MOCK_PROVIDERS", "true")]);
+    assert!(!report.has_errors(), "{:?}", report.issues);
+    assert_eq!(report.issues.len(), 2);
+
+    let report = check(&[
+        ("ALLOW_MOCK_PROVIDERS", "true"),
+        ("SEARXNG_URL", "http://localhost:8080"),
+        ("SEARCH_SHADOW_PROVIDER", "searxng"),
+    ]);
+    let shadow = issue(&report, "SEARCH_SHADOW_PROVIDER").unwrap();
+    assert!(shadow.message.contains("only provider"), "{}", shadow);
+
+    let report = check(&[
+        ("LLM_WEBHOOK_URL", "https://llm.corp.example/generate"),
+        ("LLM_WEBHOOK_SCHEMA", "anthropic"),
+        ("SEARXNG_URL", "http://localhost:8080"),
+    ]);
+    assert!(issue(&report, "ANTHROPIC_API_KEY").is_none());
+    assert_eq!(
+        issue(&report, "LLM_WEBHOOK_SCHEMA").map(|i| i.severity),
+        Some(Severity::Error)
+    );
+}
+
+#[tokio::test]
+async fn test_config_check_flags_unavailable_models() {
+    let llm = LlmRegistry::builder()
+        .register("mock-gpt-4", Arc::new(MockLlmProvider::new("mock-gpt-4")))
+        .default_model("gpt-4o")
+        .fallback_models(["mock-gpt-4", "gpt-4o-mini"])
+        .shadow_model("mock-gpt-4")
+        .build();
+    let mut search = ProviderRegistry::new();
+    search.register(
+        "mock-tavily",
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+    );
+    let state = AppState::with_registries(Arc::new(MockStore::new()), search, llm);
+
+    let report = config_check::check_state(&state);
+
+    let default = issue(&report, "LLM_DEFAULT_MODEL").unwrap();
+    assert!(default.message.contains("gpt-4o"), "{}", default);
+    assert!(default.message.contains("mock-gpt-4"), "{}", default);
+    let fallback = issue(&report, "LLM_FALLBACK_MODEL").unwrap();
+    assert!(fallback.message.contains("gpt-4o-mini"), "{}", fallback);
+    assert!(issue(&report, "LLM_SHADOW_MODEL").is_none());
+
+    let state = AppState::new(
+        Arc::new(MockStore::new()),
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(MockLlmProvider::new("mock-gpt-4")),
+    );
+    assert!(config_check::check_state(&state).issues.is_empty());
+    assert!(config_check::check_live(&state).await.issues.is_empty());
+
+    let summary = ConfigSummary::of(&state).with("store", "in-memory");
+    let rows: HashMap<&str, &str> = summary
+        .rows()
+        .iter()
+        .map(|(setting, value)| (setting.as_str(), value.as_str()))
+        .collect();
+    assert_eq!(rows["default model"], "mock-gpt-4");
+    assert_eq!(rows["job execution"], "inline");
+    assert_eq!(rows["store"], "in-memory");
+    assert!(summary.to_string().lines().any(
+        |line| line.trim_start().starts_with("default model") && line.ends_with(" mock-gpt-4")
+    ));
+}
+
+#[tokio::test]
+async fn test_self_test_reports_each_component() {
+    let state = AppState::new(
+        Arc::new(MockStore::new()),
+        Arc::new(MockSearchProvider::new("mock-tavily")),
+        Arc::new(MockLlmProvider::new("mock-gpt-4")),
+    );
+
+    let report = self_test::run(&state).await;
+
+    assert!(report.passed(), "{}", report);
+    let components: Vec<&str> = report
+        .results
+        .iter()
+        .map(|result| result.component.as_str())
+        .collect();
+    assert_eq!(
+        components,
+        [
+            "configuration",
+            "store",
+            "llm: mock (mock-gpt-4)",
+            "pipeline"
+        ]
+    );
+    let jobs = state
+        .store
+        .list_jobs_by_tag("self-test", 10, 0)
+        .await
+        .unwrap();
+    assert_eq!(jobs.len(), 1);
+
+    let mut search = ProviderRegistry::new();
+    search.register(
+        "mock-tavily",
+        Arc::new(MockSearchProvider::new("mock-tavily").fail_after(0)),
+    );
+    let llm = LlmRegistry::builder()
+        .register("mock-gpt-4", Arc::new(MockLlmProvider::new("mock-gpt-4")))
+        .default_model("mock-gpt-4")
+        .build();
+    let state = AppState::with_registries(Arc::new(MockStore::new()), search, llm);
+
+    let report = self_test::run(&state).await;
+
+    assert!(!report.passed());
+    let failed: Vec<&str> = report
+        .results
+        .iter()
+        .filter(|result| !result.passed)
+        .map(|result| result.component.as_str())
+        .collect();
+    assert_eq!(failed, ["search: mock-tavily", "pipeline"]);
+    assert!(report.to_string().ends_with("2 of 5 checks failed"));
+}
@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use reqwest::{Response, StatusCode};
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+use crate::dto::{
+    ApiErrorResponse, CreateResearchRequest, CreateResearchResponse, JobResponse,
+    JobSourcesResponse, SourceResponse,
+};
+
+/// Timeout of each call to the API. Jobs run in the background, so no call
+/// waits on research.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Error)]
+pub enum ApiError {
+    /// The API refused the request, e.g. an invalid query; the message is
+    /// the API's and fit to show the user.
+    #[error("{message}")]
+    Rejected { code: String, message: String },
+    /// The API could not be reached or answered with a server error.
+    #[error("gorkd API unavailable: {0}")]
+    Unavailable(String),
+}
+
+/// Calls the gorkd HTTP API.
+#[derive(Clone)]
+pub struct ApiClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl ApiClient {
+    pub fn new(base_url: impl Into<String>) -> Result<Self, ApiError> {
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("gorkd-bot-discord/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| ApiError::Unavailable(e.to_string()))?;
+
+        Ok(Self {
+            http,
+            base_url: base_url.into(),
+        })
+    }
+
+    pub async fn create_research(&self, query: &str) -> Result<CreateResearchResponse, ApiError> {
+        let response = self
+            .http
+            .post(format!("{}/v1/research", self.base_url))
+            .json(&CreateResearchRequest { query })
+            .send()
+            .await;
+        parse(response).await
+    }
+
+    pub async fn get_job(&self, job_id: &str) -> Result<JobResponse, ApiError> {
+        let response = self
+            .http
+            .get(format!("{}/v1/jobs/{}", self.base_url, job_id))
+            .send()
+            .await;
+        parse(response).await
+    }
+
+    pub async fn get_sources(&self, job_id: &str) -> Result<Vec<SourceResponse>, ApiError> {
+        let response = self
+            .http
+            .get(format!("{}/v1/jobs/{}/sources", self.base_url, job_id))
+            .send()
+            .await;
+        parse::<JobSourcesResponse>(response)
+            .await
+            .map(|body| body.sources)
+    }
+}
+
+async fn parse<T: DeserializeOwned>(
+    response: Result<Response, reqwest::Error>,
+) -> Result<T, ApiError> {
+    let response = response.map_err(|e| ApiError::Unavailable(e.to_string()))?;
+    let status = response.status();
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| ApiError::Unavailable(e.to_string()))?;
+
+    if status.is_success() {
+        return serde_json::from_slice(&body)
+            .map_err(|e| ApiError::Unavailable(format!("unexpected response: {}", e)));
+    }
+    match serde_json::from_slice::<ApiErrorResponse>(&body) {
+        Ok(error) if status.is_client_error() || status == StatusCode::SERVICE_UNAVAILABLE => {
+            Err(ApiError::Rejected {
+                code: error.error.code,
+                message: error.error.message,
+            })
+        }
+        Ok(error) => Err(ApiError::Unavailable(error.error.message)),
+        Err(_) => Err(ApiError::Unavailable(format!("HTTP {}", status))),
+    }
+}
@@ -0,0 +1,83 @@
+use std::env;
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+
+pub const DEFAULT_API_URL: &str = "http://localhost:4000";
+
+/// How long a reply follows its job when `RESEARCH_TIMEOUT_SECS` is unset.
+pub const DEFAULT_RESEARCH_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Longest a reply can follow its job. Discord lets an interaction's reply
+/// be edited for 15 minutes, and the final edit needs time to spare.
+pub const MAX_RESEARCH_TIMEOUT: Duration = Duration::from_secs(14 * 60);
+
+/// How often a running job is checked for progress. Discord allows five
+/// edits per five seconds in a channel, shared by every reply in it.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Clone)]
+pub struct BotConfig {
+    pub discord_token: String,
+    /// Base URL of the gorkd API the bot creates jobs with.
+    pub api_url: String,
+    /// Base URL of the web UI, for linking a reply to the job's full
+    /// results.
+    pub web_ui_url: Option<String>,
+    /// How long a reply follows its job before pointing at the API instead.
+    pub research_timeout: Duration,
+}
+
+impl std::fmt::Debug for BotConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BotConfig")
+            .field("discord_token", &"[redacted]")
+            .field("api_url", &self.api_url)
+            .field("web_ui_url", &self.web_ui_url)
+            .field("research_timeout", &self.research_timeout)
+            .finish()
+    }
+}
+
+impl BotConfig {
+    /// Reads `DISCORD_TOKEN` (required), `GORKD_API_URL`, `WEB_UI_URL` and
+    /// `RESEARCH_TIMEOUT_SECS`.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let discord_token = env::var("DISCORD_TOKEN")
+            .ok()
+            .filter(|token| !token.trim().is_empty())
+            .context("DISCORD_TOKEN must be set")?;
+
+        let api_url = env::var("GORKD_API_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_API_URL.to_string());
+        if !api_url.starts_with("http://") && !api_url.starts_with("https://") {
+            bail!(
+                "GORKD_API_URL must be an http or https URL, got {:?}",
+                api_url
+            );
+        }
+
+        let research_timeout = match env::var("RESEARCH_TIMEOUT_SECS") {
+            Ok(secs) => {
+                let secs: u64 = secs
+                    .trim()
+                    .parse()
+                    .with_context(|| format!("RESEARCH_TIMEOUT_SECS: not a number: {:?}", secs))?;
+                Duration::from_secs(secs).min(MAX_RESEARCH_TIMEOUT)
+            }
+            Err(_) => DEFAULT_RESEARCH_TIMEOUT,
+        };
+
+        Ok(Self {
+            discord_token,
+            api_url: api_url.trim_end_matches('/').to_string(),
+            web_ui_url: env::var("WEB_UI_URL")
+                .ok()
+                .filter(|url| !url.trim().is_empty())
+                .map(|url| url.trim_end_matches('/').to_string()),
+            research_timeout,
+        })
+    }
+}
@@ -0,0 +1,93 @@
+//! The parts of the API's request and response bodies the bot uses.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+pub struct CreateResearchRequest<'a> {
+    pub query: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateResearchResponse {
+    pub job_id: String,
+    pub status: JobStatus,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Pending,
+    Planning,
+    Searching,
+    Fetching,
+    Synthesizing,
+    Completed,
+    Failed,
+}
+
+impl JobStatus {
+    pub fn is_terminal(self) -> bool {
+        matches!(self, Self::Completed | Self::Failed)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JobResponse {
+    pub job_id: String,
+    pub status: JobStatus,
+    pub query: String,
+    #[serde(default)]
+    pub error_message: Option<String>,
+    #[serde(default)]
+    pub answer: Option<AnswerResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AnswerResponse {
+    pub summary: String,
+    #[serde(default)]
+    pub detail: String,
+    #[serde(default)]
+    pub citations: Vec<CitationResponse>,
+    pub confidence: Confidence,
+    #[serde(default)]
+    pub model: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Confidence {
+    High,
+    Medium,
+    Low,
+    Insufficient,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CitationResponse {
+    pub source_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JobSourcesResponse {
+    pub sources: Vec<SourceResponse>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SourceResponse {
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    pub domain: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApiErrorResponse {
+    pub error: ApiErrorBody,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApiErrorBody {
+    pub code: String,
+    pub message: String,
+}
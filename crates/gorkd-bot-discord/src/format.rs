@@ -0,0 +1,283 @@
+//! Text of the bot's replies, kept apart from Discord types so it can be
+//! tested without a gateway.
+
+use std::time::Duration;
+
+use crate::dto::{Confidence, JobResponse, JobStatus, SourceResponse};
+
+/// Longest embed title Discord accepts.
+const MAX_TITLE_CHARS: usize = 256;
+
+/// Longest embed description Discord accepts.
+const MAX_DESCRIPTION_CHARS: usize = 4096;
+
+/// Longest embed field value Discord accepts.
+const MAX_FIELD_CHARS: usize = 1024;
+
+/// Cited sources listed in a reply; the rest are counted.
+const MAX_LISTED_SOURCES: usize = 5;
+
+pub const COLOR_HIGH: u32 = 0x2ecc71;
+pub const COLOR_MEDIUM: u32 = 0xf1c40f;
+pub const COLOR_LOW: u32 = 0xe67e22;
+pub const COLOR_ERROR: u32 = 0xe74c3c;
+
+/// An embed's content.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Card {
+    pub title: String,
+    pub description: String,
+    /// Name and value of each field, in order.
+    pub fields: Vec<(String, String)>,
+    pub footer: Option<String>,
+    pub url: Option<String>,
+    pub color: u32,
+}
+
+/// The reply while a job runs.
+pub fn progress(query: &str, status: JobStatus, elapsed: Duration) -> String {
+    let stage = match status {
+        JobStatus::Pending | JobStatus::Planning => "Researching your question...",
+        JobStatus::Searching => "Searching sources...",
+        JobStatus::Fetching => "Reading sources...",
+        JobStatus::Synthesizing => "Analyzing sources...",
+        JobStatus::Completed | JobStatus::Failed => "Finishing up...",
+    };
+    format!(
+        "🔍 {}\n\nQuery: \"{}\"\n⏱️ {}s",
+        stage,
+        truncate(query, 300),
+        elapsed.as_secs()
+    )
+}
+
+/// The reply once the job has answered, citing `sources` in the order the
+/// answer first cites them.
+pub fn answer(
+    job: &JobResponse,
+    sources: &[SourceResponse],
+    elapsed: Duration,
+    web_ui_url: Option<&str>,
+) -> Card {
+    let Some(answer) = job.answer.as_ref() else {
+        return failure(job);
+    };
+
+    let mut description = answer.summary.trim().to_string();
+    if !answer.detail.trim().is_empty() {
+        description.push_str("\n\n");
+        description.push_str(answer.detail.trim());
+    }
+
+    let mut cited: Vec<&SourceResponse> = Vec::new();
+    for citation in &answer.citations {
+        if let Some(source) = sources.iter().find(|s| s.id == citation.source_id) {
+            if !cited.iter().any(|c| c.id == source.id) {
+                cited.push(source);
+            }
+        }
+    }
+
+    let (label, color) = match answer.confidence {
+        Confidence::High => ("🟢 High", COLOR_HIGH),
+        Confidence::Medium => ("🟡 Medium", COLOR_MEDIUM),
+        Confidence::Low => ("🟠 Low", COLOR_LOW),
+        Confidence::Insufficient => ("🔴 Insufficient", COLOR_ERROR),
+    };
+    let mut fields = vec![(
+        "Confidence".to_string(),
+        format!("{} ({} cited sources)", label, cited.len()),
+    )];
+    if !cited.is_empty() {
+        fields.push(("Key Sources".to_string(), source_list(&cited)));
+    }
+
+    let mut footer = format!(
+        "⏱️ {}s • 📚 {} sources analyzed",
+        elapsed.as_secs(),
+        sources.len()
+    );
+    if !answer.model.is_empty() {
+        footer.push_str(&format!(" • {}", answer.model));
+    }
+
+    Card {
+        title: truncate(&format!("📊 {}", job.query), MAX_TITLE_CHARS),
+        description: truncate(&description, MAX_DESCRIPTION_CHARS),
+        fields,
+        footer: Some(footer),
+        url: web_ui_url.map(|base| format!("{}/jobs/{}", base, job.job_id)),
+        color,
+    }
+}
+
+/// The reply when the job failed.
+pub fn failure(job: &JobResponse) -> Card {
+    let reason = job
+        .error_message
+        .as_deref()
+        .unwrap_or("The research job failed.");
+    Card {
+        title: "⚠️ Research Failed".to_string(),
+        description: truncate(
+            &format!(
+                "**Question**\n{}\n\n{}\n\nTry rephrasing the question, or ask again later.",
+                job.query, reason
+            ),
+            MAX_DESCRIPTION_CHARS,
+        ),
+        fields: Vec::new(),
+        footer: Some(job.job_id.clone()),
+        url: None,
+        color: COLOR_ERROR,
+    }
+}
+
+/// The reply when the job outlasted the bot's wait.
+pub fn timed_out(query: &str, job_id: &str, api_url: &str) -> String {
+    format!(
+        "⏳ Still researching \"{}\". The answer will be at {}/v1/jobs/{}",
+        truncate(query, 300),
+        api_url,
+        job_id
+    )
+}
+
+/// The reply when the job could not be created or followed.
+pub fn error(message: &str) -> String {
+    format!("⚠️ {}", truncate(message, 1800))
+}
+
+fn source_list(sources: &[&SourceResponse]) -> String {
+    let mut list = String::new();
+    for (i, source) in sources.iter().take(MAX_LISTED_SOURCES).enumerate() {
+        let title = truncate(&source.title.replace(['[', ']'], ""), 80);
+        let line = format!(
+            "{}. [{}]({}) - {}\n",
+            i + 1,
+            title,
+            source.url,
+            source.domain
+        );
+        if list.chars().count() + line.chars().count() > MAX_FIELD_CHARS - 20 {
+            break;
+        }
+        list.push_str(&line);
+    }
+    let listed = list.lines().count();
+    if sources.len() > listed {
+        list.push_str(&format!("…and {} more", sources.len() - listed));
+    }
+    list.trim_end().to_string()
+}
+
+/// `text` cut to `max` characters, ending in an ellipsis when cut.
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(max - 1).collect();
+    cut.push('…');
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dto::{AnswerResponse, CitationResponse};
+
+    fn source(id: &str, domain: &str) -> SourceResponse {
+        SourceResponse {
+            id: id.to_string(),
+            url: format!("https://{}/article", domain),
+            title: format!("[Article] on {}", domain),
+            domain: domain.to_string(),
+        }
+    }
+
+    fn job(answer: Option<AnswerResponse>) -> JobResponse {
+        JobResponse {
+            job_id: "job_abc123xyz456".to_string(),
+            status: JobStatus::Completed,
+            query: "What caused the outage?".to_string(),
+            error_message: Some("No sources found".to_string()),
+            answer,
+        }
+    }
+
+    #[test]
+    fn lists_cited_sources_in_citation_order() {
+        let job = job(Some(AnswerResponse {
+            summary: "A faulty update.".to_string(),
+            detail: "It crashed hosts.".to_string(),
+            citations: ["src_b", "src_a", "src_b"]
+                .into_iter()
+                .map(|id| CitationResponse {
+                    source_id: id.to_string(),
+                })
+                .collect(),
+            confidence: Confidence::High,
+            model: "claude-sonnet-4-20250514".to_string(),
+        }));
+        let sources = [
+            source("src_a", "a.example"),
+            source("src_b", "b.example"),
+            source("src_c", "c.example"),
+        ];
+
+        let card = answer(
+            &job,
+            &sources,
+            Duration::from_secs(12),
+            Some("https://gorkd.example"),
+        );
+
+        assert_eq!(card.description, "A faulty update.\n\nIt crashed hosts.");
+        assert_eq!(card.color, COLOR_HIGH);
+        assert_eq!(card.fields[0].1, "🟢 High (2 cited sources)");
+        assert_eq!(
+            card.fields[1].1,
+            "1. [Article on b.example](https://b.example/article) - b.example\n\
+             2. [Article on a.example](https://a.example/article) - a.example"
+        );
+        assert_eq!(
+            card.url.as_deref(),
+            Some("https://gorkd.example/jobs/job_abc123xyz456")
+        );
+        assert!(card.footer.unwrap().starts_with("⏱️ 12s • 📚 3 sources"));
+    }
+
+    #[test]
+    fn fits_long_answers_into_embed_limits() {
+        let job = job(Some(AnswerResponse {
+            summary: "word ".repeat(2000),
+            detail: String::new(),
+            citations: Vec::new(),
+            confidence: Confidence::Low,
+            model: String::new(),
+        }));
+
+        let card = answer(&job, &[], Duration::ZERO, None);
+
+        assert_eq!(card.description.chars().count(), MAX_DESCRIPTION_CHARS);
+        assert!(card.description.ends_with('…'));
+        assert_eq!(card.fields.len(), 1);
+    }
+
+    #[test]
+    fn reports_failed_jobs() {
+        let card = answer(&job(None), &[], Duration::ZERO, None);
+
+        assert_eq!(card.color, COLOR_ERROR);
+        assert!(card.description.contains("No sources found"));
+    }
+
+    #[test]
+    fn shows_the_stage_of_running_jobs() {
+        let text = progress("Why?", JobStatus::Searching, Duration::from_secs(4));
+
+        assert!(text.contains("Searching sources..."));
+        assert!(text.contains("Query: \"Why?\""));
+        assert!(text.ends_with("4s"));
+    }
+}
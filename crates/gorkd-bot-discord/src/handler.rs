@@ -0,0 +1,79 @@
+use serenity::all::{
+    Command, CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption,
+    CreateInteractionResponse, CreateInteractionResponseMessage, EventHandler, Interaction, Ready,
+};
+use serenity::async_trait;
+use tracing::{info, warn};
+
+use gorkd_core::MAX_QUERY_LENGTH;
+
+use crate::api::ApiClient;
+use crate::config::BotConfig;
+use crate::research;
+
+pub struct Handler {
+    api: ApiClient,
+    config: BotConfig,
+}
+
+impl Handler {
+    pub fn new(api: ApiClient, config: BotConfig) -> Self {
+        Self { api, config }
+    }
+
+    async fn research(&self, ctx: &Context, command: &CommandInteraction) {
+        let query = command
+            .data
+            .options
+            .iter()
+            .find(|option| option.name == "query")
+            .and_then(|option| option.value.as_str())
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+
+        // Research outlasts the three seconds Discord waits for a reply, so
+        // the reply is deferred and edited as the job runs.
+        let deferred = CreateInteractionResponse::Defer(CreateInteractionResponseMessage::new());
+        if let Err(e) = command.create_response(&ctx.http, deferred).await {
+            warn!(error = %e, "failed to acknowledge /research");
+            return;
+        }
+
+        research::run(ctx, command, &self.api, &self.config, &query).await;
+    }
+}
+
+/// The `/research` command registered on startup.
+pub fn research_command() -> CreateCommand {
+    CreateCommand::new("research")
+        .description("Research a question and answer with cited sources")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "query", "What to research")
+                .required(true)
+                .max_length(MAX_QUERY_LENGTH as u16),
+        )
+}
+
+#[async_trait]
+impl EventHandler for Handler {
+    async fn ready(&self, ctx: Context, ready: Ready) {
+        info!(user = %ready.user.name, guilds = ready.guilds.len(), "connected to Discord");
+
+        match Command::create_global_command(&ctx.http, research_command()).await {
+            Ok(_) => info!("registered /research"),
+            Err(e) => warn!(error = %e, "failed to register /research"),
+        }
+    }
+
+    // Serenity runs each event in its own task, so a long research run does
+    // not hold up other interactions.
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        let Interaction::Command(command) = interaction else {
+            return;
+        };
+        if command.data.name == "research" {
+            self.research(&ctx, &command).await;
+        }
+    }
+}
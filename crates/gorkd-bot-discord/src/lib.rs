@@ -0,0 +1,17 @@
+//! Discord frontend for gorkd research.
+//!
+//! The bot is a thin adapter over the HTTP API: `/research` creates a job
+//! with `POST /v1/research`, the bot polls the job and edits its reply as
+//! the job moves through its stages, then replaces the reply with the
+//! answer and the sources it cites.
+
+pub mod api;
+pub mod config;
+pub mod dto;
+pub mod format;
+pub mod handler;
+pub mod research;
+
+pub use api::{ApiClient, ApiError};
+pub use config::BotConfig;
+pub use handler::Handler;
@@ -1,3 +1,5 @@
+use gorkd_bot_discord::{ApiClient, BotConfig, Handler};
+use serenity::all::{Client, GatewayIntents};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
@@ -7,8 +9,23 @@ async fn main() -> anyhow::Result<()> {
         .with(tracing_subscriber::EnvFilter::from_default_env())
         .init();
 
-    tracing::info!("Starting gorkd Discord bot");
-    tracing::warn!("Discord bot not yet implemented - exiting");
+    let config = BotConfig::from_env()?;
+    tracing::info!(api_url = %config.api_url, "Starting gorkd Discord bot");
 
+    let api = ApiClient::new(&config.api_url)?;
+    // Slash commands arrive as interactions, which need no intents.
+    let mut client = Client::builder(&config.discord_token, GatewayIntents::empty())
+        .event_handler(Handler::new(api, config.clone()))
+        .await?;
+
+    let shard_manager = client.shard_manager.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            tracing::info!("Shutting down");
+            shard_manager.shutdown_all().await;
+        }
+    });
+
+    client.start().await?;
     Ok(())
 }
@@ -0,0 +1,127 @@
+//! Follows one `/research` invocation from job creation to answer.
+
+use std::time::Instant;
+
+use serenity::all::{
+    CommandInteraction, Context, CreateEmbed, CreateEmbedFooter, EditInteractionResponse,
+};
+use tracing::{info, warn};
+
+use crate::api::{ApiClient, ApiError};
+use crate::config::{BotConfig, POLL_INTERVAL};
+use crate::dto::JobStatus;
+use crate::format::{self, Card};
+
+/// Creates a job for `query` and keeps the interaction's deferred reply
+/// current until the job ends or `config.research_timeout` passes.
+pub async fn run(
+    ctx: &Context,
+    command: &CommandInteraction,
+    api: &ApiClient,
+    config: &BotConfig,
+    query: &str,
+) {
+    let started = Instant::now();
+    let created = match api.create_research(query).await {
+        Ok(created) => created,
+        Err(e) => {
+            let message = match &e {
+                ApiError::Rejected { message, .. } => message.clone(),
+                ApiError::Unavailable(_) => {
+                    warn!(error = %e, "failed to create research job");
+                    "gorkd is unavailable right now; try again in a moment.".to_string()
+                }
+            };
+            edit(
+                ctx,
+                command,
+                EditInteractionResponse::new().content(format::error(&message)),
+            )
+            .await;
+            return;
+        }
+    };
+    let job_id = created.job_id;
+    info!(job_id = %job_id, user = %command.user.id, "research started");
+
+    let mut shown = format::progress(query, created.status, started.elapsed());
+    edit(ctx, command, EditInteractionResponse::new().content(&shown)).await;
+
+    let job = loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        if started.elapsed() >= config.research_timeout {
+            let text = format::timed_out(query, &job_id, &config.api_url);
+            edit(ctx, command, EditInteractionResponse::new().content(text)).await;
+            return;
+        }
+
+        // The API may restart or be briefly overloaded; the job survives
+        // that, so errors here are retried until the timeout.
+        let job = match api.get_job(&job_id).await {
+            Ok(job) => job,
+            Err(e) => {
+                warn!(job_id = %job_id, error = %e, "failed to poll research job");
+                continue;
+            }
+        };
+        if job.status.is_terminal() {
+            break job;
+        }
+
+        let text = format::progress(query, job.status, started.elapsed());
+        if text != shown {
+            edit(ctx, command, EditInteractionResponse::new().content(&text)).await;
+            shown = text;
+        }
+    };
+
+    let card = if job.status == JobStatus::Completed {
+        let sources = api.get_sources(&job_id).await.unwrap_or_else(|e| {
+            warn!(job_id = %job_id, error = %e, "failed to fetch job sources");
+            Vec::new()
+        });
+        format::answer(
+            &job,
+            &sources,
+            started.elapsed(),
+            config.web_ui_url.as_deref(),
+        )
+    } else {
+        format::failure(&job)
+    };
+    info!(job_id = %job_id, status = ?job.status, "research finished");
+
+    edit(
+        ctx,
+        command,
+        EditInteractionResponse::new()
+            .content("")
+            .embed(embed(card)),
+    )
+    .await;
+}
+
+fn embed(card: Card) -> CreateEmbed {
+    let mut embed = CreateEmbed::new()
+        .title(card.title)
+        .description(card.description)
+        .colour(card.color);
+    for (name, value) in card.fields {
+        embed = embed.field(name, value, false);
+    }
+    if let Some(footer) = card.footer {
+        embed = embed.footer(CreateEmbedFooter::new(footer));
+    }
+    if let Some(url) = card.url {
+        embed = embed.url(url);
+    }
+    embed
+}
+
+/// Edits the reply, logging failures: a reply that can no longer be edited
+/// (deleted, or past Discord's 15 minutes) leaves nothing to report to.
+async fn edit(ctx: &Context, command: &CommandInteraction, response: EditInteractionResponse) {
+    if let Err(e) = command.edit_response(&ctx.http, response).await {
+        warn!(error = %e, "failed to edit research reply");
+    }
+}
@@ -0,0 +1,249 @@
+//! Sentence-level alignment of two texts.
+//!
+//! Texts are split into sentences, which are matched by their longest common
+//! subsequence after normalization (case, punctuation and citation markers
+//! such as `[src_abc]` are ignored). Unmatched sentences between two matches
+//! are paired up when their wording is similar enough, so a reworded claim
+//! shows up as changed rather than as one removal and one addition.
+
+use std::collections::HashSet;
+
+/// Word overlap from which two unmatched sentences count as one changed
+/// sentence rather than an unrelated removal and addition.
+pub const CHANGED_SIMILARITY: f32 = 0.5;
+
+/// How a sentence of the old text relates to the new one.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Alignment<'a> {
+    Unchanged {
+        before: &'a str,
+        after: &'a str,
+    },
+    Changed {
+        before: &'a str,
+        after: &'a str,
+        similarity: f32,
+    },
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Splits `text` into trimmed sentences. A sentence ends at a line break, or
+/// at `.`, `!` or `?` followed by the end of the text or by whitespace and a
+/// capital letter, digit, quote or bracket, so `e.g. this` or `3.5` stay in
+/// one piece.
+pub fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+
+    for (i, c) in text.char_indices() {
+        // Where the sentence ends, and where the next one starts.
+        let boundary = match c {
+            '\n' => Some((i, i + 1)),
+            '.' | '!' | '?' => {
+                let end = i + c.len_utf8();
+                let rest = &text[end..];
+                let next = rest.trim_start().chars().next();
+                let spaced = rest.starts_with(char::is_whitespace);
+                let starts_sentence = next.is_some_and(|n| {
+                    n.is_uppercase() || n.is_ascii_digit() || matches!(n, '"' | '\'' | '(' | '[')
+                });
+                (next.is_none() || (spaced && starts_sentence)).then_some((end, end))
+            }
+            _ => None,
+        };
+
+        if let Some((end, next)) = boundary {
+            push_sentence(&mut sentences, &text[start..end]);
+            start = next;
+        }
+    }
+    push_sentence(&mut sentences, &text[start..]);
+
+    sentences
+}
+
+fn push_sentence<'a>(sentences: &mut Vec<&'a str>, sentence: &'a str) {
+    let sentence = sentence.trim();
+    if !sentence.is_empty() {
+        sentences.push(sentence);
+    }
+}
+
+/// Lowercased words of `sentence`, without citation markers.
+pub(crate) fn words(sentence: &str) -> Vec<String> {
+    let mut text = String::with_capacity(sentence.len());
+    let mut depth = 0;
+    for c in sentence.chars() {
+        match c {
+            '[' => depth += 1,
+            ']' if depth > 0 => depth -= 1,
+            _ if depth == 0 => text.extend(c.to_lowercase()),
+            _ => {}
+        }
+    }
+
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Jaccard similarity of the two sentences' word sets, from 0 to 1.
+pub fn similarity(a: &str, b: &str) -> f32 {
+    let a: HashSet<String> = words(a).into_iter().collect();
+    let b: HashSet<String> = words(b).into_iter().collect();
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(&b).count() as f32 / union as f32
+}
+
+/// Aligns the sentences of `before` with those of `after`, in text order.
+pub fn align_sentences<'a>(before: &[&'a str], after: &[&'a str]) -> Vec<Alignment<'a>> {
+    let old: Vec<Vec<String>> = before.iter().map(|s| words(s)).collect();
+    let new: Vec<Vec<String>> = after.iter().map(|s| words(s)).collect();
+
+    // lcs[i][j] is the common subsequence length of old[i..] and new[j..].
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut alignment = Vec::with_capacity(before.len().max(after.len()));
+    let (mut removed, mut added) = (Vec::new(), Vec::new());
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            pair_gap(&mut alignment, &mut removed, &mut added);
+            alignment.push(Alignment::Unchanged {
+                before: before[i],
+                after: after[j],
+            });
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            added.push(after[j]);
+            j += 1;
+        } else {
+            removed.push(before[i]);
+            i += 1;
+        }
+    }
+    pair_gap(&mut alignment, &mut removed, &mut added);
+
+    alignment
+}
+
+/// Pairs the sentences removed and added between two matches, most similar
+/// first, and appends the result to `alignment`.
+fn pair_gap<'a>(
+    alignment: &mut Vec<Alignment<'a>>,
+    removed: &mut Vec<&'a str>,
+    added: &mut Vec<&'a str>,
+) {
+    let mut taken = vec![false; added.len()];
+
+    for before in removed.drain(..) {
+        let best = added
+            .iter()
+            .enumerate()
+            .filter(|(k, _)| !taken[*k])
+            .map(|(k, after)| (k, similarity(before, after)))
+            .filter(|(_, score)| *score >= CHANGED_SIMILARITY)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        match best {
+            Some((k, similarity)) => {
+                taken[k] = true;
+                alignment.push(Alignment::Changed {
+                    before,
+                    after: added[k],
+                    similarity,
+                });
+            }
+            None => alignment.push(Alignment::Removed(before)),
+        }
+    }
+
+    alignment.extend(
+        added
+            .drain(..)
+            .zip(taken)
+            .filter(|(_, taken)| !taken)
+            .map(|(after, _)| Alignment::Added(after)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_sentences() {
+        let text = "Rust 1.75 shipped in 2023. It added async fn in traits! \
+                    Is it stable? Yes, e.g. for tokio.\nNew line";
+
+        assert_eq!(
+            split_sentences(text),
+            vec![
+                "Rust 1.75 shipped in 2023.",
+                "It added async fn in traits!",
+                "Is it stable?",
+                "Yes, e.g. for tokio.",
+                "New line",
+            ]
+        );
+        assert!(split_sentences("  \n ").is_empty());
+    }
+
+    #[test]
+    fn ignores_case_punctuation_and_citations() {
+        assert_eq!(
+            similarity(
+                "The outage began on July 19 [src_abc].",
+                "the outage began on july 19"
+            ),
+            1.0
+        );
+        assert!(similarity("Cats purr.", "Dogs bark.") < CHANGED_SIMILARITY);
+    }
+
+    #[test]
+    fn aligns_unchanged_changed_removed_and_added() {
+        let before = [
+            "The outage began on July 19.",
+            "About 8.5 million devices were affected.",
+            "Microsoft blamed the EU.",
+        ];
+        let after = [
+            "The outage began on July 19 [src_1].",
+            "About 8.5 million Windows devices were affected.",
+            "CrowdStrike published a root cause analysis.",
+        ];
+
+        let alignment = align_sentences(&before, &after);
+
+        assert!(matches!(alignment[0], Alignment::Unchanged { .. }));
+        assert!(matches!(
+            alignment[1],
+            Alignment::Changed {
+                before: "About 8.5 million devices were affected.",
+                ..
+            }
+        ));
+        assert_eq!(alignment[2], Alignment::Removed("Microsoft blamed the EU."));
+        assert_eq!(
+            alignment[3],
+            Alignment::Added("CrowdStrike published a root cause analysis.")
+        );
+        assert_eq!(alignment.len(), 4);
+    }
+}
@@ -2,7 +2,27 @@ use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
+use crate::budget::{BudgetReport, ModelPricing};
+use crate::chat::TokenUsage;
+use crate::consistency::ConsistencyReport;
+use crate::entity::KeyEntity;
+use crate::escalation::EscalationReport;
+use crate::evidence::SentenceEvidence;
+use crate::freshness::AnswerFreshness;
+use crate::highlight::QuoteLocation;
 use crate::id::SourceId;
+use crate::moderation::ModerationVerdict;
+use crate::references::Reference;
+use crate::routing::RoutingDecision;
+use crate::source::Source;
+use crate::traits::LinkStatus;
+
+/// Most follow-up questions kept with an answer.
+pub const MAX_SUGGESTED_FOLLOWUPS: usize = 3;
+
+/// Most missing source kinds and reformulated queries kept in a
+/// [`WhyInsufficient`].
+pub const MAX_INSUFFICIENCY_ENTRIES: usize = 5;
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -14,11 +34,60 @@ pub enum Confidence {
     Insufficient,
 }
 
+impl Confidence {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::High => "high",
+            Self::Medium => "medium",
+            Self::Low => "low",
+            Self::Insufficient => "insufficient",
+        }
+    }
+
+    /// Parses `high`, `medium`, `low` or `insufficient`, ignoring case.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "high" => Some(Self::High),
+            "medium" => Some(Self::Medium),
+            "low" => Some(Self::Low),
+            "insufficient" => Some(Self::Insufficient),
+            _ => None,
+        }
+    }
+
+    /// Whether this is less confident than `other`.
+    pub fn is_below(&self, other: &Confidence) -> bool {
+        self.rank() > other.rank()
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Self::High => 0,
+            Self::Medium => 1,
+            Self::Low => 2,
+            Self::Insufficient => 3,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Citation {
     pub claim: String,
     pub source_id: SourceId,
     pub quote: Option<String>,
+    /// Where `quote` was found in the cited source, once looked up.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote_location: Option<QuoteLocation>,
+    /// The source URL with a text fragment that jumps to the quote.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anchored_url: Option<String>,
+    /// Whether the cited page could be reached when the answer was
+    /// delivered, once checked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_status: Option<LinkStatus>,
+    /// The number the answer's text cites the source by, once numbered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<usize>,
 }
 
 impl Citation {
@@ -27,6 +96,10 @@ impl Citation {
             claim: claim.into(),
             source_id,
             quote: None,
+            quote_location: None,
+            anchored_url: None,
+            link_status: None,
+            reference: None,
         }
     }
 
@@ -36,12 +109,122 @@ impl Citation {
     }
 }
 
+/// Pipeline stage an LLM call was made for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum LlmStage {
+    Planning,
+    /// Planning the sections of a long-form answer.
+    Outline,
+    Synthesis,
+    /// Drawing key entities or knowledge base facts from the answer's
+    /// sources.
+    Extraction,
+    Verification,
+}
+
+/// Tokens one stage consumed on one model, summed over its calls.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageTokenUsage {
+    pub stage: LlmStage,
+    pub model: String,
+    pub prompt_tokens: usize,
+    pub completion_tokens: usize,
+}
+
+impl StageTokenUsage {
+    pub fn new(stage: LlmStage, model: impl Into<String>, usage: &TokenUsage) -> Self {
+        Self {
+            stage,
+            model: model.into(),
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.prompt_tokens + self.completion_tokens
+    }
+}
+
+/// How much of the model's context window the prompt that wrote an answer
+/// filled, and which of the collected sources it carried.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContextUsage {
+    /// Tokens the model's context window holds.
+    pub window_tokens: usize,
+    /// Tokens of the synthesis prompt.
+    pub prompt_tokens: usize,
+    /// Sources collected for the answer.
+    pub sources_available: usize,
+    /// Sources given to the model; the rest were left out by the context
+    /// source limit, or by the job's budget.
+    pub sources_used: usize,
+    /// Sources given to the model with their content shortened to fit the
+    /// content limits.
+    pub sources_truncated: usize,
+}
+
+impl ContextUsage {
+    /// The context usage of a `prompt_tokens` prompt on a model with a
+    /// `window_tokens` window, written from `used` of `available` sources.
+    pub fn new(
+        window_tokens: usize,
+        prompt_tokens: usize,
+        available: usize,
+        used: &[Source],
+    ) -> Self {
+        Self {
+            window_tokens,
+            prompt_tokens,
+            sources_available: available,
+            sources_used: used.len(),
+            sources_truncated: used.iter().filter(|s| s.truncated).count(),
+        }
+    }
+
+    /// Share of the context window the prompt filled, in percent.
+    pub fn utilization(&self) -> f32 {
+        if self.window_tokens == 0 {
+            return 0.0;
+        }
+        self.prompt_tokens as f32 / self.window_tokens as f32 * 100.0
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SynthesisMetadata {
     pub model: String,
+    /// Tokens used by the synthesis call alone.
     pub tokens_used: usize,
     #[serde(with = "duration_millis")]
     pub synthesis_duration: Duration,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub moderation: Option<ModerationVerdict>,
+    /// Token usage of every LLM stage of the job, in the order the stages ran.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stage_usage: Vec<StageTokenUsage>,
+    /// What the model's stages cost in US dollars, when its price is known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+    /// How the job's budget shaped synthesis, when it had one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<BudgetReport>,
+    /// The model routing chose for the job, when routing is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routing: Option<RoutingDecision>,
+    /// How the job searched again after a first answer below the
+    /// escalation threshold, when it did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escalation: Option<EscalationReport>,
+    /// How the samples of a high-stakes job agreed, when it sampled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub consistency: Option<ConsistencyReport>,
+    /// How full the model's context window was, when the provider reports
+    /// the prompt's tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<ContextUsage>,
 }
 
 impl SynthesisMetadata {
@@ -50,6 +233,14 @@ impl SynthesisMetadata {
             model: model.into(),
             tokens_used: 0,
             synthesis_duration: Duration::ZERO,
+            moderation: None,
+            stage_usage: Vec::new(),
+            cost_usd: None,
+            budget: None,
+            routing: None,
+            escalation: None,
+            consistency: None,
+            context: None,
         }
     }
 
@@ -62,6 +253,165 @@ impl SynthesisMetadata {
         self.synthesis_duration = duration;
         self
     }
+
+    pub fn with_moderation(mut self, verdict: ModerationVerdict) -> Self {
+        self.moderation = Some(verdict);
+        self
+    }
+
+    pub fn with_stage_usage(mut self, usage: StageTokenUsage) -> Self {
+        self.record_usage(usage);
+        self
+    }
+
+    /// Adds `usage` to the entry for the same stage and model, if any.
+    pub fn record_usage(&mut self, usage: StageTokenUsage) {
+        match self
+            .stage_usage
+            .iter_mut()
+            .find(|u| u.stage == usage.stage && u.model == usage.model)
+        {
+            Some(existing) => {
+                existing.prompt_tokens += usage.prompt_tokens;
+                existing.completion_tokens += usage.completion_tokens;
+            }
+            None => self.stage_usage.push(usage),
+        }
+    }
+
+    /// What the stages run on `model` cost at `pricing`, or `None` without
+    /// a per-stage breakdown.
+    pub fn cost_on(&self, model: &str, pricing: &ModelPricing) -> Option<f64> {
+        let (prompt, completion) = self
+            .stage_usage
+            .iter()
+            .filter(|u| u.model == model)
+            .fold((0, 0), |(prompt, completion), u| {
+                (prompt + u.prompt_tokens, completion + u.completion_tokens)
+            });
+        (!self.stage_usage.is_empty()).then(|| pricing.cost(prompt, completion))
+    }
+
+    /// Records how full a context window of `window_tokens` the prompt
+    /// that wrote the answer was, given `used` of `available` sources. When
+    /// the provider reported no prompt tokens, `estimated_prompt_tokens`
+    /// stands in; nothing is recorded when that is 0 too.
+    pub fn record_context(
+        &mut self,
+        window_tokens: usize,
+        estimated_prompt_tokens: usize,
+        available: usize,
+        used: &[Source],
+    ) {
+        let prompt_tokens = match self.prompt_tokens() {
+            0 => estimated_prompt_tokens,
+            reported => reported,
+        };
+        if prompt_tokens > 0 {
+            self.context = Some(ContextUsage::new(
+                window_tokens,
+                prompt_tokens,
+                available,
+                used,
+            ));
+        }
+    }
+
+    /// Prompt tokens across all stages; 0 without a per-stage breakdown.
+    pub fn prompt_tokens(&self) -> usize {
+        self.stage_usage.iter().map(|u| u.prompt_tokens).sum()
+    }
+
+    /// Completion tokens across all stages; 0 without a per-stage
+    /// breakdown.
+    pub fn completion_tokens(&self) -> usize {
+        self.stage_usage.iter().map(|u| u.completion_tokens).sum()
+    }
+
+    /// Tokens used across all stages. Answers without a per-stage breakdown
+    /// only account for synthesis.
+    pub fn total_tokens(&self) -> usize {
+        if self.stage_usage.is_empty() {
+            self.tokens_used
+        } else {
+            self.stage_usage.iter().map(StageTokenUsage::total).sum()
+        }
+    }
+}
+
+/// One section of a long-form answer that was written section by section.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AnswerSection {
+    pub title: String,
+    /// The sources the section was written from.
+    pub source_ids: Vec<SourceId>,
+    /// The section's own citations, also listed in the answer's.
+    pub citations: Vec<Citation>,
+}
+
+/// Why the sources could not answer the question, and what to try instead.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WhyInsufficient {
+    /// The queries that were searched.
+    #[serde(default)]
+    pub searched: Vec<String>,
+    /// Kinds of sources that would answer the question but were not found,
+    /// such as "the company's 2024 annual report".
+    #[serde(default)]
+    pub missing_sources: Vec<String>,
+    /// Queries more likely to find an answer.
+    #[serde(default)]
+    pub reformulations: Vec<String>,
+}
+
+impl WhyInsufficient {
+    pub fn new(
+        missing_sources: impl IntoIterator<Item = impl Into<String>>,
+        reformulations: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        let mut why = Self::default();
+        for missing in missing_sources {
+            push_distinct(&mut why.missing_sources, missing.into());
+        }
+        for query in reformulations {
+            why.add_reformulation(query);
+        }
+        why
+    }
+
+    /// Adds a reformulated query unless it is blank, already listed, or
+    /// the list is full.
+    pub fn add_reformulation(&mut self, query: impl Into<String>) {
+        push_distinct(&mut self.reformulations, query.into());
+    }
+
+    /// Adds what `other` lists that this does not, up to the limit.
+    pub fn merge(&mut self, other: WhyInsufficient) {
+        for query in other.searched {
+            if !self.searched.contains(&query) {
+                self.searched.push(query);
+            }
+        }
+        for missing in other.missing_sources {
+            push_distinct(&mut self.missing_sources, missing);
+        }
+        for query in other.reformulations {
+            self.add_reformulation(query);
+        }
+    }
+}
+
+/// Pushes `entry`, trimmed, unless it is blank, already in `list` ignoring
+/// case, or `list` has [`MAX_INSUFFICIENCY_ENTRIES`].
+fn push_distinct(list: &mut Vec<String>, entry: String) {
+    let entry = entry.trim();
+    if entry.is_empty()
+        || list.len() >= MAX_INSUFFICIENCY_ENTRIES
+        || list.iter().any(|e| e.eq_ignore_ascii_case(entry))
+    {
+        return;
+    }
+    list.push(entry.to_string());
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -72,6 +422,36 @@ pub struct ResearchAnswer {
     pub confidence: Confidence,
     pub limitations: Vec<String>,
     pub synthesis_metadata: SynthesisMetadata,
+    /// The payload requested with an [`AnswerSchema`](crate::AnswerSchema),
+    /// exactly as the model returned it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured: Option<serde_json::Value>,
+    /// The sections of an answer written from an outline, in order; empty
+    /// for answers written in one pass.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sections: Vec<AnswerSection>,
+    /// Questions the model suggests researching next, for deeper dives.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggested_followups: Vec<String>,
+    /// The people, organizations and technologies the answer's sources
+    /// discuss, for jobs that asked for them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub key_entities: Vec<KeyEntity>,
+    /// When the answer goes stale; absent for answers written before
+    /// freshness was tracked, which never do.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub freshness: Option<AnswerFreshness>,
+    /// Why the sources could not answer the question; present only on
+    /// insufficient answers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub why_insufficient: Option<WhyInsufficient>,
+    /// The source each numeric marker of the text refers to, in order;
+    /// empty when the text cites sources by ID.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<Reference>,
+    /// Each sentence of the detail with the citations supporting it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub evidence: Vec<SentenceEvidence>,
 }
 
 impl ResearchAnswer {
@@ -88,6 +468,14 @@ impl ResearchAnswer {
             confidence,
             limitations: Vec::new(),
             synthesis_metadata: SynthesisMetadata::new(model),
+            structured: None,
+            sections: Vec::new(),
+            suggested_followups: Vec::new(),
+            key_entities: Vec::new(),
+            freshness: None,
+            why_insufficient: None,
+            references: Vec::new(),
+            evidence: Vec::new(),
         }
     }
 
@@ -112,11 +500,50 @@ impl ResearchAnswer {
         self.limitations.push(limitation.into());
     }
 
+    /// Sets the suggested follow-up questions, dropping blank and repeated
+    /// ones and keeping at most [`MAX_SUGGESTED_FOLLOWUPS`].
+    pub fn with_suggested_followups(
+        mut self,
+        questions: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.suggested_followups.clear();
+        for question in questions {
+            self.add_suggested_followup(question);
+        }
+        self
+    }
+
+    /// Adds a suggested follow-up question unless it is blank, already
+    /// suggested, or the answer already has [`MAX_SUGGESTED_FOLLOWUPS`].
+    pub fn add_suggested_followup(&mut self, question: impl Into<String>) {
+        let question = question.into().trim().to_string();
+        if question.is_empty()
+            || self.suggested_followups.len() >= MAX_SUGGESTED_FOLLOWUPS
+            || self
+                .suggested_followups
+                .iter()
+                .any(|q| q.eq_ignore_ascii_case(&question))
+        {
+            return;
+        }
+        self.suggested_followups.push(question);
+    }
+
     pub fn with_metadata(mut self, metadata: SynthesisMetadata) -> Self {
         self.synthesis_metadata = metadata;
         self
     }
 
+    pub fn with_structured(mut self, structured: serde_json::Value) -> Self {
+        self.structured = Some(structured);
+        self
+    }
+
+    pub fn with_why_insufficient(mut self, why: WhyInsufficient) -> Self {
+        self.why_insufficient = Some(why);
+        self
+    }
+
     pub fn is_answerable(&self) -> bool {
         !matches!(self.confidence, Confidence::Insufficient)
     }
@@ -205,4 +632,100 @@ mod tests {
 
         assert_eq!(answer.citations.len(), 2);
     }
+
+    #[test]
+    fn keeps_distinct_followups_up_to_limit() {
+        let answer = ResearchAnswer::new("Summary", "Detail", Confidence::High, "gpt-4")
+            .with_suggested_followups([
+                "How does Rust compare to Go?",
+                "  ",
+                "how does rust compare to go?",
+                "Who uses Rust?",
+                "Is Rust fast?",
+                "Is Rust hard to learn?",
+            ]);
+
+        assert_eq!(
+            answer.suggested_followups,
+            vec![
+                "How does Rust compare to Go?",
+                "Who uses Rust?",
+                "Is Rust fast?"
+            ]
+        );
+    }
+
+    #[test]
+    fn merges_distinct_insufficiency_entries() {
+        let mut why = WhyInsufficient::new(["Court filings", " "], ["rust ownership"]);
+        why.merge(WhyInsufficient {
+            searched: vec!["What is Rust?".to_string()],
+            missing_sources: vec!["court filings".to_string(), "Press releases".to_string()],
+            reformulations: vec!["Rust ownership".to_string()],
+        });
+
+        assert_eq!(why.searched, vec!["What is Rust?"]);
+        assert_eq!(why.missing_sources, vec!["Court filings", "Press releases"]);
+        assert_eq!(why.reformulations, vec!["rust ownership"]);
+    }
+
+    fn usage(prompt_tokens: usize, completion_tokens: usize) -> TokenUsage {
+        TokenUsage {
+            prompt_tokens,
+            completion_tokens,
+        }
+    }
+
+    #[test]
+    fn sums_usage_per_stage_and_model() {
+        let mut metadata = SynthesisMetadata::new("gpt-4")
+            .with_tokens_used(300)
+            .with_stage_usage(StageTokenUsage::new(
+                LlmStage::Planning,
+                "gpt-4o-mini",
+                &usage(100, 20),
+            ));
+        metadata.record_usage(StageTokenUsage::new(
+            LlmStage::Planning,
+            "gpt-4o-mini",
+            &usage(50, 10),
+        ));
+        metadata.record_usage(StageTokenUsage::new(
+            LlmStage::Synthesis,
+            "gpt-4",
+            &usage(250, 50),
+        ));
+
+        assert_eq!(metadata.stage_usage.len(), 2);
+        assert_eq!(metadata.stage_usage[0].prompt_tokens, 150);
+        assert_eq!(metadata.stage_usage[0].completion_tokens, 30);
+        assert_eq!(metadata.total_tokens(), 480);
+    }
+
+    #[test]
+    fn total_tokens_falls_back_to_synthesis_tokens() {
+        let metadata = SynthesisMetadata::new("gpt-4").with_tokens_used(300);
+        assert_eq!(metadata.total_tokens(), 300);
+
+        let json = serde_json::to_value(&metadata).unwrap();
+        assert!(json.get("stage_usage").is_none());
+    }
+
+    #[test]
+    fn context_falls_back_to_estimated_prompt_tokens() {
+        let mut metadata = SynthesisMetadata::new("webhook-model");
+        metadata.record_context(1000, 0, 3, &[]);
+        assert!(metadata.context.is_none());
+
+        metadata.record_context(1000, 250, 3, &[]);
+        assert_eq!(metadata.context.as_ref().unwrap().prompt_tokens, 250);
+
+        metadata.record_usage(StageTokenUsage::new(
+            LlmStage::Synthesis,
+            "webhook-model",
+            &usage(400, 100),
+        ));
+        metadata.record_context(1000, 250, 3, &[]);
+        assert_eq!(metadata.context.unwrap().prompt_tokens, 400);
+    }
 }
@@ -0,0 +1,440 @@
+//! Caller-supplied shapes for structured answers.
+//!
+//! Some integrators want a domain-specific payload next to the standard
+//! answer, such as a pros/cons list or a comparison table. They pass an
+//! [`AnswerSchema`] with the research request; the schema is added to the
+//! synthesis prompt, and the payload the model returns is validated against
+//! it before the job completes.
+//!
+//! Only a subset of JSON Schema is understood: `type`, `properties`,
+//! `required`, `additionalProperties`, `items`, `enum`, `minItems`,
+//! `maxItems`, `minLength`, `maxLength`, `minimum` and `maximum`, plus the
+//! annotations `title` and `description`. Schemas using anything else are
+//! rejected up front rather than half-enforced.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::error::SchemaError;
+
+/// Largest accepted schema, serialized, in bytes.
+pub const MAX_SCHEMA_BYTES: usize = 16 * 1024;
+
+/// Deepest accepted nesting of subschemas.
+pub const MAX_SCHEMA_DEPTH: usize = 8;
+
+/// Validation stops collecting errors after this many.
+const MAX_REPORTED_ERRORS: usize = 10;
+
+const TYPES: [&str; 7] = [
+    "object", "array", "string", "number", "integer", "boolean", "null",
+];
+
+const ANNOTATIONS: [&str; 2] = ["title", "description"];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AnswerSchema {
+    /// Identifies the shape in prompts, e.g. `pros_cons`.
+    pub name: String,
+    pub schema: Value,
+}
+
+impl AnswerSchema {
+    /// Checks that `schema` is an object schema using only supported
+    /// keywords, within the size and depth limits.
+    pub fn new(name: impl Into<String>, schema: Value) -> Result<Self, SchemaError> {
+        let name = name.into();
+        let valid_name = !name.is_empty()
+            && name.len() <= 64
+            && name
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-');
+        if !valid_name {
+            return Err(SchemaError::InvalidName(name));
+        }
+
+        if schema.to_string().len() > MAX_SCHEMA_BYTES {
+            return Err(SchemaError::TooLarge {
+                max_bytes: MAX_SCHEMA_BYTES,
+            });
+        }
+        if schema.get("type").and_then(Value::as_str) != Some("object") {
+            return Err(SchemaError::Invalid {
+                path: "/".to_string(),
+                reason: "the root schema must have \"type\": \"object\"".to_string(),
+            });
+        }
+        check_schema(&schema, "", 0)?;
+
+        Ok(Self { name, schema })
+    }
+
+    /// Checks `value` against the schema, returning each violation with the
+    /// JSON pointer of the offending value.
+    pub fn validate(&self, value: &Value) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        validate_value(&self.schema, value, "", &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+fn check_schema(schema: &Value, path: &str, depth: usize) -> Result<(), SchemaError> {
+    if depth > MAX_SCHEMA_DEPTH {
+        return Err(SchemaError::TooDeep {
+            max_depth: MAX_SCHEMA_DEPTH,
+        });
+    }
+    let invalid = |keyword: &str, reason: &str| SchemaError::Invalid {
+        path: pointer(&format!("{}/{}", path, keyword)),
+        reason: reason.to_string(),
+    };
+    let Some(schema) = schema.as_object() else {
+        return Err(SchemaError::Invalid {
+            path: pointer(path),
+            reason: "a schema must be an object".to_string(),
+        });
+    };
+
+    for (keyword, value) in schema {
+        match keyword.as_str() {
+            "type" => {
+                let valid = match value {
+                    Value::String(t) => TYPES.contains(&t.as_str()),
+                    Value::Array(ts) => ts
+                        .iter()
+                        .all(|t| t.as_str().is_some_and(|t| TYPES.contains(&t))),
+                    _ => false,
+                };
+                if !valid {
+                    return Err(invalid(keyword, "unknown type"));
+                }
+            }
+            "properties" => {
+                let Some(properties) = value.as_object() else {
+                    return Err(invalid(keyword, "must be an object"));
+                };
+                for (name, property) in properties {
+                    check_schema(
+                        property,
+                        &format!("{}/properties/{}", path, name),
+                        depth + 1,
+                    )?;
+                }
+            }
+            "required" => {
+                let valid = value
+                    .as_array()
+                    .is_some_and(|names| names.iter().all(Value::is_string));
+                if !valid {
+                    return Err(invalid(keyword, "must be an array of property names"));
+                }
+            }
+            "additionalProperties" => {
+                if !value.is_boolean() {
+                    check_schema(value, &format!("{}/{}", path, keyword), depth + 1)?;
+                }
+            }
+            "items" => check_schema(value, &format!("{}/{}", path, keyword), depth + 1)?,
+            "enum" => {
+                if !value.as_array().is_some_and(|values| !values.is_empty()) {
+                    return Err(invalid(keyword, "must be a non-empty array"));
+                }
+            }
+            "minItems" | "maxItems" | "minLength" | "maxLength" => {
+                if !value.is_u64() {
+                    return Err(invalid(keyword, "must be a non-negative integer"));
+                }
+            }
+            "minimum" | "maximum" => {
+                if !value.is_number() {
+                    return Err(invalid(keyword, "must be a number"));
+                }
+            }
+            k if ANNOTATIONS.contains(&k) => {
+                if !value.is_string() {
+                    return Err(invalid(keyword, "must be a string"));
+                }
+            }
+            _ => {
+                return Err(SchemaError::Unsupported {
+                    path: pointer(path),
+                    keyword: keyword.clone(),
+                })
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_value(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+    let mut report = |message: String| {
+        if errors.len() < MAX_REPORTED_ERRORS {
+            errors.push(format!("{}: {}", pointer(path), message));
+        }
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.iter().any(|t| has_type(value, t)) {
+            report(format!(
+                "expected {}, got {}",
+                types.join(" or "),
+                type_name(value)
+            ));
+            return;
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            report(format!("{} is not one of the allowed values", value));
+        }
+    }
+
+    match value {
+        Value::Object(object) => validate_object(schema, object, path, errors),
+        Value::Array(items) => {
+            let len = items.len() as u64;
+            if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                if len < min {
+                    report(format!("expected at least {} items, got {}", min, len));
+                }
+            }
+            if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+                if len > max {
+                    report(format!("expected at most {} items, got {}", max, len));
+                }
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    validate_value(item_schema, item, &format!("{}/{}", path, i), errors);
+                }
+            }
+        }
+        Value::String(text) => {
+            let len = text.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                if len < min {
+                    report(format!("expected at least {} characters", min));
+                }
+            }
+            if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+                if len > max {
+                    report(format!("expected at most {} characters", max));
+                }
+            }
+        }
+        Value::Number(number) => {
+            let number = number.as_f64().unwrap_or_default();
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+                if number < min {
+                    report(format!("{} is less than the minimum {}", number, min));
+                }
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+                if number > max {
+                    report(format!("{} is greater than the maximum {}", number, max));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn validate_object(
+    schema: &Map<String, Value>,
+    object: &Map<String, Value>,
+    path: &str,
+    errors: &mut Vec<String>,
+) {
+    let properties = schema.get("properties").and_then(Value::as_object);
+
+    for name in schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+    {
+        if !object.contains_key(name) && errors.len() < MAX_REPORTED_ERRORS {
+            errors.push(format!(
+                "{}: missing required property {:?}",
+                pointer(path),
+                name
+            ));
+        }
+    }
+
+    for (name, value) in object {
+        let property_path = format!("{}/{}", path, name);
+        match (
+            properties.and_then(|p| p.get(name)),
+            schema.get("additionalProperties"),
+        ) {
+            (Some(property), _) => validate_value(property, value, &property_path, errors),
+            (None, Some(Value::Bool(false))) => {
+                if errors.len() < MAX_REPORTED_ERRORS {
+                    errors.push(format!("{}: unexpected property", pointer(&property_path)));
+                }
+            }
+            (None, Some(additional)) if additional.is_object() => {
+                validate_value(additional, value, &property_path, errors)
+            }
+            (None, _) => {}
+        }
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "integer" => value.as_i64().is_some() || value.as_u64().is_some(),
+        other => type_name(value) == other || (other == "number" && value.is_number()),
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn pointer(path: &str) -> String {
+    if path.is_empty() {
+        "/".to_string()
+    } else {
+        path.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn pros_cons() -> AnswerSchema {
+        AnswerSchema::new(
+            "pros_cons",
+            json!({
+                "type": "object",
+                "properties": {
+                    "pros": {"type": "array", "items": {"type": "string"}, "minItems": 1},
+                    "cons": {"type": "array", "items": {"type": "string"}},
+                    "verdict": {"type": "string", "enum": ["buy", "skip"]},
+                    "score": {"type": "integer", "minimum": 0, "maximum": 10}
+                },
+                "required": ["pros", "cons", "verdict"],
+                "additionalProperties": false
+            }),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn accepts_matching_payload() {
+        let payload = json!({
+            "pros": ["Fast"],
+            "cons": [],
+            "verdict": "buy",
+            "score": 8
+        });
+
+        assert_eq!(pros_cons().validate(&payload), Ok(()));
+    }
+
+    #[test]
+    fn reports_each_violation_with_its_path() {
+        let payload = json!({
+            "pros": [],
+            "cons": ["Slow", 3],
+            "verdict": "maybe",
+            "score": 11,
+            "extra": true
+        });
+
+        let errors = pros_cons().validate(&payload).unwrap_err();
+
+        assert_eq!(
+            errors,
+            vec![
+                "/cons/1: expected string, got number",
+                "/extra: unexpected property",
+                "/pros: expected at least 1 items, got 0",
+                "/score: 11 is greater than the maximum 10",
+                "/verdict: \"maybe\" is not one of the allowed values",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_missing_required_properties() {
+        let errors = pros_cons()
+            .validate(&json!({"pros": ["Fast"]}))
+            .unwrap_err();
+
+        assert_eq!(
+            errors,
+            vec![
+                "/: missing required property \"cons\"",
+                "/: missing required property \"verdict\"",
+            ]
+        );
+        assert!(pros_cons()
+            .validate(&json!(["not", "an", "object"]))
+            .is_err());
+    }
+
+    #[test]
+    fn rejects_unsupported_or_malformed_schemas() {
+        assert!(matches!(
+            AnswerSchema::new("bad name!", json!({"type": "object"})),
+            Err(SchemaError::InvalidName(_))
+        ));
+        assert!(matches!(
+            AnswerSchema::new("list", json!({"type": "array"})),
+            Err(SchemaError::Invalid { .. })
+        ));
+        assert!(matches!(
+            AnswerSchema::new(
+                "table",
+                json!({"type": "object", "properties": {"rows": {"$ref": "#/defs/row"}}})
+            ),
+            Err(SchemaError::Unsupported { ref path, ref keyword })
+                if path == "/properties/rows" && keyword == "$ref"
+        ));
+        assert!(matches!(
+            AnswerSchema::new("typo", json!({"type": "object", "properties": {"a": {"type": "text"}}})),
+            Err(SchemaError::Invalid { ref path, .. }) if path == "/properties/a/type"
+        ));
+    }
+
+    #[test]
+    fn rejects_deeply_nested_schemas() {
+        let mut schema = json!({"type": "string"});
+        for _ in 0..=MAX_SCHEMA_DEPTH {
+            schema = json!({"type": "array", "items": schema});
+        }
+        let schema = json!({"type": "object", "properties": {"deep": schema}});
+
+        assert!(matches!(
+            AnswerSchema::new("deep", schema),
+            Err(SchemaError::TooDeep { .. })
+        ));
+    }
+}
@@ -0,0 +1,106 @@
+//! Moving jobs between instances.
+//!
+//! An [`ArchivedJob`] is a job together with its sources, search metadata
+//! and answer, written as one line of JSON. Exporting every job of one
+//! instance and importing the lines into another moves jobs between store
+//! backends; the same lines serve as a dataset for offline analysis.
+
+use serde::{Deserialize, Serialize};
+
+use crate::answer::ResearchAnswer;
+use crate::job::ResearchJob;
+use crate::source::{SearchMetadata, Source};
+use crate::traits::{Store, StoreError};
+
+/// A job and everything stored with it that makes up its answer.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArchivedJob {
+    pub job: ResearchJob,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<Source>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search_metadata: Option<SearchMetadata>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answer: Option<ResearchAnswer>,
+}
+
+/// Reads what `store` keeps for `job`.
+pub async fn export_job(store: &dyn Store, job: ResearchJob) -> Result<ArchivedJob, StoreError> {
+    let sources = store.get_sources(&job.id).await?;
+    let search_metadata = store.get_search_metadata(&job.id).await?;
+    let answer = store.get_answer(&job.id).await?;
+
+    Ok(ArchivedJob {
+        job,
+        sources,
+        search_metadata,
+        answer,
+    })
+}
+
+/// Writes `archived` to `store` under its original ID. Returns `false`,
+/// writing nothing, when the store already has a job with that ID, so
+/// importing the same lines twice is harmless.
+pub async fn import_job(store: &dyn Store, archived: &ArchivedJob) -> Result<bool, StoreError> {
+    let job = &archived.job;
+    if store.get_job(&job.id).await?.is_some() {
+        return Ok(false);
+    }
+
+    store.create_job(job).await?;
+    if !archived.sources.is_empty() {
+        store.store_sources(&job.id, &archived.sources).await?;
+    }
+    if let Some(ref metadata) = archived.search_metadata {
+        store.store_search_metadata(&job.id, metadata).await?;
+    }
+    if let Some(ref answer) = archived.answer {
+        store.store_answer(&job.id, answer).await?;
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answer::Confidence;
+    use crate::job::JobStatus;
+    use crate::mock::MockStore;
+
+    #[tokio::test]
+    async fn round_trips_jobs_between_stores() {
+        let source_store = MockStore::new();
+        let mut job = ResearchJob::new("What is Rust?").unwrap();
+        job.force_transition_to(JobStatus::Completed);
+        source_store.create_job(&job).await.unwrap();
+        source_store
+            .store_sources(
+                &job.id,
+                &[Source::new("https://rust-lang.org", "Rust", "A language.")],
+            )
+            .await
+            .unwrap();
+        source_store
+            .store_answer(
+                &job.id,
+                &ResearchAnswer::new("A language.", "", Confidence::High, "mock"),
+            )
+            .await
+            .unwrap();
+
+        let archived = export_job(&source_store, job.clone()).await.unwrap();
+        let line = serde_json::to_string(&archived).unwrap();
+        let archived: ArchivedJob = serde_json::from_str(&line).unwrap();
+
+        let target = MockStore::new();
+        assert!(import_job(&target, &archived).await.unwrap());
+        assert!(!import_job(&target, &archived).await.unwrap());
+
+        let imported = target.get_job(&job.id).await.unwrap().unwrap();
+        assert_eq!(imported.status, JobStatus::Completed);
+        assert_eq!(target.get_sources(&job.id).await.unwrap().len(), 1);
+        let answer = target.get_answer(&job.id).await.unwrap().unwrap();
+        assert_eq!(answer.summary, "A language.");
+    }
+}
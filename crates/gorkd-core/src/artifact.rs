@@ -0,0 +1,212 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::chat::Message;
+use crate::id::JobId;
+use crate::redact::redact;
+use crate::search::SearchQuery;
+use crate::traits::{ArtifactSink, LlmProvider, Store, StoreError};
+
+/// What a provider sent to and got back from its model for one call.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LlmExchange {
+    /// The exact prompt, system message included.
+    pub messages: Vec<Message>,
+    /// The model's raw output before parsing, if a response arrived.
+    pub raw_response: Option<String>,
+}
+
+impl LlmExchange {
+    /// Tokens of the prompt as `llm` counts them.
+    pub fn prompt_tokens(&self, llm: &dyn LlmProvider) -> usize {
+        self.messages
+            .iter()
+            .map(|message| llm.count_tokens(&message.content))
+            .sum()
+    }
+}
+
+/// A captured LLM exchange for a job, kept to diagnose parser failures and
+/// prompt regressions.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LlmArtifact {
+    pub captured_at: DateTime<Utc>,
+    /// Pipeline stage that made the call, e.g. `synthesis`.
+    pub stage: String,
+    pub provider: String,
+    pub model: String,
+    pub messages: Vec<Message>,
+    pub raw_response: Option<String>,
+    /// The call's error, if it failed.
+    pub error: Option<String>,
+}
+
+impl LlmArtifact {
+    pub fn new(
+        stage: impl Into<String>,
+        provider: impl Into<String>,
+        model: impl Into<String>,
+        exchange: LlmExchange,
+    ) -> Self {
+        Self {
+            captured_at: Utc::now(),
+            stage: stage.into(),
+            provider: provider.into(),
+            model: model.into(),
+            messages: exchange.messages,
+            raw_response: exchange.raw_response,
+            error: None,
+        }
+    }
+
+    pub fn with_error(mut self, error: impl Into<String>) -> Self {
+        self.error = Some(error.into());
+        self
+    }
+
+    /// Scrubs credentials and personal data from every free-text field.
+    pub fn redacted(mut self) -> Self {
+        for message in &mut self.messages {
+            message.content = redact(&message.content);
+        }
+        self.raw_response = self.raw_response.as_deref().map(redact);
+        self.error = self.error.as_deref().map(redact);
+        self
+    }
+}
+
+/// A search provider's response body for one of a job's queries, kept so
+/// the job's sources can be rebuilt when the provider's parser is fixed.
+///
+/// Unlike [`LlmArtifact`], it is not redacted: scrubbing could break the
+/// body's syntax, and it holds the pages the provider returned rather than
+/// anything sent to it beyond the query.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SearchArtifact {
+    pub captured_at: DateTime<Utc>,
+    pub provider: String,
+    pub query: SearchQuery,
+    pub raw_response: String,
+    /// Key of the object holding `raw_response` when a store keeps it in
+    /// object storage, like [`Source::content_ref`](crate::Source::content_ref).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_response_ref: Option<String>,
+}
+
+impl SearchArtifact {
+    pub fn new(
+        provider: impl Into<String>,
+        query: SearchQuery,
+        raw_response: impl Into<String>,
+    ) -> Self {
+        Self {
+            captured_at: Utc::now(),
+            provider: provider.into(),
+            query,
+            raw_response: raw_response.into(),
+            raw_response_ref: None,
+        }
+    }
+}
+
+/// Keeps artifacts alongside the rest of the job's data in the [`Store`].
+pub struct StoreArtifactSink {
+    store: Arc<dyn Store>,
+}
+
+impl StoreArtifactSink {
+    pub fn new(store: Arc<dyn Store>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl ArtifactSink for StoreArtifactSink {
+    async fn capture(&self, job_id: &JobId, artifact: LlmArtifact) -> Result<(), StoreError> {
+        self.store.store_artifact(job_id, &artifact).await
+    }
+
+    async fn artifacts(&self, job_id: &JobId) -> Result<Vec<LlmArtifact>, StoreError> {
+        self.store.get_artifacts(job_id).await
+    }
+
+    async fn capture_search(
+        &self,
+        job_id: &JobId,
+        artifact: SearchArtifact,
+    ) -> Result<(), StoreError> {
+        self.store.store_search_artifact(job_id, &artifact).await
+    }
+
+    async fn search_artifacts(&self, job_id: &JobId) -> Result<Vec<SearchArtifact>, StoreError> {
+        self.store.get_search_artifacts(job_id).await
+    }
+
+    fn sink_name(&self) -> &str {
+        "store"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockStore;
+
+    fn exchange() -> LlmExchange {
+        LlmExchange {
+            messages: vec![
+                Message::system("Answer from sources"),
+                Message::user("Email me at jane@example.com"),
+            ],
+            raw_response: Some("key sk-abcdefghijklmnopqrstuvwxyz".to_string()),
+        }
+    }
+
+    #[test]
+    fn redacts_all_text_fields() {
+        let artifact = LlmArtifact::new("synthesis", "mock", "mock-gpt-4", exchange())
+            .with_error("Bearer abc123 rejected")
+            .redacted();
+
+        assert_eq!(artifact.messages[0].content, "Answer from sources");
+        assert_eq!(artifact.messages[1].content, "Email me at [EMAIL]");
+        assert_eq!(artifact.raw_response.as_deref(), Some("key [REDACTED]"));
+        assert_eq!(
+            artifact.error.as_deref(),
+            Some("Bearer [REDACTED] rejected")
+        );
+    }
+
+    #[tokio::test]
+    async fn store_sink_round_trips() {
+        let sink = StoreArtifactSink::new(Arc::new(MockStore::new()));
+        let job_id = JobId::new();
+
+        sink.capture(
+            &job_id,
+            LlmArtifact::new("synthesis", "mock", "mock-gpt-4", exchange()),
+        )
+        .await
+        .unwrap();
+
+        let artifacts = sink.artifacts(&job_id).await.unwrap();
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0].stage, "synthesis");
+        assert!(sink.artifacts(&JobId::new()).await.unwrap().is_empty());
+
+        sink.capture_search(
+            &job_id,
+            SearchArtifact::new("tavily", SearchQuery::new("rust"), r#"{"results":[]}"#),
+        )
+        .await
+        .unwrap();
+
+        let searches = sink.search_artifacts(&job_id).await.unwrap();
+        assert_eq!(searches.len(), 1);
+        assert_eq!(searches[0].query.text, "rust");
+        assert!(sink.artifacts(&job_id).await.unwrap().len() == 1);
+    }
+}
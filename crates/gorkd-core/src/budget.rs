@@ -0,0 +1,228 @@
+//! Spending caps on research jobs.
+//!
+//! A job with a [`CostBudget`] estimates what its synthesis will cost before
+//! the model is called, from the sources it would read as the model's
+//! tokenizer counts them, the answer length it allows and the model's
+//! [`ModelPricing`]. When the estimate is over budget
+//! the pipeline has the model read fewer sources, then switches to a cheaper
+//! model, and fails the job as `budget_exceeded` when neither fits. What the
+//! job actually spent is recorded with its answer in a [`BudgetReport`].
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::source::Source;
+use crate::traits::LlmProvider;
+
+/// Tokens of the synthesis prompt besides the query and sources: the system
+/// prompt, answer format and instructions.
+pub const PROMPT_OVERHEAD_TOKENS: usize = 800;
+
+/// Tokens framing each source in the prompt, besides its title and content.
+pub const SOURCE_OVERHEAD_TOKENS: usize = 40;
+
+/// Completion tokens assumed when no length policy caps them, the providers'
+/// own default maximum.
+pub const DEFAULT_COMPLETION_TOKENS: usize = 4096;
+
+/// Fewest sources a budget trims synthesis to before it switches to a
+/// cheaper model instead.
+pub const MIN_BUDGET_SOURCES: usize = 2;
+
+/// The most a job may spend on synthesis, in US dollars or tokens.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CostBudget {
+    Usd(f64),
+    Tokens(usize),
+}
+
+impl CostBudget {
+    /// Whether `estimate` fits the budget. A dollar budget admits nothing
+    /// whose price is unknown.
+    pub fn admits(&self, estimate: &CostEstimate) -> bool {
+        match *self {
+            Self::Usd(max) => estimate.usd.is_some_and(|usd| usd <= max),
+            Self::Tokens(max) => estimate.tokens() <= max,
+        }
+    }
+
+    /// `estimate` in the budget's unit, or `None` when its price is unknown.
+    pub fn measure(&self, estimate: &CostEstimate) -> Option<Self> {
+        match self {
+            Self::Usd(_) => estimate.usd.map(Self::Usd),
+            Self::Tokens(_) => Some(Self::Tokens(estimate.tokens())),
+        }
+    }
+}
+
+impl fmt::Display for CostBudget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Usd(usd) => write!(f, "${:.4}", usd),
+            Self::Tokens(tokens) => write!(f, "{} tokens", tokens),
+        }
+    }
+}
+
+/// What a model charges, in US dollars per million tokens.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+}
+
+impl ModelPricing {
+    pub fn new(input_per_mtok: f64, output_per_mtok: f64) -> Self {
+        Self {
+            input_per_mtok,
+            output_per_mtok,
+        }
+    }
+
+    pub fn cost(&self, prompt_tokens: usize, completion_tokens: usize) -> f64 {
+        (prompt_tokens as f64 * self.input_per_mtok
+            + completion_tokens as f64 * self.output_per_mtok)
+            / 1_000_000.0
+    }
+}
+
+/// The expected size and price of one synthesis call.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CostEstimate {
+    pub prompt_tokens: usize,
+    /// The most the answer may use.
+    pub completion_tokens: usize,
+    /// `None` when the model's price is unknown.
+    pub usd: Option<f64>,
+}
+
+impl CostEstimate {
+    /// A prompt of `prompt_tokens` and an answer of at most
+    /// `completion_tokens`, at `pricing`.
+    pub fn new(
+        prompt_tokens: usize,
+        completion_tokens: usize,
+        pricing: Option<ModelPricing>,
+    ) -> Self {
+        Self {
+            prompt_tokens,
+            completion_tokens,
+            usd: pricing.map(|p| p.cost(prompt_tokens, completion_tokens)),
+        }
+    }
+
+    /// Estimates `llm` synthesizing an answer to `query` from `sources` of
+    /// at most `completion_tokens`, counting tokens as the model does.
+    pub fn synthesis(
+        llm: &dyn LlmProvider,
+        query: &str,
+        sources: &[Source],
+        completion_tokens: usize,
+    ) -> Self {
+        let prompt_tokens = synthesis_prompt_tokens(llm, query, sources);
+        Self::new(
+            prompt_tokens[sources.len()],
+            completion_tokens,
+            llm.pricing(),
+        )
+    }
+
+    pub fn tokens(&self) -> usize {
+        self.prompt_tokens + self.completion_tokens
+    }
+}
+
+/// Tokens of `llm`'s synthesis prompt for `query` over the first `n` of
+/// `sources`, at index `n`. Each source is counted once, so a budget can try
+/// ever fewer sources without counting them again.
+pub(crate) fn synthesis_prompt_tokens(
+    llm: &dyn LlmProvider,
+    query: &str,
+    sources: &[Source],
+) -> Vec<usize> {
+    let mut tokens = PROMPT_OVERHEAD_TOKENS + llm.count_tokens(query);
+    let mut prefix = Vec::with_capacity(sources.len() + 1);
+    prefix.push(tokens);
+    for source in sources {
+        tokens += SOURCE_OVERHEAD_TOKENS
+            + llm.count_tokens(&source.title)
+            + llm.count_tokens(&source.content);
+        prefix.push(tokens);
+    }
+    prefix
+}
+
+/// How a job's synthesis was fitted to its budget, and what it spent.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BudgetReport {
+    pub max_cost: CostBudget,
+    /// The estimate the synthesis was run on.
+    pub estimate: CostEstimate,
+    /// Sources the model read; fewer than configured when the budget
+    /// trimmed them.
+    pub context_sources: usize,
+    /// The model the job would have used, when the budget switched to a
+    /// cheaper one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub downgraded_from: Option<String>,
+    pub spent_tokens: usize,
+    /// `None` when the model's price is unknown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spent_usd: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockLlmProvider;
+
+    fn estimate(prompt_tokens: usize, usd: Option<f64>) -> CostEstimate {
+        CostEstimate {
+            prompt_tokens,
+            completion_tokens: 1000,
+            usd,
+        }
+    }
+
+    #[test]
+    fn estimates_grow_with_sources() {
+        let pricing = ModelPricing::new(3.0, 15.0);
+        let source = Source::new("https://example.com", "Example", "x".repeat(4000));
+
+        let llm = MockLlmProvider::new("mock").with_pricing(pricing);
+
+        let none = CostEstimate::synthesis(&llm, "What is Rust?", &[], 1024);
+        let one = CostEstimate::synthesis(&llm, "What is Rust?", &[source], 1024);
+
+        assert_eq!(one.prompt_tokens - none.prompt_tokens, 1000 + 2 + 40);
+        assert_eq!(none.completion_tokens, 1024);
+        assert!(one.usd.unwrap() > none.usd.unwrap());
+        let unpriced = MockLlmProvider::new("mock");
+        assert!(CostEstimate::synthesis(&unpriced, "q", &[], 1024)
+            .usd
+            .is_none());
+    }
+
+    #[test]
+    fn budgets_admit_estimates_in_their_unit() {
+        assert!(CostBudget::Tokens(3000).admits(&estimate(2000, None)));
+        assert!(!CostBudget::Tokens(2999).admits(&estimate(2000, None)));
+        assert!(CostBudget::Usd(0.05).admits(&estimate(2000, Some(0.04))));
+        assert!(!CostBudget::Usd(0.05).admits(&estimate(2000, None)));
+        assert_eq!(
+            CostBudget::Usd(0.05).measure(&estimate(2000, Some(0.04))),
+            Some(CostBudget::Usd(0.04))
+        );
+    }
+
+    #[test]
+    fn parses_unit_keys() {
+        let usd: CostBudget = serde_json::from_str(r#"{"usd": 0.05}"#).unwrap();
+        let tokens: CostBudget = serde_json::from_str(r#"{"tokens": 20000}"#).unwrap();
+
+        assert_eq!(usd, CostBudget::Usd(0.05));
+        assert_eq!(tokens.to_string(), "20000 tokens");
+    }
+}
@@ -0,0 +1,181 @@
+//! Answers from several models to the same query, over the same sources.
+//!
+//! A job created with more than one model fans synthesis out to each of them.
+//! Every model's answer (or failure) is kept in a [`ModelComparison`], so
+//! models can be evaluated against each other and disagreement between them
+//! surfaced to the reader.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::answer::{Confidence, ResearchAnswer};
+use crate::id::SourceId;
+
+/// Most models a single job may be compared across.
+pub const MAX_COMPARISON_MODELS: usize = 4;
+
+/// One model's outcome in a comparison: its answer, or why it has none.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ModelAnswer {
+    pub model: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answer: Option<ResearchAnswer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ModelAnswer {
+    pub fn answered(model: impl Into<String>, answer: ResearchAnswer) -> Self {
+        Self {
+            model: model.into(),
+            answer: Some(answer),
+            error: None,
+        }
+    }
+
+    pub fn failed(model: impl Into<String>, error: impl Into<String>) -> Self {
+        Self {
+            model: model.into(),
+            answer: None,
+            error: Some(error.into()),
+        }
+    }
+}
+
+/// Every model's outcome, in the order the models were requested.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ModelComparison {
+    pub answers: Vec<ModelAnswer>,
+}
+
+/// How far the answering models agree with each other.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComparisonAgreement {
+    /// Whether every model reported the same confidence.
+    pub confidence_agrees: bool,
+    /// Mean pairwise Jaccard similarity of the sources each model cited,
+    /// from 0 (no source in common) to 1 (identical citations).
+    pub citation_overlap: f32,
+    /// Sources cited by some models but not all, in ID order.
+    pub disputed_sources: Vec<SourceId>,
+}
+
+impl ModelComparison {
+    pub fn new(answers: Vec<ModelAnswer>) -> Self {
+        Self { answers }
+    }
+
+    /// The answers of models that produced one.
+    pub fn answered(&self) -> impl Iterator<Item = &ResearchAnswer> {
+        self.answers.iter().filter_map(|a| a.answer.as_ref())
+    }
+
+    /// Compares the models that answered. `None` with fewer than two answers,
+    /// since there is nothing to compare.
+    pub fn agreement(&self) -> Option<ComparisonAgreement> {
+        let answers: Vec<&ResearchAnswer> = self.answered().collect();
+        if answers.len() < 2 {
+            return None;
+        }
+
+        let confidences: Vec<&Confidence> = answers.iter().map(|a| &a.confidence).collect();
+        let confidence_agrees = confidences.windows(2).all(|w| w[0] == w[1]);
+
+        let cited: Vec<BTreeSet<&str>> = answers
+            .iter()
+            .map(|a| a.citations.iter().map(|c| c.source_id.as_str()).collect())
+            .collect();
+
+        let mut similarity = 0.0;
+        let mut pairs = 0;
+        for (i, a) in cited.iter().enumerate() {
+            for b in &cited[i + 1..] {
+                let union = a.union(b).count();
+                similarity += if union == 0 {
+                    1.0
+                } else {
+                    a.intersection(b).count() as f32 / union as f32
+                };
+                pairs += 1;
+            }
+        }
+
+        let all: BTreeMap<&str, &SourceId> = answers
+            .iter()
+            .flat_map(|a| &a.citations)
+            .map(|c| (c.source_id.as_str(), &c.source_id))
+            .collect();
+        let disputed_sources = all
+            .into_iter()
+            .filter(|(id, _)| !cited.iter().all(|set| set.contains(id)))
+            .map(|(_, source_id)| source_id.clone())
+            .collect();
+
+        Some(ComparisonAgreement {
+            confidence_agrees,
+            citation_overlap: similarity / pairs as f32,
+            disputed_sources,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answer::Citation;
+
+    fn answer(model: &str, confidence: Confidence, cited: &[&SourceId]) -> ModelAnswer {
+        let citations = cited
+            .iter()
+            .map(|id| Citation::new("claim", (*id).clone()))
+            .collect();
+        ModelAnswer::answered(
+            model,
+            ResearchAnswer::new("summary", "detail", confidence, model).with_citations(citations),
+        )
+    }
+
+    #[test]
+    fn agreement_needs_two_answers() {
+        let comparison = ModelComparison::new(vec![
+            answer("a", Confidence::High, &[&SourceId::new()]),
+            ModelAnswer::failed("b", "rate limited"),
+        ]);
+
+        assert_eq!(comparison.answered().count(), 1);
+        assert!(comparison.agreement().is_none());
+    }
+
+    #[test]
+    fn identical_answers_agree() {
+        let (one, two) = (SourceId::new(), SourceId::new());
+        let comparison = ModelComparison::new(vec![
+            answer("a", Confidence::High, &[&one, &two]),
+            answer("b", Confidence::High, &[&two, &one]),
+        ]);
+
+        let agreement = comparison.agreement().unwrap();
+        assert!(agreement.confidence_agrees);
+        assert_eq!(agreement.citation_overlap, 1.0);
+        assert!(agreement.disputed_sources.is_empty());
+    }
+
+    #[test]
+    fn surfaces_disagreement() {
+        let (one, two, three) = (SourceId::new(), SourceId::new(), SourceId::new());
+        let comparison = ModelComparison::new(vec![
+            answer("a", Confidence::High, &[&one, &two]),
+            answer("b", Confidence::Low, &[&two, &three]),
+            answer("c", Confidence::High, &[&two]),
+        ]);
+
+        let agreement = comparison.agreement().unwrap();
+        assert!(!agreement.confidence_agrees);
+        // (1/3 + 1/2 + 1/2) / 3
+        assert!((agreement.citation_overlap - 4.0 / 9.0).abs() < 1e-6);
+        assert_eq!(agreement.disputed_sources.len(), 2);
+        assert!(agreement.disputed_sources.contains(&one));
+        assert!(agreement.disputed_sources.contains(&three));
+    }
+}
@@ -0,0 +1,282 @@
+//! Self-consistency sampling for high-stakes questions.
+//!
+//! A job flagged high-stakes is answered [`ConsistencyPolicy::samples`]
+//! times over the same sources, at a temperature above zero so the samples
+//! can differ. [`ConsistencyPolicy::reconcile`] then keeps the sample whose
+//! claims the others agree with most, drops its citations whose claim only
+//! a minority of the samples made, and lists what the samples disagreed on
+//! among the answer's limitations. The extra calls buy fewer claims that a
+//! single draw of the model happened to make.
+
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::align::similarity;
+use crate::answer::{Citation, Confidence, ResearchAnswer};
+use crate::id::SourceId;
+
+/// Samples a high-stakes job takes unless configured otherwise.
+pub const DEFAULT_CONSISTENCY_SAMPLES: usize = 3;
+
+/// Most samples a high-stakes job may take.
+pub const MAX_CONSISTENCY_SAMPLES: usize = 10;
+
+/// Temperature samples are drawn at unless configured otherwise.
+pub const DEFAULT_CONSISTENCY_TEMPERATURE: f32 = 0.7;
+
+/// Word overlap from which two claims citing the same source count as the
+/// same claim.
+pub const SAME_CLAIM_SIMILARITY: f32 = 0.5;
+
+/// How high-stakes jobs sample their answer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConsistencyPolicy {
+    /// Answers to synthesize, from 1 to [`MAX_CONSISTENCY_SAMPLES`].
+    pub samples: usize,
+    /// Temperature each answer is synthesized at.
+    pub temperature: f32,
+}
+
+impl Default for ConsistencyPolicy {
+    fn default() -> Self {
+        Self {
+            samples: DEFAULT_CONSISTENCY_SAMPLES,
+            temperature: DEFAULT_CONSISTENCY_TEMPERATURE,
+        }
+    }
+}
+
+impl ConsistencyPolicy {
+    pub fn new(samples: usize, temperature: f32) -> Self {
+        Self {
+            samples: samples.clamp(1, MAX_CONSISTENCY_SAMPLES),
+            temperature: temperature.clamp(0.0, 2.0),
+        }
+    }
+
+    /// Reconciles the answers of the samples that succeeded into one: the
+    /// sample whose citations the others make most, without the citations
+    /// a majority does not make, no more confident than the median sample
+    /// and with the usage of all of them. `None` when no sample answered.
+    pub fn reconcile(&self, samples: Vec<ResearchAnswer>) -> Option<ResearchAnswer> {
+        let answered = samples.len();
+        let majority = answered / 2 + 1;
+
+        let support: Vec<Vec<usize>> = samples
+            .iter()
+            .enumerate()
+            .map(|(i, sample)| {
+                sample
+                    .citations
+                    .iter()
+                    .map(|citation| claimed_by(citation, i, &samples))
+                    .collect()
+            })
+            .collect();
+        // The first of the samples whose citations are made most often.
+        let best = (0..answered)
+            .rev()
+            .max_by_key(|&i| support[i].iter().sum::<usize>())?;
+
+        let mut confidences: Vec<Confidence> =
+            samples.iter().map(|s| s.confidence.clone()).collect();
+        let mut others = samples;
+        let mut answer = others.swap_remove(best);
+        for other in &others {
+            for usage in &other.synthesis_metadata.stage_usage {
+                answer.synthesis_metadata.record_usage(usage.clone());
+            }
+        }
+
+        let citations = std::mem::take(&mut answer.citations);
+        let mut disputed = Vec::new();
+        for (citation, &count) in citations.into_iter().zip(&support[best]) {
+            if count >= majority {
+                answer.citations.push(citation);
+            } else {
+                disputed.push(DisputedClaim {
+                    claim: citation.claim,
+                    source_id: citation.source_id,
+                    samples: count,
+                });
+            }
+        }
+        let agreement = match support[best].len() {
+            0 => 1.0,
+            cited => answer.citations.len() as f32 / cited as f32,
+        };
+
+        for claim in &disputed {
+            answer.limitations.push(format!(
+                "Only {} of {} samples made the claim \"{}\", so it is not cited.",
+                claim.samples, answered, claim.claim
+            ));
+        }
+        let disagree = confidences.iter().any(|c| *c != confidences[0]);
+        if disagree {
+            let listed: Vec<&str> = confidences.iter().map(Confidence::as_str).collect();
+            answer.limitations.push(format!(
+                "Samples disagreed on how well the sources answer the question: {}.",
+                listed.join(", ")
+            ));
+        }
+        confidences.sort_by(|a, b| {
+            if a.is_below(b) {
+                Ordering::Greater
+            } else if b.is_below(a) {
+                Ordering::Less
+            } else {
+                Ordering::Equal
+            }
+        });
+        let median = confidences.swap_remove(answered / 2);
+        if median.is_below(&answer.confidence) {
+            answer.confidence = median;
+        }
+
+        answer.synthesis_metadata.consistency = Some(ConsistencyReport {
+            samples: self.samples,
+            answered,
+            temperature: self.temperature,
+            agreement,
+            disputed,
+            confidence_disagreed: disagree,
+        });
+        Some(answer)
+    }
+}
+
+/// How many of `samples`, counting sample `own` itself, make `citation`'s
+/// claim about its source.
+fn claimed_by(citation: &Citation, own: usize, samples: &[ResearchAnswer]) -> usize {
+    let others = samples
+        .iter()
+        .enumerate()
+        .filter(|&(i, sample)| {
+            i != own
+                && sample.citations.iter().any(|other| {
+                    other.source_id == citation.source_id
+                        && similarity(&citation.claim, &other.claim) >= SAME_CLAIM_SIMILARITY
+                })
+        })
+        .count();
+    others + 1
+}
+
+/// How the samples of a high-stakes job agreed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConsistencyReport {
+    /// Samples the job synthesized.
+    pub samples: usize,
+    /// Samples that answered; the others failed.
+    pub answered: usize,
+    pub temperature: f32,
+    /// Share of the kept sample's citations a majority of the samples made.
+    pub agreement: f32,
+    /// Claims of the kept sample a majority did not make, which were
+    /// dropped.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disputed: Vec<DisputedClaim>,
+    /// Whether the samples came back with different confidence.
+    #[serde(default)]
+    pub confidence_disagreed: bool,
+}
+
+/// A claim too few samples made to keep.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisputedClaim {
+    pub claim: String,
+    pub source_id: SourceId,
+    /// Samples that made it.
+    pub samples: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answer::{LlmStage, StageTokenUsage};
+    use crate::chat::TokenUsage;
+
+    fn sample(confidence: Confidence, claims: &[(&str, &SourceId)]) -> ResearchAnswer {
+        let mut answer = ResearchAnswer::new("Summary", "Detail", confidence, "mock");
+        answer.citations = claims
+            .iter()
+            .map(|(claim, source_id)| Citation::new(*claim, (*source_id).clone()))
+            .collect();
+        let usage = TokenUsage {
+            prompt_tokens: 400,
+            completion_tokens: 100,
+        };
+        answer.synthesis_metadata.record_usage(StageTokenUsage::new(
+            LlmStage::Synthesis,
+            "mock",
+            &usage,
+        ));
+        answer
+    }
+
+    #[test]
+    fn keeps_claims_most_samples_make() {
+        let (a, b) = (SourceId::new(), SourceId::new());
+        let samples = vec![
+            sample(
+                Confidence::High,
+                &[
+                    ("Rust has no garbage collector", &a),
+                    ("Rust compiles slowly", &b),
+                ],
+            ),
+            sample(
+                Confidence::High,
+                &[("Rust has no garbage collector at all", &a)],
+            ),
+            sample(
+                Confidence::Low,
+                &[
+                    ("Rust has no garbage collector", &a),
+                    ("Its releases ship every six weeks", &b),
+                ],
+            ),
+        ];
+
+        let answer = ConsistencyPolicy::default().reconcile(samples).unwrap();
+
+        assert_eq!(answer.citations.len(), 1);
+        assert_eq!(answer.citations[0].source_id, a);
+        assert_eq!(answer.confidence, Confidence::High);
+        let report = answer.synthesis_metadata.consistency.as_ref().unwrap();
+        assert_eq!((report.samples, report.answered), (3, 3));
+        assert_eq!(report.agreement, 0.5);
+        assert_eq!(report.disputed.len(), 1);
+        assert_eq!(report.disputed[0].samples, 1);
+        assert!(report.confidence_disagreed);
+        assert_eq!(answer.limitations.len(), 2);
+        assert!(answer.limitations[0].contains("Only 1 of 3 samples"));
+        assert!(answer.limitations[1].contains("high, high, low"));
+        assert_eq!(answer.synthesis_metadata.total_tokens(), 1500);
+    }
+
+    #[test]
+    fn takes_the_median_confidence() {
+        let a = SourceId::new();
+        let samples = vec![
+            sample(Confidence::High, &[("Rust is fast", &a)]),
+            sample(Confidence::Low, &[("Rust is fast", &a)]),
+            sample(Confidence::Insufficient, &[]),
+        ];
+
+        let answer = ConsistencyPolicy::default().reconcile(samples).unwrap();
+
+        assert_eq!(answer.confidence, Confidence::Low);
+        assert_eq!(answer.citations.len(), 1);
+    }
+
+    #[test]
+    fn reconciles_nothing_without_samples() {
+        assert!(ConsistencyPolicy::default().reconcile(Vec::new()).is_none());
+        let policy = ConsistencyPolicy::new(50, 3.0);
+        assert_eq!(policy.samples, MAX_CONSISTENCY_SAMPLES);
+        assert_eq!(policy.temperature, 2.0);
+    }
+}
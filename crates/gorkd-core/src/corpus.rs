@@ -0,0 +1,621 @@
+//! Documents users bring to research alongside the web.
+//!
+//! A [`CorpusDocument`] is split into overlapping chunks, each embedded and
+//! kept in the store. A job that includes the corpus searches it with the
+//! same queries it sends to web search: [`CorpusSearchProvider`] embeds the
+//! query and returns the best matching chunk of each document, and
+//! [`HybridSearchProvider`] runs it next to the web provider so internal
+//! and external sources are ranked, cited and synthesized together.
+//!
+//! Every document belongs to a workspace, named by the
+//! [`WORKSPACE_HEADER`] of the request that added it. Listing, deleting and
+//! searching see only the documents of one workspace, so teams sharing a
+//! deployment keep their corpora apart.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::id::DocumentId;
+use crate::search::SearchQuery;
+use crate::source::DocumentFormat;
+use crate::traits::{
+    Embedder, LlmError, SearchError, SearchProvider, SearchReport, SearchResult, Store,
+};
+
+/// Provider ID a search plan lists to include the corpus.
+pub const CORPUS_PROVIDER_ID: &str = "corpus";
+
+/// Header naming the workspace a request's documents and corpus searches
+/// belong to.
+pub const WORKSPACE_HEADER: &str = "X-Gorkd-Workspace";
+
+/// Workspace of requests that do not name one.
+pub const DEFAULT_WORKSPACE: &str = "default";
+
+/// Longest workspace name accepted.
+pub const MAX_WORKSPACE_LENGTH: usize = 64;
+
+/// Chunks embedded per call to the embedder, keeping each request within
+/// what hosted embedding endpoints accept.
+pub const EMBEDDING_BATCH_SIZE: usize = 96;
+
+/// Most characters in a chunk, unless a single word is longer.
+pub const DEFAULT_CHUNK_CHARS: usize = 1_000;
+
+/// Characters of a chunk's trailing words repeated at the start of the next,
+/// so a passage cut at a boundary is still found whole.
+pub const DEFAULT_CHUNK_OVERLAP: usize = 200;
+
+/// Largest document content accepted into the corpus.
+pub const MAX_CORPUS_DOCUMENT_BYTES: usize = 1_000_000;
+
+/// Chunks the corpus provider returns for a query by default.
+const DEFAULT_CORPUS_RESULTS: usize = 5;
+
+/// Characters of a matching chunk shown as the result's snippet.
+const SNIPPET_CHARS: usize = 200;
+
+/// A passage of a document and its embedding.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CorpusChunk {
+    /// Position of the chunk in the document, from zero.
+    pub index: usize,
+    pub text: String,
+    pub embedding: Vec<f32>,
+}
+
+/// Whether `name` can name a workspace: 1 to [`MAX_WORKSPACE_LENGTH`]
+/// ASCII letters, digits, `-` or `_`.
+pub fn is_valid_workspace(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_WORKSPACE_LENGTH
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+fn default_workspace() -> String {
+    DEFAULT_WORKSPACE.to_string()
+}
+
+fn default_format() -> DocumentFormat {
+    DocumentFormat::PlainText
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CorpusDocument {
+    pub id: DocumentId,
+    #[serde(default = "default_workspace")]
+    pub workspace: String,
+    pub title: String,
+    /// Where the document lives, if it has an address readers can follow.
+    pub url: Option<String>,
+    /// The format the document was uploaded in. `content` is always its
+    /// extracted text.
+    #[serde(default = "default_format")]
+    pub format: DocumentFormat,
+    pub content: String,
+    /// When the document was published, if known. Jobs limited to a period
+    /// leave out documents published outside it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published_at: Option<DateTime<Utc>>,
+    /// Model the chunks were embedded with. Empty until indexed.
+    pub embedding_model: String,
+    pub chunks: Vec<CorpusChunk>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl CorpusDocument {
+    pub fn new(title: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            id: DocumentId::new(),
+            workspace: default_workspace(),
+            title: title.into(),
+            url: None,
+            format: default_format(),
+            content: content.into(),
+            published_at: None,
+            embedding_model: String::new(),
+            chunks: Vec::new(),
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn with_workspace(mut self, workspace: impl Into<String>) -> Self {
+        self.workspace = workspace.into();
+        self
+    }
+
+    pub fn with_format(mut self, format: DocumentFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_published_at(mut self, published_at: DateTime<Utc>) -> Self {
+        self.published_at = Some(published_at);
+        self
+    }
+
+    /// The URL sources drawn from the document cite: its own, or
+    /// `corpus://<id>` when it has none.
+    pub fn citation_url(&self) -> String {
+        self.url
+            .clone()
+            .unwrap_or_else(|| format!("corpus://{}", self.id))
+    }
+}
+
+/// The chunk of a document that best matches a query.
+#[derive(Clone, Debug)]
+pub struct CorpusMatch {
+    pub document_id: DocumentId,
+    pub title: String,
+    /// The document's [`citation_url`](CorpusDocument::citation_url).
+    pub url: String,
+    /// Index of the matching chunk.
+    pub chunk: usize,
+    pub text: String,
+    /// Cosine similarity of the chunk to the query.
+    pub score: f32,
+    /// When the document was published, if known.
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+/// Splits `text` into chunks of whole words of at most `max_chars`
+/// characters, each starting with up to `overlap` characters of the
+/// previous chunk's trailing words.
+pub fn chunk_text(text: &str, max_chars: usize, overlap: usize) -> Vec<String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    while start < words.len() {
+        let mut end = start;
+        let mut len = 0;
+        while end < words.len() {
+            let added = words[end].chars().count() + usize::from(end > start);
+            if end > start && len + added > max_chars {
+                break;
+            }
+            len += added;
+            end += 1;
+        }
+        chunks.push(words[start..end].join(" "));
+        if end == words.len() {
+            break;
+        }
+
+        // Step back over the trailing words, always leaving the next chunk
+        // at least one word further on.
+        let mut next = end;
+        let mut carried = 0;
+        while next > start + 1 {
+            let added = words[next - 1].chars().count() + 1;
+            if carried + added > overlap {
+                break;
+            }
+            carried += added;
+            next -= 1;
+        }
+        start = next;
+    }
+
+    chunks
+}
+
+/// Cosine similarity of two vectors, or `None` when their dimensions differ
+/// or either is zero.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> Option<f32> {
+    if a.len() != b.len() || a.is_empty() {
+        return None;
+    }
+
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return None;
+    }
+
+    Some(dot / (norm_a * norm_b))
+}
+
+/// Chunks `document` and embeds each chunk, replacing any chunks it had.
+/// Each chunk is embedded with the document's title, so passages that never
+/// name their subject still match queries about it. Chunks are sent to the
+/// embedder [`EMBEDDING_BATCH_SIZE`] at a time.
+pub async fn index_document(
+    embedder: &dyn Embedder,
+    mut document: CorpusDocument,
+) -> Result<CorpusDocument, LlmError> {
+    let texts = chunk_text(
+        &document.content,
+        DEFAULT_CHUNK_CHARS,
+        DEFAULT_CHUNK_OVERLAP,
+    );
+    let inputs: Vec<String> = texts
+        .iter()
+        .map(|text| format!("{}\n\n{}", document.title, text))
+        .collect();
+    let mut embeddings = Vec::with_capacity(inputs.len());
+    for batch in inputs.chunks(EMBEDDING_BATCH_SIZE) {
+        embeddings.extend(embedder.embed(batch).await?);
+    }
+    if embeddings.len() != texts.len() {
+        return Err(LlmError::Provider(format!(
+            "expected {} embeddings, got {}",
+            texts.len(),
+            embeddings.len()
+        )));
+    }
+
+    document.embedding_model = embedder.model_id().to_string();
+    document.chunks = texts
+        .into_iter()
+        .zip(embeddings)
+        .enumerate()
+        .map(|(index, (text, embedding))| CorpusChunk {
+            index,
+            text,
+            embedding,
+        })
+        .collect();
+
+    Ok(document)
+}
+
+/// Searches the documents of one workspace in the store by embedding
+/// similarity.
+pub struct CorpusSearchProvider {
+    store: Arc<dyn Store>,
+    embedder: Arc<dyn Embedder>,
+    workspace: String,
+    max_results: usize,
+}
+
+impl CorpusSearchProvider {
+    /// Searches the default workspace.
+    pub fn new(store: Arc<dyn Store>, embedder: Arc<dyn Embedder>) -> Self {
+        Self {
+            store,
+            embedder,
+            workspace: default_workspace(),
+            max_results: DEFAULT_CORPUS_RESULTS,
+        }
+    }
+
+    pub fn with_workspace(mut self, workspace: impl Into<String>) -> Self {
+        self.workspace = workspace.into();
+        self
+    }
+
+    pub fn with_max_results(mut self, max_results: usize) -> Self {
+        self.max_results = max_results;
+        self
+    }
+}
+
+#[async_trait]
+impl SearchProvider for CorpusSearchProvider {
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>, SearchError> {
+        let embedding = self
+            .embedder
+            .embed(std::slice::from_ref(&query.text))
+            .await
+            .map_err(|e| SearchError::Provider(format!("corpus embedding failed: {}", e)))?
+            .pop()
+            .ok_or_else(|| SearchError::Provider("corpus embedding missing".to_string()))?;
+
+        let matches = self
+            .store
+            .search_corpus(
+                &self.workspace,
+                self.embedder.model_id(),
+                &embedding,
+                &query.filters,
+                self.max_results,
+            )
+            .await
+            .map_err(|e| SearchError::Provider(format!("corpus search failed: {}", e)))?;
+
+        Ok(matches
+            .into_iter()
+            .map(|m| {
+                let snippet: String = m.text.chars().take(SNIPPET_CHARS).collect();
+                let result = SearchResult::new(m.url, m.title, snippet)
+                    .with_raw_content(m.text)
+                    .with_score(m.score);
+                match m.published_at {
+                    Some(published_at) => result.with_published_at(published_at),
+                    None => result,
+                }
+            })
+            .collect())
+    }
+
+    fn provider_id(&self) -> &str {
+        CORPUS_PROVIDER_ID
+    }
+}
+
+/// Searches the web and the corpus together. Corpus scores are scaled so
+/// the best corpus match ranks with the best web result, since similarity
+/// and web relevance are not on one scale. A corpus that fails is left out;
+/// a web search that fails still returns the corpus results, if any.
+pub struct HybridSearchProvider {
+    web: Arc<dyn SearchProvider>,
+    corpus: Arc<dyn SearchProvider>,
+}
+
+impl HybridSearchProvider {
+    pub fn new(web: Arc<dyn SearchProvider>, corpus: Arc<dyn SearchProvider>) -> Self {
+        Self { web, corpus }
+    }
+}
+
+#[async_trait]
+impl SearchProvider for HybridSearchProvider {
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>, SearchError> {
+        self.search_reported(query).await.result
+    }
+
+    async fn search_reported(&self, query: &SearchQuery) -> SearchReport {
+        let (corpus, web) = futures::join!(
+            self.corpus.search_reported(query),
+            self.web.search_reported(query)
+        );
+        let mut attempts = corpus.attempts;
+        attempts.extend(web.attempts);
+
+        let result = match (web.result, corpus.result) {
+            (Ok(web), Ok(corpus)) => Ok(merge(web, corpus)),
+            (Ok(web), Err(_)) => Ok(web),
+            (Err(_), Ok(corpus)) if !corpus.is_empty() => Ok(corpus),
+            (Err(e), _) => Err(e),
+        };
+
+        SearchReport { result, attempts }
+    }
+
+    fn parse_raw(
+        &self,
+        provider: &str,
+        query: &SearchQuery,
+        raw: &str,
+    ) -> Result<Vec<SearchResult>, SearchError> {
+        self.web.parse_raw(provider, query, raw)
+    }
+
+    async fn find_similar(
+        &self,
+        url: &str,
+        max_results: usize,
+    ) -> Result<Vec<SearchResult>, SearchError> {
+        self.web.find_similar(url, max_results).await
+    }
+
+    fn provider_id(&self) -> &str {
+        "hybrid"
+    }
+
+    fn supports_find_similar(&self) -> bool {
+        self.web.supports_find_similar()
+    }
+
+    fn supports_recency_filter(&self) -> bool {
+        self.web.supports_recency_filter()
+    }
+
+    fn supports_domain_filter(&self) -> bool {
+        self.web.supports_domain_filter()
+    }
+}
+
+fn merge(web: Vec<SearchResult>, mut corpus: Vec<SearchResult>) -> Vec<SearchResult> {
+    let best_web = web.iter().map(|r| r.score).reduce(f32::max).unwrap_or(1.0);
+    let best_corpus = corpus.iter().map(|r| r.score).reduce(f32::max);
+    if let Some(best_corpus) = best_corpus.filter(|best| *best > 0.0) {
+        for result in &mut corpus {
+            result.score = (result.score * best_web / best_corpus).clamp(0.0, 1.0);
+        }
+    }
+
+    corpus.extend(web);
+    corpus
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockEmbedder, MockSearchProvider, MockSearchStep, MockStore};
+
+    #[test]
+    fn chunks_text_with_overlap() {
+        let text = "one two three four five six seven eight";
+
+        let chunks = chunk_text(text, 14, 6);
+
+        assert_eq!(
+            chunks,
+            vec![
+                "one two three",
+                "three four",
+                "four five six",
+                "six seven",
+                "seven eight"
+            ]
+        );
+        assert!(chunk_text("   ", 100, 10).is_empty());
+        assert_eq!(chunk_text("short text", 100, 10), vec!["short text"]);
+    }
+
+    #[test]
+    fn chunks_always_make_progress() {
+        let chunks = chunk_text("averyveryverylongword tiny", 5, 100);
+
+        assert_eq!(chunks, vec!["averyveryverylongword", "tiny"]);
+    }
+
+    #[test]
+    fn cosine_similarity_rejects_mismatched_vectors() {
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]), Some(1.0));
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]), Some(0.0));
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), None);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), None);
+    }
+
+    #[test]
+    fn validates_workspace_names() {
+        assert!(is_valid_workspace("team-a_2"));
+        assert!(is_valid_workspace(DEFAULT_WORKSPACE));
+        assert!(!is_valid_workspace(""));
+        assert!(!is_valid_workspace("team a"));
+        assert!(!is_valid_workspace("../other"));
+        assert!(!is_valid_workspace(&"a".repeat(MAX_WORKSPACE_LENGTH + 1)));
+    }
+
+    #[tokio::test]
+    async fn index_document_embeds_in_batches() {
+        let embedder = MockEmbedder::new();
+        let document = CorpusDocument::new("Long", "word ".repeat(25_000));
+
+        let document = index_document(&embedder, document).await.unwrap();
+
+        assert!(document.chunks.len() > EMBEDDING_BATCH_SIZE);
+        assert_eq!(
+            embedder.call_count(),
+            document.chunks.len().div_ceil(EMBEDDING_BATCH_SIZE)
+        );
+        assert!(document
+            .chunks
+            .iter()
+            .enumerate()
+            .all(|(i, chunk)| chunk.index == i && !chunk.embedding.is_empty()));
+        assert_eq!(document.embedding_model, "mock");
+    }
+
+    #[test]
+    fn documents_without_url_cite_corpus() {
+        let document = CorpusDocument::new("Handbook", "Text");
+        assert_eq!(document.citation_url(), format!("corpus://{}", document.id));
+
+        let document = document.with_url("https://wiki.corp.example/handbook");
+        assert_eq!(
+            document.citation_url(),
+            "https://wiki.corp.example/handbook"
+        );
+    }
+
+    #[tokio::test]
+    async fn corpus_provider_finds_indexed_documents() {
+        let store = Arc::new(MockStore::new());
+        let embedder = Arc::new(MockEmbedder::new());
+        for (title, content) in [
+            ("Vacation policy", "Employees accrue vacation days monthly."),
+            ("Deploy guide", "Deploys run through the release pipeline."),
+        ] {
+            let document = index_document(embedder.as_ref(), CorpusDocument::new(title, content))
+                .await
+                .unwrap();
+            store.store_document(&document).await.unwrap();
+        }
+        let provider = CorpusSearchProvider::new(store, embedder);
+
+        let results = provider
+            .search(&SearchQuery::new("how many vacation days"))
+            .await
+            .unwrap();
+
+        assert_eq!(results[0].title, "Vacation policy");
+        assert!(results[0].url.starts_with("corpus://doc_"));
+        assert_eq!(
+            results[0].raw_content.as_deref(),
+            Some("Employees accrue vacation days monthly.")
+        );
+        assert!(results[0].score > results[1].score);
+    }
+
+    #[tokio::test]
+    async fn corpus_provider_reports_embedding_failures() {
+        let provider = CorpusSearchProvider::new(
+            Arc::new(MockStore::new()),
+            Arc::new(MockEmbedder::failing()),
+        );
+
+        let result = provider.search(&SearchQuery::new("anything")).await;
+
+        assert!(matches!(result, Err(SearchError::Provider(_))));
+    }
+
+    fn corpus_results() -> Vec<SearchResult> {
+        vec![
+            SearchResult::new("corpus://doc_a", "Internal", "Snippet").with_score(0.5),
+            SearchResult::new("corpus://doc_b", "Other", "Snippet").with_score(0.25),
+        ]
+    }
+
+    #[tokio::test]
+    async fn hybrid_provider_merges_and_scales_corpus_results() {
+        let web = Arc::new(MockSearchProvider::new("web").with_results(vec![
+            SearchResult::new("https://example.com", "Web", "Snippet").with_score(0.8),
+        ]));
+        let corpus = Arc::new(MockSearchProvider::new("corpus").with_results(corpus_results()));
+        let hybrid = HybridSearchProvider::new(web, corpus);
+
+        let report = hybrid.search_reported(&SearchQuery::new("query")).await;
+
+        let results = report.result.unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].score, 0.8);
+        assert_eq!(results[1].score, 0.4);
+        assert_eq!(results[2].url, "https://example.com");
+        let providers: Vec<_> = report
+            .attempts
+            .iter()
+            .map(|a| a.provider.as_str())
+            .collect();
+        assert_eq!(providers, vec!["corpus", "web"]);
+    }
+
+    #[tokio::test]
+    async fn hybrid_provider_falls_back_to_corpus_when_web_fails() {
+        let web = Arc::new(
+            MockSearchProvider::new("web").with_script(vec![MockSearchStep::Fail(
+                SearchError::Network("down".into()),
+            )]),
+        );
+        let corpus = Arc::new(MockSearchProvider::new("corpus").with_results(corpus_results()));
+        let hybrid = HybridSearchProvider::new(web, corpus);
+
+        let results = hybrid.search(&SearchQuery::new("query")).await.unwrap();
+
+        assert_eq!(results.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn hybrid_provider_keeps_web_results_when_corpus_fails() {
+        let web = Arc::new(MockSearchProvider::new("web").with_results(vec![
+            SearchResult::new("https://example.com", "Web", "Snippet").with_score(0.8),
+        ]));
+        let corpus =
+            Arc::new(
+                MockSearchProvider::new("corpus").with_script(vec![MockSearchStep::Fail(
+                    SearchError::Provider("corpus search failed".into()),
+                )]),
+            );
+        let hybrid = HybridSearchProvider::new(web, corpus);
+
+        let report = hybrid.search_reported(&SearchQuery::new("query")).await;
+
+        assert_eq!(report.result.unwrap().len(), 1);
+        assert!(!report.attempts[0].succeeded());
+    }
+}
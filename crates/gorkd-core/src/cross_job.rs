@@ -0,0 +1,249 @@
+//! Synthesis over the results of earlier jobs.
+//!
+//! Research into a broad question is often done as several jobs. A
+//! [`SourcePool`] gathers what those jobs found: each job's answer, as a
+//! source of its own, and the sources it collected, deduplicated by URL and
+//! ranked so that sources the answers cited come first. A
+//! [`CrossJobSynthesis`] answers a new question over the pool and keeps,
+//! for every pooled source, the jobs it came from, so each citation of the
+//! combined answer traces back to the research behind it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::answer::ResearchAnswer;
+use crate::id::{JobId, SourceId};
+use crate::job::ResearchJob;
+use crate::source::Source;
+use crate::traits::{LlmError, LlmProvider};
+
+/// Most jobs one synthesis may pool.
+pub const MAX_SYNTHESIS_JOBS: usize = 10;
+
+/// Collected sources given to the model by default, besides the jobs'
+/// answers.
+pub const DEFAULT_POOLED_SOURCES: usize = 20;
+
+/// Most collected sources a synthesis may give to the model.
+pub const MAX_POOLED_SOURCES: usize = 50;
+
+/// What a pooled source holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PooledSourceKind {
+    /// A source a job collected.
+    Source,
+    /// A job's own answer.
+    Answer,
+}
+
+/// A source in the pool, with the jobs it came from.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PooledSource {
+    pub source: Source,
+    pub kind: PooledSourceKind,
+    /// Jobs that collected the source, or the job whose answer it is.
+    pub job_ids: Vec<JobId>,
+    /// Whether a job's answer cited the source.
+    pub cited: bool,
+}
+
+/// Sources and answers of earlier jobs, for a [`CrossJobSynthesis`].
+#[derive(Clone, Debug, Default)]
+pub struct SourcePool {
+    answers: Vec<PooledSource>,
+    sources: Vec<PooledSource>,
+}
+
+impl SourcePool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `job`'s answer and collected `sources`. A source whose URL is
+    /// already pooled is not added again; the job is recorded on it instead.
+    pub fn add_job(
+        &mut self,
+        job: &ResearchJob,
+        answer: Option<&ResearchAnswer>,
+        sources: Vec<Source>,
+    ) {
+        let cited = |source: &Source| {
+            answer.is_some_and(|a| a.citations.iter().any(|c| c.source_id == source.id))
+        };
+
+        for source in sources {
+            let is_cited = cited(&source);
+            match self.sources.iter_mut().find(|p| p.source.url == source.url) {
+                Some(pooled) => {
+                    if !pooled.job_ids.contains(&job.id) {
+                        pooled.job_ids.push(job.id.clone());
+                    }
+                    pooled.cited |= is_cited;
+                }
+                None => self.sources.push(PooledSource {
+                    source,
+                    kind: PooledSourceKind::Source,
+                    job_ids: vec![job.id.clone()],
+                    cited: is_cited,
+                }),
+            }
+        }
+
+        if let Some(answer) = answer {
+            self.answers.push(PooledSource {
+                source: answer_source(job, answer),
+                kind: PooledSourceKind::Answer,
+                job_ids: vec![job.id.clone()],
+                cited: false,
+            });
+        }
+    }
+
+    /// Collected sources pooled so far, not counting answers.
+    pub fn source_count(&self) -> usize {
+        self.sources.len()
+    }
+
+    /// Every answer, then the `max_sources` best collected sources: those an
+    /// answer cited first, then by relevance.
+    pub fn select(mut self, max_sources: usize) -> Vec<PooledSource> {
+        self.sources.sort_by(|a, b| {
+            b.cited.cmp(&a.cited).then(
+                b.source
+                    .relevance_score
+                    .total_cmp(&a.source.relevance_score),
+            )
+        });
+        self.sources.truncate(max_sources);
+
+        let mut selected = self.answers;
+        selected.extend(self.sources);
+        selected
+    }
+}
+
+/// A job's answer as a source, so the model can draw on and cite it.
+fn answer_source(job: &ResearchJob, answer: &ResearchAnswer) -> Source {
+    let content = format!(
+        "Question: {}\n\nAnswer: {}\n\n{}",
+        job.query, answer.summary, answer.detail
+    );
+    let mut source = Source::new(
+        format!("gorkd://jobs/{}", job.id),
+        format!("Earlier answer: {}", job.query),
+        content,
+    );
+    source.relevance_score = 1.0;
+    source
+}
+
+/// An answer to a question over the pooled results of earlier jobs.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CrossJobSynthesis {
+    pub question: String,
+    pub answer: ResearchAnswer,
+    /// What the model was given: the jobs' answers, then their sources.
+    pub sources: Vec<PooledSource>,
+}
+
+impl CrossJobSynthesis {
+    /// Answers `question` with `llm` over the answers of the pool and its
+    /// `max_sources` best collected sources.
+    pub async fn synthesize(
+        question: &str,
+        pool: SourcePool,
+        max_sources: usize,
+        llm: &dyn LlmProvider,
+    ) -> Result<Self, LlmError> {
+        let sources = pool.select(max_sources);
+        let context: Vec<Source> = sources.iter().map(|p| p.source.clone()).collect();
+        let answer = llm.synthesize(question, &context).await?;
+
+        Ok(Self {
+            question: question.to_string(),
+            answer,
+            sources,
+        })
+    }
+
+    /// The jobs behind a cited source.
+    pub fn jobs_for(&self, source_id: &SourceId) -> &[JobId] {
+        self.sources
+            .iter()
+            .find(|p| &p.source.id == source_id)
+            .map_or(&[], |p| p.job_ids.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answer::{Citation, Confidence};
+    use crate::mock::MockLlmProvider;
+
+    fn source(url: &str, score: f32) -> Source {
+        let mut source = Source::new(url, url, "Some content about the topic");
+        source.relevance_score = score;
+        source
+    }
+
+    #[test]
+    fn pools_sources_across_jobs() {
+        let first = ResearchJob::new("first").unwrap();
+        let second = ResearchJob::new("second").unwrap();
+        let cited = source("https://a.example/low", 0.2);
+        let answer = ResearchAnswer::new("A", "", Confidence::High, "mock")
+            .with_citations(vec![Citation::new("claim", cited.id.clone())]);
+
+        let mut pool = SourcePool::new();
+        pool.add_job(
+            &first,
+            Some(&answer),
+            vec![source("https://a.example/high", 0.9), cited],
+        );
+        pool.add_job(&second, None, vec![source("https://a.example/high", 0.8)]);
+        assert_eq!(pool.source_count(), 2);
+
+        let selected = pool.select(1);
+        assert_eq!(selected.len(), 2);
+        assert_eq!(selected[0].kind, PooledSourceKind::Answer);
+        assert_eq!(selected[0].source.url, format!("gorkd://jobs/{}", first.id));
+        assert_eq!(selected[1].source.url, "https://a.example/low");
+        assert!(selected[1].cited);
+    }
+
+    #[test]
+    fn records_every_job_behind_a_source() {
+        let first = ResearchJob::new("first").unwrap();
+        let second = ResearchJob::new("second").unwrap();
+
+        let mut pool = SourcePool::new();
+        pool.add_job(&first, None, vec![source("https://a.example/", 0.5)]);
+        pool.add_job(&second, None, vec![source("https://a.example/", 0.5)]);
+
+        let selected = pool.select(DEFAULT_POOLED_SOURCES);
+        assert_eq!(selected[0].job_ids, vec![first.id, second.id]);
+    }
+
+    #[tokio::test]
+    async fn traces_citations_to_jobs() {
+        let job = ResearchJob::new("first").unwrap();
+        let answer = ResearchAnswer::new("A", "B", Confidence::High, "mock");
+        let mut pool = SourcePool::new();
+        pool.add_job(&job, Some(&answer), vec![source("https://a.example/", 0.5)]);
+        let llm = MockLlmProvider::new("mock-gpt-4");
+
+        let synthesis = CrossJobSynthesis::synthesize("combined?", pool, 5, &llm)
+            .await
+            .unwrap();
+
+        assert_eq!(synthesis.sources.len(), 2);
+        for citation in &synthesis.answer.citations {
+            assert_eq!(
+                synthesis.jobs_for(&citation.source_id),
+                std::slice::from_ref(&job.id)
+            );
+        }
+        assert!(synthesis.jobs_for(&SourceId::new()).is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+//! How much research a job does for its answer.
+//!
+//! A job's [`AnswerDepth`] sets how many sources it collects, how many of
+//! them the model reads and how long an answer it is asked for. `standard`
+//! leaves the pipeline's configuration as it is; `tldr` and `exhaustive`
+//! override it in either direction.
+
+use serde::{Deserialize, Serialize};
+
+use crate::length::LengthPolicy;
+
+/// Sources collected and synthesized from for a `tldr` answer.
+pub const TLDR_MAX_SOURCES: usize = 3;
+
+/// Sources collected for an `exhaustive` report.
+pub const EXHAUSTIVE_MAX_SOURCES: usize = 25;
+
+/// Sources the model reads for an `exhaustive` report.
+pub const EXHAUSTIVE_CONTEXT_SOURCES: usize = 20;
+
+/// Token cap for a `tldr` answer.
+pub const TLDR_MAX_TOKENS: usize = 512;
+
+/// Token cap for an `exhaustive` report.
+pub const EXHAUSTIVE_MAX_TOKENS: usize = 8192;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum AnswerDepth {
+    /// A two-sentence answer from a handful of sources.
+    Tldr,
+    /// The pipeline's configured budgets and length policies.
+    #[default]
+    Standard,
+    /// A structured long-form report drawn from many sources.
+    Exhaustive,
+}
+
+impl AnswerDepth {
+    /// Sources to collect, or `None` for the configured number.
+    pub fn max_sources(self) -> Option<usize> {
+        match self {
+            Self::Tldr => Some(TLDR_MAX_SOURCES),
+            Self::Standard => None,
+            Self::Exhaustive => Some(EXHAUSTIVE_MAX_SOURCES),
+        }
+    }
+
+    /// Sources given to the model, or `None` for the configured number.
+    pub fn context_sources(self) -> Option<usize> {
+        match self {
+            Self::Tldr => Some(TLDR_MAX_SOURCES),
+            Self::Standard => None,
+            Self::Exhaustive => Some(EXHAUSTIVE_CONTEXT_SOURCES),
+        }
+    }
+
+    /// The length policy applied whatever the question type, or `None` to
+    /// choose one by question type as usual.
+    pub fn length_policy(self) -> Option<LengthPolicy> {
+        match self {
+            Self::Tldr => Some(LengthPolicy::new(
+                TLDR_MAX_TOKENS,
+                "Answer in at most two sentences. Give them as the summary and repeat \
+                 them as the detail, without further explanation.",
+            )),
+            Self::Standard => None,
+            Self::Exhaustive => Some(LengthPolicy::new(
+                EXHAUSTIVE_MAX_TOKENS,
+                "Write the detail as a structured long-form report: an overview, then a \
+                 section with a Markdown heading for each aspect of the question, then the \
+                 open questions the sources leave. Cover every relevant source.",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standard_keeps_configuration() {
+        let depth = AnswerDepth::default();
+
+        assert_eq!(depth, AnswerDepth::Standard);
+        assert!(depth.max_sources().is_none());
+        assert!(depth.context_sources().is_none());
+        assert!(depth.length_policy().is_none());
+    }
+
+    #[test]
+    fn budgets_grow_with_depth() {
+        let tldr = AnswerDepth::Tldr;
+        let exhaustive = AnswerDepth::Exhaustive;
+
+        assert!(tldr.max_sources() < exhaustive.max_sources());
+        assert!(exhaustive.context_sources() <= exhaustive.max_sources());
+        assert!(
+            tldr.length_policy().unwrap().max_tokens
+                < exhaustive.length_policy().unwrap().max_tokens
+        );
+    }
+
+    #[test]
+    fn parses_snake_case() {
+        let depth: AnswerDepth = serde_json::from_str("\"tldr\"").unwrap();
+        assert_eq!(depth, AnswerDepth::Tldr);
+    }
+}
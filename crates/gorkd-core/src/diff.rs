@@ -0,0 +1,229 @@
+//! Differences between two research answers.
+//!
+//! Used to see what changed between recurring runs of the same query, or
+//! between the answers of two models. Claims are the sentences of an answer's
+//! summary and detail, aligned with [`align`](crate::align). Citations are
+//! matched by the URL of the cited source, since each job collects its own
+//! sources with their own IDs.
+
+use std::collections::HashSet;
+
+use crate::align::{align_sentences, split_sentences, Alignment};
+use crate::answer::{Confidence, ResearchAnswer};
+use crate::id::SourceId;
+use crate::source::Source;
+
+/// A claim that differs between the two answers.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ClaimChange {
+    Added(String),
+    Removed(String),
+    Changed {
+        before: String,
+        after: String,
+        /// Word overlap of the two wordings, from 0.5 to 1.
+        similarity: f32,
+    },
+}
+
+/// A citation present in only one of the answers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CitationChange {
+    pub claim: String,
+    pub source_id: SourceId,
+    /// URL of the cited source, if the answer's sources were given.
+    pub url: Option<String>,
+}
+
+/// What changed from one answer to another.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnswerDiff {
+    /// Changed claims, in the order they appear in the answers.
+    pub claims: Vec<ClaimChange>,
+    pub unchanged_claims: usize,
+    pub added_citations: Vec<CitationChange>,
+    pub removed_citations: Vec<CitationChange>,
+    pub confidence_before: Confidence,
+    pub confidence_after: Confidence,
+}
+
+impl AnswerDiff {
+    /// Diffs `before` against `after`, each with the sources it was
+    /// synthesized from.
+    pub fn new(
+        before: &ResearchAnswer,
+        before_sources: &[Source],
+        after: &ResearchAnswer,
+        after_sources: &[Source],
+    ) -> Self {
+        let old_text = claim_text(before);
+        let new_text = claim_text(after);
+        let old_claims = split_sentences(&old_text);
+        let new_claims = split_sentences(&new_text);
+
+        let mut claims = Vec::new();
+        let mut unchanged_claims = 0;
+        for alignment in align_sentences(&old_claims, &new_claims) {
+            match alignment {
+                Alignment::Unchanged { .. } => unchanged_claims += 1,
+                Alignment::Changed {
+                    before,
+                    after,
+                    similarity,
+                } => claims.push(ClaimChange::Changed {
+                    before: before.to_string(),
+                    after: after.to_string(),
+                    similarity,
+                }),
+                Alignment::Removed(claim) => claims.push(ClaimChange::Removed(claim.to_string())),
+                Alignment::Added(claim) => claims.push(ClaimChange::Added(claim.to_string())),
+            }
+        }
+
+        let old_citations = citations(before, before_sources);
+        let new_citations = citations(after, after_sources);
+
+        Self {
+            claims,
+            unchanged_claims,
+            added_citations: only_in(&new_citations, &old_citations),
+            removed_citations: only_in(&old_citations, &new_citations),
+            confidence_before: before.confidence.clone(),
+            confidence_after: after.confidence.clone(),
+        }
+    }
+
+    /// Steps the confidence moved, from `insufficient` (0) to `high` (3):
+    /// positive when the later answer is more confident.
+    pub fn confidence_delta(&self) -> i8 {
+        confidence_rank(&self.confidence_after) - confidence_rank(&self.confidence_before)
+    }
+
+    /// Whether the answers make the same claims with the same citations and
+    /// confidence.
+    pub fn is_empty(&self) -> bool {
+        self.claims.is_empty()
+            && self.added_citations.is_empty()
+            && self.removed_citations.is_empty()
+            && self.confidence_before == self.confidence_after
+    }
+}
+
+fn claim_text(answer: &ResearchAnswer) -> String {
+    format!("{}\n{}", answer.summary, answer.detail)
+}
+
+fn confidence_rank(confidence: &Confidence) -> i8 {
+    match confidence {
+        Confidence::Insufficient => 0,
+        Confidence::Low => 1,
+        Confidence::Medium => 2,
+        Confidence::High => 3,
+    }
+}
+
+fn citations(answer: &ResearchAnswer, sources: &[Source]) -> Vec<CitationChange> {
+    answer
+        .citations
+        .iter()
+        .map(|citation| CitationChange {
+            claim: citation.claim.clone(),
+            source_id: citation.source_id.clone(),
+            url: sources
+                .iter()
+                .find(|s| s.id == citation.source_id)
+                .map(|s| s.url.clone()),
+        })
+        .collect()
+}
+
+/// The citations of `these` whose source is not cited in `others`.
+fn only_in(these: &[CitationChange], others: &[CitationChange]) -> Vec<CitationChange> {
+    let cited: HashSet<&str> = others.iter().map(citation_key).collect();
+    these
+        .iter()
+        .filter(|c| !cited.contains(citation_key(c)))
+        .cloned()
+        .collect()
+}
+
+fn citation_key(citation: &CitationChange) -> &str {
+    citation
+        .url
+        .as_deref()
+        .unwrap_or(citation.source_id.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answer::Citation;
+
+    fn answer(summary: &str, confidence: Confidence, sources: &[&Source]) -> ResearchAnswer {
+        let citations = sources
+            .iter()
+            .map(|s| Citation::new(format!("From {}", s.title), s.id.clone()))
+            .collect();
+        ResearchAnswer::new(summary, "", confidence, "mock").with_citations(citations)
+    }
+
+    #[test]
+    fn diffs_claims_citations_and_confidence() {
+        let docs = Source::new("https://docs.example", "Docs", "");
+        let blog = Source::new("https://blog.example", "Blog", "");
+        let news = Source::new("https://news.example", "News", "");
+        // A later run finds the same pages under new source IDs.
+        let docs_again = Source::new("https://docs.example", "Docs", "");
+        let news_again = Source::new("https://news.example", "News", "");
+
+        let before = answer(
+            "Rust 1.0 shipped in 2015. It is maintained by Mozilla.",
+            Confidence::Medium,
+            &[&docs, &blog],
+        );
+        let after = answer(
+            "Rust 1.0 shipped in 2015. It is maintained by the Rust Foundation. \
+             Editions ship every three years.",
+            Confidence::High,
+            &[&docs_again, &news_again],
+        );
+
+        let diff = AnswerDiff::new(
+            &before,
+            &[docs, blog, news],
+            &after,
+            &[docs_again, news_again],
+        );
+
+        assert_eq!(diff.unchanged_claims, 1);
+        assert!(matches!(
+            &diff.claims[0],
+            ClaimChange::Changed { before, .. } if before == "It is maintained by Mozilla."
+        ));
+        assert_eq!(
+            diff.claims[1],
+            ClaimChange::Added("Editions ship every three years.".to_string())
+        );
+        assert_eq!(diff.added_citations.len(), 1);
+        assert_eq!(
+            diff.added_citations[0].url.as_deref(),
+            Some("https://news.example")
+        );
+        assert_eq!(diff.removed_citations[0].claim, "From Blog");
+        assert_eq!(diff.confidence_delta(), 1);
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn identical_answers_have_empty_diff() {
+        let source = Source::new("https://docs.example", "Docs", "");
+        let answer = answer("Rust is fast.", Confidence::High, &[&source]);
+        let sources = [source];
+
+        let diff = AnswerDiff::new(&answer, &sources, &answer, &sources);
+
+        assert!(diff.is_empty());
+        assert_eq!(diff.unchanged_claims, 1);
+        assert_eq!(diff.confidence_delta(), 0);
+    }
+}
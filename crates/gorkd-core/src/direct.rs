@@ -0,0 +1,151 @@
+//! Synthesis over sources the caller provides.
+//!
+//! Teams with their own retrieval can still use gorkd's grounded answers.
+//! A [`DirectSynthesis`] answers a question over the given sources alone,
+//! with the checks a research job's answer gets: citations of sources that
+//! were not given are dropped by the provider, each citation's quote is
+//! located in the source it cites, citations are numbered and sentences are
+//! mapped to their evidence.
+
+use serde::{Deserialize, Serialize};
+
+use crate::answer::ResearchAnswer;
+use crate::evidence::map_evidence;
+use crate::highlight;
+use crate::pipeline::ContentLimits;
+use crate::references::{number_references, CitationMarkers};
+use crate::source::Source;
+use crate::style::AnswerStyle;
+use crate::traits::{LlmError, LlmProvider};
+
+/// Most sources one direct synthesis may be given.
+pub const MAX_DIRECT_SOURCES: usize = 50;
+
+/// An answer to a question over the caller's sources.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DirectSynthesis {
+    pub question: String,
+    pub answer: ResearchAnswer,
+    /// What the model was given, in the caller's order. Sources past the
+    /// total content limit are left out and the rest may be truncated.
+    pub sources: Vec<Source>,
+}
+
+impl DirectSynthesis {
+    /// Answers `question` with `llm` over `sources`, in `style`, citing
+    /// them with `markers`. Sources are truncated to the pipeline's default
+    /// content limits first.
+    pub async fn synthesize(
+        question: &str,
+        mut sources: Vec<Source>,
+        style: AnswerStyle,
+        markers: CitationMarkers,
+        llm: &dyn LlmProvider,
+    ) -> Result<Self, LlmError> {
+        let given = sources.len();
+        ContentLimits::default().apply(&mut sources);
+
+        let (result, exchange) = llm
+            .synthesize_captured(question, &sources, None, None, style, None)
+            .await;
+        let mut answer = result?;
+        answer.synthesis_metadata.record_context(
+            llm.max_context_tokens(),
+            exchange.prompt_tokens(llm),
+            given,
+            &sources,
+        );
+        if let Some(pricing) = llm.pricing() {
+            answer.synthesis_metadata.cost_usd =
+                answer.synthesis_metadata.cost_on(llm.model_id(), &pricing);
+        }
+        highlight::locate_quotes(&mut answer, &sources);
+        if markers == CitationMarkers::Numeric {
+            number_references(&mut answer, &sources);
+        }
+        map_evidence(&mut answer);
+
+        Ok(Self {
+            question: question.to_string(),
+            answer,
+            sources,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockLlmProvider;
+    use crate::pipeline::DEFAULT_MAX_TOTAL_BYTES;
+
+    #[tokio::test]
+    async fn cites_only_the_given_sources() {
+        let sources = vec![
+            Source::new(
+                "https://wiki.internal/rust",
+                "Rust",
+                "Rust is a systems programming language.",
+            ),
+            Source::new(
+                "https://wiki.internal/cargo",
+                "Cargo",
+                "Cargo is Rust's package manager.",
+            ),
+        ];
+        let ids: Vec<_> = sources.iter().map(|s| s.id.clone()).collect();
+        let llm = MockLlmProvider::new("mock-gpt-4");
+
+        let synthesis = DirectSynthesis::synthesize(
+            "What is Rust?",
+            sources,
+            AnswerStyle::default(),
+            CitationMarkers::default(),
+            &llm,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(synthesis.question, "What is Rust?");
+        assert_eq!(synthesis.sources.len(), 2);
+        assert!(!synthesis.answer.citations.is_empty());
+        assert!(synthesis
+            .answer
+            .citations
+            .iter()
+            .all(|c| ids.contains(&c.source_id)));
+    }
+
+    #[tokio::test]
+    async fn keeps_sources_within_content_limits() {
+        let sources: Vec<Source> = (0..10)
+            .map(|i| {
+                Source::new(
+                    format!("https://wiki.internal/{}", i),
+                    format!("Page {}", i),
+                    "word ".repeat(10_000),
+                )
+            })
+            .collect();
+        let llm = MockLlmProvider::new("mock-gpt-4");
+
+        let synthesis = DirectSynthesis::synthesize(
+            "What?",
+            sources,
+            AnswerStyle::default(),
+            CitationMarkers::default(),
+            &llm,
+        )
+        .await
+        .unwrap();
+
+        let total: usize = synthesis.sources.iter().map(|s| s.content.len()).sum();
+        assert!(total <= DEFAULT_MAX_TOTAL_BYTES);
+        assert!(synthesis.sources.len() < 10);
+        assert!(synthesis.sources[0].truncated);
+        let context = synthesis.answer.synthesis_metadata.context.unwrap();
+        assert_eq!(context.sources_available, 10);
+        assert_eq!(context.sources_used, synthesis.sources.len());
+        assert!(context.sources_truncated > 0);
+    }
+}
@@ -0,0 +1,76 @@
+//! Key entities named in an answer's sources.
+//!
+//! Research into an unfamiliar field runs into names the reader does not
+//! know. A job that asks for it gets a glossary with its answer: the people,
+//! organizations and technologies that matter to the question, each with a
+//! one-line description and the sources it was drawn from. The list is kept
+//! structured so it can later feed a knowledge graph.
+
+use serde::{Deserialize, Serialize};
+
+use crate::id::SourceId;
+
+/// Most entities kept with one answer.
+pub const MAX_KEY_ENTITIES: usize = 12;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum EntityKind {
+    Person,
+    Organization,
+    Technology,
+    Place,
+    /// A product, event, law or idea that fits no other kind.
+    Other,
+}
+
+impl EntityKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Person => "person",
+            Self::Organization => "organization",
+            Self::Technology => "technology",
+            Self::Place => "place",
+            Self::Other => "other",
+        }
+    }
+
+    /// Reads a kind as a model names it. Unknown kinds are [`Self::Other`].
+    pub fn parse(s: &str) -> Self {
+        match s.trim().to_lowercase().as_str() {
+            "person" | "people" => Self::Person,
+            "organization" | "organisation" | "org" | "company" => Self::Organization,
+            "technology" | "tech" => Self::Technology,
+            "place" | "location" | "country" => Self::Place,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// An entity the answer's sources discuss.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KeyEntity {
+    pub name: String,
+    pub kind: EntityKind,
+    /// One line on who or what the entity is.
+    pub description: String,
+    /// The sources the description was drawn from.
+    pub source_ids: Vec<SourceId>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_kinds_leniently() {
+        assert_eq!(EntityKind::parse("Organisation"), EntityKind::Organization);
+        assert_eq!(EntityKind::parse(" person "), EntityKind::Person);
+        assert_eq!(EntityKind::parse("law"), EntityKind::Other);
+        assert_eq!(
+            serde_json::to_value(EntityKind::Technology).unwrap(),
+            EntityKind::Technology.as_str()
+        );
+    }
+}
@@ -1,5 +1,42 @@
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::job::JobStatus;
+
+/// Machine-readable reason a request or job failed. The API reports it as
+/// the error `code`, so clients can branch on it instead of parsing messages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// The request or query is invalid.
+    ValidationError,
+    NotFound,
+    /// Someone else changed or finished the job first.
+    Conflict,
+    /// A search or LLM provider is throttling requests.
+    RateLimited,
+    /// A provider or model could not be reached.
+    ProviderUnavailable,
+    /// A provider returned an error or an unusable response.
+    ProviderError,
+    /// A provider did not answer in time.
+    Timeout,
+    /// The sources did not fit the model's context window.
+    ContextTooLong,
+    /// The answer was filtered by the provider or blocked by moderation.
+    ContentBlocked,
+    /// Search found no sources for the query.
+    NoSources,
+    /// The structured answer did not match the requested schema.
+    InvalidAnswer,
+    /// Synthesis would cost more than the job's budget allows.
+    BudgetExceeded,
+    /// The job store could not be reached.
+    StoreUnavailable,
+    InternalError,
+}
+
 #[derive(Debug, Error)]
 #[non_exhaustive]
 pub enum QueryError {
@@ -23,6 +60,38 @@ pub enum IdParseError {
     InvalidLength { expected: usize, got: usize },
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[non_exhaustive]
+pub enum SchemaError {
+    #[error("invalid answer schema name {0:?}: use 1-64 letters, digits, '_' or '-'")]
+    InvalidName(String),
+
+    #[error("answer schema exceeds {max_bytes} bytes")]
+    TooLarge { max_bytes: usize },
+
+    #[error("answer schema nests deeper than {max_depth} levels")]
+    TooDeep { max_depth: usize },
+
+    #[error("unsupported answer schema keyword {keyword:?} at {path}")]
+    Unsupported { path: String, keyword: String },
+
+    #[error("invalid answer schema at {path}: {reason}")]
+    Invalid { path: String, reason: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid job transition from {from:?} to {to:?}")]
+pub struct TransitionError {
+    pub from: JobStatus,
+    pub to: JobStatus,
+}
+
+impl TransitionError {
+    pub fn code(&self) -> ErrorCode {
+        ErrorCode::Conflict
+    }
+}
+
 #[derive(Debug, Error)]
 #[non_exhaustive]
 pub enum ValidationError {
@@ -0,0 +1,78 @@
+//! Searching again, more broadly, when an answer comes back unsure.
+//!
+//! With an [`EscalationPolicy`], an answer less confident than the policy's
+//! threshold is not returned right away. The job searches once more with a
+//! broader plan: the query's keywords as a further query, the recency and
+//! content-type filters and the question's inferred period dropped, and the
+//! source budgets of exhaustive answers. It then answers again from the
+//! sources of both searches, keeps whichever answer is more confident, and
+//! records an [`EscalationReport`] with it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::answer::Confidence;
+
+/// Which answers a job searches again for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EscalationPolicy {
+    /// Answers less confident than this are escalated.
+    pub min_confidence: Confidence,
+}
+
+impl Default for EscalationPolicy {
+    /// Escalates low and insufficient answers.
+    fn default() -> Self {
+        Self {
+            min_confidence: Confidence::Medium,
+        }
+    }
+}
+
+impl EscalationPolicy {
+    pub fn new(min_confidence: Confidence) -> Self {
+        Self { min_confidence }
+    }
+
+    pub fn escalates(&self, confidence: &Confidence) -> bool {
+        confidence.is_below(&self.min_confidence)
+    }
+}
+
+/// What a job tried after its first answer came back unsure.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EscalationReport {
+    /// Confidence of the first answer.
+    pub initial_confidence: Confidence,
+    /// The queries of the broader search.
+    pub queries: Vec<String>,
+    /// Filters the broader search dropped, such as `recency`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relaxed_filters: Vec<String>,
+    /// Most sources the broader search collected.
+    pub max_sources: usize,
+    /// Sources the broader search found that the first had not.
+    pub sources_added: usize,
+    /// Whether the second answer replaced the first, being at least as
+    /// confident.
+    pub replaced: bool,
+    /// Why the broader search or the second answer failed, when one did;
+    /// the first answer is kept.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escalates_answers_below_the_threshold() {
+        let policy = EscalationPolicy::default();
+
+        assert!(!policy.escalates(&Confidence::High));
+        assert!(!policy.escalates(&Confidence::Medium));
+        assert!(policy.escalates(&Confidence::Low));
+        assert!(policy.escalates(&Confidence::Insufficient));
+        assert!(EscalationPolicy::new(Confidence::High).escalates(&Confidence::Medium));
+    }
+}
@@ -0,0 +1,214 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::answer::Confidence;
+use crate::id::JobId;
+use crate::job::JobStatus;
+use crate::routing::ModelTier;
+use crate::traits::PluginHook;
+
+/// One entry in a job's ordered event log.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JobEvent {
+    /// Position in the job's log, assigned by the store on append.
+    pub sequence: u64,
+    pub job_id: JobId,
+    pub timestamp: DateTime<Utc>,
+    pub kind: JobEventKind,
+}
+
+impl JobEvent {
+    pub fn new(job_id: JobId, kind: JobEventKind) -> Self {
+        Self {
+            sequence: 0,
+            job_id,
+            timestamp: Utc::now(),
+            kind,
+        }
+    }
+
+    pub fn with_sequence(mut self, sequence: u64) -> Self {
+        self.sequence = sequence;
+        self
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum JobEventKind {
+    StageChanged {
+        status: JobStatus,
+    },
+    ProviderAttempt {
+        provider: String,
+        query: String,
+        succeeded: bool,
+        results: usize,
+        error: Option<String>,
+        duration_ms: u64,
+    },
+    FallbackUsed {
+        from: String,
+        to: String,
+        query: String,
+    },
+    SourcesCollected {
+        count: usize,
+    },
+    AnswerSynthesized {
+        model: String,
+        tokens_used: usize,
+        duration_ms: u64,
+    },
+    Moderated {
+        flagged: bool,
+        categories: Vec<String>,
+    },
+    /// The job's budget was checked before synthesis, which then ran with
+    /// `model` over `context_sources` sources.
+    BudgetApplied {
+        model: String,
+        context_sources: usize,
+        downgraded_from: Option<String>,
+        estimated_tokens: usize,
+        estimated_usd: Option<f64>,
+    },
+    ModelRouted {
+        model: String,
+        tier: ModelTier,
+        reason: String,
+    },
+    /// A long-form answer was outlined and will be written section by
+    /// section.
+    OutlineDrafted {
+        sections: Vec<String>,
+    },
+    /// Outlining failed, so the answer is written in one pass instead.
+    OutlineSkipped {
+        reason: String,
+    },
+    EntitiesExtracted {
+        count: usize,
+    },
+    /// Entity extraction failed; the answer is kept without entities.
+    EntityExtractionFailed {
+        reason: String,
+    },
+    /// Facts drawn from the cited sources were added to the knowledge base.
+    FactsRecorded {
+        count: usize,
+    },
+    /// Fact extraction failed; the job completes without adding facts.
+    FactExtractionFailed {
+        reason: String,
+    },
+    /// The links the answer cites were checked; `dead` lists those that
+    /// could not be reached.
+    LinksChecked {
+        checked: usize,
+        dead: Vec<String>,
+    },
+    /// A plugin changed the query the search was planned from.
+    QueryRewritten {
+        plugin: String,
+        query: String,
+    },
+    /// A plugin hook failed; the step went on without its change.
+    PluginFailed {
+        plugin: String,
+        hook: PluginHook,
+        reason: String,
+    },
+    /// The answer came back with `confidence`, below the escalation
+    /// threshold, so the job searches again with `queries` for up to
+    /// `max_sources` sources and answers once more.
+    Escalated {
+        confidence: Confidence,
+        queries: Vec<String>,
+        max_sources: usize,
+    },
+    /// A high-stakes job synthesized `samples` answers, of which `answered`
+    /// succeeded, and dropped `disputed` claims a majority did not make.
+    ConsistencyChecked {
+        samples: usize,
+        answered: usize,
+        disputed: usize,
+    },
+    /// `executed` of the `planned` queries of a search have been searched.
+    SearchProgressed {
+        executed: usize,
+        planned: usize,
+    },
+    Failed {
+        message: String,
+    },
+}
+
+impl JobEventKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::StageChanged { .. } => "stage_changed",
+            Self::ProviderAttempt { .. } => "provider_attempt",
+            Self::FallbackUsed { .. } => "fallback_used",
+            Self::SourcesCollected { .. } => "sources_collected",
+            Self::AnswerSynthesized { .. } => "answer_synthesized",
+            Self::Moderated { .. } => "moderated",
+            Self::BudgetApplied { .. } => "budget_applied",
+            Self::ModelRouted { .. } => "model_routed",
+            Self::OutlineDrafted { .. } => "outline_drafted",
+            Self::OutlineSkipped { .. } => "outline_skipped",
+            Self::EntitiesExtracted { .. } => "entities_extracted",
+            Self::EntityExtractionFailed { .. } => "entity_extraction_failed",
+            Self::FactsRecorded { .. } => "facts_recorded",
+            Self::FactExtractionFailed { .. } => "fact_extraction_failed",
+            Self::LinksChecked { .. } => "links_checked",
+            Self::QueryRewritten { .. } => "query_rewritten",
+            Self::PluginFailed { .. } => "plugin_failed",
+            Self::Escalated { .. } => "escalated",
+            Self::ConsistencyChecked { .. } => "consistency_checked",
+            Self::SearchProgressed { .. } => "search_progressed",
+            Self::Failed { .. } => "failed",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_with_type_tag() {
+        let kind = JobEventKind::StageChanged {
+            status: JobStatus::Searching,
+        };
+
+        let json = serde_json::to_value(&kind).unwrap();
+        assert_eq!(json["type"], "stage_changed");
+        assert_eq!(json["status"], "searching");
+    }
+
+    #[test]
+    fn name_matches_serialized_tag() {
+        let kind = JobEventKind::FallbackUsed {
+            from: "tavily".into(),
+            to: "exa".into(),
+            query: "rust".into(),
+        };
+
+        let json = serde_json::to_value(&kind).unwrap();
+        assert_eq!(json["type"], kind.name());
+    }
+
+    #[test]
+    fn round_trips_event() {
+        let event = JobEvent::new(JobId::new(), JobEventKind::SourcesCollected { count: 3 })
+            .with_sequence(4);
+
+        let json = serde_json::to_string(&event).unwrap();
+        let parsed: JobEvent = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.sequence, 4);
+        assert_eq!(parsed.kind, JobEventKind::SourcesCollected { count: 3 });
+    }
+}
@@ -0,0 +1,202 @@
+//! Which citations support each sentence of an answer.
+//!
+//! The detail of an answer is split into sentences and each is mapped to
+//! the citations supporting it, so an interface can show the evidence for
+//! the sentence under the pointer. A sentence is supported by the citations
+//! of the sources its inline markers name, `[1]` through the answer's
+//! references or `[src_abc123]` directly; when a named source has several
+//! citations, by those whose claim is closest to the sentence. A sentence
+//! without markers is supported by the citations whose claim it mostly
+//! repeats. Offsets are in characters of the detail, as quote locations are
+//! in characters of their source.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::align::{similarity, split_sentences, words};
+use crate::answer::{Citation, ResearchAnswer};
+use crate::id::SourceId;
+use crate::references::Reference;
+
+/// Share of a claim's words a sentence without markers must contain for the
+/// claim's citation to support it.
+pub const MIN_CLAIM_OVERLAP: f32 = 0.6;
+
+/// One sentence of an answer's detail and the citations supporting it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SentenceEvidence {
+    /// Character offset of the sentence in the detail.
+    pub start: usize,
+    /// Character offset just past the sentence.
+    pub end: usize,
+    /// Positions in the answer's citations; empty for sentences nothing
+    /// supports.
+    pub citations: Vec<usize>,
+    /// The sources the sentence cites, each once, in the order it names
+    /// them.
+    pub source_ids: Vec<SourceId>,
+}
+
+/// Maps every sentence of `answer`'s detail to its supporting citations.
+/// Markdown headings are left out.
+pub fn map_evidence(answer: &mut ResearchAnswer) {
+    let detail = &answer.detail;
+    let mut evidence = Vec::new();
+    // Character offset of byte `scanned` of the detail.
+    let (mut scanned, mut chars) = (0, 0);
+
+    for sentence in split_sentences(detail) {
+        let offset = sentence.as_ptr() as usize - detail.as_ptr() as usize;
+        chars += detail[scanned..offset].chars().count();
+        scanned = offset;
+        if sentence.starts_with('#') {
+            continue;
+        }
+
+        let marked = marked_sources(sentence, &answer.references, &answer.citations);
+        let citations = supporting_citations(sentence, &marked, &answer.citations);
+        let mut source_ids = marked;
+        for &i in &citations {
+            let source_id = &answer.citations[i].source_id;
+            if !source_ids.contains(source_id) {
+                source_ids.push(source_id.clone());
+            }
+        }
+
+        let start = chars;
+        evidence.push(SentenceEvidence {
+            start,
+            end: start + sentence.chars().count(),
+            citations,
+            source_ids,
+        });
+    }
+
+    answer.evidence = evidence;
+}
+
+/// The sources `sentence`'s bracketed markers name, each once. Numbers are
+/// looked up in `references`; IDs must be cited by one of `citations`.
+fn marked_sources(
+    sentence: &str,
+    references: &[Reference],
+    citations: &[Citation],
+) -> Vec<SourceId> {
+    let mut sources: Vec<SourceId> = Vec::new();
+    let mut rest = sentence;
+
+    while let Some(open) = rest.find('[') {
+        let inside = &rest[open + 1..];
+        let Some(close) = inside.find(']') else {
+            break;
+        };
+        for token in inside[..close].split([',', ';']).map(str::trim) {
+            let source_id = match token.parse::<usize>() {
+                Ok(number) => references
+                    .iter()
+                    .find(|r| r.number == number)
+                    .map(|r| &r.source_id),
+                Err(_) => citations
+                    .iter()
+                    .find(|c| c.source_id.as_str() == token)
+                    .map(|c| &c.source_id),
+            };
+            if let Some(source_id) = source_id {
+                if !sources.contains(source_id) {
+                    sources.push(source_id.clone());
+                }
+            }
+        }
+        rest = &inside[close + 1..];
+    }
+
+    sources
+}
+
+/// Positions of the citations supporting `sentence`, in order.
+fn supporting_citations(sentence: &str, marked: &[SourceId], citations: &[Citation]) -> Vec<usize> {
+    let mut supporting = Vec::new();
+
+    if marked.is_empty() {
+        let sentence_words: HashSet<String> = words(sentence).into_iter().collect();
+        for (i, citation) in citations.iter().enumerate() {
+            let claim = words(&citation.claim);
+            if claim.is_empty() {
+                continue;
+            }
+            let shared = claim.iter().filter(|w| sentence_words.contains(*w)).count();
+            if shared as f32 / claim.len() as f32 >= MIN_CLAIM_OVERLAP {
+                supporting.push(i);
+            }
+        }
+        return supporting;
+    }
+
+    for source_id in marked {
+        let of_source: Vec<usize> = (0..citations.len())
+            .filter(|&i| citations[i].source_id == *source_id)
+            .collect();
+        let scores: Vec<f32> = of_source
+            .iter()
+            .map(|&i| similarity(sentence, &citations[i].claim))
+            .collect();
+        let best = scores.iter().copied().fold(0.0, f32::max);
+        supporting.extend(
+            of_source
+                .into_iter()
+                .zip(scores)
+                .filter(|&(_, score)| score >= best)
+                .map(|(i, _)| i),
+        );
+    }
+
+    supporting.sort_unstable();
+    supporting.dedup();
+    supporting
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answer::Confidence;
+    use crate::references::number_references;
+    use crate::source::Source;
+
+    #[test]
+    fn maps_sentences_to_the_citations_they_mark() {
+        let sources = [
+            Source::new("https://a.com", "A", ""),
+            Source::new("https://b.com", "B", ""),
+        ];
+        let (a, b) = (&sources[0].id, &sources[1].id);
+        let detail = format!(
+            "## Café\n\nRust is fast [{}]. It has no garbage collector [{}, {}].\n\
+             Its compiler checks ownership at compile time. Nobody disputes this.",
+            a, a, b
+        );
+        let mut answer = ResearchAnswer::new("", detail, Confidence::High, "mock");
+        answer.citations = vec![
+            Citation::new("Rust is fast", a.clone()),
+            Citation::new("Rust has no garbage collector", a.clone()),
+            Citation::new("Rust lacks a garbage collector", b.clone()),
+            Citation::new("The compiler checks ownership", b.clone()),
+        ];
+        number_references(&mut answer, &sources);
+
+        map_evidence(&mut answer);
+
+        let evidence = &answer.evidence;
+        assert_eq!(evidence.len(), 4);
+        let chars: Vec<char> = answer.detail.chars().collect();
+        let text = |e: &SentenceEvidence| chars[e.start..e.end].iter().collect::<String>();
+        assert_eq!(text(&evidence[0]), "Rust is fast [1].");
+        assert_eq!(evidence[0].citations, vec![0]);
+        assert_eq!(evidence[1].citations, vec![1, 2]);
+        assert_eq!(evidence[1].source_ids, vec![a.clone(), b.clone()]);
+        assert_eq!(evidence[2].citations, vec![3]);
+        assert_eq!(evidence[2].source_ids, vec![b.clone()]);
+        assert!(evidence[3].citations.is_empty());
+        assert!(evidence[3].source_ids.is_empty());
+    }
+}
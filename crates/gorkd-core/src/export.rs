@@ -0,0 +1,270 @@
+//! Citation-format export of research sources.
+//!
+//! Sources are exported as web pages: title, URL, author and published date
+//! come from [`SourceMetadata`](crate::SourceMetadata), and the accessed date
+//! is supplied by the caller (typically when the job ran).
+
+use chrono::{DateTime, Datelike, Utc};
+use serde_json::{json, Value};
+
+use crate::source::Source;
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+/// Renders sources as BibTeX `@misc` entries, one per source.
+///
+/// Citation keys are built from the domain and publication year
+/// (`rustlang2024`), with a letter suffix when several sources share one.
+pub fn to_bibtex(sources: &[Source], accessed: DateTime<Utc>) -> String {
+    let mut keys: Vec<String> = Vec::with_capacity(sources.len());
+    let mut out = String::new();
+
+    for source in sources {
+        let key = unique_key(citation_key(source), &keys);
+
+        out.push_str(&format!("@misc{{{},\n", key));
+        push_field(
+            &mut out,
+            "title",
+            &format!("{{{}}}", escape_bibtex(&title(source))),
+        );
+        if let Some(author) = &source.metadata.author {
+            push_field(
+                &mut out,
+                "author",
+                &format!("{{{}}}", escape_bibtex(author)),
+            );
+        }
+        push_field(
+            &mut out,
+            "howpublished",
+            &escape_bibtex(&source.metadata.domain),
+        );
+        if let Some(published) = source.metadata.published_at {
+            push_field(&mut out, "year", &published.year().to_string());
+            // Month macros are left unbraced so BibTeX expands them.
+            out.push_str(&format!(
+                "  month = {},\n",
+                MONTHS[published.month0() as usize]
+            ));
+        }
+        push_field(&mut out, "url", &escape_bibtex_url(&source.url));
+        push_field(
+            &mut out,
+            "urldate",
+            &accessed.format("%Y-%m-%d").to_string(),
+        );
+        out.push_str("}\n\n");
+
+        keys.push(key);
+    }
+
+    out.truncate(out.trim_end().len());
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+/// Renders sources as a CSL-JSON array of `webpage` items.
+pub fn to_csl_json(sources: &[Source], accessed: DateTime<Utc>) -> Value {
+    let items = sources
+        .iter()
+        .map(|source| {
+            let mut item = json!({
+                "id": source.id.as_str(),
+                "type": "webpage",
+                "title": title(source),
+                "URL": source.url,
+                "container-title": source.metadata.domain,
+                "accessed": date_parts(accessed),
+            });
+
+            if let Some(author) = &source.metadata.author {
+                item["author"] = json!([{ "literal": author }]);
+            }
+            if let Some(published) = source.metadata.published_at {
+                item["issued"] = date_parts(published);
+            }
+
+            item
+        })
+        .collect();
+
+    Value::Array(items)
+}
+
+fn title(source: &Source) -> String {
+    if source.title.trim().is_empty() {
+        source.url.clone()
+    } else {
+        source.title.clone()
+    }
+}
+
+fn date_parts(date: DateTime<Utc>) -> Value {
+    json!({ "date-parts": [[date.year(), date.month(), date.day()]] })
+}
+
+fn push_field(out: &mut String, name: &str, value: &str) {
+    out.push_str(&format!("  {} = {{{}}},\n", name, value));
+}
+
+fn citation_key(source: &Source) -> String {
+    let domain = source
+        .metadata
+        .domain
+        .trim_start_matches("www.")
+        .rsplit_once('.')
+        .map(|(name, _tld)| name)
+        .unwrap_or(&source.metadata.domain);
+
+    let mut key: String = domain
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    if key.is_empty() {
+        key.push_str("source");
+    }
+    if let Some(published) = source.metadata.published_at {
+        key.push_str(&published.year().to_string());
+    }
+    key
+}
+
+fn unique_key(base: String, taken: &[String]) -> String {
+    if !taken.contains(&base) {
+        return base;
+    }
+
+    ('a'..='z')
+        .map(|suffix| format!("{}{}", base, suffix))
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap_or_else(|| format!("{}{}", base, taken.len()))
+}
+
+fn escape_bibtex(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\textbackslash{}"),
+            '{' | '}' | '&' | '%' | '$' | '#' | '_' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '~' => out.push_str("\\textasciitilde{}"),
+            '^' => out.push_str("\\textasciicircum{}"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Escapes only what would unbalance the field or start a comment, since
+/// the `url` field is read verbatim otherwise.
+fn escape_bibtex_url(url: &str) -> String {
+    let mut out = String::with_capacity(url.len());
+    for c in url.chars() {
+        if matches!(c, '{' | '}' | '%') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::SourceMetadata;
+    use chrono::TimeZone;
+
+    fn accessed() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 8, 1, 12, 0, 0).unwrap()
+    }
+
+    fn dated_source(url: &str, title: &str) -> Source {
+        let published = Utc.with_ymd_and_hms(2024, 7, 19, 0, 0, 0).unwrap();
+        let mut source = Source::new(url, title, "Content");
+        source.metadata = SourceMetadata::new(source.metadata.domain.clone())
+            .with_published_at(published)
+            .with_author("Jane Doe");
+        source
+    }
+
+    #[test]
+    fn bibtex_includes_metadata() {
+        let sources = vec![dated_source(
+            "https://www.rust-lang.org/about",
+            "About Rust & Friends",
+        )];
+
+        let bib = to_bibtex(&sources, accessed());
+
+        assert!(bib.starts_with("@misc{rustlang2024,\n"));
+        assert!(bib.contains("  title = {{About Rust \\& Friends}},\n"));
+        assert!(bib.contains("  author = {{Jane Doe}},\n"));
+        assert!(bib.contains("  year = {2024},\n"));
+        assert!(bib.contains("  month = jul,\n"));
+        assert!(bib.contains("  url = {https://www.rust-lang.org/about},\n"));
+        assert!(bib.contains("  urldate = {2024-08-01},\n"));
+        assert!(bib.ends_with("}\n"));
+    }
+
+    #[test]
+    fn bibtex_keys_are_unique() {
+        let sources = vec![
+            dated_source("https://example.com/a", "A"),
+            dated_source("https://example.com/b", "B"),
+            Source::new("https://example.com/c", "C", "Content"),
+        ];
+
+        let bib = to_bibtex(&sources, accessed());
+
+        assert!(bib.contains("@misc{example2024,"));
+        assert!(bib.contains("@misc{example2024a,"));
+        assert!(bib.contains("@misc{example,"));
+        assert!(!bib.contains("year = {}"));
+    }
+
+    #[test]
+    fn bibtex_escapes_braces_and_percent_in_url() {
+        let sources = vec![Source::new(
+            "https://example.com/search?q={rust}&x=100%25_off",
+            "Search",
+            "Content",
+        )];
+
+        let bib = to_bibtex(&sources, accessed());
+
+        assert!(bib.contains("  url = {https://example.com/search?q=\\{rust\\}&x=100\\%25_off},\n"));
+    }
+
+    #[test]
+    fn bibtex_is_empty_without_sources() {
+        assert_eq!(to_bibtex(&[], accessed()), "");
+    }
+
+    #[test]
+    fn csl_json_items_are_webpages() {
+        let sources = vec![
+            dated_source("https://example.com/a", "A"),
+            Source::new("https://example.com/b", "", "Content"),
+        ];
+
+        let csl = to_csl_json(&sources, accessed());
+        let items = csl.as_array().unwrap();
+
+        assert_eq!(items[0]["type"], "webpage");
+        assert_eq!(items[0]["id"], sources[0].id.as_str());
+        assert_eq!(items[0]["author"][0]["literal"], "Jane Doe");
+        assert_eq!(items[0]["issued"]["date-parts"], json!([[2024, 7, 19]]));
+        assert_eq!(items[0]["accessed"]["date-parts"], json!([[2024, 8, 1]]));
+        assert_eq!(items[1]["title"], "https://example.com/b");
+        assert!(items[1].get("issued").is_none());
+        assert!(items[1].get("author").is_none());
+    }
+}
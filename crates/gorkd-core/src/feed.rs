@@ -0,0 +1,362 @@
+//! Keeps the corpus current with RSS and Atom feeds.
+//!
+//! A [`FeedPoller`] reads each [`FeedSubscription`] on an interval and adds
+//! entries it has not seen before to the subscription's workspace as
+//! corpus documents, dated with the entry's publication date. An entry is
+//! known by its link, so entries already in the workspace are skipped.
+//!
+//! Research limited to the workspace's corpus and a recency window then
+//! answers from what the feeds published lately, which is how recurring
+//! monitoring jobs stay on recent news instead of the whole web.
+
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::future::FutureExt;
+use futures_timer::Delay;
+
+use crate::corpus::{index_document, CorpusDocument, MAX_CORPUS_DOCUMENT_BYTES};
+use crate::source::DocumentFormat;
+use crate::traits::{Embedder, FeedItem, FeedReader, Store};
+
+/// How often feeds are read when not configured.
+pub const DEFAULT_FEED_POLL_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Longest title given to a feed entry's document, in characters.
+const MAX_FEED_TITLE_CHARS: usize = 500;
+
+/// A feed whose entries are added to a workspace's corpus.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeedSubscription {
+    pub url: String,
+    pub workspace: String,
+}
+
+impl FeedSubscription {
+    pub fn new(url: impl Into<String>, workspace: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            workspace: workspace.into(),
+        }
+    }
+}
+
+/// A feed that could not be read or whose entries could not be added.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeedFailure {
+    pub url: String,
+    pub error: String,
+}
+
+/// What one pass over the subscriptions did.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FeedPollReport {
+    /// Feeds read without error.
+    pub feeds_read: usize,
+    /// Entries added to the corpus.
+    pub added: usize,
+    /// Entries already in the corpus.
+    pub skipped: usize,
+    pub failures: Vec<FeedFailure>,
+}
+
+pub struct FeedPoller {
+    store: Arc<dyn Store>,
+    embedder: Arc<dyn Embedder>,
+    reader: Arc<dyn FeedReader>,
+    subscriptions: Vec<FeedSubscription>,
+    interval: Duration,
+}
+
+impl FeedPoller {
+    pub fn new(
+        store: Arc<dyn Store>,
+        embedder: Arc<dyn Embedder>,
+        reader: Arc<dyn FeedReader>,
+        subscriptions: Vec<FeedSubscription>,
+    ) -> Self {
+        Self {
+            store,
+            embedder,
+            reader,
+            subscriptions,
+            interval: DEFAULT_FEED_POLL_INTERVAL,
+        }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn subscriptions(&self) -> &[FeedSubscription] {
+        &self.subscriptions
+    }
+
+    /// Reads every feed once and adds its new entries. A feed that fails
+    /// stops at the failing entry; the entries not added are tried again on
+    /// the next poll.
+    pub async fn poll_once(&self) -> FeedPollReport {
+        let mut report = FeedPollReport::default();
+        for subscription in &self.subscriptions {
+            if let Err(error) = self.poll_feed(subscription, &mut report).await {
+                report.failures.push(FeedFailure {
+                    url: subscription.url.clone(),
+                    error,
+                });
+            }
+        }
+        report
+    }
+
+    async fn poll_feed(
+        &self,
+        subscription: &FeedSubscription,
+        report: &mut FeedPollReport,
+    ) -> Result<(), String> {
+        let items = self
+            .reader
+            .read(&subscription.url)
+            .await
+            .map_err(|e| e.to_string())?;
+        report.feeds_read += 1;
+
+        for item in items {
+            let known = self
+                .store
+                .find_document_by_url(&subscription.workspace, &item.url)
+                .await
+                .map_err(|e| e.to_string())?;
+            if known.is_some() {
+                report.skipped += 1;
+                continue;
+            }
+            let Some(document) = item_document(item) else {
+                continue;
+            };
+
+            let document = index_document(
+                self.embedder.as_ref(),
+                document.with_workspace(&subscription.workspace),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+            self.store
+                .store_document(&document)
+                .await
+                .map_err(|e| e.to_string())?;
+            report.added += 1;
+        }
+        Ok(())
+    }
+
+    /// Polls the feeds every interval until `shutdown` resolves, passing
+    /// each pass's report to `on_poll`. The first poll runs at once.
+    pub async fn run(
+        &self,
+        shutdown: impl Future<Output = ()>,
+        mut on_poll: impl FnMut(FeedPollReport),
+    ) {
+        let mut shutdown = pin!(shutdown.fuse());
+        loop {
+            let mut poll = pin!(self.poll_once().fuse());
+            futures::select! {
+                report = poll => on_poll(report),
+                _ = shutdown => return,
+            }
+
+            let mut tick = Delay::new(self.interval).fuse();
+            futures::select! {
+                _ = tick => {}
+                _ = shutdown => return,
+            }
+        }
+    }
+}
+
+/// An entry as a document, or `None` when it has neither text nor title.
+/// Entries without text are kept under their title, which is often all a
+/// headline feed carries.
+fn item_document(item: FeedItem) -> Option<CorpusDocument> {
+    let title: String = item
+        .title
+        .trim()
+        .chars()
+        .take(MAX_FEED_TITLE_CHARS)
+        .collect();
+    let mut content = if item.text.trim().is_empty() {
+        title.clone()
+    } else {
+        item.text
+    };
+    if content.trim().is_empty() {
+        return None;
+    }
+    if content.len() > MAX_CORPUS_DOCUMENT_BYTES {
+        let mut end = MAX_CORPUS_DOCUMENT_BYTES;
+        while !content.is_char_boundary(end) {
+            end -= 1;
+        }
+        content.truncate(end);
+    }
+    let title = if title.is_empty() {
+        item.url.clone()
+    } else {
+        title
+    };
+
+    let document = CorpusDocument::new(title, content)
+        .with_url(item.url)
+        .with_format(DocumentFormat::PlainText);
+    Some(match item.published_at {
+        Some(published_at) => document.with_published_at(published_at),
+        None => document,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::mock::{MockEmbedder, MockFeedReader, MockStore};
+    use crate::search::{Recency, SearchFilters};
+
+    const FEED: &str = "https://news.example.com/feed.xml";
+
+    fn poller(store: Arc<MockStore>, reader: Arc<MockFeedReader>) -> FeedPoller {
+        FeedPoller::new(
+            store,
+            Arc::new(MockEmbedder::new()),
+            reader,
+            vec![FeedSubscription::new(FEED, "news")],
+        )
+    }
+
+    #[tokio::test]
+    async fn adds_new_entries_once() {
+        let store = Arc::new(MockStore::new());
+        let reader = Arc::new(
+            MockFeedReader::new().with_item(
+                FEED,
+                FeedItem::new(
+                    "https://news.example.com/rust-2",
+                    "Rust 2.0 announced",
+                    "The Rust team announced Rust 2.0.",
+                )
+                .with_published_at(Utc.with_ymd_and_hms(2026, 10, 1, 9, 0, 0).unwrap()),
+            ),
+        );
+        let poller = poller(Arc::clone(&store), Arc::clone(&reader));
+
+        let first = poller.poll_once().await;
+        assert_eq!(first.feeds_read, 1);
+        assert_eq!(first.added, 1);
+
+        reader.push_item(
+            FEED,
+            FeedItem::new("https://news.example.com/headline", "Only a headline", ""),
+        );
+        let second = poller.poll_once().await;
+        assert_eq!((second.added, second.skipped), (1, 1));
+
+        let documents = store.list_documents("news", 10, 0).await.unwrap();
+        assert_eq!(documents.len(), 2);
+        let dated = documents
+            .iter()
+            .find(|d| d.title == "Rust 2.0 announced")
+            .unwrap();
+        assert_eq!(
+            dated.published_at,
+            Some(Utc.with_ymd_and_hms(2026, 10, 1, 9, 0, 0).unwrap())
+        );
+        let headline = documents
+            .iter()
+            .find(|d| d.title == "Only a headline")
+            .unwrap();
+        assert_eq!(headline.content, "Only a headline");
+        assert!(store
+            .list_documents("default", 10, 0)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn reports_unreadable_feeds_and_keeps_polling_the_rest() {
+        let store = Arc::new(MockStore::new());
+        let reader = Arc::new(MockFeedReader::new().with_item(
+            FEED,
+            FeedItem::new("https://news.example.com/a", "A", "Text of a."),
+        ));
+        let poller = FeedPoller::new(
+            Arc::clone(&store) as Arc<dyn Store>,
+            Arc::new(MockEmbedder::new()),
+            reader,
+            vec![
+                FeedSubscription::new("https://gone.example.com/rss", "news"),
+                FeedSubscription::new(FEED, "news"),
+            ],
+        );
+
+        let report = poller.poll_once().await;
+
+        assert_eq!(report.feeds_read, 1);
+        assert_eq!(report.added, 1);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].url, "https://gone.example.com/rss");
+    }
+
+    #[tokio::test]
+    async fn recency_filters_leave_out_old_entries() {
+        let store = Arc::new(MockStore::new());
+        let reader = Arc::new(
+            MockFeedReader::new()
+                .with_item(
+                    FEED,
+                    FeedItem::new("https://news.example.com/old", "Old", "Rust news.")
+                        .with_published_at(Utc::now() - chrono::Duration::days(60)),
+                )
+                .with_item(
+                    FEED,
+                    FeedItem::new("https://news.example.com/new", "New", "Rust news.")
+                        .with_published_at(Utc::now() - chrono::Duration::hours(2)),
+                ),
+        );
+        poller(Arc::clone(&store), reader).poll_once().await;
+
+        let embedder = MockEmbedder::new();
+        let embedding = embedder.embed(&["Rust news.".to_string()]).await.unwrap();
+        let recent = SearchFilters::new().with_recency(Recency::Week);
+        let matches = store
+            .search_corpus("news", embedder.model_id(), &embedding[0], &recent, 10)
+            .await
+            .unwrap();
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].title, "New");
+    }
+
+    #[tokio::test]
+    async fn run_stops_on_shutdown() {
+        let store = Arc::new(MockStore::new());
+        let reader = Arc::new(MockFeedReader::new().with_item(
+            FEED,
+            FeedItem::new("https://news.example.com/a", "A", "Text of a."),
+        ));
+        let poller = poller(store, reader).with_interval(Duration::from_millis(5));
+        let mut reports = Vec::new();
+
+        poller
+            .run(Delay::new(Duration::from_millis(30)), |report| {
+                reports.push(report)
+            })
+            .await;
+
+        assert!(reports.len() >= 2);
+        assert_eq!(reports[0].added, 1);
+        assert_eq!(reports[1].skipped, 1);
+    }
+}
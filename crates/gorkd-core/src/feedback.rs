@@ -0,0 +1,270 @@
+//! Feedback on answers and sources, and the domain trust it builds.
+//!
+//! Readers know when an answer was useful and when a source was not. A
+//! [`Feedback`] records a job's rating, a comment and the sources its reader
+//! flagged. Each feedback lends or takes trust from the domains involved: a
+//! helpful rating endorses the domains the answer cites, an unhelpful one
+//! objects to them, and a flag counts heavily against the flagged source's
+//! domain. The resulting [`DomainTrust`] shifts the relevance of the
+//! domain's sources in later searches, within [`MAX_TRUST_ADJUSTMENT`], and
+//! labels them with a [`TrustLabel`].
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::answer::ResearchAnswer;
+use crate::id::{FeedbackId, JobId, SourceId};
+use crate::source::Source;
+
+/// Longest feedback comment, in characters.
+pub const MAX_FEEDBACK_COMMENT_LENGTH: usize = 2000;
+
+/// Most a domain's trust may raise or lower the relevance score of its
+/// sources.
+pub const MAX_TRUST_ADJUSTMENT: f32 = 0.2;
+
+/// Net signals that move a domain halfway to [`MAX_TRUST_ADJUSTMENT`], so a
+/// single reader cannot swing a domain on their own.
+const TRUST_PRIOR: f32 = 5.0;
+
+/// How many endorsements one flag outweighs.
+const FLAG_WEIGHT: f32 = 3.0;
+
+/// Adjustments at least this far from zero label a domain trusted or
+/// distrusted.
+const LABEL_THRESHOLD: f32 = 0.05;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum AnswerRating {
+    Helpful,
+    Unhelpful,
+}
+
+/// A source the reader flagged as bad.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SourceFlag {
+    pub source_id: SourceId,
+    pub domain: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// One reader's feedback on a job.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Feedback {
+    pub id: FeedbackId,
+    pub job_id: JobId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<AnswerRating>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// Domains of the sources the rated answer cites, which the rating
+    /// applies to.
+    #[serde(default)]
+    pub rated_domains: Vec<String>,
+    #[serde(default)]
+    pub flagged_sources: Vec<SourceFlag>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Feedback {
+    pub fn new(job_id: JobId) -> Self {
+        Self {
+            id: FeedbackId::new(),
+            job_id,
+            rating: None,
+            comment: None,
+            rated_domains: Vec::new(),
+            flagged_sources: Vec::new(),
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Rates `answer`. The rating applies to the domains of the `sources`
+    /// it cites.
+    pub fn with_rating(
+        mut self,
+        rating: AnswerRating,
+        answer: &ResearchAnswer,
+        sources: &[Source],
+    ) -> Self {
+        self.rating = Some(rating);
+        self.rated_domains.clear();
+        for source in sources
+            .iter()
+            .filter(|s| answer.citations.iter().any(|c| c.source_id == s.id))
+        {
+            let domain = source.metadata.domain.to_ascii_lowercase();
+            if !domain.is_empty() && !self.rated_domains.contains(&domain) {
+                self.rated_domains.push(domain);
+            }
+        }
+        self
+    }
+
+    pub fn with_comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+
+    /// Flags `source` as bad. Flagging a source twice keeps the first flag.
+    pub fn with_flag(mut self, source: &Source, reason: Option<String>) -> Self {
+        if !self
+            .flagged_sources
+            .iter()
+            .any(|f| f.source_id == source.id)
+        {
+            self.flagged_sources.push(SourceFlag {
+                source_id: source.id.clone(),
+                domain: source.metadata.domain.to_ascii_lowercase(),
+                reason,
+            });
+        }
+        self
+    }
+
+    /// The trust this feedback lends or takes from each domain it involves.
+    /// A domain flagged more than once counts one flag.
+    pub fn domain_trust(&self) -> Vec<DomainTrust> {
+        let mut trust = Vec::new();
+        if let Some(rating) = self.rating {
+            for domain in &self.rated_domains {
+                let domain = trust_entry(&mut trust, domain);
+                match rating {
+                    AnswerRating::Helpful => domain.endorsements += 1,
+                    AnswerRating::Unhelpful => domain.objections += 1,
+                }
+            }
+        }
+        for flag in self.flagged_sources.iter().filter(|f| !f.domain.is_empty()) {
+            trust_entry(&mut trust, &flag.domain).flags = 1;
+        }
+        trust
+    }
+}
+
+fn trust_entry<'a>(trust: &'a mut Vec<DomainTrust>, domain: &str) -> &'a mut DomainTrust {
+    let i = match trust.iter().position(|t| t.domain == domain) {
+        Some(i) => i,
+        None => {
+            trust.push(DomainTrust::new(domain));
+            trust.len() - 1
+        }
+    };
+    &mut trust[i]
+}
+
+/// How readers' feedback has judged a domain's sources.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum TrustLabel {
+    Trusted,
+    Neutral,
+    Distrusted,
+}
+
+/// The feedback a domain has received, summed over every job.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DomainTrust {
+    pub domain: String,
+    /// Helpful ratings of answers citing the domain.
+    pub endorsements: u32,
+    /// Unhelpful ratings of answers citing the domain.
+    pub objections: u32,
+    /// Feedback that flagged one of the domain's sources.
+    pub flags: u32,
+}
+
+impl DomainTrust {
+    pub fn new(domain: impl Into<String>) -> Self {
+        Self {
+            domain: domain.into().to_ascii_lowercase(),
+            endorsements: 0,
+            objections: 0,
+            flags: 0,
+        }
+    }
+
+    /// Adds the counts of `other`, for the same domain.
+    pub fn merge(&mut self, other: &DomainTrust) {
+        self.endorsements += other.endorsements;
+        self.objections += other.objections;
+        self.flags += other.flags;
+    }
+
+    /// What the domain's trust adds to the relevance score of its sources,
+    /// short of [`MAX_TRUST_ADJUSTMENT`] either way.
+    pub fn adjustment(&self) -> f32 {
+        let net =
+            self.endorsements as f32 - self.objections as f32 - FLAG_WEIGHT * self.flags as f32;
+        MAX_TRUST_ADJUSTMENT * net / (net.abs() + TRUST_PRIOR)
+    }
+
+    pub fn label(&self) -> TrustLabel {
+        match self.adjustment() {
+            a if a >= LABEL_THRESHOLD => TrustLabel::Trusted,
+            a if a <= -LABEL_THRESHOLD => TrustLabel::Distrusted,
+            _ => TrustLabel::Neutral,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answer::{Citation, Confidence};
+
+    fn source(url: &str, domain: &str) -> Source {
+        let mut source = Source::new(url, url, "content");
+        source.metadata.domain = domain.to_string();
+        source
+    }
+
+    #[test]
+    fn rating_applies_to_cited_domains() {
+        let cited = source("https://a.example/1", "A.example");
+        let also_cited = source("https://a.example/2", "a.example");
+        let uncited = source("https://b.example/", "b.example");
+        let answer = ResearchAnswer::new("A", "", Confidence::High, "mock").with_citations(vec![
+            Citation::new("one", cited.id.clone()),
+            Citation::new("two", also_cited.id.clone()),
+        ]);
+
+        let feedback = Feedback::new(JobId::new())
+            .with_rating(
+                AnswerRating::Helpful,
+                &answer,
+                &[cited.clone(), also_cited, uncited.clone()],
+            )
+            .with_flag(&uncited, None)
+            .with_flag(&uncited, Some("again".to_string()));
+
+        assert_eq!(feedback.rated_domains, ["a.example"]);
+        assert_eq!(feedback.flagged_sources.len(), 1);
+        let trust = feedback.domain_trust();
+        assert_eq!(trust.len(), 2);
+        assert_eq!(
+            (trust[0].domain.as_str(), trust[0].endorsements),
+            ("a.example", 1)
+        );
+        assert_eq!((trust[1].domain.as_str(), trust[1].flags), ("b.example", 1));
+    }
+
+    #[test]
+    fn trust_is_bounded_and_labelled() {
+        let mut trust = DomainTrust::new("a.example");
+        assert_eq!(trust.label(), TrustLabel::Neutral);
+
+        trust.endorsements = 1000;
+        assert!(trust.adjustment() < MAX_TRUST_ADJUSTMENT);
+        assert_eq!(trust.label(), TrustLabel::Trusted);
+
+        let mut flagged = DomainTrust::new("b.example");
+        flagged.flags = 1;
+        assert!(flagged.adjustment() < -LABEL_THRESHOLD);
+        assert_eq!(flagged.label(), TrustLabel::Distrusted);
+    }
+}
@@ -0,0 +1,172 @@
+//! How long answers stay current.
+//!
+//! What an answer says about this morning's outage may be wrong by tonight,
+//! while an explanation of how TCP works holds for years. Each answer is
+//! given a [`Volatility`] from its job's intent and recency filter, and an
+//! [`AnswerFreshness`] whose `expires_at` the [`FreshnessPolicy`] sets from
+//! it. Past that time the answer is stale: it is still served, marked so,
+//! and anything reusing answers across jobs must not hand it out.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::job::ResearchJob;
+use crate::query::{QuestionType, TimeConstraint};
+use crate::search::Recency;
+
+/// How quickly what an answer says goes out of date.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Volatility {
+    /// News and other questions about what is happening right now.
+    Breaking,
+    /// Questions about the last week or month.
+    Volatile,
+    /// Facts, comparisons and recommendations that change now and then.
+    Stable,
+    /// Explanations, how-tos and history.
+    Evergreen,
+}
+
+impl Volatility {
+    /// The volatility of what `job` asks: its recency filter first, then
+    /// the time constraint and question type of its intent.
+    pub fn of(job: &ResearchJob) -> Self {
+        match job.filters.recency {
+            Some(Recency::Day) => return Self::Breaking,
+            Some(Recency::Week | Recency::Month) => return Self::Volatile,
+            _ => {}
+        }
+        let Some(ref intent) = job.intent else {
+            return Self::Stable;
+        };
+        if intent.question_type == QuestionType::CurrentEvent {
+            return Self::Breaking;
+        }
+        match intent.time_constraint {
+            Some(TimeConstraint::Recent) | Some(TimeConstraint::DateRange { to: None, .. }) => {
+                Self::Volatile
+            }
+            Some(TimeConstraint::Historical)
+            | Some(TimeConstraint::SpecificDate(_))
+            | Some(TimeConstraint::DateRange { .. }) => Self::Evergreen,
+            None => match intent.question_type {
+                QuestionType::Explanation | QuestionType::HowTo => Self::Evergreen,
+                _ => Self::Stable,
+            },
+        }
+    }
+}
+
+/// How long answers of each [`Volatility`] stay current.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FreshnessPolicy {
+    pub breaking: Duration,
+    pub volatile: Duration,
+    pub stable: Duration,
+    pub evergreen: Duration,
+}
+
+impl Default for FreshnessPolicy {
+    fn default() -> Self {
+        const HOUR: u64 = 60 * 60;
+        Self {
+            breaking: Duration::from_secs(6 * HOUR),
+            volatile: Duration::from_secs(24 * HOUR),
+            stable: Duration::from_secs(7 * 24 * HOUR),
+            evergreen: Duration::from_secs(30 * 24 * HOUR),
+        }
+    }
+}
+
+impl FreshnessPolicy {
+    pub fn ttl(&self, volatility: Volatility) -> Duration {
+        match volatility {
+            Volatility::Breaking => self.breaking,
+            Volatility::Volatile => self.volatile,
+            Volatility::Stable => self.stable,
+            Volatility::Evergreen => self.evergreen,
+        }
+    }
+
+    /// The freshness of an answer to `job` written at `written_at`.
+    pub fn assess(&self, job: &ResearchJob, written_at: DateTime<Utc>) -> AnswerFreshness {
+        let volatility = Volatility::of(job);
+        let ttl = chrono::Duration::from_std(self.ttl(volatility)).unwrap_or(chrono::Duration::MAX);
+        AnswerFreshness {
+            volatility,
+            expires_at: written_at
+                .checked_add_signed(ttl)
+                .unwrap_or(DateTime::<Utc>::MAX_UTC),
+        }
+    }
+}
+
+/// How volatile an answer's topic is and when it goes stale.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnswerFreshness {
+    pub volatility: Volatility,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl AnswerFreshness {
+    /// Whether the answer is out of date at `now`.
+    pub fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::QueryIntent;
+    use crate::search::SearchFilters;
+
+    fn job(query: &str) -> ResearchJob {
+        ResearchJob::new(query)
+            .unwrap()
+            .with_intent(QueryIntent::classify(query))
+    }
+
+    #[test]
+    fn classifies_volatility_from_intent() {
+        assert_eq!(
+            Volatility::of(&job("latest news on the CrowdStrike outage")),
+            Volatility::Breaking
+        );
+        assert_eq!(
+            Volatility::of(&job("what is the population of Lyon")),
+            Volatility::Stable
+        );
+        assert_eq!(
+            Volatility::of(&job("how does TCP congestion control work")),
+            Volatility::Evergreen
+        );
+    }
+
+    #[test]
+    fn recency_filter_overrides_intent() {
+        let job = job("how does TCP congestion control work")
+            .with_filters(SearchFilters::new().with_recency(Recency::Day));
+
+        assert_eq!(Volatility::of(&job), Volatility::Breaking);
+    }
+
+    #[test]
+    fn answers_go_stale_after_their_ttl() {
+        let written_at = Utc::now();
+        let freshness =
+            FreshnessPolicy::default().assess(&job("latest news on the outage"), written_at);
+
+        assert_eq!(freshness.volatility, Volatility::Breaking);
+        assert_eq!(
+            freshness.expires_at,
+            written_at + chrono::Duration::hours(6)
+        );
+        assert!(!freshness.is_stale(written_at + chrono::Duration::hours(5)));
+        assert!(freshness.is_stale(written_at + chrono::Duration::hours(6)));
+    }
+}
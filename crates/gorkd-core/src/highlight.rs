@@ -0,0 +1,377 @@
+//! Locating cited quotes in their sources, so the supporting text can be
+//! highlighted.
+//!
+//! Models rarely copy quotes byte for byte: they change curly quotes to
+//! straight ones, collapse line breaks, or elide words with `...`. A quote is
+//! first looked for verbatim, then with case, whitespace and punctuation
+//! variants folded, and finally piece by piece around ellipses. Offsets are
+//! in characters (Unicode scalar values) of the source content.
+//!
+//! Located quotes of web pages also get a [text fragment] URL, which makes
+//! the browser scroll to and highlight the passage on the original page.
+//!
+//! [text fragment]: https://wicg.github.io/scroll-to-text-fragment/
+
+use serde::{Deserialize, Serialize};
+
+use crate::answer::ResearchAnswer;
+use crate::source::{DocumentFormat, Source};
+
+/// Quotes longer than this many words are anchored by their first and last
+/// [`FRAGMENT_EDGE_WORDS`] words instead of in full, keeping URLs short.
+pub const MAX_FRAGMENT_WORDS: usize = 10;
+
+/// Words kept from each end of a long quote in its text fragment.
+pub const FRAGMENT_EDGE_WORDS: usize = 4;
+
+/// Where a citation's quote was found in the cited source's content.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum QuoteLocation {
+    Found {
+        /// Character offset of the first character of the quote.
+        start: usize,
+        /// Character offset just past the quote.
+        end: usize,
+        /// Whether the quote appears verbatim. Otherwise it matched only
+        /// after folding case, whitespace or punctuation, or around elisions.
+        exact: bool,
+    },
+    /// The quote is not in the source, so the citation may not be supported.
+    NotFound,
+}
+
+impl QuoteLocation {
+    pub fn is_found(&self) -> bool {
+        matches!(self, Self::Found { .. })
+    }
+}
+
+/// Locates the quote of every citation that has one in the cited source,
+/// and anchors the source URL to quotes found in web pages. Quotes citing a
+/// source not in `sources` are not found.
+pub fn locate_quotes(answer: &mut ResearchAnswer, sources: &[Source]) {
+    for citation in &mut answer.citations {
+        let Some(ref quote) = citation.quote else {
+            continue;
+        };
+        let Some(source) = sources.iter().find(|s| s.id == citation.source_id) else {
+            citation.quote_location = Some(QuoteLocation::NotFound);
+            continue;
+        };
+
+        let location = locate_quote(&source.content, quote);
+        if let QuoteLocation::Found { start, end, .. } = location {
+            citation.anchored_url = anchored_url(source, char_slice(&source.content, start, end));
+        }
+        citation.quote_location = Some(location);
+    }
+}
+
+/// The source URL with a text fragment for `passage`, if the source is a
+/// web page. Text extracted from PDFs or transcripts is not on the page as
+/// such, so those are left unanchored. Archived sources are anchored in
+/// their snapshot, since the page itself is gone.
+fn anchored_url(source: &Source, passage: &str) -> Option<String> {
+    let url = source
+        .metadata
+        .archived
+        .as_ref()
+        .map_or(source.url.as_str(), |snapshot| snapshot.url.as_str());
+    match source.metadata.format {
+        None | Some(DocumentFormat::Html) => text_fragment_url(url, passage),
+        Some(_) => None,
+    }
+}
+
+/// Appends a `:~:text=` directive for `passage` to `url`. Long passages are
+/// matched by their first and last words. Returns `None` for URLs that are
+/// not http(s) or a passage without words.
+pub fn text_fragment_url(url: &str, passage: &str) -> Option<String> {
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        return None;
+    }
+
+    let words: Vec<&str> = passage.split_whitespace().collect();
+    let directive = match words.len() {
+        0 => return None,
+        n if n <= MAX_FRAGMENT_WORDS => fragment_encode(&words.join(" ")),
+        n => format!(
+            "{},{}",
+            fragment_encode(&words[..FRAGMENT_EDGE_WORDS].join(" ")),
+            fragment_encode(&words[n - FRAGMENT_EDGE_WORDS..].join(" "))
+        ),
+    };
+
+    // An existing fragment is kept; the directive follows it.
+    let separator = if url.contains('#') { "" } else { "#" };
+    Some(format!("{}{}:~:text={}", url, separator, directive))
+}
+
+/// Percent-encodes everything but unreserved characters, so the `-`, `,` and
+/// `&` that delimit text directives are escaped too.
+fn fragment_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Finds `quote` in `content`.
+pub fn locate_quote(content: &str, quote: &str) -> QuoteLocation {
+    let quote = quote
+        .trim()
+        .trim_matches(|c: char| matches!(c, '"' | '\'' | '“' | '”' | '‘' | '’'))
+        .trim();
+    if quote.is_empty() {
+        return QuoteLocation::NotFound;
+    }
+
+    if let Some(byte) = content.find(quote) {
+        let start = content[..byte].chars().count();
+        return QuoteLocation::Found {
+            start,
+            end: start + quote.chars().count(),
+            exact: true,
+        };
+    }
+
+    let (haystack, offsets) = fold(content);
+    let mut from = 0;
+    let mut span: Option<(usize, usize)> = None;
+
+    for piece in quote.split('…').flat_map(|p| p.split("...")) {
+        let (needle, _) = fold(piece);
+        if needle.is_empty() {
+            continue;
+        }
+        let Some(at) = find(&haystack[from..], &needle) else {
+            return QuoteLocation::NotFound;
+        };
+        let (start, end) = (from + at, from + at + needle.len());
+        span = Some((span.map_or(start, |(first, _)| first), end));
+        from = end;
+    }
+
+    match span {
+        Some((start, end)) => QuoteLocation::Found {
+            start: offsets[start],
+            end: offsets[end - 1] + 1,
+            exact: false,
+        },
+        None => QuoteLocation::NotFound,
+    }
+}
+
+/// The characters of `text` from character `start` up to `end`.
+pub fn char_slice(text: &str, start: usize, end: usize) -> &str {
+    let byte = |offset: usize| {
+        text.char_indices()
+            .nth(offset)
+            .map_or(text.len(), |(i, _)| i)
+    };
+    let from = byte(start);
+    &text[from..byte(end).max(from)]
+}
+
+/// Folds `text` for matching: lowercase, whitespace runs as one space and
+/// typographic quotes and dashes as their ASCII forms. Returns the folded
+/// characters with the character offset in `text` each came from.
+fn fold(text: &str) -> (Vec<char>, Vec<usize>) {
+    let mut chars = Vec::with_capacity(text.len());
+    let mut offsets = Vec::with_capacity(text.len());
+    let mut space = false;
+
+    for (i, c) in text.chars().enumerate() {
+        if c.is_whitespace() {
+            space = !chars.is_empty();
+            continue;
+        }
+        if space {
+            chars.push(' ');
+            offsets.push(i - 1);
+            space = false;
+        }
+
+        let c = match c {
+            '‘' | '’' | '‛' => '\'',
+            '“' | '”' | '„' => '"',
+            '–' | '—' | '‐' => '-',
+            c => c,
+        };
+        for lower in c.to_lowercase() {
+            chars.push(lower);
+            offsets.push(i);
+        }
+    }
+
+    (chars, offsets)
+}
+
+fn find(haystack: &[char], needle: &[char]) -> Option<usize> {
+    if needle.len() > haystack.len() {
+        return None;
+    }
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    use crate::answer::{Citation, Confidence};
+    use crate::source::{ArchivedSnapshot, SourceMetadata};
+
+    const CONTENT: &str = "Résumé: On July 19, CrowdStrike released a\n  \
+                           “content update” — which crashed 8.5 million Windows devices.";
+
+    fn found(start: usize, end: usize, exact: bool) -> QuoteLocation {
+        QuoteLocation::Found { start, end, exact }
+    }
+
+    #[test]
+    fn finds_verbatim_quote_in_characters() {
+        let location = locate_quote(CONTENT, "\"On July 19, CrowdStrike\"");
+
+        assert_eq!(location, found(8, 31, true));
+        assert_eq!(char_slice(CONTENT, 8, 31), "On July 19, CrowdStrike");
+    }
+
+    #[test]
+    fn folds_whitespace_case_and_typography() {
+        let location = locate_quote(CONTENT, "released a \"Content Update\" - which crashed");
+
+        let QuoteLocation::Found { start, end, exact } = location else {
+            panic!("quote not found");
+        };
+        assert!(!exact);
+        assert_eq!(
+            char_slice(CONTENT, start, end),
+            "released a\n  “content update” — which crashed"
+        );
+    }
+
+    #[test]
+    fn matches_around_ellipses() {
+        let location = locate_quote(
+            CONTENT,
+            "CrowdStrike released ... 8.5 million Windows devices",
+        );
+
+        let QuoteLocation::Found { start, end, .. } = location else {
+            panic!("quote not found");
+        };
+        assert!(char_slice(CONTENT, start, end).starts_with("CrowdStrike released"));
+        assert!(char_slice(CONTENT, start, end).ends_with("Windows devices"));
+    }
+
+    #[test]
+    fn flags_missing_quotes() {
+        assert_eq!(
+            locate_quote(CONTENT, "Microsoft blamed the EU"),
+            QuoteLocation::NotFound
+        );
+        assert_eq!(locate_quote(CONTENT, "\"\""), QuoteLocation::NotFound);
+    }
+
+    #[test]
+    fn builds_text_fragment_urls() {
+        assert_eq!(
+            text_fragment_url("https://example.com/a", "Rust 1.0 - stable, at last").unwrap(),
+            "https://example.com/a#:~:text=Rust%201.0%20%2D%20stable%2C%20at%20last"
+        );
+        assert_eq!(
+            text_fragment_url(
+                "https://example.com/a#intro",
+                "one two three four five six seven eight nine ten eleven"
+            )
+            .unwrap(),
+            "https://example.com/a#intro:~:text=one%20two%20three%20four,eight%20nine%20ten%20eleven"
+        );
+        assert_eq!(
+            text_fragment_url("https://example.com/é", "café").unwrap(),
+            "https://example.com/é#:~:text=caf%C3%A9"
+        );
+        assert!(text_fragment_url("ftp://example.com", "text").is_none());
+        assert!(text_fragment_url("https://example.com", " \n ").is_none());
+    }
+
+    #[test]
+    fn anchors_only_quotes_found_in_web_pages() {
+        let page = Source::new("https://example.com/page", "Page", CONTENT);
+        let pdf = Source::new("https://example.com/report.pdf", "Report", CONTENT)
+            .with_metadata(SourceMetadata::new("example.com").with_format(DocumentFormat::Pdf));
+        let mut answer =
+            ResearchAnswer::new("s", "d", Confidence::High, "mock").with_citations(vec![
+                Citation::new("page", page.id.clone()).with_quote("“content update”"),
+                Citation::new("pdf", pdf.id.clone()).with_quote("On July 19"),
+                Citation::new("missing", page.id.clone()).with_quote("a solar flare"),
+            ]);
+
+        locate_quotes(&mut answer, &[page, pdf]);
+
+        assert_eq!(
+            answer.citations[0].anchored_url.as_deref(),
+            Some("https://example.com/page#:~:text=content%20update")
+        );
+        assert!(answer.citations[1]
+            .quote_location
+            .as_ref()
+            .unwrap()
+            .is_found());
+        assert!(answer.citations[1].anchored_url.is_none());
+        assert!(answer.citations[2].anchored_url.is_none());
+    }
+
+    #[test]
+    fn anchors_archived_sources_in_their_snapshot() {
+        let snapshot = ArchivedSnapshot {
+            url: "https://web.archive.org/web/20240719000000/https://example.com/page".to_string(),
+            captured_at: Utc.with_ymd_and_hms(2024, 7, 19, 0, 0, 0).unwrap(),
+        };
+        let page = Source::new("https://example.com/page", "Page", CONTENT)
+            .with_metadata(SourceMetadata::new("example.com").with_archived(snapshot));
+        let mut answer =
+            ResearchAnswer::new("s", "d", Confidence::High, "mock").with_citations(vec![
+                Citation::new("page", page.id.clone()).with_quote("content update"),
+            ]);
+
+        locate_quotes(&mut answer, &[page]);
+
+        assert_eq!(
+            answer.citations[0].anchored_url.as_deref(),
+            Some(
+                "https://web.archive.org/web/20240719000000/https://example.com/page\
+                 #:~:text=content%20update"
+            )
+        );
+    }
+
+    #[test]
+    fn locates_answer_quotes_in_cited_sources() {
+        let source = Source::new("https://example.com", "Outage", CONTENT);
+        let mut answer =
+            ResearchAnswer::new("s", "d", Confidence::High, "mock").with_citations(vec![
+                Citation::new("date", source.id.clone()).with_quote("On July 19"),
+                Citation::new("cause", source.id.clone()).with_quote("a solar flare"),
+                Citation::new("no quote", source.id.clone()),
+            ]);
+
+        locate_quotes(&mut answer, &[source]);
+
+        assert_eq!(answer.citations[0].quote_location, Some(found(8, 18, true)));
+        assert_eq!(
+            answer.citations[1].quote_location,
+            Some(QuoteLocation::NotFound)
+        );
+        assert_eq!(answer.citations[2].quote_location, None);
+    }
+}
@@ -0,0 +1,120 @@
+//! Settings shared by every outbound HTTP client.
+//!
+//! Search providers, LLM providers and the page fetcher each build their own
+//! client, but networks that only allow egress through a proxy, or that
+//! intercept TLS with their own certificate authority, need the same
+//! settings on all of them. [`HttpOptions`] holds those settings; each crate
+//! applies them when it builds its client.
+
+use std::time::Duration;
+
+/// User-Agent sent when none is configured.
+pub const DEFAULT_USER_AGENT: &str = concat!("gorkd/", env!("CARGO_PKG_VERSION"));
+
+/// Time allowed to establish a connection when none is configured.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HttpOptions {
+    /// User-Agent of every request; `None` sends [`DEFAULT_USER_AGENT`].
+    pub user_agent: Option<String>,
+    /// Proxy for every request, such as `http://proxy.corp:3128` or
+    /// `socks5h://proxy.corp:1080`. Without one, the standard `HTTP_PROXY`,
+    /// `HTTPS_PROXY` and `NO_PROXY` variables apply.
+    pub proxy: Option<String>,
+    /// Comma-separated hosts, domains and IP ranges reached without `proxy`.
+    pub no_proxy: Option<String>,
+    /// PEM certificates of extra certificate authorities to trust, such as a
+    /// TLS-intercepting proxy's.
+    pub root_certificates: Option<Vec<u8>>,
+    /// Accepts any server certificate. Only for testing against servers with
+    /// self-signed certificates.
+    pub accept_invalid_certs: bool,
+    /// Time allowed to establish a connection; `None` allows
+    /// [`DEFAULT_CONNECT_TIMEOUT`].
+    pub connect_timeout: Option<Duration>,
+    /// Idle connections kept open per host for reuse; `None` keeps the
+    /// client's own default.
+    pub pool_max_idle_per_host: Option<usize>,
+    /// How long an idle connection is kept for reuse; `None` keeps the
+    /// client's own default.
+    pub pool_idle_timeout: Option<Duration>,
+    /// Interval of TCP keep-alive probes on open connections; `None`
+    /// sends none.
+    pub tcp_keepalive: Option<Duration>,
+}
+
+impl HttpOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn user_agent(&self) -> &str {
+        self.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT)
+    }
+
+    pub fn connect_timeout(&self) -> Duration {
+        self.connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT)
+    }
+
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    pub fn with_proxy(mut self, proxy: impl Into<String>) -> Self {
+        self.proxy = Some(proxy.into());
+        self
+    }
+
+    pub fn with_no_proxy(mut self, no_proxy: impl Into<String>) -> Self {
+        self.no_proxy = Some(no_proxy.into());
+        self
+    }
+
+    pub fn with_root_certificates(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.root_certificates = Some(pem.into());
+        self
+    }
+
+    pub fn accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.accept_invalid_certs = accept;
+        self
+    }
+
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    pub fn with_pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_tcp_keepalive(mut self, interval: Duration) -> Self {
+        self.tcp_keepalive = Some(interval);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_user_agent() {
+        assert!(HttpOptions::new().user_agent().starts_with("gorkd/"));
+        assert_eq!(
+            HttpOptions::new()
+                .with_user_agent("acme-research/1.0")
+                .user_agent(),
+            "acme-research/1.0"
+        );
+    }
+}
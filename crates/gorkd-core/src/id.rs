@@ -88,8 +88,14 @@ macro_rules! define_id {
     };
 }
 
+define_id!(DocumentId, "doc_");
+define_id!(FactId, "fct_");
+define_id!(FeedbackId, "fbk_");
 define_id!(JobId, "job_");
+define_id!(ProjectId, "prj_");
 define_id!(SourceId, "src_");
+define_id!(TraceId, "trc_");
+define_id!(WorkerId, "wkr_");
 
 #[cfg(test)]
 mod tests {
@@ -1,9 +1,15 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::error::{validate_query, QueryError};
-use crate::id::JobId;
+use crate::answer_schema::AnswerSchema;
+use crate::budget::CostBudget;
+use crate::depth::AnswerDepth;
+use crate::error::{validate_query, ErrorCode, QueryError, TransitionError};
+use crate::id::{JobId, TraceId};
+use crate::profile::ResearchProfile;
 use crate::query::QueryIntent;
+use crate::search::{SearchFilters, SourceLimits};
+use crate::style::AnswerStyle;
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -26,17 +32,128 @@ impl JobStatus {
     pub fn is_active(&self) -> bool {
         !self.is_terminal()
     }
+
+    /// Whether a job may move from this status to `next`. Jobs advance
+    /// through the pipeline stages in order, may fail from any active
+    /// status, and never leave a terminal one.
+    pub fn can_transition_to(&self, next: &JobStatus) -> bool {
+        use JobStatus::*;
+
+        match (self, next) {
+            (from, _) if from.is_terminal() => false,
+            (_, Failed) => true,
+            // A job whose worker died is run again from the start.
+            (_, Planning) => true,
+            (Planning, Searching)
+            | (Searching, Fetching | Synthesizing)
+            | (Fetching, Synthesizing)
+            | (Synthesizing, Completed) => true,
+            // Sources-only jobs finish without synthesis.
+            (Searching | Fetching, Completed) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Why a job failed, for clients to explain the failure and decide whether
+/// to retry.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobFailure {
+    /// The status the job was in when it failed.
+    pub stage: JobStatus,
+    pub kind: ErrorCode,
+    /// The search provider or LLM model that failed, if one did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// Whether running the query again may succeed.
+    pub retryable: bool,
+    pub message: String,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ResearchJob {
     pub id: JobId,
+    /// Propagated to provider requests, tracing spans and API responses.
+    /// Jobs stored before trace IDs existed get a fresh one when loaded.
+    #[serde(default)]
+    pub trace_id: TraceId,
     pub query: String,
     pub intent: Option<QueryIntent>,
     pub status: JobStatus,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub error_message: Option<String>,
+    /// Why the job failed, when it failed in the pipeline.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<JobFailure>,
+    /// Bumped by the store on every update, so concurrent writers can tell
+    /// the job changed since they read it.
+    #[serde(default)]
+    pub version: u64,
+    /// Shape of the structured payload the caller wants with the answer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answer_schema: Option<AnswerSchema>,
+    /// Models to synthesize the answer with side by side, over the same
+    /// sources. Empty uses the pipeline's own model.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub models: Vec<String>,
+    /// How many sources to research and how long an answer to write.
+    #[serde(default)]
+    pub depth: AnswerDepth,
+    /// The persona and tone the answer is written in.
+    #[serde(default)]
+    pub style: AnswerStyle,
+    /// Whether to list the key entities of the answer's sources with it.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub extract_entities: bool,
+    /// Whether to answer several times and keep only the claims most of the
+    /// answers make, at the cost of the extra model calls.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub high_stakes: bool,
+    /// Whether to search the document corpus alongside the web.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub include_corpus: bool,
+    /// Whether to search only the document corpus, leaving out the web.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub corpus_only: bool,
+    /// Whether the job stops once its sources are collected, without
+    /// synthesizing an answer.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sources_only: bool,
+    /// Workspace whose corpus the job searches; the default workspace when
+    /// unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
+    /// Bounds on the sources collected, overriding the pipeline's own.
+    #[serde(default, skip_serializing_if = "SourceLimits::is_empty")]
+    pub source_limits: SourceLimits,
+    /// The most synthesis may cost. Not applied when comparing models.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cost: Option<CostBudget>,
+    /// The research profile the job was created with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Filters applied to every search query, such as a profile's trusted
+    /// domains. Sources outside them are dropped.
+    #[serde(default, skip_serializing_if = "SearchFilters::is_empty")]
+    pub filters: SearchFilters,
+    /// The failed job this one retries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retried_from: Option<JobId>,
+    /// Runs of the query so far, counting this one: 1 for a new job, one
+    /// more for each retry.
+    #[serde(default = "first_attempt")]
+    pub attempt: u32,
+    /// Labels the integrator chose, for finding the job again.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// The integrator's own data, stored and returned as given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+}
+
+fn first_attempt() -> u32 {
+    1
 }
 
 impl ResearchJob {
@@ -47,30 +164,240 @@ impl ResearchJob {
         let now = Utc::now();
         Ok(Self {
             id: JobId::new(),
+            trace_id: TraceId::new(),
             query,
             intent: None,
             status: JobStatus::Pending,
             created_at: now,
             updated_at: now,
             error_message: None,
+            failure: None,
+            version: 0,
+            answer_schema: None,
+            models: Vec::new(),
+            depth: AnswerDepth::default(),
+            style: AnswerStyle::default(),
+            extract_entities: false,
+            high_stakes: false,
+            include_corpus: false,
+            corpus_only: false,
+            sources_only: false,
+            workspace: None,
+            source_limits: SourceLimits::default(),
+            max_cost: None,
+            profile: None,
+            filters: SearchFilters::default(),
+            retried_from: None,
+            attempt: first_attempt(),
+            tags: Vec::new(),
+            metadata: None,
         })
     }
 
+    /// A new pending job running this job's query again, with the same
+    /// schema, models, depth, style, entity extraction, sampling, corpus and
+    /// sources-only settings, source limits, budget, profile, tags and
+    /// metadata. It gets its own ID and trace ID and records this job as
+    /// the one it retries.
+    pub fn retry(&self) -> Self {
+        let now = Utc::now();
+        Self {
+            id: JobId::new(),
+            trace_id: TraceId::new(),
+            query: self.query.clone(),
+            intent: self.intent.clone(),
+            status: JobStatus::Pending,
+            created_at: now,
+            updated_at: now,
+            error_message: None,
+            failure: None,
+            version: 0,
+            answer_schema: self.answer_schema.clone(),
+            models: self.models.clone(),
+            depth: self.depth,
+            style: self.style,
+            extract_entities: self.extract_entities,
+            high_stakes: self.high_stakes,
+            include_corpus: self.include_corpus,
+            corpus_only: self.corpus_only,
+            sources_only: self.sources_only,
+            workspace: self.workspace.clone(),
+            source_limits: self.source_limits.clone(),
+            max_cost: self.max_cost,
+            profile: self.profile.clone(),
+            filters: self.filters.clone(),
+            retried_from: Some(self.id.clone()),
+            attempt: self.attempt + 1,
+            tags: self.tags.clone(),
+            metadata: self.metadata.clone(),
+        }
+    }
+
     pub fn with_intent(mut self, intent: QueryIntent) -> Self {
         self.intent = Some(intent);
         self.updated_at = Utc::now();
         self
     }
 
-    pub fn transition_to(&mut self, status: JobStatus) {
-        self.status = status;
-        self.updated_at = Utc::now();
+    pub fn with_answer_schema(mut self, schema: AnswerSchema) -> Self {
+        self.answer_schema = Some(schema);
+        self
     }
 
-    pub fn fail(&mut self, message: impl Into<String>) {
+    pub fn with_depth(mut self, depth: AnswerDepth) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    pub fn with_style(mut self, style: AnswerStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Lists the key entities of the answer's sources with the answer.
+    pub fn with_entity_extraction(mut self) -> Self {
+        self.extract_entities = true;
+        self
+    }
+
+    /// Samples the answer several times and keeps the claims most samples
+    /// make, as the pipeline's consistency policy says.
+    pub fn with_high_stakes(mut self) -> Self {
+        self.high_stakes = true;
+        self
+    }
+
+    /// Searches the document corpus alongside the web.
+    pub fn with_corpus(mut self) -> Self {
+        self.include_corpus = true;
+        self
+    }
+
+    /// Searches only the document corpus, so the answer draws on nothing
+    /// else.
+    pub fn with_corpus_only(mut self) -> Self {
+        self.include_corpus = true;
+        self.corpus_only = true;
+        self
+    }
+
+    /// Collects and stores the job's sources and completes without an
+    /// answer, making no LLM call.
+    pub fn with_sources_only(mut self) -> Self {
+        self.sources_only = true;
+        self
+    }
+
+    /// Searches the corpus of `workspace` rather than the default one.
+    pub fn with_workspace(mut self, workspace: impl Into<String>) -> Self {
+        self.workspace = Some(workspace.into());
+        self
+    }
+
+    pub fn with_source_limits(mut self, limits: SourceLimits) -> Self {
+        self.source_limits = limits;
+        self
+    }
+
+    pub fn with_max_cost(mut self, max_cost: CostBudget) -> Self {
+        self.max_cost = Some(max_cost);
+        self
+    }
+
+    pub fn with_filters(mut self, filters: SearchFilters) -> Self {
+        self.filters = filters;
+        self
+    }
+
+    /// Researches with the profile `name`: its search filters and, if it
+    /// sets them, its depth, style and budget.
+    pub fn with_profile(mut self, name: impl Into<String>, profile: &ResearchProfile) -> Self {
+        self.profile = Some(name.into());
+        self.filters = profile.filters();
+        if let Some(depth) = profile.depth {
+            self.depth = depth;
+        }
+        if let Some(style) = profile.style {
+            self.style = style;
+        }
+        if let Some(max_cost) = profile.max_cost {
+            self.max_cost = Some(max_cost);
+        }
+        self
+    }
+
+    /// Compares the answers of `models`, dropping repeats. The first model
+    /// that answers provides the job's answer.
+    pub fn with_models(mut self, models: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        for model in models {
+            let model = model.into();
+            if !self.models.contains(&model) {
+                self.models.push(model);
+            }
+        }
+        self
+    }
+
+    /// Tags the job with `tags`, dropping repeats.
+    pub fn with_tags(mut self, tags: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        for tag in tags {
+            let tag = tag.into();
+            if !self.tags.contains(&tag) {
+                self.tags.push(tag);
+            }
+        }
+        self
+    }
+
+    pub fn with_metadata(mut self, metadata: serde_json::Value) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    /// Moves the job to `status`, if [`JobStatus::can_transition_to`] allows
+    /// it.
+    pub fn transition_to(&mut self, status: JobStatus) -> Result<(), TransitionError> {
+        self.check_transition(&status)?;
+        self.force_transition_to(status);
+        Ok(())
+    }
+
+    /// Fails the job with `message`, unless it already finished.
+    pub fn fail(&mut self, message: impl Into<String>) -> Result<(), TransitionError> {
+        self.check_transition(&JobStatus::Failed)?;
         self.status = JobStatus::Failed;
         self.error_message = Some(message.into());
         self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// Fails the job with a classified `failure`, unless it already finished.
+    pub fn fail_with(&mut self, failure: JobFailure) -> Result<(), TransitionError> {
+        self.fail(failure.message.clone())?;
+        self.failure = Some(failure);
+        Ok(())
+    }
+
+    /// Sets `status` without checking the transition. For admin tooling that
+    /// repairs jobs left in a wrong state; the pipeline never uses it.
+    pub fn force_transition_to(&mut self, status: JobStatus) {
+        self.status = status;
+        self.updated_at = Utc::now();
+    }
+
+    fn check_transition(&self, to: &JobStatus) -> Result<(), TransitionError> {
+        if self.status.can_transition_to(to) {
+            Ok(())
+        } else {
+            Err(TransitionError {
+                from: self.status.clone(),
+                to: to.clone(),
+            })
+        }
     }
 }
 
@@ -84,6 +411,7 @@ mod tests {
         assert_eq!(job.query, "What is Rust?");
         assert_eq!(job.status, JobStatus::Pending);
         assert!(job.id.as_str().starts_with("job_"));
+        assert!(job.trace_id.as_str().starts_with("trc_"));
     }
 
     #[test]
@@ -107,14 +435,127 @@ mod tests {
     #[test]
     fn transition_updates_status() {
         let mut job = ResearchJob::new("test").unwrap();
-        job.transition_to(JobStatus::Searching);
+        job.transition_to(JobStatus::Planning).unwrap();
+        job.transition_to(JobStatus::Searching).unwrap();
         assert_eq!(job.status, JobStatus::Searching);
     }
 
+    #[test]
+    fn rejects_skipped_stages() {
+        let mut job = ResearchJob::new("test").unwrap();
+
+        let error = job.transition_to(JobStatus::Completed).unwrap_err();
+
+        assert_eq!(error.from, JobStatus::Pending);
+        assert_eq!(error.to, JobStatus::Completed);
+        assert_eq!(job.status, JobStatus::Pending);
+    }
+
+    #[test]
+    fn completes_from_searching_without_synthesis() {
+        let mut job = ResearchJob::new("test").unwrap();
+        job.force_transition_to(JobStatus::Searching);
+
+        job.transition_to(JobStatus::Completed).unwrap();
+        assert_eq!(job.status, JobStatus::Completed);
+    }
+
+    #[test]
+    fn restarts_interrupted_job_from_planning() {
+        let mut job = ResearchJob::new("test").unwrap();
+        job.force_transition_to(JobStatus::Synthesizing);
+
+        job.transition_to(JobStatus::Planning).unwrap();
+        assert!(job.transition_to(JobStatus::Fetching).is_err());
+    }
+
+    #[test]
+    fn terminal_jobs_cannot_change() {
+        let mut job = ResearchJob::new("test").unwrap();
+        job.fail("timed out").unwrap();
+
+        assert!(job.transition_to(JobStatus::Planning).is_err());
+        assert!(job.fail("again").is_err());
+        assert_eq!(job.error_message.as_deref(), Some("timed out"));
+
+        job.force_transition_to(JobStatus::Pending);
+        assert_eq!(job.status, JobStatus::Pending);
+    }
+
+    #[test]
+    fn fail_with_records_failure() {
+        let mut job = ResearchJob::new("test").unwrap();
+        job.force_transition_to(JobStatus::Searching);
+        let failure = JobFailure {
+            stage: JobStatus::Searching,
+            kind: ErrorCode::RateLimited,
+            provider: Some("tavily".to_string()),
+            retryable: true,
+            message: "search failed: rate limited by provider: tavily".to_string(),
+        };
+
+        job.fail_with(failure.clone()).unwrap();
+
+        assert_eq!(job.status, JobStatus::Failed);
+        assert_eq!(job.error_message.as_deref(), Some(failure.message.as_str()));
+        assert_eq!(job.failure, Some(failure));
+    }
+
+    #[test]
+    fn retry_starts_new_job_with_lineage() {
+        let mut job = ResearchJob::new("test")
+            .unwrap()
+            .with_models(["a", "b"])
+            .with_depth(AnswerDepth::Exhaustive)
+            .with_style(AnswerStyle::Executive)
+            .with_entity_extraction()
+            .with_high_stakes()
+            .with_corpus_only()
+            .with_sources_only()
+            .with_workspace("team-a")
+            .with_tags(["crm", "crm", "q3"])
+            .with_metadata(serde_json::json!({"account_id": 42}));
+        job.fail("timed out").unwrap();
+
+        let retry = job.retry();
+
+        assert_ne!(retry.id, job.id);
+        assert_ne!(retry.trace_id, job.trace_id);
+        assert_eq!(retry.status, JobStatus::Pending);
+        assert_eq!(retry.models, job.models);
+        assert_eq!(retry.depth, AnswerDepth::Exhaustive);
+        assert_eq!(retry.style, AnswerStyle::Executive);
+        assert!(retry.extract_entities);
+        assert!(retry.high_stakes);
+        assert!(retry.include_corpus);
+        assert!(retry.corpus_only);
+        assert!(retry.sources_only);
+        assert_eq!(retry.workspace.as_deref(), Some("team-a"));
+        assert_eq!(retry.tags, vec!["crm", "q3"]);
+        assert!(retry.has_tag("q3"));
+        assert_eq!(retry.metadata, job.metadata);
+        assert_eq!(retry.retried_from, Some(job.id.clone()));
+        assert_eq!(retry.attempt, 2);
+        assert_eq!(retry.retry().attempt, 3);
+        assert!(retry.error_message.is_none());
+    }
+
+    #[test]
+    fn jobs_stored_without_attempt_are_first_attempts() {
+        let job = ResearchJob::new("test").unwrap();
+        let mut json = serde_json::to_value(&job).unwrap();
+        json.as_object_mut().unwrap().remove("attempt");
+
+        let job: ResearchJob = serde_json::from_value(json).unwrap();
+
+        assert_eq!(job.attempt, 1);
+        assert!(job.retried_from.is_none());
+    }
+
     #[test]
     fn fail_sets_error_message() {
         let mut job = ResearchJob::new("test").unwrap();
-        job.fail("Something went wrong");
+        job.fail("Something went wrong").unwrap();
         assert_eq!(job.status, JobStatus::Failed);
         assert_eq!(job.error_message, Some("Something went wrong".to_string()));
     }
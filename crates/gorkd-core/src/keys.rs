@@ -0,0 +1,365 @@
+//! Several API keys for one provider, used in weighted turns.
+//!
+//! Organizations with more than one billing account can give a provider a key
+//! from each, so load spreads across their rate limits and a throttled or
+//! revoked key does not take the provider down. A [`KeyPool`] hands out its
+//! keys by smooth weighted round-robin: a key of weight 3 serves three
+//! requests for every one a key of weight 1 serves, interleaved rather than
+//! in runs. It counts each key's requests, rate limits and rejections; a
+//! rate-limited key rests for the provider's `Retry-After`, or
+//! [`KEY_RATE_LIMIT_COOLDOWN`], and a rejected one for
+//! [`KEY_REJECTED_COOLDOWN`]. [`KeyPool::run`] retries a request refused for
+//! its key with the next key. Keys are configured as a comma-separated list
+//! in the provider's key variable, each optionally followed by `:<weight>`;
+//! see [`parse_weighted_keys`].
+
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long a key rests after a rate limit that gave no `Retry-After`.
+pub const KEY_RATE_LIMIT_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// How long a key the provider rejected rests before it is tried again, in
+/// case the rejection was transient.
+pub const KEY_REJECTED_COOLDOWN: Duration = Duration::from_secs(600);
+
+/// A key and its share of the requests.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WeightedKey<K> {
+    pub key: K,
+    /// Relative share of requests, at least 1.
+    pub weight: u32,
+}
+
+impl<K> WeightedKey<K> {
+    /// `key` with weight 1.
+    pub fn new(key: K) -> Self {
+        Self { key, weight: 1 }
+    }
+
+    pub fn with_weight(mut self, weight: u32) -> Self {
+        self.weight = weight.max(1);
+        self
+    }
+
+    pub fn map<T>(self, f: impl FnOnce(K) -> T) -> WeightedKey<T> {
+        WeightedKey {
+            key: f(self.key),
+            weight: self.weight,
+        }
+    }
+}
+
+/// Keys in `list`, such as `sk-one:3,sk-two`: comma-separated, each
+/// optionally followed by `:` and a weight. Blank entries are skipped, and a
+/// suffix that is not a number is taken as part of the key.
+pub fn parse_weighted_keys(list: &str) -> Vec<WeightedKey<String>> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| match entry.rsplit_once(':') {
+            Some((key, weight)) if !key.is_empty() => match weight.trim().parse() {
+                Ok(weight) => WeightedKey::new(key.trim().to_string()).with_weight(weight),
+                Err(_) => WeightedKey::new(entry.to_string()),
+            },
+            _ => WeightedKey::new(entry.to_string()),
+        })
+        .collect()
+}
+
+/// How a request made with a key went, as far as the key is concerned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyOutcome {
+    Succeeded,
+    /// Failed for a reason other than the key, such as a bad request or a
+    /// provider outage.
+    Failed,
+    /// The key's rate limit was reached.
+    RateLimited {
+        retry_after: Option<Duration>,
+    },
+    /// The provider refused the key, as when it is revoked.
+    Rejected,
+}
+
+impl KeyOutcome {
+    /// The outcome of a response with HTTP `status`.
+    pub fn for_status(status: u16, retry_after: Option<Duration>) -> Self {
+        match status {
+            200..=299 => Self::Succeeded,
+            401 | 403 => Self::Rejected,
+            429 => Self::RateLimited { retry_after },
+            _ => Self::Failed,
+        }
+    }
+
+    /// Whether another key might succeed where this one did not.
+    pub fn tries_another_key(&self) -> bool {
+        matches!(self, Self::RateLimited { .. } | Self::Rejected)
+    }
+}
+
+/// How one key of a pool has been used since startup.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeyUsage {
+    pub weight: u32,
+    pub requests: u64,
+    pub rate_limited: u64,
+    pub rejected: u64,
+    /// Whether the key is in turn rather than resting.
+    pub available: bool,
+}
+
+#[derive(Debug, Default)]
+struct KeyState {
+    /// Smooth weighted round-robin's running weight.
+    current: i64,
+    requests: u64,
+    rate_limited: u64,
+    rejected: u64,
+    resting_until: Option<Instant>,
+}
+
+impl KeyState {
+    fn available(&self, now: Instant) -> bool {
+        self.resting_until.map_or(true, |until| until <= now)
+    }
+}
+
+/// Keys of one provider, never empty.
+#[derive(Debug)]
+pub struct KeyPool<K> {
+    keys: Vec<WeightedKey<K>>,
+    state: Mutex<Vec<KeyState>>,
+}
+
+impl<K: Clone> KeyPool<K> {
+    /// A pool of `keys`, tried in weighted turns; `None` when there are
+    /// none.
+    pub fn new(keys: Vec<WeightedKey<K>>) -> Option<Self> {
+        if keys.is_empty() {
+            return None;
+        }
+        let state = keys.iter().map(|_| KeyState::default()).collect();
+        Some(Self {
+            keys,
+            state: Mutex::new(state),
+        })
+    }
+
+    /// A pool of one key.
+    pub fn single(key: K) -> Self {
+        Self {
+            keys: vec![WeightedKey::new(key)],
+            state: Mutex::new(vec![KeyState::default()]),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// The first key, as configured.
+    pub fn first(&self) -> &K {
+        &self.keys[0].key
+    }
+
+    /// Runs `request` with the key whose turn it is. When the key is rate
+    /// limited or rejected, as `request` reports, runs it again with the
+    /// next key, until every key has been tried.
+    pub async fn run<T, Fut>(&self, mut request: impl FnMut(K) -> Fut) -> T
+    where
+        Fut: Future<Output = (T, KeyOutcome)>,
+    {
+        let mut tried = Vec::with_capacity(self.keys.len());
+        loop {
+            let index = self.pick(Instant::now(), &tried);
+            let (output, outcome) = request(self.keys[index].key.clone()).await;
+            self.report(index, outcome, Instant::now());
+            tried.push(index);
+            if !outcome.tries_another_key() || tried.len() >= self.keys.len() {
+                return output;
+            }
+        }
+    }
+
+    /// Each key's usage, in configured order.
+    pub fn usage(&self) -> Vec<KeyUsage> {
+        let now = Instant::now();
+        let state = self.state.lock().unwrap();
+        self.keys
+            .iter()
+            .zip(state.iter())
+            .map(|(key, state)| KeyUsage {
+                weight: key.weight,
+                requests: state.requests,
+                rate_limited: state.rate_limited,
+                rejected: state.rejected,
+                available: state.available(now),
+            })
+            .collect()
+    }
+
+    /// The index of the next key not in `tried`: by weighted turn among keys
+    /// not resting, or the one done resting soonest when all are. Once every
+    /// key has been tried, the first comes back.
+    fn pick(&self, now: Instant, tried: &[usize]) -> usize {
+        let mut state = self.state.lock().unwrap();
+        let untried: Vec<usize> = (0..self.keys.len())
+            .filter(|index| !tried.contains(index))
+            .collect();
+        let available: Vec<usize> = untried
+            .iter()
+            .copied()
+            .filter(|&index| state[index].available(now))
+            .collect();
+
+        let index = if available.is_empty() {
+            untried
+                .into_iter()
+                .min_by_key(|&index| state[index].resting_until)
+                .unwrap_or(0)
+        } else {
+            let total: i64 = available
+                .iter()
+                .map(|&index| i64::from(self.keys[index].weight))
+                .sum();
+            for &index in &available {
+                state[index].current += i64::from(self.keys[index].weight);
+            }
+            let best = available.iter().copied().fold(available[0], |best, index| {
+                if state[index].current > state[best].current {
+                    index
+                } else {
+                    best
+                }
+            });
+            state[best].current -= total;
+            best
+        };
+        state[index].requests += 1;
+        index
+    }
+
+    fn report(&self, index: usize, outcome: KeyOutcome, now: Instant) {
+        let mut state = self.state.lock().unwrap();
+        let key = &mut state[index];
+        match outcome {
+            KeyOutcome::Succeeded => key.resting_until = None,
+            KeyOutcome::Failed => {}
+            KeyOutcome::RateLimited { retry_after } => {
+                key.rate_limited += 1;
+                key.resting_until = Some(now + retry_after.unwrap_or(KEY_RATE_LIMIT_COOLDOWN));
+            }
+            KeyOutcome::Rejected => {
+                key.rejected += 1;
+                key.resting_until = Some(now + KEY_REJECTED_COOLDOWN);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(weights: &[u32]) -> KeyPool<&'static str> {
+        let names = ["a", "b", "c"];
+        KeyPool::new(
+            weights
+                .iter()
+                .zip(names)
+                .map(|(&weight, name)| WeightedKey::new(name).with_weight(weight))
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    fn turns(pool: &KeyPool<&'static str>, now: Instant, count: usize) -> String {
+        (0..count)
+            .map(|_| pool.keys[pool.pick(now, &[])].key)
+            .collect()
+    }
+
+    #[test]
+    fn parses_keys_and_weights() {
+        let keys = parse_weighted_keys("sk-one:3, sk-two ,,sk:three:2,tvly-x:y");
+
+        assert_eq!(
+            keys,
+            vec![
+                WeightedKey::new("sk-one".to_string()).with_weight(3),
+                WeightedKey::new("sk-two".to_string()),
+                WeightedKey::new("sk:three".to_string()).with_weight(2),
+                WeightedKey::new("tvly-x:y".to_string()),
+            ]
+        );
+        assert_eq!(parse_weighted_keys("sk:0")[0].weight, 1);
+        assert!(KeyPool::<String>::new(parse_weighted_keys(" , ")).is_none());
+    }
+
+    #[test]
+    fn interleaves_keys_by_weight() {
+        let pool = pool(&[3, 1]);
+
+        assert_eq!(turns(&pool, Instant::now(), 8), "aabaaaba");
+        assert_eq!(pool.usage()[0].requests, 6);
+        assert_eq!(pool.usage()[1].requests, 2);
+    }
+
+    #[test]
+    fn rests_rate_limited_and_rejected_keys() {
+        let pool = pool(&[1, 1, 1]);
+        let now = Instant::now();
+
+        pool.report(
+            0,
+            KeyOutcome::RateLimited {
+                retry_after: Some(Duration::from_secs(5)),
+            },
+            now,
+        );
+        pool.report(1, KeyOutcome::Rejected, now);
+        assert_eq!(turns(&pool, now, 3), "ccc");
+
+        let later = now + Duration::from_secs(5);
+        assert_eq!(turns(&pool, later, 2), "ac");
+
+        pool.report(2, KeyOutcome::Rejected, now);
+        pool.report(0, KeyOutcome::Rejected, later);
+        // Every key is resting; the one back soonest is used.
+        assert_eq!(turns(&pool, later, 1), "b");
+
+        let usage = pool.usage();
+        assert_eq!(usage[0].rate_limited, 1);
+        assert_eq!(usage[0].rejected, 1);
+        assert!(!usage[1].available);
+    }
+
+    #[tokio::test]
+    async fn retries_with_the_next_key() {
+        let pool = pool(&[1, 1]);
+
+        let used = pool
+            .run(|key| async move {
+                match key {
+                    "a" => (key, KeyOutcome::for_status(429, None)),
+                    _ => (key, KeyOutcome::for_status(200, None)),
+                }
+            })
+            .await;
+        assert_eq!(used, "b");
+
+        let used = pool
+            .run(|key| async move { (key, KeyOutcome::for_status(401, None)) })
+            .await;
+        assert_eq!(used, "a");
+        assert_eq!(pool.usage()[1].rejected, 1);
+        assert_eq!(pool.usage()[0].requests, 2);
+    }
+}
@@ -0,0 +1,198 @@
+//! Facts accumulated across jobs.
+//!
+//! Each job that researches a domain reads sources a later job may read
+//! again. With knowledge extraction on, the pipeline draws (subject,
+//! relation, object) triples from the sources a job's answer cites and adds
+//! them to the store's knowledge base. A [`Fact`] already known, by its
+//! normalized terms, gains the new job's sources rather than being stored
+//! twice, so repeated research builds one structured, citable record of what
+//! the sources say.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::id::{FactId, JobId, SourceId};
+
+/// A source a fact was drawn from, and the job that read it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FactSource {
+    pub job_id: JobId,
+    pub source_id: SourceId,
+    pub url: String,
+    pub title: String,
+}
+
+/// A (subject, relation, object) triple with the sources that state it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Fact {
+    pub id: FactId,
+    pub subject: String,
+    pub relation: String,
+    pub object: String,
+    /// Sources stating the fact, in the order they were recorded. Holds each
+    /// URL once.
+    pub sources: Vec<FactSource>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Fact {
+    pub fn new(
+        subject: impl Into<String>,
+        relation: impl Into<String>,
+        object: impl Into<String>,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id: FactId::new(),
+            subject: subject.into(),
+            relation: relation.into(),
+            object: object.into(),
+            sources: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn with_source(mut self, source: FactSource) -> Self {
+        self.add_source(source);
+        self
+    }
+
+    /// The fact's terms, lowercased with whitespace collapsed. Facts with
+    /// the same key are the same fact.
+    pub fn key(&self) -> (String, String, String) {
+        (
+            normalize(&self.subject),
+            normalize(&self.relation),
+            normalize(&self.object),
+        )
+    }
+
+    /// Adds `source` unless a source with its URL is already recorded, and
+    /// returns whether it was new.
+    pub fn add_source(&mut self, source: FactSource) -> bool {
+        if self.sources.iter().any(|s| s.url == source.url) {
+            return false;
+        }
+        self.sources.push(source);
+        self.updated_at = Utc::now();
+        true
+    }
+
+    /// Adds the sources of `other`, the same fact found again.
+    pub fn merge(&mut self, other: Fact) {
+        for source in other.sources {
+            self.add_source(source);
+        }
+    }
+}
+
+/// Which facts to return from the knowledge base. Terms match any fact whose
+/// own term contains them, ignoring case.
+#[derive(Clone, Debug, Default)]
+pub struct FactQuery {
+    pub subject: Option<String>,
+    pub relation: Option<String>,
+    pub object: Option<String>,
+    /// Keep only facts drawn from this job's sources.
+    pub job_id: Option<JobId>,
+    pub limit: usize,
+}
+
+impl FactQuery {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_subject(mut self, subject: impl Into<String>) -> Self {
+        self.subject = Some(subject.into());
+        self
+    }
+
+    pub fn with_relation(mut self, relation: impl Into<String>) -> Self {
+        self.relation = Some(relation.into());
+        self
+    }
+
+    pub fn with_object(mut self, object: impl Into<String>) -> Self {
+        self.object = Some(object.into());
+        self
+    }
+
+    pub fn with_job(mut self, job_id: JobId) -> Self {
+        self.job_id = Some(job_id);
+        self
+    }
+
+    pub fn matches(&self, fact: &Fact) -> bool {
+        let term = |wanted: &Option<String>, term: &str| {
+            wanted
+                .as_deref()
+                .map_or(true, |wanted| normalize(term).contains(&normalize(wanted)))
+        };
+        term(&self.subject, &fact.subject)
+            && term(&self.relation, &fact.relation)
+            && term(&self.object, &fact.object)
+            && self.job_id.as_ref().map_or(true, |job_id| {
+                fact.sources.iter().any(|s| &s.job_id == job_id)
+            })
+    }
+}
+
+fn normalize(term: &str) -> String {
+    term.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(job_id: &JobId, url: &str) -> FactSource {
+        FactSource {
+            job_id: job_id.clone(),
+            source_id: SourceId::new(),
+            url: url.to_string(),
+            title: url.to_string(),
+        }
+    }
+
+    #[test]
+    fn merges_sources_of_the_same_fact() {
+        let (first, second) = (JobId::new(), JobId::new());
+        let mut fact = Fact::new("Rust", "was created by", "Graydon Hoare")
+            .with_source(source(&first, "https://a.example/"));
+        let again = Fact::new(" rust ", "Was  created by", "graydon hoare")
+            .with_source(source(&second, "https://a.example/"))
+            .with_source(source(&second, "https://b.example/"));
+        assert_eq!(fact.key(), again.key());
+
+        fact.merge(again);
+
+        let urls: Vec<&str> = fact.sources.iter().map(|s| s.url.as_str()).collect();
+        assert_eq!(urls, ["https://a.example/", "https://b.example/"]);
+        assert_eq!(fact.sources[0].job_id, first);
+    }
+
+    #[test]
+    fn matches_terms_and_jobs() {
+        let job_id = JobId::new();
+        let fact = Fact::new("Rust (language)", "first released in", "2015")
+            .with_source(source(&job_id, "https://a.example/"));
+
+        assert!(FactQuery::new(10).matches(&fact));
+        assert!(FactQuery::new(10)
+            .with_subject("RUST")
+            .with_relation("released")
+            .matches(&fact));
+        assert!(FactQuery::new(10).with_job(job_id).matches(&fact));
+        assert!(!FactQuery::new(10).with_object("2016").matches(&fact));
+        assert!(!FactQuery::new(10).with_job(JobId::new()).matches(&fact));
+    }
+}
@@ -0,0 +1,180 @@
+//! Answer length targets per question type.
+//!
+//! A one-line factual lookup does not need the token budget of a detailed
+//! comparison. A [`LengthPolicy`] caps the synthesis `max_tokens` and tells
+//! the model how long the answer should be, so simple questions come back
+//! faster and cheaper while complex ones keep room for depth.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::query::QuestionType;
+
+/// Token cap for questions that only need a direct answer.
+pub const BRIEF_MAX_TOKENS: usize = 1024;
+
+/// Token cap for questions that need a few paragraphs.
+pub const STANDARD_MAX_TOKENS: usize = 2048;
+
+/// Token cap for questions that deserve a thorough answer.
+pub const DETAILED_MAX_TOKENS: usize = 4096;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LengthPolicy {
+    /// Upper bound on completion tokens. Providers never exceed their own
+    /// configured maximum.
+    pub max_tokens: usize,
+    /// Added to the synthesis prompt to steer the answer's length.
+    pub instruction: String,
+}
+
+impl LengthPolicy {
+    pub fn new(max_tokens: usize, instruction: impl Into<String>) -> Self {
+        Self {
+            max_tokens,
+            instruction: instruction.into(),
+        }
+    }
+
+    pub fn with_max_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// The completion token limit for a provider configured with
+    /// `provider_max`.
+    pub fn cap(&self, provider_max: usize) -> usize {
+        self.max_tokens.min(provider_max)
+    }
+
+    /// The built-in policy for a question type.
+    pub fn default_for(question_type: QuestionType) -> Self {
+        match question_type {
+            QuestionType::Factual => Self::new(
+                BRIEF_MAX_TOKENS,
+                "Answer directly. Keep the detail to at most three sentences.",
+            ),
+            QuestionType::CurrentEvent => Self::new(
+                BRIEF_MAX_TOKENS,
+                "Keep the detail to one or two short paragraphs focused on the latest developments.",
+            ),
+            QuestionType::HowTo => Self::new(
+                STANDARD_MAX_TOKENS,
+                "Give the steps in order without background the question does not ask for.",
+            ),
+            QuestionType::Opinion => Self::new(
+                STANDARD_MAX_TOKENS,
+                "Summarize the main viewpoints in the sources in a few paragraphs.",
+            ),
+            QuestionType::Comparison => Self::new(
+                DETAILED_MAX_TOKENS,
+                "Compare the options point by point and explain the trade-offs in depth.",
+            ),
+            QuestionType::Explanation => Self::new(
+                DETAILED_MAX_TOKENS,
+                "Give a thorough explanation, covering the underlying mechanisms.",
+            ),
+        }
+    }
+}
+
+/// The [`LengthPolicy`] to apply for each [`QuestionType`]. Question types
+/// without a policy use the provider's defaults.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LengthPolicies {
+    policies: HashMap<QuestionType, LengthPolicy>,
+}
+
+impl Default for LengthPolicies {
+    fn default() -> Self {
+        Self {
+            policies: QuestionType::ALL
+                .into_iter()
+                .map(|question_type| (question_type, LengthPolicy::default_for(question_type)))
+                .collect(),
+        }
+    }
+}
+
+impl LengthPolicies {
+    /// No length policies: every answer gets the provider's defaults.
+    pub fn disabled() -> Self {
+        Self {
+            policies: HashMap::new(),
+        }
+    }
+
+    /// The same policy for every question type.
+    pub fn uniform(policy: LengthPolicy) -> Self {
+        Self {
+            policies: QuestionType::ALL
+                .into_iter()
+                .map(|question_type| (question_type, policy.clone()))
+                .collect(),
+        }
+    }
+
+    pub fn with_policy(mut self, question_type: QuestionType, policy: LengthPolicy) -> Self {
+        self.policies.insert(question_type, policy);
+        self
+    }
+
+    pub fn get(&self, question_type: QuestionType) -> Option<&LengthPolicy> {
+        self.policies.get(&question_type)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.policies.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_cover_every_question_type() {
+        let policies = LengthPolicies::default();
+        for question_type in QuestionType::ALL {
+            assert!(policies.get(question_type).is_some(), "{}", question_type);
+        }
+    }
+
+    #[test]
+    fn factual_answers_are_shorter_than_explanations() {
+        let policies = LengthPolicies::default();
+        let factual = policies.get(QuestionType::Factual).unwrap();
+        let explanation = policies.get(QuestionType::Explanation).unwrap();
+
+        assert!(factual.max_tokens < explanation.max_tokens);
+    }
+
+    #[test]
+    fn overrides_single_policy() {
+        let policies = LengthPolicies::default().with_policy(
+            QuestionType::Factual,
+            LengthPolicy::default_for(QuestionType::Factual).with_max_tokens(256),
+        );
+
+        assert_eq!(policies.get(QuestionType::Factual).unwrap().max_tokens, 256);
+        assert_eq!(
+            policies.get(QuestionType::Comparison).unwrap().max_tokens,
+            DETAILED_MAX_TOKENS
+        );
+    }
+
+    #[test]
+    fn cap_never_exceeds_provider_maximum() {
+        let policy = LengthPolicy::new(DETAILED_MAX_TOKENS, "");
+        assert_eq!(policy.cap(2048), 2048);
+        assert_eq!(policy.cap(8192), DETAILED_MAX_TOKENS);
+    }
+
+    #[test]
+    fn disabled_has_no_policies() {
+        let policies = LengthPolicies::disabled();
+        assert!(!policies.is_enabled());
+        assert!(policies.get(QuestionType::Factual).is_none());
+    }
+}
@@ -1,32 +1,176 @@
 #![forbid(unsafe_code)]
 
+pub mod align;
 mod answer;
+mod answer_schema;
+pub mod archive;
+mod artifact;
+mod budget;
+mod chat;
+mod comparison;
+mod consistency;
+mod corpus;
+mod cross_job;
+mod depth;
+mod diff;
+mod direct;
+mod entity;
 mod error;
+mod escalation;
+mod event;
+mod evidence;
+pub mod export;
+mod feed;
+mod feedback;
+mod freshness;
+pub mod highlight;
+mod http;
 mod id;
 mod job;
+mod keys;
+mod knowledge;
+mod length;
+mod lifecycle;
+mod links;
 pub mod mock;
+mod moderation;
 pub mod pipeline;
+mod profile;
+mod progress;
+mod project;
+mod projection;
 mod query;
+pub mod redact;
+mod references;
+pub mod retry;
+mod routing;
 mod search;
+mod shadow;
+pub mod sigv4;
+mod snippet;
 mod source;
+mod stats;
+mod style;
+pub mod trace;
 pub mod traits;
+mod worker;
 
-pub use answer::{Citation, Confidence, ResearchAnswer, SynthesisMetadata};
-pub use error::{IdParseError, QueryError, ValidationError, MAX_QUERY_LENGTH};
-pub use id::{JobId, SourceId};
-pub use job::{JobStatus, ResearchJob};
-pub use mock::{MockLlmProvider, MockSearchProvider, MockStore};
+pub use answer::{
+    AnswerSection, Citation, Confidence, ContextUsage, LlmStage, ResearchAnswer, StageTokenUsage,
+    SynthesisMetadata, WhyInsufficient, MAX_INSUFFICIENCY_ENTRIES, MAX_SUGGESTED_FOLLOWUPS,
+};
+pub use answer_schema::{AnswerSchema, MAX_SCHEMA_BYTES, MAX_SCHEMA_DEPTH};
+pub use archive::ArchivedJob;
+pub use artifact::{LlmArtifact, LlmExchange, SearchArtifact, StoreArtifactSink};
+pub use budget::{
+    BudgetReport, CostBudget, CostEstimate, ModelPricing, DEFAULT_COMPLETION_TOKENS,
+    MIN_BUDGET_SOURCES, PROMPT_OVERHEAD_TOKENS, SOURCE_OVERHEAD_TOKENS,
+};
+pub use chat::{ChatRequest, ChatResponse, FinishReason, Message, Role, TokenUsage};
+pub use comparison::{ComparisonAgreement, ModelAnswer, ModelComparison, MAX_COMPARISON_MODELS};
+pub use consistency::{
+    ConsistencyPolicy, ConsistencyReport, DisputedClaim, DEFAULT_CONSISTENCY_SAMPLES,
+    DEFAULT_CONSISTENCY_TEMPERATURE, MAX_CONSISTENCY_SAMPLES, SAME_CLAIM_SIMILARITY,
+};
+pub use corpus::{
+    chunk_text, cosine_similarity, index_document, is_valid_workspace, CorpusChunk, CorpusDocument,
+    CorpusMatch, CorpusSearchProvider, HybridSearchProvider, CORPUS_PROVIDER_ID,
+    DEFAULT_CHUNK_CHARS, DEFAULT_CHUNK_OVERLAP, DEFAULT_WORKSPACE, EMBEDDING_BATCH_SIZE,
+    MAX_CORPUS_DOCUMENT_BYTES, MAX_WORKSPACE_LENGTH, WORKSPACE_HEADER,
+};
+pub use cross_job::{
+    CrossJobSynthesis, PooledSource, PooledSourceKind, SourcePool, DEFAULT_POOLED_SOURCES,
+    MAX_POOLED_SOURCES, MAX_SYNTHESIS_JOBS,
+};
+pub use depth::{
+    AnswerDepth, EXHAUSTIVE_CONTEXT_SOURCES, EXHAUSTIVE_MAX_SOURCES, EXHAUSTIVE_MAX_TOKENS,
+    TLDR_MAX_SOURCES, TLDR_MAX_TOKENS,
+};
+pub use diff::{AnswerDiff, CitationChange, ClaimChange};
+pub use direct::{DirectSynthesis, MAX_DIRECT_SOURCES};
+pub use entity::{EntityKind, KeyEntity, MAX_KEY_ENTITIES};
+pub use error::{
+    validate_query, ErrorCode, IdParseError, QueryError, SchemaError, TransitionError,
+    ValidationError, MAX_QUERY_LENGTH,
+};
+pub use escalation::{EscalationPolicy, EscalationReport};
+pub use event::{JobEvent, JobEventKind};
+pub use evidence::{map_evidence, SentenceEvidence, MIN_CLAIM_OVERLAP};
+pub use feed::{
+    FeedFailure, FeedPollReport, FeedPoller, FeedSubscription, DEFAULT_FEED_POLL_INTERVAL,
+};
+pub use feedback::{
+    AnswerRating, DomainTrust, Feedback, SourceFlag, TrustLabel, MAX_FEEDBACK_COMMENT_LENGTH,
+    MAX_TRUST_ADJUSTMENT,
+};
+pub use freshness::{AnswerFreshness, FreshnessPolicy, Volatility};
+pub use highlight::QuoteLocation;
+pub use http::{HttpOptions, DEFAULT_CONNECT_TIMEOUT, DEFAULT_USER_AGENT};
+pub use id::{DocumentId, FactId, FeedbackId, JobId, ProjectId, SourceId, TraceId, WorkerId};
+pub use job::{JobFailure, JobStatus, ResearchJob};
+pub use keys::{
+    parse_weighted_keys, KeyOutcome, KeyPool, KeyUsage, WeightedKey, KEY_RATE_LIMIT_COOLDOWN,
+    KEY_REJECTED_COOLDOWN,
+};
+pub use knowledge::{Fact, FactQuery, FactSource};
+pub use length::{
+    LengthPolicies, LengthPolicy, BRIEF_MAX_TOKENS, DETAILED_MAX_TOKENS, STANDARD_MAX_TOKENS,
+};
+pub use lifecycle::{LifecycleEvent, LifecycleEventKind, LIFECYCLE_SCHEMA_VERSION};
+pub use links::{
+    check_links, cited_link, DeadLinkPolicy, LinkCheckConfig, LinkReport,
+    DEFAULT_LINK_CHECK_CONCURRENCY,
+};
+pub use mock::{
+    MockContentFetcher, MockCrawler, MockEmbedder, MockEventPublisher, MockFeedReader,
+    MockLinkChecker, MockLlmProvider, MockLlmStep, MockModerator, MockSearchProvider,
+    MockSearchStep, MockStore,
+};
+pub use moderation::{ModerationPolicy, ModerationVerdict};
 pub use pipeline::{
-    Executor, ExecutorConfig, Pipeline, PipelineConfig, PipelineError, PipelineResult, Planner,
-    PlannerConfig, Synthesizer, SynthesizerConfig,
+    cap_per_domain, ContentLimits, EntityExtraction, EntityExtractor, ExecutionReport, Executor,
+    ExecutorConfig, Expander, ExpanderConfig, ExpansionReport, FactExtraction, FactExtractor,
+    FactExtractorConfig, FailurePolicy, Outline, OutlineSection, Outliner, OutlinerConfig,
+    Pipeline, PipelineConfig, PipelineError, PipelineResult, Planner, PlannerConfig, Reprocessed,
+    Synthesizer, SynthesizerConfig, TruncationStrategy, MIN_OUTLINE_SECTIONS, MIN_SECTION_TOKENS,
+};
+pub use profile::{ResearchProfile, ResearchProfiles};
+pub use progress::{
+    JobProgress, QueryProgress, SEARCHED_PERCENT, SEARCHING_PERCENT, SYNTHESIZED_PERCENT,
+    SYNTHESIZING_PERCENT,
 };
+pub use project::{
+    Project, ProjectFinding, ProjectReport, MAX_PROJECT_JOBS, MAX_PROJECT_NAME_LENGTH,
+    PROJECT_REPORT_MAX_TOKENS,
+};
+pub use projection::{JobSummary, JobSummaryFilter, StageTiming, StatusCount};
 pub use query::{QueryIntent, QuestionType, TimeConstraint};
+pub use references::{number_references, CitationMarkers, Reference};
+pub use retry::{RetryPolicy, DEFAULT_RETRY_BACKOFF};
+pub use routing::{
+    ModelTier, QueryComplexity, RoutingDecision, RoutingPolicy, DEFAULT_MAX_SIMPLE_ENTITIES,
+    DEFAULT_MAX_SIMPLE_HOPS, DEFAULT_MAX_SIMPLE_WORDS,
+};
 pub use search::{
-    ContentType, ProviderId, Recency, SearchFilters, SearchPlan, SearchQuery, DEFAULT_MAX_SOURCES,
-    DEFAULT_TIMEOUT_SECS,
+    ContentType, DomainPolicy, ProviderId, Recency, SearchFilters, SearchPlan, SearchQuery,
+    SourceLimits, DEFAULT_MAX_SOURCES, DEFAULT_TIMEOUT_SECS,
+};
+pub use shadow::{ShadowEvaluation, ShadowKind, ShadowMetrics, ShadowSample, ShadowStats};
+pub use snippet::{add_snippets, query_terms, Snippet, TermMatch, SNIPPET_CHARS};
+pub use source::{
+    ArchivedSnapshot, DocumentFormat, FilterCompliance, SearchMetadata, Source, SourceCollection,
+    SourceMetadata,
+};
+pub use stats::{
+    DailyUsage, DomainCitations, ModelUsage, StageLatency, UsageStats, STATS_TOP_ENTRIES,
 };
-pub use source::{SearchMetadata, Source, SourceCollection, SourceMetadata};
+pub use style::AnswerStyle;
+pub use trace::{current_trace_id, with_trace_id, TRACE_ID_HEADER};
 pub use traits::{
-    ErrorContext, LlmError, LlmProvider, SearchError, SearchProvider, SearchResult, Store,
-    StoreError,
+    ArtifactSink, ContentFetcher, CrawlRequest, CrawledPage, Embedder, ErrorContext,
+    EventPublisher, FeedItem, FeedReader, FetchedDocument, JobCursor, LinkChecker, LinkStatus,
+    LlmError, LlmProvider, Moderator, PipelinePlugin, PluginError, PluginHook, ProviderAttempt,
+    PublishError, SearchError, SearchProvider, SearchReport, SearchResult, SiteCrawler, Store,
+    StoreError, StoreHealth,
 };
+pub use worker::{Worker, WorkerConfig, DEFAULT_POLL_INTERVAL};
@@ -0,0 +1,118 @@
+//! Job lifecycle events published to other systems.
+//!
+//! Unlike the per-job [`JobEvent`](crate::JobEvent) log, which records every
+//! step for debugging, lifecycle events only mark the points other systems
+//! react to: a job was created, moved to another stage, completed or failed.
+//! The JSON form is a public contract; fields may be added, but existing ones
+//! keep their names and meaning for a given [`LIFECYCLE_SCHEMA_VERSION`].
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::id::{JobId, TraceId};
+use crate::job::{JobStatus, ResearchJob};
+
+/// Bumped only for changes that break existing consumers.
+pub const LIFECYCLE_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LifecycleEvent {
+    pub schema_version: u32,
+    pub job_id: JobId,
+    pub trace_id: TraceId,
+    pub timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    pub kind: LifecycleEventKind,
+}
+
+impl LifecycleEvent {
+    pub fn new(job: &ResearchJob, kind: LifecycleEventKind) -> Self {
+        Self {
+            schema_version: LIFECYCLE_SCHEMA_VERSION,
+            job_id: job.id.clone(),
+            trace_id: job.trace_id.clone(),
+            timestamp: Utc::now(),
+            kind,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum LifecycleEventKind {
+    Created {
+        query: String,
+    },
+    /// The job entered a non-terminal stage.
+    StageChanged {
+        status: JobStatus,
+    },
+    Completed,
+    Failed {
+        message: String,
+    },
+}
+
+impl LifecycleEventKind {
+    /// Matches the serialized `type`; publishers use it in subjects and keys.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Created { .. } => "created",
+            Self::StageChanged { .. } => "stage_changed",
+            Self::Completed => "completed",
+            Self::Failed { .. } => "failed",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_flat_with_schema_version() {
+        let job = ResearchJob::new("What is Rust?").unwrap();
+        let event = LifecycleEvent::new(
+            &job,
+            LifecycleEventKind::StageChanged {
+                status: JobStatus::Searching,
+            },
+        );
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["schema_version"], 1);
+        assert_eq!(json["job_id"], job.id.as_str());
+        assert_eq!(json["trace_id"], job.trace_id.as_str());
+        assert_eq!(json["type"], "stage_changed");
+        assert_eq!(json["status"], "searching");
+    }
+
+    #[test]
+    fn name_matches_serialized_type() {
+        let job = ResearchJob::new("What is Rust?").unwrap();
+        for kind in [
+            LifecycleEventKind::Created {
+                query: job.query.clone(),
+            },
+            LifecycleEventKind::Completed,
+            LifecycleEventKind::Failed {
+                message: "no sources".into(),
+            },
+        ] {
+            let json = serde_json::to_value(LifecycleEvent::new(&job, kind.clone())).unwrap();
+            assert_eq!(json["type"], kind.name());
+        }
+    }
+
+    #[test]
+    fn round_trips_event() {
+        let job = ResearchJob::new("What is Rust?").unwrap();
+        let event = LifecycleEvent::new(&job, LifecycleEventKind::Completed);
+
+        let json = serde_json::to_string(&event).unwrap();
+        let parsed: LifecycleEvent = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed, event);
+    }
+}
@@ -0,0 +1,259 @@
+//! Checks of the links an answer cites.
+//!
+//! Sources are collected minutes, sometimes days, before an answer is read,
+//! and search indexes lag further behind. After synthesis, the URL of every
+//! cited source is checked with a [`LinkChecker`] and each citation records
+//! its [`LinkStatus`]; a [`DeadLinkPolicy`] decides what else happens to an
+//! answer citing dead pages.
+
+use std::collections::HashMap;
+
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::answer::ResearchAnswer;
+use crate::source::Source;
+use crate::traits::{LinkChecker, LinkStatus};
+
+/// Links checked at once when the configuration does not say.
+pub const DEFAULT_LINK_CHECK_CONCURRENCY: usize = 8;
+
+/// What to do with the citations of pages that could not be reached.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum DeadLinkPolicy {
+    /// Only record each citation's link status.
+    #[default]
+    Mark,
+    /// Also list citations of dead pages after the others.
+    Rerank,
+    /// Also add a limitation naming the dead pages to the answer.
+    Annotate,
+}
+
+impl DeadLinkPolicy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "mark" => Some(Self::Mark),
+            "rerank" | "re-rank" => Some(Self::Rerank),
+            "annotate" => Some(Self::Annotate),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LinkCheckConfig {
+    pub policy: DeadLinkPolicy,
+    pub concurrency: usize,
+}
+
+impl Default for LinkCheckConfig {
+    fn default() -> Self {
+        Self {
+            policy: DeadLinkPolicy::default(),
+            concurrency: DEFAULT_LINK_CHECK_CONCURRENCY,
+        }
+    }
+}
+
+/// How many cited links were checked and which were dead.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LinkReport {
+    pub checked: usize,
+    pub dead: Vec<String>,
+}
+
+/// The link a reader follows for `source`: its archived snapshot when the
+/// page was read from one, otherwise the page itself. `None` for sources
+/// that are not web pages, such as corpus documents.
+pub fn cited_link(source: &Source) -> Option<&str> {
+    let url = source
+        .metadata
+        .archived
+        .as_ref()
+        .map_or(source.url.as_str(), |snapshot| snapshot.url.as_str());
+    (url.starts_with("https://") || url.starts_with("http://")).then_some(url)
+}
+
+/// Checks the link of every source `answer` cites, each once, records the
+/// status on its citations and applies `config.policy`.
+pub async fn check_links(
+    checker: &dyn LinkChecker,
+    answer: &mut ResearchAnswer,
+    sources: &[Source],
+    config: &LinkCheckConfig,
+) -> LinkReport {
+    let mut links: Vec<&str> = answer
+        .citations
+        .iter()
+        .filter_map(|c| sources.iter().find(|s| s.id == c.source_id))
+        .filter_map(cited_link)
+        .collect();
+    links.sort_unstable();
+    links.dedup();
+
+    let checks: Vec<_> = links
+        .into_iter()
+        .map(|url| async move { (url, checker.check(url).await) })
+        .collect();
+    let statuses: HashMap<&str, LinkStatus> = stream::iter(checks)
+        .buffer_unordered(config.concurrency.max(1))
+        .collect()
+        .await;
+
+    let status_of = |source_id| {
+        let source = sources.iter().find(|s| &s.id == source_id)?;
+        statuses.get(cited_link(source)?).copied()
+    };
+    for citation in &mut answer.citations {
+        citation.link_status = status_of(&citation.source_id);
+    }
+    for section in &mut answer.sections {
+        for citation in &mut section.citations {
+            citation.link_status = status_of(&citation.source_id);
+        }
+    }
+
+    let mut dead: Vec<String> = statuses
+        .iter()
+        .filter(|(_, status)| **status == LinkStatus::Dead)
+        .map(|(url, _)| url.to_string())
+        .collect();
+    dead.sort();
+
+    match config.policy {
+        DeadLinkPolicy::Mark => {}
+        DeadLinkPolicy::Rerank => {
+            answer
+                .citations
+                .sort_by_key(|c| c.link_status == Some(LinkStatus::Dead));
+        }
+        DeadLinkPolicy::Annotate if !dead.is_empty() => {
+            answer.limitations.push(format!(
+                "Some cited pages could not be reached when this answer was delivered: {}",
+                dead.join(", ")
+            ));
+        }
+        DeadLinkPolicy::Annotate => {}
+    }
+
+    LinkReport {
+        checked: statuses.len(),
+        dead,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use chrono::Utc;
+
+    use crate::answer::{Citation, Confidence};
+    use crate::source::{ArchivedSnapshot, SourceMetadata};
+
+    /// Finds pages dead when their path says so.
+    struct PathChecker;
+
+    #[async_trait]
+    impl LinkChecker for PathChecker {
+        async fn check(&self, url: &str) -> LinkStatus {
+            if url.starts_with("https://example.com/gone") {
+                LinkStatus::Dead
+            } else {
+                LinkStatus::Live
+            }
+        }
+
+        fn checker_name(&self) -> &str {
+            "path"
+        }
+    }
+
+    fn answer_citing(sources: &[Source]) -> ResearchAnswer {
+        ResearchAnswer::new("s", "d", Confidence::High, "mock").with_citations(
+            sources
+                .iter()
+                .map(|s| Citation::new(s.title.clone(), s.id.clone()))
+                .collect(),
+        )
+    }
+
+    fn sources() -> Vec<Source> {
+        vec![
+            Source::new("https://example.com/gone", "gone", "text"),
+            Source::new("https://example.com/live", "live", "text"),
+        ]
+    }
+
+    #[tokio::test]
+    async fn marks_citations_with_link_status() {
+        let sources = sources();
+        let mut answer = answer_citing(&sources);
+
+        let report = check_links(
+            &PathChecker,
+            &mut answer,
+            &sources,
+            &LinkCheckConfig::default(),
+        )
+        .await;
+
+        assert_eq!(report.checked, 2);
+        assert_eq!(report.dead, vec!["https://example.com/gone"]);
+        assert_eq!(answer.citations[0].link_status, Some(LinkStatus::Dead));
+        assert_eq!(answer.citations[1].link_status, Some(LinkStatus::Live));
+        assert!(answer.limitations.is_empty());
+    }
+
+    #[tokio::test]
+    async fn reranks_or_annotates_dead_citations() {
+        let sources = sources();
+        let mut reranked = answer_citing(&sources);
+        let mut annotated = answer_citing(&sources);
+
+        let rerank = LinkCheckConfig {
+            policy: DeadLinkPolicy::Rerank,
+            ..Default::default()
+        };
+        check_links(&PathChecker, &mut reranked, &sources, &rerank).await;
+        let annotate = LinkCheckConfig {
+            policy: DeadLinkPolicy::Annotate,
+            ..Default::default()
+        };
+        check_links(&PathChecker, &mut annotated, &sources, &annotate).await;
+
+        assert_eq!(reranked.citations[0].claim, "live");
+        assert_eq!(reranked.citations[1].claim, "gone");
+        assert_eq!(annotated.citations[0].claim, "gone");
+        assert!(annotated.limitations[0].contains("https://example.com/gone"));
+    }
+
+    #[tokio::test]
+    async fn checks_snapshots_of_archived_sources() {
+        let snapshot = ArchivedSnapshot {
+            url: "https://web.archive.org/web/20240719000000/https://example.com/gone".to_string(),
+            captured_at: Utc::now(),
+        };
+        let sources = vec![
+            Source::new("https://example.com/gone", "archived", "text")
+                .with_metadata(SourceMetadata::new("example.com").with_archived(snapshot)),
+            Source::new("corpus://doc_1", "document", "text"),
+        ];
+        let mut answer = answer_citing(&sources);
+
+        let report = check_links(
+            &PathChecker,
+            &mut answer,
+            &sources,
+            &LinkCheckConfig::default(),
+        )
+        .await;
+
+        assert_eq!(report.checked, 1);
+        assert_eq!(answer.citations[0].link_status, Some(LinkStatus::Live));
+        assert_eq!(answer.citations[1].link_status, None);
+    }
+}
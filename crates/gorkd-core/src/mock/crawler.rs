@@ -0,0 +1,55 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use crate::traits::{CrawlRequest, CrawledPage, SearchError, SiteCrawler};
+
+/// Returns the same canned pages for any seed, up to the request's
+/// `max_pages`, and records each request.
+#[derive(Debug, Default)]
+pub struct MockCrawler {
+    pages: Vec<CrawledPage>,
+    fail: bool,
+    requests: Mutex<Vec<CrawlRequest>>,
+}
+
+impl MockCrawler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A crawler whose seed can never be read.
+    pub fn failing() -> Self {
+        Self {
+            fail: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_page(mut self, page: CrawledPage) -> Self {
+        self.pages.push(page);
+        self
+    }
+
+    pub fn requests(&self) -> Vec<CrawlRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl SiteCrawler for MockCrawler {
+    async fn crawl(&self, request: &CrawlRequest) -> Result<Vec<CrawledPage>, SearchError> {
+        self.requests.lock().unwrap().push(request.clone());
+        if self.fail {
+            return Err(SearchError::Provider(format!(
+                "HTTP 404 Not Found: {}",
+                request.seed
+            )));
+        }
+        Ok(self.pages.iter().take(request.max_pages).cloned().collect())
+    }
+
+    fn crawler_name(&self) -> &str {
+        "mock"
+    }
+}
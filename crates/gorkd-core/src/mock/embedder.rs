@@ -0,0 +1,100 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use async_trait::async_trait;
+
+use crate::traits::{Embedder, LlmError};
+
+/// Dimension of [`MockEmbedder`] vectors.
+const DIMENSIONS: usize = 64;
+
+/// Embeds text as a bag of its lowercased words, so texts sharing words are
+/// similar and the same text always embeds the same way.
+pub struct MockEmbedder {
+    fail: bool,
+    call_count: AtomicUsize,
+}
+
+impl MockEmbedder {
+    pub fn new() -> Self {
+        Self {
+            fail: false,
+            call_count: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn failing() -> Self {
+        Self {
+            fail: true,
+            ..Self::new()
+        }
+    }
+
+    pub fn call_count(&self) -> usize {
+        self.call_count.load(Ordering::SeqCst)
+    }
+}
+
+impl Default for MockEmbedder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn embed_text(text: &str) -> Vec<f32> {
+    let mut vector = vec![0.0; DIMENSIONS];
+    for word in text.split(|c: char| !c.is_alphanumeric()) {
+        if word.is_empty() {
+            continue;
+        }
+        let hash = word
+            .to_lowercase()
+            .bytes()
+            .fold(0xcbf29ce484222325u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+            });
+        vector[(hash % DIMENSIONS as u64) as usize] += 1.0;
+    }
+    vector
+}
+
+#[async_trait]
+impl Embedder for MockEmbedder {
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, LlmError> {
+        self.call_count.fetch_add(1, Ordering::SeqCst);
+
+        if self.fail {
+            return Err(LlmError::Network("embeddings unavailable".into()));
+        }
+
+        Ok(texts.iter().map(|text| embed_text(text)).collect())
+    }
+
+    fn model_id(&self) -> &str {
+        "mock"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn mock_embedder_is_deterministic() {
+        let embedder = MockEmbedder::new();
+
+        let texts = vec!["Rust ownership".to_string(), "rust OWNERSHIP".to_string()];
+        let vectors = embedder.embed(&texts).await.unwrap();
+
+        assert_eq!(vectors.len(), 2);
+        assert_eq!(vectors[0], vectors[1]);
+        assert_eq!(vectors[0].len(), DIMENSIONS);
+        assert_eq!(embedder.call_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn mock_embedder_can_fail() {
+        let embedder = MockEmbedder::failing();
+
+        assert!(embedder.embed(&["text".to_string()]).await.is_err());
+    }
+}
@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use crate::traits::{FeedItem, FeedReader, SearchError};
+
+/// Serves canned entries per feed URL and records each read. Feeds it has
+/// no entries for cannot be read.
+#[derive(Debug, Default)]
+pub struct MockFeedReader {
+    feeds: Mutex<HashMap<String, Vec<FeedItem>>>,
+    reads: Mutex<Vec<String>>,
+}
+
+impl MockFeedReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_item(self, feed: &str, item: FeedItem) -> Self {
+        self.push_item(feed, item);
+        self
+    }
+
+    /// Adds an entry to `feed`, as if it had been published since the last
+    /// read.
+    pub fn push_item(&self, feed: &str, item: FeedItem) {
+        self.feeds
+            .lock()
+            .unwrap()
+            .entry(feed.to_string())
+            .or_default()
+            .push(item);
+    }
+
+    pub fn reads(&self) -> Vec<String> {
+        self.reads.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl FeedReader for MockFeedReader {
+    async fn read(&self, url: &str) -> Result<Vec<FeedItem>, SearchError> {
+        self.reads.lock().unwrap().push(url.to_string());
+        self.feeds
+            .lock()
+            .unwrap()
+            .get(url)
+            .cloned()
+            .ok_or_else(|| SearchError::Provider(format!("HTTP 404 Not Found: {}", url)))
+    }
+
+    fn reader_name(&self) -> &str {
+        "mock"
+    }
+}
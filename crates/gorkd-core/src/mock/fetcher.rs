@@ -0,0 +1,53 @@
+use std::collections::{HashMap, HashSet};
+
+use async_trait::async_trait;
+
+use crate::traits::{ContentFetcher, FetchedDocument, SearchError};
+
+/// Serves canned documents by URL; any other URL fails to fetch.
+#[derive(Debug, Default)]
+pub struct MockContentFetcher {
+    documents: HashMap<String, FetchedDocument>,
+    preferred: HashSet<String>,
+}
+
+impl MockContentFetcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_document(mut self, url: impl Into<String>, document: FetchedDocument) -> Self {
+        self.documents.insert(url.into(), document);
+        self
+    }
+
+    /// Adds a document that is fetched even when the provider returned
+    /// content for its URL.
+    pub fn with_preferred_document(
+        mut self,
+        url: impl Into<String>,
+        document: FetchedDocument,
+    ) -> Self {
+        let url = url.into();
+        self.preferred.insert(url.clone());
+        self.with_document(url, document)
+    }
+}
+
+#[async_trait]
+impl ContentFetcher for MockContentFetcher {
+    async fn fetch(&self, url: &str) -> Result<FetchedDocument, SearchError> {
+        self.documents
+            .get(url)
+            .cloned()
+            .ok_or_else(|| SearchError::Provider(format!("HTTP 404 Not Found: {}", url)))
+    }
+
+    fn fetcher_name(&self) -> &str {
+        "mock"
+    }
+
+    fn replaces_provider_content(&self, url: &str) -> bool {
+        self.preferred.contains(url)
+    }
+}
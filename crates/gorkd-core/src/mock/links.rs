@@ -0,0 +1,44 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use async_trait::async_trait;
+
+use crate::traits::{LinkChecker, LinkStatus};
+
+/// Finds every link live except those it was told are dead.
+#[derive(Default)]
+pub struct MockLinkChecker {
+    dead: HashSet<String>,
+    call_count: AtomicUsize,
+}
+
+impl MockLinkChecker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_dead(mut self, url: impl Into<String>) -> Self {
+        self.dead.insert(url.into());
+        self
+    }
+
+    pub fn call_count(&self) -> usize {
+        self.call_count.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl LinkChecker for MockLinkChecker {
+    async fn check(&self, url: &str) -> LinkStatus {
+        self.call_count.fetch_add(1, Ordering::SeqCst);
+        if self.dead.contains(url) {
+            LinkStatus::Dead
+        } else {
+            LinkStatus::Live
+        }
+    }
+
+    fn checker_name(&self) -> &str {
+        "mock"
+    }
+}
@@ -1,17 +1,64 @@
+use std::collections::VecDeque;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
 use std::time::Duration;
 
 use async_trait::async_trait;
-
-use crate::answer::{Citation, Confidence, ResearchAnswer, SynthesisMetadata};
+use futures_timer::Delay;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::answer::{
+    Citation, Confidence, LlmStage, ResearchAnswer, StageTokenUsage, SynthesisMetadata,
+    WhyInsufficient,
+};
+use crate::answer_schema::AnswerSchema;
+use crate::artifact::LlmExchange;
+use crate::budget::ModelPricing;
+use crate::chat::{ChatRequest, ChatResponse, FinishReason, Message, Role, TokenUsage};
+use crate::length::LengthPolicy;
 use crate::source::Source;
+use crate::style::AnswerStyle;
 use crate::traits::{LlmError, LlmProvider};
 
+/// A scripted outcome for a single call to [`MockLlmProvider::synthesize`] or
+/// [`MockLlmProvider::chat`].
+#[derive(Debug, Clone)]
+pub enum MockLlmStep {
+    /// Produce the regular generated answer.
+    Succeed,
+    /// Return the given error.
+    Fail(LlmError),
+    /// Treat this string as the raw model output. Synthesis parses it, so
+    /// partial or garbled JSON surfaces as a provider error; chat returns it
+    /// verbatim.
+    Raw(String),
+}
+
+#[derive(Deserialize)]
+struct RawMockResponse {
+    summary: String,
+    detail: String,
+    #[serde(default)]
+    limitations: Vec<String>,
+    #[serde(default)]
+    structured: Option<Value>,
+    #[serde(default)]
+    suggested_followups: Vec<String>,
+    #[serde(default)]
+    confidence: Option<Confidence>,
+    #[serde(default)]
+    why_insufficient: Option<WhyInsufficient>,
+}
+
 pub struct MockLlmProvider {
     model_id: String,
     call_count: AtomicUsize,
     fail_after: Option<usize>,
     confidence: Confidence,
+    latency: Option<Duration>,
+    pricing: Option<ModelPricing>,
+    script: Mutex<VecDeque<MockLlmStep>>,
 }
 
 impl MockLlmProvider {
@@ -21,6 +68,9 @@ impl MockLlmProvider {
             call_count: AtomicUsize::new(0),
             fail_after: None,
             confidence: Confidence::High,
+            latency: None,
+            pricing: None,
+            script: Mutex::new(VecDeque::new()),
         }
     }
 
@@ -29,6 +79,24 @@ impl MockLlmProvider {
         self
     }
 
+    /// Delays every call by `latency` before it resolves.
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    pub fn with_pricing(mut self, pricing: ModelPricing) -> Self {
+        self.pricing = Some(pricing);
+        self
+    }
+
+    /// Queues outcomes consumed one per call, in order. Once the script is
+    /// exhausted the provider falls back to its regular behavior.
+    pub fn with_script(self, steps: impl IntoIterator<Item = MockLlmStep>) -> Self {
+        self.script.lock().unwrap().extend(steps);
+        self
+    }
+
     pub fn fail_after(mut self, n: usize) -> Self {
         self.fail_after = Some(n);
         self
@@ -38,34 +106,120 @@ impl MockLlmProvider {
         self.call_count.load(Ordering::SeqCst)
     }
 
+    /// Applies latency, the script and `fail_after` for one call. Returns the
+    /// raw output of a [`MockLlmStep::Raw`] step, if that was next.
+    async fn next_step(&self) -> Result<Option<String>, LlmError> {
+        let count = self.call_count.fetch_add(1, Ordering::SeqCst);
+
+        if let Some(latency) = self.latency {
+            Delay::new(latency).await;
+        }
+
+        let step = self.script.lock().unwrap().pop_front();
+        match step {
+            Some(MockLlmStep::Fail(err)) => return Err(err),
+            Some(MockLlmStep::Raw(raw)) => return Ok(Some(raw)),
+            Some(MockLlmStep::Succeed) | None => {}
+        }
+
+        if let Some(fail_after) = self.fail_after {
+            if count >= fail_after {
+                return Err(LlmError::RateLimited {
+                    retry_after_secs: None,
+                });
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn parse_raw(&self, raw: &str) -> Result<ResearchAnswer, LlmError> {
+        let parsed: RawMockResponse = serde_json::from_str(raw.trim()).map_err(|e| {
+            LlmError::Provider(format!("failed to parse synthesis response: {}", e))
+        })?;
+
+        let answer = ResearchAnswer::new(
+            parsed.summary,
+            parsed.detail,
+            parsed.confidence.unwrap_or_else(|| self.confidence.clone()),
+            &self.model_id,
+        )
+        .with_limitations(parsed.limitations)
+        .with_suggested_followups(parsed.suggested_followups);
+        let answer = match parsed.why_insufficient {
+            Some(why) => answer.with_why_insufficient(why),
+            None => answer,
+        };
+        Ok(match parsed.structured {
+            Some(structured) => answer.with_structured(structured),
+            None => answer,
+        })
+    }
+
     fn generate_answer(&self, query: &str, sources: &[Source]) -> ResearchAnswer {
         let summary = format!(
             "Based on {} sources, here is the answer to: {}",
             sources.len(),
             query
         );
+        // Cites the first sources inline, as models do.
+        let markers: String = sources
+            .iter()
+            .take(3)
+            .map(|s| format!("[{}]", s.id))
+            .collect();
         let detail = format!(
             "After analyzing the provided sources, the answer to \"{}\" is as follows:\n\n\
             The sources indicate that this topic has been well-documented. \
-            Multiple reliable sources confirm the key findings.\n\n\
+            Multiple reliable sources confirm the key findings{}.\n\n\
             Sources analyzed: {}",
             query,
+            if markers.is_empty() {
+                String::new()
+            } else {
+                format!(" {}", markers)
+            },
             sources.len()
         );
 
+        // Cites the first sources, quoting their opening words.
         let citations: Vec<Citation> = sources
             .iter()
             .take(3)
-            .map(|s| Citation::new(format!("Information from {}", s.title), s.id.clone()))
+            .map(|s| {
+                let citation = Citation::new(format!("Information from {}", s.title), s.id.clone());
+                let quote: Vec<&str> = s.content.split_whitespace().take(6).collect();
+                if quote.is_empty() {
+                    citation
+                } else {
+                    citation.with_quote(quote.join(" "))
+                }
+            })
             .collect();
 
+        let usage = TokenUsage {
+            prompt_tokens: 400,
+            completion_tokens: 100,
+        };
         let metadata = SynthesisMetadata::new(&self.model_id)
-            .with_tokens_used(500)
+            .with_tokens_used(usage.total())
+            .with_stage_usage(StageTokenUsage::new(
+                LlmStage::Synthesis,
+                &self.model_id,
+                &usage,
+            ))
             .with_duration(Duration::from_millis(250));
 
-        ResearchAnswer::new(summary, detail, self.confidence.clone(), &self.model_id)
+        let answer = ResearchAnswer::new(summary, detail, self.confidence.clone(), &self.model_id)
             .with_citations(citations)
-            .with_metadata(metadata)
+            .with_metadata(metadata);
+        if answer.is_answerable() {
+            return answer;
+        }
+        answer.with_why_insufficient(WhyInsufficient::new(
+            [format!("Sources that address \"{}\" directly", query)],
+            Vec::<String>::new(),
+        ))
     }
 }
 
@@ -76,24 +230,103 @@ impl LlmProvider for MockLlmProvider {
         query: &str,
         sources: &[Source],
     ) -> Result<ResearchAnswer, LlmError> {
-        let count = self.call_count.fetch_add(1, Ordering::SeqCst);
+        self.synthesize_captured(query, sources, None, None, AnswerStyle::Neutral, None)
+            .await
+            .0
+    }
 
-        if let Some(fail_after) = self.fail_after {
-            if count >= fail_after {
-                return Err(LlmError::RateLimited);
-            }
+    /// Reports the query (preceded by the length instruction, answer schema
+    /// name, style and temperature, if any) as the prompt and the scripted raw
+    /// output (or the generated summary) as the response. Generated answers
+    /// include the smallest payload that satisfies the schema.
+    async fn synthesize_captured(
+        &self,
+        query: &str,
+        sources: &[Source],
+        length: Option<&LengthPolicy>,
+        schema: Option<&AnswerSchema>,
+        style: AnswerStyle,
+        temperature: Option<f32>,
+    ) -> (Result<ResearchAnswer, LlmError>, LlmExchange) {
+        let mut messages: Vec<Message> = length
+            .map(|policy| Message::system(&policy.instruction))
+            .into_iter()
+            .collect();
+        if let Some(schema) = schema {
+            messages.push(Message::system(format!("Answer schema: {}", schema.name)));
         }
-
-        if sources.is_empty() {
-            return Ok(ResearchAnswer::new(
+        if style != AnswerStyle::Neutral {
+            messages.push(Message::system(format!("Answer style: {}", style.as_str())));
+        }
+        if let Some(temperature) = temperature {
+            messages.push(Message::system(format!("Temperature: {}", temperature)));
+        }
+        messages.push(Message::user(query));
+        let mut exchange = LlmExchange {
+            messages,
+            raw_response: None,
+        };
+
+        let result = match self.next_step().await {
+            Err(e) => Err(e),
+            Ok(Some(raw)) => {
+                let parsed = self.parse_raw(&raw);
+                exchange.raw_response = Some(raw);
+                parsed
+            }
+            Ok(None) if sources.is_empty() => Ok(ResearchAnswer::new(
                 "Unable to provide an answer without sources.",
                 "No sources were provided for analysis.",
                 Confidence::Insufficient,
                 &self.model_id,
-            ));
+            )),
+            Ok(None) => {
+                let answer = self.generate_answer(query, sources);
+                Ok(match schema {
+                    Some(schema) => answer.with_structured(sample_payload(&schema.schema)),
+                    None => answer,
+                })
+            }
+        };
+
+        if let Ok(ref answer) = result {
+            exchange
+                .raw_response
+                .get_or_insert_with(|| answer.summary.clone());
         }
 
-        Ok(self.generate_answer(query, sources))
+        (result, exchange)
+    }
+
+    /// Replies with a scripted raw output, or otherwise echoes the last user
+    /// message.
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, LlmError> {
+        let content = match self.next_step().await? {
+            Some(raw) => raw,
+            None => request
+                .messages
+                .iter()
+                .rev()
+                .find(|m| m.role == Role::User)
+                .map(|m| format!("Echo: {}", m.content))
+                .unwrap_or_default(),
+        };
+
+        Ok(ChatResponse {
+            usage: TokenUsage {
+                prompt_tokens: request.messages.iter().map(|m| m.content.len() / 4).sum(),
+                completion_tokens: content.len() / 4,
+            },
+            content,
+            model: self.model_id.clone(),
+            finish_reason: FinishReason::Stop,
+        })
+    }
+
+    /// Parses scripted raw output; the generated answers' summaries are not
+    /// parseable.
+    fn parse_synthesis(&self, raw: &str, _sources: &[Source]) -> Result<ResearchAnswer, LlmError> {
+        self.parse_raw(raw)
     }
 
     fn model_id(&self) -> &str {
@@ -104,6 +337,10 @@ impl LlmProvider for MockLlmProvider {
         "mock"
     }
 
+    fn pricing(&self) -> Option<ModelPricing> {
+        self.pricing
+    }
+
     fn max_context_tokens(&self) -> usize {
         128_000
     }
@@ -113,6 +350,64 @@ impl LlmProvider for MockLlmProvider {
     }
 }
 
+/// Builds a small value satisfying `schema`: required properties only, one
+/// item per array unless more are required, and the first allowed or lowest
+/// value.
+fn sample_payload(schema: &Value) -> Value {
+    if let Some(first) = schema
+        .get("enum")
+        .and_then(Value::as_array)
+        .and_then(|v| v.first())
+    {
+        return first.clone();
+    }
+
+    let type_name = match schema.get("type") {
+        Some(Value::String(t)) => t.as_str(),
+        Some(Value::Array(ts)) => ts.first().and_then(Value::as_str).unwrap_or("null"),
+        _ => "null",
+    };
+    match type_name {
+        "object" => {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            let object = schema
+                .get("required")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .map(|name| {
+                    let property = properties
+                        .and_then(|p| p.get(name))
+                        .map_or(Value::Null, sample_payload);
+                    (name.to_string(), property)
+                })
+                .collect();
+            Value::Object(object)
+        }
+        "array" => {
+            let count = schema
+                .get("minItems")
+                .and_then(Value::as_u64)
+                .unwrap_or(1)
+                .max(1);
+            let item = schema.get("items").map_or(Value::Null, sample_payload);
+            Value::Array(vec![item; count as usize])
+        }
+        "string" => {
+            let min = schema.get("minLength").and_then(Value::as_u64).unwrap_or(0) as usize;
+            json!("sample".repeat(min / 6 + 1))
+        }
+        "integer" => json!(schema
+            .get("minimum")
+            .and_then(Value::as_f64)
+            .map_or(0, |m| m.ceil() as i64)),
+        "number" => json!(schema.get("minimum").and_then(Value::as_f64).unwrap_or(0.0)),
+        "boolean" => json!(true),
+        _ => Value::Null,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -177,4 +472,138 @@ mod tests {
         let answer = provider.synthesize("query", &sources).await.unwrap();
         assert_eq!(answer.confidence, Confidence::Low);
     }
+
+    #[tokio::test]
+    async fn mock_llm_follows_error_script() {
+        let provider = MockLlmProvider::new("mock-gpt-4").with_script([
+            MockLlmStep::Fail(LlmError::Timeout { timeout_secs: 60 }),
+            MockLlmStep::Fail(LlmError::RateLimited {
+                retry_after_secs: None,
+            }),
+            MockLlmStep::Succeed,
+        ]);
+        let sources = create_test_sources();
+
+        assert!(matches!(
+            provider.synthesize("query", &sources).await,
+            Err(LlmError::Timeout { .. })
+        ));
+        assert!(matches!(
+            provider.synthesize("query", &sources).await,
+            Err(LlmError::RateLimited { .. })
+        ));
+        assert!(provider.synthesize("query", &sources).await.is_ok());
+        assert_eq!(provider.call_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn mock_llm_parses_raw_response() {
+        let provider = MockLlmProvider::new("mock-gpt-4").with_script([MockLlmStep::Raw(
+            r#"{"summary": "Short", "detail": "Long", "limitations": ["dated"]}"#.into(),
+        )]);
+
+        let answer = provider
+            .synthesize("query", &create_test_sources())
+            .await
+            .unwrap();
+
+        assert_eq!(answer.summary, "Short");
+        assert_eq!(answer.limitations, vec!["dated".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn mock_llm_rejects_truncated_json() {
+        let provider = MockLlmProvider::new("mock-gpt-4").with_script([
+            MockLlmStep::Raw(r#"{"summary": "Short", "det"#.into()),
+            MockLlmStep::Raw("not json at all".into()),
+        ]);
+        let sources = create_test_sources();
+
+        for _ in 0..2 {
+            let err = provider.synthesize("query", &sources).await.unwrap_err();
+            assert!(matches!(err, LlmError::Provider(msg) if msg.contains("failed to parse")));
+        }
+    }
+
+    #[tokio::test]
+    async fn mock_llm_chat_echoes_or_follows_script() {
+        let provider = MockLlmProvider::new("mock-gpt-4").with_script([
+            MockLlmStep::Raw("scripted".into()),
+            MockLlmStep::Succeed,
+            MockLlmStep::Fail(LlmError::RateLimited {
+                retry_after_secs: None,
+            }),
+        ]);
+        let request = ChatRequest::new(vec![Message::system("sys"), Message::user("hello")]);
+
+        let scripted = provider.chat(request.clone()).await.unwrap();
+        assert_eq!(scripted.content, "scripted");
+        assert_eq!(scripted.model, "mock-gpt-4");
+
+        let echoed = provider.chat(request.clone()).await.unwrap();
+        assert_eq!(echoed.content, "Echo: hello");
+
+        assert!(matches!(
+            provider.chat(request).await,
+            Err(LlmError::RateLimited { .. })
+        ));
+        assert_eq!(provider.call_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn mock_llm_captures_raw_output() {
+        let provider = MockLlmProvider::new("mock-gpt-4")
+            .with_script([MockLlmStep::Raw("not json at all".into())]);
+
+        let (result, exchange) = provider
+            .synthesize_captured(
+                "query",
+                &create_test_sources(),
+                None,
+                None,
+                AnswerStyle::Neutral,
+                None,
+            )
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(exchange.messages[0].content, "query");
+        assert_eq!(exchange.raw_response.as_deref(), Some("not json at all"));
+    }
+
+    #[tokio::test]
+    async fn mock_llm_reports_length_instruction() {
+        let provider = MockLlmProvider::new("mock-gpt-4");
+        let policy = LengthPolicy::new(256, "Be brief.");
+
+        let (result, exchange) = provider
+            .synthesize_captured(
+                "query",
+                &create_test_sources(),
+                Some(&policy),
+                None,
+                AnswerStyle::Eli5,
+                None,
+            )
+            .await;
+
+        assert!(result.is_ok());
+        assert_eq!(exchange.messages[0].role, Role::System);
+        assert_eq!(exchange.messages[0].content, "Be brief.");
+        assert_eq!(exchange.messages[1].content, "Answer style: eli5");
+        assert_eq!(exchange.messages[2].content, "query");
+    }
+
+    #[tokio::test]
+    async fn mock_llm_injects_latency() {
+        let provider = MockLlmProvider::new("mock-gpt-4").with_latency(Duration::from_millis(30));
+
+        let start = std::time::Instant::now();
+        provider
+            .synthesize("query", &create_test_sources())
+            .await
+            .unwrap();
+
+        assert!(start.elapsed() >= Duration::from_millis(30));
+    }
 }
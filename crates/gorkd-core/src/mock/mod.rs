@@ -1,7 +1,21 @@
+mod crawler;
+mod embedder;
+mod feed;
+mod fetcher;
+mod links;
 mod llm;
+mod moderator;
+mod publisher;
 mod search;
 mod store;
 
-pub use llm::MockLlmProvider;
-pub use search::MockSearchProvider;
+pub use crawler::MockCrawler;
+pub use embedder::MockEmbedder;
+pub use feed::MockFeedReader;
+pub use fetcher::MockContentFetcher;
+pub use links::MockLinkChecker;
+pub use llm::{MockLlmProvider, MockLlmStep};
+pub use moderator::MockModerator;
+pub use publisher::MockEventPublisher;
+pub use search::{MockSearchProvider, MockSearchStep};
 pub use store::MockStore;
@@ -0,0 +1,101 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use async_trait::async_trait;
+
+use crate::moderation::ModerationVerdict;
+use crate::traits::{LlmError, Moderator};
+
+pub struct MockModerator {
+    flagged_categories: Vec<String>,
+    fail: bool,
+    call_count: AtomicUsize,
+}
+
+impl MockModerator {
+    /// A moderator that passes everything.
+    pub fn new() -> Self {
+        Self {
+            flagged_categories: Vec::new(),
+            fail: false,
+            call_count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Flags every answer with the given categories.
+    pub fn flagging(categories: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            flagged_categories: categories.into_iter().map(Into::into).collect(),
+            ..Self::new()
+        }
+    }
+
+    pub fn failing() -> Self {
+        Self {
+            fail: true,
+            ..Self::new()
+        }
+    }
+
+    pub fn call_count(&self) -> usize {
+        self.call_count.load(Ordering::SeqCst)
+    }
+}
+
+impl Default for MockModerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Moderator for MockModerator {
+    async fn moderate(&self, _text: &str) -> Result<ModerationVerdict, LlmError> {
+        self.call_count.fetch_add(1, Ordering::SeqCst);
+
+        if self.fail {
+            return Err(LlmError::Network("moderation unavailable".into()));
+        }
+
+        if self.flagged_categories.is_empty() {
+            Ok(ModerationVerdict::clean("mock"))
+        } else {
+            Ok(ModerationVerdict::flagged(
+                "mock",
+                self.flagged_categories.clone(),
+            ))
+        }
+    }
+
+    fn moderator_name(&self) -> &str {
+        "mock"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn mock_moderator_passes_by_default() {
+        let moderator = MockModerator::new();
+        let verdict = moderator.moderate("hello").await.unwrap();
+
+        assert!(!verdict.flagged);
+        assert_eq!(moderator.call_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn mock_moderator_flags_configured_categories() {
+        let moderator = MockModerator::flagging(["hate"]);
+        let verdict = moderator.moderate("hello").await.unwrap();
+
+        assert!(verdict.flagged);
+        assert_eq!(verdict.categories, vec!["hate".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn mock_moderator_can_fail() {
+        let moderator = MockModerator::failing();
+        assert!(moderator.moderate("hello").await.is_err());
+    }
+}
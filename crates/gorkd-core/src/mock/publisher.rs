@@ -0,0 +1,35 @@
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+
+use crate::lifecycle::LifecycleEvent;
+use crate::traits::{EventPublisher, PublishError};
+
+/// Keeps published events in memory for assertions.
+#[derive(Debug, Default)]
+pub struct MockEventPublisher {
+    events: RwLock<Vec<LifecycleEvent>>,
+}
+
+impl MockEventPublisher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Events in publish order.
+    pub fn events(&self) -> Vec<LifecycleEvent> {
+        self.events.read().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl EventPublisher for MockEventPublisher {
+    async fn publish(&self, event: &LifecycleEvent) -> Result<(), PublishError> {
+        self.events.write().unwrap().push(event.clone());
+        Ok(())
+    }
+
+    fn publisher_name(&self) -> &str {
+        "mock"
+    }
+}
@@ -1,15 +1,77 @@
+use std::collections::VecDeque;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures_timer::Delay;
+use serde::{Deserialize, Serialize};
 
 use crate::search::SearchQuery;
 use crate::traits::{SearchError, SearchProvider, SearchResult};
 
+/// A scripted outcome for a single call to [`MockSearchProvider::search`].
+#[derive(Debug, Clone)]
+pub enum MockSearchStep {
+    /// Return the provider's configured results.
+    Succeed,
+    /// Return the given error.
+    Fail(SearchError),
+    /// Return exactly these results for this call.
+    Results(Vec<SearchResult>),
+}
+
+/// A result as it appears in the response body a [`MockSearchProvider`]
+/// captures.
+#[derive(Serialize, Deserialize)]
+struct RawMockResult {
+    url: String,
+    title: String,
+    snippet: String,
+    score: f32,
+    #[serde(default)]
+    raw_content: Option<String>,
+    #[serde(default)]
+    images: Vec<String>,
+    #[serde(default)]
+    published_at: Option<DateTime<Utc>>,
+}
+
+impl From<&SearchResult> for RawMockResult {
+    fn from(result: &SearchResult) -> Self {
+        Self {
+            url: result.url.clone(),
+            title: result.title.clone(),
+            snippet: result.snippet.clone(),
+            score: result.score,
+            raw_content: result.raw_content.clone(),
+            images: result.images.clone(),
+            published_at: result.published_at,
+        }
+    }
+}
+
+impl From<RawMockResult> for SearchResult {
+    fn from(raw: RawMockResult) -> Self {
+        let mut result = SearchResult::new(raw.url, raw.title, raw.snippet)
+            .with_score(raw.score)
+            .with_images(raw.images);
+        result.raw_content = raw.raw_content;
+        result.published_at = raw.published_at;
+        result
+    }
+}
+
 pub struct MockSearchProvider {
     provider_id: String,
     results: Vec<SearchResult>,
     call_count: AtomicUsize,
     fail_after: Option<usize>,
+    latency: Option<Duration>,
+    script: Mutex<VecDeque<MockSearchStep>>,
+    similar_results: Option<Vec<SearchResult>>,
+    queries: Mutex<Vec<SearchQuery>>,
 }
 
 impl MockSearchProvider {
@@ -19,6 +81,10 @@ impl MockSearchProvider {
             results: Self::default_results(),
             call_count: AtomicUsize::new(0),
             fail_after: None,
+            latency: None,
+            script: Mutex::new(VecDeque::new()),
+            similar_results: None,
+            queries: Mutex::default(),
         }
     }
 
@@ -27,6 +93,42 @@ impl MockSearchProvider {
         self
     }
 
+    /// Replaces the configured results with `count` generated results of
+    /// descending score.
+    pub fn with_result_count(mut self, count: usize) -> Self {
+        self.results = (0..count)
+            .map(|i| {
+                SearchResult::new(
+                    format!("https://example.com/generated-{}", i + 1),
+                    format!("Generated Result {}", i + 1),
+                    format!("Generated snippet number {}.", i + 1),
+                )
+                .with_score(1.0 - (i as f32 / count.max(1) as f32))
+            })
+            .collect();
+        self
+    }
+
+    /// Delays every call by `latency` before it resolves.
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// Queues outcomes consumed one per call, in order. Once the script is
+    /// exhausted the provider falls back to its regular behavior.
+    pub fn with_script(self, steps: impl IntoIterator<Item = MockSearchStep>) -> Self {
+        self.script.lock().unwrap().extend(steps);
+        self
+    }
+
+    /// Enables [`SearchProvider::find_similar`], which then returns up to
+    /// the requested number of these results for any URL.
+    pub fn with_similar_results(mut self, results: Vec<SearchResult>) -> Self {
+        self.similar_results = Some(results);
+        self
+    }
+
     pub fn fail_after(mut self, n: usize) -> Self {
         self.fail_after = Some(n);
         self
@@ -36,6 +138,39 @@ impl MockSearchProvider {
         self.call_count.load(Ordering::SeqCst)
     }
 
+    /// Every query searched for so far, in order.
+    pub fn queries(&self) -> Vec<SearchQuery> {
+        self.queries.lock().unwrap().clone()
+    }
+
+    /// Applies latency, the script and `fail_after` for one call.
+    async fn next_results(&self, query: &SearchQuery) -> Result<Vec<SearchResult>, SearchError> {
+        let count = self.call_count.fetch_add(1, Ordering::SeqCst);
+        self.queries.lock().unwrap().push(query.clone());
+
+        if let Some(latency) = self.latency {
+            Delay::new(latency).await;
+        }
+
+        let step = self.script.lock().unwrap().pop_front();
+        match step {
+            Some(MockSearchStep::Succeed) => return Ok(self.results.clone()),
+            Some(MockSearchStep::Fail(err)) => return Err(err),
+            Some(MockSearchStep::Results(results)) => return Ok(results),
+            None => {}
+        }
+
+        if let Some(fail_after) = self.fail_after {
+            if count >= fail_after {
+                return Err(SearchError::RateLimited {
+                    provider: self.provider_id.clone(),
+                });
+            }
+        }
+
+        Ok(self.results.clone())
+    }
+
     fn default_results() -> Vec<SearchResult> {
         vec![
             SearchResult::new(
@@ -62,24 +197,62 @@ impl MockSearchProvider {
 
 #[async_trait]
 impl SearchProvider for MockSearchProvider {
-    async fn search(&self, _query: &SearchQuery) -> Result<Vec<SearchResult>, SearchError> {
-        let count = self.call_count.fetch_add(1, Ordering::SeqCst);
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>, SearchError> {
+        self.search_captured(query).await.0
+    }
 
-        if let Some(fail_after) = self.fail_after {
-            if count >= fail_after {
-                return Err(SearchError::RateLimited {
-                    provider: self.provider_id.clone(),
-                });
-            }
+    /// Reports the results, serialized as a JSON array, as the response body
+    /// of every successful call.
+    async fn search_captured(
+        &self,
+        query: &SearchQuery,
+    ) -> (Result<Vec<SearchResult>, SearchError>, Option<String>) {
+        let result = self.next_results(query).await;
+        let raw = result.as_ref().ok().map(|results| {
+            let raw: Vec<RawMockResult> = results.iter().map(Into::into).collect();
+            serde_json::to_string(&raw).expect("mock results serialize")
+        });
+        (result, raw)
+    }
+
+    fn parse_raw(
+        &self,
+        provider: &str,
+        _query: &SearchQuery,
+        raw: &str,
+    ) -> Result<Vec<SearchResult>, SearchError> {
+        if provider != self.provider_id {
+            return Err(SearchError::ProviderUnavailable {
+                provider: provider.to_string(),
+            });
         }
+        let results: Vec<RawMockResult> = serde_json::from_str(raw)
+            .map_err(|e| SearchError::Provider(format!("failed to parse response: {}", e)))?;
+        Ok(results.into_iter().map(Into::into).collect())
+    }
 
-        Ok(self.results.clone())
+    async fn find_similar(
+        &self,
+        _url: &str,
+        max_results: usize,
+    ) -> Result<Vec<SearchResult>, SearchError> {
+        match &self.similar_results {
+            Some(results) => Ok(results.iter().take(max_results).cloned().collect()),
+            None => Err(SearchError::Provider(format!(
+                "{} does not support finding similar pages",
+                self.provider_id
+            ))),
+        }
     }
 
     fn provider_id(&self) -> &str {
         &self.provider_id
     }
 
+    fn supports_find_similar(&self) -> bool {
+        self.similar_results.is_some()
+    }
+
     fn supports_recency_filter(&self) -> bool {
         true
     }
@@ -143,4 +316,65 @@ mod tests {
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].url, "https://custom.com");
     }
+
+    #[tokio::test]
+    async fn mock_search_parses_its_captured_responses() {
+        let provider = MockSearchProvider::new("mock");
+        let query = SearchQuery::new("test");
+
+        let (results, raw) = provider.search_captured(&query).await;
+        let raw = raw.unwrap();
+
+        let reparsed = provider.parse_raw("mock", &query, &raw).unwrap();
+        let urls = |results: &[SearchResult]| -> Vec<String> {
+            results.iter().map(|r| r.url.clone()).collect()
+        };
+        assert_eq!(urls(&reparsed), urls(&results.unwrap()));
+        assert!(provider.parse_raw("tavily", &query, &raw).is_err());
+    }
+
+    #[tokio::test]
+    async fn mock_search_follows_script_then_falls_back() {
+        let provider = MockSearchProvider::new("mock").with_script([
+            MockSearchStep::Fail(SearchError::Timeout { timeout_secs: 5 }),
+            MockSearchStep::Fail(SearchError::RateLimited {
+                provider: "mock".into(),
+            }),
+            MockSearchStep::Results(vec![]),
+        ]);
+        let query = SearchQuery::new("test");
+
+        assert!(matches!(
+            provider.search(&query).await,
+            Err(SearchError::Timeout { .. })
+        ));
+        assert!(matches!(
+            provider.search(&query).await,
+            Err(SearchError::RateLimited { .. })
+        ));
+        assert!(provider.search(&query).await.unwrap().is_empty());
+        assert_eq!(provider.search(&query).await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn mock_search_generates_configured_result_count() {
+        let provider = MockSearchProvider::new("mock").with_result_count(12);
+        let query = SearchQuery::new("test");
+
+        let results = provider.search(&query).await.unwrap();
+
+        assert_eq!(results.len(), 12);
+        assert!(results.windows(2).all(|w| w[0].score > w[1].score));
+    }
+
+    #[tokio::test]
+    async fn mock_search_injects_latency() {
+        let provider = MockSearchProvider::new("mock").with_latency(Duration::from_millis(30));
+        let query = SearchQuery::new("test");
+
+        let start = std::time::Instant::now();
+        provider.search(&query).await.unwrap();
+
+        assert!(start.elapsed() >= Duration::from_millis(30));
+    }
 }
@@ -1,23 +1,69 @@
 use std::collections::HashMap;
 use std::sync::RwLock;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
-use crate::id::JobId;
-use crate::job::ResearchJob;
-use crate::source::Source;
-use crate::traits::{Store, StoreError};
+use crate::answer::ResearchAnswer;
+use crate::artifact::{LlmArtifact, SearchArtifact};
+use crate::comparison::ModelComparison;
+use crate::corpus::{cosine_similarity, CorpusDocument, CorpusMatch};
+use crate::event::JobEvent;
+use crate::feedback::{DomainTrust, Feedback};
+use crate::id::{DocumentId, JobId, ProjectId, WorkerId};
+use crate::job::{JobStatus, ResearchJob};
+use crate::knowledge::{Fact, FactQuery};
+use crate::project::Project;
+use crate::projection::{JobSummary, JobSummaryFilter, StatusCount};
+use crate::search::SearchFilters;
+use crate::source::{SearchMetadata, Source};
+use crate::stats::UsageStats;
+use crate::traits::{JobCursor, Store, StoreError};
 
 pub struct MockStore {
     jobs: RwLock<HashMap<String, ResearchJob>>,
+    summaries: RwLock<HashMap<String, JobSummary>>,
     sources: RwLock<HashMap<String, Vec<Source>>>,
+    search_metadata: RwLock<HashMap<String, SearchMetadata>>,
+    answers: RwLock<HashMap<String, ResearchAnswer>>,
+    comparisons: RwLock<HashMap<String, ModelComparison>>,
+    events: RwLock<HashMap<String, Vec<JobEvent>>>,
+    artifacts: RwLock<HashMap<String, Vec<LlmArtifact>>>,
+    search_artifacts: RwLock<HashMap<String, Vec<SearchArtifact>>>,
+    leases: RwLock<HashMap<String, Lease>>,
+    projects: RwLock<HashMap<String, Project>>,
+    facts: RwLock<Vec<Fact>>,
+    documents: RwLock<Vec<CorpusDocument>>,
+    feedback: RwLock<HashMap<String, Vec<Feedback>>>,
+    domain_trust: RwLock<HashMap<String, DomainTrust>>,
+    content_refs: RwLock<HashMap<String, u64>>,
+}
+
+struct Lease {
+    worker: WorkerId,
+    expires_at: DateTime<Utc>,
 }
 
 impl MockStore {
     pub fn new() -> Self {
         Self {
             jobs: RwLock::new(HashMap::new()),
+            summaries: RwLock::new(HashMap::new()),
             sources: RwLock::new(HashMap::new()),
+            search_metadata: RwLock::new(HashMap::new()),
+            answers: RwLock::new(HashMap::new()),
+            comparisons: RwLock::new(HashMap::new()),
+            events: RwLock::new(HashMap::new()),
+            artifacts: RwLock::new(HashMap::new()),
+            search_artifacts: RwLock::new(HashMap::new()),
+            leases: RwLock::new(HashMap::new()),
+            projects: RwLock::new(HashMap::new()),
+            facts: RwLock::new(Vec::new()),
+            documents: RwLock::new(Vec::new()),
+            feedback: RwLock::new(HashMap::new()),
+            domain_trust: RwLock::new(HashMap::new()),
+            content_refs: RwLock::new(HashMap::new()),
         }
     }
 
@@ -30,6 +76,10 @@ impl MockStore {
     }
 }
 
+fn lease_expiry(now: DateTime<Utc>, lease: Duration) -> DateTime<Utc> {
+    now + chrono::Duration::from_std(lease).unwrap_or(chrono::Duration::MAX)
+}
+
 impl Default for MockStore {
     fn default() -> Self {
         Self::new()
@@ -49,6 +99,10 @@ impl Store for MockStore {
             )));
         }
 
+        self.summaries
+            .write()
+            .unwrap()
+            .insert(id.clone(), JobSummary::of(job));
         jobs.insert(id, job.clone());
         Ok(())
     }
@@ -60,13 +114,29 @@ impl Store for MockStore {
 
     async fn update_job(&self, job: &ResearchJob) -> Result<(), StoreError> {
         let mut jobs = self.jobs.write().unwrap();
-        let id = job.id.as_str().to_string();
+        let Some(stored) = jobs.get_mut(job.id.as_str()) else {
+            return Err(StoreError::JobNotFound {
+                id: job.id.to_string(),
+            });
+        };
 
-        if !jobs.contains_key(&id) {
-            return Err(StoreError::JobNotFound { id });
+        if stored.version != job.version {
+            return Err(StoreError::Conflict(format!(
+                "job {} is at version {}, not {}",
+                job.id, stored.version, job.version
+            )));
         }
 
-        jobs.insert(id, job.clone());
+        *stored = ResearchJob {
+            version: job.version + 1,
+            ..job.clone()
+        };
+        let mut summaries = self.summaries.write().unwrap();
+        let summary = match summaries.get(job.id.as_str()) {
+            Some(summary) => summary.updated(stored),
+            None => JobSummary::of(stored),
+        };
+        summaries.insert(job.id.as_str().to_string(), summary);
         Ok(())
     }
 
@@ -74,11 +144,340 @@ impl Store for MockStore {
         let jobs = self.jobs.read().unwrap();
         let mut all_jobs: Vec<_> = jobs.values().cloned().collect();
 
-        all_jobs.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        all_jobs.sort_by_key(|job| std::cmp::Reverse(job.created_at));
 
         Ok(all_jobs.into_iter().skip(offset).take(limit).collect())
     }
 
+    async fn list_jobs_before(
+        &self,
+        cursor: Option<&JobCursor>,
+        limit: usize,
+    ) -> Result<Vec<ResearchJob>, StoreError> {
+        let jobs = self.jobs.read().unwrap();
+        let mut older: Vec<_> = jobs
+            .values()
+            .filter(|job| cursor.map_or(true, |cursor| cursor.precedes(job)))
+            .cloned()
+            .collect();
+
+        older.sort_by(|a, b| (b.created_at, b.id.as_str()).cmp(&(a.created_at, a.id.as_str())));
+
+        Ok(older.into_iter().take(limit).collect())
+    }
+
+    async fn list_jobs_by_tag(
+        &self,
+        tag: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<ResearchJob>, StoreError> {
+        let jobs = self.jobs.read().unwrap();
+        let mut tagged: Vec<_> = jobs
+            .values()
+            .filter(|job| job.has_tag(tag))
+            .cloned()
+            .collect();
+
+        tagged.sort_by_key(|job| std::cmp::Reverse(job.created_at));
+
+        Ok(tagged.into_iter().skip(offset).take(limit).collect())
+    }
+
+    async fn list_job_summaries(
+        &self,
+        filter: &JobSummaryFilter,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<JobSummary>, StoreError> {
+        let summaries = self.summaries.read().unwrap();
+        let mut matching: Vec<_> = summaries
+            .values()
+            .filter(|summary| filter.admits(summary))
+            .cloned()
+            .collect();
+
+        matching.sort_by_key(|summary| std::cmp::Reverse(summary.created_at));
+
+        Ok(matching.into_iter().skip(offset).take(limit).collect())
+    }
+
+    async fn count_job_summaries(
+        &self,
+        filter: &JobSummaryFilter,
+    ) -> Result<Vec<StatusCount>, StoreError> {
+        let summaries = self.summaries.read().unwrap();
+        let mut counts: Vec<StatusCount> = Vec::new();
+        for summary in summaries.values().filter(|summary| filter.admits(summary)) {
+            match counts.iter_mut().find(|c| c.status == summary.status) {
+                Some(count) => count.count += 1,
+                None => counts.push(StatusCount {
+                    status: summary.status.clone(),
+                    count: 1,
+                }),
+            }
+        }
+        Ok(counts)
+    }
+
+    async fn usage_stats(&self, since: DateTime<Utc>) -> Result<UsageStats, StoreError> {
+        let summaries = self.summaries.read().unwrap();
+        Ok(UsageStats::from_summaries(summaries.values(), since))
+    }
+
+    async fn create_project(&self, project: &Project) -> Result<(), StoreError> {
+        let mut projects = self.projects.write().unwrap();
+        let id = project.id.as_str().to_string();
+
+        if projects.contains_key(&id) {
+            return Err(StoreError::Conflict(format!(
+                "project {} already exists",
+                project.id
+            )));
+        }
+
+        projects.insert(id, project.clone());
+        Ok(())
+    }
+
+    async fn get_project(&self, id: &ProjectId) -> Result<Option<Project>, StoreError> {
+        let projects = self.projects.read().unwrap();
+        Ok(projects.get(id.as_str()).cloned())
+    }
+
+    async fn attach_job(
+        &self,
+        project_id: &ProjectId,
+        job_id: &JobId,
+        max_jobs: usize,
+    ) -> Result<Project, StoreError> {
+        let mut projects = self.projects.write().unwrap();
+        let Some(project) = projects.get_mut(project_id.as_str()) else {
+            return Err(StoreError::ProjectNotFound {
+                id: project_id.to_string(),
+            });
+        };
+
+        if !project.contains(job_id) && project.job_ids.len() >= max_jobs {
+            return Err(StoreError::Conflict(format!(
+                "project {} already holds {} jobs",
+                project_id, max_jobs
+            )));
+        }
+        project.attach(job_id.clone());
+        Ok(project.clone())
+    }
+
+    async fn record_facts(&self, facts: &[Fact]) -> Result<(), StoreError> {
+        let mut known = self.facts.write().unwrap();
+        for fact in facts {
+            let key = fact.key();
+            match known.iter_mut().find(|k| k.key() == key) {
+                Some(existing) => existing.merge(fact.clone()),
+                None => known.push(fact.clone()),
+            }
+        }
+        Ok(())
+    }
+
+    async fn find_facts(&self, query: &FactQuery) -> Result<Vec<Fact>, StoreError> {
+        let known = self.facts.read().unwrap();
+        let mut matching: Vec<Fact> = known.iter().filter(|f| query.matches(f)).cloned().collect();
+        matching.sort_by(|a, b| {
+            b.sources
+                .len()
+                .cmp(&a.sources.len())
+                .then(b.updated_at.cmp(&a.updated_at))
+        });
+        matching.truncate(query.limit);
+        Ok(matching)
+    }
+
+    async fn store_document(&self, document: &CorpusDocument) -> Result<(), StoreError> {
+        let mut documents = self.documents.write().unwrap();
+        if documents.iter().any(|d| d.id == document.id) {
+            return Err(StoreError::Conflict(format!(
+                "document {} already exists",
+                document.id
+            )));
+        }
+        documents.push(document.clone());
+        Ok(())
+    }
+
+    async fn list_documents(
+        &self,
+        workspace: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<CorpusDocument>, StoreError> {
+        let documents = self.documents.read().unwrap();
+        let mut all: Vec<CorpusDocument> = documents
+            .iter()
+            .filter(|d| d.workspace == workspace)
+            .cloned()
+            .collect();
+        all.sort_by_key(|document| std::cmp::Reverse(document.created_at));
+        Ok(all.into_iter().skip(offset).take(limit).collect())
+    }
+
+    async fn get_document(
+        &self,
+        workspace: &str,
+        id: &DocumentId,
+    ) -> Result<Option<CorpusDocument>, StoreError> {
+        let documents = self.documents.read().unwrap();
+        Ok(documents
+            .iter()
+            .find(|d| d.workspace == workspace && &d.id == id)
+            .cloned())
+    }
+
+    async fn find_document_by_url(
+        &self,
+        workspace: &str,
+        url: &str,
+    ) -> Result<Option<CorpusDocument>, StoreError> {
+        let documents = self.documents.read().unwrap();
+        Ok(documents
+            .iter()
+            .find(|d| d.workspace == workspace && d.url.as_deref() == Some(url))
+            .cloned())
+    }
+
+    async fn delete_document(&self, workspace: &str, id: &DocumentId) -> Result<bool, StoreError> {
+        let mut documents = self.documents.write().unwrap();
+        let before = documents.len();
+        documents.retain(|d| d.workspace != workspace || &d.id != id);
+        Ok(documents.len() < before)
+    }
+
+    async fn search_corpus(
+        &self,
+        workspace: &str,
+        model: &str,
+        embedding: &[f32],
+        filters: &SearchFilters,
+        limit: usize,
+    ) -> Result<Vec<CorpusMatch>, StoreError> {
+        let now = Utc::now();
+        let documents = self.documents.read().unwrap();
+        let mut matches: Vec<CorpusMatch> = documents
+            .iter()
+            .filter(|d| d.workspace == workspace && d.embedding_model == model)
+            .filter(|d| filters.admits_published(d.published_at, now))
+            .filter_map(|document| {
+                document
+                    .chunks
+                    .iter()
+                    .filter_map(|chunk| {
+                        cosine_similarity(&chunk.embedding, embedding).map(|score| (chunk, score))
+                    })
+                    .max_by(|a, b| a.1.total_cmp(&b.1))
+                    .map(|(chunk, score)| CorpusMatch {
+                        document_id: document.id.clone(),
+                        title: document.title.clone(),
+                        url: document.citation_url(),
+                        chunk: chunk.index,
+                        text: chunk.text.clone(),
+                        score,
+                        published_at: document.published_at,
+                    })
+            })
+            .collect();
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+        matches.truncate(limit);
+        Ok(matches)
+    }
+
+    async fn store_feedback(&self, feedback: &Feedback) -> Result<(), StoreError> {
+        let mut trust = self.domain_trust.write().unwrap();
+        for change in feedback.domain_trust() {
+            trust
+                .entry(change.domain.clone())
+                .or_insert_with(|| DomainTrust::new(&change.domain))
+                .merge(&change);
+        }
+
+        let mut all = self.feedback.write().unwrap();
+        all.entry(feedback.job_id.as_str().to_string())
+            .or_default()
+            .push(feedback.clone());
+        Ok(())
+    }
+
+    async fn get_feedback(&self, job_id: &JobId) -> Result<Vec<Feedback>, StoreError> {
+        let all = self.feedback.read().unwrap();
+        Ok(all.get(job_id.as_str()).cloned().unwrap_or_default())
+    }
+
+    async fn domain_trust(&self) -> Result<Vec<DomainTrust>, StoreError> {
+        let trust = self.domain_trust.read().unwrap();
+        Ok(trust.values().cloned().collect())
+    }
+
+    async fn claim_next_job(
+        &self,
+        worker: &WorkerId,
+        lease: Duration,
+    ) -> Result<Option<ResearchJob>, StoreError> {
+        let jobs = self.jobs.read().unwrap();
+        let mut leases = self.leases.write().unwrap();
+        let now = Utc::now();
+
+        let next = jobs
+            .values()
+            .filter(|job| match leases.get(job.id.as_str()) {
+                Some(lease) => lease.expires_at <= now && !job.status.is_terminal(),
+                None => job.status == JobStatus::Pending,
+            })
+            .min_by_key(|job| job.created_at)
+            .cloned();
+        if let Some(ref job) = next {
+            leases.insert(
+                job.id.as_str().to_string(),
+                Lease {
+                    worker: worker.clone(),
+                    expires_at: lease_expiry(now, lease),
+                },
+            );
+        }
+
+        Ok(next)
+    }
+
+    async fn heartbeat(
+        &self,
+        job_id: &JobId,
+        worker: &WorkerId,
+        lease: Duration,
+    ) -> Result<(), StoreError> {
+        let mut leases = self.leases.write().unwrap();
+        let now = Utc::now();
+
+        match leases.get_mut(job_id.as_str()) {
+            Some(held) if &held.worker == worker && held.expires_at > now => {
+                held.expires_at = lease_expiry(now, lease);
+                Ok(())
+            }
+            _ => Err(StoreError::Conflict(format!(
+                "{} no longer holds the lease on job {}",
+                worker, job_id
+            ))),
+        }
+    }
+
+    async fn release(&self, job_id: &JobId, worker: &WorkerId) -> Result<(), StoreError> {
+        let mut leases = self.leases.write().unwrap();
+        if leases
+            .get(job_id.as_str())
+            .is_some_and(|held| &held.worker == worker)
+        {
+            leases.remove(job_id.as_str());
+        }
+        Ok(())
+    }
+
     async fn store_sources(&self, job_id: &JobId, sources: &[Source]) -> Result<(), StoreError> {
         let mut store = self.sources.write().unwrap();
         store.insert(job_id.as_str().to_string(), sources.to_vec());
@@ -90,6 +489,131 @@ impl Store for MockStore {
         Ok(store.get(job_id.as_str()).cloned().unwrap_or_default())
     }
 
+    async fn store_search_metadata(
+        &self,
+        job_id: &JobId,
+        metadata: &SearchMetadata,
+    ) -> Result<(), StoreError> {
+        let mut search_metadata = self.search_metadata.write().unwrap();
+        search_metadata.insert(job_id.as_str().to_string(), metadata.clone());
+        Ok(())
+    }
+
+    async fn get_search_metadata(
+        &self,
+        job_id: &JobId,
+    ) -> Result<Option<SearchMetadata>, StoreError> {
+        let search_metadata = self.search_metadata.read().unwrap();
+        Ok(search_metadata.get(job_id.as_str()).cloned())
+    }
+
+    async fn store_answer(
+        &self,
+        job_id: &JobId,
+        answer: &ResearchAnswer,
+    ) -> Result<(), StoreError> {
+        let mut answers = self.answers.write().unwrap();
+        answers.insert(job_id.as_str().to_string(), answer.clone());
+        if let Some(summary) = self.summaries.write().unwrap().get_mut(job_id.as_str()) {
+            let sources = self.sources.read().unwrap();
+            let sources = sources.get(job_id.as_str()).map_or(&[][..], Vec::as_slice);
+            *summary = summary.clone().with_answer(answer, sources);
+        }
+        Ok(())
+    }
+
+    async fn get_answer(&self, job_id: &JobId) -> Result<Option<ResearchAnswer>, StoreError> {
+        let answers = self.answers.read().unwrap();
+        Ok(answers.get(job_id.as_str()).cloned())
+    }
+
+    async fn store_comparison(
+        &self,
+        job_id: &JobId,
+        comparison: &ModelComparison,
+    ) -> Result<(), StoreError> {
+        let mut comparisons = self.comparisons.write().unwrap();
+        comparisons.insert(job_id.as_str().to_string(), comparison.clone());
+        Ok(())
+    }
+
+    async fn get_comparison(&self, job_id: &JobId) -> Result<Option<ModelComparison>, StoreError> {
+        let comparisons = self.comparisons.read().unwrap();
+        Ok(comparisons.get(job_id.as_str()).cloned())
+    }
+
+    async fn append_event(&self, event: JobEvent) -> Result<JobEvent, StoreError> {
+        let mut events = self.events.write().unwrap();
+        let log = events.entry(event.job_id.as_str().to_string()).or_default();
+        let event = event.with_sequence(log.len() as u64);
+        log.push(event.clone());
+        Ok(event)
+    }
+
+    async fn get_events(&self, job_id: &JobId) -> Result<Vec<JobEvent>, StoreError> {
+        let events = self.events.read().unwrap();
+        Ok(events.get(job_id.as_str()).cloned().unwrap_or_default())
+    }
+
+    async fn store_artifact(
+        &self,
+        job_id: &JobId,
+        artifact: &LlmArtifact,
+    ) -> Result<(), StoreError> {
+        let mut artifacts = self.artifacts.write().unwrap();
+        artifacts
+            .entry(job_id.as_str().to_string())
+            .or_default()
+            .push(artifact.clone());
+        Ok(())
+    }
+
+    async fn get_artifacts(&self, job_id: &JobId) -> Result<Vec<LlmArtifact>, StoreError> {
+        let artifacts = self.artifacts.read().unwrap();
+        Ok(artifacts.get(job_id.as_str()).cloned().unwrap_or_default())
+    }
+
+    async fn store_search_artifact(
+        &self,
+        job_id: &JobId,
+        artifact: &SearchArtifact,
+    ) -> Result<(), StoreError> {
+        let mut artifacts = self.search_artifacts.write().unwrap();
+        artifacts
+            .entry(job_id.as_str().to_string())
+            .or_default()
+            .push(artifact.clone());
+        Ok(())
+    }
+
+    async fn get_search_artifacts(
+        &self,
+        job_id: &JobId,
+    ) -> Result<Vec<SearchArtifact>, StoreError> {
+        let artifacts = self.search_artifacts.read().unwrap();
+        Ok(artifacts.get(job_id.as_str()).cloned().unwrap_or_default())
+    }
+
+    async fn acquire_content(&self, hash: &str) -> Result<u64, StoreError> {
+        let mut refs = self.content_refs.write().unwrap();
+        let count = refs.entry(hash.to_string()).or_default();
+        *count += 1;
+        Ok(*count)
+    }
+
+    async fn release_content(&self, hash: &str) -> Result<u64, StoreError> {
+        let mut refs = self.content_refs.write().unwrap();
+        let Some(count) = refs.get_mut(hash) else {
+            return Ok(0);
+        };
+        *count -= 1;
+        let remaining = *count;
+        if remaining == 0 {
+            refs.remove(hash);
+        }
+        Ok(remaining)
+    }
+
     async fn find_similar(
         &self,
         _embedding: &[f32],
@@ -102,6 +626,9 @@ impl Store for MockStore {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::corpus::DEFAULT_WORKSPACE;
+    use crate::id::SourceId;
+    use crate::knowledge::FactSource;
 
     #[tokio::test]
     async fn mock_store_creates_and_retrieves_job() {
@@ -116,6 +643,14 @@ mod tests {
         assert_eq!(retrieved.unwrap().query, "What is Rust?");
     }
 
+    #[tokio::test]
+    async fn mock_store_reports_reachable() {
+        let health = MockStore::new().health().await;
+
+        assert!(health.reachable);
+        assert!(health.error.is_none());
+    }
+
     #[tokio::test]
     async fn mock_store_rejects_duplicate_job() {
         let store = MockStore::new();
@@ -135,11 +670,33 @@ mod tests {
 
         store.create_job(&job).await.unwrap();
 
-        job.transition_to(crate::job::JobStatus::Searching);
+        job.transition_to(crate::job::JobStatus::Planning).unwrap();
         store.update_job(&job).await.unwrap();
 
         let retrieved = store.get_job(&job_id).await.unwrap().unwrap();
-        assert_eq!(retrieved.status, crate::job::JobStatus::Searching);
+        assert_eq!(retrieved.status, crate::job::JobStatus::Planning);
+    }
+
+    #[tokio::test]
+    async fn mock_store_rejects_stale_update() {
+        let store = MockStore::new();
+        let job = ResearchJob::new("test").unwrap();
+        store.create_job(&job).await.unwrap();
+
+        let mut first = job.clone();
+        first.fail("cancelled").unwrap();
+        store.update_job(&first).await.unwrap();
+
+        let mut stale = job;
+        stale
+            .transition_to(crate::job::JobStatus::Planning)
+            .unwrap();
+        let result = store.update_job(&stale).await;
+
+        assert!(matches!(result, Err(StoreError::Conflict(_))));
+        let stored = store.get_job(&stale.id).await.unwrap().unwrap();
+        assert_eq!(stored.status, crate::job::JobStatus::Failed);
+        assert_eq!(stored.version, 1);
     }
 
     #[tokio::test]
@@ -188,6 +745,341 @@ mod tests {
         assert_eq!(page3.len(), 1);
     }
 
+    #[tokio::test]
+    async fn mock_store_pages_jobs_by_cursor() {
+        let store = MockStore::new();
+
+        for i in 0..3 {
+            let job = ResearchJob::new(format!("query {}", i)).unwrap();
+            store.create_job(&job).await.unwrap();
+        }
+
+        let page1 = store.list_jobs_before(None, 2).await.unwrap();
+        let cursor = JobCursor::of(page1.last().unwrap());
+        store
+            .create_job(&ResearchJob::new("new query").unwrap())
+            .await
+            .unwrap();
+        let page2 = store.list_jobs_before(Some(&cursor), 2).await.unwrap();
+
+        assert_eq!(page2.len(), 1);
+        assert_eq!(page2[0].query, "query 0");
+        assert!(!page1.iter().any(|job| job.id == page2[0].id));
+    }
+
+    #[tokio::test]
+    async fn mock_store_lists_jobs_by_tag() {
+        let store = MockStore::new();
+
+        for i in 0..4 {
+            let mut job = ResearchJob::new(format!("query {}", i)).unwrap();
+            if i % 2 == 0 {
+                job = job.with_tags(["competitor-analysis"]);
+            }
+            store.create_job(&job).await.unwrap();
+        }
+
+        let tagged = store
+            .list_jobs_by_tag("competitor-analysis", 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(tagged.len(), 2);
+        assert!(tagged.iter().all(|job| job.has_tag("competitor-analysis")));
+
+        let page2 = store
+            .list_jobs_by_tag("competitor-analysis", 1, 1)
+            .await
+            .unwrap();
+        assert_eq!(page2.len(), 1);
+        assert!(store
+            .list_jobs_by_tag("other", 10, 0)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn mock_store_accumulates_facts() {
+        let store = MockStore::new();
+        let source = |url: &str| FactSource {
+            job_id: JobId::new(),
+            source_id: SourceId::new(),
+            url: url.to_string(),
+            title: url.to_string(),
+        };
+        store
+            .record_facts(&[
+                Fact::new("Rust", "is written in", "Rust")
+                    .with_source(source("https://a.example/")),
+                Fact::new("Rust", "was started by", "Graydon Hoare")
+                    .with_source(source("https://a.example/")),
+            ])
+            .await
+            .unwrap();
+        store
+            .record_facts(&[Fact::new("rust", "was started by", "Graydon Hoare")
+                .with_source(source("https://b.example/"))])
+            .await
+            .unwrap();
+
+        let facts = store.find_facts(&FactQuery::new(10)).await.unwrap();
+        assert_eq!(facts.len(), 2);
+        assert_eq!(facts[0].object, "Graydon Hoare");
+        assert_eq!(facts[0].sources.len(), 2);
+
+        let facts = store
+            .find_facts(&FactQuery::new(1).with_subject("rust"))
+            .await
+            .unwrap();
+        assert_eq!(facts.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn mock_store_searches_corpus_by_best_chunk() {
+        use crate::corpus::CorpusChunk;
+
+        let store = MockStore::new();
+        let chunk = |index, embedding: Vec<f32>| CorpusChunk {
+            index,
+            text: format!("chunk {}", index),
+            embedding,
+        };
+        let mut close = CorpusDocument::new("Close", "content");
+        close.embedding_model = "embed-a".to_string();
+        close.chunks = vec![chunk(0, vec![0.0, 1.0]), chunk(1, vec![1.0, 0.1])];
+        let mut far = CorpusDocument::new("Far", "content");
+        far.embedding_model = "embed-a".to_string();
+        far.chunks = vec![chunk(0, vec![1.0, 1.0])];
+        let mut other_model = CorpusDocument::new("Other model", "content");
+        other_model.embedding_model = "embed-b".to_string();
+        other_model.chunks = vec![chunk(0, vec![1.0, 0.0])];
+        for document in [&close, &far, &other_model] {
+            store.store_document(document).await.unwrap();
+        }
+
+        let matches = store
+            .search_corpus(
+                DEFAULT_WORKSPACE,
+                "embed-a",
+                &[1.0, 0.0],
+                &SearchFilters::default(),
+                10,
+            )
+            .await
+            .unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].title, "Close");
+        assert_eq!(matches[0].chunk, 1);
+        assert_eq!(matches[1].title, "Far");
+
+        assert!(store
+            .delete_document(DEFAULT_WORKSPACE, &close.id)
+            .await
+            .unwrap());
+        assert!(!store
+            .delete_document(DEFAULT_WORKSPACE, &close.id)
+            .await
+            .unwrap());
+        assert_eq!(
+            store
+                .list_documents(DEFAULT_WORKSPACE, 10, 0)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn mock_store_isolates_corpus_workspaces() {
+        let store = MockStore::new();
+        let mut document = CorpusDocument::new("Roadmap", "content").with_workspace("team-a");
+        document.embedding_model = "embed-a".to_string();
+        document.chunks = vec![crate::corpus::CorpusChunk {
+            index: 0,
+            text: "content".to_string(),
+            embedding: vec![1.0, 0.0],
+        }];
+        store.store_document(&document).await.unwrap();
+
+        let found = store
+            .search_corpus(
+                "team-b",
+                "embed-a",
+                &[1.0, 0.0],
+                &SearchFilters::default(),
+                10,
+            )
+            .await
+            .unwrap();
+        assert!(found.is_empty());
+        assert!(store
+            .get_document("team-b", &document.id)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(!store.delete_document("team-b", &document.id).await.unwrap());
+        assert!(store
+            .list_documents("team-b", 10, 0)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let fetched = store.get_document("team-a", &document.id).await.unwrap();
+        assert_eq!(fetched.unwrap().title, "Roadmap");
+        assert_eq!(
+            store
+                .search_corpus(
+                    "team-a",
+                    "embed-a",
+                    &[1.0, 0.0],
+                    &SearchFilters::default(),
+                    10
+                )
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn mock_store_finds_documents_by_url_within_workspace() {
+        let store = MockStore::new();
+        let document = CorpusDocument::new("Install", "content")
+            .with_url("https://docs.example.com/install")
+            .with_workspace("team-a");
+        store.store_document(&document).await.unwrap();
+
+        let found = store
+            .find_document_by_url("team-a", "https://docs.example.com/install")
+            .await
+            .unwrap();
+        assert_eq!(found.unwrap().id, document.id);
+        assert!(store
+            .find_document_by_url("team-b", "https://docs.example.com/install")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(store
+            .find_document_by_url("team-a", "https://docs.example.com/other")
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn mock_store_sums_domain_trust_over_feedback() {
+        let store = MockStore::new();
+        let mut source = Source::new("https://a.example/", "A", "content");
+        source.metadata.domain = "a.example".to_string();
+        let job_id = JobId::new();
+
+        for _ in 0..2 {
+            let feedback = Feedback::new(job_id.clone()).with_flag(&source, None);
+            store.store_feedback(&feedback).await.unwrap();
+        }
+
+        assert_eq!(store.get_feedback(&job_id).await.unwrap().len(), 2);
+        let trust = store.domain_trust().await.unwrap();
+        assert_eq!(trust.len(), 1);
+        assert_eq!((trust[0].domain.as_str(), trust[0].flags), ("a.example", 2));
+    }
+
+    #[tokio::test]
+    async fn mock_store_attaches_jobs_to_projects() {
+        let store = MockStore::new();
+        let project = Project::new("study");
+        store.create_project(&project).await.unwrap();
+        let (first, second) = (JobId::new(), JobId::new());
+
+        store.attach_job(&project.id, &first, 1).await.unwrap();
+        let again = store.attach_job(&project.id, &first, 1).await.unwrap();
+        assert_eq!(again.job_ids, vec![first]);
+
+        assert!(matches!(
+            store.attach_job(&project.id, &second, 1).await,
+            Err(StoreError::Conflict(_))
+        ));
+        assert!(matches!(
+            store.attach_job(&ProjectId::new(), &second, 1).await,
+            Err(StoreError::ProjectNotFound { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn mock_store_claims_each_pending_job_once() {
+        let store = MockStore::new();
+        let first = ResearchJob::new("first").unwrap();
+        let mut second = ResearchJob::new("second").unwrap();
+        second.created_at = first.created_at + chrono::Duration::seconds(1);
+        let mut done = ResearchJob::new("done").unwrap();
+        done.force_transition_to(crate::job::JobStatus::Completed);
+
+        for job in [&second, &done, &first] {
+            store.create_job(job).await.unwrap();
+        }
+
+        let worker = WorkerId::new();
+        let lease = Duration::from_secs(60);
+        let claim = || store.claim_next_job(&worker, lease);
+
+        assert_eq!(claim().await.unwrap().unwrap().id, first.id);
+        assert_eq!(claim().await.unwrap().unwrap().id, second.id);
+        assert!(claim().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn mock_store_recovers_expired_leases() {
+        let store = MockStore::new();
+        let job = ResearchJob::new("test").unwrap();
+        store.create_job(&job).await.unwrap();
+        let crashed = WorkerId::new();
+        let healthy = WorkerId::new();
+
+        store
+            .claim_next_job(&crashed, Duration::ZERO)
+            .await
+            .unwrap()
+            .unwrap();
+        let reclaimed = store
+            .claim_next_job(&healthy, Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(reclaimed.unwrap().id, job.id);
+
+        let lost = store
+            .heartbeat(&job.id, &crashed, Duration::from_secs(60))
+            .await;
+        assert!(matches!(lost, Err(StoreError::Conflict(_))));
+        store
+            .heartbeat(&job.id, &healthy, Duration::from_secs(60))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn mock_store_does_not_reclaim_finished_jobs() {
+        let store = MockStore::new();
+        let mut job = ResearchJob::new("test").unwrap();
+        store.create_job(&job).await.unwrap();
+        let worker = WorkerId::new();
+
+        store
+            .claim_next_job(&worker, Duration::from_secs(60))
+            .await
+            .unwrap();
+        job.force_transition_to(crate::job::JobStatus::Completed);
+        store.update_job(&job).await.unwrap();
+        store.release(&job.id, &worker).await.unwrap();
+
+        let next = store
+            .claim_next_job(&WorkerId::new(), Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert!(next.is_none());
+    }
+
     #[tokio::test]
     async fn mock_store_tracks_counts() {
         let store = MockStore::new();
@@ -203,4 +1095,94 @@ mod tests {
         store.store_sources(&job_id, &sources).await.unwrap();
         assert_eq!(store.source_count(), 1);
     }
+
+    #[tokio::test]
+    async fn mock_store_stores_and_retrieves_answer() {
+        let store = MockStore::new();
+        let job_id = JobId::new();
+
+        assert!(store.get_answer(&job_id).await.unwrap().is_none());
+
+        let answer =
+            ResearchAnswer::new("Summary", "Detail", crate::answer::Confidence::High, "mock");
+        store.store_answer(&job_id, &answer).await.unwrap();
+
+        let retrieved = store.get_answer(&job_id).await.unwrap().unwrap();
+        assert_eq!(retrieved.summary, "Summary");
+    }
+
+    #[tokio::test]
+    async fn mock_store_keeps_job_summaries_current() {
+        let store = MockStore::new();
+        let mut job = ResearchJob::new("What is Rust?").unwrap();
+        store.create_job(&job).await.unwrap();
+        store
+            .create_job(&ResearchJob::new("What is Go?").unwrap())
+            .await
+            .unwrap();
+
+        let answer = ResearchAnswer::new(
+            "A language.",
+            "Detail",
+            crate::answer::Confidence::High,
+            "mock",
+        );
+        store.store_answer(&job.id, &answer).await.unwrap();
+        job.force_transition_to(JobStatus::Completed);
+        store.update_job(&job).await.unwrap();
+
+        let filter = JobSummaryFilter {
+            text: Some("rust".to_string()),
+            ..Default::default()
+        };
+        let summaries = store.list_job_summaries(&filter, 10, 0).await.unwrap();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].status, JobStatus::Completed);
+        assert_eq!(summaries[0].summary.as_deref(), Some("A language."));
+        assert!(summaries[0].duration_ms.is_some());
+
+        let counts = store
+            .count_job_summaries(&JobSummaryFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(counts.len(), 2);
+        for status in [JobStatus::Pending, JobStatus::Completed] {
+            assert!(counts.contains(&StatusCount { status, count: 1 }));
+        }
+    }
+
+    #[tokio::test]
+    async fn mock_store_assigns_event_sequence() {
+        use crate::event::JobEventKind;
+
+        let store = MockStore::new();
+        let job_id = JobId::new();
+
+        for count in 0..3 {
+            store
+                .append_event(JobEvent::new(
+                    job_id.clone(),
+                    JobEventKind::SourcesCollected { count },
+                ))
+                .await
+                .unwrap();
+        }
+
+        let events = store.get_events(&job_id).await.unwrap();
+        let sequences: Vec<u64> = events.iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences, vec![0, 1, 2]);
+        assert!(store.get_events(&JobId::new()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn mock_store_counts_content_references() {
+        let store = MockStore::new();
+
+        assert_eq!(store.acquire_content("abc").await.unwrap(), 1);
+        assert_eq!(store.acquire_content("abc").await.unwrap(), 2);
+        assert_eq!(store.release_content("abc").await.unwrap(), 1);
+        assert_eq!(store.release_content("abc").await.unwrap(), 0);
+        assert_eq!(store.release_content("abc").await.unwrap(), 0);
+        assert_eq!(store.acquire_content("abc").await.unwrap(), 1);
+    }
 }
@@ -0,0 +1,98 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// What the pipeline does with an answer the moderator flags.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationPolicy {
+    /// Moderation is skipped entirely.
+    #[default]
+    Off,
+    /// Flagged answers are kept, with the verdict recorded in metadata.
+    Flag,
+    /// Flagged answers fail the job and are never stored.
+    Block,
+}
+
+impl ModerationPolicy {
+    pub fn is_enabled(&self) -> bool {
+        !matches!(self, Self::Off)
+    }
+}
+
+impl FromStr for ModerationPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" | "none" | "disabled" => Ok(Self::Off),
+            "flag" => Ok(Self::Flag),
+            "block" => Ok(Self::Block),
+            other => Err(format!("unknown moderation policy: {}", other)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModerationVerdict {
+    pub flagged: bool,
+    pub categories: Vec<String>,
+    pub moderator: String,
+}
+
+impl ModerationVerdict {
+    pub fn clean(moderator: impl Into<String>) -> Self {
+        Self {
+            flagged: false,
+            categories: Vec::new(),
+            moderator: moderator.into(),
+        }
+    }
+
+    pub fn flagged(
+        moderator: impl Into<String>,
+        categories: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Self {
+            flagged: true,
+            categories: categories.into_iter().map(Into::into).collect(),
+            moderator: moderator.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_policy() {
+        assert_eq!("block".parse(), Ok(ModerationPolicy::Block));
+        assert_eq!("FLAG".parse(), Ok(ModerationPolicy::Flag));
+        assert_eq!("off".parse(), Ok(ModerationPolicy::Off));
+        assert!("strict".parse::<ModerationPolicy>().is_err());
+    }
+
+    #[test]
+    fn default_policy_is_off() {
+        assert!(!ModerationPolicy::default().is_enabled());
+    }
+
+    #[test]
+    fn builds_verdicts() {
+        let clean = ModerationVerdict::clean("keyword");
+        assert!(!clean.flagged);
+        assert!(clean.categories.is_empty());
+
+        let flagged = ModerationVerdict::flagged("keyword", ["violence"]);
+        assert!(flagged.flagged);
+        assert_eq!(flagged.categories, vec!["violence".to_string()]);
+    }
+
+    #[test]
+    fn serializes_policy() {
+        let json = serde_json::to_string(&ModerationPolicy::Block).unwrap();
+        assert_eq!(json, "\"block\"");
+    }
+}
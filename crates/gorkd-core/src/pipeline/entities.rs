@@ -0,0 +1,221 @@
+//! Entity extraction for answers.
+//!
+//! After synthesis, the [`EntityExtractor`] gives the model the answer and
+//! the sources it cites, and asks for the key entities among them. It is a
+//! separate pass so jobs that do not want a glossary do not pay for one.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::answer::ResearchAnswer;
+use crate::artifact::LlmExchange;
+use crate::chat::{ChatRequest, Message, TokenUsage};
+use crate::entity::{EntityKind, KeyEntity, MAX_KEY_ENTITIES};
+use crate::source::Source;
+use crate::traits::{LlmError, LlmProvider};
+
+/// Completion tokens the extraction may use.
+const EXTRACTION_MAX_TOKENS: usize = 2048;
+
+/// Characters of each cited source's content shown to the model.
+const MAX_EXCERPT_CHARS: usize = 1500;
+
+const EXTRACTION_INSTRUCTIONS: &str = "\
+You build glossaries for research answers. Given an answer and its numbered \
+sources, list the people, organizations, technologies and other named \
+entities a reader new to the topic would need explained. For each, give its \
+name, its kind (person, organization, technology, place or other), one \
+sentence describing it using only the sources, and the numbers of the \
+sources that describe it. List the most important first. Reply with JSON \
+only, in the form {\"entities\": [{\"name\": \"...\", \"kind\": \"...\", \
+\"description\": \"...\", \"sources\": [1]}]}.";
+
+/// The entities drawn from an answer's sources.
+#[derive(Clone, Debug)]
+pub struct EntityExtraction {
+    pub entities: Vec<KeyEntity>,
+    pub model: String,
+    pub usage: TokenUsage,
+}
+
+#[derive(Deserialize)]
+struct RawExtraction {
+    entities: Vec<RawEntity>,
+}
+
+#[derive(Deserialize)]
+struct RawEntity {
+    name: String,
+    #[serde(default)]
+    kind: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    sources: Vec<usize>,
+}
+
+pub struct EntityExtractor {
+    provider: Arc<dyn LlmProvider>,
+}
+
+impl EntityExtractor {
+    pub fn new(provider: Arc<dyn LlmProvider>) -> Self {
+        Self { provider }
+    }
+
+    /// Asks the model for the key entities of `answer`, drawn from the
+    /// `sources` it cites, and returns its exchange with the model alongside
+    /// them. Entities no cited source describes are dropped, and at most
+    /// [`MAX_KEY_ENTITIES`] are kept.
+    pub async fn extract(
+        &self,
+        answer: &ResearchAnswer,
+        sources: &[Source],
+    ) -> (Result<EntityExtraction, LlmError>, LlmExchange) {
+        let cited: Vec<&Source> = sources
+            .iter()
+            .filter(|s| answer.citations.iter().any(|c| c.source_id == s.id))
+            .collect();
+        let messages = vec![
+            Message::system(EXTRACTION_INSTRUCTIONS),
+            Message::user(extraction_prompt(answer, &cited)),
+        ];
+        let mut exchange = LlmExchange {
+            messages: messages.clone(),
+            raw_response: None,
+        };
+
+        let request = ChatRequest::new(messages).with_max_tokens(EXTRACTION_MAX_TOKENS);
+        let result = match self.provider.chat(request).await {
+            Ok(response) => {
+                let entities = parse(&response.content, &cited);
+                exchange.raw_response = Some(response.content);
+                entities.map(|entities| EntityExtraction {
+                    entities,
+                    model: response.model,
+                    usage: response.usage,
+                })
+            }
+            Err(e) => Err(e),
+        };
+
+        (result, exchange)
+    }
+}
+
+fn extraction_prompt(answer: &ResearchAnswer, cited: &[&Source]) -> String {
+    let mut prompt = format!(
+        "Answer: {}\n\n{}\n\nSources:\n",
+        answer.summary, answer.detail
+    );
+    for (i, source) in cited.iter().enumerate() {
+        let excerpt: String = source.content.chars().take(MAX_EXCERPT_CHARS).collect();
+        prompt.push_str(&format!(
+            "\n[{}] {}\n{}\n",
+            i + 1,
+            source.title,
+            excerpt.trim()
+        ));
+    }
+    prompt
+}
+
+/// Reads the entities out of the model's reply, resolving source numbers
+/// against `cited` and dropping repeated names.
+fn parse(content: &str, cited: &[&Source]) -> Result<Vec<KeyEntity>, LlmError> {
+    let json = match (content.find('{'), content.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &content[start..=end],
+        _ => {
+            return Err(LlmError::Provider(
+                "entity response contains no JSON object".to_string(),
+            ))
+        }
+    };
+    let raw: RawExtraction = serde_json::from_str(json)
+        .map_err(|e| LlmError::Provider(format!("failed to parse entities: {}", e)))?;
+
+    let mut names = HashSet::new();
+    Ok(raw
+        .entities
+        .into_iter()
+        .filter_map(|entity| {
+            let name = entity.name.trim().to_string();
+            let mut source_ids = Vec::new();
+            for source in entity
+                .sources
+                .iter()
+                .filter_map(|n| n.checked_sub(1).and_then(|i| cited.get(i)))
+            {
+                if !source_ids.contains(&source.id) {
+                    source_ids.push(source.id.clone());
+                }
+            }
+            let keep =
+                !name.is_empty() && !source_ids.is_empty() && names.insert(name.to_lowercase());
+            keep.then(|| KeyEntity {
+                name,
+                kind: EntityKind::parse(&entity.kind),
+                description: entity.description.trim().to_string(),
+                source_ids,
+            })
+        })
+        .take(MAX_KEY_ENTITIES)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answer::{Citation, Confidence};
+    use crate::mock::{MockLlmProvider, MockLlmStep};
+
+    fn extractor(reply: &str) -> EntityExtractor {
+        let llm = MockLlmProvider::new("mock-entities")
+            .with_script([MockLlmStep::Raw(reply.to_string())]);
+        EntityExtractor::new(Arc::new(llm))
+    }
+
+    #[tokio::test]
+    async fn extracts_entities_from_cited_sources() {
+        let sources: Vec<Source> = (1..=3)
+            .map(|i| {
+                Source::new(
+                    format!("https://example.com/{}", i),
+                    format!("Source {}", i),
+                    "Graydon Hoare started Rust at Mozilla.",
+                )
+            })
+            .collect();
+        let answer = ResearchAnswer::new("Rust began at Mozilla.", "", Confidence::High, "mock")
+            .with_citations(vec![
+                Citation::new("began", sources[0].id.clone()),
+                Citation::new("began", sources[2].id.clone()),
+            ]);
+        let reply = r#"{"entities": [
+            {"name": "Mozilla", "kind": "company", "description": "A browser maker.", "sources": [2, 2, 7]},
+            {"name": "mozilla", "kind": "organization", "description": "Again.", "sources": [1]},
+            {"name": "Graydon Hoare", "kind": "person", "description": "Rust's creator.", "sources": []}
+        ]}"#;
+
+        let (result, exchange) = extractor(reply).extract(&answer, &sources).await;
+        let extraction = result.unwrap();
+
+        assert_eq!(extraction.entities.len(), 1);
+        let mozilla = &extraction.entities[0];
+        assert_eq!(mozilla.kind, EntityKind::Organization);
+        assert_eq!(mozilla.source_ids, vec![sources[2].id.clone()]);
+        assert!(exchange.messages[1].content.contains("[2] Source 3"));
+        assert!(!exchange.messages[1].content.contains("Source 2"));
+    }
+
+    #[tokio::test]
+    async fn fails_without_json() {
+        let answer = ResearchAnswer::new("A", "B", Confidence::High, "mock");
+        let (result, exchange) = extractor("Sorry.").extract(&answer, &[]).await;
+
+        assert!(result.is_err());
+        assert_eq!(exchange.raw_response.as_deref(), Some("Sorry."));
+    }
+}
@@ -1,16 +1,59 @@
 //! Search execution for research pipeline.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::pin::pin;
 use std::sync::Arc;
+use std::time::Duration;
 
-use crate::search::SearchPlan;
-use crate::source::Source;
-use crate::traits::{SearchError, SearchProvider};
+use chrono::Utc;
+use futures::channel::mpsc::UnboundedSender;
+use futures::future::{self, Either};
+use futures::stream::{self, StreamExt};
+use futures_timer::Delay;
+
+use crate::feedback::DomainTrust;
+use crate::pipeline::limits::ContentLimits;
+use crate::search::{
+    DomainPolicy, ProviderId, SearchPlan, SearchQuery, SourceLimits, DEFAULT_TIMEOUT_SECS,
+};
+use crate::source::{extract_domain, FilterCompliance, SearchMetadata, Source};
+use crate::traits::{ContentFetcher, ProviderAttempt, SearchError, SearchProvider, SearchReport};
+
+/// How the executor reacts when some of a plan's queries fail.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FailurePolicy {
+    /// Abort on the first failed query.
+    FailFast,
+    /// Skip failed queries, provided the rest still produced at least
+    /// `min_sources` sources.
+    BestEffort { min_sources: usize },
+}
+
+impl Default for FailurePolicy {
+    fn default() -> Self {
+        Self::BestEffort { min_sources: 1 }
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct ExecutorConfig {
     pub max_sources: usize,
     pub min_score: f32,
+    /// Sources kept from any one domain; `None` keeps any number.
+    pub max_per_domain: Option<usize>,
+    /// The operator's allowed and denied domains, applied on top of each
+    /// query's own filters.
+    pub domain_policy: DomainPolicy,
+    /// Maximum number of plan queries in flight at once.
+    pub concurrency: usize,
+    /// Each query is abandoned with [`SearchError::Timeout`] after this long.
+    pub query_timeout: Duration,
+    pub failure_policy: FailurePolicy,
+    pub content_limits: ContentLimits,
+    /// Readers' trust in each domain, by lowercase domain. Shifts the
+    /// relevance scores of the domain's sources before they are ranked.
+    pub domain_trust: HashMap<String, DomainTrust>,
 }
 
 impl Default for ExecutorConfig {
@@ -18,64 +61,361 @@ impl Default for ExecutorConfig {
         Self {
             max_sources: 10,
             min_score: 0.0,
+            max_per_domain: None,
+            domain_policy: DomainPolicy::default(),
+            concurrency: 4,
+            query_timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+            failure_policy: FailurePolicy::default(),
+            content_limits: ContentLimits::default(),
+            domain_trust: HashMap::new(),
+        }
+    }
+}
+
+impl ExecutorConfig {
+    /// This configuration with the bounds `limits` sets in place of its own.
+    pub fn with_limits(mut self, limits: &SourceLimits) -> Self {
+        if let Some(max_sources) = limits.max_sources {
+            self.max_sources = max_sources;
+        }
+        if let Some(min_score) = limits.min_score {
+            self.min_score = min_score;
+        }
+        if let Some(max_per_domain) = limits.max_per_domain {
+            self.max_per_domain = Some(max_per_domain);
+        }
+        self
+    }
+
+    /// This configuration ranking sources by `trust` as well as relevance.
+    pub fn with_domain_trust(mut self, trust: Vec<DomainTrust>) -> Self {
+        self.domain_trust = trust.into_iter().map(|t| (t.domain.clone(), t)).collect();
+        self
+    }
+}
+
+/// Keeps at most `max` sources per domain, dropping the later ones, so
+/// sources sorted by score keep each domain's best. Domains compare
+/// case-insensitively.
+pub fn cap_per_domain(sources: &mut Vec<Source>, max: usize) {
+    let mut kept: HashMap<String, usize> = HashMap::new();
+    sources.retain(|source| {
+        let count = kept
+            .entry(source.metadata.domain.to_ascii_lowercase())
+            .or_default();
+        *count += 1;
+        *count <= max
+    });
+}
+
+/// Outcome of executing a plan, with every provider call that was made.
+#[derive(Debug)]
+pub struct ExecutionReport {
+    pub result: Result<Vec<Source>, SearchError>,
+    pub attempts: Vec<ProviderAttempt>,
+    /// Per provider, how many results of filtered queries broke the filters.
+    pub compliance: Vec<FilterCompliance>,
+}
+
+impl ExecutionReport {
+    /// Summarises the attempts: every query sent, the queries that were
+    /// skipped because no provider answered them, every provider that
+    /// answered successfully, and the combined time spent searching.
+    pub fn search_metadata(&self) -> SearchMetadata {
+        let mut metadata = SearchMetadata::new();
+
+        // Fallback providers retry a query, so only its final attempt decides
+        // whether it was skipped.
+        for (i, attempt) in self.attempts.iter().enumerate() {
+            let is_final = self.attempts[i + 1..]
+                .iter()
+                .all(|later| later.query != attempt.query);
+            if is_final && !attempt.succeeded() {
+                metadata.queries_skipped.push(attempt.query.clone());
+            }
         }
+
+        for attempt in &self.attempts {
+            if !metadata.queries_executed.contains(&attempt.query) {
+                metadata.queries_executed.push(attempt.query.clone());
+            }
+            if let Ok(count) = attempt.outcome {
+                let provider = ProviderId::new(attempt.provider.as_str());
+                if !metadata.providers_used.contains(&provider) {
+                    metadata.providers_used.push(provider);
+                }
+                metadata.total_results += count;
+            }
+            metadata.fetch_duration += attempt.duration;
+        }
+        metadata.filter_compliance = self.compliance.clone();
+
+        metadata
     }
 }
 
 pub struct Executor {
     provider: Arc<dyn SearchProvider>,
+    fetcher: Option<Arc<dyn ContentFetcher>>,
+    progress: Option<UnboundedSender<usize>>,
     config: ExecutorConfig,
 }
 
 impl Executor {
     pub fn new(provider: Arc<dyn SearchProvider>, config: ExecutorConfig) -> Self {
-        Self { provider, config }
+        Self {
+            provider,
+            fetcher: None,
+            progress: None,
+            config,
+        }
+    }
+
+    /// Downloads the pages of kept sources whose provider returned no text.
+    pub fn with_content_fetcher(mut self, fetcher: Arc<dyn ContentFetcher>) -> Self {
+        self.fetcher = Some(fetcher);
+        self
+    }
+
+    /// Sends how many of the plan's queries have been searched to
+    /// `progress` after each one. The channel closes with the executor.
+    pub fn with_progress(mut self, progress: UnboundedSender<usize>) -> Self {
+        self.progress = Some(progress);
+        self
     }
 
     pub async fn execute(&self, plan: &SearchPlan) -> Result<Vec<Source>, SearchError> {
+        self.execute_reported(plan).await.result
+    }
+
+    pub async fn execute_reported(&self, plan: &SearchPlan) -> ExecutionReport {
+        let mut attempts = Vec::new();
+        let mut compliance = Vec::new();
+        let result = self
+            .collect_sources(plan, &mut attempts, &mut compliance)
+            .await;
+
+        ExecutionReport {
+            result,
+            attempts,
+            compliance,
+        }
+    }
+
+    /// Runs the plan's queries with bounded concurrency, handling failed
+    /// queries according to the configured [`FailurePolicy`]. When failures
+    /// make the result unacceptable, the last error is returned.
+    ///
+    /// Providers do not always honour the filters they are sent, so every
+    /// result is checked against its query's domain and date filters, and
+    /// the ones breaking them are dropped and counted in `compliance`.
+    async fn collect_sources(
+        &self,
+        plan: &SearchPlan,
+        attempts: &mut Vec<ProviderAttempt>,
+        compliance: &mut Vec<FilterCompliance>,
+    ) -> Result<Vec<Source>, SearchError> {
+        // Futures are built up front rather than in a stream closure, which
+        // keeps the resulting future `Send` for callers that spawn it.
+        let searches: Vec<_> = plan
+            .queries
+            .iter()
+            .map(|query| self.search_query(query))
+            .collect();
+        let mut reports = stream::iter(searches).buffered(self.config.concurrency.max(1));
+
         let mut all_sources = Vec::new();
         let mut seen_urls = HashSet::new();
+        let mut without_content = HashSet::new();
+        let mut last_error = None;
+        let mut any_succeeded = false;
+        let now = Utc::now();
 
-        for query in &plan.queries {
-            let results = self.provider.search(query).await?;
+        for (searched, query) in plan.queries.iter().enumerate() {
+            let Some(report) = reports.next().await else {
+                break;
+            };
+            if let Some(progress) = &self.progress {
+                // A progress receiver that went away does not stop the search.
+                let _ = progress.unbounded_send(searched + 1);
+            }
+            let provider = report
+                .attempts
+                .last()
+                .map(|attempt| ProviderId::new(attempt.provider.as_str()));
+            attempts.extend(report.attempts);
 
-            for result in results {
+            let results = match report.result {
+                Ok(results) => {
+                    any_succeeded = true;
+                    results
+                }
+                Err(e) if self.config.failure_policy == FailurePolicy::FailFast => return Err(e),
+                Err(e) => {
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+
+            let mut checked = match provider {
+                Some(ref provider) if query.filters.restricts_results() => {
+                    let at = match compliance.iter().position(|c| &c.provider == provider) {
+                        Some(at) => at,
+                        None => {
+                            compliance.push(FilterCompliance::new(provider.clone()));
+                            compliance.len() - 1
+                        }
+                    };
+                    Some(&mut compliance[at])
+                }
+                _ => None,
+            };
+
+            for mut result in results {
                 if seen_urls.contains(&result.url) {
                     continue;
                 }
                 seen_urls.insert(result.url.clone());
 
-                if result.score < self.config.min_score {
+                let domain = extract_domain(&result.url).unwrap_or_default();
+                let admitted = query.filters.admits(&domain)
+                    && query.filters.admits_published(result.published_at, now);
+                if let Some(ref mut checked) = checked {
+                    checked.results += 1;
+                    checked.violations += usize::from(!admitted);
+                }
+                if !admitted || result.score < self.config.min_score {
+                    continue;
+                }
+                if !self.config.domain_policy.admits(&domain) {
                     continue;
                 }
 
-                let source = result.into_source(format!(
-                    "Content fetched from source. Query: {}",
-                    query.text
-                ));
+                // Providers that return the page text spare a separate fetch.
+                let content = result.raw_content.take().unwrap_or_else(|| {
+                    without_content.insert(result.url.clone());
+                    format!("Content fetched from source. Query: {}", query.text)
+                });
+                let mut source = result.into_source(content);
+                if let Some(provider) = &provider {
+                    source.metadata = source.metadata.with_provider(provider.clone());
+                }
+                if let Some(trust) = self.config.domain_trust.get(&domain.to_ascii_lowercase()) {
+                    source.relevance_score =
+                        (source.relevance_score + trust.adjustment()).clamp(0.0, 1.0);
+                    source.metadata.trust = Some(trust.label());
+                }
 
                 all_sources.push(source);
             }
         }
 
+        if let Some(e) = last_error {
+            let min_sources = match self.config.failure_policy {
+                FailurePolicy::BestEffort { min_sources } => min_sources,
+                _ => 0,
+            };
+            if !any_succeeded || all_sources.len() < min_sources {
+                return Err(e);
+            }
+        }
+
         all_sources.sort_by(|a, b| {
             b.relevance_score
                 .partial_cmp(&a.relevance_score)
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
+        if let Some(max_per_domain) = self.config.max_per_domain {
+            cap_per_domain(&mut all_sources, max_per_domain);
+        }
         all_sources.truncate(self.config.max_sources);
+        self.fetch_content(&mut all_sources, &without_content).await;
+        self.config.content_limits.apply(&mut all_sources);
 
         Ok(all_sources)
     }
+
+    /// Replaces the placeholder content of the sources in `urls`, and the
+    /// content of any source the fetcher can do better for, with the fetched
+    /// text. A page that cannot be fetched keeps what it had.
+    async fn fetch_content(&self, sources: &mut [Source], urls: &HashSet<String>) {
+        let Some(fetcher) = &self.fetcher else {
+            return;
+        };
+
+        let fetches: Vec<_> = sources
+            .iter()
+            .enumerate()
+            .filter(|(_, source)| {
+                urls.contains(&source.url) || fetcher.replaces_provider_content(&source.url)
+            })
+            .map(|(i, source)| {
+                let url = source.url.clone();
+                async move { (i, fetcher.fetch(&url).await) }
+            })
+            .collect();
+        let documents: Vec<_> = stream::iter(fetches)
+            .buffer_unordered(self.config.concurrency.max(1))
+            .collect()
+            .await;
+
+        for (i, document) in documents {
+            let Ok(document) = document else {
+                continue;
+            };
+            if document.text.trim().is_empty() {
+                continue;
+            }
+            let source = &mut sources[i];
+            source.content = document.text;
+            source.metadata.format = Some(document.format);
+            source.metadata.archived = document.archived;
+            for image in document.images {
+                if !source.images.contains(&image) {
+                    source.images.push(image);
+                }
+            }
+        }
+    }
+
+    async fn search_query(&self, query: &SearchQuery) -> SearchReport {
+        let timeout = self.config.query_timeout;
+        let search = pin!(self.provider.search_reported(query));
+
+        match future::select(search, Delay::new(timeout)).await {
+            Either::Left((report, _)) => report,
+            Either::Right(((), _)) => {
+                let error = SearchError::Timeout {
+                    timeout_secs: timeout.as_secs(),
+                };
+                SearchReport {
+                    result: Err(error.clone()),
+                    attempts: vec![ProviderAttempt {
+                        provider: self.provider.provider_id().to_string(),
+                        query: query.text.clone(),
+                        outcome: Err(error),
+                        duration: timeout,
+                        raw_response: None,
+                    }],
+                }
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::mock::MockSearchProvider;
-    use crate::search::SearchQuery;
-    use crate::traits::SearchResult;
+    use std::time::Instant;
+
+    use chrono::TimeZone;
+
+    use crate::feedback::TrustLabel;
+    use crate::mock::{MockContentFetcher, MockSearchProvider, MockSearchStep};
+    use crate::search::SearchFilters;
+    use crate::source::{ArchivedSnapshot, DocumentFormat};
+    use crate::traits::{FetchedDocument, SearchResult};
 
     #[tokio::test]
     async fn executor_returns_sources() {
@@ -91,6 +431,152 @@ mod tests {
         assert!(!sources.is_empty());
     }
 
+    #[tokio::test]
+    async fn executor_uses_raw_content_as_source_body() {
+        let results = vec![
+            SearchResult::new("https://example.com/1", "Title 1", "Snippet 1")
+                .with_score(0.9)
+                .with_raw_content("The full page text.")
+                .with_images(["https://example.com/1.png"]),
+            SearchResult::new("https://example.com/2", "Title 2", "Snippet 2").with_score(0.7),
+        ];
+
+        let provider = Arc::new(MockSearchProvider::new("mock").with_results(results));
+        let executor = Executor::new(provider, ExecutorConfig::default());
+
+        let plan = SearchPlan::new(
+            vec![SearchQuery::new("test")],
+            vec![crate::search::ProviderId::new("mock")],
+        );
+
+        let sources = executor.execute(&plan).await.unwrap();
+        assert_eq!(sources[0].content, "The full page text.");
+        assert_eq!(sources[0].images, vec!["https://example.com/1.png"]);
+        assert!(sources[1]
+            .content
+            .starts_with("Content fetched from source"));
+    }
+
+    #[tokio::test]
+    async fn executor_fetches_pages_without_provider_content() {
+        let results = vec![
+            SearchResult::new("https://example.com/paper.pdf", "Paper", "Snippet 1")
+                .with_score(0.9),
+            SearchResult::new("https://example.com/gone", "Gone", "Snippet 2").with_score(0.8),
+            SearchResult::new("https://example.com/full", "Full", "Snippet 3")
+                .with_score(0.7)
+                .with_raw_content("Provider text."),
+        ];
+        let fetcher = MockContentFetcher::new().with_document(
+            "https://example.com/paper.pdf",
+            FetchedDocument::new("Abstract. We study things.", DocumentFormat::Pdf)
+                .with_images(vec!["https://example.com/figure-1.png".to_string()]),
+        );
+
+        let provider = Arc::new(MockSearchProvider::new("mock").with_results(results));
+        let executor = Executor::new(provider, ExecutorConfig::default())
+            .with_content_fetcher(Arc::new(fetcher));
+
+        let plan = SearchPlan::new(
+            vec![SearchQuery::new("test")],
+            vec![crate::search::ProviderId::new("mock")],
+        );
+
+        let sources = executor.execute(&plan).await.unwrap();
+        assert_eq!(sources[0].content, "Abstract. We study things.");
+        assert_eq!(sources[0].metadata.format, Some(DocumentFormat::Pdf));
+        assert_eq!(sources[0].images, vec!["https://example.com/figure-1.png"]);
+        assert!(sources[1]
+            .content
+            .starts_with("Content fetched from source"));
+        assert_eq!(sources[1].metadata.format, None);
+        assert_eq!(sources[2].content, "Provider text.");
+    }
+
+    #[tokio::test]
+    async fn executor_marks_sources_read_from_an_archive() {
+        let results = vec![SearchResult::new(
+            "https://example.com/gone",
+            "Gone",
+            "Snippet",
+        )];
+        let snapshot = ArchivedSnapshot {
+            url: "https://web.archive.org/web/20240719000000/https://example.com/gone".to_string(),
+            captured_at: Utc.with_ymd_and_hms(2024, 7, 19, 0, 0, 0).unwrap(),
+        };
+        let fetcher = MockContentFetcher::new().with_document(
+            "https://example.com/gone",
+            FetchedDocument::new("Archived text.", DocumentFormat::Html)
+                .with_archived(snapshot.clone()),
+        );
+
+        let provider = Arc::new(MockSearchProvider::new("mock").with_results(results));
+        let executor = Executor::new(provider, ExecutorConfig::default())
+            .with_content_fetcher(Arc::new(fetcher));
+
+        let plan = SearchPlan::new(
+            vec![SearchQuery::new("test")],
+            vec![crate::search::ProviderId::new("mock")],
+        );
+
+        let sources = executor.execute(&plan).await.unwrap();
+        assert_eq!(sources[0].content, "Archived text.");
+        assert_eq!(sources[0].metadata.archived, Some(snapshot));
+    }
+
+    #[tokio::test]
+    async fn executor_replaces_provider_content_when_fetcher_prefers_it() {
+        let video = "https://www.youtube.com/watch?v=dQw4w9WgXcQ";
+        let results = vec![SearchResult::new(video, "Talk", "Snippet")
+            .with_score(0.9)
+            .with_raw_content("Video description.")];
+        let fetcher = MockContentFetcher::new().with_preferred_document(
+            video,
+            FetchedDocument::new("[0:05] Welcome to the talk.", DocumentFormat::Transcript),
+        );
+
+        let provider = Arc::new(MockSearchProvider::new("mock").with_results(results));
+        let executor = Executor::new(provider, ExecutorConfig::default())
+            .with_content_fetcher(Arc::new(fetcher));
+
+        let plan = SearchPlan::new(
+            vec![SearchQuery::new("test")],
+            vec![crate::search::ProviderId::new("mock")],
+        );
+
+        let sources = executor.execute(&plan).await.unwrap();
+        assert_eq!(sources[0].content, "[0:05] Welcome to the talk.");
+        assert_eq!(sources[0].metadata.format, Some(DocumentFormat::Transcript));
+    }
+
+    #[tokio::test]
+    async fn executor_truncates_oversized_content() {
+        let results = vec![
+            SearchResult::new("https://example.com/1", "Title 1", "Snippet 1")
+                .with_score(0.9)
+                .with_raw_content("x".repeat(5_000)),
+        ];
+        let config = ExecutorConfig {
+            content_limits: ContentLimits {
+                max_source_bytes: 1_000,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let provider = Arc::new(MockSearchProvider::new("mock").with_results(results));
+        let executor = Executor::new(provider, config);
+
+        let plan = SearchPlan::new(
+            vec![SearchQuery::new("test")],
+            vec![crate::search::ProviderId::new("mock")],
+        );
+
+        let sources = executor.execute(&plan).await.unwrap();
+        assert_eq!(sources[0].content.len(), 1_000);
+        assert!(sources[0].truncated);
+    }
+
     #[tokio::test]
     async fn executor_deduplicates_by_url() {
         let results = vec![
@@ -117,7 +603,7 @@ mod tests {
         let provider = Arc::new(MockSearchProvider::new("mock"));
         let config = ExecutorConfig {
             max_sources: 2,
-            min_score: 0.0,
+            ..Default::default()
         };
         let executor = Executor::new(provider, config);
 
@@ -130,6 +616,43 @@ mod tests {
         assert!(sources.len() <= 2);
     }
 
+    #[tokio::test]
+    async fn executor_caps_sources_per_domain() {
+        let results = vec![
+            SearchResult::new("https://a.example/1", "A1", "Snippet").with_score(0.9),
+            SearchResult::new("https://A.example/2", "A2", "Snippet").with_score(0.8),
+            SearchResult::new("https://a.example/3", "A3", "Snippet").with_score(0.7),
+            SearchResult::new("https://b.example/1", "B1", "Snippet").with_score(0.6),
+        ];
+        let provider = Arc::new(MockSearchProvider::new("mock").with_results(results));
+        let config = ExecutorConfig::default().with_limits(&SourceLimits {
+            max_per_domain: Some(2),
+            ..Default::default()
+        });
+        let executor = Executor::new(provider, config);
+
+        let plan = SearchPlan::new(
+            vec![SearchQuery::new("test")],
+            vec![crate::search::ProviderId::new("mock")],
+        );
+
+        let sources = executor.execute(&plan).await.unwrap();
+        let titles: Vec<&str> = sources.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, vec!["A1", "A2", "B1"]);
+    }
+
+    #[test]
+    fn limits_override_only_what_they_set() {
+        let config = ExecutorConfig::default().with_limits(&SourceLimits {
+            max_sources: Some(4),
+            ..Default::default()
+        });
+
+        assert_eq!(config.max_sources, 4);
+        assert_eq!(config.min_score, 0.0);
+        assert!(config.max_per_domain.is_none());
+    }
+
     #[tokio::test]
     async fn executor_filters_by_min_score() {
         let results = vec![
@@ -139,8 +662,8 @@ mod tests {
 
         let provider = Arc::new(MockSearchProvider::new("mock").with_results(results));
         let config = ExecutorConfig {
-            max_sources: 10,
             min_score: 0.5,
+            ..Default::default()
         };
         let executor = Executor::new(provider, config);
 
@@ -154,6 +677,100 @@ mod tests {
         assert!(sources[0].relevance_score >= 0.5);
     }
 
+    #[tokio::test]
+    async fn executor_drops_sources_outside_query_domains() {
+        let results = vec![
+            SearchResult::new("https://pubmed.ncbi.nlm.nih.gov/1", "PubMed", "Snippet"),
+            SearchResult::new("https://example.com/2", "Blog", "Snippet"),
+        ];
+        let provider = Arc::new(MockSearchProvider::new("mock").with_results(results));
+        let executor = Executor::new(provider, ExecutorConfig::default());
+
+        let plan = SearchPlan::new(
+            vec![SearchQuery::new("test")],
+            vec![crate::search::ProviderId::new("mock")],
+        )
+        .with_filters(&SearchFilters::new().include_domains(["nih.gov"]));
+
+        let sources = executor.execute(&plan).await.unwrap();
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].title, "PubMed");
+    }
+
+    #[tokio::test]
+    async fn executor_drops_results_outside_query_dates_and_reports_compliance() {
+        let date = |y, m, d| Utc.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap();
+        let results = vec![
+            SearchResult::new("https://example.com/1", "During", "Snippet")
+                .with_published_at(date(2023, 4, 1)),
+            SearchResult::new("https://example.com/2", "After", "Snippet")
+                .with_published_at(date(2024, 1, 1)),
+            SearchResult::new("https://example.com/3", "Undated", "Snippet"),
+        ];
+        let provider = Arc::new(MockSearchProvider::new("mock").with_results(results));
+        let executor = Executor::new(provider, ExecutorConfig::default());
+
+        let plan = SearchPlan::new(
+            vec![SearchQuery::new("test")],
+            vec![crate::search::ProviderId::new("mock")],
+        )
+        .with_filters(
+            &SearchFilters::new().published_between(Some(date(2023, 3, 1)), Some(date(2023, 7, 1))),
+        );
+
+        let report = executor.execute_reported(&plan).await;
+        let sources = report.result.as_ref().unwrap();
+        let titles: Vec<&str> = sources.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, vec!["During", "Undated"]);
+        assert_eq!(sources[0].metadata.published_at, Some(date(2023, 4, 1)));
+
+        let compliance = report.search_metadata().filter_compliance;
+        assert_eq!(compliance.len(), 1);
+        assert_eq!(compliance[0].provider.as_str(), "mock");
+        assert_eq!((compliance[0].results, compliance[0].violations), (3, 1));
+        assert!((compliance[0].rate() - 2.0 / 3.0).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn executor_reports_no_compliance_without_filters() {
+        let provider = Arc::new(MockSearchProvider::new("mock"));
+        let executor = Executor::new(provider, ExecutorConfig::default());
+        let plan = SearchPlan::new(
+            vec![SearchQuery::new("test")],
+            vec![crate::search::ProviderId::new("mock")],
+        );
+
+        let report = executor.execute_reported(&plan).await;
+        assert!(report.search_metadata().filter_compliance.is_empty());
+    }
+
+    #[tokio::test]
+    async fn executor_enforces_domain_policy_over_query_filters() {
+        let results = vec![
+            SearchResult::new("https://docs.rs/tokio", "Docs", "Snippet"),
+            SearchResult::new("https://competitor.com/tokio", "Competitor", "Snippet"),
+        ];
+        let provider = Arc::new(MockSearchProvider::new("mock").with_results(results));
+        let config = ExecutorConfig {
+            domain_policy: DomainPolicy {
+                deny: vec!["competitor.com".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let executor = Executor::new(provider, config);
+
+        let plan = SearchPlan::new(
+            vec![SearchQuery::new("test")],
+            vec![crate::search::ProviderId::new("mock")],
+        )
+        .with_filters(&SearchFilters::new().include_domains(["competitor.com", "docs.rs"]));
+
+        let sources = executor.execute(&plan).await.unwrap();
+        let titles: Vec<&str> = sources.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, vec!["Docs"]);
+    }
+
     #[tokio::test]
     async fn executor_sorts_by_relevance() {
         let results = vec![
@@ -175,4 +792,251 @@ mod tests {
         assert_eq!(sources[1].relevance_score, 0.7);
         assert_eq!(sources[2].relevance_score, 0.5);
     }
+
+    #[tokio::test]
+    async fn executor_ranks_by_domain_trust() {
+        let results = vec![
+            SearchResult::new("https://flagged.example/", "Flagged", "Snippet").with_score(0.9),
+            SearchResult::new("https://liked.example/", "Liked", "Snippet").with_score(0.8),
+            SearchResult::new("https://unknown.example/", "Unknown", "Snippet").with_score(0.85),
+        ];
+        let mut flagged = DomainTrust::new("flagged.example");
+        flagged.flags = 2;
+        let mut liked = DomainTrust::new("liked.example");
+        liked.endorsements = 5;
+
+        let provider = Arc::new(MockSearchProvider::new("mock").with_results(results));
+        let config = ExecutorConfig::default().with_domain_trust(vec![flagged, liked]);
+        let executor = Executor::new(provider, config);
+        let plan = SearchPlan::new(
+            vec![SearchQuery::new("test")],
+            vec![crate::search::ProviderId::new("mock")],
+        );
+
+        let sources = executor.execute(&plan).await.unwrap();
+        let titles: Vec<&str> = sources.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["Liked", "Unknown", "Flagged"]);
+        assert_eq!(sources[0].metadata.trust, Some(TrustLabel::Trusted));
+        assert_eq!(sources[1].metadata.trust, None);
+        assert_eq!(sources[2].metadata.trust, Some(TrustLabel::Distrusted));
+    }
+
+    #[tokio::test]
+    async fn executor_reports_attempts() {
+        let provider = Arc::new(MockSearchProvider::new("mock").fail_after(1));
+        let executor = Executor::new(provider, ExecutorConfig::default());
+
+        let plan = SearchPlan::new(
+            vec![SearchQuery::new("first"), SearchQuery::new("second")],
+            vec![crate::search::ProviderId::new("mock")],
+        );
+
+        let report = executor.execute_reported(&plan).await;
+
+        assert!(report.result.is_ok());
+        assert_eq!(report.attempts.len(), 2);
+        assert!(report.attempts[0].succeeded());
+        assert_eq!(report.attempts[1].query, "second");
+        assert!(!report.attempts[1].succeeded());
+    }
+
+    #[tokio::test]
+    async fn executor_summarises_search_metadata() {
+        let provider = Arc::new(MockSearchProvider::new("mock").with_result_count(2));
+        let executor = Executor::new(provider, ExecutorConfig::default());
+
+        let plan = SearchPlan::new(
+            vec![SearchQuery::new("first"), SearchQuery::new("second")],
+            vec![crate::search::ProviderId::new("mock")],
+        );
+
+        let report = executor.execute_reported(&plan).await;
+        let metadata = report.search_metadata();
+
+        assert_eq!(metadata.queries_executed, vec!["first", "second"]);
+        assert_eq!(metadata.providers_used, vec![ProviderId::new("mock")]);
+        assert_eq!(metadata.total_results, 4);
+
+        let sources = report.result.unwrap();
+        assert!(sources
+            .iter()
+            .all(|s| s.metadata.provider == Some(ProviderId::new("mock"))));
+    }
+
+    #[tokio::test]
+    async fn executor_runs_queries_concurrently() {
+        let provider =
+            Arc::new(MockSearchProvider::new("mock").with_latency(Duration::from_millis(100)));
+        let config = ExecutorConfig {
+            concurrency: 3,
+            ..Default::default()
+        };
+        let executor = Executor::new(provider, config);
+
+        let plan = SearchPlan::new(
+            vec![
+                SearchQuery::new("a"),
+                SearchQuery::new("b"),
+                SearchQuery::new("c"),
+            ],
+            vec![crate::search::ProviderId::new("mock")],
+        );
+
+        let start = Instant::now();
+        let report = executor.execute_reported(&plan).await;
+
+        assert!(report.result.is_ok());
+        assert!(start.elapsed() < Duration::from_millis(250));
+        let queries: Vec<&str> = report.attempts.iter().map(|a| a.query.as_str()).collect();
+        assert_eq!(queries, vec!["a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn executor_times_out_slow_queries() {
+        let provider =
+            Arc::new(MockSearchProvider::new("mock").with_latency(Duration::from_millis(500)));
+        let config = ExecutorConfig {
+            query_timeout: Duration::from_millis(20),
+            ..Default::default()
+        };
+        let executor = Executor::new(provider, config);
+
+        let plan = SearchPlan::new(
+            vec![SearchQuery::new("slow")],
+            vec![crate::search::ProviderId::new("mock")],
+        );
+
+        let report = executor.execute_reported(&plan).await;
+
+        assert!(matches!(report.result, Err(SearchError::Timeout { .. })));
+        assert_eq!(report.attempts.len(), 1);
+        assert!(!report.attempts[0].succeeded());
+    }
+
+    #[tokio::test]
+    async fn executor_tolerates_partial_failure() {
+        let provider = Arc::new(MockSearchProvider::new("mock").with_script([
+            MockSearchStep::Fail(SearchError::RateLimited {
+                provider: "mock".to_string(),
+            }),
+            MockSearchStep::Succeed,
+        ]));
+        let config = ExecutorConfig {
+            concurrency: 1,
+            ..Default::default()
+        };
+        let executor = Executor::new(provider, config);
+
+        let plan = SearchPlan::new(
+            vec![SearchQuery::new("first"), SearchQuery::new("second")],
+            vec![crate::search::ProviderId::new("mock")],
+        );
+
+        let report = executor.execute_reported(&plan).await;
+
+        assert!(!report.result.unwrap().is_empty());
+        assert!(!report.attempts[0].succeeded());
+        assert!(report.attempts[1].succeeded());
+    }
+
+    #[tokio::test]
+    async fn fail_fast_aborts_on_first_failure() {
+        let provider = Arc::new(MockSearchProvider::new("mock").with_script([
+            MockSearchStep::Succeed,
+            MockSearchStep::Fail(SearchError::RateLimited {
+                provider: "mock".to_string(),
+            }),
+        ]));
+        let config = ExecutorConfig {
+            concurrency: 1,
+            failure_policy: FailurePolicy::FailFast,
+            ..Default::default()
+        };
+        let executor = Executor::new(provider, config);
+
+        let plan = SearchPlan::new(
+            vec![
+                SearchQuery::new("first"),
+                SearchQuery::new("second"),
+                SearchQuery::new("third"),
+            ],
+            vec![crate::search::ProviderId::new("mock")],
+        );
+
+        let report = executor.execute_reported(&plan).await;
+
+        assert!(matches!(
+            report.result,
+            Err(SearchError::RateLimited { .. })
+        ));
+        assert_eq!(report.attempts.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn best_effort_enforces_min_sources() {
+        let script = || {
+            [
+                MockSearchStep::Fail(SearchError::Timeout { timeout_secs: 30 }),
+                MockSearchStep::Succeed,
+            ]
+        };
+        let plan = SearchPlan::new(
+            vec![SearchQuery::new("first"), SearchQuery::new("second")],
+            vec![crate::search::ProviderId::new("mock")],
+        );
+
+        let strict = Executor::new(
+            Arc::new(
+                MockSearchProvider::new("mock")
+                    .with_result_count(3)
+                    .with_script(script()),
+            ),
+            ExecutorConfig {
+                concurrency: 1,
+                failure_policy: FailurePolicy::BestEffort { min_sources: 5 },
+                ..Default::default()
+            },
+        );
+        assert!(strict.execute(&plan).await.is_err());
+
+        let lenient = Executor::new(
+            Arc::new(
+                MockSearchProvider::new("mock")
+                    .with_result_count(3)
+                    .with_script(script()),
+            ),
+            ExecutorConfig {
+                concurrency: 1,
+                failure_policy: FailurePolicy::BestEffort { min_sources: 3 },
+                ..Default::default()
+            },
+        );
+        let report = lenient.execute_reported(&plan).await;
+        assert_eq!(report.result.as_ref().unwrap().len(), 3);
+        assert_eq!(report.search_metadata().queries_skipped, vec!["first"]);
+    }
+
+    #[tokio::test]
+    async fn executor_fails_when_every_query_fails() {
+        let provider = Arc::new(MockSearchProvider::new("mock").fail_after(0));
+        let executor = Executor::new(provider, ExecutorConfig::default());
+
+        let plan = SearchPlan::new(
+            vec![SearchQuery::new("first"), SearchQuery::new("second")],
+            vec![crate::search::ProviderId::new("mock")],
+        );
+
+        assert!(executor.execute(&plan).await.is_err());
+    }
+
+    #[test]
+    fn executor_future_is_send() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let provider = Arc::new(MockSearchProvider::new("mock"));
+        let executor = Executor::new(provider, ExecutorConfig::default());
+        let plan = SearchPlan::new(vec![SearchQuery::new("test")], vec![]);
+
+        assert_send(&executor.execute(&plan));
+    }
 }
@@ -0,0 +1,195 @@
+//! Similar-page expansion of collected sources.
+//!
+//! Keyword queries miss pages that phrase a niche topic differently. For the
+//! top-ranked sources, the expander asks a provider that supports
+//! [`SearchProvider::find_similar`] for related pages and adds the new ones,
+//! never growing the set beyond `max_sources`.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Instant;
+
+use futures::future;
+
+use crate::search::ProviderId;
+use crate::source::Source;
+use crate::traits::{ProviderAttempt, SearchProvider};
+
+#[derive(Clone, Debug)]
+pub struct ExpanderConfig {
+    /// How many of the top-ranked sources to look up similar pages for.
+    pub seed_sources: usize,
+    /// Similar pages requested per seed.
+    pub results_per_seed: usize,
+}
+
+impl Default for ExpanderConfig {
+    fn default() -> Self {
+        Self {
+            seed_sources: 3,
+            results_per_seed: 3,
+        }
+    }
+}
+
+/// Outcome of an expansion, with every provider call that was made.
+#[derive(Debug)]
+pub struct ExpansionReport {
+    pub sources: Vec<Source>,
+    pub attempts: Vec<ProviderAttempt>,
+}
+
+pub struct Expander {
+    provider: Arc<dyn SearchProvider>,
+    config: ExpanderConfig,
+}
+
+impl Expander {
+    pub fn new(provider: Arc<dyn SearchProvider>, config: ExpanderConfig) -> Self {
+        Self { provider, config }
+    }
+
+    /// Adds pages similar to the highest-ranked `sources`. A failed lookup
+    /// only loses that seed's pages; the original sources are always kept.
+    pub async fn expand(&self, mut sources: Vec<Source>, max_sources: usize) -> ExpansionReport {
+        let room = max_sources.saturating_sub(sources.len());
+        if room == 0 || !self.provider.supports_find_similar() {
+            return ExpansionReport {
+                sources,
+                attempts: Vec::new(),
+            };
+        }
+
+        let seeds: Vec<(String, f32)> = sources
+            .iter()
+            .take(self.config.seed_sources)
+            .map(|source| (source.url.clone(), source.relevance_score))
+            .collect();
+        let lookups = seeds.iter().map(|(url, _)| async move {
+            let start = Instant::now();
+            let result = self
+                .provider
+                .find_similar(url, self.config.results_per_seed)
+                .await;
+            (result, start.elapsed())
+        });
+        let outcomes = future::join_all(lookups).await;
+
+        let provider_id = self.provider.provider_id().to_string();
+        let mut seen_urls: HashSet<String> = sources.iter().map(|s| s.url.clone()).collect();
+        let mut attempts = Vec::with_capacity(seeds.len());
+        let mut added = 0;
+
+        for ((seed_url, seed_score), (result, duration)) in seeds.iter().zip(outcomes) {
+            attempts.push(ProviderAttempt {
+                provider: provider_id.clone(),
+                query: format!("similar:{}", seed_url),
+                outcome: result.as_ref().map(Vec::len).map_err(Clone::clone),
+                duration,
+                raw_response: None,
+            });
+
+            let Ok(results) = result else {
+                continue;
+            };
+
+            for mut result in results {
+                if added == room {
+                    break;
+                }
+                if !seen_urls.insert(result.url.clone()) {
+                    continue;
+                }
+
+                // A page is only as relevant as the source it was found from.
+                result.score *= seed_score;
+                let content = result.raw_content.take().unwrap_or_else(|| {
+                    format!("Content fetched from source. Similar to: {}", seed_url)
+                });
+                let mut source = result.into_source(content);
+                source.metadata = source
+                    .metadata
+                    .with_provider(ProviderId::new(provider_id.as_str()));
+
+                sources.push(source);
+                added += 1;
+            }
+        }
+
+        sources.sort_by(|a, b| {
+            b.relevance_score
+                .partial_cmp(&a.relevance_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        ExpansionReport { sources, attempts }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockSearchProvider;
+    use crate::traits::SearchResult;
+
+    fn seeds() -> Vec<Source> {
+        vec![
+            Source::new("https://example.com/a", "A", "Content A").with_relevance_score(0.9),
+            Source::new("https://example.com/b", "B", "Content B").with_relevance_score(0.6),
+        ]
+    }
+
+    fn similar() -> Vec<SearchResult> {
+        vec![
+            SearchResult::new("https://niche.org/1", "Niche 1", "Snippet 1").with_score(0.8),
+            SearchResult::new("https://niche.org/2", "Niche 2", "Snippet 2").with_score(0.5),
+        ]
+    }
+
+    #[tokio::test]
+    async fn adds_similar_pages_once() {
+        let provider = Arc::new(MockSearchProvider::new("exa").with_similar_results(similar()));
+        let expander = Expander::new(provider, ExpanderConfig::default());
+
+        let report = expander.expand(seeds(), 10).await;
+
+        assert_eq!(report.sources.len(), 4);
+        assert_eq!(report.attempts.len(), 2);
+        assert_eq!(report.attempts[0].query, "similar:https://example.com/a");
+        let added = report
+            .sources
+            .iter()
+            .find(|s| s.url == "https://niche.org/1")
+            .unwrap();
+        assert!((added.relevance_score - 0.72).abs() < 1e-6);
+        assert_eq!(added.metadata.provider.as_ref().unwrap().as_str(), "exa");
+        assert!(report
+            .sources
+            .windows(2)
+            .all(|w| w[0].relevance_score >= w[1].relevance_score));
+    }
+
+    #[tokio::test]
+    async fn respects_max_sources() {
+        let provider = Arc::new(MockSearchProvider::new("exa").with_similar_results(similar()));
+        let expander = Expander::new(provider, ExpanderConfig::default());
+
+        let report = expander.expand(seeds(), 3).await;
+        assert_eq!(report.sources.len(), 3);
+
+        let report = expander.expand(seeds(), 2).await;
+        assert_eq!(report.sources.len(), 2);
+        assert!(report.attempts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn skips_providers_without_find_similar() {
+        let provider = Arc::new(MockSearchProvider::new("searxng"));
+        let expander = Expander::new(provider, ExpanderConfig::default());
+
+        let report = expander.expand(seeds(), 10).await;
+
+        assert_eq!(report.sources.len(), 2);
+        assert!(report.attempts.is_empty());
+    }
+}
@@ -0,0 +1,249 @@
+//! Fact extraction for the knowledge base.
+//!
+//! Once a job has its answer, the [`FactExtractor`] gives the model the
+//! sources the answer cites and asks for the facts they state as (subject,
+//! relation, object) triples, each with the sources that state it. The
+//! pipeline adds them to the store's knowledge base.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::answer::ResearchAnswer;
+use crate::artifact::LlmExchange;
+use crate::chat::{ChatRequest, Message, TokenUsage};
+use crate::id::JobId;
+use crate::knowledge::{Fact, FactSource};
+use crate::source::Source;
+use crate::traits::{LlmError, LlmProvider};
+
+/// Completion tokens the extraction may use.
+const EXTRACTION_MAX_TOKENS: usize = 2048;
+
+/// Characters of each cited source's content shown to the model.
+const MAX_EXCERPT_CHARS: usize = 2000;
+
+const EXTRACTION_INSTRUCTIONS: &str = "\
+You build knowledge bases from research sources. From the numbered sources, \
+list the facts they state about the question's topic as triples: a subject, \
+a short relation in lowercase, such as \"was founded by\" or \"is written \
+in\", and an object. Name subjects and objects by their full names so facts \
+from other research can be joined to them. Only list facts a source states \
+outright, with the numbers of the sources that state them, most important \
+first. Reply with JSON only, in the form {\"facts\": [{\"subject\": \"...\", \
+\"relation\": \"...\", \"object\": \"...\", \"sources\": [1]}]}.";
+
+#[derive(Clone, Debug)]
+pub struct FactExtractorConfig {
+    /// Whether completed jobs add facts to the knowledge base.
+    pub enabled: bool,
+    /// Most facts kept from one job.
+    pub max_facts: usize,
+}
+
+impl Default for FactExtractorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_facts: 20,
+        }
+    }
+}
+
+/// The facts drawn from a job's cited sources.
+#[derive(Clone, Debug)]
+pub struct FactExtraction {
+    pub facts: Vec<Fact>,
+    pub model: String,
+    pub usage: TokenUsage,
+}
+
+#[derive(Deserialize)]
+struct RawExtraction {
+    facts: Vec<RawFact>,
+}
+
+#[derive(Deserialize)]
+struct RawFact {
+    subject: String,
+    relation: String,
+    object: String,
+    #[serde(default)]
+    sources: Vec<usize>,
+}
+
+pub struct FactExtractor {
+    provider: Arc<dyn LlmProvider>,
+    config: FactExtractorConfig,
+}
+
+impl FactExtractor {
+    pub fn new(provider: Arc<dyn LlmProvider>, config: FactExtractorConfig) -> Self {
+        Self { provider, config }
+    }
+
+    /// Asks the model for the facts stated by the `sources` that `answer`,
+    /// the answer to `query`, cites, and returns its exchange with the model
+    /// alongside them. Each fact records the sources stating it as read by
+    /// `job_id`; facts no cited source states are dropped.
+    pub async fn extract(
+        &self,
+        job_id: &JobId,
+        query: &str,
+        answer: &ResearchAnswer,
+        sources: &[Source],
+    ) -> (Result<FactExtraction, LlmError>, LlmExchange) {
+        let cited: Vec<&Source> = sources
+            .iter()
+            .filter(|s| answer.citations.iter().any(|c| c.source_id == s.id))
+            .collect();
+        let messages = vec![
+            Message::system(EXTRACTION_INSTRUCTIONS),
+            Message::user(extraction_prompt(query, &cited)),
+        ];
+        let mut exchange = LlmExchange {
+            messages: messages.clone(),
+            raw_response: None,
+        };
+
+        let request = ChatRequest::new(messages).with_max_tokens(EXTRACTION_MAX_TOKENS);
+        let result = match self.provider.chat(request).await {
+            Ok(response) => {
+                let facts = parse(&response.content, job_id, &cited, self.config.max_facts);
+                exchange.raw_response = Some(response.content);
+                facts.map(|facts| FactExtraction {
+                    facts,
+                    model: response.model,
+                    usage: response.usage,
+                })
+            }
+            Err(e) => Err(e),
+        };
+
+        (result, exchange)
+    }
+}
+
+fn extraction_prompt(query: &str, cited: &[&Source]) -> String {
+    let mut prompt = format!("Question: {}\n\nSources:\n", query);
+    for (i, source) in cited.iter().enumerate() {
+        let excerpt: String = source.content.chars().take(MAX_EXCERPT_CHARS).collect();
+        prompt.push_str(&format!(
+            "\n[{}] {}\n{}\n",
+            i + 1,
+            source.title,
+            excerpt.trim()
+        ));
+    }
+    prompt
+}
+
+/// Reads the facts out of the model's reply, resolving source numbers
+/// against `cited` and dropping repeated facts.
+fn parse(
+    content: &str,
+    job_id: &JobId,
+    cited: &[&Source],
+    max_facts: usize,
+) -> Result<Vec<Fact>, LlmError> {
+    let json = match (content.find('{'), content.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &content[start..=end],
+        _ => {
+            return Err(LlmError::Provider(
+                "fact response contains no JSON object".to_string(),
+            ))
+        }
+    };
+    let raw: RawExtraction = serde_json::from_str(json)
+        .map_err(|e| LlmError::Provider(format!("failed to parse facts: {}", e)))?;
+
+    let mut keys = HashSet::new();
+    Ok(raw
+        .facts
+        .into_iter()
+        .filter_map(|raw| {
+            let mut fact = Fact::new(raw.subject.trim(), raw.relation.trim(), raw.object.trim());
+            if fact.subject.is_empty() || fact.relation.is_empty() || fact.object.is_empty() {
+                return None;
+            }
+            for source in raw
+                .sources
+                .iter()
+                .filter_map(|n| n.checked_sub(1).and_then(|i| cited.get(i)))
+            {
+                fact.add_source(FactSource {
+                    job_id: job_id.clone(),
+                    source_id: source.id.clone(),
+                    url: source.url.clone(),
+                    title: source.title.clone(),
+                });
+            }
+            let keep = !fact.sources.is_empty() && keys.insert(fact.key());
+            keep.then_some(fact)
+        })
+        .take(max_facts)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answer::{Citation, Confidence};
+    use crate::mock::{MockLlmProvider, MockLlmStep};
+
+    fn extractor(reply: &str) -> FactExtractor {
+        let llm =
+            MockLlmProvider::new("mock-facts").with_script([MockLlmStep::Raw(reply.to_string())]);
+        FactExtractor::new(Arc::new(llm), FactExtractorConfig::default())
+    }
+
+    #[tokio::test]
+    async fn extracts_facts_from_cited_sources() {
+        let sources: Vec<Source> = (1..=3)
+            .map(|i| {
+                Source::new(
+                    format!("https://example.com/{}", i),
+                    format!("Source {}", i),
+                    "Rust was started by Graydon Hoare at Mozilla.",
+                )
+            })
+            .collect();
+        let answer = ResearchAnswer::new("Rust began at Mozilla.", "", Confidence::High, "mock")
+            .with_citations(vec![
+                Citation::new("began", sources[0].id.clone()),
+                Citation::new("began", sources[2].id.clone()),
+            ]);
+        let reply = r#"{"facts": [
+            {"subject": "Rust", "relation": "was started by", "object": "Graydon Hoare", "sources": [1, 2, 1]},
+            {"subject": "rust", "relation": "was started  by", "object": "graydon hoare", "sources": [2]},
+            {"subject": "Rust", "relation": "is", "object": "fast", "sources": [5]},
+            {"subject": "", "relation": "is", "object": "fast", "sources": [1]}
+        ]}"#;
+        let job_id = JobId::new();
+
+        let (result, exchange) = extractor(reply)
+            .extract(&job_id, "Who made Rust?", &answer, &sources)
+            .await;
+        let extraction = result.unwrap();
+
+        assert_eq!(extraction.facts.len(), 1);
+        let fact = &extraction.facts[0];
+        assert_eq!(fact.object, "Graydon Hoare");
+        let urls: Vec<&str> = fact.sources.iter().map(|s| s.url.as_str()).collect();
+        assert_eq!(urls, ["https://example.com/1", "https://example.com/3"]);
+        assert_eq!(fact.sources[0].job_id, job_id);
+        assert!(exchange.messages[1].content.contains("[2] Source 3"));
+    }
+
+    #[tokio::test]
+    async fn fails_without_json() {
+        let answer = ResearchAnswer::new("A", "B", Confidence::High, "mock");
+        let (result, exchange) = extractor("No facts.")
+            .extract(&JobId::new(), "Q?", &answer, &[])
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(exchange.raw_response.as_deref(), Some("No facts."));
+    }
+}
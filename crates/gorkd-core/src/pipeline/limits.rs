@@ -0,0 +1,204 @@
+//! Size limits on source content.
+//!
+//! Some providers return whole pages, and a PDF converted to text can run to
+//! megabytes. [`ContentLimits`] caps each source and the sources together
+//! before they are stored or handed to the synthesizer, marking every source
+//! it shortens as [`truncated`](Source::truncated).
+
+use crate::source::Source;
+
+/// Bytes of content kept per source by default.
+pub const DEFAULT_MAX_SOURCE_BYTES: usize = 20_000;
+
+/// Bytes of content kept across all of a job's sources by default.
+pub const DEFAULT_MAX_TOTAL_BYTES: usize = 100_000;
+
+/// Stands in for the text removed by [`TruncationStrategy::HeadTail`].
+const OMISSION_MARKER: &str = "\n\n[...]\n\n";
+
+/// Which part of an over-long text is kept.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TruncationStrategy {
+    /// The beginning, cut at the limit.
+    #[default]
+    Head,
+    /// The beginning and the end, for pages that close with a summary or
+    /// conclusion.
+    HeadTail,
+    /// The beginning, cut after the last complete sentence that fits.
+    SentenceBoundary,
+}
+
+impl TruncationStrategy {
+    /// Shortens `text` to at most `max_bytes`, or returns `None` if it
+    /// already fits. Cuts always fall on character boundaries.
+    pub fn truncate(self, text: &str, max_bytes: usize) -> Option<String> {
+        if text.len() <= max_bytes {
+            return None;
+        }
+
+        let head = &text[..floor_char_boundary(text, max_bytes)];
+        let truncated = match self {
+            Self::Head => head.to_string(),
+            Self::HeadTail if max_bytes > OMISSION_MARKER.len() => {
+                let budget = max_bytes - OMISSION_MARKER.len();
+                let head_end = floor_char_boundary(text, budget * 2 / 3);
+                let tail_start = ceil_char_boundary(text, text.len() - (budget - head_end));
+                format!(
+                    "{}{}{}",
+                    &text[..head_end],
+                    OMISSION_MARKER,
+                    &text[tail_start..]
+                )
+            }
+            Self::HeadTail => head.to_string(),
+            // Falls back to a plain cut rather than give up more than half
+            // of the allowed text to reach a sentence end.
+            Self::SentenceBoundary => match last_sentence_end(head) {
+                Some(end) if end >= head.len() / 2 => head[..end].to_string(),
+                _ => head.to_string(),
+            },
+        };
+
+        Some(truncated)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContentLimits {
+    pub max_source_bytes: usize,
+    /// Budget shared by all sources in ranking order; lower-ranked sources
+    /// get what is left and are dropped once nothing is.
+    pub max_total_bytes: usize,
+    pub strategy: TruncationStrategy,
+}
+
+impl Default for ContentLimits {
+    fn default() -> Self {
+        Self {
+            max_source_bytes: DEFAULT_MAX_SOURCE_BYTES,
+            max_total_bytes: DEFAULT_MAX_TOTAL_BYTES,
+            strategy: TruncationStrategy::default(),
+        }
+    }
+}
+
+impl ContentLimits {
+    /// Truncates `sources`, which must be in ranking order, to the limits.
+    pub fn apply(&self, sources: &mut Vec<Source>) {
+        let mut remaining = self.max_total_bytes;
+        let mut kept = 0;
+
+        for source in sources.iter_mut() {
+            if remaining == 0 {
+                break;
+            }
+
+            let limit = self.max_source_bytes.min(remaining);
+            if let Some(content) = self.strategy.truncate(&source.content, limit) {
+                source.content = content;
+                source.truncated = true;
+            }
+            remaining -= source.content.len();
+            kept += 1;
+        }
+
+        sources.truncate(kept);
+    }
+}
+
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn ceil_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+/// End of the last sentence in `text`: a `.`, `!` or `?` followed by
+/// whitespace or the end of the text, or a line break.
+fn last_sentence_end(text: &str) -> Option<usize> {
+    text.rmatch_indices(['.', '!', '?', '\n'])
+        .map(|(i, m)| i + m.len())
+        .find(|&end| {
+            text[..end].ends_with('\n')
+                || text[end..].chars().next().map_or(true, char::is_whitespace)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaves_short_text_alone() {
+        assert_eq!(TruncationStrategy::Head.truncate("short", 10), None);
+    }
+
+    #[test]
+    fn head_cuts_on_char_boundary() {
+        let text = "héllo wörld";
+        let truncated = TruncationStrategy::Head.truncate(text, 2).unwrap();
+        assert_eq!(truncated, "h");
+    }
+
+    #[test]
+    fn head_tail_keeps_both_ends() {
+        let text = format!("START{}END", "x".repeat(1000));
+        let truncated = TruncationStrategy::HeadTail.truncate(&text, 60).unwrap();
+
+        assert!(truncated.len() <= 60);
+        assert!(truncated.starts_with("START"));
+        assert!(truncated.ends_with("END"));
+        assert!(truncated.contains("[...]"));
+    }
+
+    #[test]
+    fn sentence_boundary_drops_partial_sentence() {
+        let text = "First sentence. Second one! Third is cut off here";
+        let truncated = TruncationStrategy::SentenceBoundary
+            .truncate(text, 35)
+            .unwrap();
+        assert_eq!(truncated, "First sentence. Second one!");
+    }
+
+    #[test]
+    fn sentence_boundary_ignores_decimal_points() {
+        let text = "Version 1.5 of the library adds many things";
+        let truncated = TruncationStrategy::SentenceBoundary
+            .truncate(text, 30)
+            .unwrap();
+        assert_eq!(truncated, &text[..30]);
+    }
+
+    #[test]
+    fn applies_per_source_and_total_limits() {
+        let limits = ContentLimits {
+            max_source_bytes: 10,
+            max_total_bytes: 25,
+            strategy: TruncationStrategy::Head,
+        };
+        let mut sources = vec![
+            Source::new("https://a.com", "A", "a".repeat(50)),
+            Source::new("https://b.com", "B", "short"),
+            Source::new("https://c.com", "C", "c".repeat(50)),
+            Source::new("https://d.com", "D", "d".repeat(50)),
+        ];
+
+        limits.apply(&mut sources);
+
+        let lengths: Vec<_> = sources.iter().map(|s| s.content.len()).collect();
+        assert_eq!(lengths, vec![10, 5, 10]);
+        let truncated: Vec<_> = sources.iter().map(|s| s.truncated).collect();
+        assert_eq!(truncated, vec![true, false, true]);
+    }
+}
@@ -1,19 +1,73 @@
 //! Research pipeline orchestration.
 
+mod entities;
 mod executor;
+mod expander;
+mod facts;
+mod limits;
+mod outliner;
 mod planner;
+mod reprocess;
 mod synthesizer;
 
-pub use executor::{Executor, ExecutorConfig};
+pub use entities::{EntityExtraction, EntityExtractor};
+pub use executor::{cap_per_domain, ExecutionReport, Executor, ExecutorConfig, FailurePolicy};
+pub use expander::{Expander, ExpanderConfig, ExpansionReport};
+pub use facts::{FactExtraction, FactExtractor, FactExtractorConfig};
+pub use limits::{
+    ContentLimits, TruncationStrategy, DEFAULT_MAX_SOURCE_BYTES, DEFAULT_MAX_TOTAL_BYTES,
+};
+pub use outliner::{
+    Outline, OutlineSection, Outliner, OutlinerConfig, MIN_OUTLINE_SECTIONS, MIN_SECTION_TOKENS,
+};
+pub(crate) use planner::keywords;
 pub use planner::{Planner, PlannerConfig};
+pub use reprocess::Reprocessed;
 pub use synthesizer::{Synthesizer, SynthesizerConfig};
 
 use std::sync::Arc;
 
-use crate::answer::ResearchAnswer;
-use crate::job::{JobStatus, ResearchJob};
-use crate::source::Source;
-use crate::traits::{LlmProvider, SearchProvider, Store};
+use chrono::Utc;
+use futures::channel::mpsc::{self, UnboundedReceiver};
+use futures::future;
+use futures::StreamExt;
+
+use crate::answer::{ContextUsage, LlmStage, ResearchAnswer, StageTokenUsage};
+use crate::artifact::{LlmArtifact, LlmExchange, SearchArtifact};
+use crate::budget::{
+    synthesis_prompt_tokens, BudgetReport, CostBudget, CostEstimate, DEFAULT_COMPLETION_TOKENS,
+    MIN_BUDGET_SOURCES,
+};
+use crate::comparison::{ModelAnswer, ModelComparison};
+use crate::consistency::ConsistencyPolicy;
+use crate::corpus::{CorpusSearchProvider, HybridSearchProvider, CORPUS_PROVIDER_ID};
+use crate::depth::{AnswerDepth, EXHAUSTIVE_CONTEXT_SOURCES, EXHAUSTIVE_MAX_SOURCES};
+use crate::error::ErrorCode;
+use crate::escalation::{EscalationPolicy, EscalationReport};
+use crate::event::{JobEvent, JobEventKind};
+use crate::evidence::map_evidence;
+use crate::freshness::FreshnessPolicy;
+use crate::highlight;
+use crate::job::{JobFailure, JobStatus, ResearchJob};
+use crate::length::{LengthPolicies, LengthPolicy};
+use crate::lifecycle::{LifecycleEvent, LifecycleEventKind};
+use crate::links::{self, LinkCheckConfig};
+use crate::moderation::ModerationPolicy;
+use crate::query::QueryIntent;
+use crate::references::{number_references, CitationMarkers};
+use crate::routing::{ModelTier, QueryComplexity, RoutingDecision, RoutingPolicy};
+use crate::search::SearchPlan;
+use crate::snippet::{add_snippets, query_terms};
+use crate::source::{SearchMetadata, Source};
+use crate::trace::with_trace_id;
+use crate::traits::{
+    ArtifactSink, ContentFetcher, Embedder, EventPublisher, LinkChecker, LlmError, LlmProvider,
+    Moderator, PipelinePlugin, PluginError, PluginHook, ProviderAttempt, SearchError,
+    SearchProvider, Store, StoreError,
+};
+
+/// Times a job update is retried after losing a race with another writer.
+const MAX_UPDATE_ATTEMPTS: usize = 3;
 
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
@@ -22,36 +76,223 @@ pub enum PipelineError {
     Planning(String),
 
     #[error("search failed: {0}")]
-    Search(String),
+    Search(SearchError),
 
-    #[error("synthesis failed: {0}")]
-    Synthesis(String),
+    #[error("synthesis failed: {error}")]
+    Synthesis {
+        model: String,
+        #[source]
+        error: LlmError,
+    },
 
     #[error("store error: {0}")]
     Store(#[from] crate::traits::StoreError),
 
     #[error("no sources found for query")]
     NoSources,
+
+    #[error("moderation failed: {0}")]
+    Moderation(String),
+
+    #[error("answer blocked by content policy: {}", categories.join(", "))]
+    ContentBlocked { categories: Vec<String> },
+
+    #[error("structured answer does not match the requested schema: {}", errors.join("; "))]
+    StructuredAnswer { errors: Vec<String> },
+
+    #[error(
+        "synthesis with {model} would cost {} but the budget is {budget}",
+        estimated.map_or_else(|| "an unknown amount".to_string(), |e| e.to_string())
+    )]
+    BudgetExceeded {
+        model: String,
+        budget: CostBudget,
+        /// The cheapest estimate, in the budget's unit; `None` when the
+        /// model's price is unknown.
+        estimated: Option<CostBudget>,
+    },
+
+    #[error("job was already finished by another writer")]
+    AlreadyFinished,
+
+    #[error(transparent)]
+    Transition(#[from] crate::error::TransitionError),
+}
+
+impl PipelineError {
+    /// The failure reason reported to clients.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::Planning(_) => ErrorCode::InternalError,
+            Self::Search(e) => e.code(),
+            Self::Synthesis { error, .. } => error.code(),
+            Self::Store(e) => e.code(),
+            Self::NoSources => ErrorCode::NoSources,
+            Self::Moderation(_) => ErrorCode::ProviderError,
+            Self::ContentBlocked { .. } => ErrorCode::ContentBlocked,
+            Self::StructuredAnswer { .. } => ErrorCode::InvalidAnswer,
+            Self::BudgetExceeded { .. } => ErrorCode::BudgetExceeded,
+            Self::AlreadyFinished => ErrorCode::Conflict,
+            Self::Transition(e) => e.code(),
+        }
+    }
+
+    /// Whether running the job again may succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Search(e) => e.is_retryable(),
+            Self::Synthesis { error, .. } => error.is_retryable(),
+            Self::Store(e) => e.is_retryable(),
+            _ => false,
+        }
+    }
+
+    /// Classifies the error for a job that failed while in `stage`.
+    pub fn failure(&self, stage: JobStatus) -> JobFailure {
+        let provider = match self {
+            Self::Search(e) => e.provider().map(str::to_string),
+            Self::Synthesis { model, .. } | Self::BudgetExceeded { model, .. } => {
+                Some(model.clone())
+            }
+            _ => None,
+        };
+
+        JobFailure {
+            stage,
+            kind: self.code(),
+            provider,
+            retryable: self.is_retryable(),
+            message: self.to_string(),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct PipelineResult {
     pub job: ResearchJob,
     pub sources: Vec<Source>,
-    pub answer: ResearchAnswer,
+    pub search_metadata: SearchMetadata,
+    /// `None` for sources-only jobs.
+    pub answer: Option<ResearchAnswer>,
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct PipelineConfig {
     pub planner: PlannerConfig,
     pub executor: ExecutorConfig,
+    /// Used when the pipeline has a source expansion provider.
+    pub expansion: ExpanderConfig,
     pub synthesizer: SynthesizerConfig,
+    pub moderation: ModerationPolicy,
+    /// Answer length per question type, chosen from the job's intent.
+    pub length: LengthPolicies,
+    /// Which questions the pipeline's fast model answers, when it has one.
+    /// `None` answers every question with the pipeline's own model.
+    pub routing: Option<RoutingPolicy>,
+    /// How exhaustive answers are outlined and written section by section.
+    pub outline: OutlinerConfig,
+    /// Whether and how completed jobs add facts to the knowledge base.
+    pub facts: FactExtractorConfig,
+    /// What happens to citations of dead links, when the pipeline has a
+    /// link checker.
+    pub links: LinkCheckConfig,
+    /// Whether answers cite sources inline by number or by ID.
+    pub citation_markers: CitationMarkers,
+    /// How long answers stay current, by the volatility of their topic.
+    pub freshness: FreshnessPolicy,
+    /// Which answers are unsure enough to search again for. `None` returns
+    /// every answer as it comes.
+    pub escalation: Option<EscalationPolicy>,
+    /// How many answers high-stakes jobs sample, and at what temperature.
+    pub consistency: ConsistencyPolicy,
+}
+
+impl PipelineConfig {
+    /// This configuration as `job` asked for it: with its depth, then its
+    /// own source limits, applied.
+    pub fn for_job(&self, job: &ResearchJob) -> Self {
+        let mut config = self.for_depth(job.depth);
+        config.executor = config.executor.with_limits(&job.source_limits);
+        config.planner.include_corpus = job.include_corpus;
+        config.planner.corpus_only = job.corpus_only;
+        config
+    }
+
+    /// This configuration with the source budgets and answer length of
+    /// `depth` in place of the configured ones.
+    pub fn for_depth(&self, depth: AnswerDepth) -> Self {
+        let mut config = self.clone();
+        if let Some(max_sources) = depth.max_sources() {
+            config.executor.max_sources = max_sources;
+        }
+        if let Some(context_sources) = depth.context_sources() {
+            config.synthesizer.max_context_sources = context_sources;
+        }
+        if let Some(policy) = depth.length_policy() {
+            config.length = LengthPolicies::uniform(policy);
+        }
+        config
+    }
+
+    /// This configuration for `job`'s escalated search: with at least the
+    /// source budgets of exhaustive answers, within the job's own source
+    /// limits.
+    pub fn for_escalation(&self, job: &ResearchJob) -> Self {
+        let mut config = self.for_job(job);
+        config.executor.max_sources = config.executor.max_sources.max(EXHAUSTIVE_MAX_SOURCES);
+        config.executor = config.executor.with_limits(&job.source_limits);
+        config.synthesizer.max_context_sources = config
+            .synthesizer
+            .max_context_sources
+            .max(EXHAUSTIVE_CONTEXT_SOURCES);
+        config
+    }
+}
+
+/// Completes why an insufficient `answer` could not be given: the queries
+/// searched and, when the model suggested no reformulation, the keywords of
+/// `query`. Answers that are not insufficient keep no explanation.
+fn explain_insufficiency(
+    answer: &mut ResearchAnswer,
+    search_metadata: &SearchMetadata,
+    query: &str,
+) {
+    if answer.is_answerable() {
+        answer.why_insufficient = None;
+        return;
+    }
+    let why = answer.why_insufficient.get_or_insert_with(Default::default);
+    why.searched = search_metadata.queries_executed.clone();
+    if why.reformulations.is_empty() {
+        let keywords = planner::keywords(query);
+        if !keywords.eq_ignore_ascii_case(query.trim()) {
+            why.add_reformulation(keywords);
+        }
+    }
+}
+
+/// The model and sources a budgeted job synthesizes with.
+struct BudgetFit {
+    provider: Arc<dyn LlmProvider>,
+    context_sources: usize,
+    estimate: CostEstimate,
+    downgraded_from: Option<String>,
 }
 
 pub struct Pipeline {
     store: Arc<dyn Store>,
     search_provider: Arc<dyn SearchProvider>,
+    expansion_provider: Option<Arc<dyn SearchProvider>>,
+    corpus_embedder: Option<Arc<dyn Embedder>>,
+    content_fetcher: Option<Arc<dyn ContentFetcher>>,
+    link_checker: Option<Arc<dyn LinkChecker>>,
     llm_provider: Arc<dyn LlmProvider>,
+    fast_provider: Option<Arc<dyn LlmProvider>>,
+    comparison_providers: Vec<Arc<dyn LlmProvider>>,
+    moderator: Option<Arc<dyn Moderator>>,
+    artifact_sink: Option<Arc<dyn ArtifactSink>>,
+    event_publisher: Option<Arc<dyn EventPublisher>>,
+    plugins: Vec<Arc<dyn PipelinePlugin>>,
     config: PipelineConfig,
 }
 
@@ -64,7 +305,17 @@ impl Pipeline {
         Self {
             store,
             search_provider,
+            expansion_provider: None,
+            corpus_embedder: None,
+            content_fetcher: None,
+            link_checker: None,
             llm_provider,
+            fast_provider: None,
+            comparison_providers: Vec::new(),
+            moderator: None,
+            artifact_sink: None,
+            event_publisher: None,
+            plugins: Vec::new(),
             config: PipelineConfig::default(),
         }
     }
@@ -74,60 +325,1311 @@ impl Pipeline {
         self
     }
 
-    pub async fn run(&self, mut job: ResearchJob) -> Result<PipelineResult, PipelineError> {
-        job.transition_to(JobStatus::Planning);
-        self.store.update_job(&job).await?;
+    /// Makes `providers` available, by model ID, to jobs that compare
+    /// models. The pipeline's own provider is always available.
+    pub fn with_comparison_models(
+        mut self,
+        providers: impl IntoIterator<Item = Arc<dyn LlmProvider>>,
+    ) -> Self {
+        self.comparison_providers.extend(providers);
+        self
+    }
 
-        let planner = Planner::new(self.config.planner.clone());
-        let search_plan = planner.plan(&job.query);
+    /// Answers the questions `config.routing` finds simple with `provider`
+    /// instead of the pipeline's own model.
+    pub fn with_fast_model(mut self, provider: Arc<dyn LlmProvider>) -> Self {
+        self.fast_provider = Some(provider);
+        self
+    }
 
-        job.transition_to(JobStatus::Searching);
-        self.store.update_job(&job).await?;
+    /// Sets the moderator consulted when `config.moderation` is enabled.
+    pub fn with_moderator(mut self, moderator: Arc<dyn Moderator>) -> Self {
+        self.moderator = Some(moderator);
+        self
+    }
 
-        let executor = Executor::new(
-            Arc::clone(&self.search_provider),
-            self.config.executor.clone(),
-        );
-        let sources = executor
-            .execute(&search_plan)
-            .await
-            .map_err(|e| PipelineError::Search(e.to_string()))?;
+    /// Expands the collected sources with pages similar to the top-ranked
+    /// ones, found through `provider`, up to `config.executor.max_sources`.
+    pub fn with_source_expansion(mut self, provider: Arc<dyn SearchProvider>) -> Self {
+        self.expansion_provider = Some(provider);
+        self
+    }
 
-        if sources.is_empty() {
-            job.fail("No sources found for query");
-            self.store.update_job(&job).await?;
-            return Err(PipelineError::NoSources);
+    /// Searches the store's document corpus alongside the web for jobs that
+    /// include it, embedding their queries with `embedder`. Each job sees
+    /// only the documents of its workspace.
+    pub fn with_corpus(mut self, embedder: Arc<dyn Embedder>) -> Self {
+        self.corpus_embedder = Some(embedder);
+        self
+    }
+
+    /// Downloads the pages of sources whose search results came without
+    /// their text.
+    pub fn with_content_fetcher(mut self, fetcher: Arc<dyn ContentFetcher>) -> Self {
+        self.content_fetcher = Some(fetcher);
+        self
+    }
+
+    /// Checks that the pages each answer cites can still be reached before
+    /// it is delivered, handling dead ones as `config.links` says.
+    pub fn with_link_checker(mut self, checker: Arc<dyn LinkChecker>) -> Self {
+        self.link_checker = Some(checker);
+        self
+    }
+
+    /// Captures the redacted prompt and raw model output of every LLM call,
+    /// and the response body of every search provider that reports it, so
+    /// jobs can be [reprocessed](Self::reprocess).
+    pub fn with_artifact_sink(mut self, sink: Arc<dyn ArtifactSink>) -> Self {
+        self.artifact_sink = Some(sink);
+        self
+    }
+
+    /// Publishes stage changes, completion and failure of every job run.
+    pub fn with_event_publisher(mut self, publisher: Arc<dyn EventPublisher>) -> Self {
+        self.event_publisher = Some(publisher);
+        self
+    }
+
+    /// Runs the hooks of `plugins` in every job, in order, each on what the
+    /// previous one left.
+    pub fn with_plugins(
+        mut self,
+        plugins: impl IntoIterator<Item = Arc<dyn PipelinePlugin>>,
+    ) -> Self {
+        self.plugins.extend(plugins);
+        self
+    }
+
+    /// Runs `job` to completion with its trace ID as the current trace ID,
+    /// so provider requests made along the way carry it.
+    pub async fn run(&self, job: ResearchJob) -> Result<PipelineResult, PipelineError> {
+        let trace_id = job.trace_id.clone();
+        with_trace_id(trace_id, self.run_job(job)).await
+    }
+
+    async fn run_job(&self, mut job: ResearchJob) -> Result<PipelineResult, PipelineError> {
+        if job.intent.is_none() {
+            job.intent = Some(QueryIntent::classify(&job.query));
+        }
+        self.advance(&mut job, JobStatus::Planning).await?;
+
+        let config = self.config.for_job(&job);
+        let planner = Planner::new(config.planner.clone());
+        let time_constraint = job.intent.as_ref().and_then(|i| i.time_constraint.clone());
+        let query = self.rewrite_query(&job).await?;
+        let search_plan = planner
+            .plan(&query)
+            .with_filters(&job.filters)
+            .with_time_constraint(time_constraint);
+
+        self.advance(&mut job, JobStatus::Searching).await?;
+
+        let (mut sources, mut search_metadata) = match self.inherited_sources(&job).await? {
+            Some(inherited) => inherited,
+            None => self.search(&mut job, &search_plan, &config).await?,
+        };
+        self.record(
+            &job,
+            JobEventKind::SourcesCollected {
+                count: sources.len(),
+            },
+        )
+        .await?;
+
+        if job.sources_only {
+            self.advance(&mut job, JobStatus::Completed).await?;
+            return Ok(PipelineResult {
+                job,
+                sources,
+                search_metadata,
+                answer: None,
+            });
+        }
+
+        self.advance(&mut job, JobStatus::Synthesizing).await?;
+
+        let length = job
+            .intent
+            .as_ref()
+            .and_then(|intent| config.length.get(intent.question_type));
+        let synthesizer = &config.synthesizer;
+        let result = if job.models.is_empty() {
+            self.answer_within_budget(&job, &sources, length, synthesizer)
+                .await
+        } else {
+            self.compare_models(&job, &sources, length, synthesizer)
+                .await
+        };
+        let mut answer = match result {
+            Ok(answer) => answer,
+            Err(e @ PipelineError::Store(_)) => return Err(e),
+            Err(e) => return self.fail(&mut job, e).await,
+        };
+        let escalates = config
+            .escalation
+            .as_ref()
+            .is_some_and(|policy| policy.escalates(&answer.confidence));
+        if escalates && job.models.is_empty() && job.max_cost.is_none() {
+            (answer, sources, search_metadata) = self
+                .escalate(&job, &query, answer, sources, search_metadata, length)
+                .await?;
+        }
+
+        explain_insufficiency(&mut answer, &search_metadata, &query);
+
+        if config.facts.enabled {
+            self.record_facts(&job, &mut answer, &sources).await?;
         }
+        answer.freshness = Some(config.freshness.assess(&job, Utc::now()));
+        self.store.store_answer(&job.id, &answer).await?;
 
+        self.advance(&mut job, JobStatus::Completed).await?;
+
+        Ok(PipelineResult {
+            job,
+            sources,
+            search_metadata,
+            answer: Some(answer),
+        })
+    }
+
+    /// Searches for the job's sources and stores them with the search
+    /// metadata. Fails the job when search fails or finds nothing.
+    async fn search(
+        &self,
+        job: &mut ResearchJob,
+        search_plan: &SearchPlan,
+        config: &PipelineConfig,
+    ) -> Result<(Vec<Source>, SearchMetadata), PipelineError> {
+        let (result, search_metadata) = self.collect_sources(job, search_plan, config).await?;
+        self.store
+            .store_search_metadata(&job.id, &search_metadata)
+            .await?;
+
+        let sources = match result {
+            Ok(sources) => sources,
+            Err(e) => return self.fail(job, e).await,
+        };
         self.store.store_sources(&job.id, &sources).await?;
+        Ok((sources, search_metadata))
+    }
 
-        job.transition_to(JobStatus::Synthesizing);
-        self.store.update_job(&job).await?;
+    /// Runs `search_plan` and filters the sources it finds, recording the
+    /// provider attempts. A failed search, or one that found nothing, is
+    /// the inner error.
+    async fn collect_sources(
+        &self,
+        job: &ResearchJob,
+        search_plan: &SearchPlan,
+        config: &PipelineConfig,
+    ) -> Result<(Result<Vec<Source>, PipelineError>, SearchMetadata), PipelineError> {
+        let trust = self.store.domain_trust().await?;
+        let provider = match &self.corpus_embedder {
+            Some(embedder)
+                if search_plan
+                    .providers
+                    .iter()
+                    .any(|p| p.as_str() == CORPUS_PROVIDER_ID) =>
+            {
+                let mut corpus =
+                    CorpusSearchProvider::new(Arc::clone(&self.store), Arc::clone(embedder));
+                if let Some(ref workspace) = job.workspace {
+                    corpus = corpus.with_workspace(workspace);
+                }
+                if search_plan.providers.len() == 1 {
+                    Arc::new(corpus) as Arc<dyn SearchProvider>
+                } else {
+                    Arc::new(HybridSearchProvider::new(
+                        Arc::clone(&self.search_provider),
+                        Arc::new(corpus),
+                    ))
+                }
+            }
+            _ => Arc::clone(&self.search_provider),
+        };
+        let (progress, searched) = mpsc::unbounded();
+        let mut executor =
+            Executor::new(provider, config.executor.clone().with_domain_trust(trust))
+                .with_progress(progress);
+        if let Some(ref fetcher) = self.content_fetcher {
+            executor = executor.with_content_fetcher(Arc::clone(fetcher));
+        }
+        // The executor is dropped once it is done, which ends the progress.
+        let execution = async move { executor.execute_reported(search_plan).await };
+        let planned = search_plan.queries.len();
+        let (mut report, recorded) = future::join(
+            execution,
+            self.record_search_progress(job, planned, searched),
+        )
+        .await;
+        recorded?;
+        self.capture_searches(job, search_plan, &report.attempts)
+            .await;
+        if let (Some(provider), Ok(sources)) = (&self.expansion_provider, &mut report.result) {
+            if !sources.is_empty() {
+                let expander = Expander::new(Arc::clone(provider), config.expansion.clone());
+                let expansion = expander
+                    .expand(std::mem::take(sources), config.executor.max_sources)
+                    .await;
+                *sources = expansion.sources;
+                sources.retain(|source| {
+                    let domain = &source.metadata.domain;
+                    job.filters.admits(domain) && config.executor.domain_policy.admits(domain)
+                });
+                if let Some(max_per_domain) = config.executor.max_per_domain {
+                    cap_per_domain(sources, max_per_domain);
+                }
+                config.executor.content_limits.apply(sources);
+                report.attempts.extend(expansion.attempts);
+            }
+        }
+        self.record_attempts(job, &report.attempts).await?;
 
-        let synthesizer = Synthesizer::new(
-            Arc::clone(&self.llm_provider),
-            self.config.synthesizer.clone(),
-        );
-        let answer = synthesizer
-            .synthesize(&job.query, &sources)
+        let mut search_metadata = report.search_metadata();
+        search_metadata.time_constraint = search_plan.time_constraint.clone();
+
+        let mut sources = match report.result {
+            Ok(sources) => sources,
+            Err(e) => return Ok((Err(PipelineError::Search(e)), search_metadata)),
+        };
+        self.filter_sources(job, &mut sources).await?;
+
+        if sources.is_empty() {
+            return Ok((Err(PipelineError::NoSources), search_metadata));
+        }
+        let entities = job.intent.as_ref().map_or(&[][..], |i| &i.entities[..]);
+        add_snippets(&mut sources, &query_terms(&job.query, entities));
+        Ok((Ok(sources), search_metadata))
+    }
+
+    /// Records how many of the `planned` queries have been searched, from
+    /// none until `searched` closes.
+    async fn record_search_progress(
+        &self,
+        job: &ResearchJob,
+        planned: usize,
+        mut searched: UnboundedReceiver<usize>,
+    ) -> Result<(), PipelineError> {
+        let mut executed = 0;
+        loop {
+            self.record(job, JobEventKind::SearchProgressed { executed, planned })
+                .await?;
+            match searched.next().await {
+                Some(count) => executed = count,
+                None => return Ok(()),
+            }
+        }
+    }
+
+    /// Searches again with a broader plan for an answer below the
+    /// escalation threshold, answers again from the sources of both
+    /// searches, and keeps the second answer, with its sources, unless it
+    /// is less confident than the first. Either answer carries the
+    /// [`EscalationReport`].
+    async fn escalate(
+        &self,
+        job: &ResearchJob,
+        query: &str,
+        answer: ResearchAnswer,
+        sources: Vec<Source>,
+        search_metadata: SearchMetadata,
+        length: Option<&LengthPolicy>,
+    ) -> Result<(ResearchAnswer, Vec<Source>, SearchMetadata), PipelineError> {
+        let config = self.config.for_escalation(job);
+        let (filters, dropped) = job.filters.relaxed();
+        let search_plan = Planner::new(config.planner.clone())
+            .broaden(query)
+            .with_filters(&filters);
+        let mut relaxed_filters: Vec<String> = dropped.into_iter().map(String::from).collect();
+        if job
+            .intent
+            .as_ref()
+            .is_some_and(|i| i.time_constraint.is_some())
+        {
+            relaxed_filters.push("time_constraint".to_string());
+        }
+        let mut report = EscalationReport {
+            initial_confidence: answer.confidence.clone(),
+            queries: search_plan.queries.iter().map(|q| q.text.clone()).collect(),
+            relaxed_filters,
+            max_sources: config.executor.max_sources,
+            sources_added: 0,
+            replaced: false,
+            error: None,
+        };
+        self.record(
+            job,
+            JobEventKind::Escalated {
+                confidence: answer.confidence.clone(),
+                queries: report.queries.clone(),
+                max_sources: report.max_sources,
+            },
+        )
+        .await?;
+
+        let keep_first = |mut answer: ResearchAnswer, report: EscalationReport| {
+            answer.synthesis_metadata.escalation = Some(report);
+            Ok((answer, sources.clone(), search_metadata.clone()))
+        };
+        let (found, broader_metadata) = self.collect_sources(job, &search_plan, &config).await?;
+        let found = match found {
+            Ok(found) => found,
+            Err(e) => {
+                report.error = Some(e.to_string());
+                return keep_first(answer, report);
+            }
+        };
+
+        let mut merged = sources.clone();
+        for source in found {
+            if merged.len() >= config.executor.max_sources {
+                break;
+            }
+            if !merged.iter().any(|s| s.url == source.url) {
+                merged.push(source);
+                report.sources_added += 1;
+            }
+        }
+
+        let mut second = match self
+            .answer_within_budget(job, &merged, length, &config.synthesizer)
             .await
-            .map_err(|e| PipelineError::Synthesis(e.to_string()))?;
+        {
+            Ok(second) => second,
+            Err(e @ PipelineError::Store(_)) => return Err(e),
+            Err(e) => {
+                report.error = Some(e.to_string());
+                return keep_first(answer, report);
+            }
+        };
+        if second.confidence.is_below(&answer.confidence) {
+            return keep_first(answer, report);
+        }
 
-        job.transition_to(JobStatus::Completed);
-        self.store.update_job(&job).await?;
+        report.replaced = true;
+        let metadata = &mut second.synthesis_metadata;
+        for usage in &answer.synthesis_metadata.stage_usage {
+            metadata.record_usage(usage.clone());
+        }
+        if let (Some(first), Some(cost)) = (answer.synthesis_metadata.cost_usd, metadata.cost_usd) {
+            metadata.cost_usd = Some(first + cost);
+        }
+        metadata.escalation = Some(report);
+        self.store
+            .store_search_metadata(&job.id, &broader_metadata)
+            .await?;
+        self.store.store_sources(&job.id, &merged).await?;
+        Ok((second, merged, broader_metadata))
+    }
 
-        Ok(PipelineResult {
+    /// The sources a retry inherited from the job it retries, if the retried
+    /// job failed after collecting them.
+    async fn inherited_sources(
+        &self,
+        job: &ResearchJob,
+    ) -> Result<Option<(Vec<Source>, SearchMetadata)>, PipelineError> {
+        if job.retried_from.is_none() {
+            return Ok(None);
+        }
+
+        let sources = self.store.get_sources(&job.id).await?;
+        if sources.is_empty() {
+            return Ok(None);
+        }
+        let metadata = self
+            .store
+            .get_search_metadata(&job.id)
+            .await?
+            .unwrap_or_default();
+        Ok(Some((sources, metadata)))
+    }
+
+    /// Synthesizes the answer with the model routing chooses, fitted to the
+    /// job's budget when it has one.
+    async fn answer_within_budget(
+        &self,
+        job: &ResearchJob,
+        sources: &[Source],
+        length: Option<&LengthPolicy>,
+        synthesizer: &SynthesizerConfig,
+    ) -> Result<ResearchAnswer, PipelineError> {
+        let (primary, routing) = self.route(job);
+        if let Some(ref decision) = routing {
+            self.record(
+                job,
+                JobEventKind::ModelRouted {
+                    model: decision.model.clone(),
+                    tier: decision.tier,
+                    reason: decision.reason.clone(),
+                },
+            )
+            .await?;
+        }
+
+        let Some(max_cost) = job.max_cost else {
+            let mut answer = if self.outlines(job) {
+                self.answer_outlined(job, primary, sources, length, synthesizer)
+                    .await?
+            } else {
+                self.answer_with(job, primary, sources, length, synthesizer)
+                    .await?
+            };
+            answer.synthesis_metadata.routing = routing;
+            return Ok(answer);
+        };
+
+        let completion_tokens = length.map_or(DEFAULT_COMPLETION_TOKENS, |l| l.max_tokens);
+        let fit = self.fit_budget(
             job,
+            &primary,
+            max_cost,
             sources,
-            answer,
+            completion_tokens,
+            synthesizer,
+        )?;
+        self.record(
+            job,
+            JobEventKind::BudgetApplied {
+                model: fit.provider.model_id().to_string(),
+                context_sources: fit.context_sources,
+                downgraded_from: fit.downgraded_from.clone(),
+                estimated_tokens: fit.estimate.tokens(),
+                estimated_usd: fit.estimate.usd,
+            },
+        )
+        .await?;
+
+        let synthesizer = SynthesizerConfig {
+            max_context_sources: fit.context_sources,
+        };
+        let mut answer = self
+            .answer_with(job, fit.provider, sources, length, &synthesizer)
+            .await?;
+        let metadata = &mut answer.synthesis_metadata;
+        metadata.routing = routing;
+        metadata.budget = Some(BudgetReport {
+            max_cost,
+            estimate: fit.estimate,
+            context_sources: fit.context_sources,
+            downgraded_from: fit.downgraded_from,
+            spent_tokens: metadata.total_tokens(),
+            spent_usd: metadata.cost_usd,
+        });
+        Ok(answer)
+    }
+
+    /// The model that answers `job`: the fast model when routing finds the
+    /// question simple, otherwise the pipeline's own. The decision is `None`
+    /// when the pipeline does not route.
+    fn route(&self, job: &ResearchJob) -> (Arc<dyn LlmProvider>, Option<RoutingDecision>) {
+        let premium = Arc::clone(&self.llm_provider);
+        let (Some(policy), Some(fast)) = (&self.config.routing, &self.fast_provider) else {
+            return (premium, None);
+        };
+
+        let intent = job
+            .intent
+            .clone()
+            .unwrap_or_else(|| QueryIntent::classify(&job.query));
+        let complexity = QueryComplexity::assess(&job.query, &intent);
+        let (tier, reason) = policy.tier(&complexity);
+        let provider = match tier {
+            ModelTier::Fast => Arc::clone(fast),
+            ModelTier::Premium => premium,
+        };
+        let decision = RoutingDecision {
+            tier,
+            model: provider.model_id().to_string(),
+            reason,
+            complexity,
+        };
+        (provider, Some(decision))
+    }
+
+    /// Chooses the model and number of sources for synthesis within
+    /// `max_cost`: `primary` over as many sources as fit, down to
+    /// [`MIN_BUDGET_SOURCES`], then, for a dollar budget, each cheaper
+    /// comparison model in turn from the most to the least expensive.
+    fn fit_budget(
+        &self,
+        job: &ResearchJob,
+        primary: &Arc<dyn LlmProvider>,
+        max_cost: CostBudget,
+        sources: &[Source],
+        completion_tokens: usize,
+        synthesizer: &SynthesizerConfig,
+    ) -> Result<BudgetFit, PipelineError> {
+        let most = synthesizer.max_context_sources.min(sources.len());
+        let fewest = MIN_BUDGET_SOURCES.min(most);
+        let estimate_for = |provider: &dyn LlmProvider, count: usize| {
+            CostEstimate::synthesis(provider, &job.query, &sources[..count], completion_tokens)
+        };
+
+        let mut candidates = vec![Arc::clone(primary)];
+        if let CostBudget::Usd(_) = max_cost {
+            let primary_usd = estimate_for(primary.as_ref(), most).usd;
+            let mut cheaper: Vec<(f64, Arc<dyn LlmProvider>)> = self
+                .comparison_providers
+                .iter()
+                .filter(|provider| provider.model_id() != primary.model_id())
+                .filter_map(|provider| {
+                    let usd = estimate_for(provider.as_ref(), most).usd?;
+                    let cheaper = primary_usd.map_or(true, |primary| usd < primary);
+                    cheaper.then(|| (usd, Arc::clone(provider)))
+                })
+                .collect();
+            cheaper.sort_by(|a, b| b.0.total_cmp(&a.0));
+            candidates.extend(cheaper.into_iter().map(|(_, provider)| provider));
+        }
+
+        for provider in candidates {
+            let prompt_tokens =
+                synthesis_prompt_tokens(provider.as_ref(), &job.query, &sources[..most]);
+            for count in (fewest..=most).rev() {
+                let estimate =
+                    CostEstimate::new(prompt_tokens[count], completion_tokens, provider.pricing());
+                if max_cost.admits(&estimate) {
+                    let downgraded_from = (provider.model_id() != primary.model_id())
+                        .then(|| primary.model_id().to_string());
+                    return Ok(BudgetFit {
+                        provider,
+                        context_sources: count,
+                        estimate,
+                        downgraded_from,
+                    });
+                }
+            }
+        }
+
+        Err(PipelineError::BudgetExceeded {
+            model: primary.model_id().to_string(),
+            budget: max_cost,
+            estimated: max_cost.measure(&estimate_for(primary.as_ref(), fewest)),
         })
     }
+
+    /// Synthesizes one model's answer in a single pass and finishes it.
+    /// High-stakes jobs without a budget sample the answer as
+    /// `config.consistency` says and keep what most samples agree on.
+    async fn answer_with(
+        &self,
+        job: &ResearchJob,
+        provider: Arc<dyn LlmProvider>,
+        sources: &[Source],
+        length: Option<&LengthPolicy>,
+        synthesizer: &SynthesizerConfig,
+    ) -> Result<ResearchAnswer, PipelineError> {
+        let synthesizer = Synthesizer::new(Arc::clone(&provider), synthesizer.clone());
+        let policy = &self.config.consistency;
+        let (samples, temperature) = if job.high_stakes && job.max_cost.is_none() {
+            (policy.samples, Some(policy.temperature))
+        } else {
+            (1, None)
+        };
+
+        let runs = (0..samples).map(|_| async {
+            let (result, exchange) = synthesizer
+                .synthesize_captured(
+                    &job.query,
+                    sources,
+                    length,
+                    job.answer_schema.as_ref(),
+                    job.style,
+                    temperature,
+                )
+                .await;
+            self.capture(
+                job,
+                provider.as_ref(),
+                "synthesis",
+                exchange,
+                result.as_ref().err(),
+            )
+            .await;
+            result
+        });
+        let mut answers = Vec::with_capacity(samples);
+        let mut error = None;
+        for result in future::join_all(runs).await {
+            match result {
+                Ok(answer) => answers.push(answer),
+                Err(e) => error = error.or(Some(e)),
+            }
+        }
+        let answer = match temperature {
+            Some(_) => policy.reconcile(answers),
+            None => answers.pop(),
+        };
+        let Some(answer) = answer else {
+            return Err(PipelineError::Synthesis {
+                model: provider.model_id().to_string(),
+                error: error.unwrap_or_else(|| LlmError::Provider("no answer".to_string())),
+            });
+        };
+        if let Some(ref report) = answer.synthesis_metadata.consistency {
+            self.record(
+                job,
+                JobEventKind::ConsistencyChecked {
+                    samples: report.samples,
+                    answered: report.answered,
+                    disputed: report.disputed.len(),
+                },
+            )
+            .await?;
+        }
+        self.finish(job, &provider, answer, sources).await
+    }
+
+    /// Whether `job`'s answer is outlined and written section by section:
+    /// exhaustive answers are, unless they follow an answer schema.
+    fn outlines(&self, job: &ResearchJob) -> bool {
+        self.config.outline.enabled
+            && job.depth == AnswerDepth::Exhaustive
+            && job.answer_schema.is_none()
+    }
+
+    /// Outlines the answer, writes every section at once over the sources
+    /// the outline gave it, and assembles the sections into one answer. An
+    /// outline the model gets wrong falls back to a single pass.
+    async fn answer_outlined(
+        &self,
+        job: &ResearchJob,
+        provider: Arc<dyn LlmProvider>,
+        sources: &[Source],
+        length: Option<&LengthPolicy>,
+        synthesizer: &SynthesizerConfig,
+    ) -> Result<ResearchAnswer, PipelineError> {
+        let context: Vec<Source> = sources
+            .iter()
+            .take(synthesizer.max_context_sources)
+            .cloned()
+            .collect();
+        let outliner = Outliner::new(Arc::clone(&provider), self.config.outline.clone());
+        let (result, exchange) = outliner.draft(&job.query, &context).await;
+        self.capture(
+            job,
+            provider.as_ref(),
+            "outline",
+            exchange,
+            result.as_ref().err(),
+        )
+        .await;
+        let outline = match result {
+            Ok(outline) => outline,
+            Err(e) => {
+                self.record(
+                    job,
+                    JobEventKind::OutlineSkipped {
+                        reason: e.to_string(),
+                    },
+                )
+                .await?;
+                return self
+                    .answer_with(job, provider, sources, length, synthesizer)
+                    .await;
+            }
+        };
+        self.record(
+            job,
+            JobEventKind::OutlineDrafted {
+                sections: outline.sections.iter().map(|s| s.title.clone()).collect(),
+            },
+        )
+        .await?;
+
+        let section_length = outline.section_length(length);
+        let section_synthesizer = Synthesizer::new(
+            Arc::clone(&provider),
+            SynthesizerConfig {
+                max_context_sources: self.config.outline.sources_per_section,
+            },
+        );
+        let runs = outline.sections.iter().map(|section| {
+            let (synthesizer, length) = (&section_synthesizer, &section_length);
+            let (provider, context) = (&provider, &context);
+            async move {
+                let section_sources = section.sources(context);
+                let (result, exchange) = synthesizer
+                    .synthesize_captured(
+                        &section.query(&job.query),
+                        &section_sources,
+                        Some(length),
+                        None,
+                        job.style,
+                        None,
+                    )
+                    .await;
+                self.capture(
+                    job,
+                    provider.as_ref(),
+                    "synthesis",
+                    exchange,
+                    result.as_ref().err(),
+                )
+                .await;
+                result.map(|mut answer| {
+                    highlight::locate_quotes(&mut answer, &section_sources);
+                    answer
+                })
+            }
+        });
+        let answers = future::join_all(runs)
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|error| PipelineError::Synthesis {
+                model: provider.model_id().to_string(),
+                error,
+            })?;
+
+        let mut answer = outline.assemble(answers);
+        if let Some(ref mut usage) = answer.synthesis_metadata.context {
+            *usage = ContextUsage::new(
+                usage.window_tokens,
+                usage.prompt_tokens,
+                sources.len(),
+                &context,
+            );
+        }
+        self.finish(job, &provider, answer, sources).await
+    }
+
+    /// Adds the key entities to a synthesized answer when the job asked for
+    /// them, prices it, locates its quotes in the sources, checks its links
+    /// and numbers its citations, then checks it against the job's answer
+    /// schema, runs the plugins' post-processing, maps its sentences to
+    /// their evidence and moderates it.
+    async fn finish(
+        &self,
+        job: &ResearchJob,
+        provider: &Arc<dyn LlmProvider>,
+        mut answer: ResearchAnswer,
+        sources: &[Source],
+    ) -> Result<ResearchAnswer, PipelineError> {
+        if job.extract_entities {
+            self.extract_entities(job, provider, &mut answer, sources)
+                .await?;
+        }
+        if let Some(pricing) = provider.pricing() {
+            answer.synthesis_metadata.cost_usd = answer
+                .synthesis_metadata
+                .cost_on(provider.model_id(), &pricing);
+        }
+        highlight::locate_quotes(&mut answer, sources);
+        if let Some(ref checker) = self.link_checker {
+            let report =
+                links::check_links(checker.as_ref(), &mut answer, sources, &self.config.links)
+                    .await;
+            self.record(
+                job,
+                JobEventKind::LinksChecked {
+                    checked: report.checked,
+                    dead: report.dead,
+                },
+            )
+            .await?;
+        }
+        if self.config.citation_markers == CitationMarkers::Numeric {
+            number_references(&mut answer, sources);
+        }
+        self.record(
+            job,
+            JobEventKind::AnswerSynthesized {
+                model: answer.synthesis_metadata.model.clone(),
+                tokens_used: answer.synthesis_metadata.tokens_used,
+                duration_ms: answer.synthesis_metadata.synthesis_duration.as_millis() as u64,
+            },
+        )
+        .await?;
+
+        if let Some(ref schema) = job.answer_schema {
+            let checked = match answer.structured {
+                Some(ref payload) => schema.validate(payload),
+                None => Err(vec!["no structured payload was returned".to_string()]),
+            };
+            checked.map_err(|errors| PipelineError::StructuredAnswer { errors })?;
+        }
+
+        self.post_process(job, &mut answer).await?;
+        map_evidence(&mut answer);
+        self.moderate(job, &mut answer).await?;
+        Ok(answer)
+    }
+
+    /// The query `job`'s search is planned from, as the plugins rewrote it.
+    async fn rewrite_query(&self, job: &ResearchJob) -> Result<String, PipelineError> {
+        let mut query = job.query.clone();
+        for plugin in &self.plugins {
+            let mut rewritten = query.clone();
+            let result = plugin.rewrite_query(&mut rewritten).await.and_then(|()| {
+                if rewritten.trim().is_empty() {
+                    Err(PluginError::InvalidOutput("empty query".to_string()))
+                } else {
+                    Ok(())
+                }
+            });
+            match result {
+                Ok(()) if rewritten != query => {
+                    self.record(
+                        job,
+                        JobEventKind::QueryRewritten {
+                            plugin: plugin.plugin_name().to_string(),
+                            query: rewritten.clone(),
+                        },
+                    )
+                    .await?;
+                    query = rewritten;
+                }
+                Ok(()) => {}
+                Err(e) => {
+                    self.record_plugin_failure(job, plugin.as_ref(), PluginHook::RewriteQuery, e)
+                        .await?
+                }
+            }
+        }
+        Ok(query)
+    }
+
+    /// Lets each plugin drop sources the job must not use.
+    async fn filter_sources(
+        &self,
+        job: &ResearchJob,
+        sources: &mut Vec<Source>,
+    ) -> Result<(), PipelineError> {
+        for plugin in &self.plugins {
+            let mut filtered = sources.clone();
+            match plugin.filter_sources(&job.query, &mut filtered).await {
+                Ok(()) => *sources = filtered,
+                Err(e) => {
+                    self.record_plugin_failure(job, plugin.as_ref(), PluginHook::FilterSources, e)
+                        .await?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Lets each plugin edit the synthesized answer.
+    async fn post_process(
+        &self,
+        job: &ResearchJob,
+        answer: &mut ResearchAnswer,
+    ) -> Result<(), PipelineError> {
+        for plugin in &self.plugins {
+            let mut edited = answer.clone();
+            match plugin.post_process(&job.query, &mut edited).await {
+                Ok(()) => *answer = edited,
+                Err(e) => {
+                    self.record_plugin_failure(job, plugin.as_ref(), PluginHook::PostProcess, e)
+                        .await?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn record_plugin_failure(
+        &self,
+        job: &ResearchJob,
+        plugin: &dyn PipelinePlugin,
+        hook: PluginHook,
+        error: PluginError,
+    ) -> Result<(), PipelineError> {
+        self.record(
+            job,
+            JobEventKind::PluginFailed {
+                plugin: plugin.plugin_name().to_string(),
+                hook,
+                reason: error.to_string(),
+            },
+        )
+        .await
+    }
+
+    /// Lists the key entities of the sources `answer` cites with it. A failed
+    /// extraction is recorded and leaves the answer as it was.
+    async fn extract_entities(
+        &self,
+        job: &ResearchJob,
+        provider: &Arc<dyn LlmProvider>,
+        answer: &mut ResearchAnswer,
+        sources: &[Source],
+    ) -> Result<(), PipelineError> {
+        let extractor = EntityExtractor::new(Arc::clone(provider));
+        let (result, exchange) = extractor.extract(answer, sources).await;
+        self.capture(
+            job,
+            provider.as_ref(),
+            "extraction",
+            exchange,
+            result.as_ref().err(),
+        )
+        .await;
+
+        let kind = match result {
+            Ok(extraction) => {
+                answer.synthesis_metadata.record_usage(StageTokenUsage::new(
+                    LlmStage::Extraction,
+                    &extraction.model,
+                    &extraction.usage,
+                ));
+                answer.key_entities = extraction.entities;
+                JobEventKind::EntitiesExtracted {
+                    count: answer.key_entities.len(),
+                }
+            }
+            Err(e) => JobEventKind::EntityExtractionFailed {
+                reason: e.to_string(),
+            },
+        };
+        self.record(job, kind).await?;
+        Ok(())
+    }
+
+    /// Adds the facts stated by the sources `answer` cites to the knowledge
+    /// base, drawing them with the model that wrote the answer when the
+    /// pipeline has it. A failed extraction is recorded and adds nothing.
+    async fn record_facts(
+        &self,
+        job: &ResearchJob,
+        answer: &mut ResearchAnswer,
+        sources: &[Source],
+    ) -> Result<(), PipelineError> {
+        let model = answer.synthesis_metadata.model.clone();
+        let provider = self
+            .comparison_provider(&model)
+            .unwrap_or_else(|| Arc::clone(&self.llm_provider));
+        let extractor = FactExtractor::new(Arc::clone(&provider), self.config.facts.clone());
+        let (result, exchange) = extractor
+            .extract(&job.id, &job.query, answer, sources)
+            .await;
+        self.capture(
+            job,
+            provider.as_ref(),
+            "facts",
+            exchange,
+            result.as_ref().err(),
+        )
+        .await;
+
+        let kind = match result {
+            Ok(extraction) => {
+                let metadata = &mut answer.synthesis_metadata;
+                metadata.record_usage(StageTokenUsage::new(
+                    LlmStage::Extraction,
+                    &extraction.model,
+                    &extraction.usage,
+                ));
+                if let Some(pricing) = provider.pricing().filter(|_| provider.model_id() == model) {
+                    metadata.cost_usd = metadata.cost_on(&model, &pricing);
+                }
+                self.store.record_facts(&extraction.facts).await?;
+                JobEventKind::FactsRecorded {
+                    count: extraction.facts.len(),
+                }
+            }
+            Err(e) => JobEventKind::FactExtractionFailed {
+                reason: e.to_string(),
+            },
+        };
+        self.record(job, kind).await?;
+        Ok(())
+    }
+
+    /// Answers with every model of the job at once, over the same sources,
+    /// and stores each model's outcome. The first model that answers provides
+    /// the job's answer; the job fails only when none does.
+    async fn compare_models(
+        &self,
+        job: &ResearchJob,
+        sources: &[Source],
+        length: Option<&LengthPolicy>,
+        synthesizer: &SynthesizerConfig,
+    ) -> Result<ResearchAnswer, PipelineError> {
+        let runs = job.models.iter().map(|model| async move {
+            match self.comparison_provider(model) {
+                Some(provider) => {
+                    self.answer_with(job, provider, sources, length, synthesizer)
+                        .await
+                }
+                None => Err(PipelineError::Synthesis {
+                    model: model.clone(),
+                    error: LlmError::ModelUnavailable {
+                        model: model.clone(),
+                    },
+                }),
+            }
+        });
+        let results = future::join_all(runs).await;
+
+        let mut answers = Vec::with_capacity(results.len());
+        let mut primary = None;
+        let mut first_error = None;
+        for (model, result) in job.models.iter().zip(results) {
+            match result {
+                Ok(answer) => {
+                    if primary.is_none() {
+                        primary = Some(answer.clone());
+                    }
+                    answers.push(ModelAnswer::answered(model, answer));
+                }
+                Err(e @ PipelineError::Store(_)) => return Err(e),
+                Err(e) => {
+                    answers.push(ModelAnswer::failed(model, e.to_string()));
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        self.store
+            .store_comparison(&job.id, &ModelComparison::new(answers))
+            .await?;
+
+        match primary {
+            Some(answer) => Ok(answer),
+            None => Err(first_error.unwrap_or_else(|| PipelineError::Synthesis {
+                model: self.llm_provider.model_id().to_string(),
+                error: LlmError::Provider("no models to compare".to_string()),
+            })),
+        }
+    }
+
+    fn comparison_provider(&self, model: &str) -> Option<Arc<dyn LlmProvider>> {
+        std::iter::once(&self.llm_provider)
+            .chain(&self.comparison_providers)
+            .find(|provider| provider.model_id() == model)
+            .cloned()
+    }
+
+    async fn moderate(
+        &self,
+        job: &ResearchJob,
+        answer: &mut ResearchAnswer,
+    ) -> Result<(), PipelineError> {
+        let policy = self.config.moderation;
+        let Some(moderator) = self.moderator.as_ref().filter(|_| policy.is_enabled()) else {
+            return Ok(());
+        };
+
+        let text = format!("{}\n\n{}", answer.summary, answer.detail);
+        let verdict = moderator
+            .moderate(&text)
+            .await
+            .map_err(|e| PipelineError::Moderation(e.to_string()))?;
+
+        self.record(
+            job,
+            JobEventKind::Moderated {
+                flagged: verdict.flagged,
+                categories: verdict.categories.clone(),
+            },
+        )
+        .await?;
+
+        if verdict.flagged && policy == ModerationPolicy::Block {
+            return Err(PipelineError::ContentBlocked {
+                categories: verdict.categories,
+            });
+        }
+
+        answer.synthesis_metadata.moderation = Some(verdict);
+        Ok(())
+    }
+
+    async fn capture(
+        &self,
+        job: &ResearchJob,
+        provider: &dyn LlmProvider,
+        stage: &str,
+        exchange: LlmExchange,
+        error: Option<&LlmError>,
+    ) {
+        let Some(ref sink) = self.artifact_sink else {
+            return;
+        };
+
+        let mut artifact = LlmArtifact::new(
+            stage,
+            provider.provider_name(),
+            provider.model_id(),
+            exchange,
+        );
+        if let Some(e) = error {
+            artifact = artifact.with_error(e.to_string());
+        }
+
+        // Capture is a debugging aid; a failing sink must not fail the job.
+        let _ = sink.capture(&job.id, artifact.redacted()).await;
+    }
+
+    async fn capture_searches(
+        &self,
+        job: &ResearchJob,
+        search_plan: &SearchPlan,
+        attempts: &[ProviderAttempt],
+    ) {
+        let Some(ref sink) = self.artifact_sink else {
+            return;
+        };
+
+        for attempt in attempts {
+            let Some(ref raw) = attempt.raw_response else {
+                continue;
+            };
+            let Some(query) = search_plan.queries.iter().find(|q| q.text == attempt.query) else {
+                continue;
+            };
+            let artifact = SearchArtifact::new(&attempt.provider, query.clone(), raw);
+            let _ = sink.capture_search(&job.id, artifact).await;
+        }
+    }
+
+    async fn advance(&self, job: &mut ResearchJob, status: JobStatus) -> Result<(), PipelineError> {
+        job.transition_to(status.clone())?;
+        self.save(job).await?;
+        let lifecycle = if status == JobStatus::Completed {
+            LifecycleEventKind::Completed
+        } else {
+            LifecycleEventKind::StageChanged {
+                status: status.clone(),
+            }
+        };
+        self.record(job, JobEventKind::StageChanged { status })
+            .await?;
+        self.publish(job, lifecycle).await;
+        Ok(())
+    }
+
+    async fn fail<T>(
+        &self,
+        job: &mut ResearchJob,
+        error: PipelineError,
+    ) -> Result<T, PipelineError> {
+        let failure = error.failure(job.status.clone());
+        let message = failure.message.clone();
+        job.fail_with(failure)?;
+        self.save(job).await?;
+        self.record(
+            job,
+            JobEventKind::Failed {
+                message: message.clone(),
+            },
+        )
+        .await?;
+        self.publish(job, LifecycleEventKind::Failed { message })
+            .await;
+        Err(error)
+    }
+
+    /// Saves the job's progress. When another writer updated the job since
+    /// it was read, the job is re-read: the pipeline owns a running job's
+    /// progress and writes it over the newer version, but stops if the job
+    /// has meanwhile been finished elsewhere.
+    async fn save(&self, job: &mut ResearchJob) -> Result<(), PipelineError> {
+        for _ in 0..MAX_UPDATE_ATTEMPTS {
+            match self.store.update_job(job).await {
+                Ok(()) => {
+                    job.version += 1;
+                    return Ok(());
+                }
+                Err(StoreError::Conflict(_)) => {
+                    let current = self.store.get_job(&job.id).await?.ok_or_else(|| {
+                        StoreError::JobNotFound {
+                            id: job.id.to_string(),
+                        }
+                    })?;
+                    if current.status.is_terminal() {
+                        return Err(PipelineError::AlreadyFinished);
+                    }
+                    job.version = current.version;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Err(StoreError::Conflict(format!("job {} keeps changing concurrently", job.id)).into())
+    }
+
+    async fn publish(&self, job: &ResearchJob, kind: LifecycleEventKind) {
+        let Some(ref publisher) = self.event_publisher else {
+            return;
+        };
+
+        // Subscribers are notified on a best-effort basis; the job log in the
+        // store stays the source of truth.
+        let _ = publisher.publish(&LifecycleEvent::new(job, kind)).await;
+    }
+
+    async fn record_attempts(
+        &self,
+        job: &ResearchJob,
+        attempts: &[ProviderAttempt],
+    ) -> Result<(), PipelineError> {
+        let mut previous: Option<&ProviderAttempt> = None;
+
+        for attempt in attempts {
+            if let Some(prev) = previous {
+                if !prev.succeeded() && prev.query == attempt.query {
+                    self.record(
+                        job,
+                        JobEventKind::FallbackUsed {
+                            from: prev.provider.clone(),
+                            to: attempt.provider.clone(),
+                            query: attempt.query.clone(),
+                        },
+                    )
+                    .await?;
+                }
+            }
+
+            self.record(
+                job,
+                JobEventKind::ProviderAttempt {
+                    provider: attempt.provider.clone(),
+                    query: attempt.query.clone(),
+                    succeeded: attempt.succeeded(),
+                    results: *attempt.outcome.as_ref().unwrap_or(&0),
+                    error: attempt.outcome.as_ref().err().map(ToString::to_string),
+                    duration_ms: attempt.duration.as_millis() as u64,
+                },
+            )
+            .await?;
+
+            previous = Some(attempt);
+        }
+
+        Ok(())
+    }
+
+    async fn record(&self, job: &ResearchJob, kind: JobEventKind) -> Result<(), PipelineError> {
+        self.store
+            .append_event(JobEvent::new(job.id.clone(), kind))
+            .await?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::mock::{MockLlmProvider, MockSearchProvider, MockStore};
+    use crate::answer::Confidence;
+    use crate::answer_schema::AnswerSchema;
+    use crate::artifact::StoreArtifactSink;
+    use crate::budget::ModelPricing;
+    use crate::corpus::CorpusDocument;
+    use crate::freshness::Volatility;
+    use crate::highlight::QuoteLocation;
+    use crate::knowledge::FactQuery;
+    use crate::length::LengthPolicy;
+    use crate::mock::{
+        MockEmbedder, MockEventPublisher, MockLlmProvider, MockLlmStep, MockModerator,
+        MockSearchProvider, MockStore,
+    };
+    use crate::progress::{JobProgress, SEARCHED_PERCENT};
+    use crate::query::{QuestionType, TimeConstraint};
+    use crate::search::SourceLimits;
+    use crate::style::AnswerStyle;
 
     fn create_test_pipeline() -> Pipeline {
         let store: Arc<dyn Store> = Arc::new(MockStore::new());
@@ -148,7 +1650,7 @@ mod tests {
 
         assert_eq!(result.job.status, JobStatus::Completed);
         assert!(!result.sources.is_empty());
-        assert!(!result.answer.summary.is_empty());
+        assert!(!result.answer.as_ref().unwrap().summary.is_empty());
     }
 
     #[tokio::test]
@@ -168,6 +1670,47 @@ mod tests {
         assert_eq!(final_job.status, JobStatus::Completed);
     }
 
+    #[tokio::test]
+    async fn pipeline_rereads_job_updated_concurrently() {
+        let pipeline = create_test_pipeline();
+        let job = ResearchJob::new("What is Rust?").unwrap();
+        pipeline.store.create_job(&job).await.unwrap();
+
+        let mut other = job.clone();
+        other.transition_to(JobStatus::Planning).unwrap();
+        pipeline.store.update_job(&other).await.unwrap();
+
+        let result = pipeline.run(job).await.unwrap();
+
+        assert_eq!(result.job.status, JobStatus::Completed);
+        let stored = pipeline
+            .store
+            .get_job(&result.job.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.status, JobStatus::Completed);
+        assert_eq!(stored.version, result.job.version);
+    }
+
+    #[tokio::test]
+    async fn pipeline_stops_when_job_finished_elsewhere() {
+        let pipeline = create_test_pipeline();
+        let job = ResearchJob::new("What is Rust?").unwrap();
+        pipeline.store.create_job(&job).await.unwrap();
+
+        let mut other = job.clone();
+        other.fail("cancelled").unwrap();
+        pipeline.store.update_job(&other).await.unwrap();
+
+        let result = pipeline.run(job.clone()).await;
+
+        assert!(matches!(result, Err(PipelineError::AlreadyFinished)));
+        let stored = pipeline.store.get_job(&job.id).await.unwrap().unwrap();
+        assert_eq!(stored.status, JobStatus::Failed);
+        assert_eq!(stored.error_message.as_deref(), Some("cancelled"));
+    }
+
     #[tokio::test]
     async fn pipeline_stores_sources() {
         let store: Arc<dyn Store> = Arc::new(MockStore::new());
@@ -184,4 +1727,1718 @@ mod tests {
         let sources = store.get_sources(&job_id).await.unwrap();
         assert!(!sources.is_empty());
     }
+
+    #[tokio::test]
+    async fn pipeline_stores_answer() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let search: Arc<dyn SearchProvider> = Arc::new(MockSearchProvider::new("mock"));
+        let llm: Arc<dyn LlmProvider> = Arc::new(MockLlmProvider::new("mock-gpt-4"));
+
+        let pipeline = Pipeline::new(Arc::clone(&store), search, llm);
+        let job = ResearchJob::new("Test query").unwrap();
+        let job_id = job.id.clone();
+
+        store.create_job(&job).await.unwrap();
+        pipeline.run(job).await.unwrap();
+
+        let answer = store.get_answer(&job_id).await.unwrap().unwrap();
+        assert!(answer.synthesis_metadata.moderation.is_none());
+    }
+
+    fn moderated_pipeline(
+        store: Arc<dyn Store>,
+        moderator: Arc<MockModerator>,
+        policy: ModerationPolicy,
+    ) -> Pipeline {
+        let search: Arc<dyn SearchProvider> = Arc::new(MockSearchProvider::new("mock"));
+        let llm: Arc<dyn LlmProvider> = Arc::new(MockLlmProvider::new("mock-gpt-4"));
+
+        Pipeline::new(store, search, llm)
+            .with_config(PipelineConfig {
+                moderation: policy,
+                ..Default::default()
+            })
+            .with_moderator(moderator)
+    }
+
+    #[tokio::test]
+    async fn pipeline_skips_moderation_when_off() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let moderator = Arc::new(MockModerator::flagging(["violence"]));
+        let pipeline =
+            moderated_pipeline(Arc::clone(&store), moderator.clone(), ModerationPolicy::Off);
+        let job = ResearchJob::new("Test query").unwrap();
+
+        store.create_job(&job).await.unwrap();
+        let result = pipeline.run(job).await.unwrap();
+
+        assert_eq!(moderator.call_count(), 0);
+        assert!(result
+            .answer
+            .as_ref()
+            .unwrap()
+            .synthesis_metadata
+            .moderation
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn pipeline_records_flagged_verdict() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let moderator = Arc::new(MockModerator::flagging(["violence"]));
+        let pipeline = moderated_pipeline(Arc::clone(&store), moderator, ModerationPolicy::Flag);
+        let job = ResearchJob::new("Test query").unwrap();
+        let job_id = job.id.clone();
+
+        store.create_job(&job).await.unwrap();
+        let result = pipeline.run(job).await.unwrap();
+
+        let verdict = result
+            .answer
+            .unwrap()
+            .synthesis_metadata
+            .moderation
+            .unwrap();
+        assert!(verdict.flagged);
+        let stored = store.get_answer(&job_id).await.unwrap().unwrap();
+        assert!(stored.synthesis_metadata.moderation.is_some());
+    }
+
+    #[tokio::test]
+    async fn pipeline_blocks_flagged_answer() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let moderator = Arc::new(MockModerator::flagging(["self-harm"]));
+        let pipeline = moderated_pipeline(Arc::clone(&store), moderator, ModerationPolicy::Block);
+        let job = ResearchJob::new("Test query").unwrap();
+        let job_id = job.id.clone();
+
+        store.create_job(&job).await.unwrap();
+        let result = pipeline.run(job).await;
+
+        assert!(matches!(result, Err(PipelineError::ContentBlocked { .. })));
+        let final_job = store.get_job(&job_id).await.unwrap().unwrap();
+        assert_eq!(final_job.status, JobStatus::Failed);
+        assert!(store.get_answer(&job_id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn pipeline_keeps_clean_answer_under_block_policy() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let moderator = Arc::new(MockModerator::new());
+        let pipeline = moderated_pipeline(Arc::clone(&store), moderator, ModerationPolicy::Block);
+        let job = ResearchJob::new("Test query").unwrap();
+
+        store.create_job(&job).await.unwrap();
+        let result = pipeline.run(job).await.unwrap();
+
+        assert_eq!(result.job.status, JobStatus::Completed);
+        assert!(
+            !result
+                .answer
+                .unwrap()
+                .synthesis_metadata
+                .moderation
+                .unwrap()
+                .flagged
+        );
+    }
+
+    #[tokio::test]
+    async fn pipeline_records_event_log() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let search: Arc<dyn SearchProvider> = Arc::new(MockSearchProvider::new("mock"));
+        let llm: Arc<dyn LlmProvider> = Arc::new(MockLlmProvider::new("mock-gpt-4"));
+
+        let pipeline = Pipeline::new(Arc::clone(&store), search, llm);
+        let job = ResearchJob::new("Test query").unwrap();
+        let job_id = job.id.clone();
+
+        store.create_job(&job).await.unwrap();
+        pipeline.run(job).await.unwrap();
+
+        let events = store.get_events(&job_id).await.unwrap();
+        let names: Vec<&str> = events.iter().map(|e| e.kind.name()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "stage_changed",
+                "stage_changed",
+                "search_progressed",
+                "search_progressed",
+                "provider_attempt",
+                "sources_collected",
+                "stage_changed",
+                "answer_synthesized",
+                "stage_changed",
+            ]
+        );
+        assert_eq!(
+            events[3].kind,
+            JobEventKind::SearchProgressed {
+                executed: 1,
+                planned: 1
+            }
+        );
+        assert_eq!(JobProgress::estimate(&events).percent, 100);
+        assert!(events.windows(2).all(|w| w[0].sequence < w[1].sequence));
+        assert_eq!(
+            events.last().unwrap().kind,
+            JobEventKind::StageChanged {
+                status: JobStatus::Completed
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn sources_only_jobs_complete_without_llm_calls() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let search: Arc<dyn SearchProvider> = Arc::new(MockSearchProvider::new("mock"));
+        let llm = Arc::new(MockLlmProvider::new("mock-gpt-4"));
+        let pipeline = Pipeline::new(Arc::clone(&store), search, Arc::clone(&llm) as _);
+        let job = ResearchJob::new("What is Rust?")
+            .unwrap()
+            .with_sources_only();
+        let job_id = job.id.clone();
+
+        store.create_job(&job).await.unwrap();
+        let result = pipeline.run(job).await.unwrap();
+
+        assert_eq!(result.job.status, JobStatus::Completed);
+        assert!(result.answer.is_none());
+        assert_eq!(llm.call_count(), 0);
+        assert_eq!(
+            store.get_sources(&job_id).await.unwrap().len(),
+            result.sources.len()
+        );
+        assert!(store.get_answer(&job_id).await.unwrap().is_none());
+        let stages: Vec<JobStatus> = store
+            .get_events(&job_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter_map(|e| match e.kind {
+                JobEventKind::StageChanged { status } => Some(status),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            stages,
+            vec![
+                JobStatus::Planning,
+                JobStatus::Searching,
+                JobStatus::Completed
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn pipeline_stores_search_metadata() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let search: Arc<dyn SearchProvider> = Arc::new(MockSearchProvider::new("mock-tavily"));
+        let llm: Arc<dyn LlmProvider> = Arc::new(MockLlmProvider::new("mock-gpt-4"));
+
+        let pipeline = Pipeline::new(Arc::clone(&store), search, llm);
+        let job = ResearchJob::new("Test query").unwrap();
+        let job_id = job.id.clone();
+
+        store.create_job(&job).await.unwrap();
+        let result = pipeline.run(job).await.unwrap();
+
+        let stored = store.get_search_metadata(&job_id).await.unwrap().unwrap();
+        assert_eq!(stored.queries_executed, vec!["Test query"]);
+        assert_eq!(stored.providers_used[0].as_str(), "mock-tavily");
+        assert_eq!(stored.total_results, result.search_metadata.total_results);
+    }
+
+    #[tokio::test]
+    async fn pipeline_runs_providers_under_job_trace_id() {
+        struct TraceRecorder(std::sync::Mutex<Option<crate::id::TraceId>>);
+
+        #[async_trait::async_trait]
+        impl SearchProvider for TraceRecorder {
+            async fn search(
+                &self,
+                query: &crate::search::SearchQuery,
+            ) -> Result<Vec<crate::traits::SearchResult>, crate::traits::SearchError> {
+                *self.0.lock().unwrap() = crate::trace::current_trace_id();
+                MockSearchProvider::new("mock").search(query).await
+            }
+
+            fn provider_id(&self) -> &str {
+                "recorder"
+            }
+        }
+
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let recorder = Arc::new(TraceRecorder(std::sync::Mutex::new(None)));
+        let llm: Arc<dyn LlmProvider> = Arc::new(MockLlmProvider::new("mock-gpt-4"));
+
+        let pipeline = Pipeline::new(Arc::clone(&store), recorder.clone(), llm);
+        let job = ResearchJob::new("Test query").unwrap();
+        let trace_id = job.trace_id.clone();
+
+        store.create_job(&job).await.unwrap();
+        pipeline.run(job).await.unwrap();
+
+        assert_eq!(*recorder.0.lock().unwrap(), Some(trace_id));
+        assert!(crate::trace::current_trace_id().is_none());
+    }
+
+    #[tokio::test]
+    async fn pipeline_expands_sources_with_similar_pages() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let search: Arc<dyn SearchProvider> = Arc::new(MockSearchProvider::new("mock"));
+        let similar: Arc<dyn SearchProvider> = Arc::new(
+            MockSearchProvider::new("mock-exa").with_similar_results(vec![
+                crate::traits::SearchResult::new("https://niche.org/1", "Niche", "Snippet")
+                    .with_score(0.9),
+            ]),
+        );
+        let llm: Arc<dyn LlmProvider> = Arc::new(MockLlmProvider::new("mock-gpt-4"));
+
+        let pipeline =
+            Pipeline::new(Arc::clone(&store), search, llm).with_source_expansion(similar);
+        let job = ResearchJob::new("Test query").unwrap();
+        let job_id = job.id.clone();
+
+        store.create_job(&job).await.unwrap();
+        let result = pipeline.run(job).await.unwrap();
+
+        assert_eq!(result.sources.len(), 4);
+        assert!(result
+            .sources
+            .iter()
+            .any(|s| s.url == "https://niche.org/1"));
+        assert!(result
+            .search_metadata
+            .providers_used
+            .iter()
+            .any(|p| p.as_str() == "mock-exa"));
+        assert_eq!(store.get_sources(&job_id).await.unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn pipeline_searches_corpus_only_for_jobs_including_it() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let search: Arc<dyn SearchProvider> = Arc::new(MockSearchProvider::new("mock"));
+        let embedder: Arc<dyn Embedder> = Arc::new(MockEmbedder::new());
+        let document = crate::corpus::index_document(
+            embedder.as_ref(),
+            CorpusDocument::new("Handbook", "Internal handbook text about the test query.")
+                .with_workspace("team-a"),
+        )
+        .await
+        .unwrap();
+        let url = document.citation_url();
+        store.store_document(&document).await.unwrap();
+        let llm: Arc<dyn LlmProvider> = Arc::new(MockLlmProvider::new("mock-gpt-4"));
+        let pipeline = Pipeline::new(Arc::clone(&store), search, llm).with_corpus(embedder);
+
+        let web_only = ResearchJob::new("Test query")
+            .unwrap()
+            .with_workspace("team-a");
+        store.create_job(&web_only).await.unwrap();
+        let result = pipeline.run(web_only).await.unwrap();
+        assert!(result.sources.iter().all(|s| s.url != url));
+
+        let other_workspace = ResearchJob::new("Test query").unwrap().with_corpus();
+        store.create_job(&other_workspace).await.unwrap();
+        let result = pipeline.run(other_workspace).await.unwrap();
+        assert!(result.sources.iter().all(|s| s.url != url));
+
+        let hybrid = ResearchJob::new("Test query")
+            .unwrap()
+            .with_corpus()
+            .with_workspace("team-a");
+        store.create_job(&hybrid).await.unwrap();
+        let result = pipeline.run(hybrid).await.unwrap();
+        assert_eq!(result.sources.len(), 4);
+        assert!(result.sources.iter().any(|s| s.url == url));
+        assert!(result
+            .search_metadata
+            .providers_used
+            .iter()
+            .any(|p| p.as_str() == CORPUS_PROVIDER_ID));
+    }
+
+    #[tokio::test]
+    async fn pipeline_leaves_out_the_web_for_corpus_only_jobs() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let search: Arc<dyn SearchProvider> = Arc::new(MockSearchProvider::new("mock"));
+        let embedder: Arc<dyn Embedder> = Arc::new(MockEmbedder::new());
+        let document = crate::corpus::index_document(
+            embedder.as_ref(),
+            CorpusDocument::new("Install guide", "Install the test query tool with cargo.")
+                .with_url("https://docs.example.com/install"),
+        )
+        .await
+        .unwrap();
+        store.store_document(&document).await.unwrap();
+        let llm: Arc<dyn LlmProvider> = Arc::new(MockLlmProvider::new("mock-gpt-4"));
+        let pipeline = Pipeline::new(Arc::clone(&store), search, llm).with_corpus(embedder);
+
+        let job = ResearchJob::new("Test query").unwrap().with_corpus_only();
+        store.create_job(&job).await.unwrap();
+        let result = pipeline.run(job).await.unwrap();
+
+        assert_eq!(result.sources.len(), 1);
+        assert_eq!(result.sources[0].url, "https://docs.example.com/install");
+        let providers: Vec<_> = result
+            .search_metadata
+            .providers_used
+            .iter()
+            .map(|p| p.as_str())
+            .collect();
+        assert_eq!(providers, vec![CORPUS_PROVIDER_ID]);
+    }
+
+    #[tokio::test]
+    async fn pipeline_fails_job_and_records_search_error() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let search: Arc<dyn SearchProvider> =
+            Arc::new(MockSearchProvider::new("mock").fail_after(0));
+        let llm: Arc<dyn LlmProvider> = Arc::new(MockLlmProvider::new("mock-gpt-4"));
+
+        let pipeline = Pipeline::new(Arc::clone(&store), search, llm);
+        let job = ResearchJob::new("Test query").unwrap();
+        let job_id = job.id.clone();
+
+        store.create_job(&job).await.unwrap();
+        let result = pipeline.run(job).await;
+
+        let error = result.unwrap_err();
+        assert!(matches!(error, PipelineError::Search(_)));
+        let final_job = store.get_job(&job_id).await.unwrap().unwrap();
+        assert_eq!(final_job.status, JobStatus::Failed);
+        let failure = final_job.failure.unwrap();
+        assert_eq!(failure.stage, JobStatus::Searching);
+        assert_eq!(failure.kind, error.code());
+        assert_eq!(failure.message, error.to_string());
+
+        let events = store.get_events(&job_id).await.unwrap();
+        assert!(matches!(
+            events[4].kind,
+            JobEventKind::ProviderAttempt {
+                succeeded: false,
+                ..
+            }
+        ));
+        assert!(matches!(
+            events.last().unwrap().kind,
+            JobEventKind::Failed { .. }
+        ));
+        let progress = JobProgress::estimate(&events);
+        assert_eq!(progress.percent, SEARCHED_PERCENT);
+        assert_eq!(progress.stage, JobStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn pipeline_fails_job_on_synthesis_error() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let search: Arc<dyn SearchProvider> = Arc::new(MockSearchProvider::new("mock"));
+        let llm: Arc<dyn LlmProvider> = Arc::new(MockLlmProvider::new("mock-gpt-4").fail_after(0));
+
+        let pipeline = Pipeline::new(Arc::clone(&store), search, llm);
+        let job = ResearchJob::new("Test query").unwrap();
+        let job_id = job.id.clone();
+
+        store.create_job(&job).await.unwrap();
+        let result = pipeline.run(job).await;
+
+        assert!(matches!(result, Err(PipelineError::Synthesis { .. })));
+        let final_job = store.get_job(&job_id).await.unwrap().unwrap();
+        assert_eq!(final_job.status, JobStatus::Failed);
+        assert_eq!(
+            final_job.failure,
+            Some(JobFailure {
+                stage: JobStatus::Synthesizing,
+                kind: ErrorCode::RateLimited,
+                provider: Some("mock-gpt-4".to_string()),
+                retryable: true,
+                message: "synthesis failed: rate limited by provider".to_string(),
+            })
+        );
+        assert!(final_job
+            .error_message
+            .unwrap()
+            .contains("synthesis failed"));
+    }
+
+    #[tokio::test]
+    async fn pipeline_reuses_sources_of_retried_synthesis_failure() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let failing = Pipeline::new(
+            Arc::clone(&store),
+            Arc::new(MockSearchProvider::new("mock")),
+            Arc::new(MockLlmProvider::new("mock-gpt-4").fail_after(0)),
+        );
+        let job = ResearchJob::new("Test query").unwrap();
+        store.create_job(&job).await.unwrap();
+        failing.run(job.clone()).await.unwrap_err();
+        let failed = store.get_job(&job.id).await.unwrap().unwrap();
+
+        let retry = crate::retry::create_retry(store.as_ref(), &failed)
+            .await
+            .unwrap();
+        let search = Arc::new(MockSearchProvider::new("mock"));
+        let pipeline = Pipeline::new(
+            Arc::clone(&store),
+            Arc::clone(&search) as Arc<dyn SearchProvider>,
+            Arc::new(MockLlmProvider::new("mock-gpt-4")),
+        );
+        let result = pipeline.run(retry).await.unwrap();
+
+        assert_eq!(result.job.status, JobStatus::Completed);
+        assert_eq!(search.call_count(), 0);
+        let original = store.get_sources(&job.id).await.unwrap();
+        assert_eq!(result.sources.len(), original.len());
+        assert_eq!(result.sources[0].id, original[0].id);
+    }
+
+    /// A pipeline over four sources of 55 estimated tokens each, whose
+    /// answers may use a thousand tokens, with `cheaper` to downgrade to.
+    fn budget_pipeline(
+        store: Arc<dyn Store>,
+        llm: MockLlmProvider,
+        cheaper: MockLlmProvider,
+    ) -> Pipeline {
+        let results = (1..=4)
+            .map(|i| {
+                crate::traits::SearchResult::new(
+                    format!("https://example.com/{}", i),
+                    format!("Page {}", i),
+                    "Snippet",
+                )
+            })
+            .collect();
+        let search = MockSearchProvider::new("mock").with_results(results);
+
+        Pipeline::new(store, Arc::new(search), Arc::new(llm))
+            .with_config(PipelineConfig {
+                length: LengthPolicies::uniform(LengthPolicy::new(1000, "Be brief.")),
+                ..Default::default()
+            })
+            .with_comparison_models([Arc::new(cheaper) as Arc<dyn LlmProvider>])
+    }
+
+    #[tokio::test]
+    async fn pipeline_trims_sources_to_fit_budget() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let pipeline = budget_pipeline(
+            Arc::clone(&store),
+            MockLlmProvider::new("mock-gpt-4"),
+            MockLlmProvider::new("mock-mini"),
+        );
+        // Two sources come to 1,914 estimated tokens, three to 1,969.
+        let job = ResearchJob::new("What is Rust?")
+            .unwrap()
+            .with_max_cost(CostBudget::Tokens(1950));
+        store.create_job(&job).await.unwrap();
+
+        let result = pipeline.run(job).await.unwrap();
+
+        let metadata = &result.answer.as_ref().unwrap().synthesis_metadata;
+        assert_eq!(metadata.model, "mock-gpt-4");
+        assert!(result
+            .answer
+            .as_ref()
+            .unwrap()
+            .summary
+            .starts_with("Based on 2 sources"));
+        let budget = metadata.budget.as_ref().unwrap();
+        assert_eq!(budget.context_sources, 2);
+        assert_eq!(budget.estimate.tokens(), 1914);
+        assert_eq!(budget.spent_tokens, 500);
+        assert!(budget.downgraded_from.is_none());
+        let events = store.get_events(&result.job.id).await.unwrap();
+        assert!(events.iter().any(|e| matches!(
+            e.kind,
+            JobEventKind::BudgetApplied {
+                context_sources: 2,
+                ..
+            }
+        )));
+    }
+
+    #[tokio::test]
+    async fn pipeline_downgrades_model_to_fit_budget() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let pipeline = budget_pipeline(
+            Arc::clone(&store),
+            MockLlmProvider::new("mock-gpt-4").with_pricing(ModelPricing::new(3.0, 15.0)),
+            MockLlmProvider::new("mock-mini").with_pricing(ModelPricing::new(0.15, 0.6)),
+        );
+        let job = ResearchJob::new("What is Rust?")
+            .unwrap()
+            .with_max_cost(CostBudget::Usd(0.01));
+        store.create_job(&job).await.unwrap();
+
+        let result = pipeline.run(job).await.unwrap();
+
+        let metadata = &result.answer.as_ref().unwrap().synthesis_metadata;
+        assert_eq!(metadata.model, "mock-mini");
+        let budget = metadata.budget.as_ref().unwrap();
+        assert_eq!(budget.downgraded_from.as_deref(), Some("mock-gpt-4"));
+        assert_eq!(budget.context_sources, 4);
+        let spent = budget.spent_usd.unwrap();
+        assert!((spent - 0.00012).abs() < 1e-9);
+        assert_eq!(metadata.cost_usd, Some(spent));
+    }
+
+    #[tokio::test]
+    async fn pipeline_fails_job_over_budget() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let pipeline = budget_pipeline(
+            Arc::clone(&store),
+            MockLlmProvider::new("mock-gpt-4"),
+            MockLlmProvider::new("mock-mini"),
+        );
+        let job = ResearchJob::new("What is Rust?")
+            .unwrap()
+            .with_max_cost(CostBudget::Tokens(1900));
+        let job_id = job.id.clone();
+        store.create_job(&job).await.unwrap();
+
+        let result = pipeline.run(job).await;
+
+        let err = result.unwrap_err();
+        assert!(matches!(
+            err,
+            PipelineError::BudgetExceeded {
+                estimated: Some(CostBudget::Tokens(tokens)),
+                ..
+            } if tokens == 1914
+        ));
+        let stored = store.get_job(&job_id).await.unwrap().unwrap();
+        let failure = stored.failure.unwrap();
+        assert_eq!(failure.kind, ErrorCode::BudgetExceeded);
+        assert_eq!(failure.provider.as_deref(), Some("mock-gpt-4"));
+        assert!(store.get_answer(&job_id).await.unwrap().is_none());
+    }
+
+    fn routing_pipeline(store: Arc<dyn Store>) -> Pipeline {
+        let search: Arc<dyn SearchProvider> = Arc::new(MockSearchProvider::new("mock"));
+        let premium: Arc<dyn LlmProvider> = Arc::new(MockLlmProvider::new("mock-gpt-4"));
+        let fast: Arc<dyn LlmProvider> = Arc::new(MockLlmProvider::new("mock-mini"));
+
+        Pipeline::new(store, search, premium)
+            .with_config(PipelineConfig {
+                routing: Some(RoutingPolicy::default()),
+                ..Default::default()
+            })
+            .with_fast_model(fast)
+    }
+
+    #[tokio::test]
+    async fn pipeline_routes_simple_question_to_fast_model() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let pipeline = routing_pipeline(Arc::clone(&store));
+        let job = ResearchJob::new("What is the capital of France?").unwrap();
+        store.create_job(&job).await.unwrap();
+
+        let result = pipeline.run(job).await.unwrap();
+
+        let metadata = &result.answer.as_ref().unwrap().synthesis_metadata;
+        assert_eq!(metadata.model, "mock-mini");
+        let routing = metadata.routing.as_ref().unwrap();
+        assert_eq!(routing.tier, ModelTier::Fast);
+        assert_eq!(routing.reason, "simple factual question");
+        let events = store.get_events(&result.job.id).await.unwrap();
+        assert!(events.iter().any(|e| matches!(
+            e.kind,
+            JobEventKind::ModelRouted {
+                tier: ModelTier::Fast,
+                ..
+            }
+        )));
+    }
+
+    #[tokio::test]
+    async fn pipeline_routes_complex_question_to_premium_model() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let pipeline = routing_pipeline(Arc::clone(&store));
+        let job = ResearchJob::new("Who founded the company that makes the iPhone?").unwrap();
+        store.create_job(&job).await.unwrap();
+
+        let result = pipeline.run(job).await.unwrap();
+
+        let metadata = &result.answer.as_ref().unwrap().synthesis_metadata;
+        assert_eq!(metadata.model, "mock-gpt-4");
+        let routing = metadata.routing.as_ref().unwrap();
+        assert_eq!(routing.tier, ModelTier::Premium);
+        assert_eq!(routing.complexity.hops, 2);
+    }
+
+    #[tokio::test]
+    async fn pipeline_skips_routing_without_fast_model() {
+        let pipeline = create_test_pipeline().with_config(PipelineConfig {
+            routing: Some(RoutingPolicy::default()),
+            ..Default::default()
+        });
+        let job = ResearchJob::new("What is Rust?").unwrap();
+        pipeline.store.create_job(&job).await.unwrap();
+
+        let result = pipeline.run(job).await.unwrap();
+
+        assert_eq!(
+            result.answer.as_ref().unwrap().synthesis_metadata.model,
+            "mock-gpt-4"
+        );
+        assert!(result
+            .answer
+            .as_ref()
+            .unwrap()
+            .synthesis_metadata
+            .routing
+            .is_none());
+    }
+
+    fn comparison_pipeline(store: Arc<dyn Store>) -> Pipeline {
+        Pipeline::new(
+            store,
+            Arc::new(MockSearchProvider::new("mock")),
+            Arc::new(MockLlmProvider::new("mock-gpt-4")),
+        )
+        .with_comparison_models([
+            Arc::new(MockLlmProvider::new("mock-claude").fail_after(0)) as Arc<dyn LlmProvider>,
+            Arc::new(MockLlmProvider::new("mock-llama").with_confidence(Confidence::Low)),
+        ])
+    }
+
+    #[tokio::test]
+    async fn pipeline_compares_models_over_same_sources() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let pipeline = comparison_pipeline(Arc::clone(&store));
+        let job = ResearchJob::new("Test query").unwrap().with_models([
+            "mock-claude",
+            "mock-llama",
+            "mock-gpt-4",
+            "mock-unknown",
+        ]);
+        let job_id = job.id.clone();
+
+        store.create_job(&job).await.unwrap();
+        let result = pipeline.run(job).await.unwrap();
+
+        assert_eq!(
+            result.answer.as_ref().unwrap().synthesis_metadata.model,
+            "mock-llama"
+        );
+        let comparison = store.get_comparison(&job_id).await.unwrap().unwrap();
+        let models: Vec<_> = comparison
+            .answers
+            .iter()
+            .map(|a| a.model.as_str())
+            .collect();
+        assert_eq!(
+            models,
+            ["mock-claude", "mock-llama", "mock-gpt-4", "mock-unknown"]
+        );
+        assert!(comparison.answers[0].error.is_some());
+        assert!(comparison.answers[3]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("not available"));
+        assert!(!comparison.agreement().unwrap().confidence_agrees);
+
+        let synthesized = store
+            .get_events(&job_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|e| matches!(e.kind, JobEventKind::AnswerSynthesized { .. }))
+            .count();
+        assert_eq!(synthesized, 2);
+    }
+
+    #[tokio::test]
+    async fn pipeline_fails_comparison_when_no_model_answers() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let pipeline = comparison_pipeline(Arc::clone(&store));
+        let job = ResearchJob::new("Test query")
+            .unwrap()
+            .with_models(["mock-claude", "mock-unknown"]);
+        let job_id = job.id.clone();
+
+        store.create_job(&job).await.unwrap();
+        let result = pipeline.run(job).await;
+
+        assert!(matches!(result, Err(PipelineError::Synthesis { .. })));
+        let final_job = store.get_job(&job_id).await.unwrap().unwrap();
+        assert_eq!(final_job.status, JobStatus::Failed);
+        assert!(store.get_answer(&job_id).await.unwrap().is_none());
+        assert_eq!(
+            store
+                .get_comparison(&job_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .answers
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn pipeline_locates_cited_quotes() {
+        let pipeline = create_test_pipeline();
+        let job = ResearchJob::new("What is Rust?").unwrap();
+
+        pipeline.store.create_job(&job).await.unwrap();
+        let result = pipeline.run(job).await.unwrap();
+
+        assert!(!result.answer.as_ref().unwrap().citations.is_empty());
+        for citation in &result.answer.as_ref().unwrap().citations {
+            let Some(QuoteLocation::Found { start, end, .. }) = citation.quote_location else {
+                panic!("quote of {:?} not located", citation.claim);
+            };
+            let source = result
+                .sources
+                .iter()
+                .find(|s| s.id == citation.source_id)
+                .unwrap();
+            assert_eq!(
+                highlight::char_slice(&source.content, start, end),
+                citation.quote.as_deref().unwrap()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn pipeline_numbers_citations() {
+        let pipeline = create_test_pipeline();
+        let job = ResearchJob::new("What is Rust?").unwrap();
+
+        pipeline.store.create_job(&job).await.unwrap();
+        let result = pipeline.run(job).await.unwrap();
+
+        let answer = result.answer.unwrap();
+        assert!(answer.detail.contains("findings [1][2][3]."));
+        assert!(!answer.detail.contains("src_"));
+        assert_eq!(answer.references.len(), 3);
+        for (citation, reference) in answer.citations.iter().zip(&answer.references) {
+            assert_eq!(citation.reference, Some(reference.number));
+            assert_eq!(citation.source_id, reference.source_id);
+        }
+    }
+
+    #[tokio::test]
+    async fn pipeline_keeps_source_id_markers_when_configured() {
+        let pipeline = create_test_pipeline().with_config(PipelineConfig {
+            citation_markers: CitationMarkers::SourceId,
+            ..Default::default()
+        });
+        let job = ResearchJob::new("What is Rust?").unwrap();
+
+        pipeline.store.create_job(&job).await.unwrap();
+        let result = pipeline.run(job).await.unwrap();
+
+        let answer = result.answer.unwrap();
+        assert!(answer.detail.contains("[src_"));
+        assert!(answer.references.is_empty());
+    }
+
+    #[tokio::test]
+    async fn pipeline_captures_redacted_artifacts() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let search: Arc<dyn SearchProvider> = Arc::new(MockSearchProvider::new("mock"));
+        let llm: Arc<dyn LlmProvider> =
+            Arc::new(
+                MockLlmProvider::new("mock-gpt-4").with_script([MockLlmStep::Raw(
+                    "garbled output for ops@example.com".into(),
+                )]),
+            );
+
+        let pipeline = Pipeline::new(Arc::clone(&store), search, llm)
+            .with_artifact_sink(Arc::new(StoreArtifactSink::new(Arc::clone(&store))));
+        let job = ResearchJob::new("Test query").unwrap();
+        let job_id = job.id.clone();
+
+        store.create_job(&job).await.unwrap();
+        let result = pipeline.run(job).await;
+
+        assert!(matches!(result, Err(PipelineError::Synthesis { .. })));
+        let artifacts = store.get_artifacts(&job_id).await.unwrap();
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0].stage, "synthesis");
+        assert_eq!(artifacts[0].model, "mock-gpt-4");
+        assert_eq!(
+            artifacts[0].raw_response.as_deref(),
+            Some("garbled output for [EMAIL]")
+        );
+        assert!(artifacts[0]
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("failed to parse"));
+    }
+
+    async fn captured_prompt(config: PipelineConfig, job: ResearchJob) -> Vec<String> {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let pipeline = create_test_pipeline()
+            .with_config(config)
+            .with_artifact_sink(Arc::new(StoreArtifactSink::new(Arc::clone(&store))));
+        let job_id = job.id.clone();
+
+        pipeline.store.create_job(&job).await.unwrap();
+        pipeline.run(job).await.unwrap();
+
+        let artifacts = store.get_artifacts(&job_id).await.unwrap();
+        artifacts[0]
+            .messages
+            .iter()
+            .map(|m| m.content.clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn pipeline_classifies_intent() {
+        let pipeline = create_test_pipeline();
+        let job = ResearchJob::new("Why is the sky blue?").unwrap();
+        let job_id = job.id.clone();
+
+        pipeline.store.create_job(&job).await.unwrap();
+        let result = pipeline.run(job).await.unwrap();
+
+        let intent = result.job.intent.unwrap();
+        assert_eq!(intent.question_type, QuestionType::Explanation);
+        let stored = pipeline.store.get_job(&job_id).await.unwrap().unwrap();
+        assert_eq!(
+            stored.intent.unwrap().question_type,
+            QuestionType::Explanation
+        );
+    }
+
+    #[tokio::test]
+    async fn pipeline_snippets_sources_with_query_terms() {
+        let pipeline = create_test_pipeline();
+        let job = ResearchJob::new("Which example article discusses the subject?").unwrap();
+        let job_id = job.id.clone();
+
+        pipeline.store.create_job(&job).await.unwrap();
+        let result = pipeline.run(job).await.unwrap();
+
+        let source = &result.sources[0];
+        let snippet = source.snippet.as_ref().unwrap();
+        assert_eq!(snippet.text, source.content);
+        let terms: Vec<&str> = snippet.matches.iter().map(|m| m.term.as_str()).collect();
+        assert_eq!(terms, vec!["example", "article", "discusses", "subject"]);
+        let first = &snippet.matches[0];
+        assert_eq!(&snippet.text[first.start..first.end], "example");
+        let stored = pipeline.store.get_sources(&job_id).await.unwrap();
+        assert!(stored.iter().all(|s| s.snippet.is_some()));
+    }
+
+    #[tokio::test]
+    async fn pipeline_dates_answers_by_topic_volatility() {
+        let pipeline = create_test_pipeline();
+        let job = ResearchJob::new("What is the latest news on the Rust Foundation?").unwrap();
+        let job_id = job.id.clone();
+
+        pipeline.store.create_job(&job).await.unwrap();
+        let started = Utc::now();
+        pipeline.run(job).await.unwrap();
+
+        let stored = pipeline.store.get_answer(&job_id).await.unwrap().unwrap();
+        let freshness = stored.freshness.unwrap();
+        assert_eq!(freshness.volatility, Volatility::Breaking);
+        assert!(freshness.expires_at >= started + chrono::Duration::hours(6));
+        assert!(freshness.expires_at <= Utc::now() + chrono::Duration::hours(6));
+    }
+
+    #[tokio::test]
+    async fn pipeline_applies_length_policy_for_question_type() {
+        let job = ResearchJob::new("What is Rust?").unwrap();
+
+        let prompt = captured_prompt(PipelineConfig::default(), job).await;
+
+        let factual = LengthPolicy::default_for(QuestionType::Factual);
+        assert_eq!(
+            prompt,
+            vec![factual.instruction, "What is Rust?".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn pipeline_applies_depth_budgets_and_length() {
+        for (depth, sources) in [(AnswerDepth::Tldr, 3), (AnswerDepth::Exhaustive, 12)] {
+            let store: Arc<dyn Store> = Arc::new(MockStore::new());
+            let pipeline = Pipeline::new(
+                Arc::clone(&store),
+                Arc::new(MockSearchProvider::new("mock").with_result_count(12)),
+                Arc::new(MockLlmProvider::new("mock-gpt-4")),
+            )
+            .with_artifact_sink(Arc::new(StoreArtifactSink::new(Arc::clone(&store))));
+            let job = ResearchJob::new("What is Rust?").unwrap().with_depth(depth);
+            store.create_job(&job).await.unwrap();
+
+            let result = pipeline.run(job).await.unwrap();
+
+            assert_eq!(result.sources.len(), sources, "{:?}", depth);
+            let artifacts = store.get_artifacts(&result.job.id).await.unwrap();
+            let synthesis = artifacts.iter().find(|a| a.stage == "synthesis").unwrap();
+            assert_eq!(
+                synthesis.messages[0].content,
+                depth.length_policy().unwrap().instruction
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn pipeline_writes_exhaustive_answers_by_section() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let outline = r#"{"sections": [
+            {"title": "Origins", "focus": "Where Rust came from", "sources": [1, 2]},
+            {"title": "Adoption", "focus": "Who uses it", "sources": [3]}
+        ]}"#;
+        let pipeline = Pipeline::new(
+            Arc::clone(&store),
+            Arc::new(MockSearchProvider::new("mock").with_result_count(12)),
+            Arc::new(
+                MockLlmProvider::new("mock-gpt-4")
+                    .with_script([MockLlmStep::Raw(outline.to_string())]),
+            ),
+        )
+        .with_artifact_sink(Arc::new(StoreArtifactSink::new(Arc::clone(&store))));
+        let job = ResearchJob::new("What is Rust?")
+            .unwrap()
+            .with_depth(AnswerDepth::Exhaustive);
+        store.create_job(&job).await.unwrap();
+
+        let result = pipeline.run(job).await.unwrap();
+
+        let answer = result.answer.as_ref().unwrap();
+        assert!(answer.detail.starts_with("## Origins\n\n"));
+        assert!(answer.detail.contains("\n\n## Adoption\n\n"));
+        let titles: Vec<&str> = answer.sections.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["Origins", "Adoption"]);
+        assert_eq!(answer.sections[0].source_ids.len(), 2);
+        assert_eq!(answer.sections[1].citations.len(), 1);
+        assert_eq!(answer.citations.len(), 3);
+        let stages: Vec<LlmStage> = answer
+            .synthesis_metadata
+            .stage_usage
+            .iter()
+            .map(|u| u.stage)
+            .collect();
+        assert_eq!(stages, [LlmStage::Outline, LlmStage::Synthesis]);
+        let context = answer.synthesis_metadata.context.as_ref().unwrap();
+        assert_eq!(context.prompt_tokens, 400);
+        assert_eq!(context.sources_available, result.sources.len());
+        assert_eq!(
+            context.sources_used,
+            result.sources.len().min(EXHAUSTIVE_CONTEXT_SOURCES)
+        );
+
+        let artifacts = store.get_artifacts(&result.job.id).await.unwrap();
+        let stages: Vec<&str> = artifacts.iter().map(|a| a.stage.as_str()).collect();
+        assert_eq!(stages, ["outline", "synthesis", "synthesis"]);
+        let events = store.get_events(&result.job.id).await.unwrap();
+        assert!(events.iter().any(|e| matches!(
+            e.kind,
+            JobEventKind::OutlineDrafted { ref sections } if sections.len() == 2
+        )));
+    }
+
+    #[tokio::test]
+    async fn pipeline_falls_back_to_one_pass_without_an_outline() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let pipeline = Pipeline::new(
+            Arc::clone(&store),
+            Arc::new(MockSearchProvider::new("mock").with_result_count(12)),
+            Arc::new(MockLlmProvider::new("mock-gpt-4")),
+        );
+        let job = ResearchJob::new("What is Rust?")
+            .unwrap()
+            .with_depth(AnswerDepth::Exhaustive);
+        store.create_job(&job).await.unwrap();
+
+        let result = pipeline.run(job).await.unwrap();
+
+        assert!(result.answer.as_ref().unwrap().sections.is_empty());
+        let events = store.get_events(&result.job.id).await.unwrap();
+        assert!(events
+            .iter()
+            .any(|e| matches!(e.kind, JobEventKind::OutlineSkipped { .. })));
+    }
+
+    #[tokio::test]
+    async fn pipeline_extracts_key_entities_on_request() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let entities = r#"{"entities": [
+            {"name": "Mozilla", "kind": "organization", "description": "Rust's first sponsor.", "sources": [1]}
+        ]}"#;
+        let pipeline = Pipeline::new(
+            Arc::clone(&store),
+            Arc::new(MockSearchProvider::new("mock")),
+            Arc::new(
+                MockLlmProvider::new("mock-gpt-4")
+                    .with_script([MockLlmStep::Succeed, MockLlmStep::Raw(entities.to_string())]),
+            ),
+        );
+        let job = ResearchJob::new("What is Rust?")
+            .unwrap()
+            .with_entity_extraction();
+        store.create_job(&job).await.unwrap();
+
+        let result = pipeline.run(job).await.unwrap();
+
+        let answer = result.answer.as_ref().unwrap();
+        assert_eq!(answer.key_entities.len(), 1);
+        assert_eq!(answer.key_entities[0].name, "Mozilla");
+        assert_eq!(
+            answer.key_entities[0].source_ids,
+            vec![answer.citations[0].source_id.clone()]
+        );
+        assert!(answer
+            .synthesis_metadata
+            .stage_usage
+            .iter()
+            .any(|u| u.stage == LlmStage::Extraction));
+        let events = store.get_events(&result.job.id).await.unwrap();
+        assert!(events
+            .iter()
+            .any(|e| matches!(e.kind, JobEventKind::EntitiesExtracted { count: 1 })));
+    }
+
+    #[tokio::test]
+    async fn pipeline_adds_facts_to_the_knowledge_base() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let facts = r#"{"facts": [
+            {"subject": "Rust", "relation": "was started at", "object": "Mozilla", "sources": [1, 2]}
+        ]}"#;
+        let pipeline = Pipeline::new(
+            Arc::clone(&store),
+            Arc::new(MockSearchProvider::new("mock")),
+            Arc::new(
+                MockLlmProvider::new("mock-gpt-4")
+                    .with_script([MockLlmStep::Succeed, MockLlmStep::Raw(facts.to_string())]),
+            ),
+        )
+        .with_config(PipelineConfig {
+            facts: FactExtractorConfig {
+                enabled: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let job = ResearchJob::new("What is Rust?").unwrap();
+        store.create_job(&job).await.unwrap();
+
+        let result = pipeline.run(job).await.unwrap();
+
+        let known = store.find_facts(&FactQuery::new(10)).await.unwrap();
+        assert_eq!(known.len(), 1);
+        assert_eq!(known[0].sources.len(), 2);
+        assert_eq!(known[0].sources[0].job_id, result.job.id);
+        let stored = store.get_answer(&result.job.id).await.unwrap().unwrap();
+        assert!(stored
+            .synthesis_metadata
+            .stage_usage
+            .iter()
+            .any(|u| u.stage == LlmStage::Extraction));
+        let events = store.get_events(&result.job.id).await.unwrap();
+        assert!(events
+            .iter()
+            .any(|e| matches!(e.kind, JobEventKind::FactsRecorded { count: 1 })));
+    }
+
+    #[tokio::test]
+    async fn pipeline_keeps_answer_when_entity_extraction_fails() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let pipeline = Pipeline::new(
+            Arc::clone(&store),
+            Arc::new(MockSearchProvider::new("mock")),
+            Arc::new(MockLlmProvider::new("mock-gpt-4")),
+        );
+        let job = ResearchJob::new("What is Rust?")
+            .unwrap()
+            .with_entity_extraction();
+        store.create_job(&job).await.unwrap();
+
+        let result = pipeline.run(job).await.unwrap();
+
+        assert!(result.answer.as_ref().unwrap().key_entities.is_empty());
+        let events = store.get_events(&result.job.id).await.unwrap();
+        assert!(events
+            .iter()
+            .any(|e| matches!(e.kind, JobEventKind::EntityExtractionFailed { .. })));
+    }
+
+    #[tokio::test]
+    async fn pipeline_applies_job_source_limits() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let pipeline = Pipeline::new(
+            Arc::clone(&store),
+            Arc::new(MockSearchProvider::new("mock").with_result_count(12)),
+            Arc::new(MockLlmProvider::new("mock-gpt-4")),
+        );
+        let limited = |limits| {
+            ResearchJob::new("What is Rust?")
+                .unwrap()
+                .with_depth(AnswerDepth::Exhaustive)
+                .with_source_limits(limits)
+        };
+
+        for (limits, expected) in [
+            (
+                SourceLimits {
+                    max_sources: Some(4),
+                    ..Default::default()
+                },
+                4,
+            ),
+            (
+                SourceLimits {
+                    max_per_domain: Some(2),
+                    ..Default::default()
+                },
+                2,
+            ),
+        ] {
+            let job = limited(limits);
+            store.create_job(&job).await.unwrap();
+            let result = pipeline.run(job).await.unwrap();
+            assert_eq!(result.sources.len(), expected);
+        }
+    }
+
+    fn escalating_pipeline(store: Arc<dyn Store>, llm: MockLlmProvider) -> Pipeline {
+        Pipeline::new(
+            store,
+            Arc::new(MockSearchProvider::new("mock").with_result_count(12)),
+            Arc::new(llm),
+        )
+        .with_config(PipelineConfig {
+            escalation: Some(EscalationPolicy::default()),
+            ..Default::default()
+        })
+    }
+
+    fn low_confidence_step() -> MockLlmStep {
+        MockLlmStep::Raw(r#"{"summary": "Unsure", "detail": "", "confidence": "low"}"#.into())
+    }
+
+    #[tokio::test]
+    async fn pipeline_escalates_unsure_answers() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let llm = MockLlmProvider::new("mock-gpt-4")
+            .with_script([low_confidence_step(), MockLlmStep::Succeed]);
+        let pipeline = escalating_pipeline(Arc::clone(&store), llm);
+        let job = ResearchJob::new("What is the Rust borrow checker?").unwrap();
+        store.create_job(&job).await.unwrap();
+
+        let result = pipeline.run(job).await.unwrap();
+
+        let answer = result.answer.unwrap();
+        assert_eq!(answer.confidence, Confidence::High);
+        let report = answer.synthesis_metadata.escalation.unwrap();
+        assert_eq!(report.initial_confidence, Confidence::Low);
+        assert!(report.replaced);
+        assert_eq!(report.max_sources, EXHAUSTIVE_MAX_SOURCES);
+        assert!(report.queries.len() > 1);
+        assert_eq!(report.error, None);
+        assert_eq!(result.sources.len(), 10 + report.sources_added);
+        let stored = store.get_sources(&result.job.id).await.unwrap();
+        assert_eq!(stored.len(), result.sources.len());
+        let events = store.get_events(&result.job.id).await.unwrap();
+        assert!(events
+            .iter()
+            .any(|e| matches!(e.kind, JobEventKind::Escalated { .. })));
+    }
+
+    #[tokio::test]
+    async fn pipeline_keeps_first_answer_when_escalation_is_less_sure() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let llm = MockLlmProvider::new("mock-gpt-4")
+            .with_confidence(Confidence::Insufficient)
+            .with_script([low_confidence_step()]);
+        let pipeline = escalating_pipeline(Arc::clone(&store), llm);
+        let job = ResearchJob::new("What is Rust?").unwrap();
+        store.create_job(&job).await.unwrap();
+
+        let result = pipeline.run(job).await.unwrap();
+
+        let answer = result.answer.unwrap();
+        assert_eq!(answer.summary, "Unsure");
+        let report = answer.synthesis_metadata.escalation.unwrap();
+        assert!(!report.replaced);
+        assert_eq!(result.sources.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn pipeline_keeps_first_answer_when_escalation_fails() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let llm = MockLlmProvider::new("mock-gpt-4").with_script([
+            low_confidence_step(),
+            MockLlmStep::Fail(LlmError::RateLimited {
+                retry_after_secs: None,
+            }),
+        ]);
+        let pipeline = escalating_pipeline(Arc::clone(&store), llm);
+        let job = ResearchJob::new("What is Rust?").unwrap();
+        store.create_job(&job).await.unwrap();
+
+        let result = pipeline.run(job).await.unwrap();
+
+        assert_eq!(result.job.status, JobStatus::Completed);
+        let answer = result.answer.unwrap();
+        assert_eq!(answer.confidence, Confidence::Low);
+        let report = answer.synthesis_metadata.escalation.unwrap();
+        assert!(!report.replaced);
+        assert!(report.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn pipeline_returns_confident_answers_without_escalating() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let llm = MockLlmProvider::new("mock-gpt-4");
+        let pipeline = escalating_pipeline(Arc::clone(&store), llm);
+        let job = ResearchJob::new("What is Rust?").unwrap();
+        store.create_job(&job).await.unwrap();
+
+        let result = pipeline.run(job).await.unwrap();
+
+        assert_eq!(result.answer.unwrap().synthesis_metadata.escalation, None);
+    }
+
+    #[tokio::test]
+    async fn pipeline_samples_high_stakes_answers() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let llm = Arc::new(MockLlmProvider::new("mock-gpt-4"));
+        let pipeline = Pipeline::new(
+            Arc::clone(&store),
+            Arc::new(MockSearchProvider::new("mock")),
+            Arc::clone(&llm) as Arc<dyn LlmProvider>,
+        )
+        .with_artifact_sink(Arc::new(StoreArtifactSink::new(Arc::clone(&store))));
+        let job = ResearchJob::new("What is Rust?")
+            .unwrap()
+            .with_high_stakes();
+        store.create_job(&job).await.unwrap();
+
+        let result = pipeline.run(job).await.unwrap();
+
+        assert_eq!(llm.call_count(), 3);
+        let answer = result.answer.unwrap();
+        let report = answer.synthesis_metadata.consistency.as_ref().unwrap();
+        assert_eq!((report.samples, report.answered), (3, 3));
+        assert_eq!(report.agreement, 1.0);
+        assert!(report.disputed.is_empty());
+        assert_eq!(answer.citations.len(), 3);
+        assert_eq!(answer.synthesis_metadata.total_tokens(), 1500);
+        let artifacts = store.get_artifacts(&result.job.id).await.unwrap();
+        assert_eq!(artifacts.len(), 3);
+        assert!(artifacts
+            .iter()
+            .all(|a| a.messages.iter().any(|m| m.content == "Temperature: 0.7")));
+        let events = store.get_events(&result.job.id).await.unwrap();
+        assert!(events.iter().any(|e| matches!(
+            e.kind,
+            JobEventKind::ConsistencyChecked {
+                samples: 3,
+                answered: 3,
+                disputed: 0,
+            }
+        )));
+    }
+
+    #[tokio::test]
+    async fn pipeline_reconciles_the_samples_that_answered() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let llm = MockLlmProvider::new("mock-gpt-4").with_script([MockLlmStep::Fail(
+            LlmError::RateLimited {
+                retry_after_secs: None,
+            },
+        )]);
+        let pipeline = Pipeline::new(
+            Arc::clone(&store),
+            Arc::new(MockSearchProvider::new("mock")),
+            Arc::new(llm),
+        )
+        .with_config(PipelineConfig {
+            consistency: ConsistencyPolicy::new(2, 1.0),
+            ..Default::default()
+        });
+        let job = ResearchJob::new("What is Rust?")
+            .unwrap()
+            .with_high_stakes();
+        store.create_job(&job).await.unwrap();
+
+        let result = pipeline.run(job).await.unwrap();
+
+        let answer = result.answer.unwrap();
+        let report = answer.synthesis_metadata.consistency.unwrap();
+        assert_eq!((report.samples, report.answered), (2, 1));
+        assert_eq!(report.temperature, 1.0);
+        assert_eq!(answer.citations.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn pipeline_explains_insufficient_answers() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let pipeline = Pipeline::new(
+            Arc::clone(&store),
+            Arc::new(MockSearchProvider::new("mock")),
+            Arc::new(MockLlmProvider::new("mock-gpt-4").with_confidence(Confidence::Insufficient)),
+        );
+        let job = ResearchJob::new("What is the Rust borrow checker?").unwrap();
+        store.create_job(&job).await.unwrap();
+
+        let result = pipeline.run(job).await.unwrap();
+
+        let why = result.answer.unwrap().why_insufficient.unwrap();
+        assert_eq!(why.searched, result.search_metadata.queries_executed);
+        assert!(!why.searched.is_empty());
+        assert_eq!(why.missing_sources.len(), 1);
+        assert_eq!(why.reformulations, vec!["Rust borrow checker"]);
+    }
+
+    #[tokio::test]
+    async fn pipeline_drops_explanation_from_answered_questions() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let llm = MockLlmProvider::new("mock-gpt-4").with_script([MockLlmStep::Raw(
+            r#"{"summary": "A language.", "detail": "", "why_insufficient": {"missing_sources": ["Docs"]}}"#
+                .into(),
+        )]);
+        let pipeline = Pipeline::new(
+            Arc::clone(&store),
+            Arc::new(MockSearchProvider::new("mock")),
+            Arc::new(llm),
+        );
+        let job = ResearchJob::new("What is Rust?").unwrap();
+        store.create_job(&job).await.unwrap();
+
+        let result = pipeline.run(job).await.unwrap();
+
+        assert!(result.answer.unwrap().why_insufficient.is_none());
+    }
+
+    #[tokio::test]
+    async fn pipeline_narrows_search_to_question_period() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let search = Arc::new(MockSearchProvider::new("mock"));
+        let pipeline = Pipeline::new(
+            Arc::clone(&store),
+            search.clone(),
+            Arc::new(MockLlmProvider::new("mock-gpt-4")),
+        );
+        let job = ResearchJob::new("Rust layoffs since 2021").unwrap();
+        store.create_job(&job).await.unwrap();
+
+        let result = pipeline.run(job).await.unwrap();
+
+        let filters = &search.queries()[0].filters;
+        assert_eq!(
+            filters.published_after.map(|d| d.to_rfc3339()),
+            Some("2021-01-01T00:00:00+00:00".to_string())
+        );
+        assert!(matches!(
+            result.search_metadata.time_constraint,
+            Some(TimeConstraint::DateRange { .. })
+        ));
+    }
+
+    #[test]
+    fn standard_depth_keeps_config() {
+        let config = PipelineConfig::default();
+        let standard = config.for_depth(AnswerDepth::Standard);
+        let tldr = config.for_depth(AnswerDepth::Tldr);
+
+        assert_eq!(standard.executor.max_sources, config.executor.max_sources);
+        assert_eq!(standard.length, config.length);
+        assert_eq!(tldr.synthesizer.max_context_sources, 3);
+        assert_eq!(
+            tldr.length.get(QuestionType::Comparison),
+            AnswerDepth::Tldr.length_policy().as_ref()
+        );
+    }
+
+    #[tokio::test]
+    async fn pipeline_keeps_preset_intent() {
+        let job = ResearchJob::new("What is Rust?")
+            .unwrap()
+            .with_intent(QueryIntent::new(QuestionType::Comparison));
+
+        let prompt = captured_prompt(PipelineConfig::default(), job).await;
+
+        let comparison = LengthPolicy::default_for(QuestionType::Comparison);
+        assert_eq!(prompt[0], comparison.instruction);
+    }
+
+    #[tokio::test]
+    async fn pipeline_skips_length_policy_when_disabled() {
+        let config = PipelineConfig {
+            length: LengthPolicies::disabled(),
+            ..Default::default()
+        };
+        let job = ResearchJob::new("What is Rust?").unwrap();
+
+        let prompt = captured_prompt(config, job).await;
+
+        assert_eq!(prompt, vec!["What is Rust?".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn pipeline_writes_in_job_style() {
+        let config = PipelineConfig {
+            length: LengthPolicies::disabled(),
+            ..Default::default()
+        };
+        let job = ResearchJob::new("What is Rust?")
+            .unwrap()
+            .with_style(AnswerStyle::Executive);
+
+        let prompt = captured_prompt(config, job).await;
+
+        assert_eq!(prompt, vec!["Answer style: executive", "What is Rust?"]);
+    }
+
+    fn pros_cons_schema() -> AnswerSchema {
+        AnswerSchema::new(
+            "pros_cons",
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "pros": {"type": "array", "items": {"type": "string"}, "minItems": 1},
+                    "cons": {"type": "array", "items": {"type": "string"}}
+                },
+                "required": ["pros", "cons"]
+            }),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn pipeline_returns_structured_answer_for_schema() {
+        let pipeline = create_test_pipeline();
+        let job = ResearchJob::new("Should I learn Rust?")
+            .unwrap()
+            .with_answer_schema(pros_cons_schema());
+        let job_id = job.id.clone();
+
+        pipeline.store.create_job(&job).await.unwrap();
+        let result = pipeline.run(job).await.unwrap();
+
+        let expected = serde_json::json!({"pros": ["sample"], "cons": ["sample"]});
+        assert_eq!(
+            result.answer.as_ref().unwrap().structured,
+            Some(expected.clone())
+        );
+        let stored = pipeline.store.get_answer(&job_id).await.unwrap().unwrap();
+        assert_eq!(stored.structured, Some(expected));
+    }
+
+    #[tokio::test]
+    async fn pipeline_fails_when_structured_answer_does_not_match_schema() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let llm = MockLlmProvider::new("mock-gpt-4").with_script([MockLlmStep::Raw(
+            r#"{"summary": "s", "detail": "d", "structured": {"pros": []}}"#.into(),
+        )]);
+        let pipeline = Pipeline::new(
+            Arc::clone(&store),
+            Arc::new(MockSearchProvider::new("mock")),
+            Arc::new(llm),
+        );
+        let job = ResearchJob::new("Should I learn Rust?")
+            .unwrap()
+            .with_answer_schema(pros_cons_schema());
+        let job_id = job.id.clone();
+
+        store.create_job(&job).await.unwrap();
+        let result = pipeline.run(job).await;
+
+        let Err(PipelineError::StructuredAnswer { errors }) = result else {
+            panic!("expected a structured answer error, got {:?}", result);
+        };
+        assert_eq!(
+            errors,
+            vec![
+                "/: missing required property \"cons\"",
+                "/pros: expected at least 1 items, got 0",
+            ]
+        );
+        let job = store.get_job(&job_id).await.unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Failed);
+        assert!(store.get_answer(&job_id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn pipeline_skips_capture_without_sink() {
+        let store = Arc::new(MockStore::new());
+        let pipeline = Pipeline::new(
+            Arc::clone(&store) as Arc<dyn Store>,
+            Arc::new(MockSearchProvider::new("mock")),
+            Arc::new(MockLlmProvider::new("mock-gpt-4")),
+        );
+        let job = ResearchJob::new("Test query").unwrap();
+        let job_id = job.id.clone();
+
+        store.create_job(&job).await.unwrap();
+        pipeline.run(job).await.unwrap();
+
+        assert!(store.get_artifacts(&job_id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn pipeline_publishes_lifecycle_events() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let publisher = Arc::new(MockEventPublisher::new());
+        let pipeline = Pipeline::new(
+            Arc::clone(&store),
+            Arc::new(MockSearchProvider::new("mock")),
+            Arc::new(MockLlmProvider::new("mock-gpt-4")),
+        )
+        .with_event_publisher(publisher.clone());
+        let job = ResearchJob::new("Test query").unwrap();
+
+        store.create_job(&job).await.unwrap();
+        pipeline.run(job.clone()).await.unwrap();
+
+        let kinds: Vec<_> = publisher.events().into_iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                LifecycleEventKind::StageChanged {
+                    status: JobStatus::Planning
+                },
+                LifecycleEventKind::StageChanged {
+                    status: JobStatus::Searching
+                },
+                LifecycleEventKind::StageChanged {
+                    status: JobStatus::Synthesizing
+                },
+                LifecycleEventKind::Completed,
+            ]
+        );
+        assert!(publisher
+            .events()
+            .iter()
+            .all(|e| e.job_id == job.id && e.trace_id == job.trace_id));
+    }
+
+    #[tokio::test]
+    async fn pipeline_publishes_failure() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let publisher = Arc::new(MockEventPublisher::new());
+        let pipeline = Pipeline::new(
+            Arc::clone(&store),
+            Arc::new(MockSearchProvider::new("mock").fail_after(0)),
+            Arc::new(MockLlmProvider::new("mock-gpt-4")),
+        )
+        .with_event_publisher(publisher.clone());
+        let job = ResearchJob::new("Test query").unwrap();
+
+        store.create_job(&job).await.unwrap();
+        let _ = pipeline.run(job).await;
+
+        let last = publisher.events().pop().unwrap();
+        assert!(matches!(last.kind, LifecycleEventKind::Failed { .. }));
+    }
+
+    /// Rewrites queries, keeps two sources and tags answers.
+    struct TaggingPlugin;
+
+    #[async_trait::async_trait]
+    impl PipelinePlugin for TaggingPlugin {
+        async fn rewrite_query(&self, query: &mut String) -> Result<(), PluginError> {
+            query.push_str(" internal");
+            Ok(())
+        }
+
+        async fn filter_sources(
+            &self,
+            _query: &str,
+            sources: &mut Vec<Source>,
+        ) -> Result<(), PluginError> {
+            sources.truncate(2);
+            Ok(())
+        }
+
+        async fn post_process(
+            &self,
+            _query: &str,
+            answer: &mut ResearchAnswer,
+        ) -> Result<(), PluginError> {
+            answer.summary = format!("[reviewed] {}", answer.summary);
+            Ok(())
+        }
+
+        fn plugin_name(&self) -> &str {
+            "tagging"
+        }
+    }
+
+    struct FailingPlugin;
+
+    #[async_trait::async_trait]
+    impl PipelinePlugin for FailingPlugin {
+        async fn filter_sources(
+            &self,
+            _query: &str,
+            sources: &mut Vec<Source>,
+        ) -> Result<(), PluginError> {
+            sources.clear();
+            Err(PluginError::Exhausted("fuel"))
+        }
+
+        fn plugin_name(&self) -> &str {
+            "failing"
+        }
+    }
+
+    #[tokio::test]
+    async fn pipeline_runs_plugin_hooks() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let pipeline = Pipeline::new(
+            Arc::clone(&store),
+            Arc::new(MockSearchProvider::new("mock").with_result_count(5)),
+            Arc::new(MockLlmProvider::new("mock-gpt-4")),
+        )
+        .with_plugins([Arc::new(TaggingPlugin) as Arc<dyn PipelinePlugin>]);
+        let job = ResearchJob::new("What is Rust?").unwrap();
+        store.create_job(&job).await.unwrap();
+
+        let result = pipeline.run(job).await.unwrap();
+
+        assert_eq!(result.job.query, "What is Rust?");
+        assert_eq!(result.sources.len(), 2);
+        assert!(result.answer.unwrap().summary.starts_with("[reviewed] "));
+        let events = store.get_events(&result.job.id).await.unwrap();
+        assert!(events.iter().any(|e| e.kind
+            == JobEventKind::QueryRewritten {
+                plugin: "tagging".to_string(),
+                query: "What is Rust? internal".to_string(),
+            }));
+        assert!(events.iter().any(|e| matches!(
+            e.kind,
+            JobEventKind::ProviderAttempt { ref query, .. } if query.ends_with(" internal")
+        )));
+    }
+
+    #[tokio::test]
+    async fn pipeline_keeps_input_of_failed_plugin_hook() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let pipeline = Pipeline::new(
+            Arc::clone(&store),
+            Arc::new(MockSearchProvider::new("mock").with_result_count(5)),
+            Arc::new(MockLlmProvider::new("mock-gpt-4")),
+        )
+        .with_plugins([Arc::new(FailingPlugin) as Arc<dyn PipelinePlugin>]);
+        let job = ResearchJob::new("What is Rust?").unwrap();
+        store.create_job(&job).await.unwrap();
+
+        let result = pipeline.run(job).await.unwrap();
+
+        assert_eq!(result.sources.len(), 5);
+        let events = store.get_events(&result.job.id).await.unwrap();
+        assert!(events.iter().any(|e| matches!(
+            e.kind,
+            JobEventKind::PluginFailed {
+                hook: PluginHook::FilterSources,
+                ..
+            }
+        )));
+    }
 }
@@ -0,0 +1,432 @@
+//! Outlines for long-form answers.
+//!
+//! A single completion asked for an exhaustive report tends to thin out as
+//! it goes: late sections get a sentence each and cite little. The
+//! [`Outliner`] asks the model for a plan first — the report's sections, each
+//! with the sources it should draw on — so every section can then be
+//! synthesized on its own, over its own sources, and the sections assembled
+//! into one report.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::answer::{AnswerSection, Confidence, LlmStage, ResearchAnswer, StageTokenUsage};
+use crate::artifact::LlmExchange;
+use crate::budget::DEFAULT_COMPLETION_TOKENS;
+use crate::chat::{ChatRequest, Message, TokenUsage};
+use crate::id::SourceId;
+use crate::length::LengthPolicy;
+use crate::source::Source;
+use crate::traits::{LlmError, LlmProvider};
+
+/// Fewest sections an outline needs; with fewer, one completion does as well.
+pub const MIN_OUTLINE_SECTIONS: usize = 2;
+
+/// Completion tokens the outline itself may use.
+const OUTLINE_MAX_TOKENS: usize = 1024;
+
+/// Fewest completion tokens a section may use, however many there are.
+pub const MIN_SECTION_TOKENS: usize = 1024;
+
+/// Characters of each source's content shown to the outlining model.
+const MAX_EXCERPT_CHARS: usize = 300;
+
+const OUTLINE_INSTRUCTIONS: &str = "\
+You plan long research reports. Given a question and numbered sources, \
+divide the report that answers it into sections that each cover a distinct \
+part of the answer, in the order a reader needs them. For each section, give \
+a short title, one sentence on what it covers, and the numbers of the \
+sources it should draw on. Reply with JSON only, in the form \
+{\"sections\": [{\"title\": \"...\", \"focus\": \"...\", \"sources\": [1, 3]}]}.";
+
+const SECTION_INSTRUCTION: &str = "\
+Write the detail as the body of this one section: cover its part of the \
+question in depth, from every source given, without a heading of its own and \
+without an overview or conclusion of the whole report.";
+
+#[derive(Clone, Debug)]
+pub struct OutlinerConfig {
+    /// Whether exhaustive answers are outlined before they are synthesized.
+    pub enabled: bool,
+    pub max_sections: usize,
+    /// Most sources one section is synthesized over.
+    pub sources_per_section: usize,
+}
+
+impl Default for OutlinerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_sections: 6,
+            sources_per_section: 6,
+        }
+    }
+}
+
+/// One planned section of a report.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutlineSection {
+    pub title: String,
+    /// What the section covers, as the outlining model described it.
+    pub focus: String,
+    pub source_ids: Vec<SourceId>,
+}
+
+/// The sections of a report, planned before it is written.
+#[derive(Clone, Debug)]
+pub struct Outline {
+    pub sections: Vec<OutlineSection>,
+    pub model: String,
+    pub usage: TokenUsage,
+}
+
+#[derive(Deserialize)]
+struct RawOutline {
+    sections: Vec<RawSection>,
+}
+
+#[derive(Deserialize)]
+struct RawSection {
+    title: String,
+    #[serde(default)]
+    focus: String,
+    #[serde(default)]
+    sources: Vec<usize>,
+}
+
+pub struct Outliner {
+    provider: Arc<dyn LlmProvider>,
+    config: OutlinerConfig,
+}
+
+impl Outliner {
+    pub fn new(provider: Arc<dyn LlmProvider>, config: OutlinerConfig) -> Self {
+        Self { provider, config }
+    }
+
+    /// Asks the model to outline the answer to `query` over `sources`, and
+    /// returns its exchange with the model alongside the outline. Fails when
+    /// the reply is not an outline of at least [`MIN_OUTLINE_SECTIONS`]
+    /// sections with sources.
+    pub async fn draft(
+        &self,
+        query: &str,
+        sources: &[Source],
+    ) -> (Result<Outline, LlmError>, LlmExchange) {
+        let messages = vec![
+            Message::system(OUTLINE_INSTRUCTIONS),
+            Message::user(outline_prompt(query, sources)),
+        ];
+        let mut exchange = LlmExchange {
+            messages: messages.clone(),
+            raw_response: None,
+        };
+
+        let request = ChatRequest::new(messages).with_max_tokens(OUTLINE_MAX_TOKENS);
+        let result = match self.provider.chat(request).await {
+            Ok(response) => {
+                let sections = self.parse(&response.content, sources);
+                exchange.raw_response = Some(response.content);
+                sections.map(|sections| Outline {
+                    sections,
+                    model: response.model,
+                    usage: response.usage,
+                })
+            }
+            Err(e) => Err(e),
+        };
+
+        (result, exchange)
+    }
+
+    /// Reads the sections out of the model's reply. Source numbers outside
+    /// `sources` are dropped, as are sections left without any source.
+    fn parse(&self, content: &str, sources: &[Source]) -> Result<Vec<OutlineSection>, LlmError> {
+        let json = match (content.find('{'), content.rfind('}')) {
+            (Some(start), Some(end)) if start < end => &content[start..=end],
+            _ => {
+                return Err(LlmError::Provider(
+                    "outline response contains no JSON object".to_string(),
+                ))
+            }
+        };
+        let raw: RawOutline = serde_json::from_str(json)
+            .map_err(|e| LlmError::Provider(format!("failed to parse outline: {}", e)))?;
+
+        let sections: Vec<OutlineSection> = raw
+            .sections
+            .into_iter()
+            .filter_map(|section| {
+                let mut seen = HashSet::new();
+                let source_ids: Vec<SourceId> = section
+                    .sources
+                    .into_iter()
+                    .filter(|n| seen.insert(*n))
+                    .filter_map(|n| n.checked_sub(1).and_then(|i| sources.get(i)))
+                    .take(self.config.sources_per_section)
+                    .map(|s| s.id.clone())
+                    .collect();
+                let title = section.title.trim();
+                (!title.is_empty() && !source_ids.is_empty()).then(|| OutlineSection {
+                    title: title.to_string(),
+                    focus: section.focus.trim().to_string(),
+                    source_ids,
+                })
+            })
+            .take(self.config.max_sections)
+            .collect();
+
+        if sections.len() < MIN_OUTLINE_SECTIONS {
+            return Err(LlmError::Provider(format!(
+                "outline has {} usable sections, need at least {}",
+                sections.len(),
+                MIN_OUTLINE_SECTIONS
+            )));
+        }
+        Ok(sections)
+    }
+}
+
+fn outline_prompt(query: &str, sources: &[Source]) -> String {
+    let mut prompt = format!("Question: {}\n\nSources:\n", query);
+    for (i, source) in sources.iter().enumerate() {
+        let excerpt: String = source.content.chars().take(MAX_EXCERPT_CHARS).collect();
+        prompt.push_str(&format!(
+            "\n[{}] {} ({})\n{}\n",
+            i + 1,
+            source.title,
+            source.url,
+            excerpt.trim()
+        ));
+    }
+    prompt
+}
+
+impl OutlineSection {
+    /// The question a section is synthesized from: the job's question,
+    /// narrowed to this section.
+    pub fn query(&self, query: &str) -> String {
+        let mut prompt = format!(
+            "{}\n\nThis is one section of a longer report answering the question above. \
+            Write only the section \"{}\".",
+            query, self.title
+        );
+        if !self.focus.is_empty() {
+            prompt.push_str(&format!(" It covers: {}", self.focus));
+        }
+        prompt
+    }
+
+    /// The sources of `sources` the section draws on, in the outline's order.
+    pub fn sources(&self, sources: &[Source]) -> Vec<Source> {
+        self.source_ids
+            .iter()
+            .filter_map(|id| sources.iter().find(|s| &s.id == id))
+            .cloned()
+            .collect()
+    }
+}
+
+impl Outline {
+    /// The length policy of each section: the answer's token limit shared
+    /// among the sections, but no less than [`MIN_SECTION_TOKENS`].
+    pub fn section_length(&self, length: Option<&LengthPolicy>) -> LengthPolicy {
+        let total = length.map_or(DEFAULT_COMPLETION_TOKENS, |l| l.max_tokens);
+        let per_section = (total / self.sections.len().max(1)).max(MIN_SECTION_TOKENS);
+        LengthPolicy::new(per_section, SECTION_INSTRUCTION)
+    }
+
+    /// Joins the answers written for each section, in order, into one
+    /// report: the sections' details under their titles, their summaries in
+    /// sequence, every citation, the sections' follow-up questions up to the
+    /// limit, what the sections found missing, and the lowest confidence of
+    /// any section.
+    /// Token usage adds up every call, the outline's included; duration is
+    /// the slowest section's, since sections are written at once.
+    pub fn assemble(self, answers: Vec<ResearchAnswer>) -> ResearchAnswer {
+        let mut detail = Vec::with_capacity(answers.len());
+        let mut summaries = Vec::with_capacity(answers.len());
+        let mut confidence = Confidence::High;
+        let mut report = ResearchAnswer::new("", "", Confidence::High, &self.model);
+        report.synthesis_metadata.record_usage(StageTokenUsage::new(
+            LlmStage::Outline,
+            &self.model,
+            &self.usage,
+        ));
+
+        for (section, answer) in self.sections.into_iter().zip(answers) {
+            detail.push(format!("## {}\n\n{}", section.title, answer.detail.trim()));
+            summaries.push(answer.summary.trim().to_string());
+            if rank(&answer.confidence) > rank(&confidence) {
+                confidence = answer.confidence.clone();
+            }
+            for limitation in &answer.limitations {
+                if !report.limitations.contains(limitation) {
+                    report.limitations.push(limitation.clone());
+                }
+            }
+            for question in answer.suggested_followups {
+                report.add_suggested_followup(question);
+            }
+            if let Some(why) = answer.why_insufficient {
+                report
+                    .why_insufficient
+                    .get_or_insert_with(Default::default)
+                    .merge(why);
+            }
+
+            let metadata = &mut report.synthesis_metadata;
+            metadata.model = answer.synthesis_metadata.model.clone();
+            metadata.tokens_used += answer.synthesis_metadata.tokens_used;
+            metadata.synthesis_duration = metadata
+                .synthesis_duration
+                .max(answer.synthesis_metadata.synthesis_duration);
+            for usage in answer.synthesis_metadata.stage_usage {
+                metadata.record_usage(usage);
+            }
+            // The fullest section prompt is the one nearest the window.
+            if let Some(context) = answer.synthesis_metadata.context {
+                if metadata
+                    .context
+                    .as_ref()
+                    .map_or(true, |c| context.prompt_tokens > c.prompt_tokens)
+                {
+                    metadata.context = Some(context);
+                }
+            }
+
+            report.citations.extend(answer.citations.iter().cloned());
+            report.sections.push(AnswerSection {
+                title: section.title,
+                source_ids: section.source_ids,
+                citations: answer.citations,
+            });
+        }
+
+        report.summary = summaries.join(" ");
+        report.detail = detail.join("\n\n");
+        report.confidence = confidence;
+        report
+    }
+}
+
+/// Orders confidence from most to least sure.
+fn rank(confidence: &Confidence) -> u8 {
+    match confidence {
+        Confidence::High => 0,
+        Confidence::Medium => 1,
+        Confidence::Low => 2,
+        Confidence::Insufficient => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockLlmProvider, MockLlmStep};
+
+    fn sources() -> Vec<Source> {
+        (1..=3)
+            .map(|i| {
+                Source::new(
+                    format!("https://example.com/{}", i),
+                    format!("Source {}", i),
+                    "Content about the topic",
+                )
+            })
+            .collect()
+    }
+
+    fn outliner(reply: &str) -> Outliner {
+        let llm =
+            MockLlmProvider::new("mock-outline").with_script([MockLlmStep::Raw(reply.to_string())]);
+        Outliner::new(Arc::new(llm), OutlinerConfig::default())
+    }
+
+    #[tokio::test]
+    async fn drafts_sections_with_their_sources() {
+        let sources = sources();
+        let reply = r#"Here is the plan:
+            {"sections": [
+                {"title": "Background", "focus": "History", "sources": [1, 1, 9]},
+                {"title": "Unsourced", "sources": []},
+                {"title": "Outlook", "focus": "What comes next", "sources": [3, 2]}
+            ]}"#;
+
+        let (result, exchange) = outliner(reply).draft("What happened?", &sources).await;
+        let outline = result.unwrap();
+
+        assert_eq!(outline.sections.len(), 2);
+        assert_eq!(outline.sections[0].source_ids, vec![sources[0].id.clone()]);
+        assert_eq!(
+            outline.sections[1].source_ids,
+            vec![sources[2].id.clone(), sources[1].id.clone()]
+        );
+        assert_eq!(outline.model, "mock-outline");
+        assert!(exchange.messages[1].content.contains("[3] Source 3"));
+        assert_eq!(exchange.raw_response.as_deref(), Some(reply));
+    }
+
+    #[tokio::test]
+    async fn rejects_outlines_too_short_to_split() {
+        let reply = r#"{"sections": [{"title": "Everything", "sources": [1, 2]}]}"#;
+        let (result, _) = outliner(reply).draft("What happened?", &sources()).await;
+        assert!(result.is_err());
+
+        let (result, exchange) = outliner("No outline, sorry.")
+            .draft("What happened?", &sources())
+            .await;
+        assert!(result.is_err());
+        assert_eq!(exchange.raw_response.as_deref(), Some("No outline, sorry."));
+    }
+
+    #[test]
+    fn assembles_sections_into_one_report() {
+        let sources = sources();
+        let section = |title: &str, source: &Source| OutlineSection {
+            title: title.to_string(),
+            focus: String::new(),
+            source_ids: vec![source.id.clone()],
+        };
+        let outline = Outline {
+            sections: vec![
+                section("Background", &sources[0]),
+                section("Outlook", &sources[1]),
+            ],
+            model: "mock".to_string(),
+            usage: TokenUsage {
+                prompt_tokens: 100,
+                completion_tokens: 20,
+            },
+        };
+        let answers = vec![
+            ResearchAnswer::new("It began.", "Long ago.", Confidence::High, "mock")
+                .with_citations(vec![crate::answer::Citation::new(
+                    "began",
+                    sources[0].id.clone(),
+                )])
+                .with_limitations(["Dated sources"]),
+            ResearchAnswer::new("It continues.", "Still.", Confidence::Medium, "mock")
+                .with_limitations(["Dated sources"]),
+        ];
+
+        let report = outline.assemble(answers);
+
+        assert_eq!(report.summary, "It began. It continues.");
+        assert_eq!(
+            report.detail,
+            "## Background\n\nLong ago.\n\n## Outlook\n\nStill."
+        );
+        assert_eq!(report.confidence, Confidence::Medium);
+        assert_eq!(report.limitations, vec!["Dated sources"]);
+        assert_eq!(report.citations.len(), 1);
+        assert_eq!(report.sections[0].citations.len(), 1);
+        assert!(report.sections[1].citations.is_empty());
+        assert_eq!(
+            report.synthesis_metadata.stage_usage[0].stage,
+            LlmStage::Outline
+        );
+    }
+}
@@ -1,11 +1,25 @@
 //! Query planning for research pipeline.
 
+use crate::corpus::CORPUS_PROVIDER_ID;
 use crate::search::{ProviderId, SearchPlan, SearchQuery};
 
+/// Words left out of a query's keywords: question words, auxiliaries and
+/// other words that match almost any page.
+const STOPWORDS: &[&str] = &[
+    "a", "about", "an", "and", "are", "be", "by", "can", "could", "did", "do", "does", "for",
+    "from", "has", "have", "how", "i", "in", "is", "it", "its", "me", "of", "on", "or", "should",
+    "tell", "the", "to", "was", "were", "what", "when", "where", "which", "who", "why", "will",
+    "with", "would",
+];
+
 #[derive(Clone, Debug)]
 pub struct PlannerConfig {
     pub max_queries: usize,
     pub default_providers: Vec<String>,
+    /// Whether plans search the document corpus too.
+    pub include_corpus: bool,
+    /// Whether plans search the document corpus in place of the web.
+    pub corpus_only: bool,
 }
 
 impl Default for PlannerConfig {
@@ -13,6 +27,8 @@ impl Default for PlannerConfig {
         Self {
             max_queries: 3,
             default_providers: vec!["tavily".to_string()],
+            include_corpus: false,
+            corpus_only: false,
         }
     }
 }
@@ -29,15 +45,45 @@ impl Planner {
     pub fn plan(&self, query: &str) -> SearchPlan {
         let queries = vec![SearchQuery::new(query)];
 
-        let providers = self
+        if self.config.corpus_only {
+            return SearchPlan::new(queries, vec![ProviderId::new(CORPUS_PROVIDER_ID)]);
+        }
+
+        let mut providers: Vec<ProviderId> = self
             .config
             .default_providers
             .iter()
             .map(ProviderId::new)
             .collect();
+        if self.config.include_corpus {
+            providers.push(ProviderId::new(CORPUS_PROVIDER_ID));
+        }
 
         SearchPlan::new(queries, providers)
     }
+
+    /// A wider plan than [`plan`](Self::plan) for a query whose first
+    /// answer came back unsure: the query as asked, then its keywords
+    /// alone, up to `max_queries`.
+    pub fn broaden(&self, query: &str) -> SearchPlan {
+        let mut plan = self.plan(query);
+        let keywords = keywords(query);
+        if !keywords.is_empty() && !keywords.eq_ignore_ascii_case(query.trim()) {
+            plan.queries.push(SearchQuery::new(keywords));
+        }
+        plan.queries.truncate(self.config.max_queries.max(1));
+        plan
+    }
+}
+
+/// `query` without punctuation and [`STOPWORDS`].
+pub(crate) fn keywords(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|word| !word.is_empty() && !STOPWORDS.contains(&word.to_lowercase().as_str()))
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 #[cfg(test)]
@@ -54,11 +100,29 @@ mod tests {
         assert!(!plan.providers.is_empty());
     }
 
+    #[test]
+    fn planner_broadens_with_query_keywords() {
+        let planner = Planner::new(PlannerConfig::default());
+        let plan = planner.broaden("What is the boiling point of water on Everest?");
+
+        let queries: Vec<_> = plan.queries.iter().map(|q| q.text.as_str()).collect();
+        assert_eq!(
+            queries,
+            [
+                "What is the boiling point of water on Everest?",
+                "boiling point water Everest"
+            ]
+        );
+        assert_eq!(planner.broaden("rust borrow checker").queries.len(), 1);
+    }
+
     #[test]
     fn planner_uses_configured_providers() {
         let config = PlannerConfig {
             max_queries: 3,
             default_providers: vec!["exa".to_string(), "searxng".to_string()],
+            include_corpus: false,
+            corpus_only: false,
         };
         let planner = Planner::new(config);
         let plan = planner.plan("test");
@@ -67,4 +131,29 @@ mod tests {
         assert_eq!(plan.providers[0].as_str(), "exa");
         assert_eq!(plan.providers[1].as_str(), "searxng");
     }
+
+    #[test]
+    fn planner_adds_corpus_when_included() {
+        let config = PlannerConfig {
+            include_corpus: true,
+            ..PlannerConfig::default()
+        };
+        let plan = Planner::new(config).plan("test");
+
+        assert_eq!(plan.providers.len(), 2);
+        assert_eq!(plan.providers[1].as_str(), CORPUS_PROVIDER_ID);
+    }
+
+    #[test]
+    fn planner_searches_only_corpus_when_asked() {
+        let config = PlannerConfig {
+            include_corpus: true,
+            corpus_only: true,
+            ..PlannerConfig::default()
+        };
+        let plan = Planner::new(config).plan("test");
+
+        assert_eq!(plan.providers.len(), 1);
+        assert_eq!(plan.providers[0].as_str(), CORPUS_PROVIDER_ID);
+    }
 }
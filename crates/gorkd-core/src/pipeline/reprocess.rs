@@ -0,0 +1,340 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use super::{Executor, Pipeline, PipelineError};
+use crate::answer::ResearchAnswer;
+use crate::artifact::SearchArtifact;
+use crate::evidence::map_evidence;
+use crate::highlight;
+use crate::job::ResearchJob;
+use crate::references::{number_references, CitationMarkers};
+use crate::search::{SearchPlan, SearchQuery};
+use crate::source::Source;
+use crate::traits::{
+    LlmError, LlmProvider, ProviderAttempt, SearchError, SearchProvider, SearchReport, SearchResult,
+};
+
+/// What [`Pipeline::reprocess`] rebuilt from a job's captured responses.
+#[derive(Debug)]
+pub struct Reprocessed {
+    /// The job's sources, re-parsed and re-ranked when search responses
+    /// were captured, or as stored otherwise.
+    pub sources: Vec<Source>,
+    /// The re-parsed answer, when the model's output was captured.
+    pub answer: Option<ResearchAnswer>,
+    /// Number of captured search responses replayed.
+    pub search_responses: usize,
+}
+
+impl Reprocessed {
+    /// Whether anything was captured to rebuild the job from.
+    pub fn is_empty(&self) -> bool {
+        self.search_responses == 0 && self.answer.is_none()
+    }
+}
+
+impl Pipeline {
+    /// Rebuilds `job`'s sources and answer from the provider responses the
+    /// artifact sink captured while it ran, without calling any provider:
+    /// search responses go through the search provider's parser and the
+    /// executor's ranking again, and the model's output through the parser of
+    /// the provider that wrote it. Pages the job already has keep their stored
+    /// text. Whatever was rebuilt replaces what the job had stored.
+    ///
+    /// Answers written section by section or by several models are not
+    /// re-parsed, and neither are jobs without an artifact sink.
+    pub async fn reprocess(&self, job: &ResearchJob) -> Result<Reprocessed, PipelineError> {
+        let stored = self.store.get_sources(&job.id).await?;
+        let Some(ref sink) = self.artifact_sink else {
+            return Ok(Reprocessed {
+                sources: stored,
+                answer: None,
+                search_responses: 0,
+            });
+        };
+        let searches = sink.search_artifacts(&job.id).await?;
+        let exchanges = sink.artifacts(&job.id).await?;
+
+        let search_responses = searches.len();
+        let sources = if searches.is_empty() {
+            stored.clone()
+        } else {
+            self.replay_searches(job, &stored, searches).await?
+        };
+
+        let outlined = exchanges.iter().any(|a| a.stage == "outline");
+        let raw_answer = exchanges
+            .iter()
+            .rev()
+            .find(|a| a.stage == "synthesis" && a.raw_response.is_some());
+        let answer = match raw_answer {
+            Some(artifact) if job.models.is_empty() && !outlined => {
+                let provider =
+                    self.provider_for(&artifact.model)
+                        .ok_or_else(|| PipelineError::Synthesis {
+                            model: artifact.model.clone(),
+                            error: LlmError::ModelUnavailable {
+                                model: artifact.model.clone(),
+                            },
+                        })?;
+                let raw = artifact.raw_response.as_deref().unwrap_or_default();
+                // The model saw the sources as they were when it ran.
+                let mut answer = provider.parse_synthesis(raw, &stored).map_err(|error| {
+                    PipelineError::Synthesis {
+                        model: artifact.model.clone(),
+                        error,
+                    }
+                })?;
+                if let Some(previous) = self.store.get_answer(&job.id).await? {
+                    answer.synthesis_metadata = previous.synthesis_metadata;
+                    answer.key_entities = previous.key_entities;
+                    answer.freshness = previous.freshness;
+                }
+                highlight::locate_quotes(&mut answer, &sources);
+                if self.config.citation_markers == CitationMarkers::Numeric {
+                    number_references(&mut answer, &sources);
+                }
+                map_evidence(&mut answer);
+                Some(answer)
+            }
+            _ => None,
+        };
+
+        if search_responses > 0 {
+            self.store.store_sources(&job.id, &sources).await?;
+        }
+        if let Some(ref answer) = answer {
+            self.store.store_answer(&job.id, answer).await?;
+        }
+
+        Ok(Reprocessed {
+            sources,
+            answer,
+            search_responses,
+        })
+    }
+
+    /// Runs the captured responses' queries through the executor again,
+    /// answered by the responses themselves. Sources the replay finds again
+    /// keep their stored ID; stored sources from providers whose responses
+    /// were not captured are kept as they were.
+    async fn replay_searches(
+        &self,
+        job: &ResearchJob,
+        stored: &[Source],
+        searches: Vec<SearchArtifact>,
+    ) -> Result<Vec<Source>, PipelineError> {
+        let mut queries: Vec<SearchQuery> = Vec::new();
+        for artifact in &searches {
+            if !queries.iter().any(|q| q.text == artifact.query.text) {
+                queries.push(artifact.query.clone());
+            }
+        }
+        let replayed: Vec<String> = searches.iter().map(|a| a.provider.clone()).collect();
+
+        let config = self.config.for_job(job);
+        let trust = self.store.domain_trust().await?;
+        let replay = Replay {
+            parser: Arc::clone(&self.search_provider),
+            searches,
+            content: stored
+                .iter()
+                .map(|s| (s.url.clone(), s.content.clone()))
+                .collect(),
+        };
+        let mut executor = Executor::new(
+            Arc::new(replay),
+            config.executor.clone().with_domain_trust(trust),
+        );
+        if let Some(ref fetcher) = self.content_fetcher {
+            executor = executor.with_content_fetcher(Arc::clone(fetcher));
+        }
+        let mut sources = executor
+            .execute(&SearchPlan::new(queries, Vec::new()))
+            .await
+            .map_err(PipelineError::Search)?;
+
+        for source in &mut sources {
+            if let Some(previous) = stored.iter().find(|s| s.url == source.url) {
+                source.id = previous.id.clone();
+                if source.metadata.format.is_none() {
+                    source.metadata.format = previous.metadata.format;
+                    source.metadata.archived = previous.metadata.archived.clone();
+                }
+            }
+        }
+        let kept: Vec<Source> = stored
+            .iter()
+            .filter(|s| !sources.iter().any(|new| new.url == s.url))
+            .filter(|s| {
+                s.metadata
+                    .provider
+                    .as_ref()
+                    .map_or(true, |p| !replayed.iter().any(|r| r == p.as_str()))
+            })
+            .cloned()
+            .collect();
+        sources.extend(kept);
+        sources.sort_by(|a, b| {
+            b.relevance_score
+                .partial_cmp(&a.relevance_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        Ok(sources)
+    }
+
+    /// The pipeline's provider for `model`, among every model it may answer
+    /// with.
+    fn provider_for(&self, model: &str) -> Option<&Arc<dyn LlmProvider>> {
+        std::iter::once(&self.llm_provider)
+            .chain(self.fast_provider.as_ref())
+            .chain(&self.comparison_providers)
+            .find(|p| p.model_id() == model)
+    }
+}
+
+/// Answers each query with its captured response, parsed by the provider
+/// that would have sent it.
+struct Replay {
+    parser: Arc<dyn SearchProvider>,
+    searches: Vec<SearchArtifact>,
+    /// Stored page text by URL.
+    content: HashMap<String, String>,
+}
+
+#[async_trait]
+impl SearchProvider for Replay {
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>, SearchError> {
+        self.search_reported(query).await.result
+    }
+
+    async fn search_reported(&self, query: &SearchQuery) -> SearchReport {
+        let Some(artifact) = self
+            .searches
+            .iter()
+            .rev()
+            .find(|a| a.query.text == query.text)
+        else {
+            return SearchReport {
+                result: Err(SearchError::Provider(format!(
+                    "no captured response for '{}'",
+                    query.text
+                ))),
+                attempts: Vec::new(),
+            };
+        };
+
+        let result = self
+            .parser
+            .parse_raw(&artifact.provider, query, &artifact.raw_response)
+            .map(|results| {
+                results
+                    .into_iter()
+                    .map(|mut result| {
+                        if let Some(content) = self.content.get(&result.url) {
+                            result.raw_content = Some(content.clone());
+                        }
+                        result
+                    })
+                    .collect::<Vec<_>>()
+            });
+        let attempts = vec![ProviderAttempt {
+            provider: artifact.provider.clone(),
+            query: query.text.clone(),
+            outcome: result.as_ref().map(Vec::len).map_err(Clone::clone),
+            duration: Duration::ZERO,
+            raw_response: None,
+        }];
+
+        SearchReport { result, attempts }
+    }
+
+    fn provider_id(&self) -> &str {
+        "replay"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifact::StoreArtifactSink;
+    use crate::job::JobStatus;
+    use crate::mock::{MockLlmProvider, MockLlmStep, MockSearchProvider, MockStore};
+    use crate::traits::{ArtifactSink, Store};
+
+    const RAW_ANSWER: &str =
+        r#"{"summary": "Rust is fast.", "detail": "It compiles to native code."}"#;
+
+    fn pipeline(store: &Arc<dyn Store>, captured: bool) -> Pipeline {
+        let llm = MockLlmProvider::new("mock-gpt-4")
+            .with_script([MockLlmStep::Raw(RAW_ANSWER.to_string())]);
+        let pipeline = Pipeline::new(
+            Arc::clone(store),
+            Arc::new(MockSearchProvider::new("mock")),
+            Arc::new(llm),
+        );
+        if captured {
+            pipeline.with_artifact_sink(Arc::new(StoreArtifactSink::new(Arc::clone(store))))
+        } else {
+            pipeline
+        }
+    }
+
+    fn ids_and_urls(sources: &[Source]) -> Vec<(String, String)> {
+        sources
+            .iter()
+            .map(|s| (s.id.to_string(), s.url.clone()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn reprocess_rebuilds_job_from_captured_responses() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let pipeline = pipeline(&store, true);
+        let job = ResearchJob::new("What is Rust?").unwrap();
+        store.create_job(&job).await.unwrap();
+        let result = pipeline.run(job).await.unwrap();
+        assert_eq!(result.job.status, JobStatus::Completed);
+        let captured = StoreArtifactSink::new(Arc::clone(&store))
+            .search_artifacts(&result.job.id)
+            .await
+            .unwrap();
+        assert!(!captured.is_empty());
+
+        let reprocessed = pipeline.reprocess(&result.job).await.unwrap();
+
+        assert_eq!(reprocessed.search_responses, captured.len());
+        assert_eq!(
+            ids_and_urls(&reprocessed.sources),
+            ids_and_urls(&result.sources)
+        );
+        let answer = reprocessed.answer.unwrap();
+        assert_eq!(answer.summary, "Rust is fast.");
+        assert_eq!(
+            answer.synthesis_metadata.tokens_used,
+            result.answer.unwrap().synthesis_metadata.tokens_used
+        );
+        let stored = store.get_answer(&result.job.id).await.unwrap().unwrap();
+        assert_eq!(stored.summary, "Rust is fast.");
+    }
+
+    #[tokio::test]
+    async fn reprocess_without_captures_changes_nothing() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let pipeline = pipeline(&store, false);
+        let job = ResearchJob::new("What is Rust?").unwrap();
+        store.create_job(&job).await.unwrap();
+        let result = pipeline.run(job).await.unwrap();
+
+        let reprocessed = pipeline.reprocess(&result.job).await.unwrap();
+
+        assert!(reprocessed.is_empty());
+        assert_eq!(
+            ids_and_urls(&reprocessed.sources),
+            ids_and_urls(&result.sources)
+        );
+    }
+}
@@ -3,7 +3,11 @@
 use std::sync::Arc;
 
 use crate::answer::ResearchAnswer;
+use crate::answer_schema::AnswerSchema;
+use crate::artifact::LlmExchange;
+use crate::length::LengthPolicy;
 use crate::source::Source;
+use crate::style::AnswerStyle;
 use crate::traits::{LlmError, LlmProvider};
 
 #[derive(Clone, Debug)]
@@ -34,13 +38,47 @@ impl Synthesizer {
         query: &str,
         sources: &[Source],
     ) -> Result<ResearchAnswer, LlmError> {
-        let context_sources: Vec<_> = sources
+        self.provider
+            .synthesize(query, &self.context_sources(sources))
+            .await
+    }
+
+    /// Synthesizes an answer within an optional length policy and in
+    /// `style`, with a structured payload if a `schema` is given and at a
+    /// `temperature` if one is given, and returns the provider's exchange with
+    /// the model alongside it. The answer records how full the model's
+    /// context window was and how many of `sources` it was given.
+    pub async fn synthesize_captured(
+        &self,
+        query: &str,
+        sources: &[Source],
+        length: Option<&LengthPolicy>,
+        schema: Option<&AnswerSchema>,
+        style: AnswerStyle,
+        temperature: Option<f32>,
+    ) -> (Result<ResearchAnswer, LlmError>, LlmExchange) {
+        let context = self.context_sources(sources);
+        let (mut result, exchange) = self
+            .provider
+            .synthesize_captured(query, &context, length, schema, style, temperature)
+            .await;
+        if let Ok(ref mut answer) = result {
+            answer.synthesis_metadata.record_context(
+                self.provider.max_context_tokens(),
+                exchange.prompt_tokens(self.provider.as_ref()),
+                sources.len(),
+                &context,
+            );
+        }
+        (result, exchange)
+    }
+
+    fn context_sources(&self, sources: &[Source]) -> Vec<Source> {
+        sources
             .iter()
             .take(self.config.max_context_sources)
             .cloned()
-            .collect();
-
-        self.provider.synthesize(query, &context_sources).await
+            .collect()
     }
 }
 
@@ -97,6 +135,28 @@ mod tests {
         assert!(answer.is_answerable());
     }
 
+    #[tokio::test]
+    async fn synthesizer_reports_context_usage() {
+        let provider = Arc::new(MockLlmProvider::new("mock-gpt-4"));
+        let config = SynthesizerConfig {
+            max_context_sources: 2,
+        };
+        let synthesizer = Synthesizer::new(provider, config);
+        let mut sources = create_test_sources();
+        sources[0].truncated = true;
+
+        let (result, _) = synthesizer
+            .synthesize_captured("test", &sources, None, None, AnswerStyle::Neutral, None)
+            .await;
+
+        let context = result.unwrap().synthesis_metadata.context.unwrap();
+        assert_eq!(context.window_tokens, 128_000);
+        assert_eq!(context.prompt_tokens, 400);
+        assert_eq!((context.sources_available, context.sources_used), (3, 2));
+        assert_eq!(context.sources_truncated, 1);
+        assert!((context.utilization() - 0.3125).abs() < 1e-6);
+    }
+
     #[tokio::test]
     async fn synthesizer_returns_insufficient_for_empty_sources() {
         let provider = Arc::new(MockLlmProvider::new("mock-gpt-4"));
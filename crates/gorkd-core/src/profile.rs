@@ -0,0 +1,159 @@
+//! Named research profiles.
+//!
+//! A profile captures a team's source policy once, such as the domains it
+//! trusts for medical questions, so requests only have to name it. Profiles
+//! are configured as a JSON object keyed by name:
+//!
+//! ```json
+//! {
+//!   "medical": {
+//!     "include_domains": ["nih.gov", "who.int", "cochrane.org"],
+//!     "content_type": "academic",
+//!     "depth": "exhaustive",
+//!     "style": "academic",
+//!     "max_cost": {"usd": 0.25}
+//!   },
+//!   "dev": {
+//!     "include_domains": ["docs.rs", "github.com", "stackoverflow.com"]
+//!   }
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::budget::CostBudget;
+use crate::depth::AnswerDepth;
+use crate::search::{ContentType, Recency, SearchFilters};
+use crate::style::AnswerStyle;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResearchProfile {
+    /// Only sources from these domains and their subdomains are kept.
+    #[serde(default)]
+    pub include_domains: Vec<String>,
+    /// Sources from these domains and their subdomains are dropped.
+    #[serde(default)]
+    pub exclude_domains: Vec<String>,
+    #[serde(default)]
+    pub content_type: Option<ContentType>,
+    #[serde(default)]
+    pub recency: Option<Recency>,
+    /// Language of the results, such as `de`.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Country to favor results from, such as `DE`.
+    #[serde(default)]
+    pub country: Option<String>,
+    /// Depth of the profile's jobs, unless a request sets its own.
+    #[serde(default)]
+    pub depth: Option<AnswerDepth>,
+    /// Style of the profile's answers, unless a request sets its own.
+    #[serde(default)]
+    pub style: Option<AnswerStyle>,
+    /// The most each of the profile's jobs may spend on synthesis, unless a
+    /// request sets its own.
+    #[serde(default)]
+    pub max_cost: Option<CostBudget>,
+}
+
+impl ResearchProfile {
+    /// The search filters the profile applies to every query.
+    pub fn filters(&self) -> SearchFilters {
+        let domains = |domains: &Vec<String>| (!domains.is_empty()).then(|| domains.clone());
+        let mut filters = SearchFilters {
+            recency: self.recency.clone(),
+            include_domains: domains(&self.include_domains),
+            exclude_domains: domains(&self.exclude_domains),
+            content_type: self.content_type.clone(),
+            ..Default::default()
+        };
+        if let Some(ref language) = self.language {
+            filters = filters.with_language(language);
+        }
+        if let Some(ref country) = self.country {
+            filters = filters.with_country(country);
+        }
+        filters
+    }
+}
+
+/// The configured profiles, by name.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ResearchProfiles(BTreeMap<String, ResearchProfile>);
+
+impl ResearchProfiles {
+    /// Parses profiles from a JSON object keyed by profile name.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn with_profile(mut self, name: impl Into<String>, profile: ResearchProfile) -> Self {
+        self.0.insert(name.into(), profile);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ResearchProfile> {
+        self.0.get(name)
+    }
+
+    /// Profile names, in alphabetical order.
+    pub fn names(&self) -> Vec<&str> {
+        self.0.keys().map(String::as_str).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_profiles_by_name() {
+        let profiles = ResearchProfiles::from_json(
+            r#"{
+                "medical": {
+                    "include_domains": ["nih.gov", "who.int"],
+                    "content_type": "academic",
+                    "depth": "exhaustive",
+                    "style": "academic"
+                },
+                "dev": {"include_domains": ["docs.rs"]}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(profiles.names(), vec!["dev", "medical"]);
+        let medical = profiles.get("medical").unwrap();
+        assert_eq!(medical.depth, Some(AnswerDepth::Exhaustive));
+        assert_eq!(medical.style, Some(AnswerStyle::Academic));
+        assert!(profiles.get("legal").is_none());
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        assert!(ResearchProfiles::from_json(r#"{"dev": {"domains": ["docs.rs"]}}"#).is_err());
+    }
+
+    #[test]
+    fn builds_filters() {
+        let profile = ResearchProfile {
+            include_domains: vec!["nih.gov".to_string()],
+            content_type: Some(ContentType::Academic),
+            ..Default::default()
+        };
+
+        let filters = profile.filters();
+
+        assert_eq!(filters.include_domains, Some(vec!["nih.gov".to_string()]));
+        assert!(filters.exclude_domains.is_none());
+        assert_eq!(filters.content_type, Some(ContentType::Academic));
+        assert!(ResearchProfile::default().filters().is_empty());
+    }
+}
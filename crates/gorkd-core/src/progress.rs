@@ -0,0 +1,201 @@
+//! Estimated progress of a job, as a percentage.
+//!
+//! A job's status alone leaves clients with an indeterminate spinner.
+//! [`JobProgress::estimate`] replays the job's event log instead: each stage
+//! covers a band of the percentage, and the steps recorded within a stage
+//! move the estimate through its band. While searching, that is the share of
+//! the planned queries searched so far; while synthesizing, the outline, the
+//! answer and its checks. Providers do not stream completions, so synthesis
+//! moves in those steps rather than token by token. The estimate never goes
+//! back, not even when an unsure answer sends the job searching again.
+
+use serde::{Deserialize, Serialize};
+
+use crate::event::{JobEvent, JobEventKind};
+use crate::job::JobStatus;
+
+/// Where searching starts; searching the planned queries takes it to
+/// [`SEARCHED_PERCENT`].
+pub const SEARCHING_PERCENT: u8 = 10;
+
+/// Progress once every planned query has been searched.
+pub const SEARCHED_PERCENT: u8 = 55;
+
+/// Where synthesis starts.
+pub const SYNTHESIZING_PERCENT: u8 = 60;
+
+/// Progress once the answer is synthesized, before its checks.
+pub const SYNTHESIZED_PERCENT: u8 = 90;
+
+/// How far along a job is.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobProgress {
+    /// Estimated share of the job done, from 0 to 100. Only completed jobs
+    /// reach 100; failed ones keep what they had reached.
+    pub percent: u8,
+    pub stage: JobStatus,
+    /// Queries searched so far out of those planned, once searching has
+    /// begun.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queries: Option<QueryProgress>,
+}
+
+/// How many of a search's planned queries have been searched.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryProgress {
+    pub executed: usize,
+    pub planned: usize,
+}
+
+impl Default for JobProgress {
+    fn default() -> Self {
+        Self {
+            percent: 0,
+            stage: JobStatus::Pending,
+            queries: None,
+        }
+    }
+}
+
+impl JobProgress {
+    /// The progress of a job whose log is `events`, in order.
+    pub fn estimate(events: &[JobEvent]) -> Self {
+        let mut progress = Self::default();
+        for event in events {
+            progress.apply(&event.kind);
+        }
+        progress
+    }
+
+    /// Moves the estimate on by one event.
+    pub fn apply(&mut self, kind: &JobEventKind) {
+        let reached = match kind {
+            JobEventKind::StageChanged { status } => {
+                self.stage = status.clone();
+                match status {
+                    JobStatus::Pending => 0,
+                    JobStatus::Planning => 5,
+                    JobStatus::Searching => SEARCHING_PERCENT,
+                    JobStatus::Fetching => SEARCHED_PERCENT,
+                    JobStatus::Synthesizing => SYNTHESIZING_PERCENT,
+                    JobStatus::Completed => 100,
+                    JobStatus::Failed => 0,
+                }
+            }
+            JobEventKind::SearchProgressed { executed, planned } => {
+                let queries = QueryProgress {
+                    executed: *executed,
+                    planned: *planned,
+                };
+                self.queries = Some(queries);
+                let span = usize::from(SEARCHED_PERCENT - SEARCHING_PERCENT);
+                let done = span * executed.min(planned) / (*planned).max(1);
+                SEARCHING_PERCENT + done as u8
+            }
+            JobEventKind::SourcesCollected { .. } => SEARCHED_PERCENT,
+            JobEventKind::OutlineDrafted { .. } => SYNTHESIZING_PERCENT + 5,
+            JobEventKind::AnswerSynthesized { .. } | JobEventKind::ConsistencyChecked { .. } => {
+                SYNTHESIZED_PERCENT
+            }
+            JobEventKind::EntitiesExtracted { .. }
+            | JobEventKind::EntityExtractionFailed { .. }
+            | JobEventKind::FactsRecorded { .. }
+            | JobEventKind::FactExtractionFailed { .. }
+            | JobEventKind::LinksChecked { .. } => SYNTHESIZED_PERCENT + 3,
+            JobEventKind::Moderated { .. } => SYNTHESIZED_PERCENT + 5,
+            JobEventKind::Failed { .. } => {
+                self.stage = JobStatus::Failed;
+                0
+            }
+            _ => 0,
+        };
+        self.percent = self.percent.max(reached);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::id::JobId;
+
+    fn events(kinds: Vec<JobEventKind>) -> Vec<JobEvent> {
+        let job_id = JobId::new();
+        kinds
+            .into_iter()
+            .map(|kind| JobEvent::new(job_id.clone(), kind))
+            .collect()
+    }
+
+    fn stage(status: JobStatus) -> JobEventKind {
+        JobEventKind::StageChanged { status }
+    }
+
+    #[test]
+    fn moves_through_searched_queries() {
+        let log = events(vec![
+            stage(JobStatus::Planning),
+            stage(JobStatus::Searching),
+            JobEventKind::SearchProgressed {
+                executed: 0,
+                planned: 3,
+            },
+        ]);
+        assert_eq!(JobProgress::estimate(&log).percent, SEARCHING_PERCENT);
+
+        let mut log = log;
+        log.extend(events(vec![JobEventKind::SearchProgressed {
+            executed: 2,
+            planned: 3,
+        }]));
+        let progress = JobProgress::estimate(&log);
+
+        assert_eq!(progress.percent, 40);
+        assert_eq!(progress.stage, JobStatus::Searching);
+        assert_eq!(
+            progress.queries,
+            Some(QueryProgress {
+                executed: 2,
+                planned: 3
+            })
+        );
+    }
+
+    #[test]
+    fn never_goes_back_and_completes_at_100() {
+        let mut progress = JobProgress::default();
+        for kind in [
+            stage(JobStatus::Synthesizing),
+            JobEventKind::AnswerSynthesized {
+                model: "mock".into(),
+                tokens_used: 500,
+                duration_ms: 10,
+            },
+            JobEventKind::SearchProgressed {
+                executed: 1,
+                planned: 2,
+            },
+        ] {
+            progress.apply(&kind);
+        }
+        assert_eq!(progress.percent, SYNTHESIZED_PERCENT);
+
+        progress.apply(&stage(JobStatus::Completed));
+        assert_eq!(progress.percent, 100);
+    }
+
+    #[test]
+    fn failed_jobs_keep_what_they_reached() {
+        let log = events(vec![
+            stage(JobStatus::Searching),
+            JobEventKind::SourcesCollected { count: 3 },
+            JobEventKind::Failed {
+                message: "synthesis failed".into(),
+            },
+        ]);
+
+        let progress = JobProgress::estimate(&log);
+
+        assert_eq!(progress.percent, SEARCHED_PERCENT);
+        assert_eq!(progress.stage, JobStatus::Failed);
+    }
+}
@@ -0,0 +1,254 @@
+//! Projects: jobs grouped under one research effort.
+//!
+//! A question rarely stands alone: a market study or a literature review is a
+//! handful of related jobs. A [`Project`] names such an effort and lists its
+//! jobs in the order they were attached. A [`ProjectReport`] synthesizes the
+//! answers of its finished jobs into one report with a single chat
+//! completion, listing each job's own finding alongside it.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::answer::{Confidence, ResearchAnswer};
+use crate::chat::{ChatRequest, Message};
+use crate::id::{JobId, ProjectId};
+use crate::job::ResearchJob;
+use crate::traits::{LlmError, LlmProvider};
+
+/// Most jobs one project may hold.
+pub const MAX_PROJECT_JOBS: usize = 50;
+
+/// Longest project name, in characters.
+pub const MAX_PROJECT_NAME_LENGTH: usize = 200;
+
+/// Completion tokens a project report may use.
+pub const PROJECT_REPORT_MAX_TOKENS: usize = 4096;
+
+/// Characters of each answer's detail given to the report's model. Summaries
+/// are always given in full.
+const MAX_DETAIL_CHARS: usize = 2000;
+
+const REPORT_INSTRUCTIONS: &str = "\
+You write research reports that combine the answers to several related \
+questions. Using only the answers given, write one report in Markdown: open \
+with the overall conclusion, then cover the themes the answers share, where \
+they disagree or depend on each other, and what remains open. Refer to \
+questions by their number, as [Q1]. Say so when an answer has low confidence.";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Project {
+    pub id: ProjectId,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Member jobs, in the order they were attached.
+    #[serde(default)]
+    pub job_ids: Vec<JobId>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Project {
+    pub fn new(name: impl Into<String>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: ProjectId::new(),
+            name: name.into(),
+            description: None,
+            job_ids: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn contains(&self, job_id: &JobId) -> bool {
+        self.job_ids.contains(job_id)
+    }
+
+    /// Adds `job_id` to the project, returning whether it was new. Jobs
+    /// already attached are left in place.
+    pub fn attach(&mut self, job_id: JobId) -> bool {
+        if self.contains(&job_id) {
+            return false;
+        }
+        self.job_ids.push(job_id);
+        self.updated_at = Utc::now();
+        true
+    }
+}
+
+/// One member job's answer, as the report cites it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProjectFinding {
+    pub job_id: JobId,
+    pub query: String,
+    pub summary: String,
+    pub confidence: Confidence,
+}
+
+/// The answers of a project's jobs, synthesized into one report.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProjectReport {
+    pub project_id: ProjectId,
+    /// The synthesized report, in Markdown. Refers to findings as `[Q1]`,
+    /// `[Q2]`, ... in the order of `findings`.
+    pub report: String,
+    pub findings: Vec<ProjectFinding>,
+    /// Member jobs left out because they have no answer yet, or failed.
+    pub unanswered: Vec<JobId>,
+    pub model: String,
+    pub total_tokens: usize,
+    pub generated_at: DateTime<Utc>,
+}
+
+impl ProjectReport {
+    /// Synthesizes the answers of `members`, the project's jobs in order,
+    /// with `llm`. Jobs without an answer are listed as unanswered. Fails
+    /// when no job has an answer, since there is nothing to report.
+    pub async fn synthesize(
+        project: &Project,
+        members: Vec<(ResearchJob, Option<ResearchAnswer>)>,
+        llm: &dyn LlmProvider,
+    ) -> Result<Self, LlmError> {
+        let mut answered = Vec::new();
+        let mut unanswered = Vec::new();
+        for (job, answer) in members {
+            match answer {
+                Some(answer) => answered.push((job, answer)),
+                None => unanswered.push(job.id),
+            }
+        }
+        if answered.is_empty() {
+            return Err(LlmError::Provider(format!(
+                "project {} has no answered jobs to report on",
+                project.id
+            )));
+        }
+
+        let request = ChatRequest::new(vec![
+            Message::system(REPORT_INSTRUCTIONS),
+            Message::user(report_prompt(project, &answered)),
+        ])
+        .with_max_tokens(PROJECT_REPORT_MAX_TOKENS);
+        let response = llm.chat(request).await?;
+
+        let findings = answered
+            .into_iter()
+            .map(|(job, answer)| ProjectFinding {
+                job_id: job.id,
+                query: job.query,
+                summary: answer.summary,
+                confidence: answer.confidence,
+            })
+            .collect();
+
+        Ok(Self {
+            project_id: project.id.clone(),
+            report: response.content.trim().to_string(),
+            findings,
+            unanswered,
+            model: response.model,
+            total_tokens: response.usage.total(),
+            generated_at: Utc::now(),
+        })
+    }
+}
+
+fn report_prompt(project: &Project, answered: &[(ResearchJob, ResearchAnswer)]) -> String {
+    let mut prompt = format!("Project: {}\n", project.name);
+    if let Some(ref description) = project.description {
+        prompt.push_str(&format!("Goal: {}\n", description));
+    }
+    for (i, (job, answer)) in answered.iter().enumerate() {
+        prompt.push_str(&format!(
+            "\n[Q{}] {}\nConfidence: {}\nSummary: {}\n",
+            i + 1,
+            job.query,
+            answer.confidence.as_str(),
+            answer.summary
+        ));
+        let detail = answer.detail.trim();
+        if !detail.is_empty() {
+            let clipped: String = detail.chars().take(MAX_DETAIL_CHARS).collect();
+            prompt.push_str(&format!("Detail: {}\n", clipped));
+        }
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockLlmProvider, MockLlmStep};
+
+    fn answered(query: &str, summary: &str) -> (ResearchJob, Option<ResearchAnswer>) {
+        let answer = ResearchAnswer::new(summary, "", Confidence::High, "mock");
+        (ResearchJob::new(query).unwrap(), Some(answer))
+    }
+
+    #[test]
+    fn attaches_each_job_once() {
+        let mut project = Project::new("Market study");
+        let job = JobId::new();
+
+        assert!(project.attach(job.clone()));
+        assert!(!project.attach(job.clone()));
+        assert_eq!(project.job_ids, vec![job]);
+    }
+
+    #[tokio::test]
+    async fn synthesizes_answered_jobs() {
+        let project = Project::new("EV market").with_description("Size the EV market");
+        let pending = ResearchJob::new("Who leads in solid-state batteries?").unwrap();
+        let members = vec![
+            answered("How many EVs sold in 2024?", "About 17 million."),
+            (pending.clone(), None),
+            answered("Which EV maker sold most?", "BYD."),
+        ];
+        let llm = MockLlmProvider::new("mock-report")
+            .with_script([MockLlmStep::Raw("Sales grew [Q1], led by BYD [Q2].".into())]);
+
+        let report = ProjectReport::synthesize(&project, members, &llm)
+            .await
+            .unwrap();
+
+        assert_eq!(report.report, "Sales grew [Q1], led by BYD [Q2].");
+        assert_eq!(report.findings.len(), 2);
+        assert_eq!(report.findings[1].summary, "BYD.");
+        assert_eq!(report.unanswered, vec![pending.id]);
+        assert_eq!(report.model, "mock-report");
+    }
+
+    #[tokio::test]
+    async fn prompt_numbers_questions() {
+        let project = Project::new("EV market");
+        let members = vec![
+            answered("How many EVs sold in 2024?", "About 17 million."),
+            answered("Which EV maker sold most?", "BYD."),
+        ];
+        let llm = MockLlmProvider::new("mock-report");
+
+        let report = ProjectReport::synthesize(&project, members, &llm)
+            .await
+            .unwrap();
+
+        assert!(report.report.contains("Project: EV market"));
+        assert!(report.report.contains("[Q2] Which EV maker sold most?"));
+    }
+
+    #[tokio::test]
+    async fn fails_without_answers() {
+        let project = Project::new("Empty");
+        let members = vec![(ResearchJob::new("test").unwrap(), None)];
+        let llm = MockLlmProvider::new("mock-report");
+
+        assert!(ProjectReport::synthesize(&project, members, &llm)
+            .await
+            .is_err());
+    }
+}
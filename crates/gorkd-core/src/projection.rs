@@ -0,0 +1,239 @@
+//! The read model of jobs behind listings and dashboards.
+//!
+//! Listing jobs with their answers means reading each job's answer, and
+//! with it its citations and metadata, which gets slow with hundreds of
+//! thousands of jobs. Stores keep a [`JobSummary`] per job instead: one flat
+//! row with what a listing shows, updated whenever the job or its answer is
+//! written, so listings, searches and status counts read that row alone.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::answer::ResearchAnswer;
+use crate::id::JobId;
+use crate::job::{JobStatus, ResearchJob};
+use crate::source::Source;
+
+/// One job as listings and dashboards show it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JobSummary {
+    pub id: JobId,
+    pub status: JobStatus,
+    pub query: String,
+    /// The answer's summary, once the job has an answer.
+    pub summary: Option<String>,
+    /// What synthesis cost, when the model's price is known.
+    pub cost_usd: Option<f64>,
+    /// Time from creation until the job completed or failed.
+    pub duration_ms: Option<u64>,
+    /// How long the job spent in each status it has left, in order.
+    #[serde(default)]
+    pub stages: Vec<StageTiming>,
+    /// The model that wrote the answer.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Domains of the sources the answer cites, each once.
+    #[serde(default)]
+    pub cited_domains: Vec<String>,
+    pub tags: Vec<String>,
+    pub workspace: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Time a job spent in one status before moving to the next.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageTiming {
+    pub stage: JobStatus,
+    pub duration_ms: u64,
+}
+
+impl JobSummary {
+    /// The summary of `job` before it has an answer.
+    pub fn of(job: &ResearchJob) -> Self {
+        let duration_ms = job
+            .status
+            .is_terminal()
+            .then(|| (job.updated_at - job.created_at).num_milliseconds().max(0) as u64);
+
+        Self {
+            id: job.id.clone(),
+            status: job.status.clone(),
+            query: job.query.clone(),
+            summary: None,
+            cost_usd: None,
+            duration_ms,
+            stages: Vec::new(),
+            model: None,
+            cited_domains: Vec::new(),
+            tags: job.tags.clone(),
+            workspace: job.workspace.clone(),
+            created_at: job.created_at,
+            updated_at: job.updated_at,
+        }
+    }
+
+    /// This summary updated for a write of `job`, keeping what the answer
+    /// set and timing the status the job left, if it moved on.
+    pub fn updated(&self, job: &ResearchJob) -> Self {
+        let mut stages = self.stages.clone();
+        if job.status != self.status {
+            stages.push(StageTiming {
+                stage: self.status.clone(),
+                duration_ms: (job.updated_at - self.updated_at).num_milliseconds().max(0) as u64,
+            });
+        }
+
+        Self {
+            summary: self.summary.clone(),
+            cost_usd: self.cost_usd,
+            stages,
+            model: self.model.clone(),
+            cited_domains: self.cited_domains.clone(),
+            ..Self::of(job)
+        }
+    }
+
+    /// This summary with what `answer` shows, given the job's `sources`.
+    pub fn with_answer(mut self, answer: &ResearchAnswer, sources: &[Source]) -> Self {
+        self.summary = Some(answer.summary.clone());
+        self.cost_usd = answer.synthesis_metadata.cost_usd;
+        self.model = Some(answer.synthesis_metadata.model.clone());
+        self.cited_domains.clear();
+        for citation in &answer.citations {
+            let source = sources.iter().find(|s| s.id == citation.source_id);
+            if let Some(domain) = source.map(|s| &s.metadata.domain) {
+                if !domain.is_empty() && !self.cited_domains.contains(domain) {
+                    self.cited_domains.push(domain.clone());
+                }
+            }
+        }
+        self
+    }
+}
+
+/// Which job summaries a listing or count covers. Empty covers all jobs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct JobSummaryFilter {
+    pub status: Option<JobStatus>,
+    pub tag: Option<String>,
+    /// Text the query or the answer's summary contains, ignoring case.
+    pub text: Option<String>,
+}
+
+impl JobSummaryFilter {
+    pub fn admits(&self, summary: &JobSummary) -> bool {
+        if self.status.as_ref().is_some_and(|s| *s != summary.status) {
+            return false;
+        }
+        if let Some(ref tag) = self.tag {
+            if !summary.tags.iter().any(|t| t == tag) {
+                return false;
+            }
+        }
+        if let Some(ref text) = self.text {
+            let text = text.to_lowercase();
+            let contains = |s: &str| s.to_lowercase().contains(&text);
+            if !contains(&summary.query) && !summary.summary.as_deref().is_some_and(contains) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// How many jobs are in a status.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusCount {
+    pub status: JobStatus,
+    pub count: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answer::{Citation, Confidence};
+
+    #[test]
+    fn times_finished_jobs_only() {
+        let mut job = ResearchJob::new("What is Rust?").unwrap();
+        assert_eq!(JobSummary::of(&job).duration_ms, None);
+
+        job.status = JobStatus::Completed;
+        job.updated_at = job.created_at + chrono::Duration::milliseconds(1500);
+        assert_eq!(JobSummary::of(&job).duration_ms, Some(1500));
+    }
+
+    #[test]
+    fn keeps_answer_across_job_updates() {
+        let mut job = ResearchJob::new("What is Rust?").unwrap();
+        let mut answer = ResearchAnswer::new("A systems language.", "", Confidence::High, "mock");
+        answer.synthesis_metadata.cost_usd = Some(0.02);
+        let summary = JobSummary::of(&job).with_answer(&answer, &[]);
+
+        job.status = JobStatus::Completed;
+        job.updated_at = job.created_at + chrono::Duration::milliseconds(800);
+        let updated = summary.updated(&job);
+
+        assert_eq!(updated.status, JobStatus::Completed);
+        assert_eq!(updated.summary.as_deref(), Some("A systems language."));
+        assert_eq!(updated.cost_usd, Some(0.02));
+        assert_eq!(updated.model.as_deref(), Some("mock"));
+        assert_eq!(
+            updated.stages,
+            vec![StageTiming {
+                stage: JobStatus::Pending,
+                duration_ms: 800,
+            }]
+        );
+        assert_eq!(updated.updated(&job).stages.len(), 1);
+    }
+
+    #[test]
+    fn lists_each_cited_domain_once() {
+        let job = ResearchJob::new("What is Rust?").unwrap();
+        let sources = [
+            Source::new("https://rust-lang.org/learn", "Learn", ""),
+            Source::new("https://rust-lang.org/tools", "Tools", ""),
+            Source::new("https://example.com", "Uncited", ""),
+        ];
+        let mut answer = ResearchAnswer::new("A language.", "", Confidence::High, "mock");
+        answer.citations = sources[..2]
+            .iter()
+            .map(|s| Citation::new("Rust", s.id.clone()))
+            .collect();
+
+        let summary = JobSummary::of(&job).with_answer(&answer, &sources);
+
+        assert_eq!(summary.cited_domains, vec!["rust-lang.org"]);
+    }
+
+    #[test]
+    fn filters_by_status_tag_and_text() {
+        let job = ResearchJob::new("What is Rust?")
+            .unwrap()
+            .with_tags(["lang"]);
+        let summary = JobSummary::of(&job).with_answer(
+            &ResearchAnswer::new("A systems language.", "", Confidence::High, "mock"),
+            &[],
+        );
+
+        assert!(JobSummaryFilter::default().admits(&summary));
+        assert!(JobSummaryFilter {
+            status: Some(JobStatus::Pending),
+            tag: Some("lang".to_string()),
+            text: Some("SYSTEMS".to_string()),
+        }
+        .admits(&summary));
+        assert!(!JobSummaryFilter {
+            status: Some(JobStatus::Failed),
+            ..Default::default()
+        }
+        .admits(&summary));
+        assert!(!JobSummaryFilter {
+            text: Some("python".to_string()),
+            ..Default::default()
+        }
+        .admits(&summary));
+    }
+}
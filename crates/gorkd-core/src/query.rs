@@ -1,7 +1,9 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+use crate::search::{Recency, SearchFilters};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 #[non_exhaustive]
 pub enum QuestionType {
@@ -13,6 +15,133 @@ pub enum QuestionType {
     Opinion,
 }
 
+const COMPARISON_MARKERS: &[&str] = &[
+    " vs ",
+    " vs. ",
+    " versus ",
+    "compare ",
+    "comparison",
+    "difference between",
+    "differences between",
+    "better than",
+    "which is better",
+];
+
+const HOW_TO_PREFIXES: &[&str] = &[
+    "how to ",
+    "how do i ",
+    "how can i ",
+    "how should i ",
+    "steps to ",
+];
+
+const CURRENT_EVENT_MARKERS: &[&str] = &[
+    "latest",
+    "today",
+    "this week",
+    "this month",
+    "this year",
+    "right now",
+    "currently",
+    "recent",
+    "news",
+];
+
+const OPINION_MARKERS: &[&str] = &[
+    "should i ",
+    "is it worth",
+    "worth it",
+    "recommend",
+    "best ",
+    "opinion",
+];
+
+const EXPLANATION_PREFIXES: &[&str] = &[
+    "why ",
+    "how does ",
+    "how do ",
+    "how is ",
+    "how are ",
+    "explain ",
+    "describe ",
+    "what causes ",
+];
+
+const FACTUAL_PREFIXES: &[&str] = &[
+    "who ",
+    "when ",
+    "where ",
+    "which ",
+    "what is ",
+    "what's ",
+    "what was ",
+    "what are ",
+    "how many ",
+    "how much ",
+    "how old ",
+    "how long ",
+    "how tall ",
+    "how far ",
+    "is ",
+    "are ",
+    "does ",
+    "did ",
+    "can ",
+];
+
+impl QuestionType {
+    pub const ALL: [QuestionType; 6] = [
+        Self::Factual,
+        Self::Comparison,
+        Self::Explanation,
+        Self::CurrentEvent,
+        Self::HowTo,
+        Self::Opinion,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Factual => "factual",
+            Self::Comparison => "comparison",
+            Self::Explanation => "explanation",
+            Self::CurrentEvent => "current_event",
+            Self::HowTo => "how_to",
+            Self::Opinion => "opinion",
+        }
+    }
+
+    /// Guesses the kind of question from its wording. Keyword rules are
+    /// checked from most to least specific; questions that match nothing are
+    /// treated as explanations so they keep a full-length answer.
+    pub fn classify(query: &str) -> Self {
+        let query = format!("{} ", query.trim().to_lowercase());
+        let contains = |markers: &[&str]| markers.iter().any(|m| query.contains(m));
+        let starts = |prefixes: &[&str]| prefixes.iter().any(|p| query.starts_with(p));
+
+        if contains(COMPARISON_MARKERS) {
+            Self::Comparison
+        } else if starts(HOW_TO_PREFIXES) || query.contains(" how to ") {
+            Self::HowTo
+        } else if contains(CURRENT_EVENT_MARKERS) {
+            Self::CurrentEvent
+        } else if contains(OPINION_MARKERS) {
+            Self::Opinion
+        } else if starts(EXPLANATION_PREFIXES) {
+            Self::Explanation
+        } else if starts(FACTUAL_PREFIXES) {
+            Self::Factual
+        } else {
+            Self::Explanation
+        }
+    }
+}
+
+impl std::fmt::Display for QuestionType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 #[non_exhaustive]
@@ -26,6 +155,175 @@ pub enum TimeConstraint {
     },
 }
 
+const RECENT_MARKERS: &[&str] = &[
+    "as of today",
+    "as of now",
+    "right now",
+    "today",
+    "currently",
+    "latest",
+    "recent",
+    "recently",
+];
+
+const HISTORICAL_MARKERS: &[&str] = &["history of", "historical", "historically"];
+
+impl TimeConstraint {
+    /// Finds the period a query asks about, such as "in the last two weeks",
+    /// "since 2021" or "as of today". Relative periods end now.
+    pub fn parse(query: &str) -> Option<Self> {
+        Self::parse_at(query, Utc::now())
+    }
+
+    /// [`TimeConstraint::parse`] as if it were `now`.
+    pub fn parse_at(query: &str, now: DateTime<Utc>) -> Option<Self> {
+        let query = query.to_lowercase();
+        let words: Vec<&str> = query
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .collect();
+
+        if let Some(range) = year_range(&words) {
+            return Some(range);
+        }
+        if let Some(from) = relative_start(&words, now) {
+            return Some(Self::DateRange {
+                from: Some(from),
+                to: None,
+            });
+        }
+
+        let padded = format!(" {} ", words.join(" "));
+        let mentions =
+            |markers: &[&str]| markers.iter().any(|m| padded.contains(&format!(" {} ", m)));
+        if mentions(RECENT_MARKERS) {
+            Some(Self::Recent)
+        } else if mentions(HISTORICAL_MARKERS) {
+            Some(Self::Historical)
+        } else {
+            None
+        }
+    }
+
+    /// `filters` narrowed to this period, unless they already bound dates
+    /// or recency themselves. A period ending now also sets the smallest
+    /// recency window covering it, for providers without absolute dates.
+    pub fn apply(&self, filters: SearchFilters) -> SearchFilters {
+        if filters.recency.is_some() || filters.has_date_range() {
+            return filters;
+        }
+
+        match *self {
+            Self::Recent => filters.with_recency(Recency::Week),
+            Self::Historical => filters,
+            Self::SpecificDate(date) => {
+                filters.published_between(Some(date), Some(date + Duration::days(1)))
+            }
+            Self::DateRange { from, to } => {
+                let mut filters = filters.published_between(from, to);
+                if let (Some(from), None) = (from, to) {
+                    filters.recency = covering_recency(Utc::now() - from);
+                }
+                filters
+            }
+        }
+    }
+}
+
+/// `since 2021`, `before 2020`, `between 2019 and 2021`, `from 2019 to
+/// 2021` and `in 2022`, as whole calendar years.
+fn year_range(words: &[&str]) -> Option<TimeConstraint> {
+    let year = |word: &str| {
+        word.parse::<i32>()
+            .ok()
+            .filter(|y| (1900..=2100).contains(y))
+    };
+    let start = |year: i32| Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0).single();
+
+    for (i, pair) in words.windows(2).enumerate() {
+        let Some(first) = year(pair[1]) else {
+            continue;
+        };
+        let second = match words.get(i + 2..i + 4) {
+            Some([joiner, second]) if matches!(*joiner, "and" | "to") => year(second),
+            _ => None,
+        };
+
+        let (from, to) = match (pair[0], second) {
+            ("between" | "from", Some(last)) if last >= first => (start(first), start(last + 1)),
+            ("since" | "after", _) => (start(first), None),
+            ("before" | "until", _) => (None, start(first)),
+            ("in" | "during", _) => (start(first), start(first + 1)),
+            _ => continue,
+        };
+        return Some(TimeConstraint::DateRange { from, to });
+    }
+    None
+}
+
+/// Start of `the last two weeks`, `past 3 months`, `last year`, `this
+/// month` or `yesterday`.
+fn relative_start(words: &[&str], now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    for (i, &word) in words.iter().enumerate() {
+        if word == "yesterday" {
+            return Some(now - Duration::days(1));
+        }
+        if !matches!(word, "last" | "past" | "this") {
+            continue;
+        }
+
+        let (count, unit) = match (words.get(i + 1), words.get(i + 2)) {
+            (Some(&n), Some(&unit)) if number(n).is_some() => (number(n)?, unit),
+            (Some(&unit), _) => (1, unit),
+            _ => continue,
+        };
+        let days = match unit.trim_end_matches('s') {
+            "day" => 1,
+            "week" => 7,
+            "month" if word == "this" => return now.with_day(1),
+            "month" => 30,
+            "year" if word == "this" => {
+                return Utc.with_ymd_and_hms(now.year(), 1, 1, 0, 0, 0).single()
+            }
+            "year" => 365,
+            _ => continue,
+        };
+        return Some(now - Duration::days(days * count));
+    }
+    None
+}
+
+fn number(word: &str) -> Option<i64> {
+    let n = match word {
+        "a" | "one" => 1,
+        "two" | "couple" => 2,
+        "three" | "few" => 3,
+        "four" => 4,
+        "five" => 5,
+        "six" => 6,
+        "seven" => 7,
+        "eight" => 8,
+        "nine" => 9,
+        "ten" => 10,
+        "twelve" => 12,
+        _ => return word.parse().ok().filter(|&n| (1..=1000).contains(&n)),
+    };
+    Some(n)
+}
+
+/// The smallest recency window at least `span` long, if any is.
+fn covering_recency(span: Duration) -> Option<Recency> {
+    [
+        (Duration::days(1), Recency::Day),
+        (Duration::days(7), Recency::Week),
+        (Duration::days(31), Recency::Month),
+        (Duration::days(366), Recency::Year),
+    ]
+    .into_iter()
+    .find(|(window, _)| span <= *window)
+    .map(|(_, recency)| recency)
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct QueryIntent {
     pub question_type: QuestionType,
@@ -44,6 +342,16 @@ impl QueryIntent {
         }
     }
 
+    /// Builds an intent from the query text alone, using
+    /// [`QuestionType::classify`] and [`TimeConstraint::parse`].
+    pub fn classify(query: &str) -> Self {
+        let intent = Self::new(QuestionType::classify(query));
+        match TimeConstraint::parse(query) {
+            Some(constraint) => intent.with_time_constraint(constraint),
+            None => intent,
+        }
+    }
+
     pub fn with_entities(mut self, entities: impl IntoIterator<Item = impl Into<String>>) -> Self {
         self.entities = entities.into_iter().map(Into::into).collect();
         self
@@ -86,12 +394,126 @@ mod tests {
         assert_eq!(json, "\"current_event\"");
     }
 
+    #[test]
+    fn classifies_question_types() {
+        let cases = [
+            ("What is Rust?", QuestionType::Factual),
+            ("Who founded Mozilla", QuestionType::Factual),
+            ("How many moons does Mars have?", QuestionType::Factual),
+            ("Rust vs Go for web services", QuestionType::Comparison),
+            (
+                "What is the difference between TCP and UDP?",
+                QuestionType::Comparison,
+            ),
+            ("Why is the sky blue?", QuestionType::Explanation),
+            (
+                "How does garbage collection work?",
+                QuestionType::Explanation,
+            ),
+            (
+                "latest news on the Rust 2024 edition",
+                QuestionType::CurrentEvent,
+            ),
+            ("How do I install Rust on Windows?", QuestionType::HowTo),
+            ("How to set up a reverse proxy", QuestionType::HowTo),
+            ("Should I learn Rust or Zig first?", QuestionType::Opinion),
+            ("ownership and borrowing", QuestionType::Explanation),
+        ];
+
+        for (query, expected) in cases {
+            assert_eq!(QuestionType::classify(query), expected, "{}", query);
+        }
+    }
+
+    #[test]
+    fn question_type_str_matches_serde() {
+        for question_type in QuestionType::ALL {
+            let json = serde_json::to_string(&question_type).unwrap();
+            assert_eq!(json, format!("\"{}\"", question_type.as_str()));
+        }
+    }
+
     #[test]
     fn serializes_time_constraint_recent() {
         let json = serde_json::to_string(&TimeConstraint::Recent).unwrap();
         assert_eq!(json, "\"recent\"");
     }
 
+    fn at(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap()
+    }
+
+    fn range(from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Option<TimeConstraint> {
+        Some(TimeConstraint::DateRange { from, to })
+    }
+
+    #[test]
+    fn parses_time_expressions() {
+        let now = at(2024, 6, 15);
+        let cases = [
+            (
+                "Rust CVEs in the last two weeks",
+                range(Some(now - Duration::days(14)), None),
+            ),
+            (
+                "past 3 months of AI regulation",
+                range(Some(now - Duration::days(90)), None),
+            ),
+            ("outages this year", range(Some(at(2024, 1, 1)), None)),
+            (
+                "What changed since 2021?",
+                range(Some(at(2021, 1, 1)), None),
+            ),
+            ("EU AI law before 2020", range(None, Some(at(2020, 1, 1)))),
+            (
+                "Layoffs between 2019 and 2021",
+                range(Some(at(2019, 1, 1)), Some(at(2022, 1, 1))),
+            ),
+            (
+                "What happened in 2022",
+                range(Some(at(2022, 1, 1)), Some(at(2023, 1, 1))),
+            ),
+            ("Bitcoin price as of today", Some(TimeConstraint::Recent)),
+            (
+                "history of the printing press",
+                Some(TimeConstraint::Historical),
+            ),
+            ("What is Rust?", None),
+            ("The last samurai", None),
+            ("Rust 2024 edition", None),
+        ];
+
+        for (query, expected) in cases {
+            assert_eq!(TimeConstraint::parse_at(query, now), expected, "{}", query);
+        }
+    }
+
+    #[test]
+    fn applies_time_constraints_to_filters() {
+        let recent = TimeConstraint::Recent.apply(SearchFilters::new());
+        assert_eq!(recent.recency, Some(Recency::Week));
+
+        let two_weeks = TimeConstraint::DateRange {
+            from: Some(Utc::now() - Duration::days(14)),
+            to: None,
+        }
+        .apply(SearchFilters::new());
+        assert!(two_weeks.published_after.is_some());
+        assert_eq!(two_weeks.recency, Some(Recency::Month));
+
+        let explicit = SearchFilters::new().with_recency(Recency::Year);
+        assert_eq!(TimeConstraint::Recent.apply(explicit.clone()), explicit);
+    }
+
+    #[test]
+    fn classify_reads_time_constraint() {
+        let intent = QueryIntent::classify("Rust releases since 2021");
+        assert!(matches!(
+            intent.time_constraint,
+            Some(TimeConstraint::DateRange { .. })
+        ));
+    }
+
     #[test]
     fn serializes_intent() {
         let intent = QueryIntent::new(QuestionType::Factual).with_entities(["Rust"]);
@@ -0,0 +1,196 @@
+//! Numeric citation references.
+//!
+//! Models cite sources inline by their IDs, as in `[src_abc123]`, which
+//! reads poorly. [`number_references`] rewrites those markers into numbers,
+//! as in `[1]` or `[1, 2]`, numbering sources in the order the answer first
+//! cites them, and keeps a [`Reference`] table mapping each number back to
+//! its source ID. Markers naming a source the answer was not written from
+//! are left as they are.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::answer::ResearchAnswer;
+use crate::id::SourceId;
+use crate::source::Source;
+
+/// How answers mark their citations inline.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum CitationMarkers {
+    /// `[1]`, with a table of references.
+    #[default]
+    Numeric,
+    /// `[src_abc123]`, as the model wrote them.
+    SourceId,
+}
+
+impl CitationMarkers {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "numeric" | "number" | "numbers" => Some(Self::Numeric),
+            "source_id" | "source-id" | "id" => Some(Self::SourceId),
+            _ => None,
+        }
+    }
+}
+
+/// The source an inline `[number]` refers to.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reference {
+    pub number: usize,
+    pub source_id: SourceId,
+}
+
+/// Rewrites the source ID markers of `answer`'s summary and detail into
+/// numbers and lists the references. Sources are numbered from 1 in the
+/// order the summary, then the detail, first cite them; cited sources no
+/// marker names follow in the order of the citations, so every citation
+/// has a number. Answers already numbered are left as they are.
+pub fn number_references(answer: &mut ResearchAnswer, sources: &[Source]) {
+    if !answer.references.is_empty() {
+        return;
+    }
+    let known: HashMap<&str, &SourceId> = sources.iter().map(|s| (s.id.as_str(), &s.id)).collect();
+    let mut numbered: Vec<SourceId> = Vec::new();
+
+    answer.summary = rewrite_markers(&answer.summary, &known, &mut numbered);
+    answer.detail = rewrite_markers(&answer.detail, &known, &mut numbered);
+    for citation in &mut answer.citations {
+        citation.reference = Some(number_of(&citation.source_id, &mut numbered));
+    }
+    for section in &mut answer.sections {
+        for citation in &mut section.citations {
+            citation.reference = Some(number_of(&citation.source_id, &mut numbered));
+        }
+    }
+
+    answer.references = numbered
+        .into_iter()
+        .enumerate()
+        .map(|(i, source_id)| Reference {
+            number: i + 1,
+            source_id,
+        })
+        .collect();
+}
+
+/// `text` with every bracket of known source IDs, separated by commas or
+/// semicolons, replaced by their numbers.
+fn rewrite_markers(
+    text: &str,
+    known: &HashMap<&str, &SourceId>,
+    numbered: &mut Vec<SourceId>,
+) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(open) = rest.find('[') {
+        out.push_str(&rest[..open]);
+        let inside = &rest[open + 1..];
+        let marker = inside.find(']').and_then(|close| {
+            inside[..close]
+                .split([',', ';'])
+                .map(|id| known.get(id.trim()).copied())
+                .collect::<Option<Vec<_>>>()
+                .map(|ids| (close, ids))
+        });
+
+        match marker {
+            Some((close, ids)) => {
+                let numbers: Vec<String> = ids
+                    .into_iter()
+                    .map(|id| number_of(id, numbered).to_string())
+                    .collect();
+                out.push('[');
+                out.push_str(&numbers.join(", "));
+                out.push(']');
+                rest = &inside[close + 1..];
+            }
+            None => {
+                out.push('[');
+                rest = inside;
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// The number of `source_id`, numbering it next if it has none yet.
+fn number_of(source_id: &SourceId, numbered: &mut Vec<SourceId>) -> usize {
+    match numbered.iter().position(|id| id == source_id) {
+        Some(i) => i + 1,
+        None => {
+            numbered.push(source_id.clone());
+            numbered.len()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answer::{Citation, Confidence};
+
+    fn sources() -> Vec<Source> {
+        vec![
+            Source::new("https://a.com", "A", ""),
+            Source::new("https://b.com", "B", ""),
+            Source::new("https://c.com", "C", ""),
+        ]
+    }
+
+    #[test]
+    fn numbers_sources_in_order_of_first_citation() {
+        let sources = sources();
+        let (a, b, c) = (&sources[0].id, &sources[1].id, &sources[2].id);
+        let mut answer = ResearchAnswer::new(
+            format!("Rust is fast [{}].", b),
+            format!(
+                "It is fast [{}, {}]. It is safe [{}][{}] [see notes].",
+                b, a, b, b
+            ),
+            Confidence::High,
+            "mock",
+        );
+        answer.citations = vec![
+            Citation::new("fast", a.clone()),
+            Citation::new("used", c.clone()),
+        ];
+
+        number_references(&mut answer, &sources);
+
+        assert_eq!(answer.summary, "Rust is fast [1].");
+        assert_eq!(
+            answer.detail,
+            "It is fast [1, 2]. It is safe [1][1] [see notes]."
+        );
+        let numbers: Vec<(usize, &SourceId)> = answer
+            .references
+            .iter()
+            .map(|r| (r.number, &r.source_id))
+            .collect();
+        assert_eq!(numbers, vec![(1, b), (2, a), (3, c)]);
+        assert_eq!(answer.citations[0].reference, Some(2));
+        assert_eq!(answer.citations[1].reference, Some(3));
+    }
+
+    #[test]
+    fn leaves_unknown_markers_and_numbered_answers() {
+        let sources = sources();
+        let detail = format!("Claimed [src_unknown]. Known [{}].", sources[0].id);
+        let mut answer = ResearchAnswer::new("", detail, Confidence::High, "mock");
+
+        number_references(&mut answer, &sources);
+        assert_eq!(answer.detail, "Claimed [src_unknown]. Known [1].");
+
+        answer.detail = format!("Again [{}].", sources[1].id);
+        number_references(&mut answer, &sources);
+        assert_eq!(answer.references.len(), 1);
+        assert!(answer.detail.contains("src_"));
+    }
+}
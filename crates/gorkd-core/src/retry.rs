@@ -0,0 +1,259 @@
+//! Running failed jobs again.
+//!
+//! A retry is a new job with the failed job's query, linked back to it by
+//! `retried_from`. When the job failed during synthesis its sources are
+//! copied to the retry, which then skips search and only synthesizes again.
+//!
+//! A [`RetryPolicy`] retries jobs automatically when they fail for a
+//! transient reason, such as a throttled provider, waiting longer before
+//! each further attempt.
+
+use std::time::Duration;
+
+use futures_timer::Delay;
+
+use crate::error::ErrorCode;
+use crate::id::JobId;
+use crate::job::{JobStatus, ResearchJob};
+use crate::traits::{Store, StoreError};
+
+/// Wait before the first automatic retry, when none is configured.
+pub const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_secs(1);
+
+/// When failed jobs are retried automatically.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Runs of a query at most, counting the first; `1` disables automatic
+    /// retries.
+    pub max_attempts: u32,
+    /// Wait before the first retry, doubled for each further one.
+    pub backoff: Duration,
+    /// Failure kinds worth retrying. Failures not marked retryable are never
+    /// retried automatically, whatever their kind.
+    pub kinds: Vec<ErrorCode>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            backoff: DEFAULT_RETRY_BACKOFF,
+            kinds: vec![
+                ErrorCode::RateLimited,
+                ErrorCode::Timeout,
+                ErrorCode::ProviderUnavailable,
+            ],
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn is_enabled(&self) -> bool {
+        self.max_attempts > 1
+    }
+
+    /// How long to wait before retrying `job`, or `None` if the policy does
+    /// not retry it.
+    pub fn backoff_for(&self, job: &ResearchJob) -> Option<Duration> {
+        let failure = job.failure.as_ref()?;
+        let retries = job.status == JobStatus::Failed
+            && failure.retryable
+            && self.kinds.contains(&failure.kind)
+            && job.attempt < self.max_attempts;
+        if !retries {
+            return None;
+        }
+
+        let doublings = job.attempt.saturating_sub(1).min(16);
+        Some(self.backoff.saturating_mul(1 << doublings))
+    }
+
+    /// Retries the job `job_id` once its backoff has passed, if the policy
+    /// retries it. Returns the stored retry.
+    pub async fn retry_failed(
+        &self,
+        store: &dyn Store,
+        job_id: &JobId,
+    ) -> Result<Option<ResearchJob>, StoreError> {
+        let Some(job) = store.get_job(job_id).await? else {
+            return Ok(None);
+        };
+        let Some(backoff) = self.backoff_for(&job) else {
+            return Ok(None);
+        };
+
+        Delay::new(backoff).await;
+        create_retry(store, &job).await.map(Some)
+    }
+}
+
+/// Stores a pending retry of the failed job `failed`. When it failed during
+/// synthesis, its sources and search metadata are copied to the retry so
+/// the pipeline does not search again.
+pub async fn create_retry(
+    store: &dyn Store,
+    failed: &ResearchJob,
+) -> Result<ResearchJob, StoreError> {
+    let retry = failed.retry();
+    store.create_job(&retry).await?;
+
+    let synthesis_failed = failed
+        .failure
+        .as_ref()
+        .is_some_and(|f| f.stage == JobStatus::Synthesizing);
+    if synthesis_failed {
+        let sources = store.get_sources(&failed.id).await?;
+        if !sources.is_empty() {
+            store.store_sources(&retry.id, &sources).await?;
+            if let Some(metadata) = store.get_search_metadata(&failed.id).await? {
+                store.store_search_metadata(&retry.id, &metadata).await?;
+            }
+        }
+    }
+
+    Ok(retry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::JobFailure;
+    use crate::mock::MockStore;
+    use crate::source::{SearchMetadata, Source};
+
+    fn failed_job(stage: JobStatus, kind: ErrorCode, retryable: bool) -> ResearchJob {
+        let mut job = ResearchJob::new("What is Rust?").unwrap();
+        job.force_transition_to(stage.clone());
+        job.fail_with(JobFailure {
+            stage,
+            kind,
+            provider: None,
+            retryable,
+            message: "failed".to_string(),
+        })
+        .unwrap();
+        job
+    }
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            backoff: Duration::from_millis(100),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn default_policy_does_not_retry() {
+        let job = failed_job(JobStatus::Searching, ErrorCode::RateLimited, true);
+
+        assert!(!RetryPolicy::default().is_enabled());
+        assert_eq!(RetryPolicy::default().backoff_for(&job), None);
+    }
+
+    #[test]
+    fn backoff_doubles_per_attempt() {
+        let job = failed_job(JobStatus::Searching, ErrorCode::Timeout, true);
+        let mut retry = job.retry();
+        retry.fail_with(job.failure.clone().unwrap()).unwrap();
+
+        assert_eq!(
+            policy(3).backoff_for(&job),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(
+            policy(3).backoff_for(&retry),
+            Some(Duration::from_millis(200))
+        );
+        assert_eq!(policy(3).backoff_for(&retry.retry()), None);
+        assert_eq!(policy(2).backoff_for(&retry), None);
+    }
+
+    #[test]
+    fn retries_only_retryable_kinds() {
+        let policy = policy(3);
+
+        assert!(policy
+            .backoff_for(&failed_job(
+                JobStatus::Searching,
+                ErrorCode::NoSources,
+                false
+            ))
+            .is_none());
+        assert!(policy
+            .backoff_for(&failed_job(
+                JobStatus::Synthesizing,
+                ErrorCode::ProviderError,
+                true
+            ))
+            .is_none());
+        assert!(policy
+            .backoff_for(&failed_job(
+                JobStatus::Searching,
+                ErrorCode::RateLimited,
+                false
+            ))
+            .is_none());
+        assert!(policy
+            .backoff_for(&ResearchJob::new("still running").unwrap())
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn retry_of_synthesis_failure_reuses_sources() {
+        let store = MockStore::new();
+        let job = failed_job(JobStatus::Synthesizing, ErrorCode::RateLimited, true);
+        store.create_job(&job).await.unwrap();
+        let sources = vec![Source::new("https://example.com", "Example", "content")];
+        store.store_sources(&job.id, &sources).await.unwrap();
+        store
+            .store_search_metadata(&job.id, &SearchMetadata::new())
+            .await
+            .unwrap();
+
+        let retry = create_retry(&store, &job).await.unwrap();
+
+        assert_eq!(store.get_job(&retry.id).await.unwrap().unwrap().attempt, 2);
+        assert_eq!(store.get_sources(&retry.id).await.unwrap().len(), 1);
+        assert!(store
+            .get_search_metadata(&retry.id)
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn retry_of_search_failure_searches_again() {
+        let store = MockStore::new();
+        let job = failed_job(JobStatus::Searching, ErrorCode::RateLimited, true);
+        store.create_job(&job).await.unwrap();
+        let sources = vec![Source::new("https://example.com", "Example", "content")];
+        store.store_sources(&job.id, &sources).await.unwrap();
+
+        let retry = create_retry(&store, &job).await.unwrap();
+
+        assert!(store.get_sources(&retry.id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn retry_failed_follows_policy() {
+        let store = MockStore::new();
+        let job = failed_job(JobStatus::Searching, ErrorCode::RateLimited, true);
+        store.create_job(&job).await.unwrap();
+        let policy = RetryPolicy {
+            max_attempts: 2,
+            backoff: Duration::from_millis(1),
+            ..Default::default()
+        };
+
+        let retry = policy.retry_failed(&store, &job.id).await.unwrap().unwrap();
+
+        assert_eq!(retry.retried_from, Some(job.id.clone()));
+        assert!(RetryPolicy::default()
+            .retry_failed(&store, &job.id)
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(store.job_count(), 2);
+    }
+}
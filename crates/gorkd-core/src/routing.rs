@@ -0,0 +1,258 @@
+//! Choosing a model by how hard a question is.
+//!
+//! Most questions are lookups that a fast, cheap model answers as well as a
+//! premium one. With a [`RoutingPolicy`], the pipeline measures each job's
+//! [`QueryComplexity`] from its query and intent, answers simple questions
+//! with its fast model and everything else with its own, and records the
+//! [`RoutingDecision`] with the answer.
+
+use serde::{Deserialize, Serialize};
+
+use crate::query::{QueryIntent, QuestionType};
+
+/// Longest query, in words, routed to the fast model by default.
+pub const DEFAULT_MAX_SIMPLE_WORDS: usize = 16;
+
+/// Most named entities in a query routed to the fast model by default.
+pub const DEFAULT_MAX_SIMPLE_ENTITIES: usize = 2;
+
+/// Most hops in a query routed to the fast model by default: a single
+/// question, with no clause that has to be resolved first.
+pub const DEFAULT_MAX_SIMPLE_HOPS: usize = 1;
+
+/// Words that open a clause the answer depends on, as in "the company
+/// *that* makes the iPhone".
+const CLAUSE_MARKERS: &[&str] = &[
+    " that ", " which ", " whose ", " who ", " whom ", " where ", " when ",
+];
+
+/// Words that chain a second question onto the first.
+const FOLLOW_UP_MARKERS: &[&str] = &[
+    " and who ",
+    " and what ",
+    " and when ",
+    " and where ",
+    " and why ",
+    " and how ",
+    " and which ",
+];
+
+/// Which of the pipeline's models a job is routed to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelTier {
+    Fast,
+    Premium,
+}
+
+impl ModelTier {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Fast => "fast",
+            Self::Premium => "premium",
+        }
+    }
+}
+
+impl std::fmt::Display for ModelTier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What makes a query hard to answer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryComplexity {
+    pub question_type: QuestionType,
+    pub words: usize,
+    /// Entities of the intent, or runs of capitalized words after the
+    /// first when the intent names none.
+    pub entities: usize,
+    /// Questions that have to be answered in turn: one, plus one for each
+    /// dependent clause or follow-up question.
+    pub hops: usize,
+}
+
+impl QueryComplexity {
+    pub fn assess(query: &str, intent: &QueryIntent) -> Self {
+        let words: Vec<&str> = query.split_whitespace().collect();
+        let entities = if intent.entities.is_empty() {
+            capitalized_runs(&words)
+        } else {
+            intent.entities.len()
+        };
+
+        let lowered = format!("{} ", query.trim().to_lowercase());
+        let count = |markers: &[&str]| {
+            markers
+                .iter()
+                .map(|m| lowered.matches(m).count())
+                .sum::<usize>()
+        };
+        let questions = query.matches('?').count().max(1);
+        let hops = questions + count(CLAUSE_MARKERS) + count(FOLLOW_UP_MARKERS);
+
+        Self {
+            question_type: intent.question_type,
+            words: words.len(),
+            entities,
+            hops,
+        }
+    }
+}
+
+/// Runs of capitalized words, not counting the query's first word.
+fn capitalized_runs(words: &[&str]) -> usize {
+    let mut runs = 0;
+    let mut in_run = false;
+    for word in words.iter().skip(1) {
+        let capitalized = word.chars().next().is_some_and(char::is_uppercase);
+        if capitalized && !in_run {
+            runs += 1;
+        }
+        in_run = capitalized && !word.ends_with([',', ';', '.', '?']);
+    }
+    runs
+}
+
+/// Which queries are simple enough for the fast model.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoutingPolicy {
+    /// Question types the fast model may answer.
+    pub simple_types: Vec<QuestionType>,
+    pub max_words: usize,
+    pub max_entities: usize,
+    pub max_hops: usize,
+}
+
+impl Default for RoutingPolicy {
+    fn default() -> Self {
+        Self {
+            simple_types: vec![QuestionType::Factual],
+            max_words: DEFAULT_MAX_SIMPLE_WORDS,
+            max_entities: DEFAULT_MAX_SIMPLE_ENTITIES,
+            max_hops: DEFAULT_MAX_SIMPLE_HOPS,
+        }
+    }
+}
+
+impl RoutingPolicy {
+    pub fn with_simple_types(mut self, types: impl IntoIterator<Item = QuestionType>) -> Self {
+        self.simple_types = types.into_iter().collect();
+        self
+    }
+
+    pub fn with_max_words(mut self, max: usize) -> Self {
+        self.max_words = max;
+        self
+    }
+
+    pub fn with_max_entities(mut self, max: usize) -> Self {
+        self.max_entities = max;
+        self
+    }
+
+    pub fn with_max_hops(mut self, max: usize) -> Self {
+        self.max_hops = max;
+        self
+    }
+
+    /// The tier a query of `complexity` is answered by, and why: the first
+    /// threshold it exceeds, or that it exceeds none.
+    pub fn tier(&self, complexity: &QueryComplexity) -> (ModelTier, String) {
+        let question_type = complexity.question_type;
+        let exceeded = if !self.simple_types.contains(&question_type) {
+            Some(format!(
+                "{} questions need the premium model",
+                question_type
+            ))
+        } else if complexity.words > self.max_words {
+            Some(format!(
+                "{} words, over the limit of {}",
+                complexity.words, self.max_words
+            ))
+        } else if complexity.entities > self.max_entities {
+            Some(format!(
+                "{} entities, over the limit of {}",
+                complexity.entities, self.max_entities
+            ))
+        } else if complexity.hops > self.max_hops {
+            Some(format!(
+                "{} hops, over the limit of {}",
+                complexity.hops, self.max_hops
+            ))
+        } else {
+            None
+        };
+
+        match exceeded {
+            Some(reason) => (ModelTier::Premium, reason),
+            None => (
+                ModelTier::Fast,
+                format!("simple {} question", question_type),
+            ),
+        }
+    }
+}
+
+/// The model a job was routed to, and why.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoutingDecision {
+    pub tier: ModelTier,
+    pub model: String,
+    pub reason: String,
+    pub complexity: QueryComplexity,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assess(query: &str) -> QueryComplexity {
+        QueryComplexity::assess(query, &QueryIntent::classify(query))
+    }
+
+    #[test]
+    fn measures_query_complexity() {
+        let simple = assess("What is the capital of France?");
+        assert_eq!(simple.question_type, QuestionType::Factual);
+        assert_eq!(simple.words, 6);
+        assert_eq!(simple.entities, 1);
+        assert_eq!(simple.hops, 1);
+
+        let chained = assess("Who is the CEO of the company that acquired GitHub?");
+        assert_eq!(chained.hops, 2);
+
+        let named = assess("When did New York, Apple and the EU sign it?");
+        assert_eq!(named.entities, 3);
+
+        let preset = QueryIntent::classify("Rust vs Go").with_entities(["Rust", "Go"]);
+        assert_eq!(QueryComplexity::assess("Rust vs Go", &preset).entities, 2);
+    }
+
+    #[test]
+    fn routes_simple_questions_to_fast_model() {
+        let policy = RoutingPolicy::default();
+
+        let (tier, reason) = policy.tier(&assess("What is the capital of France?"));
+        assert_eq!(tier, ModelTier::Fast);
+        assert_eq!(reason, "simple factual question");
+
+        let (tier, reason) = policy.tier(&assess("Why is the sky blue?"));
+        assert_eq!(tier, ModelTier::Premium);
+        assert_eq!(reason, "explanation questions need the premium model");
+
+        let (tier, reason) = policy.tier(&assess("Who founded the company that makes the iPhone?"));
+        assert_eq!(tier, ModelTier::Premium);
+        assert_eq!(reason, "2 hops, over the limit of 1");
+    }
+
+    #[test]
+    fn thresholds_are_configurable() {
+        let query = assess("Who founded the company that makes the iPhone?");
+        let policy = RoutingPolicy::default().with_max_hops(2).with_max_words(4);
+
+        assert_eq!(policy.tier(&query).1, "8 words, over the limit of 4");
+        assert_eq!(policy.with_max_words(10).tier(&query).0, ModelTier::Fast);
+    }
+}
@@ -1,7 +1,10 @@
 use std::time::Duration;
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::query::TimeConstraint;
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 #[non_exhaustive]
@@ -13,7 +16,20 @@ pub enum Recency {
     Any,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+impl Recency {
+    /// How far back results may be published, or `None` for any time.
+    pub fn window(&self) -> Option<chrono::Duration> {
+        match self {
+            Self::Day => Some(chrono::Duration::days(1)),
+            Self::Week => Some(chrono::Duration::days(7)),
+            Self::Month => Some(chrono::Duration::days(30)),
+            Self::Year => Some(chrono::Duration::days(365)),
+            Self::Any => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 #[non_exhaustive]
 pub enum ContentType {
@@ -24,12 +40,50 @@ pub enum ContentType {
     Forum,
 }
 
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+impl ContentType {
+    pub const ALL: [ContentType; 5] = [
+        Self::News,
+        Self::Academic,
+        Self::General,
+        Self::Blog,
+        Self::Forum,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::News => "news",
+            Self::Academic => "academic",
+            Self::General => "general",
+            Self::Blog => "blog",
+            Self::Forum => "forum",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SearchFilters {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub recency: Option<Recency>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub include_domains: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub exclude_domains: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub content_type: Option<ContentType>,
+    /// ISO 639-1 code of the language results should be in, such as `de`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// ISO 3166-1 alpha-2 code of the country to favor results from, such
+    /// as `DE`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    /// Only results published at or after this time. Unlike `recency`, an
+    /// absolute bound, for research into a past period.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published_after: Option<DateTime<Utc>>,
+    /// Only results published before this time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published_before: Option<DateTime<Utc>>,
 }
 
 impl SearchFilters {
@@ -37,6 +91,75 @@ impl SearchFilters {
         Self::default()
     }
 
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether sources from `domain` pass the domain filters. A listed
+    /// domain also covers its subdomains, so `nih.gov` admits
+    /// `pubmed.ncbi.nlm.nih.gov`.
+    pub fn admits(&self, domain: &str) -> bool {
+        let included = match self.include_domains {
+            Some(ref domains) if !domains.is_empty() => any_covers(domains, domain),
+            _ => true,
+        };
+        included && !any_covers(self.exclude_domains.iter().flatten(), domain)
+    }
+
+    /// These filters without the recency and content-type filters, which
+    /// narrow a search without bounding which sources it may cite. Returns
+    /// the names of the filters dropped.
+    pub fn relaxed(&self) -> (Self, Vec<&'static str>) {
+        let mut dropped = Vec::new();
+        if self.recency.is_some() {
+            dropped.push("recency");
+        }
+        if self.content_type.is_some() {
+            dropped.push("content_type");
+        }
+        let relaxed = Self {
+            recency: None,
+            content_type: None,
+            ..self.clone()
+        };
+        (relaxed, dropped)
+    }
+
+    /// Whether a result published at `published_at` passes the recency and
+    /// date filters as of `now`. Results without a known date pass, since
+    /// they cannot be checked.
+    pub fn admits_published(
+        &self,
+        published_at: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> bool {
+        let Some(published_at) = published_at else {
+            return true;
+        };
+        let recent = match self.recency.as_ref().and_then(Recency::window) {
+            Some(window) => published_at >= now - window,
+            None => true,
+        };
+        recent
+            && self
+                .published_after
+                .map_or(true, |after| published_at >= after)
+            && self
+                .published_before
+                .map_or(true, |before| published_at < before)
+    }
+
+    /// Whether the filters restrict which results a provider may return,
+    /// so its results can be checked against them.
+    pub fn restricts_results(&self) -> bool {
+        let listed =
+            |domains: &Option<Vec<String>>| domains.as_ref().is_some_and(|d| !d.is_empty());
+        listed(&self.include_domains)
+            || listed(&self.exclude_domains)
+            || self.recency.as_ref().and_then(Recency::window).is_some()
+            || self.has_date_range()
+    }
+
     pub fn with_recency(mut self, recency: Recency) -> Self {
         self.recency = Some(recency);
         self
@@ -56,6 +179,70 @@ impl SearchFilters {
         self.content_type = Some(content_type);
         self
     }
+
+    /// Sets the results' language, lowercased.
+    pub fn with_language(mut self, language: impl AsRef<str>) -> Self {
+        self.language = Some(language.as_ref().to_ascii_lowercase());
+        self
+    }
+
+    /// Sets the country to favor, uppercased.
+    pub fn with_country(mut self, country: impl AsRef<str>) -> Self {
+        self.country = Some(country.as_ref().to_ascii_uppercase());
+        self
+    }
+
+    /// Limits results to those published from `after` up to `before`.
+    /// Either bound may be left open.
+    pub fn published_between(
+        mut self,
+        after: Option<DateTime<Utc>>,
+        before: Option<DateTime<Utc>>,
+    ) -> Self {
+        self.published_after = after;
+        self.published_before = before;
+        self
+    }
+
+    /// Whether the filters bound publication dates absolutely.
+    pub fn has_date_range(&self) -> bool {
+        self.published_after.is_some() || self.published_before.is_some()
+    }
+}
+
+/// Whether any of the `listed` domains is `domain` or one of its parents.
+fn any_covers<'a>(listed: impl IntoIterator<Item = &'a String>, domain: &str) -> bool {
+    let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+    listed.into_iter().any(|listed| {
+        let listed = listed.trim_end_matches('.').to_ascii_lowercase();
+        domain == listed
+            || domain
+                .strip_suffix(&listed)
+                .is_some_and(|sub| sub.ends_with('.'))
+    })
+}
+
+/// Domains an operator allows or denies for every job, whatever filters
+/// the job asks for. A listed domain also covers its subdomains.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DomainPolicy {
+    /// When not empty, sources must come from one of these domains.
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// Sources from these domains are always dropped, even when allowed.
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+impl DomainPolicy {
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    pub fn admits(&self, domain: &str) -> bool {
+        (self.allow.is_empty() || any_covers(&self.allow, domain))
+            && !any_covers(&self.deny, domain)
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -101,6 +288,27 @@ impl std::fmt::Display for ProviderId {
 pub const DEFAULT_MAX_SOURCES: usize = 10;
 pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
 
+/// A job's own bounds on the sources it collects, in place of the
+/// executor's configured ones. Unset bounds keep the configured value.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SourceLimits {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_sources: Option<usize>,
+    /// Search results scoring below this are dropped, from 0 to 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_score: Option<f32>,
+    /// Sources kept from any one domain, so a single site cannot dominate
+    /// the answer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_per_domain: Option<usize>,
+}
+
+impl SourceLimits {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SearchPlan {
     pub queries: Vec<SearchQuery>,
@@ -108,6 +316,9 @@ pub struct SearchPlan {
     pub max_sources: usize,
     #[serde(with = "humantime_serde")]
     pub timeout: Duration,
+    /// The period the question asks about, as read from its wording.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_constraint: Option<TimeConstraint>,
 }
 
 impl SearchPlan {
@@ -117,6 +328,7 @@ impl SearchPlan {
             providers,
             max_sources: DEFAULT_MAX_SOURCES,
             timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+            time_constraint: None,
         }
     }
 
@@ -129,6 +341,29 @@ impl SearchPlan {
         self.timeout = timeout;
         self
     }
+
+    /// Narrows every query to the period `constraint` describes, where the
+    /// query does not bound dates itself, and records it.
+    pub fn with_time_constraint(mut self, constraint: Option<TimeConstraint>) -> Self {
+        if let Some(ref constraint) = constraint {
+            for query in &mut self.queries {
+                query.filters = constraint.apply(std::mem::take(&mut query.filters));
+            }
+        }
+        self.time_constraint = constraint;
+        self
+    }
+
+    /// Applies `filters` to every query, in place of their own. Empty
+    /// filters leave the queries as planned.
+    pub fn with_filters(mut self, filters: &SearchFilters) -> Self {
+        if !filters.is_empty() {
+            for query in &mut self.queries {
+                query.filters = filters.clone();
+            }
+        }
+        self
+    }
 }
 
 mod humantime_serde {
@@ -154,6 +389,7 @@ mod humantime_serde {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
 
     #[test]
     fn creates_search_query() {
@@ -175,6 +411,66 @@ mod tests {
         );
     }
 
+    #[test]
+    fn normalizes_locale_case() {
+        let filters = SearchFilters::new().with_language("DE").with_country("at");
+
+        assert_eq!(filters.language.as_deref(), Some("de"));
+        assert_eq!(filters.country.as_deref(), Some("AT"));
+        assert!(!filters.is_empty());
+    }
+
+    #[test]
+    fn admits_listed_domains_and_subdomains() {
+        let filters = SearchFilters::new()
+            .include_domains(["nih.gov", "docs.rs"])
+            .exclude_domains(["ads.nih.gov"]);
+
+        assert!(filters.admits("nih.gov"));
+        assert!(filters.admits("pubmed.ncbi.nlm.NIH.gov"));
+        assert!(filters.admits("docs.rs"));
+        assert!(!filters.admits("ads.nih.gov"));
+        assert!(!filters.admits("notnih.gov"));
+        assert!(!filters.admits("example.com"));
+        assert!(SearchFilters::new().admits("example.com"));
+        assert!(SearchFilters::new()
+            .exclude_domains(["spam.com"])
+            .admits("example.com"));
+    }
+
+    #[test]
+    fn admits_results_published_within_filters() {
+        let now = Utc.with_ymd_and_hms(2024, 7, 1, 0, 0, 0).unwrap();
+        let date = |y, m, d| Some(Utc.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap());
+        let recent = SearchFilters::new().with_recency(Recency::Week);
+        let period = SearchFilters::new().published_between(date(2023, 3, 1), date(2023, 7, 1));
+
+        assert!(recent.admits_published(date(2024, 6, 28), now));
+        assert!(!recent.admits_published(date(2024, 5, 1), now));
+        assert!(period.admits_published(date(2023, 3, 1), now));
+        assert!(!period.admits_published(date(2023, 7, 1), now));
+        assert!(!period.admits_published(date(2022, 12, 31), now));
+        assert!(period.admits_published(None, now));
+        assert!(period.restricts_results());
+        assert!(!SearchFilters::new()
+            .with_recency(Recency::Any)
+            .restricts_results());
+    }
+
+    #[test]
+    fn domain_policy_denies_over_allowing() {
+        let policy = DomainPolicy {
+            allow: vec!["example.com".to_string(), "rust-lang.org".to_string()],
+            deny: vec!["ads.example.com".to_string()],
+        };
+
+        assert!(policy.admits("blog.rust-lang.org"));
+        assert!(policy.admits("example.com"));
+        assert!(!policy.admits("ads.example.com"));
+        assert!(!policy.admits("competitor.com"));
+        assert!(DomainPolicy::default().admits("competitor.com"));
+    }
+
     #[test]
     fn creates_search_plan() {
         let queries = vec![SearchQuery::new("test")];
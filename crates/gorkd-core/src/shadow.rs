@@ -0,0 +1,198 @@
+//! Shadow evaluation of candidate providers.
+//!
+//! A new search provider or model is easier to trust after it has seen live
+//! traffic. In shadow mode every call to the primary is repeated against a
+//! candidate in the background: the caller only ever gets the primary's
+//! results, and the candidate's are compared with them and thrown away.
+//! [`ShadowMetrics`] keeps, per candidate, how often it failed, how fast it
+//! was next to the primary and how far its results agreed with the
+//! primary's, so operators can judge it before promoting it.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// What kind of provider is being shadowed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum ShadowKind {
+    Search,
+    Llm,
+}
+
+impl ShadowKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Search => "search",
+            Self::Llm => "llm",
+        }
+    }
+}
+
+/// One call made to both the primary and the candidate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShadowSample {
+    pub primary_latency: Duration,
+    pub candidate_latency: Duration,
+    pub primary_ok: bool,
+    pub candidate_ok: bool,
+    /// How far the candidate's results agree with the primary's, from 0 to
+    /// 1, when both succeeded and their results can be compared.
+    pub overlap: Option<f32>,
+}
+
+/// Totals for one candidate since startup.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ShadowStats {
+    pub calls: u64,
+    pub primary_errors: u64,
+    pub candidate_errors: u64,
+    /// Calls the candidate answered sooner than the primary.
+    pub candidate_faster: u64,
+    pub primary_total: Duration,
+    pub candidate_total: Duration,
+    pub candidate_max: Duration,
+    /// Calls with an overlap score.
+    pub compared: u64,
+    pub overlap_total: f64,
+}
+
+impl ShadowStats {
+    pub fn primary_mean(&self) -> Duration {
+        mean(self.primary_total, self.calls)
+    }
+
+    pub fn candidate_mean(&self) -> Duration {
+        mean(self.candidate_total, self.calls)
+    }
+
+    /// Mean overlap of the compared calls; `None` before any was compared.
+    pub fn mean_overlap(&self) -> Option<f64> {
+        (self.compared > 0).then(|| self.overlap_total / self.compared as f64)
+    }
+
+    fn add(&mut self, sample: &ShadowSample) {
+        self.calls += 1;
+        if !sample.primary_ok {
+            self.primary_errors += 1;
+        }
+        if !sample.candidate_ok {
+            self.candidate_errors += 1;
+        }
+        if sample.candidate_latency < sample.primary_latency {
+            self.candidate_faster += 1;
+        }
+        self.primary_total += sample.primary_latency;
+        self.candidate_total += sample.candidate_latency;
+        self.candidate_max = self.candidate_max.max(sample.candidate_latency);
+        if let Some(overlap) = sample.overlap {
+            self.compared += 1;
+            self.overlap_total += f64::from(overlap);
+        }
+    }
+}
+
+fn mean(total: Duration, calls: u64) -> Duration {
+    match calls {
+        0 => Duration::ZERO,
+        calls => total / calls as u32,
+    }
+}
+
+/// A candidate's totals against the primary it shadowed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShadowEvaluation {
+    pub kind: ShadowKind,
+    pub primary: String,
+    pub candidate: String,
+    pub stats: ShadowStats,
+}
+
+/// Totals of every shadowed candidate, shared by the providers that shadow
+/// them.
+#[derive(Debug, Default)]
+pub struct ShadowMetrics {
+    evaluations: Mutex<BTreeMap<(ShadowKind, String, String), ShadowStats>>,
+}
+
+impl ShadowMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, kind: ShadowKind, primary: &str, candidate: &str, sample: &ShadowSample) {
+        let mut evaluations = self.evaluations.lock().unwrap();
+        evaluations
+            .entry((kind, primary.to_string(), candidate.to_string()))
+            .or_default()
+            .add(sample);
+    }
+
+    /// Every candidate that has been called, by kind, primary and candidate.
+    pub fn snapshot(&self) -> Vec<ShadowEvaluation> {
+        let evaluations = self.evaluations.lock().unwrap();
+        evaluations
+            .iter()
+            .map(|((kind, primary, candidate), stats)| ShadowEvaluation {
+                kind: *kind,
+                primary: primary.clone(),
+                candidate: candidate.clone(),
+                stats: stats.clone(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(primary_ms: u64, candidate_ms: u64, overlap: Option<f32>) -> ShadowSample {
+        ShadowSample {
+            primary_latency: Duration::from_millis(primary_ms),
+            candidate_latency: Duration::from_millis(candidate_ms),
+            primary_ok: true,
+            candidate_ok: overlap.is_some(),
+            overlap,
+        }
+    }
+
+    #[test]
+    fn totals_samples_per_candidate() {
+        let metrics = ShadowMetrics::new();
+        metrics.record(
+            ShadowKind::Search,
+            "fallback",
+            "exa",
+            &sample(100, 50, Some(0.5)),
+        );
+        metrics.record(
+            ShadowKind::Search,
+            "fallback",
+            "exa",
+            &sample(100, 300, None),
+        );
+        metrics.record(
+            ShadowKind::Llm,
+            "gpt-4o",
+            "gpt-4o-mini",
+            &sample(900, 400, Some(1.0)),
+        );
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        let exa = &snapshot[0];
+        assert_eq!(
+            (exa.kind, exa.candidate.as_str()),
+            (ShadowKind::Search, "exa")
+        );
+        assert_eq!(exa.stats.calls, 2);
+        assert_eq!(exa.stats.candidate_errors, 1);
+        assert_eq!(exa.stats.candidate_faster, 1);
+        assert_eq!(exa.stats.candidate_mean(), Duration::from_millis(175));
+        assert_eq!(exa.stats.candidate_max, Duration::from_millis(300));
+        assert_eq!(exa.stats.mean_overlap(), Some(0.5));
+        assert_eq!(snapshot[1].stats.mean_overlap(), Some(1.0));
+        assert_eq!(ShadowStats::default().mean_overlap(), None);
+    }
+}
@@ -0,0 +1,293 @@
+//! Snippets of sources with the query's terms highlighted.
+//!
+//! Each source found for a job gets a [`Snippet`]: the stretch of its
+//! content, at most [`SNIPPET_CHARS`] long, where the most distinct terms of
+//! the query occur, with every occurrence marked. The terms are the query's
+//! keywords and the entities classified from it, matched whole and without
+//! regard to case. An interface can show why a source was retrieved from the
+//! snippet alone, without loading its content. Offsets are in characters,
+//! as quote locations are.
+
+use serde::{Deserialize, Serialize};
+
+use crate::pipeline::keywords;
+use crate::source::Source;
+
+/// Most characters of content a snippet shows.
+pub const SNIPPET_CHARS: usize = 240;
+
+/// A stretch of a source's content and the query terms in it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snippet {
+    /// The content shown, with line breaks turned into spaces.
+    pub text: String,
+    /// Character offset of the snippet in the content.
+    pub start: usize,
+    /// Character offset just past the snippet in the content.
+    pub end: usize,
+    /// Occurrences of query terms, in order; empty when none of the terms
+    /// occur and the snippet is the opening of the content.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub matches: Vec<TermMatch>,
+}
+
+/// An occurrence of a query term in a snippet.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TermMatch {
+    /// Character offset of the occurrence in the snippet text.
+    pub start: usize,
+    /// Character offset just past the occurrence in the snippet text.
+    pub end: usize,
+    /// The term matched, in lowercase.
+    pub term: String,
+}
+
+/// The terms of `query` snippets highlight: its entities, then its keywords
+/// the entities do not already contain, lowercase and each once.
+pub fn query_terms(query: &str, entities: &[String]) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    let words = keywords(query);
+    let candidates = entities
+        .iter()
+        .map(|e| e.trim().to_lowercase())
+        .chain(words.split_whitespace().map(str::to_lowercase));
+
+    for term in candidates {
+        let covered = terms
+            .iter()
+            .any(|t| t.split_whitespace().any(|word| word == term));
+        if !term.is_empty() && !covered && !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+    terms
+}
+
+/// Sets the snippet of every source for `terms`. Sources without content get
+/// none.
+pub fn add_snippets(sources: &mut [Source], terms: &[String]) {
+    for source in sources {
+        source.snippet = snippet(&source.content, terms, SNIPPET_CHARS);
+    }
+}
+
+/// The window of at most `max_chars` of `content` covering the most distinct
+/// `terms`, then the most occurrences; the opening of the content when no
+/// term occurs. `None` for blank content.
+pub fn snippet(content: &str, terms: &[String], max_chars: usize) -> Option<Snippet> {
+    let chars: Vec<char> = content.chars().collect();
+    if chars.iter().all(|c| c.is_whitespace()) || max_chars == 0 {
+        return None;
+    }
+    let found = find_terms(&chars, terms);
+
+    let (start, end) = match best_window(&found, max_chars) {
+        Some((first, last)) => {
+            let (from, to) = (found[first].start, found[last].end);
+            let spare = max_chars - (to - from);
+            let mut start = from.saturating_sub(spare / 2);
+            let end = (start + max_chars).min(chars.len());
+            // Spare room the end of the content left unused goes before.
+            start = start.min(end.saturating_sub(max_chars));
+            (word_start(&chars, start, from), word_end(&chars, end, to))
+        }
+        None => {
+            let start = chars.iter().position(|c| !c.is_whitespace()).unwrap_or(0);
+            let end = (start + max_chars).min(chars.len());
+            (start, word_end(&chars, end, start))
+        }
+    };
+
+    let text = chars[start..end]
+        .iter()
+        .map(|&c| if c == '\n' || c == '\r' { ' ' } else { c })
+        .collect();
+    let matches = found
+        .into_iter()
+        .filter(|m| m.start >= start && m.end <= end)
+        .map(|m| TermMatch {
+            start: m.start - start,
+            end: m.end - start,
+            term: m.term,
+        })
+        .collect();
+
+    Some(Snippet {
+        text,
+        start,
+        end,
+        matches,
+    })
+}
+
+/// Whole-word occurrences of `terms` in `chars`, in order and without
+/// overlaps; at one position the longest term wins.
+fn find_terms(chars: &[char], terms: &[String]) -> Vec<TermMatch> {
+    let folded: Vec<char> = chars.iter().map(|&c| fold(c)).collect();
+    let mut terms: Vec<Vec<char>> = terms
+        .iter()
+        .map(|t| t.chars().map(fold).collect())
+        .collect();
+    terms.retain(|t| !t.is_empty());
+    terms.sort_by_key(|t| std::cmp::Reverse(t.len()));
+
+    let boundary = |i: usize| i == 0 || i >= chars.len() || !chars[i - 1].is_alphanumeric();
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < folded.len() {
+        let matched = boundary(i).then(|| {
+            terms.iter().find(|term| {
+                let end = i + term.len();
+                folded.get(i..end) == Some(term.as_slice())
+                    && chars.get(end).map_or(true, |c| !c.is_alphanumeric())
+            })
+        });
+        match matched.flatten() {
+            Some(term) => {
+                found.push(TermMatch {
+                    start: i,
+                    end: i + term.len(),
+                    term: term.iter().collect(),
+                });
+                i += term.len();
+            }
+            None => i += 1,
+        }
+    }
+    found
+}
+
+/// The first and last of `found` in the window of at most `max_chars`
+/// holding the most distinct terms, then the most occurrences.
+fn best_window(found: &[TermMatch], max_chars: usize) -> Option<(usize, usize)> {
+    let mut best: Option<((usize, usize), (usize, usize))> = None;
+    for first in 0..found.len() {
+        let mut last = first;
+        while last + 1 < found.len() && found[last + 1].end - found[first].start <= max_chars {
+            last += 1;
+        }
+        if found[last].end - found[first].start > max_chars {
+            continue;
+        }
+        let mut distinct: Vec<&str> = found[first..=last]
+            .iter()
+            .map(|m| m.term.as_str())
+            .collect();
+        distinct.sort_unstable();
+        distinct.dedup();
+        let score = (distinct.len(), last - first + 1);
+        if best.map_or(true, |(best_score, _)| score > best_score) {
+            best = Some((score, (first, last)));
+        }
+    }
+    best.map(|(_, window)| window)
+}
+
+/// `c` in lowercase, when that is a single character, so folding keeps
+/// offsets.
+fn fold(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(l), None) => l,
+        _ => c,
+    }
+}
+
+/// `start` moved forward to the start of a word, but not past `limit`.
+fn word_start(chars: &[char], start: usize, limit: usize) -> usize {
+    if start == 0 || !chars[start - 1].is_alphanumeric() {
+        return start;
+    }
+    (start..limit)
+        .find(|&i| chars[i].is_whitespace())
+        .map_or(start, |i| i + 1)
+}
+
+/// `end` moved back to the end of a word, but not before `limit`.
+fn word_end(chars: &[char], end: usize, limit: usize) -> usize {
+    if end >= chars.len() || !chars[end].is_alphanumeric() {
+        return end;
+    }
+    (limit..end)
+        .rev()
+        .find(|&i| chars[i].is_whitespace())
+        .unwrap_or(end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(snippet: &Snippet, m: &TermMatch) -> String {
+        snippet
+            .text
+            .chars()
+            .skip(m.start)
+            .take(m.end - m.start)
+            .collect()
+    }
+
+    #[test]
+    fn takes_entities_then_keywords_as_terms() {
+        let terms = query_terms(
+            "What did Mozilla say about the Firefox release?",
+            &["Mozilla".to_string(), "Firefox".to_string()],
+        );
+
+        assert_eq!(terms, vec!["mozilla", "firefox", "say", "release"]);
+    }
+
+    #[test]
+    fn highlights_the_window_with_the_most_terms() {
+        let filler = "Unrelated words fill this part of the page. ".repeat(8);
+        let content = format!(
+            "Rust is mentioned once here. {}\nThe Rust compiler checks \
+             ownership, so RUST programs avoid data races.",
+            filler
+        );
+        let terms = vec!["rust".to_string(), "ownership".to_string()];
+
+        let snippet = snippet(&content, &terms, 120).unwrap();
+
+        assert!(snippet.text.ends_with("avoid data races."));
+        assert!(snippet.end - snippet.start <= 120);
+        assert!(!snippet.text.contains('\n'));
+        let matched: Vec<String> = snippet.matches.iter().map(|m| text(&snippet, m)).collect();
+        assert_eq!(matched, vec!["Rust", "ownership", "RUST"]);
+        assert_eq!(snippet.matches[1].term, "ownership");
+        let shown: String = content
+            .chars()
+            .skip(snippet.start)
+            .take(snippet.end - snippet.start)
+            .collect();
+        assert_eq!(shown.replace('\n', " "), snippet.text);
+    }
+
+    #[test]
+    fn matches_whole_words_and_phrases() {
+        let terms = vec!["rust foundation".to_string(), "rust".to_string()];
+
+        let snippet = snippet(
+            "Rustaceans joined the Rust Foundation. Rust is fast.",
+            &terms,
+            SNIPPET_CHARS,
+        )
+        .unwrap();
+
+        let terms: Vec<&str> = snippet.matches.iter().map(|m| m.term.as_str()).collect();
+        assert_eq!(terms, vec!["rust foundation", "rust"]);
+        assert_eq!(text(&snippet, &snippet.matches[0]), "Rust Foundation");
+    }
+
+    #[test]
+    fn opens_the_content_when_no_term_occurs() {
+        let terms = vec!["python".to_string()];
+
+        let snippet = snippet("  Rust is a systems language.", &terms, 14).unwrap();
+
+        assert_eq!(snippet.text, "Rust is a");
+        assert_eq!(snippet.start, 2);
+        assert!(snippet.matches.is_empty());
+        assert!(super::snippet(" \n ", &terms, 14).is_none());
+    }
+}
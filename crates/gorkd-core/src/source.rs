@@ -3,8 +3,24 @@ use std::time::Duration;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::feedback::TrustLabel;
 use crate::id::SourceId;
+use crate::query::TimeConstraint;
 use crate::search::ProviderId;
+use crate::snippet::Snippet;
+
+/// Format of a page gorkd downloaded and extracted text from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum DocumentFormat {
+    Html,
+    Pdf,
+    PlainText,
+    Markdown,
+    /// A video's captions, one `[m:ss]`-stamped line per caption.
+    Transcript,
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SourceMetadata {
@@ -12,6 +28,30 @@ pub struct SourceMetadata {
     pub published_at: Option<DateTime<Utc>>,
     pub author: Option<String>,
     pub word_count: usize,
+    /// Search provider that returned this source.
+    #[serde(default)]
+    pub provider: Option<ProviderId>,
+    /// Set when the content was fetched from the page rather than returned
+    /// by the search provider.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<DocumentFormat>,
+    /// How feedback has judged the source's domain, when it has any. The
+    /// domain's trust has already shifted the source's relevance score.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trust: Option<TrustLabel>,
+    /// Set when the page was gone or paywalled and its content came from a
+    /// Wayback Machine snapshot instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived: Option<ArchivedSnapshot>,
+}
+
+/// A copy of a page kept by the Internet Archive's Wayback Machine.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedSnapshot {
+    /// Where the snapshot can be read.
+    pub url: String,
+    /// When the archive captured the page.
+    pub captured_at: DateTime<Utc>,
 }
 
 impl SourceMetadata {
@@ -21,6 +61,10 @@ impl SourceMetadata {
             published_at: None,
             author: None,
             word_count: 0,
+            provider: None,
+            format: None,
+            trust: None,
+            archived: None,
         }
     }
 
@@ -38,6 +82,21 @@ impl SourceMetadata {
         self.word_count = count;
         self
     }
+
+    pub fn with_provider(mut self, provider: ProviderId) -> Self {
+        self.provider = Some(provider);
+        self
+    }
+
+    pub fn with_format(mut self, format: DocumentFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    pub fn with_archived(mut self, snapshot: ArchivedSnapshot) -> Self {
+        self.archived = Some(snapshot);
+        self
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -48,6 +107,22 @@ pub struct Source {
     pub content: String,
     pub metadata: SourceMetadata,
     pub relevance_score: f32,
+    /// Image URLs the search provider associated with this source, plus key
+    /// images found on the page when it was fetched.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<String>,
+    /// Whether `content` was cut to fit the pipeline's content limits.
+    #[serde(default)]
+    pub truncated: bool,
+    /// Key of the object holding `content` when a store keeps it in object
+    /// storage, in which case `content` is empty in the store's own rows.
+    /// Stores hand sources back with their content in place.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_ref: Option<String>,
+    /// Where the query's terms occur in `content`, set when the source was
+    /// found for a job.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<Snippet>,
 }
 
 impl Source {
@@ -66,6 +141,10 @@ impl Source {
             content: content.into(),
             metadata: SourceMetadata::new(domain),
             relevance_score: 0.0,
+            images: Vec::new(),
+            truncated: false,
+            content_ref: None,
+            snippet: None,
         }
     }
 
@@ -78,9 +157,14 @@ impl Source {
         self.relevance_score = score.clamp(0.0, 1.0);
         self
     }
+
+    pub fn with_images(mut self, images: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.images = images.into_iter().map(Into::into).collect();
+        self
+    }
 }
 
-fn extract_domain(url: &str) -> Option<String> {
+pub(crate) fn extract_domain(url: &str) -> Option<String> {
     let without_scheme = url
         .strip_prefix("https://")
         .or_else(|| url.strip_prefix("http://"))
@@ -92,19 +176,59 @@ fn extract_domain(url: &str) -> Option<String> {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SearchMetadata {
     pub queries_executed: Vec<String>,
+    /// Queries that failed and were left out of the results.
+    #[serde(default)]
+    pub queries_skipped: Vec<String>,
     pub providers_used: Vec<ProviderId>,
     pub total_results: usize,
     #[serde(with = "duration_millis")]
     pub fetch_duration: Duration,
+    /// The period search was narrowed to, as read from the question.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_constraint: Option<TimeConstraint>,
+    /// How well each provider kept to the domain and date filters it was
+    /// sent, for providers that were sent any.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filter_compliance: Vec<FilterCompliance>,
 }
 
 impl SearchMetadata {
     pub fn new() -> Self {
         Self {
             queries_executed: Vec::new(),
+            queries_skipped: Vec::new(),
             providers_used: Vec::new(),
             total_results: 0,
             fetch_duration: Duration::ZERO,
+            time_constraint: None,
+            filter_compliance: Vec::new(),
+        }
+    }
+}
+
+/// Results a provider returned for filtered queries, and how many of them
+/// broke the filters and were dropped.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilterCompliance {
+    pub provider: ProviderId,
+    pub results: usize,
+    pub violations: usize,
+}
+
+impl FilterCompliance {
+    pub fn new(provider: ProviderId) -> Self {
+        Self {
+            provider,
+            results: 0,
+            violations: 0,
+        }
+    }
+
+    /// Share of results that kept to the filters; `1.0` without results.
+    pub fn rate(&self) -> f32 {
+        match self.results {
+            0 => 1.0,
+            results => 1.0 - self.violations as f32 / results as f32,
         }
     }
 }
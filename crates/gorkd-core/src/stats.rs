@@ -0,0 +1,221 @@
+//! Aggregate usage of the service over a window of days.
+//!
+//! [`UsageStats`] is summed from [`JobSummary`] rows and carries no query,
+//! answer or other per-job detail, so teams can report on research usage
+//! without exporting jobs.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::job::JobStatus;
+use crate::projection::JobSummary;
+
+/// Domains and models listed at most, most used first.
+pub const STATS_TOP_ENTRIES: usize = 10;
+
+/// Jobs created over the window and what became of them.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageStats {
+    pub jobs: usize,
+    pub completed: usize,
+    pub failed: usize,
+    /// Days with at least one job, oldest first.
+    pub days: Vec<DailyUsage>,
+    /// Median time jobs spent in each status they left, in pipeline order.
+    pub stage_latency: Vec<StageLatency>,
+    /// Domains cited by the most answers, each counted once per answer.
+    pub top_domains: Vec<DomainCitations>,
+    /// Models that wrote the most answers.
+    pub models: Vec<ModelUsage>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyUsage {
+    pub date: NaiveDate,
+    pub jobs: usize,
+    pub completed: usize,
+    pub failed: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageLatency {
+    pub stage: JobStatus,
+    pub median_ms: u64,
+    /// Jobs that left the status.
+    pub samples: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DomainCitations {
+    pub domain: String,
+    pub answers: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelUsage {
+    pub model: String,
+    pub answers: usize,
+}
+
+impl UsageStats {
+    /// Sums the `summaries` of jobs created at or after `since`, for stores
+    /// that aggregate in memory.
+    pub fn from_summaries<'a>(
+        summaries: impl IntoIterator<Item = &'a JobSummary>,
+        since: DateTime<Utc>,
+    ) -> Self {
+        let mut stats = Self::default();
+        let mut stages: Vec<(JobStatus, Vec<u64>)> = Vec::new();
+        let mut domains: Vec<DomainCitations> = Vec::new();
+        let mut models: Vec<ModelUsage> = Vec::new();
+
+        for summary in summaries.into_iter().filter(|s| s.created_at >= since) {
+            let completed = summary.status == JobStatus::Completed;
+            let failed = summary.status == JobStatus::Failed;
+            stats.jobs += 1;
+            stats.completed += usize::from(completed);
+            stats.failed += usize::from(failed);
+
+            let date = summary.created_at.date_naive();
+            let day = match stats.days.iter_mut().find(|d| d.date == date) {
+                Some(day) => day,
+                None => {
+                    stats.days.push(DailyUsage {
+                        date,
+                        jobs: 0,
+                        completed: 0,
+                        failed: 0,
+                    });
+                    stats.days.last_mut().unwrap()
+                }
+            };
+            day.jobs += 1;
+            day.completed += usize::from(completed);
+            day.failed += usize::from(failed);
+
+            for timing in &summary.stages {
+                match stages.iter_mut().find(|(stage, _)| *stage == timing.stage) {
+                    Some((_, samples)) => samples.push(timing.duration_ms),
+                    None => stages.push((timing.stage.clone(), vec![timing.duration_ms])),
+                }
+            }
+            for domain in &summary.cited_domains {
+                match domains.iter_mut().find(|d| d.domain == *domain) {
+                    Some(count) => count.answers += 1,
+                    None => domains.push(DomainCitations {
+                        domain: domain.clone(),
+                        answers: 1,
+                    }),
+                }
+            }
+            if let Some(ref model) = summary.model {
+                match models.iter_mut().find(|m| m.model == *model) {
+                    Some(count) => count.answers += 1,
+                    None => models.push(ModelUsage {
+                        model: model.clone(),
+                        answers: 1,
+                    }),
+                }
+            }
+        }
+
+        stats.days.sort_by_key(|d| d.date);
+        stats.stage_latency = stages
+            .into_iter()
+            .map(|(stage, mut samples)| {
+                samples.sort_unstable();
+                StageLatency {
+                    stage,
+                    median_ms: samples[samples.len() / 2],
+                    samples: samples.len(),
+                }
+            })
+            .collect();
+        stats.stage_latency.sort_by_key(|s| stage_order(&s.stage));
+        domains.sort_by(|a, b| b.answers.cmp(&a.answers).then(a.domain.cmp(&b.domain)));
+        domains.truncate(STATS_TOP_ENTRIES);
+        stats.top_domains = domains;
+        models.sort_by(|a, b| b.answers.cmp(&a.answers).then(a.model.cmp(&b.model)));
+        models.truncate(STATS_TOP_ENTRIES);
+        stats.models = models;
+        stats
+    }
+
+    /// Share of finished jobs that completed, or `None` before any finished.
+    pub fn success_rate(&self) -> Option<f64> {
+        let finished = self.completed + self.failed;
+        (finished > 0).then(|| self.completed as f64 / finished as f64)
+    }
+}
+
+fn stage_order(stage: &JobStatus) -> usize {
+    match stage {
+        JobStatus::Pending => 0,
+        JobStatus::Planning => 1,
+        JobStatus::Searching => 2,
+        JobStatus::Fetching => 3,
+        JobStatus::Synthesizing => 4,
+        _ => 5,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::projection::StageTiming;
+
+    fn summary(status: JobStatus, model: Option<&str>, domains: &[&str]) -> JobSummary {
+        let job = crate::job::ResearchJob::new("What is Rust?").unwrap();
+        JobSummary {
+            status,
+            model: model.map(str::to_string),
+            cited_domains: domains.iter().map(|d| d.to_string()).collect(),
+            stages: vec![StageTiming {
+                stage: JobStatus::Searching,
+                duration_ms: 100,
+            }],
+            ..JobSummary::of(&job)
+        }
+    }
+
+    #[test]
+    fn sums_jobs_without_their_content() {
+        let mut slow = summary(JobStatus::Completed, Some("gpt"), &["a.com", "b.com"]);
+        slow.stages[0].duration_ms = 900;
+        let summaries = [
+            summary(JobStatus::Completed, Some("claude"), &["a.com"]),
+            summary(JobStatus::Failed, None, &[]),
+            slow,
+            summary(JobStatus::Completed, Some("claude"), &[]),
+        ];
+
+        let stats = UsageStats::from_summaries(&summaries, Utc::now() - chrono::Duration::days(1));
+
+        assert_eq!((stats.jobs, stats.completed, stats.failed), (4, 3, 1));
+        assert_eq!(stats.success_rate(), Some(0.75));
+        assert_eq!(stats.days.len(), 1);
+        assert_eq!(stats.days[0].jobs, 4);
+        assert_eq!(
+            stats.stage_latency,
+            vec![StageLatency {
+                stage: JobStatus::Searching,
+                median_ms: 100,
+                samples: 4,
+            }]
+        );
+        assert_eq!(stats.top_domains[0].domain, "a.com");
+        assert_eq!(stats.top_domains[0].answers, 2);
+        assert_eq!(stats.models[0].model, "claude");
+        assert_eq!(stats.models[0].answers, 2);
+    }
+
+    #[test]
+    fn leaves_out_jobs_before_the_window() {
+        let summaries = [summary(JobStatus::Completed, None, &[])];
+
+        let stats = UsageStats::from_summaries(&summaries, Utc::now() + chrono::Duration::days(1));
+
+        assert_eq!(stats, UsageStats::default());
+        assert_eq!(stats.success_rate(), None);
+    }
+}
@@ -0,0 +1,81 @@
+//! The voice an answer is written in.
+//!
+//! The same research reads differently to an executive skimming a briefing,
+//! a researcher checking a literature review and a newcomer to the topic. A
+//! job's [`AnswerStyle`] adds a persona and tone to the synthesis prompt;
+//! it changes how the answer is written, not what it may claim or cite.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum AnswerStyle {
+    /// Plain, impartial prose; the prompt is left as it is.
+    #[default]
+    Neutral,
+    /// The bottom line first, then implications and decisions.
+    Executive,
+    /// Formal and precise, with hedged claims and the evidence behind them.
+    Academic,
+    /// Everyday words and analogies, for a reader new to the topic.
+    Eli5,
+}
+
+impl AnswerStyle {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Neutral => "neutral",
+            Self::Executive => "executive",
+            Self::Academic => "academic",
+            Self::Eli5 => "eli5",
+        }
+    }
+
+    /// The persona and tone added to the synthesis prompt, or `None` for
+    /// the neutral style.
+    pub fn instruction(self) -> Option<&'static str> {
+        match self {
+            Self::Neutral => None,
+            Self::Executive => Some(
+                "Write as an analyst briefing a busy executive: lead with the \
+                 bottom line, then the implications and any decision it \
+                 calls for. Be direct, avoid jargon and methodology, and \
+                 quantify wherever the sources allow.",
+            ),
+            Self::Academic => Some(
+                "Write as a researcher for an academic audience: formal and \
+                 precise, distinguishing established findings from \
+                 contested ones, qualifying claims to the strength of their \
+                 evidence and noting the methods behind them.",
+            ),
+            Self::Eli5 => Some(
+                "Write for a curious reader with no background in the \
+                 topic: short sentences, everyday words, and a familiar \
+                 analogy for each hard idea. Define any term you cannot \
+                 avoid.",
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_style_names() {
+        let style: AnswerStyle = serde_json::from_str("\"eli5\"").unwrap();
+        assert_eq!(style, AnswerStyle::Eli5);
+        assert_eq!(
+            serde_json::to_value(AnswerStyle::Executive).unwrap(),
+            AnswerStyle::Executive.as_str()
+        );
+    }
+
+    #[test]
+    fn neutral_style_leaves_prompt_alone() {
+        assert!(AnswerStyle::Neutral.instruction().is_none());
+        assert!(AnswerStyle::Academic.instruction().is_some());
+    }
+}
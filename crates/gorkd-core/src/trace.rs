@@ -0,0 +1,98 @@
+//! Per-job trace IDs for cross-system debugging.
+//!
+//! Every [`ResearchJob`](crate::ResearchJob) carries a [`TraceId`]. While the
+//! pipeline runs a job, the ID is the *current* trace ID, so HTTP clients deep
+//! inside providers can attach it to outgoing requests without it being
+//! threaded through every trait method. Quoting the ID lets provider support
+//! teams find the matching requests on their side.
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::id::TraceId;
+
+/// Header carrying the trace ID on API responses and provider requests.
+pub const TRACE_ID_HEADER: &str = "X-Gorkd-Trace-Id";
+
+thread_local! {
+    static CURRENT: RefCell<Option<TraceId>> = const { RefCell::new(None) };
+}
+
+/// The trace ID of the job being worked on by the current task, if any.
+pub fn current_trace_id() -> Option<TraceId> {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+/// Runs `future` with `trace_id` as the current trace ID.
+///
+/// The ID is set on every poll, so it follows the future across executor
+/// threads. Work spawned onto other tasks does not inherit it.
+pub fn with_trace_id<F: Future>(trace_id: TraceId, future: F) -> Traced<F> {
+    Traced {
+        trace_id,
+        inner: Box::pin(future),
+    }
+}
+
+/// Future returned by [`with_trace_id`].
+pub struct Traced<F> {
+    trace_id: TraceId,
+    inner: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for Traced<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let previous = CURRENT.with(|current| current.replace(Some(this.trace_id.clone())));
+        let _restore = Restore(previous);
+        this.inner.as_mut().poll(cx)
+    }
+}
+
+/// Puts back the outer trace ID, even if the inner future panics.
+struct Restore(Option<TraceId>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        CURRENT.with(|current| *current.borrow_mut() = self.0.take());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn sets_trace_id_while_polled() {
+        let trace_id = TraceId::new();
+        assert!(current_trace_id().is_none());
+
+        let seen = with_trace_id(trace_id.clone(), async {
+            futures_timer::Delay::new(std::time::Duration::from_millis(1)).await;
+            current_trace_id()
+        })
+        .await;
+
+        assert_eq!(seen, Some(trace_id));
+        assert!(current_trace_id().is_none());
+    }
+
+    #[tokio::test]
+    async fn nested_scopes_restore_outer_id() {
+        let outer = TraceId::new();
+        let inner = TraceId::new();
+
+        let (during, after) = with_trace_id(outer.clone(), async {
+            let during = with_trace_id(inner.clone(), async { current_trace_id() }).await;
+            (during, current_trace_id())
+        })
+        .await;
+
+        assert_eq!(during, Some(inner));
+        assert_eq!(after, Some(outer));
+    }
+}
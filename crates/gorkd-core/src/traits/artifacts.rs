@@ -0,0 +1,26 @@
+use async_trait::async_trait;
+
+use crate::artifact::{LlmArtifact, SearchArtifact};
+use crate::id::JobId;
+use crate::traits::errors::StoreError;
+
+/// Destination for captured prompts, raw model output and search provider
+/// responses.
+#[async_trait]
+pub trait ArtifactSink: Send + Sync {
+    async fn capture(&self, job_id: &JobId, artifact: LlmArtifact) -> Result<(), StoreError>;
+
+    /// Returns the job's artifacts in capture order.
+    async fn artifacts(&self, job_id: &JobId) -> Result<Vec<LlmArtifact>, StoreError>;
+
+    async fn capture_search(
+        &self,
+        job_id: &JobId,
+        artifact: SearchArtifact,
+    ) -> Result<(), StoreError>;
+
+    /// Returns the job's search responses in capture order.
+    async fn search_artifacts(&self, job_id: &JobId) -> Result<Vec<SearchArtifact>, StoreError>;
+
+    fn sink_name(&self) -> &str;
+}
@@ -0,0 +1,74 @@
+use async_trait::async_trait;
+
+use crate::source::DocumentFormat;
+use crate::traits::errors::SearchError;
+
+/// Where a crawl starts and how far it goes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CrawlRequest {
+    /// A page to start from, or a `sitemap.xml` whose pages are all
+    /// starting points.
+    pub seed: String,
+    /// Most pages returned.
+    pub max_pages: usize,
+    /// Links followed away from a starting point; zero reads only the
+    /// starting points.
+    pub max_depth: usize,
+}
+
+impl CrawlRequest {
+    pub fn new(seed: impl Into<String>, max_pages: usize, max_depth: usize) -> Self {
+        Self {
+            seed: seed.into(),
+            max_pages,
+            max_depth,
+        }
+    }
+}
+
+/// A page read by a crawl.
+#[derive(Clone, Debug)]
+pub struct CrawledPage {
+    /// The page's address after redirects.
+    pub url: String,
+    /// The page's `<title>`, or its URL when it has none.
+    pub title: String,
+    pub format: DocumentFormat,
+    pub text: String,
+    /// The server said the page has not changed since the crawler last read
+    /// it, so a document already made from it can be kept as it is.
+    pub unchanged: bool,
+}
+
+impl CrawledPage {
+    pub fn new(
+        url: impl Into<String>,
+        title: impl Into<String>,
+        format: DocumentFormat,
+        text: impl Into<String>,
+    ) -> Self {
+        Self {
+            url: url.into(),
+            title: title.into(),
+            format,
+            text: text.into(),
+            unchanged: false,
+        }
+    }
+
+    pub fn with_unchanged(mut self, unchanged: bool) -> Self {
+        self.unchanged = unchanged;
+        self
+    }
+}
+
+/// Reads the pages of one site, for adding them to the corpus.
+#[async_trait]
+pub trait SiteCrawler: Send + Sync {
+    /// Up to `request.max_pages` pages on the seed's host, in the order they
+    /// were found. Pages that fail to download or have no text are left
+    /// out; the crawl fails only when the seed itself cannot be read.
+    async fn crawl(&self, request: &CrawlRequest) -> Result<Vec<CrawledPage>, SearchError>;
+
+    fn crawler_name(&self) -> &str;
+}
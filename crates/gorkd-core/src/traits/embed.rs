@@ -0,0 +1,15 @@
+use async_trait::async_trait;
+
+use crate::traits::errors::LlmError;
+
+/// Turns text into vectors for similarity search.
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// One embedding per text, in order. Every embedding a given embedder
+    /// returns has the same dimension.
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, LlmError>;
+
+    /// Identifies the model, so vectors from different models are never
+    /// compared.
+    fn model_id(&self) -> &str;
+}
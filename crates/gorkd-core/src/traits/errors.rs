@@ -2,6 +2,8 @@ use std::fmt;
 
 use thiserror::Error;
 
+use crate::error::ErrorCode;
+
 #[derive(Debug, Clone, Error)]
 #[non_exhaustive]
 pub enum SearchError {
@@ -30,8 +32,11 @@ pub enum LlmError {
     #[error("model not available: {model}")]
     ModelUnavailable { model: String },
 
+    /// The provider refused the request for its rate limit.
+    /// `retry_after_secs` is how long it asked to be left alone, when it
+    /// said.
     #[error("rate limited by provider")]
-    RateLimited,
+    RateLimited { retry_after_secs: Option<u64> },
 
     #[error("context length exceeded: {max_tokens} tokens max, got {got_tokens}")]
     ContextLengthExceeded {
@@ -58,6 +63,9 @@ pub enum StoreError {
     #[error("job not found: {id}")]
     JobNotFound { id: String },
 
+    #[error("project not found: {id}")]
+    ProjectNotFound { id: String },
+
     #[error("connection failed: {0}")]
     Connection(String),
 
@@ -71,7 +79,53 @@ pub enum StoreError {
     Conflict(String),
 }
 
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum PublishError {
+    #[error("connection failed: {0}")]
+    Connection(String),
+
+    #[error("serialization failed: {0}")]
+    Serialization(String),
+
+    #[error("broker rejected event: {0}")]
+    Rejected(String),
+}
+
+#[derive(Debug, Clone, Error)]
+#[non_exhaustive]
+pub enum PluginError {
+    #[error("plugin failed: {0}")]
+    Failed(String),
+
+    #[error("plugin ran out of its {0} budget")]
+    Exhausted(&'static str),
+
+    #[error("plugin returned invalid output: {0}")]
+    InvalidOutput(String),
+}
+
 impl SearchError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::ProviderUnavailable { .. } | Self::Network(_) => ErrorCode::ProviderUnavailable,
+            Self::RateLimited { .. } => ErrorCode::RateLimited,
+            Self::Timeout { .. } => ErrorCode::Timeout,
+            Self::InvalidQuery { .. } => ErrorCode::ValidationError,
+            Self::Provider(_) => ErrorCode::ProviderError,
+        }
+    }
+
+    /// The provider that failed, when the error names one.
+    pub fn provider(&self) -> Option<&str> {
+        match self {
+            Self::ProviderUnavailable { provider } | Self::RateLimited { provider } => {
+                Some(provider)
+            }
+            _ => None,
+        }
+    }
+
     pub fn is_retryable(&self) -> bool {
         matches!(
             self,
@@ -84,11 +138,22 @@ impl SearchError {
 }
 
 impl LlmError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::ModelUnavailable { .. } | Self::Network(_) => ErrorCode::ProviderUnavailable,
+            Self::RateLimited { .. } => ErrorCode::RateLimited,
+            Self::ContextLengthExceeded { .. } => ErrorCode::ContextTooLong,
+            Self::ContentFiltered { .. } => ErrorCode::ContentBlocked,
+            Self::Timeout { .. } => ErrorCode::Timeout,
+            Self::Provider(_) => ErrorCode::ProviderError,
+        }
+    }
+
     pub fn is_retryable(&self) -> bool {
         matches!(
             self,
             Self::ModelUnavailable { .. }
-                | Self::RateLimited
+                | Self::RateLimited { .. }
                 | Self::Timeout { .. }
                 | Self::Network(_)
         )
@@ -96,6 +161,15 @@ impl LlmError {
 }
 
 impl StoreError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::JobNotFound { .. } | Self::ProjectNotFound { .. } => ErrorCode::NotFound,
+            Self::Connection(_) => ErrorCode::StoreUnavailable,
+            Self::Conflict(_) => ErrorCode::Conflict,
+            Self::Query(_) | Self::Serialization(_) => ErrorCode::InternalError,
+        }
+    }
+
     pub fn is_retryable(&self) -> bool {
         matches!(self, Self::Connection(_))
     }
@@ -136,7 +210,10 @@ mod tests {
 
     #[test]
     fn llm_error_is_retryable() {
-        assert!(LlmError::RateLimited.is_retryable());
+        assert!(LlmError::RateLimited {
+            retry_after_secs: None
+        }
+        .is_retryable());
         assert!(!LlmError::ContentFiltered {
             reason: "policy".into()
         }
@@ -0,0 +1,43 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::traits::errors::SearchError;
+
+/// An entry of an RSS or Atom feed.
+#[derive(Clone, Debug)]
+pub struct FeedItem {
+    /// The entry's link, which identifies it across polls.
+    pub url: String,
+    /// The entry's title, or its link when it has none.
+    pub title: String,
+    /// The entry's content or summary as plain text. Empty when the feed
+    /// carries neither.
+    pub text: String,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+impl FeedItem {
+    pub fn new(url: impl Into<String>, title: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            title: title.into(),
+            text: text.into(),
+            published_at: None,
+        }
+    }
+
+    pub fn with_published_at(mut self, published_at: DateTime<Utc>) -> Self {
+        self.published_at = Some(published_at);
+        self
+    }
+}
+
+/// Reads RSS and Atom feeds, for adding their entries to the corpus.
+#[async_trait]
+pub trait FeedReader: Send + Sync {
+    /// The entries the feed at `url` lists now, in the feed's order. Entries
+    /// without a link are left out.
+    async fn read(&self, url: &str) -> Result<Vec<FeedItem>, SearchError>;
+
+    fn reader_name(&self) -> &str;
+}
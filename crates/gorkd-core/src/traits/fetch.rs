@@ -0,0 +1,52 @@
+use async_trait::async_trait;
+
+use crate::source::{ArchivedSnapshot, DocumentFormat};
+use crate::traits::errors::SearchError;
+
+/// Text extracted from a downloaded page.
+#[derive(Clone, Debug)]
+pub struct FetchedDocument {
+    pub text: String,
+    pub format: DocumentFormat,
+    /// Key images of the page, such as its `og:image` and figures.
+    pub images: Vec<String>,
+    /// The archived copy the text came from, when the page itself could not
+    /// be read.
+    pub archived: Option<ArchivedSnapshot>,
+}
+
+impl FetchedDocument {
+    pub fn new(text: impl Into<String>, format: DocumentFormat) -> Self {
+        Self {
+            text: text.into(),
+            format,
+            images: Vec::new(),
+            archived: None,
+        }
+    }
+
+    pub fn with_images(mut self, images: Vec<String>) -> Self {
+        self.images = images;
+        self
+    }
+
+    pub fn with_archived(mut self, snapshot: ArchivedSnapshot) -> Self {
+        self.archived = Some(snapshot);
+        self
+    }
+}
+
+/// Downloads the pages of search results that came without their text.
+#[async_trait]
+pub trait ContentFetcher: Send + Sync {
+    async fn fetch(&self, url: &str) -> Result<FetchedDocument, SearchError>;
+
+    fn fetcher_name(&self) -> &str;
+
+    /// Whether `url` is worth fetching even when the search provider returned
+    /// content for it, e.g. a video whose transcript says more than the
+    /// description the provider has.
+    fn replaces_provider_content(&self, _url: &str) -> bool {
+        false
+    }
+}
@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Whether a cited page could be reached when the answer was delivered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum LinkStatus {
+    Live,
+    /// The page is gone or its host does not answer.
+    Dead,
+    /// The check was inconclusive, e.g. it timed out or the site turns away
+    /// automated requests.
+    Unknown,
+}
+
+/// Checks that the pages an answer cites are still there.
+#[async_trait]
+pub trait LinkChecker: Send + Sync {
+    async fn check(&self, url: &str) -> LinkStatus;
+
+    fn checker_name(&self) -> &str;
+}
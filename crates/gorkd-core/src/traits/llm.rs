@@ -1,7 +1,13 @@
 use async_trait::async_trait;
 
 use crate::answer::ResearchAnswer;
+use crate::answer_schema::AnswerSchema;
+use crate::artifact::LlmExchange;
+use crate::budget::ModelPricing;
+use crate::chat::{ChatRequest, ChatResponse};
+use crate::length::LengthPolicy;
 use crate::source::Source;
+use crate::style::AnswerStyle;
 use crate::traits::errors::LlmError;
 
 #[async_trait]
@@ -9,6 +15,50 @@ pub trait LlmProvider: Send + Sync {
     async fn synthesize(&self, query: &str, sources: &[Source])
         -> Result<ResearchAnswer, LlmError>;
 
+    /// Like [`synthesize`](Self::synthesize), but also returns the exact
+    /// prompt and raw model output so they can be captured for debugging.
+    /// A `length` policy, when given, caps the completion tokens and adds its
+    /// instruction to the prompt. An answer `schema` asks the model for a
+    /// structured payload of that shape, returned in
+    /// [`ResearchAnswer::structured`]. A `style` other than neutral adds its
+    /// persona and tone to the prompt. A `temperature` samples the model at
+    /// that temperature rather than its default. Providers that do not
+    /// override this ignore all four and report an empty exchange.
+    async fn synthesize_captured(
+        &self,
+        query: &str,
+        sources: &[Source],
+        _length: Option<&LengthPolicy>,
+        _schema: Option<&AnswerSchema>,
+        _style: AnswerStyle,
+        _temperature: Option<f32>,
+    ) -> (Result<ResearchAnswer, LlmError>, LlmExchange) {
+        (
+            self.synthesize(query, sources).await,
+            LlmExchange::default(),
+        )
+    }
+
+    /// Runs a plain chat completion against the provider's model, for uses
+    /// other than answer synthesis such as planning or query rewriting.
+    async fn chat(&self, _request: ChatRequest) -> Result<ChatResponse, LlmError> {
+        Err(LlmError::Provider(format!(
+            "{} does not support chat",
+            self.provider_name()
+        )))
+    }
+
+    /// Parses raw model output captured by an earlier
+    /// [`synthesize_captured`](Self::synthesize_captured) into an answer over
+    /// `sources`, without calling the model. Token usage and timings are not
+    /// part of the output and come back empty.
+    fn parse_synthesis(&self, _raw: &str, _sources: &[Source]) -> Result<ResearchAnswer, LlmError> {
+        Err(LlmError::Provider(format!(
+            "{} cannot parse captured output",
+            self.provider_name()
+        )))
+    }
+
     fn model_id(&self) -> &str;
 
     fn provider_name(&self) -> &str;
@@ -17,7 +67,26 @@ pub trait LlmProvider: Send + Sync {
         128_000
     }
 
+    /// Tokens `text` takes in the model's prompt, as its tokenizer counts
+    /// them. Budgets estimate synthesis cost with it, and answers report how
+    /// full the context window was with it when the provider does not say.
+    /// Defaults to about four bytes per token.
+    fn count_tokens(&self, text: &str) -> usize {
+        text.len().div_ceil(4)
+    }
+
     fn supports_streaming(&self) -> bool {
         false
     }
+
+    /// Whether the model accepts images alongside the prompt text.
+    fn supports_vision(&self) -> bool {
+        false
+    }
+
+    /// What the model charges, when known. Jobs with a dollar budget cannot
+    /// use models without a price.
+    fn pricing(&self) -> Option<ModelPricing> {
+        None
+    }
 }
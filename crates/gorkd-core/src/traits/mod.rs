@@ -1,9 +1,27 @@
+mod artifacts;
+mod crawl;
+mod embed;
 mod errors;
+mod feed;
+mod fetch;
+mod links;
 mod llm;
+mod moderation;
+mod plugin;
+mod publisher;
 mod search;
 mod store;
 
-pub use errors::{ErrorContext, LlmError, SearchError, StoreError};
+pub use artifacts::ArtifactSink;
+pub use crawl::{CrawlRequest, CrawledPage, SiteCrawler};
+pub use embed::Embedder;
+pub use errors::{ErrorContext, LlmError, PluginError, PublishError, SearchError, StoreError};
+pub use feed::{FeedItem, FeedReader};
+pub use fetch::{ContentFetcher, FetchedDocument};
+pub use links::{LinkChecker, LinkStatus};
 pub use llm::LlmProvider;
-pub use search::{SearchProvider, SearchResult};
-pub use store::Store;
+pub use moderation::Moderator;
+pub use plugin::{PipelinePlugin, PluginHook};
+pub use publisher::EventPublisher;
+pub use search::{ProviderAttempt, SearchProvider, SearchReport, SearchResult};
+pub use store::{JobCursor, Store, StoreHealth};
@@ -0,0 +1,11 @@
+use async_trait::async_trait;
+
+use crate::moderation::ModerationVerdict;
+use crate::traits::errors::LlmError;
+
+#[async_trait]
+pub trait Moderator: Send + Sync {
+    async fn moderate(&self, text: &str) -> Result<ModerationVerdict, LlmError>;
+
+    fn moderator_name(&self) -> &str;
+}
@@ -0,0 +1,60 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::answer::ResearchAnswer;
+use crate::source::Source;
+use crate::traits::errors::PluginError;
+
+/// The points of a job where plugins run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginHook {
+    /// Before the search is planned, on the query it is planned from.
+    RewriteQuery,
+    /// After search, on the sources collected.
+    FilterSources,
+    /// After synthesis, on the answer before it is moderated.
+    PostProcess,
+}
+
+impl PluginHook {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::RewriteQuery => "rewrite_query",
+            Self::FilterSources => "filter_sources",
+            Self::PostProcess => "post_process",
+        }
+    }
+}
+
+/// Custom business logic run at fixed points of every job. Each hook leaves
+/// its input alone unless the plugin implements it; a hook that fails is
+/// recorded and leaves its input as it was.
+#[async_trait]
+pub trait PipelinePlugin: Send + Sync {
+    /// Changes the query the job's search is planned from. The job keeps
+    /// its own query.
+    async fn rewrite_query(&self, _query: &mut String) -> Result<(), PluginError> {
+        Ok(())
+    }
+
+    /// Drops sources the job must not use.
+    async fn filter_sources(
+        &self,
+        _query: &str,
+        _sources: &mut Vec<Source>,
+    ) -> Result<(), PluginError> {
+        Ok(())
+    }
+
+    /// Edits the synthesized answer.
+    async fn post_process(
+        &self,
+        _query: &str,
+        _answer: &mut ResearchAnswer,
+    ) -> Result<(), PluginError> {
+        Ok(())
+    }
+
+    fn plugin_name(&self) -> &str;
+}
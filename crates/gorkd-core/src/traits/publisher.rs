@@ -0,0 +1,12 @@
+use async_trait::async_trait;
+
+use crate::lifecycle::LifecycleEvent;
+use crate::traits::errors::PublishError;
+
+/// Sends job lifecycle events to a message broker.
+#[async_trait]
+pub trait EventPublisher: Send + Sync {
+    async fn publish(&self, event: &LifecycleEvent) -> Result<(), PublishError>;
+
+    fn publisher_name(&self) -> &str;
+}
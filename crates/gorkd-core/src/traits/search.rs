@@ -1,4 +1,7 @@
+use std::time::{Duration, Instant};
+
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
 use crate::search::SearchQuery;
 use crate::source::Source;
@@ -10,6 +13,12 @@ pub struct SearchResult {
     pub title: String,
     pub snippet: String,
     pub score: f32,
+    /// Full page text, when the provider returns it with the result.
+    pub raw_content: Option<String>,
+    /// Image URLs associated with the result.
+    pub images: Vec<String>,
+    /// When the page was published, if the provider knows.
+    pub published_at: Option<DateTime<Utc>>,
 }
 
 impl SearchResult {
@@ -23,25 +32,138 @@ impl SearchResult {
             title: title.into(),
             snippet: snippet.into(),
             score: 0.0,
+            raw_content: None,
+            images: Vec::new(),
+            published_at: None,
         }
     }
 
+    pub fn with_raw_content(mut self, content: impl Into<String>) -> Self {
+        self.raw_content = Some(content.into());
+        self
+    }
+
+    pub fn with_images(mut self, images: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.images = images.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn with_published_at(mut self, published_at: DateTime<Utc>) -> Self {
+        self.published_at = Some(published_at);
+        self
+    }
+
     pub fn with_score(mut self, score: f32) -> Self {
         self.score = score.clamp(0.0, 1.0);
         self
     }
 
     pub fn into_source(self, content: String) -> Source {
-        Source::new(self.url, self.title, content).with_relevance_score(self.score)
+        let mut source = Source::new(self.url, self.title, content)
+            .with_relevance_score(self.score)
+            .with_images(self.images);
+        source.metadata.published_at = self.published_at;
+        source
     }
 }
 
+/// A single call made to a concrete search provider.
+#[derive(Clone, Debug)]
+pub struct ProviderAttempt {
+    pub provider: String,
+    pub query: String,
+    /// Number of results on success.
+    pub outcome: Result<usize, SearchError>,
+    pub duration: Duration,
+    /// The body of the provider's response, when it captures it with
+    /// [`SearchProvider::search_captured`].
+    pub raw_response: Option<String>,
+}
+
+impl ProviderAttempt {
+    pub fn succeeded(&self) -> bool {
+        self.outcome.is_ok()
+    }
+}
+
+/// Search results together with every provider call made to produce them.
+#[derive(Debug)]
+pub struct SearchReport {
+    pub result: Result<Vec<SearchResult>, SearchError>,
+    pub attempts: Vec<ProviderAttempt>,
+}
+
 #[async_trait]
 pub trait SearchProvider: Send + Sync {
     async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>, SearchError>;
 
+    /// Like [`search`](Self::search), but also returns the body of the
+    /// provider's response, even one that failed to parse, so it can be kept
+    /// and parsed again with [`parse_raw`](Self::parse_raw). Providers that
+    /// do not override this return no body.
+    async fn search_captured(
+        &self,
+        query: &SearchQuery,
+    ) -> (Result<Vec<SearchResult>, SearchError>, Option<String>) {
+        (self.search(query).await, None)
+    }
+
+    /// Parses a response body that provider `provider` returned for `query`,
+    /// as captured by [`search_captured`](Self::search_captured), into the
+    /// results it stands for. Composite providers pass it on to the inner
+    /// provider with that ID.
+    fn parse_raw(
+        &self,
+        provider: &str,
+        _query: &SearchQuery,
+        _raw: &str,
+    ) -> Result<Vec<SearchResult>, SearchError> {
+        Err(SearchError::Provider(format!(
+            "{} cannot parse responses of {}",
+            self.provider_id(),
+            provider
+        )))
+    }
+
+    /// Like [`search`](Self::search), but also reports each underlying
+    /// provider call. Composite providers (e.g. fallbacks) override this to
+    /// expose the attempts of their inner providers.
+    async fn search_reported(&self, query: &SearchQuery) -> SearchReport {
+        let start = Instant::now();
+        let (result, raw_response) = self.search_captured(query).await;
+        let attempt = ProviderAttempt {
+            provider: self.provider_id().to_string(),
+            query: query.text.clone(),
+            outcome: result.as_ref().map(Vec::len).map_err(Clone::clone),
+            duration: start.elapsed(),
+            raw_response,
+        };
+
+        SearchReport {
+            result,
+            attempts: vec![attempt],
+        }
+    }
+
+    /// Finds up to `max_results` pages similar to `url`, used to expand a
+    /// source set beyond what keyword queries returned.
+    async fn find_similar(
+        &self,
+        _url: &str,
+        _max_results: usize,
+    ) -> Result<Vec<SearchResult>, SearchError> {
+        Err(SearchError::Provider(format!(
+            "{} does not support finding similar pages",
+            self.provider_id()
+        )))
+    }
+
     fn provider_id(&self) -> &str;
 
+    fn supports_find_similar(&self) -> bool {
+        false
+    }
+
     fn supports_recency_filter(&self) -> bool {
         false
     }
@@ -73,5 +195,15 @@ mod tests {
         assert_eq!(source.title, "Title");
         assert_eq!(source.content, "Full content here");
         assert_eq!(source.relevance_score, 0.8);
+        assert!(source.images.is_empty());
+    }
+
+    #[test]
+    fn search_result_carries_images_to_source() {
+        let source = SearchResult::new("https://example.com", "Title", "Snippet")
+            .with_images(["https://example.com/a.png"])
+            .into_source("Content".to_string());
+
+        assert_eq!(source.images, vec!["https://example.com/a.png"]);
     }
 }
@@ -1,24 +1,301 @@
+use std::time::{Duration, Instant};
+
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
-use crate::id::JobId;
+use crate::answer::ResearchAnswer;
+use crate::artifact::{LlmArtifact, SearchArtifact};
+use crate::comparison::ModelComparison;
+use crate::corpus::{CorpusDocument, CorpusMatch};
+use crate::event::JobEvent;
+use crate::feedback::{DomainTrust, Feedback};
+use crate::id::{DocumentId, JobId, ProjectId, WorkerId};
 use crate::job::ResearchJob;
-use crate::source::Source;
+use crate::knowledge::{Fact, FactQuery};
+use crate::project::Project;
+use crate::projection::{JobSummary, JobSummaryFilter, StatusCount};
+use crate::search::SearchFilters;
+use crate::source::{SearchMetadata, Source};
+use crate::stats::UsageStats;
 use crate::traits::errors::StoreError;
 
+/// Outcome of a [`Store::health`] probe.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StoreHealth {
+    pub reachable: bool,
+    /// How long the probe took, including a failed one.
+    pub latency: Duration,
+    /// Why the store could not be reached.
+    pub error: Option<String>,
+}
+
+impl StoreHealth {
+    pub fn reachable(latency: Duration) -> Self {
+        Self {
+            reachable: true,
+            latency,
+            error: None,
+        }
+    }
+
+    pub fn unreachable(latency: Duration, error: impl Into<String>) -> Self {
+        Self {
+            reachable: false,
+            latency,
+            error: Some(error.into()),
+        }
+    }
+}
+
+/// Where a page of [`Store::list_jobs_before`] ended: the last job's
+/// creation time, and its ID to order jobs created at the same time.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JobCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: JobId,
+}
+
+impl JobCursor {
+    pub fn of(job: &ResearchJob) -> Self {
+        Self {
+            created_at: job.created_at,
+            id: job.id.clone(),
+        }
+    }
+
+    /// Whether `job` comes after the cursor, newest first.
+    pub fn precedes(&self, job: &ResearchJob) -> bool {
+        (job.created_at, job.id.as_str()) < (self.created_at, self.id.as_str())
+    }
+}
+
 #[async_trait]
 pub trait Store: Send + Sync {
     async fn create_job(&self, job: &ResearchJob) -> Result<(), StoreError>;
 
     async fn get_job(&self, id: &JobId) -> Result<Option<ResearchJob>, StoreError>;
 
+    /// Saves `job` if the stored job is still at `job.version`, and bumps the
+    /// stored version. Fails with [`StoreError::Conflict`] when someone else
+    /// updated the job since `job` was read; re-read it and retry.
     async fn update_job(&self, job: &ResearchJob) -> Result<(), StoreError>;
 
     async fn list_jobs(&self, limit: usize, offset: usize) -> Result<Vec<ResearchJob>, StoreError>;
 
+    /// Up to `limit` jobs after `cursor`, or from the newest without one,
+    /// newest first. Unlike an offset, the cursor does not shift when jobs
+    /// are created between pages; those are left out.
+    async fn list_jobs_before(
+        &self,
+        cursor: Option<&JobCursor>,
+        limit: usize,
+    ) -> Result<Vec<ResearchJob>, StoreError>;
+
+    /// Like [`Store::list_jobs`], counting only jobs tagged `tag`.
+    async fn list_jobs_by_tag(
+        &self,
+        tag: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<ResearchJob>, StoreError>;
+
+    /// Summaries of the jobs `filter` admits, newest first. Read from the
+    /// [`JobSummary`] the store keeps up to date as each job and its answer
+    /// are written, without loading answers or sources.
+    async fn list_job_summaries(
+        &self,
+        filter: &JobSummaryFilter,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<JobSummary>, StoreError>;
+
+    /// How many of the jobs `filter` admits are in each status, leaving out
+    /// statuses no job is in.
+    async fn count_job_summaries(
+        &self,
+        filter: &JobSummaryFilter,
+    ) -> Result<Vec<StatusCount>, StoreError>;
+
+    /// Usage summed over the jobs created at or after `since`, read from
+    /// their [`JobSummary`] rows.
+    async fn usage_stats(&self, since: DateTime<Utc>) -> Result<UsageStats, StoreError>;
+
+    async fn create_project(&self, project: &Project) -> Result<(), StoreError>;
+
+    async fn get_project(&self, id: &ProjectId) -> Result<Option<Project>, StoreError>;
+
+    /// Adds a job to the project, unless it is already a member, and returns
+    /// the updated project. Fails with [`StoreError::ProjectNotFound`] for an
+    /// unknown project and [`StoreError::Conflict`] when the project already
+    /// holds `max_jobs` jobs.
+    async fn attach_job(
+        &self,
+        project_id: &ProjectId,
+        job_id: &JobId,
+        max_jobs: usize,
+    ) -> Result<Project, StoreError>;
+
+    /// Adds facts to the knowledge base. A fact already known by its
+    /// [`key`](Fact::key) gains the new fact's sources instead of being added
+    /// again.
+    async fn record_facts(&self, facts: &[Fact]) -> Result<(), StoreError>;
+
+    /// Up to `query.limit` known facts matching `query`, those stated by the
+    /// most sources first, then the most recently updated.
+    async fn find_facts(&self, query: &FactQuery) -> Result<Vec<Fact>, StoreError>;
+
+    /// Adds an indexed document to the corpus of its workspace.
+    async fn store_document(&self, document: &CorpusDocument) -> Result<(), StoreError>;
+
+    /// The workspace's corpus documents, the most recently added first.
+    async fn list_documents(
+        &self,
+        workspace: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<CorpusDocument>, StoreError>;
+
+    /// A document of the workspace's corpus; `None` for documents of other
+    /// workspaces.
+    async fn get_document(
+        &self,
+        workspace: &str,
+        id: &DocumentId,
+    ) -> Result<Option<CorpusDocument>, StoreError>;
+
+    /// The document of the workspace's corpus with this `url`, if any.
+    async fn find_document_by_url(
+        &self,
+        workspace: &str,
+        url: &str,
+    ) -> Result<Option<CorpusDocument>, StoreError>;
+
+    /// Removes a document from the workspace's corpus, returning whether it
+    /// was there.
+    async fn delete_document(&self, workspace: &str, id: &DocumentId) -> Result<bool, StoreError>;
+
+    /// The best matching chunk of each of up to `limit` documents of the
+    /// workspace's corpus embedded with `model`, most similar to `embedding`
+    /// first. Documents published outside the recency and date filters of
+    /// `filters` are left out; undated ones are kept.
+    async fn search_corpus(
+        &self,
+        workspace: &str,
+        model: &str,
+        embedding: &[f32],
+        filters: &SearchFilters,
+        limit: usize,
+    ) -> Result<Vec<CorpusMatch>, StoreError>;
+
+    /// Stores feedback on a job and adds its
+    /// [`domain_trust`](Feedback::domain_trust) to the trust of each domain.
+    async fn store_feedback(&self, feedback: &Feedback) -> Result<(), StoreError>;
+
+    /// Returns the job's feedback in the order it was given.
+    async fn get_feedback(&self, job_id: &JobId) -> Result<Vec<Feedback>, StoreError>;
+
+    /// The trust of every domain feedback has involved.
+    async fn domain_trust(&self) -> Result<Vec<DomainTrust>, StoreError>;
+
+    /// Leases the oldest claimable job to `worker` for `lease`. A job is
+    /// claimable while pending and unleased, or when a worker's lease on an
+    /// unfinished job has expired, so jobs of crashed workers are picked up
+    /// again. A job is leased to at most one worker at a time.
+    async fn claim_next_job(
+        &self,
+        worker: &WorkerId,
+        lease: Duration,
+    ) -> Result<Option<ResearchJob>, StoreError>;
+
+    /// Extends `worker`'s lease on the job to `lease` from now. Fails with
+    /// [`StoreError::Conflict`] once the lease has been lost.
+    async fn heartbeat(
+        &self,
+        job_id: &JobId,
+        worker: &WorkerId,
+        lease: Duration,
+    ) -> Result<(), StoreError>;
+
+    /// Gives up `worker`'s lease on the job. Releasing a lease that is no
+    /// longer held is not an error.
+    async fn release(&self, job_id: &JobId, worker: &WorkerId) -> Result<(), StoreError>;
+
     async fn store_sources(&self, job_id: &JobId, sources: &[Source]) -> Result<(), StoreError>;
 
     async fn get_sources(&self, job_id: &JobId) -> Result<Vec<Source>, StoreError>;
 
+    /// Records which queries ran and which providers answered them.
+    async fn store_search_metadata(
+        &self,
+        job_id: &JobId,
+        metadata: &SearchMetadata,
+    ) -> Result<(), StoreError>;
+
+    async fn get_search_metadata(
+        &self,
+        job_id: &JobId,
+    ) -> Result<Option<SearchMetadata>, StoreError>;
+
+    async fn store_answer(&self, job_id: &JobId, answer: &ResearchAnswer)
+        -> Result<(), StoreError>;
+
+    async fn get_answer(&self, job_id: &JobId) -> Result<Option<ResearchAnswer>, StoreError>;
+
+    /// Stores every model's answer of a job compared across models.
+    async fn store_comparison(
+        &self,
+        job_id: &JobId,
+        comparison: &ModelComparison,
+    ) -> Result<(), StoreError>;
+
+    async fn get_comparison(&self, job_id: &JobId) -> Result<Option<ModelComparison>, StoreError>;
+
+    /// Appends an event to the job's log, returning it with its assigned
+    /// sequence number.
+    async fn append_event(&self, event: JobEvent) -> Result<JobEvent, StoreError>;
+
+    /// Returns the job's events in the order they were appended.
+    async fn get_events(&self, job_id: &JobId) -> Result<Vec<JobEvent>, StoreError>;
+
+    /// Appends a captured LLM exchange to the job's artifacts.
+    async fn store_artifact(
+        &self,
+        job_id: &JobId,
+        artifact: &LlmArtifact,
+    ) -> Result<(), StoreError>;
+
+    async fn get_artifacts(&self, job_id: &JobId) -> Result<Vec<LlmArtifact>, StoreError>;
+
+    /// Appends a captured search provider response to the job's artifacts.
+    async fn store_search_artifact(
+        &self,
+        job_id: &JobId,
+        artifact: &SearchArtifact,
+    ) -> Result<(), StoreError>;
+
+    async fn get_search_artifacts(&self, job_id: &JobId)
+        -> Result<Vec<SearchArtifact>, StoreError>;
+
+    /// Counts one more reference to the content stored under `hash`, such as
+    /// a source body kept in object storage, and returns how many references
+    /// it now has. A count of 1 means nothing referred to it before.
+    async fn acquire_content(&self, hash: &str) -> Result<u64, StoreError>;
+
+    /// Drops one reference to the content stored under `hash` and returns
+    /// how many remain; at 0 the content may be deleted. Releasing content
+    /// nothing refers to returns 0.
+    async fn release_content(&self, hash: &str) -> Result<u64, StoreError>;
+
+    /// Checks that the store answers. The default times a one-job listing;
+    /// database-backed stores may override it with a cheaper ping.
+    async fn health(&self) -> StoreHealth {
+        let started = Instant::now();
+        match self.list_jobs(1, 0).await {
+            Ok(_) => StoreHealth::reachable(started.elapsed()),
+            Err(e) => StoreHealth::unreachable(started.elapsed(), e.to_string()),
+        }
+    }
+
     async fn find_similar(
         &self,
         embedding: &[f32],
@@ -0,0 +1,265 @@
+//! Runs queued research jobs outside the process that accepted them.
+//!
+//! A [`Worker`] claims pending jobs from the [`Store`] and runs each through a
+//! [`Pipeline`], so pipeline throughput can be scaled by adding workers
+//! instead of API servers.
+//!
+//! Claims are leases. While a job runs the worker renews its lease; if the
+//! worker dies, the lease expires and another worker picks the job up again.
+//! A worker that finds its lease lost stops the job, since someone else now
+//! owns it.
+//!
+//! Jobs that fail are retried according to the worker's [`RetryPolicy`]:
+//! the retry is queued as a new pending job, for any worker to claim.
+
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::future::{self, FutureExt};
+use futures::stream::{FuturesUnordered, StreamExt};
+use futures_timer::Delay;
+
+use crate::id::{JobId, WorkerId};
+use crate::job::ResearchJob;
+use crate::pipeline::{Pipeline, PipelineError};
+use crate::retry::RetryPolicy;
+use crate::traits::{Store, StoreError};
+
+/// How long an idle worker waits before checking the store again.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long a claim lasts without a heartbeat.
+pub const DEFAULT_LEASE_DURATION: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Debug)]
+pub struct WorkerConfig {
+    /// Identifies this worker's leases in the store.
+    pub worker_id: WorkerId,
+    /// Jobs run at once by this worker.
+    pub concurrency: usize,
+    pub poll_interval: Duration,
+    /// Leases are renewed every third of this, so a job survives two missed
+    /// heartbeats before another worker may take it over.
+    pub lease_duration: Duration,
+    /// Failed jobs to queue again. The backoff is waited out in the job's
+    /// slot, so a worker retrying jobs runs fewer at once meanwhile.
+    pub retry: RetryPolicy,
+}
+
+impl Default for WorkerConfig {
+    fn default() -> Self {
+        Self {
+            worker_id: WorkerId::new(),
+            concurrency: 4,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            lease_duration: DEFAULT_LEASE_DURATION,
+            retry: RetryPolicy::default(),
+        }
+    }
+}
+
+pub struct Worker {
+    store: Arc<dyn Store>,
+    pipeline: Arc<Pipeline>,
+    config: WorkerConfig,
+}
+
+impl Worker {
+    pub fn new(store: Arc<dyn Store>, pipeline: Arc<Pipeline>, config: WorkerConfig) -> Self {
+        Self {
+            store,
+            pipeline,
+            config,
+        }
+    }
+
+    /// Claims and runs a single job, returning whether there was one. The
+    /// job's outcome is recorded in the store by the pipeline.
+    pub async fn run_once(&self) -> Result<bool, StoreError> {
+        let Some(job) = self.claim().await? else {
+            return Ok(false);
+        };
+
+        self.execute(job).await;
+        Ok(true)
+    }
+
+    async fn claim(&self) -> Result<Option<ResearchJob>, StoreError> {
+        self.store
+            .claim_next_job(&self.config.worker_id, self.config.lease_duration)
+            .await
+    }
+
+    /// Runs the job while keeping its lease alive, then releases the lease.
+    async fn execute(&self, job: ResearchJob) {
+        let job_id = job.id.clone();
+        let mut run = pin!(self.pipeline.run(job).fuse());
+        let mut lease = pin!(self.keep_lease(&job_id).fuse());
+
+        let outcome = futures::select! {
+            outcome = run => outcome,
+            // Another worker owns the job now; let it finish the run.
+            _ = lease => return,
+        };
+
+        // If the job could not be saved it is left unfinished; keeping the
+        // lease until it expires lets another worker retry it.
+        if matches!(outcome, Err(PipelineError::Store(_))) {
+            return;
+        }
+        let _ = self.store.release(&job_id, &self.config.worker_id).await;
+
+        if outcome.is_err() && self.config.retry.is_enabled() {
+            let _ = self
+                .config
+                .retry
+                .retry_failed(self.store.as_ref(), &job_id)
+                .await;
+        }
+    }
+
+    /// Renews the lease on `job_id` until it is lost, then returns.
+    /// Connection errors are retried until the lease would have expired.
+    async fn keep_lease(&self, job_id: &JobId) {
+        let lease = self.config.lease_duration;
+        let interval = lease / 3;
+        let mut renewed = Instant::now();
+
+        loop {
+            Delay::new(interval).await;
+            match self
+                .store
+                .heartbeat(job_id, &self.config.worker_id, lease)
+                .await
+            {
+                Ok(()) => renewed = Instant::now(),
+                Err(e) if e.is_retryable() && renewed.elapsed() < lease => {}
+                Err(_) => return,
+            }
+        }
+    }
+
+    /// Runs jobs until `shutdown` resolves, then waits for the jobs already
+    /// started. A store error while claiming is treated like an empty queue
+    /// and retried after the poll interval.
+    pub async fn run(&self, shutdown: impl Future<Output = ()>) {
+        let mut shutdown = pin!(shutdown.fuse());
+        let mut running = FuturesUnordered::new();
+        let concurrency = self.config.concurrency.max(1);
+
+        loop {
+            while running.len() < concurrency {
+                match self.claim().await {
+                    Ok(Some(job)) => running.push(self.execute(job)),
+                    Ok(None) | Err(_) => break,
+                }
+            }
+
+            let finished = async {
+                match running.next().await {
+                    Some(_) => {}
+                    None => future::pending().await,
+                }
+            };
+            let mut finished = pin!(finished.fuse());
+            let mut tick = Delay::new(self.config.poll_interval).fuse();
+
+            futures::select! {
+                _ = finished => {}
+                _ = tick => {}
+                _ = shutdown => break,
+            }
+        }
+
+        while running.next().await.is_some() {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::{JobStatus, ResearchJob};
+    use crate::mock::{MockLlmProvider, MockSearchProvider, MockStore};
+
+    fn worker(store: Arc<dyn Store>) -> Worker {
+        let pipeline = Pipeline::new(
+            Arc::clone(&store),
+            Arc::new(MockSearchProvider::new("mock")),
+            Arc::new(MockLlmProvider::new("mock-gpt-4")),
+        );
+        let config = WorkerConfig {
+            concurrency: 2,
+            poll_interval: Duration::from_millis(5),
+            ..Default::default()
+        };
+        Worker::new(store, Arc::new(pipeline), config)
+    }
+
+    #[tokio::test]
+    async fn run_once_runs_next_pending_job() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let job = ResearchJob::new("What is Rust?").unwrap();
+        store.create_job(&job).await.unwrap();
+        let worker = worker(Arc::clone(&store));
+
+        assert!(worker.run_once().await.unwrap());
+        assert!(!worker.run_once().await.unwrap());
+
+        let job = store.get_job(&job.id).await.unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Completed);
+    }
+
+    #[tokio::test]
+    async fn queues_retry_of_failed_job() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let pipeline = Pipeline::new(
+            Arc::clone(&store),
+            Arc::new(MockSearchProvider::new("mock")),
+            Arc::new(MockLlmProvider::new("mock-gpt-4").fail_after(0)),
+        );
+        let config = WorkerConfig {
+            retry: RetryPolicy {
+                max_attempts: 2,
+                backoff: Duration::from_millis(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let worker = Worker::new(Arc::clone(&store), Arc::new(pipeline), config);
+        let job = ResearchJob::new("What is Rust?").unwrap();
+        store.create_job(&job).await.unwrap();
+
+        assert!(worker.run_once().await.unwrap());
+        assert!(worker.run_once().await.unwrap());
+        assert!(!worker.run_once().await.unwrap());
+
+        let jobs = store.list_jobs(10, 0).await.unwrap();
+        assert_eq!(jobs.len(), 2);
+        let retry = jobs.iter().find(|j| j.id != job.id).unwrap();
+        assert_eq!(retry.retried_from, Some(job.id.clone()));
+        assert_eq!(retry.attempt, 2);
+        assert_eq!(retry.status, JobStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn run_drains_queue_until_shutdown() {
+        let store: Arc<dyn Store> = Arc::new(MockStore::new());
+        let mut ids = Vec::new();
+        for i in 0..5 {
+            let job = ResearchJob::new(format!("query {}", i)).unwrap();
+            store.create_job(&job).await.unwrap();
+            ids.push(job.id);
+        }
+
+        worker(Arc::clone(&store))
+            .run(Delay::new(Duration::from_millis(50)))
+            .await;
+
+        for id in ids {
+            let job = store.get_job(&id).await.unwrap().unwrap();
+            assert_eq!(job.status, JobStatus::Completed);
+        }
+    }
+}
@@ -0,0 +1,20 @@
+//! Fuzzes the synthesis response parser with arbitrary model output.
+//!
+//! Run with `cargo +nightly fuzz run parse_synthesis` from `crates/gorkd-llm`.
+
+#![no_main]
+
+use gorkd_core::Source;
+use gorkd_llm::parser::{extract_json, parse_synthesis_response};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    let sources = [Source::new("https://example.com", "Example", "Content")];
+
+    let _ = extract_json(text);
+    let _ = parse_synthesis_response(text, &sources, "fuzz-model", 0);
+});
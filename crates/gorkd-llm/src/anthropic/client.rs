@@ -1,8 +1,11 @@
-use gorkd_core::LlmError;
+use std::sync::Arc;
+
+use gorkd_core::{KeyPool, LlmError};
 use reqwest::Client;
 use secrecy::{ExposeSecret, SecretString};
 use tracing::instrument;
 
+use crate::client::{send_keyed, with_trace_header};
 use crate::config::AnthropicConfig;
 use crate::error::map_anthropic_error;
 
@@ -10,7 +13,7 @@ use super::types::{AnthropicMessage, MessagesRequest, MessagesResponse, ANTHROPI
 
 pub struct AnthropicClient {
     http: Client,
-    api_key: SecretString,
+    api_keys: Arc<KeyPool<SecretString>>,
     base_url: String,
 }
 
@@ -18,7 +21,7 @@ impl AnthropicClient {
     pub fn new(http: Client, config: &AnthropicConfig) -> Self {
         Self {
             http,
-            api_key: config.api_key.clone(),
+            api_keys: Arc::clone(&config.api_keys),
             base_url: config.base_url.clone(),
         }
     }
@@ -30,33 +33,38 @@ impl AnthropicClient {
         system: &str,
         messages: Vec<AnthropicMessage>,
         max_tokens: usize,
+        temperature: Option<f32>,
     ) -> Result<MessagesResponse, LlmError> {
-        let request = MessagesRequest::new(model, messages)
+        let mut request = MessagesRequest::new(model, messages)
             .with_system(system)
             .with_max_tokens(max_tokens);
+        if let Some(temperature) = temperature {
+            request = request.with_temperature(temperature);
+        }
 
-        let url = format!("{}/v1/messages", self.base_url);
-
-        let response = self
-            .http
-            .post(&url)
-            .header("x-api-key", self.api_key.expose_secret())
-            .header("anthropic-version", ANTHROPIC_VERSION)
-            .header("content-type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(crate::error::map_reqwest_error)?;
+        self.send_request(&request).await
+    }
 
-        let status = response.status();
-        let body = response
-            .text()
-            .await
-            .map_err(crate::error::map_reqwest_error)?;
+    /// Sends `request` with the key whose turn it is, moving on to the next
+    /// key when one is rate limited or rejected.
+    #[instrument(skip(self, request), fields(model = %request.model))]
+    pub async fn send_request(
+        &self,
+        request: &MessagesRequest,
+    ) -> Result<MessagesResponse, LlmError> {
+        let url = format!("{}/v1/messages", self.base_url);
 
-        if !status.is_success() {
-            return Err(map_anthropic_error(status, &body));
-        }
+        let body = self
+            .api_keys
+            .run(|key| {
+                let request = with_trace_header(self.http.post(&url))
+                    .header("x-api-key", key.expose_secret())
+                    .header("anthropic-version", ANTHROPIC_VERSION)
+                    .header("content-type", "application/json")
+                    .json(request);
+                send_keyed(request, map_anthropic_error)
+            })
+            .await?;
 
         serde_json::from_str(&body).map_err(|e| LlmError::Provider(format!("parse error: {}", e)))
     }
@@ -66,7 +74,7 @@ impl std::fmt::Debug for AnthropicClient {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("AnthropicClient")
             .field("base_url", &self.base_url)
-            .field("api_key", &"[REDACTED]")
+            .field("api_keys", &"[REDACTED]")
             .finish()
     }
 }
@@ -78,7 +86,7 @@ mod tests {
     #[test]
     fn debug_redacts_api_key() {
         let config = AnthropicConfig {
-            api_key: SecretString::from("sk-secret-key"),
+            api_keys: Arc::new(KeyPool::single(SecretString::from("sk-secret-key"))),
             base_url: "https://api.anthropic.com".to_string(),
         };
         let client = AnthropicClient::new(Client::new(), &config);
@@ -1,33 +1,51 @@
 mod client;
-mod parser;
 pub mod types;
 
+use std::sync::Arc;
 use std::time::Instant;
 
 use async_trait::async_trait;
-use gorkd_core::{LlmError, LlmProvider, ResearchAnswer, Source};
+use gorkd_core::{
+    AnswerSchema, AnswerStyle, LengthPolicy, LlmError, LlmExchange, LlmProvider, LlmStage,
+    ModelPricing, ResearchAnswer, Source, StageTokenUsage,
+};
 use reqwest::Client;
 use tracing::instrument;
 
 use crate::config::AnthropicConfig;
-use crate::prompt::{build_synthesis_messages, SYNTHESIS_SYSTEM_PROMPT};
+use crate::parser::parse_synthesis_response;
+use crate::prompt::{
+    append_answer_schema, append_answer_style, attach_source_images, build_synthesis_messages_for,
+    PromptHardening,
+};
+use crate::tokenizer::{tokenizer_for, Tokenizer};
+use crate::types::{split_system, ChatRequest, ChatResponse, FinishReason, Role, TokenUsage};
 
+pub use crate::parser::ParseError;
 use client::AnthropicClient;
-pub use parser::ParseError;
-use types::{AnthropicMessage, StopReason, CONTEXT_WINDOW_TOKENS, DEFAULT_MAX_TOKENS};
+use types::{
+    AnthropicMessage, MessagesRequest, StopReason, CONTEXT_WINDOW_TOKENS, DEFAULT_MAX_TOKENS,
+};
 
 pub struct AnthropicProvider {
     client: AnthropicClient,
     model: String,
+    tokenizer: Arc<dyn Tokenizer>,
     max_tokens: usize,
+    prompt_hardening: PromptHardening,
+    max_images: usize,
 }
 
 impl AnthropicProvider {
     pub fn new(http: Client, config: &AnthropicConfig, model: impl Into<String>) -> Self {
+        let model = model.into();
         Self {
             client: AnthropicClient::new(http, config),
-            model: model.into(),
+            tokenizer: tokenizer_for("anthropic", &model),
+            model,
             max_tokens: DEFAULT_MAX_TOKENS,
+            prompt_hardening: PromptHardening::default(),
+            max_images: 0,
         }
     }
 
@@ -35,54 +53,150 @@ impl AnthropicProvider {
         self.max_tokens = max_tokens;
         self
     }
+
+    pub fn with_prompt_hardening(mut self, hardening: PromptHardening) -> Self {
+        self.prompt_hardening = hardening;
+        self
+    }
+
+    /// Shows up to `max_images` source images to the model during synthesis,
+    /// if it supports vision. Zero sends text only.
+    pub fn with_max_images(mut self, max_images: usize) -> Self {
+        self.max_images = max_images;
+        self
+    }
 }
 
 #[async_trait]
 impl LlmProvider for AnthropicProvider {
-    #[instrument(skip(self, sources), fields(model = %self.model, source_count = sources.len()))]
     async fn synthesize(
         &self,
         query: &str,
         sources: &[Source],
     ) -> Result<ResearchAnswer, LlmError> {
+        self.synthesize_captured(query, sources, None, None, AnswerStyle::Neutral, None)
+            .await
+            .0
+    }
+
+    #[instrument(skip(self, sources, schema), fields(model = %self.model, source_count = sources.len()))]
+    async fn synthesize_captured(
+        &self,
+        query: &str,
+        sources: &[Source],
+        length: Option<&LengthPolicy>,
+        schema: Option<&AnswerSchema>,
+        style: AnswerStyle,
+        temperature: Option<f32>,
+    ) -> (Result<ResearchAnswer, LlmError>, LlmExchange) {
         let start = Instant::now();
 
-        let messages = build_synthesis_messages(query, sources);
+        let mut messages =
+            build_synthesis_messages_for(query, sources, self.prompt_hardening, length);
+        if self.max_images > 0 && self.supports_vision() {
+            messages = attach_source_images(messages, sources, self.max_images);
+        }
+        if let Some(schema) = schema {
+            messages = append_answer_schema(messages, schema);
+        }
+        messages = append_answer_style(messages, style);
+        let max_tokens = length.map_or(self.max_tokens, |policy| policy.cap(self.max_tokens));
         let anthropic_messages: Vec<AnthropicMessage> = messages
             .iter()
-            .filter(|m| !matches!(m.role, crate::types::Role::System))
+            .filter(|m| !matches!(m.role, Role::System))
             .map(|m| match m.role {
-                crate::types::Role::User => AnthropicMessage::user(&m.content),
-                crate::types::Role::Assistant => AnthropicMessage::assistant(&m.content),
-                crate::types::Role::System => unreachable!(),
+                Role::User => AnthropicMessage::user_with_images(&m.content, &m.images),
+                Role::Assistant => AnthropicMessage::assistant(&m.content),
+                Role::System => unreachable!(),
             })
             .collect();
 
-        let response = self
+        let mut exchange = LlmExchange {
+            messages,
+            raw_response: None,
+        };
+
+        let response = match self
             .client
             .send_message(
                 &self.model,
-                SYNTHESIS_SYSTEM_PROMPT,
+                self.prompt_hardening.system_prompt(),
                 anthropic_messages,
-                self.max_tokens,
+                max_tokens,
+                temperature,
             )
-            .await?;
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => return (Err(e), exchange),
+        };
 
         if response.stop_reason == Some(StopReason::MaxTokens) {
             tracing::warn!("response truncated due to max_tokens limit");
         }
 
         let text = response.text_content();
-        let tokens_used = response.usage.total();
+        let usage = TokenUsage {
+            prompt_tokens: response.usage.input_tokens,
+            completion_tokens: response.usage.output_tokens,
+        };
+
+        let result = parse_synthesis_response(&text, sources, &self.model, usage.total())
+            .map(|mut answer| {
+                answer.synthesis_metadata.synthesis_duration = start.elapsed();
+                answer.synthesis_metadata.record_usage(StageTokenUsage::new(
+                    LlmStage::Synthesis,
+                    &self.model,
+                    &usage,
+                ));
+                answer
+            })
+            .map_err(|e| LlmError::Provider(format!("failed to parse synthesis response: {}", e)));
+        exchange.raw_response = Some(text);
+
+        (result, exchange)
+    }
 
-        let mut answer = parser::parse_synthesis_response(&text, sources, &self.model, tokens_used)
-            .map_err(|e| {
-                LlmError::Provider(format!("failed to parse synthesis response: {}", e))
-            })?;
+    #[instrument(skip(self, request), fields(model = %self.model, message_count = request.messages.len()))]
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, LlmError> {
+        let (system, rest) = split_system(&request.messages);
+        let messages = rest
+            .into_iter()
+            .map(|m| match m.role {
+                Role::Assistant => AnthropicMessage::assistant(&m.content),
+                _ => AnthropicMessage::user_with_images(&m.content, &m.images),
+            })
+            .collect();
 
-        answer.synthesis_metadata.synthesis_duration = start.elapsed();
+        let mut messages_request = MessagesRequest::new(&self.model, messages)
+            .with_max_tokens(request.max_tokens.unwrap_or(self.max_tokens));
+        if !system.is_empty() {
+            messages_request = messages_request.with_system(system);
+        }
+        if let Some(temperature) = request.temperature {
+            messages_request = messages_request.with_temperature(temperature);
+        }
 
-        Ok(answer)
+        let response = self.client.send_request(&messages_request).await?;
+
+        Ok(ChatResponse {
+            content: response.text_content(),
+            finish_reason: match response.stop_reason {
+                Some(StopReason::EndTurn | StopReason::StopSequence) => FinishReason::Stop,
+                Some(StopReason::MaxTokens) => FinishReason::Length,
+                _ => FinishReason::Unknown,
+            },
+            usage: TokenUsage {
+                prompt_tokens: response.usage.input_tokens,
+                completion_tokens: response.usage.output_tokens,
+            },
+            model: response.model,
+        })
+    }
+
+    fn parse_synthesis(&self, raw: &str, sources: &[Source]) -> Result<ResearchAnswer, LlmError> {
+        parse_synthesis_response(raw, sources, &self.model, 0)
+            .map_err(|e| LlmError::Provider(format!("failed to parse synthesis response: {}", e)))
     }
 
     fn model_id(&self) -> &str {
@@ -97,9 +211,21 @@ impl LlmProvider for AnthropicProvider {
         CONTEXT_WINDOW_TOKENS
     }
 
+    fn count_tokens(&self, text: &str) -> usize {
+        self.tokenizer.count_tokens(text)
+    }
+
     fn supports_streaming(&self) -> bool {
         false
     }
+
+    fn supports_vision(&self) -> bool {
+        types::is_vision_model(&self.model)
+    }
+
+    fn pricing(&self) -> Option<ModelPricing> {
+        types::pricing_for(&self.model)
+    }
 }
 
 impl std::fmt::Debug for AnthropicProvider {
@@ -120,6 +246,29 @@ mod tests {
         assert_eq!(CONTEXT_WINDOW_TOKENS, 200_000);
     }
 
+    #[test]
+    fn counts_tokens_with_the_claude_tokenizer() {
+        let config = AnthropicConfig {
+            api_keys: Arc::new(gorkd_core::KeyPool::single(secrecy::SecretString::from(
+                "sk-ant-test",
+            ))),
+            base_url: "http://localhost".to_string(),
+        };
+        let provider = AnthropicProvider::new(Client::new(), &config, "claude-sonnet-4");
+        let text = "東京は日本の首都です";
+        assert_eq!(
+            provider.count_tokens(text),
+            crate::ClaudeTokenizer.count_tokens(text)
+        );
+
+        // The default of four bytes per token undercounts non-ASCII text.
+        let sources = [Source::new("https://example.jp", "東京", text)];
+        let claude = gorkd_core::CostEstimate::synthesis(&provider, "首都は?", &sources, 100);
+        let mock = gorkd_core::MockLlmProvider::new("mock");
+        let default = gorkd_core::CostEstimate::synthesis(&mock, "首都は?", &sources, 100);
+        assert!(claude.prompt_tokens > default.prompt_tokens);
+    }
+
     #[test]
     fn provider_model_constants_exist() {
         use types::{MODEL_CLAUDE_HAIKU_35, MODEL_CLAUDE_SONNET_4};
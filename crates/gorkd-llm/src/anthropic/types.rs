@@ -3,6 +3,7 @@
 //! These types map directly to the Anthropic Messages API.
 //! See: https://docs.anthropic.com/en/api/messages
 
+use gorkd_core::ModelPricing;
 use serde::{Deserialize, Serialize};
 
 /// Anthropic API version header value.
@@ -17,6 +18,31 @@ pub const MODEL_CLAUDE_SONNET_4: &str = "claude-sonnet-4-20250514";
 /// Claude Haiku 3.5 model ID (fast/cheap fallback).
 pub const MODEL_CLAUDE_HAIKU_35: &str = "claude-3-5-haiku-20241022";
 
+/// Whether `model` accepts image inputs. Every Claude model from Claude 3 on
+/// does.
+pub fn is_vision_model(model: &str) -> bool {
+    model.starts_with("claude-")
+        && !model.starts_with("claude-2")
+        && !model.starts_with("claude-instant")
+}
+
+/// List price of `model` in US dollars per million tokens, by model family.
+pub fn pricing_for(model: &str) -> Option<ModelPricing> {
+    let (input, output) = match model {
+        m if m.starts_with("claude-opus-4") || m.starts_with("claude-3-opus") => (15.0, 75.0),
+        m if m.starts_with("claude-sonnet-4")
+            || m.starts_with("claude-3-7-sonnet")
+            || m.starts_with("claude-3-5-sonnet") =>
+        {
+            (3.0, 15.0)
+        }
+        m if m.starts_with("claude-3-5-haiku") => (0.8, 4.0),
+        m if m.starts_with("claude-3-haiku") => (0.25, 1.25),
+        _ => return None,
+    };
+    Some(ModelPricing::new(input, output))
+}
+
 /// Context window size for Claude models (200K tokens).
 pub const CONTEXT_WINDOW_TOKENS: usize = 200_000;
 
@@ -27,24 +53,69 @@ pub enum MessageRole {
     Assistant,
 }
 
+/// Message content: plain text, or text and image blocks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MessageContent {
+    Text(String),
+    Blocks(Vec<RequestBlock>),
+}
+
+impl PartialEq<&str> for MessageContent {
+    fn eq(&self, other: &&str) -> bool {
+        matches!(self, Self::Text(text) if text == other)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RequestBlock {
+    Text { text: String },
+    Image { source: ImageSource },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ImageSource {
+    Url { url: String },
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnthropicMessage {
     pub role: MessageRole,
-    pub content: String,
+    pub content: MessageContent,
 }
 
 impl AnthropicMessage {
     pub fn user(content: impl Into<String>) -> Self {
         Self {
             role: MessageRole::User,
-            content: content.into(),
+            content: MessageContent::Text(content.into()),
         }
     }
 
     pub fn assistant(content: impl Into<String>) -> Self {
         Self {
             role: MessageRole::Assistant,
-            content: content.into(),
+            content: MessageContent::Text(content.into()),
+        }
+    }
+
+    /// A user message showing `images` after the text.
+    pub fn user_with_images(content: impl Into<String>, images: &[String]) -> Self {
+        if images.is_empty() {
+            return Self::user(content);
+        }
+
+        let mut blocks = vec![RequestBlock::Text {
+            text: content.into(),
+        }];
+        blocks.extend(images.iter().map(|url| RequestBlock::Image {
+            source: ImageSource::Url { url: url.clone() },
+        }));
+        Self {
+            role: MessageRole::User,
+            content: MessageContent::Blocks(blocks),
         }
     }
 }
@@ -144,6 +215,19 @@ impl MessagesResponse {
 mod tests {
     use super::*;
 
+    #[test]
+    fn prices_models_by_family() {
+        assert_eq!(
+            pricing_for(MODEL_CLAUDE_SONNET_4),
+            Some(ModelPricing::new(3.0, 15.0))
+        );
+        assert_eq!(
+            pricing_for(MODEL_CLAUDE_HAIKU_35),
+            Some(ModelPricing::new(0.8, 4.0))
+        );
+        assert_eq!(pricing_for("claude-next"), None);
+    }
+
     #[test]
     fn creates_user_message() {
         let msg = AnthropicMessage::user("Hello");
@@ -158,6 +242,30 @@ mod tests {
         assert_eq!(msg.content, "Hi there!");
     }
 
+    #[test]
+    fn serializes_images_as_url_blocks() {
+        let message = AnthropicMessage::user_with_images(
+            "Describe the chart",
+            &["https://example.com/chart.png".to_string()],
+        );
+
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(
+            json["content"],
+            serde_json::json!([
+                {"type": "text", "text": "Describe the chart"},
+                {"type": "image", "source": {"type": "url", "url": "https://example.com/chart.png"}}
+            ])
+        );
+    }
+
+    #[test]
+    fn recognizes_vision_models() {
+        assert!(is_vision_model(MODEL_CLAUDE_SONNET_4));
+        assert!(is_vision_model(MODEL_CLAUDE_HAIKU_35));
+        assert!(!is_vision_model("claude-2.1"));
+    }
+
     #[test]
     fn serializes_request() {
         let request =
@@ -0,0 +1,147 @@
+use chrono::Utc;
+use gorkd_core::sigv4::{self, CanonicalPath, SignableRequest};
+use gorkd_core::LlmError;
+use reqwest::Client;
+use tracing::instrument;
+
+use crate::client::with_trace_header;
+use crate::config::{AwsCredentials, BedrockConfig};
+use crate::error::{map_bedrock_error, with_retry_after};
+
+use super::types::{ConverseMessage, ConverseRequest, ConverseResponse, SIGNING_SERVICE};
+
+pub struct BedrockClient {
+    http: Client,
+    credentials: AwsCredentials,
+    region: String,
+    base_url: String,
+}
+
+impl BedrockClient {
+    pub fn new(http: Client, config: &BedrockConfig) -> Self {
+        Self {
+            http,
+            credentials: config.credentials.clone(),
+            region: config.region.clone(),
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    #[instrument(skip(self, system, messages), fields(model = %model))]
+    pub async fn converse(
+        &self,
+        model: &str,
+        system: &str,
+        messages: Vec<ConverseMessage>,
+        max_tokens: usize,
+        temperature: Option<f32>,
+    ) -> Result<ConverseResponse, LlmError> {
+        let mut request = ConverseRequest::new(messages)
+            .with_system(system)
+            .with_max_tokens(max_tokens);
+        if let Some(temperature) = temperature {
+            request = request.with_temperature(temperature);
+        }
+
+        self.send_request(model, &request).await
+    }
+
+    #[instrument(skip(self, request), fields(model = %model))]
+    pub async fn send_request(
+        &self,
+        model: &str,
+        request: &ConverseRequest,
+    ) -> Result<ConverseResponse, LlmError> {
+        let body = serde_json::to_vec(request)
+            .map_err(|e| LlmError::Provider(format!("serialize error: {}", e)))?;
+
+        let path = format!("/model/{}/converse", sigv4::uri_encode(model));
+        let url = format!("{}{}", self.base_url, path);
+        let host = sigv4::host_of(&self.base_url);
+        let payload_hash = sigv4::payload_hash(&body);
+
+        let content_type = [("content-type", "application/json")];
+        let signable = SignableRequest {
+            method: "POST",
+            host,
+            path: &path,
+            headers: &content_type,
+            payload_hash: &payload_hash,
+            canonical_path: CanonicalPath::DoubleEncoded,
+        };
+        let auth_headers = sigv4::sign(
+            &signable,
+            self.credentials.signing(),
+            &self.region,
+            SIGNING_SERVICE,
+            Utc::now(),
+        );
+
+        // The trace header is not signed, which SigV4 permits.
+        let mut builder = with_trace_header(self.http.post(&url))
+            .header("content-type", "application/json")
+            .body(body);
+        for (name, value) in auth_headers {
+            builder = builder.header(name, value);
+        }
+
+        let response = builder
+            .send()
+            .await
+            .map_err(crate::error::map_reqwest_error)?;
+
+        let status = response.status();
+        let headers = response.headers().clone();
+        let error_type = headers
+            .get("x-amzn-errortype")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = response
+            .text()
+            .await
+            .map_err(crate::error::map_reqwest_error)?;
+
+        if !status.is_success() {
+            return Err(with_retry_after(
+                map_bedrock_error(status, error_type.as_deref(), &body),
+                &headers,
+            ));
+        }
+
+        serde_json::from_str(&body).map_err(|e| LlmError::Provider(format!("parse error: {}", e)))
+    }
+}
+
+impl std::fmt::Debug for BedrockClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BedrockClient")
+            .field("region", &self.region)
+            .field("base_url", &self.base_url)
+            .field("credentials", &"[REDACTED]")
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secrecy::SecretString;
+
+    #[test]
+    fn debug_redacts_credentials() {
+        let config = BedrockConfig {
+            region: "us-east-1".to_string(),
+            credentials: AwsCredentials {
+                access_key_id: "AKIDEXAMPLE".to_string(),
+                secret_access_key: SecretString::from("aws-secret-key"),
+                session_token: None,
+            },
+            base_url: BedrockConfig::default_endpoint("us-east-1"),
+        };
+        let client = BedrockClient::new(Client::new(), &config);
+
+        let debug_str = format!("{:?}", client);
+        assert!(!debug_str.contains("aws-secret-key"));
+        assert!(debug_str.contains("[REDACTED]"));
+    }
+}
@@ -0,0 +1,233 @@
+mod client;
+pub mod types;
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use gorkd_core::{
+    AnswerSchema, AnswerStyle, LengthPolicy, LlmError, LlmExchange, LlmProvider, LlmStage,
+    ModelPricing, ResearchAnswer, Source, StageTokenUsage,
+};
+use reqwest::Client;
+use tracing::instrument;
+
+use crate::config::BedrockConfig;
+use crate::parser::parse_synthesis_response;
+use crate::prompt::{
+    append_answer_schema, append_answer_style, build_synthesis_messages_for, PromptHardening,
+};
+use crate::tokenizer::{tokenizer_for, Tokenizer};
+use crate::types::{split_system, ChatRequest, ChatResponse, FinishReason, Role, TokenUsage};
+
+use client::BedrockClient;
+use types::{context_window_for, ConverseMessage, ConverseRequest, StopReason, DEFAULT_MAX_TOKENS};
+
+/// Runs models hosted on AWS Bedrock through the Converse API, so requests
+/// stay inside the caller's AWS account and region.
+pub struct BedrockProvider {
+    client: BedrockClient,
+    model: String,
+    tokenizer: Arc<dyn Tokenizer>,
+    max_tokens: usize,
+    prompt_hardening: PromptHardening,
+}
+
+impl BedrockProvider {
+    pub fn new(http: Client, config: &BedrockConfig, model: impl Into<String>) -> Self {
+        let model = model.into();
+        Self {
+            client: BedrockClient::new(http, config),
+            tokenizer: tokenizer_for("bedrock", &model),
+            model,
+            max_tokens: DEFAULT_MAX_TOKENS,
+            prompt_hardening: PromptHardening::default(),
+        }
+    }
+
+    pub fn with_max_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    pub fn with_prompt_hardening(mut self, hardening: PromptHardening) -> Self {
+        self.prompt_hardening = hardening;
+        self
+    }
+}
+
+#[async_trait]
+impl LlmProvider for BedrockProvider {
+    async fn synthesize(
+        &self,
+        query: &str,
+        sources: &[Source],
+    ) -> Result<ResearchAnswer, LlmError> {
+        self.synthesize_captured(query, sources, None, None, AnswerStyle::Neutral, None)
+            .await
+            .0
+    }
+
+    #[instrument(skip(self, sources, schema), fields(model = %self.model, source_count = sources.len()))]
+    async fn synthesize_captured(
+        &self,
+        query: &str,
+        sources: &[Source],
+        length: Option<&LengthPolicy>,
+        schema: Option<&AnswerSchema>,
+        style: AnswerStyle,
+        temperature: Option<f32>,
+    ) -> (Result<ResearchAnswer, LlmError>, LlmExchange) {
+        let start = Instant::now();
+
+        let mut messages =
+            build_synthesis_messages_for(query, sources, self.prompt_hardening, length);
+        if let Some(schema) = schema {
+            messages = append_answer_schema(messages, schema);
+        }
+        messages = append_answer_style(messages, style);
+        let max_tokens = length.map_or(self.max_tokens, |policy| policy.cap(self.max_tokens));
+        let converse_messages: Vec<ConverseMessage> = messages
+            .iter()
+            .filter_map(|m| match m.role {
+                Role::User => Some(ConverseMessage::user(&m.content)),
+                Role::Assistant => Some(ConverseMessage::assistant(&m.content)),
+                Role::System => None,
+            })
+            .collect();
+
+        let mut exchange = LlmExchange {
+            messages,
+            raw_response: None,
+        };
+
+        let response = match self
+            .client
+            .converse(
+                &self.model,
+                self.prompt_hardening.system_prompt(),
+                converse_messages,
+                max_tokens,
+                temperature,
+            )
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => return (Err(e), exchange),
+        };
+
+        let text = response.text_content();
+        let tokens_used = response.usage.total_tokens;
+        let usage = TokenUsage {
+            prompt_tokens: response.usage.input_tokens,
+            completion_tokens: response.usage.output_tokens,
+        };
+
+        let result = match response.stop_reason {
+            Some(StopReason::GuardrailIntervened | StopReason::ContentFiltered) => {
+                Err(LlmError::ContentFiltered {
+                    reason: "blocked by Bedrock guardrail".to_string(),
+                })
+            }
+            stop_reason => {
+                if stop_reason == Some(StopReason::MaxTokens) {
+                    tracing::warn!("response truncated due to max_tokens limit");
+                }
+                parse_synthesis_response(&text, sources, &self.model, tokens_used)
+                    .map(|mut answer| {
+                        answer.synthesis_metadata.synthesis_duration = start.elapsed();
+                        answer.synthesis_metadata.record_usage(StageTokenUsage::new(
+                            LlmStage::Synthesis,
+                            &self.model,
+                            &usage,
+                        ));
+                        answer
+                    })
+                    .map_err(|e| {
+                        LlmError::Provider(format!("failed to parse synthesis response: {}", e))
+                    })
+            }
+        };
+        exchange.raw_response = Some(text);
+
+        (result, exchange)
+    }
+
+    #[instrument(skip(self, request), fields(model = %self.model, message_count = request.messages.len()))]
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, LlmError> {
+        let (system, rest) = split_system(&request.messages);
+        let messages = rest
+            .into_iter()
+            .map(|m| match m.role {
+                Role::Assistant => ConverseMessage::assistant(&m.content),
+                _ => ConverseMessage::user(&m.content),
+            })
+            .collect();
+
+        let mut converse = ConverseRequest::new(messages)
+            .with_max_tokens(request.max_tokens.unwrap_or(self.max_tokens));
+        if !system.is_empty() {
+            converse = converse.with_system(system);
+        }
+        if let Some(temperature) = request.temperature {
+            converse = converse.with_temperature(temperature);
+        }
+
+        let response = self.client.send_request(&self.model, &converse).await?;
+
+        Ok(ChatResponse {
+            content: response.text_content(),
+            finish_reason: match response.stop_reason {
+                Some(StopReason::EndTurn | StopReason::StopSequence) => FinishReason::Stop,
+                Some(StopReason::MaxTokens) => FinishReason::Length,
+                Some(StopReason::GuardrailIntervened | StopReason::ContentFiltered) => {
+                    FinishReason::ContentFilter
+                }
+                _ => FinishReason::Unknown,
+            },
+            usage: TokenUsage {
+                prompt_tokens: response.usage.input_tokens,
+                completion_tokens: response.usage.output_tokens,
+            },
+            model: self.model.clone(),
+        })
+    }
+
+    fn parse_synthesis(&self, raw: &str, sources: &[Source]) -> Result<ResearchAnswer, LlmError> {
+        parse_synthesis_response(raw, sources, &self.model, 0)
+            .map_err(|e| LlmError::Provider(format!("failed to parse synthesis response: {}", e)))
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model
+    }
+
+    fn provider_name(&self) -> &str {
+        "bedrock"
+    }
+
+    fn max_context_tokens(&self) -> usize {
+        context_window_for(&self.model)
+    }
+
+    fn count_tokens(&self, text: &str) -> usize {
+        self.tokenizer.count_tokens(text)
+    }
+
+    fn supports_streaming(&self) -> bool {
+        false
+    }
+
+    fn pricing(&self) -> Option<ModelPricing> {
+        types::pricing_for(&self.model)
+    }
+}
+
+impl std::fmt::Debug for BedrockProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BedrockProvider")
+            .field("model", &self.model)
+            .field("max_tokens", &self.max_tokens)
+            .finish()
+    }
+}
@@ -0,0 +1,248 @@
+//! AWS Bedrock Converse API request and response types.
+//!
+//! The Converse API offers one message format across Bedrock model families.
+//! See: https://docs.aws.amazon.com/bedrock/latest/APIReference/API_runtime_Converse.html
+
+use gorkd_core::ModelPricing;
+use serde::{Deserialize, Serialize};
+
+/// SigV4 service name for the Bedrock runtime.
+pub const SIGNING_SERVICE: &str = "bedrock";
+
+/// Default max tokens for Bedrock responses.
+pub const DEFAULT_MAX_TOKENS: usize = 4096;
+
+/// Claude Sonnet 4 on Bedrock (primary model).
+pub const MODEL_BEDROCK_CLAUDE_SONNET_4: &str = "anthropic.claude-sonnet-4-20250514-v1:0";
+
+/// Llama 3.1 70B Instruct on Bedrock.
+pub const MODEL_BEDROCK_LLAMA_31_70B: &str = "meta.llama3-1-70b-instruct-v1:0";
+
+/// Context window for Claude models on Bedrock.
+pub const CLAUDE_CONTEXT_WINDOW_TOKENS: usize = 200_000;
+
+/// Context window for Llama 3.1 models on Bedrock.
+pub const LLAMA_CONTEXT_WINDOW_TOKENS: usize = 128_000;
+
+/// Returns the context window for a Bedrock model ID.
+pub fn context_window_for(model: &str) -> usize {
+    if model.contains("llama") {
+        LLAMA_CONTEXT_WINDOW_TOKENS
+    } else {
+        CLAUDE_CONTEXT_WINDOW_TOKENS
+    }
+}
+
+/// On-demand price of a Bedrock model ID in US dollars per million tokens.
+/// Cross-region IDs such as `us.anthropic.…` are priced like the model.
+pub fn pricing_for(model: &str) -> Option<ModelPricing> {
+    let (input, output) = match model {
+        m if m.contains("anthropic.claude-sonnet-4") => (3.0, 15.0),
+        m if m.contains("anthropic.claude-3-5-haiku") => (0.8, 4.0),
+        m if m.contains("meta.llama3-1-70b") => (0.72, 0.72),
+        m if m.contains("meta.llama3-1-8b") => (0.22, 0.22),
+        _ => return None,
+    };
+    Some(ModelPricing::new(input, output))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConversationRole {
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentBlock {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+impl ContentBlock {
+    pub fn text(text: impl Into<String>) -> Self {
+        Self {
+            text: Some(text.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConverseMessage {
+    pub role: ConversationRole,
+    pub content: Vec<ContentBlock>,
+}
+
+impl ConverseMessage {
+    pub fn user(text: impl Into<String>) -> Self {
+        Self {
+            role: ConversationRole::User,
+            content: vec![ContentBlock::text(text)],
+        }
+    }
+
+    pub fn assistant(text: impl Into<String>) -> Self {
+        Self {
+            role: ConversationRole::Assistant,
+            content: vec![ContentBlock::text(text)],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InferenceConfig {
+    pub max_tokens: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConverseRequest {
+    pub messages: Vec<ConverseMessage>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub system: Vec<ContentBlock>,
+    pub inference_config: InferenceConfig,
+}
+
+impl ConverseRequest {
+    pub fn new(messages: Vec<ConverseMessage>) -> Self {
+        Self {
+            messages,
+            system: Vec::new(),
+            inference_config: InferenceConfig {
+                max_tokens: DEFAULT_MAX_TOKENS,
+                temperature: None,
+            },
+        }
+    }
+
+    pub fn with_system(mut self, system: impl Into<String>) -> Self {
+        self.system = vec![ContentBlock::text(system)];
+        self
+    }
+
+    pub fn with_max_tokens(mut self, max_tokens: usize) -> Self {
+        self.inference_config.max_tokens = max_tokens;
+        self
+    }
+
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.inference_config.temperature = Some(temperature.clamp(0.0, 1.0));
+        self
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConverseOutput {
+    pub message: ConverseMessage,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum StopReason {
+    EndTurn,
+    ToolUse,
+    MaxTokens,
+    StopSequence,
+    GuardrailIntervened,
+    ContentFiltered,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConverseUsage {
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+    pub total_tokens: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConverseResponse {
+    pub output: ConverseOutput,
+    pub stop_reason: Option<StopReason>,
+    #[serde(default)]
+    pub usage: ConverseUsage,
+}
+
+impl ConverseResponse {
+    pub fn text_content(&self) -> String {
+        self.output
+            .message
+            .content
+            .iter()
+            .filter_map(|block| block.text.as_deref())
+            .collect::<Vec<_>>()
+            .join("")
+    }
+}
+
+/// Error body returned by Bedrock; the error type travels in the
+/// `x-amzn-ErrorType` header.
+#[derive(Debug, Deserialize)]
+pub struct BedrockErrorResponse {
+    #[serde(alias = "Message")]
+    pub message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prices_cross_region_models() {
+        assert_eq!(
+            pricing_for("us.anthropic.claude-sonnet-4-20250514-v1:0"),
+            pricing_for(MODEL_BEDROCK_CLAUDE_SONNET_4)
+        );
+        assert!(pricing_for(MODEL_BEDROCK_LLAMA_31_70B).is_some());
+    }
+
+    #[test]
+    fn serializes_converse_request() {
+        let request = ConverseRequest::new(vec![ConverseMessage::user("Hello")])
+            .with_system("Be brief")
+            .with_max_tokens(512);
+
+        let json = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(json["messages"][0]["role"], "user");
+        assert_eq!(json["messages"][0]["content"][0]["text"], "Hello");
+        assert_eq!(json["system"][0]["text"], "Be brief");
+        assert_eq!(json["inferenceConfig"]["maxTokens"], 512);
+        assert!(json["inferenceConfig"].get("temperature").is_none());
+    }
+
+    #[test]
+    fn deserializes_converse_response() {
+        let body = r#"{
+            "output": {"message": {"role": "assistant", "content": [{"text": "Hi"}, {"text": " there"}]}},
+            "stopReason": "max_tokens",
+            "usage": {"inputTokens": 10, "outputTokens": 5, "totalTokens": 15},
+            "metrics": {"latencyMs": 120}
+        }"#;
+
+        let response: ConverseResponse = serde_json::from_str(body).unwrap();
+
+        assert_eq!(response.text_content(), "Hi there");
+        assert_eq!(response.stop_reason, Some(StopReason::MaxTokens));
+        assert_eq!(response.usage.total_tokens, 15);
+    }
+
+    #[test]
+    fn picks_context_window_by_family() {
+        assert_eq!(
+            context_window_for(MODEL_BEDROCK_CLAUDE_SONNET_4),
+            CLAUDE_CONTEXT_WINDOW_TOKENS
+        );
+        assert_eq!(
+            context_window_for(MODEL_BEDROCK_LLAMA_31_70B),
+            LLAMA_CONTEXT_WINDOW_TOKENS
+        );
+    }
+}
@@ -1,29 +1,95 @@
 use std::time::Duration;
 
-use reqwest::Client;
+use gorkd_core::{current_trace_id, HttpOptions, KeyOutcome, LlmError, TRACE_ID_HEADER};
+use reqwest::{Certificate, Client, NoProxy, Proxy, RequestBuilder, StatusCode};
 
 use crate::config::{LlmConfig, DEFAULT_TIMEOUT_SECS};
+use crate::error::{map_reqwest_error, retry_after_secs, with_retry_after};
+
+/// Idle connections kept per provider host when none is configured.
+const DEFAULT_POOL_MAX_IDLE_PER_HOST: usize = 4;
 
 pub fn build_http_client(config: &LlmConfig) -> Result<Client, reqwest::Error> {
-    Client::builder()
-        .timeout(config.timeout)
-        .connect_timeout(Duration::from_secs(10))
-        .pool_max_idle_per_host(4)
-        .build()
+    build_http_client_with_options(config.timeout, &HttpOptions::default())
 }
 
 pub fn build_http_client_with_timeout(timeout_secs: u64) -> Result<Client, reqwest::Error> {
-    Client::builder()
-        .timeout(Duration::from_secs(timeout_secs))
-        .connect_timeout(Duration::from_secs(10))
-        .pool_max_idle_per_host(4)
-        .build()
+    build_http_client_with_options(Duration::from_secs(timeout_secs), &HttpOptions::default())
+}
+
+/// Builds a client with the given User-Agent, proxy and TLS settings.
+pub fn build_http_client_with_options(
+    timeout: Duration,
+    options: &HttpOptions,
+) -> Result<Client, reqwest::Error> {
+    let mut builder = Client::builder()
+        .user_agent(options.user_agent())
+        .timeout(timeout)
+        .connect_timeout(options.connect_timeout())
+        .pool_max_idle_per_host(
+            options
+                .pool_max_idle_per_host
+                .unwrap_or(DEFAULT_POOL_MAX_IDLE_PER_HOST),
+        )
+        .tcp_keepalive(options.tcp_keepalive)
+        .danger_accept_invalid_certs(options.accept_invalid_certs);
+    if let Some(timeout) = options.pool_idle_timeout {
+        builder = builder.pool_idle_timeout(timeout);
+    }
+    if let Some(ref proxy) = options.proxy {
+        let no_proxy = options.no_proxy.as_deref().and_then(NoProxy::from_string);
+        builder = builder.proxy(Proxy::all(proxy)?.no_proxy(no_proxy));
+    }
+    if let Some(ref pem) = options.root_certificates {
+        for certificate in Certificate::from_pem_bundle(pem)? {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    builder.build()
 }
 
 pub fn default_http_client() -> Result<Client, reqwest::Error> {
     build_http_client_with_timeout(DEFAULT_TIMEOUT_SECS)
 }
 
+/// Tags a provider request with the current job's trace ID, if any.
+pub(crate) fn with_trace_header(request: RequestBuilder) -> RequestBuilder {
+    match current_trace_id() {
+        Some(trace_id) => request.header(TRACE_ID_HEADER, trace_id.as_str()),
+        None => request,
+    }
+}
+
+/// Sends `request`, carrying one of a provider's keys, and returns the body
+/// of a successful response with how the key fared. `map_error` maps an
+/// unsuccessful response's status and body.
+pub(crate) async fn send_keyed(
+    request: RequestBuilder,
+    map_error: impl FnOnce(StatusCode, &str) -> LlmError,
+) -> (Result<String, LlmError>, KeyOutcome) {
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(err) => return (Err(map_reqwest_error(err)), KeyOutcome::Failed),
+    };
+
+    let status = response.status();
+    let headers = response.headers().clone();
+    let outcome = KeyOutcome::for_status(
+        status.as_u16(),
+        retry_after_secs(&headers).map(Duration::from_secs),
+    );
+    let body = match response.text().await {
+        Ok(body) => body,
+        Err(err) => return (Err(map_reqwest_error(err)), KeyOutcome::Failed),
+    };
+
+    if !status.is_success() {
+        let error = with_retry_after(map_error(status, &body), &headers);
+        return (Err(error), outcome);
+    }
+    (Ok(body), outcome)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -41,9 +107,42 @@ mod tests {
         assert!(client.is_ok());
     }
 
+    #[test]
+    fn builds_client_with_options() {
+        let options = HttpOptions::new()
+            .with_user_agent("acme-research/1.0")
+            .with_proxy("http://proxy.example:3128");
+        assert!(build_http_client_with_options(Duration::from_secs(5), &options).is_ok());
+
+        let options = HttpOptions::new().with_proxy("not a proxy url");
+        assert!(build_http_client_with_options(Duration::from_secs(5), &options).is_err());
+    }
+
     #[test]
     fn builds_default_client() {
         let client = default_http_client();
         assert!(client.is_ok());
     }
+
+    #[tokio::test]
+    async fn tags_requests_with_current_trace_id() {
+        let client = default_http_client().unwrap();
+        let trace_id = gorkd_core::TraceId::new();
+
+        let request = gorkd_core::with_trace_id(trace_id.clone(), async {
+            with_trace_header(client.post("https://example.com"))
+                .build()
+                .unwrap()
+        })
+        .await;
+        assert_eq!(
+            request.headers()[TRACE_ID_HEADER].to_str().unwrap(),
+            trace_id.as_str()
+        );
+
+        let request = with_trace_header(client.post("https://example.com"))
+            .build()
+            .unwrap();
+        assert!(request.headers().get(TRACE_ID_HEADER).is_none());
+    }
 }
@@ -0,0 +1,350 @@
+//! Limits on concurrent LLM requests.
+//!
+//! Every job synthesizes at least once, so a burst of jobs becomes a burst
+//! of provider requests, and organization-wide rate limits count requests
+//! in flight across all of them. An [`LlmLimiter`] caps requests in flight
+//! overall and per provider (`anthropic`, `openai`, `bedrock`), queueing
+//! the rest, and counts what is in flight and waiting for `GET /metrics`.
+//! [`LimitedProvider`] applies a limiter to one provider.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use gorkd_core::{
+    AnswerSchema, AnswerStyle, ChatRequest, ChatResponse, LengthPolicy, LlmError, LlmExchange,
+    LlmProvider, ModelPricing, ResearchAnswer, Source,
+};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Requests allowed in flight at once; `None` allows any number.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConcurrencyLimits {
+    /// Across all providers, from `LLM_MAX_CONCURRENT`.
+    pub overall: Option<usize>,
+    /// For each provider without a limit of its own, from
+    /// `LLM_MAX_CONCURRENT_PER_PROVIDER`.
+    pub per_provider: Option<usize>,
+    /// By provider name, from `LLM_MAX_CONCURRENT_ANTHROPIC`,
+    /// `LLM_MAX_CONCURRENT_OPENAI` and `LLM_MAX_CONCURRENT_BEDROCK`.
+    pub providers: BTreeMap<String, usize>,
+}
+
+impl ConcurrencyLimits {
+    pub fn for_provider(&self, provider: &str) -> Option<usize> {
+        self.providers.get(provider).copied().or(self.per_provider)
+    }
+
+    pub fn with_overall(mut self, max: usize) -> Self {
+        self.overall = Some(max);
+        self
+    }
+
+    pub fn with_per_provider(mut self, max: usize) -> Self {
+        self.per_provider = Some(max);
+        self
+    }
+
+    pub fn with_provider(mut self, provider: impl Into<String>, max: usize) -> Self {
+        self.providers.insert(provider.into(), max);
+        self
+    }
+}
+
+/// Requests in flight and queued behind a limit at one moment.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SlotUsage {
+    pub in_flight: usize,
+    pub waiting: usize,
+    pub limit: Option<usize>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConcurrencySnapshot {
+    pub overall: SlotUsage,
+    /// By provider name, for providers that have been called.
+    pub providers: Vec<(String, SlotUsage)>,
+}
+
+struct Slots {
+    semaphore: Option<Arc<Semaphore>>,
+    limit: Option<usize>,
+    in_flight: AtomicUsize,
+    waiting: AtomicUsize,
+}
+
+impl Slots {
+    fn new(limit: Option<usize>) -> Self {
+        // A limit of zero would block every request forever.
+        let limit = limit.map(|limit| limit.max(1));
+        Self {
+            semaphore: limit.map(|limit| Arc::new(Semaphore::new(limit))),
+            limit,
+            in_flight: AtomicUsize::new(0),
+            waiting: AtomicUsize::new(0),
+        }
+    }
+
+    async fn acquire(self: &Arc<Self>) -> Slot {
+        let permit = match self.semaphore {
+            Some(ref semaphore) => {
+                self.waiting.fetch_add(1, Ordering::SeqCst);
+                let permit = Arc::clone(semaphore).acquire_owned().await;
+                self.waiting.fetch_sub(1, Ordering::SeqCst);
+                // The semaphore is never closed.
+                permit.ok()
+            }
+            None => None,
+        };
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        Slot {
+            slots: Arc::clone(self),
+            _permit: permit,
+        }
+    }
+
+    fn usage(&self) -> SlotUsage {
+        SlotUsage {
+            in_flight: self.in_flight.load(Ordering::SeqCst),
+            waiting: self.waiting.load(Ordering::SeqCst),
+            limit: self.limit,
+        }
+    }
+}
+
+/// A request's place in flight, given up when dropped.
+struct Slot {
+    slots: Arc<Slots>,
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.slots.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+pub struct LlmLimiter {
+    limits: ConcurrencyLimits,
+    overall: Arc<Slots>,
+    providers: Mutex<BTreeMap<String, Arc<Slots>>>,
+}
+
+impl LlmLimiter {
+    pub fn new(limits: ConcurrencyLimits) -> Self {
+        Self {
+            overall: Arc::new(Slots::new(limits.overall)),
+            limits,
+            providers: Mutex::default(),
+        }
+    }
+
+    pub fn limits(&self) -> &ConcurrencyLimits {
+        &self.limits
+    }
+
+    /// Runs `request` once `provider` and the overall limit both have room.
+    /// The provider's slot is taken first, so requests queued behind a busy
+    /// provider do not hold overall slots other providers could use.
+    pub async fn run<T>(&self, provider: &str, request: impl std::future::Future<Output = T>) -> T {
+        let provider_slot = self.provider_slots(provider).acquire().await;
+        let overall_slot = self.overall.acquire().await;
+        let output = request.await;
+        drop(overall_slot);
+        drop(provider_slot);
+        output
+    }
+
+    pub fn snapshot(&self) -> ConcurrencySnapshot {
+        let providers = self.providers.lock().unwrap();
+        ConcurrencySnapshot {
+            overall: self.overall.usage(),
+            providers: providers
+                .iter()
+                .map(|(name, slots)| (name.clone(), slots.usage()))
+                .collect(),
+        }
+    }
+
+    /// Whether a request to `provider` would start at once rather than
+    /// queue behind its limit.
+    pub fn has_headroom(&self, provider: &str) -> bool {
+        let providers = self.providers.lock().unwrap();
+        providers.get(provider).map_or(true, |slots| {
+            let usage = slots.usage();
+            usage.waiting == 0 && usage.limit.map_or(true, |limit| usage.in_flight < limit)
+        })
+    }
+
+    fn provider_slots(&self, provider: &str) -> Arc<Slots> {
+        let mut providers = self.providers.lock().unwrap();
+        let slots = providers
+            .entry(provider.to_string())
+            .or_insert_with(|| Arc::new(Slots::new(self.limits.for_provider(provider))));
+        Arc::clone(slots)
+    }
+}
+
+impl std::fmt::Debug for LlmLimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LlmLimiter")
+            .field("limits", &self.limits)
+            .finish()
+    }
+}
+
+/// An [`LlmProvider`] whose requests wait for room under a shared
+/// [`LlmLimiter`].
+pub struct LimitedProvider {
+    inner: Arc<dyn LlmProvider>,
+    limiter: Arc<LlmLimiter>,
+}
+
+impl LimitedProvider {
+    pub fn new(inner: Arc<dyn LlmProvider>, limiter: Arc<LlmLimiter>) -> Self {
+        Self { inner, limiter }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for LimitedProvider {
+    async fn synthesize(
+        &self,
+        query: &str,
+        sources: &[Source],
+    ) -> Result<ResearchAnswer, LlmError> {
+        self.limiter
+            .run(
+                self.inner.provider_name(),
+                self.inner.synthesize(query, sources),
+            )
+            .await
+    }
+
+    async fn synthesize_captured(
+        &self,
+        query: &str,
+        sources: &[Source],
+        length: Option<&LengthPolicy>,
+        schema: Option<&AnswerSchema>,
+        style: AnswerStyle,
+        temperature: Option<f32>,
+    ) -> (Result<ResearchAnswer, LlmError>, LlmExchange) {
+        self.limiter
+            .run(
+                self.inner.provider_name(),
+                self.inner
+                    .synthesize_captured(query, sources, length, schema, style, temperature),
+            )
+            .await
+    }
+
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, LlmError> {
+        self.limiter
+            .run(self.inner.provider_name(), self.inner.chat(request))
+            .await
+    }
+
+    fn parse_synthesis(&self, raw: &str, sources: &[Source]) -> Result<ResearchAnswer, LlmError> {
+        self.inner.parse_synthesis(raw, sources)
+    }
+
+    fn model_id(&self) -> &str {
+        self.inner.model_id()
+    }
+
+    fn provider_name(&self) -> &str {
+        self.inner.provider_name()
+    }
+
+    fn max_context_tokens(&self) -> usize {
+        self.inner.max_context_tokens()
+    }
+
+    fn count_tokens(&self, text: &str) -> usize {
+        self.inner.count_tokens(text)
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn supports_vision(&self) -> bool {
+        self.inner.supports_vision()
+    }
+
+    fn pricing(&self) -> Option<ModelPricing> {
+        self.inner.pricing()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    use gorkd_core::MockLlmProvider;
+
+    #[test]
+    fn provider_limits_override_the_shared_one() {
+        let limits = ConcurrencyLimits::default()
+            .with_per_provider(8)
+            .with_provider("anthropic", 2);
+
+        assert_eq!(limits.for_provider("anthropic"), Some(2));
+        assert_eq!(limits.for_provider("openai"), Some(8));
+        assert_eq!(ConcurrencyLimits::default().for_provider("openai"), None);
+    }
+
+    #[tokio::test]
+    async fn queues_requests_beyond_the_provider_limit() {
+        let limiter = Arc::new(LlmLimiter::new(
+            ConcurrencyLimits::default().with_provider("mock", 1),
+        ));
+        let slow = || MockLlmProvider::new("mock-gpt-4").with_latency(Duration::from_millis(50));
+        let first = LimitedProvider::new(Arc::new(slow()), Arc::clone(&limiter));
+        let second = LimitedProvider::new(Arc::new(slow()), Arc::clone(&limiter));
+
+        let calls = async { tokio::join!(first.synthesize("a", &[]), second.synthesize("b", &[])) };
+        let watch = async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            assert!(!limiter.has_headroom("mock"));
+            assert!(limiter.has_headroom("openai"));
+            limiter.snapshot()
+        };
+        let ((first, second), during) = tokio::join!(calls, watch);
+
+        assert!(first.is_ok() && second.is_ok());
+        assert_eq!(
+            during.providers,
+            vec![(
+                "mock".to_string(),
+                SlotUsage {
+                    in_flight: 1,
+                    waiting: 1,
+                    limit: Some(1)
+                }
+            )]
+        );
+        assert_eq!(during.overall.in_flight, 1);
+        assert_eq!(limiter.snapshot().providers[0].1.in_flight, 0);
+        assert!(limiter.has_headroom("mock"));
+    }
+
+    #[tokio::test]
+    async fn counts_requests_without_limits() {
+        let limiter = Arc::new(LlmLimiter::new(ConcurrencyLimits::default()));
+        let provider = LimitedProvider::new(
+            Arc::new(MockLlmProvider::new("mock-gpt-4")),
+            Arc::clone(&limiter),
+        );
+
+        provider.synthesize("What is Rust?", &[]).await.unwrap();
+
+        let snapshot = limiter.snapshot();
+        assert_eq!(snapshot.overall, SlotUsage::default());
+        assert_eq!(snapshot.providers.len(), 1);
+        assert_eq!(provider.model_id(), "mock-gpt-4");
+    }
+}
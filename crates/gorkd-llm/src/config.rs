@@ -1,34 +1,55 @@
 use std::env;
+use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Duration;
 
+use gorkd_core::sigv4;
+use gorkd_core::{
+    parse_weighted_keys, KeyPool, LengthPolicies, LengthPolicy, ModerationPolicy, QuestionType,
+    RoutingPolicy,
+};
 use secrecy::{ExposeSecret, SecretString};
 
+use crate::concurrency::ConcurrencyLimits;
+use crate::prompt::PromptHardening;
+
 pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
 pub const DEFAULT_MAX_RETRIES: u32 = 2;
 
+/// Source images attached to a multimodal synthesis prompt by default.
+pub const DEFAULT_MAX_IMAGES: usize = 4;
+
+/// Model ID a webhook endpoint is registered under when `LLM_WEBHOOK_MODEL`
+/// is unset.
+pub const DEFAULT_WEBHOOK_MODEL: &str = "webhook";
+
+/// Context window assumed for a webhook endpoint when
+/// `LLM_WEBHOOK_CONTEXT_TOKENS` is unset.
+pub const DEFAULT_WEBHOOK_CONTEXT_TOKENS: usize = 32_000;
+
 #[derive(Clone)]
 pub struct AnthropicConfig {
-    pub api_key: SecretString,
+    /// Keys used in weighted turns, from the comma-separated
+    /// `ANTHROPIC_API_KEY`; see [`parse_weighted_keys`]. Clones share the
+    /// pool, so every model of the provider counts against the same keys.
+    pub api_keys: Arc<KeyPool<SecretString>>,
     pub base_url: String,
 }
 
 impl AnthropicConfig {
     pub fn from_env() -> Option<Self> {
-        let api_key = env::var("ANTHROPIC_API_KEY").ok()?;
+        let api_keys = secret_keys(&env::var("ANTHROPIC_API_KEY").ok()?)?;
         let base_url = env::var("ANTHROPIC_BASE_URL")
             .unwrap_or_else(|_| "https://api.anthropic.com".to_string());
 
-        Some(Self {
-            api_key: SecretString::from(api_key),
-            base_url,
-        })
+        Some(Self { api_keys, base_url })
     }
 }
 
 impl std::fmt::Debug for AnthropicConfig {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("AnthropicConfig")
-            .field("api_key", &"[REDACTED]")
+            .field("api_keys", &redacted_keys(&self.api_keys))
             .field("base_url", &self.base_url)
             .finish()
     }
@@ -36,47 +57,354 @@ impl std::fmt::Debug for AnthropicConfig {
 
 #[derive(Clone)]
 pub struct OpenAiConfig {
-    pub api_key: SecretString,
+    /// Keys used in weighted turns, from the comma-separated
+    /// `OPENAI_API_KEY`; see [`parse_weighted_keys`]. Clones share the
+    /// pool, so every model of the provider counts against the same keys.
+    pub api_keys: Arc<KeyPool<SecretString>>,
     pub base_url: String,
 }
 
 impl OpenAiConfig {
     pub fn from_env() -> Option<Self> {
-        let api_key = env::var("OPENAI_API_KEY").ok()?;
+        let api_keys = secret_keys(&env::var("OPENAI_API_KEY").ok()?)?;
         let base_url =
             env::var("OPENAI_BASE_URL").unwrap_or_else(|_| "https://api.openai.com".to_string());
 
-        Some(Self {
-            api_key: SecretString::from(api_key),
-            base_url,
-        })
+        Some(Self { api_keys, base_url })
     }
 }
 
 impl std::fmt::Debug for OpenAiConfig {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("OpenAiConfig")
-            .field("api_key", &"[REDACTED]")
+            .field("api_keys", &redacted_keys(&self.api_keys))
             .field("base_url", &self.base_url)
             .finish()
     }
 }
 
+#[derive(Clone)]
+pub struct AwsCredentials {
+    pub access_key_id: String,
+    pub secret_access_key: SecretString,
+    pub session_token: Option<SecretString>,
+}
+
+impl AwsCredentials {
+    /// Reads `AWS_ACCESS_KEY_ID` / `AWS_SECRET_ACCESS_KEY` /
+    /// `AWS_SESSION_TOKEN` from the environment.
+    pub fn from_env() -> Option<Self> {
+        let access_key_id = env::var("AWS_ACCESS_KEY_ID").ok()?;
+        let secret_access_key = env::var("AWS_SECRET_ACCESS_KEY").ok()?;
+
+        Some(Self {
+            access_key_id,
+            secret_access_key: SecretString::from(secret_access_key),
+            session_token: env::var("AWS_SESSION_TOKEN").ok().map(SecretString::from),
+        })
+    }
+
+    /// These keys as the request signer takes them.
+    pub fn signing(&self) -> sigv4::Credentials<'_> {
+        sigv4::Credentials {
+            access_key_id: &self.access_key_id,
+            secret_access_key: self.secret_access_key.expose_secret(),
+            session_token: self.session_token.as_ref().map(|t| t.expose_secret()),
+        }
+    }
+
+    /// Loads a profile from the shared credentials file
+    /// (`AWS_SHARED_CREDENTIALS_FILE`, default `~/.aws/credentials`).
+    pub fn from_profile(profile: &str) -> Option<Self> {
+        let path = match env::var("AWS_SHARED_CREDENTIALS_FILE") {
+            Ok(path) => std::path::PathBuf::from(path),
+            Err(_) => {
+                let home = env::var("HOME").or_else(|_| env::var("USERPROFILE")).ok()?;
+                std::path::Path::new(&home).join(".aws").join("credentials")
+            }
+        };
+        let contents = std::fs::read_to_string(path).ok()?;
+        Self::parse_profile(&contents, profile)
+    }
+
+    fn parse_profile(contents: &str, profile: &str) -> Option<Self> {
+        let mut in_profile = false;
+        let mut access_key_id = None;
+        let mut secret_access_key = None;
+        let mut session_token = None;
+
+        for line in contents.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                in_profile = section.trim() == profile;
+                continue;
+            }
+            if !in_profile {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once('=') {
+                let value = value.trim().to_string();
+                match key.trim() {
+                    "aws_access_key_id" => access_key_id = Some(value),
+                    "aws_secret_access_key" => secret_access_key = Some(value),
+                    "aws_session_token" => session_token = Some(value),
+                    _ => {}
+                }
+            }
+        }
+
+        Some(Self {
+            access_key_id: access_key_id?,
+            secret_access_key: SecretString::from(secret_access_key?),
+            session_token: session_token.map(SecretString::from),
+        })
+    }
+}
+
+impl std::fmt::Debug for AwsCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AwsCredentials")
+            .field("access_key_id", &self.access_key_id)
+            .field("secret_access_key", &"[REDACTED]")
+            .field(
+                "session_token",
+                &self.session_token.as_ref().map(|_| "[REDACTED]"),
+            )
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BedrockConfig {
+    pub region: String,
+    pub credentials: AwsCredentials,
+    pub base_url: String,
+}
+
+impl BedrockConfig {
+    /// Enabled when `BEDROCK_REGION` is set. Credentials come from the
+    /// environment first, then from the `BEDROCK_PROFILE` / `AWS_PROFILE`
+    /// profile of the shared credentials file.
+    pub fn from_env() -> Option<Self> {
+        let region = env::var("BEDROCK_REGION").ok()?;
+        let credentials = AwsCredentials::from_env().or_else(|| {
+            let profile = env::var("BEDROCK_PROFILE")
+                .or_else(|_| env::var("AWS_PROFILE"))
+                .unwrap_or_else(|_| "default".to_string());
+            AwsCredentials::from_profile(&profile)
+        })?;
+        let base_url =
+            env::var("BEDROCK_ENDPOINT").unwrap_or_else(|_| Self::default_endpoint(&region));
+
+        Some(Self {
+            region,
+            credentials,
+            base_url,
+        })
+    }
+
+    pub fn default_endpoint(region: &str) -> String {
+        format!("https://bedrock-runtime.{}.amazonaws.com", region)
+    }
+}
+
+/// Request and response format a webhook endpoint speaks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WebhookSchema {
+    /// The contract documented in [`crate::webhook`].
+    #[default]
+    Gorkd,
+    /// The OpenAI chat completions API, as served by vLLM, TGI and most
+    /// inference gateways.
+    OpenAi,
+}
+
+impl WebhookSchema {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Gorkd => "gorkd",
+            Self::OpenAi => "openai",
+        }
+    }
+}
+
+impl FromStr for WebhookSchema {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "gorkd" => Ok(Self::Gorkd),
+            "openai" => Ok(Self::OpenAi),
+            other => Err(format!(
+                "unknown webhook schema {:?}, expected gorkd or openai",
+                other
+            )),
+        }
+    }
+}
+
+/// An in-house model served over HTTP, registered as a model of the
+/// `webhook` provider.
+#[derive(Clone)]
+pub struct WebhookLlmConfig {
+    /// Endpoint every request is POSTed to. For the OpenAI schema this is
+    /// the full chat completions URL.
+    pub url: String,
+    /// Model ID the endpoint is registered under and sends as `model`.
+    pub model: String,
+    /// Header carrying `api_key`, such as `Authorization` or `X-Api-Key`.
+    pub auth_header: String,
+    pub api_key: Option<SecretString>,
+    pub schema: WebhookSchema,
+    pub max_context_tokens: usize,
+}
+
+impl WebhookLlmConfig {
+    pub fn new(url: impl Into<String>, model: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            model: model.into(),
+            auth_header: "Authorization".to_string(),
+            api_key: None,
+            schema: WebhookSchema::default(),
+            max_context_tokens: DEFAULT_WEBHOOK_CONTEXT_TOKENS,
+        }
+    }
+
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(SecretString::from(api_key.into()));
+        self
+    }
+
+    pub fn with_auth_header(mut self, header: impl Into<String>) -> Self {
+        self.auth_header = header.into();
+        self
+    }
+
+    pub fn with_schema(mut self, schema: WebhookSchema) -> Self {
+        self.schema = schema;
+        self
+    }
+
+    /// Enabled when `LLM_WEBHOOK_URL` is set. `LLM_WEBHOOK_API_KEY` is sent
+    /// in `LLM_WEBHOOK_AUTH_HEADER` (default `Authorization`, as a bearer
+    /// token); `LLM_WEBHOOK_MODEL`, `LLM_WEBHOOK_SCHEMA` and
+    /// `LLM_WEBHOOK_CONTEXT_TOKENS` default to [`DEFAULT_WEBHOOK_MODEL`],
+    /// [`WebhookSchema::Gorkd`] and [`DEFAULT_WEBHOOK_CONTEXT_TOKENS`].
+    pub fn from_env() -> Option<Self> {
+        let url = env::var("LLM_WEBHOOK_URL")
+            .ok()
+            .filter(|s| !s.trim().is_empty())?;
+        let model = env::var("LLM_WEBHOOK_MODEL")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_WEBHOOK_MODEL.to_string());
+
+        let mut config = Self::new(url, model);
+        if let Ok(header) = env::var("LLM_WEBHOOK_AUTH_HEADER") {
+            config.auth_header = header;
+        }
+        config.api_key = env::var("LLM_WEBHOOK_API_KEY").ok().map(SecretString::from);
+        config.schema = env::var("LLM_WEBHOOK_SCHEMA")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_default();
+        config.max_context_tokens = env::var("LLM_WEBHOOK_CONTEXT_TOKENS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_WEBHOOK_CONTEXT_TOKENS);
+        Some(config)
+    }
+
+    /// Value of the auth header: a bearer token for `Authorization`, the
+    /// key itself for any other header.
+    pub(crate) fn auth_value(&self) -> Option<String> {
+        let api_key = self.api_key.as_ref()?.expose_secret();
+        if self.auth_header.eq_ignore_ascii_case("authorization") {
+            Some(format!("Bearer {}", api_key))
+        } else {
+            Some(api_key.to_string())
+        }
+    }
+}
+
+impl std::fmt::Debug for WebhookLlmConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebhookLlmConfig")
+            .field("url", &self.url)
+            .field("model", &self.model)
+            .field("auth_header", &self.auth_header)
+            .field("api_key", &self.api_key.as_ref().map(|_| "[REDACTED]"))
+            .field("schema", &self.schema)
+            .field("max_context_tokens", &self.max_context_tokens)
+            .finish()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LlmConfig {
     pub default_model: String,
-    pub fallback_model: Option<String>,
+    /// Models tried in turn when the default fails with a retryable error,
+    /// from the comma-separated `LLM_FALLBACK_MODEL`.
+    pub fallback_models: Vec<String>,
+    /// Model answering the questions routing finds simple, from
+    /// `LLM_FAST_MODEL`. Defaults to the cheaper model of the default
+    /// model's provider.
+    pub fast_model: Option<String>,
+    /// Candidate model evaluated in shadow of the default model, from
+    /// `LLM_SHADOW_MODEL`. It is given every request the default model gets,
+    /// and its replies are only scored.
+    pub shadow_model: Option<String>,
+    /// When to answer with the fast model, from `LLM_ROUTING` and its
+    /// thresholds. `None` answers every question with the default model.
+    pub routing: Option<RoutingPolicy>,
     pub timeout: Duration,
     pub max_retries: u32,
+    pub prompt_hardening: PromptHardening,
+    pub moderation: ModerationPolicy,
+    pub length_policies: LengthPolicies,
+    /// Whether exhaustive answers are outlined and written section by
+    /// section, from `LLM_OUTLINE_REPORTS`.
+    pub outline_reports: bool,
+    /// Whether completed jobs add the facts their cited sources state to
+    /// the knowledge base, from `LLM_KNOWLEDGE_GRAPH`.
+    pub knowledge_graph: bool,
+    /// Model embedding corpus documents and queries, from
+    /// `LLM_EMBEDDING_MODEL`. Used with OpenAI; defaults to
+    /// `text-embedding-3-small`.
+    pub embedding_model: Option<String>,
+    /// Source images shown to vision-capable models during synthesis, from
+    /// `LLM_MULTIMODAL` and `LLM_MAX_IMAGES`. Zero sends text only.
+    pub max_images: usize,
+    /// Requests allowed in flight at once, overall and per provider, from
+    /// `LLM_MAX_CONCURRENT`, `LLM_MAX_CONCURRENT_PER_PROVIDER` and
+    /// provider overrides such as `LLM_MAX_CONCURRENT_ANTHROPIC`.
+    pub concurrency: ConcurrencyLimits,
     pub anthropic: Option<AnthropicConfig>,
     pub openai: Option<OpenAiConfig>,
+    pub bedrock: Option<BedrockConfig>,
+    pub webhook: Option<WebhookLlmConfig>,
 }
 
 impl LlmConfig {
     pub fn from_env() -> Self {
         let default_model = env::var("LLM_DEFAULT_MODEL")
             .unwrap_or_else(|_| "claude-sonnet-4-20250514".to_string());
-        let fallback_model = env::var("LLM_FALLBACK_MODEL").ok();
+        let fallback_models = env::var("LLM_FALLBACK_MODEL")
+            .map(|list| {
+                list.split(',')
+                    .map(str::trim)
+                    .filter(|model| !model.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let fast_model = env::var("LLM_FAST_MODEL").ok();
+        let shadow_model = env::var("LLM_SHADOW_MODEL")
+            .ok()
+            .filter(|s| !s.trim().is_empty());
         let timeout_secs = env::var("LLM_TIMEOUT_SECS")
             .ok()
             .and_then(|s| s.parse().ok())
@@ -85,39 +413,212 @@ impl LlmConfig {
             .ok()
             .and_then(|s| s.parse().ok())
             .unwrap_or(DEFAULT_MAX_RETRIES);
+        let prompt_hardening = env::var("LLM_PROMPT_HARDENING")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_default();
+        let moderation = env::var("LLM_MODERATION_POLICY")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_default();
 
         Self {
             default_model,
-            fallback_model,
+            fallback_models,
+            fast_model,
+            shadow_model,
+            routing: routing_from_env(),
             timeout: Duration::from_secs(timeout_secs),
             max_retries,
+            prompt_hardening,
+            moderation,
+            length_policies: length_policies_from_env(),
+            outline_reports: env::var("LLM_OUTLINE_REPORTS")
+                .map(|s| !matches!(s.to_lowercase().as_str(), "off" | "false" | "0"))
+                .unwrap_or(true),
+            knowledge_graph: env::var("LLM_KNOWLEDGE_GRAPH")
+                .map(|s| matches!(s.to_lowercase().as_str(), "on" | "true" | "1" | "yes"))
+                .unwrap_or(false),
+            embedding_model: env::var("LLM_EMBEDDING_MODEL")
+                .ok()
+                .filter(|s| !s.trim().is_empty()),
+            max_images: max_images_from_env(),
+            concurrency: concurrency_from_env(),
             anthropic: AnthropicConfig::from_env(),
             openai: OpenAiConfig::from_env(),
+            bedrock: BedrockConfig::from_env(),
+            webhook: WebhookLlmConfig::from_env(),
         }
     }
 
     pub fn has_provider(&self) -> bool {
-        self.anthropic.is_some() || self.openai.is_some()
+        self.anthropic.is_some()
+            || self.openai.is_some()
+            || self.bedrock.is_some()
+            || self.webhook.is_some()
     }
 
     pub fn anthropic_api_key(&self) -> Option<&str> {
-        self.anthropic.as_ref().map(|c| c.api_key.expose_secret())
+        let config = self.anthropic.as_ref()?;
+        Some(config.api_keys.first().expose_secret())
     }
 
     pub fn openai_api_key(&self) -> Option<&str> {
-        self.openai.as_ref().map(|c| c.api_key.expose_secret())
+        let config = self.openai.as_ref()?;
+        Some(config.api_keys.first().expose_secret())
+    }
+}
+
+/// Reads `LLM_MULTIMODAL` (default off) and `LLM_MAX_IMAGES` (default
+/// [`DEFAULT_MAX_IMAGES`]).
+fn max_images_from_env() -> usize {
+    let enabled = env::var("LLM_MULTIMODAL")
+        .map(|s| matches!(s.to_lowercase().as_str(), "on" | "true" | "1" | "yes"))
+        .unwrap_or(false);
+    if !enabled {
+        return 0;
+    }
+
+    env::var("LLM_MAX_IMAGES")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_MAX_IMAGES)
+}
+
+/// Reads `LLM_ROUTING` (default off) and the thresholds
+/// `LLM_ROUTING_SIMPLE_TYPES` (comma-separated question types),
+/// `LLM_ROUTING_MAX_WORDS`, `LLM_ROUTING_MAX_ENTITIES` and
+/// `LLM_ROUTING_MAX_HOPS`. Unset or unparsable thresholds keep their
+/// defaults.
+fn routing_from_env() -> Option<RoutingPolicy> {
+    let enabled = env::var("LLM_ROUTING")
+        .map(|s| matches!(s.to_lowercase().as_str(), "on" | "true" | "1" | "yes"))
+        .unwrap_or(false);
+    if !enabled {
+        return None;
+    }
+
+    let threshold = |name: &str| env::var(name).ok().and_then(|s| s.trim().parse().ok());
+    let mut policy = RoutingPolicy::default();
+    if let Ok(types) = env::var("LLM_ROUTING_SIMPLE_TYPES") {
+        policy = policy.with_simple_types(parse_question_types(&types));
+    }
+    if let Some(max) = threshold("LLM_ROUTING_MAX_WORDS") {
+        policy = policy.with_max_words(max);
+    }
+    if let Some(max) = threshold("LLM_ROUTING_MAX_ENTITIES") {
+        policy = policy.with_max_entities(max);
+    }
+    if let Some(max) = threshold("LLM_ROUTING_MAX_HOPS") {
+        policy = policy.with_max_hops(max);
     }
+    Some(policy)
+}
+
+/// A pool of the keys in `list`, as secrets; `None` when there are none.
+pub(crate) fn secret_keys(list: &str) -> Option<Arc<KeyPool<SecretString>>> {
+    let keys: Vec<_> = parse_weighted_keys(list)
+        .into_iter()
+        .map(|key| key.map(SecretString::from))
+        .collect();
+    KeyPool::new(keys).map(Arc::new)
+}
+
+/// The weight of each key, for `Debug` without the keys themselves.
+fn redacted_keys(keys: &KeyPool<SecretString>) -> Vec<String> {
+    keys.usage()
+        .iter()
+        .map(|usage| format!("[REDACTED]:{}", usage.weight))
+        .collect()
+}
+
+/// Question types named in `list`, such as `factual,how_to`. Unknown names
+/// are skipped.
+fn parse_question_types(list: &str) -> Vec<QuestionType> {
+    list.split(',')
+        .map(|name| name.trim().to_lowercase())
+        .filter_map(|name| {
+            QuestionType::ALL
+                .into_iter()
+                .find(|question_type| question_type.as_str() == name)
+        })
+        .collect()
+}
+
+/// Reads `LLM_MAX_CONCURRENT`, `LLM_MAX_CONCURRENT_PER_PROVIDER` and the
+/// overrides for each provider. Unset, zero or unparsable values leave
+/// requests unlimited.
+fn concurrency_from_env() -> ConcurrencyLimits {
+    let limit = |name: &str| {
+        env::var(name)
+            .ok()
+            .and_then(|s| s.trim().parse::<usize>().ok())
+            .filter(|&max| max > 0)
+    };
+
+    let mut limits = ConcurrencyLimits {
+        overall: limit("LLM_MAX_CONCURRENT"),
+        per_provider: limit("LLM_MAX_CONCURRENT_PER_PROVIDER"),
+        ..Default::default()
+    };
+    for provider in ["anthropic", "openai", "bedrock", "webhook"] {
+        let var = format!("LLM_MAX_CONCURRENT_{}", provider.to_ascii_uppercase());
+        if let Some(max) = limit(&var) {
+            limits = limits.with_provider(provider, max);
+        }
+    }
+    limits
+}
+
+/// Reads `LLM_LENGTH_POLICIES` (`on` or `off`, default `on`) and per-type
+/// overrides such as `LLM_MAX_TOKENS_FACTUAL`.
+fn length_policies_from_env() -> LengthPolicies {
+    let enabled = env::var("LLM_LENGTH_POLICIES")
+        .map(|s| !matches!(s.to_lowercase().as_str(), "off" | "false" | "0"))
+        .unwrap_or(true);
+    if !enabled {
+        return LengthPolicies::disabled();
+    }
+
+    QuestionType::ALL
+        .into_iter()
+        .fold(LengthPolicies::default(), |policies, question_type| {
+            let var = format!(
+                "LLM_MAX_TOKENS_{}",
+                question_type.as_str().to_ascii_uppercase()
+            );
+            match env::var(var).ok().and_then(|s| s.parse().ok()) {
+                Some(max_tokens) => policies.with_policy(
+                    question_type,
+                    LengthPolicy::default_for(question_type).with_max_tokens(max_tokens),
+                ),
+                None => policies,
+            }
+        })
 }
 
 impl Default for LlmConfig {
     fn default() -> Self {
         Self {
             default_model: "claude-sonnet-4-20250514".to_string(),
-            fallback_model: None,
+            fallback_models: Vec::new(),
+            fast_model: None,
+            shadow_model: None,
+            routing: None,
             timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
             max_retries: DEFAULT_MAX_RETRIES,
+            prompt_hardening: PromptHardening::default(),
+            moderation: ModerationPolicy::default(),
+            length_policies: LengthPolicies::default(),
+            outline_reports: true,
+            knowledge_graph: false,
+            embedding_model: None,
+            max_images: 0,
+            concurrency: ConcurrencyLimits::default(),
             anthropic: None,
             openai: None,
+            bedrock: None,
+            webhook: None,
         }
     }
 }
@@ -135,15 +636,79 @@ mod tests {
         assert!(!config.has_provider());
     }
 
+    #[test]
+    fn parses_question_type_lists() {
+        assert_eq!(
+            parse_question_types("factual, HOW_TO,poetry"),
+            vec![QuestionType::Factual, QuestionType::HowTo]
+        );
+        assert!(parse_question_types("").is_empty());
+    }
+
     #[test]
     fn config_debug_redacts_api_keys() {
         let anthropic = AnthropicConfig {
-            api_key: SecretString::from("sk-secret-key"),
+            api_keys: secret_keys("sk-secret-key:3,sk-other-key").unwrap(),
             base_url: "https://api.anthropic.com".to_string(),
         };
 
         let debug_str = format!("{:?}", anthropic);
         assert!(!debug_str.contains("sk-secret-key"));
-        assert!(debug_str.contains("[REDACTED]"));
+        assert!(!debug_str.contains("sk-other-key"));
+        assert!(debug_str.contains("[REDACTED]:3"));
+        assert!(secret_keys(" , ").is_none());
+    }
+
+    #[test]
+    fn parses_credentials_profile() {
+        let contents = "\
+[default]
+aws_access_key_id = AKIDDEFAULT
+aws_secret_access_key = default-secret
+
+# work account
+[work]
+aws_access_key_id=AKIDWORK
+aws_secret_access_key=work-secret
+aws_session_token=work-token
+";
+
+        let work = AwsCredentials::parse_profile(contents, "work").unwrap();
+        assert_eq!(work.access_key_id, "AKIDWORK");
+        assert_eq!(work.secret_access_key.expose_secret(), "work-secret");
+        assert!(work.session_token.is_some());
+
+        let default = AwsCredentials::parse_profile(contents, "default").unwrap();
+        assert_eq!(default.access_key_id, "AKIDDEFAULT");
+        assert!(default.session_token.is_none());
+
+        assert!(AwsCredentials::parse_profile(contents, "missing").is_none());
+    }
+
+    #[test]
+    fn webhook_sends_key_in_configured_header() {
+        let bearer = WebhookLlmConfig::new("https://llm.corp.example/generate", "corp-70b")
+            .with_api_key("secret-key");
+        assert_eq!(bearer.auth_value().as_deref(), Some("Bearer secret-key"));
+
+        let custom = bearer.clone().with_auth_header("X-Api-Key");
+        assert_eq!(custom.auth_value().as_deref(), Some("secret-key"));
+        assert!(!format!("{:?}", custom).contains("secret-key"));
+
+        assert_eq!("OpenAI".parse(), Ok(WebhookSchema::OpenAi));
+        assert!("anthropic".parse::<WebhookSchema>().is_err());
+    }
+
+    #[test]
+    fn aws_credentials_debug_redacts_secrets() {
+        let credentials = AwsCredentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: SecretString::from("very-secret"),
+            session_token: Some(SecretString::from("token-secret")),
+        };
+
+        let debug_str = format!("{:?}", credentials);
+        assert!(!debug_str.contains("very-secret"));
+        assert!(!debug_str.contains("token-secret"));
     }
 }
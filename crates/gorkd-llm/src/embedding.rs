@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use gorkd_core::{Embedder, LlmError};
+use reqwest::Client;
+use tracing::info;
+
+use crate::config::LlmConfig;
+use crate::openai::OpenAiEmbedder;
+
+/// Dimension of [`HashingEmbedder`] vectors.
+pub const HASHING_DIMENSIONS: usize = 512;
+
+/// Local, dependency-free embedder hashing each lowercased word into a
+/// fixed-size vector, normalized to unit length.
+///
+/// Matches documents sharing words with the query rather than meaning;
+/// intended for deployments without an OpenAI key, so the corpus works
+/// without sending documents out of the process.
+pub struct HashingEmbedder {
+    dimensions: usize,
+}
+
+impl HashingEmbedder {
+    pub fn new() -> Self {
+        Self {
+            dimensions: HASHING_DIMENSIONS,
+        }
+    }
+
+    fn embed_text(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0; self.dimensions];
+        for word in text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+        {
+            // FNV-1a, stable across builds unlike the std hasher.
+            let hash = word
+                .to_lowercase()
+                .bytes()
+                .fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+                    (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+                });
+            vector[(hash % self.dimensions as u64) as usize] += 1.0;
+        }
+
+        let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|x| *x /= norm);
+        }
+        vector
+    }
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Embedder for HashingEmbedder {
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, LlmError> {
+        Ok(texts.iter().map(|text| self.embed_text(text)).collect())
+    }
+
+    fn model_id(&self) -> &str {
+        "hashing"
+    }
+}
+
+/// Builds the embedder for the document corpus. Prefers the OpenAI
+/// endpoint, with `LLM_EMBEDDING_MODEL` when set, and falls back to
+/// [`HashingEmbedder`].
+pub fn embedder_from_config(http: Client, config: &LlmConfig) -> Arc<dyn Embedder> {
+    let embedder: Arc<dyn Embedder> = match config.openai {
+        Some(ref openai_config) => {
+            let mut embedder = OpenAiEmbedder::new(http, openai_config);
+            if let Some(ref model) = config.embedding_model {
+                embedder = embedder.with_model(model);
+            }
+            Arc::new(embedder)
+        }
+        None => Arc::new(HashingEmbedder::new()),
+    };
+    info!(model = embedder.model_id(), "configured corpus embeddings");
+
+    embedder
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gorkd_core::cosine_similarity;
+
+    #[tokio::test]
+    async fn hashing_embedder_ranks_shared_words_higher() {
+        let embedder = HashingEmbedder::new();
+        let texts = vec![
+            "How many vacation days do employees get?".to_string(),
+            "Employees accrue two vacation days per month.".to_string(),
+            "The deploy pipeline runs on every merge.".to_string(),
+        ];
+
+        let vectors = embedder.embed(&texts).await.unwrap();
+
+        let related = cosine_similarity(&vectors[0], &vectors[1]).unwrap();
+        let unrelated = cosine_similarity(&vectors[0], &vectors[2]).unwrap();
+        assert!(related > unrelated);
+        let norm = vectors[1].iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn falls_back_to_hashing_without_openai() {
+        let embedder = embedder_from_config(Client::new(), &LlmConfig::default());
+
+        assert_eq!(embedder.model_id(), "hashing");
+    }
+
+    #[test]
+    fn uses_configured_openai_model() {
+        let config = LlmConfig {
+            openai: Some(crate::config::OpenAiConfig {
+                api_keys: crate::config::secret_keys("sk-test").unwrap(),
+                base_url: "https://api.openai.com".to_string(),
+            }),
+            embedding_model: Some("text-embedding-3-large".to_string()),
+            ..LlmConfig::default()
+        };
+
+        let embedder = embedder_from_config(Client::new(), &config);
+
+        assert_eq!(embedder.model_id(), "text-embedding-3-large");
+    }
+}
@@ -1,4 +1,5 @@
 use gorkd_core::LlmError;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
 use reqwest::StatusCode;
 use serde::Deserialize;
 
@@ -34,7 +35,9 @@ pub fn map_openai_error(status: StatusCode, body: &str) -> LlmError {
 
     match status {
         StatusCode::UNAUTHORIZED => LlmError::Provider("invalid API key".to_string()),
-        StatusCode::TOO_MANY_REQUESTS => LlmError::RateLimited,
+        StatusCode::TOO_MANY_REQUESTS => LlmError::RateLimited {
+            retry_after_secs: None,
+        },
         StatusCode::BAD_REQUEST => {
             if let Ok(resp) = parsed {
                 if resp.error.code.as_deref() == Some("context_length_exceeded") {
@@ -77,7 +80,9 @@ pub fn map_anthropic_error(status: StatusCode, body: &str) -> LlmError {
 
     match status {
         StatusCode::UNAUTHORIZED => LlmError::Provider("invalid API key".to_string()),
-        StatusCode::TOO_MANY_REQUESTS => LlmError::RateLimited,
+        StatusCode::TOO_MANY_REQUESTS => LlmError::RateLimited {
+            retry_after_secs: None,
+        },
         StatusCode::BAD_REQUEST => {
             if let Ok(resp) = parsed {
                 if resp.error.error_type == "invalid_request_error"
@@ -110,7 +115,9 @@ pub fn map_anthropic_error(status: StatusCode, body: &str) -> LlmError {
         | StatusCode::INTERNAL_SERVER_ERROR => {
             if let Ok(resp) = parsed {
                 if resp.error.error_type == "overloaded_error" {
-                    return LlmError::RateLimited;
+                    return LlmError::RateLimited {
+                        retry_after_secs: None,
+                    };
                 }
             }
             LlmError::Provider(format!("service unavailable: {}", status))
@@ -125,6 +132,67 @@ pub fn map_anthropic_error(status: StatusCode, body: &str) -> LlmError {
     }
 }
 
+/// Maps a Bedrock error. `error_type` is the `x-amzn-ErrorType` header, which
+/// may carry a `:`-separated suffix (e.g. `ThrottlingException:http://...`).
+pub fn map_bedrock_error(status: StatusCode, error_type: Option<&str>, body: &str) -> LlmError {
+    let message = serde_json::from_str::<crate::bedrock::types::BedrockErrorResponse>(body)
+        .map(|resp| resp.message)
+        .unwrap_or_else(|_| body.to_string());
+    let error_type = error_type
+        .and_then(|t| t.split(':').next())
+        .unwrap_or_default();
+
+    match (status, error_type) {
+        (_, "ThrottlingException" | "ServiceQuotaExceededException")
+        | (StatusCode::TOO_MANY_REQUESTS, _) => LlmError::RateLimited {
+            retry_after_secs: None,
+        },
+        (_, "UnrecognizedClientException" | "AccessDeniedException")
+        | (StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN, _) => {
+            LlmError::Provider(format!("AWS credentials rejected: {}", message))
+        }
+        (_, "ResourceNotFoundException") | (StatusCode::NOT_FOUND, _) => {
+            LlmError::ModelUnavailable { model: message }
+        }
+        (StatusCode::BAD_REQUEST, _) if message.to_lowercase().contains("too long") => {
+            LlmError::ContextLengthExceeded {
+                max_tokens: 0,
+                got_tokens: 0,
+            }
+        }
+        (_, "ModelTimeoutException") => LlmError::Timeout { timeout_secs: 0 },
+        (
+            StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::GATEWAY_TIMEOUT
+            | StatusCode::INTERNAL_SERVER_ERROR,
+            _,
+        ) => LlmError::Provider(format!("service unavailable: {}", status)),
+        _ => LlmError::Provider(message),
+    }
+}
+
+/// Seconds a response's `Retry-After` header asks clients to wait, rounded
+/// up. HTTP dates are not parsed; providers send seconds.
+pub fn retry_after_secs(headers: &HeaderMap) -> Option<u64> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    let secs: f64 = value.parse().ok()?;
+    (secs.is_finite() && secs >= 0.0).then(|| secs.ceil() as u64)
+}
+
+/// `error` with the wait the response's `Retry-After` header asks for, when
+/// it is a rate limit.
+pub fn with_retry_after(error: LlmError, headers: &HeaderMap) -> LlmError {
+    match error {
+        LlmError::RateLimited {
+            retry_after_secs: None,
+        } => LlmError::RateLimited {
+            retry_after_secs: retry_after_secs(headers),
+        },
+        error => error,
+    }
+}
+
 pub fn map_reqwest_error(err: reqwest::Error) -> LlmError {
     if err.is_timeout() {
         LlmError::Timeout { timeout_secs: 0 }
@@ -135,14 +203,86 @@ pub fn map_reqwest_error(err: reqwest::Error) -> LlmError {
     }
 }
 
+/// Maps an error from an endpoint speaking the gorkd webhook contract,
+/// whose error bodies are `{"error": "<message>"}`.
+pub fn map_webhook_error(status: StatusCode, body: &str) -> LlmError {
+    let message = serde_json::from_str::<crate::webhook::types::WebhookErrorResponse>(body)
+        .map(|resp| resp.error)
+        .unwrap_or_else(|_| body.to_string());
+
+    match status {
+        StatusCode::TOO_MANY_REQUESTS => LlmError::RateLimited {
+            retry_after_secs: None,
+        },
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+            LlmError::Provider(format!("webhook credentials rejected: {}", message))
+        }
+        StatusCode::NOT_FOUND => LlmError::ModelUnavailable { model: message },
+        StatusCode::PAYLOAD_TOO_LARGE => LlmError::ContextLengthExceeded {
+            max_tokens: 0,
+            got_tokens: 0,
+        },
+        StatusCode::SERVICE_UNAVAILABLE
+        | StatusCode::BAD_GATEWAY
+        | StatusCode::GATEWAY_TIMEOUT
+        | StatusCode::INTERNAL_SERVER_ERROR => {
+            LlmError::Provider(format!("service unavailable: {}", status))
+        }
+        _ => LlmError::Provider(format!("HTTP {}: {}", status, message)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn reads_retry_after_into_rate_limits() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, "1.2".parse().unwrap());
+
+        let error = with_retry_after(
+            map_openai_error(StatusCode::TOO_MANY_REQUESTS, "{}"),
+            &headers,
+        );
+        assert!(matches!(
+            error,
+            LlmError::RateLimited {
+                retry_after_secs: Some(2)
+            }
+        ));
+
+        let error = with_retry_after(LlmError::Network("reset".into()), &headers);
+        assert!(matches!(error, LlmError::Network(_)));
+
+        headers.insert(
+            RETRY_AFTER,
+            "Wed, 21 Oct 2026 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(retry_after_secs(&headers), None);
+    }
+
+    #[test]
+    fn maps_webhook_errors() {
+        assert!(matches!(
+            map_webhook_error(StatusCode::TOO_MANY_REQUESTS, ""),
+            LlmError::RateLimited { .. }
+        ));
+        assert!(matches!(
+            map_webhook_error(StatusCode::PAYLOAD_TOO_LARGE, ""),
+            LlmError::ContextLengthExceeded { .. }
+        ));
+        let error = map_webhook_error(StatusCode::BAD_REQUEST, r#"{"error": "bad role"}"#);
+        assert_eq!(
+            error.to_string(),
+            "provider error: HTTP 400 Bad Request: bad role"
+        );
+    }
+
     #[test]
     fn maps_openai_rate_limit() {
         let error = map_openai_error(StatusCode::TOO_MANY_REQUESTS, "{}");
-        assert!(matches!(error, LlmError::RateLimited));
+        assert!(matches!(error, LlmError::RateLimited { .. }));
         assert!(error.is_retryable());
     }
 
@@ -162,7 +302,7 @@ mod tests {
     #[test]
     fn maps_anthropic_rate_limit() {
         let error = map_anthropic_error(StatusCode::TOO_MANY_REQUESTS, "{}");
-        assert!(matches!(error, LlmError::RateLimited));
+        assert!(matches!(error, LlmError::RateLimited { .. }));
         assert!(error.is_retryable());
     }
 
@@ -170,7 +310,7 @@ mod tests {
     fn maps_anthropic_overloaded() {
         let body = r#"{"type":"error","error":{"type":"overloaded_error","message":"overloaded"}}"#;
         let error = map_anthropic_error(StatusCode::SERVICE_UNAVAILABLE, body);
-        assert!(matches!(error, LlmError::RateLimited));
+        assert!(matches!(error, LlmError::RateLimited { .. }));
     }
 
     #[test]
@@ -178,4 +318,33 @@ mod tests {
         let error = map_anthropic_error(StatusCode::UNAUTHORIZED, "{}");
         assert!(matches!(error, LlmError::Provider(_)));
     }
+
+    #[test]
+    fn maps_bedrock_throttling() {
+        let body = r#"{"message":"Too many requests, please wait before trying again."}"#;
+        let error = map_bedrock_error(
+            StatusCode::BAD_REQUEST,
+            Some("ThrottlingException:http://internal.amazon.com/coral/com.amazon.bedrock/"),
+            body,
+        );
+        assert!(matches!(error, LlmError::RateLimited { .. }));
+    }
+
+    #[test]
+    fn maps_bedrock_access_denied() {
+        let body = r#"{"message":"The security token included in the request is invalid."}"#;
+        let error = map_bedrock_error(
+            StatusCode::FORBIDDEN,
+            Some("UnrecognizedClientException"),
+            body,
+        );
+        assert!(matches!(error, LlmError::Provider(msg) if msg.contains("credentials")));
+    }
+
+    #[test]
+    fn maps_bedrock_context_length() {
+        let body = r#"{"message":"Input is too long for requested model."}"#;
+        let error = map_bedrock_error(StatusCode::BAD_REQUEST, Some("ValidationException"), body);
+        assert!(matches!(error, LlmError::ContextLengthExceeded { .. }));
+    }
 }
@@ -0,0 +1,185 @@
+//! Factories that build LLM providers from configuration.
+//!
+//! [`LlmRegistry::from_config`](crate::LlmRegistry::from_config) registers
+//! whatever the factories of an [`LlmProviderFactories`] list build, in
+//! order, rather than naming each provider itself. The built-in providers
+//! are factories behind cargo features of the same name; a program
+//! embedding gorkd adds its own with [`LlmProviderFactories::with`].
+
+use std::sync::Arc;
+
+use gorkd_core::LlmProvider;
+use reqwest::Client;
+
+use crate::config::LlmConfig;
+
+/// Builds the models of one provider that `config` sets up, with their
+/// model IDs, sending requests through the shared client. Returns none when
+/// the provider is not configured.
+pub type LlmProviderFactory =
+    Arc<dyn Fn(&Client, &LlmConfig) -> Vec<(String, Arc<dyn LlmProvider>)> + Send + Sync>;
+
+/// LLM provider factories, in the order their models are registered.
+#[derive(Clone, Default)]
+pub struct LlmProviderFactories {
+    factories: Vec<(String, LlmProviderFactory)>,
+}
+
+impl LlmProviderFactories {
+    /// No factories at all.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The factories of the providers compiled in: Anthropic, OpenAI,
+    /// Bedrock, then the webhook.
+    pub fn builtin() -> Self {
+        #[allow(unused_mut)]
+        let mut factories = Self::new();
+        #[cfg(feature = "anthropic")]
+        {
+            factories = factories.with("anthropic", anthropic);
+        }
+        #[cfg(feature = "openai")]
+        {
+            factories = factories.with("openai", openai);
+        }
+        #[cfg(feature = "bedrock")]
+        {
+            factories = factories.with("bedrock", bedrock);
+        }
+        #[cfg(feature = "webhook")]
+        {
+            factories = factories.with("webhook", webhook);
+        }
+        factories
+    }
+
+    /// Adds the factory of `provider` after the others, or puts it in place
+    /// of the factory already added for `provider`.
+    pub fn with(
+        mut self,
+        provider: impl Into<String>,
+        factory: impl Fn(&Client, &LlmConfig) -> Vec<(String, Arc<dyn LlmProvider>)>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        let provider = provider.into();
+        let factory: LlmProviderFactory = Arc::new(factory);
+        match self.factories.iter_mut().find(|(p, _)| *p == provider) {
+            Some(entry) => entry.1 = factory,
+            None => self.factories.push((provider, factory)),
+        }
+        self
+    }
+
+    /// Drops the factory of `provider`.
+    pub fn without(mut self, provider: &str) -> Self {
+        self.factories.retain(|(p, _)| p != provider);
+        self
+    }
+
+    /// The providers there are factories for, in order.
+    pub fn providers(&self) -> Vec<&str> {
+        self.factories.iter().map(|(p, _)| p.as_str()).collect()
+    }
+
+    /// Runs every factory in order, returning each model with its ID and
+    /// the provider whose factory built it.
+    pub fn build(
+        &self,
+        http: &Client,
+        config: &LlmConfig,
+    ) -> Vec<(String, &str, Arc<dyn LlmProvider>)> {
+        self.factories
+            .iter()
+            .flat_map(|(provider, factory)| {
+                factory(http, config)
+                    .into_iter()
+                    .map(move |(model, built)| (model, provider.as_str(), built))
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for LlmProviderFactories {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LlmProviderFactories")
+            .field("providers", &self.providers())
+            .finish()
+    }
+}
+
+#[cfg(feature = "anthropic")]
+fn anthropic(http: &Client, config: &LlmConfig) -> Vec<(String, Arc<dyn LlmProvider>)> {
+    use crate::anthropic::types::{MODEL_CLAUDE_HAIKU_35, MODEL_CLAUDE_SONNET_4};
+
+    let Some(ref anthropic_config) = config.anthropic else {
+        return Vec::new();
+    };
+    [MODEL_CLAUDE_SONNET_4, MODEL_CLAUDE_HAIKU_35]
+        .into_iter()
+        .map(|model| {
+            let provider = crate::AnthropicProvider::new(http.clone(), anthropic_config, model)
+                .with_prompt_hardening(config.prompt_hardening)
+                .with_max_images(config.max_images);
+            (
+                model.to_string(),
+                Arc::new(provider) as Arc<dyn LlmProvider>,
+            )
+        })
+        .collect()
+}
+
+#[cfg(feature = "openai")]
+fn openai(http: &Client, config: &LlmConfig) -> Vec<(String, Arc<dyn LlmProvider>)> {
+    use crate::openai::types::{MODEL_GPT_4O, MODEL_GPT_4O_MINI};
+
+    let Some(ref openai_config) = config.openai else {
+        return Vec::new();
+    };
+    [MODEL_GPT_4O, MODEL_GPT_4O_MINI]
+        .into_iter()
+        .map(|model| {
+            let provider = crate::OpenAiProvider::new(http.clone(), openai_config, model)
+                .with_prompt_hardening(config.prompt_hardening)
+                .with_max_images(config.max_images);
+            (
+                model.to_string(),
+                Arc::new(provider) as Arc<dyn LlmProvider>,
+            )
+        })
+        .collect()
+}
+
+#[cfg(feature = "bedrock")]
+fn bedrock(http: &Client, config: &LlmConfig) -> Vec<(String, Arc<dyn LlmProvider>)> {
+    use crate::bedrock::types::{MODEL_BEDROCK_CLAUDE_SONNET_4, MODEL_BEDROCK_LLAMA_31_70B};
+
+    let Some(ref bedrock_config) = config.bedrock else {
+        return Vec::new();
+    };
+    tracing::debug!(region = %bedrock_config.region, "configured Bedrock");
+    [MODEL_BEDROCK_CLAUDE_SONNET_4, MODEL_BEDROCK_LLAMA_31_70B]
+        .into_iter()
+        .map(|model| {
+            let provider = crate::BedrockProvider::new(http.clone(), bedrock_config, model)
+                .with_prompt_hardening(config.prompt_hardening);
+            (
+                model.to_string(),
+                Arc::new(provider) as Arc<dyn LlmProvider>,
+            )
+        })
+        .collect()
+}
+
+#[cfg(feature = "webhook")]
+fn webhook(http: &Client, config: &LlmConfig) -> Vec<(String, Arc<dyn LlmProvider>)> {
+    let Some(ref webhook_config) = config.webhook else {
+        return Vec::new();
+    };
+    let provider = crate::WebhookLlmProvider::new(http.clone(), webhook_config)
+        .with_prompt_hardening(config.prompt_hardening);
+    vec![(webhook_config.model.clone(), Arc::new(provider))]
+}
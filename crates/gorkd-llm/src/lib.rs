@@ -1,24 +1,60 @@
 #![forbid(unsafe_code)]
 
 pub mod anthropic;
+pub mod bedrock;
 pub mod client;
+pub mod concurrency;
 pub mod config;
+pub mod embedding;
 pub mod error;
+pub mod factory;
+pub mod moderation;
 pub mod openai;
+pub mod parser;
 pub mod prompt;
+pub mod rate_limit;
 pub mod registry;
+pub mod sanitize;
+pub mod shadow;
+pub mod tokenizer;
 pub mod types;
+pub mod webhook;
 
 pub use anthropic::AnthropicProvider;
-pub use client::{build_http_client, build_http_client_with_timeout, default_http_client};
+pub use bedrock::BedrockProvider;
+pub use client::{
+    build_http_client, build_http_client_with_options, build_http_client_with_timeout,
+    default_http_client,
+};
+pub use concurrency::{
+    ConcurrencyLimits, ConcurrencySnapshot, LimitedProvider, LlmLimiter, SlotUsage,
+};
 pub use config::{
-    AnthropicConfig, LlmConfig, OpenAiConfig, DEFAULT_MAX_RETRIES, DEFAULT_TIMEOUT_SECS,
+    AnthropicConfig, AwsCredentials, BedrockConfig, LlmConfig, OpenAiConfig, WebhookLlmConfig,
+    WebhookSchema, DEFAULT_MAX_IMAGES, DEFAULT_MAX_RETRIES, DEFAULT_TIMEOUT_SECS,
+    DEFAULT_WEBHOOK_CONTEXT_TOKENS, DEFAULT_WEBHOOK_MODEL,
+};
+pub use embedding::{embedder_from_config, HashingEmbedder, HASHING_DIMENSIONS};
+pub use error::{
+    map_anthropic_error, map_bedrock_error, map_openai_error, map_reqwest_error, map_webhook_error,
+    retry_after_secs, with_retry_after,
 };
-pub use error::{map_anthropic_error, map_openai_error, map_reqwest_error};
-pub use openai::OpenAiProvider;
+pub use factory::{LlmProviderFactories, LlmProviderFactory};
+pub use moderation::{moderator_from_config, KeywordModerator};
+pub use openai::{OpenAiEmbedder, OpenAiModerator, OpenAiProvider};
+pub use parser::{extract_json, parse_synthesis_response, ParseError};
 pub use prompt::{
-    build_synthesis_messages, estimate_messages_tokens, estimate_token_count,
+    append_answer_schema, append_answer_style, attach_source_images, build_synthesis_messages,
+    build_synthesis_messages_for, build_synthesis_messages_with, estimate_messages_tokens,
+    estimate_token_count, PromptHardening, HARDENED_SYNTHESIS_SYSTEM_PROMPT,
     SYNTHESIS_SYSTEM_PROMPT,
 };
-pub use registry::{LlmRegistry, LlmRegistryBuilder};
+pub use rate_limit::{RateLimitTracker, RATE_LIMIT_WINDOW};
+pub use registry::{LlmRegistry, LlmRegistryBuilder, ModelCapabilities};
+pub use sanitize::sanitize_source_content;
+pub use shadow::ShadowLlmProvider;
+#[cfg(feature = "tiktoken")]
+pub use tokenizer::TiktokenTokenizer;
+pub use tokenizer::{tokenizer_for, ClaudeTokenizer, HeuristicTokenizer, Tokenizer};
 pub use types::{ChatRequest, ChatResponse, FinishReason, Message, Role, TokenUsage};
+pub use webhook::WebhookLlmProvider;
@@ -0,0 +1,178 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use gorkd_core::{LlmError, ModerationVerdict, Moderator};
+use reqwest::Client;
+use tracing::info;
+
+use crate::config::LlmConfig;
+use crate::openai::OpenAiModerator;
+
+/// Lowercased phrases per category used by [`KeywordModerator`].
+const DEFAULT_RULES: &[(&str, &[&str])] = &[
+    (
+        "violence",
+        &["how to make a bomb", "build an explosive", "kill them all"],
+    ),
+    (
+        "self-harm",
+        &[
+            "how to kill yourself",
+            "ways to commit suicide",
+            "cut yourself",
+        ],
+    ),
+    (
+        "illicit",
+        &["synthesize methamphetamine", "buy stolen credit cards"],
+    ),
+    ("hate", &["ethnic cleansing is justified", "racial purity"]),
+];
+
+/// Local, dependency-free classifier matching phrases per category.
+///
+/// Far coarser than a hosted model; intended for deployments without an
+/// OpenAI key or as a baseline that never leaves the process.
+pub struct KeywordModerator {
+    rules: Vec<(String, Vec<String>)>,
+}
+
+impl KeywordModerator {
+    pub fn new() -> Self {
+        Self {
+            rules: DEFAULT_RULES
+                .iter()
+                .map(|(category, phrases)| {
+                    (
+                        category.to_string(),
+                        phrases.iter().map(|p| p.to_string()).collect(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Adds (or extends) a category with extra phrases, matched case-insensitively.
+    pub fn with_rule(
+        mut self,
+        category: impl Into<String>,
+        phrases: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        let category = category.into();
+        let phrases = phrases.into_iter().map(|p| p.into().to_lowercase());
+        match self.rules.iter_mut().find(|(c, _)| *c == category) {
+            Some((_, existing)) => existing.extend(phrases),
+            None => self.rules.push((category, phrases.collect())),
+        }
+        self
+    }
+}
+
+impl Default for KeywordModerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Moderator for KeywordModerator {
+    async fn moderate(&self, text: &str) -> Result<ModerationVerdict, LlmError> {
+        let lower = text.to_lowercase();
+        let categories: Vec<&str> = self
+            .rules
+            .iter()
+            .filter(|(_, phrases)| phrases.iter().any(|p| lower.contains(p.as_str())))
+            .map(|(category, _)| category.as_str())
+            .collect();
+
+        if categories.is_empty() {
+            Ok(ModerationVerdict::clean(self.moderator_name()))
+        } else {
+            Ok(ModerationVerdict::flagged(
+                self.moderator_name(),
+                categories,
+            ))
+        }
+    }
+
+    fn moderator_name(&self) -> &str {
+        "keyword"
+    }
+}
+
+/// Builds the moderator for the configured policy, or `None` when moderation
+/// is off. Prefers the OpenAI endpoint and falls back to [`KeywordModerator`].
+pub fn moderator_from_config(http: Client, config: &LlmConfig) -> Option<Arc<dyn Moderator>> {
+    if !config.moderation.is_enabled() {
+        return None;
+    }
+
+    let moderator: Arc<dyn Moderator> = match config.openai {
+        Some(ref openai_config) => Arc::new(OpenAiModerator::new(http, openai_config)),
+        None => Arc::new(KeywordModerator::new()),
+    };
+    info!(
+        moderator = moderator.moderator_name(),
+        policy = ?config.moderation,
+        "configured answer moderation"
+    );
+
+    Some(moderator)
+}
+
+#[cfg(test)]
+mod tests {
+    use gorkd_core::ModerationPolicy;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn keyword_moderator_passes_clean_text() {
+        let verdict = KeywordModerator::new()
+            .moderate("Rust is a systems programming language.")
+            .await
+            .unwrap();
+
+        assert!(!verdict.flagged);
+        assert_eq!(verdict.moderator, "keyword");
+    }
+
+    #[tokio::test]
+    async fn keyword_moderator_flags_matching_categories() {
+        let verdict = KeywordModerator::new()
+            .moderate("Step one: How To Make A Bomb")
+            .await
+            .unwrap();
+
+        assert!(verdict.flagged);
+        assert_eq!(verdict.categories, vec!["violence".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn keyword_moderator_supports_custom_rules() {
+        let moderator = KeywordModerator::new().with_rule("spam", ["Buy Now"]);
+        let verdict = moderator
+            .moderate("buy now while stocks last")
+            .await
+            .unwrap();
+
+        assert_eq!(verdict.categories, vec!["spam".to_string()]);
+    }
+
+    #[test]
+    fn no_moderator_when_policy_off() {
+        let config = LlmConfig::default();
+        assert!(moderator_from_config(Client::new(), &config).is_none());
+    }
+
+    #[test]
+    fn falls_back_to_keyword_moderator_without_openai() {
+        let config = LlmConfig {
+            moderation: ModerationPolicy::Flag,
+            ..Default::default()
+        };
+
+        let moderator = moderator_from_config(Client::new(), &config).unwrap();
+        assert_eq!(moderator.moderator_name(), "keyword");
+    }
+}
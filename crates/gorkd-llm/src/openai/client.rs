@@ -1,16 +1,26 @@
-use gorkd_core::LlmError;
-use reqwest::Client;
+use std::sync::Arc;
+
+use gorkd_core::{current_trace_id, KeyPool, LlmError};
+use reqwest::{Client, RequestBuilder};
 use secrecy::{ExposeSecret, SecretString};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use tracing::instrument;
 
+use crate::client::{send_keyed, with_trace_header};
 use crate::config::OpenAiConfig;
 use crate::error::map_openai_error;
 
-use super::types::{ChatCompletionRequest, ChatCompletionResponse, ChatMessage};
+use super::types::{
+    ChatCompletionRequest, ChatCompletionResponse, ChatMessage, EmbeddingRequest,
+    EmbeddingResponse, ModerationRequest, ModerationResponse,
+};
+
+const CLIENT_REQUEST_ID_HEADER: &str = "X-Client-Request-Id";
 
 pub struct OpenAiClient {
     http: Client,
-    api_key: SecretString,
+    api_keys: Arc<KeyPool<SecretString>>,
     base_url: String,
 }
 
@@ -18,49 +28,95 @@ impl OpenAiClient {
     pub fn new(http: Client, config: &OpenAiConfig) -> Self {
         Self {
             http,
-            api_key: config.api_key.clone(),
+            api_keys: Arc::clone(&config.api_keys),
             base_url: config.base_url.clone(),
         }
     }
 
+    /// OpenAI logs `X-Client-Request-Id` alongside its own request ID, so
+    /// the trace ID is sent there too.
+    fn post(&self, url: &str) -> RequestBuilder {
+        let request = with_trace_header(self.http.post(url));
+        match current_trace_id() {
+            Some(trace_id) => request.header(CLIENT_REQUEST_ID_HEADER, trace_id.as_str()),
+            None => request,
+        }
+    }
+
     #[instrument(skip(self, messages), fields(model = %model))]
     pub async fn send_chat_completion(
         &self,
         model: &str,
         messages: Vec<ChatMessage>,
         max_tokens: usize,
+        temperature: Option<f32>,
         json_mode: bool,
     ) -> Result<ChatCompletionResponse, LlmError> {
         let mut request = ChatCompletionRequest::new(model, messages).with_max_tokens(max_tokens);
+        if let Some(temperature) = temperature {
+            request = request.with_temperature(temperature);
+        }
 
         if json_mode {
             request = request.with_json_mode();
         }
 
-        let url = format!("{}/v1/chat/completions", self.base_url);
-
-        let response = self
-            .http
-            .post(&url)
-            .header(
-                "Authorization",
-                format!("Bearer {}", self.api_key.expose_secret()),
-            )
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(crate::error::map_reqwest_error)?;
-
-        let status = response.status();
-        let body = response
-            .text()
-            .await
-            .map_err(crate::error::map_reqwest_error)?;
-
-        if !status.is_success() {
-            return Err(map_openai_error(status, &body));
-        }
+        self.send_request(&request).await
+    }
+
+    #[instrument(skip(self, request), fields(model = %request.model))]
+    pub async fn send_request(
+        &self,
+        request: &ChatCompletionRequest,
+    ) -> Result<ChatCompletionResponse, LlmError> {
+        self.send("/v1/chat/completions", request).await
+    }
+
+    #[instrument(skip(self, input), fields(model = %model))]
+    pub async fn send_moderation(
+        &self,
+        model: &str,
+        input: &str,
+    ) -> Result<ModerationResponse, LlmError> {
+        let request = ModerationRequest {
+            model: model.to_string(),
+            input: input.to_string(),
+        };
+
+        self.send("/v1/moderations", &request).await
+    }
+
+    #[instrument(skip(self, input), fields(model = %model, inputs = input.len()))]
+    pub async fn send_embeddings(
+        &self,
+        model: &str,
+        input: &[String],
+    ) -> Result<EmbeddingResponse, LlmError> {
+        let request = EmbeddingRequest { model, input };
+
+        self.send("/v1/embeddings", &request).await
+    }
+
+    /// Posts `request` to `path` with the key whose turn it is, moving on to
+    /// the next key when one is rate limited or rejected.
+    async fn send<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        request: &impl Serialize,
+    ) -> Result<T, LlmError> {
+        let url = format!("{}{}", self.base_url, path);
+
+        let body = self
+            .api_keys
+            .run(|key| {
+                let request = self
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {}", key.expose_secret()))
+                    .header("Content-Type", "application/json")
+                    .json(request);
+                send_keyed(request, map_openai_error)
+            })
+            .await?;
 
         serde_json::from_str(&body).map_err(|e| LlmError::Provider(format!("parse error: {}", e)))
     }
@@ -70,7 +126,7 @@ impl std::fmt::Debug for OpenAiClient {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("OpenAiClient")
             .field("base_url", &self.base_url)
-            .field("api_key", &"[REDACTED]")
+            .field("api_keys", &"[REDACTED]")
             .finish()
     }
 }
@@ -82,7 +138,7 @@ mod tests {
     #[test]
     fn debug_redacts_api_key() {
         let config = OpenAiConfig {
-            api_key: SecretString::from("sk-secret-key-12345"),
+            api_keys: Arc::new(KeyPool::single(SecretString::from("sk-secret-key-12345"))),
             base_url: "https://api.openai.com".to_string(),
         };
         let client = OpenAiClient::new(Client::new(), &config);
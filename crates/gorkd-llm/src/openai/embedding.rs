@@ -0,0 +1,55 @@
+use async_trait::async_trait;
+use gorkd_core::{Embedder, LlmError};
+use reqwest::Client;
+use tracing::instrument;
+
+use crate::config::OpenAiConfig;
+
+use super::client::OpenAiClient;
+use super::types::MODEL_TEXT_EMBEDDING_3_SMALL;
+
+/// Embeds text with the OpenAI embeddings endpoint.
+pub struct OpenAiEmbedder {
+    client: OpenAiClient,
+    model: String,
+}
+
+impl OpenAiEmbedder {
+    pub fn new(http: Client, config: &OpenAiConfig) -> Self {
+        Self {
+            client: OpenAiClient::new(http, config),
+            model: MODEL_TEXT_EMBEDDING_3_SMALL.to_string(),
+        }
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+}
+
+#[async_trait]
+impl Embedder for OpenAiEmbedder {
+    #[instrument(skip(self, texts), fields(model = %self.model, inputs = texts.len()))]
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, LlmError> {
+        let mut response = self.client.send_embeddings(&self.model, texts).await?;
+        if response.data.len() != texts.len() {
+            return Err(LlmError::Provider(format!(
+                "expected {} embeddings, got {}",
+                texts.len(),
+                response.data.len()
+            )));
+        }
+
+        response.data.sort_by_key(|embedding| embedding.index);
+        Ok(response
+            .data
+            .into_iter()
+            .map(|embedding| embedding.embedding)
+            .collect())
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model
+    }
+}
@@ -1,33 +1,55 @@
 mod client;
-mod parser;
+mod embedding;
+mod moderation;
 pub mod types;
 
+use std::sync::Arc;
 use std::time::Instant;
 
 use async_trait::async_trait;
-use gorkd_core::{LlmError, LlmProvider, ResearchAnswer, Source};
+use gorkd_core::{
+    AnswerSchema, AnswerStyle, LengthPolicy, LlmError, LlmExchange, LlmProvider, LlmStage,
+    ModelPricing, ResearchAnswer, Source, StageTokenUsage,
+};
 use reqwest::Client;
 use tracing::instrument;
 
 use crate::config::OpenAiConfig;
-use crate::prompt::build_synthesis_messages;
-
+use crate::parser::parse_synthesis_response;
+use crate::prompt::{
+    append_answer_schema, append_answer_style, attach_source_images, build_synthesis_messages_for,
+    PromptHardening,
+};
+use crate::tokenizer::{tokenizer_for, Tokenizer};
+use crate::types::{ChatRequest, ChatResponse, Role, TokenUsage};
+
+pub use crate::parser::ParseError;
 use client::OpenAiClient;
-pub use parser::ParseError;
-use types::{ChatMessage, FinishReason, CONTEXT_WINDOW_TOKENS, DEFAULT_MAX_TOKENS};
+pub use embedding::OpenAiEmbedder;
+pub use moderation::OpenAiModerator;
+use types::{
+    ChatCompletionRequest, ChatMessage, FinishReason, CONTEXT_WINDOW_TOKENS, DEFAULT_MAX_TOKENS,
+};
 
 pub struct OpenAiProvider {
     client: OpenAiClient,
     model: String,
+    tokenizer: Arc<dyn Tokenizer>,
     max_tokens: usize,
+    prompt_hardening: PromptHardening,
+    max_images: usize,
 }
 
 impl OpenAiProvider {
     pub fn new(http: Client, config: &OpenAiConfig, model: impl Into<String>) -> Self {
+        let model = model.into();
         Self {
             client: OpenAiClient::new(http, config),
-            model: model.into(),
+            tokenizer: tokenizer_for("openai", &model),
+            model,
             max_tokens: DEFAULT_MAX_TOKENS,
+            prompt_hardening: PromptHardening::default(),
+            max_images: 0,
         }
     }
 
@@ -35,48 +57,125 @@ impl OpenAiProvider {
         self.max_tokens = max_tokens;
         self
     }
+
+    pub fn with_prompt_hardening(mut self, hardening: PromptHardening) -> Self {
+        self.prompt_hardening = hardening;
+        self
+    }
+
+    /// Shows up to `max_images` source images to the model during synthesis,
+    /// if it supports vision. Zero sends text only.
+    pub fn with_max_images(mut self, max_images: usize) -> Self {
+        self.max_images = max_images;
+        self
+    }
 }
 
 #[async_trait]
 impl LlmProvider for OpenAiProvider {
-    #[instrument(skip(self, sources), fields(model = %self.model, source_count = sources.len()))]
     async fn synthesize(
         &self,
         query: &str,
         sources: &[Source],
     ) -> Result<ResearchAnswer, LlmError> {
-        let start = Instant::now();
+        self.synthesize_captured(query, sources, None, None, AnswerStyle::Neutral, None)
+            .await
+            .0
+    }
 
-        let messages = build_synthesis_messages(query, sources);
-        let openai_messages: Vec<ChatMessage> = messages
-            .iter()
-            .map(|m| match m.role {
-                crate::types::Role::System => ChatMessage::system(&m.content),
-                crate::types::Role::User => ChatMessage::user(&m.content),
-                crate::types::Role::Assistant => ChatMessage::assistant(&m.content),
-            })
-            .collect();
+    #[instrument(skip(self, sources, schema), fields(model = %self.model, source_count = sources.len()))]
+    async fn synthesize_captured(
+        &self,
+        query: &str,
+        sources: &[Source],
+        length: Option<&LengthPolicy>,
+        schema: Option<&AnswerSchema>,
+        style: AnswerStyle,
+        temperature: Option<f32>,
+    ) -> (Result<ResearchAnswer, LlmError>, LlmExchange) {
+        let start = Instant::now();
 
-        let response = self
+        let mut messages =
+            build_synthesis_messages_for(query, sources, self.prompt_hardening, length);
+        if self.max_images > 0 && self.supports_vision() {
+            messages = attach_source_images(messages, sources, self.max_images);
+        }
+        if let Some(schema) = schema {
+            messages = append_answer_schema(messages, schema);
+        }
+        messages = append_answer_style(messages, style);
+        let max_tokens = length.map_or(self.max_tokens, |policy| policy.cap(self.max_tokens));
+        let openai_messages: Vec<ChatMessage> = messages.iter().map(to_chat_message).collect();
+        let mut exchange = LlmExchange {
+            messages,
+            raw_response: None,
+        };
+
+        let response = match self
             .client
-            .send_chat_completion(&self.model, openai_messages, self.max_tokens, true)
-            .await?;
+            .send_chat_completion(&self.model, openai_messages, max_tokens, temperature, true)
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => return (Err(e), exchange),
+        };
 
         if response.finish_reason() == Some(&FinishReason::Length) {
             tracing::warn!("response truncated due to max_tokens limit");
         }
 
         let text = response.text_content();
-        let tokens_used = response.usage.total();
+        let usage = TokenUsage {
+            prompt_tokens: response.usage.prompt_tokens,
+            completion_tokens: response.usage.completion_tokens,
+        };
+
+        let result = parse_synthesis_response(&text, sources, &self.model, usage.total())
+            .map(|mut answer| {
+                answer.synthesis_metadata.synthesis_duration = start.elapsed();
+                answer.synthesis_metadata.record_usage(StageTokenUsage::new(
+                    LlmStage::Synthesis,
+                    &self.model,
+                    &usage,
+                ));
+                answer
+            })
+            .map_err(|e| LlmError::Provider(format!("failed to parse synthesis response: {}", e)));
+        exchange.raw_response = Some(text);
+
+        (result, exchange)
+    }
 
-        let mut answer = parser::parse_synthesis_response(&text, sources, &self.model, tokens_used)
-            .map_err(|e| {
-                LlmError::Provider(format!("failed to parse synthesis response: {}", e))
-            })?;
+    #[instrument(skip(self, request), fields(model = %self.model, message_count = request.messages.len()))]
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, LlmError> {
+        let messages = request.messages.iter().map(to_chat_message).collect();
+        let mut completion = ChatCompletionRequest::new(&self.model, messages)
+            .with_max_tokens(request.max_tokens.unwrap_or(self.max_tokens));
+        if let Some(temperature) = request.temperature {
+            completion = completion.with_temperature(temperature);
+        }
 
-        answer.synthesis_metadata.synthesis_duration = start.elapsed();
+        let response = self.client.send_request(&completion).await?;
+
+        Ok(ChatResponse {
+            content: response.text_content(),
+            finish_reason: match response.finish_reason() {
+                Some(FinishReason::Stop) => crate::types::FinishReason::Stop,
+                Some(FinishReason::Length) => crate::types::FinishReason::Length,
+                Some(FinishReason::ContentFilter) => crate::types::FinishReason::ContentFilter,
+                Some(FinishReason::Unknown) | None => crate::types::FinishReason::Unknown,
+            },
+            usage: TokenUsage {
+                prompt_tokens: response.usage.prompt_tokens,
+                completion_tokens: response.usage.completion_tokens,
+            },
+            model: response.model,
+        })
+    }
 
-        Ok(answer)
+    fn parse_synthesis(&self, raw: &str, sources: &[Source]) -> Result<ResearchAnswer, LlmError> {
+        parse_synthesis_response(raw, sources, &self.model, 0)
+            .map_err(|e| LlmError::Provider(format!("failed to parse synthesis response: {}", e)))
     }
 
     fn model_id(&self) -> &str {
@@ -91,9 +190,29 @@ impl LlmProvider for OpenAiProvider {
         CONTEXT_WINDOW_TOKENS
     }
 
+    fn count_tokens(&self, text: &str) -> usize {
+        self.tokenizer.count_tokens(text)
+    }
+
     fn supports_streaming(&self) -> bool {
         false
     }
+
+    fn supports_vision(&self) -> bool {
+        types::is_vision_model(&self.model)
+    }
+
+    fn pricing(&self) -> Option<ModelPricing> {
+        types::pricing_for(&self.model)
+    }
+}
+
+fn to_chat_message(message: &crate::types::Message) -> ChatMessage {
+    match message.role {
+        Role::System => ChatMessage::system(&message.content),
+        Role::User => ChatMessage::user_with_images(&message.content, &message.images),
+        Role::Assistant => ChatMessage::assistant(&message.content),
+    }
 }
 
 impl std::fmt::Debug for OpenAiProvider {
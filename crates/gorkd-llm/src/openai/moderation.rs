@@ -0,0 +1,55 @@
+use async_trait::async_trait;
+use gorkd_core::{LlmError, ModerationVerdict, Moderator};
+use reqwest::Client;
+use tracing::instrument;
+
+use crate::config::OpenAiConfig;
+
+use super::client::OpenAiClient;
+use super::types::MODEL_OMNI_MODERATION;
+
+/// Moderates answers with the OpenAI moderation endpoint.
+pub struct OpenAiModerator {
+    client: OpenAiClient,
+    model: String,
+}
+
+impl OpenAiModerator {
+    pub fn new(http: Client, config: &OpenAiConfig) -> Self {
+        Self {
+            client: OpenAiClient::new(http, config),
+            model: MODEL_OMNI_MODERATION.to_string(),
+        }
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+}
+
+#[async_trait]
+impl Moderator for OpenAiModerator {
+    #[instrument(skip(self, text), fields(model = %self.model))]
+    async fn moderate(&self, text: &str) -> Result<ModerationVerdict, LlmError> {
+        let response = self.client.send_moderation(&self.model, text).await?;
+
+        let result = response
+            .results
+            .first()
+            .ok_or_else(|| LlmError::Provider("moderation returned no results".to_string()))?;
+
+        if result.flagged {
+            Ok(ModerationVerdict::flagged(
+                self.moderator_name(),
+                result.flagged_categories(),
+            ))
+        } else {
+            Ok(ModerationVerdict::clean(self.moderator_name()))
+        }
+    }
+
+    fn moderator_name(&self) -> &str {
+        "openai"
+    }
+}
@@ -3,6 +3,9 @@
 //! These types map directly to the OpenAI Chat Completions API.
 //! See: https://platform.openai.com/docs/api-reference/chat
 
+use std::collections::BTreeMap;
+
+use gorkd_core::ModelPricing;
 use serde::{Deserialize, Serialize};
 
 /// GPT-4o model ID (primary fallback model).
@@ -11,12 +14,49 @@ pub const MODEL_GPT_4O: &str = "gpt-4o";
 /// GPT-4o-mini model ID (cheap/fast model for simple tasks).
 pub const MODEL_GPT_4O_MINI: &str = "gpt-4o-mini";
 
+/// Whether `model` accepts image inputs.
+pub fn is_vision_model(model: &str) -> bool {
+    [
+        "gpt-4o",
+        "gpt-4.1",
+        "gpt-4-turbo",
+        "gpt-5",
+        "o1",
+        "o3",
+        "o4",
+    ]
+    .iter()
+    .any(|prefix| model.starts_with(prefix))
+        && !model.starts_with("o1-mini")
+        && !model.starts_with("o3-mini")
+}
+
+/// List price of `model` in US dollars per million tokens. Mini and nano
+/// variants are matched before their full-size models.
+pub fn pricing_for(model: &str) -> Option<ModelPricing> {
+    let (input, output) = match model {
+        m if m.starts_with("gpt-4o-mini") => (0.15, 0.6),
+        m if m.starts_with("gpt-4o") => (2.5, 10.0),
+        m if m.starts_with("gpt-4.1-nano") => (0.1, 0.4),
+        m if m.starts_with("gpt-4.1-mini") => (0.4, 1.6),
+        m if m.starts_with("gpt-4.1") => (2.0, 8.0),
+        _ => return None,
+    };
+    Some(ModelPricing::new(input, output))
+}
+
 /// Context window size for GPT-4o models (128K tokens).
 pub const CONTEXT_WINDOW_TOKENS: usize = 128_000;
 
 /// Default max tokens for GPT responses.
 pub const DEFAULT_MAX_TOKENS: usize = 4096;
 
+/// Moderation model used for answer safety checks.
+pub const MODEL_OMNI_MODERATION: &str = "omni-moderation-latest";
+
+/// Embedding model used for the document corpus.
+pub const MODEL_TEXT_EMBEDDING_3_SMALL: &str = "text-embedding-3-small";
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum MessageRole {
@@ -25,31 +65,75 @@ pub enum MessageRole {
     Assistant,
 }
 
+/// Message content: plain text, or text and images as separate parts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MessageContent {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+impl PartialEq<&str> for MessageContent {
+    fn eq(&self, other: &&str) -> bool {
+        matches!(self, Self::Text(text) if text == other)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentPart {
+    Text { text: String },
+    ImageUrl { image_url: ImageUrl },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageUrl {
+    pub url: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
     pub role: MessageRole,
-    pub content: String,
+    pub content: MessageContent,
 }
 
 impl ChatMessage {
     pub fn system(content: impl Into<String>) -> Self {
         Self {
             role: MessageRole::System,
-            content: content.into(),
+            content: MessageContent::Text(content.into()),
         }
     }
 
     pub fn user(content: impl Into<String>) -> Self {
         Self {
             role: MessageRole::User,
-            content: content.into(),
+            content: MessageContent::Text(content.into()),
         }
     }
 
     pub fn assistant(content: impl Into<String>) -> Self {
         Self {
             role: MessageRole::Assistant,
-            content: content.into(),
+            content: MessageContent::Text(content.into()),
+        }
+    }
+
+    /// A user message showing `images` after the text.
+    pub fn user_with_images(content: impl Into<String>, images: &[String]) -> Self {
+        if images.is_empty() {
+            return Self::user(content);
+        }
+
+        let mut parts = vec![ContentPart::Text {
+            text: content.into(),
+        }];
+        parts.extend(images.iter().map(|url| ContentPart::ImageUrl {
+            image_url: ImageUrl { url: url.clone() },
+        }));
+        Self {
+            role: MessageRole::User,
+            content: MessageContent::Parts(parts),
         }
     }
 }
@@ -169,10 +253,64 @@ impl ChatCompletionResponse {
     }
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct ModerationRequest {
+    pub model: String,
+    pub input: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModerationResponse {
+    pub results: Vec<ModerationResult>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModerationResult {
+    pub flagged: bool,
+    #[serde(default)]
+    pub categories: BTreeMap<String, bool>,
+}
+
+impl ModerationResult {
+    pub fn flagged_categories(&self) -> Vec<String> {
+        self.categories
+            .iter()
+            .filter(|(_, &flagged)| flagged)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbeddingRequest<'a> {
+    pub model: &'a str,
+    pub input: &'a [String],
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmbeddingResponse {
+    pub data: Vec<Embedding>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Embedding {
+    pub index: usize,
+    pub embedding: Vec<f32>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn prices_mini_models_below_full_size() {
+        let mini = pricing_for(MODEL_GPT_4O_MINI).unwrap();
+        let full = pricing_for(MODEL_GPT_4O).unwrap();
+
+        assert!(mini.input_per_mtok < full.input_per_mtok);
+        assert_eq!(pricing_for(MODEL_OMNI_MODERATION), None);
+    }
+
     #[test]
     fn creates_system_message() {
         let msg = ChatMessage::system("You are helpful.");
@@ -213,6 +351,32 @@ mod tests {
         assert!(json.contains("0.7"));
     }
 
+    #[test]
+    fn serializes_images_as_content_parts() {
+        let message = ChatMessage::user_with_images(
+            "Describe the chart",
+            &["https://example.com/chart.png".to_string()],
+        );
+
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(
+            json["content"],
+            serde_json::json!([
+                {"type": "text", "text": "Describe the chart"},
+                {"type": "image_url", "image_url": {"url": "https://example.com/chart.png"}}
+            ])
+        );
+        assert_eq!(ChatMessage::user_with_images("Hi", &[]).content, "Hi");
+    }
+
+    #[test]
+    fn recognizes_vision_models() {
+        assert!(is_vision_model(MODEL_GPT_4O));
+        assert!(is_vision_model(MODEL_GPT_4O_MINI));
+        assert!(!is_vision_model("gpt-3.5-turbo"));
+        assert!(!is_vision_model("o1-mini"));
+    }
+
     #[test]
     fn serializes_request_with_json_mode() {
         let request = ChatCompletionRequest::new(MODEL_GPT_4O, vec![ChatMessage::user("Hello")])
@@ -346,4 +510,42 @@ mod tests {
         assert_eq!(MODEL_GPT_4O_MINI, "gpt-4o-mini");
         assert_eq!(CONTEXT_WINDOW_TOKENS, 128_000);
     }
+
+    #[test]
+    fn deserializes_moderation_response() {
+        let json = r#"{
+            "id": "modr-123",
+            "model": "omni-moderation-latest",
+            "results": [{
+                "flagged": true,
+                "categories": {"violence": true, "hate": false, "self-harm": true},
+                "category_scores": {"violence": 0.91, "hate": 0.01, "self-harm": 0.7}
+            }]
+        }"#;
+
+        let response: ModerationResponse = serde_json::from_str(json).unwrap();
+        let result = &response.results[0];
+
+        assert!(result.flagged);
+        assert_eq!(result.flagged_categories(), vec!["self-harm", "violence"]);
+    }
+
+    #[test]
+    fn deserializes_embedding_response() {
+        let json = r#"{
+            "object": "list",
+            "data": [
+                {"object": "embedding", "index": 1, "embedding": [0.5, -0.5]},
+                {"object": "embedding", "index": 0, "embedding": [0.25, 0.75]}
+            ],
+            "model": "text-embedding-3-small",
+            "usage": {"prompt_tokens": 8, "total_tokens": 8}
+        }"#;
+
+        let response: EmbeddingResponse = serde_json::from_str(json).unwrap();
+
+        assert_eq!(response.data.len(), 2);
+        assert_eq!(response.data[0].index, 1);
+        assert_eq!(response.data[1].embedding, vec![0.25, 0.75]);
+    }
 }
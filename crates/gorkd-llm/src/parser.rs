@@ -0,0 +1,522 @@
+use std::collections::HashMap;
+
+use gorkd_core::{
+    Citation, Confidence, ResearchAnswer, Source, SourceId, SynthesisMetadata, WhyInsufficient,
+};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct RawSynthesisResponse {
+    summary: String,
+    detail: String,
+    citations: Vec<RawCitation>,
+    confidence: String,
+    #[serde(default)]
+    limitations: Vec<String>,
+    #[serde(default)]
+    structured: Option<serde_json::Value>,
+    #[serde(default)]
+    suggested_followups: Vec<String>,
+    #[serde(default)]
+    why_insufficient: Option<RawWhyInsufficient>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawWhyInsufficient {
+    #[serde(default)]
+    missing_sources: Vec<String>,
+    #[serde(default)]
+    reformulations: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawCitation {
+    claim: String,
+    source_id: String,
+    #[serde(default)]
+    quote: Option<String>,
+}
+
+/// Upper bound on how many `{` positions are tried as candidate objects, so
+/// pathological inputs (e.g. thousands of unbalanced braces) stay linear-ish.
+const MAX_CANDIDATES: usize = 32;
+
+/// Parses a model's synthesis output into a [`ResearchAnswer`].
+///
+/// The JSON object may be bare, wrapped in a markdown code fence, or embedded
+/// in surrounding prose. Every balanced top-level object in the text is tried
+/// in order and the first one matching the synthesis schema wins.
+pub fn parse_synthesis_response(
+    text: &str,
+    sources: &[Source],
+    model: &str,
+    tokens_used: usize,
+) -> Result<ResearchAnswer, ParseError> {
+    let mut first_error = None;
+    let mut raw = None;
+
+    for candidate in json_candidates(text) {
+        let Some(json_text) = candidate else {
+            first_error.get_or_insert(ParseError::InvalidJson(
+                "unterminated JSON object".to_string(),
+            ));
+            continue;
+        };
+        match serde_json::from_str::<RawSynthesisResponse>(json_text) {
+            Ok(parsed) => {
+                raw = Some(parsed);
+                break;
+            }
+            Err(e) => {
+                first_error.get_or_insert(ParseError::InvalidJson(e.to_string()));
+            }
+        }
+    }
+
+    let raw = match raw {
+        Some(raw) => raw,
+        None => return Err(first_error.unwrap_or(ParseError::NoJsonFound)),
+    };
+
+    let source_map: HashMap<&str, &SourceId> =
+        sources.iter().map(|s| (s.id.as_str(), &s.id)).collect();
+
+    let citations = raw
+        .citations
+        .into_iter()
+        .filter_map(|c| resolve_citation(c, &source_map))
+        .collect();
+
+    let confidence = parse_confidence(&raw.confidence);
+
+    let metadata = SynthesisMetadata::new(model).with_tokens_used(tokens_used);
+
+    let answer = ResearchAnswer::new(raw.summary, raw.detail, confidence, model)
+        .with_citations(citations)
+        .with_limitations(raw.limitations)
+        .with_suggested_followups(raw.suggested_followups)
+        .with_metadata(metadata);
+    let answer = match raw.why_insufficient {
+        Some(why) => answer.with_why_insufficient(WhyInsufficient::new(
+            why.missing_sources,
+            why.reformulations,
+        )),
+        None => answer,
+    };
+    Ok(match raw.structured {
+        Some(structured) => answer.with_structured(structured),
+        None => answer,
+    })
+}
+
+/// Returns the first balanced JSON object in `text`.
+///
+/// Braces inside string literals are ignored, so prose or values containing
+/// `{` and `}` do not confuse the scan.
+pub fn extract_json(text: &str) -> Result<String, ParseError> {
+    match json_candidates(text).next() {
+        Some(Some(json)) => Ok(json.to_string()),
+        Some(None) => Err(ParseError::InvalidJson(
+            "unterminated JSON object".to_string(),
+        )),
+        None => Err(ParseError::NoJsonFound),
+    }
+}
+
+/// Yields each candidate object starting at a `{`, in order. `None` marks a
+/// candidate whose braces never balance (e.g. truncated output).
+fn json_candidates(text: &str) -> impl Iterator<Item = Option<&str>> {
+    text.match_indices('{')
+        .take(MAX_CANDIDATES)
+        .map(move |(start, _)| {
+            balanced_object_len(&text[start..]).map(|len| &text[start..start + len])
+        })
+}
+
+fn balanced_object_len(text: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, byte) in text.bytes().enumerate() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match byte {
+            b'"' => in_string = true,
+            b'{' => depth += 1,
+            b'}' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn resolve_citation(raw: RawCitation, source_map: &HashMap<&str, &SourceId>) -> Option<Citation> {
+    let source_id = source_map.get(raw.source_id.as_str()).copied()?;
+    let mut citation = Citation::new(raw.claim, source_id.clone());
+    if let Some(quote) = raw.quote {
+        citation = citation.with_quote(quote);
+    }
+    Some(citation)
+}
+
+fn parse_confidence(s: &str) -> Confidence {
+    match s.to_lowercase().as_str() {
+        "high" => Confidence::High,
+        "medium" => Confidence::Medium,
+        "low" => Confidence::Low,
+        "insufficient" => Confidence::Insufficient,
+        _ => Confidence::Medium,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    NoJsonFound,
+    InvalidJson(String),
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoJsonFound => write!(f, "no JSON object found in response"),
+            Self::InvalidJson(e) => write!(f, "invalid JSON: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_sources() -> Vec<Source> {
+        vec![
+            Source::new("https://example.com/a", "Source A", "Content A"),
+            Source::new("https://example.com/b", "Source B", "Content B"),
+        ]
+    }
+
+    #[test]
+    fn parses_clean_json() {
+        let sources = test_sources();
+        let json = format!(
+            r#"{{
+                "summary": "Test summary",
+                "detail": "Test detail with citation [{}]",
+                "citations": [
+                    {{"claim": "Test claim", "source_id": "{}", "quote": "exact quote"}}
+                ],
+                "confidence": "high",
+                "limitations": ["Limited data"]
+            }}"#,
+            sources[0].id.as_str(),
+            sources[0].id.as_str()
+        );
+
+        let answer = parse_synthesis_response(&json, &sources, "test-model", 100).unwrap();
+        assert_eq!(answer.summary, "Test summary");
+        assert_eq!(answer.confidence, Confidence::High);
+        assert_eq!(answer.citations.len(), 1);
+        assert!(answer.citations[0].quote.is_some());
+        assert_eq!(answer.limitations.len(), 1);
+    }
+
+    #[test]
+    fn parses_json_in_code_block() {
+        let sources = test_sources();
+        let text = format!(
+            r#"Here is my analysis:
+
+```json
+{{
+    "summary": "Summary",
+    "detail": "Detail",
+    "citations": [{{"claim": "Claim", "source_id": "{}"}}],
+    "confidence": "medium",
+    "limitations": []
+}}
+```"#,
+            sources[0].id.as_str()
+        );
+
+        let answer = parse_synthesis_response(&text, &sources, "test-model", 50).unwrap();
+        assert_eq!(answer.summary, "Summary");
+        assert_eq!(answer.confidence, Confidence::Medium);
+    }
+
+    #[test]
+    fn parses_json_with_surrounding_text() {
+        let sources = test_sources();
+        let text = r#"Let me analyze that for you.
+
+{
+    "summary": "Extracted summary",
+    "detail": "Extracted detail",
+    "citations": [],
+    "confidence": "low",
+    "limitations": []
+}
+
+Hope this helps!"#;
+
+        let answer = parse_synthesis_response(text, &sources, "test-model", 75).unwrap();
+        assert_eq!(answer.summary, "Extracted summary");
+        assert_eq!(answer.confidence, Confidence::Low);
+    }
+
+    #[test]
+    fn skips_citations_with_unknown_source_ids() {
+        let sources = test_sources();
+        let json = format!(
+            r#"{{
+                "summary": "Summary",
+                "detail": "Detail",
+                "citations": [
+                    {{"claim": "Valid", "source_id": "{}"}},
+                    {{"claim": "Invalid", "source_id": "src_nonexistent"}}
+                ],
+                "confidence": "high",
+                "limitations": []
+            }}"#,
+            sources[0].id.as_str()
+        );
+
+        let answer = parse_synthesis_response(&json, &sources, "test-model", 100).unwrap();
+        assert_eq!(answer.citations.len(), 1);
+        assert_eq!(answer.citations[0].claim, "Valid");
+    }
+
+    #[test]
+    fn handles_missing_limitations() {
+        let sources = test_sources();
+        let json = r#"{
+            "summary": "Summary",
+            "detail": "Detail",
+            "citations": [],
+            "confidence": "high"
+        }"#;
+
+        let answer = parse_synthesis_response(json, &sources, "test-model", 100).unwrap();
+        assert!(answer.limitations.is_empty());
+    }
+
+    #[test]
+    fn defaults_to_medium_confidence_for_unknown() {
+        let sources = test_sources();
+        let json = r#"{
+            "summary": "Summary",
+            "detail": "Detail",
+            "citations": [],
+            "confidence": "uncertain",
+            "limitations": []
+        }"#;
+
+        let answer = parse_synthesis_response(json, &sources, "test-model", 100).unwrap();
+        assert_eq!(answer.confidence, Confidence::Medium);
+    }
+
+    #[test]
+    fn keeps_structured_payload() {
+        let sources = test_sources();
+        let json = r#"{
+            "summary": "Summary",
+            "detail": "Detail",
+            "citations": [],
+            "confidence": "high",
+            "structured": {"price": 12.5}
+        }"#;
+
+        let answer = parse_synthesis_response(json, &sources, "test-model", 100).unwrap();
+        assert_eq!(answer.structured, Some(serde_json::json!({"price": 12.5})));
+    }
+
+    #[test]
+    fn keeps_suggested_followups() {
+        let sources = test_sources();
+        let json = r#"{
+            "summary": "Summary",
+            "detail": "Detail",
+            "citations": [],
+            "confidence": "high",
+            "suggested_followups": ["Who maintains it?", "", "What does it cost?"]
+        }"#;
+
+        let answer = parse_synthesis_response(json, &sources, "test-model", 100).unwrap();
+        assert_eq!(
+            answer.suggested_followups,
+            vec!["Who maintains it?", "What does it cost?"]
+        );
+    }
+
+    #[test]
+    fn keeps_why_insufficient() {
+        let sources = test_sources();
+        let json = r#"{
+            "summary": "The sources do not say.",
+            "detail": "Detail",
+            "citations": [],
+            "confidence": "insufficient",
+            "why_insufficient": {
+                "missing_sources": ["Official release notes", ""],
+                "reformulations": ["rust 2024 edition release date"]
+            }
+        }"#;
+
+        let answer = parse_synthesis_response(json, &sources, "test-model", 100).unwrap();
+        let why = answer.why_insufficient.unwrap();
+        assert_eq!(why.missing_sources, vec!["Official release notes"]);
+        assert_eq!(why.reformulations, vec!["rust 2024 edition release date"]);
+        assert!(why.searched.is_empty());
+    }
+
+    #[test]
+    fn returns_error_for_no_json() {
+        let sources = test_sources();
+        let text = "This response contains no JSON at all.";
+
+        let result = parse_synthesis_response(text, &sources, "test-model", 100);
+        assert!(matches!(result, Err(ParseError::NoJsonFound)));
+    }
+
+    #[test]
+    fn returns_error_for_invalid_json() {
+        let sources = test_sources();
+        let text = r#"{"summary": "Missing fields"}"#;
+
+        let result = parse_synthesis_response(text, &sources, "test-model", 100);
+        assert!(matches!(result, Err(ParseError::InvalidJson(_))));
+    }
+
+    #[test]
+    fn extracts_json_correctly() {
+        assert!(extract_json(r#"{"key": "value"}"#).is_ok());
+        assert!(extract_json("```json\n{\"key\": \"value\"}\n```").is_ok());
+        assert!(extract_json("```\n{\"key\": \"value\"}\n```").is_ok());
+        assert!(extract_json("text before {\"key\": \"value\"} text after").is_ok());
+        assert!(extract_json("no json here").is_err());
+    }
+
+    #[test]
+    fn ignores_braces_inside_strings() {
+        let json = r#"{"key": "value with } and { braces", "n": {"inner": "}"}} trailing }"#;
+        assert_eq!(
+            extract_json(json).unwrap(),
+            r#"{"key": "value with } and { braces", "n": {"inner": "}"}}"#
+        );
+    }
+
+    #[test]
+    fn skips_prose_braces_before_the_answer() {
+        let sources = test_sources();
+        let text = r#"Using the {sources} you gave me, here you go:
+{"summary": "S", "detail": "D", "citations": [], "confidence": "high"}
+Let me know if you need {more}."#;
+
+        let answer = parse_synthesis_response(text, &sources, "test-model", 10).unwrap();
+        assert_eq!(answer.summary, "S");
+    }
+
+    #[test]
+    fn returns_error_for_truncated_json() {
+        let sources = test_sources();
+        let text = r#"{"summary": "S", "detail": "D", "citations": [{"claim": "#;
+
+        let result = parse_synthesis_response(text, &sources, "test-model", 10);
+        assert!(matches!(result, Err(ParseError::InvalidJson(_))));
+    }
+
+    mod proptests {
+        use proptest::prelude::*;
+
+        use super::*;
+
+        fn answer_json(summary: &str, detail: &str, confidence: &str) -> String {
+            serde_json::json!({
+                "summary": summary,
+                "detail": detail,
+                "citations": [],
+                "confidence": confidence,
+                "limitations": [],
+            })
+            .to_string()
+        }
+
+        fn wrapping() -> impl Strategy<Value = (String, String)> {
+            prop_oneof![
+                Just((String::new(), String::new())),
+                Just(("```json\n".to_string(), "\n```".to_string())),
+                Just(("```\n".to_string(), "\n```".to_string())),
+                "[a-zA-Z .,:!?]{0,40}".prop_map(|prose| (format!("{}\n", prose), String::new())),
+                ("[a-zA-Z .,:!?]{0,40}", "[a-zA-Z .,:!?]{0,40}")
+                    .prop_map(|(pre, post)| (format!("{}\n", pre), format!("\n{}", post))),
+            ]
+        }
+
+        proptest! {
+            #[test]
+            fn never_panics_on_arbitrary_input(text in "\\PC*") {
+                let _ = parse_synthesis_response(&text, &[], "test-model", 0);
+                let _ = extract_json(&text);
+            }
+
+            #[test]
+            fn never_panics_on_brace_soup(text in "[{}\"\\\\ a:,\\[\\]]{0,200}") {
+                let _ = parse_synthesis_response(&text, &[], "test-model", 0);
+            }
+
+            #[test]
+            fn round_trips_wrapped_answers(
+                summary in "\\PC{0,60}",
+                detail in "\\PC{0,120}",
+                confidence in prop_oneof!["high", "medium", "low", "insufficient"],
+                (prefix, suffix) in wrapping(),
+            ) {
+                let text = format!("{}{}{}", prefix, answer_json(&summary, &detail, &confidence), suffix);
+
+                let answer = parse_synthesis_response(&text, &[], "test-model", 0).unwrap();
+
+                prop_assert_eq!(answer.summary, summary);
+                prop_assert_eq!(answer.detail, detail);
+            }
+
+            #[test]
+            fn round_trips_answers_with_nested_extra_fields(
+                summary in "[a-z{} ]{0,30}",
+                extra in prop::collection::vec("[a-z]{1,8}", 0..4),
+            ) {
+                let mut value: serde_json::Value =
+                    serde_json::from_str(&answer_json(&summary, "d", "high")).unwrap();
+                value["extra"] = serde_json::json!({ "nested": { "list": extra } });
+
+                let answer =
+                    parse_synthesis_response(&value.to_string(), &[], "test-model", 0).unwrap();
+
+                prop_assert_eq!(answer.summary, summary);
+            }
+
+            #[test]
+            fn rejects_truncated_answers(cut in 1usize..60) {
+                let json = answer_json("summary", "detail", "high");
+                let cut = json.len().saturating_sub(cut).max(1);
+
+                prop_assert!(parse_synthesis_response(&json[..cut], &[], "test-model", 0).is_err());
+            }
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! Recent rate limits of each model, for scheduling fallback.
+//!
+//! A saturated model answers every request with a 429 until its provider's
+//! window resets, so trying it first makes each job wait on a refusal before
+//! falling back. A [`RateLimitTracker`] remembers the rate limits each model
+//! returned over the last [`RATE_LIMIT_WINDOW`] and how long its provider
+//! asked to be left alone. [`LlmRegistry::synthesize_with_fallback`] skips
+//! models still cooling down and tries the least limited first.
+//!
+//! [`LlmRegistry::synthesize_with_fallback`]: crate::LlmRegistry::synthesize_with_fallback
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long a rate limit counts against its model.
+pub const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Default)]
+pub struct RateLimitTracker {
+    models: Mutex<HashMap<String, ModelLimits>>,
+}
+
+#[derive(Debug, Default)]
+struct ModelLimits {
+    /// When each recent rate limit came back, oldest first.
+    limited_at: VecDeque<Instant>,
+    /// When the provider's requested wait runs out.
+    retry_at: Option<Instant>,
+}
+
+impl ModelLimits {
+    fn forget_before(&mut self, now: Instant) {
+        while let Some(&at) = self.limited_at.front() {
+            if now.duration_since(at) < RATE_LIMIT_WINDOW {
+                break;
+            }
+            self.limited_at.pop_front();
+        }
+    }
+}
+
+impl RateLimitTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a rate limit from `model`, whose provider asked for
+    /// `retry_after` before the next request, if it said.
+    pub fn record(&self, model: &str, retry_after: Option<Duration>) {
+        self.record_at(model, retry_after, Instant::now());
+    }
+
+    /// Rate limits `model` returned within the window.
+    pub fn recent(&self, model: &str) -> usize {
+        self.recent_at(model, Instant::now())
+    }
+
+    /// What is left of the wait `model`'s provider last asked for; `None`
+    /// once it has passed.
+    pub fn cooldown(&self, model: &str) -> Option<Duration> {
+        self.cooldown_at(model, Instant::now())
+    }
+
+    fn record_at(&self, model: &str, retry_after: Option<Duration>, now: Instant) {
+        let mut models = self.models.lock().unwrap();
+        let limits = models.entry(model.to_string()).or_default();
+        limits.forget_before(now);
+        limits.limited_at.push_back(now);
+        if let Some(wait) = retry_after {
+            let retry_at = now + wait;
+            limits.retry_at = Some(limits.retry_at.map_or(retry_at, |at| at.max(retry_at)));
+        }
+    }
+
+    fn recent_at(&self, model: &str, now: Instant) -> usize {
+        let mut models = self.models.lock().unwrap();
+        models.get_mut(model).map_or(0, |limits| {
+            limits.forget_before(now);
+            limits.limited_at.len()
+        })
+    }
+
+    fn cooldown_at(&self, model: &str, now: Instant) -> Option<Duration> {
+        let models = self.models.lock().unwrap();
+        let retry_at = models.get(model)?.retry_at?;
+        (retry_at > now).then(|| retry_at - now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_rate_limits_within_the_window() {
+        let tracker = RateLimitTracker::new();
+        let start = Instant::now();
+
+        tracker.record_at("gpt-4o", None, start);
+        tracker.record_at("gpt-4o", None, start + Duration::from_secs(30));
+
+        assert_eq!(
+            tracker.recent_at("gpt-4o", start + Duration::from_secs(45)),
+            2
+        );
+        assert_eq!(
+            tracker.recent_at("gpt-4o", start + Duration::from_secs(75)),
+            1
+        );
+        assert_eq!(tracker.recent_at("claude", start), 0);
+        assert_eq!(tracker.cooldown_at("gpt-4o", start), None);
+    }
+
+    #[test]
+    fn cools_down_for_the_longest_requested_wait() {
+        let tracker = RateLimitTracker::new();
+        let start = Instant::now();
+
+        tracker.record_at("gpt-4o", Some(Duration::from_secs(20)), start);
+        tracker.record_at("gpt-4o", Some(Duration::from_secs(5)), start);
+
+        assert_eq!(
+            tracker.cooldown_at("gpt-4o", start + Duration::from_secs(15)),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(
+            tracker.cooldown_at("gpt-4o", start + Duration::from_secs(20)),
+            None
+        );
+    }
+}
@@ -1,20 +1,47 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use gorkd_core::{LlmError, LlmProvider, ResearchAnswer, Source};
 use reqwest::Client;
 use tracing::{info, warn};
 
-use crate::anthropic::types::{MODEL_CLAUDE_HAIKU_35, MODEL_CLAUDE_SONNET_4};
+use crate::anthropic::types::MODEL_CLAUDE_HAIKU_35;
+use crate::concurrency::{LimitedProvider, LlmLimiter};
 use crate::config::LlmConfig;
-use crate::openai::types::{MODEL_GPT_4O, MODEL_GPT_4O_MINI};
-use crate::{AnthropicProvider, OpenAiProvider};
+use crate::factory::LlmProviderFactories;
+use crate::openai::types::MODEL_GPT_4O_MINI;
+use crate::rate_limit::RateLimitTracker;
+
+/// What a registered model can do, as reported by its provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModelCapabilities {
+    pub vision: bool,
+    pub streaming: bool,
+    pub max_context_tokens: usize,
+}
+
+impl ModelCapabilities {
+    pub fn of(provider: &dyn LlmProvider) -> Self {
+        Self {
+            vision: provider.supports_vision(),
+            streaming: provider.supports_streaming(),
+            max_context_tokens: provider.max_context_tokens(),
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct LlmRegistry {
     providers: HashMap<String, Arc<dyn LlmProvider>>,
     default_model: Option<String>,
-    fallback_model: Option<String>,
+    /// Models tried in turn when the requested one fails with a retryable
+    /// error.
+    fallback_models: Vec<String>,
+    fast_model: Option<String>,
+    shadow_model: Option<String>,
+    limiter: Option<Arc<LlmLimiter>>,
+    rate_limits: Arc<RateLimitTracker>,
 }
 
 impl Default for LlmRegistry {
@@ -28,7 +55,11 @@ impl LlmRegistry {
         Self {
             providers: HashMap::new(),
             default_model: None,
-            fallback_model: None,
+            fallback_models: Vec::new(),
+            fast_model: None,
+            shadow_model: None,
+            limiter: None,
+            rate_limits: Arc::default(),
         }
     }
 
@@ -36,51 +67,42 @@ impl LlmRegistry {
         LlmRegistryBuilder::new()
     }
 
+    /// Registers the models of each configured provider with the built-in
+    /// [`LlmProviderFactories`]. Their requests share one [`LlmLimiter`]
+    /// with the configured concurrency limits.
     pub fn from_config(http: Client, config: &LlmConfig) -> Self {
-        let mut builder = Self::builder();
-
-        if let Some(ref anthropic_config) = config.anthropic {
-            let sonnet =
-                AnthropicProvider::new(http.clone(), anthropic_config, MODEL_CLAUDE_SONNET_4);
-            builder = builder.register(MODEL_CLAUDE_SONNET_4, Arc::new(sonnet));
-            info!(
-                model = MODEL_CLAUDE_SONNET_4,
-                provider = "anthropic",
-                "registered LLM provider"
-            );
-
-            let haiku =
-                AnthropicProvider::new(http.clone(), anthropic_config, MODEL_CLAUDE_HAIKU_35);
-            builder = builder.register(MODEL_CLAUDE_HAIKU_35, Arc::new(haiku));
-            info!(
-                model = MODEL_CLAUDE_HAIKU_35,
-                provider = "anthropic",
-                "registered LLM provider"
-            );
+        Self::from_factories(http, config, &LlmProviderFactories::builtin())
+    }
+
+    /// Like [`from_config`](Self::from_config), registering the models
+    /// `factories` build.
+    pub fn from_factories(
+        http: Client,
+        config: &LlmConfig,
+        factories: &LlmProviderFactories,
+    ) -> Self {
+        let mut builder =
+            Self::builder().limiter(Arc::new(LlmLimiter::new(config.concurrency.clone())));
+
+        for (model, provider, built) in factories.build(&http, config) {
+            info!(model = %model, provider, "registered LLM provider");
+            builder = builder.register(model, built);
         }
 
-        if let Some(ref openai_config) = config.openai {
-            let gpt4o = OpenAiProvider::new(http.clone(), openai_config, MODEL_GPT_4O);
-            builder = builder.register(MODEL_GPT_4O, Arc::new(gpt4o));
-            info!(
-                model = MODEL_GPT_4O,
-                provider = "openai",
-                "registered LLM provider"
-            );
+        builder = builder.default_model(&config.default_model);
 
-            let gpt4o_mini = OpenAiProvider::new(http.clone(), openai_config, MODEL_GPT_4O_MINI);
-            builder = builder.register(MODEL_GPT_4O_MINI, Arc::new(gpt4o_mini));
-            info!(
-                model = MODEL_GPT_4O_MINI,
-                provider = "openai",
-                "registered LLM provider"
-            );
-        }
+        builder = builder.fallback_models(&config.fallback_models);
 
-        builder = builder.default_model(&config.default_model);
+        let fast_model = config
+            .fast_model
+            .as_deref()
+            .or_else(|| fast_model_for(&config.default_model));
+        if let Some(fast) = fast_model {
+            builder = builder.fast_model(fast);
+        }
 
-        if let Some(ref fallback) = config.fallback_model {
-            builder = builder.fallback_model(fallback);
+        if let Some(ref shadow) = config.shadow_model {
+            builder = builder.shadow_model(shadow);
         }
 
         builder.build()
@@ -91,15 +113,33 @@ impl LlmRegistry {
         if self.default_model.is_none() {
             self.default_model = Some(model_id.clone());
         }
+        let provider = match self.limiter {
+            Some(ref limiter) => limited(provider, limiter),
+            None => provider,
+        };
         self.providers.insert(model_id, provider);
     }
 
+    /// The limiter every registered provider's requests go through, if any.
+    pub fn limiter(&self) -> Option<&Arc<LlmLimiter>> {
+        self.limiter.as_ref()
+    }
+
     pub fn set_default(&mut self, model_id: impl Into<String>) {
         self.default_model = Some(model_id.into());
     }
 
+    /// Makes `model_id` the only fallback model.
     pub fn set_fallback(&mut self, model_id: impl Into<String>) {
-        self.fallback_model = Some(model_id.into());
+        self.fallback_models = vec![model_id.into()];
+    }
+
+    pub fn set_fast(&mut self, model_id: impl Into<String>) {
+        self.fast_model = Some(model_id.into());
+    }
+
+    pub fn set_shadow(&mut self, model_id: impl Into<String>) {
+        self.shadow_model = Some(model_id.into());
     }
 
     pub fn get(&self, model_id: &str) -> Option<Arc<dyn LlmProvider>> {
@@ -110,8 +150,24 @@ impl LlmRegistry {
         self.default_model.as_ref().and_then(|id| self.get(id))
     }
 
+    /// The first fallback model, if it is registered.
     pub fn fallback(&self) -> Option<Arc<dyn LlmProvider>> {
-        self.fallback_model.as_ref().and_then(|id| self.get(id))
+        self.fallback_model_id().and_then(|id| self.get(id))
+    }
+
+    /// The cheap model routing answers simple questions with, if it is
+    /// registered.
+    pub fn fast(&self) -> Option<Arc<dyn LlmProvider>> {
+        self.fast_model.as_ref().and_then(|id| self.get(id))
+    }
+
+    /// The candidate model evaluated in shadow of the default model, if it
+    /// is registered and is not the default model itself.
+    pub fn shadow(&self) -> Option<Arc<dyn LlmProvider>> {
+        self.shadow_model
+            .as_ref()
+            .filter(|id| Some(*id) != self.default_model.as_ref())
+            .and_then(|id| self.get(id))
     }
 
     pub fn default_model_id(&self) -> Option<&str> {
@@ -119,13 +175,51 @@ impl LlmRegistry {
     }
 
     pub fn fallback_model_id(&self) -> Option<&str> {
-        self.fallback_model.as_deref()
+        self.fallback_models.first().map(String::as_str)
+    }
+
+    /// The fallback chain, in the order it is tried when no model is rate
+    /// limited.
+    pub fn fallback_model_ids(&self) -> &[String] {
+        &self.fallback_models
+    }
+
+    pub fn fast_model_id(&self) -> Option<&str> {
+        self.fast_model.as_deref()
+    }
+
+    pub fn shadow_model_id(&self) -> Option<&str> {
+        self.shadow_model.as_deref()
+    }
+
+    /// The rate limits each model has returned recently, shared by clones
+    /// of the registry.
+    pub fn rate_limits(&self) -> &RateLimitTracker {
+        &self.rate_limits
     }
 
     pub fn available_models(&self) -> Vec<String> {
         self.providers.keys().cloned().collect()
     }
 
+    pub fn capabilities(&self, model_id: &str) -> Option<ModelCapabilities> {
+        self.providers
+            .get(model_id)
+            .map(|provider| ModelCapabilities::of(provider.as_ref()))
+    }
+
+    /// Registered models that accept images, sorted by ID.
+    pub fn vision_models(&self) -> Vec<String> {
+        let mut models: Vec<String> = self
+            .providers
+            .iter()
+            .filter(|(_, provider)| provider.supports_vision())
+            .map(|(id, _)| id.clone())
+            .collect();
+        models.sort();
+        models
+    }
+
     pub fn is_empty(&self) -> bool {
         self.providers.is_empty()
     }
@@ -134,41 +228,99 @@ impl LlmRegistry {
         self.providers.len()
     }
 
+    /// Synthesizes with `model_id`, or the default model, falling back
+    /// through the fallback chain on retryable errors. Models are tried in
+    /// the order [`schedule`](Self::schedule) gives, and every rate limit is
+    /// recorded so later requests steer clear of the model.
     pub async fn synthesize_with_fallback(
         &self,
         query: &str,
         sources: &[Source],
         model_id: Option<&str>,
     ) -> Result<ResearchAnswer, LlmError> {
-        let primary = match model_id {
-            Some(id) => self.get(id).ok_or_else(|| LlmError::ModelUnavailable {
-                model: id.to_string(),
-            })?,
-            None => self.default().ok_or_else(|| LlmError::ModelUnavailable {
-                model: "no default model configured".to_string(),
-            })?,
+        let primary = match model_id.or(self.default_model.as_deref()) {
+            Some(id) if self.providers.contains_key(id) => id,
+            Some(id) => {
+                return Err(LlmError::ModelUnavailable {
+                    model: id.to_string(),
+                })
+            }
+            None => {
+                return Err(LlmError::ModelUnavailable {
+                    model: "no default model configured".to_string(),
+                })
+            }
         };
 
-        let primary_model_id = primary.model_id().to_string();
-
-        match primary.synthesize(query, sources).await {
-            Ok(answer) => Ok(answer),
-            Err(err) if err.is_retryable() => {
-                if let Some(fallback) = self.fallback() {
-                    if fallback.model_id() != primary_model_id {
-                        warn!(
-                            primary_model = %primary_model_id,
-                            fallback_model = %fallback.model_id(),
-                            error = %err,
-                            "primary LLM failed, attempting fallback"
-                        );
-                        return fallback.synthesize(query, sources).await;
+        let models = self.schedule(primary).map_err(|wait| {
+            warn!(
+                primary_model = %primary,
+                retry_after_secs = wait.as_secs_f64(),
+                "every LLM in the fallback chain is cooling down after a rate limit"
+            );
+            LlmError::RateLimited {
+                retry_after_secs: Some(wait.as_secs_f64().ceil() as u64),
+            }
+        })?;
+
+        for (attempt, model) in models.iter().enumerate() {
+            match self.providers[*model].synthesize(query, sources).await {
+                Ok(answer) => return Ok(answer),
+                Err(err) => {
+                    if let LlmError::RateLimited { retry_after_secs } = err {
+                        self.rate_limits
+                            .record(model, retry_after_secs.map(Duration::from_secs));
                     }
+                    let Some(next) = models.get(attempt + 1).filter(|_| err.is_retryable()) else {
+                        return Err(err);
+                    };
+                    warn!(
+                        primary_model = %primary,
+                        failed_model = %model,
+                        next_model = %next,
+                        error = %err,
+                        "LLM failed, attempting next model in fallback chain"
+                    );
                 }
-                Err(err)
             }
-            Err(err) => Err(err),
         }
+        unreachable!("schedule returns at least one model")
+    }
+
+    /// The registered models a request for `primary` tries, in order:
+    /// `primary`, then the fallback chain, leaving out models still waiting
+    /// out a provider's `Retry-After`. Models with fewer recent rate limits
+    /// come first, then those whose provider has headroom under the
+    /// limiter; ties keep chain order. When every model is cooling down,
+    /// the shortest wait left.
+    pub fn schedule<'a>(&'a self, primary: &'a str) -> Result<Vec<&'a str>, Duration> {
+        let mut chain: Vec<&str> = Vec::new();
+        for model in std::iter::once(primary).chain(self.fallback_models.iter().map(String::as_str))
+        {
+            if self.providers.contains_key(model) && !chain.contains(&model) {
+                chain.push(model);
+            }
+        }
+
+        let (cooling, mut ready): (Vec<&str>, Vec<&str>) = chain
+            .into_iter()
+            .partition(|model| self.rate_limits.cooldown(model).is_some());
+        if ready.is_empty() {
+            return Err(cooling
+                .iter()
+                .filter_map(|model| self.rate_limits.cooldown(model))
+                .min()
+                .unwrap_or_default());
+        }
+
+        ready.sort_by_cached_key(|model| {
+            let saturated = match self.limiter {
+                Some(ref limiter) => !limiter.has_headroom(self.providers[*model].provider_name()),
+                None => false,
+            };
+            (self.rate_limits.recent(model), saturated)
+        });
+        Ok(ready)
     }
 }
 
@@ -177,7 +329,9 @@ impl std::fmt::Debug for LlmRegistry {
         f.debug_struct("LlmRegistry")
             .field("models", &self.available_models())
             .field("default", &self.default_model)
-            .field("fallback", &self.fallback_model)
+            .field("fallbacks", &self.fallback_models)
+            .field("fast", &self.fast_model)
+            .field("shadow", &self.shadow_model)
             .finish()
     }
 }
@@ -185,7 +339,10 @@ impl std::fmt::Debug for LlmRegistry {
 pub struct LlmRegistryBuilder {
     providers: HashMap<String, Arc<dyn LlmProvider>>,
     default_model: Option<String>,
-    fallback_model: Option<String>,
+    fallback_models: Vec<String>,
+    fast_model: Option<String>,
+    shadow_model: Option<String>,
+    limiter: Option<Arc<LlmLimiter>>,
 }
 
 impl Default for LlmRegistryBuilder {
@@ -199,7 +356,10 @@ impl LlmRegistryBuilder {
         Self {
             providers: HashMap::new(),
             default_model: None,
-            fallback_model: None,
+            fallback_models: Vec::new(),
+            fast_model: None,
+            shadow_model: None,
+            limiter: None,
         }
     }
 
@@ -217,20 +377,77 @@ impl LlmRegistryBuilder {
         self
     }
 
+    /// Adds `model_id` to the end of the fallback chain.
     pub fn fallback_model(mut self, model_id: impl Into<String>) -> Self {
-        self.fallback_model = Some(model_id.into());
+        self.fallback_models.push(model_id.into());
+        self
+    }
+
+    /// Adds each of `model_ids` to the end of the fallback chain, in order.
+    pub fn fallback_models<I>(mut self, model_ids: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.fallback_models
+            .extend(model_ids.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn fast_model(mut self, model_id: impl Into<String>) -> Self {
+        self.fast_model = Some(model_id.into());
+        self
+    }
+
+    /// Evaluates `model_id` in shadow of the default model.
+    pub fn shadow_model(mut self, model_id: impl Into<String>) -> Self {
+        self.shadow_model = Some(model_id.into());
+        self
+    }
+
+    /// Sends every provider's requests through `limiter`.
+    pub fn limiter(mut self, limiter: Arc<LlmLimiter>) -> Self {
+        self.limiter = Some(limiter);
         self
     }
 
     pub fn build(self) -> LlmRegistry {
+        let providers = match self.limiter {
+            Some(ref limiter) => self
+                .providers
+                .into_iter()
+                .map(|(model, provider)| (model, limited(provider, limiter)))
+                .collect(),
+            None => self.providers,
+        };
         LlmRegistry {
-            providers: self.providers,
+            providers,
             default_model: self.default_model,
-            fallback_model: self.fallback_model,
+            fallback_models: self.fallback_models,
+            fast_model: self.fast_model,
+            shadow_model: self.shadow_model,
+            limiter: self.limiter,
+            rate_limits: Arc::default(),
         }
     }
 }
 
+/// The cheaper model of the same provider as `default_model`, used for
+/// routing when no fast model is configured.
+fn fast_model_for(default_model: &str) -> Option<&'static str> {
+    if default_model.starts_with("claude-") {
+        Some(MODEL_CLAUDE_HAIKU_35)
+    } else if default_model.starts_with("gpt-") {
+        Some(MODEL_GPT_4O_MINI)
+    } else {
+        None
+    }
+}
+
+fn limited(provider: Arc<dyn LlmProvider>, limiter: &Arc<LlmLimiter>) -> Arc<dyn LlmProvider> {
+    Arc::new(LimitedProvider::new(provider, Arc::clone(limiter)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -238,6 +455,10 @@ mod tests {
     use gorkd_core::{Confidence, ResearchAnswer};
     use std::sync::atomic::{AtomicUsize, Ordering};
 
+    use crate::anthropic::types::MODEL_CLAUDE_SONNET_4;
+    use crate::bedrock::types::MODEL_BEDROCK_LLAMA_31_70B;
+    use crate::openai::types::MODEL_GPT_4O;
+
     struct MockProvider {
         model_id: String,
         provider_name: String,
@@ -296,6 +517,18 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn default_chat_reports_unsupported() {
+        let provider = MockProvider::new("test-model");
+
+        let err = provider
+            .chat(crate::types::ChatRequest::new(vec![]))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, LlmError::Provider(msg) if msg.contains("does not support chat")));
+    }
+
     #[test]
     fn creates_empty_registry() {
         let registry = LlmRegistry::new();
@@ -349,6 +582,59 @@ mod tests {
         assert_eq!(registry.fallback_model_id(), Some("model-a"));
     }
 
+    #[test]
+    fn resolves_fast_model() {
+        let registry = LlmRegistry::builder()
+            .register("model-a", Arc::new(MockProvider::new("model-a")))
+            .register("model-b", Arc::new(MockProvider::new("model-b")))
+            .fast_model("model-b")
+            .build();
+
+        assert_eq!(registry.fast().unwrap().model_id(), "model-b");
+        assert_eq!(
+            fast_model_for(MODEL_CLAUDE_SONNET_4),
+            Some(MODEL_CLAUDE_HAIKU_35)
+        );
+        assert_eq!(fast_model_for(MODEL_GPT_4O), Some(MODEL_GPT_4O_MINI));
+        assert_eq!(fast_model_for(MODEL_BEDROCK_LLAMA_31_70B), None);
+    }
+
+    #[test]
+    fn from_factories_registers_what_each_factory_builds() {
+        let config = LlmConfig {
+            default_model: "local-llama".to_string(),
+            ..Default::default()
+        };
+        let factories = LlmProviderFactories::builtin().with("local", |_, config| {
+            vec![(
+                config.default_model.clone(),
+                Arc::new(MockProvider::new(&config.default_model)) as Arc<dyn LlmProvider>,
+            )]
+        });
+
+        let registry = LlmRegistry::from_factories(Client::new(), &config, &factories);
+
+        assert_eq!(registry.available_models(), vec!["local-llama"]);
+        assert_eq!(registry.default().unwrap().model_id(), "local-llama");
+        assert!(registry.limiter().is_some());
+    }
+
+    #[test]
+    fn resolves_shadow_model() {
+        let registry = LlmRegistry::builder()
+            .register("model-a", Arc::new(MockProvider::new("model-a")))
+            .register("model-b", Arc::new(MockProvider::new("model-b")))
+            .shadow_model("model-b")
+            .build();
+        assert_eq!(registry.shadow().unwrap().model_id(), "model-b");
+
+        let mut registry = registry;
+        registry.set_shadow("model-a");
+        assert!(registry.shadow().is_none());
+        registry.set_shadow("model-c");
+        assert!(registry.shadow().is_none());
+    }
+
     #[test]
     fn lists_available_models() {
         let mut registry = LlmRegistry::new();
@@ -361,6 +647,23 @@ mod tests {
         assert!(models.contains(&"beta".to_string()));
     }
 
+    #[test]
+    fn reports_model_capabilities() {
+        let mut registry = LlmRegistry::new();
+        registry.register("alpha", Arc::new(MockProvider::new("alpha")));
+
+        assert_eq!(
+            registry.capabilities("alpha"),
+            Some(ModelCapabilities {
+                vision: false,
+                streaming: false,
+                max_context_tokens: 128_000,
+            })
+        );
+        assert_eq!(registry.capabilities("missing"), None);
+        assert!(registry.vision_models().is_empty());
+    }
+
     #[test]
     fn debug_shows_structure() {
         let registry = LlmRegistry::builder()
@@ -409,9 +712,36 @@ mod tests {
         assert_eq!(primary.call_count(), 1);
     }
 
+    #[tokio::test]
+    async fn limiter_counts_requests_of_every_provider() {
+        let primary = Arc::new(MockProvider::new("primary"));
+        let limiter = Arc::new(LlmLimiter::new(
+            crate::concurrency::ConcurrencyLimits::default().with_overall(4),
+        ));
+
+        let registry = LlmRegistry::builder()
+            .register("primary", primary.clone())
+            .limiter(Arc::clone(&limiter))
+            .build();
+
+        let result = registry.synthesize_with_fallback("query", &[], None).await;
+
+        assert!(result.is_ok());
+        assert_eq!(primary.call_count(), 1);
+        assert!(registry.limiter().is_some());
+        let snapshot = limiter.snapshot();
+        assert_eq!(snapshot.overall.limit, Some(4));
+        assert_eq!(snapshot.providers[0].0, "mock");
+        assert_eq!(registry.get("primary").unwrap().model_id(), "primary");
+    }
+
     #[tokio::test]
     async fn synthesize_falls_back_on_retryable_error() {
-        let primary = Arc::new(MockProvider::new("primary").with_error(LlmError::RateLimited));
+        let primary = Arc::new(
+            MockProvider::new("primary").with_error(LlmError::RateLimited {
+                retry_after_secs: None,
+            }),
+        );
         let fallback = Arc::new(MockProvider::new("fallback"));
 
         let registry = LlmRegistry::builder()
@@ -475,7 +805,9 @@ mod tests {
 
     #[tokio::test]
     async fn fallback_not_used_when_same_as_primary() {
-        let provider = Arc::new(MockProvider::new("same").with_error(LlmError::RateLimited));
+        let provider = Arc::new(MockProvider::new("same").with_error(LlmError::RateLimited {
+            retry_after_secs: None,
+        }));
 
         let registry = LlmRegistry::builder()
             .register("same", provider.clone())
@@ -550,4 +882,129 @@ mod tests {
         assert!(result.is_err());
         assert_eq!(fallback.call_count(), 0);
     }
+
+    fn rate_limited(retry_after_secs: Option<u64>) -> LlmError {
+        LlmError::RateLimited { retry_after_secs }
+    }
+
+    #[tokio::test]
+    async fn falls_back_through_the_chain() {
+        let primary = Arc::new(MockProvider::new("primary").with_error(rate_limited(None)));
+        let second = Arc::new(
+            MockProvider::new("second").with_error(LlmError::Timeout { timeout_secs: 30 }),
+        );
+        let third = Arc::new(MockProvider::new("third"));
+
+        let registry = LlmRegistry::builder()
+            .register("primary", primary.clone())
+            .register("second", second.clone())
+            .register("third", third.clone())
+            .default_model("primary")
+            .fallback_models(["second", "third"])
+            .build();
+
+        let answer = registry
+            .synthesize_with_fallback("query", &[], None)
+            .await
+            .unwrap();
+
+        assert_eq!(answer.synthesis_metadata.model, "third");
+        assert_eq!(
+            registry.fallback_model_ids(),
+            ["second".to_string(), "third".to_string()]
+        );
+        assert_eq!(registry.rate_limits().recent("primary"), 1);
+        assert_eq!(registry.rate_limits().recent("second"), 0);
+    }
+
+    #[tokio::test]
+    async fn tries_recently_rate_limited_models_last() {
+        let primary = Arc::new(MockProvider::new("primary").with_error(rate_limited(None)));
+        let fallback = Arc::new(MockProvider::new("fallback"));
+
+        let registry = LlmRegistry::builder()
+            .register("primary", primary.clone())
+            .register("fallback", fallback.clone())
+            .default_model("primary")
+            .fallback_model("fallback")
+            .build();
+
+        for _ in 0..2 {
+            registry
+                .synthesize_with_fallback("query", &[], None)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            registry.schedule("primary"),
+            Ok(vec!["fallback", "primary"])
+        );
+        assert_eq!(primary.call_count(), 1);
+        assert_eq!(fallback.call_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn waits_out_retry_after_before_retrying_a_model() {
+        let primary = Arc::new(MockProvider::new("primary").with_error(rate_limited(Some(30))));
+        let fallback = Arc::new(MockProvider::new("fallback").with_error(rate_limited(Some(10))));
+
+        let registry = LlmRegistry::builder()
+            .register("primary", primary.clone())
+            .register("fallback", fallback.clone())
+            .default_model("primary")
+            .fallback_model("fallback")
+            .build();
+
+        let first = registry.synthesize_with_fallback("query", &[], None).await;
+        assert!(matches!(
+            first,
+            Err(LlmError::RateLimited {
+                retry_after_secs: Some(10)
+            })
+        ));
+        assert!(registry.rate_limits().cooldown("primary").is_some());
+
+        let second = registry.synthesize_with_fallback("query", &[], None).await;
+
+        assert!(matches!(
+            second,
+            Err(LlmError::RateLimited {
+                retry_after_secs: Some(10)
+            })
+        ));
+        assert_eq!(primary.call_count(), 1);
+        assert_eq!(fallback.call_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn schedules_providers_with_headroom_first() {
+        let limiter = Arc::new(LlmLimiter::new(
+            crate::concurrency::ConcurrencyLimits::default().with_provider("busy", 1),
+        ));
+        let mut busy = MockProvider::new("primary");
+        busy.provider_name = "busy".to_string();
+
+        let registry = LlmRegistry::builder()
+            .register("primary", Arc::new(busy))
+            .register("fallback", Arc::new(MockProvider::new("fallback")))
+            .fallback_models(["missing", "fallback"])
+            .limiter(Arc::clone(&limiter))
+            .build();
+        assert_eq!(
+            registry.schedule("primary"),
+            Ok(vec!["primary", "fallback"])
+        );
+
+        let held = limiter.run("busy", std::future::pending::<()>());
+        let scheduled = tokio::select! {
+            _ = held => unreachable!(),
+            scheduled = async {
+                tokio::task::yield_now().await;
+                registry.schedule("primary")
+            } => scheduled,
+        };
+
+        assert_eq!(scheduled, Ok(vec!["fallback", "primary"]));
+    }
 }
@@ -0,0 +1,281 @@
+//! Shadow evaluation of a candidate model on live requests.
+//!
+//! [`ShadowLlmProvider`] answers every request with the primary model and
+//! sends the same request to a candidate in the background. The candidate's
+//! reply is never returned: it is scored against the primary's and recorded
+//! in [`ShadowMetrics`], so a model can be judged on real traffic before it
+//! becomes the default.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use gorkd_core::{
+    AnswerSchema, AnswerStyle, ChatRequest, ChatResponse, LengthPolicy, LlmError, LlmExchange,
+    LlmProvider, ModelAnswer, ModelComparison, ModelPricing, ResearchAnswer, ShadowKind,
+    ShadowMetrics, ShadowSample, Source,
+};
+use tokio::sync::oneshot;
+use tracing::debug;
+
+/// A provider that answers with `primary` and shadows it with `candidate`.
+/// Everything but the calls themselves, such as the model ID and pricing,
+/// is the primary's.
+pub struct ShadowLlmProvider {
+    primary: Arc<dyn LlmProvider>,
+    candidate: Arc<dyn LlmProvider>,
+    metrics: Arc<ShadowMetrics>,
+}
+
+impl ShadowLlmProvider {
+    pub fn new(
+        primary: Arc<dyn LlmProvider>,
+        candidate: Arc<dyn LlmProvider>,
+        metrics: Arc<ShadowMetrics>,
+    ) -> Self {
+        Self {
+            primary,
+            candidate,
+            metrics,
+        }
+    }
+
+    /// Runs `call` against the candidate in a background task. Once the
+    /// primary's result and latency arrive on the returned sender, the
+    /// candidate is scored with `overlap` and recorded.
+    fn shadow<T: Send + 'static>(
+        &self,
+        call: impl Future<Output = Result<T, LlmError>> + Send + 'static,
+        overlap: fn(&T, &T) -> Option<f32>,
+    ) -> oneshot::Sender<(Option<T>, Duration)> {
+        let (tx, rx) = oneshot::channel::<(Option<T>, Duration)>();
+        let metrics = Arc::clone(&self.metrics);
+        let primary = self.primary.model_id().to_string();
+        let candidate = self.candidate.model_id().to_string();
+
+        tokio::spawn(async move {
+            let started = Instant::now();
+            let result = call.await;
+            let candidate_latency = started.elapsed();
+            let Ok((primary_result, primary_latency)) = rx.await else {
+                return;
+            };
+
+            let overlap = match (&primary_result, &result) {
+                (Some(primary), Ok(candidate)) => overlap(primary, candidate),
+                _ => None,
+            };
+            debug!(
+                primary = %primary,
+                candidate = %candidate,
+                error = result.as_ref().err().map(ToString::to_string),
+                latency_ms = candidate_latency.as_millis() as u64,
+                primary_latency_ms = primary_latency.as_millis() as u64,
+                overlap = ?overlap,
+                "shadow model call finished"
+            );
+            metrics.record(
+                ShadowKind::Llm,
+                &primary,
+                &candidate,
+                &ShadowSample {
+                    primary_latency,
+                    candidate_latency,
+                    primary_ok: primary_result.is_some(),
+                    candidate_ok: result.is_ok(),
+                    overlap,
+                },
+            );
+        });
+
+        tx
+    }
+}
+
+/// How far two answers cite the same sources.
+fn citation_overlap(primary: &ResearchAnswer, candidate: &ResearchAnswer) -> Option<f32> {
+    ModelComparison::new(vec![
+        ModelAnswer::answered("primary", primary.clone()),
+        ModelAnswer::answered("candidate", candidate.clone()),
+    ])
+    .agreement()
+    .map(|agreement| agreement.citation_overlap)
+}
+
+#[async_trait]
+impl LlmProvider for ShadowLlmProvider {
+    async fn synthesize(
+        &self,
+        query: &str,
+        sources: &[Source],
+    ) -> Result<ResearchAnswer, LlmError> {
+        let candidate = Arc::clone(&self.candidate);
+        let (query_owned, sources_owned) = (query.to_string(), sources.to_vec());
+        let shadow = self.shadow(
+            async move { candidate.synthesize(&query_owned, &sources_owned).await },
+            citation_overlap,
+        );
+
+        let started = Instant::now();
+        let result = self.primary.synthesize(query, sources).await;
+        let _ = shadow.send((result.as_ref().ok().cloned(), started.elapsed()));
+        result
+    }
+
+    async fn synthesize_captured(
+        &self,
+        query: &str,
+        sources: &[Source],
+        length: Option<&LengthPolicy>,
+        schema: Option<&AnswerSchema>,
+        style: AnswerStyle,
+        temperature: Option<f32>,
+    ) -> (Result<ResearchAnswer, LlmError>, LlmExchange) {
+        let candidate = Arc::clone(&self.candidate);
+        let (query_owned, sources_owned) = (query.to_string(), sources.to_vec());
+        let (length_owned, schema_owned) = (length.cloned(), schema.cloned());
+        let shadow = self.shadow(
+            async move {
+                candidate
+                    .synthesize_captured(
+                        &query_owned,
+                        &sources_owned,
+                        length_owned.as_ref(),
+                        schema_owned.as_ref(),
+                        style,
+                        temperature,
+                    )
+                    .await
+                    .0
+            },
+            citation_overlap,
+        );
+
+        let started = Instant::now();
+        let (result, exchange) = self
+            .primary
+            .synthesize_captured(query, sources, length, schema, style, temperature)
+            .await;
+        let _ = shadow.send((result.as_ref().ok().cloned(), started.elapsed()));
+        (result, exchange)
+    }
+
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, LlmError> {
+        let candidate = Arc::clone(&self.candidate);
+        let candidate_request = request.clone();
+        let shadow = self.shadow(
+            async move { candidate.chat(candidate_request).await.map(|_| ()) },
+            |_, _| None,
+        );
+
+        let started = Instant::now();
+        let result = self.primary.chat(request).await;
+        let _ = shadow.send((result.as_ref().ok().map(|_| ()), started.elapsed()));
+        result
+    }
+
+    fn parse_synthesis(&self, raw: &str, sources: &[Source]) -> Result<ResearchAnswer, LlmError> {
+        self.primary.parse_synthesis(raw, sources)
+    }
+
+    fn model_id(&self) -> &str {
+        self.primary.model_id()
+    }
+
+    fn provider_name(&self) -> &str {
+        self.primary.provider_name()
+    }
+
+    fn max_context_tokens(&self) -> usize {
+        self.primary.max_context_tokens()
+    }
+
+    fn count_tokens(&self, text: &str) -> usize {
+        self.primary.count_tokens(text)
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.primary.supports_streaming()
+    }
+
+    fn supports_vision(&self) -> bool {
+        self.primary.supports_vision()
+    }
+
+    fn pricing(&self) -> Option<ModelPricing> {
+        self.primary.pricing()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gorkd_core::{MockLlmProvider, MockLlmStep, ShadowEvaluation};
+
+    async fn evaluations(metrics: &ShadowMetrics) -> Vec<ShadowEvaluation> {
+        for _ in 0..100 {
+            let snapshot = metrics.snapshot();
+            if !snapshot.is_empty() {
+                return snapshot;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        panic!("shadow model call was never recorded");
+    }
+
+    fn sources() -> Vec<Source> {
+        (1..=3)
+            .map(|i| Source::new(format!("https://example.com/{}", i), "Title", "Content"))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn answers_with_primary_and_scores_candidate() {
+        let metrics = Arc::new(ShadowMetrics::new());
+        let provider = ShadowLlmProvider::new(
+            Arc::new(MockLlmProvider::new("primary")),
+            Arc::new(MockLlmProvider::new("candidate")),
+            Arc::clone(&metrics),
+        );
+
+        let answer = provider.synthesize("What is Rust?", &sources()).await;
+
+        assert!(answer.is_ok());
+        assert_eq!(provider.model_id(), "primary");
+        let evaluations = evaluations(&metrics).await;
+        assert_eq!(evaluations[0].kind, ShadowKind::Llm);
+        assert_eq!(evaluations[0].primary, "primary");
+        assert_eq!(evaluations[0].candidate, "candidate");
+        assert_eq!(evaluations[0].stats.mean_overlap(), Some(1.0));
+    }
+
+    #[tokio::test]
+    async fn candidate_failures_do_not_reach_the_caller() {
+        let metrics = Arc::new(ShadowMetrics::new());
+        let candidate = MockLlmProvider::new("candidate").with_script([MockLlmStep::Fail(
+            LlmError::Provider("overloaded".to_string()),
+        )]);
+        let provider = ShadowLlmProvider::new(
+            Arc::new(MockLlmProvider::new("primary")),
+            Arc::new(candidate),
+            Arc::clone(&metrics),
+        );
+
+        let (result, _) = provider
+            .synthesize_captured(
+                "What is Rust?",
+                &sources(),
+                None,
+                None,
+                AnswerStyle::Neutral,
+                None,
+            )
+            .await;
+
+        assert!(result.is_ok());
+        let stats = &evaluations(&metrics).await[0].stats;
+        assert_eq!((stats.calls, stats.candidate_errors), (1, 1));
+        assert_eq!(stats.mean_overlap(), None);
+    }
+}
@@ -0,0 +1,200 @@
+use std::sync::Arc;
+
+use crate::types::Message;
+
+/// Per-message overhead for role markers and separators, in tokens.
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+
+/// Counts tokens the way a given model would.
+pub trait Tokenizer: Send + Sync {
+    fn count_tokens(&self, text: &str) -> usize;
+
+    fn count_message_tokens(&self, messages: &[Message]) -> usize {
+        messages
+            .iter()
+            .map(|m| self.count_tokens(&m.content))
+            .sum::<usize>()
+            + messages.len() * MESSAGE_OVERHEAD_TOKENS
+    }
+
+    fn name(&self) -> &str;
+}
+
+/// Dependency-free estimate used when no real tokenizer is available.
+///
+/// ASCII text averages roughly four bytes per token, while non-ASCII scripts
+/// (CJK, Cyrillic, emoji, ...) are closer to one token per character, so the
+/// two are counted separately rather than dividing the byte length by four.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeuristicTokenizer;
+
+impl Tokenizer for HeuristicTokenizer {
+    fn count_tokens(&self, text: &str) -> usize {
+        let (ascii, other) = text.chars().fold((0usize, 0usize), |(ascii, other), c| {
+            if c.is_ascii() {
+                (ascii + 1, other)
+            } else {
+                (ascii, other + 1)
+            }
+        });
+        ascii.div_ceil(4) + other
+    }
+
+    fn name(&self) -> &str {
+        "heuristic"
+    }
+}
+
+/// Exact BPE token counts for OpenAI models via `tiktoken-rs`.
+#[cfg(feature = "tiktoken")]
+pub struct TiktokenTokenizer {
+    bpe: &'static tiktoken_rs::CoreBPE,
+    name: &'static str,
+}
+
+#[cfg(feature = "tiktoken")]
+impl TiktokenTokenizer {
+    /// Picks the encoding used by `model`, falling back to `o200k_base` for
+    /// models `tiktoken-rs` does not recognise.
+    pub fn for_model(model: &str) -> Self {
+        use tiktoken_rs::tokenizer::{get_tokenizer, Tokenizer as Encoding};
+
+        match get_tokenizer(model) {
+            Some(Encoding::Cl100kBase) => Self::cl100k_base(),
+            Some(Encoding::P50kBase) => Self {
+                bpe: tiktoken_rs::p50k_base_singleton(),
+                name: "p50k_base",
+            },
+            Some(Encoding::P50kEdit) => Self {
+                bpe: tiktoken_rs::p50k_edit_singleton(),
+                name: "p50k_edit",
+            },
+            Some(Encoding::R50kBase) | Some(Encoding::Gpt2) => Self {
+                bpe: tiktoken_rs::r50k_base_singleton(),
+                name: "r50k_base",
+            },
+            Some(Encoding::O200kBase) | None => Self {
+                bpe: tiktoken_rs::o200k_base_singleton(),
+                name: "o200k_base",
+            },
+        }
+    }
+
+    pub fn cl100k_base() -> Self {
+        Self {
+            bpe: tiktoken_rs::cl100k_base_singleton(),
+            name: "cl100k_base",
+        }
+    }
+}
+
+#[cfg(feature = "tiktoken")]
+impl Tokenizer for TiktokenTokenizer {
+    fn count_tokens(&self, text: &str) -> usize {
+        self.bpe.encode_with_special_tokens(text).len()
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+}
+
+/// Approximate token counts for Claude models.
+///
+/// Anthropic does not publish a local tokenizer for current Claude models.
+/// With the `tiktoken` feature the count is derived from `cl100k_base`, which
+/// tracks Claude closely after a small upward correction; without it the
+/// heuristic is used. Both err on the side of over-counting.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClaudeTokenizer;
+
+impl ClaudeTokenizer {
+    /// Claude's vocabulary splits text slightly finer than `cl100k_base`.
+    #[cfg(feature = "tiktoken")]
+    const CL100K_CORRECTION: f64 = 1.1;
+}
+
+impl Tokenizer for ClaudeTokenizer {
+    #[cfg(feature = "tiktoken")]
+    fn count_tokens(&self, text: &str) -> usize {
+        let base = TiktokenTokenizer::cl100k_base().count_tokens(text);
+        (base as f64 * Self::CL100K_CORRECTION).ceil() as usize
+    }
+
+    #[cfg(not(feature = "tiktoken"))]
+    fn count_tokens(&self, text: &str) -> usize {
+        HeuristicTokenizer.count_tokens(text)
+    }
+
+    fn name(&self) -> &str {
+        "claude-approx"
+    }
+}
+
+/// Returns the most accurate tokenizer available for a provider/model pair.
+pub fn tokenizer_for(provider: &str, model: &str) -> Arc<dyn Tokenizer> {
+    match provider {
+        "anthropic" => Arc::new(ClaudeTokenizer),
+        "bedrock" if model.starts_with("anthropic.") => Arc::new(ClaudeTokenizer),
+        #[cfg(feature = "tiktoken")]
+        "openai" => Arc::new(TiktokenTokenizer::for_model(model)),
+        _ => Arc::new(HeuristicTokenizer),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heuristic_counts_ascii_by_four() {
+        assert_eq!(HeuristicTokenizer.count_tokens(""), 0);
+        assert_eq!(HeuristicTokenizer.count_tokens("abcd"), 1);
+        assert_eq!(HeuristicTokenizer.count_tokens("abcde"), 2);
+    }
+
+    #[test]
+    fn heuristic_counts_non_ascii_per_char() {
+        let text = "東京は日本の首都です";
+        assert_eq!(HeuristicTokenizer.count_tokens(text), 10);
+        assert!(HeuristicTokenizer.count_tokens(text) > text.len() / 4);
+    }
+
+    #[test]
+    fn counts_message_overhead() {
+        let messages = vec![Message::system("abcd"), Message::user("abcd")];
+        assert_eq!(HeuristicTokenizer.count_message_tokens(&messages), 2 + 8);
+    }
+
+    #[test]
+    fn selects_claude_tokenizer_for_anthropic() {
+        assert_eq!(
+            tokenizer_for("anthropic", "claude-sonnet-4").name(),
+            "claude-approx"
+        );
+        assert_eq!(
+            tokenizer_for("bedrock", "anthropic.claude-sonnet-4-20250514-v1:0").name(),
+            "claude-approx"
+        );
+        assert_eq!(tokenizer_for("mock", "mock-model").name(), "heuristic");
+    }
+
+    #[cfg(feature = "tiktoken")]
+    #[test]
+    fn tiktoken_counts_openai_models() {
+        let tokenizer = tokenizer_for("openai", "gpt-4o");
+        assert_eq!(tokenizer.name(), "o200k_base");
+        assert_eq!(tokenizer.count_tokens("hello world"), 2);
+
+        let legacy = TiktokenTokenizer::for_model("gpt-4");
+        assert_eq!(legacy.name(), "cl100k_base");
+    }
+
+    #[cfg(feature = "tiktoken")]
+    #[test]
+    fn claude_estimate_exceeds_cl100k() {
+        let text = "The quick brown fox jumps over the lazy dog.";
+        let base = TiktokenTokenizer::cl100k_base().count_tokens(text);
+        assert!(ClaudeTokenizer.count_tokens(text) >= base);
+    }
+}
@@ -1,108 +1,19 @@
-use serde::{Deserialize, Serialize};
+//! Chat types shared with `gorkd-core`, re-exported for provider code.
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum Role {
-    System,
-    User,
-    Assistant,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Message {
-    pub role: Role,
-    pub content: String,
-}
-
-impl Message {
-    pub fn system(content: impl Into<String>) -> Self {
-        Self {
-            role: Role::System,
-            content: content.into(),
-        }
-    }
-
-    pub fn user(content: impl Into<String>) -> Self {
-        Self {
-            role: Role::User,
-            content: content.into(),
-        }
-    }
-
-    pub fn assistant(content: impl Into<String>) -> Self {
-        Self {
-            role: Role::Assistant,
-            content: content.into(),
-        }
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct ChatRequest {
-    pub model: String,
-    pub messages: Vec<Message>,
-    pub max_tokens: Option<usize>,
-    pub temperature: Option<f32>,
-}
-
-impl ChatRequest {
-    pub fn new(model: impl Into<String>, messages: Vec<Message>) -> Self {
-        Self {
-            model: model.into(),
-            messages,
-            max_tokens: None,
-            temperature: None,
-        }
-    }
-
-    pub fn with_max_tokens(mut self, max_tokens: usize) -> Self {
-        self.max_tokens = Some(max_tokens);
-        self
-    }
-
-    pub fn with_temperature(mut self, temperature: f32) -> Self {
-        self.temperature = Some(temperature.clamp(0.0, 2.0));
-        self
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct ChatResponse {
-    pub content: String,
-    pub model: String,
-    pub usage: TokenUsage,
-    pub finish_reason: FinishReason,
-}
-
-#[derive(Debug, Clone, Default)]
-pub struct TokenUsage {
-    pub prompt_tokens: usize,
-    pub completion_tokens: usize,
-}
+pub use gorkd_core::{ChatRequest, ChatResponse, FinishReason, Message, Role, TokenUsage};
 
-impl TokenUsage {
-    pub fn total(&self) -> usize {
-        self.prompt_tokens + self.completion_tokens
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum FinishReason {
-    Stop,
-    Length,
-    ContentFilter,
-    Unknown,
-}
-
-impl From<&str> for FinishReason {
-    fn from(s: &str) -> Self {
-        match s {
-            "stop" | "end_turn" => Self::Stop,
-            "length" | "max_tokens" => Self::Length,
-            "content_filter" => Self::ContentFilter,
-            _ => Self::Unknown,
-        }
-    }
+/// Splits system messages out of a conversation for APIs that take the system
+/// prompt as a separate field. Multiple system messages are joined with a
+/// blank line.
+pub(crate) fn split_system(messages: &[Message]) -> (String, Vec<&Message>) {
+    let system = messages
+        .iter()
+        .filter(|m| m.role == Role::System)
+        .map(|m| m.content.as_str())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let rest = messages.iter().filter(|m| m.role != Role::System).collect();
+    (system, rest)
 }
 
 #[cfg(test)]
@@ -110,54 +21,19 @@ mod tests {
     use super::*;
 
     #[test]
-    fn creates_messages() {
-        let system = Message::system("You are a helpful assistant");
-        let user = Message::user("Hello!");
-        let assistant = Message::assistant("Hi there!");
-
-        assert_eq!(system.role, Role::System);
-        assert_eq!(user.role, Role::User);
-        assert_eq!(assistant.role, Role::Assistant);
-    }
-
-    #[test]
-    fn chat_request_clamps_temperature() {
-        let request = ChatRequest::new("gpt-4", vec![]).with_temperature(3.0);
-        assert_eq!(request.temperature, Some(2.0));
-
-        let request = ChatRequest::new("gpt-4", vec![]).with_temperature(-1.0);
-        assert_eq!(request.temperature, Some(0.0));
-    }
-
-    #[test]
-    fn token_usage_calculates_total() {
-        let usage = TokenUsage {
-            prompt_tokens: 100,
-            completion_tokens: 50,
-        };
-        assert_eq!(usage.total(), 150);
-    }
-
-    #[test]
-    fn finish_reason_parses() {
-        assert_eq!(FinishReason::from("stop"), FinishReason::Stop);
-        assert_eq!(FinishReason::from("end_turn"), FinishReason::Stop);
-        assert_eq!(FinishReason::from("length"), FinishReason::Length);
-        assert_eq!(FinishReason::from("max_tokens"), FinishReason::Length);
-        assert_eq!(FinishReason::from("unknown"), FinishReason::Unknown);
-    }
-
-    #[test]
-    fn serializes_role() {
-        let json = serde_json::to_string(&Role::User).unwrap();
-        assert_eq!(json, "\"user\"");
-    }
-
-    #[test]
-    fn serializes_message() {
-        let msg = Message::user("Hello");
-        let json = serde_json::to_string(&msg).unwrap();
-        assert!(json.contains("\"role\":\"user\""));
-        assert!(json.contains("\"content\":\"Hello\""));
+    fn splits_system_messages() {
+        let messages = vec![
+            Message::system("Be brief"),
+            Message::user("Hello"),
+            Message::system("Answer in English"),
+            Message::assistant("Hi"),
+        ];
+
+        let (system, rest) = split_system(&messages);
+
+        assert_eq!(system, "Be brief\n\nAnswer in English");
+        assert_eq!(rest.len(), 2);
+        assert_eq!(rest[0].role, Role::User);
+        assert_eq!(rest[1].role, Role::Assistant);
     }
 }
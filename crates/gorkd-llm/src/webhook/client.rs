@@ -0,0 +1,187 @@
+use gorkd_core::LlmError;
+use reqwest::{Client, RequestBuilder, StatusCode};
+use tracing::instrument;
+
+use crate::client::with_trace_header;
+use crate::config::{WebhookLlmConfig, WebhookSchema};
+use crate::error::{map_openai_error, map_webhook_error, with_retry_after};
+use crate::openai::types::{
+    ChatCompletionRequest, ChatCompletionResponse, ChatMessage, FinishReason as OpenAiFinishReason,
+};
+use crate::types::{ChatResponse, FinishReason, Message, Role, TokenUsage};
+
+use super::types::{WebhookMessage, WebhookRequest, WebhookResponse};
+
+/// Sends completions to a webhook endpoint in the schema it speaks and
+/// normalizes the replies to [`ChatResponse`].
+pub struct WebhookClient {
+    http: Client,
+    url: String,
+    auth_header: String,
+    auth_value: Option<String>,
+    schema: WebhookSchema,
+}
+
+impl WebhookClient {
+    pub fn new(http: Client, config: &WebhookLlmConfig) -> Self {
+        Self {
+            http,
+            url: config.url.clone(),
+            auth_header: config.auth_header.clone(),
+            auth_value: config.auth_value(),
+            schema: config.schema,
+        }
+    }
+
+    fn post(&self) -> RequestBuilder {
+        let request =
+            with_trace_header(self.http.post(&self.url)).header("Content-Type", "application/json");
+        match &self.auth_value {
+            Some(value) => request.header(&self.auth_header, value),
+            None => request,
+        }
+    }
+
+    #[instrument(skip(self, messages), fields(model = %model, schema = self.schema.as_str()))]
+    pub async fn complete(
+        &self,
+        model: &str,
+        messages: &[Message],
+        max_tokens: usize,
+        temperature: Option<f32>,
+        json: bool,
+    ) -> Result<ChatResponse, LlmError> {
+        match self.schema {
+            WebhookSchema::Gorkd => {
+                let request = build_request(model, messages, max_tokens, temperature, json);
+                let body = self.send(&request, map_webhook_error).await?;
+                parse_response(&body, model)
+            }
+            WebhookSchema::OpenAi => {
+                let request = build_openai_request(model, messages, max_tokens, temperature, json);
+                let body = self.send(&request, map_openai_error).await?;
+                parse_openai_response(&body)
+            }
+        }
+    }
+
+    async fn send(
+        &self,
+        request: &impl serde::Serialize,
+        map_error: fn(StatusCode, &str) -> LlmError,
+    ) -> Result<String, LlmError> {
+        let response = self
+            .post()
+            .json(request)
+            .send()
+            .await
+            .map_err(crate::error::map_reqwest_error)?;
+
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response
+            .text()
+            .await
+            .map_err(crate::error::map_reqwest_error)?;
+
+        if !status.is_success() {
+            return Err(with_retry_after(map_error(status, &body), &headers));
+        }
+        Ok(body)
+    }
+}
+
+pub(super) fn build_request<'a>(
+    model: &'a str,
+    messages: &'a [Message],
+    max_tokens: usize,
+    temperature: Option<f32>,
+    json: bool,
+) -> WebhookRequest<'a> {
+    WebhookRequest {
+        model,
+        messages: messages.iter().map(WebhookMessage::from).collect(),
+        max_tokens,
+        temperature,
+        json,
+    }
+}
+
+pub(super) fn build_openai_request(
+    model: &str,
+    messages: &[Message],
+    max_tokens: usize,
+    temperature: Option<f32>,
+    json: bool,
+) -> ChatCompletionRequest {
+    let messages = messages
+        .iter()
+        .map(|message| match message.role {
+            Role::System => ChatMessage::system(&message.content),
+            Role::User => ChatMessage::user(&message.content),
+            Role::Assistant => ChatMessage::assistant(&message.content),
+        })
+        .collect();
+    let mut request = ChatCompletionRequest::new(model, messages).with_max_tokens(max_tokens);
+    if let Some(temperature) = temperature {
+        request = request.with_temperature(temperature);
+    }
+    if json {
+        request = request.with_json_mode();
+    }
+    request
+}
+
+/// Parses a reply in the gorkd schema. Replies that do not name a model
+/// are attributed to `model`.
+pub(super) fn parse_response(body: &str, model: &str) -> Result<ChatResponse, LlmError> {
+    let response: WebhookResponse = serde_json::from_str(body)
+        .map_err(|e| LlmError::Provider(format!("parse error: {}", e)))?;
+
+    Ok(ChatResponse {
+        content: response.content,
+        finish_reason: response
+            .finish_reason
+            .as_deref()
+            .map_or(FinishReason::Unknown, FinishReason::from),
+        usage: TokenUsage {
+            prompt_tokens: response.usage.prompt_tokens,
+            completion_tokens: response.usage.completion_tokens,
+        },
+        model: response.model.unwrap_or_else(|| model.to_string()),
+    })
+}
+
+pub(super) fn parse_openai_response(body: &str) -> Result<ChatResponse, LlmError> {
+    let response: ChatCompletionResponse = serde_json::from_str(body)
+        .map_err(|e| LlmError::Provider(format!("parse error: {}", e)))?;
+
+    Ok(ChatResponse {
+        content: response.text_content(),
+        finish_reason: match response.finish_reason() {
+            Some(OpenAiFinishReason::Stop) => FinishReason::Stop,
+            Some(OpenAiFinishReason::Length) => FinishReason::Length,
+            Some(OpenAiFinishReason::ContentFilter) => FinishReason::ContentFilter,
+            Some(OpenAiFinishReason::Unknown) | None => FinishReason::Unknown,
+        },
+        usage: TokenUsage {
+            prompt_tokens: response.usage.prompt_tokens,
+            completion_tokens: response.usage.completion_tokens,
+        },
+        model: response.model,
+    })
+}
+
+impl std::fmt::Debug for WebhookClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebhookClient")
+            .field("url", &self.url)
+            .field("auth_header", &self.auth_header)
+            .field(
+                "auth_value",
+                &self.auth_value.as_ref().map(|_| "[REDACTED]"),
+            )
+            .field("schema", &self.schema)
+            .finish()
+    }
+}
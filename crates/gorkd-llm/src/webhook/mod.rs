@@ -0,0 +1,316 @@
+//! Webhook provider for in-house models.
+//!
+//! A model served behind any HTTP endpoint can answer research questions
+//! without a provider of its own in this crate. The endpoint speaks either
+//! the OpenAI chat completions API ([`WebhookSchema::OpenAi`]) or the
+//! smaller gorkd contract ([`WebhookSchema::Gorkd`]), under which each
+//! request is POSTed as:
+//!
+//! ```json
+//! {
+//!   "model": "corp-llm-70b",
+//!   "messages": [
+//!     {"role": "system", "content": "You are a research assistant..."},
+//!     {"role": "user", "content": "Question: ..."}
+//!   ],
+//!   "max_tokens": 4096,
+//!   "temperature": 0.2,
+//!   "json": true
+//! }
+//! ```
+//!
+//! `temperature` is left out when the caller sets none. `json` asks for a
+//! reply that is a single JSON object, as synthesis expects. The endpoint
+//! answers with:
+//!
+//! ```json
+//! {
+//!   "content": "{\"summary\": \"...\", \"citations\": [...]}",
+//!   "finish_reason": "stop",
+//!   "usage": {"prompt_tokens": 1830, "completion_tokens": 412},
+//!   "model": "corp-llm-70b-2024-06"
+//! }
+//! ```
+//!
+//! Only `content` is required. Errors are non-2xx statuses with an optional
+//! `{"error": "<message>"}` body: 429 is retried as a rate limit, 413 means
+//! the prompt was too long, 404 that the model is unavailable. The
+//! configured API key is sent in the configured header with every request,
+//! along with `X-Gorkd-Trace-Id` when the request belongs to a job.
+//!
+//! [`WebhookSchema::OpenAi`]: crate::config::WebhookSchema::OpenAi
+//! [`WebhookSchema::Gorkd`]: crate::config::WebhookSchema::Gorkd
+
+mod client;
+pub mod types;
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use gorkd_core::{
+    AnswerSchema, AnswerStyle, LengthPolicy, LlmError, LlmExchange, LlmProvider, LlmStage,
+    ModelPricing, ResearchAnswer, Source, StageTokenUsage,
+};
+use reqwest::Client;
+use tracing::instrument;
+
+use crate::config::WebhookLlmConfig;
+use crate::parser::parse_synthesis_response;
+use crate::prompt::{
+    append_answer_schema, append_answer_style, build_synthesis_messages_for, PromptHardening,
+};
+use crate::tokenizer::{tokenizer_for, Tokenizer};
+use crate::types::{ChatRequest, ChatResponse, FinishReason};
+use client::WebhookClient;
+use types::DEFAULT_MAX_TOKENS;
+
+/// A model behind an endpoint implementing the contract in the module
+/// documentation. Its prompts are text only and it reports no pricing.
+pub struct WebhookLlmProvider {
+    client: WebhookClient,
+    model: String,
+    tokenizer: Arc<dyn Tokenizer>,
+    max_tokens: usize,
+    max_context_tokens: usize,
+    prompt_hardening: PromptHardening,
+}
+
+impl WebhookLlmProvider {
+    pub fn new(http: Client, config: &WebhookLlmConfig) -> Self {
+        Self {
+            client: WebhookClient::new(http, config),
+            model: config.model.clone(),
+            tokenizer: tokenizer_for("webhook", &config.model),
+            max_tokens: DEFAULT_MAX_TOKENS,
+            max_context_tokens: config.max_context_tokens,
+            prompt_hardening: PromptHardening::default(),
+        }
+    }
+
+    pub fn with_max_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    pub fn with_prompt_hardening(mut self, hardening: PromptHardening) -> Self {
+        self.prompt_hardening = hardening;
+        self
+    }
+}
+
+#[async_trait]
+impl LlmProvider for WebhookLlmProvider {
+    async fn synthesize(
+        &self,
+        query: &str,
+        sources: &[Source],
+    ) -> Result<ResearchAnswer, LlmError> {
+        self.synthesize_captured(query, sources, None, None, AnswerStyle::Neutral, None)
+            .await
+            .0
+    }
+
+    #[instrument(skip(self, sources, schema), fields(model = %self.model, source_count = sources.len()))]
+    async fn synthesize_captured(
+        &self,
+        query: &str,
+        sources: &[Source],
+        length: Option<&LengthPolicy>,
+        schema: Option<&AnswerSchema>,
+        style: AnswerStyle,
+        temperature: Option<f32>,
+    ) -> (Result<ResearchAnswer, LlmError>, LlmExchange) {
+        let start = Instant::now();
+
+        let mut messages =
+            build_synthesis_messages_for(query, sources, self.prompt_hardening, length);
+        if let Some(schema) = schema {
+            messages = append_answer_schema(messages, schema);
+        }
+        messages = append_answer_style(messages, style);
+        let max_tokens = length.map_or(self.max_tokens, |policy| policy.cap(self.max_tokens));
+
+        let response = self
+            .client
+            .complete(&self.model, &messages, max_tokens, temperature, true)
+            .await;
+        let mut exchange = LlmExchange {
+            messages,
+            raw_response: None,
+        };
+        let response = match response {
+            Ok(response) => response,
+            Err(e) => return (Err(e), exchange),
+        };
+
+        if response.finish_reason == FinishReason::Length {
+            tracing::warn!("response truncated due to max_tokens limit");
+        }
+
+        let result = parse_synthesis_response(
+            &response.content,
+            sources,
+            &self.model,
+            response.usage.total(),
+        )
+        .map(|mut answer| {
+            answer.synthesis_metadata.synthesis_duration = start.elapsed();
+            answer.synthesis_metadata.record_usage(StageTokenUsage::new(
+                LlmStage::Synthesis,
+                &self.model,
+                &response.usage,
+            ));
+            answer
+        })
+        .map_err(|e| LlmError::Provider(format!("failed to parse synthesis response: {}", e)));
+        exchange.raw_response = Some(response.content);
+
+        (result, exchange)
+    }
+
+    #[instrument(skip(self, request), fields(model = %self.model, message_count = request.messages.len()))]
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, LlmError> {
+        self.client
+            .complete(
+                &self.model,
+                &request.messages,
+                request.max_tokens.unwrap_or(self.max_tokens),
+                request.temperature,
+                false,
+            )
+            .await
+    }
+
+    fn parse_synthesis(&self, raw: &str, sources: &[Source]) -> Result<ResearchAnswer, LlmError> {
+        parse_synthesis_response(raw, sources, &self.model, 0)
+            .map_err(|e| LlmError::Provider(format!("failed to parse synthesis response: {}", e)))
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model
+    }
+
+    fn provider_name(&self) -> &str {
+        "webhook"
+    }
+
+    fn max_context_tokens(&self) -> usize {
+        self.max_context_tokens
+    }
+
+    fn count_tokens(&self, text: &str) -> usize {
+        self.tokenizer.count_tokens(text)
+    }
+
+    fn supports_streaming(&self) -> bool {
+        false
+    }
+
+    fn pricing(&self) -> Option<ModelPricing> {
+        None
+    }
+}
+
+impl std::fmt::Debug for WebhookLlmProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebhookLlmProvider")
+            .field("client", &self.client)
+            .field("model", &self.model)
+            .field("max_tokens", &self.max_tokens)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Message;
+
+    fn messages() -> Vec<Message> {
+        vec![
+            Message::system("Answer in JSON"),
+            Message::user("What is Rust?").with_images(vec!["https://example.com/a.png".into()]),
+        ]
+    }
+
+    #[test]
+    fn builds_gorkd_requests_without_images() {
+        let messages = messages();
+
+        let request = client::build_request("corp-70b", &messages, 512, None, true);
+
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "model": "corp-70b",
+                "messages": [
+                    {"role": "system", "content": "Answer in JSON"},
+                    {"role": "user", "content": "What is Rust?"}
+                ],
+                "max_tokens": 512,
+                "json": true
+            })
+        );
+    }
+
+    #[test]
+    fn builds_openai_requests() {
+        let request = client::build_openai_request("corp-70b", &messages(), 512, Some(0.2), true);
+
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["model"], "corp-70b");
+        assert_eq!(json["messages"][1]["content"], "What is Rust?");
+        assert_eq!(json["response_format"]["type"], "json_object");
+        assert_eq!(json["temperature"].as_f64().map(|t| t as f32), Some(0.2));
+    }
+
+    #[test]
+    fn parses_gorkd_responses() {
+        let full = client::parse_response(
+            r#"{"content": "{}", "finish_reason": "length",
+                "usage": {"prompt_tokens": 10, "completion_tokens": 5},
+                "model": "corp-70b-2024-06"}"#,
+            "corp-70b",
+        )
+        .unwrap();
+        assert_eq!(full.finish_reason, FinishReason::Length);
+        assert_eq!(full.usage.total(), 15);
+        assert_eq!(full.model, "corp-70b-2024-06");
+
+        let minimal = client::parse_response(r#"{"content": "Hi"}"#, "corp-70b").unwrap();
+        assert_eq!(minimal.content, "Hi");
+        assert_eq!(minimal.finish_reason, FinishReason::Unknown);
+        assert_eq!(minimal.usage.total(), 0);
+        assert_eq!(minimal.model, "corp-70b");
+
+        assert!(client::parse_response(r#"{"text": "Hi"}"#, "corp-70b").is_err());
+    }
+
+    #[test]
+    fn parses_openai_responses() {
+        let response = client::parse_openai_response(
+            r#"{"id": "cmpl-1", "object": "chat.completion", "created": 0, "model": "corp-70b",
+                "choices": [{"index": 0, "message": {"role": "assistant", "content": "Hi"},
+                             "finish_reason": "stop"}],
+                "usage": {"prompt_tokens": 3, "completion_tokens": 1, "total_tokens": 4}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(response.content, "Hi");
+        assert_eq!(response.finish_reason, FinishReason::Stop);
+        assert_eq!(response.usage.total(), 4);
+    }
+
+    #[test]
+    fn reports_configured_model() {
+        let config = WebhookLlmConfig::new("https://llm.corp.example/generate", "corp-70b")
+            .with_api_key("secret-key");
+        let provider = WebhookLlmProvider::new(Client::new(), &config);
+
+        assert_eq!(provider.model_id(), "corp-70b");
+        assert_eq!(provider.provider_name(), "webhook");
+        assert!(!provider.supports_vision());
+        assert!(!format!("{:?}", provider).contains("secret-key"));
+    }
+}
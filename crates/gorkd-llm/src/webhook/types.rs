@@ -0,0 +1,62 @@
+//! Request and response types of the gorkd webhook contract.
+//!
+//! See the [module documentation](super) for the contract itself.
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{Message, Role};
+
+/// Completion tokens requested when the caller sets no limit.
+pub const DEFAULT_MAX_TOKENS: usize = 4096;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookRequest<'a> {
+    pub model: &'a str,
+    pub messages: Vec<WebhookMessage<'a>>,
+    pub max_tokens: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Whether the reply must be a single JSON object.
+    pub json: bool,
+}
+
+/// A text-only message; source images are never sent to a webhook.
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookMessage<'a> {
+    pub role: Role,
+    pub content: &'a str,
+}
+
+impl<'a> From<&'a Message> for WebhookMessage<'a> {
+    fn from(message: &'a Message) -> Self {
+        Self {
+            role: message.role,
+            content: &message.content,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookResponse {
+    pub content: String,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+    #[serde(default)]
+    pub usage: WebhookUsage,
+    /// Model that answered, if the endpoint routes to several.
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WebhookUsage {
+    #[serde(default)]
+    pub prompt_tokens: usize,
+    #[serde(default)]
+    pub completion_tokens: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookErrorResponse {
+    pub error: String,
+}
@@ -1,6 +1,7 @@
 //! Integration tests for LLM providers.
 //!
-//! These tests make real API calls and require valid API keys.
+//! These tests make real API calls and require valid API keys (or, for
+//! Bedrock, `BEDROCK_REGION` plus AWS credentials).
 //! Run with: `cargo test -p gorkd-llm -- --ignored --test-threads=1`
 
 mod fixtures;
@@ -8,11 +9,13 @@ mod fixtures;
 use std::collections::HashSet;
 use std::sync::Arc;
 
-use gorkd_core::{Confidence, LlmError, LlmProvider, Source};
+use gorkd_core::{Confidence, KeyPool, LlmError, LlmProvider, Source};
 use gorkd_llm::anthropic::types::{MODEL_CLAUDE_HAIKU_35, MODEL_CLAUDE_SONNET_4};
+use gorkd_llm::bedrock::types::{MODEL_BEDROCK_CLAUDE_SONNET_4, MODEL_BEDROCK_LLAMA_31_70B};
 use gorkd_llm::openai::types::{MODEL_GPT_4O, MODEL_GPT_4O_MINI};
 use gorkd_llm::{
-    AnthropicConfig, AnthropicProvider, LlmConfig, LlmRegistry, OpenAiConfig, OpenAiProvider,
+    build_synthesis_messages_with, AnthropicConfig, AnthropicProvider, BedrockConfig,
+    BedrockProvider, LlmConfig, LlmRegistry, OpenAiConfig, OpenAiProvider, PromptHardening,
 };
 use reqwest::Client;
 use secrecy::SecretString;
@@ -35,7 +38,7 @@ fn create_http_client() -> Client {
 fn create_anthropic_provider(model: &str) -> Option<AnthropicProvider> {
     let api_key = get_anthropic_api_key()?;
     let config = AnthropicConfig {
-        api_key: SecretString::from(api_key),
+        api_keys: Arc::new(KeyPool::single(SecretString::from(api_key))),
         base_url: "https://api.anthropic.com".to_string(),
     };
     Some(AnthropicProvider::new(create_http_client(), &config, model))
@@ -44,12 +47,17 @@ fn create_anthropic_provider(model: &str) -> Option<AnthropicProvider> {
 fn create_openai_provider(model: &str) -> Option<OpenAiProvider> {
     let api_key = get_openai_api_key()?;
     let config = OpenAiConfig {
-        api_key: SecretString::from(api_key),
+        api_keys: Arc::new(KeyPool::single(SecretString::from(api_key))),
         base_url: "https://api.openai.com".to_string(),
     };
     Some(OpenAiProvider::new(create_http_client(), &config, model))
 }
 
+fn create_bedrock_provider(model: &str) -> Option<BedrockProvider> {
+    let config = BedrockConfig::from_env()?;
+    Some(BedrockProvider::new(create_http_client(), &config, model))
+}
+
 fn assert_valid_answer(
     answer: &gorkd_core::ResearchAnswer,
     sources: &[Source],
@@ -154,6 +162,40 @@ async fn openai_gpt4o_mini_synthesizes_answer() {
     assert_valid_answer(&answer, &sources, "gpt-4o-mini");
 }
 
+#[tokio::test]
+#[ignore = "requires BEDROCK_REGION and AWS credentials"]
+async fn bedrock_claude_synthesizes_answer() {
+    let Some(provider) = create_bedrock_provider(MODEL_BEDROCK_CLAUDE_SONNET_4) else {
+        eprintln!("Skipping: Bedrock not configured");
+        return;
+    };
+
+    let sources = fixtures::minimal_sources();
+    let answer = provider
+        .synthesize(fixtures::SIMPLE_QUERY, &sources)
+        .await
+        .expect("synthesis should succeed");
+
+    assert_valid_answer(&answer, &sources, "claude");
+}
+
+#[tokio::test]
+#[ignore = "requires BEDROCK_REGION and AWS credentials"]
+async fn bedrock_llama_synthesizes_answer() {
+    let Some(provider) = create_bedrock_provider(MODEL_BEDROCK_LLAMA_31_70B) else {
+        eprintln!("Skipping: Bedrock not configured");
+        return;
+    };
+
+    let sources = fixtures::minimal_sources();
+    let answer = provider
+        .synthesize(fixtures::SIMPLE_QUERY, &sources)
+        .await
+        .expect("synthesis should succeed");
+
+    assert_valid_answer(&answer, &sources, "llama");
+}
+
 #[tokio::test]
 #[ignore = "requires ANTHROPIC_API_KEY"]
 async fn anthropic_handles_empty_sources() {
@@ -287,7 +329,7 @@ async fn openai_validates_citations() {
 #[tokio::test]
 async fn invalid_anthropic_key_returns_auth_error() {
     let config = AnthropicConfig {
-        api_key: SecretString::from("sk-invalid-key-12345"),
+        api_keys: Arc::new(KeyPool::single(SecretString::from("sk-invalid-key-12345"))),
         base_url: "https://api.anthropic.com".to_string(),
     };
     let provider = AnthropicProvider::new(create_http_client(), &config, MODEL_CLAUDE_HAIKU_35);
@@ -312,7 +354,7 @@ async fn invalid_anthropic_key_returns_auth_error() {
 #[tokio::test]
 async fn invalid_openai_key_returns_auth_error() {
     let config = OpenAiConfig {
-        api_key: SecretString::from("sk-invalid-key-12345"),
+        api_keys: Arc::new(KeyPool::single(SecretString::from("sk-invalid-key-12345"))),
         base_url: "https://api.openai.com".to_string(),
     };
     let provider = OpenAiProvider::new(create_http_client(), &config, MODEL_GPT_4O_MINI);
@@ -355,11 +397,11 @@ async fn registry_fallback_on_rate_limit() {
     let http = create_http_client();
 
     let anthropic_config = AnthropicConfig {
-        api_key: SecretString::from(anthropic_key),
+        api_keys: Arc::new(KeyPool::single(SecretString::from(anthropic_key))),
         base_url: "https://api.anthropic.com".to_string(),
     };
     let openai_config = OpenAiConfig {
-        api_key: SecretString::from(openai_key),
+        api_keys: Arc::new(KeyPool::single(SecretString::from(openai_key))),
         base_url: "https://api.openai.com".to_string(),
     };
 
@@ -407,3 +449,56 @@ async fn registry_from_config_integration() {
 
     assert!(answer.is_answerable());
 }
+
+#[test]
+fn hardened_prompt_strips_adversarial_instructions() {
+    let sources = fixtures::adversarial_sources();
+
+    let messages =
+        build_synthesis_messages_with(fixtures::SIMPLE_QUERY, &sources, PromptHardening::Hardened);
+    let user = &messages[1].content;
+
+    assert!(!user.contains(fixtures::INJECTION_CANARY));
+    assert!(!user.contains("<|im_start|>"));
+    assert_eq!(user.matches("</source>").count(), sources.len());
+    assert!(user.contains("memory safety"));
+    assert!(user.contains("Graydon Hoare"));
+}
+
+#[tokio::test]
+#[ignore = "requires ANTHROPIC_API_KEY"]
+async fn anthropic_hardened_prompt_resists_injection() {
+    let Some(provider) = create_anthropic_provider(MODEL_CLAUDE_HAIKU_35) else {
+        eprintln!("Skipping: ANTHROPIC_API_KEY not set");
+        return;
+    };
+    let provider = provider.with_prompt_hardening(PromptHardening::Hardened);
+
+    let sources = fixtures::adversarial_sources();
+    let answer = provider
+        .synthesize(fixtures::SIMPLE_QUERY, &sources)
+        .await
+        .expect("synthesis should succeed");
+
+    assert!(!answer.summary.contains(fixtures::INJECTION_CANARY));
+    assert!(!answer.detail.contains(fixtures::INJECTION_CANARY));
+}
+
+#[tokio::test]
+#[ignore = "requires OPENAI_API_KEY"]
+async fn openai_hardened_prompt_resists_injection() {
+    let Some(provider) = create_openai_provider(MODEL_GPT_4O_MINI) else {
+        eprintln!("Skipping: OPENAI_API_KEY not set");
+        return;
+    };
+    let provider = provider.with_prompt_hardening(PromptHardening::Hardened);
+
+    let sources = fixtures::adversarial_sources();
+    let answer = provider
+        .synthesize(fixtures::SIMPLE_QUERY, &sources)
+        .await
+        .expect("synthesis should succeed");
+
+    assert!(!answer.summary.contains(fixtures::INJECTION_CANARY));
+    assert!(!answer.detail.contains(fixtures::INJECTION_CANARY));
+}
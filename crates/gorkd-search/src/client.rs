@@ -1,11 +1,12 @@
 #![allow(missing_docs)]
 
+use std::sync::OnceLock;
 use std::time::Duration;
 
-use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
+use gorkd_core::{current_trace_id, HttpOptions, TRACE_ID_HEADER};
+use reqwest::{Certificate, NoProxy, Proxy};
 use thiserror::Error;
 
-const DEFAULT_USER_AGENT: &str = concat!("gorkd/", env!("CARGO_PKG_VERSION"));
 const DEFAULT_TIMEOUT_SECS: u64 = 30;
 
 #[derive(Debug, Error)]
@@ -26,17 +27,35 @@ pub struct HttpClient {
 
 impl HttpClient {
     pub fn new(timeout: Duration) -> Result<Self, HttpClientError> {
-        let mut headers = HeaderMap::new();
-        headers.insert(USER_AGENT, HeaderValue::from_static(DEFAULT_USER_AGENT));
+        Self::with_options(timeout, &HttpOptions::default())
+    }
 
-        let client = reqwest::Client::builder()
-            .default_headers(headers)
+    /// Creates a client with the given User-Agent, proxy and TLS settings.
+    pub fn with_options(timeout: Duration, options: &HttpOptions) -> Result<Self, HttpClientError> {
+        let mut builder = reqwest::Client::builder()
+            .user_agent(options.user_agent())
             .timeout(timeout)
-            .connect_timeout(Duration::from_secs(10))
-            .build()?;
+            .connect_timeout(options.connect_timeout())
+            .tcp_keepalive(options.tcp_keepalive)
+            .danger_accept_invalid_certs(options.accept_invalid_certs);
+        if let Some(max) = options.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        if let Some(timeout) = options.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        if let Some(ref proxy) = options.proxy {
+            let no_proxy = options.no_proxy.as_deref().and_then(NoProxy::from_string);
+            builder = builder.proxy(Proxy::all(proxy)?.no_proxy(no_proxy));
+        }
+        if let Some(ref pem) = options.root_certificates {
+            for certificate in Certificate::from_pem_bundle(pem)? {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
 
         Ok(Self {
-            inner: client,
+            inner: builder.build()?,
             timeout,
         })
     }
@@ -53,18 +72,39 @@ impl HttpClient {
         self.timeout
     }
 
+    /// Starts a GET request, tagged with the current job's trace ID.
     pub fn get(&self, url: &str) -> reqwest::RequestBuilder {
-        self.inner.get(url)
+        with_trace_header(self.inner.get(url))
+    }
+
+    /// Starts a HEAD request, tagged with the current job's trace ID.
+    pub fn head(&self, url: &str) -> reqwest::RequestBuilder {
+        with_trace_header(self.inner.head(url))
     }
 
+    /// Starts a POST request, tagged with the current job's trace ID.
     pub fn post(&self, url: &str) -> reqwest::RequestBuilder {
-        self.inner.post(url)
+        with_trace_header(self.inner.post(url))
     }
 }
 
+fn with_trace_header(request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    match current_trace_id() {
+        Some(trace_id) => request.header(TRACE_ID_HEADER, trace_id.as_str()),
+        None => request,
+    }
+}
+
+/// A clone of one client built on first use, so providers created without a
+/// client share its connection pool instead of each opening their own.
 impl Default for HttpClient {
     fn default() -> Self {
-        Self::with_default_timeout().expect("failed to create default HTTP client")
+        static SHARED: OnceLock<HttpClient> = OnceLock::new();
+        SHARED
+            .get_or_init(|| {
+                Self::with_default_timeout().expect("failed to create default HTTP client")
+            })
+            .clone()
     }
 }
 
@@ -90,6 +130,26 @@ mod tests {
         assert_eq!(client.timeout(), Duration::from_secs(DEFAULT_TIMEOUT_SECS));
     }
 
+    #[test]
+    fn creates_client_with_options() {
+        let options = HttpOptions::new()
+            .with_user_agent("acme-research/1.0")
+            .with_proxy("socks5h://proxy.example:1080")
+            .with_no_proxy("localhost,.internal")
+            .with_pool_max_idle_per_host(16)
+            .with_pool_idle_timeout(Duration::from_secs(30))
+            .with_tcp_keepalive(Duration::from_secs(60));
+
+        assert!(HttpClient::with_options(Duration::from_secs(5), &options).is_ok());
+        assert!(matches!(
+            HttpClient::with_options(
+                Duration::from_secs(5),
+                &HttpOptions::new().with_user_agent("bad\nagent")
+            ),
+            Err(HttpClientError::Request(_))
+        ));
+    }
+
     #[test]
     fn exposes_inner_client() {
         let client = HttpClient::default();
@@ -107,4 +167,22 @@ mod tests {
         let client = HttpClient::default();
         let _request = client.post("https://example.com");
     }
+
+    #[tokio::test]
+    async fn tags_requests_with_current_trace_id() {
+        let client = HttpClient::default();
+        let trace_id = gorkd_core::TraceId::new();
+
+        let request = gorkd_core::with_trace_id(trace_id.clone(), async {
+            client.post("https://example.com").build().unwrap()
+        })
+        .await;
+        assert_eq!(
+            request.headers()[TRACE_ID_HEADER].to_str().unwrap(),
+            trace_id.as_str()
+        );
+
+        let request = client.get("https://example.com").build().unwrap();
+        assert!(request.headers().get(TRACE_ID_HEADER).is_none());
+    }
 }
@@ -0,0 +1,239 @@
+//! Conditional requests for pages downloaded again.
+//!
+//! Recurring research, re-crawls and feed polls download the same URLs over
+//! and over. A [`ValidatorCache`] keeps what was extracted from each response
+//! along with its `ETag` and `Last-Modified` validators; the next request for
+//! the URL sends them back as `If-None-Match` and `If-Modified-Since`, and a
+//! `304 Not Modified` answer is served from the cache instead of being
+//! downloaded and extracted again.
+
+use std::collections::{HashMap, VecDeque};
+use std::env;
+use std::sync::Mutex;
+
+use reqwest::header::{HeaderMap, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::RequestBuilder;
+
+/// Memory a cache may hold when `FETCH_CACHE_MB` is unset.
+pub const DEFAULT_VALIDATOR_CACHE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Reads `FETCH_CACHE_MB`; `0` turns conditional requests off.
+pub fn cache_bytes_from_env() -> usize {
+    env::var("FETCH_CACHE_MB")
+        .ok()
+        .and_then(|s| s.trim().parse::<usize>().ok())
+        .map_or(DEFAULT_VALIDATOR_CACHE_BYTES, |mb| {
+            mb.saturating_mul(1024 * 1024)
+        })
+}
+
+/// What a server said to identify a version of a resource.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Validators {
+    /// The `ETag` header.
+    pub etag: Option<String>,
+    /// The `Last-Modified` header.
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    /// The validators of a response.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_owned)
+        };
+        Self {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        }
+    }
+
+    /// Whether the response had neither validator, so it cannot be asked for
+    /// conditionally.
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+
+    /// Makes `request` conditional on the resource having changed.
+    pub fn apply(&self, mut request: RequestBuilder) -> RequestBuilder {
+        if let Some(etag) = &self.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &self.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+        request
+    }
+}
+
+/// Responses kept by URL with their validators, up to a memory budget. The
+/// oldest entries are dropped first once it is exceeded.
+pub struct ValidatorCache<T> {
+    max_bytes: usize,
+    state: Mutex<CacheState<T>>,
+}
+
+struct CacheState<T> {
+    entries: HashMap<String, Entry<T>>,
+    /// URLs in the order they were inserted, oldest first.
+    order: VecDeque<String>,
+    bytes: usize,
+}
+
+struct Entry<T> {
+    validators: Validators,
+    value: T,
+    bytes: usize,
+}
+
+impl<T: Clone> ValidatorCache<T> {
+    /// Creates a cache holding up to `max_bytes` of responses.
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            state: Mutex::new(CacheState {
+                entries: HashMap::new(),
+                order: VecDeque::new(),
+                bytes: 0,
+            }),
+        }
+    }
+
+    /// The validators and value kept for `url`.
+    pub fn get(&self, url: &str) -> Option<(Validators, T)> {
+        let state = self.state.lock().unwrap();
+        state
+            .entries
+            .get(url)
+            .map(|entry| (entry.validators.clone(), entry.value.clone()))
+    }
+
+    /// Keeps `value`, `bytes` long, for `url`. Responses without validators
+    /// and those larger than the whole budget are not kept, and forget what
+    /// was kept for the URL before.
+    pub fn insert(&self, url: &str, validators: Validators, value: T, bytes: usize) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(old) = state.entries.remove(url) {
+            state.bytes -= old.bytes;
+            state.order.retain(|key| key != url);
+        }
+        if validators.is_empty() || bytes > self.max_bytes {
+            return;
+        }
+
+        while state.bytes + bytes > self.max_bytes {
+            let Some(oldest) = state.order.pop_front() else {
+                break;
+            };
+            if let Some(entry) = state.entries.remove(&oldest) {
+                state.bytes -= entry.bytes;
+            }
+        }
+        state.bytes += bytes;
+        state.order.push_back(url.to_string());
+        state.entries.insert(
+            url.to_string(),
+            Entry {
+                validators,
+                value,
+                bytes,
+            },
+        );
+    }
+
+    /// Number of responses kept.
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    /// Whether no response is kept.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> std::fmt::Debug for ValidatorCache<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ValidatorCache")
+            .field("max_bytes", &self.max_bytes)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn etag(tag: &str) -> Validators {
+        Validators {
+            etag: Some(tag.to_string()),
+            last_modified: None,
+        }
+    }
+
+    #[test]
+    fn reads_validators_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, HeaderValue::from_static("\"v1\""));
+        headers.insert(
+            LAST_MODIFIED,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+
+        let validators = Validators::from_headers(&headers);
+
+        assert_eq!(validators.etag.as_deref(), Some("\"v1\""));
+        assert_eq!(
+            validators.last_modified.as_deref(),
+            Some("Wed, 21 Oct 2015 07:28:00 GMT")
+        );
+        assert!(Validators::from_headers(&HeaderMap::new()).is_empty());
+    }
+
+    #[test]
+    fn sends_validators_as_conditions() {
+        let validators = Validators {
+            etag: Some("\"v1\"".to_string()),
+            last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string()),
+        };
+
+        let request = validators
+            .apply(reqwest::Client::new().get("https://example.com/"))
+            .build()
+            .unwrap();
+
+        assert_eq!(request.headers()[IF_NONE_MATCH], "\"v1\"");
+        assert_eq!(
+            request.headers()[IF_MODIFIED_SINCE],
+            "Wed, 21 Oct 2015 07:28:00 GMT"
+        );
+    }
+
+    #[test]
+    fn drops_oldest_entries_over_budget() {
+        let cache = ValidatorCache::new(10);
+
+        cache.insert("https://a.com", etag("a"), "a", 4);
+        cache.insert("https://b.com", etag("b"), "b", 4);
+        cache.insert("https://c.com", etag("c"), "c", 4);
+
+        assert!(cache.get("https://a.com").is_none());
+        assert_eq!(cache.get("https://c.com"), Some((etag("c"), "c")));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn forgets_responses_without_validators() {
+        let cache = ValidatorCache::new(10);
+
+        cache.insert("https://a.com", etag("a"), "a", 4);
+        cache.insert("https://a.com", Validators::default(), "new", 4);
+        cache.insert("https://b.com", etag("b"), "too large", 11);
+
+        assert!(cache.is_empty());
+    }
+}
@@ -1,14 +1,23 @@
 #![allow(missing_docs)]
 
-use std::env;
 use std::time::Duration;
+use std::{env, fs};
 
+use gorkd_core::{
+    parse_weighted_keys, ContentLimits, ContentType, DomainPolicy, HttpOptions, TruncationStrategy,
+    WeightedKey, EXHAUSTIVE_MAX_SOURCES,
+};
 use thiserror::Error;
 
+use crate::client::{HttpClient, HttpClientError};
+use crate::routing::ProviderRoutes;
+use crate::tavily::TavilyOptions;
+use crate::webhook::WebhookConfig;
+
 #[derive(Debug, Error)]
 #[non_exhaustive]
 pub enum ConfigError {
-    #[error("no search providers configured - set at least one of: TAVILY_API_KEY, EXA_API_KEY, or SEARXNG_URL")]
+    #[error("no search providers configured - set at least one of: TAVILY_API_KEY, EXA_API_KEY, SEARXNG_URL, SEARCH_WEBHOOK_URL or SEARCH_WEBHOOKS_FILE")]
     NoProvidersConfigured,
 
     #[error("invalid SEARXNG_URL: {0}")]
@@ -23,27 +32,98 @@ const DEFAULT_MAX_RESULTS: usize = 10;
 
 #[derive(Clone, Debug)]
 pub struct SearchConfig {
-    pub tavily_api_key: Option<String>,
-    pub exa_api_key: Option<String>,
-    pub searxng_url: Option<String>,
+    /// Keys used in weighted turns, from the comma-separated
+    /// `TAVILY_API_KEY`; see [`parse_weighted_keys`].
+    pub tavily_api_keys: Vec<WeightedKey<String>>,
+    /// Keys used in weighted turns, from the comma-separated `EXA_API_KEY`.
+    pub exa_api_keys: Vec<WeightedKey<String>>,
+    /// Tavily extras, from `TAVILY_INCLUDE_ANSWER`,
+    /// `TAVILY_INCLUDE_RAW_CONTENT` and `TAVILY_INCLUDE_IMAGES`.
+    pub tavily_options: TavilyOptions,
+    /// SearXNG instances to rotate across, from comma-separated `SEARXNG_URL`.
+    pub searxng_urls: Vec<String>,
+    /// SearXNG engines to query, from `SEARXNG_ENGINES`; empty uses the
+    /// instance defaults.
+    pub searxng_engines: Vec<String>,
+    /// Custom search endpoints, from the JSON array in the file at
+    /// `SEARCH_WEBHOOKS_FILE` and the single endpoint at `SEARCH_WEBHOOK_URL`
+    /// (ID `webhook`, sent `SEARCH_WEBHOOK_TOKEN` as a bearer token).
+    pub webhooks: Vec<WebhookConfig>,
     pub timeout: Duration,
     pub max_results: usize,
+    /// Most sources a caller may ask a job to collect, from
+    /// `SEARCH_MAX_SOURCES_LIMIT`.
+    pub max_sources_limit: usize,
+    /// Sources kept from any one domain, from `SEARCH_MAX_PER_DOMAIN`;
+    /// unset or `0` keeps any number. Callers may lower it per job.
+    pub max_per_domain: Option<usize>,
+    /// Domains every job is limited to or kept from, whatever it asks for,
+    /// from comma-separated `SEARCH_ALLOW_DOMAINS` and `SEARCH_DENY_DOMAINS`.
+    pub domain_policy: DomainPolicy,
+    /// Expand sources with pages similar to the top-ranked ones, from
+    /// `SEARCH_EXPAND_SIMILAR`. Needs a provider that can find similar
+    /// pages, currently Exa.
+    pub expand_similar: bool,
+    /// Caps on fetched page content, from `SOURCE_MAX_BYTES`,
+    /// `SOURCES_MAX_TOTAL_BYTES` and `SOURCE_TRUNCATION`.
+    pub content_limits: ContentLimits,
+    /// Download pages that came back without content and extract their
+    /// text, from `SEARCH_FETCH_CONTENT`. Handles HTML, PDF, plain text and
+    /// markdown.
+    pub fetch_content: bool,
+    /// Read fetched pages that are gone, forbidden or paywalled from their
+    /// closest Wayback Machine snapshot, from `SEARCH_ARCHIVE_FALLBACK`.
+    pub archive_fallback: bool,
+    /// Use the transcripts of YouTube videos as their content, from
+    /// `SEARCH_YOUTUBE_TRANSCRIPTS`.
+    pub youtube_transcripts: bool,
+    /// Caption languages to prefer, from comma-separated
+    /// `YOUTUBE_TRANSCRIPT_LANGS` (default `en`).
+    pub youtube_transcript_languages: Vec<String>,
+    /// Providers tried first for each content type, from comma-separated
+    /// `SEARCH_ROUTE_<TYPE>` such as `SEARCH_ROUTE_ACADEMIC=exa,searxng`.
+    /// An empty value removes the type's default route.
+    pub routes: ProviderRoutes,
+    /// Configured provider to evaluate in shadow rather than search with,
+    /// from `SEARCH_SHADOW_PROVIDER`. It is sent every query alongside the
+    /// others and its results are only scored.
+    pub shadow_provider: Option<String>,
+    /// Proxy, TLS and connection pool settings of the client every provider
+    /// shares. Not read by [`from_env`](Self::from_env); set them with
+    /// [`with_http_options`](Self::with_http_options).
+    pub http: HttpOptions,
 }
 
 impl SearchConfig {
     pub fn from_env() -> Result<Self, ConfigError> {
-        let tavily_api_key = env::var("TAVILY_API_KEY").ok().filter(|s| !s.is_empty());
-        let exa_api_key = env::var("EXA_API_KEY").ok().filter(|s| !s.is_empty());
-        let searxng_url = env::var("SEARXNG_URL").ok().filter(|s| !s.is_empty());
-
-        if tavily_api_key.is_none() && exa_api_key.is_none() && searxng_url.is_none() {
+        let tavily_api_keys = env::var("TAVILY_API_KEY")
+            .map(|s| parse_weighted_keys(&s))
+            .unwrap_or_default();
+        let exa_api_keys = env::var("EXA_API_KEY")
+            .map(|s| parse_weighted_keys(&s))
+            .unwrap_or_default();
+        let searxng_urls = env::var("SEARXNG_URL")
+            .map(|s| parse_list(&s))
+            .unwrap_or_default();
+        let searxng_engines = env::var("SEARXNG_ENGINES")
+            .map(|s| parse_list(&s))
+            .unwrap_or_default();
+
+        let webhooks = webhooks_from_env()?;
+
+        if tavily_api_keys.is_empty()
+            && exa_api_keys.is_empty()
+            && searxng_urls.is_empty()
+            && webhooks.is_empty()
+        {
             return Err(ConfigError::NoProvidersConfigured);
         }
 
-        if let Some(ref url) = searxng_url {
-            if url::Url::parse(url).is_err() {
-                return Err(ConfigError::InvalidSearxngUrl(url.clone()));
-            }
+        if let Some(url) = searxng_urls
+            .iter()
+            .find(|url| url::Url::parse(url).is_err())
+        {
+            return Err(ConfigError::InvalidSearxngUrl(url.clone()));
         }
 
         let timeout_secs = env::var("SEARCH_TIMEOUT_SECS")
@@ -56,50 +136,247 @@ impl SearchConfig {
             .and_then(|s| s.parse::<usize>().ok())
             .unwrap_or(DEFAULT_MAX_RESULTS);
 
+        let max_sources_limit = env::var("SEARCH_MAX_SOURCES_LIMIT")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .filter(|&limit| limit > 0)
+            .unwrap_or(EXHAUSTIVE_MAX_SOURCES);
+
+        let max_per_domain = env::var("SEARCH_MAX_PER_DOMAIN")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .filter(|&max| max > 0);
+
+        let domain_policy = DomainPolicy {
+            allow: env::var("SEARCH_ALLOW_DOMAINS")
+                .map(|s| parse_list(&s))
+                .unwrap_or_default(),
+            deny: env::var("SEARCH_DENY_DOMAINS")
+                .map(|s| parse_list(&s))
+                .unwrap_or_default(),
+        };
+
+        let defaults = ContentLimits::default();
+        let strategy = match env::var("SOURCE_TRUNCATION") {
+            Ok(value) if !value.is_empty() => {
+                parse_truncation(&value).ok_or_else(|| ConfigError::InvalidValue {
+                    name: "SOURCE_TRUNCATION".to_string(),
+                    reason: format!("expected head, head_tail or sentence, got {:?}", value),
+                })?
+            }
+            _ => defaults.strategy,
+        };
+        let content_limits = ContentLimits {
+            max_source_bytes: env::var("SOURCE_MAX_BYTES")
+                .ok()
+                .and_then(|s| s.parse::<usize>().ok())
+                .unwrap_or(defaults.max_source_bytes),
+            max_total_bytes: env::var("SOURCES_MAX_TOTAL_BYTES")
+                .ok()
+                .and_then(|s| s.parse::<usize>().ok())
+                .unwrap_or(defaults.max_total_bytes),
+            strategy,
+        };
+
+        let youtube_transcript_languages = env::var("YOUTUBE_TRANSCRIPT_LANGS")
+            .map(|s| parse_list(&s))
+            .ok()
+            .filter(|languages| !languages.is_empty())
+            .unwrap_or_else(default_transcript_languages);
+
+        let tavily_options = TavilyOptions {
+            include_answer: env_flag("TAVILY_INCLUDE_ANSWER"),
+            include_raw_content: env_flag("TAVILY_INCLUDE_RAW_CONTENT"),
+            include_images: env_flag("TAVILY_INCLUDE_IMAGES"),
+        };
+
         Ok(Self {
-            tavily_api_key,
-            exa_api_key,
-            searxng_url,
+            tavily_api_keys,
+            exa_api_keys,
+            tavily_options,
+            searxng_urls,
+            searxng_engines,
+            webhooks,
             timeout: Duration::from_secs(timeout_secs),
             max_results,
+            max_sources_limit,
+            max_per_domain,
+            domain_policy,
+            expand_similar: env_flag("SEARCH_EXPAND_SIMILAR"),
+            content_limits,
+            fetch_content: env_flag("SEARCH_FETCH_CONTENT"),
+            archive_fallback: env_flag("SEARCH_ARCHIVE_FALLBACK"),
+            youtube_transcripts: env_flag("SEARCH_YOUTUBE_TRANSCRIPTS"),
+            youtube_transcript_languages,
+            routes: routes_from(|name| env::var(name).ok()),
+            shadow_provider: env::var("SEARCH_SHADOW_PROVIDER")
+                .ok()
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty()),
+            http: HttpOptions::default(),
         })
     }
 
+    pub fn with_http_options(mut self, options: HttpOptions) -> Self {
+        self.http = options;
+        self
+    }
+
+    /// Builds the client the providers share, with the search timeout and
+    /// the configured HTTP settings.
+    pub fn http_client(&self) -> Result<HttpClient, HttpClientError> {
+        HttpClient::with_options(self.timeout, &self.http)
+    }
+
     pub fn has_tavily(&self) -> bool {
-        self.tavily_api_key.is_some()
+        !self.tavily_api_keys.is_empty()
     }
 
     pub fn has_exa(&self) -> bool {
-        self.exa_api_key.is_some()
+        !self.exa_api_keys.is_empty()
     }
 
     pub fn has_searxng(&self) -> bool {
-        self.searxng_url.is_some()
+        !self.searxng_urls.is_empty()
+    }
+
+    pub fn has_webhooks(&self) -> bool {
+        !self.webhooks.is_empty()
     }
 
-    pub fn available_providers(&self) -> Vec<&'static str> {
+    pub fn available_providers(&self) -> Vec<String> {
         let mut providers = Vec::new();
         if self.has_tavily() {
-            providers.push("tavily");
+            providers.push("tavily".to_string());
         }
         if self.has_exa() {
-            providers.push("exa");
+            providers.push("exa".to_string());
         }
         if self.has_searxng() {
-            providers.push("searxng");
+            providers.push("searxng".to_string());
         }
+        providers.extend(self.webhooks.iter().map(|webhook| webhook.id.clone()));
         providers
     }
 }
 
+fn parse_truncation(value: &str) -> Option<TruncationStrategy> {
+    match value.to_lowercase().as_str() {
+        "head" => Some(TruncationStrategy::Head),
+        "head_tail" | "head-tail" => Some(TruncationStrategy::HeadTail),
+        "sentence" | "sentence_boundary" => Some(TruncationStrategy::SentenceBoundary),
+        _ => None,
+    }
+}
+
+/// Reads the webhook endpoints of `SEARCH_WEBHOOKS_FILE` and
+/// `SEARCH_WEBHOOK_URL`. Their IDs must be unique and differ from the
+/// built-in providers'.
+fn webhooks_from_env() -> Result<Vec<WebhookConfig>, ConfigError> {
+    let invalid = |name: &str, reason: String| ConfigError::InvalidValue {
+        name: name.to_string(),
+        reason,
+    };
+
+    let mut webhooks = match env::var("SEARCH_WEBHOOKS_FILE") {
+        Ok(path) if !path.is_empty() => {
+            let json = fs::read_to_string(&path)
+                .map_err(|e| invalid("SEARCH_WEBHOOKS_FILE", format!("{}: {}", path, e)))?;
+            WebhookConfig::parse_list(&json)
+                .map_err(|e| invalid("SEARCH_WEBHOOKS_FILE", format!("{}: {}", path, e)))?
+        }
+        _ => Vec::new(),
+    };
+    if let Some(url) = env::var("SEARCH_WEBHOOK_URL")
+        .ok()
+        .filter(|s| !s.is_empty())
+    {
+        let mut webhook = WebhookConfig::new("webhook", url);
+        if let Some(token) = env::var("SEARCH_WEBHOOK_TOKEN")
+            .ok()
+            .filter(|s| !s.is_empty())
+        {
+            webhook = webhook.with_bearer_token(token);
+        }
+        webhook
+            .validate()
+            .map_err(|e| invalid("SEARCH_WEBHOOK_URL", e))?;
+        webhooks.push(webhook);
+    }
+
+    for (i, webhook) in webhooks.iter().enumerate() {
+        let taken = crate::registry::PROVIDER_ORDER.contains(&webhook.id.as_str())
+            || webhooks[..i].iter().any(|other| other.id == webhook.id);
+        if taken {
+            return Err(invalid(
+                "SEARCH_WEBHOOKS_FILE",
+                format!("webhook id {} is already in use", webhook.id),
+            ));
+        }
+    }
+    Ok(webhooks)
+}
+
+/// The default routes with those of `SEARCH_ROUTE_<TYPE>` variables, read
+/// through `var`, in place.
+fn routes_from(var: impl Fn(&str) -> Option<String>) -> ProviderRoutes {
+    ContentType::ALL
+        .into_iter()
+        .fold(ProviderRoutes::default(), |routes, content_type| {
+            let name = format!(
+                "SEARCH_ROUTE_{}",
+                content_type.as_str().to_ascii_uppercase()
+            );
+            match var(&name) {
+                Some(value) => routes.with_route(content_type, parse_list(&value)),
+                None => routes,
+            }
+        })
+}
+
+fn default_transcript_languages() -> Vec<String> {
+    vec!["en".to_string()]
+}
+
+fn env_flag(name: &str) -> bool {
+    env::var(name)
+        .map(|s| matches!(s.to_lowercase().as_str(), "true" | "1" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+/// Splits a comma-separated environment value, dropping empty entries.
+pub(crate) fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
 impl Default for SearchConfig {
     fn default() -> Self {
         Self {
-            tavily_api_key: None,
-            exa_api_key: None,
-            searxng_url: None,
+            tavily_api_keys: Vec::new(),
+            exa_api_keys: Vec::new(),
+            tavily_options: TavilyOptions::default(),
+            searxng_urls: Vec::new(),
+            searxng_engines: Vec::new(),
+            webhooks: Vec::new(),
             timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
             max_results: DEFAULT_MAX_RESULTS,
+            max_sources_limit: EXHAUSTIVE_MAX_SOURCES,
+            max_per_domain: None,
+            domain_policy: DomainPolicy::default(),
+            expand_similar: false,
+            content_limits: ContentLimits::default(),
+            fetch_content: false,
+            archive_fallback: false,
+            youtube_transcripts: false,
+            youtube_transcript_languages: default_transcript_languages(),
+            routes: ProviderRoutes::default(),
+            shadow_provider: None,
+            http: HttpOptions::default(),
         }
     }
 }
@@ -113,8 +390,62 @@ mod tests {
         env::remove_var("TAVILY_API_KEY");
         env::remove_var("EXA_API_KEY");
         env::remove_var("SEARXNG_URL");
+        env::remove_var("SEARXNG_ENGINES");
         env::remove_var("SEARCH_TIMEOUT_SECS");
         env::remove_var("SEARCH_MAX_RESULTS");
+        env::remove_var("SEARCH_MAX_SOURCES_LIMIT");
+        env::remove_var("SEARCH_MAX_PER_DOMAIN");
+        env::remove_var("SEARCH_ALLOW_DOMAINS");
+        env::remove_var("SEARCH_DENY_DOMAINS");
+        env::remove_var("TAVILY_INCLUDE_ANSWER");
+        env::remove_var("TAVILY_INCLUDE_RAW_CONTENT");
+        env::remove_var("TAVILY_INCLUDE_IMAGES");
+        env::remove_var("SEARCH_EXPAND_SIMILAR");
+        env::remove_var("SOURCE_MAX_BYTES");
+        env::remove_var("SOURCES_MAX_TOTAL_BYTES");
+        env::remove_var("SOURCE_TRUNCATION");
+        env::remove_var("SEARCH_FETCH_CONTENT");
+        env::remove_var("SEARCH_YOUTUBE_TRANSCRIPTS");
+        env::remove_var("YOUTUBE_TRANSCRIPT_LANGS");
+        env::remove_var("SEARCH_SHADOW_PROVIDER");
+        env::remove_var("SEARCH_WEBHOOKS_FILE");
+        env::remove_var("SEARCH_WEBHOOK_URL");
+        env::remove_var("SEARCH_WEBHOOK_TOKEN");
+    }
+
+    #[test]
+    fn parses_truncation_strategies() {
+        assert_eq!(parse_truncation("head"), Some(TruncationStrategy::Head));
+        assert_eq!(
+            parse_truncation("Head_Tail"),
+            Some(TruncationStrategy::HeadTail)
+        );
+        assert_eq!(
+            parse_truncation("sentence"),
+            Some(TruncationStrategy::SentenceBoundary)
+        );
+        assert_eq!(parse_truncation("middle"), None);
+    }
+
+    #[test]
+    fn overrides_default_routes() {
+        let routes = routes_from(|name| match name {
+            "SEARCH_ROUTE_ACADEMIC" => Some("searxng, exa".to_string()),
+            "SEARCH_ROUTE_NEWS" => Some(String::new()),
+            "SEARCH_ROUTE_FORUM" => Some("searxng".to_string()),
+            _ => None,
+        });
+
+        assert_eq!(
+            routes.route(&ContentType::Academic),
+            Some(&["searxng".to_string(), "exa".to_string()][..])
+        );
+        assert!(routes.route(&ContentType::News).is_none());
+        assert_eq!(
+            routes.route(&ContentType::General),
+            ProviderRoutes::default().route(&ContentType::General)
+        );
+        assert!(routes.route(&ContentType::Forum).is_some());
     }
 
     #[test]
@@ -133,18 +464,46 @@ mod tests {
         assert!(config.has_tavily());
         assert!(!config.has_exa());
         assert!(!config.has_searxng());
-        assert_eq!(config.tavily_api_key.as_deref(), Some("tvly-test-key"));
+        assert_eq!(
+            config.tavily_api_keys,
+            vec![WeightedKey::new("tvly-test-key".to_string())]
+        );
+    }
+
+    #[test]
+    fn loads_tavily_options() {
+        clear_env();
+        env::set_var("TAVILY_API_KEY", "tvly-test-key");
+        env::set_var("TAVILY_INCLUDE_ANSWER", "true");
+        env::set_var("TAVILY_INCLUDE_RAW_CONTENT", "1");
+
+        let config = SearchConfig::from_env().unwrap();
+        assert_eq!(
+            config.tavily_options,
+            TavilyOptions {
+                include_answer: true,
+                include_raw_content: true,
+                include_images: false,
+            }
+        );
     }
 
     #[test]
     fn loads_exa_config() {
         clear_env();
-        env::set_var("EXA_API_KEY", "exa-test-key");
+        env::set_var("EXA_API_KEY", "exa-test-key:3, exa-spare-key");
 
         let config = SearchConfig::from_env().unwrap();
         assert!(!config.has_tavily());
         assert!(config.has_exa());
-        assert_eq!(config.exa_api_key.as_deref(), Some("exa-test-key"));
+        assert_eq!(
+            config.exa_api_keys,
+            vec![
+                WeightedKey::new("exa-test-key".to_string()).with_weight(3),
+                WeightedKey::new("exa-spare-key".to_string()),
+            ]
+        );
+        assert!(!config.expand_similar);
     }
 
     #[test]
@@ -154,7 +513,33 @@ mod tests {
 
         let config = SearchConfig::from_env().unwrap();
         assert!(config.has_searxng());
-        assert_eq!(config.searxng_url.as_deref(), Some("http://localhost:8080"));
+        assert_eq!(config.searxng_urls, vec!["http://localhost:8080"]);
+        assert!(config.searxng_engines.is_empty());
+    }
+
+    #[test]
+    fn loads_multiple_searxng_instances_and_engines() {
+        clear_env();
+        env::set_var("SEARXNG_URL", "https://a.example, https://b.example,");
+        env::set_var("SEARXNG_ENGINES", "google,bing, duckduckgo");
+
+        let config = SearchConfig::from_env().unwrap();
+        assert_eq!(
+            config.searxng_urls,
+            vec!["https://a.example", "https://b.example"]
+        );
+        assert_eq!(config.searxng_engines, vec!["google", "bing", "duckduckgo"]);
+    }
+
+    #[test]
+    fn rejects_any_invalid_searxng_url() {
+        clear_env();
+        env::set_var("SEARXNG_URL", "https://a.example,not-a-valid-url");
+
+        let result = SearchConfig::from_env();
+        assert!(
+            matches!(result, Err(ConfigError::InvalidSearxngUrl(url)) if url == "not-a-valid-url")
+        );
     }
 
     #[test]
@@ -188,6 +573,40 @@ mod tests {
         assert_eq!(config.max_results, 25);
     }
 
+    #[test]
+    fn loads_source_caps() {
+        clear_env();
+        env::set_var("TAVILY_API_KEY", "test");
+
+        let config = SearchConfig::from_env().unwrap();
+        assert_eq!(config.max_sources_limit, EXHAUSTIVE_MAX_SOURCES);
+        assert_eq!(config.max_per_domain, None);
+
+        env::set_var("SEARCH_MAX_SOURCES_LIMIT", "40");
+        env::set_var("SEARCH_MAX_PER_DOMAIN", "2");
+        let config = SearchConfig::from_env().unwrap();
+        assert_eq!(config.max_sources_limit, 40);
+        assert_eq!(config.max_per_domain, Some(2));
+
+        env::set_var("SEARCH_MAX_PER_DOMAIN", "0");
+        let config = SearchConfig::from_env().unwrap();
+        assert_eq!(config.max_per_domain, None);
+    }
+
+    #[test]
+    fn loads_domain_policy() {
+        clear_env();
+        env::set_var("TAVILY_API_KEY", "test");
+        env::set_var("SEARCH_DENY_DOMAINS", "competitor.com, , paywalled.example");
+
+        let config = SearchConfig::from_env().unwrap();
+        assert!(config.domain_policy.allow.is_empty());
+        assert_eq!(
+            config.domain_policy.deny,
+            vec!["competitor.com", "paywalled.example"]
+        );
+    }
+
     #[test]
     fn ignores_empty_env_vars() {
         clear_env();
@@ -210,6 +629,31 @@ mod tests {
         assert_eq!(providers, vec!["tavily", "exa"]);
     }
 
+    #[test]
+    fn loads_webhooks() {
+        clear_env();
+        let path = env::temp_dir().join(format!("gorkd-webhooks-{}.json", std::process::id()));
+        fs::write(
+            &path,
+            r#"[{"id": "confluence", "url": "https://adapter.corp.example/search"}]"#,
+        )
+        .unwrap();
+        env::set_var("SEARCH_WEBHOOKS_FILE", &path);
+        env::set_var("SEARCH_WEBHOOK_URL", "http://localhost:9200/search");
+        env::set_var("SEARCH_WEBHOOK_TOKEN", "secret");
+
+        let config = SearchConfig::from_env().unwrap();
+        assert_eq!(config.available_providers(), vec!["confluence", "webhook"]);
+        assert_eq!(config.webhooks[1].headers["Authorization"], "Bearer secret");
+
+        fs::write(&path, r#"[{"id": "exa", "url": "https://a.example/"}]"#).unwrap();
+        let result = SearchConfig::from_env();
+        fs::remove_file(&path).unwrap();
+        assert!(
+            matches!(result, Err(ConfigError::InvalidValue { ref reason, .. }) if reason.contains("already in use"))
+        );
+    }
+
     #[test]
     fn default_config_has_no_providers() {
         let config = SearchConfig::default();
@@ -0,0 +1,808 @@
+//! Website crawling for adding a site to the document corpus.
+//!
+//! A crawl starts from one page, following links breadth-first up to a
+//! depth, or from a `sitemap.xml`, whose pages are all starting points.
+//! Only pages on the seed's host are read, one at a time, and never a path
+//! the site's `robots.txt` disallows; its `Crawl-delay` is honored up to
+//! [`MAX_CRAWL_DELAY`]. Text is extracted as for fetched sources, so HTML,
+//! PDF, markdown and plain text pages are all kept.
+//!
+//! With a [`ValidatorCache`], pages read by an earlier crawl are asked for
+//! conditionally. One the server says has not changed is returned from the
+//! cache marked [`unchanged`](CrawledPage::unchanged), so it need not be
+//! embedded again, and its links are still followed.
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::StatusCode;
+use tracing::{debug, instrument};
+use url::Url;
+
+use crate::client::HttpClient;
+use crate::conditional::{ValidatorCache, Validators};
+use crate::fetch::{
+    attribute, detect_format, extract_text, find_tags, map_reqwest_error,
+    DEFAULT_MAX_DOWNLOAD_BYTES,
+};
+use gorkd_core::{
+    CrawlRequest, CrawledPage, DocumentFormat, SearchError, SiteCrawler, DEFAULT_USER_AGENT,
+};
+
+/// Longest pause between requests a site's `Crawl-delay` can ask for.
+pub const MAX_CRAWL_DELAY: Duration = Duration::from_secs(10);
+
+/// Downloads allowed per page asked for, bounding crawls of sites whose
+/// links mostly lead to errors or files without text.
+const DOWNLOADS_PER_PAGE: usize = 3;
+
+/// Nested sitemaps read from a sitemap index.
+const MAX_SITEMAPS: usize = 10;
+
+/// Links to these are never downloaded, as they have no text to extract.
+const SKIPPED_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "svg", "webp", "ico", "css", "js", "zip", "gz", "tar", "mp3",
+    "mp4", "webm", "woff", "woff2",
+];
+
+/// Crawls sites over HTTP.
+///
+/// Implements the `SiteCrawler` trait. Requests go through [`HttpClient`],
+/// and `robots.txt` rules are read for the product token of the client's
+/// User-Agent.
+pub struct HttpCrawler {
+    client: HttpClient,
+    robots_agent: String,
+    max_download_bytes: usize,
+    cache: Option<Arc<ValidatorCache<ReadPage>>>,
+}
+
+impl HttpCrawler {
+    /// Creates a crawler obeying `robots.txt` rules for `gorkd`.
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            robots_agent: product_token(DEFAULT_USER_AGENT),
+            max_download_bytes: DEFAULT_MAX_DOWNLOAD_BYTES,
+            cache: None,
+        }
+    }
+
+    /// Obeys the `robots.txt` rules for the product token of `user_agent`,
+    /// e.g. `acme-research` for `acme-research/1.0`, which should match the
+    /// User-Agent the client sends.
+    pub fn with_user_agent(mut self, user_agent: &str) -> Self {
+        self.robots_agent = product_token(user_agent);
+        self
+    }
+
+    /// Sets the largest response body that will be downloaded.
+    pub fn with_max_download_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_download_bytes = max_bytes;
+        self
+    }
+
+    /// Keeps up to `max_bytes` of page text to reuse when a page has not
+    /// changed since it was last crawled; `0` keeps none.
+    pub fn with_validator_cache(mut self, max_bytes: usize) -> Self {
+        self.cache = (max_bytes > 0).then(|| Arc::new(ValidatorCache::new(max_bytes)));
+        self
+    }
+
+    async fn download(&self, url: &Url) -> Result<Download, SearchError> {
+        self.download_if_changed(url, None)
+            .await
+            .map(|download| download.expect("unconditional requests are always answered"))
+    }
+
+    /// Downloads `url`, or returns `None` when `conditions` are given and
+    /// the server says it has not changed.
+    async fn download_if_changed(
+        &self,
+        url: &Url,
+        conditions: Option<&Validators>,
+    ) -> Result<Option<Download>, SearchError> {
+        let timeout_secs = self.client.timeout().as_secs();
+        let mut request = self.client.get(url.as_str());
+        if let Some(conditions) = conditions {
+            request = conditions.apply(request);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| map_reqwest_error(e, timeout_secs))?;
+
+        let status = response.status();
+        if status == StatusCode::NOT_MODIFIED && conditions.is_some() {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            return Err(SearchError::Provider(format!("HTTP {}", status)));
+        }
+        let too_large = || {
+            SearchError::Provider(format!(
+                "{} is larger than {} bytes",
+                url, self.max_download_bytes
+            ))
+        };
+        if response
+            .content_length()
+            .is_some_and(|len| len > self.max_download_bytes as u64)
+        {
+            return Err(too_large());
+        }
+
+        let final_url = response.url().clone();
+        let validators = Validators::from_headers(response.headers());
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned);
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| map_reqwest_error(e, timeout_secs))?;
+        if body.len() > self.max_download_bytes {
+            return Err(too_large());
+        }
+
+        Ok(Some(Download {
+            url: final_url,
+            content_type,
+            body: body.to_vec(),
+            validators,
+        }))
+    }
+
+    /// The site's rules for this crawler.
+    async fn robots(&self, site: &Url) -> RobotsRules {
+        read_robots(&self.client, site, &self.robots_agent).await
+    }
+
+    /// The pages listed by the sitemap at `url`, reading up to
+    /// [`MAX_SITEMAPS`] nested sitemaps of an index.
+    async fn sitemap_pages(&self, url: &Url, limit: usize) -> Result<Vec<Url>, SearchError> {
+        let sitemap = self.download(url).await?;
+        let mut entries = parse_sitemap(&String::from_utf8_lossy(&sitemap.body));
+        let mut pages = entries.pages;
+
+        entries.sitemaps.truncate(MAX_SITEMAPS);
+        for nested in entries.sitemaps {
+            if pages.len() >= limit {
+                break;
+            }
+            let Ok(nested) = Url::parse(&nested) else {
+                continue;
+            };
+            match self.download(&nested).await {
+                Ok(download) => {
+                    pages.extend(parse_sitemap(&String::from_utf8_lossy(&download.body)).pages)
+                }
+                Err(e) => debug!(sitemap = %nested, error = %e, "skipping nested sitemap"),
+            }
+        }
+
+        Ok(pages
+            .iter()
+            .filter_map(|page| Url::parse(page).ok())
+            .take(limit)
+            .collect())
+    }
+
+    /// Extracts the title, text and links of a downloaded page and keeps
+    /// them for the next crawl. `None` for pages off the site or without
+    /// text; errors only for the seed, whose failure fails the crawl.
+    async fn read(
+        &self,
+        url: &Url,
+        download: Download,
+        is_seed: bool,
+    ) -> Result<Option<ReadPage>, SearchError> {
+        if download.url.host_str() != url.host_str() {
+            debug!(%url, redirect = %download.url, "skipping page redirected off site");
+            return Ok(None);
+        }
+
+        let Some(format) = detect_format(
+            download.content_type.as_deref(),
+            download.url.as_str(),
+            &download.body,
+        ) else {
+            debug!(%url, content_type = ?download.content_type, "skipping page without text");
+            return Ok(None);
+        };
+
+        let mut title = None;
+        let mut links = Vec::new();
+        if format == DocumentFormat::Html {
+            let html = String::from_utf8_lossy(&download.body);
+            title = extract_title(&html);
+            links = extract_links(&html, &download.url);
+        }
+
+        // PDF parsing is CPU-bound and can take a while on large documents.
+        let text = match format {
+            DocumentFormat::Pdf => {
+                let body = download.body;
+                tokio::task::spawn_blocking(move || extract_text(format, &body))
+                    .await
+                    .map_err(|e| SearchError::Provider(format!("PDF extraction failed: {}", e)))?
+            }
+            _ => extract_text(format, &download.body),
+        };
+        let text = match text {
+            Ok(text) if !text.trim().is_empty() => text,
+            Ok(_) => return Ok(None),
+            Err(e) if is_seed => return Err(e),
+            Err(e) => {
+                debug!(%url, error = %e, "skipping page whose text could not be read");
+                return Ok(None);
+            }
+        };
+
+        let page = ReadPage {
+            url: download.url,
+            format,
+            title,
+            text,
+            links,
+        };
+        if let Some(cache) = &self.cache {
+            let bytes = page.text.len();
+            cache.insert(url.as_str(), download.validators, page.clone(), bytes);
+        }
+        Ok(Some(page))
+    }
+}
+
+struct Download {
+    /// Where the request ended up after redirects.
+    url: Url,
+    content_type: Option<String>,
+    body: Vec<u8>,
+    validators: Validators,
+}
+
+/// What a crawl read from a page, kept to reuse while the page is unchanged.
+#[derive(Clone)]
+struct ReadPage {
+    /// Where the request ended up after redirects.
+    url: Url,
+    format: DocumentFormat,
+    title: Option<String>,
+    text: String,
+    /// Every link of an HTML page, on the site or not.
+    links: Vec<Url>,
+}
+
+#[async_trait]
+impl SiteCrawler for HttpCrawler {
+    #[instrument(skip(self, request), fields(crawler = "http", seed = %request.seed))]
+    async fn crawl(&self, request: &CrawlRequest) -> Result<Vec<CrawledPage>, SearchError> {
+        let seed = Url::parse(&request.seed)
+            .ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https") && url.host_str().is_some())
+            .ok_or_else(|| SearchError::InvalidQuery {
+                reason: format!("not an http or https URL: {}", request.seed),
+            })?;
+        let host = seed.host_str().unwrap_or_default().to_string();
+        let robots = self.robots(&seed).await;
+        let delay = robots.crawl_delay.map(|delay| delay.min(MAX_CRAWL_DELAY));
+
+        let mut queue = VecDeque::new();
+        let mut seen = HashSet::new();
+        let from_sitemap = is_sitemap(&seed);
+        if from_sitemap {
+            let max_downloads = request.max_pages.saturating_mul(DOWNLOADS_PER_PAGE);
+            for page in self.sitemap_pages(&seed, max_downloads).await? {
+                if page.host_str() == Some(host.as_str()) && seen.insert(page_key(&page)) {
+                    queue.push_back((page, 0));
+                }
+            }
+        } else {
+            if !robots.allows(&seed) {
+                return Err(SearchError::Provider(format!(
+                    "robots.txt of {} disallows {}",
+                    host, seed
+                )));
+            }
+            seen.insert(page_key(&seed));
+            queue.push_back((seed.clone(), 0));
+        }
+
+        let mut pages = Vec::new();
+        let mut downloads = 0;
+        while let Some((url, depth)) = queue.pop_front() {
+            if pages.len() >= request.max_pages
+                || downloads >= request.max_pages.saturating_mul(DOWNLOADS_PER_PAGE)
+            {
+                break;
+            }
+            if !robots.allows(&url) {
+                debug!(%url, "skipping page disallowed by robots.txt");
+                continue;
+            }
+            if let Some(delay) = delay.filter(|_| downloads > 0) {
+                tokio::time::sleep(delay).await;
+            }
+            downloads += 1;
+
+            let is_seed = !from_sitemap && url == seed;
+            let cached = self
+                .cache
+                .as_ref()
+                .and_then(|cache| cache.get(url.as_str()));
+            let (page, unchanged) = match self
+                .download_if_changed(&url, cached.as_ref().map(|(validators, _)| validators))
+                .await
+            {
+                Ok(None) => match cached {
+                    Some((_, page)) => (page, true),
+                    None => continue,
+                },
+                Ok(Some(download)) => match self.read(&url, download, is_seed).await? {
+                    Some(page) => (page, false),
+                    None => continue,
+                },
+                Err(e) if is_seed => return Err(e),
+                Err(e) => {
+                    debug!(%url, error = %e, "skipping page that failed to download");
+                    continue;
+                }
+            };
+
+            if depth < request.max_depth {
+                for link in &page.links {
+                    if link.host_str() == Some(host.as_str()) && seen.insert(page_key(link)) {
+                        queue.push_back((link.clone(), depth + 1));
+                    }
+                }
+            }
+
+            let page_url = page.url.to_string();
+            let title = page.title.unwrap_or_else(|| page_url.clone());
+            pages.push(
+                CrawledPage::new(page_url, title, page.format, page.text).with_unchanged(unchanged),
+            );
+        }
+
+        debug!(
+            pages = pages.len(),
+            downloads,
+            unvisited = queue.len(),
+            "finished crawl"
+        );
+        Ok(pages)
+    }
+
+    fn crawler_name(&self) -> &str {
+        "http"
+    }
+}
+
+/// The rules of `site`'s `robots.txt` for `agent`. A missing `robots.txt`
+/// allows everything; one that cannot be read allows nothing.
+pub(crate) async fn read_robots(client: &HttpClient, site: &Url, agent: &str) -> RobotsRules {
+    let Ok(robots_url) = site.join("/robots.txt") else {
+        return RobotsRules::default();
+    };
+
+    let response = match client.get(robots_url.as_str()).send().await {
+        Ok(response) => response,
+        Err(e) => {
+            debug!(error = %e, "robots.txt unreachable, reading nothing");
+            return RobotsRules::disallow_all();
+        }
+    };
+    let status = response.status();
+    if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+        debug!(%status, "robots.txt unavailable, reading nothing");
+        return RobotsRules::disallow_all();
+    }
+    if !status.is_success() {
+        return RobotsRules::default();
+    }
+
+    match response.text().await {
+        Ok(text) => RobotsRules::parse(&text, agent),
+        Err(_) => RobotsRules::disallow_all(),
+    }
+}
+
+/// The product token of a User-Agent, lowercased: `gorkd` for `gorkd/0.1.0`.
+pub(crate) fn product_token(user_agent: &str) -> String {
+    user_agent
+        .split(['/', ' '])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+fn is_sitemap(url: &Url) -> bool {
+    url.path().to_ascii_lowercase().ends_with(".xml")
+}
+
+/// A page's address without its fragment, so `/a#x` and `/a#y` are crawled
+/// once.
+fn page_key(url: &Url) -> String {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    url.to_string()
+}
+
+/// The rules of one `robots.txt` that apply to one crawler.
+#[derive(Debug, Default)]
+pub(crate) struct RobotsRules {
+    /// Path patterns and whether each allows or disallows.
+    rules: Vec<(String, bool)>,
+    pub(crate) crawl_delay: Option<Duration>,
+}
+
+impl RobotsRules {
+    fn disallow_all() -> Self {
+        Self {
+            rules: vec![("/".to_string(), false)],
+            crawl_delay: None,
+        }
+    }
+
+    /// The rules of the groups naming `agent`, or of the `*` groups when
+    /// none does.
+    fn parse(text: &str, agent: &str) -> Self {
+        let mut groups: Vec<(Vec<String>, Self)> = Vec::new();
+        let mut reading_agents = false;
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+
+            match key.trim().to_ascii_lowercase().as_str() {
+                "user-agent" => {
+                    if !reading_agents {
+                        groups.push((Vec::new(), Self::default()));
+                    }
+                    if let Some((agents, _)) = groups.last_mut() {
+                        agents.push(value.to_ascii_lowercase());
+                    }
+                    reading_agents = true;
+                }
+                rule @ ("allow" | "disallow") => {
+                    reading_agents = false;
+                    // An empty `Disallow` allows everything, like no rule.
+                    if let Some((_, rules)) = groups.last_mut().filter(|_| !value.is_empty()) {
+                        rules.rules.push((value.to_string(), rule == "allow"));
+                    }
+                }
+                "crawl-delay" => {
+                    reading_agents = false;
+                    if let Some((_, rules)) = groups.last_mut() {
+                        rules.crawl_delay = value
+                            .parse::<f64>()
+                            .ok()
+                            .filter(|secs| secs.is_finite() && *secs >= 0.0)
+                            .map(Duration::from_secs_f64);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let named = groups
+            .iter()
+            .any(|(agents, _)| agents.iter().any(|a| a == agent));
+        let mut merged = Self::default();
+        for (agents, rules) in groups {
+            let applies = if named {
+                agents.iter().any(|a| a == agent)
+            } else {
+                agents.iter().any(|a| a == "*")
+            };
+            if applies {
+                merged.rules.extend(rules.rules);
+                merged.crawl_delay = merged.crawl_delay.or(rules.crawl_delay);
+            }
+        }
+        merged
+    }
+
+    /// Whether `url` may be crawled: the longest matching pattern decides,
+    /// and `Allow` wins a tie.
+    pub(crate) fn allows(&self, url: &Url) -> bool {
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+
+        self.rules
+            .iter()
+            .filter(|(pattern, _)| pattern_matches(pattern, &path))
+            .max_by_key(|(pattern, allow)| (pattern.len(), *allow))
+            .map_or(true, |(_, allow)| *allow)
+    }
+}
+
+/// Whether a `robots.txt` path pattern matches `path`. Patterns match path
+/// prefixes, `*` matches any run of characters and a trailing `$` anchors
+/// the pattern at the end of the path.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = path.strip_prefix(parts.next().unwrap_or_default()) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return !anchored || rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    if anchored {
+        rest.ends_with(last)
+    } else {
+        rest.contains(last)
+    }
+}
+
+/// The `<loc>` entries of a sitemap: pages of a `<urlset>`, or nested
+/// sitemaps of a `<sitemapindex>`.
+#[derive(Debug, Default, PartialEq)]
+struct SitemapEntries {
+    pages: Vec<String>,
+    sitemaps: Vec<String>,
+}
+
+fn parse_sitemap(xml: &str) -> SitemapEntries {
+    let mut locations = Vec::new();
+    let mut rest = xml;
+
+    while let Some(start) = rest.find("<loc>") {
+        rest = &rest[start + "<loc>".len()..];
+        let Some(end) = rest.find("</loc>") else {
+            break;
+        };
+        let location = rest[..end].trim();
+        let location = location
+            .strip_prefix("<![CDATA[")
+            .and_then(|l| l.strip_suffix("]]>"))
+            .unwrap_or(location);
+        if !location.is_empty() {
+            locations.push(decode_entities(location.trim()));
+        }
+        rest = &rest[end..];
+    }
+
+    if xml.contains("<sitemapindex") {
+        SitemapEntries {
+            pages: Vec::new(),
+            sitemaps: locations,
+        }
+    } else {
+        SitemapEntries {
+            pages: locations,
+            sitemaps: Vec::new(),
+        }
+    }
+}
+
+/// The text of a page's `<title>`, with whitespace collapsed.
+fn extract_title(html: &str) -> Option<String> {
+    // ASCII lowercasing keeps byte offsets, so matches index into `html`.
+    let lower = html.to_ascii_lowercase();
+    let open = lower.find("<title")?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+
+    let title = decode_entities(&html[start..end]);
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!title.is_empty()).then_some(title)
+}
+
+/// The http(s) links of a page, resolved against `page_url`, leaving out
+/// `rel="nofollow"` links and links to images, media and archives.
+fn extract_links(html: &str, page_url: &Url) -> Vec<Url> {
+    let lower = html.to_ascii_lowercase();
+
+    find_tags(html, &lower, "a", 0..html.len())
+        .filter(|tag| {
+            !attribute(tag, "rel").is_some_and(|rel| {
+                rel.split_whitespace()
+                    .any(|r| r.eq_ignore_ascii_case("nofollow"))
+            })
+        })
+        .filter_map(|tag| attribute(tag, "href"))
+        .filter_map(|href| page_url.join(&href).ok())
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .filter(|url| {
+            !url.path().rsplit_once('.').is_some_and(|(_, ext)| {
+                SKIPPED_EXTENSIONS
+                    .iter()
+                    .any(|skipped| ext.eq_ignore_ascii_case(skipped))
+            })
+        })
+        .map(|mut url| {
+            url.set_fragment(None);
+            url
+        })
+        .collect()
+}
+
+pub(crate) fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn reads_product_token_of_user_agent() {
+        assert_eq!(product_token("gorkd/0.1.0"), "gorkd");
+        assert_eq!(
+            product_token("Acme-Research/1.0 (+https://acme.example)"),
+            "acme-research"
+        );
+    }
+
+    #[test]
+    fn robots_rules_prefer_the_named_group() {
+        let robots = "\
+            User-agent: *\n\
+            Disallow: /\n\
+            \n\
+            # Our crawler may read the docs.\n\
+            User-agent: Gorkd\n\
+            User-agent: other-bot\n\
+            Disallow: /private/\n\
+            Allow: /private/public-page\n\
+            Crawl-delay: 2\n";
+
+        let rules = RobotsRules::parse(robots, "gorkd");
+
+        assert!(rules.allows(&url("https://docs.example.com/guide")));
+        assert!(!rules.allows(&url("https://docs.example.com/private/keys")));
+        assert!(rules.allows(&url("https://docs.example.com/private/public-page")));
+        assert_eq!(rules.crawl_delay, Some(Duration::from_secs(2)));
+
+        let rules = RobotsRules::parse(robots, "someone-else");
+        assert!(!rules.allows(&url("https://docs.example.com/guide")));
+    }
+
+    #[test]
+    fn robots_rules_default_to_allowing() {
+        let rules = RobotsRules::parse("User-agent: *\nDisallow:\n", "gorkd");
+        assert!(rules.allows(&url("https://docs.example.com/anything")));
+
+        let rules = RobotsRules::parse("", "gorkd");
+        assert!(rules.allows(&url("https://docs.example.com/anything")));
+
+        assert!(!RobotsRules::disallow_all().allows(&url("https://docs.example.com/")));
+    }
+
+    #[test]
+    fn robots_patterns_support_wildcards_and_anchors() {
+        assert!(pattern_matches("/docs", "/docs/install"));
+        assert!(!pattern_matches("/docs", "/blog"));
+        assert!(pattern_matches("/*.pdf$", "/papers/a.pdf"));
+        assert!(!pattern_matches("/*.pdf$", "/papers/a.pdf?download=1"));
+        assert!(pattern_matches("/*?print=", "/guide?print=1"));
+        assert!(pattern_matches("/search$", "/search"));
+        assert!(!pattern_matches("/search$", "/search/more"));
+    }
+
+    #[test]
+    fn robots_rules_match_query_strings() {
+        let rules = RobotsRules::parse("User-agent: *\nDisallow: /*?session=\n", "gorkd");
+
+        assert!(rules.allows(&url("https://docs.example.com/guide")));
+        assert!(!rules.allows(&url("https://docs.example.com/guide?session=abc")));
+    }
+
+    #[test]
+    fn parses_sitemaps_and_sitemap_indexes() {
+        let urlset = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+              <url><loc>https://docs.example.com/</loc></url>
+              <url><loc> https://docs.example.com/search?q=a&amp;b=c </loc><lastmod>2024-01-01</lastmod></url>
+              <url><loc><![CDATA[https://docs.example.com/cdata]]></loc></url>
+            </urlset>"#;
+        assert_eq!(
+            parse_sitemap(urlset).pages,
+            vec![
+                "https://docs.example.com/",
+                "https://docs.example.com/search?q=a&b=c",
+                "https://docs.example.com/cdata",
+            ]
+        );
+
+        let index = r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+              <sitemap><loc>https://docs.example.com/sitemap-1.xml</loc></sitemap>
+            </sitemapindex>"#;
+        assert_eq!(
+            parse_sitemap(index),
+            SitemapEntries {
+                pages: Vec::new(),
+                sitemaps: vec!["https://docs.example.com/sitemap-1.xml".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn extracts_page_title() {
+        let html = "<html><head><TITLE>\n  Install &amp; configure\n</TITLE></head></html>";
+        assert_eq!(extract_title(html).as_deref(), Some("Install & configure"));
+
+        assert_eq!(extract_title("<html><title> </title></html>"), None);
+        assert_eq!(extract_title("<html><body>No title</body></html>"), None);
+    }
+
+    #[test]
+    fn extracts_followable_links() {
+        let html = r#"<body>
+            <a href="/guide/install#step-2">Install</a>
+            <a class="next" href='configure'>Configure</a>
+            <a href="https://other.example/">Elsewhere</a>
+            <a href="/login" rel="nofollow">Log in</a>
+            <a href="/diagram.PNG">Diagram</a>
+            <a href="mailto:docs@example.com">Mail</a>
+            <a name="anchor">No link</a>
+            <abbr title="not a link">A</abbr>
+        </body>"#;
+
+        let links = extract_links(html, &url("https://docs.example.com/guide/"));
+
+        let links: Vec<String> = links.iter().map(Url::to_string).collect();
+        assert_eq!(
+            links,
+            vec![
+                "https://docs.example.com/guide/install",
+                "https://docs.example.com/guide/configure",
+                "https://other.example/",
+            ]
+        );
+    }
+
+    #[test]
+    fn recognizes_sitemap_seeds_and_page_keys() {
+        assert!(is_sitemap(&url("https://docs.example.com/sitemap.xml")));
+        assert!(!is_sitemap(&url("https://docs.example.com/guide")));
+        assert_eq!(
+            page_key(&url("https://docs.example.com/guide#intro")),
+            "https://docs.example.com/guide"
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_seeds_that_are_not_web_urls() {
+        let crawler = HttpCrawler::new(HttpClient::default());
+
+        let result = crawler
+            .crawl(&CrawlRequest::new("ftp://docs.example.com/", 10, 1))
+            .await;
+
+        assert!(matches!(result, Err(SearchError::InvalidQuery { .. })));
+    }
+}
@@ -3,25 +3,29 @@
 //! Exa offers semantic/neural search capabilities for understanding query intent
 //! and finding conceptually relevant results. API docs: <https://docs.exa.ai/reference/search>
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use tracing::{debug, instrument, warn};
 
 use crate::client::HttpClient;
-use gorkd_core::{Recency, SearchQuery};
+use gorkd_core::{KeyOutcome, KeyPool, Recency, SearchQuery, WeightedKey};
 use gorkd_core::{SearchError, SearchProvider, SearchResult};
 
 const EXA_API_URL: &str = "https://api.exa.ai/search";
+const EXA_FIND_SIMILAR_URL: &str = "https://api.exa.ai/findSimilar";
 const PROVIDER_ID: &str = "exa";
 
 /// Exa search provider.
 ///
 /// Implements the `SearchProvider` trait for Exa's semantic search API.
-/// Supports date filtering, domain filtering, and neural/keyword search modes.
+/// Supports date filtering, domain filtering, and neural/keyword search modes,
+/// and finding pages similar to a given URL.
 #[derive(Clone)]
 pub struct ExaProvider {
-    api_key: String,
+    api_keys: Arc<KeyPool<String>>,
     client: HttpClient,
     search_type: SearchType,
 }
@@ -30,7 +34,7 @@ impl ExaProvider {
     /// Creates a new Exa provider with the given API key.
     pub fn new(api_key: impl Into<String>) -> Self {
         Self {
-            api_key: api_key.into(),
+            api_keys: Arc::new(KeyPool::single(api_key.into())),
             client: HttpClient::default(),
             search_type: SearchType::Auto,
         }
@@ -39,12 +43,22 @@ impl ExaProvider {
     /// Creates a new Exa provider with a custom HTTP client.
     pub fn with_client(api_key: impl Into<String>, client: HttpClient) -> Self {
         Self {
-            api_key: api_key.into(),
+            api_keys: Arc::new(KeyPool::single(api_key.into())),
             client,
             search_type: SearchType::Auto,
         }
     }
 
+    /// Uses `keys` in weighted turns in place of the single key, moving on
+    /// to the next when one is rate limited or rejected. An empty list
+    /// keeps the single key.
+    pub fn with_api_keys(mut self, keys: Vec<WeightedKey<String>>) -> Self {
+        if let Some(pool) = KeyPool::new(keys) {
+            self.api_keys = Arc::new(pool);
+        }
+        self
+    }
+
     /// Sets the search type for queries.
     ///
     /// - `Auto` (default): Intelligently combines neural and other methods
@@ -64,6 +78,7 @@ impl ExaProvider {
             exclude_domains: None,
             start_published_date: None,
             end_published_date: None,
+            user_location: query.filters.country.clone(),
             text: true,
         };
 
@@ -72,6 +87,14 @@ impl ExaProvider {
             request.start_published_date = Some(recency_to_start_date(recency));
         }
 
+        // Absolute dates are more specific than a recency window.
+        if let Some(after) = query.filters.published_after {
+            request.start_published_date = Some(format_date(after));
+        }
+        if let Some(before) = query.filters.published_before {
+            request.end_published_date = Some(format_date(before));
+        }
+
         // Map domain filters
         if let Some(ref domains) = query.filters.include_domains {
             if !domains.is_empty() {
@@ -87,64 +110,149 @@ impl ExaProvider {
 
         request
     }
-}
 
-#[async_trait]
-impl SearchProvider for ExaProvider {
-    #[instrument(skip(self), fields(provider = PROVIDER_ID))]
-    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>, SearchError> {
-        let request = self.build_request(query);
+    fn build_find_similar_request(url: &str, max_results: usize) -> FindSimilarRequest {
+        FindSimilarRequest {
+            url: url.to_string(),
+            num_results: max_results.min(u8::MAX as usize) as u8,
+            exclude_source_domain: true,
+            text: true,
+        }
+    }
 
-        debug!(
-            query = %request.query,
-            search_type = ?request.search_type,
-            "executing exa search"
-        );
+    /// Sends `body` to an Exa endpoint and maps the results.
+    async fn send<T: Serialize>(
+        &self,
+        endpoint: &str,
+        body: &T,
+    ) -> Result<Vec<SearchResult>, SearchError> {
+        parse_response(&self.send_raw(endpoint, body).await?)
+    }
+
+    /// Sends `body` to an Exa endpoint and returns the body of a successful
+    /// response.
+    async fn send_raw<T: Serialize>(
+        &self,
+        endpoint: &str,
+        body: &T,
+    ) -> Result<String, SearchError> {
+        self.api_keys
+            .run(|key| self.send_with_key(key, endpoint, body))
+            .await
+    }
 
-        let response = self
+    /// Sends `body` to an Exa endpoint with `key`, reporting how the key
+    /// fared.
+    async fn send_with_key<T: Serialize>(
+        &self,
+        key: String,
+        endpoint: &str,
+        body: &T,
+    ) -> (Result<String, SearchError>, KeyOutcome) {
+        let timeout_secs = self.client.timeout().as_secs();
+        let response = match self
             .client
-            .post(EXA_API_URL)
-            .header("x-api-key", &self.api_key)
+            .post(endpoint)
+            .header("x-api-key", key)
             .header("Content-Type", "application/json")
-            .json(&request)
+            .json(body)
             .send()
             .await
-            .map_err(|e| map_reqwest_error(e, self.client.timeout().as_secs()))?;
+        {
+            Ok(response) => response,
+            Err(e) => return (Err(map_reqwest_error(e, timeout_secs)), KeyOutcome::Failed),
+        };
 
         let status = response.status();
+        let outcome = KeyOutcome::for_status(status.as_u16(), None);
 
         if !status.is_success() {
-            return Err(map_http_error(status));
+            return (Err(map_http_error(status)), outcome);
         }
 
-        let exa_response: ExaResponse = response.json().await.map_err(|e| {
-            warn!(error = %e, "failed to parse exa response");
-            SearchError::Provider(format!("failed to parse response: {}", e))
-        })?;
+        let body = response
+            .text()
+            .await
+            .map_err(|e| map_reqwest_error(e, timeout_secs));
+        (body, outcome)
+    }
+}
+
+fn parse_response(body: &str) -> Result<Vec<SearchResult>, SearchError> {
+    let exa_response: ExaResponse = serde_json::from_str(body).map_err(|e| {
+        warn!(error = %e, "failed to parse exa response");
+        SearchError::Provider(format!("failed to parse response: {}", e))
+    })?;
+
+    debug!(
+        result_count = exa_response.results.len(),
+        request_id = %exa_response.request_id.as_deref().unwrap_or("unknown"),
+        "exa request completed"
+    );
+
+    Ok(map_results(exa_response.results))
+}
+
+#[async_trait]
+impl SearchProvider for ExaProvider {
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>, SearchError> {
+        self.search_captured(query).await.0
+    }
+
+    #[instrument(skip(self), fields(provider = PROVIDER_ID))]
+    async fn search_captured(
+        &self,
+        query: &SearchQuery,
+    ) -> (Result<Vec<SearchResult>, SearchError>, Option<String>) {
+        let request = self.build_request(query);
 
         debug!(
-            result_count = exa_response.results.len(),
-            request_id = %exa_response.request_id.as_deref().unwrap_or("unknown"),
-            "exa search completed"
+            query = %request.query,
+            search_type = ?request.search_type,
+            "executing exa search"
         );
 
-        let results = exa_response
-            .results
-            .into_iter()
-            .map(|r| {
-                let snippet = r.text.unwrap_or_default();
-                let normalized_score = normalize_score(r.score);
-                SearchResult::new(r.url, r.title, snippet).with_score(normalized_score)
-            })
-            .collect();
+        match self.send_raw(EXA_API_URL, &request).await {
+            Ok(body) => (parse_response(&body), Some(body)),
+            Err(e) => (Err(e), None),
+        }
+    }
 
-        Ok(results)
+    fn parse_raw(
+        &self,
+        provider: &str,
+        _query: &SearchQuery,
+        raw: &str,
+    ) -> Result<Vec<SearchResult>, SearchError> {
+        if provider != PROVIDER_ID {
+            return Err(SearchError::ProviderUnavailable {
+                provider: provider.to_string(),
+            });
+        }
+        parse_response(raw)
+    }
+
+    #[instrument(skip(self), fields(provider = PROVIDER_ID))]
+    async fn find_similar(
+        &self,
+        url: &str,
+        max_results: usize,
+    ) -> Result<Vec<SearchResult>, SearchError> {
+        let request = Self::build_find_similar_request(url, max_results);
+
+        debug!(url = %request.url, "executing exa findSimilar");
+
+        self.send(EXA_FIND_SIMILAR_URL, &request).await
     }
 
     fn provider_id(&self) -> &str {
         PROVIDER_ID
     }
 
+    fn supports_find_similar(&self) -> bool {
+        true
+    }
+
     fn supports_recency_filter(&self) -> bool {
         true
     }
@@ -189,11 +297,26 @@ struct ExaRequest {
     start_published_date: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     end_published_date: Option<String>,
+    /// Two-letter country code results are localized for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user_location: Option<String>,
+    /// Request text content in results.
+    text: bool,
+}
+
+/// Request body for Exa findSimilar API.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FindSimilarRequest {
+    url: String,
+    num_results: u8,
+    /// Skip pages from the seed's own site, which rarely corroborate it.
+    exclude_source_domain: bool,
     /// Request text content in results.
     text: bool,
 }
 
-/// Response from Exa search API.
+/// Response from Exa search and findSimilar APIs.
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct ExaResponse {
@@ -212,7 +335,6 @@ struct ExaResult {
     #[serde(default)]
     score: Option<f32>,
     #[serde(default)]
-    #[allow(dead_code)]
     published_date: Option<String>,
 }
 
@@ -220,6 +342,21 @@ struct ExaResult {
 // Mapping Functions
 // ============================================================================
 
+fn map_results(results: Vec<ExaResult>) -> Vec<SearchResult> {
+    results
+        .into_iter()
+        .map(|r| {
+            let snippet = r.text.unwrap_or_default();
+            let normalized_score = normalize_score(r.score);
+            let result = SearchResult::new(r.url, r.title, snippet).with_score(normalized_score);
+            match r.published_date.as_deref().and_then(parse_date) {
+                Some(published_at) => result.with_published_at(published_at),
+                None => result,
+            }
+        })
+        .collect()
+}
+
 /// Converts a Recency enum to an ISO 8601 start date string.
 fn recency_to_start_date(recency: &Recency) -> String {
     let now = Utc::now();
@@ -231,7 +368,17 @@ fn recency_to_start_date(recency: &Recency) -> String {
         Recency::Any => return now.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
         _ => now - Duration::days(365), // Default to year for unknown variants
     };
-    start.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+    format_date(start)
+}
+
+fn format_date(date: DateTime<Utc>) -> String {
+    date.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+}
+
+fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(date)
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
 }
 
 /// Normalizes Exa scores to a 0.0-1.0 range.
@@ -292,6 +439,25 @@ mod tests {
         assert_eq!(provider.provider_id(), "exa");
         assert!(provider.supports_recency_filter());
         assert!(provider.supports_domain_filter());
+        assert!(provider.supports_find_similar());
+    }
+
+    #[test]
+    fn serializes_find_similar_request() {
+        let request = ExaProvider::build_find_similar_request("https://example.com/page", 3);
+
+        let json = serde_json::to_string(&request).unwrap();
+
+        assert!(json.contains("\"url\":\"https://example.com/page\""));
+        assert!(json.contains("\"numResults\":3"));
+        assert!(json.contains("\"excludeSourceDomain\":true"));
+        assert!(json.contains("\"text\":true"));
+    }
+
+    #[test]
+    fn clamps_find_similar_result_count() {
+        let request = ExaProvider::build_find_similar_request("https://example.com", 1000);
+        assert_eq!(request.num_results, u8::MAX);
     }
 
     #[test]
@@ -325,6 +491,29 @@ mod tests {
         assert!(date_str.ends_with("Z"));
     }
 
+    #[test]
+    fn builds_request_with_date_range() {
+        let provider = ExaProvider::new("test-key");
+        let after = "2023-03-01T00:00:00Z".parse().unwrap();
+        let before = "2023-07-01T00:00:00Z".parse().unwrap();
+        let query = SearchQuery::new("test").with_filters(
+            SearchFilters::new()
+                .with_recency(Recency::Week)
+                .published_between(Some(after), Some(before)),
+        );
+
+        let request = provider.build_request(&query);
+
+        assert_eq!(
+            request.start_published_date.as_deref(),
+            Some("2023-03-01T00:00:00.000Z")
+        );
+        assert_eq!(
+            request.end_published_date.as_deref(),
+            Some("2023-07-01T00:00:00.000Z")
+        );
+    }
+
     #[test]
     fn builds_request_with_domain_filters() {
         let provider = ExaProvider::new("test-key");
@@ -401,6 +590,7 @@ mod tests {
             exclude_domains: None,
             start_published_date: Some("2024-01-01T00:00:00.000Z".to_string()),
             end_published_date: None,
+            user_location: Some("DE".to_string()),
             text: true,
         };
 
@@ -412,6 +602,7 @@ mod tests {
         assert!(json.contains("\"includeDomains\":[\"example.com\"]"));
         assert!(json.contains("\"startPublishedDate\":\"2024-01-01T00:00:00.000Z\""));
         assert!(json.contains("\"text\":true"));
+        assert!(json.contains("\"userLocation\":\"DE\""));
         // excludeDomains and endPublishedDate should be skipped when None
         assert!(!json.contains("excludeDomains"));
         assert!(!json.contains("endPublishedDate"));
@@ -468,6 +659,25 @@ mod tests {
         assert_eq!(response.results[0].published_date, None);
     }
 
+    #[test]
+    fn maps_published_dates() {
+        let json = r#"{
+            "results": [
+                {"title": "A", "url": "https://a.example", "publishedDate": "2024-06-15T00:00:00.000Z"},
+                {"title": "B", "url": "https://b.example", "publishedDate": "June 2024"}
+            ]
+        }"#;
+        let response: ExaResponse = serde_json::from_str(json).unwrap();
+
+        let results = map_results(response.results);
+
+        assert_eq!(
+            results[0].published_at.unwrap().to_rfc3339(),
+            "2024-06-15T00:00:00+00:00"
+        );
+        assert!(results[1].published_at.is_none());
+    }
+
     #[test]
     fn recency_produces_valid_dates() {
         let recencies = [
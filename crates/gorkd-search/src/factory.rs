@@ -0,0 +1,214 @@
+//! Factories that build search providers from configuration.
+//!
+//! [`ProviderRegistry::from_config`](crate::ProviderRegistry::from_config)
+//! does not know the providers it registers: it runs every factory of a
+//! [`ProviderFactories`] list, in order, and registers what each one builds.
+//! The built-in providers are factories like any other, each behind a cargo
+//! feature of the same name, so a new provider is added by adding its
+//! factory, in this crate or in the program embedding it.
+
+use std::sync::Arc;
+
+use gorkd_core::traits::SearchProvider;
+
+use crate::client::HttpClient;
+use crate::config::SearchConfig;
+
+/// Builds the providers of one kind that `config` sets up, with their IDs,
+/// sending requests through the shared client. Returns none when the kind
+/// is not configured.
+pub type ProviderFactory =
+    Arc<dyn Fn(&SearchConfig, &HttpClient) -> Vec<(String, Arc<dyn SearchProvider>)> + Send + Sync>;
+
+/// Provider factories in priority order.
+#[derive(Clone, Default)]
+pub struct ProviderFactories {
+    factories: Vec<(String, ProviderFactory)>,
+}
+
+impl ProviderFactories {
+    /// No factories at all.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The factories of the providers compiled in, in the order of
+    /// [`PROVIDER_ORDER`](crate::PROVIDER_ORDER), then webhooks.
+    pub fn builtin() -> Self {
+        #[allow(unused_mut)]
+        let mut factories = Self::new();
+        #[cfg(feature = "tavily")]
+        {
+            factories = factories.with("tavily", tavily);
+        }
+        #[cfg(feature = "exa")]
+        {
+            factories = factories.with("exa", exa);
+        }
+        #[cfg(feature = "searxng")]
+        {
+            factories = factories.with("searxng", searxng);
+        }
+        #[cfg(feature = "webhook")]
+        {
+            factories = factories.with("webhook", webhooks);
+        }
+        factories
+    }
+
+    /// Adds the factory of providers of `kind` after the others, or puts it
+    /// in place of the factory already added for `kind`.
+    pub fn with(
+        mut self,
+        kind: impl Into<String>,
+        factory: impl Fn(&SearchConfig, &HttpClient) -> Vec<(String, Arc<dyn SearchProvider>)>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        let kind = kind.into();
+        let factory: ProviderFactory = Arc::new(factory);
+        match self.factories.iter_mut().find(|(k, _)| *k == kind) {
+            Some(entry) => entry.1 = factory,
+            None => self.factories.push((kind, factory)),
+        }
+        self
+    }
+
+    /// Drops the factory of providers of `kind`.
+    pub fn without(mut self, kind: &str) -> Self {
+        self.factories.retain(|(k, _)| k != kind);
+        self
+    }
+
+    /// The kinds of provider there are factories for, in order.
+    pub fn kinds(&self) -> Vec<&str> {
+        self.factories
+            .iter()
+            .map(|(kind, _)| kind.as_str())
+            .collect()
+    }
+
+    /// Runs every factory in order, returning each provider with its ID and
+    /// the kind of its factory.
+    pub fn build(
+        &self,
+        config: &SearchConfig,
+        client: &HttpClient,
+    ) -> Vec<(String, &str, Arc<dyn SearchProvider>)> {
+        self.factories
+            .iter()
+            .flat_map(|(kind, factory)| {
+                factory(config, client)
+                    .into_iter()
+                    .map(move |(id, provider)| (id, kind.as_str(), provider))
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for ProviderFactories {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProviderFactories")
+            .field("kinds", &self.kinds())
+            .finish()
+    }
+}
+
+#[cfg(feature = "tavily")]
+fn tavily(config: &SearchConfig, client: &HttpClient) -> Vec<(String, Arc<dyn SearchProvider>)> {
+    let Some(first) = config.tavily_api_keys.first() else {
+        return Vec::new();
+    };
+    let provider = crate::TavilyProvider::with_client(&first.key, client.clone())
+        .with_api_keys(config.tavily_api_keys.clone())
+        .with_options(config.tavily_options);
+    vec![("tavily".to_string(), Arc::new(provider))]
+}
+
+#[cfg(feature = "exa")]
+fn exa(config: &SearchConfig, client: &HttpClient) -> Vec<(String, Arc<dyn SearchProvider>)> {
+    let Some(first) = config.exa_api_keys.first() else {
+        return Vec::new();
+    };
+    let provider = crate::ExaProvider::with_client(&first.key, client.clone())
+        .with_api_keys(config.exa_api_keys.clone());
+    vec![("exa".to_string(), Arc::new(provider))]
+}
+
+#[cfg(feature = "searxng")]
+fn searxng(config: &SearchConfig, client: &HttpClient) -> Vec<(String, Arc<dyn SearchProvider>)> {
+    if !config.has_searxng() {
+        return Vec::new();
+    }
+    let provider = crate::SearxngProvider::from_instances(&config.searxng_urls)
+        .with_http_client(client.clone())
+        .with_engines(&config.searxng_engines);
+    tracing::debug!(
+        instances = ?config.searxng_urls,
+        engines = ?config.searxng_engines,
+        "configured SearXNG"
+    );
+    vec![("searxng".to_string(), Arc::new(provider))]
+}
+
+#[cfg(feature = "webhook")]
+fn webhooks(config: &SearchConfig, client: &HttpClient) -> Vec<(String, Arc<dyn SearchProvider>)> {
+    config
+        .webhooks
+        .iter()
+        .map(|webhook| {
+            let provider =
+                crate::WebhookSearchProvider::with_client(webhook.clone(), client.clone())
+                    .with_max_results(config.max_results);
+            (
+                webhook.id.clone(),
+                Arc::new(provider) as Arc<dyn SearchProvider>,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use gorkd_core::traits::{SearchError, SearchResult};
+    use gorkd_core::SearchQuery;
+
+    struct NamedProvider(&'static str);
+
+    #[async_trait]
+    impl SearchProvider for NamedProvider {
+        async fn search(&self, _query: &SearchQuery) -> Result<Vec<SearchResult>, SearchError> {
+            Ok(vec![])
+        }
+
+        fn provider_id(&self) -> &str {
+            self.0
+        }
+    }
+
+    fn named(id: &'static str) -> Vec<(String, Arc<dyn SearchProvider>)> {
+        vec![(id.to_string(), Arc::new(NamedProvider(id)))]
+    }
+
+    #[test]
+    fn replaces_factories_of_the_same_kind_in_place() {
+        let factories = ProviderFactories::new()
+            .with("a", |_, _| named("a"))
+            .with("b", |_, _| named("b"))
+            .with("a", |_, _| named("a2"));
+
+        assert_eq!(factories.kinds(), vec!["a", "b"]);
+        let client = HttpClient::with_default_timeout().unwrap();
+        let built = factories.build(&SearchConfig::default(), &client);
+        let ids: Vec<_> = built
+            .iter()
+            .map(|(id, kind, _)| (id.as_str(), *kind))
+            .collect();
+        assert_eq!(ids, vec![("a2", "a"), ("b", "b")]);
+
+        assert_eq!(factories.without("a").kinds(), vec!["b"]);
+    }
+}
@@ -1,55 +1,104 @@
 //! Fallback search provider that tries multiple providers in order.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use tracing::{debug, info, warn};
 
-use gorkd_core::traits::{SearchError, SearchProvider, SearchResult};
-use gorkd_core::SearchQuery;
+use gorkd_core::traits::{SearchError, SearchProvider, SearchReport, SearchResult};
+use gorkd_core::{ContentType, SearchQuery};
 
 use crate::ProviderRegistry;
 
 /// A search provider that tries multiple providers in priority order.
 pub struct FallbackSearchProvider {
     providers: Vec<Arc<dyn SearchProvider>>,
+    /// Orders for queries filtered to a content type, in place of
+    /// `providers`.
+    routes: HashMap<ContentType, Vec<Arc<dyn SearchProvider>>>,
 }
 
 impl FallbackSearchProvider {
     /// Creates a new fallback provider from a list of providers.
     pub fn new(providers: Vec<Arc<dyn SearchProvider>>) -> Self {
-        Self { providers }
+        Self {
+            providers,
+            routes: HashMap::new(),
+        }
     }
 
-    /// Creates a fallback provider from a registry using its priority order.
+    /// Creates a fallback provider from a registry using its priority order,
+    /// and its routes for queries filtered to a content type.
     pub fn from_registry(registry: &ProviderRegistry) -> Self {
-        Self::new(registry.providers_in_order())
+        registry.routes().content_types().into_iter().fold(
+            Self::new(registry.providers_in_order()),
+            |fallback, content_type| {
+                let providers = registry.providers_for(&content_type);
+                fallback.with_route(content_type, providers)
+            },
+        )
+    }
+
+    /// Tries `providers` in order for queries filtered to `content_type`.
+    pub fn with_route(
+        mut self,
+        content_type: ContentType,
+        providers: Vec<Arc<dyn SearchProvider>>,
+    ) -> Self {
+        self.routes.insert(content_type, providers);
+        self
+    }
+
+    /// The providers `query` is tried with, in order.
+    fn providers_for(&self, query: &SearchQuery) -> &[Arc<dyn SearchProvider>] {
+        query
+            .filters
+            .content_type
+            .as_ref()
+            .and_then(|content_type| self.routes.get(content_type))
+            .unwrap_or(&self.providers)
     }
 }
 
 #[async_trait]
 impl SearchProvider for FallbackSearchProvider {
     async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>, SearchError> {
-        if self.providers.is_empty() {
-            return Err(SearchError::ProviderUnavailable {
-                provider: "none".to_string(),
-            });
+        self.search_reported(query).await.result
+    }
+
+    async fn search_reported(&self, query: &SearchQuery) -> SearchReport {
+        let providers = self.providers_for(query);
+        if providers.is_empty() {
+            return SearchReport {
+                result: Err(SearchError::ProviderUnavailable {
+                    provider: "none".to_string(),
+                }),
+                attempts: Vec::new(),
+            };
         }
 
+        let mut attempts = Vec::new();
         let mut last_error: Option<SearchError> = None;
 
-        for provider in &self.providers {
+        for provider in providers {
             let provider_id = provider.provider_id();
             debug!(provider = %provider_id, query = %query.text, "attempting search");
 
-            match provider.search(query).await {
+            let report = provider.search_reported(query).await;
+            attempts.extend(report.attempts);
+
+            match report.result {
                 Ok(results) => {
                     info!(
                         provider = %provider_id,
                         results = results.len(),
                         "search succeeded"
                     );
-                    return Ok(results);
+                    return SearchReport {
+                        result: Ok(results),
+                        attempts,
+                    };
                 }
                 Err(e) => {
                     warn!(
@@ -64,16 +113,38 @@ impl SearchProvider for FallbackSearchProvider {
                         continue;
                     }
 
-                    return Err(e);
+                    return SearchReport {
+                        result: Err(e),
+                        attempts,
+                    };
                 }
             }
         }
 
-        Err(
-            last_error.unwrap_or_else(|| SearchError::ProviderUnavailable {
-                provider: "all".to_string(),
-            }),
-        )
+        SearchReport {
+            result: Err(
+                last_error.unwrap_or_else(|| SearchError::ProviderUnavailable {
+                    provider: "all".to_string(),
+                }),
+            ),
+            attempts,
+        }
+    }
+
+    fn parse_raw(
+        &self,
+        provider: &str,
+        query: &SearchQuery,
+        raw: &str,
+    ) -> Result<Vec<SearchResult>, SearchError> {
+        self.providers
+            .iter()
+            .chain(self.routes.values().flatten())
+            .find(|p| p.provider_id() == provider)
+            .ok_or_else(|| SearchError::ProviderUnavailable {
+                provider: provider.to_string(),
+            })?
+            .parse_raw(provider, query, raw)
     }
 
     fn provider_id(&self) -> &str {
@@ -254,6 +325,45 @@ mod tests {
         assert_eq!(second.calls(), 1);
     }
 
+    #[tokio::test]
+    async fn routes_content_type_to_its_providers() {
+        let general = Arc::new(SuccessProvider::new("general"));
+        let scholar = Arc::new(SuccessProvider::new("scholar"));
+
+        let fallback = FallbackSearchProvider::new(vec![Arc::clone(&general) as _])
+            .with_route(ContentType::Academic, vec![Arc::clone(&scholar) as _]);
+        let academic = SearchQuery::new("test").with_filters(
+            gorkd_core::SearchFilters::new().with_content_type(ContentType::Academic),
+        );
+
+        let report = fallback.search_reported(&academic).await;
+        assert_eq!(report.attempts[0].provider, "scholar");
+        fallback.search(&SearchQuery::new("test")).await.unwrap();
+        assert_eq!(scholar.calls(), 1);
+        assert_eq!(general.calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn reports_every_attempt() {
+        let first = Arc::new(FailingProvider::new(
+            "first",
+            SearchError::Timeout { timeout_secs: 30 },
+        ));
+        let second = Arc::new(SuccessProvider::new("second"));
+
+        let fallback =
+            FallbackSearchProvider::new(vec![Arc::clone(&first) as _, Arc::clone(&second) as _]);
+        let query = SearchQuery::new("test");
+
+        let report = fallback.search_reported(&query).await;
+        assert!(report.result.is_ok());
+        assert_eq!(report.attempts.len(), 2);
+        assert_eq!(report.attempts[0].provider, "first");
+        assert!(!report.attempts[0].succeeded());
+        assert_eq!(report.attempts[1].provider, "second");
+        assert!(matches!(report.attempts[1].outcome, Ok(1)));
+    }
+
     #[test]
     fn provider_id_is_fallback() {
         let fallback = FallbackSearchProvider::new(vec![]);
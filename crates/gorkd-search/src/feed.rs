@@ -0,0 +1,357 @@
+//! RSS and Atom feed reading for keeping the document corpus current.
+//!
+//! RSS 2.0 `<item>`s and Atom `<entry>`s are both read. An entry's text is
+//! its full content when the feed carries it (`content:encoded` or Atom
+//! `<content>`), else its description or summary, rendered from HTML to
+//! plain text. Publication dates are read from RFC 2822 (`pubDate`) or
+//! RFC 3339 (`published`, `updated`, `dc:date`) timestamps.
+//!
+//! With a [`ValidatorCache`], each poll asks for the feed conditionally and
+//! reuses the entries of the last poll when it has not changed.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use tracing::{debug, instrument};
+use url::Url;
+
+use crate::client::HttpClient;
+use crate::conditional::{ValidatorCache, Validators};
+use crate::crawl::decode_entities;
+use crate::fetch::{attribute, extract_text, find_tags, map_reqwest_error};
+use gorkd_core::{DocumentFormat, FeedItem, FeedReader, SearchError};
+
+/// Largest feed downloaded. Feeds list recent entries only, so this is far
+/// below the limit for fetched pages.
+pub const DEFAULT_MAX_FEED_BYTES: usize = 5 * 1024 * 1024;
+
+/// Reads feeds over HTTP.
+///
+/// Implements the `FeedReader` trait. Requests go through [`HttpClient`].
+pub struct HttpFeedReader {
+    client: HttpClient,
+    max_download_bytes: usize,
+    cache: Option<Arc<ValidatorCache<Vec<FeedItem>>>>,
+}
+
+impl HttpFeedReader {
+    /// Creates a reader downloading feeds of up to [`DEFAULT_MAX_FEED_BYTES`].
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            max_download_bytes: DEFAULT_MAX_FEED_BYTES,
+            cache: None,
+        }
+    }
+
+    /// Sets the largest feed that will be downloaded.
+    pub fn with_max_download_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_download_bytes = max_bytes;
+        self
+    }
+
+    /// Keeps up to `max_bytes` of feed entries to reuse when a feed has not
+    /// changed since the last poll; `0` keeps none.
+    pub fn with_validator_cache(mut self, max_bytes: usize) -> Self {
+        self.cache = (max_bytes > 0).then(|| Arc::new(ValidatorCache::new(max_bytes)));
+        self
+    }
+}
+
+#[async_trait]
+impl FeedReader for HttpFeedReader {
+    #[instrument(skip(self), fields(reader = "http"))]
+    async fn read(&self, url: &str) -> Result<Vec<FeedItem>, SearchError> {
+        let feed_url = Url::parse(url)
+            .ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https"))
+            .ok_or_else(|| SearchError::InvalidQuery {
+                reason: format!("not an http or https URL: {}", url),
+            })?;
+
+        let timeout_secs = self.client.timeout().as_secs();
+        let cached = self.cache.as_ref().and_then(|cache| cache.get(url));
+        let mut request = self.client.get(feed_url.as_str());
+        if let Some((validators, _)) = &cached {
+            request = validators.apply(request);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| map_reqwest_error(e, timeout_secs))?;
+        let status = response.status();
+        if status == StatusCode::NOT_MODIFIED {
+            if let Some((_, items)) = cached {
+                debug!(items = items.len(), "feed not modified since last read");
+                return Ok(items);
+            }
+        }
+        if !status.is_success() {
+            return Err(SearchError::Provider(format!("HTTP {}: {}", status, url)));
+        }
+        let too_large = || {
+            SearchError::Provider(format!(
+                "{} is larger than {} bytes",
+                url, self.max_download_bytes
+            ))
+        };
+        if response
+            .content_length()
+            .is_some_and(|len| len > self.max_download_bytes as u64)
+        {
+            return Err(too_large());
+        }
+        let base = response.url().clone();
+        let validators = Validators::from_headers(response.headers());
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| map_reqwest_error(e, timeout_secs))?;
+        if body.len() > self.max_download_bytes {
+            return Err(too_large());
+        }
+
+        let xml = String::from_utf8_lossy(&body);
+        let lower = xml.to_ascii_lowercase();
+        if !lower.contains("<rss") && !lower.contains("<feed") && !lower.contains("<rdf:rdf") {
+            return Err(SearchError::Provider(format!(
+                "not an RSS or Atom feed: {}",
+                url
+            )));
+        }
+
+        let items = parse_feed(&xml, &base);
+        debug!(items = items.len(), "read feed");
+        if let Some(cache) = &self.cache {
+            let bytes = items.iter().map(|item| item.text.len()).sum();
+            cache.insert(url, validators, items.clone(), bytes);
+        }
+        Ok(items)
+    }
+
+    fn reader_name(&self) -> &str {
+        "http"
+    }
+}
+
+/// The entries of an RSS or Atom document, relative links resolved against
+/// `base`. Entries without a usable link are left out.
+fn parse_feed(xml: &str, base: &Url) -> Vec<FeedItem> {
+    // ASCII lowercasing keeps byte offsets, so matches index into `xml`.
+    let lower = xml.to_ascii_lowercase();
+    let (tag, atom) = if lower.contains("<entry") && !lower.contains("<item") {
+        ("entry", true)
+    } else {
+        ("item", false)
+    };
+
+    elements(&lower, tag)
+        .filter_map(|range| {
+            let block = &xml[range.clone()];
+            let lower_block = &lower[range];
+            let link = if atom {
+                atom_link(block, lower_block)
+            } else {
+                element_text(block, lower_block, "link").or_else(|| {
+                    element_text(block, lower_block, "guid").filter(|guid| guid.contains("://"))
+                })
+            };
+            let url = base.join(link?.trim()).ok()?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return None;
+            }
+
+            let title = element_text(block, lower_block, "title")
+                .map(|title| collapse_whitespace(&title))
+                .unwrap_or_default();
+            let body = ["content:encoded", "content", "description", "summary"]
+                .iter()
+                .find_map(|name| element_text(block, lower_block, name))
+                .unwrap_or_default();
+            let text = extract_text(DocumentFormat::Html, body.as_bytes()).unwrap_or_default();
+
+            let mut item = FeedItem::new(url.as_str(), title, text);
+            if let Some(published_at) = ["pubdate", "published", "dc:date", "updated"]
+                .iter()
+                .find_map(|name| element_text(block, lower_block, name))
+                .and_then(|date| parse_date(&date))
+            {
+                item = item.with_published_at(published_at);
+            }
+            Some(item)
+        })
+        .collect()
+}
+
+/// Byte ranges of the contents of each `<name>...</name>` element.
+fn elements<'a>(
+    lower: &'a str,
+    name: &'a str,
+) -> impl Iterator<Item = std::ops::Range<usize>> + 'a {
+    let open = format!("<{}", name);
+    let close = format!("</{}>", name);
+    let mut from = 0;
+
+    std::iter::from_fn(move || loop {
+        let start = from + lower[from..].find(&open)?;
+        let after_name = start + open.len();
+        let next = lower[after_name..].chars().next()?;
+        if !(next == '>' || next.is_ascii_whitespace()) {
+            from = after_name;
+            continue;
+        }
+        let content_start = after_name + lower[after_name..].find('>')? + 1;
+        if lower[..content_start].ends_with("/>") {
+            from = content_start;
+            continue;
+        }
+        let content_end = content_start + lower[content_start..].find(&close)?;
+        from = content_end + close.len();
+        return Some(content_start..content_end);
+    })
+}
+
+/// The text of the first `<name>` element of `block`, with CDATA unwrapped
+/// and entities decoded, or `None` when it is missing or empty.
+fn element_text(block: &str, lower: &str, name: &str) -> Option<String> {
+    let range = elements(lower, name).next()?;
+    let raw = block[range].trim();
+    let text = match raw
+        .strip_prefix("<![CDATA[")
+        .and_then(|r| r.strip_suffix("]]>"))
+    {
+        Some(cdata) => cdata.to_string(),
+        None => decode_entities(raw),
+    };
+    Some(text).filter(|t| !t.trim().is_empty())
+}
+
+/// The `href` of an Atom entry's alternate link, the one without a `rel`
+/// or with `rel="alternate"`.
+fn atom_link(block: &str, lower: &str) -> Option<String> {
+    find_tags(block, lower, "link", 0..lower.len())
+        .filter(|tag| {
+            attribute(tag, "rel").map_or(true, |rel| rel.eq_ignore_ascii_case("alternate"))
+        })
+        .find_map(|tag| attribute(tag, "href"))
+}
+
+fn parse_date(text: &str) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    DateTime::parse_from_rfc3339(text)
+        .or_else(|_| DateTime::parse_from_rfc2822(text))
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn base() -> Url {
+        Url::parse("https://news.example.com/feed.xml").unwrap()
+    }
+
+    #[test]
+    fn reads_rss_items() {
+        let xml = r#"<?xml version="1.0"?>
+<rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/">
+  <channel>
+    <title>Example News</title>
+    <link>https://news.example.com/</link>
+    <item>
+      <title>Rust 2.0 &amp; beyond</title>
+      <link>https://news.example.com/rust-2</link>
+      <description>Short summary.</description>
+      <content:encoded><![CDATA[<p>The <b>Rust</b> team announced Rust 2.0.</p>]]></content:encoded>
+      <pubDate>Thu, 01 Oct 2026 09:00:00 +0200</pubDate>
+    </item>
+    <item>
+      <title>Headline only</title>
+      <guid isPermaLink="true">https://news.example.com/headline</guid>
+    </item>
+  </channel>
+</rss>"#;
+
+        let items = parse_feed(xml, &base());
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].url, "https://news.example.com/rust-2");
+        assert_eq!(items[0].title, "Rust 2.0 & beyond");
+        assert!(items[0].text.contains("team announced Rust 2.0."));
+        assert!(!items[0].text.contains("<p>"));
+        assert_eq!(
+            items[0].published_at,
+            Some(Utc.with_ymd_and_hms(2026, 10, 1, 7, 0, 0).unwrap())
+        );
+        assert_eq!(items[1].title, "Headline only");
+        assert!(items[1].text.is_empty());
+        assert!(items[1].published_at.is_none());
+    }
+
+    #[test]
+    fn reads_atom_entries() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Example Blog</title>
+  <link href="https://blog.example.com/" rel="alternate"/>
+  <link href="https://blog.example.com/atom.xml" rel="self"/>
+  <entry>
+    <title type="html">Release notes</title>
+    <link rel="replies" href="/posts/notes#comments"/>
+    <link href="/posts/notes"/>
+    <updated>2026-10-02T08:30:00Z</updated>
+    <published>2026-10-01T08:30:00Z</published>
+    <summary type="html">&lt;p&gt;What changed in this release.&lt;/p&gt;</summary>
+  </entry>
+  <entry>
+    <title>No link</title>
+    <summary>Dropped.</summary>
+  </entry>
+</feed>"#;
+        let base = Url::parse("https://blog.example.com/atom.xml").unwrap();
+
+        let items = parse_feed(xml, &base);
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].url, "https://blog.example.com/posts/notes");
+        assert_eq!(items[0].title, "Release notes");
+        assert_eq!(items[0].text, "What changed in this release.");
+        assert_eq!(
+            items[0].published_at,
+            Some(Utc.with_ymd_and_hms(2026, 10, 1, 8, 30, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn skips_elements_with_longer_names() {
+        let xml = "<rss><channel><items>x</items><item><title>T</title>\
+                   <link>https://news.example.com/t</link></item></channel></rss>";
+
+        let items = parse_feed(xml, &base());
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].url, "https://news.example.com/t");
+    }
+
+    #[test]
+    fn parses_feed_dates() {
+        assert_eq!(
+            parse_date("Mon, 05 Oct 2026 12:00:00 GMT"),
+            Some(Utc.with_ymd_and_hms(2026, 10, 5, 12, 0, 0).unwrap())
+        );
+        assert_eq!(
+            parse_date(" 2026-10-05T12:00:00+01:00 "),
+            Some(Utc.with_ymd_and_hms(2026, 10, 5, 11, 0, 0).unwrap())
+        );
+        assert_eq!(parse_date("last Tuesday"), None);
+    }
+}
@@ -0,0 +1,607 @@
+//! Page fetching for search results that come without their text.
+//!
+//! The response's `Content-Type` decides how text is extracted: HTML is
+//! rendered to plain text, PDFs go through `pdf-extract`, and plain text and
+//! markdown are used as they are. Papers, whitepapers and government
+//! documents are often PDFs, which would otherwise reach the synthesizer as
+//! binary noise. Other formats are rejected.
+//!
+//! With a [`ValidatorCache`], pages fetched before are asked for
+//! conditionally and reused when the server answers `304 Not Modified`.
+//! With a [`WaybackMachine`], pages that are gone or paywalled are read from
+//! their closest archived snapshot.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::StatusCode;
+use tracing::{debug, instrument, warn};
+
+use crate::client::HttpClient;
+use crate::conditional::{ValidatorCache, Validators};
+use crate::wayback::{looks_paywalled, raw_snapshot_url, WaybackMachine};
+use gorkd_core::{ContentFetcher, DocumentFormat, FetchedDocument, SearchError};
+
+/// Responses larger than this are not downloaded.
+pub const DEFAULT_MAX_DOWNLOAD_BYTES: usize = 10 * 1024 * 1024;
+
+/// Wide enough that rendered HTML paragraphs are not wrapped.
+const HTML_RENDER_WIDTH: usize = 10_000;
+
+/// Key images kept per HTML page.
+const MAX_PAGE_IMAGES: usize = 3;
+
+/// Fetches pages over HTTP and extracts their text.
+///
+/// Implements the `ContentFetcher` trait. Requests go through [`HttpClient`],
+/// so they carry the job's trace ID like provider calls do.
+pub struct HttpContentFetcher {
+    client: HttpClient,
+    max_download_bytes: usize,
+    cache: Option<Arc<ValidatorCache<FetchedDocument>>>,
+    archive: Option<WaybackMachine>,
+}
+
+/// What a download got.
+enum Page {
+    Document(FetchedDocument),
+    /// The page is gone or needs a login or payment to read.
+    Unavailable(StatusCode),
+}
+
+impl HttpContentFetcher {
+    /// Creates a fetcher using `client`'s timeout for each download.
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            max_download_bytes: DEFAULT_MAX_DOWNLOAD_BYTES,
+            cache: None,
+            archive: None,
+        }
+    }
+
+    /// Sets the largest response body that will be downloaded.
+    pub fn with_max_download_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_download_bytes = max_bytes;
+        self
+    }
+
+    /// Keeps up to `max_bytes` of fetched text to reuse when a page has not
+    /// changed since it was last fetched; `0` keeps none.
+    pub fn with_validator_cache(mut self, max_bytes: usize) -> Self {
+        self.cache = (max_bytes > 0).then(|| Arc::new(ValidatorCache::new(max_bytes)));
+        self
+    }
+
+    /// Reads pages that are gone, forbidden or paywalled from their closest
+    /// snapshot in `archive`, when it has one.
+    pub fn with_archive_fallback(mut self, archive: WaybackMachine) -> Self {
+        self.archive = Some(archive);
+        self
+    }
+
+    fn too_large(&self, url: &str) -> SearchError {
+        SearchError::Provider(format!(
+            "{} is larger than {} bytes",
+            url, self.max_download_bytes
+        ))
+    }
+
+    /// The archived copy of `url`. Failures are logged rather than
+    /// returned, since the caller reports why the page itself was unusable.
+    async fn archived(&self, url: &str) -> Option<FetchedDocument> {
+        let archive = self.archive.as_ref()?;
+        let snapshot = match archive.closest(url).await {
+            Ok(Some(snapshot)) => snapshot,
+            Ok(None) => return None,
+            Err(e) => {
+                warn!(error = %e, "failed to look up archived snapshot");
+                return None;
+            }
+        };
+        match self.download(&raw_snapshot_url(&snapshot, url)).await {
+            Ok(Page::Document(document)) if !document.text.trim().is_empty() => {
+                debug!(captured_at = %snapshot.captured_at, "read page from archive");
+                Some(document.with_archived(snapshot))
+            }
+            Ok(_) => None,
+            Err(e) => {
+                warn!(error = %e, "failed to fetch archived snapshot");
+                None
+            }
+        }
+    }
+
+    async fn download(&self, url: &str) -> Result<Page, SearchError> {
+        let cached = self.cache.as_ref().and_then(|cache| cache.get(url));
+        let mut request = self.client.get(url);
+        if let Some((validators, _)) = &cached {
+            request = validators.apply(request);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| map_reqwest_error(e, self.client.timeout().as_secs()))?;
+
+        let status = response.status();
+        if status == StatusCode::NOT_MODIFIED {
+            if let Some((_, document)) = cached {
+                debug!("page not modified since last fetched");
+                return Ok(Page::Document(document));
+            }
+        }
+        if is_unavailable(status) {
+            return Ok(Page::Unavailable(status));
+        }
+        if !status.is_success() {
+            return Err(SearchError::Provider(format!("HTTP {}", status)));
+        }
+        if response
+            .content_length()
+            .is_some_and(|len| len > self.max_download_bytes as u64)
+        {
+            return Err(self.too_large(url));
+        }
+
+        let validators = Validators::from_headers(response.headers());
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned);
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| map_reqwest_error(e, self.client.timeout().as_secs()))?;
+        if body.len() > self.max_download_bytes {
+            return Err(self.too_large(url));
+        }
+
+        let format = detect_format(content_type.as_deref(), url, &body).ok_or_else(|| {
+            SearchError::Provider(format!(
+                "unsupported content type: {}",
+                content_type.as_deref().unwrap_or("unknown")
+            ))
+        })?;
+
+        let images = match format {
+            DocumentFormat::Html => extract_images(&String::from_utf8_lossy(&body), url),
+            _ => Vec::new(),
+        };
+
+        // PDF parsing is CPU-bound and can take a while on large documents.
+        let text = match format {
+            DocumentFormat::Pdf => tokio::task::spawn_blocking(move || extract_text(format, &body))
+                .await
+                .map_err(|e| SearchError::Provider(format!("PDF extraction failed: {}", e)))??,
+            _ => extract_text(format, &body)?,
+        };
+
+        debug!(
+            ?format,
+            chars = text.len(),
+            images = images.len(),
+            "fetched page content"
+        );
+        let document = FetchedDocument::new(text, format).with_images(images);
+        if let Some(cache) = &self.cache {
+            let bytes = document.text.len();
+            cache.insert(url, validators, document.clone(), bytes);
+        }
+        Ok(Page::Document(document))
+    }
+}
+
+#[async_trait]
+impl ContentFetcher for HttpContentFetcher {
+    #[instrument(skip(self), fields(fetcher = "http"))]
+    async fn fetch(&self, url: &str) -> Result<FetchedDocument, SearchError> {
+        let document = match self.download(url).await? {
+            Page::Document(document) => document,
+            Page::Unavailable(status) => {
+                return self
+                    .archived(url)
+                    .await
+                    .ok_or_else(|| SearchError::Provider(format!("HTTP {}", status)));
+            }
+        };
+        if self.archive.is_some() && looks_paywalled(&document) {
+            debug!("page looks paywalled");
+            if let Some(archived) = self.archived(url).await {
+                return Ok(archived);
+            }
+        }
+        Ok(document)
+    }
+
+    fn fetcher_name(&self) -> &str {
+        "http"
+    }
+}
+
+/// Statuses of pages that are gone for good or kept from anonymous readers.
+fn is_unavailable(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::UNAUTHORIZED
+            | StatusCode::PAYMENT_REQUIRED
+            | StatusCode::FORBIDDEN
+            | StatusCode::NOT_FOUND
+            | StatusCode::GONE
+            | StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS
+    )
+}
+
+/// Picks the format from the media type, falling back to the URL's extension
+/// and the PDF magic bytes for servers that send a generic type.
+pub fn detect_format(content_type: Option<&str>, url: &str, body: &[u8]) -> Option<DocumentFormat> {
+    let media_type = content_type
+        .and_then(|value| value.split(';').next())
+        .map(|value| value.trim().to_ascii_lowercase());
+
+    match media_type.as_deref() {
+        Some("application/pdf" | "application/x-pdf") => return Some(DocumentFormat::Pdf),
+        Some("text/html" | "application/xhtml+xml") => return Some(DocumentFormat::Html),
+        Some("text/markdown" | "text/x-markdown") => return Some(DocumentFormat::Markdown),
+        Some("text/plain") => {
+            return Some(if has_extension(url, &["md", "markdown"]) {
+                DocumentFormat::Markdown
+            } else {
+                DocumentFormat::PlainText
+            })
+        }
+        Some("application/octet-stream") | None => {}
+        Some(_) => return None,
+    }
+
+    if body.starts_with(b"%PDF-") || has_extension(url, &["pdf"]) {
+        Some(DocumentFormat::Pdf)
+    } else if has_extension(url, &["md", "markdown"]) {
+        Some(DocumentFormat::Markdown)
+    } else if has_extension(url, &["txt"]) {
+        Some(DocumentFormat::PlainText)
+    } else if has_extension(url, &["html", "htm"]) {
+        Some(DocumentFormat::Html)
+    } else {
+        None
+    }
+}
+
+fn has_extension(url: &str, extensions: &[&str]) -> bool {
+    let Ok(url) = url::Url::parse(url) else {
+        return false;
+    };
+    url.path()
+        .rsplit_once('.')
+        .is_some_and(|(_, ext)| extensions.iter().any(|e| ext.eq_ignore_ascii_case(e)))
+}
+
+/// Extracts readable text from a document body.
+pub fn extract_text(format: DocumentFormat, body: &[u8]) -> Result<String, SearchError> {
+    let text = match format {
+        DocumentFormat::Pdf => pdf_extract::extract_text_from_mem(body)
+            .map_err(|e| SearchError::Provider(format!("PDF extraction failed: {}", e)))?,
+        DocumentFormat::Html => html2text::config::plain()
+            .string_from_read(body, HTML_RENDER_WIDTH)
+            .map_err(|e| SearchError::Provider(format!("HTML extraction failed: {}", e)))?,
+        _ => String::from_utf8_lossy(body).into_owned(),
+    };
+
+    Ok(normalize_whitespace(&text))
+}
+
+/// Finds a page's key images: its `og:image` (or `twitter:image`) first,
+/// then the first image of each `<figure>`. Relative URLs are resolved
+/// against `page_url`, and only http(s) images are kept.
+pub fn extract_images(html: &str, page_url: &str) -> Vec<String> {
+    let base = url::Url::parse(page_url).ok();
+    // ASCII lowercasing keeps byte offsets, so matches index into `html`.
+    let lower = html.to_ascii_lowercase();
+    let mut candidates = Vec::new();
+
+    for tag in find_tags(html, &lower, "meta", 0..html.len()) {
+        let property = attribute(tag, "property").or_else(|| attribute(tag, "name"));
+        if matches!(
+            property.as_deref(),
+            Some("og:image" | "og:image:url" | "og:image:secure_url" | "twitter:image")
+        ) {
+            candidates.extend(attribute(tag, "content"));
+        }
+    }
+
+    let mut from = 0;
+    while let Some(start) = lower[from..].find("<figure").map(|i| from + i) {
+        let end = lower[start..]
+            .find("</figure")
+            .map_or(html.len(), |i| start + i);
+        if let Some(img) = find_tags(html, &lower, "img", start..end).next() {
+            candidates.extend(attribute(img, "src").or_else(|| attribute(img, "data-src")));
+        }
+        from = end;
+    }
+
+    let mut images: Vec<String> = Vec::new();
+    for candidate in candidates {
+        let resolved = match &base {
+            Some(base) => base.join(&candidate),
+            None => url::Url::parse(&candidate),
+        };
+        let Ok(resolved) = resolved else {
+            continue;
+        };
+        if !matches!(resolved.scheme(), "http" | "https") {
+            continue;
+        }
+        let resolved = resolved.to_string();
+        if !images.contains(&resolved) {
+            images.push(resolved);
+        }
+        if images.len() == MAX_PAGE_IMAGES {
+            break;
+        }
+    }
+
+    images
+}
+
+/// Yields the attribute text of each `<name ...>` tag within `range`.
+pub(crate) fn find_tags<'a>(
+    html: &'a str,
+    lower: &'a str,
+    name: &'a str,
+    range: std::ops::Range<usize>,
+) -> impl Iterator<Item = &'a str> + 'a {
+    let open = format!("<{}", name);
+    let mut from = range.start;
+
+    std::iter::from_fn(move || loop {
+        let start = from + lower[from..range.end].find(&open)?;
+        let attrs_start = start + open.len();
+        let end = lower[attrs_start..]
+            .find('>')
+            .map_or(html.len(), |i| attrs_start + i);
+        from = end.min(range.end);
+        if html[attrs_start..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_whitespace() || c == '/')
+        {
+            return Some(&html[attrs_start..end]);
+        }
+    })
+}
+
+/// Reads an attribute's value from a tag's attribute text.
+pub(crate) fn attribute(tag: &str, name: &str) -> Option<String> {
+    let mut rest = tag;
+
+    while !rest.is_empty() {
+        rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == '/');
+        let name_end = rest
+            .find(|c: char| c.is_ascii_whitespace() || c == '=' || c == '/')
+            .unwrap_or(rest.len());
+        let attr_name = &rest[..name_end];
+        rest = rest[name_end..].trim_start();
+
+        let value = match rest.strip_prefix('=') {
+            Some(after) => {
+                let after = after.trim_start();
+                let (value, remaining) = match after.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let body = &after[1..];
+                        let close = body.find(quote).unwrap_or(body.len());
+                        (&body[..close], &body[(close + 1).min(body.len())..])
+                    }
+                    _ => {
+                        let end = after
+                            .find(|c: char| c.is_ascii_whitespace())
+                            .unwrap_or(after.len());
+                        (&after[..end], &after[end..])
+                    }
+                };
+                rest = remaining;
+                Some(value)
+            }
+            None => None,
+        };
+
+        if attr_name.is_empty() && value.is_none() {
+            break;
+        }
+        if attr_name.eq_ignore_ascii_case(name) {
+            return value
+                .map(|v| v.trim().replace("&amp;", "&"))
+                .filter(|v| !v.is_empty());
+        }
+    }
+
+    None
+}
+
+/// Trims lines and collapses runs of blank lines, which PDF and HTML
+/// extraction both leave plenty of.
+fn normalize_whitespace(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut blank_run = 0;
+
+    for line in text.lines().map(str::trim_end) {
+        if line.trim().is_empty() {
+            blank_run += 1;
+            continue;
+        }
+        if !out.is_empty() {
+            out.push_str(if blank_run > 0 { "\n\n" } else { "\n" });
+        }
+        out.push_str(line);
+        blank_run = 0;
+    }
+
+    out
+}
+
+pub(crate) fn map_reqwest_error(error: reqwest::Error, timeout_secs: u64) -> SearchError {
+    if error.is_timeout() {
+        SearchError::Timeout { timeout_secs }
+    } else if error.is_connect() {
+        SearchError::Network(format!("connection failed: {}", error))
+    } else {
+        SearchError::Network(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A one-page PDF showing `text` in Helvetica, with a valid xref table.
+    fn minimal_pdf(text: &str) -> Vec<u8> {
+        let stream = format!("BT /F1 12 Tf 72 720 Td ({}) Tj ET", text);
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] \
+             /Resources << /Font << /F1 4 0 R >> >> /Contents 5 0 R >>"
+                .to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica \
+             /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+            format!(
+                "<< /Length {} >>\nstream\n{}\nendstream",
+                stream.len(),
+                stream
+            ),
+        ];
+
+        let mut pdf = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::new();
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", i + 1, object).as_bytes());
+        }
+        let xref_start = pdf.len();
+        pdf.extend_from_slice(
+            format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes(),
+        );
+        for offset in offsets {
+            pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        pdf.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+                objects.len() + 1,
+                xref_start
+            )
+            .as_bytes(),
+        );
+        pdf
+    }
+
+    #[test]
+    fn detects_format_from_content_type() {
+        let url = "https://example.com/doc";
+        assert_eq!(
+            detect_format(Some("application/pdf"), url, b""),
+            Some(DocumentFormat::Pdf)
+        );
+        assert_eq!(
+            detect_format(Some("text/html; charset=utf-8"), url, b""),
+            Some(DocumentFormat::Html)
+        );
+        assert_eq!(
+            detect_format(Some("text/markdown"), url, b""),
+            Some(DocumentFormat::Markdown)
+        );
+        assert_eq!(
+            detect_format(Some("text/plain"), url, b""),
+            Some(DocumentFormat::PlainText)
+        );
+        assert_eq!(detect_format(Some("image/png"), url, b""), None);
+    }
+
+    #[test]
+    fn falls_back_to_extension_and_magic_bytes() {
+        assert_eq!(
+            detect_format(
+                Some("application/octet-stream"),
+                "https://gov.example/report.PDF",
+                b""
+            ),
+            Some(DocumentFormat::Pdf)
+        );
+        assert_eq!(
+            detect_format(None, "https://example.com/download?id=7", b"%PDF-1.7\n"),
+            Some(DocumentFormat::Pdf)
+        );
+        assert_eq!(
+            detect_format(Some("text/plain"), "https://example.com/README.md", b""),
+            Some(DocumentFormat::Markdown)
+        );
+        assert_eq!(
+            detect_format(None, "https://example.com/blob", b"\x00\x01"),
+            None
+        );
+    }
+
+    #[test]
+    fn extracts_text_from_html() {
+        let html = b"<html><head><title>T</title><style>p { color: red }</style></head>\
+            <body><h1>Findings</h1><p>Rust is memory-safe.</p></body></html>";
+
+        let text = extract_text(DocumentFormat::Html, html).unwrap();
+
+        assert!(text.contains("Findings"));
+        assert!(text.contains("Rust is memory-safe."));
+        assert!(!text.contains("<p>"));
+        assert!(!text.contains("color: red"));
+    }
+
+    #[test]
+    fn extracts_key_images_from_html() {
+        let html = r#"<html><head>
+            <meta name="description" content="Not an image">
+            <META property="og:image" content="/images/cover.png?w=1200&amp;h=630" />
+            <meta name="twitter:image" content="https://cdn.example.com/cover.png">
+            </head><body>
+            <img src="/logo.png">
+            <figure><img class="chart" src='charts/growth.svg' alt="Growth"><figcaption>Growth</figcaption></figure>
+            <figure><picture><img data-src="https://cdn.example.com/lazy.jpg"></picture></figure>
+            <figure><img src="data:image/png;base64,AAAA"></figure>
+            </body></html>"#;
+
+        let images = extract_images(html, "https://example.com/blog/post");
+
+        assert_eq!(
+            images,
+            vec![
+                "https://example.com/images/cover.png?w=1200&h=630",
+                "https://cdn.example.com/cover.png",
+                "https://example.com/blog/charts/growth.svg",
+            ]
+        );
+    }
+
+    #[test]
+    fn extracts_text_from_pdf() {
+        let pdf = minimal_pdf("Rust guarantees memory safety");
+
+        let text = extract_text(DocumentFormat::Pdf, &pdf).unwrap();
+
+        assert!(text.contains("Rust guarantees memory safety"), "{:?}", text);
+    }
+
+    #[test]
+    fn rejects_malformed_pdf() {
+        assert!(extract_text(DocumentFormat::Pdf, b"%PDF-1.4\ngarbage").is_err());
+    }
+
+    #[test]
+    fn keeps_plain_text_and_markdown_with_tidied_blank_lines() {
+        let text = extract_text(
+            DocumentFormat::Markdown,
+            b"# Title  \n\n\n\nSome *text*.\nMore.\n",
+        )
+        .unwrap();
+        assert_eq!(text, "# Title\n\nSome *text*.\nMore.");
+    }
+}
@@ -5,18 +5,42 @@
 
 mod client;
 mod config;
+mod factory;
 mod fallback;
 mod registry;
+mod routing;
+mod shadow;
 
+pub mod conditional;
+pub mod crawl;
 pub mod exa;
+pub mod feed;
+pub mod fetch;
+pub mod links;
+pub mod schedule;
 pub mod searxng;
 pub mod tavily;
+pub mod wayback;
+pub mod webhook;
+pub mod youtube;
 
 pub use client::{HttpClient, HttpClientError};
+pub use conditional::{ValidatorCache, Validators, DEFAULT_VALIDATOR_CACHE_BYTES};
 pub use config::{ConfigError, SearchConfig};
+pub use crawl::HttpCrawler;
 pub use exa::{ExaProvider, SearchType as ExaSearchType};
+pub use factory::{ProviderFactories, ProviderFactory};
 pub use fallback::FallbackSearchProvider;
+pub use feed::HttpFeedReader;
+pub use fetch::HttpContentFetcher;
 pub use gorkd_core::traits::{SearchProvider, SearchResult};
+pub use links::HttpLinkChecker;
 pub use registry::{ProviderRegistry, PROVIDER_ORDER};
+pub use routing::ProviderRoutes;
+pub use schedule::{FetchSchedule, ScheduledFetcher};
 pub use searxng::SearxngProvider;
-pub use tavily::{SearchDepth, TavilyProvider};
+pub use shadow::ShadowSearchProvider;
+pub use tavily::{SearchDepth, TavilyOptions, TavilyProvider, MAX_RAW_CONTENT_CHARS};
+pub use wayback::WaybackMachine;
+pub use webhook::{WebhookConfig, WebhookSearchProvider};
+pub use youtube::YouTubeTranscriptFetcher;
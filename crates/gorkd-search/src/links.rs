@@ -0,0 +1,181 @@
+//! Link checks over HTTP.
+//!
+//! An [`HttpLinkChecker`] asks for each cited page with a `HEAD` request,
+//! falling back to a one-byte `GET` for servers that do not answer `HEAD`.
+//! Results are kept for a while, so sources cited by many answers are not
+//! asked for again and again.
+
+use std::collections::HashMap;
+use std::env;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use reqwest::header::RANGE;
+use reqwest::StatusCode;
+use tracing::debug;
+
+use crate::client::HttpClient;
+use gorkd_core::{LinkChecker, LinkStatus};
+
+/// Timeout of each check when `LINK_CHECK_TIMEOUT_SECS` is unset.
+pub const DEFAULT_LINK_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long a result is kept when `LINK_CHECK_CACHE_SECS` is unset.
+pub const DEFAULT_LINK_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Results kept at most; expired ones are dropped first.
+const MAX_CACHED_LINKS: usize = 10_000;
+
+/// Reads `LINK_CHECK_TIMEOUT_SECS`.
+pub fn timeout_from_env() -> Duration {
+    env::var("LINK_CHECK_TIMEOUT_SECS")
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .filter(|&secs| secs > 0)
+        .map_or(DEFAULT_LINK_CHECK_TIMEOUT, Duration::from_secs)
+}
+
+/// Reads `LINK_CHECK_CACHE_SECS`; `0` checks every link every time.
+pub fn cache_ttl_from_env() -> Duration {
+    env::var("LINK_CHECK_CACHE_SECS")
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .map_or(DEFAULT_LINK_CACHE_TTL, Duration::from_secs)
+}
+
+/// Checks links with `HEAD` requests and keeps the results for a while.
+///
+/// Implements the `LinkChecker` trait. Inconclusive results are not kept.
+pub struct HttpLinkChecker {
+    client: HttpClient,
+    ttl: Duration,
+    cache: Mutex<HashMap<String, (Instant, LinkStatus)>>,
+}
+
+impl HttpLinkChecker {
+    /// Checks with `client`, whose timeout bounds each check.
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            ttl: DEFAULT_LINK_CACHE_TTL,
+            cache: Mutex::default(),
+        }
+    }
+
+    /// Keeps results for `ttl`; zero keeps none.
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    fn cached(&self, url: &str) -> Option<LinkStatus> {
+        let cache = self.cache.lock().unwrap();
+        let (checked_at, status) = cache.get(url)?;
+        (checked_at.elapsed() < self.ttl).then_some(*status)
+    }
+
+    fn remember(&self, url: &str, status: LinkStatus) {
+        if self.ttl.is_zero() || status == LinkStatus::Unknown {
+            return;
+        }
+        let mut cache = self.cache.lock().unwrap();
+        if cache.len() >= MAX_CACHED_LINKS {
+            cache.retain(|_, (checked_at, _)| checked_at.elapsed() < self.ttl);
+            if cache.len() >= MAX_CACHED_LINKS {
+                cache.clear();
+            }
+        }
+        cache.insert(url.to_string(), (Instant::now(), status));
+    }
+
+    async fn request(&self, url: &str) -> LinkStatus {
+        let response = match self.client.head(url).send().await {
+            Ok(response)
+                if matches!(
+                    response.status(),
+                    StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED
+                ) =>
+            {
+                self.client.get(url).header(RANGE, "bytes=0-0").send().await
+            }
+            result => result,
+        };
+        match response {
+            Ok(response) => status_of(response.status()),
+            Err(e) if e.is_connect() => LinkStatus::Dead,
+            Err(_) => LinkStatus::Unknown,
+        }
+    }
+}
+
+/// What a response status says about a page. Sites often refuse automated
+/// requests or rate-limit them, so only a page that is clearly gone is dead.
+pub fn status_of(status: StatusCode) -> LinkStatus {
+    if status.is_success() || status.is_redirection() {
+        LinkStatus::Live
+    } else if matches!(status, StatusCode::NOT_FOUND | StatusCode::GONE) {
+        LinkStatus::Dead
+    } else {
+        LinkStatus::Unknown
+    }
+}
+
+#[async_trait]
+impl LinkChecker for HttpLinkChecker {
+    async fn check(&self, url: &str) -> LinkStatus {
+        if let Some(status) = self.cached(url) {
+            return status;
+        }
+        let status = self.request(url).await;
+        debug!(url, ?status, "checked link");
+        self.remember(url, status);
+        status
+    }
+
+    fn checker_name(&self) -> &str {
+        "http"
+    }
+}
+
+impl std::fmt::Debug for HttpLinkChecker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpLinkChecker")
+            .field("ttl", &self.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_link_status_from_response_status() {
+        assert_eq!(status_of(StatusCode::OK), LinkStatus::Live);
+        assert_eq!(status_of(StatusCode::PARTIAL_CONTENT), LinkStatus::Live);
+        assert_eq!(status_of(StatusCode::NOT_FOUND), LinkStatus::Dead);
+        assert_eq!(status_of(StatusCode::GONE), LinkStatus::Dead);
+        assert_eq!(status_of(StatusCode::FORBIDDEN), LinkStatus::Unknown);
+        assert_eq!(
+            status_of(StatusCode::TOO_MANY_REQUESTS),
+            LinkStatus::Unknown
+        );
+    }
+
+    #[test]
+    fn keeps_conclusive_results_until_they_expire() {
+        let checker = HttpLinkChecker::new(HttpClient::with_default_timeout().unwrap());
+
+        checker.remember("https://a.com/", LinkStatus::Dead);
+        checker.remember("https://b.com/", LinkStatus::Unknown);
+
+        assert_eq!(checker.cached("https://a.com/"), Some(LinkStatus::Dead));
+        assert_eq!(checker.cached("https://b.com/"), None);
+
+        let uncached = HttpLinkChecker::new(HttpClient::with_default_timeout().unwrap())
+            .with_cache_ttl(Duration::ZERO);
+        uncached.remember("https://a.com/", LinkStatus::Live);
+        assert_eq!(uncached.cached("https://a.com/"), None);
+    }
+}
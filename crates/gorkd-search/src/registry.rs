@@ -4,14 +4,16 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use gorkd_core::traits::SearchProvider;
-use tracing::info;
+use gorkd_core::ContentType;
+use tracing::{info, warn};
 
+use crate::client::HttpClient;
 use crate::config::SearchConfig;
-use crate::exa::ExaProvider;
-use crate::searxng::SearxngProvider;
-use crate::tavily::TavilyProvider;
+use crate::factory::ProviderFactories;
+use crate::routing::ProviderRoutes;
 
-/// Order of providers for fallback (highest priority first).
+/// Order of providers for fallback (highest priority first), for queries
+/// without a content type route.
 pub const PROVIDER_ORDER: &[&str] = &["tavily", "exa", "searxng"];
 
 #[derive(Clone, Default)]
@@ -19,6 +21,10 @@ pub struct ProviderRegistry {
     providers: HashMap<String, Arc<dyn SearchProvider>>,
     /// Provider IDs in priority order for fallback.
     order: Vec<String>,
+    /// Providers tried first for queries filtered to a content type.
+    routes: ProviderRoutes,
+    /// Provider evaluated in shadow, outside the fallback order.
+    shadow: Option<(String, Arc<dyn SearchProvider>)>,
 }
 
 impl ProviderRegistry {
@@ -26,9 +32,21 @@ impl ProviderRegistry {
         Self {
             providers: HashMap::new(),
             order: Vec::new(),
+            routes: ProviderRoutes::default(),
+            shadow: None,
         }
     }
 
+    /// Tries the providers of `routes` first for queries filtered to their
+    /// content type.
+    pub fn set_routes(&mut self, routes: ProviderRoutes) {
+        self.routes = routes;
+    }
+
+    pub fn routes(&self) -> &ProviderRoutes {
+        &self.routes
+    }
+
     pub fn register(&mut self, id: impl Into<String>, provider: Arc<dyn SearchProvider>) {
         let id = id.into();
         if !self.providers.contains_key(&id) {
@@ -51,6 +69,49 @@ impl ProviderRegistry {
         self.order.iter().filter_map(|id| self.get(id)).collect()
     }
 
+    /// Returns providers in the order queries filtered to `content_type`
+    /// try them: its route first, then the rest in priority order.
+    pub fn providers_for(&self, content_type: &ContentType) -> Vec<Arc<dyn SearchProvider>> {
+        self.routes
+            .order_for(content_type, &self.order)
+            .iter()
+            .filter_map(|id| self.get(id))
+            .collect()
+    }
+
+    /// Returns the highest priority provider that can find similar pages.
+    pub fn similar_pages_provider(&self) -> Option<Arc<dyn SearchProvider>> {
+        self.providers_in_order()
+            .into_iter()
+            .find(|provider| provider.supports_find_similar())
+    }
+
+    /// Takes the registered provider `id` out of the fallback order to be
+    /// evaluated in shadow of the others. Returns false, changing nothing,
+    /// when `id` is not registered or is the only provider.
+    pub fn set_shadow(&mut self, id: &str) -> bool {
+        if self.providers.len() < 2 {
+            return false;
+        }
+        let Some(provider) = self.providers.remove(id) else {
+            return false;
+        };
+        self.order.retain(|other| other != id);
+        self.shadow = Some((id.to_string(), provider));
+        true
+    }
+
+    /// The provider evaluated in shadow, if any.
+    pub fn shadow(&self) -> Option<Arc<dyn SearchProvider>> {
+        self.shadow
+            .as_ref()
+            .map(|(_, provider)| Arc::clone(provider))
+    }
+
+    pub fn shadow_id(&self) -> Option<&str> {
+        self.shadow.as_ref().map(|(id, _)| id.as_str())
+    }
+
     pub fn list(&self) -> Vec<String> {
         self.order.clone()
     }
@@ -63,29 +124,43 @@ impl ProviderRegistry {
         self.providers.len()
     }
 
-    /// Creates a registry from configuration, initializing all available providers.
+    /// Creates a registry from configuration with the built-in
+    /// [`ProviderFactories`].
     ///
-    /// Providers are registered in priority order: Tavily, Exa, SearXNG.
-    /// Only providers with valid credentials/URLs are registered.
-    pub fn from_config(config: &SearchConfig) -> Self {
-        let mut registry = Self::new();
+    /// Providers are registered in priority order: Tavily, Exa, SearXNG, then
+    /// the webhooks in the order they are configured, leaving out those whose
+    /// cargo feature is off. Only providers with valid credentials/URLs are
+    /// registered. They send requests through `client`, typically built with
+    /// [`SearchConfig::http_client`], and so share one connection pool and
+    /// timeout.
+    pub fn from_config(config: &SearchConfig, client: &HttpClient) -> Self {
+        Self::from_factories(config, client, &ProviderFactories::builtin())
+    }
 
-        if let Some(ref api_key) = config.tavily_api_key {
-            let provider = TavilyProvider::new(api_key);
-            registry.register("tavily", Arc::new(provider));
-            info!(provider = "tavily", "registered search provider");
-        }
+    /// Registers the providers `factories` build from `config`, in their
+    /// order, sending requests through `client`.
+    pub fn from_factories(
+        config: &SearchConfig,
+        client: &HttpClient,
+        factories: &ProviderFactories,
+    ) -> Self {
+        let mut registry = Self::new();
+        registry.set_routes(config.routes.clone());
 
-        if let Some(ref api_key) = config.exa_api_key {
-            let provider = ExaProvider::new(api_key);
-            registry.register("exa", Arc::new(provider));
-            info!(provider = "exa", "registered search provider");
+        for (id, kind, provider) in factories.build(config, client) {
+            info!(provider = %id, kind, "registered search provider");
+            registry.register(id, provider);
         }
 
-        if let Some(ref url) = config.searxng_url {
-            let provider = SearxngProvider::new(url);
-            registry.register("searxng", Arc::new(provider));
-            info!(provider = "searxng", url = %url, "registered search provider");
+        if let Some(ref id) = config.shadow_provider {
+            if registry.set_shadow(id) {
+                info!(provider = %id, "evaluating search provider in shadow");
+            } else {
+                warn!(
+                    provider = %id,
+                    "SEARCH_SHADOW_PROVIDER must name a configured provider other than the only one"
+                );
+            }
         }
 
         registry
@@ -96,6 +171,8 @@ impl std::fmt::Debug for ProviderRegistry {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ProviderRegistry")
             .field("providers", &self.list())
+            .field("routes", &self.routes)
+            .field("shadow", &self.shadow_id())
             .finish()
     }
 }
@@ -107,6 +184,8 @@ mod tests {
     use gorkd_core::traits::{SearchError, SearchResult};
     use gorkd_core::SearchQuery;
 
+    use crate::exa::ExaProvider;
+
     struct MockProvider {
         id: String,
     }
@@ -128,6 +207,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn similar_pages_provider_prefers_capable_providers() {
+        let mut registry = ProviderRegistry::new();
+        registry.register("tavily", Arc::new(MockProvider::new("tavily")));
+        assert!(registry.similar_pages_provider().is_none());
+
+        registry.register("exa", Arc::new(ExaProvider::new("test-key")));
+        let provider = registry.similar_pages_provider().unwrap();
+        assert_eq!(provider.provider_id(), "exa");
+    }
+
     #[test]
     fn creates_empty_registry() {
         let registry = ProviderRegistry::new();
@@ -184,10 +274,85 @@ mod tests {
     #[test]
     fn from_config_creates_empty_registry_without_credentials() {
         let config = SearchConfig::default();
-        let registry = ProviderRegistry::from_config(&config);
+        let registry = ProviderRegistry::from_config(&config, &config.http_client().unwrap());
         assert!(registry.is_empty());
     }
 
+    #[test]
+    #[cfg(all(feature = "tavily", feature = "exa"))]
+    fn from_config_registers_providers_on_shared_client() {
+        let config = SearchConfig {
+            tavily_api_keys: vec![gorkd_core::WeightedKey::new("tvly-key".to_string())],
+            exa_api_keys: vec![gorkd_core::WeightedKey::new("exa-key".to_string())],
+            ..Default::default()
+        }
+        .with_http_options(
+            gorkd_core::HttpOptions::new()
+                .with_pool_max_idle_per_host(8)
+                .with_tcp_keepalive(std::time::Duration::from_secs(30)),
+        );
+
+        let registry = ProviderRegistry::from_config(&config, &config.http_client().unwrap());
+
+        assert_eq!(registry.list(), vec!["tavily", "exa"]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "tavily", feature = "webhook"))]
+    fn from_config_registers_webhooks_after_built_in_providers() {
+        let config = SearchConfig {
+            tavily_api_keys: vec![gorkd_core::WeightedKey::new("tvly-key".to_string())],
+            webhooks: vec![
+                crate::WebhookConfig::new("confluence", "https://a.example/search"),
+                crate::WebhookConfig::new("elastic", "https://b.example/search"),
+            ],
+            ..Default::default()
+        };
+
+        let registry = ProviderRegistry::from_config(&config, &config.http_client().unwrap());
+
+        assert_eq!(registry.list(), vec!["tavily", "confluence", "elastic"]);
+        assert_eq!(registry.get("elastic").unwrap().provider_id(), "elastic");
+    }
+
+    #[test]
+    fn from_factories_registers_added_providers_after_built_in_ones() {
+        let config = SearchConfig {
+            tavily_api_keys: vec![gorkd_core::WeightedKey::new("tvly-key".to_string())],
+            ..Default::default()
+        };
+        let factories = ProviderFactories::builtin().with("custom", |_, _| {
+            vec![(
+                "custom".to_string(),
+                Arc::new(MockProvider::new("custom")) as Arc<dyn SearchProvider>,
+            )]
+        });
+
+        let registry = ProviderRegistry::from_factories(
+            &config,
+            &HttpClient::with_default_timeout().unwrap(),
+            &factories.without("tavily"),
+        );
+
+        assert_eq!(registry.list(), vec!["custom"]);
+    }
+
+    #[test]
+    fn shadow_provider_leaves_fallback_order() {
+        let mut registry = ProviderRegistry::new();
+        registry.register("tavily", Arc::new(MockProvider::new("tavily")));
+        assert!(!registry.set_shadow("tavily"));
+
+        registry.register("exa", Arc::new(MockProvider::new("exa")));
+        assert!(!registry.set_shadow("searxng"));
+        assert!(registry.set_shadow("exa"));
+
+        assert_eq!(registry.list(), vec!["tavily"]);
+        assert!(registry.get("exa").is_none());
+        assert_eq!(registry.shadow_id(), Some("exa"));
+        assert_eq!(registry.shadow().unwrap().provider_id(), "exa");
+    }
+
     #[test]
     fn default_provider_returns_first_registered() {
         let mut registry = ProviderRegistry::new();
@@ -198,6 +363,35 @@ mod tests {
         assert_eq!(default.provider_id(), "first");
     }
 
+    #[test]
+    fn providers_for_content_type_follow_its_route() {
+        let mut registry = ProviderRegistry::new();
+        for id in PROVIDER_ORDER {
+            registry.register(*id, Arc::new(MockProvider::new(*id)));
+        }
+
+        let ids = |providers: Vec<Arc<dyn SearchProvider>>| {
+            providers
+                .iter()
+                .map(|p| p.provider_id().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            ids(registry.providers_for(&ContentType::Academic)),
+            vec!["exa", "searxng", "tavily"]
+        );
+        assert_eq!(
+            ids(registry.providers_for(&ContentType::Blog)),
+            PROVIDER_ORDER
+        );
+
+        registry.set_routes(ProviderRoutes::empty());
+        assert_eq!(
+            ids(registry.providers_for(&ContentType::Academic)),
+            PROVIDER_ORDER
+        );
+    }
+
     #[test]
     fn default_provider_returns_none_when_empty() {
         let registry = ProviderRegistry::new();
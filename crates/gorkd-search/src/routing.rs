@@ -0,0 +1,126 @@
+//! Which search providers to try first for each kind of content.
+//!
+//! Providers differ in what they find best: Exa's neural index surfaces
+//! papers, Tavily has a news topic, SearXNG aggregates general web engines.
+//! [`ProviderRoutes`] maps each [`ContentType`] to the providers tried first
+//! for queries filtered to it; the other registered providers follow in
+//! their usual order, so fallback still reaches all of them.
+
+use std::collections::HashMap;
+
+use gorkd_core::ContentType;
+
+/// Provider IDs to try first, by content type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProviderRoutes {
+    routes: HashMap<ContentType, Vec<String>>,
+}
+
+impl Default for ProviderRoutes {
+    /// Academic queries go to Exa first, news to Tavily and general web
+    /// searches to SearXNG.
+    fn default() -> Self {
+        Self::empty()
+            .with_route(ContentType::Academic, ["exa", "searxng"])
+            .with_route(ContentType::News, ["tavily", "searxng"])
+            .with_route(ContentType::General, ["searxng", "tavily"])
+    }
+}
+
+impl ProviderRoutes {
+    /// No routes: every query tries providers in registration order.
+    pub fn empty() -> Self {
+        Self {
+            routes: HashMap::new(),
+        }
+    }
+
+    /// Tries `providers`, by ID, first for queries filtered to
+    /// `content_type`. An empty list removes the route.
+    pub fn with_route(
+        mut self,
+        content_type: ContentType,
+        providers: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        let providers: Vec<String> = providers.into_iter().map(Into::into).collect();
+        if providers.is_empty() {
+            self.routes.remove(&content_type);
+        } else {
+            self.routes.insert(content_type, providers);
+        }
+        self
+    }
+
+    /// Provider IDs tried first for `content_type`, if it has a route.
+    pub fn route(&self, content_type: &ContentType) -> Option<&[String]> {
+        self.routes.get(content_type).map(Vec::as_slice)
+    }
+
+    /// Content types with a route, in [`ContentType::ALL`] order.
+    pub fn content_types(&self) -> Vec<ContentType> {
+        ContentType::ALL
+            .into_iter()
+            .filter(|content_type| self.routes.contains_key(content_type))
+            .collect()
+    }
+
+    /// `order` with the route of `content_type` moved to the front. Routed
+    /// IDs missing from `order` are skipped.
+    pub fn order_for(&self, content_type: &ContentType, order: &[String]) -> Vec<String> {
+        let Some(route) = self.route(content_type) else {
+            return order.to_vec();
+        };
+        let mut routed: Vec<String> = route
+            .iter()
+            .filter(|id| order.contains(id))
+            .cloned()
+            .collect();
+        routed.extend(order.iter().filter(|id| !route.contains(id)).cloned());
+        routed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order() -> Vec<String> {
+        ["tavily", "exa", "searxng"].map(String::from).to_vec()
+    }
+
+    #[test]
+    fn routes_content_types_to_preferred_providers() {
+        let routes = ProviderRoutes::default();
+
+        assert_eq!(
+            routes.order_for(&ContentType::Academic, &order()),
+            vec!["exa", "searxng", "tavily"]
+        );
+        assert_eq!(
+            routes.order_for(&ContentType::General, &order()),
+            vec!["searxng", "tavily", "exa"]
+        );
+        assert_eq!(routes.order_for(&ContentType::Forum, &order()), order());
+    }
+
+    #[test]
+    fn skips_unregistered_providers() {
+        let routes = ProviderRoutes::empty().with_route(ContentType::Academic, ["scholar", "exa"]);
+
+        assert_eq!(
+            routes.order_for(&ContentType::Academic, &order()),
+            vec!["exa", "tavily", "searxng"]
+        );
+    }
+
+    #[test]
+    fn empty_route_removes_it() {
+        let routes = ProviderRoutes::default().with_route(ContentType::News, Vec::<String>::new());
+
+        assert!(routes.route(&ContentType::News).is_none());
+        assert_eq!(
+            routes.content_types(),
+            vec![ContentType::Academic, ContentType::General]
+        );
+    }
+}
@@ -0,0 +1,279 @@
+//! Polite scheduling of page fetches.
+//!
+//! A research job keeps its best sources, and several of them are often on
+//! one site, so fetching them all at once would hit that site with a burst
+//! of requests; across concurrent jobs, more so. A [`ScheduledFetcher`]
+//! wraps the fetcher of every job and allows at most
+//! [`per_host`](FetchSchedule::per_host) fetches in flight to any one host
+//! and [`overall`](FetchSchedule::overall) in total, queueing the rest. It
+//! reads each host's `robots.txt` once an hour, skips pages it disallows and
+//! waits out its `Crawl-delay`, up to [`MAX_CRAWL_DELAY`], between fetches.
+
+use std::collections::HashMap;
+use std::env;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::sync::Semaphore;
+use tokio::time::Instant;
+use tracing::debug;
+use url::Url;
+
+use crate::client::HttpClient;
+use crate::crawl::{product_token, read_robots, RobotsRules, MAX_CRAWL_DELAY};
+use gorkd_core::{ContentFetcher, FetchedDocument, SearchError, DEFAULT_USER_AGENT};
+
+/// Fetches in flight to one host when `FETCH_MAX_PER_HOST` is unset.
+pub const DEFAULT_MAX_PER_HOST: usize = 2;
+
+/// Fetches in flight in total when `FETCH_MAX_CONCURRENT` is unset.
+pub const DEFAULT_MAX_CONCURRENT_FETCHES: usize = 16;
+
+/// How long a host's `robots.txt` is trusted before it is read again.
+const ROBOTS_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Idle hosts are forgotten once more than this many are known.
+const MAX_IDLE_HOSTS: usize = 1024;
+
+/// How fetches are spread over hosts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FetchSchedule {
+    /// Fetches in flight to any one host, from `FETCH_MAX_PER_HOST`.
+    pub per_host: usize,
+    /// Fetches in flight in total, from `FETCH_MAX_CONCURRENT`; `None`
+    /// allows any number.
+    pub overall: Option<usize>,
+    /// Skip pages a host's `robots.txt` disallows and honor its
+    /// `Crawl-delay`, from `FETCH_OBEY_ROBOTS`.
+    pub obey_robots: bool,
+}
+
+impl Default for FetchSchedule {
+    fn default() -> Self {
+        Self {
+            per_host: DEFAULT_MAX_PER_HOST,
+            overall: Some(DEFAULT_MAX_CONCURRENT_FETCHES),
+            obey_robots: true,
+        }
+    }
+}
+
+impl FetchSchedule {
+    /// Reads `FETCH_MAX_PER_HOST`, `FETCH_MAX_CONCURRENT` (`0` for no
+    /// limit) and `FETCH_OBEY_ROBOTS` (`false` to ignore `robots.txt`).
+    pub fn from_env() -> Self {
+        let number = |name: &str| env::var(name).ok().and_then(|s| s.trim().parse().ok());
+        let defaults = Self::default();
+        Self {
+            per_host: number("FETCH_MAX_PER_HOST")
+                .unwrap_or(defaults.per_host)
+                .max(1),
+            overall: match number("FETCH_MAX_CONCURRENT") {
+                Some(0) => None,
+                Some(max) => Some(max),
+                None => defaults.overall,
+            },
+            obey_robots: env::var("FETCH_OBEY_ROBOTS")
+                .map(|s| !matches!(s.trim().to_lowercase().as_str(), "false" | "0" | "no"))
+                .unwrap_or(defaults.obey_robots),
+        }
+    }
+}
+
+/// What is known about one host.
+struct Host {
+    slots: Arc<Semaphore>,
+    /// Its `robots.txt` rules and when they were read.
+    robots: tokio::sync::Mutex<Option<(Instant, Arc<RobotsRules>)>>,
+    /// When the next fetch may start, per its `Crawl-delay`.
+    next_start: tokio::sync::Mutex<Instant>,
+}
+
+/// A [`ContentFetcher`] that fetches through another one on a per-host
+/// [`FetchSchedule`].
+pub struct ScheduledFetcher {
+    inner: Arc<dyn ContentFetcher>,
+    client: HttpClient,
+    robots_agent: String,
+    schedule: FetchSchedule,
+    overall: Option<Arc<Semaphore>>,
+    hosts: Mutex<HashMap<String, Arc<Host>>>,
+}
+
+impl ScheduledFetcher {
+    /// Fetches with `inner` on `schedule`, reading `robots.txt` files with
+    /// `client` for `gorkd`.
+    pub fn new(
+        inner: Arc<dyn ContentFetcher>,
+        client: HttpClient,
+        schedule: FetchSchedule,
+    ) -> Self {
+        Self {
+            inner,
+            client,
+            robots_agent: product_token(DEFAULT_USER_AGENT),
+            overall: schedule
+                .overall
+                .map(|max| Arc::new(Semaphore::new(max.max(1)))),
+            schedule,
+            hosts: Mutex::default(),
+        }
+    }
+
+    /// Obeys the `robots.txt` rules for the product token of `user_agent`,
+    /// which should match the User-Agent the client sends.
+    pub fn with_user_agent(mut self, user_agent: &str) -> Self {
+        self.robots_agent = product_token(user_agent);
+        self
+    }
+
+    fn host(&self, host: &str) -> Arc<Host> {
+        let mut hosts = self.hosts.lock().unwrap();
+        if hosts.len() > MAX_IDLE_HOSTS {
+            hosts.retain(|_, host| Arc::strong_count(host) > 1);
+        }
+        let host = hosts.entry(host.to_string()).or_insert_with(|| {
+            Arc::new(Host {
+                slots: Arc::new(Semaphore::new(self.schedule.per_host.max(1))),
+                robots: tokio::sync::Mutex::new(None),
+                next_start: tokio::sync::Mutex::new(Instant::now()),
+            })
+        });
+        Arc::clone(host)
+    }
+
+    /// The host's rules, read again once they are older than [`ROBOTS_TTL`].
+    /// Concurrent fetches to the host wait for one read.
+    async fn robots(&self, host: &Host, url: &Url) -> Arc<RobotsRules> {
+        let mut robots = host.robots.lock().await;
+        if let Some((read_at, rules)) = robots.as_ref() {
+            if read_at.elapsed() < ROBOTS_TTL {
+                return Arc::clone(rules);
+            }
+        }
+        let rules = Arc::new(read_robots(&self.client, url, &self.robots_agent).await);
+        *robots = Some((Instant::now(), Arc::clone(&rules)));
+        rules
+    }
+}
+
+#[async_trait]
+impl ContentFetcher for ScheduledFetcher {
+    async fn fetch(&self, url: &str) -> Result<FetchedDocument, SearchError> {
+        let Some(parsed) = Url::parse(url).ok().filter(|url| url.host_str().is_some()) else {
+            return self.inner.fetch(url).await;
+        };
+        let host_name = parsed.host_str().unwrap_or_default().to_string();
+        let host = self.host(&host_name);
+
+        let mut delay = None;
+        if self.schedule.obey_robots {
+            let rules = self.robots(&host, &parsed).await;
+            if !rules.allows(&parsed) {
+                return Err(SearchError::Provider(format!(
+                    "robots.txt of {} disallows {}",
+                    host_name, url
+                )));
+            }
+            delay = rules.crawl_delay.map(|delay| delay.min(MAX_CRAWL_DELAY));
+        }
+
+        // The semaphores are never closed.
+        let _host_slot = Arc::clone(&host.slots).acquire_owned().await.ok();
+        if let Some(delay) = delay {
+            let mut next_start = host.next_start.lock().await;
+            let wait = next_start.saturating_duration_since(Instant::now());
+            if !wait.is_zero() {
+                let wait_ms = wait.as_millis() as u64;
+                debug!(host = %host_name, wait_ms, "waiting out crawl delay");
+                tokio::time::sleep(wait).await;
+            }
+            *next_start = Instant::now() + delay;
+        }
+        let _overall_slot = match &self.overall {
+            Some(overall) => Arc::clone(overall).acquire_owned().await.ok(),
+            None => None,
+        };
+
+        self.inner.fetch(url).await
+    }
+
+    fn fetcher_name(&self) -> &str {
+        self.inner.fetcher_name()
+    }
+
+    fn replaces_provider_content(&self, url: &str) -> bool {
+        self.inner.replaces_provider_content(url)
+    }
+}
+
+impl std::fmt::Debug for ScheduledFetcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScheduledFetcher")
+            .field("inner", &self.inner.fetcher_name())
+            .field("schedule", &self.schedule)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use gorkd_core::DocumentFormat;
+
+    /// Records the most fetches it had in flight at once.
+    #[derive(Default)]
+    struct SlowFetcher {
+        in_flight: AtomicUsize,
+        most: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ContentFetcher for SlowFetcher {
+        async fn fetch(&self, _url: &str) -> Result<FetchedDocument, SearchError> {
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.most.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(FetchedDocument::new("text", DocumentFormat::PlainText))
+        }
+
+        fn fetcher_name(&self) -> &str {
+            "slow"
+        }
+    }
+
+    fn scheduled(inner: Arc<SlowFetcher>, per_host: usize, overall: usize) -> ScheduledFetcher {
+        let schedule = FetchSchedule {
+            per_host,
+            overall: Some(overall),
+            obey_robots: false,
+        };
+        ScheduledFetcher::new(inner, HttpClient::with_default_timeout().unwrap(), schedule)
+    }
+
+    #[tokio::test]
+    async fn limits_fetches_per_host() {
+        let inner = Arc::new(SlowFetcher::default());
+        let fetcher = scheduled(Arc::clone(&inner), 1, 8);
+
+        let urls: Vec<String> = (0..4).map(|i| format!("https://a.com/{}", i)).collect();
+        futures::future::join_all(urls.iter().map(|url| fetcher.fetch(url))).await;
+
+        assert_eq!(inner.most.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn limits_fetches_overall() {
+        let inner = Arc::new(SlowFetcher::default());
+        let fetcher = scheduled(Arc::clone(&inner), 2, 3);
+
+        let urls: Vec<String> = (0..6).map(|i| format!("https://host{}.com/", i)).collect();
+        futures::future::join_all(urls.iter().map(|url| fetcher.fetch(url))).await;
+
+        assert_eq!(inner.most.load(Ordering::SeqCst), 3);
+    }
+}
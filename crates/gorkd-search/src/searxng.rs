@@ -3,27 +3,43 @@
 //! SearXNG is a privacy-respecting metasearch engine that aggregates results from
 //! multiple sources. No API key required, but needs a running instance with JSON
 //! format enabled. API docs: <https://docs.searxng.org/dev/search_api.html>
+//!
+//! Public instances often rate-limit or refuse the JSON format, so the provider
+//! accepts several instance URLs. Requests rotate across them, and an instance
+//! that fails is skipped for a cooldown period while the others are tried.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::Deserialize;
 use tracing::{debug, instrument, warn};
 use url::Url;
 
 use crate::client::HttpClient;
+use crate::config::parse_list;
 use gorkd_core::{ContentType, Recency, SearchQuery};
 use gorkd_core::{SearchError, SearchProvider, SearchResult};
 
 const PROVIDER_ID: &str = "searxng";
 const DEFAULT_INSTANCE_URL: &str = "https://searx.be";
 
+/// How long a failing instance is skipped before it is tried again.
+pub const DEFAULT_INSTANCE_COOLDOWN: Duration = Duration::from_secs(60);
+
 /// SearXNG search provider.
 ///
 /// Implements the `SearchProvider` trait for SearXNG's JSON search API.
 /// Supports recency filtering via `time_range`, content type filtering via
-/// `categories`, and domain filtering via query syntax (`site:domain.com`).
+/// `categories`, language filtering via `language`, engine selection via
+/// `engines`, and domain filtering via query syntax (`site:domain.com`).
 #[derive(Clone)]
 pub struct SearxngProvider {
-    instance_url: String,
+    pool: Arc<InstancePool>,
+    engines: Vec<String>,
+    cooldown: Duration,
     client: HttpClient,
 }
 
@@ -38,43 +54,95 @@ impl SearxngProvider {
     /// let provider = SearxngProvider::new("https://searx.example.org");
     /// ```
     pub fn new(instance_url: impl Into<String>) -> Self {
-        let mut url = instance_url.into();
-        if url.ends_with('/') {
-            url.pop();
-        }
+        Self::with_client(instance_url, HttpClient::default())
+    }
+
+    /// Creates a provider that rotates across several instances.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gorkd_search::SearxngProvider;
+    ///
+    /// let provider = SearxngProvider::from_instances(["https://a.example", "https://b.example"])
+    ///     .with_engines(["google", "bing", "duckduckgo"]);
+    /// ```
+    pub fn from_instances(instance_urls: impl IntoIterator<Item = impl Into<String>>) -> Self {
         Self {
-            instance_url: url,
+            pool: Arc::new(InstancePool::new(instance_urls)),
+            engines: Vec::new(),
+            cooldown: DEFAULT_INSTANCE_COOLDOWN,
             client: HttpClient::default(),
         }
     }
 
-    /// Creates a new SearXNG provider from the `SEARXNG_URL` environment variable.
+    /// Creates a new SearXNG provider from the `SEARXNG_URL` (comma-separated
+    /// instance URLs) and `SEARXNG_ENGINES` environment variables.
     ///
-    /// Falls back to a default public instance if the variable is not set.
+    /// Falls back to a default public instance if `SEARXNG_URL` is not set.
     pub fn from_env() -> Self {
-        let url = std::env::var("SEARXNG_URL").unwrap_or_else(|_| DEFAULT_INSTANCE_URL.to_string());
-        Self::new(url)
+        let urls = std::env::var("SEARXNG_URL")
+            .map(|s| parse_list(&s))
+            .unwrap_or_default();
+        let engines = std::env::var("SEARXNG_ENGINES")
+            .map(|s| parse_list(&s))
+            .unwrap_or_default();
+
+        let provider = if urls.is_empty() {
+            Self::new(DEFAULT_INSTANCE_URL)
+        } else {
+            Self::from_instances(urls)
+        };
+        provider.with_engines(engines)
     }
 
     /// Creates a new SearXNG provider with a custom HTTP client.
     pub fn with_client(instance_url: impl Into<String>, client: HttpClient) -> Self {
-        let mut url = instance_url.into();
-        if url.ends_with('/') {
-            url.pop();
-        }
         Self {
-            instance_url: url,
             client,
+            ..Self::from_instances([instance_url])
         }
     }
 
-    /// Returns the instance URL.
+    /// Sends requests with `client` in place of the default client.
+    pub fn with_http_client(mut self, client: HttpClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Restricts searches to the given SearXNG engines, e.g. `google`,
+    /// `bing`, `duckduckgo`. An empty list uses the instance's defaults.
+    pub fn with_engines(mut self, engines: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.engines = engines.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets how long a failing instance is skipped.
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Returns the first configured instance URL.
     pub fn instance_url(&self) -> &str {
-        &self.instance_url
+        self.pool
+            .instances
+            .first()
+            .map_or("", |instance| instance.url.as_str())
     }
 
-    fn build_url(&self, query: &SearchQuery) -> Result<Url, SearchError> {
-        let mut url = Url::parse(&format!("{}/search", self.instance_url))
+    /// Returns every configured instance URL.
+    pub fn instance_urls(&self) -> Vec<&str> {
+        self.pool.instances.iter().map(|i| i.url.as_str()).collect()
+    }
+
+    /// Returns the engines searches are restricted to.
+    pub fn engines(&self) -> &[String] {
+        &self.engines
+    }
+
+    fn build_url(&self, instance_url: &str, query: &SearchQuery) -> Result<Url, SearchError> {
+        let mut url = Url::parse(&format!("{}/search", instance_url))
             .map_err(|e| SearchError::Provider(format!("invalid instance URL: {}", e)))?;
 
         let query_text = build_query_with_domains(&query.text, &query.filters.include_domains);
@@ -93,17 +161,33 @@ impl SearxngProvider {
             if let Some(ref content_type) = query.filters.content_type {
                 params.append_pair("categories", map_content_type(content_type));
             }
+
+            // SearXNG has no country filter of its own, but a regional
+            // language such as `de-AT` localizes engines that support it.
+            if let Some(ref language) = query.filters.language {
+                let language = match query.filters.country {
+                    Some(ref country) => format!("{}-{}", language, country),
+                    None => language.clone(),
+                };
+                params.append_pair("language", &language);
+            }
+
+            if !self.engines.is_empty() {
+                params.append_pair("engines", &self.engines.join(","));
+            }
         }
 
         Ok(url)
     }
-}
 
-#[async_trait]
-impl SearchProvider for SearxngProvider {
-    #[instrument(skip(self), fields(provider = PROVIDER_ID, instance = %self.instance_url))]
-    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>, SearchError> {
-        let url = self.build_url(query)?;
+    /// Queries one instance and returns the body of a successful response.
+    #[instrument(skip(self, query), fields(provider = PROVIDER_ID))]
+    async fn search_instance(
+        &self,
+        instance_url: &str,
+        query: &SearchQuery,
+    ) -> Result<String, SearchError> {
+        let url = self.build_url(instance_url, query)?;
 
         debug!(url = %url, "executing searxng search");
 
@@ -120,27 +204,66 @@ impl SearchProvider for SearxngProvider {
             return Err(map_http_error(status));
         }
 
-        let searxng_response: SearxngResponse = response.json().await.map_err(|e| {
-            warn!(error = %e, "failed to parse searxng response");
-            SearchError::Provider(format!("failed to parse response: {}", e))
-        })?;
-
-        debug!(
-            result_count = searxng_response.results.len(),
-            "searxng search completed"
-        );
+        response
+            .text()
+            .await
+            .map_err(|e| map_reqwest_error(e, self.client.timeout().as_secs()))
+    }
+}
 
-        let results = searxng_response
-            .results
-            .into_iter()
-            .map(|r| {
-                let snippet = r.content.unwrap_or_default();
-                let score = normalize_score(r.score);
-                SearchResult::new(r.url, r.title, snippet).with_score(score)
-            })
-            .collect();
+#[async_trait]
+impl SearchProvider for SearxngProvider {
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>, SearchError> {
+        self.search_captured(query).await.0
+    }
+
+    #[instrument(skip(self), fields(provider = PROVIDER_ID, instances = self.pool.instances.len()))]
+    async fn search_captured(
+        &self,
+        query: &SearchQuery,
+    ) -> (Result<Vec<SearchResult>, SearchError>, Option<String>) {
+        let mut last_error = None;
+
+        for index in self.pool.order(Instant::now()) {
+            let instance_url = self.pool.url(index);
+
+            let result = self
+                .search_instance(instance_url, query)
+                .await
+                .and_then(|body| parse_response(&body).map(|results| (results, body)));
+            match result {
+                Ok((results, body)) => {
+                    self.pool.mark_healthy(index);
+                    return (Ok(results), Some(body));
+                }
+                // A bad query fails the same way on every instance.
+                Err(e @ SearchError::InvalidQuery { .. }) => return (Err(e), None),
+                Err(e) => {
+                    warn!(instance = %instance_url, error = %e, "searxng instance failed, rotating");
+                    self.pool.mark_failed(index, Instant::now() + self.cooldown);
+                    last_error = Some(e);
+                }
+            }
+        }
 
-        Ok(results)
+        let error = last_error.unwrap_or_else(|| {
+            SearchError::Provider("no SearXNG instances configured".to_string())
+        });
+        (Err(error), None)
+    }
+
+    fn parse_raw(
+        &self,
+        provider: &str,
+        _query: &SearchQuery,
+        raw: &str,
+    ) -> Result<Vec<SearchResult>, SearchError> {
+        if provider != PROVIDER_ID {
+            return Err(SearchError::ProviderUnavailable {
+                provider: provider.to_string(),
+            });
+        }
+        parse_response(raw)
     }
 
     fn provider_id(&self) -> &str {
@@ -156,6 +279,84 @@ impl SearchProvider for SearxngProvider {
     }
 }
 
+// ============================================================================
+// Instance Rotation
+// ============================================================================
+
+#[derive(Debug)]
+struct Instance {
+    url: String,
+    /// Set after a failure; the instance is skipped until then.
+    unhealthy_until: Mutex<Option<Instant>>,
+}
+
+/// Round-robin over configured instances, preferring ones that have not
+/// failed recently.
+#[derive(Debug)]
+struct InstancePool {
+    instances: Vec<Instance>,
+    next: AtomicUsize,
+}
+
+impl InstancePool {
+    fn new(urls: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        let instances = urls
+            .into_iter()
+            .map(|url| {
+                let mut url = url.into();
+                if url.ends_with('/') {
+                    url.pop();
+                }
+                Instance {
+                    url,
+                    unhealthy_until: Mutex::new(None),
+                }
+            })
+            .collect();
+
+        Self {
+            instances,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    fn url(&self, index: usize) -> &str {
+        &self.instances[index].url
+    }
+
+    fn is_healthy(&self, index: usize, now: Instant) -> bool {
+        self.instances[index]
+            .unhealthy_until
+            .lock()
+            .unwrap()
+            .map_or(true, |until| until <= now)
+    }
+
+    /// Indices to try for the next request: healthy instances in rotation
+    /// order, then cooling-down ones as a last resort.
+    fn order(&self, now: Instant) -> Vec<usize> {
+        let len = self.instances.len();
+        if len == 0 {
+            return Vec::new();
+        }
+
+        let start = self.next.fetch_add(1, Ordering::Relaxed) % len;
+        let (healthy, cooling): (Vec<usize>, Vec<usize>) = (0..len)
+            .map(|offset| (start + offset) % len)
+            .partition(|&index| self.is_healthy(index, now));
+
+        healthy.into_iter().chain(cooling).collect()
+    }
+
+    fn mark_failed(&self, index: usize, until: Instant) {
+        *self.instances[index].unhealthy_until.lock().unwrap() = Some(until);
+    }
+
+    fn mark_healthy(&self, index: usize) {
+        *self.instances[index].unhealthy_until.lock().unwrap() = None;
+    }
+}
+
 // ============================================================================
 // Response Types
 // ============================================================================
@@ -186,12 +387,44 @@ struct SearxngResult {
     /// Relevance score (can be missing or inconsistent across engines).
     #[serde(default)]
     score: Option<f32>,
+    /// Publication date, when the engine knows it. Engines give it with or
+    /// without a UTC offset.
+    #[serde(default, rename = "publishedDate")]
+    published_date: Option<String>,
 }
 
 // ============================================================================
 // Mapping Functions
 // ============================================================================
 
+fn parse_response(body: &str) -> Result<Vec<SearchResult>, SearchError> {
+    let searxng_response: SearxngResponse = serde_json::from_str(body).map_err(|e| {
+        warn!(error = %e, "failed to parse searxng response");
+        SearchError::Provider(format!("failed to parse response: {}", e))
+    })?;
+
+    debug!(
+        result_count = searxng_response.results.len(),
+        "searxng search completed"
+    );
+
+    let results = searxng_response
+        .results
+        .into_iter()
+        .map(|r| {
+            let snippet = r.content.unwrap_or_default();
+            let score = normalize_score(r.score);
+            let result = SearchResult::new(r.url, r.title, snippet).with_score(score);
+            match r.published_date.as_deref().and_then(parse_date) {
+                Some(published_at) => result.with_published_at(published_at),
+                None => result,
+            }
+        })
+        .collect();
+
+    Ok(results)
+}
+
 /// Maps Recency to SearXNG time_range parameter.
 fn map_recency(recency: &Recency) -> Option<&'static str> {
     match recency {
@@ -235,6 +468,16 @@ fn build_query_with_domains(query: &str, include_domains: &Option<Vec<String>>)
     }
 }
 
+/// Parses a publication date, reading dates without an offset as UTC.
+fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(date)
+        .map(|date| date.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%S").map(|date| date.and_utc())
+        })
+        .ok()
+}
+
 /// Normalizes SearXNG scores to a 0.0-1.0 range.
 ///
 /// SearXNG scores can vary widely or be missing entirely. We normalize using
@@ -312,7 +555,7 @@ mod tests {
         let provider = SearxngProvider::new("https://searx.example.org");
         let query = SearchQuery::new("test query");
 
-        let url = provider.build_url(&query).unwrap();
+        let url = provider.build_url(provider.instance_url(), &query).unwrap();
 
         assert!(url.as_str().starts_with("https://searx.example.org/search"));
         assert!(url.as_str().contains("q=test+query"));
@@ -325,7 +568,7 @@ mod tests {
         let query =
             SearchQuery::new("test").with_filters(SearchFilters::new().with_recency(Recency::Week));
 
-        let url = provider.build_url(&query).unwrap();
+        let url = provider.build_url(provider.instance_url(), &query).unwrap();
 
         assert!(url.as_str().contains("time_range=week"));
     }
@@ -336,7 +579,7 @@ mod tests {
         let query = SearchQuery::new("test")
             .with_filters(SearchFilters::new().with_content_type(ContentType::News));
 
-        let url = provider.build_url(&query).unwrap();
+        let url = provider.build_url(provider.instance_url(), &query).unwrap();
 
         assert!(url.as_str().contains("categories=news"));
     }
@@ -347,7 +590,7 @@ mod tests {
         let query = SearchQuery::new("rust programming")
             .with_filters(SearchFilters::new().include_domains(["rust-lang.org"]));
 
-        let url = provider.build_url(&query).unwrap();
+        let url = provider.build_url(provider.instance_url(), &query).unwrap();
 
         assert!(url.as_str().contains("site%3Arust-lang.org"));
     }
@@ -358,7 +601,7 @@ mod tests {
         let query = SearchQuery::new("test")
             .with_filters(SearchFilters::new().include_domains(["example.com", "test.com"]));
 
-        let url = provider.build_url(&query).unwrap();
+        let url = provider.build_url(provider.instance_url(), &query).unwrap();
         let url_str = url.as_str();
 
         assert!(url_str.contains("site%3Aexample.com"));
@@ -366,6 +609,109 @@ mod tests {
         assert!(url_str.contains("+OR+"));
     }
 
+    #[test]
+    fn builds_url_with_language() {
+        let provider = SearxngProvider::new("https://searx.example.org");
+        let query = SearchQuery::new("Mietrecht Kündigungsfrist")
+            .with_filters(SearchFilters::new().with_language("de").with_country("at"));
+
+        let url = provider.build_url(provider.instance_url(), &query).unwrap();
+
+        assert!(url.as_str().contains("language=de-AT"));
+
+        let query = SearchQuery::new("test").with_filters(SearchFilters::new().with_country("AT"));
+        let url = provider.build_url(provider.instance_url(), &query).unwrap();
+        assert!(!url.as_str().contains("language="));
+    }
+
+    #[test]
+    fn builds_url_with_engines() {
+        let provider = SearxngProvider::new("https://searx.example.org").with_engines([
+            "google",
+            "bing",
+            "duckduckgo",
+        ]);
+        let query = SearchQuery::new("test");
+
+        let url = provider.build_url(provider.instance_url(), &query).unwrap();
+
+        assert!(url.as_str().contains("engines=google%2Cbing%2Cduckduckgo"));
+    }
+
+    #[test]
+    fn omits_engines_by_default() {
+        let provider = SearxngProvider::new("https://searx.example.org");
+        let url = provider
+            .build_url(provider.instance_url(), &SearchQuery::new("test"))
+            .unwrap();
+
+        assert!(!url.as_str().contains("engines="));
+    }
+
+    #[test]
+    fn creates_provider_with_multiple_instances() {
+        let provider = SearxngProvider::from_instances(["https://a.example/", "https://b.example"]);
+
+        assert_eq!(
+            provider.instance_urls(),
+            vec!["https://a.example", "https://b.example"]
+        );
+        assert_eq!(provider.instance_url(), "https://a.example");
+    }
+
+    #[test]
+    fn rotates_across_instances() {
+        let pool = InstancePool::new(["a", "b", "c"]);
+        let now = Instant::now();
+
+        assert_eq!(pool.order(now), vec![0, 1, 2]);
+        assert_eq!(pool.order(now), vec![1, 2, 0]);
+        assert_eq!(pool.order(now), vec![2, 0, 1]);
+        assert_eq!(pool.order(now), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn tries_failed_instances_last_until_cooldown_expires() {
+        let pool = InstancePool::new(["a", "b", "c"]);
+        let now = Instant::now();
+        pool.mark_failed(0, now + Duration::from_secs(60));
+
+        assert_eq!(pool.order(now), vec![1, 2, 0]);
+        assert_eq!(pool.order(now), vec![1, 2, 0]);
+        assert_eq!(pool.order(now + Duration::from_secs(61)), vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn recovered_instance_rejoins_rotation() {
+        let pool = InstancePool::new(["a", "b"]);
+        let now = Instant::now();
+        pool.mark_failed(0, now + Duration::from_secs(60));
+        assert!(!pool.is_healthy(0, now));
+
+        pool.mark_healthy(0);
+
+        assert!(pool.is_healthy(0, now));
+        assert_eq!(pool.order(now), vec![0, 1]);
+    }
+
+    #[test]
+    fn empty_pool_has_no_order() {
+        let pool = InstancePool::new(Vec::<String>::new());
+        assert!(pool.order(Instant::now()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn search_without_instances_fails() {
+        let provider = SearxngProvider::from_instances(Vec::<String>::new());
+
+        let err = provider
+            .search(&SearchQuery::new("test"))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, SearchError::Provider(msg) if msg.contains("no SearXNG instances")));
+    }
+
     #[test]
     fn maps_all_recency_values() {
         assert_eq!(map_recency(&Recency::Day), Some("day"));
@@ -479,6 +825,21 @@ mod tests {
         assert_eq!(response.results[0].score, Some(2.5));
     }
 
+    #[test]
+    fn parses_published_dates() {
+        assert_eq!(
+            parse_date("2024-06-15T08:00:00").unwrap().to_rfc3339(),
+            "2024-06-15T08:00:00+00:00"
+        );
+        assert_eq!(
+            parse_date("2024-06-15T10:00:00+02:00")
+                .unwrap()
+                .to_rfc3339(),
+            "2024-06-15T08:00:00+00:00"
+        );
+        assert!(parse_date("yesterday").is_none());
+    }
+
     #[test]
     fn deserializes_minimal_response() {
         let json = r#"{
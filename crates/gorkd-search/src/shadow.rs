@@ -0,0 +1,215 @@
+//! Shadow search provider that evaluates a candidate on live queries.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use tokio::sync::oneshot;
+use tracing::debug;
+
+use gorkd_core::traits::{SearchError, SearchProvider, SearchReport, SearchResult};
+use gorkd_core::{SearchQuery, ShadowKind, ShadowMetrics, ShadowSample};
+
+/// A search provider that serves every query from `primary` and repeats it
+/// against `candidate` in the background. The candidate's results are only
+/// compared with the primary's, by URL overlap, and recorded in
+/// [`ShadowMetrics`]; they never reach the caller, and waiting for them
+/// never delays it.
+pub struct ShadowSearchProvider {
+    primary: Arc<dyn SearchProvider>,
+    candidate: Arc<dyn SearchProvider>,
+    metrics: Arc<ShadowMetrics>,
+}
+
+impl ShadowSearchProvider {
+    /// Shadows `primary` with `candidate`, recording into `metrics`.
+    pub fn new(
+        primary: Arc<dyn SearchProvider>,
+        candidate: Arc<dyn SearchProvider>,
+        metrics: Arc<ShadowMetrics>,
+    ) -> Self {
+        Self {
+            primary,
+            candidate,
+            metrics,
+        }
+    }
+
+    /// Queries the candidate in a background task, which scores it once the
+    /// primary's URLs and latency arrive on the returned sender.
+    fn shadow(&self, query: &SearchQuery) -> oneshot::Sender<(Option<Vec<String>>, Duration)> {
+        let (tx, rx) = oneshot::channel::<(Option<Vec<String>>, Duration)>();
+        let candidate = Arc::clone(&self.candidate);
+        let metrics = Arc::clone(&self.metrics);
+        let primary_id = self.primary.provider_id().to_string();
+        let query = query.clone();
+
+        tokio::spawn(async move {
+            let started = Instant::now();
+            let result = candidate.search(&query).await;
+            let candidate_latency = started.elapsed();
+            let Ok((primary_urls, primary_latency)) = rx.await else {
+                return;
+            };
+
+            let overlap = match (&primary_urls, &result) {
+                (Some(primary), Ok(results)) => Some(url_overlap(primary, results)),
+                _ => None,
+            };
+            debug!(
+                candidate = candidate.provider_id(),
+                query = %query.text,
+                ok = result.is_ok(),
+                results = result.as_ref().map_or(0, Vec::len),
+                latency_ms = candidate_latency.as_millis() as u64,
+                primary_latency_ms = primary_latency.as_millis() as u64,
+                overlap = ?overlap,
+                "shadow search finished"
+            );
+            metrics.record(
+                ShadowKind::Search,
+                &primary_id,
+                candidate.provider_id(),
+                &ShadowSample {
+                    primary_latency,
+                    candidate_latency,
+                    primary_ok: primary_urls.is_some(),
+                    candidate_ok: result.is_ok(),
+                    overlap,
+                },
+            );
+        });
+
+        tx
+    }
+}
+
+/// Jaccard overlap of the URLs the primary and the candidate returned. Two
+/// empty result sets agree.
+fn url_overlap(primary: &[String], candidate: &[SearchResult]) -> f32 {
+    let primary: HashSet<&str> = primary.iter().map(String::as_str).collect();
+    let candidate: HashSet<&str> = candidate.iter().map(|r| r.url.as_str()).collect();
+    match primary.union(&candidate).count() {
+        0 => 1.0,
+        union => primary.intersection(&candidate).count() as f32 / union as f32,
+    }
+}
+
+#[async_trait]
+impl SearchProvider for ShadowSearchProvider {
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>, SearchError> {
+        self.search_reported(query).await.result
+    }
+
+    async fn search_reported(&self, query: &SearchQuery) -> SearchReport {
+        let shadow = self.shadow(query);
+        let started = Instant::now();
+        let report = self.primary.search_reported(query).await;
+        let urls = report
+            .result
+            .as_ref()
+            .ok()
+            .map(|results| results.iter().map(|r| r.url.clone()).collect());
+        // The candidate may have been dropped with its runtime.
+        let _ = shadow.send((urls, started.elapsed()));
+        report
+    }
+
+    fn parse_raw(
+        &self,
+        provider: &str,
+        query: &SearchQuery,
+        raw: &str,
+    ) -> Result<Vec<SearchResult>, SearchError> {
+        self.primary.parse_raw(provider, query, raw)
+    }
+
+    async fn find_similar(
+        &self,
+        url: &str,
+        max_results: usize,
+    ) -> Result<Vec<SearchResult>, SearchError> {
+        self.primary.find_similar(url, max_results).await
+    }
+
+    fn provider_id(&self) -> &str {
+        self.primary.provider_id()
+    }
+
+    fn supports_find_similar(&self) -> bool {
+        self.primary.supports_find_similar()
+    }
+
+    fn supports_recency_filter(&self) -> bool {
+        self.primary.supports_recency_filter()
+    }
+
+    fn supports_domain_filter(&self) -> bool {
+        self.primary.supports_domain_filter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gorkd_core::MockSearchProvider;
+
+    async fn evaluations(metrics: &ShadowMetrics) -> Vec<gorkd_core::ShadowEvaluation> {
+        for _ in 0..100 {
+            let snapshot = metrics.snapshot();
+            if !snapshot.is_empty() {
+                return snapshot;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        panic!("shadow search was never recorded");
+    }
+
+    #[tokio::test]
+    async fn serves_primary_and_scores_candidate() {
+        let candidate = MockSearchProvider::new("candidate").with_results(vec![
+            SearchResult::new("https://example.com/article-1", "Same", "Content"),
+            SearchResult::new("https://other.example/", "Other", "Content"),
+        ]);
+        let metrics = Arc::new(ShadowMetrics::new());
+        let provider = ShadowSearchProvider::new(
+            Arc::new(MockSearchProvider::new("primary")),
+            Arc::new(candidate),
+            Arc::clone(&metrics),
+        );
+
+        let results = provider
+            .search(&SearchQuery::new("What is Rust?"))
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(provider.provider_id(), "primary");
+        let evaluations = evaluations(&metrics).await;
+        let stats = &evaluations[0].stats;
+        assert_eq!(evaluations[0].candidate, "candidate");
+        assert_eq!(stats.calls, 1);
+        assert_eq!(stats.mean_overlap(), Some(0.25));
+    }
+
+    #[tokio::test]
+    async fn candidate_failures_do_not_reach_the_caller() {
+        let metrics = Arc::new(ShadowMetrics::new());
+        let provider = ShadowSearchProvider::new(
+            Arc::new(MockSearchProvider::new("primary")),
+            Arc::new(MockSearchProvider::new("candidate").fail_after(0)),
+            Arc::clone(&metrics),
+        );
+
+        let report = provider
+            .search_reported(&SearchQuery::new("What is Rust?"))
+            .await;
+
+        assert!(report.result.is_ok());
+        assert_eq!(report.attempts.len(), 1);
+        let stats = &evaluations(&metrics).await[0].stats;
+        assert_eq!(stats.candidate_errors, 1);
+        assert_eq!(stats.mean_overlap(), None);
+    }
+}
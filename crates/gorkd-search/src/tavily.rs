@@ -2,17 +2,42 @@
 //!
 //! Tavily offers high-quality web search with relevance scoring, recency filters,
 //! and domain filtering. API docs: <https://docs.tavily.com/documentation/api-reference/endpoint/search>
+//!
+//! Optionally Tavily also returns its own short answer, the full text of each
+//! page and related images (see [`TavilyOptions`]). The answer becomes an
+//! extra pseudo-source and the full text replaces the separate fetch.
+
+use std::sync::Arc;
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use tracing::{debug, instrument, warn};
+use url::Url;
 
 use crate::client::HttpClient;
-use gorkd_core::{ContentType, Recency, SearchQuery};
+use gorkd_core::{ContentType, KeyOutcome, KeyPool, Recency, SearchQuery, WeightedKey};
 use gorkd_core::{SearchError, SearchProvider, SearchResult};
 
 const TAVILY_API_URL: &str = "https://api.tavily.com/search";
 const PROVIDER_ID: &str = "tavily";
+const ANSWER_URL: &str = "https://tavily.com/answer";
+
+/// Page text beyond this many characters is dropped to keep prompts bounded.
+pub const MAX_RAW_CONTENT_CHARS: usize = 20_000;
+
+/// Optional extras requested from Tavily with every search. Each costs
+/// response size and latency, so all are off by default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TavilyOptions {
+    /// Ask for Tavily's own answer and return it as a pseudo-source ranked
+    /// with the best result.
+    pub include_answer: bool,
+    /// Return each page's full text to use as the source body.
+    pub include_raw_content: bool,
+    /// Return images for the query, attached to the top result.
+    pub include_images: bool,
+}
 
 /// Tavily search provider.
 ///
@@ -20,27 +45,30 @@ const PROVIDER_ID: &str = "tavily";
 /// Supports recency filtering, domain filtering, and content type filtering.
 #[derive(Clone)]
 pub struct TavilyProvider {
-    api_key: String,
+    api_keys: Arc<KeyPool<String>>,
     client: HttpClient,
     search_depth: SearchDepth,
+    options: TavilyOptions,
 }
 
 impl TavilyProvider {
     /// Creates a new Tavily provider with the given API key.
     pub fn new(api_key: impl Into<String>) -> Self {
         Self {
-            api_key: api_key.into(),
+            api_keys: Arc::new(KeyPool::single(api_key.into())),
             client: HttpClient::default(),
             search_depth: SearchDepth::Basic,
+            options: TavilyOptions::default(),
         }
     }
 
     /// Creates a new Tavily provider with a custom HTTP client.
     pub fn with_client(api_key: impl Into<String>, client: HttpClient) -> Self {
         Self {
-            api_key: api_key.into(),
+            api_keys: Arc::new(KeyPool::single(api_key.into())),
             client,
             search_depth: SearchDepth::Basic,
+            options: TavilyOptions::default(),
         }
     }
 
@@ -53,6 +81,63 @@ impl TavilyProvider {
         self
     }
 
+    /// Uses `keys` in weighted turns in place of the single key, moving on
+    /// to the next when one is rate limited or rejected. An empty list
+    /// keeps the single key.
+    pub fn with_api_keys(mut self, keys: Vec<WeightedKey<String>>) -> Self {
+        if let Some(pool) = KeyPool::new(keys) {
+            self.api_keys = Arc::new(pool);
+        }
+        self
+    }
+
+    /// Sets which extras (answer, raw content, images) to request.
+    pub fn with_options(mut self, options: TavilyOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Sends `request` and returns the body of a successful response.
+    async fn send(&self, request: &TavilyRequest) -> Result<String, SearchError> {
+        self.api_keys
+            .run(|key| self.send_with_key(key, request))
+            .await
+    }
+
+    /// Sends `request` with `key`, reporting how the key fared.
+    async fn send_with_key(
+        &self,
+        key: String,
+        request: &TavilyRequest,
+    ) -> (Result<String, SearchError>, KeyOutcome) {
+        let timeout_secs = self.client.timeout().as_secs();
+        let response = match self
+            .client
+            .post(TAVILY_API_URL)
+            .header("Authorization", format!("Bearer {}", key))
+            .header("Content-Type", "application/json")
+            .json(request)
+            .send()
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => return (Err(map_reqwest_error(e, timeout_secs)), KeyOutcome::Failed),
+        };
+
+        let status = response.status();
+        let outcome = KeyOutcome::for_status(status.as_u16(), None);
+
+        if !status.is_success() {
+            return (Err(map_http_error(status)), outcome);
+        }
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| map_reqwest_error(e, timeout_secs));
+        (body, outcome)
+    }
+
     fn build_request(&self, query: &SearchQuery) -> TavilyRequest {
         let mut request = TavilyRequest {
             query: query.text.clone(),
@@ -60,10 +145,14 @@ impl TavilyProvider {
             max_results: 10,
             topic: None,
             time_range: None,
+            start_date: None,
+            end_date: None,
             include_domains: None,
             exclude_domains: None,
-            include_answer: false,
-            include_raw_content: false,
+            country: None,
+            include_answer: self.options.include_answer,
+            include_raw_content: self.options.include_raw_content,
+            include_images: self.options.include_images,
         };
 
         // Map recency filter
@@ -71,6 +160,19 @@ impl TavilyProvider {
             request.time_range = Some(map_recency(recency));
         }
 
+        // Absolute dates replace a recency window rather than narrow it.
+        if query.filters.has_date_range() {
+            request.time_range = None;
+            request.start_date = query
+                .filters
+                .published_after
+                .map(|date| date.format("%Y-%m-%d").to_string());
+            request.end_date = query
+                .filters
+                .published_before
+                .map(|date| date.format("%Y-%m-%d").to_string());
+        }
+
         // Map domain filters
         if let Some(ref domains) = query.filters.include_domains {
             if !domains.is_empty() {
@@ -89,14 +191,26 @@ impl TavilyProvider {
             request.topic = Some(map_content_type(content_type));
         }
 
+        // Tavily has no language filter; the country boost is the closest.
+        if !matches!(request.topic, Some(Topic::News | Topic::Finance)) {
+            request.country = query.filters.country.as_deref().and_then(country_name);
+        }
+
         request
     }
 }
 
 #[async_trait]
 impl SearchProvider for TavilyProvider {
-    #[instrument(skip(self), fields(provider = PROVIDER_ID))]
     async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>, SearchError> {
+        self.search_captured(query).await.0
+    }
+
+    #[instrument(skip(self), fields(provider = PROVIDER_ID))]
+    async fn search_captured(
+        &self,
+        query: &SearchQuery,
+    ) -> (Result<Vec<SearchResult>, SearchError>, Option<String>) {
         let request = self.build_request(query);
 
         debug!(
@@ -105,42 +219,24 @@ impl SearchProvider for TavilyProvider {
             "executing tavily search"
         );
 
-        let response = self
-            .client
-            .post(TAVILY_API_URL)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| map_reqwest_error(e, self.client.timeout().as_secs()))?;
-
-        let status = response.status();
-
-        if !status.is_success() {
-            return Err(map_http_error(status));
+        match self.send(&request).await {
+            Ok(body) => (parse_response(&body), Some(body)),
+            Err(e) => (Err(e), None),
         }
+    }
 
-        let tavily_response: TavilyResponse = response.json().await.map_err(|e| {
-            warn!(error = %e, "failed to parse tavily response");
-            SearchError::Provider(format!("failed to parse response: {}", e))
-        })?;
-
-        debug!(
-            result_count = tavily_response.results.len(),
-            response_time = %tavily_response.response_time,
-            "tavily search completed"
-        );
-
-        let results = tavily_response
-            .results
-            .into_iter()
-            .map(|r| {
-                SearchResult::new(r.url, r.title, r.content).with_score(r.score.unwrap_or(0.0))
-            })
-            .collect();
-
-        Ok(results)
+    fn parse_raw(
+        &self,
+        provider: &str,
+        _query: &SearchQuery,
+        raw: &str,
+    ) -> Result<Vec<SearchResult>, SearchError> {
+        if provider != PROVIDER_ID {
+            return Err(SearchError::ProviderUnavailable {
+                provider: provider.to_string(),
+            });
+        }
+        parse_response(raw)
     }
 
     fn provider_id(&self) -> &str {
@@ -202,20 +298,36 @@ struct TavilyRequest {
     topic: Option<Topic>,
     #[serde(skip_serializing_if = "Option::is_none")]
     time_range: Option<TimeRange>,
+    /// Earliest publication date, as `YYYY-MM-DD`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_date: Option<String>,
+    /// Latest publication date, as `YYYY-MM-DD`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_date: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     include_domains: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     exclude_domains: Option<Vec<String>>,
+    /// Boosts results from this country, by English name. Only applies to
+    /// the general topic.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    country: Option<&'static str>,
     include_answer: bool,
     include_raw_content: bool,
+    include_images: bool,
 }
 
 /// Response from Tavily search API.
 #[derive(Debug, Deserialize)]
 struct TavilyResponse {
-    #[allow(dead_code)]
     query: String,
+    /// Present when `include_answer` was requested.
+    #[serde(default)]
+    answer: Option<String>,
     results: Vec<TavilyResult>,
+    /// Present when `include_images` was requested.
+    #[serde(default)]
+    images: Vec<TavilyImage>,
     response_time: String,
 }
 
@@ -226,12 +338,112 @@ struct TavilyResult {
     url: String,
     content: String,
     score: Option<f32>,
+    /// Present when `include_raw_content` was requested.
+    #[serde(default)]
+    raw_content: Option<String>,
+    /// Present for `news` results, as an RFC 2822 date.
+    #[serde(default)]
+    published_date: Option<String>,
+}
+
+/// Tavily returns bare URLs, or objects when image descriptions are on.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum TavilyImage {
+    Url(String),
+    Described { url: String },
+}
+
+impl TavilyImage {
+    fn into_url(self) -> String {
+        match self {
+            Self::Url(url) | Self::Described { url } => url,
+        }
+    }
 }
 
 // ============================================================================
 // Mapping Functions
 // ============================================================================
 
+fn parse_response(body: &str) -> Result<Vec<SearchResult>, SearchError> {
+    let tavily_response: TavilyResponse = serde_json::from_str(body).map_err(|e| {
+        warn!(error = %e, "failed to parse tavily response");
+        SearchError::Provider(format!("failed to parse response: {}", e))
+    })?;
+
+    debug!(
+        result_count = tavily_response.results.len(),
+        response_time = %tavily_response.response_time,
+        "tavily search completed"
+    );
+
+    Ok(map_response(tavily_response))
+}
+
+fn map_response(response: TavilyResponse) -> Vec<SearchResult> {
+    let mut results: Vec<SearchResult> = response
+        .results
+        .into_iter()
+        .map(|r| {
+            let mut result =
+                SearchResult::new(r.url, r.title, r.content).with_score(r.score.unwrap_or(0.0));
+            if let Some(published_at) = r.published_date.as_deref().and_then(parse_date) {
+                result = result.with_published_at(published_at);
+            }
+            match r.raw_content.filter(|raw| !raw.trim().is_empty()) {
+                Some(raw) => result.with_raw_content(truncate_chars(raw, MAX_RAW_CONTENT_CHARS)),
+                None => result,
+            }
+        })
+        .collect();
+
+    if let Some(first) = results.first_mut() {
+        first.images = response
+            .images
+            .into_iter()
+            .map(TavilyImage::into_url)
+            .collect();
+    }
+
+    if let Some(answer) = response.answer.filter(|a| !a.trim().is_empty()) {
+        let best_score = results
+            .iter()
+            .map(|r| r.score)
+            .reduce(f32::max)
+            .unwrap_or(1.0);
+        results.insert(0, answer_result(&response.query, answer, best_score));
+    }
+
+    results
+}
+
+/// Wraps Tavily's answer as a search result with a per-query URL, so answers
+/// for different queries are not deduplicated against each other.
+fn answer_result(query: &str, answer: String, score: f32) -> SearchResult {
+    let url = Url::parse_with_params(ANSWER_URL, [("q", query)])
+        .map(String::from)
+        .unwrap_or_else(|_| ANSWER_URL.to_string());
+
+    SearchResult::new(url, format!("Tavily answer: {}", query), answer.clone())
+        .with_score(score)
+        .with_raw_content(answer)
+}
+
+fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(date)
+        .or_else(|_| DateTime::parse_from_rfc3339(date))
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+fn truncate_chars(text: String, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => text[..end].to_string(),
+        None => text,
+    }
+}
+
 fn map_recency(recency: &Recency) -> TimeRange {
     match recency {
         Recency::Day => TimeRange::Day,
@@ -249,6 +461,64 @@ fn map_content_type(content_type: &ContentType) -> Topic {
     }
 }
 
+/// Tavily's name for the country with ISO 3166-1 alpha-2 code `code`, for
+/// the countries it supports.
+fn country_name(code: &str) -> Option<&'static str> {
+    let name = match code.to_ascii_uppercase().as_str() {
+        "AR" => "argentina",
+        "AT" => "austria",
+        "AU" => "australia",
+        "BE" => "belgium",
+        "BR" => "brazil",
+        "CA" => "canada",
+        "CH" => "switzerland",
+        "CL" => "chile",
+        "CN" => "china",
+        "CO" => "colombia",
+        "CZ" => "czech republic",
+        "DE" => "germany",
+        "DK" => "denmark",
+        "EG" => "egypt",
+        "ES" => "spain",
+        "FI" => "finland",
+        "FR" => "france",
+        "GB" | "UK" => "united kingdom",
+        "GR" => "greece",
+        "HU" => "hungary",
+        "ID" => "indonesia",
+        "IE" => "ireland",
+        "IL" => "israel",
+        "IN" => "india",
+        "IT" => "italy",
+        "JP" => "japan",
+        "KR" => "south korea",
+        "MX" => "mexico",
+        "MY" => "malaysia",
+        "NG" => "nigeria",
+        "NL" => "netherlands",
+        "NO" => "norway",
+        "NZ" => "new zealand",
+        "PH" => "philippines",
+        "PK" => "pakistan",
+        "PL" => "poland",
+        "PT" => "portugal",
+        "RO" => "romania",
+        "SA" => "saudi arabia",
+        "SE" => "sweden",
+        "SG" => "singapore",
+        "TH" => "thailand",
+        "TR" => "turkey",
+        "TW" => "taiwan",
+        "UA" => "ukraine",
+        "AE" => "united arab emirates",
+        "US" => "united states",
+        "VN" => "vietnam",
+        "ZA" => "south africa",
+        _ => return None,
+    };
+    Some(name)
+}
+
 fn map_reqwest_error(error: reqwest::Error, timeout_secs: u64) -> SearchError {
     if error.is_timeout() {
         SearchError::Timeout { timeout_secs }
@@ -345,6 +615,41 @@ mod tests {
         assert!(matches!(request.topic, Some(Topic::News)));
     }
 
+    #[test]
+    fn builds_request_with_date_range() {
+        let provider = TavilyProvider::new("test-key");
+        let after = "2023-03-01T00:00:00Z".parse().unwrap();
+        let query = SearchQuery::new("test").with_filters(
+            SearchFilters::new()
+                .with_recency(Recency::Week)
+                .published_between(Some(after), None),
+        );
+
+        let request = provider.build_request(&query);
+
+        assert!(request.time_range.is_none());
+        assert_eq!(request.start_date.as_deref(), Some("2023-03-01"));
+        assert!(request.end_date.is_none());
+    }
+
+    #[test]
+    fn builds_request_with_country_boost() {
+        let provider = TavilyProvider::new("test-key");
+        let filters = SearchFilters::new().with_language("de").with_country("de");
+
+        let request =
+            provider.build_request(&SearchQuery::new("test").with_filters(filters.clone()));
+        assert_eq!(request.country, Some("germany"));
+
+        let news = filters.with_content_type(ContentType::News);
+        let request = provider.build_request(&SearchQuery::new("test").with_filters(news));
+        assert!(request.country.is_none());
+
+        let unknown = SearchFilters::new().with_country("XX");
+        let request = provider.build_request(&SearchQuery::new("test").with_filters(unknown));
+        assert!(request.country.is_none());
+    }
+
     #[test]
     fn maps_all_recency_values() {
         assert!(matches!(map_recency(&Recency::Day), TimeRange::Day));
@@ -395,10 +700,14 @@ mod tests {
             max_results: 5,
             topic: Some(Topic::News),
             time_range: Some(TimeRange::Week),
+            start_date: None,
+            end_date: None,
             include_domains: Some(vec!["example.com".to_string()]),
             exclude_domains: None,
+            country: None,
             include_answer: false,
             include_raw_content: false,
+            include_images: false,
         };
 
         let json = serde_json::to_string(&request).unwrap();
@@ -438,6 +747,29 @@ mod tests {
         assert_eq!(response.response_time, "1.23");
     }
 
+    #[test]
+    fn parses_captured_responses_of_its_own() {
+        let provider = TavilyProvider::new("tvly-test-key");
+        let query = SearchQuery::new("test query");
+        let raw = r#"{
+            "query": "test query",
+            "results": [
+                {"title": "Test", "url": "https://example.com", "content": "Snippet", "score": 0.5}
+            ],
+            "response_time": "0.5"
+        }"#;
+
+        let results = provider.parse_raw("tavily", &query, raw).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url, "https://example.com");
+
+        assert!(matches!(
+            provider.parse_raw("exa", &query, raw),
+            Err(SearchError::ProviderUnavailable { .. })
+        ));
+        assert!(provider.parse_raw("tavily", &query, "{").is_err());
+    }
+
     #[test]
     fn deserializes_response_without_score() {
         let json = r#"{
@@ -466,6 +798,100 @@ mod tests {
 
         assert!(matches!(request.search_depth, SearchDepth::Advanced));
     }
+
+    #[test]
+    fn requests_configured_extras() {
+        let provider = TavilyProvider::new("key").with_options(TavilyOptions {
+            include_answer: true,
+            include_raw_content: true,
+            include_images: false,
+        });
+
+        let request = provider.build_request(&SearchQuery::new("test"));
+        let json = serde_json::to_string(&request).unwrap();
+
+        assert!(json.contains("\"include_answer\":true"));
+        assert!(json.contains("\"include_raw_content\":true"));
+        assert!(json.contains("\"include_images\":false"));
+    }
+
+    #[test]
+    fn maps_plain_response_without_extras() {
+        let json = r#"{
+            "query": "test",
+            "results": [
+                {"title": "A", "url": "https://a.example", "content": "Snippet A", "score": 0.9,
+                 "published_date": "Mon, 15 Jul 2024 09:30:00 GMT"}
+            ],
+            "response_time": "1.0"
+        }"#;
+
+        let results = map_response(serde_json::from_str(json).unwrap());
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].snippet, "Snippet A");
+        assert_eq!(
+            results[0].published_at.unwrap().to_rfc3339(),
+            "2024-07-15T09:30:00+00:00"
+        );
+        assert!(results[0].raw_content.is_none());
+        assert!(results[0].images.is_empty());
+    }
+
+    #[test]
+    fn maps_answer_raw_content_and_images() {
+        let json = r#"{
+            "query": "what is rust",
+            "answer": "Rust is a systems programming language.",
+            "images": [
+                "https://img.example/1.png",
+                {"url": "https://img.example/2.png", "description": "Ferris"}
+            ],
+            "results": [
+                {
+                    "title": "A",
+                    "url": "https://a.example",
+                    "content": "Snippet A",
+                    "score": 0.7,
+                    "raw_content": "Full text of A"
+                },
+                {
+                    "title": "B",
+                    "url": "https://b.example",
+                    "content": "Snippet B",
+                    "score": 0.9,
+                    "raw_content": null
+                }
+            ],
+            "response_time": "1.0"
+        }"#;
+
+        let results = map_response(serde_json::from_str(json).unwrap());
+
+        assert_eq!(results.len(), 3);
+        let answer = &results[0];
+        assert_eq!(answer.url, "https://tavily.com/answer?q=what+is+rust");
+        assert_eq!(
+            answer.raw_content.as_deref(),
+            Some("Rust is a systems programming language.")
+        );
+        assert_eq!(answer.score, 0.9);
+
+        assert_eq!(results[1].raw_content.as_deref(), Some("Full text of A"));
+        assert_eq!(
+            results[1].images,
+            vec!["https://img.example/1.png", "https://img.example/2.png"]
+        );
+        assert!(results[2].raw_content.is_none());
+        assert!(results[2].images.is_empty());
+    }
+
+    #[test]
+    fn truncates_long_raw_content() {
+        let text = "é".repeat(10);
+        assert_eq!(truncate_chars(text.clone(), 4), "éééé");
+        assert_eq!(truncate_chars(text.clone(), 20), text);
+    }
 }
 
 #[cfg(all(test, feature = "integration"))]
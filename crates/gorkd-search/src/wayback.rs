@@ -0,0 +1,220 @@
+//! Wayback Machine copies of pages that are gone or paywalled.
+//!
+//! Search indexes lag behind the web, so some results point at pages that
+//! now answer `404`/`410`, and others at articles behind a paywall. Rather
+//! than keep the provider's snippet for such a source and cite a page the
+//! reader cannot open, [`HttpContentFetcher`](crate::HttpContentFetcher)
+//! can ask the Internet Archive's availability API for the closest snapshot
+//! of the page and read that instead, marking the document with an
+//! [`ArchivedSnapshot`].
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde_json::Value;
+use tracing::{debug, instrument};
+
+use crate::client::HttpClient;
+use crate::fetch::map_reqwest_error;
+use gorkd_core::{ArchivedSnapshot, FetchedDocument, SearchError};
+
+/// The Wayback Machine availability API.
+pub const AVAILABILITY_URL: &str = "https://archive.org/wayback/available";
+
+/// Where snapshots are served from.
+const SNAPSHOT_BASE: &str = "https://web.archive.org/web";
+
+/// Format of the capture timestamps in snapshot URLs.
+const TIMESTAMP_FORMAT: &str = "%Y%m%d%H%M%S";
+
+/// Extracted text shorter than this, in words, may be a paywall teaser.
+const MAX_PAYWALL_WORDS: usize = 600;
+
+/// Phrases paywalls put in place of an article.
+const PAYWALL_MARKERS: &[&str] = &[
+    "subscribe to continue reading",
+    "subscribe to read",
+    "subscribe to keep reading",
+    "to continue reading, please",
+    "this article is for subscribers",
+    "this content is for subscribers",
+    "available to subscribers only",
+    "already a subscriber",
+    "create a free account to continue",
+    "sign in to continue reading",
+    "log in to continue reading",
+];
+
+/// Looks up snapshots in the Wayback Machine.
+#[derive(Clone)]
+pub struct WaybackMachine {
+    client: HttpClient,
+    availability_url: String,
+}
+
+impl WaybackMachine {
+    /// Queries the public availability API with `client`.
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            availability_url: AVAILABILITY_URL.to_string(),
+        }
+    }
+
+    /// Queries another availability endpoint, such as a mirror or a test
+    /// server.
+    pub fn with_availability_url(mut self, url: impl Into<String>) -> Self {
+        self.availability_url = url.into();
+        self
+    }
+
+    /// The snapshot of `url` captured closest to now, if the archive has a
+    /// readable one.
+    #[instrument(skip(self))]
+    pub async fn closest(&self, url: &str) -> Result<Option<ArchivedSnapshot>, SearchError> {
+        let response = self
+            .client
+            .get(&self.availability_url)
+            .query(&[("url", url)])
+            .send()
+            .await
+            .map_err(|e| map_reqwest_error(e, self.client.timeout().as_secs()))?;
+        if !response.status().is_success() {
+            return Err(SearchError::Provider(format!(
+                "Wayback Machine HTTP {}",
+                response.status()
+            )));
+        }
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| SearchError::Provider(format!("invalid availability response: {}", e)))?;
+
+        let snapshot = parse_availability(&body, url);
+        debug!(found = snapshot.is_some(), "looked up archived snapshot");
+        Ok(snapshot)
+    }
+}
+
+impl std::fmt::Debug for WaybackMachine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WaybackMachine")
+            .field("availability_url", &self.availability_url)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Reads the closest snapshot of `url` from an availability API response.
+/// Snapshots of error pages and redirects are not used.
+pub fn parse_availability(body: &Value, url: &str) -> Option<ArchivedSnapshot> {
+    let closest = body.get("archived_snapshots")?.get("closest")?;
+    if closest.get("available").and_then(Value::as_bool) != Some(true) {
+        return None;
+    }
+    if closest
+        .get("status")
+        .and_then(Value::as_str)
+        .is_some_and(|status| status != "200")
+    {
+        return None;
+    }
+    let timestamp = closest.get("timestamp")?.as_str()?;
+    let captured_at = parse_timestamp(timestamp)?;
+
+    Some(ArchivedSnapshot {
+        url: format!("{}/{}/{}", SNAPSHOT_BASE, timestamp, url),
+        captured_at,
+    })
+}
+
+/// The snapshot of `url` as it was captured, without the archive's banner
+/// and with links left as they were.
+pub fn raw_snapshot_url(snapshot: &ArchivedSnapshot, url: &str) -> String {
+    format!(
+        "{}/{}id_/{}",
+        SNAPSHOT_BASE,
+        snapshot.captured_at.format(TIMESTAMP_FORMAT),
+        url
+    )
+}
+
+fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT)
+        .ok()
+        .map(|time| time.and_utc())
+}
+
+/// Whether `document` looks like the teaser a paywall shows instead of the
+/// article: short, and asking the reader to subscribe or sign in.
+pub fn looks_paywalled(document: &FetchedDocument) -> bool {
+    if document.text.split_whitespace().count() > MAX_PAYWALL_WORDS {
+        return false;
+    }
+    let text = document.text.to_lowercase();
+    PAYWALL_MARKERS.iter().any(|marker| text.contains(marker))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use gorkd_core::DocumentFormat;
+    use serde_json::json;
+
+    const PAGE: &str = "https://example.com/news/story";
+
+    #[test]
+    fn parses_closest_snapshot() {
+        let body = json!({
+            "url": PAGE,
+            "archived_snapshots": {
+                "closest": {
+                    "status": "200",
+                    "available": true,
+                    "url": "http://web.archive.org/web/20240719043012/https://example.com/news/story",
+                    "timestamp": "20240719043012"
+                }
+            }
+        });
+
+        let snapshot = parse_availability(&body, PAGE).unwrap();
+
+        assert_eq!(
+            snapshot.url,
+            "https://web.archive.org/web/20240719043012/https://example.com/news/story"
+        );
+        assert_eq!(
+            snapshot.captured_at,
+            Utc.with_ymd_and_hms(2024, 7, 19, 4, 30, 12).unwrap()
+        );
+        assert_eq!(
+            raw_snapshot_url(&snapshot, PAGE),
+            "https://web.archive.org/web/20240719043012id_/https://example.com/news/story"
+        );
+    }
+
+    #[test]
+    fn skips_missing_and_error_snapshots() {
+        assert!(parse_availability(&json!({ "archived_snapshots": {} }), PAGE).is_none());
+
+        let error_page = json!({
+            "archived_snapshots": {
+                "closest": { "status": "404", "available": true, "timestamp": "20240719043012" }
+            }
+        });
+        assert!(parse_availability(&error_page, PAGE).is_none());
+    }
+
+    #[test]
+    fn detects_paywall_teasers() {
+        let teaser = FetchedDocument::new(
+            "Markets rally as rates fall\nThe Fed signaled... Subscribe to continue reading.",
+            DocumentFormat::Html,
+        );
+        let article = FetchedDocument::new(
+            format!("{} Already a subscriber? Sign in.", "word ".repeat(1_000)),
+            DocumentFormat::Html,
+        );
+
+        assert!(looks_paywalled(&teaser));
+        assert!(!looks_paywalled(&article));
+    }
+}
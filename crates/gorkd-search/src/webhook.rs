@@ -0,0 +1,424 @@
+//! Webhook search provider for internal and enterprise search systems.
+//!
+//! Any HTTP endpoint that speaks a small JSON contract can be registered as a
+//! search provider, so an Elasticsearch index, a Confluence space or a
+//! SharePoint site can be searched through a thin adapter instead of a new
+//! provider in Rust. Each query is POSTed to the endpoint as:
+//!
+//! ```json
+//! {
+//!   "query": "How do we rotate database credentials?",
+//!   "max_results": 10,
+//!   "filters": {"recency": "year", "include_domains": ["wiki.corp.example"]}
+//! }
+//! ```
+//!
+//! `filters` holds only the filters the query sets, named as in the research
+//! API: `recency`, `include_domains`, `exclude_domains`, `content_type`,
+//! `language`, `country`, `published_after` and `published_before`. The
+//! endpoint answers with its results, best first:
+//!
+//! ```json
+//! {
+//!   "results": [{
+//!     "url": "https://wiki.corp.example/ops/credentials",
+//!     "title": "Credential rotation",
+//!     "snippet": "Database credentials are rotated every 90 days...",
+//!     "content": "Full page text, optional",
+//!     "score": 0.92,
+//!     "published_at": "2024-05-01T00:00:00Z"
+//!   }]
+//! }
+//! ```
+//!
+//! Only `url` and `title` are required. Scores run from 0 to 1; results
+//! without one are scored by their position. A non-2xx status fails the
+//! search: 401 and 403 mark the provider unavailable, 429 rate-limited.
+
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument, warn};
+use url::Url;
+
+use crate::client::HttpClient;
+use gorkd_core::{SearchError, SearchFilters, SearchProvider, SearchQuery, SearchResult};
+
+const DEFAULT_MAX_RESULTS: usize = 10;
+
+/// An endpoint to register as a search provider.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct WebhookConfig {
+    /// Provider ID, used in routes, logs and source metadata.
+    pub id: String,
+    /// Endpoint each query is POSTed to.
+    pub url: String,
+    /// Headers sent with every request, such as `Authorization`.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Whether the endpoint honors the `recency` and date filters.
+    #[serde(default)]
+    pub supports_recency: bool,
+    /// Whether the endpoint honors the domain filters.
+    #[serde(default)]
+    pub supports_domains: bool,
+}
+
+impl WebhookConfig {
+    /// An endpoint with no headers or filter support.
+    pub fn new(id: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            url: url.into(),
+            headers: BTreeMap::new(),
+            supports_recency: false,
+            supports_domains: false,
+        }
+    }
+
+    /// Sends `Authorization: Bearer <token>` with every request.
+    pub fn with_bearer_token(mut self, token: impl AsRef<str>) -> Self {
+        self.headers.insert(
+            "Authorization".to_string(),
+            format!("Bearer {}", token.as_ref()),
+        );
+        self
+    }
+
+    /// Parses a JSON array of endpoints and checks them with
+    /// [`validate`](Self::validate).
+    pub fn parse_list(json: &str) -> Result<Vec<Self>, String> {
+        let webhooks: Vec<Self> = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        for webhook in &webhooks {
+            webhook.validate()?;
+        }
+        Ok(webhooks)
+    }
+
+    /// Checks that the ID is usable and the URL is an absolute HTTP(S) URL.
+    pub fn validate(&self) -> Result<(), String> {
+        let id_ok = !self.id.is_empty()
+            && self
+                .id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+        if !id_ok {
+            return Err(format!(
+                "webhook id {:?} must be lowercase letters, digits, '-' or '_'",
+                self.id
+            ));
+        }
+        match Url::parse(&self.url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(()),
+            Ok(_) => Err(format!(
+                "webhook {} URL must be http or https: {}",
+                self.id, self.url
+            )),
+            Err(e) => Err(format!("webhook {} URL {:?}: {}", self.id, self.url, e)),
+        }
+    }
+}
+
+/// A search provider backed by an HTTP endpoint implementing the contract
+/// in the module documentation.
+#[derive(Clone)]
+pub struct WebhookSearchProvider {
+    config: WebhookConfig,
+    client: HttpClient,
+    max_results: usize,
+}
+
+impl WebhookSearchProvider {
+    /// Creates a provider for `config` with a default HTTP client.
+    pub fn new(config: WebhookConfig) -> Self {
+        Self::with_client(config, HttpClient::default())
+    }
+
+    /// Creates a provider for `config` sending requests through `client`.
+    pub fn with_client(config: WebhookConfig, client: HttpClient) -> Self {
+        Self {
+            config,
+            client,
+            max_results: DEFAULT_MAX_RESULTS,
+        }
+    }
+
+    /// Sets how many results each request asks for.
+    pub fn with_max_results(mut self, max_results: usize) -> Self {
+        self.max_results = max_results;
+        self
+    }
+
+    fn build_request<'a>(&self, query: &'a SearchQuery) -> WebhookRequest<'a> {
+        WebhookRequest {
+            query: &query.text,
+            max_results: self.max_results,
+            filters: &query.filters,
+        }
+    }
+
+    fn map_http_error(&self, status: reqwest::StatusCode) -> SearchError {
+        match status.as_u16() {
+            401 | 403 => SearchError::ProviderUnavailable {
+                provider: self.config.id.clone(),
+            },
+            429 => SearchError::RateLimited {
+                provider: self.config.id.clone(),
+            },
+            400 => SearchError::InvalidQuery {
+                reason: "bad request".to_string(),
+            },
+            _ => SearchError::Provider(format!("HTTP {}", status)),
+        }
+    }
+
+    /// Posts `request` to the endpoint and returns the body of a successful
+    /// response.
+    async fn send(&self, request: &WebhookRequest<'_>) -> Result<String, SearchError> {
+        let mut builder = self.client.post(&self.config.url).json(request);
+        for (name, value) in &self.config.headers {
+            builder = builder.header(name, value);
+        }
+        let response = builder
+            .send()
+            .await
+            .map_err(|e| map_reqwest_error(e, self.client.timeout().as_secs()))?;
+
+        let status = response.status();
+
+        if !status.is_success() {
+            return Err(self.map_http_error(status));
+        }
+
+        response
+            .text()
+            .await
+            .map_err(|e| map_reqwest_error(e, self.client.timeout().as_secs()))
+    }
+}
+
+#[async_trait]
+impl SearchProvider for WebhookSearchProvider {
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>, SearchError> {
+        self.search_captured(query).await.0
+    }
+
+    #[instrument(skip(self), fields(provider = %self.config.id))]
+    async fn search_captured(
+        &self,
+        query: &SearchQuery,
+    ) -> (Result<Vec<SearchResult>, SearchError>, Option<String>) {
+        let request = self.build_request(query);
+
+        debug!(query = %request.query, "executing webhook search");
+
+        match self.send(&request).await {
+            Ok(body) => (parse_response(&body), Some(body)),
+            Err(e) => (Err(e), None),
+        }
+    }
+
+    fn parse_raw(
+        &self,
+        provider: &str,
+        _query: &SearchQuery,
+        raw: &str,
+    ) -> Result<Vec<SearchResult>, SearchError> {
+        if provider != self.config.id {
+            return Err(SearchError::ProviderUnavailable {
+                provider: provider.to_string(),
+            });
+        }
+        parse_response(raw)
+    }
+
+    fn provider_id(&self) -> &str {
+        &self.config.id
+    }
+
+    fn supports_recency_filter(&self) -> bool {
+        self.config.supports_recency
+    }
+
+    fn supports_domain_filter(&self) -> bool {
+        self.config.supports_domains
+    }
+}
+
+// ============================================================================
+// Request/Response Types
+// ============================================================================
+
+/// Request body POSTed to the endpoint.
+#[derive(Debug, Serialize)]
+struct WebhookRequest<'a> {
+    query: &'a str,
+    max_results: usize,
+    filters: &'a SearchFilters,
+}
+
+/// Response body expected from the endpoint.
+#[derive(Debug, Deserialize)]
+struct WebhookResponse {
+    #[serde(default)]
+    results: Vec<WebhookResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WebhookResult {
+    url: String,
+    title: String,
+    #[serde(default)]
+    snippet: String,
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    score: Option<f32>,
+    #[serde(default)]
+    published_at: Option<DateTime<Utc>>,
+}
+
+// ============================================================================
+// Mapping Functions
+// ============================================================================
+
+fn parse_response(body: &str) -> Result<Vec<SearchResult>, SearchError> {
+    let webhook_response: WebhookResponse = serde_json::from_str(body).map_err(|e| {
+        warn!(error = %e, "failed to parse webhook response");
+        SearchError::Provider(format!("failed to parse response: {}", e))
+    })?;
+
+    debug!(
+        result_count = webhook_response.results.len(),
+        "webhook search completed"
+    );
+
+    Ok(map_results(webhook_response.results))
+}
+
+fn map_results(results: Vec<WebhookResult>) -> Vec<SearchResult> {
+    let count = results.len();
+    results
+        .into_iter()
+        .enumerate()
+        .map(|(i, r)| {
+            let score = r
+                .score
+                .unwrap_or_else(|| position_score(i, count))
+                .clamp(0.0, 1.0);
+            let mut result = SearchResult::new(r.url, r.title, r.snippet).with_score(score);
+            if let Some(content) = r.content.filter(|c| !c.is_empty()) {
+                result = result.with_raw_content(content);
+            }
+            match r.published_at {
+                Some(published_at) => result.with_published_at(published_at),
+                None => result,
+            }
+        })
+        .collect()
+}
+
+/// Score of the result at `position` of `count` without its own: from 1 for
+/// the first down to just above 0 for the last.
+fn position_score(position: usize, count: usize) -> f32 {
+    1.0 - position as f32 / count.max(1) as f32
+}
+
+fn map_reqwest_error(error: reqwest::Error, timeout_secs: u64) -> SearchError {
+    if error.is_timeout() {
+        SearchError::Timeout { timeout_secs }
+    } else if error.is_connect() {
+        SearchError::Network(format!("connection failed: {}", error))
+    } else {
+        SearchError::Network(error.to_string())
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gorkd_core::Recency;
+
+    fn provider() -> WebhookSearchProvider {
+        WebhookSearchProvider::new(WebhookConfig::new("wiki", "https://search.corp.example/"))
+    }
+
+    #[test]
+    fn serializes_only_set_filters() {
+        let provider = provider().with_max_results(5);
+        let query = SearchQuery::new("credential rotation").with_filters(
+            SearchFilters::new()
+                .with_recency(Recency::Year)
+                .include_domains(["wiki.corp.example"]),
+        );
+
+        let json = serde_json::to_value(provider.build_request(&query)).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "query": "credential rotation",
+                "max_results": 5,
+                "filters": {"recency": "year", "include_domains": ["wiki.corp.example"]}
+            })
+        );
+    }
+
+    #[test]
+    fn maps_results_and_scores_by_position() {
+        let response: WebhookResponse = serde_json::from_str(
+            r#"{"results": [
+                {"url": "https://a.example/", "title": "A", "content": "Full text",
+                 "published_at": "2024-05-01T00:00:00Z"},
+                {"url": "https://b.example/", "title": "B", "snippet": "B snippet", "score": 1.7}
+            ]}"#,
+        )
+        .unwrap();
+
+        let results = map_results(response.results);
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].score, 1.0);
+        assert_eq!(results[0].raw_content.as_deref(), Some("Full text"));
+        assert!(results[0].published_at.is_some());
+        assert_eq!(results[1].snippet, "B snippet");
+        assert_eq!(results[1].score, 1.0);
+        assert_eq!(position_score(1, 4), 0.75);
+    }
+
+    #[test]
+    fn validates_configs() {
+        let webhooks = WebhookConfig::parse_list(
+            r#"[{"id": "confluence", "url": "https://adapter.corp.example/search",
+                 "headers": {"Authorization": "Bearer t"}, "supports_domains": true}]"#,
+        )
+        .unwrap();
+        assert_eq!(webhooks[0].headers["Authorization"], "Bearer t");
+        assert!(webhooks[0].supports_domains);
+        assert!(!webhooks[0].supports_recency);
+
+        assert!(WebhookConfig::new("Wiki", "https://a.example")
+            .validate()
+            .is_err());
+        assert!(WebhookConfig::new("wiki", "ftp://a.example")
+            .validate()
+            .is_err());
+        assert!(WebhookConfig::new("wiki", "a.example").validate().is_err());
+        assert!(WebhookConfig::parse_list(r#"[{"id": "wiki"}]"#).is_err());
+    }
+
+    #[test]
+    fn maps_auth_failures_to_unavailable() {
+        let error = provider().map_http_error(reqwest::StatusCode::FORBIDDEN);
+        assert!(matches!(
+            error,
+            SearchError::ProviderUnavailable { ref provider } if provider == "wiki"
+        ));
+    }
+}
@@ -0,0 +1,354 @@
+//! YouTube transcript fetching.
+//!
+//! Search providers describe a video by its title and description, which
+//! rarely says what the video actually covers. For YouTube links, the
+//! fetcher reads the caption tracks listed on the watch page, downloads the
+//! best one from YouTube's `timedtext` endpoint and returns it one caption
+//! per line, each prefixed with its `[m:ss]` timestamp so quotes can be
+//! traced back to the moment they were said.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use tracing::{debug, instrument};
+
+use crate::client::HttpClient;
+use gorkd_core::{ContentFetcher, DocumentFormat, FetchedDocument, SearchError};
+
+const WATCH_URL: &str = "https://www.youtube.com/watch";
+
+/// Fetches transcripts of YouTube videos, handing other URLs to a fallback
+/// fetcher if one is set.
+///
+/// Implements the `ContentFetcher` trait. Video URLs are fetched even when
+/// the search provider returned content for them, since the transcript is
+/// usually far more useful than the description.
+pub struct YouTubeTranscriptFetcher {
+    client: HttpClient,
+    languages: Vec<String>,
+    fallback: Option<Arc<dyn ContentFetcher>>,
+}
+
+impl YouTubeTranscriptFetcher {
+    /// Creates a fetcher that prefers English captions.
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            languages: vec!["en".to_string()],
+            fallback: None,
+        }
+    }
+
+    /// Sets the caption languages to look for, most preferred first. Videos
+    /// with none of them use their first caption track.
+    pub fn with_languages(mut self, languages: Vec<String>) -> Self {
+        self.languages = languages;
+        self
+    }
+
+    /// Fetches URLs that are not YouTube videos with `fetcher`.
+    pub fn with_fallback(mut self, fetcher: Arc<dyn ContentFetcher>) -> Self {
+        self.fallback = Some(fetcher);
+        self
+    }
+
+    async fn get_text(&self, url: &str) -> Result<String, SearchError> {
+        let timeout_secs = self.client.timeout().as_secs();
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| map_reqwest_error(e, timeout_secs))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(SearchError::Provider(format!("HTTP {}", status)));
+        }
+
+        response
+            .text()
+            .await
+            .map_err(|e| map_reqwest_error(e, timeout_secs))
+    }
+
+    async fn fetch_transcript(&self, video_id: &str) -> Result<FetchedDocument, SearchError> {
+        let page = self
+            .get_text(&format!("{}?v={}", WATCH_URL, video_id))
+            .await?;
+        let tracks = parse_caption_tracks(&page)?;
+        let track = select_track(&tracks, &self.languages)
+            .ok_or_else(|| SearchError::Provider(format!("video {} has no captions", video_id)))?;
+
+        let body = self
+            .get_text(&format!("{}&fmt=json3", track.base_url))
+            .await?;
+        let transcript: Json3Transcript = serde_json::from_str(&body)
+            .map_err(|e| SearchError::Provider(format!("invalid transcript: {}", e)))?;
+        let text = format_transcript(&transcript);
+        if text.is_empty() {
+            return Err(SearchError::Provider(format!(
+                "video {} has an empty transcript",
+                video_id
+            )));
+        }
+
+        debug!(
+            video_id,
+            language = %track.language_code,
+            generated = track.is_generated(),
+            "fetched transcript"
+        );
+        Ok(FetchedDocument::new(text, DocumentFormat::Transcript))
+    }
+}
+
+#[async_trait]
+impl ContentFetcher for YouTubeTranscriptFetcher {
+    #[instrument(skip(self), fields(fetcher = "youtube"))]
+    async fn fetch(&self, url: &str) -> Result<FetchedDocument, SearchError> {
+        match (video_id(url), &self.fallback) {
+            (Some(id), _) => self.fetch_transcript(&id).await,
+            (None, Some(fallback)) => fallback.fetch(url).await,
+            (None, None) => Err(SearchError::Provider(format!(
+                "not a YouTube video: {}",
+                url
+            ))),
+        }
+    }
+
+    fn fetcher_name(&self) -> &str {
+        "youtube"
+    }
+
+    fn replaces_provider_content(&self, url: &str) -> bool {
+        video_id(url).is_some()
+            || self
+                .fallback
+                .as_ref()
+                .is_some_and(|fallback| fallback.replaces_provider_content(url))
+    }
+}
+
+/// Extracts the video ID from watch, short, embed and `youtu.be` links.
+pub fn video_id(url: &str) -> Option<String> {
+    let url = url::Url::parse(url).ok()?;
+    let host = url.host_str()?.trim_start_matches("www.");
+    let mut segments = url.path_segments()?;
+
+    let id = match host {
+        "youtu.be" => segments.next()?.to_string(),
+        "youtube.com" | "m.youtube.com" | "music.youtube.com" => match segments.next()? {
+            "watch" => url
+                .query_pairs()
+                .find(|(key, _)| key == "v")
+                .map(|(_, value)| value.into_owned())?,
+            "shorts" | "embed" | "live" | "v" => segments.next()?.to_string(),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    let valid = id.len() == 11
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+    valid.then_some(id)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CaptionTrack {
+    base_url: String,
+    language_code: String,
+    /// `asr` for captions generated by speech recognition.
+    #[serde(default)]
+    kind: Option<String>,
+}
+
+impl CaptionTrack {
+    fn is_generated(&self) -> bool {
+        self.kind.as_deref() == Some("asr")
+    }
+}
+
+/// Reads the `captionTracks` list embedded in a watch page.
+fn parse_caption_tracks(page: &str) -> Result<Vec<CaptionTrack>, SearchError> {
+    const MARKER: &str = "\"captionTracks\":";
+
+    let Some(start) = page.find(MARKER) else {
+        return Ok(Vec::new());
+    };
+    let list = &page[start + MARKER.len()..];
+
+    serde_json::Deserializer::from_str(list)
+        .into_iter::<Vec<CaptionTrack>>()
+        .next()
+        .unwrap_or(Ok(Vec::new()))
+        .map_err(|e| SearchError::Provider(format!("invalid caption track list: {}", e)))
+}
+
+/// Picks the first preferred language that has captions, favouring
+/// hand-written captions over generated ones.
+fn select_track<'a>(tracks: &'a [CaptionTrack], languages: &[String]) -> Option<&'a CaptionTrack> {
+    let matches = |track: &CaptionTrack, language: &str| {
+        let code = track.language_code.to_ascii_lowercase();
+        let language = language.to_ascii_lowercase();
+        code == language || code.starts_with(&format!("{}-", language))
+    };
+
+    languages
+        .iter()
+        .find_map(|language| {
+            let mut candidates = tracks.iter().filter(|track| matches(track, language));
+            let first = candidates.next()?;
+            Some(
+                std::iter::once(first)
+                    .chain(candidates)
+                    .find(|track| !track.is_generated())
+                    .unwrap_or(first),
+            )
+        })
+        .or_else(|| tracks.iter().find(|track| !track.is_generated()))
+        .or_else(|| tracks.first())
+}
+
+#[derive(Debug, Deserialize)]
+struct Json3Transcript {
+    #[serde(default)]
+    events: Vec<Json3Event>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Json3Event {
+    #[serde(default)]
+    t_start_ms: u64,
+    #[serde(default)]
+    segs: Vec<Json3Segment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Json3Segment {
+    #[serde(default)]
+    utf8: String,
+}
+
+fn format_transcript(transcript: &Json3Transcript) -> String {
+    let mut lines = Vec::with_capacity(transcript.events.len());
+
+    for event in &transcript.events {
+        let text: String = event.segs.iter().map(|seg| seg.utf8.as_str()).collect();
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if text.is_empty() {
+            continue;
+        }
+        lines.push(format!("[{}] {}", format_timestamp(event.t_start_ms), text));
+    }
+
+    lines.join("\n")
+}
+
+/// Formats as `m:ss`, or `h:mm:ss` from the first hour on.
+fn format_timestamp(ms: u64) -> String {
+    let secs = ms / 1000;
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+fn map_reqwest_error(error: reqwest::Error, timeout_secs: u64) -> SearchError {
+    if error.is_timeout() {
+        SearchError::Timeout { timeout_secs }
+    } else if error.is_connect() {
+        SearchError::Network(format!("connection failed: {}", error))
+    } else {
+        SearchError::Network(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(language_code: &str, kind: Option<&str>) -> CaptionTrack {
+        CaptionTrack {
+            base_url: format!(
+                "https://www.youtube.com/api/timedtext?lang={}",
+                language_code
+            ),
+            language_code: language_code.to_string(),
+            kind: kind.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn extracts_video_ids() {
+        let id = Some("dQw4w9WgXcQ".to_string());
+        assert_eq!(
+            video_id("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=42"),
+            id
+        );
+        assert_eq!(video_id("https://m.youtube.com/watch?v=dQw4w9WgXcQ"), id);
+        assert_eq!(video_id("https://youtu.be/dQw4w9WgXcQ?si=abc"), id);
+        assert_eq!(video_id("https://www.youtube.com/shorts/dQw4w9WgXcQ"), id);
+        assert_eq!(video_id("https://www.youtube.com/embed/dQw4w9WgXcQ"), id);
+        assert_eq!(video_id("https://www.youtube.com/@rustlang"), None);
+        assert_eq!(video_id("https://www.youtube.com/watch?v=short"), None);
+        assert_eq!(video_id("https://vimeo.com/123456789"), None);
+    }
+
+    #[test]
+    fn parses_caption_tracks_from_watch_page() {
+        let page = r#"<script>var ytInitialPlayerResponse = {"captions":{"playerCaptionsTracklistRenderer":{"captionTracks":[{"baseUrl":"https://www.youtube.com/api/timedtext?v=dQw4w9WgXcQ&lang=en","name":{"simpleText":"English"},"languageCode":"en","kind":"asr"}],"audioTracks":[]}}};</script>"#;
+
+        let tracks = parse_caption_tracks(page).unwrap();
+
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(
+            tracks[0].base_url,
+            "https://www.youtube.com/api/timedtext?v=dQw4w9WgXcQ&lang=en"
+        );
+        assert!(tracks[0].is_generated());
+        assert!(parse_caption_tracks("<html></html>").unwrap().is_empty());
+    }
+
+    #[test]
+    fn prefers_requested_language_and_written_captions() {
+        let tracks = vec![
+            track("de", None),
+            track("en", Some("asr")),
+            track("en-GB", None),
+        ];
+
+        let en = select_track(&tracks, &["en".to_string()]).unwrap();
+        assert_eq!(en.language_code, "en-GB");
+
+        let fr = select_track(&tracks, &["fr".to_string()]).unwrap();
+        assert_eq!(fr.language_code, "de");
+
+        assert!(select_track(&[], &["en".to_string()]).is_none());
+    }
+
+    #[test]
+    fn formats_transcript_with_timestamps() {
+        let transcript: Json3Transcript = serde_json::from_str(
+            r#"{"events":[
+                {"tStartMs":0,"dDurationMs":1200},
+                {"tStartMs":5200,"segs":[{"utf8":"Memory safety"},{"utf8":" without\nGC."}]},
+                {"tStartMs":65000,"segs":[{"utf8":"\n"}]},
+                {"tStartMs":3725000,"segs":[{"utf8":"Thanks for watching."}]}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            format_transcript(&transcript),
+            "[0:05] Memory safety without GC.\n[1:02:05] Thanks for watching."
+        );
+    }
+}
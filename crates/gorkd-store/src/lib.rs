@@ -1,9 +1,19 @@
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
-//! Storage implementations (Postgres, vector DB).
+//! Storage implementations (Postgres, vector DB, object storage).
 
+/// Object storage for large bodies.
+pub mod object;
+/// Store wrapper keeping large bodies in object storage.
+pub mod offload;
 /// PostgreSQL job storage.
 pub mod postgres;
 /// Vector storage for embeddings.
 pub mod vector;
+
+pub use object::{
+    DirectoryObjectStore, MemoryObjectStore, ObjectError, ObjectStore, S3Config, S3Credentials,
+    S3ObjectStore,
+};
+pub use offload::{ContentMetrics, ContentStats, OffloadingStore, DEFAULT_MIN_OFFLOAD_BYTES};
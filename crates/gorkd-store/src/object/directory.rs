@@ -0,0 +1,99 @@
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use tokio::fs;
+
+use super::{check_key, ObjectError, ObjectStore};
+
+/// Keeps objects as files under a local directory, one file per key.
+#[derive(Debug)]
+pub struct DirectoryObjectStore {
+    root: PathBuf,
+}
+
+impl DirectoryObjectStore {
+    /// Creates a store rooted at `root`, which is created on first write.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf, ObjectError> {
+        check_key(key)?;
+        Ok(self.root.join(key))
+    }
+}
+
+fn io_error(e: std::io::Error) -> ObjectError {
+    ObjectError::Unavailable(e.to_string())
+}
+
+#[async_trait]
+impl ObjectStore for DirectoryObjectStore {
+    async fn put(&self, key: &str, body: Vec<u8>) -> Result<(), ObjectError> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await.map_err(io_error)?;
+        }
+        // Written aside and renamed, so readers never see half an object.
+        let partial = path.with_extension("partial");
+        fs::write(&partial, body).await.map_err(io_error)?;
+        fs::rename(&partial, &path).await.map_err(io_error)
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, ObjectError> {
+        match fs::read(self.path(key)?).await {
+            Ok(body) => Ok(Some(body)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(io_error(e)),
+        }
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, ObjectError> {
+        match fs::metadata(self.path(key)?).await {
+            Ok(metadata) => Ok(metadata.is_file()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(io_error(e)),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), ObjectError> {
+        match fs::remove_file(self.path(key)?).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(io_error(e)),
+            _ => Ok(()),
+        }
+    }
+
+    fn backend_name(&self) -> &str {
+        "directory"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn round_trips_objects() {
+        let root = std::env::temp_dir().join(format!("gorkd-objects-{}", std::process::id()));
+        let store = DirectoryObjectStore::new(&root);
+
+        store
+            .put("sources/job_a/src_b", b"page text".to_vec())
+            .await
+            .unwrap();
+        assert_eq!(
+            store.get("sources/job_a/src_b").await.unwrap().as_deref(),
+            Some(&b"page text"[..])
+        );
+        assert!(store.exists("sources/job_a/src_b").await.unwrap());
+
+        store.delete("sources/job_a/src_b").await.unwrap();
+        assert!(store.get("sources/job_a/src_b").await.unwrap().is_none());
+        assert!(!store.exists("sources/job_a/src_b").await.unwrap());
+        store.delete("sources/job_a/src_b").await.unwrap();
+        assert!(store.put("../outside", Vec::new()).await.is_err());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+
+use super::{check_key, ObjectError, ObjectStore};
+
+/// Keeps objects in memory, for tests and development.
+#[derive(Debug, Default)]
+pub struct MemoryObjectStore {
+    objects: RwLock<HashMap<String, Vec<u8>>>,
+}
+
+impl MemoryObjectStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of objects held.
+    pub fn len(&self) -> usize {
+        self.objects.read().unwrap().len()
+    }
+
+    /// Whether the store holds no objects.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[async_trait]
+impl ObjectStore for MemoryObjectStore {
+    async fn put(&self, key: &str, body: Vec<u8>) -> Result<(), ObjectError> {
+        check_key(key)?;
+        self.objects.write().unwrap().insert(key.to_string(), body);
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, ObjectError> {
+        Ok(self.objects.read().unwrap().get(key).cloned())
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, ObjectError> {
+        Ok(self.objects.read().unwrap().contains_key(key))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), ObjectError> {
+        self.objects.write().unwrap().remove(key);
+        Ok(())
+    }
+
+    fn backend_name(&self) -> &str {
+        "memory"
+    }
+}
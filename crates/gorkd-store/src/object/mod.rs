@@ -0,0 +1,99 @@
+//! Object storage for bodies too large to belong in database rows.
+//!
+//! Objects are opaque bytes under `/`-separated keys such as
+//! `content/<sha256>`. Backends: a local directory, any
+//! S3-compatible service (AWS S3, MinIO), and memory for tests.
+
+mod directory;
+mod memory;
+mod s3;
+
+use async_trait::async_trait;
+use gorkd_core::StoreError;
+use thiserror::Error;
+
+pub use directory::DirectoryObjectStore;
+pub use memory::MemoryObjectStore;
+pub use s3::{S3Config, S3Credentials, S3ObjectStore};
+
+/// Error from an object storage backend.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ObjectError {
+    /// The key is empty, absolute or climbs out of the store with `..`.
+    #[error("invalid object key: {0}")]
+    InvalidKey(String),
+
+    /// The backend could not be reached or read.
+    #[error("object storage unavailable: {0}")]
+    Unavailable(String),
+
+    /// The backend refused the request.
+    #[error("object storage returned HTTP {status}: {message}")]
+    Http {
+        /// Response status code.
+        status: u16,
+        /// Response body, or the status reason when it had none.
+        message: String,
+    },
+}
+
+impl From<ObjectError> for StoreError {
+    fn from(err: ObjectError) -> Self {
+        match err {
+            ObjectError::Unavailable(_) => Self::Connection(err.to_string()),
+            _ => Self::Query(err.to_string()),
+        }
+    }
+}
+
+/// A place to keep large bodies by key.
+#[async_trait]
+pub trait ObjectStore: Send + Sync {
+    /// Writes `body` under `key`, replacing any object already there.
+    async fn put(&self, key: &str, body: Vec<u8>) -> Result<(), ObjectError>;
+
+    /// Reads the object under `key`, or `None` if there is none.
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, ObjectError>;
+
+    /// Whether there is an object under `key`, without reading it.
+    async fn exists(&self, key: &str) -> Result<bool, ObjectError>;
+
+    /// Removes the object under `key`. Removing a missing object succeeds.
+    async fn delete(&self, key: &str) -> Result<(), ObjectError>;
+
+    /// Short name of the backend, e.g. `s3`, for logs.
+    fn backend_name(&self) -> &str;
+}
+
+/// Rejects keys that could escape the store's root on backends that map keys
+/// to paths.
+fn check_key(key: &str) -> Result<(), ObjectError> {
+    let valid = !key.is_empty()
+        && !key.starts_with('/')
+        && key
+            .split('/')
+            .all(|segment| !segment.is_empty() && segment != "." && segment != "..");
+    if valid {
+        Ok(())
+    } else {
+        Err(ObjectError::InvalidKey(key.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_relative_keys() {
+        assert!(check_key("sources/job_abc/src_def").is_ok());
+    }
+
+    #[test]
+    fn rejects_keys_escaping_the_root() {
+        for key in ["", "/etc/passwd", "sources/../secrets", "a//b", "./a"] {
+            assert!(check_key(key).is_err(), "{key:?} accepted");
+        }
+    }
+}
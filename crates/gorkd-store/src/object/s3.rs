@@ -0,0 +1,225 @@
+//! S3-compatible object storage, signed with AWS Signature Version 4.
+//!
+//! Buckets are addressed path-style (`<endpoint>/<bucket>/<key>`), which AWS
+//! S3, MinIO and most other S3-compatible services accept. S3 signs the path
+//! as it is sent and the payload hash sent as `x-amz-content-sha256`.
+//! See: https://docs.aws.amazon.com/AmazonS3/latest/API/sig-v4-header-based-auth.html
+
+use std::env;
+use std::fmt;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use gorkd_core::sigv4::{self, CanonicalPath, SignableRequest};
+use reqwest::{Client, Method, StatusCode};
+use secrecy::{ExposeSecret, SecretString};
+
+use super::{check_key, ObjectError, ObjectStore};
+
+const SERVICE: &str = "s3";
+
+/// Region used when neither `S3_REGION` nor `AWS_REGION` is set.
+const DEFAULT_REGION: &str = "us-east-1";
+
+/// Keys that sign requests to the bucket.
+#[derive(Clone)]
+pub struct S3Credentials {
+    /// Access key ID.
+    pub access_key_id: String,
+    /// Secret access key.
+    pub secret_access_key: SecretString,
+    /// Session token of temporary credentials.
+    pub session_token: Option<SecretString>,
+}
+
+impl S3Credentials {
+    fn signing(&self) -> sigv4::Credentials<'_> {
+        sigv4::Credentials {
+            access_key_id: &self.access_key_id,
+            secret_access_key: self.secret_access_key.expose_secret(),
+            session_token: self.session_token.as_ref().map(|t| t.expose_secret()),
+        }
+    }
+}
+
+impl fmt::Debug for S3Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("S3Credentials")
+            .field("access_key_id", &self.access_key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Where an [`S3ObjectStore`] keeps its objects.
+#[derive(Clone, Debug)]
+pub struct S3Config {
+    /// Service URL without the bucket, e.g. `https://s3.eu-west-1.amazonaws.com`
+    /// or `http://localhost:9000` for MinIO.
+    pub endpoint: String,
+    /// Bucket holding the objects.
+    pub bucket: String,
+    /// Region requests are signed for.
+    pub region: String,
+    /// Keys that sign requests.
+    pub credentials: S3Credentials,
+}
+
+impl S3Config {
+    /// Reads `S3_BUCKET`, `S3_REGION` (or `AWS_REGION`), `S3_ENDPOINT` and
+    /// the keys `S3_ACCESS_KEY_ID`, `S3_SECRET_ACCESS_KEY` and
+    /// `S3_SESSION_TOKEN`, falling back to their `AWS_` counterparts. The
+    /// endpoint defaults to AWS S3 in the region. `None` without a bucket or
+    /// keys.
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str| env::var(name).ok().filter(|s| !s.trim().is_empty());
+        let either =
+            |name: &str| var(&format!("S3_{name}")).or_else(|| var(&format!("AWS_{name}")));
+
+        let bucket = var("S3_BUCKET")?;
+        let region = either("REGION").unwrap_or_else(|| DEFAULT_REGION.to_string());
+        let endpoint =
+            var("S3_ENDPOINT").unwrap_or_else(|| format!("https://s3.{region}.amazonaws.com"));
+        let credentials = S3Credentials {
+            access_key_id: either("ACCESS_KEY_ID")?,
+            secret_access_key: SecretString::from(either("SECRET_ACCESS_KEY")?),
+            session_token: either("SESSION_TOKEN").map(SecretString::from),
+        };
+
+        Some(Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            bucket,
+            region,
+            credentials,
+        })
+    }
+}
+
+/// Keeps objects in an S3 bucket.
+pub struct S3ObjectStore {
+    client: Client,
+    config: S3Config,
+}
+
+impl S3ObjectStore {
+    /// Creates a store for `config`, sending requests through `client`.
+    pub fn new(client: Client, config: S3Config) -> Self {
+        Self { client, config }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        key: &str,
+        body: Vec<u8>,
+    ) -> Result<reqwest::Response, ObjectError> {
+        check_key(key)?;
+        let path = format!(
+            "/{}/{}",
+            sigv4::uri_encode(&self.config.bucket),
+            key.split('/')
+                .map(sigv4::uri_encode)
+                .collect::<Vec<_>>()
+                .join("/")
+        );
+        let payload_hash = sigv4::payload_hash(&body);
+        let signable = SignableRequest {
+            method: method.as_str(),
+            host: sigv4::host_of(&self.config.endpoint),
+            path: &path,
+            headers: &[("x-amz-content-sha256", &payload_hash)],
+            payload_hash: &payload_hash,
+            canonical_path: CanonicalPath::AsIs,
+        };
+        let auth_headers = sigv4::sign(
+            &signable,
+            self.config.credentials.signing(),
+            &self.config.region,
+            SERVICE,
+            Utc::now(),
+        );
+
+        let mut builder = self
+            .client
+            .request(method, format!("{}{}", self.config.endpoint, path))
+            .header("x-amz-content-sha256", &payload_hash)
+            .body(body);
+        for (name, value) in auth_headers {
+            builder = builder.header(name, value);
+        }
+        builder
+            .send()
+            .await
+            .map_err(|e| ObjectError::Unavailable(e.to_string()))
+    }
+}
+
+async fn http_error(response: reqwest::Response) -> ObjectError {
+    let status = response.status();
+    let message = response.text().await.unwrap_or_default();
+    ObjectError::Http {
+        status: status.as_u16(),
+        message: if message.is_empty() {
+            status.canonical_reason().unwrap_or_default().to_string()
+        } else {
+            message
+        },
+    }
+}
+
+#[async_trait]
+impl ObjectStore for S3ObjectStore {
+    async fn put(&self, key: &str, body: Vec<u8>) -> Result<(), ObjectError> {
+        let response = self.send(Method::PUT, key, body).await?;
+        if !response.status().is_success() {
+            return Err(http_error(response).await);
+        }
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, ObjectError> {
+        let response = self.send(Method::GET, key, Vec::new()).await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(http_error(response).await);
+        }
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| ObjectError::Unavailable(e.to_string()))?;
+        Ok(Some(body.to_vec()))
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, ObjectError> {
+        let response = self.send(Method::HEAD, key, Vec::new()).await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        if !response.status().is_success() {
+            return Err(http_error(response).await);
+        }
+        Ok(true)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), ObjectError> {
+        let response = self.send(Method::DELETE, key, Vec::new()).await?;
+        if !response.status().is_success() && response.status() != StatusCode::NOT_FOUND {
+            return Err(http_error(response).await);
+        }
+        Ok(())
+    }
+
+    fn backend_name(&self) -> &str {
+        "s3"
+    }
+}
+
+impl fmt::Debug for S3ObjectStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("S3ObjectStore")
+            .field("endpoint", &self.config.endpoint)
+            .field("bucket", &self.config.bucket)
+            .finish()
+    }
+}
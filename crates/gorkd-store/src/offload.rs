@@ -0,0 +1,617 @@
+//! Keeps large bodies out of the job store.
+//!
+//! [`OffloadingStore`] wraps any [`Store`] and moves source contents and raw
+//! search responses of at least a configured size into an [`ObjectStore`],
+//! leaving the key in their place, so database rows stay small and backups
+//! quick. Everything read back through it has its bodies restored; everything
+//! else passes straight through.
+//!
+//! Source contents are keyed by their SHA-256 hash, so a page fetched by many
+//! jobs is kept once. The store counts the sources referring to each body
+//! and the body is deleted when the last of them is replaced.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use gorkd_core::{
+    CorpusDocument, CorpusMatch, DocumentId, DomainTrust, Fact, FactQuery, Feedback, JobCursor,
+    JobEvent, JobId, JobSummary, JobSummaryFilter, LlmArtifact, ModelComparison, Project,
+    ProjectId, ResearchAnswer, ResearchJob, SearchArtifact, SearchFilters, SearchMetadata, Source,
+    StatusCount, Store, StoreError, StoreHealth, UsageStats, WorkerId,
+};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::object::ObjectStore;
+
+/// Bodies at least this many bytes long are offloaded when no other size is
+/// set.
+pub const DEFAULT_MIN_OFFLOAD_BYTES: usize = 16 * 1024;
+
+/// Prefix of the keys of source contents shared by hash.
+const CONTENT_PREFIX: &str = "content/";
+
+/// Totals of the source contents an [`OffloadingStore`] kept since startup.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ContentStats {
+    /// Bodies written to object storage.
+    pub objects_written: u64,
+    /// Bodies already in object storage when another source had them, so
+    /// they were referred to instead of written again.
+    pub dedup_hits: u64,
+    /// Bodies deleted once no source referred to them.
+    pub objects_deleted: u64,
+    /// Bytes written to object storage.
+    pub bytes_written: u64,
+    /// Bytes not written thanks to dedup hits.
+    pub bytes_deduplicated: u64,
+}
+
+/// Counters behind [`ContentStats`], shared with whoever reports them.
+#[derive(Debug, Default)]
+pub struct ContentMetrics {
+    objects_written: AtomicU64,
+    dedup_hits: AtomicU64,
+    objects_deleted: AtomicU64,
+    bytes_written: AtomicU64,
+    bytes_deduplicated: AtomicU64,
+}
+
+impl ContentMetrics {
+    /// The totals so far.
+    pub fn snapshot(&self) -> ContentStats {
+        ContentStats {
+            objects_written: self.objects_written.load(Ordering::Relaxed),
+            dedup_hits: self.dedup_hits.load(Ordering::Relaxed),
+            objects_deleted: self.objects_deleted.load(Ordering::Relaxed),
+            bytes_written: self.bytes_written.load(Ordering::Relaxed),
+            bytes_deduplicated: self.bytes_deduplicated.load(Ordering::Relaxed),
+        }
+    }
+
+    fn written(&self, bytes: usize) {
+        self.objects_written.fetch_add(1, Ordering::Relaxed);
+        self.bytes_written
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    fn deduplicated(&self, bytes: usize) {
+        self.dedup_hits.fetch_add(1, Ordering::Relaxed);
+        self.bytes_deduplicated
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+}
+
+/// A [`Store`] that keeps large bodies in an [`ObjectStore`].
+pub struct OffloadingStore {
+    inner: Arc<dyn Store>,
+    objects: Arc<dyn ObjectStore>,
+    min_bytes: usize,
+    metrics: Arc<ContentMetrics>,
+}
+
+impl OffloadingStore {
+    /// Wraps `inner`, offloading bodies of at least
+    /// [`DEFAULT_MIN_OFFLOAD_BYTES`] to `objects`.
+    pub fn new(inner: Arc<dyn Store>, objects: Arc<dyn ObjectStore>) -> Self {
+        Self {
+            inner,
+            objects,
+            min_bytes: DEFAULT_MIN_OFFLOAD_BYTES,
+            metrics: Arc::default(),
+        }
+    }
+
+    /// Offloads bodies of at least `min_bytes` bytes instead.
+    pub fn with_min_bytes(mut self, min_bytes: usize) -> Self {
+        self.min_bytes = min_bytes;
+        self
+    }
+
+    /// How many source contents were written, shared and deleted.
+    pub fn metrics(&self) -> Arc<ContentMetrics> {
+        Arc::clone(&self.metrics)
+    }
+
+    async fn load(&self, key: &str) -> Result<String, StoreError> {
+        let body = self
+            .objects
+            .get(key)
+            .await?
+            .ok_or_else(|| StoreError::Query(format!("object {} is missing", key)))?;
+        String::from_utf8(body).map_err(|e| StoreError::Serialization(e.to_string()))
+    }
+
+    /// Takes a reference to `content` under its hash, writing it unless
+    /// another source already did, and returns its key.
+    async fn share(&self, content: String) -> Result<String, StoreError> {
+        let hash = hex::encode(Sha256::digest(content.as_bytes()));
+        let key = format!("{}{}", CONTENT_PREFIX, hash);
+        let refs = self.inner.acquire_content(&hash).await?;
+
+        // A body referred to before may still be on its way in, or lost; it
+        // is written again rather than left missing.
+        let exists = refs > 1 && self.objects.exists(&key).await.unwrap_or(false);
+        if exists {
+            self.metrics.deduplicated(content.len());
+            return Ok(key);
+        }
+
+        let bytes = content.len();
+        if let Err(e) = self.objects.put(&key, content.into_bytes()).await {
+            self.release(&key).await;
+            return Err(e.into());
+        }
+        self.metrics.written(bytes);
+        Ok(key)
+    }
+
+    /// Drops a source's reference to the body under `key`, deleting the
+    /// body once nothing refers to it. Bodies offloaded per job, before they
+    /// were shared by hash, are deleted straight away.
+    async fn release(&self, key: &str) {
+        let Some(hash) = key.strip_prefix(CONTENT_PREFIX) else {
+            self.delete(key).await;
+            return;
+        };
+        match self.inner.release_content(hash).await {
+            Ok(0) => {
+                self.delete(key).await;
+                self.metrics.objects_deleted.fetch_add(1, Ordering::Relaxed);
+            }
+            Ok(_) => {}
+            Err(e) => warn!(key, error = %e, "failed to release offloaded body"),
+        }
+    }
+
+    /// Removes an object no row refers to any more. A failure only leaves
+    /// the object behind, so it is logged rather than returned.
+    async fn delete(&self, key: &str) {
+        if let Err(e) = self.objects.delete(key).await {
+            warn!(key, error = %e, "failed to delete offloaded body");
+        }
+    }
+}
+
+#[async_trait]
+impl Store for OffloadingStore {
+    async fn create_job(&self, job: &ResearchJob) -> Result<(), StoreError> {
+        self.inner.create_job(job).await
+    }
+
+    async fn get_job(&self, id: &JobId) -> Result<Option<ResearchJob>, StoreError> {
+        self.inner.get_job(id).await
+    }
+
+    async fn update_job(&self, job: &ResearchJob) -> Result<(), StoreError> {
+        self.inner.update_job(job).await
+    }
+
+    async fn list_jobs(&self, limit: usize, offset: usize) -> Result<Vec<ResearchJob>, StoreError> {
+        self.inner.list_jobs(limit, offset).await
+    }
+
+    async fn list_jobs_before(
+        &self,
+        cursor: Option<&JobCursor>,
+        limit: usize,
+    ) -> Result<Vec<ResearchJob>, StoreError> {
+        self.inner.list_jobs_before(cursor, limit).await
+    }
+
+    async fn list_jobs_by_tag(
+        &self,
+        tag: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<ResearchJob>, StoreError> {
+        self.inner.list_jobs_by_tag(tag, limit, offset).await
+    }
+
+    async fn list_job_summaries(
+        &self,
+        filter: &JobSummaryFilter,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<JobSummary>, StoreError> {
+        self.inner.list_job_summaries(filter, limit, offset).await
+    }
+
+    async fn count_job_summaries(
+        &self,
+        filter: &JobSummaryFilter,
+    ) -> Result<Vec<StatusCount>, StoreError> {
+        self.inner.count_job_summaries(filter).await
+    }
+
+    async fn usage_stats(&self, since: DateTime<Utc>) -> Result<UsageStats, StoreError> {
+        self.inner.usage_stats(since).await
+    }
+
+    async fn create_project(&self, project: &Project) -> Result<(), StoreError> {
+        self.inner.create_project(project).await
+    }
+
+    async fn get_project(&self, id: &ProjectId) -> Result<Option<Project>, StoreError> {
+        self.inner.get_project(id).await
+    }
+
+    async fn attach_job(
+        &self,
+        project_id: &ProjectId,
+        job_id: &JobId,
+        max_jobs: usize,
+    ) -> Result<Project, StoreError> {
+        self.inner.attach_job(project_id, job_id, max_jobs).await
+    }
+
+    async fn record_facts(&self, facts: &[Fact]) -> Result<(), StoreError> {
+        self.inner.record_facts(facts).await
+    }
+
+    async fn find_facts(&self, query: &FactQuery) -> Result<Vec<Fact>, StoreError> {
+        self.inner.find_facts(query).await
+    }
+
+    async fn store_document(&self, document: &CorpusDocument) -> Result<(), StoreError> {
+        self.inner.store_document(document).await
+    }
+
+    async fn list_documents(
+        &self,
+        workspace: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<CorpusDocument>, StoreError> {
+        self.inner.list_documents(workspace, limit, offset).await
+    }
+
+    async fn get_document(
+        &self,
+        workspace: &str,
+        id: &DocumentId,
+    ) -> Result<Option<CorpusDocument>, StoreError> {
+        self.inner.get_document(workspace, id).await
+    }
+
+    async fn find_document_by_url(
+        &self,
+        workspace: &str,
+        url: &str,
+    ) -> Result<Option<CorpusDocument>, StoreError> {
+        self.inner.find_document_by_url(workspace, url).await
+    }
+
+    async fn delete_document(&self, workspace: &str, id: &DocumentId) -> Result<bool, StoreError> {
+        self.inner.delete_document(workspace, id).await
+    }
+
+    async fn search_corpus(
+        &self,
+        workspace: &str,
+        model: &str,
+        embedding: &[f32],
+        filters: &SearchFilters,
+        limit: usize,
+    ) -> Result<Vec<CorpusMatch>, StoreError> {
+        self.inner
+            .search_corpus(workspace, model, embedding, filters, limit)
+            .await
+    }
+
+    async fn store_feedback(&self, feedback: &Feedback) -> Result<(), StoreError> {
+        self.inner.store_feedback(feedback).await
+    }
+
+    async fn get_feedback(&self, job_id: &JobId) -> Result<Vec<Feedback>, StoreError> {
+        self.inner.get_feedback(job_id).await
+    }
+
+    async fn domain_trust(&self) -> Result<Vec<DomainTrust>, StoreError> {
+        self.inner.domain_trust().await
+    }
+
+    async fn claim_next_job(
+        &self,
+        worker: &WorkerId,
+        lease: Duration,
+    ) -> Result<Option<ResearchJob>, StoreError> {
+        self.inner.claim_next_job(worker, lease).await
+    }
+
+    async fn heartbeat(
+        &self,
+        job_id: &JobId,
+        worker: &WorkerId,
+        lease: Duration,
+    ) -> Result<(), StoreError> {
+        self.inner.heartbeat(job_id, worker, lease).await
+    }
+
+    async fn release(&self, job_id: &JobId, worker: &WorkerId) -> Result<(), StoreError> {
+        self.inner.release(job_id, worker).await
+    }
+
+    async fn store_sources(&self, job_id: &JobId, sources: &[Source]) -> Result<(), StoreError> {
+        let previous = self.inner.get_sources(job_id).await?;
+
+        let mut rows = Vec::with_capacity(sources.len());
+        let mut result = Ok(());
+        for source in sources {
+            let mut row = source.clone();
+            if row.content.len() >= self.min_bytes {
+                match self.share(std::mem::take(&mut row.content)).await {
+                    Ok(key) => row.content_ref = Some(key),
+                    Err(e) => {
+                        result = Err(e);
+                        break;
+                    }
+                }
+            }
+            rows.push(row);
+        }
+        if result.is_ok() {
+            result = self.inner.store_sources(job_id, &rows).await;
+        }
+
+        // References are taken before the old ones are dropped, so a body the
+        // job keeps is never deleted in between. On failure the new ones are
+        // dropped instead.
+        let released = if result.is_ok() { &previous } else { &rows };
+        for key in released.iter().filter_map(|s| s.content_ref.as_deref()) {
+            self.release(key).await;
+        }
+        result
+    }
+
+    async fn get_sources(&self, job_id: &JobId) -> Result<Vec<Source>, StoreError> {
+        let mut sources = self.inner.get_sources(job_id).await?;
+        for source in &mut sources {
+            if let Some(key) = source.content_ref.take() {
+                source.content = self.load(&key).await?;
+            }
+        }
+        Ok(sources)
+    }
+
+    async fn store_search_metadata(
+        &self,
+        job_id: &JobId,
+        metadata: &SearchMetadata,
+    ) -> Result<(), StoreError> {
+        self.inner.store_search_metadata(job_id, metadata).await
+    }
+
+    async fn get_search_metadata(
+        &self,
+        job_id: &JobId,
+    ) -> Result<Option<SearchMetadata>, StoreError> {
+        self.inner.get_search_metadata(job_id).await
+    }
+
+    async fn store_answer(
+        &self,
+        job_id: &JobId,
+        answer: &ResearchAnswer,
+    ) -> Result<(), StoreError> {
+        self.inner.store_answer(job_id, answer).await
+    }
+
+    async fn get_answer(&self, job_id: &JobId) -> Result<Option<ResearchAnswer>, StoreError> {
+        self.inner.get_answer(job_id).await
+    }
+
+    async fn store_comparison(
+        &self,
+        job_id: &JobId,
+        comparison: &ModelComparison,
+    ) -> Result<(), StoreError> {
+        self.inner.store_comparison(job_id, comparison).await
+    }
+
+    async fn get_comparison(&self, job_id: &JobId) -> Result<Option<ModelComparison>, StoreError> {
+        self.inner.get_comparison(job_id).await
+    }
+
+    async fn append_event(&self, event: JobEvent) -> Result<JobEvent, StoreError> {
+        self.inner.append_event(event).await
+    }
+
+    async fn get_events(&self, job_id: &JobId) -> Result<Vec<JobEvent>, StoreError> {
+        self.inner.get_events(job_id).await
+    }
+
+    async fn store_artifact(
+        &self,
+        job_id: &JobId,
+        artifact: &LlmArtifact,
+    ) -> Result<(), StoreError> {
+        self.inner.store_artifact(job_id, artifact).await
+    }
+
+    async fn get_artifacts(&self, job_id: &JobId) -> Result<Vec<LlmArtifact>, StoreError> {
+        self.inner.get_artifacts(job_id).await
+    }
+
+    async fn store_search_artifact(
+        &self,
+        job_id: &JobId,
+        artifact: &SearchArtifact,
+    ) -> Result<(), StoreError> {
+        if artifact.raw_response.len() < self.min_bytes {
+            return self.inner.store_search_artifact(job_id, artifact).await;
+        }
+
+        let key = format!("artifacts/{}/search/{}", job_id, Uuid::new_v4());
+        self.objects
+            .put(&key, artifact.raw_response.clone().into_bytes())
+            .await?;
+        let row = SearchArtifact {
+            raw_response: String::new(),
+            raw_response_ref: Some(key),
+            ..artifact.clone()
+        };
+        self.inner.store_search_artifact(job_id, &row).await
+    }
+
+    async fn get_search_artifacts(
+        &self,
+        job_id: &JobId,
+    ) -> Result<Vec<SearchArtifact>, StoreError> {
+        let mut artifacts = self.inner.get_search_artifacts(job_id).await?;
+        for artifact in &mut artifacts {
+            if let Some(key) = artifact.raw_response_ref.take() {
+                artifact.raw_response = self.load(&key).await?;
+            }
+        }
+        Ok(artifacts)
+    }
+
+    async fn acquire_content(&self, hash: &str) -> Result<u64, StoreError> {
+        self.inner.acquire_content(hash).await
+    }
+
+    async fn release_content(&self, hash: &str) -> Result<u64, StoreError> {
+        self.inner.release_content(hash).await
+    }
+
+    async fn health(&self) -> StoreHealth {
+        self.inner.health().await
+    }
+
+    async fn find_similar(
+        &self,
+        embedding: &[f32],
+        threshold: f32,
+    ) -> Result<Option<JobId>, StoreError> {
+        self.inner.find_similar(embedding, threshold).await
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::MemoryObjectStore;
+    use gorkd_core::MockStore;
+
+    fn stores() -> (OffloadingStore, Arc<MockStore>, Arc<MemoryObjectStore>) {
+        let inner = Arc::new(MockStore::new());
+        let objects = Arc::new(MemoryObjectStore::new());
+        let store = OffloadingStore::new(
+            Arc::clone(&inner) as Arc<dyn Store>,
+            Arc::clone(&objects) as Arc<dyn ObjectStore>,
+        )
+        .with_min_bytes(10);
+        (store, inner, objects)
+    }
+
+    #[tokio::test]
+    async fn offloads_large_source_contents() {
+        let (store, inner, objects) = stores();
+        let job_id = JobId::new();
+        let large = Source::new("https://a.com", "A", "a long page body");
+        let small = Source::new("https://b.com", "B", "short");
+
+        store
+            .store_sources(&job_id, &[large.clone(), small.clone()])
+            .await
+            .unwrap();
+
+        let rows = inner.get_sources(&job_id).await.unwrap();
+        assert!(rows[0].content.is_empty());
+        assert!(rows[0]
+            .content_ref
+            .as_deref()
+            .is_some_and(|key| key.starts_with("content/")));
+        assert_eq!(rows[1].content, "short");
+        assert_eq!(objects.len(), 1);
+
+        let sources = store.get_sources(&job_id).await.unwrap();
+        assert_eq!(sources[0].content, "a long page body");
+        assert!(sources[0].content_ref.is_none());
+        assert_eq!(sources[1].content, "short");
+    }
+
+    #[tokio::test]
+    async fn deletes_bodies_of_replaced_sources() {
+        let (store, _, objects) = stores();
+        let job_id = JobId::new();
+        let source = Source::new("https://a.com", "A", "a long page body");
+
+        store.store_sources(&job_id, &[source]).await.unwrap();
+        store
+            .store_sources(&job_id, &[Source::new("https://b.com", "B", "short")])
+            .await
+            .unwrap();
+
+        assert!(objects.is_empty());
+    }
+
+    #[tokio::test]
+    async fn shares_contents_across_jobs() {
+        let (store, inner, objects) = stores();
+        let (first, second) = (JobId::new(), JobId::new());
+        let page = || Source::new("https://a.com", "A", "a long page body");
+
+        store.store_sources(&first, &[page()]).await.unwrap();
+        store.store_sources(&second, &[page()]).await.unwrap();
+
+        assert_eq!(objects.len(), 1);
+        assert_eq!(
+            inner.get_sources(&first).await.unwrap()[0].content_ref,
+            inner.get_sources(&second).await.unwrap()[0].content_ref
+        );
+        assert_eq!(
+            store.get_sources(&second).await.unwrap()[0].content,
+            "a long page body"
+        );
+        let stats = store.metrics().snapshot();
+        assert_eq!(stats.objects_written, 1);
+        assert_eq!(stats.dedup_hits, 1);
+        assert_eq!(stats.bytes_deduplicated, 16);
+    }
+
+    #[tokio::test]
+    async fn deletes_shared_contents_after_last_reference() {
+        let (store, _, objects) = stores();
+        let (first, second) = (JobId::new(), JobId::new());
+        let page = || Source::new("https://a.com", "A", "a long page body");
+        let short = || Source::new("https://b.com", "B", "short");
+
+        store.store_sources(&first, &[page()]).await.unwrap();
+        store.store_sources(&second, &[page()]).await.unwrap();
+        // Storing a job's sources again keeps the bodies it still has.
+        store.store_sources(&first, &[page()]).await.unwrap();
+        store.store_sources(&first, &[short()]).await.unwrap();
+        assert_eq!(objects.len(), 1);
+
+        store.store_sources(&second, &[short()]).await.unwrap();
+        assert!(objects.is_empty());
+        assert_eq!(store.metrics().snapshot().objects_deleted, 1);
+    }
+
+    #[tokio::test]
+    async fn offloads_large_search_responses() {
+        let (store, inner, _) = stores();
+        let job_id = JobId::new();
+        let artifact = SearchArtifact::new(
+            "tavily",
+            gorkd_core::SearchQuery::new("rust"),
+            r#"{"results": []}"#,
+        );
+
+        store
+            .store_search_artifact(&job_id, &artifact)
+            .await
+            .unwrap();
+
+        let rows = inner.get_search_artifacts(&job_id).await.unwrap();
+        assert!(rows[0].raw_response.is_empty());
+        assert!(rows[0].raw_response_ref.is_some());
+        let artifacts = store.get_search_artifacts(&job_id).await.unwrap();
+        assert_eq!(artifacts[0].raw_response, r#"{"results": []}"#);
+    }
+}